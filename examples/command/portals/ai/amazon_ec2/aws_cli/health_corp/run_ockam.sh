@@ -33,7 +33,6 @@ ockam project enroll "$ENROLLMENT_TICKET"
 # Create a TCP Portal Inlet to AI API.
 # This makes the remote AI API available on all localhost IPs at - 0.0.0.0:3000
 ockam node create
-ockam policy create --resource tcp-inlet --expression '(= subject.ai-outlet "true")'
-ockam tcp-inlet create --from 0.0.0.0:3000 --via ai
+ockam tcp-inlet create --from 0.0.0.0:3000 --via ai --allow '(= subject.ai-outlet "true")'
 
 EOS
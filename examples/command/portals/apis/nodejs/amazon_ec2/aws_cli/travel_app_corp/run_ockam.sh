@@ -33,7 +33,6 @@ ockam project enroll "$ENROLLMENT_TICKET"
 # Create a TCP Portal Inlet to postgres.
 # This makes the remote postgres available on all localhost IPs at - 0.0.0.0:3000
 ockam node create
-ockam policy create --resource tcp-inlet --expression '(= subject.monitoring-api-outlet "true")'
-ockam tcp-inlet create --from 0.0.0.0:3000 --via monitoring-api
+ockam tcp-inlet create --from 0.0.0.0:3000 --via monitoring-api --allow '(= subject.monitoring-api-outlet "true")'
 
 EOS
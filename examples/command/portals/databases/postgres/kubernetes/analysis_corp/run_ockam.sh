@@ -26,8 +26,7 @@ ockam project enroll /etc/ockam/enrollment/ticket
 # Create a TCP Portal Inlet to postgres.
 # This makes the remote postgres available on localhost:15432
 ockam node create
-ockam policy create --resource tcp-inlet --expression '(= subject.postgres-outlet "true")'
-ockam tcp-inlet create --from 0.0.0.0:15432 --via postgres
+ockam tcp-inlet create --from 0.0.0.0:15432 --via postgres --allow '(= subject.postgres-outlet "true")'
 
 # Run the container forever.
 tail -f /dev/null
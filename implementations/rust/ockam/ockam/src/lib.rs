@@ -83,8 +83,8 @@ pub use ockam_node::{
 };
 #[cfg(feature = "ockam_transport_tcp")]
 pub use ockam_transport_tcp::{
-    TcpConnectionOptions, TcpInletOptions, TcpListenerOptions, TcpOutletOptions, TcpTransport,
-    TcpTransportExtension,
+    TcpConnectionOptions, TcpInletOptions, TcpKeepaliveOptions, TcpListenerOptions,
+    TcpOutletOptions, TcpTransport, TcpTransportExtension,
 };
 pub use relay_service::{RelayService, RelayServiceOptions};
 
@@ -0,0 +1,89 @@
+use core::time::Duration;
+use ockam_core::compat::sync::{Arc, RwLock};
+use ockam_core::Result;
+use serde::{Deserialize, Serialize};
+
+/// Result of the keepalive messages periodically sent by a
+/// [`RemoteRelay`](super::RemoteRelay) through its forwarding route.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, Default)]
+pub struct RelayHeartbeatStatus {
+    last_success_at: Option<u64>,
+    round_trip_time: Option<Duration>,
+    consecutive_failures: u32,
+}
+
+impl RelayHeartbeatStatus {
+    /// Time, in milliseconds since the UNIX epoch, of the last successful heartbeat
+    pub fn last_success_at(&self) -> Option<u64> {
+        self.last_success_at
+    }
+    /// Round-trip time of the last successful keepalive message
+    pub fn round_trip_time(&self) -> Option<Duration> {
+        self.round_trip_time
+    }
+    /// Number of heartbeats which failed since the last successful one
+    pub fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures
+    }
+}
+
+/// Shared handle on the [`RelayHeartbeatStatus`] of a [`RemoteRelay`](super::RemoteRelay).
+///
+/// The handle can be cloned and kept by the caller in order to inspect the
+/// health of the relay while the worker keeps updating it.
+#[derive(Clone, Debug, Default)]
+pub struct RemoteRelayHeartbeats {
+    status: Arc<RwLock<RelayHeartbeatStatus>>,
+}
+
+impl RemoteRelayHeartbeats {
+    /// Return the current heartbeat status
+    pub fn status(&self) -> RelayHeartbeatStatus {
+        self.status.read().unwrap().clone()
+    }
+
+    /// A keepalive message came back after `round_trip_time`
+    pub(super) fn record_success(&self, now: u64, round_trip_time: Duration) {
+        let mut status = self.status.write().unwrap();
+        status.last_success_at = Some(now);
+        status.round_trip_time = Some(round_trip_time);
+        status.consecutive_failures = 0;
+    }
+
+    /// The relay was registered, the failures of a previous registration don't apply to it
+    pub(super) fn record_registration(&self) {
+        let mut status = self.status.write().unwrap();
+        status.consecutive_failures = 0;
+    }
+
+    /// A message was received through the relay, which proves that it is still alive
+    pub(super) fn record_activity(&self, now: u64) {
+        let mut status = self.status.write().unwrap();
+        status.last_success_at = Some(now);
+        status.consecutive_failures = 0;
+    }
+
+    /// A keepalive message could not be sent or was not received back in time
+    pub(super) fn record_failure(&self) {
+        let mut status = self.status.write().unwrap();
+        status.consecutive_failures = status.consecutive_failures.saturating_add(1);
+    }
+}
+
+/// Current time in milliseconds since the UNIX epoch
+#[cfg(feature = "std")]
+pub(super) fn now_millis() -> Result<u64> {
+    use ockam_core::compat::time::{SystemTime, UNIX_EPOCH};
+    use ockam_core::errcode::{Kind, Origin};
+
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .map_err(|_| ockam_core::Error::new(Origin::Core, Kind::Unsupported, "Can't get time"))
+}
+
+/// Current time in milliseconds since the UNIX epoch
+#[cfg(not(feature = "std"))]
+pub(super) fn now_millis() -> Result<u64> {
+    Ok(ockam_core::compat::time::now()? * 1000)
+}
@@ -14,7 +14,7 @@ use ockam_core::{
 use ockam_node::{DelayedEvent, WorkerBuilder};
 use tracing::debug;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub(super) enum RelayType {
     Static,
     Ephemeral,
@@ -29,12 +29,20 @@ impl RelayType {
             RelayType::StaticWithoutHeartbeats => "static_w/o_heartbeats",
         }
     }
+
+    /// Default interval between two heartbeats
+    fn heartbeat_interval(&self) -> Duration {
+        match self {
+            RelayType::Static => Duration::from_secs(5),
+            RelayType::Ephemeral | RelayType::StaticWithoutHeartbeats => Duration::from_secs(10),
+        }
+    }
 }
 
 impl RemoteRelay {
     fn mailboxes(
         addresses: Addresses,
-        heartbeat_source_address: Option<Address>,
+        heartbeat_source_address: Address,
         outgoing_access_control: Arc<dyn OutgoingAccessControl>,
     ) -> Mailboxes {
         let main_internal = Mailbox::new(
@@ -49,29 +57,25 @@ impl RemoteRelay {
             Arc::new(AllowAll),
         );
 
-        let mut additional_mailboxes = vec![main_remote];
-
-        if let Some(heartbeat_source_address) = heartbeat_source_address {
-            let heartbeat = Mailbox::new(
-                addresses.heartbeat,
-                Arc::new(AllowSourceAddress(heartbeat_source_address)),
-                Arc::new(DenyAll),
-            );
-            additional_mailboxes.push(heartbeat);
-        }
+        let heartbeat = Mailbox::new(
+            addresses.heartbeat,
+            Arc::new(AllowSourceAddress(heartbeat_source_address)),
+            Arc::new(DenyAll),
+        );
 
-        Mailboxes::new(main_internal, additional_mailboxes)
+        Mailboxes::new(main_internal, vec![main_remote, heartbeat])
     }
 }
 
 impl RemoteRelay {
     fn new(
         addresses: Addresses,
+        relay_type: RelayType,
         registration_route: Route,
         registration_payload: String,
         flow_control_id: Option<FlowControlId>,
-        heartbeat: Option<DelayedEvent<Vec<u8>>>,
-        heartbeat_interval: Duration,
+        heartbeat: DelayedEvent<Vec<u8>>,
+        options: &RemoteRelayOptions,
     ) -> Self {
         Self {
             addresses,
@@ -79,8 +83,14 @@ impl RemoteRelay {
             registration_route,
             registration_payload,
             flow_control_id,
+            relay_type,
             heartbeat,
-            heartbeat_interval,
+            heartbeat_interval: options
+                .heartbeat_interval
+                .unwrap_or_else(|| relay_type.heartbeat_interval()),
+            heartbeats: options.heartbeats(),
+            forwarding_route: None,
+            pending_keepalive: None,
         }
     }
 
@@ -113,19 +123,17 @@ impl RemoteRelay {
 
         let relay = Self::new(
             addresses.clone(),
+            RelayType::Static,
             registration_route,
             alias.into(),
             flow_control_id,
-            Some(heartbeat),
-            Duration::from_secs(5),
+            heartbeat,
+            &options,
         );
 
         debug!("Starting static RemoteRelay at {}", &addresses.heartbeat);
-        let mailboxes = Self::mailboxes(
-            addresses,
-            Some(heartbeat_source_address),
-            outgoing_access_control,
-        );
+        let mailboxes =
+            Self::mailboxes(addresses, heartbeat_source_address, outgoing_access_control);
         WorkerBuilder::new(relay)
             .with_mailboxes(mailboxes)
             .start(ctx)
@@ -154,6 +162,9 @@ impl RemoteRelay {
 
         let registration_route = route![hub_route, "forwarding_service"];
 
+        let heartbeat = DelayedEvent::create(ctx, addresses.heartbeat.clone(), vec![]).await?;
+        let heartbeat_source_address = heartbeat.address();
+
         let flow_control_id =
             options.setup_flow_control(ctx.flow_controls(), &addresses, registration_route.next()?);
         let outgoing_access_control =
@@ -161,18 +172,20 @@ impl RemoteRelay {
 
         let relay = Self::new(
             addresses.clone(),
+            RelayType::Ephemeral,
             registration_route,
             "register".to_string(),
             flow_control_id,
-            None,
-            Duration::from_secs(10),
+            heartbeat,
+            &options,
         );
 
         debug!(
             "Starting ephemeral RemoteRelay at {}",
             &addresses.main_internal
         );
-        let mailboxes = Self::mailboxes(addresses, None, outgoing_access_control);
+        let mailboxes =
+            Self::mailboxes(addresses, heartbeat_source_address, outgoing_access_control);
         WorkerBuilder::new(relay)
             .with_mailboxes(mailboxes)
             .start(ctx)
@@ -208,6 +221,9 @@ impl RemoteRelay {
 
         let registration_route = route![hub_route.into(), "forwarding_service"];
 
+        let heartbeat = DelayedEvent::create(ctx, addresses.heartbeat.clone(), vec![]).await?;
+        let heartbeat_source_address = heartbeat.address();
+
         let flow_control_id =
             options.setup_flow_control(ctx.flow_controls(), &addresses, registration_route.next()?);
         let outgoing_access_control =
@@ -215,18 +231,20 @@ impl RemoteRelay {
 
         let relay = Self::new(
             addresses.clone(),
+            RelayType::StaticWithoutHeartbeats,
             registration_route,
             alias.into(),
             flow_control_id,
-            None,
-            Duration::from_secs(10),
+            heartbeat,
+            &options,
         );
 
         debug!(
             "Starting static RemoteRelay without heartbeats at {}",
             &addresses.main_internal
         );
-        let mailboxes = Self::mailboxes(addresses, None, outgoing_access_control);
+        let mailboxes =
+            Self::mailboxes(addresses, heartbeat_source_address, outgoing_access_control);
         WorkerBuilder::new(relay)
             .with_mailboxes(mailboxes)
             .start(ctx)
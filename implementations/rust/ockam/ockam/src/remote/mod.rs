@@ -2,15 +2,18 @@
 //! which allows other nodes forward messages to local workers on this node using that alias.
 
 mod addresses;
+mod heartbeats;
 mod info;
 mod lifecycle;
 mod options;
 mod worker;
 
+pub use heartbeats::*;
 pub use info::*;
 pub use options::*;
 
 use crate::remote::addresses::Addresses;
+use crate::remote::lifecycle::RelayType;
 use core::time::Duration;
 use ockam_core::compat::{string::String, vec::Vec};
 use ockam_core::flow_control::FlowControlId;
@@ -25,7 +28,13 @@ pub struct RemoteRelay {
     registration_route: Route,
     registration_payload: String,
     flow_control_id: Option<FlowControlId>,
-    // We only use Heartbeat for static RemoteRelay
-    heartbeat: Option<DelayedEvent<Vec<u8>>>,
+    // Static RemoteRelay also uses the heartbeat to refresh its registration
+    relay_type: RelayType,
+    heartbeat: DelayedEvent<Vec<u8>>,
     heartbeat_interval: Duration,
+    heartbeats: RemoteRelayHeartbeats,
+    // Route to this worker through the relay, known once registered
+    forwarding_route: Option<Route>,
+    // Sending time of the keepalive message we are waiting for
+    pending_keepalive: Option<u64>,
 }
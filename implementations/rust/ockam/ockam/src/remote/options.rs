@@ -1,10 +1,14 @@
-use crate::remote::Addresses;
+use crate::remote::{Addresses, RemoteRelayHeartbeats};
+use core::time::Duration;
 use ockam_core::compat::sync::Arc;
 use ockam_core::flow_control::{FlowControlId, FlowControlOutgoingAccessControl, FlowControls};
 use ockam_core::{Address, AllowAll, OutgoingAccessControl};
 
 /// Trust options for [`RemoteRelay`](super::RemoteRelay)
-pub struct RemoteRelayOptions {}
+pub struct RemoteRelayOptions {
+    pub(super) heartbeat_interval: Option<Duration>,
+    pub(super) heartbeats: RemoteRelayHeartbeats,
+}
 
 impl RemoteRelayOptions {
     /// Usually [`FlowControlId`] should be shared with the Producer that was used to create this
@@ -15,7 +19,29 @@ impl RemoteRelayOptions {
     /// through the [`RemoteRelay`](super::RemoteRelay) through the same Secure Channel.
     #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
-        Self {}
+        Self {
+            heartbeat_interval: None,
+            heartbeats: RemoteRelayHeartbeats::default(),
+        }
+    }
+
+    /// Interval between two keepalive messages sent through the relay.
+    /// Defaults to the interval used to refresh the relay registration.
+    pub fn with_heartbeat_interval(mut self, heartbeat_interval: Duration) -> Self {
+        self.heartbeat_interval = Some(heartbeat_interval);
+        self
+    }
+
+    /// Record the heartbeats of the relay in an existing [`RemoteRelayHeartbeats`],
+    /// so that the status survives the re-creation of the relay
+    pub fn with_heartbeats(mut self, heartbeats: RemoteRelayHeartbeats) -> Self {
+        self.heartbeats = heartbeats;
+        self
+    }
+
+    /// Handle on the heartbeat status of the relay created with these options
+    pub fn heartbeats(&self) -> RemoteRelayHeartbeats {
+        self.heartbeats.clone()
     }
 
     pub(super) fn setup_flow_control(
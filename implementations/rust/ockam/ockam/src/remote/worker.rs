@@ -1,12 +1,82 @@
+use crate::remote::heartbeats::now_millis;
+use crate::remote::lifecycle::RelayType;
 use crate::remote::{RemoteRelay, RemoteRelayInfo};
 use crate::{Context, OckamError};
+use core::time::Duration;
 use ockam_core::compat::{
     boxed::Box,
     string::{String, ToString},
     vec::Vec,
 };
-use ockam_core::{Any, Decodable, Result, Routed, Worker};
-use tracing::{debug, info};
+use ockam_core::{route, Any, Decodable, Result, Routed, Worker};
+use tracing::{debug, info, warn};
+
+/// Prefix of the keepalive messages sent to ourselves through the relay
+const KEEPALIVE_PREFIX: &str = "keepalive:";
+
+impl RemoteRelay {
+    /// Send a keepalive message to ourselves through the relay, after checking
+    /// that the previous one made it back
+    async fn send_keepalive(&mut self, ctx: &Context) -> Result<()> {
+        if self.pending_keepalive.take().is_some() {
+            warn!(
+                "RemoteRelay {} didn't receive its last keepalive message back",
+                self.addresses.main_remote
+            );
+            self.heartbeats.record_failure();
+        }
+
+        let forwarding_route = match &self.forwarding_route {
+            Some(forwarding_route) => forwarding_route.clone(),
+            None => return Ok(()),
+        };
+
+        let sent_at = now_millis()?;
+        let result = ctx
+            .send_from_address(
+                route![forwarding_route, self.addresses.main_remote.clone()],
+                format!("{KEEPALIVE_PREFIX}{sent_at}"),
+                self.addresses.main_remote.clone(),
+            )
+            .await;
+
+        match result {
+            Ok(()) => self.pending_keepalive = Some(sent_at),
+            Err(err) => {
+                warn!(
+                    "RemoteRelay {} couldn't send a keepalive message: {}",
+                    self.addresses.main_remote, err
+                );
+                self.heartbeats.record_failure();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Handle a keepalive message which went through the relay
+    fn receive_keepalive(&mut self, sent_at: &str) -> Result<()> {
+        let sent_at = sent_at
+            .parse::<u64>()
+            .map_err(|_| OckamError::InvalidHubResponse)?;
+
+        // Ignore keepalive messages that were already counted as failed
+        if self.pending_keepalive != Some(sent_at) {
+            return Ok(());
+        }
+        self.pending_keepalive = None;
+
+        let now = now_millis()?;
+        let round_trip_time = Duration::from_millis(now.saturating_sub(sent_at));
+        debug!(
+            "RemoteRelay {} keepalive round-trip time: {:?}",
+            self.addresses.main_remote, round_trip_time
+        );
+        self.heartbeats.record_success(now, round_trip_time);
+
+        Ok(())
+    }
+}
 
 #[crate::worker]
 impl Worker for RemoteRelay {
@@ -32,18 +102,20 @@ impl Worker for RemoteRelay {
         msg: Routed<Self::Message>,
     ) -> Result<()> {
         if msg.msg_addr() == self.addresses.heartbeat {
-            // Heartbeat message, send registration message
-            ctx.send_from_address(
-                self.registration_route.clone(),
-                self.registration_payload.clone(),
-                self.addresses.main_remote.clone(),
-            )
-            .await?;
-
-            if let Some(heartbeat) = &mut self.heartbeat {
-                heartbeat.schedule(self.heartbeat_interval).await?;
+            // Heartbeat message, send registration message for static relays
+            if self.relay_type == RelayType::Static {
+                ctx.send_from_address(
+                    self.registration_route.clone(),
+                    self.registration_payload.clone(),
+                    self.addresses.main_remote.clone(),
+                )
+                .await?;
             }
 
+            self.send_keepalive(ctx).await?;
+
+            self.heartbeat.schedule(self.heartbeat_interval).await?;
+
             Ok(())
         } else if msg.msg_addr() == self.addresses.main_remote {
             let return_route = msg.return_route();
@@ -60,6 +132,11 @@ impl Worker for RemoteRelay {
                         .map_err(|_| OckamError::InvalidHubResponse)?;
                     let payload =
                         String::from_utf8(payload).map_err(|_| OckamError::InvalidHubResponse)?;
+
+                    if let Some(sent_at) = payload.strip_prefix(KEEPALIVE_PREFIX) {
+                        return self.receive_keepalive(sent_at);
+                    }
+
                     // using ends_with() instead of == to allow for prefixes
                     if !payload.ends_with(&self.registration_payload) {
                         return Err(OckamError::InvalidHubResponse)?;
@@ -76,7 +153,7 @@ impl Worker for RemoteRelay {
                         ctx.send_from_address(
                             self.addresses.completion_callback.clone(),
                             RemoteRelayInfo::new(
-                                return_route,
+                                return_route.clone(),
                                 address,
                                 self.addresses.main_remote.clone(),
                                 self.flow_control_id.clone(),
@@ -85,12 +162,12 @@ impl Worker for RemoteRelay {
                         )
                         .await?;
 
+                        self.heartbeats.record_registration();
                         self.completion_msg_sent = true;
                     }
 
-                    if let Some(heartbeat) = &mut self.heartbeat {
-                        heartbeat.schedule(self.heartbeat_interval).await?;
-                    }
+                    self.forwarding_route = Some(return_route);
+                    self.heartbeat.schedule(self.heartbeat_interval).await?;
 
                     Ok(())
                 }
@@ -110,9 +187,8 @@ impl Worker for RemoteRelay {
 
                     // We received message from the other node, our registration is still alive, let's reset
                     // heartbeat timer
-                    if let Some(heartbeat) = &mut self.heartbeat {
-                        heartbeat.schedule(self.heartbeat_interval).await?;
-                    }
+                    self.heartbeats.record_activity(now_millis()?);
+                    self.heartbeat.schedule(self.heartbeat_interval).await?;
 
                     Ok(())
                 }
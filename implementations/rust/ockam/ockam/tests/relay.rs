@@ -250,3 +250,38 @@ async fn test4(ctx: &mut Context) -> Result<()> {
 
     Ok(())
 }
+
+// Cloud: Hosts a Relay service and listens on a tcp port
+// Server: Connects to a Cloud using tcp and creates a Relay sending keepalive messages through it
+#[ockam_macros::test]
+async fn test5(ctx: &mut Context) -> Result<()> {
+    let tcp_listener_options = TcpListenerOptions::new();
+    let options = RelayServiceOptions::new()
+        .service_as_consumer(&tcp_listener_options.spawner_flow_control_id())
+        .relay_as_consumer(&tcp_listener_options.spawner_flow_control_id());
+    RelayService::create(ctx, "forwarding_service", options).await?;
+    let cloud_tcp = TcpTransport::create(ctx).await?;
+    let cloud_listener = cloud_tcp
+        .listen("127.0.0.1:0", tcp_listener_options)
+        .await?;
+
+    let server_tcp = TcpTransport::create(ctx).await?;
+    let cloud_connection = server_tcp
+        .connect(cloud_listener.socket_string(), TcpConnectionOptions::new())
+        .await?;
+
+    let options = RemoteRelayOptions::new().with_heartbeat_interval(Duration::from_millis(50));
+    let heartbeats = options.heartbeats();
+    RemoteRelay::create(ctx, cloud_connection, options).await?;
+
+    assert_eq!(heartbeats.status().last_success_at(), None);
+
+    ctx.sleep(Duration::from_millis(500)).await;
+
+    let status = heartbeats.status();
+    assert!(status.last_success_at().is_some());
+    assert!(status.round_trip_time().is_some());
+    assert_eq!(status.consecutive_failures(), 0);
+
+    Ok(())
+}
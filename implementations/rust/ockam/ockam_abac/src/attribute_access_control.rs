@@ -12,7 +12,7 @@ use ockam_core::{IncomingAccessControl, RelayMessage};
 
 use crate::expr::str;
 use crate::Expr::*;
-use crate::{eval, Env, Expr};
+use crate::{eval, failing_component, Env, Expr};
 use ockam_core::compat::format;
 use ockam_core::compat::string::ToString;
 use ockam_identity::{Identifier, IdentitiesAttributes, IdentitySecureChannelLocalInfo};
@@ -91,6 +91,50 @@ impl AbacAccessControl {
 impl AbacAccessControl {
     /// Returns true if the identity is authorized
     pub async fn is_identity_authorized(&self, id: Identifier) -> Result<bool> {
+        let environment = self.identity_environment(&id).await?;
+
+        // Finally, evaluate the expression and return the result:
+        match eval(&self.policy_expression, &environment) {
+            Ok(Expr::Bool(b)) => {
+                debug! {
+                    policy        = %self.policy_expression,
+                    id            = %id,
+                    is_authorized = %b,
+                    "policy evaluated"
+                }
+                Ok(b)
+            }
+            Ok(x) => {
+                warn! {
+                    policy = %self.policy_expression,
+                    id     = %id,
+                    expr   = %x,
+                    "evaluation did not yield a boolean result"
+                }
+                Ok(false)
+            }
+            Err(e) => {
+                warn! {
+                    policy = %self.policy_expression,
+                    id     = %id,
+                    err    = %e,
+                    "policy evaluation failed"
+                }
+                Ok(false)
+            }
+        }
+    }
+
+    /// Return the component of the policy expression which denies access to an identity,
+    /// or `None` if the identity is authorized
+    pub async fn failing_component(&self, id: Identifier) -> Result<Option<Expr>> {
+        let environment = self.identity_environment(&id).await?;
+        Ok(failing_component(&self.policy_expression, &environment))
+    }
+
+    /// Return the environment used to evaluate the policy expression for an identity:
+    /// its identifier and the attributes attested by the authority
+    async fn identity_environment(&self, id: &Identifier) -> Result<Env> {
         let mut environment = self.environment.clone();
 
         // add the identifier itself as a subject parameter
@@ -103,7 +147,7 @@ impl AbacAccessControl {
         // Get identity attributes and populate the environment:
         match self
             .identities_attributes
-            .get_attributes(&id, &self.authority)
+            .get_attributes(id, &self.authority)
             .await?
         {
             Some(attrs) => {
@@ -164,37 +208,7 @@ impl AbacAccessControl {
                 );
             }
         }
-
-        // Finally, evaluate the expression and return the result:
-        match eval(&self.policy_expression, &environment) {
-            Ok(Expr::Bool(b)) => {
-                debug! {
-                    policy        = %self.policy_expression,
-                    id            = %id,
-                    is_authorized = %b,
-                    "policy evaluated"
-                }
-                Ok(b)
-            }
-            Ok(x) => {
-                warn! {
-                    policy = %self.policy_expression,
-                    id     = %id,
-                    expr   = %x,
-                    "evaluation did not yield a boolean result"
-                }
-                Ok(false)
-            }
-            Err(e) => {
-                warn! {
-                    policy = %self.policy_expression,
-                    id     = %id,
-                    err    = %e,
-                    "policy evaluation failed"
-                }
-                Ok(false)
-            }
-        }
+        Ok(environment)
     }
 }
 
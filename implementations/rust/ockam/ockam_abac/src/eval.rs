@@ -266,6 +266,24 @@ pub fn eval(expr: &Expr, env: &Env) -> Result<Expr, EvalError> {
     Ok(pop(&mut args))
 }
 
+/// Return the part of an expression which makes it evaluate to something else than `true`.
+///
+/// For an `and` expression, this is the failing component of its first argument which does not
+/// evaluate to `true`. For any other expression, this is the expression itself.
+/// Return `None` if the expression evaluates to `true`.
+pub fn failing_component(expr: &Expr, env: &Env) -> Option<Expr> {
+    if matches!(eval(expr, env), Ok(Expr::Bool(true))) {
+        return None;
+    }
+    match expr {
+        Expr::List(xs) if matches!(xs.first(), Some(Expr::Ident(id)) if id == "and") => xs[1..]
+            .iter()
+            .find_map(|x| failing_component(x, env))
+            .or_else(|| Some(expr.clone())),
+        _ => Some(expr.clone()),
+    }
+}
+
 /// Pop off the topmost stack value.
 ///
 /// # Panics
@@ -296,7 +314,7 @@ where
 #[cfg(test)]
 mod tests {
     use crate::attribute_access_control::{ABAC_HAS_CREDENTIAL_KEY, SUBJECT_KEY};
-    use crate::{eval, Env, Expr};
+    use crate::{eval, failing_component, parse, Env, Expr};
 
     #[test]
     fn test() {
@@ -324,4 +342,33 @@ mod tests {
         let res = eval(&check_credential_expression, &environment).unwrap();
         matches!(res, Expr::Bool(true));
     }
+
+    #[test]
+    fn test_failing_component() {
+        let mut environment = Env::new();
+        environment.put("subject.has_credential", Expr::CONST_TRUE);
+        environment.put("subject.component", Expr::Str("db".into()));
+
+        let expression = parse(r#"(and subject.has_credential (= subject.component "web"))"#)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            failing_component(&expression, &environment),
+            Some(parse(r#"(= subject.component "web")"#).unwrap().unwrap())
+        );
+
+        // an 'or' expression fails as a whole
+        let expression = parse(r#"(or (= subject.component "web") (= subject.component "api"))"#)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            failing_component(&expression, &environment),
+            Some(expression.clone())
+        );
+
+        let expression = parse(r#"(and subject.has_credential (= subject.component "db"))"#)
+            .unwrap()
+            .unwrap();
+        assert_eq!(failing_component(&expression, &environment), None);
+    }
 }
@@ -23,7 +23,7 @@ pub mod resource;
 pub use attribute_access_control::AbacAccessControl;
 pub use env::Env;
 pub use error::{EvalError, ParseError};
-pub use eval::eval;
+pub use eval::{eval, failing_component};
 pub use expr::Expr;
 pub use policy::{storage::*, Policies, PolicyAccessControl, ResourcePolicy, ResourceTypePolicy};
 pub use resource::{Resource, ResourceType};
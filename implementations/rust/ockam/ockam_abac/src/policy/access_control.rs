@@ -1,4 +1,4 @@
-use crate::{AbacAccessControl, Action, Env, Policies, Resource};
+use crate::{AbacAccessControl, Action, Env, Expr, Policies, Resource};
 use core::fmt;
 use core::fmt::{Debug, Formatter};
 use ockam_core::compat::boxed::Box;
@@ -11,6 +11,7 @@ use tracing::debug;
 ///
 /// Attributes come from a pre-populated environment and are augmented
 /// by subject attributes from credential data.
+#[derive(Clone)]
 pub struct PolicyAccessControl {
     policies: Policies,
     identities_attributes: Arc<IdentitiesAttributes>,
@@ -55,6 +56,28 @@ impl PolicyAccessControl {
             action,
         }
     }
+
+    /// Return the component of the policy expression which denies access to an identity.
+    ///
+    /// Return `None` if the identity is authorized, or if there is no policy for
+    /// the resource and action, in which case access is always denied.
+    pub async fn failing_component(&self, id: Identifier) -> ockam_core::Result<Option<Expr>> {
+        let Some(expression) = self
+            .policies
+            .get_expression_for_resource(&self.resource, &self.action)
+            .await?
+        else {
+            return Ok(None);
+        };
+        AbacAccessControl::new(
+            self.identities_attributes.clone(),
+            self.authority.clone(),
+            expression,
+            self.environment.clone(),
+        )
+        .failing_component(id)
+        .await
+    }
 }
 
 #[async_trait]
@@ -1,6 +1,7 @@
-use crate::{Resource, ResourceName};
+use crate::{Resource, ResourceName, ResourceType};
 use ockam_core::async_trait;
 use ockam_core::compat::boxed::Box;
+use ockam_core::compat::vec::Vec;
 use ockam_core::Result;
 
 /// This repository stores resources.
@@ -12,6 +13,16 @@ pub trait ResourcesRepository: Send + Sync + 'static {
     /// Return the policy associated to a given resource name and resource type
     async fn get_resource(&self, resource_name: &ResourceName) -> Result<Option<Resource>>;
 
+    /// Return all the resources of a given resource type
+    async fn get_resources_by_type(&self, resource_type: &ResourceType) -> Result<Vec<Resource>>;
+
     /// Delete all the entries for the given resource name
     async fn delete_resource(&self, resource_name: &ResourceName) -> Result<()>;
+
+    /// Rename a resource, and move the policies stored for its previous name
+    async fn rename_resource(
+        &self,
+        resource_name: &ResourceName,
+        new_resource_name: &ResourceName,
+    ) -> Result<()>;
 }
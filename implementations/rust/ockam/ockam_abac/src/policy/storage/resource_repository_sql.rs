@@ -54,6 +54,18 @@ impl ResourcesRepository for ResourcesSqlxDatabase {
         Ok(row.map(|r| r.try_into()).transpose()?)
     }
 
+    async fn get_resources_by_type(&self, resource_type: &ResourceType) -> Result<Vec<Resource>> {
+        let query = query_as(
+            r#"SELECT resource_name, resource_type
+            FROM resource
+            WHERE node_name=$1 and resource_type=$2"#,
+        )
+        .bind(self.database.node_name()?.to_sql())
+        .bind(resource_type.to_sql());
+        let rows: Vec<ResourceRow> = query.fetch_all(&*self.database.pool).await.into_core()?;
+        rows.into_iter().map(|r| r.try_into()).collect()
+    }
+
     async fn delete_resource(&self, resource_name: &ResourceName) -> Result<()> {
         let mut transaction = self.database.begin().await.into_core()?;
 
@@ -75,6 +87,34 @@ impl ResourcesRepository for ResourcesSqlxDatabase {
 
         transaction.commit().await.void()
     }
+
+    async fn rename_resource(
+        &self,
+        resource_name: &ResourceName,
+        new_resource_name: &ResourceName,
+    ) -> Result<()> {
+        let mut transaction = self.database.begin().await.into_core()?;
+
+        let query = query(
+            r#"UPDATE resource SET resource_name=?
+            WHERE node_name=? and resource_name=?"#,
+        )
+        .bind(new_resource_name.to_sql())
+        .bind(self.database.node_name()?.to_sql())
+        .bind(resource_name.to_sql());
+        query.execute(&mut *transaction).await.void()?;
+
+        let query = sqlx::query(
+            r#"UPDATE resource_policy SET resource_name=?
+            WHERE node_name=? and resource_name=?"#,
+        )
+        .bind(new_resource_name.to_sql())
+        .bind(self.database.node_name()?.to_sql())
+        .bind(resource_name.to_sql());
+        query.execute(&mut *transaction).await.void()?;
+
+        transaction.commit().await.void()
+    }
 }
 
 /// Low-level representation of a row in the resource_type_policy table
@@ -124,6 +164,30 @@ mod test {
         let r2 = Resource::new(rn2.clone(), rt.clone());
         repository.store_resource(&r2).await?;
 
+        // we can list the resources of a given type
+        let r3 = Resource::new(ResourceName::new(&random_string()), ResourceType::TcpInlet);
+        repository.store_resource(&r3).await?;
+        let mut outlets = repository.get_resources_by_type(&rt).await?;
+        outlets.sort_by(|a, b| a.resource_name.as_str().cmp(b.resource_name.as_str()));
+        let mut expected = vec![r1.clone(), r2.clone()];
+        expected.sort_by(|a, b| a.resource_name.as_str().cmp(b.resource_name.as_str()));
+        assert_eq!(outlets, expected);
+        assert_eq!(
+            repository
+                .get_resources_by_type(&ResourceType::TcpInlet)
+                .await?,
+            vec![r3]
+        );
+
+        // we can rename a given entry
+        let rn4 = ResourceName::new(&random_string());
+        repository.rename_resource(&rn2, &rn4).await?;
+        assert!(repository.get_resource(&rn2).await?.is_none());
+        assert_eq!(
+            repository.get_resource(&rn4).await?.unwrap(),
+            Resource::new(rn4.clone(), rt.clone())
+        );
+
         // we can delete a given entry
         repository.delete_resource(&rn1).await?;
         assert!(repository.get_resource(&rn1).await?.is_none());
@@ -24,6 +24,7 @@ pub struct CredentialIssuer {
     credentials: Arc<Credentials>,
     issuer: Identifier,
     subject_attributes: Attributes,
+    /// Default and maximum time-to-live of the issued credentials
     credential_ttl: Duration,
 
     account_authority: Option<AccountAuthorityInfo>,
@@ -66,11 +67,22 @@ impl CredentialIssuer {
         }
     }
 
-    #[instrument(skip_all, fields(subject = %subject))]
+    /// Return the time-to-live of a credential: the requested time-to-live, capped by the
+    /// configured time-to-live, or the configured time-to-live if none was requested
+    pub fn credential_ttl(&self, requested_ttl: Option<Duration>) -> Duration {
+        match requested_ttl {
+            Some(requested_ttl) => requested_ttl.min(self.credential_ttl),
+            None => self.credential_ttl,
+        }
+    }
+
+    #[instrument(skip_all, fields(subject = %subject, requested_ttl = requested_ttl.map_or("n/a".to_string(), |d| d.as_secs().to_string())))]
     pub async fn issue_credential(
         &self,
         subject: &Identifier,
+        requested_ttl: Option<Duration>,
     ) -> Result<Option<CredentialAndPurposeKey>> {
+        let credential_ttl = self.credential_ttl(requested_ttl);
         // Check if it has a valid project admin credential
         if let Some(info) = self.account_authority.as_ref() {
             if let Some(attrs) = self
@@ -89,12 +101,7 @@ impl CredentialIssuer {
                     let credential = self
                         .credentials
                         .credentials_creation()
-                        .issue_credential(
-                            &self.issuer,
-                            subject,
-                            subject_attributes,
-                            self.credential_ttl,
-                        )
+                        .issue_credential(&self.issuer, subject, subject_attributes, credential_ttl)
                         .await?;
                     info!("Successfully issued a credential for admin {}", subject);
 
@@ -120,12 +127,7 @@ impl CredentialIssuer {
         let credential = self
             .credentials
             .credentials_creation()
-            .issue_credential(
-                &self.issuer,
-                subject,
-                subject_attributes,
-                self.credential_ttl,
-            )
+            .issue_credential(&self.issuer, subject, subject_attributes, credential_ttl)
             .await?;
 
         info!("Successfully issued a credential for {}", subject);
@@ -2,6 +2,7 @@ use core::time::Duration;
 use minicbor::Decoder;
 use tracing::trace;
 
+use crate::authenticator::credential_issuer::types::IssueCredential;
 use crate::authenticator::credential_issuer::CredentialIssuer;
 use crate::authenticator::direct::AccountAuthorityInfo;
 use crate::authenticator::AuthorityMembersRepository;
@@ -80,7 +81,17 @@ impl Worker for CredentialIssuerWorker {
         }
         let res = match (req.method(), req.path()) {
             (Some(Method::Post), "/") | (Some(Method::Post), "/credential") => {
-                match self.credential_issuer.issue_credential(&from).await {
+                // Older clients don't send a request body
+                let request: IssueCredential = if req.has_body() {
+                    dec.decode()?
+                } else {
+                    IssueCredential::default()
+                };
+                match self
+                    .credential_issuer
+                    .issue_credential(&from, request.ttl())
+                    .await
+                {
                     Ok(Some(crd)) => Response::ok().with_headers(&req).body(crd).to_vec()?,
                     Ok(None) => Response::forbidden(&req, "unauthorized member").to_vec()?,
                     Err(error) => Response::internal_error(&req, &error.to_string()).to_vec()?,
@@ -1,6 +1,7 @@
 #[allow(clippy::module_inception)]
 mod credential_issuer;
 mod credential_issuer_worker;
+pub mod types;
 
 pub use credential_issuer::*;
 pub use credential_issuer_worker::*;
@@ -0,0 +1,23 @@
+use core::time::Duration;
+use minicbor::{Decode, Encode};
+
+/// Request for a credential.
+/// The requested time-to-live is capped by the maximum time-to-live configured on the authority
+#[derive(Debug, Clone, Default, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct IssueCredential {
+    #[n(1)] ttl_secs: Option<u64>,
+}
+
+impl IssueCredential {
+    pub fn new(ttl: Option<Duration>) -> Self {
+        IssueCredential {
+            ttl_secs: ttl.map(|d| d.as_secs()),
+        }
+    }
+
+    pub fn ttl(&self) -> Option<Duration> {
+        self.ttl_secs.map(Duration::from_secs)
+    }
+}
@@ -3,11 +3,13 @@ use std::collections::{BTreeMap, HashMap};
 
 use ockam::identity::AttributesEntry;
 use ockam::identity::Identifier;
-use ockam_core::api::Request;
+use ockam_core::api::{Reply, Request, Status};
 use ockam_core::async_trait;
 use ockam_node::Context;
 
-use crate::authenticator::direct::types::AddMember;
+use crate::authenticator::direct::types::{
+    AddMember, ListMembers, MembersPage, UpdateMember, UpdatedMember,
+};
 use crate::cloud::{AuthorityNodeClient, HasSecureClient};
 use crate::nodes::service::default_address::DefaultAddress;
 
@@ -20,7 +22,18 @@ pub trait Members {
         attributes: BTreeMap<String, String>,
     ) -> miette::Result<()>;
 
-    async fn delete_member(&self, ctx: &Context, identifier: Identifier) -> miette::Result<()>;
+    /// Add and remove attributes of a member and return its attributes before and after the update,
+    /// or return None if the identifier is not a member
+    async fn update_member(
+        &self,
+        ctx: &Context,
+        identifier: Identifier,
+        attributes: BTreeMap<String, String>,
+        removed_attributes: Vec<String>,
+    ) -> miette::Result<Option<UpdatedMember>>;
+
+    /// Delete a member and return true, or return false if the identifier is not a member
+    async fn delete_member(&self, ctx: &Context, identifier: Identifier) -> miette::Result<bool>;
 
     async fn delete_all_members(&self, ctx: &Context, except: Identifier) -> miette::Result<()>;
 
@@ -30,6 +43,14 @@ pub trait Members {
         &self,
         ctx: &Context,
     ) -> miette::Result<HashMap<Identifier, AttributesEntry>>;
+
+    /// Return at most `limit` members, sorted by the time they were added, skipping the first `offset` ones
+    async fn list_members_page(
+        &self,
+        ctx: &Context,
+        offset: u64,
+        limit: u64,
+    ) -> miette::Result<MembersPage>;
 }
 
 #[async_trait]
@@ -49,14 +70,37 @@ impl Members for AuthorityNodeClient {
             .into_diagnostic()
     }
 
-    async fn delete_member(&self, ctx: &Context, identifier: Identifier) -> miette::Result<()> {
+    async fn update_member(
+        &self,
+        ctx: &Context,
+        identifier: Identifier,
+        attributes: BTreeMap<String, String>,
+        removed_attributes: Vec<String>,
+    ) -> miette::Result<Option<UpdatedMember>> {
+        let req = Request::put(format!("/members/{identifier}"))
+            .body(UpdateMember::new(attributes, removed_attributes));
+        match self
+            .get_secure_client()
+            .ask(ctx, DefaultAddress::DIRECT_AUTHENTICATOR, req)
+            .await
+            .into_diagnostic()?
+        {
+            Reply::Failed(_, Some(Status::NotFound)) => Ok(None),
+            reply => reply.success().into_diagnostic().map(Some),
+        }
+    }
+
+    async fn delete_member(&self, ctx: &Context, identifier: Identifier) -> miette::Result<bool> {
         let req = Request::delete(format!("/{identifier}"));
-        self.get_secure_client()
+        match self
+            .get_secure_client()
             .tell(ctx, DefaultAddress::DIRECT_AUTHENTICATOR, req)
             .await
             .into_diagnostic()?
-            .success()
-            .into_diagnostic()
+        {
+            Reply::Failed(_, Some(Status::NotFound)) => Ok(false),
+            reply => reply.success().into_diagnostic().map(|_| true),
+        }
     }
 
     async fn delete_all_members(&self, ctx: &Context, except: Identifier) -> miette::Result<()> {
@@ -93,4 +137,19 @@ impl Members for AuthorityNodeClient {
             .success()
             .into_diagnostic()
     }
+
+    async fn list_members_page(
+        &self,
+        ctx: &Context,
+        offset: u64,
+        limit: u64,
+    ) -> miette::Result<MembersPage> {
+        let req = Request::get("/members_page").body(ListMembers::new(offset, limit));
+        self.get_secure_client()
+            .ask(ctx, DefaultAddress::DIRECT_AUTHENTICATOR, req)
+            .await
+            .into_diagnostic()?
+            .success()
+            .into_diagnostic()
+    }
 }
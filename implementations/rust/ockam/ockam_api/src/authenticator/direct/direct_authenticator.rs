@@ -8,6 +8,7 @@ use ockam_core::compat::sync::Arc;
 use ockam_core::Result;
 
 use crate::authenticator::common::EnrollerAccessControlChecks;
+use crate::authenticator::direct::types::{MembersPage, UpdateMember, UpdatedMember};
 use crate::authenticator::{AuthorityMember, AuthorityMembersRepository};
 
 /// Identity attribute key that indicates the role of the subject
@@ -157,24 +158,164 @@ impl DirectAuthenticator {
 
         let mut res = HashMap::<Identifier, AttributesEntry>::default();
         for member in all_members {
-            let entry = AttributesEntry::new(
-                member.attributes().clone(),
-                member.added_at(),
-                None,
-                Some(member.added_by().clone()),
-            );
-            res.insert(member.identifier().clone(), entry);
+            let (identifier, entry) = Self::member_entry(member);
+            res.insert(identifier, entry);
         }
 
         Ok(Either::Left(res))
     }
 
+    /// Return at most `limit` members, skipping the first `offset` ones,
+    /// and the offset of the next page if there are more members
+    #[instrument(skip_all, fields(enroller = %enroller, offset = offset, limit = limit))]
+    pub async fn list_members_page(
+        &self,
+        enroller: &Identifier,
+        offset: u64,
+        limit: u64,
+    ) -> Result<DirectAuthenticatorResult<MembersPage>> {
+        let check = EnrollerAccessControlChecks::check_identifier(
+            self.members.clone(),
+            self.identities_attributes.clone(),
+            enroller,
+            &self.account_authority,
+        )
+        .await?;
+
+        if !check.is_enroller {
+            warn!("Non-enroller {} is trying to list members", enroller);
+            return Ok(Either::Right(DirectAuthenticatorError(
+                "Non-enroller is trying to list members".to_string(),
+            )));
+        }
+
+        // request one more member to know if there is a next page
+        let mut members = self.members.get_members_page(offset, limit + 1).await?;
+        let next_offset = if members.len() as u64 > limit {
+            members.truncate(limit as usize);
+            Some(offset + limit)
+        } else {
+            None
+        };
+        let members = members.into_iter().map(Self::member_entry).collect();
+
+        Ok(Either::Left(MembersPage::new(members, next_offset)))
+    }
+
+    fn member_entry(member: AuthorityMember) -> (Identifier, AttributesEntry) {
+        let entry = AttributesEntry::new(
+            member.attributes().clone(),
+            member.added_at(),
+            None,
+            Some(member.added_by().clone()),
+        );
+        (member.identifier().clone(), entry)
+    }
+
+    /// Update the attributes of a member and return its attributes before and after the update,
+    /// or return None if the identifier is not a member.
+    /// The credentials issued after the update contain the new attributes
+    #[instrument(skip_all, fields(enroller = %enroller, identifier = %identifier))]
+    pub async fn update_member(
+        &self,
+        enroller: &Identifier,
+        identifier: &Identifier,
+        update: &UpdateMember,
+    ) -> Result<DirectAuthenticatorResult<Option<UpdatedMember>>> {
+        let check_enroller = EnrollerAccessControlChecks::check_identifier(
+            self.members.clone(),
+            self.identities_attributes.clone(),
+            enroller,
+            &self.account_authority,
+        )
+        .await?;
+
+        if !check_enroller.is_enroller {
+            warn!(
+                "Non-enroller {} is trying to update member {}",
+                enroller, identifier
+            );
+            return Ok(Either::Right(DirectAuthenticatorError(
+                "Non-enroller is trying to update a member".to_string(),
+            )));
+        }
+
+        let member = match self.members.get_member(identifier).await? {
+            Some(member) => member,
+            None => return Ok(Either::Left(None)),
+        };
+
+        // The attributes of pre-trusted identities are reset every time the authority starts
+        if member.is_pre_trusted() {
+            warn!(
+                "Enroller {} is trying to update a pre trusted identity {}",
+                enroller, identifier
+            );
+            return Ok(Either::Right(DirectAuthenticatorError(
+                "Enroller is trying to update a pre trusted identity".to_string(),
+            )));
+        }
+
+        let before = member.attributes().clone();
+        let after = update.apply(&before);
+
+        // Only admins can update enrollers, or turn a member into an enroller
+        if (EnrollerAccessControlChecks::check_bin_attributes_is_enroller(&before)
+            || EnrollerAccessControlChecks::check_bin_attributes_is_enroller(&after))
+            && !check_enroller.is_admin
+        {
+            warn!(
+                "Not admin {} is trying to update the enroller role of {}",
+                enroller, identifier
+            );
+            return Ok(Either::Right(DirectAuthenticatorError(
+                "Not admin is trying to update an enroller".to_string(),
+            )));
+        }
+
+        if !self
+            .members
+            .update_member_attributes(identifier, after.clone())
+            .await?
+        {
+            // the member was deleted in the meantime
+            return Ok(Either::Left(None));
+        }
+
+        let updated = UpdatedMember::new(
+            identifier.clone(),
+            Self::str_attributes(&before),
+            Self::str_attributes(&after),
+        );
+        info!(
+            "Successfully updated member {} by {}. Attributes: {:?}",
+            identifier,
+            enroller,
+            updated.after()
+        );
+
+        Ok(Either::Left(Some(updated)))
+    }
+
+    fn str_attributes(attributes: &BTreeMap<Vec<u8>, Vec<u8>>) -> BTreeMap<String, String> {
+        attributes
+            .iter()
+            .filter_map(|(k, v)| {
+                Some((
+                    String::from_utf8(k.clone()).ok()?,
+                    String::from_utf8(v.clone()).ok()?,
+                ))
+            })
+            .collect()
+    }
+
+    /// Delete a member and return true, or return false if the identifier is not a member
     #[instrument(skip_all, fields(enroller = %enroller, identifier = %identifier))]
     pub async fn delete_member(
         &self,
         enroller: &Identifier,
         identifier: &Identifier,
-    ) -> Result<DirectAuthenticatorResult<()>> {
+    ) -> Result<DirectAuthenticatorResult<bool>> {
         let check_enroller = EnrollerAccessControlChecks::check_identifier(
             self.members.clone(),
             self.identities_attributes.clone(),
@@ -201,6 +342,10 @@ impl DirectAuthenticator {
         )
         .await?;
 
+        if !check_member.is_member {
+            return Ok(Either::Left(false));
+        }
+
         if check_member.is_pre_trusted {
             warn!(
                 "Enroller {} is trying to delete a pre trusted identity {}",
@@ -225,6 +370,6 @@ impl DirectAuthenticator {
 
         info!("Successfully deleted member {}", identifier);
 
-        Ok(Either::Left(()))
+        Ok(Either::Left(true))
     }
 }
@@ -8,7 +8,7 @@ use ockam_core::compat::sync::Arc;
 use ockam_core::{Result, Routed, Worker};
 use ockam_node::Context;
 
-use crate::authenticator::direct::types::AddMember;
+use crate::authenticator::direct::types::{AddMember, ListMembers, UpdateMember};
 use crate::authenticator::direct::DirectAuthenticator;
 use crate::authenticator::AuthorityMembersRepository;
 
@@ -97,12 +97,49 @@ impl Worker for DirectAuthenticatorWorker {
                     Either::Right(error) => Response::forbidden(&req, &error.0).to_vec()?,
                 }
             }
+            (Some(Method::Get), ["members_page"]) => {
+                let list: ListMembers = dec.decode()?;
+                let res = self
+                    .authenticator
+                    .list_members_page(&from, list.offset(), list.limit())
+                    .await?;
+
+                match res {
+                    Either::Left(page) => Response::ok().with_headers(&req).body(page).to_vec()?,
+                    Either::Right(error) => Response::forbidden(&req, &error.0).to_vec()?,
+                }
+            }
+            (Some(Method::Put), ["members", id]) => {
+                let identifier = Identifier::try_from(id.to_string())?;
+                let update: UpdateMember = dec.decode()?;
+                let res = self
+                    .authenticator
+                    .update_member(&from, &identifier, &update)
+                    .await?;
+
+                match res {
+                    Either::Left(Some(updated)) => {
+                        Response::ok().with_headers(&req).body(updated).to_vec()?
+                    }
+                    Either::Left(None) => Response::not_found(
+                        &req,
+                        &format!("{identifier} is not a member of the project"),
+                    )
+                    .to_vec()?,
+                    Either::Right(error) => Response::forbidden(&req, &error.0).to_vec()?,
+                }
+            }
             (Some(Method::Delete), [id]) | (Some(Method::Delete), ["members", id]) => {
                 let identifier = Identifier::try_from(id.to_string())?;
                 let res = self.authenticator.delete_member(&from, &identifier).await?;
 
                 match res {
-                    Either::Left(_) => Response::ok().with_headers(&req).to_vec()?,
+                    Either::Left(true) => Response::ok().with_headers(&req).to_vec()?,
+                    Either::Left(false) => Response::not_found(
+                        &req,
+                        &format!("{identifier} is not a member of the project"),
+                    )
+                    .to_vec()?,
                     Either::Right(error) => Response::forbidden(&req, &error.0).to_vec()?,
                 }
             }
@@ -1,8 +1,13 @@
 use minicbor::{Decode, Encode};
-use ockam::identity::Identifier;
+use ockam::identity::{AttributesEntry, Identifier, TimestampInSeconds};
+use ockam_multiaddr::MultiAddr;
 use std::collections::BTreeMap;
 use std::time::Duration;
 
+use crate::authenticator::one_time_code::OneTimeCode;
+use crate::cli_state::enrollments::EnrollmentTicket;
+use crate::cloud::project::models::ProjectModel;
+
 #[derive(Debug, Decode, Encode)]
 #[rustfmt::skip]
 #[cbor(map)]
@@ -33,6 +38,84 @@ impl AddMember {
     }
 }
 
+/// Changes to the attributes of an existing member
+#[derive(Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct UpdateMember {
+    #[b(1)] attributes: BTreeMap<String, String>,
+    #[b(2)] removed_attributes: Vec<String>,
+}
+
+impl UpdateMember {
+    pub fn new(attributes: BTreeMap<String, String>, removed_attributes: Vec<String>) -> Self {
+        UpdateMember {
+            attributes,
+            removed_attributes,
+        }
+    }
+
+    /// Attributes which are added or replaced
+    pub fn attributes(&self) -> &BTreeMap<String, String> {
+        &self.attributes
+    }
+
+    /// Keys of the attributes which are removed
+    pub fn removed_attributes(&self) -> &[String] {
+        &self.removed_attributes
+    }
+
+    /// Return the attributes resulting from the application of these changes
+    pub fn apply(&self, attributes: &BTreeMap<Vec<u8>, Vec<u8>>) -> BTreeMap<Vec<u8>, Vec<u8>> {
+        let mut updated = attributes.clone();
+        for key in &self.removed_attributes {
+            updated.remove(key.as_bytes());
+        }
+        updated.extend(
+            self.attributes
+                .iter()
+                .map(|(k, v)| (k.as_bytes().to_vec(), v.as_bytes().to_vec())),
+        );
+        updated
+    }
+}
+
+/// Attributes of a member before and after an update
+#[derive(Debug, Clone, PartialEq, Eq, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct UpdatedMember {
+    #[n(1)] identifier: Identifier,
+    #[b(2)] before: BTreeMap<String, String>,
+    #[b(3)] after: BTreeMap<String, String>,
+}
+
+impl UpdatedMember {
+    pub fn new(
+        identifier: Identifier,
+        before: BTreeMap<String, String>,
+        after: BTreeMap<String, String>,
+    ) -> Self {
+        UpdatedMember {
+            identifier,
+            before,
+            after,
+        }
+    }
+
+    pub fn identifier(&self) -> &Identifier {
+        &self.identifier
+    }
+
+    pub fn before(&self) -> &BTreeMap<String, String> {
+        &self.before
+    }
+
+    pub fn after(&self) -> &BTreeMap<String, String> {
+        &self.after
+    }
+}
+
 #[derive(Debug, Decode, Encode)]
 #[rustfmt::skip]
 #[cbor(map)]
@@ -79,3 +162,128 @@ impl CreateToken {
         self.ttl_secs
     }
 }
+
+/// Enrollment ticket issued by an authority node.
+/// It contains a one-time code, and the information needed to enroll with the authority
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct AuthorityTicket {
+    #[n(1)] one_time_code: OneTimeCode,
+    #[n(2)] project_identifier: String,
+    #[n(3)] authority_change_history: String,
+    #[n(4)] expires_at: u64,
+    #[n(5)] usage_count: u64,
+}
+
+impl AuthorityTicket {
+    pub fn new(
+        one_time_code: OneTimeCode,
+        project_identifier: String,
+        authority_change_history: String,
+        expires_at: TimestampInSeconds,
+        usage_count: u64,
+    ) -> Self {
+        AuthorityTicket {
+            one_time_code,
+            project_identifier,
+            authority_change_history,
+            expires_at: expires_at.0,
+            usage_count,
+        }
+    }
+
+    pub fn one_time_code(&self) -> &OneTimeCode {
+        &self.one_time_code
+    }
+
+    /// Identifier of the project associated to the authority
+    pub fn project_identifier(&self) -> &str {
+        &self.project_identifier
+    }
+
+    /// Hex-encoded change history of the authority identity
+    pub fn authority_change_history(&self) -> &str {
+        &self.authority_change_history
+    }
+
+    pub fn expires_at(&self) -> TimestampInSeconds {
+        TimestampInSeconds(self.expires_at)
+    }
+
+    /// Number of times the one-time code can be used
+    pub fn usage_count(&self) -> u64 {
+        self.usage_count
+    }
+
+    /// Return an enrollment ticket which can be used with `ockam project enroll`.
+    /// The authority is described as a project with no project node
+    pub fn to_enrollment_ticket(
+        &self,
+        project_name: &str,
+        authority_route: &MultiAddr,
+    ) -> EnrollmentTicket {
+        let project = ProjectModel {
+            id: self.project_identifier.clone(),
+            name: project_name.to_string(),
+            authority_access_route: Some(authority_route.to_string()),
+            authority_identity: Some(self.authority_change_history.clone()),
+            ..Default::default()
+        };
+        EnrollmentTicket::new(self.one_time_code.clone(), Some(project))
+    }
+}
+
+/// Request for a page of the members of a project
+#[derive(Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ListMembers {
+    #[n(1)] offset: u64,
+    #[n(2)] limit: u64,
+}
+
+impl ListMembers {
+    pub fn new(offset: u64, limit: u64) -> Self {
+        ListMembers { offset, limit }
+    }
+
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+}
+
+/// Page of the members of a project, sorted by the time they were added
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct MembersPage {
+    #[n(1)] members: Vec<(Identifier, AttributesEntry)>,
+    /// Offset of the next page, if there are more members
+    #[n(2)] next_offset: Option<u64>,
+}
+
+impl MembersPage {
+    pub fn new(members: Vec<(Identifier, AttributesEntry)>, next_offset: Option<u64>) -> Self {
+        MembersPage {
+            members,
+            next_offset,
+        }
+    }
+
+    pub fn members(&self) -> &[(Identifier, AttributesEntry)] {
+        &self.members
+    }
+
+    pub fn into_members(self) -> Vec<(Identifier, AttributesEntry)> {
+        self.members
+    }
+
+    pub fn next_offset(&self) -> Option<u64> {
+        self.next_offset
+    }
+}
@@ -8,9 +8,15 @@ use crate::authenticator::common::EnrollerAccessControlChecks;
 use crate::authenticator::one_time_code::OneTimeCode;
 use crate::authenticator::{
     AuthorityEnrollmentTokenRepository, AuthorityMember, AuthorityMembersRepository,
+    EnrollmentTokenUse,
 };
 
-pub struct EnrollmentTokenAcceptorError(pub String);
+pub enum EnrollmentTokenAcceptorError {
+    /// The token can not be accepted, for the given reason
+    Refused(String),
+    /// The token has expired
+    Expired,
+}
 
 pub type EnrollmentTokenAcceptorResult<T> = Either<T, EnrollmentTokenAcceptorError>;
 
@@ -39,16 +45,20 @@ impl EnrollmentTokenAcceptor {
         // Not allow updating existing members
         if check.is_member {
             warn!("{} is already a member", from);
-            return Ok(Either::Right(EnrollmentTokenAcceptorError(
+            return Ok(Either::Right(EnrollmentTokenAcceptorError::Refused(
                 "Already a member".to_string(),
             )));
         }
 
         let token = match self.tokens.use_token(otc, now()?).await {
-            Ok(Some(token)) => token,
-            Ok(None) => {
+            Ok(EnrollmentTokenUse::Used(token)) => token,
+            Ok(EnrollmentTokenUse::Expired) => {
+                warn!("Expired enrollment token received from {}", from);
+                return Ok(Either::Right(EnrollmentTokenAcceptorError::Expired));
+            }
+            Ok(EnrollmentTokenUse::Unknown) => {
                 warn!("Unknown enrollment token received from {}", from);
-                return Ok(Either::Right(EnrollmentTokenAcceptorError(
+                return Ok(Either::Right(EnrollmentTokenAcceptorError::Refused(
                     "Unknown enrollment token".to_string(),
                 )));
             }
@@ -57,7 +67,7 @@ impl EnrollmentTokenAcceptor {
                     "Error using an enrollment token received from {}. Error: {}",
                     from, err
                 );
-                return Ok(Either::Right(EnrollmentTokenAcceptorError(
+                return Ok(Either::Right(EnrollmentTokenAcceptorError::Refused(
                     "Error using the enrollment token".to_string(),
                 )));
             }
@@ -77,7 +87,7 @@ impl EnrollmentTokenAcceptor {
                 "Error adding member {} using enrollment token: {}",
                 from, err
             );
-            return Ok(Either::Right(EnrollmentTokenAcceptorError(
+            return Ok(Either::Right(EnrollmentTokenAcceptorError::Refused(
                 "Error adding member using enrollment token".to_string(),
             )));
         }
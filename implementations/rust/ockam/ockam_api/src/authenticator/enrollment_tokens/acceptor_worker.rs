@@ -1,13 +1,15 @@
 use either::Either;
 use minicbor::Decoder;
 use ockam::identity::IdentitySecureChannelLocalInfo;
-use ockam_core::api::{Method, RequestHeader, Response};
+use ockam_core::api::{Method, RequestHeader, Response, Status};
 use ockam_core::compat::sync::Arc;
 use ockam_core::{Result, Routed, Worker};
 use ockam_node::Context;
 use tracing::trace;
 
-use crate::authenticator::enrollment_tokens::EnrollmentTokenAcceptor;
+use crate::authenticator::enrollment_tokens::{
+    EnrollmentTokenAcceptor, EnrollmentTokenAcceptorError,
+};
 use crate::authenticator::one_time_code::OneTimeCode;
 use crate::authenticator::{AuthorityEnrollmentTokenRepository, AuthorityMembersRepository};
 
@@ -62,7 +64,15 @@ impl Worker for EnrollmentTokenAcceptorWorker {
                 let res = self.acceptor.accept_token(otc, &from).await?;
                 match res {
                     Either::Left(_) => Response::ok().with_headers(&req).to_vec()?,
-                    Either::Right(error) => Response::forbidden(&req, &error.0).to_vec()?,
+                    Either::Right(EnrollmentTokenAcceptorError::Refused(reason)) => {
+                        Response::forbidden(&req, &reason).to_vec()?
+                    }
+                    // An expired token gets a distinct status, so that clients can tell
+                    // the user to request a new token
+                    Either::Right(EnrollmentTokenAcceptorError::Expired) => {
+                        Response::error(&req, "Expired enrollment token", Status::Unauthorized)
+                            .to_vec()?
+                    }
                 }
             }
             _ => Response::unknown_path(&req).to_vec()?,
@@ -44,7 +44,6 @@ impl EnrollmentTokenIssuer {
         }
     }
 
-    #[instrument(skip_all, fields(enroller = %enroller, token_duration = token_duration.map_or("n/a".to_string(), |d| d.as_secs().to_string()), ttl_count = ttl_count.map_or("n/a".to_string(), |t| t.to_string())))]
     pub async fn issue_token(
         &self,
         enroller: &Identifier,
@@ -52,6 +51,21 @@ impl EnrollmentTokenIssuer {
         token_duration: Option<Duration>,
         ttl_count: Option<u64>,
     ) -> Result<EnrollmentTokenIssuerResult<OneTimeCode>> {
+        Ok(self
+            .issue_enrollment_token(enroller, attrs, token_duration, ttl_count)
+            .await?
+            .map_left(|token| token.one_time_code))
+    }
+
+    /// Issue an enrollment token and return it with its expiration time and usage count
+    #[instrument(skip_all, fields(enroller = %enroller, token_duration = token_duration.map_or("n/a".to_string(), |d| d.as_secs().to_string()), ttl_count = ttl_count.map_or("n/a".to_string(), |t| t.to_string())))]
+    pub async fn issue_enrollment_token(
+        &self,
+        enroller: &Identifier,
+        attrs: BTreeMap<String, String>,
+        token_duration: Option<Duration>,
+        ttl_count: Option<u64>,
+    ) -> Result<EnrollmentTokenIssuerResult<EnrollmentToken>> {
         let check = EnrollerAccessControlChecks::check_identifier(
             self.members.clone(),
             self.identities_attributes.clone(),
@@ -95,7 +109,7 @@ impl EnrollmentTokenIssuer {
         let now = now()?;
         let expires_at = now + max_token_duration.as_secs();
         let tkn = EnrollmentToken {
-            one_time_code: one_time_code.clone(),
+            one_time_code,
             reference: Some(reference.clone()),
             issued_by: enroller.clone(),
             created_at: now,
@@ -103,13 +117,13 @@ impl EnrollmentTokenIssuer {
             ttl_count,
             attrs,
         };
-        self.tokens.store_new_token(tkn).await?;
+        self.tokens.store_new_token(tkn.clone()).await?;
 
         info!(
             "Successfully issued an enrollment token. TTL count: {}, expires_at: {}, reference: {}",
             ttl_count, expires_at.0, reference
         );
 
-        Ok(Either::Left(one_time_code))
+        Ok(Either::Left(tkn))
     }
 }
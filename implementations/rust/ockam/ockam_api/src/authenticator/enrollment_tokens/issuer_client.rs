@@ -6,7 +6,7 @@ use ockam_core::async_trait;
 use ockam_core::compat::time::Duration;
 use ockam_node::Context;
 
-use crate::authenticator::direct::types::CreateToken;
+use crate::authenticator::direct::types::{AuthorityTicket, CreateToken};
 use crate::authenticator::one_time_code::OneTimeCode;
 use crate::cloud::{AuthorityNodeClient, HasSecureClient};
 use crate::nodes::service::default_address::DefaultAddress;
@@ -20,6 +20,16 @@ pub trait TokenIssuer {
         duration: Option<Duration>,
         ttl_count: Option<u64>,
     ) -> miette::Result<OneTimeCode>;
+
+    /// Create an enrollment ticket on an authority node.
+    /// Unlike a one-time code, a ticket contains the information needed to enroll with the authority
+    async fn create_ticket(
+        &self,
+        ctx: &Context,
+        attributes: BTreeMap<String, String>,
+        duration: Option<Duration>,
+        ttl_count: Option<u64>,
+    ) -> miette::Result<AuthorityTicket>;
 }
 
 #[async_trait]
@@ -44,4 +54,25 @@ impl TokenIssuer for AuthorityNodeClient {
             .success()
             .into_diagnostic()
     }
+
+    async fn create_ticket(
+        &self,
+        ctx: &Context,
+        attributes: BTreeMap<String, String>,
+        duration: Option<Duration>,
+        ttl_count: Option<u64>,
+    ) -> miette::Result<AuthorityTicket> {
+        let body = CreateToken::new()
+            .with_attributes(attributes)
+            .with_ttl(duration)
+            .with_ttl_count(ttl_count);
+
+        let req = Request::post("/tickets").body(body);
+        self.get_secure_client()
+            .ask(ctx, DefaultAddress::ENROLLMENT_TOKEN_ISSUER, req)
+            .await
+            .into_diagnostic()?
+            .success()
+            .into_diagnostic()
+    }
 }
@@ -9,13 +9,20 @@ use ockam_core::compat::time::Duration;
 use ockam_core::{Result, Routed, Worker};
 use ockam_node::Context;
 
-use crate::authenticator::direct::types::CreateToken;
+use crate::authenticator::direct::types::{AuthorityTicket, CreateToken};
 use crate::authenticator::direct::AccountAuthorityInfo;
 use crate::authenticator::enrollment_tokens::EnrollmentTokenIssuer;
 use crate::authenticator::{AuthorityEnrollmentTokenRepository, AuthorityMembersRepository};
 
 pub struct EnrollmentTokenIssuerWorker {
     pub(super) issuer: EnrollmentTokenIssuer,
+    pub(super) ticket_authority: Option<TicketAuthority>,
+}
+
+/// Authority information added to the one-time codes in order to create enrollment tickets
+pub(super) struct TicketAuthority {
+    project_identifier: String,
+    authority_change_history: String,
 }
 
 impl EnrollmentTokenIssuerWorker {
@@ -32,8 +39,23 @@ impl EnrollmentTokenIssuerWorker {
                 identities_attributes,
                 account_authority,
             ),
+            ticket_authority: None,
         }
     }
+
+    /// Support the issuance of enrollment tickets, containing the hex-encoded
+    /// change history of the authority and the identifier of its project
+    pub fn with_tickets(
+        mut self,
+        project_identifier: String,
+        authority_change_history: String,
+    ) -> Self {
+        self.ticket_authority = Some(TicketAuthority {
+            project_identifier,
+            authority_change_history,
+        });
+        self
+    }
 }
 
 #[ockam_core::worker]
@@ -82,6 +104,43 @@ impl Worker for EnrollmentTokenIssuerWorker {
                     Either::Right(error) => Response::forbidden(&req, &error.0).to_vec()?,
                 }
             }
+            (Some(Method::Post), "/tickets") => {
+                let att: CreateToken = dec.decode()?;
+                match &self.ticket_authority {
+                    Some(ticket_authority) => {
+                        let duration = att.ttl_secs().map(Duration::from_secs);
+                        let ttl_count = att.ttl_count();
+                        let res = self
+                            .issuer
+                            .issue_enrollment_token(
+                                &from,
+                                att.into_owned_attributes(),
+                                duration,
+                                ttl_count,
+                            )
+                            .await?;
+
+                        match res {
+                            Either::Left(token) => {
+                                let ticket = AuthorityTicket::new(
+                                    token.one_time_code,
+                                    ticket_authority.project_identifier.clone(),
+                                    ticket_authority.authority_change_history.clone(),
+                                    token.expires_at,
+                                    token.ttl_count,
+                                );
+                                Response::ok().with_headers(&req).body(&ticket).to_vec()?
+                            }
+                            Either::Right(error) => Response::forbidden(&req, &error.0).to_vec()?,
+                        }
+                    }
+                    None => Response::not_found(
+                        &req,
+                        "This authority node does not issue enrollment tickets",
+                    )
+                    .to_vec()?,
+                }
+            }
             _ => Response::unknown_path(&req).to_vec()?,
         };
         c.send(return_route, res).await
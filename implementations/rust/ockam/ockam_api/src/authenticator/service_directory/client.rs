@@ -0,0 +1,63 @@
+use miette::IntoDiagnostic;
+
+use ockam_core::api::Request;
+use ockam_core::async_trait;
+use ockam_multiaddr::MultiAddr;
+use ockam_node::Context;
+
+use crate::authenticator::service_directory::types::PublishService;
+use crate::authenticator::PublishedService;
+use crate::cloud::{AuthorityNodeClient, HasSecureClient};
+use crate::nodes::service::default_address::DefaultAddress;
+
+#[async_trait]
+pub trait ServiceDirectory {
+    /// Publish a service under a name, so that other project members can discover its route
+    async fn publish_service(
+        &self,
+        ctx: &Context,
+        name: &str,
+        route: &MultiAddr,
+        description: Option<String>,
+    ) -> miette::Result<()>;
+
+    /// Return the services published by all the project members, sorted by name
+    async fn list_published_services(&self, ctx: &Context)
+        -> miette::Result<Vec<PublishedService>>;
+}
+
+#[async_trait]
+impl ServiceDirectory for AuthorityNodeClient {
+    async fn publish_service(
+        &self,
+        ctx: &Context,
+        name: &str,
+        route: &MultiAddr,
+        description: Option<String>,
+    ) -> miette::Result<()> {
+        let req = Request::post("/services").body(PublishService::new(
+            name,
+            route.to_string(),
+            description,
+        ));
+        self.get_secure_client()
+            .tell(ctx, DefaultAddress::SERVICE_DIRECTORY, req)
+            .await
+            .into_diagnostic()?
+            .success()
+            .into_diagnostic()
+    }
+
+    async fn list_published_services(
+        &self,
+        ctx: &Context,
+    ) -> miette::Result<Vec<PublishedService>> {
+        let req = Request::get("/services");
+        self.get_secure_client()
+            .ask(ctx, DefaultAddress::SERVICE_DIRECTORY, req)
+            .await
+            .into_diagnostic()?
+            .success()
+            .into_diagnostic()
+    }
+}
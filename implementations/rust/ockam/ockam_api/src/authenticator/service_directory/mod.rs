@@ -0,0 +1,7 @@
+pub mod types;
+
+mod client;
+mod service_directory_worker;
+
+pub use client::*;
+pub use service_directory_worker::*;
@@ -0,0 +1,129 @@
+use minicbor::Decoder;
+use tracing::trace;
+
+use ockam::identity::utils::now;
+use ockam::identity::{IdentitiesAttributes, IdentitySecureChannelLocalInfo};
+use ockam_core::api::{Method, RequestHeader, Response};
+use ockam_core::compat::str::FromStr;
+use ockam_core::compat::sync::Arc;
+use ockam_core::{Result, Routed, Worker};
+use ockam_multiaddr::MultiAddr;
+use ockam_node::Context;
+
+use crate::authenticator::common::EnrollerAccessControlChecks;
+use crate::authenticator::direct::AccountAuthorityInfo;
+use crate::authenticator::service_directory::types::PublishService;
+use crate::authenticator::{
+    AuthorityMembersRepository, AuthorityServiceDirectoryRepository, PublishedService,
+};
+
+/// This worker lets project members publish services under a human-readable name,
+/// and discover the services published by other members
+pub struct ServiceDirectoryWorker {
+    services: Arc<dyn AuthorityServiceDirectoryRepository>,
+    members: Arc<dyn AuthorityMembersRepository>,
+    identities_attributes: Arc<IdentitiesAttributes>,
+    account_authority: Option<AccountAuthorityInfo>,
+}
+
+impl ServiceDirectoryWorker {
+    pub fn new(
+        services: Arc<dyn AuthorityServiceDirectoryRepository>,
+        members: Arc<dyn AuthorityMembersRepository>,
+        identities_attributes: Arc<IdentitiesAttributes>,
+        account_authority: Option<AccountAuthorityInfo>,
+    ) -> Self {
+        Self {
+            services,
+            members,
+            identities_attributes,
+            account_authority,
+        }
+    }
+}
+
+#[ockam_core::worker]
+impl Worker for ServiceDirectoryWorker {
+    type Context = Context;
+    type Message = Vec<u8>;
+
+    async fn handle_message(&mut self, c: &mut Context, m: Routed<Self::Message>) -> Result<()> {
+        let secure_channel_info = match IdentitySecureChannelLocalInfo::find_info(m.local_message())
+        {
+            Ok(secure_channel_info) => secure_channel_info,
+            Err(_e) => {
+                let resp = Response::bad_request_no_request("secure channel required").to_vec()?;
+                c.send(m.return_route(), resp).await?;
+                return Ok(());
+            }
+        };
+
+        let from = secure_channel_info.their_identity_id();
+        let return_route = m.return_route();
+        let body = m.into_body()?;
+        let mut dec = Decoder::new(&body);
+        let req: RequestHeader = dec.decode()?;
+        trace! {
+            target: "service_directory",
+            from   = %from,
+            id     = %req.id(),
+            method = ?req.method(),
+            path   = %req.path(),
+            body   = %req.has_body(),
+            "request"
+        }
+
+        // Only project members can publish and discover services
+        let check = EnrollerAccessControlChecks::check_identifier(
+            self.members.clone(),
+            self.identities_attributes.clone(),
+            &from,
+            &self.account_authority,
+        )
+        .await?;
+        if !check.is_member {
+            let resp =
+                Response::forbidden(&req, "only project members can use the service directory")
+                    .to_vec()?;
+            c.send(return_route, resp).await?;
+            return Ok(());
+        }
+
+        let path_segments = req.path_segments::<5>();
+        let res = match (req.method(), path_segments.as_slice()) {
+            (Some(Method::Post), ["services"]) => {
+                let publish: PublishService = dec.decode()?;
+                if publish.name().trim().is_empty() {
+                    Response::bad_request(&req, "the name of a published service can't be empty")
+                        .to_vec()?
+                } else if MultiAddr::from_str(publish.route()).is_err() {
+                    Response::bad_request(
+                        &req,
+                        &format!("the route {} is not a valid multiaddr", publish.route()),
+                    )
+                    .to_vec()?
+                } else {
+                    self.services
+                        .publish_service(PublishedService::new(
+                            publish.name(),
+                            from.clone(),
+                            publish.route(),
+                            publish.description().map(|d| d.to_string()),
+                            now()?,
+                        ))
+                        .await?;
+                    Response::ok().with_headers(&req).to_vec()?
+                }
+            }
+            (Some(Method::Get), ["services"]) => {
+                let services = self.services.get_published_services().await?;
+                Response::ok().with_headers(&req).body(services).to_vec()?
+            }
+            _ => Response::unknown_path(&req).to_vec()?,
+        };
+
+        c.send(return_route, res).await?;
+
+        Ok(())
+    }
+}
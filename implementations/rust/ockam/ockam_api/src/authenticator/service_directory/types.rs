@@ -0,0 +1,38 @@
+use minicbor::{Decode, Encode};
+
+/// Request to publish a service in the directory of the Authority node.
+/// The service is published for the identity sending the request
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PublishService {
+    #[n(1)] name: String,
+    #[n(2)] route: String,
+    #[n(3)] description: Option<String>,
+}
+
+impl PublishService {
+    pub fn new(
+        name: impl Into<String>,
+        route: impl Into<String>,
+        description: Option<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            route: route.into(),
+            description,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn route(&self) -> &str {
+        &self.route
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+}
@@ -1,5 +1,5 @@
 use crate::authenticator::one_time_code::OneTimeCode;
-use crate::authenticator::EnrollmentToken;
+use crate::authenticator::{EnrollmentToken, EnrollmentTokenUse};
 use ockam::identity::TimestampInSeconds;
 use ockam_core::async_trait;
 use ockam_core::compat::boxed::Box;
@@ -8,12 +8,14 @@ use ockam_core::Result;
 /// This repository stores enrollment tokens on the Authority node
 #[async_trait]
 pub trait AuthorityEnrollmentTokenRepository: Send + Sync + 'static {
-    /// Use previously issued token
+    /// Use previously issued token.
+    /// The usage count of the token is decremented atomically, so that a token can't be used
+    /// more times than allowed by concurrent enrollments
     async fn use_token(
         &self,
         one_time_code: OneTimeCode,
         now: TimestampInSeconds,
-    ) -> Result<Option<EnrollmentToken>>;
+    ) -> Result<EnrollmentTokenUse>;
 
     /// Store a newly issued enrolment token
     async fn store_new_token(&self, token: EnrollmentToken) -> Result<()>;
@@ -8,7 +8,7 @@ use ockam_node::database::{FromSqlxError, SqlxDatabase, ToSqlxType, ToVoid};
 
 use crate::authenticator::one_time_code::OneTimeCode;
 use crate::authenticator::{
-    AuthorityEnrollmentTokenRepository, EnrollmentToken, EnrollmentTokenRow,
+    AuthorityEnrollmentTokenRepository, EnrollmentToken, EnrollmentTokenRow, EnrollmentTokenUse,
 };
 
 /// Implementation of [`AuthorityEnrollmentTokenRepository`] trait based on an underlying database
@@ -39,51 +39,70 @@ impl AuthorityEnrollmentTokenRepository for AuthorityEnrollmentTokenSqlxDatabase
         &self,
         one_time_code: OneTimeCode,
         now: TimestampInSeconds,
-    ) -> Result<Option<EnrollmentToken>> {
-        // We need to delete expired tokens regularly
-        // Also makes sure we don't get expired tokens later inside this function
-        let query1 =
-            query("DELETE FROM authority_enrollment_token WHERE expires_at<=?").bind(now.to_sql());
-
-        let res = query1.execute(&*self.database.pool).await.into_core()?;
-        debug!("Deleted {} expired enrollment tokens", res.rows_affected());
-
+    ) -> Result<EnrollmentTokenUse> {
         let mut transaction = self.database.pool.begin().await.into_core()?;
 
+        // Decrementing the usage count first takes a write lock on the database.
+        // This way, concurrent uses of the same token can not exceed its usage count
+        let query1 = query(
+            "UPDATE authority_enrollment_token SET ttl_count=ttl_count-1 WHERE one_time_code=? AND expires_at>? AND ttl_count>0",
+        )
+        .bind(one_time_code.to_sql())
+        .bind(now.to_sql());
+        let used = query1
+            .execute(&mut *transaction)
+            .await
+            .into_core()?
+            .rows_affected()
+            == 1;
+
         let query2 = query_as("SELECT one_time_code, reference, issued_by, created_at, expires_at, ttl_count, attributes FROM authority_enrollment_token WHERE one_time_code=?")
             .bind(one_time_code.to_sql());
         let row: Option<EnrollmentTokenRow> =
             query2.fetch_optional(&mut *transaction).await.into_core()?;
         let token: Option<EnrollmentToken> = row.map(|r| r.try_into()).transpose()?;
 
-        if let Some(token) = &token {
-            if token.ttl_count <= 1 {
-                let query3 = query("DElETE FROM authority_enrollment_token WHERE one_time_code=?")
-                    .bind(one_time_code.to_sql());
-                query3.execute(&mut *transaction).await.void()?;
-                debug!(
-                    "Deleted enrollment token because it has been used. Reference: {}",
-                    token.reference()
-                );
-            } else {
-                let new_ttl_count = token.ttl_count - 1;
-                let query3 = query(
-                    "UPDATE authority_enrollment_token SET ttl_count=? WHERE one_time_code=?",
-                )
-                .bind(new_ttl_count as i64)
-                .bind(one_time_code.to_sql());
-                query3.execute(&mut *transaction).await.void()?;
+        let token_use = match token {
+            Some(mut token) if used => {
+                if token.ttl_count == 0 {
+                    let query3 =
+                        query("DELETE FROM authority_enrollment_token WHERE one_time_code=?")
+                            .bind(one_time_code.to_sql());
+                    query3.execute(&mut *transaction).await.void()?;
+                    debug!(
+                        "Deleted enrollment token because it has been used. Reference: {}",
+                        token.reference()
+                    );
+                } else {
+                    debug!(
+                        "Decreasing enrollment token usage count to {}. Reference: {}",
+                        token.ttl_count,
+                        token.reference()
+                    );
+                }
+                // Return the token as it was before being used
+                token.ttl_count += 1;
+                EnrollmentTokenUse::Used(token)
+            }
+            Some(token) if token.expires_at <= now => {
                 debug!(
-                    "Decreasing enrollment token usage count to {}. Reference: {}",
-                    new_ttl_count,
+                    "The enrollment token has expired. Reference: {}",
                     token.reference()
                 );
+                EnrollmentTokenUse::Expired
             }
-        }
+            _ => EnrollmentTokenUse::Unknown,
+        };
 
         transaction.commit().await.void()?;
 
-        Ok(token)
+        // We need to delete expired tokens regularly
+        let query4 =
+            query("DELETE FROM authority_enrollment_token WHERE expires_at<=?").bind(now.to_sql());
+        let res = query4.execute(&*self.database.pool).await.into_core()?;
+        debug!("Deleted {} expired enrollment tokens", res.rows_affected());
+
+        Ok(token_use)
     }
 
     async fn store_new_token(&self, token: EnrollmentToken) -> Result<()> {
@@ -141,7 +160,10 @@ mod tests {
 
         repository.store_new_token(token).await?;
 
-        let token1 = repository.use_token(one_time_code.clone(), now()?).await?;
+        let token1 = repository
+            .use_token(one_time_code.clone(), now()?)
+            .await?
+            .token();
         assert!(token1.is_some());
         let token1 = token1.unwrap();
         assert_eq!(token1.one_time_code, one_time_code);
@@ -152,7 +174,7 @@ mod tests {
         assert_eq!(token1.ttl_count, 1);
         assert_eq!(token1.attrs, attrs);
 
-        let token2 = repository.use_token(one_time_code, now()?).await?;
+        let token2 = repository.use_token(one_time_code, now()?).await?.token();
         assert!(token2.is_none());
 
         Ok(())
@@ -188,7 +210,10 @@ mod tests {
 
         repository.store_new_token(token).await?;
 
-        let token1 = repository.use_token(one_time_code.clone(), now()?).await?;
+        let token1 = repository
+            .use_token(one_time_code.clone(), now()?)
+            .await?
+            .token();
         assert!(token1.is_some());
         let token1 = token1.unwrap();
         assert_eq!(token1.one_time_code, one_time_code);
@@ -231,9 +256,18 @@ mod tests {
 
         repository.store_new_token(token).await?;
 
-        let token1 = repository.use_token(one_time_code.clone(), now()?).await?;
-        let token2 = repository.use_token(one_time_code.clone(), now()?).await?;
-        let token3 = repository.use_token(one_time_code.clone(), now()?).await?;
+        let token1 = repository
+            .use_token(one_time_code.clone(), now()?)
+            .await?
+            .token();
+        let token2 = repository
+            .use_token(one_time_code.clone(), now()?)
+            .await?
+            .token();
+        let token3 = repository
+            .use_token(one_time_code.clone(), now()?)
+            .await?
+            .token();
         assert!(token1.is_some());
         assert!(token2.is_some());
         assert!(token3.is_none());
@@ -286,7 +320,11 @@ mod tests {
         tokio::time::sleep(Duration::from_secs(2)).await;
 
         let token1 = repository.use_token(one_time_code.clone(), now()?).await?;
-        assert!(token1.is_none());
+        assert!(token1 == EnrollmentTokenUse::Expired);
+
+        // an expired token is deleted
+        let token2 = repository.use_token(one_time_code.clone(), now()?).await?;
+        assert!(token2 == EnrollmentTokenUse::Unknown);
 
         Ok(())
     }
@@ -2,6 +2,7 @@ use crate::authenticator::{AuthorityMember, PreTrustedIdentities};
 use ockam::identity::Identifier;
 use ockam_core::async_trait;
 use ockam_core::compat::boxed::Box;
+use ockam_core::compat::collections::BTreeMap;
 use ockam_core::compat::vec::Vec;
 use ockam_core::Result;
 
@@ -14,12 +15,24 @@ pub trait AuthorityMembersRepository: Send + Sync + 'static {
     /// Return all members of the Project
     async fn get_members(&self) -> Result<Vec<AuthorityMember>>;
 
+    /// Return at most `limit` members of the Project, skipping the first `offset` ones.
+    /// Members are sorted by the time they were added
+    async fn get_members_page(&self, offset: u64, limit: u64) -> Result<Vec<AuthorityMember>>;
+
     /// Delete a member from the Project (unless it's pre-trusted)
     async fn delete_member(&self, identifier: &Identifier) -> Result<()>;
 
     /// Add a member to the Project
     async fn add_member(&self, member: AuthorityMember) -> Result<()>;
 
+    /// Replace the attributes of a member of the Project.
+    /// Return false if the identifier is not a member
+    async fn update_member_attributes(
+        &self,
+        identifier: &Identifier,
+        attributes: BTreeMap<Vec<u8>, Vec<u8>>,
+    ) -> Result<bool>;
+
     /// Remove the old pre-trusted members and store new pre-trusted members
     async fn bootstrap_pre_trusted_members(
         &self,
@@ -4,6 +4,7 @@ use tracing::debug;
 
 use ockam::identity::Identifier;
 use ockam_core::async_trait;
+use ockam_core::compat::collections::BTreeMap;
 use ockam_core::Result;
 use ockam_node::database::{FromSqlxError, SqlxDatabase, ToSqlxType, ToVoid};
 
@@ -50,6 +51,15 @@ impl AuthorityMembersRepository for AuthorityMembersSqlxDatabase {
         row.into_iter().map(|r| r.try_into()).collect()
     }
 
+    async fn get_members_page(&self, offset: u64, limit: u64) -> Result<Vec<AuthorityMember>> {
+        let query = query_as("SELECT identifier, attributes, added_by, added_at, is_pre_trusted FROM authority_member ORDER BY added_at, identifier LIMIT ? OFFSET ?")
+            .bind(limit as i64)
+            .bind(offset as i64);
+        let row: Vec<AuthorityMemberRow> =
+            query.fetch_all(&*self.database.pool).await.into_core()?;
+        row.into_iter().map(|r| r.try_into()).collect()
+    }
+
     async fn delete_member(&self, identifier: &Identifier) -> Result<()> {
         let query = query("DELETE FROM authority_member WHERE identifier=? AND is_pre_trusted=?")
             .bind(identifier.to_sql())
@@ -68,6 +78,18 @@ impl AuthorityMembersRepository for AuthorityMembersSqlxDatabase {
         query.execute(&*self.database.pool).await.void()
     }
 
+    async fn update_member_attributes(
+        &self,
+        identifier: &Identifier,
+        attributes: BTreeMap<Vec<u8>, Vec<u8>>,
+    ) -> Result<bool> {
+        let query = query("UPDATE authority_member SET attributes=? WHERE identifier=?")
+            .bind(minicbor::to_vec(attributes)?.to_sql())
+            .bind(identifier.to_sql());
+        let result = query.execute(&*self.database.pool).await.into_core()?;
+        Ok(result.rows_affected() > 0)
+    }
+
     async fn bootstrap_pre_trusted_members(
         &self,
         pre_trusted_identities: &PreTrustedIdentities,
@@ -100,8 +122,6 @@ mod tests {
     use crate::authenticator::PreTrustedIdentity;
     use ockam::identity::models::IDENTIFIER_LEN;
     use ockam::identity::utils::now;
-    use ockam::identity::Identifier;
-    use ockam_core::compat::collections::BTreeMap;
     use ockam_core::compat::rand::RngCore;
     use ockam_core::compat::sync::Arc;
     use rand::thread_rng;
@@ -159,6 +179,13 @@ mod tests {
         assert!(members.contains(&member1));
         assert!(members.contains(&member2));
 
+        // members are returned in the order they were added
+        let page = repository.get_members_page(0, 1).await?;
+        assert_eq!(page, vec![member1.clone()]);
+        let page = repository.get_members_page(1, 10).await?;
+        assert_eq!(page, vec![member2.clone()]);
+        assert!(repository.get_members_page(2, 10).await?.is_empty());
+
         repository.delete_member(&identifier1).await?;
 
         let members = repository.get_members().await?;
@@ -168,6 +195,47 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_authority_members_repository_update_attributes() -> Result<()> {
+        let repository = create_repository().await?;
+
+        let identifier = random_identifier();
+        let mut attributes = BTreeMap::<Vec<u8>, Vec<u8>>::default();
+        attributes.insert("role".as_bytes().to_vec(), "user".as_bytes().to_vec());
+        let member = AuthorityMember::new(
+            identifier.clone(),
+            attributes,
+            random_identifier(),
+            now()?,
+            false,
+        );
+        repository.add_member(member.clone()).await?;
+
+        let mut updated_attributes = BTreeMap::<Vec<u8>, Vec<u8>>::default();
+        updated_attributes.insert("role".as_bytes().to_vec(), "admin".as_bytes().to_vec());
+        assert!(
+            repository
+                .update_member_attributes(&identifier, updated_attributes.clone())
+                .await?
+        );
+
+        // only the attributes are changed
+        let updated = repository.get_member(&identifier).await?.unwrap();
+        assert_eq!(updated.attributes(), &updated_attributes);
+        assert_eq!(updated.added_by(), member.added_by());
+        assert_eq!(updated.added_at(), member.added_at());
+
+        // an unknown identifier is not updated
+        assert!(
+            !repository
+                .update_member_attributes(&random_identifier(), updated_attributes)
+                .await?
+        );
+        assert_eq!(repository.get_members().await?.len(), 1);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_authority_members_repository_bootstrap() -> Result<()> {
         let repository = create_repository().await?;
@@ -0,0 +1,16 @@
+use crate::authenticator::PublishedService;
+use ockam_core::async_trait;
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
+
+/// This repository stores the services published by project members on the Authority node
+#[async_trait]
+pub trait AuthorityServiceDirectoryRepository: Send + Sync + 'static {
+    /// Publish a service, or replace the service published with the same name
+    /// by the same identity
+    async fn publish_service(&self, service: PublishedService) -> Result<()>;
+
+    /// Return all the published services, sorted by name
+    async fn get_published_services(&self) -> Result<Vec<PublishedService>>;
+}
@@ -0,0 +1,120 @@
+use sqlx::*;
+use tracing::debug;
+
+use ockam_core::async_trait;
+use ockam_core::Result;
+use ockam_node::database::{FromSqlxError, SqlxDatabase, ToSqlxType, ToVoid};
+
+use crate::authenticator::{
+    AuthorityServiceDirectoryRepository, PublishedService, PublishedServiceRow,
+};
+
+/// Implementation of [`AuthorityServiceDirectoryRepository`] trait based on an underlying database
+/// using sqlx as its API, and Sqlite as its driver
+#[derive(Clone)]
+pub struct AuthorityServiceDirectorySqlxDatabase {
+    database: SqlxDatabase,
+}
+
+impl AuthorityServiceDirectorySqlxDatabase {
+    /// Create a new database
+    pub fn new(database: SqlxDatabase) -> Self {
+        debug!("create a repository for the authority service directory");
+        Self { database }
+    }
+
+    /// Create a new in-memory database
+    pub async fn create() -> Result<Self> {
+        Ok(Self::new(
+            SqlxDatabase::in_memory("authority service directory").await?,
+        ))
+    }
+}
+
+#[async_trait]
+impl AuthorityServiceDirectoryRepository for AuthorityServiceDirectorySqlxDatabase {
+    async fn publish_service(&self, service: PublishedService) -> Result<()> {
+        let query =
+            query("INSERT OR REPLACE INTO authority_published_service VALUES (?1, ?2, ?3, ?4, ?5)")
+                .bind(service.name().to_sql())
+                .bind(service.identifier().to_sql())
+                .bind(service.route().to_sql())
+                .bind(service.description().map(|d| d.to_sql()))
+                .bind(service.published_at().to_sql());
+        query.execute(&*self.database.pool).await.void()
+    }
+
+    async fn get_published_services(&self) -> Result<Vec<PublishedService>> {
+        let query = query_as("SELECT name, identifier, route, description, published_at FROM authority_published_service ORDER BY name, published_at");
+        let rows: Vec<PublishedServiceRow> =
+            query.fetch_all(&*self.database.pool).await.into_core()?;
+        rows.into_iter().map(|r| r.try_into()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam::identity::models::IDENTIFIER_LEN;
+    use ockam::identity::{Identifier, TimestampInSeconds};
+    use ockam_core::compat::rand::RngCore;
+    use rand::thread_rng;
+
+    fn random_identifier() -> Identifier {
+        let mut data = [0u8; IDENTIFIER_LEN];
+        thread_rng().fill_bytes(&mut data);
+        Identifier(data)
+    }
+
+    #[tokio::test]
+    async fn test_publish_services() -> Result<()> {
+        let repository = AuthorityServiceDirectorySqlxDatabase::create().await?;
+        let alice = random_identifier();
+        let bob = random_identifier();
+
+        let postgres = PublishedService::new(
+            "postgres-prod",
+            alice.clone(),
+            "/project/p/service/forward_to_db/secure/api/service/outlet",
+            Some("production database".to_string()),
+            TimestampInSeconds(10),
+        );
+        let api = PublishedService::new(
+            "api",
+            alice.clone(),
+            "/project/p/service/forward_to_api/secure/api/service/outlet",
+            None,
+            TimestampInSeconds(20),
+        );
+        repository.publish_service(postgres.clone()).await?;
+        repository.publish_service(api.clone()).await?;
+
+        // the services are sorted by name
+        let services = repository.get_published_services().await?;
+        assert_eq!(services, vec![api.clone(), postgres]);
+
+        // a service published again by the same identity is replaced
+        let postgres = PublishedService::new(
+            "postgres-prod",
+            alice,
+            "/project/p/service/forward_to_db2/secure/api/service/outlet",
+            None,
+            TimestampInSeconds(30),
+        );
+        repository.publish_service(postgres.clone()).await?;
+
+        // another identity can publish a service with the same name
+        let other_postgres = PublishedService::new(
+            "postgres-prod",
+            bob,
+            "/project/p/service/forward_to_db3/secure/api/service/outlet",
+            None,
+            TimestampInSeconds(40),
+        );
+        repository.publish_service(other_postgres.clone()).await?;
+
+        let services = repository.get_published_services().await?;
+        assert_eq!(services, vec![api, postgres, other_postgres]);
+        Ok(())
+    }
+}
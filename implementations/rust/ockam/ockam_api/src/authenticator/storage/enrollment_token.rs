@@ -31,6 +31,28 @@ impl EnrollmentToken {
     }
 }
 
+/// Outcome of the presentation of a [`OneTimeCode`]
+#[derive(Clone, Eq, PartialEq)]
+pub enum EnrollmentTokenUse {
+    /// The token is valid and its usage count has been decremented.
+    /// The returned token has the usage count it had before being used
+    Used(EnrollmentToken),
+    /// The token exists but it has expired. It is deleted
+    Expired,
+    /// The token is unknown, or it has already been used as many times as allowed
+    Unknown,
+}
+
+impl EnrollmentTokenUse {
+    /// Return the token if it could be used
+    pub fn token(self) -> Option<EnrollmentToken> {
+        match self {
+            EnrollmentTokenUse::Used(token) => Some(token),
+            EnrollmentTokenUse::Expired | EnrollmentTokenUse::Unknown => None,
+        }
+    }
+}
+
 // Low-level representation of a table row
 #[derive(sqlx::FromRow)]
 pub(crate) struct EnrollmentTokenRow {
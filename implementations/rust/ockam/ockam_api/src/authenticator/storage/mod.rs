@@ -3,11 +3,17 @@ mod authority_enrollment_token_repository_sql;
 mod authority_member;
 mod authority_members_repository;
 mod authority_members_repository_sql;
+mod authority_service_directory_repository;
+mod authority_service_directory_repository_sql;
 mod enrollment_token;
+mod published_service;
 
 pub use authority_enrollment_token_repository::*;
 pub use authority_enrollment_token_repository_sql::*;
 pub use authority_member::*;
 pub use authority_members_repository::*;
 pub use authority_members_repository_sql::*;
+pub use authority_service_directory_repository::*;
+pub use authority_service_directory_repository_sql::*;
 pub use enrollment_token::*;
+pub use published_service::*;
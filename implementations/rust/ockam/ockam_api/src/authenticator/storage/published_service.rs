@@ -0,0 +1,78 @@
+use minicbor::{Decode, Encode};
+use serde::Serialize;
+
+use ockam::identity::{Identifier, TimestampInSeconds};
+use ockam_core::compat::str::FromStr;
+use ockam_core::{Error, Result};
+
+/// Service published in the directory of the Authority node.
+/// Several project members can publish a service with the same name
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PublishedService {
+    #[n(1)] name: String,
+    #[n(2)] identifier: Identifier,
+    #[n(3)] route: String,
+    #[n(4)] description: Option<String>,
+    #[n(5)] published_at: TimestampInSeconds,
+}
+
+impl PublishedService {
+    pub fn new(
+        name: impl Into<String>,
+        identifier: Identifier,
+        route: impl Into<String>,
+        description: Option<String>,
+        published_at: TimestampInSeconds,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            identifier,
+            route: route.into(),
+            description,
+            published_at,
+        }
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Identifier of the project member who published the service
+    pub fn identifier(&self) -> &Identifier {
+        &self.identifier
+    }
+    /// Full route to the service, as a multiaddr
+    pub fn route(&self) -> &str {
+        &self.route
+    }
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+    pub fn published_at(&self) -> TimestampInSeconds {
+        self.published_at
+    }
+}
+
+// Low-level representation of a table row
+#[derive(sqlx::FromRow)]
+pub(crate) struct PublishedServiceRow {
+    name: String,
+    identifier: String,
+    route: String,
+    description: Option<String>,
+    published_at: i64,
+}
+
+impl TryFrom<PublishedServiceRow> for PublishedService {
+    type Error = Error;
+
+    fn try_from(value: PublishedServiceRow) -> Result<Self, Self::Error> {
+        Ok(PublishedService::new(
+            value.name,
+            Identifier::from_str(&value.identifier)?,
+            value.route,
+            value.description,
+            TimestampInSeconds(value.published_at as u64),
+        ))
+    }
+}
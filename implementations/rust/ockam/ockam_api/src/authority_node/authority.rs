@@ -7,9 +7,11 @@ use crate::authenticator::direct::{AccountAuthorityInfo, DirectAuthenticatorWork
 use crate::authenticator::enrollment_tokens::{
     EnrollmentTokenAcceptorWorker, EnrollmentTokenIssuerWorker,
 };
+use crate::authenticator::service_directory::ServiceDirectoryWorker;
 use crate::authenticator::{
     AuthorityEnrollmentTokenRepository, AuthorityEnrollmentTokenSqlxDatabase,
-    AuthorityMembersRepository, AuthorityMembersSqlxDatabase,
+    AuthorityMembersRepository, AuthorityMembersSqlxDatabase, AuthorityServiceDirectoryRepository,
+    AuthorityServiceDirectorySqlxDatabase,
 };
 use ockam::identity::{
     Identifier, Identities, SecureChannelListenerOptions, SecureChannels, TrustEveryonePolicy,
@@ -34,11 +36,13 @@ use crate::nodes::service::default_address::DefaultAddress;
 //   - a credential issuer
 //   - an enrollment token issuer
 //   - an enrollment token acceptor
+//   - a service directory
 pub struct Authority {
     identifier: Identifier,
     secure_channels: Arc<SecureChannels>,
     members: Arc<dyn AuthorityMembersRepository>,
     tokens: Arc<dyn AuthorityEnrollmentTokenRepository>,
+    services: Arc<dyn AuthorityServiceDirectoryRepository>,
     account_authority: Option<AccountAuthorityInfo>,
 }
 
@@ -69,6 +73,7 @@ impl Authority {
 
         let members = Arc::new(AuthorityMembersSqlxDatabase::new(database.clone()));
         let tokens = Arc::new(AuthorityEnrollmentTokenSqlxDatabase::new(database.clone()));
+        let services = Arc::new(AuthorityServiceDirectorySqlxDatabase::new(database.clone()));
 
         Self::bootstrap_repository(members.clone(), configuration).await?;
 
@@ -98,6 +103,7 @@ impl Authority {
             secure_channels,
             members,
             tokens,
+            services,
             account_authority,
         })
     }
@@ -183,12 +189,19 @@ impl Authority {
             return Ok(());
         }
 
+        let authority_change_history = self
+            .secure_channels
+            .identities()
+            .get_identity(&self.identifier)
+            .await?
+            .export_as_string()?;
         let issuer = EnrollmentTokenIssuerWorker::new(
             self.tokens.clone(),
             self.members.clone(),
             self.secure_channels.identities().identities_attributes(),
             self.account_authority.clone(),
-        );
+        )
+        .with_tickets(configuration.project_identifier(), authority_change_history);
         let acceptor =
             EnrollmentTokenAcceptorWorker::new(self.tokens.clone(), self.members.clone());
 
@@ -247,6 +260,29 @@ impl Authority {
         Ok(())
     }
 
+    /// Start the service directory, where project members publish and discover services
+    pub async fn start_service_directory(
+        &self,
+        ctx: &Context,
+        secure_channel_flow_control_id: &FlowControlId,
+    ) -> Result<()> {
+        let directory = ServiceDirectoryWorker::new(
+            self.services.clone(),
+            self.members.clone(),
+            self.secure_channels.identities().identities_attributes(),
+            self.account_authority.clone(),
+        );
+
+        let address = DefaultAddress::SERVICE_DIRECTORY.to_string();
+        ctx.flow_controls()
+            .add_consumer(address.clone(), secure_channel_flow_control_id);
+
+        ctx.start_worker(address.clone(), directory).await?;
+
+        info!("started a service directory at '{address}'");
+        Ok(())
+    }
+
     /// Start the Okta service to retrieve attributes authenticated by Okta
     pub async fn start_okta(
         &self,
@@ -0,0 +1,70 @@
+use std::fmt::{Display, Formatter};
+use std::net::SocketAddr;
+
+use minicbor::{Decode, Encode};
+use serde::Serialize;
+use tokio::io::{copy, sink};
+use tokio::net::TcpListener;
+
+use ockam::Result;
+
+use crate::error::ApiError;
+
+/// What the TCP server targeted by `ockam bench portal` does with the data it receives
+#[derive(Copy, Clone, Debug, Decode, Encode, Serialize, PartialEq, Eq, Default)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+#[serde(rename_all = "kebab-case")]
+pub enum BenchTargetMode {
+    /// The data is sent back, so that the round-trip time of each payload can be measured
+    #[default]
+    #[n(0)] Echo,
+    /// The data is dropped, so that only the throughput from the inlet to the outlet is measured
+    #[n(1)] Discard,
+}
+
+impl Display for BenchTargetMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            BenchTargetMode::Echo => "echo",
+            BenchTargetMode::Discard => "discard",
+        })
+    }
+}
+
+/// Start a TCP server, on a local port, which echoes or discards the data of its connections.
+/// The server runs as long as the node
+pub async fn start_bench_target(mode: BenchTargetMode) -> Result<SocketAddr> {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .map_err(|e| ApiError::core(format!("cannot start the bench target: {e}")))?;
+    let socket_addr = listener
+        .local_addr()
+        .map_err(|e| ApiError::core(format!("cannot start the bench target: {e}")))?;
+    info!(%socket_addr, %mode, "started a bench target");
+
+    tokio::spawn(async move {
+        loop {
+            let (stream, peer) = match listener.accept().await {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    warn!(%socket_addr, %e, "the bench target stopped accepting connections");
+                    return;
+                }
+            };
+            let _ = stream.set_nodelay(true);
+            tokio::spawn(async move {
+                let (mut reader, mut writer) = stream.into_split();
+                let result = match mode {
+                    BenchTargetMode::Echo => copy(&mut reader, &mut writer).await,
+                    BenchTargetMode::Discard => copy(&mut reader, &mut sink()).await,
+                };
+                match result {
+                    Ok(bytes) => debug!(%peer, bytes, "bench connection closed"),
+                    Err(e) => debug!(%peer, %e, "bench connection failed"),
+                }
+            });
+        }
+    });
+    Ok(socket_addr)
+}
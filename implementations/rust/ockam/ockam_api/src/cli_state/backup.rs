@@ -0,0 +1,173 @@
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use rand::random;
+
+use crate::cli_state::{CliState, CliStateError, Result};
+
+/// The methods below support the backup of the local state to a tar.gz archive,
+/// and its restoration.
+///
+/// An archive contains the files which are deleted by `ockam reset`:
+///
+///  - the nodes database
+///  - the nodes directory, containing the nodes logs
+///  - the files of the vaults stored in the state directory
+///
+/// The application database is not part of the archive since it is never reset.
+impl CliState {
+    /// Write a tar.gz archive of the local state to the given path
+    #[instrument(skip_all, fields(path = %path.display()))]
+    pub async fn backup(&self, path: &Path) -> Result<()> {
+        let mut entries = vec![Self::make_database_path(&self.dir())];
+        let nodes_dir = Self::make_nodes_dir_path(&self.dir());
+        if nodes_dir.exists() {
+            entries.push(nodes_dir);
+        }
+        for vault in self.get_named_vaults().await? {
+            let vault_path = vault.path();
+            if vault_path == self.database_path() {
+                continue;
+            }
+            if vault_path.starts_with(self.dir()) && vault_path.exists() {
+                entries.push(vault_path);
+            } else {
+                warn!(vault = %vault.name(), path = %vault_path.display(), "The vault file is not stored in the state directory and is not part of the backup");
+            }
+        }
+
+        let file = File::create(path)?;
+        let mut archive = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+        for entry in entries {
+            let name = entry
+                .strip_prefix(self.dir())
+                .map_err(|_| CliStateError::InvalidPath(entry.display().to_string()))?;
+            if entry.is_dir() {
+                archive.append_dir_all(name, &entry)?;
+            } else {
+                archive.append_path_with_name(&entry, name)?;
+            }
+        }
+        archive.into_inner()?.finish()?;
+        info!("The local state was backed up to {path:?}");
+        Ok(())
+    }
+
+    /// Stop the running nodes and replace the local state with the content of a backup archive.
+    ///
+    /// A new `CliState` must be created to access the restored state.
+    #[instrument(skip_all, fields(path = %path.display()))]
+    pub async fn restore(&self, path: &Path) -> Result<()> {
+        for node in self.get_nodes().await? {
+            if node.is_running() {
+                self.stop_node(&node.name(), true).await?;
+            }
+        }
+        Self::restore_at(&self.dir(), path)
+    }
+
+    /// Replace the local state stored in `root_path` with the content of a backup archive.
+    ///
+    /// The archive is first unpacked to a temporary directory, so that the current state is left
+    /// untouched if the archive can't be read.
+    /// Nodes must be stopped before calling this function.
+    pub fn restore_at(root_path: &Path, archive_path: &Path) -> Result<()> {
+        let unpack_dir = root_path.join(format!(".restore-{}", hex::encode(random::<[u8; 4]>())));
+        let result = Self::unpack_backup(archive_path, &unpack_dir)
+            .and_then(|_| Self::replace_state(root_path, &unpack_dir));
+        let _ = std::fs::remove_dir_all(&unpack_dir);
+        result?;
+        info!("The local state was restored from {archive_path:?}");
+        Ok(())
+    }
+
+    /// Unpack a backup archive and check that it contains a nodes database
+    fn unpack_backup(archive_path: &Path, unpack_dir: &Path) -> Result<()> {
+        let file = File::open(archive_path)?;
+        let mut archive = tar::Archive::new(GzDecoder::new(file));
+        // Entries with absolute paths or '..' components are skipped by unpack
+        archive.unpack(unpack_dir).map_err(|e| {
+            CliStateError::InvalidData(format!(
+                "The file {} is not a valid backup archive: {e}",
+                archive_path.display()
+            ))
+        })?;
+        if !Self::make_database_path(unpack_dir).exists() {
+            return Err(CliStateError::InvalidData(format!(
+                "The file {} is not a valid backup archive: the nodes database is missing",
+                archive_path.display()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Delete the current state files and move the unpacked files in their place
+    fn replace_state(root_path: &Path, unpack_dir: &Path) -> Result<()> {
+        Self::delete_at(root_path)?;
+        for entry in std::fs::read_dir(unpack_dir)? {
+            let entry = entry?;
+            let target: PathBuf = root_path.join(entry.file_name());
+            if target.is_dir() {
+                std::fs::remove_dir_all(&target)?;
+            } else if target.exists() {
+                std::fs::remove_file(&target)?;
+            }
+            std::fs::rename(entry.path(), target)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_backup_and_restore() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let archive = dir.path().join("backup.tar.gz");
+
+        let state = CliState::create(dir.path().join("state")).await?;
+        state.create_node("node-1").await?;
+        // the second vault is stored in a separate file
+        state.get_or_create_named_vault("vault-1").await?;
+        state.get_or_create_named_vault("vault-2").await?;
+        state.backup(&archive).await?;
+
+        // create more state after the backup
+        state.create_node("node-2").await?;
+
+        CliState::restore_at(&state.dir(), &archive)?;
+
+        // only the state present at the time of the backup is available
+        let state = CliState::create(state.dir()).await?;
+        let nodes = state.get_nodes().await?;
+        assert_eq!(
+            nodes.iter().map(|n| n.name()).collect::<Vec<_>>(),
+            vec!["node-1".to_string()]
+        );
+        let vault = state.get_named_vault("vault-2").await?;
+        assert!(vault.path().exists());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_restore_invalid_archive() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let state_dir = dir.path().join("state");
+        let state = CliState::create(state_dir.clone()).await?;
+        state.create_node("node-1").await?;
+
+        let archive = dir.path().join("invalid.tar.gz");
+        std::fs::write(&archive, "not an archive")?;
+        assert!(CliState::restore_at(&state_dir, &archive).is_err());
+
+        // the current state is left untouched
+        let state = CliState::create(state_dir).await?;
+        assert_eq!(state.get_nodes().await?.len(), 1);
+        Ok(())
+    }
+}
@@ -1,7 +1,9 @@
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use colorful::{Colorful, RGB};
 use rand::random;
+use serde::Serialize;
 
 use cli_state::error::Result;
 use ockam::SqlxDatabase;
@@ -29,6 +31,8 @@ const NOTIFICATIONS_CHANNEL_CAPACITY: usize = 16;
 ///
 /// - One file per additional vault created with the `ockam vault create` command
 ///
+/// - A "state.lock" file, used to serialize the updates of the state when several processes use it. See [`crate::cli_state::StateLock`]
+///
 /// The database files are accessed with the SqlxDatabase struct, and use different migration files to define their
 /// schema.
 ///
@@ -46,6 +50,7 @@ pub struct CliState {
     database: SqlxDatabase,
     application_database: SqlxDatabase,
     exporting_enabled: ExportingEnabled,
+    defaults: CliDefaults,
     /// Broadcast channel to be notified of major events during a process supported by the
     /// CliState API
     notifications: Sender<Notification>,
@@ -108,10 +113,99 @@ impl CliState {
 
     /// Stop nodes and remove all the directories storing state
     pub async fn reset(&self) -> Result<()> {
-        self.delete_all_named_identities().await?;
-        self.delete_all_nodes(true).await?;
-        self.delete_all_named_vaults().await?;
-        self.delete()
+        self.with_write_lock(async {
+            self.delete_all_named_identities().await?;
+            self.delete_all_nodes(true).await?;
+            self.delete_all_named_vaults().await?;
+            self.delete()
+        })
+        .await
+    }
+
+    /// Stop and delete only the selected parts of the local state.
+    ///
+    /// Nodes are stopped before being deleted. Identities can only be deleted if they are not used
+    /// by any remaining node, this is checked before anything is deleted.
+    /// The returned summary lists the names of all the deleted entities.
+    #[instrument(skip_all, fields(selection = ?selection))]
+    pub async fn reset_selection(&self, selection: &ResetSelection) -> Result<ResetSummary> {
+        self.with_write_lock(self.reset_selection_impl(selection))
+            .await
+    }
+
+    async fn reset_selection_impl(&self, selection: &ResetSelection) -> Result<ResetSummary> {
+        if selection.is_everything() {
+            let summary = ResetSummary {
+                nodes: self.get_nodes().await?.iter().map(|n| n.name()).collect(),
+                identities: self
+                    .get_named_identities()
+                    .await?
+                    .iter()
+                    .map(|i| i.name())
+                    .collect(),
+                projects: self
+                    .projects()
+                    .get_projects()
+                    .await?
+                    .iter()
+                    .map(|p| p.name().to_string())
+                    .collect(),
+                spaces: self
+                    .get_spaces()
+                    .await?
+                    .iter()
+                    .map(|s| s.name.clone())
+                    .collect(),
+            };
+            self.reset().await?;
+            return Ok(summary);
+        }
+
+        let identities = if selection.identities {
+            self.get_named_identities().await?
+        } else {
+            vec![]
+        };
+        if !selection.nodes {
+            for identity in identities.iter() {
+                let nodes = self.get_nodes_by_identity_name(&identity.name()).await?;
+                if !nodes.is_empty() {
+                    let node_names: Vec<String> = nodes.iter().map(|n| n.name()).collect();
+                    return Err(CliStateError::InvalidOperation(format!(
+                        "The identity named {} cannot be deleted because it is used by the node(s): {}. \
+                         Select the nodes as well to delete them",
+                        identity.name(),
+                        node_names.join(", ")
+                    )));
+                }
+            }
+        }
+
+        let mut summary = ResetSummary::default();
+        if selection.nodes {
+            for node in self.get_nodes().await? {
+                self.delete_node(&node.name(), true).await?;
+                summary.nodes.push(node.name());
+            }
+        }
+        for identity in identities {
+            self.delete_identity_by_name(&identity.name()).await?;
+            summary.identities.push(identity.name());
+        }
+        if selection.projects {
+            let projects = self.projects();
+            for project in projects.get_projects().await? {
+                projects.delete_project(project.project_id()).await?;
+                summary.projects.push(project.name().to_string());
+            }
+        }
+        if selection.spaces {
+            for space in self.get_spaces().await? {
+                self.delete_space(&space.space_id()).await?;
+                summary.spaces.push(space.name.clone());
+            }
+        }
+        Ok(summary)
     }
 
     /// Removes all the directories storing state without loading the current state
@@ -177,6 +271,44 @@ impl CliState {
     }
 }
 
+/// Parts of the local state which can be reset independently.
+/// When nothing is selected, the whole state is reset.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResetSelection {
+    pub nodes: bool,
+    pub identities: bool,
+    pub projects: bool,
+    pub spaces: bool,
+}
+
+impl ResetSelection {
+    /// Return true if the whole state must be reset
+    pub fn is_everything(&self) -> bool {
+        !(self.nodes || self.identities || self.projects || self.spaces)
+    }
+}
+
+/// Names of the entities deleted by a reset
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ResetSummary {
+    pub nodes: Vec<String>,
+    pub identities: Vec<String>,
+    pub projects: Vec<String>,
+    pub spaces: Vec<String>,
+}
+
+/// Values used when a command does not specify them, for example when they are
+/// set in a configuration file shared by all the users of a machine
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CliDefaults {
+    /// Node used instead of the default node
+    pub node_name: Option<String>,
+    /// Identity used instead of the default identity
+    pub identity_name: Option<String>,
+    /// Timeout of the requests sent to the local nodes
+    pub request_timeout: Option<Duration>,
+}
+
 /// Low-level functions for creating / deleting CliState files
 impl CliState {
     /// Create a new CliState where the data is stored at a given path
@@ -203,6 +335,7 @@ impl CliState {
             // the function set_tracing_enabled can be used to enable tracing, which
             // is eventually used to trace user journeys.
             exporting_enabled: ExportingEnabled::Off,
+            defaults: CliDefaults::default(),
             notifications,
         };
         Ok(state)
@@ -223,6 +356,15 @@ impl CliState {
         }
     }
 
+    /// Return the values used when a command does not specify them
+    pub fn defaults(&self) -> CliDefaults {
+        self.defaults.clone()
+    }
+
+    pub fn set_defaults(self, defaults: CliDefaults) -> CliState {
+        CliState { defaults, ..self }
+    }
+
     pub(super) fn make_database_path(root_path: &Path) -> PathBuf {
         root_path.join("database.sqlite3")
     }
@@ -240,11 +382,13 @@ impl CliState {
     }
 
     /// Delete the state files
-    fn delete_at(root_path: &Path) -> Result<()> {
+    pub(super) fn delete_at(root_path: &Path) -> Result<()> {
         // Delete nodes logs
         let _ = std::fs::remove_dir_all(Self::make_nodes_dir_path(root_path));
         // Delete the nodes database, keep the application database
         let _ = std::fs::remove_file(Self::make_database_path(root_path));
+        // Delete the lock file, it is recreated by the next state mutation
+        let _ = std::fs::remove_file(Self::make_lock_path(root_path));
         Ok(())
     }
 
@@ -253,7 +397,7 @@ impl CliState {
     /// $OCKAM_HOME/.ockam.
     ///
     /// If $OCKAM_HOME is not defined then $HOME is used instead
-    pub(super) fn default_dir() -> Result<PathBuf> {
+    pub fn default_dir() -> Result<PathBuf> {
         Ok(get_env_with_default::<PathBuf>(
             "OCKAM_HOME",
             home::home_dir()
@@ -308,7 +452,8 @@ mod tests {
             [
                 "vault-vault2".to_string(),
                 "application_database.sqlite3".to_string(),
-                "database.sqlite3".to_string()
+                "database.sqlite3".to_string(),
+                "state.lock".to_string()
             ]
             .iter()
             .sorted()
@@ -327,6 +472,50 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_reset_selection() -> Result<()> {
+        let db_file = NamedTempFile::new().unwrap();
+        let cli_state_directory = db_file.path().parent().unwrap().join(random_name());
+        let cli = CliState::create(cli_state_directory.clone()).await?;
+
+        let identity1 = cli.create_identity_with_name("identity1").await?;
+        cli.create_identity_with_name("identity2").await?;
+        cli.create_node_with_identifier("node1", &identity1.identifier())
+            .await?;
+
+        // an identity used by a node can't be deleted unless the nodes are selected
+        let selection = ResetSelection {
+            identities: true,
+            ..Default::default()
+        };
+        assert!(cli.reset_selection(&selection).await.is_err());
+        assert_eq!(cli.get_named_identities().await?.len(), 2);
+
+        // only the nodes are deleted
+        let selection = ResetSelection {
+            nodes: true,
+            ..Default::default()
+        };
+        let summary = cli.reset_selection(&selection).await?;
+        assert_eq!(summary.nodes, vec!["node1".to_string()]);
+        assert!(summary.identities.is_empty());
+        assert!(cli.get_nodes().await?.is_empty());
+        assert_eq!(cli.get_named_identities().await?.len(), 2);
+
+        // then the identities
+        let selection = ResetSelection {
+            identities: true,
+            ..Default::default()
+        };
+        let summary = cli.reset_selection(&selection).await?;
+        assert_eq!(
+            summary.identities.iter().sorted().as_slice(),
+            ["identity1".to_string(), "identity2".to_string()]
+        );
+        assert!(cli.get_named_identities().await?.is_empty());
+        Ok(())
+    }
+
     /// HELPERS
     fn list_file_names(dir: &Path) -> Vec<String> {
         fs::read_dir(dir)
@@ -1,15 +1,20 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 use time::OffsetDateTime;
 
 use crate::authenticator::one_time_code::OneTimeCode;
+use ockam::identity::models::CredentialData;
 use ockam::identity::Identifier;
 
 use crate::cli_state::Result;
 use crate::cli_state::{CliState, CliStateError};
 use crate::cloud::email_address::EmailAddress;
 use crate::cloud::project::models::ProjectModel;
+use crate::cloud::project::Project;
 use crate::error::ApiError;
+use crate::nodes::service::CredentialScope;
 
 /// The following CliState methods help keeping track of
 ///
@@ -102,6 +107,96 @@ impl CliState {
 
         Ok(true)
     }
+
+    /// Return the membership of an identity for each known project.
+    ///
+    /// An identity is a member of a project if it is one of its administrators, or if
+    /// a project member credential issued to that identity was cached by any node.
+    /// When several credentials are cached for the same project, the one expiring last is returned.
+    #[instrument(skip_all, fields(name = name))]
+    pub async fn get_project_memberships(&self, name: &str) -> Result<Vec<ProjectMembership>> {
+        let identifier = self.get_identifier_by_name(name).await?;
+        let enrolled_at = self
+            .get_identity_enrollments(EnrollmentStatus::Enrolled)
+            .await?
+            .into_iter()
+            .find(|enrollment| enrollment.identifier() == &identifier)
+            .and_then(|enrollment| enrollment.enrolled_at());
+        let credentials = self
+            .credentials_repository()
+            .get_all_for_subject(&identifier)
+            .await?;
+
+        let mut memberships = vec![];
+        for project in self.projects().get_projects().await? {
+            let is_admin = self.is_project_admin(&identifier, &project).await?;
+            let credential = credentials
+                .iter()
+                .filter(|(_, scope)| {
+                    matches!(
+                        CredentialScope::from_str(scope),
+                        Ok(CredentialScope::ProjectMember { project_id }) if project_id == project.project_id()
+                    )
+                })
+                .filter_map(|(credential, _)| credential.get_credential_data().ok())
+                .max_by_key(|data| data.expires_at.0);
+            memberships.push(ProjectMembership {
+                project,
+                enrolled_at: if is_admin { enrolled_at } else { None },
+                is_admin,
+                credential,
+            });
+        }
+        Ok(memberships)
+    }
+}
+
+/// Membership of an identity in a project, as known locally
+#[derive(Clone, Debug)]
+pub struct ProjectMembership {
+    project: Project,
+    enrolled_at: Option<OffsetDateTime>,
+    is_admin: bool,
+    credential: Option<CredentialData>,
+}
+
+impl ProjectMembership {
+    pub fn project(&self) -> &Project {
+        &self.project
+    }
+
+    /// Return true if the identity is an administrator of the project or holds a credential for it
+    pub fn is_enrolled(&self) -> bool {
+        self.is_admin || self.credential.is_some()
+    }
+
+    pub fn is_admin(&self) -> bool {
+        self.is_admin
+    }
+
+    /// Return the enrollment date of the identity, if it administers the project
+    pub fn enrolled_at(&self) -> Option<OffsetDateTime> {
+        self.enrolled_at
+    }
+
+    /// Return the cached credential expiring last
+    pub fn credential(&self) -> Option<&CredentialData> {
+        self.credential.as_ref()
+    }
+
+    /// Return the attributes of the cached credential, as strings
+    pub fn credential_attributes(&self) -> BTreeMap<String, String> {
+        self.credential
+            .iter()
+            .flat_map(|credential| credential.subject_attributes.map.iter())
+            .map(|(key, value)| {
+                (
+                    String::from_utf8_lossy(key).to_string(),
+                    String::from_utf8_lossy(value).to_string(),
+                )
+            })
+            .collect()
+    }
 }
 
 #[derive(Debug)]
@@ -45,6 +45,27 @@ pub enum CliStateError {
     #[diagnostic(code("OCK500"))]
     InvalidOperation(String),
 
+    #[error("The local state is locked by {owner}")]
+    #[diagnostic(
+        code("OCK409"),
+        help("Please wait for the other ockam command to finish, or increase the OCKAM_STATE_LOCK_TIMEOUT value")
+    )]
+    Locked { owner: String },
+
+    #[error("The vault {name} is encrypted and locked")]
+    #[diagnostic(
+        code("OCK401"),
+        help("Please set the OCKAM_VAULT_PASSWORD environment variable with the vault passphrase, or run the command in an interactive terminal")
+    )]
+    VaultLocked { name: String },
+
+    #[error("The passphrase of the vault {name} is incorrect")]
+    #[diagnostic(
+        code("OCK401"),
+        help("Please try again with the passphrase used to encrypt the vault")
+    )]
+    InvalidVaultPassphrase { name: String },
+
     #[error("Invalid configuration version '{0}'")]
     #[diagnostic(
         code("OCK500"),
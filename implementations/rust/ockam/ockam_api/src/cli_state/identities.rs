@@ -40,6 +40,14 @@ impl CliState {
         };
 
         let vault = self.get_named_vault(vault_name).await?;
+
+        // if the vault is associated to a specific KMS key, use it instead of creating a new key
+        if let Some(kms_key_id) = vault.kms_key_id() {
+            return self
+                .create_identity_with_key_id(name, vault_name, &kms_key_id)
+                .await;
+        }
+
         let identities = self.make_identities(vault.vault().await?).await?;
         let identity = identities.identities_creation().create_identity().await?;
 
@@ -141,8 +149,8 @@ impl CliState {
         &self,
         name: &Option<String>,
     ) -> Result<NamedIdentity> {
-        match name {
-            // Identity specified.
+        match name.as_ref().or(self.defaults().identity_name.as_ref()) {
+            // Identity specified, or configured as the default identity.
             Some(name) => self.get_named_identity(name).await,
             // No identity specified.
             None => self.get_or_create_default_named_identity().await,
@@ -325,6 +333,22 @@ impl CliState {
         Ok(self.identities_repository().set_as_default(name).await?)
     }
 
+    /// Rotate the key of a named identity.
+    ///
+    /// The identity keeps the same identifier but a new change, signed by both the previous
+    /// and the new key, is appended to its change history.
+    #[instrument(skip_all, fields(name = %name))]
+    pub async fn rotate_identity_by_name(&self, name: &str) -> Result<NamedIdentity> {
+        let named_identity = self.get_named_identity(name).await?;
+        let vault = self.get_named_vault(&named_identity.vault_name()).await?;
+        let identities = self.make_identities(vault.vault().await?).await?;
+        identities
+            .identities_creation()
+            .rotate_identity(&named_identity.identifier())
+            .await?;
+        Ok(named_identity)
+    }
+
     /// Delete an identity by name:
     ///
     ///  - check that the identity is not used by a node first
@@ -366,20 +390,23 @@ impl CliState {
         name: &str,
         vault_name: &str,
     ) -> Result<NamedIdentity> {
-        let repository = self.identities_repository();
+        self.with_write_lock(async {
+            let repository = self.identities_repository();
 
-        // If there is no previously created identity we set this identity as the default one
-        let is_default_identity = repository.get_default_named_identity().await?.is_none();
-        let mut named_identity = repository
-            .store_named_identity(identifier, name, vault_name)
-            .await?;
-        if is_default_identity {
-            repository
-                .set_as_default_by_identifier(&named_identity.identifier())
+            // If there is no previously created identity we set this identity as the default one
+            let is_default_identity = repository.get_default_named_identity().await?.is_none();
+            let mut named_identity = repository
+                .store_named_identity(identifier, name, vault_name)
                 .await?;
-            named_identity = named_identity.set_as_default();
-        }
-        Ok(named_identity)
+            if is_default_identity {
+                repository
+                    .set_as_default_by_identifier(&named_identity.identifier())
+                    .await?;
+                named_identity = named_identity.set_as_default();
+            }
+            Ok(named_identity)
+        })
+        .await
     }
 
     /// Return the change history of a persisted identity
@@ -530,6 +557,22 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_rotate_identity() -> Result<()> {
+        let cli = CliState::test().await?;
+        let identity = cli.create_identity_with_name("name").await?;
+        let before = cli.get_change_history(&identity.identifier()).await?;
+
+        // the identifier is kept but a new change is added to the change history
+        let rotated = cli.rotate_identity_by_name("name").await?;
+        assert_eq!(rotated.identifier(), identity.identifier());
+
+        let after = cli.get_change_history(&identity.identifier()).await?;
+        assert_eq!(after.0.len(), before.0.len() + 1);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_delete_identity() -> Result<()> {
         let cli = CliState::test().await?;
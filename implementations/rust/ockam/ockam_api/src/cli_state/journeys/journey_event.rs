@@ -14,6 +14,21 @@ pub const TCP_INLET_TO: &Key = &Key::from_static_str("app.tcp_inlet.to");
 pub const TCP_INLET_ALIAS: &Key = &Key::from_static_str("app.tcp_inlet.alias");
 pub const TCP_INLET_CONNECTION_STATUS: &Key =
     &Key::from_static_str("app.tcp_inlet.connection_status");
+pub const TCP_INLET_PREVIOUS_CONNECTION_STATUS: &Key =
+    &Key::from_static_str("app.tcp_inlet.previous_connection_status");
+pub const TCP_INLET_CONNECTION_FAILURE: &Key =
+    &Key::from_static_str("app.tcp_inlet.connection_failure");
+pub const TCP_INLET_RECONNECTION_ATTEMPTS: &Key =
+    &Key::from_static_str("app.tcp_inlet.reconnection_attempts");
+
+pub const RELAY_NAME: &Key = &Key::from_static_str("app.relay.name");
+pub const RELAY_RECONNECTION_ATTEMPTS: &Key =
+    &Key::from_static_str("app.relay.reconnection_attempts");
+
+pub const SECURE_CHANNEL_ADDRESS: &Key = &Key::from_static_str("app.secure_channel.address");
+pub const SECURE_CHANNEL_THEIR_IDENTIFIER: &Key =
+    &Key::from_static_str("app.secure_channel.their_identifier");
+pub const SECURE_CHANNEL_USED_BY: &Key = &Key::from_static_str("app.secure_channel.used_by");
 
 /// List of all the journey events that we want to track
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -21,9 +36,13 @@ pub enum JourneyEvent {
     Enrolled,
     NodeCreated,
     TcpInletCreated,
+    TcpInletConnectionLost,
+    TcpInletConnectionRestored,
     TcpOutletCreated,
     RelayCreated,
+    RelayReconnected,
     PortalCreated,
+    SecureChannelDeleted,
     Ok {
         command_name: String,
     },
@@ -44,6 +63,84 @@ impl JourneyEvent {
             message,
         }
     }
+
+    /// Name of the event, used to store and select events, for example `tcp-inlet-created`
+    pub fn name(&self) -> &'static str {
+        match self {
+            JourneyEvent::Enrolled => "enrolled",
+            JourneyEvent::NodeCreated => "node-created",
+            JourneyEvent::TcpInletCreated => "tcp-inlet-created",
+            JourneyEvent::TcpInletConnectionLost => "tcp-inlet-connection-lost",
+            JourneyEvent::TcpInletConnectionRestored => "tcp-inlet-connection-restored",
+            JourneyEvent::TcpOutletCreated => "tcp-outlet-created",
+            JourneyEvent::RelayCreated => "relay-created",
+            JourneyEvent::RelayReconnected => "relay-reconnected",
+            JourneyEvent::PortalCreated => "portal-created",
+            JourneyEvent::SecureChannelDeleted => "secure-channel-deleted",
+            JourneyEvent::Ok { .. } => "command-ok",
+            JourneyEvent::Error { .. } => "command-error",
+        }
+    }
+
+    /// Names of all the events
+    pub fn names() -> Vec<&'static str> {
+        vec![
+            "enrolled",
+            "node-created",
+            "tcp-inlet-created",
+            "tcp-inlet-connection-lost",
+            "tcp-inlet-connection-restored",
+            "tcp-outlet-created",
+            "relay-created",
+            "relay-reconnected",
+            "portal-created",
+            "secure-channel-deleted",
+            "command-ok",
+            "command-error",
+        ]
+    }
+}
+
+/// Return a human-readable name for an attribute key.
+/// For example `app.tcp_inlet.alias` is displayed as `TCP inlet alias`
+pub fn attribute_display_name(key: &str) -> String {
+    let name = match key {
+        "app.tcp_outlet.at" => "TCP outlet node",
+        "app.tcp_outlet.from" => "TCP outlet from",
+        "app.tcp_outlet.to" => "TCP outlet to",
+        "app.tcp_outlet.alias" => "TCP outlet alias",
+        "app.tcp_inlet.at" => "TCP inlet node",
+        "app.tcp_inlet.from" => "TCP inlet from",
+        "app.tcp_inlet.to" => "TCP inlet to",
+        "app.tcp_inlet.alias" => "TCP inlet alias",
+        "app.tcp_inlet.connection_status" => "TCP inlet connection status",
+        "app.tcp_inlet.previous_connection_status" => "TCP inlet previous connection status",
+        "app.tcp_inlet.connection_failure" => "TCP inlet connection failure",
+        "app.tcp_inlet.reconnection_attempts" => "TCP inlet reconnection attempts",
+        "app.relay.name" => "Relay name",
+        "app.relay.reconnection_attempts" => "Relay reconnection attempts",
+        "app.secure_channel.address" => "Secure channel address",
+        "app.secure_channel.their_identifier" => "Secure channel peer identifier",
+        "app.secure_channel.used_by" => "Secure channel used by",
+        "app.user_name" => "User name",
+        "app.user_email" => "User email",
+        "app.node_name" => "Node name",
+        "app.event.command" => "Command",
+        "app.event.error_message" => "Error message",
+        other => {
+            // app.event.some_key -> Some key
+            let name = other
+                .trim_start_matches("app.")
+                .trim_start_matches("event.")
+                .replace(['.', '_'], " ");
+            let mut chars = name.chars();
+            return match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => name,
+            };
+        }
+    };
+    name.to_string()
 }
 
 impl Display for JourneyEvent {
@@ -52,9 +149,15 @@ impl Display for JourneyEvent {
             JourneyEvent::Enrolled => f.write_str("✅ enrolled"),
             JourneyEvent::NodeCreated => f.write_str("✅ node created"),
             JourneyEvent::TcpInletCreated => f.write_str("✅ tcp inlet created"),
+            JourneyEvent::TcpInletConnectionLost => f.write_str("❌ tcp inlet connection lost"),
+            JourneyEvent::TcpInletConnectionRestored => {
+                f.write_str("✅ tcp inlet connection restored")
+            }
             JourneyEvent::TcpOutletCreated => f.write_str("✅ tcp outlet created"),
             JourneyEvent::RelayCreated => f.write_str("✅ relay created"),
+            JourneyEvent::RelayReconnected => f.write_str("✅ relay reconnected"),
             JourneyEvent::PortalCreated => f.write_str("✅ portal created"),
+            JourneyEvent::SecureChannelDeleted => f.write_str("❌ secure channel deleted"),
             JourneyEvent::Ok { command_name } => f.write_str(command_name),
             JourneyEvent::Error { command_name, .. } => {
                 f.write_fmt(format_args!("❌ {} error", command_name))
@@ -62,3 +165,34 @@ impl Display for JourneyEvent {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attribute_display_name() {
+        assert_eq!(
+            attribute_display_name(TCP_INLET_ALIAS.as_str()),
+            "TCP inlet alias"
+        );
+        assert_eq!(attribute_display_name(RELAY_NAME.as_str()), "Relay name");
+        assert_eq!(
+            attribute_display_name("app.event.ockam_version"),
+            "Ockam version"
+        );
+    }
+
+    #[test]
+    fn test_event_names() {
+        for event in [
+            JourneyEvent::Enrolled,
+            JourneyEvent::TcpInletCreated,
+            JourneyEvent::SecureChannelDeleted,
+            JourneyEvent::ok("command".to_string()),
+            JourneyEvent::error("command".to_string(), "message".to_string()),
+        ] {
+            assert!(JourneyEvent::names().contains(&event.name()));
+        }
+    }
+}
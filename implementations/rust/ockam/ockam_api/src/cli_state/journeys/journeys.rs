@@ -2,7 +2,9 @@ use crate::cloud::project::Project;
 use crate::journeys::attributes::{
     default_attributes, make_host, make_host_trace_id, make_journey_span_id, make_project_trace_id,
 };
-use crate::journeys::{Journey, JourneyEvent, ProjectJourney};
+use crate::journeys::{
+    Journey, JourneyEvent, JourneyEventsFilter, ProjectJourney, StoredJourneyEvent,
+};
 use crate::logs::CurrentSpan;
 use crate::{CliState, Result};
 use chrono::{DateTime, Utc};
@@ -10,7 +12,7 @@ use either::Either;
 use ockam_core::{OpenTelemetryContext, OCKAM_TRACER_NAME};
 use opentelemetry::trace::{Link, SpanBuilder, SpanId, TraceContextExt, TraceId, Tracer};
 use opentelemetry::{global, Context, Key, KeyValue};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::ops::Add;
 use std::time::{Duration, SystemTime};
 
@@ -69,6 +71,8 @@ const DEFAULT_JOURNEY_MAX_DURATION: Duration = Duration::from_secs(5 * 86400);
 ///  - a project trace: with all the events happening for a given project (so potentially across hosts).
 ///    That trace has a trace id based on the project id and it persists even across resets.
 ///
+/// The events are also stored locally, even when tracing is disabled, so that they can be listed
+/// with `ockam journey list`.
 ///
 impl CliState {
     /// This method adds a successful event to the project/host journeys
@@ -105,6 +109,7 @@ impl CliState {
         event: JourneyEvent,
         attributes: HashMap<&Key, String>,
     ) -> Result<()> {
+        self.store_journey_event(&event, &attributes).await?;
         if !self.is_tracing_enabled() {
             return Ok(());
         }
@@ -135,6 +140,54 @@ impl CliState {
         Ok(())
     }
 
+    /// Store a journey event locally, with the attributes provided by the caller
+    async fn store_journey_event(
+        &self,
+        event: &JourneyEvent,
+        attributes: &HashMap<&Key, String>,
+    ) -> Result<()> {
+        let mut stored_attributes: BTreeMap<String, String> = attributes
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect();
+        if let JourneyEvent::Error { message, .. } = event {
+            stored_attributes.insert(APPLICATION_EVENT_ERROR_MESSAGE.to_string(), message.clone());
+        };
+        let stored_event = StoredJourneyEvent::new(
+            event.name(),
+            &event.to_string(),
+            attributes.get(NODE_NAME).cloned(),
+            stored_attributes,
+            Utc::now(),
+        );
+        Ok(self
+            .user_journey_repository()
+            .store_journey_event(stored_event)
+            .await?)
+    }
+
+    /// Return the journey events stored on this host
+    #[instrument(skip_all)]
+    pub async fn get_journey_events(
+        &self,
+        filter: JourneyEventsFilter,
+    ) -> Result<Vec<StoredJourneyEvent>> {
+        Ok(self
+            .user_journey_repository()
+            .get_journey_events(filter)
+            .await?)
+    }
+
+    /// Delete the journey events stored on this host which happened before a given time.
+    /// Return the number of deleted events
+    #[instrument(skip_all)]
+    pub async fn delete_journey_events_before(&self, before: DateTime<Utc>) -> Result<u64> {
+        Ok(self
+            .user_journey_repository()
+            .delete_journey_events_before(before)
+            .await?)
+    }
+
     /// Add both attributes to the current span
     ///  - caller attributes
     ///  - project attributes
@@ -4,8 +4,10 @@ mod journey_event;
 #[allow(clippy::module_inception)]
 pub mod journeys;
 mod project_journey;
+mod stored_journey_event;
 
 pub use journey::*;
 pub use journey_event::*;
 pub use journeys::*;
 pub use project_journey::*;
+pub use stored_journey_event::*;
@@ -0,0 +1,103 @@
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+
+/// A journey event, as recorded on this host.
+///
+/// Contrary to the spans exported for a journey, stored events only contain the attributes
+/// provided when the event was added. They can be listed with `ockam journey list`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredJourneyEvent {
+    event: String,
+    description: String,
+    node_name: Option<String>,
+    attributes: BTreeMap<String, String>,
+    timestamp: DateTime<Utc>,
+}
+
+impl StoredJourneyEvent {
+    pub fn new(
+        event: &str,
+        description: &str,
+        node_name: Option<String>,
+        attributes: BTreeMap<String, String>,
+        timestamp: DateTime<Utc>,
+    ) -> StoredJourneyEvent {
+        StoredJourneyEvent {
+            event: event.to_string(),
+            description: description.to_string(),
+            node_name,
+            attributes,
+            timestamp,
+        }
+    }
+
+    /// Name of the event, for example `tcp-inlet-created`
+    pub fn event(&self) -> String {
+        self.event.clone()
+    }
+
+    /// Description of the event, as displayed in a journey
+    pub fn description(&self) -> String {
+        self.description.clone()
+    }
+
+    /// Name of the node concerned by the event, if any
+    pub fn node_name(&self) -> Option<String> {
+        self.node_name.clone()
+    }
+
+    /// Attributes of the event, indexed by attribute key
+    pub fn attributes(&self) -> BTreeMap<String, String> {
+        self.attributes.clone()
+    }
+
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+}
+
+/// Criteria used to select stored journey events
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JourneyEventsFilter {
+    since: Option<DateTime<Utc>>,
+    event: Option<String>,
+    node_name: Option<String>,
+}
+
+impl JourneyEventsFilter {
+    /// Only select the events which happened at or after a given time
+    pub fn with_since(self, since: DateTime<Utc>) -> Self {
+        Self {
+            since: Some(since),
+            ..self
+        }
+    }
+
+    /// Only select the events with a given name, for example `tcp-inlet-created`
+    pub fn with_event(self, event: &str) -> Self {
+        Self {
+            event: Some(event.to_string()),
+            ..self
+        }
+    }
+
+    /// Only select the events concerning a given node
+    pub fn with_node_name(self, node_name: &str) -> Self {
+        Self {
+            node_name: Some(node_name.to_string()),
+            ..self
+        }
+    }
+
+    pub fn since(&self) -> Option<DateTime<Utc>> {
+        self.since
+    }
+
+    pub fn event(&self) -> Option<String> {
+        self.event.clone()
+    }
+
+    pub fn node_name(&self) -> Option<String> {
+        self.node_name.clone()
+    }
+}
@@ -0,0 +1,193 @@
+use std::fs::{File, OpenOptions};
+use std::future::Future;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use fs2::FileExt;
+use ockam_core::env::get_env_with_default;
+
+use crate::cli_state::{CliState, CliStateError, Result};
+
+/// Maximum time spent waiting for the lock of the local state.
+/// This value can be overridden with the OCKAM_STATE_LOCK_TIMEOUT environment variable
+const DEFAULT_LOCK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Delay between two attempts to take the lock
+const LOCK_RETRY_DELAY: Duration = Duration::from_millis(20);
+
+tokio::task_local! {
+    /// This value is set when the current task holds the lock, so that locked operations can be nested
+    static LOCK_HELD: ();
+}
+
+/// The StateLock is an advisory file lock which serializes the mutations of the local state
+/// when several `ockam` processes are running concurrently.
+///
+/// Only the operations which need to read and then modify the state, for example to decide which
+/// identity is the default one, take the lock. Read-only operations don't take it.
+///
+/// The lock file contains the process id of the current lock owner, so that the processes which
+/// can't get the lock in time can report it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateLock {
+    path: PathBuf,
+}
+
+impl StateLock {
+    /// Create a lock using a specific file
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Return the path of the lock file
+    pub fn path(&self) -> PathBuf {
+        self.path.clone()
+    }
+
+    /// Run a future while holding the lock.
+    /// If the current task already holds the lock, the future is run directly.
+    pub async fn with_write_lock<T>(&self, f: impl Future<Output = Result<T>>) -> Result<T> {
+        if LOCK_HELD.try_with(|_| ()).is_ok() {
+            return f.await;
+        }
+        // the lock is released when the file is closed
+        let _file = self.lock().await?;
+        LOCK_HELD.scope((), f).await
+    }
+
+    /// Wait until the lock file can be exclusively locked, then write the current process id to it
+    async fn lock(&self) -> Result<File> {
+        let timeout = get_env_with_default("OCKAM_STATE_LOCK_TIMEOUT", DEFAULT_LOCK_TIMEOUT)?;
+        self.lock_with_timeout(timeout).await
+    }
+
+    async fn lock_with_timeout(&self, timeout: Duration) -> Result<File> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&self.path)?;
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            match file.try_lock_exclusive() {
+                Ok(()) => break,
+                Err(e) if e.kind() == fs2::lock_contended_error().kind() => {
+                    if Instant::now() >= deadline {
+                        return Err(CliStateError::Locked {
+                            owner: Self::read_owner(&mut file),
+                        });
+                    }
+                    tokio::time::sleep(LOCK_RETRY_DELAY).await;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        file.set_len(0)?;
+        file.seek(SeekFrom::Start(0))?;
+        write!(file, "{}", std::process::id())?;
+        file.flush()?;
+        Ok(file)
+    }
+
+    /// Return a description of the process currently holding the lock
+    fn read_owner(file: &mut File) -> String {
+        let mut content = String::new();
+        let pid = file
+            .seek(SeekFrom::Start(0))
+            .and_then(|_| file.read_to_string(&mut content))
+            .ok()
+            .and_then(|_| content.trim().parse::<u32>().ok());
+        match pid {
+            Some(pid) => format!("pid {pid}"),
+            None => "another process".to_string(),
+        }
+    }
+}
+
+impl CliState {
+    /// Return the lock used to serialize the mutations of the local state
+    pub fn state_lock(&self) -> StateLock {
+        StateLock::new(Self::make_lock_path(&self.dir()))
+    }
+
+    /// Run a future while holding the lock of the local state
+    pub(super) async fn with_write_lock<T>(&self, f: impl Future<Output = Result<T>>) -> Result<T> {
+        self.state_lock().with_write_lock(f).await
+    }
+
+    pub(super) fn make_lock_path(root_path: &Path) -> PathBuf {
+        root_path.join("state.lock")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli_state::random_name;
+    use tokio::task::JoinSet;
+
+    #[tokio::test]
+    async fn test_concurrent_mutations() -> Result<()> {
+        let dir = CliState::test_dir()?;
+        CliState::create(dir.clone()).await?;
+        let mut tasks = JoinSet::new();
+        for i in 0..20 {
+            let dir = dir.clone();
+            tasks.spawn(async move {
+                // each task uses its own CliState, as separate processes would do
+                let state = CliState::create(dir).await?;
+                let identity = state
+                    .create_identity_with_name(&format!("identity-{i}"))
+                    .await?;
+                state
+                    .create_node_with_identifier(&format!("node-{i}"), &identity.identifier())
+                    .await?;
+                Ok::<(), CliStateError>(())
+            });
+        }
+        while let Some(result) = tasks.join_next().await {
+            result.unwrap()?;
+        }
+
+        let state = CliState::create(dir).await?;
+        let identities = state.get_named_identities().await?;
+        assert_eq!(identities.len(), 20);
+        assert_eq!(identities.iter().filter(|i| i.is_default()).count(), 1);
+        let nodes = state.get_nodes().await?;
+        assert_eq!(nodes.len(), 20);
+        assert_eq!(nodes.iter().filter(|n| n.is_default()).count(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_nested_locks() -> Result<()> {
+        let state = CliState::test().await?;
+        let result = state
+            .with_write_lock(async { state.with_write_lock(async { Ok(1) }).await })
+            .await?;
+        assert_eq!(result, 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_lock_timeout() -> Result<()> {
+        let lock = StateLock::new(CliState::test_dir()?.join(random_name()));
+        let _file = lock.lock().await?;
+
+        // the same file can't be locked twice, even in the same process
+        match lock.lock_with_timeout(Duration::from_millis(100)).await {
+            Err(CliStateError::Locked { owner }) => {
+                assert_eq!(owner, format!("pid {}", std::process::id()))
+            }
+            _ => panic!("the lock should not be acquired"),
+        }
+        Ok(())
+    }
+}
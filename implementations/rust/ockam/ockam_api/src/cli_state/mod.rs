@@ -2,11 +2,18 @@ pub use cli_state::*;
 pub use enrollments::*;
 pub use error::*;
 pub use identities::*;
+pub use lock::*;
 pub use nodes::*;
 pub use notifications::*;
+pub use runs::*;
+pub use state_export::*;
 pub use storage::*;
+pub use ticket_templates::*;
+pub use vault_encryption::*;
+pub use vault_keys::*;
 pub use vaults::*;
 
+mod backup;
 #[allow(clippy::module_inception)]
 pub mod cli_state;
 pub mod enrollments;
@@ -14,16 +21,22 @@ pub mod error;
 pub mod identities;
 mod identities_attributes;
 pub mod journeys;
+pub mod lock;
 pub mod nodes;
 pub mod notifications;
 pub mod policies;
 pub mod projects;
 pub mod repositories;
 mod resources;
+pub mod runs;
 pub mod secure_channels;
 pub mod spaces;
+pub mod state_export;
 pub mod storage;
 pub mod test_support;
+pub mod ticket_templates;
 pub mod trust;
 pub mod users;
+pub mod vault_encryption;
+pub mod vault_keys;
 pub mod vaults;
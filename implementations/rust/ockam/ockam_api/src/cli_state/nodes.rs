@@ -1,5 +1,8 @@
+use std::fmt::{Display, Formatter};
 use std::path::PathBuf;
 use std::process;
+use std::str::FromStr;
+use std::time::Duration;
 
 use nix::errno::Errno;
 use serde::Serialize;
@@ -10,12 +13,13 @@ use ockam::identity::Identifier;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::Error;
 use ockam_multiaddr::MultiAddr;
-use ockam_transport_tcp::TcpListener;
+use ockam_transport_tcp::{TcpListener, TcpProxy};
 
 use crate::cli_state::{random_name, Result};
 use crate::cli_state::{CliState, CliStateError};
 use crate::cloud::project::Project;
 use crate::config::lookup::InternetAddress;
+use crate::logs::{LogFormat, LogLevelFilter, LoggingConfiguration};
 use crate::NamedVault;
 
 /// The methods below support the creation and update of local nodes
@@ -112,6 +116,43 @@ impl CliState {
         Ok(())
     }
 
+    /// Delete a node, giving its process `stop_timeout` to stop gracefully.
+    /// If the process is still running after that time, it is killed with a SIGKILL signal.
+    ///
+    /// Return true if the node process had to be killed
+    #[instrument(skip_all, fields(node_name = node_name))]
+    pub async fn delete_node_with_timeout(
+        &self,
+        node_name: &str,
+        stop_timeout: Duration,
+    ) -> Result<bool> {
+        let node = self.get_node(node_name).await?;
+        self.stop_node(node_name, false).await?;
+
+        let mut killed = false;
+        if let Some(pid) = node.pid() {
+            if pid != process::id() && !Self::wait_for_node_exit(&node, stop_timeout).await {
+                warn!(name = %node_name, %pid, "the node did not stop in time, killing it");
+                Self::send_signal(&node, pid, nix::sys::signal::Signal::SIGKILL)?;
+                killed = true;
+            }
+        }
+        self.remove_node(node_name).await?;
+        Ok(killed)
+    }
+
+    /// Wait until the process of a node exits, and return false if it is still running after the timeout
+    async fn wait_for_node_exit(node: &NodeInfo, timeout: Duration) -> bool {
+        let deadline = tokio::time::Instant::now() + timeout;
+        while node.is_running() {
+            if tokio::time::Instant::now() >= deadline {
+                return false;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        true
+    }
+
     /// Delete all created nodes
     #[instrument(skip_all, fields(force = %force))]
     pub async fn delete_all_nodes(&self, force: bool) -> Result<()> {
@@ -155,16 +196,20 @@ impl CliState {
         };
 
         // remove the node from the database
-        let repository = self.nodes_repository();
-        let node_exists = repository.get_node(node_name).await.is_ok();
-        repository.delete_node(node_name).await?;
-        // set another node as the default node
-        if node_exists {
-            let other_nodes = repository.get_nodes().await?;
-            if let Some(other_node) = other_nodes.first() {
-                repository.set_default_node(&other_node.name()).await?;
+        self.with_write_lock(async {
+            let repository = self.nodes_repository();
+            let node_exists = repository.get_node(node_name).await.is_ok();
+            repository.delete_node(node_name).await?;
+            // set another node as the default node
+            if node_exists {
+                let other_nodes = repository.get_nodes().await?;
+                if let Some(other_node) = other_nodes.first() {
+                    repository.set_default_node(&other_node.name()).await?;
+                }
             }
-        }
+            Ok(())
+        })
+        .await?;
 
         // remove the node directory
         let _ = std::fs::remove_dir_all(self.node_dir(node_name));
@@ -172,6 +217,42 @@ impl CliState {
         Ok(())
     }
 
+    /// Rename a node:
+    ///
+    ///  - rename it in the repository, with the data stored for its previous name
+    ///  - move the node directory
+    ///
+    /// The node must not be running since its process uses its current name
+    #[instrument(skip_all, fields(node_name = node_name, new_node_name = new_node_name))]
+    pub async fn rename_node(&self, node_name: &str, new_node_name: &str) -> Result<NodeInfo> {
+        self.with_write_lock(async {
+            let repository = self.nodes_repository();
+            let node = self.get_node(node_name).await?;
+            if node.is_running() {
+                return Err(CliStateError::InvalidOperation(format!(
+                    "The node {node_name} must be stopped before being renamed"
+                )));
+            }
+            if repository.get_node(new_node_name).await?.is_some() {
+                return Err(CliStateError::AlreadyExists {
+                    resource: "node".to_string(),
+                    name: new_node_name.to_string(),
+                });
+            }
+            repository.rename_node(node_name, new_node_name).await?;
+            Ok(())
+        })
+        .await?;
+
+        // move the node directory
+        let node_dir = self.node_dir(node_name);
+        if node_dir.exists() {
+            std::fs::rename(node_dir, self.node_dir(new_node_name))?;
+        }
+        debug!(name=%node_name, new_name=%new_node_name, "node renamed");
+        self.get_node(new_node_name).await
+    }
+
     /// Stop a background node
     ///
     ///  - if force is true, send a SIGKILL signal to the node process
@@ -187,14 +268,20 @@ impl CliState {
             if pid == process::id() {
                 return Ok(());
             }
-            nix::sys::signal::kill(
-                nix::unistd::Pid::from_raw(pid as i32),
-                if force {
-                    nix::sys::signal::Signal::SIGKILL
-                } else {
-                    nix::sys::signal::Signal::SIGTERM
-                },
-            )
+            let signal = if force {
+                nix::sys::signal::Signal::SIGKILL
+            } else {
+                nix::sys::signal::Signal::SIGTERM
+            };
+            Self::send_signal(&node, pid, signal)?;
+        }
+        info!(name = %node.name(), "node process killed");
+        Ok(())
+    }
+
+    /// Send a signal to the process of a node. A process which does not exist anymore is ignored
+    fn send_signal(node: &NodeInfo, pid: u32, signal: nix::sys::signal::Signal) -> Result<()> {
+        nix::sys::signal::kill(nix::unistd::Pid::from_raw(pid as i32), signal)
             .or_else(|e| {
                 if e == Errno::ESRCH {
                     tracing::warn!(node = %node.name(), %pid, "No such process");
@@ -208,10 +295,7 @@ impl CliState {
                     std::io::ErrorKind::Other,
                     format!("failed to stop PID `{pid}` with error `{e}`"),
                 ))
-            })?;
-        }
-        info!(name = %node.name(), "node process killed");
-        Ok(())
+            })
     }
 
     /// Set a node as the default node
@@ -253,6 +337,164 @@ impl CliState {
     }
 }
 
+/// The following methods support the supervision of background nodes
+impl CliState {
+    /// Record that a background node must be restarted when its process exits unexpectedly
+    #[instrument(skip_all, fields(node_name = node_name, max_restarts = max_restarts))]
+    pub async fn set_node_supervision(
+        &self,
+        node_name: &str,
+        max_restarts: u32,
+        max_backoff: Duration,
+    ) -> Result<NodeSupervision> {
+        let supervision = NodeSupervision::new(node_name, max_restarts, max_backoff);
+        self.nodes_repository()
+            .set_node_supervision(&supervision)
+            .await?;
+        Ok(supervision)
+    }
+
+    /// Return the supervision settings and state of a node, if it is supervised
+    #[instrument(skip_all, fields(node_name = node_name))]
+    pub async fn get_node_supervision(&self, node_name: &str) -> Result<Option<NodeSupervision>> {
+        Ok(self
+            .nodes_repository()
+            .get_node_supervision(node_name)
+            .await?)
+    }
+
+    /// Update the supervision state of a node
+    #[instrument(skip_all, fields(node_name = supervision.node_name()))]
+    pub async fn update_node_supervision(&self, supervision: &NodeSupervision) -> Result<()> {
+        Ok(self
+            .nodes_repository()
+            .set_node_supervision(supervision)
+            .await?)
+    }
+}
+
+/// The following methods support the proxy configuration of nodes
+impl CliState {
+    /// Record the HTTP proxy that a node uses for its outgoing TCP connections.
+    /// The proxy URL and the no-proxy list are validated before being stored
+    #[instrument(skip_all, fields(node_name = node_name))]
+    pub async fn set_node_proxy(
+        &self,
+        node_name: &str,
+        proxy_url: &str,
+        no_proxy: Option<&str>,
+    ) -> Result<NodeProxy> {
+        let proxy = NodeProxy::new(node_name, proxy_url, no_proxy);
+        proxy.tcp_proxy()?;
+        self.nodes_repository().set_node_proxy(&proxy).await?;
+        Ok(proxy)
+    }
+
+    /// Return the HTTP proxy used by a node, if any
+    #[instrument(skip_all, fields(node_name = node_name))]
+    pub async fn get_node_proxy(&self, node_name: &str) -> Result<Option<NodeProxy>> {
+        Ok(self.nodes_repository().get_node_proxy(node_name).await?)
+    }
+
+    /// Stop using an HTTP proxy for the outgoing TCP connections of a node
+    #[instrument(skip_all, fields(node_name = node_name))]
+    pub async fn delete_node_proxy(&self, node_name: &str) -> Result<()> {
+        Ok(self.nodes_repository().delete_node_proxy(node_name).await?)
+    }
+}
+
+/// The following methods support the logging configuration of nodes
+impl CliState {
+    /// Record the logging configuration of a node, or remove it if it doesn't set anything
+    #[instrument(skip_all, fields(node_name = logging.node_name()))]
+    pub async fn set_node_logging(&self, logging: &NodeLogging) -> Result<()> {
+        if logging.is_empty() {
+            self.nodes_repository()
+                .delete_node_logging(&logging.node_name())
+                .await?
+        } else {
+            self.nodes_repository().set_node_logging(logging).await?
+        }
+        Ok(())
+    }
+
+    /// Return the logging configuration of a node, if any
+    #[instrument(skip_all, fields(node_name = node_name))]
+    pub async fn get_node_logging(&self, node_name: &str) -> Result<Option<NodeLogging>> {
+        Ok(self.nodes_repository().get_node_logging(node_name).await?)
+    }
+
+    /// Record the log level of a node, keeping the rest of its logging configuration
+    #[instrument(skip_all, fields(node_name = node_name, log_level = %log_level))]
+    pub async fn set_node_log_level(
+        &self,
+        node_name: &str,
+        log_level: &LogLevelFilter,
+    ) -> Result<NodeLogging> {
+        let logging = self
+            .get_node_logging(node_name)
+            .await?
+            .unwrap_or_else(|| NodeLogging::new(node_name))
+            .with_log_level(Some(log_level.clone()));
+        self.set_node_logging(&logging).await?;
+        Ok(logging)
+    }
+}
+
+/// The following methods support starting nodes again with the options they were created with
+impl CliState {
+    /// Record the command line arguments setting the options of a node
+    #[instrument(skip_all, fields(node_name = node_name))]
+    pub async fn set_node_arguments(&self, node_name: &str, arguments: &[String]) -> Result<()> {
+        Ok(self
+            .nodes_repository()
+            .set_node_arguments(node_name, arguments)
+            .await?)
+    }
+
+    /// Return the command line arguments setting the options of a node, if they were recorded
+    #[instrument(skip_all, fields(node_name = node_name))]
+    pub async fn get_node_arguments(&self, node_name: &str) -> Result<Option<Vec<String>>> {
+        Ok(self
+            .nodes_repository()
+            .get_node_arguments(node_name)
+            .await?)
+    }
+}
+
+/// The following methods support the nodes run by a system service manager
+impl CliState {
+    /// Record that a node is run by a system service
+    #[instrument(skip_all, fields(node_name = system_service.node_name()))]
+    pub async fn set_node_system_service(&self, system_service: &NodeSystemService) -> Result<()> {
+        Ok(self
+            .nodes_repository()
+            .set_node_system_service(system_service)
+            .await?)
+    }
+
+    /// Return the system service running a node, if any
+    #[instrument(skip_all, fields(node_name = node_name))]
+    pub async fn get_node_system_service(
+        &self,
+        node_name: &str,
+    ) -> Result<Option<NodeSystemService>> {
+        Ok(self
+            .nodes_repository()
+            .get_node_system_service(node_name)
+            .await?)
+    }
+
+    /// Forget the system service running a node, once it is uninstalled
+    #[instrument(skip_all, fields(node_name = node_name))]
+    pub async fn delete_node_system_service(&self, node_name: &str) -> Result<()> {
+        Ok(self
+            .nodes_repository()
+            .delete_node_system_service(node_name)
+            .await?)
+    }
+}
+
 /// The following methods return nodes data
 impl CliState {
     /// Return a node by name
@@ -292,12 +534,22 @@ impl CliState {
     /// Return the node information for the given node name, otherwise for the default node
     #[instrument(skip_all, fields(node_name = node_name.clone()))]
     pub async fn get_node_or_default(&self, node_name: &Option<String>) -> Result<NodeInfo> {
-        match node_name {
+        match node_name.as_ref().or(self.defaults().node_name.as_ref()) {
             Some(name) => self.get_node(name).await,
             None => self.get_default_node().await,
         }
     }
 
+    /// Return the nodes using a given identity
+    #[instrument(skip_all, fields(identity_name = identity_name))]
+    pub async fn get_nodes_by_identity_name(&self, identity_name: &str) -> Result<Vec<NodeInfo>> {
+        let identifier = self.get_identifier_by_name(identity_name).await?;
+        Ok(self
+            .nodes_repository()
+            .get_nodes_by_identifier(&identifier)
+            .await?)
+    }
+
     /// Return the project associated to a node if there is one
     #[instrument(skip_all, fields(node_name = node_name))]
     pub async fn get_node_project(&self, node_name: &str) -> Result<Project> {
@@ -347,34 +599,24 @@ impl CliState {
         node_name: &str,
         identifier: &Identifier,
     ) -> Result<NodeInfo> {
-        let repository = self.nodes_repository();
-        let is_default = repository.is_default_node(node_name).await?
-            || repository.get_nodes().await?.is_empty();
-        let tcp_listener_address = repository.get_tcp_listener_address(node_name).await?;
-        let node_info = NodeInfo::new(
-            node_name.to_string(),
-            identifier.clone(),
-            0,
-            is_default,
-            false,
-            tcp_listener_address,
-            Some(process::id()),
-        );
-        repository.store_node(&node_info).await?;
-        Ok(node_info)
-    }
-
-    /// Return the nodes using a given identity
-    #[instrument(skip_all, fields(identity_name = identity_name))]
-    pub(super) async fn get_nodes_by_identity_name(
-        &self,
-        identity_name: &str,
-    ) -> Result<Vec<NodeInfo>> {
-        let identifier = self.get_identifier_by_name(identity_name).await?;
-        Ok(self
-            .nodes_repository()
-            .get_nodes_by_identifier(&identifier)
-            .await?)
+        self.with_write_lock(async {
+            let repository = self.nodes_repository();
+            let is_default = repository.is_default_node(node_name).await?
+                || repository.get_nodes().await?.is_empty();
+            let tcp_listener_address = repository.get_tcp_listener_address(node_name).await?;
+            let node_info = NodeInfo::new(
+                node_name.to_string(),
+                identifier.clone(),
+                0,
+                is_default,
+                false,
+                tcp_listener_address,
+                Some(process::id()),
+            );
+            repository.store_node(&node_info).await?;
+            Ok(node_info)
+        })
+        .await
     }
 
     /// Return the vault which was used to create the identity associated to a node
@@ -414,6 +656,354 @@ pub enum NodeProcessStatus {
     Stopped,
 }
 
+/// Supervision settings and state of a background node which is restarted when its process crashes
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct NodeSupervision {
+    node_name: String,
+    max_restarts: u32,
+    #[serde(serialize_with = "serialize_duration_secs")]
+    max_backoff: Duration,
+    restart_count: u32,
+    last_crash_at: Option<u64>,
+    is_failed: bool,
+    #[serde(skip)]
+    watchdog_pid: Option<u32>,
+}
+
+impl NodeSupervision {
+    pub fn new(node_name: &str, max_restarts: u32, max_backoff: Duration) -> Self {
+        Self {
+            node_name: node_name.to_string(),
+            max_restarts,
+            max_backoff,
+            restart_count: 0,
+            last_crash_at: None,
+            is_failed: false,
+            watchdog_pid: None,
+        }
+    }
+
+    pub fn with_state(
+        self,
+        restart_count: u32,
+        last_crash_at: Option<u64>,
+        is_failed: bool,
+        watchdog_pid: Option<u32>,
+    ) -> Self {
+        Self {
+            restart_count,
+            last_crash_at,
+            is_failed,
+            watchdog_pid,
+            ..self
+        }
+    }
+
+    pub fn node_name(&self) -> String {
+        self.node_name.clone()
+    }
+
+    /// Maximum number of consecutive restarts before giving up
+    pub fn max_restarts(&self) -> u32 {
+        self.max_restarts
+    }
+
+    /// Maximum delay between two restarts
+    pub fn max_backoff(&self) -> Duration {
+        self.max_backoff
+    }
+
+    /// Total number of restarts after a crash
+    pub fn restart_count(&self) -> u32 {
+        self.restart_count
+    }
+
+    /// Timestamp, in seconds, of the last crash
+    pub fn last_crash_at(&self) -> Option<u64> {
+        self.last_crash_at
+    }
+
+    /// Return true if the node crashed too many times in a row and is not restarted anymore
+    pub fn is_failed(&self) -> bool {
+        self.is_failed
+    }
+
+    pub fn watchdog_pid(&self) -> Option<u32> {
+        self.watchdog_pid
+    }
+
+    /// Record a crash of the node process at the given time
+    pub fn record_crash(self, now: u64) -> Self {
+        Self {
+            restart_count: self.restart_count + 1,
+            last_crash_at: Some(now),
+            ..self
+        }
+    }
+
+    pub fn set_failed(self) -> Self {
+        Self {
+            is_failed: true,
+            ..self
+        }
+    }
+
+    /// Set the process id of the watchdog, which restarts the supervision
+    pub fn set_watchdog_pid(self, watchdog_pid: u32) -> Self {
+        Self {
+            is_failed: false,
+            watchdog_pid: Some(watchdog_pid),
+            ..self
+        }
+    }
+}
+
+/// HTTP proxy used by a node for its outgoing TCP connections.
+///
+/// The proxy URL can contain credentials, so it is not displayed by the `Debug` implementation
+#[derive(PartialEq, Eq, Clone)]
+pub struct NodeProxy {
+    node_name: String,
+    url: String,
+    no_proxy: Option<String>,
+}
+
+impl NodeProxy {
+    pub fn new(node_name: &str, url: &str, no_proxy: Option<&str>) -> Self {
+        Self {
+            node_name: node_name.to_string(),
+            url: url.to_string(),
+            no_proxy: no_proxy.map(|n| n.to_string()),
+        }
+    }
+
+    pub fn node_name(&self) -> String {
+        self.node_name.clone()
+    }
+
+    /// URL of the proxy, possibly with credentials
+    pub fn url(&self) -> String {
+        self.url.clone()
+    }
+
+    /// Comma-separated list of the hosts and networks reached without the proxy
+    pub fn no_proxy(&self) -> Option<String> {
+        self.no_proxy.clone()
+    }
+
+    /// Return the proxy configuration used by the TCP transport
+    pub fn tcp_proxy(&self) -> ockam_core::Result<TcpProxy> {
+        let proxy = TcpProxy::parse(&self.url)?;
+        match &self.no_proxy {
+            Some(no_proxy) => proxy.with_no_proxy(no_proxy),
+            None => Ok(proxy),
+        }
+    }
+}
+
+impl std::fmt::Debug for NodeProxy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let url = match TcpProxy::parse(&self.url) {
+            Ok(proxy) => proxy.to_string(),
+            Err(_) => "<invalid>".to_string(),
+        };
+        f.debug_struct("NodeProxy")
+            .field("node_name", &self.node_name)
+            .field("url", &url)
+            .field("no_proxy", &self.no_proxy)
+            .finish()
+    }
+}
+
+/// Logging configuration of a node, applied when its process starts.
+/// The values which are not set are taken from the environment variables
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct NodeLogging {
+    node_name: String,
+    log_level: Option<LogLevelFilter>,
+    log_format: Option<LogFormat>,
+    max_size_bytes: Option<u64>,
+    max_files: Option<u64>,
+}
+
+impl NodeLogging {
+    pub fn new(node_name: &str) -> Self {
+        Self {
+            node_name: node_name.to_string(),
+            log_level: None,
+            log_format: None,
+            max_size_bytes: None,
+            max_files: None,
+        }
+    }
+
+    pub fn with_log_level(self, log_level: Option<LogLevelFilter>) -> Self {
+        Self { log_level, ..self }
+    }
+
+    pub fn with_log_format(self, log_format: Option<LogFormat>) -> Self {
+        Self { log_format, ..self }
+    }
+
+    pub fn with_max_size_bytes(self, max_size_bytes: Option<u64>) -> Self {
+        Self {
+            max_size_bytes,
+            ..self
+        }
+    }
+
+    pub fn with_max_files(self, max_files: Option<u64>) -> Self {
+        Self { max_files, ..self }
+    }
+
+    pub fn node_name(&self) -> String {
+        self.node_name.clone()
+    }
+
+    /// Log level, or list of log filter directives
+    pub fn log_level(&self) -> Option<LogLevelFilter> {
+        self.log_level.clone()
+    }
+
+    pub fn log_format(&self) -> Option<LogFormat> {
+        self.log_format.clone()
+    }
+
+    /// Maximum size of a log file before it is rotated
+    pub fn max_size_bytes(&self) -> Option<u64> {
+        self.max_size_bytes
+    }
+
+    /// Maximum number of log files kept for the node
+    pub fn max_files(&self) -> Option<u64> {
+        self.max_files
+    }
+
+    /// Return true if no logging parameter is set
+    pub fn is_empty(&self) -> bool {
+        self.log_level.is_none()
+            && self.log_format.is_none()
+            && self.max_size_bytes.is_none()
+            && self.max_files.is_none()
+    }
+
+    /// Override a logging configuration with the parameters which are set
+    pub fn apply(&self, mut configuration: LoggingConfiguration) -> LoggingConfiguration {
+        if let Some(log_level) = &self.log_level {
+            configuration = configuration.set_log_level(log_level.clone());
+        }
+        if let Some(log_format) = &self.log_format {
+            configuration = configuration.set_format(log_format.clone());
+        }
+        if let Some(max_size_bytes) = self.max_size_bytes {
+            configuration = configuration.set_max_file_size_bytes(max_size_bytes);
+        }
+        if let Some(max_files) = self.max_files {
+            configuration = configuration.set_max_files(max_files);
+        }
+        configuration
+    }
+}
+
+/// Service manager of the operating system, which can run a node
+/// independently of the session of the user who created it
+#[derive(Serialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum SystemServiceManager {
+    /// A systemd user unit, on Linux
+    Systemd,
+    /// A launchd agent, on macOS
+    Launchd,
+    /// A service registered with the service control manager, on Windows
+    Windows,
+}
+
+impl SystemServiceManager {
+    /// Return the service manager of the current operating system, if it is supported
+    pub fn current() -> Option<Self> {
+        if cfg!(target_os = "linux") {
+            Some(SystemServiceManager::Systemd)
+        } else if cfg!(target_os = "macos") {
+            Some(SystemServiceManager::Launchd)
+        } else if cfg!(target_os = "windows") {
+            Some(SystemServiceManager::Windows)
+        } else {
+            None
+        }
+    }
+}
+
+impl Display for SystemServiceManager {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SystemServiceManager::Systemd => write!(f, "systemd"),
+            SystemServiceManager::Launchd => write!(f, "launchd"),
+            SystemServiceManager::Windows => write!(f, "windows"),
+        }
+    }
+}
+
+impl FromStr for SystemServiceManager {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "systemd" => Ok(SystemServiceManager::Systemd),
+            "launchd" => Ok(SystemServiceManager::Launchd),
+            "windows" => Ok(SystemServiceManager::Windows),
+            _ => Err(Error::new(
+                Origin::Api,
+                Kind::Invalid,
+                format!("unknown system service manager: {s}"),
+            )),
+        }
+    }
+}
+
+/// System service running a node
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct NodeSystemService {
+    node_name: String,
+    manager: SystemServiceManager,
+    unit: String,
+}
+
+impl NodeSystemService {
+    pub fn new(node_name: &str, manager: SystemServiceManager, unit: &str) -> Self {
+        Self {
+            node_name: node_name.to_string(),
+            manager,
+            unit: unit.to_string(),
+        }
+    }
+
+    pub fn node_name(&self) -> String {
+        self.node_name.clone()
+    }
+
+    pub fn manager(&self) -> SystemServiceManager {
+        self.manager
+    }
+
+    /// Path of the unit or plist file defining the service, or name of a Windows service
+    pub fn unit(&self) -> String {
+        self.unit.clone()
+    }
+}
+
+impl Display for NodeSystemService {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.manager, self.unit)
+    }
+}
+
+fn serialize_duration_secs<S: serde::Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_u64(duration.as_secs())
+}
+
 /// This struct contains all the data associated to a node
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct NodeInfo {
@@ -639,6 +1229,33 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_rename_node() -> Result<()> {
+        let cli = CliState::test().await?;
+        let node_info1 = cli.create_node("node-1").await?;
+        cli.create_node("node-2").await?;
+        let _ = cli.stdout_logs("node-1")?;
+
+        // a running node cannot be renamed
+        let result = cli.rename_node("node-1", "node-3").await;
+        assert!(result.is_err());
+        cli.stop_node("node-1", false).await?;
+
+        // a node cannot be renamed with the name of another node
+        let result = cli.rename_node("node-1", "node-2").await;
+        assert!(result.is_err());
+
+        // the node and its directory are renamed
+        let result = cli.rename_node("node-1", "node-3").await?;
+        assert_eq!(result.name(), "node-3");
+        assert_eq!(result.identifier(), node_info1.identifier());
+        assert!(result.is_default());
+        assert!(cli.get_node("node-1").await.is_err());
+        assert!(!cli.node_dir("node-1").exists());
+        assert!(cli.node_dir("node-3").exists());
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_create_node_with_optional_values() -> Result<()> {
         let cli = CliState::test().await?;
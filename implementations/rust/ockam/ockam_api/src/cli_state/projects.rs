@@ -1,7 +1,11 @@
+use ockam::identity::utils::now;
 use ockam::identity::{Identifier, IdentitiesVerification};
 use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
 
+use ockam_core::env::get_env_with_default;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::Error;
 use ockam_vault::SoftwareVaultForVerifyingSignatures;
@@ -11,13 +15,18 @@ use crate::cloud::email_address::EmailAddress;
 use crate::cloud::project::models::ProjectModel;
 use crate::cloud::project::Project;
 use crate::cloud::share::RoleInShare;
-use crate::{EnrollmentStatus, ProjectsRepository};
+use crate::{EnrollmentStatus, ProjectsRepository, StateLock};
 
 use super::Result;
 
+/// Time during which a project retrieved from the Controller is used without being fetched again.
+/// This value can be overridden with the OCKAM_PROJECT_CACHE_TTL environment variable
+const DEFAULT_PROJECT_CACHE_TTL: Duration = Duration::from_secs(300);
+
 pub struct Projects {
     projects_repository: Arc<dyn ProjectsRepository>,
     identities_verification: IdentitiesVerification,
+    state_lock: Option<StateLock>,
 }
 
 impl Projects {
@@ -28,13 +37,90 @@ impl Projects {
         Self {
             projects_repository,
             identities_verification,
+            state_lock: None,
+        }
+    }
+
+    /// Serialize the updates of projects with other processes using the same local state
+    pub fn with_state_lock(self, state_lock: StateLock) -> Self {
+        Self {
+            state_lock: Some(state_lock),
+            ..self
         }
     }
 
     #[instrument(skip_all, fields(project_id = project_model.id))]
     pub async fn import_and_store_project(&self, project_model: ProjectModel) -> Result<Project> {
         let project = Project::import(project_model.clone()).await?;
-        self.store_project(project).await
+        let project = self.store_project(project).await?;
+        self.projects_repository
+            .set_project_fetched_at(project.project_id(), now()?)
+            .await?;
+        Ok(project)
+    }
+
+    /// Store a project exported from another machine, for example on a machine
+    /// which can't reach the Controller.
+    ///
+    /// The project must have an identity and a route, and its authority must be complete
+    /// if it has one. A project with the same name is replaced by the imported project
+    #[instrument(skip_all, fields(project_id = project_model.id))]
+    pub async fn import_project(&self, project_model: ProjectModel) -> Result<Project> {
+        if project_model.id.is_empty() || project_model.name.is_empty() {
+            Err(Error::new(
+                Origin::Api,
+                Kind::Invalid,
+                "an imported project must have an id and a name",
+            ))?
+        }
+        let project = Project::import(project_model).await?;
+        project.project_identifier()?;
+        project.project_multiaddr()?;
+        if project.model().authority_identity.is_some()
+            || project.model().authority_access_route.is_some()
+        {
+            project.authority_identifier()?;
+            project.authority_multiaddr()?;
+        }
+
+        if let Some(existing) = self
+            .projects_repository
+            .get_project_by_name(project.name())
+            .await?
+        {
+            if existing.id != project.project_id() {
+                self.delete_project(&existing.id).await?;
+            }
+        }
+        let project = self.store_project(project).await?;
+        self.projects_repository
+            .set_project_imported(project.project_id())
+            .await?;
+        Ok(project)
+    }
+
+    /// Return true if the project was imported from a file rather than retrieved from the Controller
+    #[instrument(skip_all, fields(project_id = project_id))]
+    pub async fn is_project_imported(&self, project_id: &str) -> Result<bool> {
+        Ok(self
+            .projects_repository
+            .is_project_imported(project_id)
+            .await?)
+    }
+
+    /// Return true if the project was retrieved from the Controller
+    /// less than OCKAM_PROJECT_CACHE_TTL ago
+    #[instrument(skip_all, fields(project_id = project_id))]
+    pub async fn is_project_cached(&self, project_id: &str) -> Result<bool> {
+        let ttl = get_env_with_default("OCKAM_PROJECT_CACHE_TTL", DEFAULT_PROJECT_CACHE_TTL)?;
+        let fetched_at = self
+            .projects_repository
+            .get_project_fetched_at(project_id)
+            .await?;
+        Ok(match fetched_at {
+            Some(fetched_at) => now()?.0.saturating_sub(fetched_at.0) < ttl.as_secs(),
+            None => false,
+        })
     }
 
     #[instrument(skip_all, fields(project_id = project.project_id()))]
@@ -51,41 +137,55 @@ impl Projects {
                 .await?;
         }
 
-        self.projects_repository
-            .store_project(project.model())
-            .await?;
-
-        // If there is no previous default project set this project as the default
-        let default_project = self.projects_repository.get_default_project().await?;
-        if default_project.is_none() {
+        self.with_write_lock(async {
             self.projects_repository
-                .set_default_project(project.project_id())
-                .await?
-        };
+                .store_project(project.model())
+                .await?;
+
+            // If there is no previous default project set this project as the default
+            let default_project = self.projects_repository.get_default_project().await?;
+            if default_project.is_none() {
+                self.projects_repository
+                    .set_default_project(project.project_id())
+                    .await?
+            };
+            Ok(())
+        })
+        .await?;
 
         Ok(project)
     }
 
     #[instrument(skip_all, fields(project_id = project_id))]
     pub async fn delete_project(&self, project_id: &str) -> Result<()> {
-        // delete the project
-        let project_exists = self
-            .projects_repository
-            .get_project(project_id)
-            .await
-            .is_ok();
-        self.projects_repository.delete_project(project_id).await?;
-
-        // set another project as the default project
-        if project_exists {
-            let other_projects = self.projects_repository.get_projects().await?;
-            if let Some(other_project) = other_projects.first() {
-                self.projects_repository
-                    .set_default_project(&other_project.id)
-                    .await?;
+        self.with_write_lock(async {
+            // delete the project
+            let project_exists = self
+                .projects_repository
+                .get_project(project_id)
+                .await
+                .is_ok();
+            self.projects_repository.delete_project(project_id).await?;
+
+            // set another project as the default project
+            if project_exists {
+                let other_projects = self.projects_repository.get_projects().await?;
+                if let Some(other_project) = other_projects.first() {
+                    self.projects_repository
+                        .set_default_project(&other_project.id)
+                        .await?;
+                }
             }
+            Ok(())
+        })
+        .await
+    }
+
+    async fn with_write_lock<T>(&self, f: impl Future<Output = Result<T>>) -> Result<T> {
+        match &self.state_lock {
+            Some(state_lock) => state_lock.with_write_lock(f).await,
+            None => f.await,
         }
-        Ok(())
     }
 
     #[instrument(skip_all, fields(project_id = project_id))]
@@ -203,5 +303,6 @@ impl CliState {
         );
 
         Projects::new(self.projects_repository(), identities_verification)
+            .with_state_lock(self.state_lock())
     }
 }
@@ -1,5 +1,5 @@
 use ockam::identity::storage::{PurposeKeysRepository, PurposeKeysSqlxDatabase};
-use ockam::identity::{ChangeHistoryRepository, ChangeHistorySqlxDatabase};
+use ockam::identity::{ChangeHistoryRepository, ChangeHistorySqlxDatabase, CredentialSqlxDatabase};
 use ockam_abac::{ResourcesRepository, ResourcesSqlxDatabase};
 use ockam_core::compat::sync::Arc;
 use ockam_vault::storage::{SecretsRepository, SecretsSqlxDatabase};
@@ -34,6 +34,10 @@ impl CliState {
         Arc::new(VaultsSqlxDatabase::new(self.database()))
     }
 
+    pub(super) fn credentials_repository(&self) -> CredentialSqlxDatabase {
+        CredentialSqlxDatabase::new(self.database())
+    }
+
     pub(super) fn enrollment_repository(&self) -> Arc<dyn EnrollmentsRepository> {
         Arc::new(EnrollmentsSqlxDatabase::new(self.database()))
     }
@@ -50,10 +54,18 @@ impl CliState {
         Arc::new(ResourcesSqlxDatabase::new(self.database()))
     }
 
+    pub(super) fn runs_repository(&self) -> Arc<dyn RunsRepository> {
+        Arc::new(RunsSqlxDatabase::new(self.database()))
+    }
+
     pub(super) fn spaces_repository(&self) -> Arc<dyn SpacesRepository> {
         Arc::new(SpacesSqlxDatabase::new(self.database()))
     }
 
+    pub(super) fn ticket_templates_repository(&self) -> Arc<dyn TicketTemplatesRepository> {
+        Arc::new(TicketTemplatesSqlxDatabase::new(self.database()))
+    }
+
     pub(super) fn users_repository(&self) -> Arc<dyn UsersRepository> {
         Arc::new(UsersSqlxDatabase::new(self.database()))
     }
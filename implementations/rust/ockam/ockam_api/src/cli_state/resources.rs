@@ -1,6 +1,8 @@
 use super::Result;
 use crate::CliState;
-use ockam_abac::{Resource, ResourceName};
+use ockam_abac::{
+    Resource, ResourceName, ResourceType, ResourcesRepository, ResourcesSqlxDatabase,
+};
 
 impl CliState {
     pub async fn store_resource(&self, resource: &Resource) -> Result<()> {
@@ -14,4 +16,29 @@ impl CliState {
             .await?;
         Ok(())
     }
+
+    /// Rename a resource and move the policies stored for its previous name
+    pub async fn rename_resource(
+        &self,
+        resource_name: &ResourceName,
+        new_resource_name: &ResourceName,
+    ) -> Result<()> {
+        self.resources_repository()
+            .rename_resource(resource_name, new_resource_name)
+            .await?;
+        Ok(())
+    }
+
+    /// Return the resources of a given type which have been created on a node
+    pub async fn get_node_resources(
+        &self,
+        node_name: &str,
+        resource_type: &ResourceType,
+    ) -> Result<Vec<Resource>> {
+        let mut database = self.database();
+        database.set_node_name(node_name);
+        Ok(ResourcesSqlxDatabase::new(database)
+            .get_resources_by_type(resource_type)
+            .await?)
+    }
 }
@@ -0,0 +1,208 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::Error;
+
+use crate::cli_state::{CliState, Result};
+
+/// The methods below record the resources created by the execution of a configuration file
+/// with `ockam run`, so that they can be deleted with `ockam run --teardown`
+impl CliState {
+    /// Start recording the resources created by the execution of a configuration file.
+    /// The resources recorded by a previous execution of the same file are kept.
+    pub async fn start_run(&self, run_id: &str, contents: &str) -> Result<()> {
+        Ok(self
+            .runs_repository()
+            .store_run(run_id, &Run::hash_contents(contents))
+            .await?)
+    }
+
+    /// Record a resource created by the execution of a configuration file
+    pub async fn add_run_resource(&self, run_id: &str, resource: &RunResource) -> Result<()> {
+        Ok(self
+            .runs_repository()
+            .add_run_resource(run_id, resource)
+            .await?)
+    }
+
+    /// Return the resources recorded for a configuration file, if it was executed
+    pub async fn get_run(&self, run_id: &str) -> Result<Option<Run>> {
+        Ok(self.runs_repository().get_run(run_id).await?)
+    }
+
+    /// Forget the resources recorded for a configuration file
+    pub async fn delete_run(&self, run_id: &str) -> Result<()> {
+        Ok(self.runs_repository().delete_run(run_id).await?)
+    }
+}
+
+/// Resources created by the executions of a configuration file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Run {
+    run_id: String,
+    contents_hash: String,
+    resources: Vec<RunResource>,
+}
+
+impl Run {
+    pub fn new(run_id: &str, contents_hash: &str, resources: Vec<RunResource>) -> Self {
+        Self {
+            run_id: run_id.to_string(),
+            contents_hash: contents_hash.to_string(),
+            resources,
+        }
+    }
+
+    pub fn run_id(&self) -> &str {
+        &self.run_id
+    }
+
+    pub fn contents_hash(&self) -> &str {
+        &self.contents_hash
+    }
+
+    pub fn resources(&self) -> &[RunResource] {
+        &self.resources
+    }
+
+    /// Return true if the configuration file was last executed with these contents
+    pub fn has_contents(&self, contents: &str) -> bool {
+        self.contents_hash == Self::hash_contents(contents)
+    }
+
+    /// Return the hash identifying the contents of a configuration file
+    pub fn hash_contents(contents: &str) -> String {
+        hex::encode(Sha256::digest(contents.as_bytes()))
+    }
+
+    /// Return the resources in the order in which they must be deleted:
+    /// the resources hosted by a node first, then the node itself
+    pub fn resources_in_teardown_order(&self) -> Vec<RunResource> {
+        let mut resources = self.resources.clone();
+        resources.sort();
+        resources
+    }
+}
+
+/// A resource created by the execution of a configuration file
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RunResource {
+    kind: RunResourceKind,
+    name: String,
+    node_name: String,
+}
+
+impl RunResource {
+    pub fn new(kind: RunResourceKind, name: &str, node_name: &str) -> Self {
+        Self {
+            kind,
+            name: name.to_string(),
+            node_name: node_name.to_string(),
+        }
+    }
+
+    pub fn kind(&self) -> RunResourceKind {
+        self.kind
+    }
+
+    /// Node name, relay name, outlet address or inlet alias
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Name of the node hosting the resource. For a node, this is its own name
+    pub fn node_name(&self) -> &str {
+        &self.node_name
+    }
+}
+
+/// Kinds of resources which can be deleted after the execution of a configuration file.
+/// They are declared in the order in which they must be deleted
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum RunResourceKind {
+    TcpInlet,
+    TcpOutlet,
+    Relay,
+    Node,
+}
+
+impl Display for RunResourceKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            RunResourceKind::TcpInlet => "tcp-inlet",
+            RunResourceKind::TcpOutlet => "tcp-outlet",
+            RunResourceKind::Relay => "relay",
+            RunResourceKind::Node => "node",
+        })
+    }
+}
+
+impl FromStr for RunResourceKind {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "tcp-inlet" => Ok(RunResourceKind::TcpInlet),
+            "tcp-outlet" => Ok(RunResourceKind::TcpOutlet),
+            "relay" => Ok(RunResourceKind::Relay),
+            "node" => Ok(RunResourceKind::Node),
+            _ => Err(Error::new(
+                Origin::Api,
+                Kind::Serialization,
+                format!("unknown run resource type {s}"),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_record_run_resources() -> Result<()> {
+        let cli = CliState::test().await?;
+        cli.start_run("ockam.yaml", "nodes: [n1]").await?;
+        cli.add_run_resource(
+            "ockam.yaml",
+            &RunResource::new(RunResourceKind::Node, "n1", "n1"),
+        )
+        .await?;
+        cli.add_run_resource(
+            "ockam.yaml",
+            &RunResource::new(RunResourceKind::Relay, "r1", "n1"),
+        )
+        .await?;
+        cli.add_run_resource(
+            "ockam.yaml",
+            &RunResource::new(RunResourceKind::TcpInlet, "i1", "n1"),
+        )
+        .await?;
+
+        let run = cli.get_run("ockam.yaml").await?.unwrap();
+        assert!(run.has_contents("nodes: [n1]"));
+        assert!(!run.has_contents("nodes: [n2]"));
+        let kinds = run
+            .resources_in_teardown_order()
+            .iter()
+            .map(|r| r.kind())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                RunResourceKind::TcpInlet,
+                RunResourceKind::Relay,
+                RunResourceKind::Node
+            ]
+        );
+
+        cli.delete_run("ockam.yaml").await?;
+        assert_eq!(cli.get_run("ockam.yaml").await?, None);
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use ockam::identity::{Identities, SecureChannels};
 
@@ -6,11 +7,18 @@ use crate::cli_state::CliState;
 use crate::cli_state::Result;
 
 impl CliState {
-    pub async fn secure_channels(&self, node_name: &str) -> Result<Arc<SecureChannels>> {
+    /// Create the secure channels service of a node.
+    /// The attributes of the identities expire after `attributes_max_age` when it is set
+    pub async fn secure_channels(
+        &self,
+        node_name: &str,
+        attributes_max_age: Option<Duration>,
+    ) -> Result<Arc<SecureChannels>> {
         debug!("create the secure channels service");
         let vault = self.get_node_vault(node_name).await?.vault().await?;
         let identities = Identities::create(self.database())
             .with_vault(vault)
+            .with_attributes_max_age(attributes_max_age)
             .build();
         Ok(SecureChannels::from_identities(identities))
     }
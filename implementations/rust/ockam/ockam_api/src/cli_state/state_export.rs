@@ -0,0 +1,657 @@
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use argon2::Argon2;
+use rand::random;
+use serde::{Deserialize, Serialize};
+use sqlx::*;
+
+use ockam::identity::models::ChangeHistory;
+use ockam::identity::{Identifier, Identity};
+use ockam_node::database::{FromSqlxError, SqlxDatabase, ToSqlxType, ToVoid};
+
+use crate::cli_state::{
+    CliState, CliStateError, NodeInfo, Result, TicketTemplate, VaultEncryption,
+};
+use crate::cloud::project::models::ProjectModel;
+use crate::cloud::space::Space;
+
+/// The methods below support the export of the local state to an archive which can be
+/// imported on another machine.
+///
+/// An export contains:
+///
+///  - the named identities with their change history
+///  - the secrets of the vaults which are not stored in a KMS
+///  - the spaces and projects
+///  - the nodes configuration. Runtime data, like the node process id or its TCP listener address, is not exported
+///  - the enrollment ticket templates
+///
+impl CliState {
+    /// Export the local state
+    #[instrument(skip_all)]
+    pub async fn export_state(&self) -> Result<StateExport> {
+        let mut identities = vec![];
+        for identity in self.get_named_identities().await? {
+            let change_history = self
+                .change_history_repository()
+                .get_change_history(&identity.identifier())
+                .await?
+                .ok_or_else(|| {
+                    CliStateError::InvalidData(format!(
+                        "The change history of the identity {} is missing",
+                        identity.name()
+                    ))
+                })?;
+            identities.push(IdentityExport {
+                name: identity.name(),
+                vault_name: identity.vault_name(),
+                is_default: identity.is_default(),
+                change_history: change_history.export_as_string()?,
+            });
+        }
+
+        let mut vaults = vec![];
+        for vault in self.get_named_vaults().await? {
+            let (signing_secrets, x25519_secrets) = if vault.is_kms() {
+                (vec![], vec![])
+            } else {
+                let database = vault.database().await?;
+                (
+                    get_signing_secrets(&database).await?,
+                    get_x25519_secrets(&database).await?,
+                )
+            };
+            vaults.push(VaultExport {
+                name: vault.name(),
+                is_kms: vault.is_kms(),
+                kms_key_id: vault.kms_key_id(),
+                encryption: vault.encryption().map(|e| VaultEncryptionExport {
+                    salt: e.salt(),
+                    check: e.check(),
+                }),
+                signing_secrets,
+                x25519_secrets,
+            });
+        }
+
+        let projects_repository = self.projects_repository();
+        let default_project = projects_repository.get_default_project().await?;
+        let projects = projects_repository
+            .get_projects()
+            .await?
+            .into_iter()
+            .map(|project| ProjectExport {
+                is_default: default_project.as_ref().map(|p| &p.id) == Some(&project.id),
+                project,
+            })
+            .collect();
+
+        let spaces_repository = self.spaces_repository();
+        let default_space = spaces_repository.get_default_space().await?;
+        let spaces = spaces_repository
+            .get_spaces()
+            .await?
+            .into_iter()
+            .map(|space| SpaceExport {
+                is_default: default_space.as_ref().map(|s| &s.id) == Some(&space.id),
+                space,
+            })
+            .collect();
+
+        let mut nodes = vec![];
+        for node in self.get_nodes().await? {
+            nodes.push(NodeExport {
+                name: node.name(),
+                identifier: node.identifier().to_string(),
+                verbosity: node.verbosity(),
+                is_default: node.is_default(),
+                is_authority: node.is_authority_node(),
+                project_name: self
+                    .nodes_repository()
+                    .get_node_project_name(&node.name())
+                    .await?,
+            });
+        }
+
+        Ok(StateExport {
+            identities,
+            vaults,
+            projects,
+            spaces,
+            nodes,
+            ticket_templates: self.get_ticket_templates().await?,
+        })
+    }
+
+    /// Merge an exported state into the local state.
+    ///
+    /// When an entity with the same name already exists locally, it is replaced if `overwrite` is
+    /// true, otherwise it is skipped. Vault secrets are always merged into the vault with the same name.
+    /// The local default identity, project, space and node are only set from the import when
+    /// there is no local default yet.
+    #[instrument(skip_all, fields(overwrite = overwrite))]
+    pub async fn import_state(
+        &self,
+        state: &StateExport,
+        overwrite: bool,
+    ) -> Result<StateImportSummary> {
+        self.with_write_lock(self.import_state_impl(state, overwrite))
+            .await
+    }
+
+    async fn import_state_impl(
+        &self,
+        state: &StateExport,
+        overwrite: bool,
+    ) -> Result<StateImportSummary> {
+        let mut summary = StateImportSummary::default();
+
+        for vault in &state.vaults {
+            let (named_vault, created) = match self
+                .vaults_repository()
+                .get_named_vault(&vault.name)
+                .await?
+            {
+                Some(named_vault) => (named_vault, false),
+                None if vault.is_kms => (
+                    self.create_kms_vault(&Some(vault.name.clone()), &None, &vault.kms_key_id)
+                        .await?,
+                    true,
+                ),
+                None => (
+                    self.create_named_vault(&Some(vault.name.clone()), &None)
+                        .await?,
+                    true,
+                ),
+            };
+            if named_vault.is_kms() != vault.is_kms {
+                summary.skipped(
+                    "vault",
+                    &vault.name,
+                    "a vault with a different type already exists",
+                );
+                continue;
+            }
+            // the secrets are exported as stored, so they can only be imported
+            // in a vault using the same encryption
+            let encryption = vault
+                .encryption
+                .as_ref()
+                .map(|e| VaultEncryption::new(e.salt.clone(), e.check.clone()));
+            if named_vault.encryption() != encryption {
+                if !created {
+                    summary.skipped(
+                        "vault",
+                        &vault.name,
+                        "a vault with a different encryption already exists",
+                    );
+                    continue;
+                }
+                if let Some(encryption) = &encryption {
+                    self.vaults_repository()
+                        .set_vault_encryption(&vault.name, encryption)
+                        .await?;
+                }
+            }
+            if !vault.is_kms {
+                let database = named_vault.database().await?;
+                store_signing_secrets(&database, &vault.signing_secrets).await?;
+                store_x25519_secrets(&database, &vault.x25519_secrets).await?;
+            }
+            summary.imported("vault", &vault.name);
+        }
+
+        let identities_repository = self.identities_repository();
+        let has_default_identity = identities_repository
+            .get_default_named_identity()
+            .await?
+            .is_some();
+        for identity in &state.identities {
+            let change_history = ChangeHistory::import_from_string(&identity.change_history)?;
+            let identifier = Identity::create_from_change_history(&change_history)
+                .await?
+                .identifier()
+                .clone();
+            self.change_history_repository()
+                .store_change_history(&identifier, change_history)
+                .await?;
+
+            if identities_repository
+                .get_named_identity(&identity.name)
+                .await?
+                .is_some()
+            {
+                if !overwrite {
+                    summary.skipped("identity", &identity.name, "it already exists");
+                    continue;
+                }
+                summary.overwritten("identity", &identity.name);
+            } else {
+                summary.imported("identity", &identity.name);
+            }
+            identities_repository
+                .store_named_identity(&identifier, &identity.name, &identity.vault_name)
+                .await?;
+            if identity.is_default && !has_default_identity {
+                identities_repository.set_as_default(&identity.name).await?;
+            }
+        }
+
+        let spaces_repository = self.spaces_repository();
+        let has_default_space = spaces_repository.get_default_space().await?.is_some();
+        for exported in &state.spaces {
+            let space = &exported.space;
+            if spaces_repository.get_space(&space.id).await?.is_some() {
+                if !overwrite {
+                    summary.skipped("space", &space.name, "it already exists");
+                    continue;
+                }
+                summary.overwritten("space", &space.name);
+            } else {
+                summary.imported("space", &space.name);
+            }
+            spaces_repository.store_space(space).await?;
+            if exported.is_default && !has_default_space {
+                spaces_repository.set_default_space(&space.id).await?;
+            }
+        }
+
+        let projects_repository = self.projects_repository();
+        let has_default_project = projects_repository.get_default_project().await?.is_some();
+        for exported in &state.projects {
+            let project = &exported.project;
+            if projects_repository
+                .get_project(&project.id)
+                .await?
+                .is_some()
+            {
+                if !overwrite {
+                    summary.skipped("project", &project.name, "it already exists");
+                    continue;
+                }
+                summary.overwritten("project", &project.name);
+            } else {
+                summary.imported("project", &project.name);
+            }
+            projects_repository.store_project(project).await?;
+            if exported.is_default && !has_default_project {
+                projects_repository.set_default_project(&project.id).await?;
+            }
+        }
+
+        let nodes_repository = self.nodes_repository();
+        let has_default_node = nodes_repository.get_default_node().await?.is_some();
+        for node in &state.nodes {
+            if let Some(existing) = nodes_repository.get_node(&node.name).await? {
+                if !overwrite {
+                    summary.skipped("node", &node.name, "it already exists");
+                    continue;
+                }
+                if existing.is_running() {
+                    summary.skipped("node", &node.name, "it is currently running");
+                    continue;
+                }
+                summary.overwritten("node", &node.name);
+            } else {
+                summary.imported("node", &node.name);
+            }
+            let identifier = Identifier::try_from(node.identifier.as_str())?;
+            nodes_repository
+                .store_node(&NodeInfo::new(
+                    node.name.clone(),
+                    identifier,
+                    node.verbosity,
+                    node.is_default && !has_default_node,
+                    node.is_authority,
+                    None,
+                    None,
+                ))
+                .await?;
+            if let Some(project_name) = &node.project_name {
+                nodes_repository
+                    .set_node_project_name(&node.name, project_name)
+                    .await?;
+            }
+        }
+
+        let ticket_templates_repository = self.ticket_templates_repository();
+        for template in &state.ticket_templates {
+            if ticket_templates_repository
+                .get_ticket_template(template.name())
+                .await?
+                .is_some()
+            {
+                if !overwrite {
+                    summary.skipped("ticket template", template.name(), "it already exists");
+                    continue;
+                }
+                summary.overwritten("ticket template", template.name());
+            } else {
+                summary.imported("ticket template", template.name());
+            }
+            ticket_templates_repository
+                .store_ticket_template(template)
+                .await?;
+        }
+
+        Ok(summary)
+    }
+}
+
+/// Exported local state
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateExport {
+    identities: Vec<IdentityExport>,
+    vaults: Vec<VaultExport>,
+    projects: Vec<ProjectExport>,
+    spaces: Vec<SpaceExport>,
+    nodes: Vec<NodeExport>,
+    #[serde(default)]
+    ticket_templates: Vec<TicketTemplate>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct IdentityExport {
+    name: String,
+    vault_name: String,
+    is_default: bool,
+    change_history: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct VaultExport {
+    name: String,
+    is_kms: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    kms_key_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    encryption: Option<VaultEncryptionExport>,
+    signing_secrets: Vec<SecretExport>,
+    x25519_secrets: Vec<SecretExport>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct VaultEncryptionExport {
+    #[serde(with = "hex")]
+    salt: Vec<u8>,
+    #[serde(with = "hex")]
+    check: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow)]
+struct SecretExport {
+    #[serde(with = "hex")]
+    handle: Vec<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    secret_type: Option<String>,
+    #[serde(with = "hex")]
+    secret: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct ProjectExport {
+    project: ProjectModel,
+    is_default: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct SpaceExport {
+    space: Space,
+    is_default: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct NodeExport {
+    name: String,
+    identifier: String,
+    verbosity: u8,
+    is_default: bool,
+    is_authority: bool,
+    project_name: Option<String>,
+}
+
+/// Entities created, replaced or skipped when importing a state
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct StateImportSummary {
+    pub imported: Vec<String>,
+    pub overwritten: Vec<String>,
+    pub skipped: Vec<String>,
+}
+
+impl StateImportSummary {
+    fn imported(&mut self, resource: &str, name: &str) {
+        self.imported.push(format!("{resource} {name}"));
+    }
+
+    fn overwritten(&mut self, resource: &str, name: &str) {
+        self.overwritten.push(format!("{resource} {name}"));
+    }
+
+    fn skipped(&mut self, resource: &str, name: &str, reason: &str) {
+        warn!("The {resource} {name} is not imported because {reason}");
+        self.skipped.push(format!("{resource} {name} ({reason})"));
+    }
+}
+
+/// File format of an exported state.
+///
+/// The state can be encrypted with a key derived from a passphrase, using argon2 and AES-256-GCM,
+/// like the encrypted vaults. The argon2 parameters are not read from the archive.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "format", rename_all = "snake_case")]
+pub enum StateArchive {
+    Plain {
+        state: StateExport,
+    },
+    Encrypted {
+        #[serde(with = "hex")]
+        salt: Vec<u8>,
+        #[serde(with = "hex")]
+        nonce: Vec<u8>,
+        #[serde(with = "hex")]
+        ciphertext: Vec<u8>,
+    },
+}
+
+impl StateArchive {
+    /// Create an archive, encrypted if a passphrase is provided
+    pub fn new(state: StateExport, passphrase: Option<&str>) -> Result<Self> {
+        let passphrase = match passphrase {
+            Some(passphrase) => passphrase,
+            None => return Ok(StateArchive::Plain { state }),
+        };
+        let salt: [u8; 16] = random();
+        let nonce: [u8; 12] = random();
+        let cipher = Self::make_cipher(passphrase, &salt)?;
+        let plaintext = serde_json::to_vec(&state)?;
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce), plaintext.as_slice())
+            .map_err(|_| CliStateError::InvalidOperation("Failed to encrypt the state".into()))?;
+        Ok(StateArchive::Encrypted {
+            salt: salt.to_vec(),
+            nonce: nonce.to_vec(),
+            ciphertext,
+        })
+    }
+
+    /// Read an archive from bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        serde_json::from_slice(bytes).map_err(|e| {
+            CliStateError::InvalidData(format!("The file is not a valid state archive: {e}"))
+        })
+    }
+
+    /// Serialize the archive
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec_pretty(self)?)
+    }
+
+    /// Return true if a passphrase is required to read the state
+    pub fn is_encrypted(&self) -> bool {
+        matches!(self, StateArchive::Encrypted { .. })
+    }
+
+    /// Return the archived state, decrypting it if necessary
+    pub fn state(self, passphrase: Option<&str>) -> Result<StateExport> {
+        match self {
+            StateArchive::Plain { state } => Ok(state),
+            StateArchive::Encrypted {
+                salt,
+                nonce,
+                ciphertext,
+            } => {
+                let passphrase = passphrase.ok_or_else(|| {
+                    CliStateError::InvalidOperation(
+                        "A passphrase is required to read this state archive".into(),
+                    )
+                })?;
+                if nonce.len() != 12 {
+                    return Err(CliStateError::InvalidData(
+                        "The state archive nonce is invalid".into(),
+                    ));
+                }
+                let cipher = Self::make_cipher(passphrase, &salt)?;
+                let plaintext = cipher
+                    .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+                    .map_err(|_| {
+                        CliStateError::InvalidData(
+                            "The state archive can't be decrypted. Please check the passphrase"
+                                .into(),
+                        )
+                    })?;
+                Ok(serde_json::from_slice(&plaintext)?)
+            }
+        }
+    }
+
+    fn make_cipher(passphrase: &str, salt: &[u8]) -> Result<Aes256Gcm> {
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+            .map_err(|e| {
+                CliStateError::InvalidData(format!(
+                    "Cannot derive the state archive key from the passphrase: {e}"
+                ))
+            })?;
+        Ok(Aes256Gcm::new(&key.into()))
+    }
+}
+
+/// Support functions to copy the raw secrets of a vault database
+async fn get_signing_secrets(database: &SqlxDatabase) -> Result<Vec<SecretExport>> {
+    let query = query_as("SELECT handle, secret_type, secret FROM signing_secret");
+    let rows: Vec<SecretExport> = query.fetch_all(&*database.pool).await.into_core()?;
+    Ok(rows)
+}
+
+async fn get_x25519_secrets(database: &SqlxDatabase) -> Result<Vec<SecretExport>> {
+    let query = query_as("SELECT handle, NULL AS secret_type, secret FROM x25519_secret");
+    let rows: Vec<SecretExport> = query.fetch_all(&*database.pool).await.into_core()?;
+    Ok(rows)
+}
+
+async fn store_signing_secrets(database: &SqlxDatabase, secrets: &[SecretExport]) -> Result<()> {
+    for secret in secrets {
+        let secret_type = secret
+            .secret_type
+            .clone()
+            .ok_or_else(|| CliStateError::InvalidData("A signing secret type is missing".into()))?;
+        let query = query("INSERT OR IGNORE INTO signing_secret VALUES (?, ?, ?)")
+            .bind(secret.handle.to_sql())
+            .bind(secret_type.to_sql())
+            .bind(secret.secret.to_sql());
+        query.execute(&*database.pool).await.void()?;
+    }
+    Ok(())
+}
+
+async fn store_x25519_secrets(database: &SqlxDatabase, secrets: &[SecretExport]) -> Result<()> {
+    for secret in secrets {
+        let query = query("INSERT OR IGNORE INTO x25519_secret VALUES (?, ?)")
+            .bind(secret.handle.to_sql())
+            .bind(secret.secret.to_sql());
+        query.execute(&*database.pool).await.void()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_export_and_import() -> Result<()> {
+        let source = CliState::test().await?;
+        let identity = source.create_identity_with_name("alice").await?;
+        source
+            .create_node_with_identifier("node-1", &identity.identifier())
+            .await?;
+        source
+            .create_ticket_template(&TicketTemplate::new(
+                "db-reader",
+                Default::default(),
+                Some(1),
+                None,
+            ))
+            .await?;
+        let archive = StateArchive::new(source.export_state().await?, Some("passphrase"))?;
+        let bytes = archive.to_bytes()?;
+
+        // the archive can't be read with a wrong passphrase
+        let archive = StateArchive::from_bytes(&bytes)?;
+        assert!(archive.is_encrypted());
+        assert!(archive.clone().state(Some("wrong")).is_err());
+        let state = archive.state(Some("passphrase"))?;
+
+        // the local default identity is kept
+        let target = CliState::test().await?;
+        target.create_identity_with_name("bob").await?;
+        let summary = target.import_state(&state, false).await?;
+        assert!(summary.imported.contains(&"identity alice".to_string()));
+        assert!(summary.imported.contains(&"node node-1".to_string()));
+        assert!(summary
+            .imported
+            .contains(&"ticket template db-reader".to_string()));
+        assert_eq!(target.get_default_identity_name().await?, "bob");
+
+        // the identity keys can be used on the target machine
+        let alice = target.get_named_identity("alice").await?;
+        assert_eq!(alice.identifier(), identity.identifier());
+        let vault = target
+            .get_named_vault(&alice.vault_name())
+            .await?
+            .vault()
+            .await?;
+        let handle = target
+            .make_identities(vault.clone())
+            .await?
+            .identities_keys()
+            .get_secret_key(&target.get_identity(&alice.identifier()).await?)
+            .await?;
+        assert!(vault.identity_vault.sign(&handle, b"data").await.is_ok());
+
+        // a second import skips the existing entities
+        let summary = target.import_state(&state, false).await?;
+        assert!(summary.imported.iter().all(|i| !i.starts_with("identity")));
+        assert!(summary
+            .skipped
+            .iter()
+            .any(|s| s.starts_with("identity alice")));
+
+        // unless they are overwritten
+        let summary = target.import_state(&state, true).await?;
+        assert!(summary.overwritten.contains(&"identity alice".to_string()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_archives_with_an_invalid_salt_are_rejected() {
+        let archive = StateArchive::Encrypted {
+            salt: vec![],
+            nonce: vec![0; 12],
+            ciphertext: vec![0; 32],
+        };
+        let error = archive.state(Some("passphrase")).unwrap_err();
+        assert!(error.to_string().contains("key"));
+    }
+}
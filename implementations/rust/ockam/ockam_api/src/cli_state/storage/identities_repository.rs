@@ -62,6 +62,9 @@ pub trait IdentitiesRepository: Send + Sync + 'static {
         vault_name: &str,
     ) -> Result<Vec<NamedIdentity>>;
 
+    /// Set the name of the vault storing the keys of a named identity
+    async fn set_vault_name(&self, name: &str, vault_name: &str) -> Result<()>;
+
     /// Set an identity as the default one, given its name
     async fn set_as_default(&self, name: &str) -> Result<()>;
 
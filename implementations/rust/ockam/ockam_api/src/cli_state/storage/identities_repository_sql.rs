@@ -183,6 +183,13 @@ impl IdentitiesRepository for IdentitiesSqlxDatabase {
         row.iter().map(|r| r.named_identity()).collect()
     }
 
+    async fn set_vault_name(&self, name: &str, vault_name: &str) -> Result<()> {
+        let query = query("UPDATE named_identity SET vault_name = ? WHERE name = ?")
+            .bind(vault_name.to_sql())
+            .bind(name.to_sql());
+        query.execute(&*self.database.pool).await.void()
+    }
+
     async fn set_as_default(&self, name: &str) -> Result<()> {
         let mut transaction = self.database.begin().await.into_core()?;
         // set the identifier as the default one
@@ -369,6 +376,14 @@ mod tests {
         let names: Vec<String> = result.iter().map(|i| i.name()).collect();
         assert_eq!(names, vec!["name1", "name3"]);
 
+        // An identity can be moved to another vault
+        repository.set_vault_name("name3", "vault2").await?;
+        let result = repository
+            .get_named_identities_by_vault_name("vault2")
+            .await?;
+        let names: Vec<String> = result.iter().map(|i| i.name()).collect();
+        assert_eq!(names, vec!["name2", "name3"]);
+
         Ok(())
     }
 
@@ -1,4 +1,4 @@
-use crate::journeys::{Journey, ProjectJourney};
+use crate::journeys::{Journey, JourneyEventsFilter, ProjectJourney, StoredJourneyEvent};
 use chrono::{DateTime, Utc};
 use ockam_core::async_trait;
 use ockam_core::Result;
@@ -23,4 +23,17 @@ pub trait JourneysRepository: Send + Sync + 'static {
 
     /// Return the most recent host journey started after now
     async fn get_host_journey(&self, now: DateTime<Utc>) -> Result<Option<Journey>>;
+
+    /// Store a journey event
+    async fn store_journey_event(&self, event: StoredJourneyEvent) -> Result<()>;
+
+    /// Return the journey events selected by a filter, ordered by timestamp
+    async fn get_journey_events(
+        &self,
+        filter: JourneyEventsFilter,
+    ) -> Result<Vec<StoredJourneyEvent>>;
+
+    /// Delete the journey events which happened before a given time.
+    /// Return the number of deleted events
+    async fn delete_journey_events_before(&self, before: DateTime<Utc>) -> Result<u64>;
 }
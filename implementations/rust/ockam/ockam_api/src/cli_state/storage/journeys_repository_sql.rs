@@ -1,7 +1,8 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, TimeZone, Utc};
 use sqlx::*;
+use std::collections::BTreeMap;
 
-use crate::journeys::{Journey, ProjectJourney};
+use crate::journeys::{Journey, JourneyEventsFilter, ProjectJourney, StoredJourneyEvent};
 use crate::storage::journeys_repository::JourneysRepository;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::Result;
@@ -99,6 +100,50 @@ impl JourneysRepository for JourneysSqlxDatabase {
             .into_core()?;
         Ok(row.map(|r| r.host_journey()).transpose()?)
     }
+
+    async fn store_journey_event(&self, event: StoredJourneyEvent) -> Result<()> {
+        let attributes = serde_json::to_string(&event.attributes()).map_err(|e| {
+            ockam_core::Error::new(Origin::Api, Kind::Serialization, format!("{e:?}"))
+        })?;
+        let query = query("INSERT INTO journey_event VALUES (?, ?, ?, ?, ?)")
+            .bind(event.event().to_sql())
+            .bind(event.description().to_sql())
+            .bind(event.node_name().map(|n| n.to_sql()))
+            .bind(attributes.to_sql())
+            .bind((event.timestamp().timestamp_millis() as u64).to_sql());
+        query.execute(&*self.database.pool).await.void()
+    }
+
+    async fn get_journey_events(
+        &self,
+        filter: JourneyEventsFilter,
+    ) -> Result<Vec<StoredJourneyEvent>> {
+        let since = filter.since().map(|since| since.timestamp_millis() as u64);
+        let query = query_as(
+            "\
+        SELECT event, description, node_name, attributes, event_timestamp \
+        FROM journey_event \
+        WHERE (? IS NULL OR event_timestamp >= ?) \
+        AND (? IS NULL OR event = ?) \
+        AND (? IS NULL OR node_name = ?) \
+        ORDER BY event_timestamp ASC",
+        )
+        .bind(since.map(|s| s.to_sql()))
+        .bind(since.map(|s| s.to_sql()))
+        .bind(filter.event().map(|e| e.to_sql()))
+        .bind(filter.event().map(|e| e.to_sql()))
+        .bind(filter.node_name().map(|n| n.to_sql()))
+        .bind(filter.node_name().map(|n| n.to_sql()));
+        let rows: Vec<JourneyEventRow> = query.fetch_all(&*self.database.pool).await.into_core()?;
+        rows.iter().map(|r| r.journey_event()).collect()
+    }
+
+    async fn delete_journey_events_before(&self, before: DateTime<Utc>) -> Result<u64> {
+        let query = query("DELETE FROM journey_event WHERE event_timestamp < ?")
+            .bind((before.timestamp_millis() as u64).to_sql());
+        let result = query.execute(&*self.database.pool).await.into_core()?;
+        Ok(result.rows_affected())
+    }
 }
 
 //  Database serialization / deserialization
@@ -179,6 +224,42 @@ impl HostJourneyRow {
     }
 }
 
+/// Low-level representation of a row in the journey event table
+#[derive(sqlx::FromRow)]
+struct JourneyEventRow {
+    event: String,
+    description: String,
+    node_name: Option<String>,
+    attributes: String,
+    event_timestamp: i64,
+}
+
+impl JourneyEventRow {
+    fn journey_event(&self) -> Result<StoredJourneyEvent> {
+        let attributes: BTreeMap<String, String> =
+            serde_json::from_str(&self.attributes).map_err(|e| {
+                ockam_core::Error::new(Origin::Api, Kind::Serialization, format!("{e:?}"))
+            })?;
+        let timestamp = Utc
+            .timestamp_millis_opt(self.event_timestamp)
+            .single()
+            .ok_or_else(|| {
+                ockam_core::Error::new(
+                    Origin::Api,
+                    Kind::Serialization,
+                    format!("invalid journey event timestamp {}", self.event_timestamp),
+                )
+            })?;
+        Ok(StoredJourneyEvent::new(
+            &self.event,
+            &self.description,
+            self.node_name.clone(),
+            attributes,
+            timestamp,
+        ))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -348,6 +429,69 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_journey_events() -> Result<()> {
+        let repository = create_repository().await?;
+        // event timestamps are stored with a millisecond precision
+        let now = Utc
+            .timestamp_millis_opt(Utc::now().timestamp_millis())
+            .unwrap();
+
+        let event1 = StoredJourneyEvent::new(
+            "node-created",
+            "✅ node created",
+            Some("n1".to_string()),
+            BTreeMap::from([("app.node_name".to_string(), "n1".to_string())]),
+            now.sub(Duration::from_secs(3 * 3600)),
+        );
+        let event2 = StoredJourneyEvent::new(
+            "tcp-inlet-created",
+            "✅ tcp inlet created",
+            Some("n2".to_string()),
+            BTreeMap::from([("app.tcp_inlet.alias".to_string(), "inlet".to_string())]),
+            now.sub(Duration::from_secs(3600)),
+        );
+        let event3 = StoredJourneyEvent::new("command-ok", "node list", None, BTreeMap::new(), now);
+        for event in [&event3, &event1, &event2] {
+            repository.store_journey_event(event.clone()).await?;
+        }
+
+        // all the events are returned by timestamp
+        let actual = repository
+            .get_journey_events(JourneyEventsFilter::default())
+            .await?;
+        assert_eq!(actual, vec![event1.clone(), event2.clone(), event3.clone()]);
+
+        // filter the events
+        let actual = repository
+            .get_journey_events(
+                JourneyEventsFilter::default().with_since(now.sub(Duration::from_secs(7200))),
+            )
+            .await?;
+        assert_eq!(actual, vec![event2.clone(), event3.clone()]);
+
+        let actual = repository
+            .get_journey_events(JourneyEventsFilter::default().with_event("tcp-inlet-created"))
+            .await?;
+        assert_eq!(actual, vec![event2.clone()]);
+
+        let actual = repository
+            .get_journey_events(JourneyEventsFilter::default().with_node_name("n1"))
+            .await?;
+        assert_eq!(actual, vec![event1.clone()]);
+
+        // delete the oldest events
+        let deleted = repository
+            .delete_journey_events_before(now.sub(Duration::from_secs(1800)))
+            .await?;
+        assert_eq!(deleted, 2);
+        let actual = repository
+            .get_journey_events(JourneyEventsFilter::default())
+            .await?;
+        assert_eq!(actual, vec![event3]);
+        Ok(())
+    }
+
     /// HELPERS
     async fn create_repository() -> Result<Arc<dyn JourneysRepository>> {
         Ok(Arc::new(JourneysSqlxDatabase::create().await?))
@@ -8,8 +8,12 @@ pub use nodes_repository::*;
 pub use nodes_repository_sql::*;
 pub use projects_repository::*;
 pub use projects_repository_sql::*;
+pub use runs_repository::*;
+pub use runs_repository_sql::*;
 pub use spaces_repository::*;
 pub use spaces_repository_sql::*;
+pub use ticket_templates_repository::*;
+pub use ticket_templates_repository_sql::*;
 pub use users_repository::*;
 pub use users_repository_sql::*;
 pub use vaults_repository::*;
@@ -25,8 +29,12 @@ mod nodes_repository;
 mod nodes_repository_sql;
 mod projects_repository;
 mod projects_repository_sql;
+mod runs_repository;
+mod runs_repository_sql;
 mod spaces_repository;
 mod spaces_repository_sql;
+mod ticket_templates_repository;
+mod ticket_templates_repository_sql;
 mod users_repository;
 mod users_repository_sql;
 mod vaults_repository;
@@ -2,7 +2,7 @@ use ockam::identity::Identifier;
 use ockam_core::async_trait;
 use ockam_core::Result;
 
-use crate::cli_state::NodeInfo;
+use crate::cli_state::{NodeInfo, NodeLogging, NodeProxy, NodeSupervision, NodeSystemService};
 use crate::config::lookup::InternetAddress;
 
 /// This trait supports the storage of node data:
@@ -12,6 +12,7 @@ use crate::config::lookup::InternetAddress;
 ///  - a node can be associated to a (single) project
 ///  - when a node is running we can persist its process id and its TCP listener address
 ///  - one of the nodes is always set as the default node
+///  - a background node can be supervised, in order to be restarted when its process crashes
 ///  - a node can be set as an authority node. The purpose of this flag is to be able to display
 ///    the node status without being able to start a TCP connection since the TCP listener might not be accessible
 ///
@@ -41,6 +42,9 @@ pub trait NodesRepository: Send + Sync + 'static {
     /// Delete a node given its name
     async fn delete_node(&self, node_name: &str) -> Result<()>;
 
+    /// Rename a node and move all the data stored for its previous name
+    async fn rename_node(&self, node_name: &str, new_node_name: &str) -> Result<()>;
+
     /// Set the TCP listener of a node
     async fn set_tcp_listener_address(
         &self,
@@ -65,4 +69,43 @@ pub trait NodesRepository: Send + Sync + 'static {
 
     /// Return the name of the project associated to a node
     async fn get_node_project_name(&self, node_name: &str) -> Result<Option<String>>;
+
+    /// Store or update the supervision settings and state of a node
+    async fn set_node_supervision(&self, supervision: &NodeSupervision) -> Result<()>;
+
+    /// Return the supervision settings and state of a node, if it is supervised
+    async fn get_node_supervision(&self, node_name: &str) -> Result<Option<NodeSupervision>>;
+
+    /// Store or update the proxy used by a node
+    async fn set_node_proxy(&self, proxy: &NodeProxy) -> Result<()>;
+
+    /// Return the proxy used by a node, if any
+    async fn get_node_proxy(&self, node_name: &str) -> Result<Option<NodeProxy>>;
+
+    /// Remove the proxy used by a node
+    async fn delete_node_proxy(&self, node_name: &str) -> Result<()>;
+
+    /// Store or update the logging configuration of a node
+    async fn set_node_logging(&self, logging: &NodeLogging) -> Result<()>;
+
+    /// Return the logging configuration of a node, if any
+    async fn get_node_logging(&self, node_name: &str) -> Result<Option<NodeLogging>>;
+
+    /// Remove the logging configuration of a node
+    async fn delete_node_logging(&self, node_name: &str) -> Result<()>;
+
+    /// Store the system service running a node
+    async fn set_node_system_service(&self, system_service: &NodeSystemService) -> Result<()>;
+
+    /// Return the system service running a node, if any
+    async fn get_node_system_service(&self, node_name: &str) -> Result<Option<NodeSystemService>>;
+
+    /// Remove the system service running a node
+    async fn delete_node_system_service(&self, node_name: &str) -> Result<()>;
+
+    /// Store the command line arguments setting the options of a node
+    async fn set_node_arguments(&self, node_name: &str, arguments: &[String]) -> Result<()>;
+
+    /// Return the command line arguments setting the options of a node, if they were stored
+    async fn get_node_arguments(&self, node_name: &str) -> Result<Option<Vec<String>>>;
 }
@@ -1,3 +1,4 @@
+use ockam::identity::TimestampInSeconds;
 use ockam_core::async_trait;
 use ockam_core::Result;
 
@@ -30,6 +31,24 @@ pub trait ProjectsRepository: Send + Sync + 'static {
     /// Set one project as the default project
     async fn set_default_project(&self, project_id: &str) -> Result<()>;
 
+    /// Mark a project as imported from a file rather than retrieved from the Controller.
+    /// The mark is removed when the project is stored again
+    async fn set_project_imported(&self, project_id: &str) -> Result<()>;
+
+    /// Return true if the project was imported from a file
+    async fn is_project_imported(&self, project_id: &str) -> Result<bool>;
+
+    /// Set the last time a project was retrieved from the Controller.
+    /// That time is reset when the project is stored again
+    async fn set_project_fetched_at(
+        &self,
+        project_id: &str,
+        fetched_at: TimestampInSeconds,
+    ) -> Result<()>;
+
+    /// Return the last time a project was retrieved from the Controller, if known
+    async fn get_project_fetched_at(&self, project_id: &str) -> Result<Option<TimestampInSeconds>>;
+
     /// Delete a project
     /// Return true if the project could be deleted
     async fn delete_project(&self, project_id: &str) -> Result<()>;
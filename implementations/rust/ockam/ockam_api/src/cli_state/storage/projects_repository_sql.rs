@@ -3,7 +3,7 @@ use std::str::FromStr;
 use sqlx::sqlite::SqliteRow;
 use sqlx::*;
 
-use ockam::identity::Identifier;
+use ockam::identity::{Identifier, TimestampInSeconds};
 use ockam_core::async_trait;
 use ockam_core::env::FromString;
 use ockam_core::errcode::{Kind, Origin};
@@ -109,6 +109,13 @@ impl ProjectsRepository for ProjectsSqlxDatabase {
             .bind(true.to_sql());
         query5.execute(&mut *transaction).await.void()?;
 
+        // remove the okta and kafka configurations which might have been disabled since then
+        let query6 = query("DELETE FROM okta_config WHERE project_id=$1").bind(project.id.to_sql());
+        query6.execute(&mut *transaction).await.void()?;
+        let query7 =
+            query("DELETE FROM kafka_config WHERE project_id=$1").bind(project.id.to_sql());
+        query7.execute(&mut *transaction).await.void()?;
+
         // store the okta configuration if any
         for okta_config in &project.okta_config {
             let query = query("INSERT OR REPLACE INTO okta_config VALUES (?, ?, ?, ?, ?)")
@@ -246,6 +253,44 @@ impl ProjectsRepository for ProjectsSqlxDatabase {
         transaction.commit().await.void()
     }
 
+    async fn set_project_imported(&self, project_id: &str) -> Result<()> {
+        let query = query("UPDATE project SET is_imported = ? WHERE project_id = ?")
+            .bind(true.to_sql())
+            .bind(project_id.to_sql());
+        query.execute(&*self.database.pool).await.void()
+    }
+
+    async fn is_project_imported(&self, project_id: &str) -> Result<bool> {
+        let query = query_scalar("SELECT is_imported FROM project WHERE project_id=$1")
+            .bind(project_id.to_sql());
+        let is_imported: Option<bool> = query
+            .fetch_optional(&*self.database.pool)
+            .await
+            .into_core()?;
+        Ok(is_imported.unwrap_or(false))
+    }
+
+    async fn set_project_fetched_at(
+        &self,
+        project_id: &str,
+        fetched_at: TimestampInSeconds,
+    ) -> Result<()> {
+        let query = query("UPDATE project SET fetched_at = ? WHERE project_id = ?")
+            .bind(fetched_at.to_sql())
+            .bind(project_id.to_sql());
+        query.execute(&*self.database.pool).await.void()
+    }
+
+    async fn get_project_fetched_at(&self, project_id: &str) -> Result<Option<TimestampInSeconds>> {
+        let query = query_scalar("SELECT fetched_at FROM project WHERE project_id=$1")
+            .bind(project_id.to_sql());
+        let fetched_at: Option<Option<i64>> = query
+            .fetch_optional(&*self.database.pool)
+            .await
+            .into_core()?;
+        Ok(fetched_at.flatten().map(|t| TimestampInSeconds(t as u64)))
+    }
+
     async fn delete_project(&self, project_id: &str) -> Result<()> {
         let mut transaction = self.database.begin().await.into_core()?;
 
@@ -479,6 +524,50 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_imported_project() -> Result<()> {
+        let repository = create_repository().await?;
+
+        let mut project = create_project("1", "name1", vec![], vec![]);
+        repository.store_project(&project).await?;
+        assert!(!repository.is_project_imported("1").await?);
+
+        repository.set_project_imported("1").await?;
+        assert!(repository.is_project_imported("1").await?);
+
+        // storing the project again updates it without duplicating its configurations
+        project.okta_config = None;
+        repository.store_project(&project).await?;
+        assert_eq!(repository.get_projects().await?, vec![project.clone()]);
+        assert!(!repository.is_project_imported("1").await?);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_project_fetched_at() -> Result<()> {
+        let repository = create_repository().await?;
+
+        let project = create_project("1", "name1", vec![], vec![]);
+        repository.store_project(&project).await?;
+        assert_eq!(repository.get_project_fetched_at("1").await?, None);
+
+        repository
+            .set_project_fetched_at("1", TimestampInSeconds(10))
+            .await?;
+        assert_eq!(
+            repository.get_project_fetched_at("1").await?,
+            Some(TimestampInSeconds(10))
+        );
+
+        // storing the project again resets the time
+        repository.store_project(&project).await?;
+        assert_eq!(repository.get_project_fetched_at("1").await?, None);
+
+        // an unknown project has no fetch time
+        assert_eq!(repository.get_project_fetched_at("2").await?, None);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_store_project_space() -> Result<()> {
         let db = SqlxDatabase::in_memory("projects").await?;
@@ -0,0 +1,24 @@
+use crate::cli_state::{Run, RunResource};
+use ockam_core::async_trait;
+use ockam_core::Result;
+
+/// This trait supports the storage of the resources created by the execution
+/// of a configuration file with `ockam run`
+///
+///  - a run is identified by the configuration file which was executed
+///  - executing the same file again adds its new resources to the existing ones
+///
+#[async_trait]
+pub trait RunsRepository: Send + Sync + 'static {
+    /// Store a run, with the hash of the executed configuration
+    async fn store_run(&self, run_id: &str, contents_hash: &str) -> Result<()>;
+
+    /// Add a resource created by a run
+    async fn add_run_resource(&self, run_id: &str, resource: &RunResource) -> Result<()>;
+
+    /// Return a run and the resources it created
+    async fn get_run(&self, run_id: &str) -> Result<Option<Run>>;
+
+    /// Delete a run and its resources
+    async fn delete_run(&self, run_id: &str) -> Result<()>;
+}
@@ -0,0 +1,151 @@
+use std::str::FromStr;
+
+use sqlx::*;
+
+use ockam_core::async_trait;
+use ockam_core::Result;
+use ockam_node::database::{FromSqlxError, SqlxDatabase, ToSqlxType, ToVoid};
+
+use crate::cli_state::{Run, RunResource, RunResourceKind};
+
+use super::RunsRepository;
+
+#[derive(Clone)]
+pub struct RunsSqlxDatabase {
+    database: SqlxDatabase,
+}
+
+impl RunsSqlxDatabase {
+    /// Create a new database
+    pub fn new(database: SqlxDatabase) -> Self {
+        debug!("create a repository for runs");
+        Self { database }
+    }
+
+    /// Create a new in-memory database
+    pub async fn create() -> Result<Self> {
+        Ok(Self::new(SqlxDatabase::in_memory("runs").await?))
+    }
+}
+
+#[async_trait]
+impl RunsRepository for RunsSqlxDatabase {
+    async fn store_run(&self, run_id: &str, contents_hash: &str) -> Result<()> {
+        let query = query("INSERT OR REPLACE INTO run VALUES (?, ?)")
+            .bind(run_id.to_sql())
+            .bind(contents_hash.to_sql());
+        query.execute(&*self.database.pool).await.void()
+    }
+
+    async fn add_run_resource(&self, run_id: &str, resource: &RunResource) -> Result<()> {
+        let query = query("INSERT OR REPLACE INTO run_resource VALUES (?, ?, ?, ?)")
+            .bind(run_id.to_sql())
+            .bind(resource.kind().to_string().to_sql())
+            .bind(resource.name().to_sql())
+            .bind(resource.node_name().to_sql());
+        query.execute(&*self.database.pool).await.void()
+    }
+
+    async fn get_run(&self, run_id: &str) -> Result<Option<Run>> {
+        let mut transaction = self.database.begin().await.into_core()?;
+
+        let query1 = query_as("SELECT run_id, contents_hash FROM run WHERE run_id = ?")
+            .bind(run_id.to_sql());
+        let row: Option<RunRow> = query1.fetch_optional(&mut *transaction).await.into_core()?;
+        let run = match row {
+            Some(row) => {
+                let query2 = query_as(
+                    "SELECT resource_type, resource_name, node_name FROM run_resource WHERE run_id = ?",
+                )
+                .bind(run_id.to_sql());
+                let rows: Vec<RunResourceRow> =
+                    query2.fetch_all(&mut *transaction).await.into_core()?;
+                let resources = rows
+                    .into_iter()
+                    .map(|r| r.run_resource())
+                    .collect::<Result<Vec<_>>>()?;
+                Some(Run::new(&row.run_id, &row.contents_hash, resources))
+            }
+            None => None,
+        };
+        transaction.commit().await.void()?;
+        Ok(run)
+    }
+
+    async fn delete_run(&self, run_id: &str) -> Result<()> {
+        let mut transaction = self.database.begin().await.into_core()?;
+
+        let query1 = query("DELETE FROM run WHERE run_id = ?").bind(run_id.to_sql());
+        query1.execute(&mut *transaction).await.void()?;
+
+        let query2 = query("DELETE FROM run_resource WHERE run_id = ?").bind(run_id.to_sql());
+        query2.execute(&mut *transaction).await.void()?;
+
+        transaction.commit().await.void()
+    }
+}
+
+// Database serialization / deserialization
+
+#[derive(FromRow)]
+pub(crate) struct RunRow {
+    run_id: String,
+    contents_hash: String,
+}
+
+#[derive(FromRow)]
+pub(crate) struct RunResourceRow {
+    resource_type: String,
+    resource_name: String,
+    node_name: String,
+}
+
+impl RunResourceRow {
+    pub(crate) fn run_resource(&self) -> Result<RunResource> {
+        Ok(RunResource::new(
+            RunResourceKind::from_str(&self.resource_type)?,
+            &self.resource_name,
+            &self.node_name,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_repository() -> Result<()> {
+        let repository = RunsSqlxDatabase::create().await?;
+
+        // a run and its resources can be stored
+        repository.store_run("/tmp/ockam.yaml", "hash1").await?;
+        let node = RunResource::new(RunResourceKind::Node, "n1", "n1");
+        let inlet = RunResource::new(RunResourceKind::TcpInlet, "db", "n1");
+        repository
+            .add_run_resource("/tmp/ockam.yaml", &node)
+            .await?;
+        repository
+            .add_run_resource("/tmp/ockam.yaml", &inlet)
+            .await?;
+        // a resource recorded twice is only stored once
+        repository
+            .add_run_resource("/tmp/ockam.yaml", &inlet)
+            .await?;
+
+        let run = repository.get_run("/tmp/ockam.yaml").await?.unwrap();
+        assert_eq!(run.contents_hash(), "hash1");
+        assert_eq!(run.resources().len(), 2);
+
+        // the resources are kept when the run is executed again
+        repository.store_run("/tmp/ockam.yaml", "hash2").await?;
+        let run = repository.get_run("/tmp/ockam.yaml").await?.unwrap();
+        assert_eq!(run.contents_hash(), "hash2");
+        assert_eq!(run.resources().len(), 2);
+
+        // a run can be deleted
+        repository.delete_run("/tmp/ockam.yaml").await?;
+        assert_eq!(repository.get_run("/tmp/ockam.yaml").await?, None);
+        Ok(())
+    }
+}
@@ -0,0 +1,22 @@
+use crate::cli_state::TicketTemplate;
+use ockam_core::async_trait;
+use ockam_core::Result;
+
+/// This trait supports the storage of named templates for enrollment tickets.
+///
+/// A template stores the attributes, usage count and validity of the tickets created
+/// with `ockam project ticket --template`
+#[async_trait]
+pub trait TicketTemplatesRepository: Send + Sync + 'static {
+    /// Store a ticket template, replacing an existing template with the same name
+    async fn store_ticket_template(&self, template: &TicketTemplate) -> Result<()>;
+
+    /// Return a ticket template given its name
+    async fn get_ticket_template(&self, name: &str) -> Result<Option<TicketTemplate>>;
+
+    /// Return all the ticket templates, sorted by name
+    async fn get_ticket_templates(&self) -> Result<Vec<TicketTemplate>>;
+
+    /// Delete a ticket template and return true if it existed
+    async fn delete_ticket_template(&self, name: &str) -> Result<bool>;
+}
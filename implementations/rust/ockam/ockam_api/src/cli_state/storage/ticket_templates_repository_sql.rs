@@ -0,0 +1,137 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use sqlx::*;
+
+use ockam_core::async_trait;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::Result;
+use ockam_node::database::{FromSqlxError, SqlxDatabase, ToSqlxType, ToVoid};
+
+use crate::cli_state::TicketTemplate;
+
+use super::TicketTemplatesRepository;
+
+#[derive(Clone)]
+pub struct TicketTemplatesSqlxDatabase {
+    database: SqlxDatabase,
+}
+
+impl TicketTemplatesSqlxDatabase {
+    /// Create a new database
+    pub fn new(database: SqlxDatabase) -> Self {
+        debug!("create a repository for ticket templates");
+        Self { database }
+    }
+
+    /// Create a new in-memory database
+    pub async fn create() -> Result<Self> {
+        Ok(Self::new(
+            SqlxDatabase::in_memory("ticket templates").await?,
+        ))
+    }
+}
+
+#[async_trait]
+impl TicketTemplatesRepository for TicketTemplatesSqlxDatabase {
+    async fn store_ticket_template(&self, template: &TicketTemplate) -> Result<()> {
+        let attributes = serde_json::to_string(template.attributes()).map_err(|e| {
+            ockam_core::Error::new(Origin::Api, Kind::Serialization, format!("{e:?}"))
+        })?;
+        let query = query("INSERT OR REPLACE INTO ticket_template VALUES (?, ?, ?, ?)")
+            .bind(template.name().to_sql())
+            .bind(attributes.to_sql())
+            .bind(template.usage_count().map(|c| c as i64))
+            .bind(template.expires_in().map(|d| d.as_secs() as i64));
+        query.execute(&*self.database.pool).await.void()
+    }
+
+    async fn get_ticket_template(&self, name: &str) -> Result<Option<TicketTemplate>> {
+        let query = query_as(
+            "SELECT name, attributes, usage_count, expires_in FROM ticket_template WHERE name = ?",
+        )
+        .bind(name.to_sql());
+        let row: Option<TicketTemplateRow> = query
+            .fetch_optional(&*self.database.pool)
+            .await
+            .into_core()?;
+        row.map(|r| r.ticket_template()).transpose()
+    }
+
+    async fn get_ticket_templates(&self) -> Result<Vec<TicketTemplate>> {
+        let query = query_as(
+            "SELECT name, attributes, usage_count, expires_in FROM ticket_template ORDER BY name",
+        );
+        let rows: Vec<TicketTemplateRow> =
+            query.fetch_all(&*self.database.pool).await.into_core()?;
+        rows.iter().map(|r| r.ticket_template()).collect()
+    }
+
+    async fn delete_ticket_template(&self, name: &str) -> Result<bool> {
+        let query = query("DELETE FROM ticket_template WHERE name = ?").bind(name.to_sql());
+        let result = query.execute(&*self.database.pool).await.into_core()?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+// Database serialization / deserialization
+
+#[derive(FromRow)]
+pub(crate) struct TicketTemplateRow {
+    name: String,
+    attributes: String,
+    usage_count: Option<i64>,
+    expires_in: Option<i64>,
+}
+
+impl TicketTemplateRow {
+    pub(crate) fn ticket_template(&self) -> Result<TicketTemplate> {
+        let attributes: BTreeMap<String, String> =
+            serde_json::from_str(&self.attributes).map_err(|e| {
+                ockam_core::Error::new(Origin::Api, Kind::Serialization, format!("{e:?}"))
+            })?;
+        Ok(TicketTemplate::new(
+            &self.name,
+            attributes,
+            self.usage_count.map(|c| c as u64),
+            self.expires_in.map(|s| Duration::from_secs(s as u64)),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_repository() -> Result<()> {
+        let repository = TicketTemplatesSqlxDatabase::create().await?;
+
+        // a template can be stored and retrieved
+        let attributes = BTreeMap::from([
+            ("component".to_string(), "db".to_string()),
+            ("access".to_string(), "read".to_string()),
+        ]);
+        let db_reader = TicketTemplate::new(
+            "db-reader",
+            attributes,
+            Some(1),
+            Some(Duration::from_secs(2 * 24 * 60 * 60)),
+        );
+        repository.store_ticket_template(&db_reader).await?;
+        let result = repository.get_ticket_template("db-reader").await?;
+        assert_eq!(result, Some(db_reader.clone()));
+
+        // the templates are listed by name
+        let admin = TicketTemplate::new("admin", BTreeMap::new(), None, None);
+        repository.store_ticket_template(&admin).await?;
+        let result = repository.get_ticket_templates().await?;
+        assert_eq!(result, vec![admin, db_reader]);
+
+        // a template can be deleted
+        assert!(repository.delete_ticket_template("admin").await?);
+        assert!(!repository.delete_ticket_template("admin").await?);
+        assert_eq!(repository.get_ticket_template("admin").await?, None);
+        Ok(())
+    }
+}
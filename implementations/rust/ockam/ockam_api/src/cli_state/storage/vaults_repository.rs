@@ -3,18 +3,28 @@ use std::path::Path;
 use ockam_core::async_trait;
 use ockam_core::Result;
 
-use crate::NamedVault;
+use crate::{NamedVault, VaultEncryption};
 
 /// This trait allows vaults to be defined with a name and a path
 /// in order to make it possible to store identity keys in different databases on disk (or in a KMS)
 #[async_trait]
 pub trait VaultsRepository: Send + Sync + 'static {
     /// Store a new vault path with an associated name
-    async fn store_vault(&self, name: &str, path: &Path, is_kms: bool) -> Result<NamedVault>;
+    /// A KMS vault can be associated to a specific KMS key
+    async fn store_vault(
+        &self,
+        name: &str,
+        path: &Path,
+        is_kms: bool,
+        kms_key_id: Option<&str>,
+    ) -> Result<NamedVault>;
 
     /// Update a vault path
     async fn update_vault(&self, name: &str, path: &Path) -> Result<()>;
 
+    /// Set the parameters used to encrypt the secrets of a vault
+    async fn set_vault_encryption(&self, name: &str, encryption: &VaultEncryption) -> Result<()>;
+
     /// Delete a vault given its name
     async fn delete_named_vault(&self, name: &str) -> Result<()>;
 
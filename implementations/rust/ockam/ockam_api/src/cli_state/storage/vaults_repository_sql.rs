@@ -3,7 +3,7 @@ use std::str::FromStr;
 
 use sqlx::*;
 
-use crate::cli_state::{NamedVault, VaultsRepository};
+use crate::cli_state::{NamedVault, VaultEncryption, VaultsRepository};
 use ockam::{FromSqlxError, SqlxDatabase, ToSqlxType, ToVoid};
 use ockam_core::async_trait;
 use ockam_core::Result;
@@ -27,15 +27,24 @@ impl VaultsSqlxDatabase {
 
 #[async_trait]
 impl VaultsRepository for VaultsSqlxDatabase {
-    async fn store_vault(&self, name: &str, path: &Path, is_kms: bool) -> Result<NamedVault> {
-        let query = query("INSERT INTO vault VALUES (?1, ?2, ?3, ?4)")
-            .bind(name.to_sql())
-            .bind(path.to_sql())
-            .bind(true.to_sql())
-            .bind(is_kms.to_sql());
+    async fn store_vault(
+        &self,
+        name: &str,
+        path: &Path,
+        is_kms: bool,
+        kms_key_id: Option<&str>,
+    ) -> Result<NamedVault> {
+        let query = query(
+            "INSERT INTO vault (name, path, is_default, is_kms, kms_key_id) VALUES (?1, ?2, ?3, ?4, ?5)",
+        )
+        .bind(name.to_sql())
+        .bind(path.to_sql())
+        .bind(true.to_sql())
+        .bind(is_kms.to_sql())
+        .bind(kms_key_id.map(|k| k.to_sql()));
         query.execute(&*self.database.pool).await.void()?;
 
-        Ok(NamedVault::new(name, path.into(), is_kms))
+        Ok(NamedVault::new(name, path.into(), is_kms).with_kms_key_id(kms_key_id))
     }
 
     async fn update_vault(&self, name: &str, path: &Path) -> Result<()> {
@@ -45,6 +54,14 @@ impl VaultsRepository for VaultsSqlxDatabase {
         query.execute(&*self.database.pool).await.void()
     }
 
+    async fn set_vault_encryption(&self, name: &str, encryption: &VaultEncryption) -> Result<()> {
+        let query = query("UPDATE vault SET encryption_salt=$1, encryption_check=$2 WHERE name=$3")
+            .bind(encryption.salt().to_sql())
+            .bind(encryption.check().to_sql())
+            .bind(name.to_sql());
+        query.execute(&*self.database.pool).await.void()
+    }
+
     /// Delete a vault by name
     async fn delete_named_vault(&self, name: &str) -> Result<()> {
         let query = query("DELETE FROM vault WHERE name=?").bind(name.to_sql());
@@ -52,8 +69,8 @@ impl VaultsRepository for VaultsSqlxDatabase {
     }
 
     async fn get_named_vault(&self, name: &str) -> Result<Option<NamedVault>> {
-        let query =
-            query_as("SELECT name, path, is_kms FROM vault WHERE name = $1").bind(name.to_sql());
+        let query = query_as("SELECT name, path, is_kms, kms_key_id, encryption_salt, encryption_check FROM vault WHERE name = $1")
+            .bind(name.to_sql());
         let row: Option<VaultRow> = query
             .fetch_optional(&*self.database.pool)
             .await
@@ -62,8 +79,8 @@ impl VaultsRepository for VaultsSqlxDatabase {
     }
 
     async fn get_named_vault_with_path(&self, path: &Path) -> Result<Option<NamedVault>> {
-        let query =
-            query_as("SELECT name, path, is_kms FROM vault WHERE path = $1").bind(path.to_sql());
+        let query = query_as("SELECT name, path, is_kms, kms_key_id, encryption_salt, encryption_check FROM vault WHERE path = $1")
+            .bind(path.to_sql());
         let row: Option<VaultRow> = query
             .fetch_optional(&*self.database.pool)
             .await
@@ -72,7 +89,9 @@ impl VaultsRepository for VaultsSqlxDatabase {
     }
 
     async fn get_named_vaults(&self) -> Result<Vec<NamedVault>> {
-        let query = query_as("SELECT name, path, is_kms FROM vault");
+        let query = query_as(
+            "SELECT name, path, is_kms, kms_key_id, encryption_salt, encryption_check FROM vault",
+        );
         let rows: Vec<VaultRow> = query.fetch_all(&*self.database.pool).await.into_core()?;
         rows.iter().map(|r| r.named_vault()).collect()
     }
@@ -85,6 +104,9 @@ pub(crate) struct VaultRow {
     name: String,
     path: String,
     is_kms: bool,
+    kms_key_id: Option<String>,
+    encryption_salt: Option<Vec<u8>>,
+    encryption_check: Option<Vec<u8>>,
 }
 
 impl VaultRow {
@@ -93,7 +115,16 @@ impl VaultRow {
             &self.name,
             PathBuf::from_str(self.path.as_str()).unwrap(),
             self.is_kms,
-        ))
+        )
+        .with_kms_key_id(self.kms_key_id.as_deref())
+        .with_encryption(self.encryption()))
+    }
+
+    fn encryption(&self) -> Option<VaultEncryption> {
+        match (&self.encryption_salt, &self.encryption_check) {
+            (Some(salt), Some(check)) => Some(VaultEncryption::new(salt.clone(), check.clone())),
+            _ => None,
+        }
     }
 }
 
@@ -108,14 +139,14 @@ mod test {
 
         // A vault can be defined with a path and stored under a specific name
         let named_vault1 = repository
-            .store_vault("vault1", Path::new("path"), false)
+            .store_vault("vault1", Path::new("path"), false, None)
             .await?;
         let expected = NamedVault::new("vault1", Path::new("path").into(), false);
         assert_eq!(named_vault1, expected);
 
         // A vault with the same name can not be created twice
         let result = repository
-            .store_vault("vault1", Path::new("path"), false)
+            .store_vault("vault1", Path::new("path"), false, None)
             .await;
         assert!(result.is_err());
 
@@ -152,10 +183,39 @@ mod test {
 
         // A KMS vault can be created by setting the kms flag to true
         let kms = repository
-            .store_vault("kms", Path::new("path"), true)
+            .store_vault("kms", Path::new("path"), true, None)
             .await?;
         let expected = NamedVault::new("kms", Path::new("path").into(), true);
         assert_eq!(kms, expected);
+
+        // A KMS vault can also be associated to a specific key
+        let key_arn = "arn:aws:kms:us-east-1:123456789012:key/1234abcd";
+        let kms_with_key = repository
+            .store_vault("kms-with-key", Path::new("path2"), true, Some(key_arn))
+            .await?;
+        assert_eq!(kms_with_key.kms_key_id(), Some(key_arn.to_string()));
+
+        let result = repository.get_named_vault("kms-with-key").await?;
+        assert_eq!(result, Some(kms_with_key));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_set_vault_encryption() -> Result<()> {
+        let repository = create_repository().await?;
+
+        let vault = repository
+            .store_vault("vault", Path::new("path"), false, None)
+            .await?;
+        assert_eq!(vault.encryption(), None);
+
+        // The encryption parameters of a vault can be set
+        let encryption = VaultEncryption::new(vec![1; 16], vec![2; 48]);
+        repository
+            .set_vault_encryption("vault", &encryption)
+            .await?;
+        let result = repository.get_named_vault("vault").await?;
+        assert_eq!(result.and_then(|v| v.encryption()), Some(encryption));
         Ok(())
     }
 
@@ -0,0 +1,188 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::cli_state::{CliState, CliStateError, Result};
+
+/// The methods below support the management of named templates for the enrollment tickets
+/// created with `ockam project ticket`
+impl CliState {
+    /// Create a ticket template. The creation fails if a template with the same name already exists
+    #[instrument(skip_all, fields(name = template.name()))]
+    pub async fn create_ticket_template(&self, template: &TicketTemplate) -> Result<()> {
+        let repository = self.ticket_templates_repository();
+        if repository
+            .get_ticket_template(template.name())
+            .await?
+            .is_some()
+        {
+            return Err(CliStateError::AlreadyExists {
+                resource: "ticket template".to_string(),
+                name: template.name().to_string(),
+            });
+        }
+        Ok(repository.store_ticket_template(template).await?)
+    }
+
+    /// Return a ticket template given its name
+    pub async fn get_ticket_template(&self, name: &str) -> Result<TicketTemplate> {
+        self.ticket_templates_repository()
+            .get_ticket_template(name)
+            .await?
+            .ok_or_else(|| CliStateError::ResourceNotFound {
+                resource: "ticket template".to_string(),
+                name: name.to_string(),
+            })
+    }
+
+    /// Return all the ticket templates, sorted by name
+    pub async fn get_ticket_templates(&self) -> Result<Vec<TicketTemplate>> {
+        Ok(self
+            .ticket_templates_repository()
+            .get_ticket_templates()
+            .await?)
+    }
+
+    /// Delete a ticket template
+    #[instrument(skip_all, fields(name = name))]
+    pub async fn delete_ticket_template(&self, name: &str) -> Result<()> {
+        if !self
+            .ticket_templates_repository()
+            .delete_ticket_template(name)
+            .await?
+        {
+            return Err(CliStateError::ResourceNotFound {
+                resource: "ticket template".to_string(),
+                name: name.to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Named set of parameters used to create enrollment tickets
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TicketTemplate {
+    name: String,
+    attributes: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    usage_count: Option<u64>,
+    /// Validity of a ticket, in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    expires_in: Option<u64>,
+}
+
+impl TicketTemplate {
+    pub fn new(
+        name: &str,
+        attributes: BTreeMap<String, String>,
+        usage_count: Option<u64>,
+        expires_in: Option<Duration>,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            attributes,
+            usage_count,
+            expires_in: expires_in.map(|d| d.as_secs()),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Attributes given to the identities enrolled with a ticket
+    pub fn attributes(&self) -> &BTreeMap<String, String> {
+        &self.attributes
+    }
+
+    /// Number of times a ticket can be used to enroll
+    pub fn usage_count(&self) -> Option<u64> {
+        self.usage_count
+    }
+
+    /// Validity of a ticket
+    pub fn expires_in(&self) -> Option<Duration> {
+        self.expires_in.map(Duration::from_secs)
+    }
+
+    /// Return a copy of this template where the given values take precedence.
+    /// Attributes are merged, an attribute given as an override replacing the template value
+    pub fn with_overrides(
+        &self,
+        attributes: BTreeMap<String, String>,
+        usage_count: Option<u64>,
+        expires_in: Option<Duration>,
+    ) -> Self {
+        let mut merged = self.clone();
+        merged.attributes.extend(attributes);
+        merged.usage_count = usage_count.or(self.usage_count);
+        merged.expires_in = expires_in.map(|d| d.as_secs()).or(self.expires_in);
+        merged
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_ticket_templates() -> Result<()> {
+        let cli = CliState::test().await?;
+        let template = TicketTemplate::new(
+            "db-reader",
+            BTreeMap::from([("component".to_string(), "db".to_string())]),
+            Some(1),
+            None,
+        );
+        cli.create_ticket_template(&template).await?;
+
+        // a template can't be created twice
+        let result = cli.create_ticket_template(&template).await;
+        assert!(matches!(result, Err(CliStateError::AlreadyExists { .. })));
+        assert_eq!(cli.get_ticket_template("db-reader").await?, template);
+
+        // a template can only be deleted once
+        cli.delete_ticket_template("db-reader").await?;
+        let result = cli.delete_ticket_template("db-reader").await;
+        assert!(matches!(
+            result,
+            Err(CliStateError::ResourceNotFound { .. })
+        ));
+        assert!(cli.get_ticket_templates().await?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_with_overrides() {
+        let template = TicketTemplate::new(
+            "db-reader",
+            BTreeMap::from([
+                ("component".to_string(), "db".to_string()),
+                ("access".to_string(), "read".to_string()),
+            ]),
+            Some(1),
+            Some(Duration::from_secs(3600)),
+        );
+
+        let merged = template.with_overrides(
+            BTreeMap::from([
+                ("access".to_string(), "write".to_string()),
+                ("extra".to_string(), "1".to_string()),
+            ]),
+            None,
+            Some(Duration::from_secs(60)),
+        );
+        assert_eq!(
+            merged.attributes(),
+            &BTreeMap::from([
+                ("component".to_string(), "db".to_string()),
+                ("access".to_string(), "write".to_string()),
+                ("extra".to_string(), "1".to_string()),
+            ])
+        );
+        assert_eq!(merged.usage_count(), Some(1));
+        assert_eq!(merged.expires_in(), Some(Duration::from_secs(60)));
+    }
+}
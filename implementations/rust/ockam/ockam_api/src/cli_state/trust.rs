@@ -61,7 +61,8 @@ impl CliState {
                 NodeManagerCredentialRetrieverOptions::None,
                 Some(authority_identifier.clone()),
                 NodeManagerCredentialRetrieverOptions::None,
-            );
+            )
+            .with_authority_route(Some(authority_multiaddr.clone()));
 
             info!(
                     "TrustOptions configured: Authority: {}. Credentials retrieved from Remote Authority: {}",
@@ -163,7 +164,9 @@ impl CliState {
             project_admin_retriever,
             Some(authority_identifier.clone()),
             account_admin_retriever,
-        );
+        )
+        .with_authority_route(Some(authority_multiaddr.clone()))
+        .with_project(project.name(), project_id);
 
         info!(
             "TrustOptions configured: Authority: {}. Credentials retrieved from project: {}",
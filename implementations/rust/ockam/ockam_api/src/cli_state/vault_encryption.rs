@@ -0,0 +1,202 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use argon2::Argon2;
+use ockam_core::compat::rand::{thread_rng, RngCore};
+use ockam_core::env::get_env;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_vault::storage::SecretsEncryption;
+
+use crate::cli_state::Result;
+use crate::CliStateError;
+
+/// Environment variable which can be used to provide the passphrase of an encrypted vault
+pub const OCKAM_VAULT_PASSWORD: &str = "OCKAM_VAULT_PASSWORD";
+
+/// Length of the random salt used to derive a vault encryption key
+const SALT_LENGTH: usize = 16;
+
+/// Value encrypted with the vault key in order to check a passphrase
+const CHECK_VALUE: &[u8] = b"ockam-vault-encryption";
+
+/// Encryption keys of the vaults which have been unlocked during this process, indexed by salt
+static UNLOCKED_VAULTS: Mutex<BTreeMap<Vec<u8>, SecretsEncryption>> = Mutex::new(BTreeMap::new());
+
+/// Last passphrase which was used to unlock a vault during this process
+static ENTERED_PASSPHRASE: Mutex<Option<String>> = Mutex::new(None);
+
+/// Provider used to ask for a vault passphrase when it is not set with OCKAM_VAULT_PASSWORD
+static PASSPHRASE_PROVIDER: Mutex<Option<Arc<dyn VaultPassphraseProvider>>> = Mutex::new(None);
+
+/// This trait is used to get the passphrase of an encrypted vault when
+/// the OCKAM_VAULT_PASSWORD environment variable is not set.
+///
+/// For example the ockam command prompts the user when the terminal is interactive.
+/// Another implementation could retrieve the passphrase from the OS keychain.
+pub trait VaultPassphraseProvider: Send + Sync + 'static {
+    /// Return the passphrase of the given vault, or None if it cannot be provided
+    fn passphrase(&self, vault_name: &str) -> Option<String>;
+}
+
+/// Set the provider used to get the passphrase of encrypted vaults
+pub fn set_vault_passphrase_provider(provider: Arc<dyn VaultPassphraseProvider>) {
+    if let Ok(mut current) = PASSPHRASE_PROVIDER.lock() {
+        *current = Some(provider)
+    }
+}
+
+/// Return the passphrase which was entered to unlock a vault during this process, if any.
+/// This passphrase can be passed to child processes so that they don't need to ask for it again.
+pub fn entered_vault_passphrase() -> Option<String> {
+    ENTERED_PASSPHRASE.lock().ok().and_then(|p| p.clone())
+}
+
+/// The parameters used to encrypt the secrets of a vault with a passphrase:
+///
+///  - the encryption key is derived from the passphrase and a random salt with argon2
+///  - a known value is encrypted with that key in order to check a passphrase
+///    before using it to read secrets
+///
+/// The passphrase and the key are never stored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VaultEncryption {
+    salt: Vec<u8>,
+    check: Vec<u8>,
+}
+
+impl VaultEncryption {
+    /// Create vault encryption parameters
+    pub fn new(salt: Vec<u8>, check: Vec<u8>) -> Self {
+        Self { salt, check }
+    }
+
+    /// Create new encryption parameters for a passphrase
+    /// and return the corresponding secrets encryption
+    pub fn create(passphrase: &str) -> Result<(Self, SecretsEncryption)> {
+        let mut salt = vec![0u8; SALT_LENGTH];
+        thread_rng().fill_bytes(&mut salt);
+        let encryption = Self::derive_encryption(passphrase, &salt)?;
+        let check = encryption.encrypt(CHECK_VALUE)?;
+        let vault_encryption = Self { salt, check };
+        vault_encryption.remember(encryption.clone());
+        Ok((vault_encryption, encryption))
+    }
+
+    /// Return the salt used to derive the encryption key
+    pub fn salt(&self) -> Vec<u8> {
+        self.salt.clone()
+    }
+
+    /// Return the value used to check a passphrase
+    pub fn check(&self) -> Vec<u8> {
+        self.check.clone()
+    }
+
+    /// Return the secrets encryption of a vault if the passphrase is correct
+    pub fn open(&self, vault_name: &str, passphrase: &str) -> Result<SecretsEncryption> {
+        let encryption = Self::derive_encryption(passphrase, &self.salt)?;
+        match encryption.decrypt(&self.check) {
+            Ok(check) if check == CHECK_VALUE => {
+                self.remember(encryption.clone());
+                Ok(encryption)
+            }
+            _ => Err(CliStateError::InvalidVaultPassphrase {
+                name: vault_name.to_string(),
+            }),
+        }
+    }
+
+    /// Return the secrets encryption of a vault. The passphrase is taken from:
+    ///
+    ///  - the vaults already unlocked by this process
+    ///  - the OCKAM_VAULT_PASSWORD environment variable
+    ///  - the registered passphrase provider
+    ///
+    /// If no passphrase can be found the vault is locked and an error is returned.
+    pub fn unlock(&self, vault_name: &str) -> Result<SecretsEncryption> {
+        if let Some(encryption) = UNLOCKED_VAULTS
+            .lock()
+            .ok()
+            .and_then(|vaults| vaults.get(&self.salt).cloned())
+        {
+            return Ok(encryption);
+        }
+
+        if let Some(passphrase) = get_env::<String>(OCKAM_VAULT_PASSWORD)? {
+            return self.open(vault_name, &passphrase);
+        }
+
+        let provider = PASSPHRASE_PROVIDER.lock().ok().and_then(|p| p.clone());
+        match provider.and_then(|p| p.passphrase(vault_name)) {
+            Some(passphrase) => {
+                let encryption = self.open(vault_name, &passphrase)?;
+                if let Ok(mut entered) = ENTERED_PASSPHRASE.lock() {
+                    *entered = Some(passphrase);
+                }
+                Ok(encryption)
+            }
+            None => Err(CliStateError::VaultLocked {
+                name: vault_name.to_string(),
+            }),
+        }
+    }
+
+    /// Keep the encryption of an unlocked vault for the rest of the process
+    fn remember(&self, encryption: SecretsEncryption) {
+        if let Ok(mut vaults) = UNLOCKED_VAULTS.lock() {
+            vaults.insert(self.salt.clone(), encryption);
+        }
+    }
+
+    fn derive_encryption(passphrase: &str, salt: &[u8]) -> Result<SecretsEncryption> {
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+            .map_err(|e| {
+                ockam_core::Error::new(
+                    Origin::Api,
+                    Kind::Invalid,
+                    format!("cannot derive a vault key from the passphrase: {e}"),
+                )
+            })?;
+        Ok(SecretsEncryption::new(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_vault_encryption() -> Result<()> {
+        let (vault_encryption, encryption) = VaultEncryption::create("passphrase")?;
+        let encrypted = encryption.encrypt(&[1; 32])?;
+
+        // the same passphrase gives the same key
+        let opened = vault_encryption.open("vault", "passphrase")?;
+        assert_eq!(opened.decrypt(&encrypted)?, vec![1; 32]);
+
+        // a wrong passphrase is rejected
+        let result = vault_encryption.open("vault", "wrong");
+        assert!(matches!(
+            result,
+            Err(CliStateError::InvalidVaultPassphrase { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_unlock_vault_encryption() -> Result<()> {
+        // a vault created in this process is already unlocked
+        let (vault_encryption, _) = VaultEncryption::create("passphrase")?;
+        assert!(vault_encryption.unlock("vault").is_ok());
+
+        // otherwise the vault is locked when no passphrase can be found
+        let other = VaultEncryption::new(vec![1; SALT_LENGTH], vault_encryption.check());
+        if std::env::var(OCKAM_VAULT_PASSWORD).is_err() {
+            let result = other.unlock("other");
+            assert!(matches!(result, Err(CliStateError::VaultLocked { .. })));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,498 @@
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+
+use ockam::identity::models::{CredentialVerifyingKey, PurposePublicKey};
+use ockam::identity::{Purpose, Vault};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_vault::storage::{SecretsRepository, SecretsSqlxDatabase};
+use ockam_vault::{SigningSecretKeyHandle, VerifyingPublicKey, X25519SecretKeyHandle};
+
+use crate::cli_state::{CliState, NamedIdentity, NamedVault, Result};
+
+/// The methods below support the inspection of the keys stored in a vault
+/// and the move of an identity, with all its keys, from one vault to another.
+///
+/// The keys referenced by an identity are:
+///
+///  - its current identity key
+///  - its secure channel purpose key, if one has been created
+///  - its credentials purpose key, if one has been created
+///
+impl CliState {
+    /// Return the keys stored in a vault, with the identity referencing them.
+    ///
+    /// For a KMS vault only the keys referenced by identities and the secure channel keys,
+    /// which are always stored locally, are returned.
+    #[instrument(skip_all, fields(vault_name = vault_name))]
+    pub async fn get_vault_keys(&self, vault_name: &str) -> Result<Vec<VaultKey>> {
+        let named_vault = self.get_named_vault(vault_name).await?;
+        let vault = named_vault.vault().await?;
+
+        let mut referenced_keys = vec![];
+        for identity in self
+            .identities_repository()
+            .get_named_identities_by_vault_name(vault_name)
+            .await?
+        {
+            referenced_keys.extend(
+                self.get_identity_keys(&identity, &vault, named_vault.is_kms())
+                    .await?,
+            );
+        }
+
+        let secrets = named_vault.secrets_repository().await?;
+        let mut keys = vec![];
+        for handle in secrets.get_signing_secret_handles().await? {
+            let key = VaultKey::signing_key(&handle, false);
+            keys.push(Self::with_reference(key, &referenced_keys));
+        }
+        for handle in secrets.get_x25519_secret_handles().await? {
+            let key = VaultKey::x25519_key(&handle);
+            keys.push(Self::with_reference(key, &referenced_keys));
+        }
+        // the keys stored in a KMS are not present in the local database
+        for key in referenced_keys {
+            if !keys.iter().any(|k| k.key_id == key.key_id) {
+                keys.push(key);
+            }
+        }
+        Ok(keys)
+    }
+
+    /// Move an identity and its keys to another vault.
+    ///
+    /// The secrets are copied to the destination vault, then the identity is associated to the
+    /// destination vault. If the identity can still sign data, the secrets are removed from the
+    /// source vault. Otherwise the move is reverted.
+    ///
+    /// Keys stored in a KMS can not be exported, so identities using a KMS vault can not be moved.
+    /// When `dry_run` is true, only the keys which would be moved are returned.
+    #[instrument(skip_all, fields(identity_name = identity_name, vault_name = vault_name))]
+    pub async fn move_identity(
+        &self,
+        identity_name: &str,
+        vault_name: &str,
+        dry_run: bool,
+    ) -> Result<IdentityMove> {
+        self.with_write_lock(async {
+            let identity = self.get_named_identity(identity_name).await?;
+            let source = self.get_named_vault(&identity.vault_name()).await?;
+            let destination = self.get_named_vault(vault_name).await?;
+
+            if source.name() == destination.name() {
+                return Err(ockam_core::Error::new(
+                    Origin::Api,
+                    Kind::Invalid,
+                    format!("the identity {identity_name} is already stored in the vault {vault_name}"),
+                ))?;
+            }
+            for vault in [&source, &destination] {
+                if vault.is_kms() {
+                    return Err(ockam_core::Error::new(
+                        Origin::Api,
+                        Kind::Unsupported,
+                        format!(
+                            "the vault {} is a KMS vault. The keys stored in a KMS can not be exported or imported",
+                            vault.name()
+                        ),
+                    ))?;
+                }
+            }
+
+            let keys = self
+                .get_identity_keys(&identity, &source.vault().await?, false)
+                .await?;
+            let result = IdentityMove {
+                identity_name: identity_name.to_string(),
+                from_vault: source.name(),
+                to_vault: destination.name(),
+                keys: keys.clone(),
+            };
+            if dry_run {
+                return Ok(result);
+            }
+
+            let source_secrets = source.secrets_repository().await?;
+            let destination_secrets = destination.secrets_repository().await?;
+            Self::copy_secrets(&keys, &source_secrets, &destination_secrets).await?;
+            self.identities_repository()
+                .set_vault_name(identity_name, vault_name)
+                .await?;
+
+            if let Err(e) = self.check_identity_can_sign(&identity, &destination).await {
+                self.identities_repository()
+                    .set_vault_name(identity_name, &source.name())
+                    .await?;
+                Self::delete_secrets(&keys, &destination_secrets).await?;
+                return Err(e);
+            }
+            Self::delete_secrets(&keys, &source_secrets).await?;
+            Ok(result)
+        })
+        .await
+    }
+}
+
+/// Private functions
+impl CliState {
+    /// Return the keys referenced by an identity
+    async fn get_identity_keys(
+        &self,
+        identity: &NamedIdentity,
+        vault: &Vault,
+        is_kms: bool,
+    ) -> Result<Vec<VaultKey>> {
+        let identifier = identity.identifier();
+        let public_key = self
+            .get_identity(&identifier)
+            .await?
+            .get_latest_public_key()?;
+        let handle = vault
+            .identity_vault
+            .get_secret_key_handle(&public_key)
+            .await?;
+        let mut keys = vec![
+            VaultKey::signing_key(&handle, is_kms).referenced_by(identity, KeyUsage::Identity)
+        ];
+
+        let identities = self.make_identities(vault.clone()).await?;
+        let verification = identities.purpose_keys().purpose_keys_verification();
+        for purpose in [Purpose::SecureChannel, Purpose::Credentials] {
+            let attestation = match self
+                .purpose_keys_repository()
+                .get_purpose_key(&identifier, purpose)
+                .await?
+            {
+                Some(attestation) => attestation,
+                None => continue,
+            };
+            let data = verification
+                .verify_purpose_key_attestation(Some(&identifier), &attestation)
+                .await?;
+            let key = match data.public_key {
+                PurposePublicKey::SecureChannelStatic(public_key) => {
+                    let handle = vault
+                        .secure_channel_vault
+                        .get_x25519_secret_key_handle(&public_key)
+                        .await?;
+                    VaultKey::x25519_key(&handle).referenced_by(identity, KeyUsage::SecureChannel)
+                }
+                PurposePublicKey::CredentialSigning(public_key) => {
+                    let public_key = match public_key {
+                        CredentialVerifyingKey::EdDSACurve25519(k) => {
+                            VerifyingPublicKey::EdDSACurve25519(k)
+                        }
+                        CredentialVerifyingKey::ECDSASHA256CurveP256(k) => {
+                            VerifyingPublicKey::ECDSASHA256CurveP256(k)
+                        }
+                    };
+                    let handle = vault
+                        .credential_vault
+                        .get_secret_key_handle(&public_key)
+                        .await?;
+                    VaultKey::signing_key(&handle, is_kms)
+                        .referenced_by(identity, KeyUsage::Credentials)
+                }
+            };
+            keys.push(key);
+        }
+        Ok(keys)
+    }
+
+    /// Copy the secrets of some keys from one vault to another
+    async fn copy_secrets(
+        keys: &[VaultKey],
+        source: &SecretsSqlxDatabase,
+        destination: &SecretsSqlxDatabase,
+    ) -> Result<()> {
+        for key in keys {
+            match &key.handle {
+                KeyHandle::Signing(handle) => {
+                    let secret = source
+                        .get_signing_secret(handle)
+                        .await?
+                        .ok_or_else(|| Self::missing_secret(key))?;
+                    destination.store_signing_secret(handle, secret).await?;
+                }
+                KeyHandle::X25519(handle) => {
+                    let secret = source
+                        .get_x25519_secret(handle)
+                        .await?
+                        .ok_or_else(|| Self::missing_secret(key))?;
+                    destination.store_x25519_secret(handle, secret).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Delete the secrets of some keys from a vault
+    async fn delete_secrets(keys: &[VaultKey], secrets: &SecretsSqlxDatabase) -> Result<()> {
+        for key in keys {
+            match &key.handle {
+                KeyHandle::Signing(handle) => {
+                    secrets.delete_signing_secret(handle).await?;
+                }
+                KeyHandle::X25519(handle) => {
+                    secrets.delete_x25519_secret(handle).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that an identity can sign data with the keys of a given vault
+    async fn check_identity_can_sign(
+        &self,
+        identity: &NamedIdentity,
+        named_vault: &NamedVault,
+    ) -> Result<()> {
+        let vault = named_vault.vault().await?;
+        let public_key = self
+            .get_identity(&identity.identifier())
+            .await?
+            .get_latest_public_key()?;
+        let handle = vault
+            .identity_vault
+            .get_secret_key_handle(&public_key)
+            .await?;
+        let data = identity.identifier().to_string();
+        let signature = vault.identity_vault.sign(&handle, data.as_bytes()).await?;
+        if vault
+            .verifying_vault
+            .verify_signature(&public_key, data.as_bytes(), &signature)
+            .await?
+        {
+            Ok(())
+        } else {
+            Err(ockam_core::Error::new(
+                Origin::Api,
+                Kind::Invalid,
+                format!(
+                    "the identity {} can not sign data with the vault {}",
+                    identity.name(),
+                    named_vault.name()
+                ),
+            ))?
+        }
+    }
+
+    fn missing_secret(key: &VaultKey) -> ockam_core::Error {
+        ockam_core::Error::new(
+            Origin::Api,
+            Kind::NotFound,
+            format!("the secret of the key {} was not found", key.key_id),
+        )
+    }
+
+    /// Set the identity and usage of a key if it is referenced by an identity
+    fn with_reference(key: VaultKey, referenced_keys: &[VaultKey]) -> VaultKey {
+        match referenced_keys.iter().find(|k| k.key_id == key.key_id) {
+            Some(referenced) => referenced.clone(),
+            None => key,
+        }
+    }
+}
+
+/// A key stored in a vault
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VaultKey {
+    key_id: String,
+    key_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    identity_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    usage: Option<KeyUsage>,
+    #[serde(skip)]
+    handle: KeyHandle,
+}
+
+impl VaultKey {
+    fn signing_key(handle: &SigningSecretKeyHandle, is_kms: bool) -> Self {
+        let value = handle.handle().value();
+        // KMS handles are key ids, software handles are hashes of the public key
+        let key_id = match (is_kms, String::from_utf8(value.clone())) {
+            (true, Ok(key_id)) => key_id,
+            _ => hex::encode(value),
+        };
+        let key_type = match handle {
+            SigningSecretKeyHandle::EdDSACurve25519(_) => "EdDSACurve25519",
+            SigningSecretKeyHandle::ECDSASHA256CurveP256(_) => "ECDSASHA256CurveP256",
+        };
+        Self {
+            key_id,
+            key_type: key_type.to_string(),
+            identity_name: None,
+            usage: None,
+            handle: KeyHandle::Signing(handle.clone()),
+        }
+    }
+
+    fn x25519_key(handle: &X25519SecretKeyHandle) -> Self {
+        Self {
+            key_id: hex::encode(handle.0.value()),
+            key_type: "X25519".to_string(),
+            identity_name: None,
+            usage: None,
+            handle: KeyHandle::X25519(handle.clone()),
+        }
+    }
+
+    fn referenced_by(self, identity: &NamedIdentity, usage: KeyUsage) -> Self {
+        Self {
+            identity_name: Some(identity.name()),
+            usage: Some(usage),
+            ..self
+        }
+    }
+
+    /// Return the key id
+    pub fn key_id(&self) -> String {
+        self.key_id.clone()
+    }
+
+    /// Return the key type
+    pub fn key_type(&self) -> String {
+        self.key_type.clone()
+    }
+
+    /// Return the name of the identity referencing this key, if any
+    pub fn identity_name(&self) -> Option<String> {
+        self.identity_name.clone()
+    }
+
+    /// Return how the key is used by its identity, if any
+    pub fn usage(&self) -> Option<KeyUsage> {
+        self.usage.clone()
+    }
+}
+
+/// Handle to the secret of a vault key
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum KeyHandle {
+    Signing(SigningSecretKeyHandle),
+    X25519(X25519SecretKeyHandle),
+}
+
+/// Usage of a key by an identity
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyUsage {
+    /// The key is the identity key
+    Identity,
+    /// The key is used to create secure channels
+    SecureChannel,
+    /// The key is used to issue credentials
+    Credentials,
+}
+
+impl Display for KeyUsage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeyUsage::Identity => f.write_str("identity key"),
+            KeyUsage::SecureChannel => f.write_str("secure channel key"),
+            KeyUsage::Credentials => f.write_str("credentials key"),
+        }
+    }
+}
+
+/// Result of the move of an identity to another vault
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IdentityMove {
+    identity_name: String,
+    from_vault: String,
+    to_vault: String,
+    keys: Vec<VaultKey>,
+}
+
+impl IdentityMove {
+    /// Return the name of the moved identity
+    pub fn identity_name(&self) -> String {
+        self.identity_name.clone()
+    }
+
+    /// Return the name of the vault the identity was moved from
+    pub fn from_vault(&self) -> String {
+        self.from_vault.clone()
+    }
+
+    /// Return the name of the vault the identity was moved to
+    pub fn to_vault(&self) -> String {
+        self.to_vault.clone()
+    }
+
+    /// Return the moved keys
+    pub fn keys(&self) -> Vec<VaultKey> {
+        self.keys.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_get_vault_keys() -> Result<()> {
+        let cli = CliState::test().await?;
+        let vault = cli.get_or_create_named_vault("vault").await?;
+        let identity = cli
+            .create_identity_with_name_and_vault("identity", "vault")
+            .await?;
+
+        // create a secure channel purpose key
+        let identities = cli.make_identities(vault.vault().await?).await?;
+        identities
+            .purpose_keys()
+            .purpose_keys_creation()
+            .get_or_create_secure_channel_purpose_key(&identity.identifier())
+            .await?;
+
+        let keys = cli.get_vault_keys("vault").await?;
+        assert_eq!(keys.len(), 2);
+        assert!(keys
+            .iter()
+            .all(|k| k.identity_name() == Some("identity".into())));
+        assert!(keys
+            .iter()
+            .any(|k| k.usage() == Some(KeyUsage::Identity) && k.key_type() != "X25519"));
+        assert!(keys
+            .iter()
+            .any(|k| k.usage() == Some(KeyUsage::SecureChannel) && k.key_type() == "X25519"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_move_identity() -> Result<()> {
+        let cli = CliState::test().await?;
+        cli.get_or_create_named_vault("vault1").await?;
+        cli.get_or_create_named_vault("vault2").await?;
+        let identity = cli
+            .create_identity_with_name_and_vault("identity", "vault1")
+            .await?;
+        let keys = cli.get_vault_keys("vault1").await?;
+
+        // a dry run does not change anything
+        let result = cli.move_identity("identity", "vault2", true).await?;
+        assert_eq!(result.keys(), keys);
+        assert_eq!(cli.get_named_identity("identity").await?, identity);
+        assert!(cli.get_vault_keys("vault2").await?.is_empty());
+
+        // the identity keys are moved to the destination vault
+        let result = cli.move_identity("identity", "vault2", false).await?;
+        assert_eq!(result.from_vault(), "vault1");
+        assert_eq!(result.to_vault(), "vault2");
+        assert_eq!(
+            cli.get_named_identity("identity").await?.vault_name(),
+            "vault2"
+        );
+        assert_eq!(cli.get_vault_keys("vault2").await?, keys);
+        assert!(cli.get_vault_keys("vault1").await?.is_empty());
+
+        // the identity can't be moved to the vault it is already using
+        assert!(cli
+            .move_identity("identity", "vault2", false)
+            .await
+            .is_err());
+        Ok(())
+    }
+}
@@ -7,9 +7,14 @@ use std::sync::Arc;
 use ockam::identity::{Identities, Vault};
 use ockam_core::errcode::{Kind, Origin};
 use ockam_node::database::SqlxDatabase;
-use ockam_vault_aws::AwsSigningVault;
-
-use crate::cli_state::{random_name, CliState, Result};
+use ockam_vault::storage::SecretsSqlxDatabase;
+use ockam_vault::VaultForSigning;
+#[cfg(feature = "aws-kms")]
+use ockam_vault::{HandleToSecret, SigningSecretKeyHandle};
+#[cfg(feature = "aws-kms")]
+use ockam_vault_aws::{AwsKmsConfig, AwsSigningVault, InitialKeysDiscovery};
+
+use crate::cli_state::{random_name, CliState, Result, VaultEncryption};
 use crate::CliStateError;
 
 static DEFAULT_VAULT_NAME: &str = "default";
@@ -31,25 +36,79 @@ impl CliState {
         vault_name: &Option<String>,
         path: &Option<PathBuf>,
     ) -> Result<NamedVault> {
-        self.create_a_vault(vault_name, path, false).await
+        self.create_a_vault(vault_name, path, false, None).await
     }
 
     /// Create a KMS vault with a given name
     /// If the path is not specified then:
     ///   - if this is the first vault then secrets are persisted in the main database
     ///   - if this is a new vault then secrets are persisted in $OCKAM_HOME/vault_name
+    ///
+    /// If a key id is specified, that KMS key is used to create identities with this vault.
+    /// Otherwise, the keys accessible with the current credentials are listed when the vault is opened.
     #[instrument(skip_all, fields(vault_name = vault_name.clone(), path = path.clone().map_or("n/a".to_string(), |p| p.to_string_lossy().to_string())))]
     pub async fn create_kms_vault(
         &self,
         vault_name: &Option<String>,
         path: &Option<PathBuf>,
+        kms_key_id: &Option<String>,
+    ) -> Result<NamedVault> {
+        self.create_a_vault(vault_name, path, true, kms_key_id.as_deref())
+            .await
+    }
+
+    /// Create a vault whose secrets are encrypted with a key derived from a passphrase
+    #[instrument(skip_all, fields(vault_name = vault_name.clone(), path = path.clone().map_or("n/a".to_string(), |p| p.to_string_lossy().to_string())))]
+    pub async fn create_encrypted_vault(
+        &self,
+        vault_name: &Option<String>,
+        path: &Option<PathBuf>,
+        passphrase: &str,
     ) -> Result<NamedVault> {
-        self.create_a_vault(vault_name, path, true).await
+        self.with_write_lock(async {
+            let named_vault = self.create_a_vault(vault_name, path, false, None).await?;
+            let (encryption, _) = VaultEncryption::create(passphrase)?;
+            self.vaults_repository()
+                .set_vault_encryption(&named_vault.name(), &encryption)
+                .await?;
+            Ok(named_vault.with_encryption(Some(encryption)))
+        })
+        .await
+    }
+
+    /// Encrypt the secrets of an existing vault with a key derived from a passphrase
+    /// The vault must not be a KMS vault and must not be already encrypted
+    #[instrument(skip_all, fields(vault_name = vault_name))]
+    pub async fn encrypt_vault(&self, vault_name: &str, passphrase: &str) -> Result<NamedVault> {
+        self.with_write_lock(async {
+            let named_vault = self.get_named_vault(vault_name).await?;
+            if named_vault.is_kms() {
+                return Err(CliStateError::InvalidOperation(format!(
+                    "The vault {vault_name} is a KMS vault. Its keys are not stored locally and cannot be encrypted"
+                )));
+            }
+            if named_vault.is_encrypted() {
+                return Err(CliStateError::InvalidOperation(format!(
+                    "The vault {vault_name} is already encrypted"
+                )));
+            }
+
+            let (encryption, secrets_encryption) = VaultEncryption::create(passphrase)?;
+            SecretsSqlxDatabase::new(named_vault.database().await?)
+                .encrypt_secrets(&secrets_encryption)
+                .await?;
+            self.vaults_repository()
+                .set_vault_encryption(vault_name, &encryption)
+                .await?;
+            Ok(named_vault.with_encryption(Some(encryption)))
+        })
+        .await
     }
 
     /// Delete an existing vault
     #[instrument(skip_all, fields(vault_name = vault_name))]
     pub async fn delete_named_vault(&self, vault_name: &str) -> Result<()> {
+        self.with_write_lock(async {
         // first check that no identity is using the vault
         let identities_repository = self.identities_repository();
         let identities_using_the_vault = identities_repository
@@ -87,6 +146,8 @@ impl CliState {
             }
         }
         Ok(())
+        })
+        .await
     }
 
     /// Delete all named identities
@@ -140,26 +201,29 @@ impl CliState {
     /// or a path using the vault name
     #[instrument(skip_all, fields(vault_name = vault_name))]
     pub async fn get_or_create_named_vault(&self, vault_name: &str) -> Result<NamedVault> {
-        let vaults_repository = self.vaults_repository();
-        let is_default = vault_name == DEFAULT_VAULT_NAME;
+        self.with_write_lock(async {
+            let vaults_repository = self.vaults_repository();
+            let is_default = vault_name == DEFAULT_VAULT_NAME;
 
-        if let Ok(Some(existing_vault)) = vaults_repository.get_named_vault(vault_name).await {
-            return Ok(existing_vault);
-        }
+            if let Ok(Some(existing_vault)) = vaults_repository.get_named_vault(vault_name).await {
+                return Ok(existing_vault);
+            }
 
-        self.notify("We need a Vault to store Identity secrets.".to_string());
-        self.notify("There is no default Vault on this machine, creating one...".to_string());
-        let named_vault = self
-            .create_a_vault(&Some(vault_name.to_string()), &None, false)
-            .await?;
-        self.notify("Created a new Vault on your disk.".to_string());
-        if is_default {
-            self.notify(format!(
-                "Marked this new vault as your default Vault, {}.\n",
-                "on this machine".dim()
-            ));
-        }
-        Ok(named_vault)
+            self.notify("We need a Vault to store Identity secrets.".to_string());
+            self.notify("There is no default Vault on this machine, creating one...".to_string());
+            let named_vault = self
+                .create_a_vault(&Some(vault_name.to_string()), &None, false, None)
+                .await?;
+            self.notify("Created a new Vault on your disk.".to_string());
+            if is_default {
+                self.notify(format!(
+                    "Marked this new vault as your default Vault, {}.\n",
+                    "on this machine".dim()
+                ));
+            }
+            Ok(named_vault)
+        })
+        .await
     }
 
     /// Return the existing vault if there is only one
@@ -167,19 +231,22 @@ impl CliState {
     /// If there are more than one vaults, return an error
     #[instrument(skip_all)]
     pub async fn get_or_create_default_named_vault(&self) -> Result<NamedVault> {
-        let vaults = self.vaults_repository().get_named_vaults().await?;
-        match &vaults[..] {
-            [] => self.get_or_create_named_vault(DEFAULT_VAULT_NAME).await,
-            [vault] => Ok(vault.clone()),
-            _ => Err(ockam_core::Error::new(
-                Origin::Api,
-                Kind::Invalid,
-                format!(
-                    "There are {} vaults, please specify which vault should be used",
-                    vaults.len()
-                ),
-            ))?,
-        }
+        self.with_write_lock(async {
+            let vaults = self.vaults_repository().get_named_vaults().await?;
+            match &vaults[..] {
+                [] => self.get_or_create_named_vault(DEFAULT_VAULT_NAME).await,
+                [vault] => Ok(vault.clone()),
+                _ => Err(ockam_core::Error::new(
+                    Origin::Api,
+                    Kind::Invalid,
+                    format!(
+                        "There are {} vaults, please specify which vault should be used",
+                        vaults.len()
+                    ),
+                ))?,
+            }
+        })
+        .await
     }
 
     /// Return either the default vault or a vault with the given name
@@ -236,58 +303,62 @@ impl CliState {
         vault_name: &Option<String>,
         path: &Option<PathBuf>,
         is_kms: bool,
+        kms_key_id: Option<&str>,
     ) -> Result<NamedVault> {
-        let vaults_repository = self.vaults_repository();
-
-        // determine the vault name to use if not given by the user
-        let vault_name = match vault_name {
-            Some(vault_name) => vault_name.clone(),
-            None => self.make_vault_name().await?,
-        };
-
-        // verify that a vault with that name does not exist
-        if vaults_repository
-            .get_named_vault(&vault_name)
-            .await?
-            .is_some()
-        {
-            return Err(CliStateError::AlreadyExists {
-                resource: "vault".to_string(),
-                name: vault_name.to_string(),
-            });
-        }
-
-        // determine the vault path
-        // if the vault is the first vault we store the data directly in the main database
-        // otherwise we open a new file with the vault name
-        let path = match path {
-            Some(path) => path.clone(),
-            None => self.make_vault_path(&vault_name).await?,
-        };
-
-        // check if the new file can be created
-        let path_taken = self.get_named_vault_with_path(&path).await?.is_some();
-        if path_taken {
-            return Err(CliStateError::AlreadyExists {
-                resource: "vault path".to_string(),
-                name: format!("{path:?}"),
-            });
-        } else {
-            // create a new file if we need to store the vault data outside of the main database
-            if path != self.database_path() {
-                // similar to File::create_new which is unstable for now
-                OpenOptions::new()
-                    .read(true)
-                    .write(true)
-                    .create_new(true)
-                    .open(&path)?;
+        self.with_write_lock(async {
+            let vaults_repository = self.vaults_repository();
+
+            // determine the vault name to use if not given by the user
+            let vault_name = match vault_name {
+                Some(vault_name) => vault_name.clone(),
+                None => self.make_vault_name().await?,
+            };
+
+            // verify that a vault with that name does not exist
+            if vaults_repository
+                .get_named_vault(&vault_name)
+                .await?
+                .is_some()
+            {
+                return Err(CliStateError::AlreadyExists {
+                    resource: "vault".to_string(),
+                    name: vault_name.to_string(),
+                });
             }
-        };
 
-        // store the vault metadata
-        Ok(vaults_repository
-            .store_vault(&vault_name, &path, is_kms)
-            .await?)
+            // determine the vault path
+            // if the vault is the first vault we store the data directly in the main database
+            // otherwise we open a new file with the vault name
+            let path = match path {
+                Some(path) => path.clone(),
+                None => self.make_vault_path(&vault_name).await?,
+            };
+
+            // check if the new file can be created
+            let path_taken = self.get_named_vault_with_path(&path).await?.is_some();
+            if path_taken {
+                return Err(CliStateError::AlreadyExists {
+                    resource: "vault path".to_string(),
+                    name: format!("{path:?}"),
+                });
+            } else {
+                // create a new file if we need to store the vault data outside of the main database
+                if path != self.database_path() {
+                    // similar to File::create_new which is unstable for now
+                    OpenOptions::new()
+                        .read(true)
+                        .write(true)
+                        .create_new(true)
+                        .open(&path)?;
+                }
+            };
+
+            // store the vault metadata
+            Ok(vaults_repository
+                .store_vault(&vault_name, &path, is_kms, kms_key_id)
+                .await?)
+        })
+        .await
     }
 
     /// Return the vault name to use for a vault:
@@ -337,6 +408,10 @@ pub struct NamedVault {
     name: String,
     path: PathBuf,
     is_kms: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    kms_key_id: Option<String>,
+    #[serde(skip)]
+    encryption: Option<VaultEncryption>,
 }
 
 impl NamedVault {
@@ -346,9 +421,24 @@ impl NamedVault {
             name: name.to_string(),
             path,
             is_kms,
+            kms_key_id: None,
+            encryption: None,
         }
     }
 
+    /// Set the KMS key used by a KMS vault
+    pub fn with_kms_key_id(self, kms_key_id: Option<&str>) -> Self {
+        Self {
+            kms_key_id: kms_key_id.map(|k| k.to_string()),
+            ..self
+        }
+    }
+
+    /// Set the parameters used to encrypt the vault secrets
+    pub fn with_encryption(self, encryption: Option<VaultEncryption>) -> Self {
+        Self { encryption, ..self }
+    }
+
     /// Return the vault name
     pub fn name(&self) -> String {
         self.name.clone()
@@ -369,19 +459,72 @@ impl NamedVault {
         self.is_kms
     }
 
+    /// Return the id of the KMS key used by this vault, if one was specified
+    pub fn kms_key_id(&self) -> Option<String> {
+        self.kms_key_id.clone()
+    }
+
+    /// Return the parameters used to encrypt the vault secrets, if the vault is encrypted
+    pub fn encryption(&self) -> Option<VaultEncryption> {
+        self.encryption.clone()
+    }
+
+    /// Return true if the secrets of this vault are encrypted with a passphrase
+    pub fn is_encrypted(&self) -> bool {
+        self.encryption.is_some()
+    }
+
+    /// Return a vault where the signing operations are delegated to the KMS if this vault
+    /// is a KMS vault. The secure channel keys are always stored locally.
     pub async fn vault(&self) -> Result<Vault> {
-        let mut vault = Vault::create_with_database(self.database().await?);
+        let mut vault =
+            Vault::create_with_secrets_repository(Arc::new(self.secrets_repository().await?));
         if self.is_kms {
-            let aws_vault = Arc::new(AwsSigningVault::create().await?);
-            vault.identity_vault = aws_vault.clone();
-            vault.credential_vault = aws_vault;
+            let kms_vault = self.kms_vault().await?;
+            vault.identity_vault = kms_vault.clone();
+            vault.credential_vault = kms_vault;
             Ok(vault)
         } else {
             Ok(vault)
         }
     }
 
-    async fn database(&self) -> Result<SqlxDatabase> {
+    #[cfg(feature = "aws-kms")]
+    async fn kms_vault(&self) -> Result<Arc<dyn VaultForSigning>> {
+        let mut config = AwsKmsConfig::default().await?;
+        // only use the configured key instead of listing all the keys of the KMS
+        if let Some(kms_key_id) = &self.kms_key_id {
+            let handle = SigningSecretKeyHandle::ECDSASHA256CurveP256(HandleToSecret::new(
+                kms_key_id.as_bytes().to_vec(),
+            ));
+            config = config.with_initial_keys_discovery(InitialKeysDiscovery::Keys(vec![handle]));
+        }
+        Ok(Arc::new(AwsSigningVault::create_with_config(config).await?))
+    }
+
+    #[cfg(not(feature = "aws-kms"))]
+    async fn kms_vault(&self) -> Result<Arc<dyn VaultForSigning>> {
+        Err(ockam_core::Error::new(
+            Origin::Api,
+            Kind::Unsupported,
+            format!(
+                "the vault {} is a KMS vault but this build does not support KMS vaults. Enable the 'aws-kms' feature",
+                self.name
+            ),
+        ))?
+    }
+
+    /// Return the repository for the vault secrets.
+    /// If the vault is encrypted, it must be unlocked first with its passphrase
+    pub(crate) async fn secrets_repository(&self) -> Result<SecretsSqlxDatabase> {
+        let secrets = SecretsSqlxDatabase::new(self.database().await?);
+        match &self.encryption {
+            Some(encryption) => Ok(secrets.with_encryption(encryption.unlock(&self.name)?)),
+            None => Ok(secrets),
+        }
+    }
+
+    pub(crate) async fn database(&self) -> Result<SqlxDatabase> {
         // FIXME: We should really have one instance of the SqlxDatabase per process
         Ok(SqlxDatabase::create(self.path.as_path()).await?)
     }
@@ -398,6 +541,12 @@ impl Display for NamedVault {
                 false => "OCKAM",
             }
         )?;
+        if let Some(kms_key_id) = &self.kms_key_id {
+            writeln!(f, "Key: {kms_key_id}")?;
+        }
+        if self.is_encrypted() {
+            writeln!(f, "Encrypted: true")?;
+        }
         Ok(())
     }
 }
@@ -407,6 +556,7 @@ mod tests {
     use super::*;
     use ockam::identity::models::{PurposeKeyAttestation, PurposeKeyAttestationSignature};
     use ockam::identity::Purpose;
+    use ockam_vault::storage::SecretsRepository;
     use ockam_vault::{
         ECDSASHA256CurveP256SecretKey, ECDSASHA256CurveP256Signature, HandleToSecret,
         SigningSecret, SigningSecretKeyHandle, X25519SecretKey, X25519SecretKeyHandle,
@@ -663,4 +813,56 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_encrypt_vault() -> Result<()> {
+        let cli = CliState::test().await?;
+
+        // create an identity with a plain text vault
+        let vault = cli.create_named_vault(&None, &None).await?;
+        cli.create_identity_with_name("name").await?;
+        let handles = cli
+            .secrets_repository()
+            .get_signing_secret_handles()
+            .await?;
+        assert_eq!(handles.len(), 1);
+
+        // encrypt the vault
+        let encrypted = cli.encrypt_vault(&vault.name(), "passphrase").await?;
+        assert!(encrypted.is_encrypted());
+        assert_eq!(cli.get_named_vault(&vault.name()).await?, encrypted);
+
+        // the secrets can not be read without the passphrase anymore
+        let result = cli
+            .secrets_repository()
+            .get_signing_secret(&handles[0])
+            .await;
+        assert!(result.is_err());
+
+        // the identity can still use its key with the unlocked vault
+        let secrets = encrypted.secrets_repository().await?;
+        assert!(secrets.get_signing_secret(&handles[0]).await?.is_some());
+        encrypted
+            .vault()
+            .await?
+            .identity_vault
+            .sign(&handles[0], b"data")
+            .await?;
+
+        // a wrong passphrase is rejected
+        let result = encrypted.encryption().unwrap().open(&vault.name(), "wrong");
+        assert!(result.is_err());
+
+        // the vault can not be encrypted twice
+        let result = cli.encrypt_vault(&vault.name(), "passphrase").await;
+        assert!(result.is_err());
+
+        // a new vault can be created encrypted
+        let vault2 = cli
+            .create_encrypted_vault(&Some("vault2".to_string()), &None, "passphrase2")
+            .await?;
+        assert!(cli.get_named_vault("vault2").await?.is_encrypted());
+        assert!(vault2.vault().await.is_ok());
+        Ok(())
+    }
 }
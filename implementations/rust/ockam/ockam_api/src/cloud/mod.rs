@@ -5,7 +5,9 @@ pub mod email_address;
 pub mod enroll;
 pub mod lease_manager;
 pub mod operation;
+pub mod pagination;
 pub mod project;
+pub mod relay;
 pub mod secure_clients;
 pub mod share;
 pub mod space;
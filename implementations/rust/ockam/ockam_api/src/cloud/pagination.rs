@@ -0,0 +1,91 @@
+use serde::Serialize;
+
+/// Page of a list of resources, like the spaces or the projects of a user.
+///
+/// The Controller returns the complete lists of spaces and projects, so that they can be
+/// stored locally, and the pages are extracted from those lists
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Pagination {
+    /// Maximum number of items in a page. All the items are returned if not set
+    limit: Option<usize>,
+    /// Number of the page, starting at 1
+    page: usize,
+}
+
+impl Pagination {
+    pub fn new(limit: Option<usize>, page: usize) -> Self {
+        Self {
+            limit,
+            page: page.max(1),
+        }
+    }
+
+    /// Return true if the items are split into pages
+    pub fn is_paginated(&self) -> bool {
+        self.limit.is_some()
+    }
+
+    /// Return the items of the current page, with the information needed to get the next pages
+    pub fn paginate<T>(&self, items: Vec<T>) -> Page<T> {
+        let total = items.len();
+        let items: Vec<T> = match self.limit {
+            Some(limit) => items
+                .into_iter()
+                .skip((self.page - 1).saturating_mul(limit))
+                .take(limit)
+                .collect(),
+            None => items,
+        };
+        let has_more = match self.limit {
+            Some(limit) => self.page.saturating_mul(limit) < total,
+            None => false,
+        };
+        Page {
+            items,
+            page: self.page,
+            limit: self.limit,
+            total,
+            has_more,
+        }
+    }
+}
+
+/// Items of a page, with the pagination metadata
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub page: usize,
+    pub limit: Option<usize>,
+    /// Total number of items, in all the pages
+    pub total: usize,
+    /// True if there are items after this page
+    pub has_more: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paginate() {
+        let items: Vec<u32> = (1..=5).collect();
+
+        let page = Pagination::new(Some(2), 1).paginate(items.clone());
+        assert_eq!(page.items, vec![1, 2]);
+        assert_eq!(page.total, 5);
+        assert!(page.has_more);
+
+        let page = Pagination::new(Some(2), 3).paginate(items.clone());
+        assert_eq!(page.items, vec![5]);
+        assert!(!page.has_more);
+
+        let page = Pagination::new(Some(2), 4).paginate(items.clone());
+        assert!(page.items.is_empty());
+        assert!(!page.has_more);
+
+        // all the items are returned without a limit
+        let page = Pagination::new(None, 1).paginate(items.clone());
+        assert_eq!(page.items, items);
+        assert!(!page.has_more);
+    }
+}
@@ -15,6 +15,10 @@ pub trait ProjectsOrchestratorApi {
 
     async fn get_project(&self, ctx: &Context, project_id: &str) -> miette::Result<Project>;
 
+    /// Retrieve the project from the Controller and update its local copy,
+    /// even if that copy is still recent
+    async fn refresh_project(&self, ctx: &Context, project_id: &str) -> miette::Result<Project>;
+
     async fn get_project_by_name(
         &self,
         ctx: &Context,
@@ -0,0 +1,186 @@
+use minicbor::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+
+use ockam::identity::{Identifier, TimestampInSeconds};
+use ockam_core::api::Request;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{async_trait, Error, Result};
+use ockam_node::Context;
+
+use crate::cloud::{HasSecureClient, ProjectNodeClient};
+
+const TARGET: &str = "ockam_api::cloud::relay";
+const API_SERVICE: &str = "relays";
+
+/// Registration of a static relay on a project node
+#[derive(Encode, Decode, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct RelayRegistration {
+    #[n(1)] pub relay_name: String,
+    /// Identifier of the node which registered the relay
+    #[n(2)] pub identifier: Identifier,
+    /// Time of the registration, in seconds since the UNIX epoch
+    #[n(3)] pub registered_at: u64,
+}
+
+impl RelayRegistration {
+    pub fn new(relay_name: impl Into<String>, identifier: Identifier, registered_at: u64) -> Self {
+        Self {
+            relay_name: relay_name.into(),
+            identifier,
+            registered_at,
+        }
+    }
+
+    pub fn registered_at(&self) -> TimestampInSeconds {
+        TimestampInSeconds(self.registered_at)
+    }
+
+    /// Check that a relay can be registered under the same name by `identifier`.
+    ///
+    /// Registering a relay under a name which is already used by another node
+    /// takes its traffic over, which is only allowed when `force` is set.
+    /// If the existing registrations could not be retrieved, the relay is only
+    /// registered when `force` is set.
+    pub fn check_collision(
+        existing: &Result<Option<RelayRegistration>>,
+        identifier: &Identifier,
+        force: bool,
+    ) -> Result<()> {
+        if force {
+            return Ok(());
+        }
+        match existing {
+            Ok(Some(existing)) if &existing.identifier != identifier => {
+                Err(existing.collision_error())
+            }
+            Ok(_) => Ok(()),
+            Err(err) => Err(Error::new(
+                Origin::Api,
+                err.code().kind,
+                format!(
+                    "The relays registered in the project could not be retrieved to check that \
+                    the relay name is not already used: {err}. Use --force to create the relay anyway"
+                ),
+            )),
+        }
+    }
+
+    /// Check that the relay registered by `identifier` was not taken over by
+    /// another node while it was being created
+    pub fn check_ownership(
+        registered: Option<&RelayRegistration>,
+        identifier: &Identifier,
+    ) -> Result<()> {
+        match registered {
+            Some(registered) if &registered.identifier != identifier => {
+                Err(registered.collision_error())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn collision_error(&self) -> Error {
+        Error::new(
+            Origin::Api,
+            Kind::AlreadyExists,
+            format!(
+                "The relay '{}' is already registered in the project by {} since {}. \
+                Use --force to take it over",
+                self.relay_name,
+                self.identifier,
+                chrono::DateTime::from_timestamp(self.registered_at as i64, 0)
+                    .map(|t| t.to_rfc3339())
+                    .unwrap_or_else(|| self.registered_at.to_string()),
+            ),
+        )
+    }
+}
+
+#[async_trait]
+pub trait ProjectRelays {
+    /// Return the current registration of a relay in the project, if any
+    async fn get_relay_registration(
+        &self,
+        ctx: &Context,
+        relay_name: &str,
+    ) -> Result<Option<RelayRegistration>>;
+}
+
+#[async_trait]
+impl ProjectRelays for ProjectNodeClient {
+    async fn get_relay_registration(
+        &self,
+        ctx: &Context,
+        relay_name: &str,
+    ) -> Result<Option<RelayRegistration>> {
+        trace!(target: TARGET, relay_name, "getting relay registration");
+        self.get_secure_client()
+            .ask(ctx, API_SERVICE, Request::get(format!("/{relay_name}")))
+            .await?
+            .found()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn identifier(byte: u8) -> Identifier {
+        Identifier::try_from(format!("I{}", hex::encode([byte; 32])).as_str()).unwrap()
+    }
+
+    #[test]
+    fn relay_collisions_are_rejected_unless_forced() {
+        let alice = identifier(1);
+        let bob = identifier(2);
+        let registration = RelayRegistration::new("forward_to_db", alice.clone(), 1_700_000_000);
+
+        let registered = Ok(Some(registration));
+
+        // no registration yet, or registered by ourselves
+        assert!(RelayRegistration::check_collision(&Ok(None), &bob, false).is_ok());
+        assert!(RelayRegistration::check_collision(&registered, &alice, false).is_ok());
+
+        // registered by another node
+        let err = RelayRegistration::check_collision(&registered, &bob, false)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("forward_to_db"));
+        assert!(err.contains(&alice.to_string()));
+        assert!(err.contains("2023-11-14T22:13:20"));
+
+        // taken over on purpose
+        assert!(RelayRegistration::check_collision(&registered, &bob, true).is_ok());
+    }
+
+    #[test]
+    fn relays_are_not_registered_if_the_registrations_cannot_be_checked() {
+        let alice = identifier(1);
+        let unavailable = Err(Error::new(
+            Origin::Api,
+            Kind::Timeout,
+            "the project node did not respond",
+        ));
+
+        let err = RelayRegistration::check_collision(&unavailable, &alice, false)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("the project node did not respond"));
+        assert!(err.contains("--force"));
+        assert!(RelayRegistration::check_collision(&unavailable, &alice, true).is_ok());
+    }
+
+    #[test]
+    fn relay_taken_over_during_creation_is_detected() {
+        let alice = identifier(1);
+        let bob = identifier(2);
+
+        // the relay was registered by bob between the check and the creation
+        let registration = RelayRegistration::new("forward_to_db", bob.clone(), 1_700_000_000);
+        assert!(RelayRegistration::check_ownership(Some(&registration), &alice).is_err());
+        assert!(RelayRegistration::check_ownership(Some(&registration), &bob).is_ok());
+        assert!(RelayRegistration::check_ownership(None, &alice).is_ok());
+    }
+}
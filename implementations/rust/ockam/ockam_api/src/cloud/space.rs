@@ -1,6 +1,6 @@
 use miette::IntoDiagnostic;
 use minicbor::{Decode, Encode};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use ockam_core::api::Request;
 use ockam_core::async_trait;
@@ -12,7 +12,7 @@ use crate::nodes::InMemoryNode;
 
 const TARGET: &str = "ockam_api::cloud::space";
 
-#[derive(Encode, Decode, Serialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Encode, Decode, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[rustfmt::skip]
 #[cbor(map)]
 pub struct Space {
@@ -0,0 +1,17 @@
+use ockam::{Any, Context, Result, Routed, Worker};
+
+/// Worker dropping all the messages it receives.
+/// It can be used as the end of a route, to measure its throughput
+pub struct Discard;
+
+#[ockam::worker]
+impl Worker for Discard {
+    type Context = Context;
+    type Message = Any;
+
+    #[instrument(skip_all, name = "Discard::handle_message")]
+    async fn handle_message(&mut self, _ctx: &mut Context, msg: Routed<Any>) -> Result<()> {
+        trace!(src = %msg.src_addr(), "discarding a message");
+        Ok(())
+    }
+}
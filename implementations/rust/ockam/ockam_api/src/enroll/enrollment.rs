@@ -1,3 +1,4 @@
+use crate::authenticator::credential_issuer::types::IssueCredential;
 use crate::authenticator::one_time_code::OneTimeCode;
 use crate::cloud::enroll::auth0::{AuthenticateOidcToken, OidcToken};
 use crate::cloud::HasSecureClient;
@@ -8,6 +9,7 @@ use ockam::identity::SecureClient;
 use ockam_core::api::{Reply, Request, Status};
 use ockam_core::async_trait;
 use ockam_node::Context;
+use std::time::Duration;
 
 const TARGET: &str = "ockam_api::cloud::enroll";
 
@@ -38,7 +40,14 @@ pub trait Enrollment {
         token: &OneTimeCode,
     ) -> miette::Result<EnrollStatus>;
 
-    async fn issue_credential(&self, ctx: &Context) -> miette::Result<CredentialAndPurposeKey>;
+    /// Request a credential from the authority.
+    /// If a time-to-live is given, the credential expires earlier than the default
+    /// expiration configured on the authority. The time-to-live may be capped by the authority
+    async fn issue_credential(
+        &self,
+        ctx: &Context,
+        ttl: Option<Duration>,
+    ) -> miette::Result<CredentialAndPurposeKey>;
 }
 
 #[async_trait]
@@ -71,8 +80,12 @@ impl<T: HasSecureClient + Send + Sync> Enrollment for T {
         self.get_secure_client().present_token(ctx, token).await
     }
 
-    async fn issue_credential(&self, ctx: &Context) -> miette::Result<CredentialAndPurposeKey> {
-        self.get_secure_client().issue_credential(ctx).await
+    async fn issue_credential(
+        &self,
+        ctx: &Context,
+        ttl: Option<Duration>,
+    ) -> miette::Result<CredentialAndPurposeKey> {
+        self.get_secure_client().issue_credential(ctx, ttl).await
     }
 }
 
@@ -146,8 +159,12 @@ impl Enrollment for SecureClient {
     }
 
     #[instrument(skip_all)]
-    async fn issue_credential(&self, ctx: &Context) -> miette::Result<CredentialAndPurposeKey> {
-        let req = Request::post("/");
+    async fn issue_credential(
+        &self,
+        ctx: &Context,
+        ttl: Option<Duration>,
+    ) -> miette::Result<CredentialAndPurposeKey> {
+        let req = Request::post("/").body(IssueCredential::new(ttl));
         trace!(target: TARGET, "getting a credential");
         self.ask(ctx, DefaultAddress::CREDENTIAL_ISSUER, req)
             .await
@@ -0,0 +1,544 @@
+use std::fmt::{Display, Formatter};
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::time::Duration;
+
+use bytes::{BufMut, BytesMut};
+use kafka_protocol::messages::{
+    ApiKey, MetadataRequest, MetadataResponse, RequestHeader, ResponseHeader, SaslHandshakeRequest,
+    SaslHandshakeResponse,
+};
+use kafka_protocol::protocol::{Builder, Decodable, Encodable, StrBytes};
+use minicbor::{Decode, Encode};
+use serde::Serialize;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+
+/// Maximum duration of each step of the pre-flight validation
+const PREFLIGHT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Versions of the requests sent during the pre-flight validation.
+/// They are supported by all the Kafka brokers since 0.10
+const METADATA_VERSION: i16 = 1;
+const SASL_HANDSHAKE_VERSION: i16 = 1;
+
+/// Maximum size accepted for a response during the pre-flight validation
+const MAX_RESPONSE_SIZE: usize = 10 * 1024 * 1024;
+
+/// Authentication mode expected by the Kafka brokers
+#[derive(Copy, Clone, Debug, Decode, Encode, Serialize, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+#[serde(rename_all = "kebab-case")]
+pub enum BrokerAuth {
+    /// The brokers accept unauthenticated plain text connections
+    #[n(0)] None,
+    /// The brokers require SASL/PLAIN authentication
+    #[n(1)] SaslPlain,
+    /// The brokers require SASL/SCRAM authentication
+    #[n(2)] SaslScram,
+    /// The brokers require TLS connections, possibly with SASL authentication
+    #[n(3)] Tls,
+}
+
+impl Display for BrokerAuth {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            BrokerAuth::None => "none",
+            BrokerAuth::SaslPlain => "sasl-plain",
+            BrokerAuth::SaslScram => "sasl-scram",
+            BrokerAuth::Tls => "tls",
+        })
+    }
+}
+
+impl FromStr for BrokerAuth {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(BrokerAuth::None),
+            "sasl-plain" => Ok(BrokerAuth::SaslPlain),
+            "sasl-scram" => Ok(BrokerAuth::SaslScram),
+            "tls" => Ok(BrokerAuth::Tls),
+            other => Err(format!(
+                "invalid broker authentication mode '{other}'. Expected one of: none, sasl-plain, sasl-scram, tls"
+            )),
+        }
+    }
+}
+
+/// Result of the validation of a declared broker authentication mode
+#[derive(Copy, Clone, Debug, Decode, Encode, Serialize, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+#[serde(rename_all = "snake_case")]
+pub enum BrokerAuthStatus {
+    /// The brokers require the declared authentication mode
+    #[n(0)] Valid,
+    /// The brokers require a different authentication mode
+    #[n(1)] Mismatch,
+    /// The authentication mode required by the brokers could not be checked
+    #[n(2)] Unverified,
+}
+
+impl Display for BrokerAuthStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            BrokerAuthStatus::Valid => "valid",
+            BrokerAuthStatus::Mismatch => "mismatch",
+            BrokerAuthStatus::Unverified => "unverified",
+        })
+    }
+}
+
+/// The declared authentication mode of a Kafka service and the result of its validation
+#[derive(Clone, Debug, Decode, Encode, Serialize, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct BrokerAuthValidation {
+    #[n(1)] declared: BrokerAuth,
+    #[n(2)] status: BrokerAuthStatus,
+    #[n(3)] message: String,
+}
+
+impl BrokerAuthValidation {
+    fn new(declared: BrokerAuth, status: BrokerAuthStatus, message: impl Into<String>) -> Self {
+        Self {
+            declared,
+            status,
+            message: message.into(),
+        }
+    }
+
+    /// Create a validation result for a mode which cannot be checked from the current node
+    pub fn unverified(declared: BrokerAuth, message: impl Into<String>) -> Self {
+        Self::new(declared, BrokerAuthStatus::Unverified, message)
+    }
+
+    pub fn declared(&self) -> BrokerAuth {
+        self.declared
+    }
+
+    pub fn status(&self) -> BrokerAuthStatus {
+        self.status
+    }
+
+    pub fn message(&self) -> String {
+        self.message.clone()
+    }
+
+    pub fn is_mismatch(&self) -> bool {
+        self.status == BrokerAuthStatus::Mismatch
+    }
+}
+
+impl Display for BrokerAuthValidation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({}): {}", self.declared, self.status, self.message)
+    }
+}
+
+/// Connect to the bootstrap server and check that the brokers require the declared authentication mode.
+///
+/// The validation sends a Metadata request, which is only answered by brokers accepting
+/// unauthenticated connections, then a SaslHandshake request to get the SASL mechanisms
+/// enabled on the brokers. A TLS listener is detected when it answers with a TLS record.
+///
+/// An error is returned if the bootstrap server cannot be reached.
+pub async fn validate_broker_auth(
+    bootstrap_server_addr: SocketAddr,
+    declared: BrokerAuth,
+) -> Result<BrokerAuthValidation> {
+    let detected = detect_broker_auth(bootstrap_server_addr).await?;
+    debug!(%bootstrap_server_addr, %declared, %detected, "validated the kafka brokers authentication");
+    Ok(check_broker_auth(declared, &detected))
+}
+
+/// Authentication mode detected on the brokers
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum DetectedBrokerAuth {
+    None,
+    Sasl(Vec<String>),
+    Tls,
+    Unknown,
+}
+
+impl DetectedBrokerAuth {
+    /// Return the authentication mode which should be declared for these brokers
+    fn suggested_mode(&self) -> Option<BrokerAuth> {
+        match self {
+            DetectedBrokerAuth::None => Some(BrokerAuth::None),
+            DetectedBrokerAuth::Sasl(mechanisms) => {
+                if mechanisms.iter().any(|m| m.starts_with("SCRAM-")) {
+                    Some(BrokerAuth::SaslScram)
+                } else if mechanisms.iter().any(|m| m == "PLAIN") {
+                    Some(BrokerAuth::SaslPlain)
+                } else {
+                    None
+                }
+            }
+            DetectedBrokerAuth::Tls => Some(BrokerAuth::Tls),
+            DetectedBrokerAuth::Unknown => None,
+        }
+    }
+}
+
+impl Display for DetectedBrokerAuth {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DetectedBrokerAuth::None => f.write_str("no authentication"),
+            DetectedBrokerAuth::Sasl(mechanisms) => write!(
+                f,
+                "SASL authentication with the mechanisms: {}",
+                mechanisms.join(", ")
+            ),
+            DetectedBrokerAuth::Tls => f.write_str("TLS connections"),
+            DetectedBrokerAuth::Unknown => f.write_str("an unknown authentication mode"),
+        }
+    }
+}
+
+fn check_broker_auth(declared: BrokerAuth, detected: &DetectedBrokerAuth) -> BrokerAuthValidation {
+    let is_valid = match (declared, detected) {
+        (_, DetectedBrokerAuth::Unknown) => {
+            return BrokerAuthValidation::unverified(
+                declared,
+                "the brokers closed the connection without revealing their authentication mode",
+            )
+        }
+        (BrokerAuth::None, DetectedBrokerAuth::None) => true,
+        (BrokerAuth::Tls, DetectedBrokerAuth::Tls) => true,
+        (BrokerAuth::SaslPlain, DetectedBrokerAuth::Sasl(mechanisms)) => {
+            mechanisms.iter().any(|m| m == "PLAIN")
+        }
+        (BrokerAuth::SaslScram, DetectedBrokerAuth::Sasl(mechanisms)) => {
+            mechanisms.iter().any(|m| m.starts_with("SCRAM-"))
+        }
+        _ => false,
+    };
+
+    if is_valid {
+        BrokerAuthValidation::new(
+            declared,
+            BrokerAuthStatus::Valid,
+            format!("the brokers require {detected}"),
+        )
+    } else {
+        let hint = match detected.suggested_mode() {
+            Some(mode) => format!(". Use --broker-auth {mode} instead"),
+            None => "".to_string(),
+        };
+        BrokerAuthValidation::new(
+            declared,
+            BrokerAuthStatus::Mismatch,
+            format!(
+                "the authentication mode '{declared}' was declared but the brokers require {detected}{hint}"
+            ),
+        )
+    }
+}
+
+async fn detect_broker_auth(bootstrap_server_addr: SocketAddr) -> Result<DetectedBrokerAuth> {
+    let metadata_request = MetadataRequest::builder()
+        .topics(Some(vec![]))
+        .build()
+        .map_err(|e| invalid_request(e.to_string()))?;
+    let mut stream = connect(bootstrap_server_addr).await?;
+    match exchange::<_, MetadataResponse>(
+        &mut stream,
+        ApiKey::MetadataKey,
+        METADATA_VERSION,
+        &metadata_request,
+    )
+    .await?
+    {
+        Exchange::Response(_) => return Ok(DetectedBrokerAuth::None),
+        Exchange::Tls => return Ok(DetectedBrokerAuth::Tls),
+        Exchange::Closed => (),
+    };
+
+    // the connection is closed by SASL listeners when a request is sent before authenticating
+    let sasl_handshake_request = SaslHandshakeRequest::builder()
+        .mechanism(StrBytes::from_static_str("PLAIN"))
+        .build()
+        .map_err(|e| invalid_request(e.to_string()))?;
+    let mut stream = connect(bootstrap_server_addr).await?;
+    match exchange::<_, SaslHandshakeResponse>(
+        &mut stream,
+        ApiKey::SaslHandshakeKey,
+        SASL_HANDSHAKE_VERSION,
+        &sasl_handshake_request,
+    )
+    .await?
+    {
+        Exchange::Response(response) => Ok(DetectedBrokerAuth::Sasl(
+            response.mechanisms.iter().map(|m| m.to_string()).collect(),
+        )),
+        Exchange::Tls => Ok(DetectedBrokerAuth::Tls),
+        Exchange::Closed => Ok(DetectedBrokerAuth::Unknown),
+    }
+}
+
+/// Outcome of a request sent to a broker
+enum Exchange<T> {
+    Response(T),
+    Tls,
+    Closed,
+}
+
+async fn connect(bootstrap_server_addr: SocketAddr) -> Result<TcpStream> {
+    match timeout(PREFLIGHT_TIMEOUT, TcpStream::connect(bootstrap_server_addr)).await {
+        Ok(Ok(stream)) => Ok(stream),
+        Ok(Err(e)) => Err(Error::new(
+            Origin::Transport,
+            Kind::Io,
+            format!("cannot connect to the kafka bootstrap server {bootstrap_server_addr}: {e}"),
+        )),
+        Err(_) => Err(Error::new(
+            Origin::Transport,
+            Kind::Timeout,
+            format!(
+                "timed out while connecting to the kafka bootstrap server {bootstrap_server_addr}"
+            ),
+        )),
+    }
+}
+
+/// Send a request and read its response.
+/// A closed connection, an I/O error or a timeout are all interpreted as a refused request
+async fn exchange<Req: Encodable, Res: Decodable>(
+    stream: &mut TcpStream,
+    api_key: ApiKey,
+    api_version: i16,
+    request: &Req,
+) -> Result<Exchange<Res>> {
+    let message = encode_message(api_key, api_version, request)?;
+    if stream.write_all(&message).await.is_err() {
+        return Ok(Exchange::Closed);
+    }
+
+    let mut size = [0u8; 4];
+    match timeout(PREFLIGHT_TIMEOUT, stream.read_exact(&mut size)).await {
+        Ok(Ok(_)) => (),
+        _ => return Ok(Exchange::Closed),
+    }
+    // TLS records start with a content type (alert or handshake) followed by the 0x03 major version
+    if matches!(size[0], 0x15 | 0x16) && size[1] == 0x03 {
+        return Ok(Exchange::Tls);
+    }
+
+    let size = u32::from_be_bytes(size) as usize;
+    if size > MAX_RESPONSE_SIZE {
+        return Ok(Exchange::Closed);
+    }
+    let mut buffer = vec![0u8; size];
+    match timeout(PREFLIGHT_TIMEOUT, stream.read_exact(&mut buffer)).await {
+        Ok(Ok(_)) => (),
+        _ => return Ok(Exchange::Closed),
+    }
+
+    let mut buffer = BytesMut::from(buffer.as_slice());
+    ResponseHeader::decode(&mut buffer, api_key.response_header_version(api_version))
+        .map_err(|e| invalid_response(e.to_string()))?;
+    let response =
+        Res::decode(&mut buffer, api_version).map_err(|e| invalid_response(e.to_string()))?;
+    Ok(Exchange::Response(response))
+}
+
+/// Encode a request with its header and the size prefix used by the Kafka protocol
+fn encode_message<T: Encodable>(
+    api_key: ApiKey,
+    api_version: i16,
+    request: &T,
+) -> Result<BytesMut> {
+    let header = RequestHeader::builder()
+        .request_api_key(api_key as i16)
+        .request_api_version(api_version)
+        .correlation_id(1)
+        .client_id(Some(StrBytes::from_static_str("ockam")))
+        .build()
+        .map_err(|e| invalid_request(e.to_string()))?;
+
+    let mut buffer = BytesMut::new();
+    header
+        .encode(&mut buffer, api_key.request_header_version(api_version))
+        .map_err(|e| invalid_request(e.to_string()))?;
+    request
+        .encode(&mut buffer, api_version)
+        .map_err(|e| invalid_request(e.to_string()))?;
+
+    let mut message = BytesMut::with_capacity(buffer.len() + 4);
+    message.put_u32(buffer.len() as u32);
+    message.extend_from_slice(&buffer);
+    Ok(message)
+}
+
+fn invalid_request(message: String) -> Error {
+    Error::new(
+        Origin::Application,
+        Kind::Serialization,
+        format!("cannot encode a kafka request: {message}"),
+    )
+}
+
+fn invalid_response(message: String) -> Error {
+    Error::new(
+        Origin::Application,
+        Kind::Serialization,
+        format!("cannot decode a kafka response: {message}"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kafka_protocol::messages::BrokerId;
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn test_validate_unauthenticated_brokers() -> Result<()> {
+        let address = start_broker(BrokerBehavior::Unauthenticated).await;
+
+        let validation = validate_broker_auth(address, BrokerAuth::None).await?;
+        assert_eq!(validation.status(), BrokerAuthStatus::Valid);
+
+        let validation = validate_broker_auth(address, BrokerAuth::SaslScram).await?;
+        assert!(validation.is_mismatch());
+        assert!(validation.message().contains("--broker-auth none"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_validate_sasl_brokers() -> Result<()> {
+        let address = start_broker(BrokerBehavior::Sasl(vec!["SCRAM-SHA-512"])).await;
+
+        let validation = validate_broker_auth(address, BrokerAuth::SaslScram).await?;
+        assert_eq!(validation.status(), BrokerAuthStatus::Valid);
+
+        let validation = validate_broker_auth(address, BrokerAuth::SaslPlain).await?;
+        assert!(validation.is_mismatch());
+        assert!(validation.message().contains("SCRAM-SHA-512"));
+
+        let validation = validate_broker_auth(address, BrokerAuth::None).await?;
+        assert!(validation.is_mismatch());
+        assert!(validation.message().contains("--broker-auth sasl-scram"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_validate_tls_brokers() -> Result<()> {
+        let address = start_broker(BrokerBehavior::Tls).await;
+
+        let validation = validate_broker_auth(address, BrokerAuth::Tls).await?;
+        assert_eq!(validation.status(), BrokerAuthStatus::Valid);
+
+        let validation = validate_broker_auth(address, BrokerAuth::None).await?;
+        assert!(validation.is_mismatch());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_validate_unreachable_brokers() -> Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        drop(listener);
+
+        let result = validate_broker_auth(address, BrokerAuth::None).await;
+        assert!(result.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_broker_auth() {
+        for mode in [
+            BrokerAuth::None,
+            BrokerAuth::SaslPlain,
+            BrokerAuth::SaslScram,
+            BrokerAuth::Tls,
+        ] {
+            assert_eq!(BrokerAuth::from_str(&mode.to_string()), Ok(mode));
+        }
+        assert!(BrokerAuth::from_str("kerberos").is_err());
+    }
+
+    /// HELPERS
+    #[derive(Clone)]
+    enum BrokerBehavior {
+        Unauthenticated,
+        Sasl(Vec<&'static str>),
+        Tls,
+    }
+
+    /// Start a fake broker answering the pre-flight requests according to a given behavior
+    async fn start_broker(behavior: BrokerBehavior) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let behavior = behavior.clone();
+                tokio::spawn(async move {
+                    let (api_key, api_version) = read_request(&mut stream).await;
+                    let response = match (behavior, api_key) {
+                        (BrokerBehavior::Tls, _) => {
+                            BytesMut::from([0x15, 0x03, 0x03, 0x00, 0x02, 0x02, 0x46].as_slice())
+                        }
+                        (BrokerBehavior::Unauthenticated, ApiKey::MetadataKey) => {
+                            let response = MetadataResponse::builder()
+                                .controller_id(BrokerId::from(0_i32))
+                                .build()
+                                .unwrap();
+                            encode_response(api_key, api_version, &response)
+                        }
+                        (BrokerBehavior::Sasl(mechanisms), ApiKey::SaslHandshakeKey) => {
+                            let response = SaslHandshakeResponse::builder()
+                                .error_code(33)
+                                .mechanisms(
+                                    mechanisms
+                                        .into_iter()
+                                        .map(StrBytes::from_static_str)
+                                        .collect(),
+                                )
+                                .build()
+                                .unwrap();
+                            encode_response(api_key, api_version, &response)
+                        }
+                        // close the connection
+                        _ => return,
+                    };
+                    stream.write_all(&response).await.unwrap();
+                });
+            }
+        });
+        address
+    }
+
+    async fn read_request(stream: &mut TcpStream) -> (ApiKey, i16) {
+        let size = stream.read_u32().await.unwrap() as usize;
+        let mut buffer = vec![0u8; size];
+        stream.read_exact(&mut buffer).await.unwrap();
+        let api_key = i16::from_be_bytes([buffer[0], buffer[1]]);
+        let api_version = i16::from_be_bytes([buffer[2], buffer[3]]);
+        (ApiKey::try_from(api_key).unwrap(), api_version)
+    }
+
+    fn encode_response<T: Encodable>(api_key: ApiKey, api_version: i16, response: &T) -> BytesMut {
+        let mut buffer = BytesMut::new();
+        ResponseHeader::builder()
+            .correlation_id(1)
+            .build()
+            .unwrap()
+            .encode(&mut buffer, api_key.response_header_version(api_version))
+            .unwrap();
+        response.encode(&mut buffer, api_version).unwrap();
+
+        let mut message = BytesMut::new();
+        message.put_u32(buffer.len() as u32);
+        message.extend_from_slice(&buffer);
+        message
+    }
+}
@@ -127,7 +127,7 @@ impl KafkaInletController {
             format!("kafka-inlet-{}", random_string()),
             prefix,
             suffix,
-            None,
+            vec![],
             true,
         );
         if let Some(expr) = policy_expression {
@@ -109,6 +109,7 @@ mod test {
             inlet_controller,
             secure_channel_controller.into_trait(),
             listener_address,
+            Default::default(),
         )
         .await?;
 
@@ -1,6 +1,7 @@
 //!This service allows encrypted transparent communication from the kafka producer
 //! to the kafka consumer without any modification in the existing application.
 
+mod broker_auth;
 mod inlet_controller;
 mod integration_test;
 mod length_delimited;
@@ -11,6 +12,7 @@ mod portal_worker;
 mod protocol_aware;
 mod secure_channel_map;
 
+pub use broker_auth::{validate_broker_auth, BrokerAuth, BrokerAuthStatus, BrokerAuthValidation};
 pub(crate) use inlet_controller::KafkaInletController;
 use ockam::identity::Identifier;
 use ockam_abac::attribute_access_control::{
@@ -22,6 +24,7 @@ use ockam_core::Address;
 pub(crate) use outlet_service::prefix_relay::PrefixRelayService;
 pub(crate) use outlet_service::OutletManagerService;
 pub(crate) use portal_listener::KafkaPortalListener;
+pub use protocol_aware::TopicAllowlist;
 pub(crate) use secure_channel_map::ConsumerNodeAddr;
 pub(crate) use secure_channel_map::KafkaSecureChannelControllerImpl;
 
@@ -1,6 +1,6 @@
 use crate::kafka::outlet_controller::KafkaOutletController;
 use crate::kafka::portal_worker::KafkaPortalWorker;
-use crate::kafka::protocol_aware::OutletInterceptorImpl;
+use crate::kafka::protocol_aware::{OutletInterceptorImpl, TopicAllowlist, TopicUuidMap};
 use crate::kafka::KAFKA_OUTLET_INTERCEPTOR_ADDRESS;
 use ockam::identity::{Identifier, SecureChannels};
 use ockam::{Any, Context, Result, Routed, Worker};
@@ -18,6 +18,8 @@ pub(crate) struct OutletManagerService {
     outlet_controller: KafkaOutletController,
     incoming_access_control: Arc<AbacAccessControl>,
     spawner_flow_control_id: FlowControlId,
+    topic_allowlist: TopicAllowlist,
+    uuid_to_name: TopicUuidMap,
 }
 
 impl OutletManagerService {
@@ -27,6 +29,7 @@ impl OutletManagerService {
         authority_identifier: Identifier,
         default_secure_channel_listener_flow_control_id: FlowControlId,
         policy_expression: Option<Expr>,
+        topic_allowlist: TopicAllowlist,
     ) -> Result<()> {
         let flow_controls = context.flow_controls();
 
@@ -49,6 +52,8 @@ impl OutletManagerService {
             outlet_controller: KafkaOutletController::new(policy_expression),
             incoming_access_control: Arc::new(abac),
             spawner_flow_control_id: spawner_flow_control_id.clone(),
+            topic_allowlist,
+            uuid_to_name: Default::default(),
         };
 
         let incoming = worker.incoming_access_control.clone();
@@ -97,6 +102,8 @@ impl Worker for OutletManagerService {
             Arc::new(OutletInterceptorImpl::new(
                 self.outlet_controller.clone(),
                 self.spawner_flow_control_id.clone(),
+                self.topic_allowlist.clone(),
+                self.uuid_to_name.clone(),
             )),
             &context.flow_controls().clone(),
             secure_channel_flow_control_id,
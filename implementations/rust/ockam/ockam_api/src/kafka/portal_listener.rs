@@ -5,7 +5,7 @@ use tracing::trace;
 
 use crate::kafka::inlet_controller::KafkaInletController;
 use crate::kafka::portal_worker::KafkaPortalWorker;
-use crate::kafka::protocol_aware::TopicUuidMap;
+use crate::kafka::protocol_aware::{TopicAllowlist, TopicUuidMap};
 use crate::kafka::secure_channel_map::KafkaSecureChannelController;
 
 /// First point of ingress of kafka connections, at the first message it spawns new stateful workers
@@ -14,6 +14,7 @@ pub(crate) struct KafkaPortalListener {
     inlet_controller: KafkaInletController,
     secure_channel_controller: Arc<dyn KafkaSecureChannelController>,
     uuid_to_name: TopicUuidMap,
+    topic_allowlist: TopicAllowlist,
 }
 
 #[ockam::worker]
@@ -48,6 +49,7 @@ impl Worker for KafkaPortalListener {
             self.secure_channel_controller.clone(),
             self.uuid_to_name.clone(),
             self.inlet_controller.clone(),
+            self.topic_allowlist.clone(),
             None,
             flow_control_id,
             route![inlet_responder_address],
@@ -75,6 +77,7 @@ impl KafkaPortalListener {
         inlet_controller: KafkaInletController,
         secure_channel_controller: Arc<dyn KafkaSecureChannelController>,
         listener_address: Address,
+        topic_allowlist: TopicAllowlist,
     ) -> ockam_core::Result<()> {
         context
             .start_worker(
@@ -83,6 +86,7 @@ impl KafkaPortalListener {
                     inlet_controller,
                     secure_channel_controller,
                     uuid_to_name: Default::default(),
+                    topic_allowlist,
                 },
             )
             .await
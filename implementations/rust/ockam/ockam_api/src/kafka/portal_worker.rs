@@ -14,7 +14,9 @@ use ockam_transport_tcp::{PortalMessage, MAX_PAYLOAD_SIZE};
 
 use crate::kafka::inlet_controller::KafkaInletController;
 use crate::kafka::length_delimited::{length_encode, KafkaMessageDecoder};
-use crate::kafka::protocol_aware::{InletInterceptorImpl, KafkaMessageInterceptor, TopicUuidMap};
+use crate::kafka::protocol_aware::{
+    InletInterceptorImpl, KafkaMessageInterceptor, TopicAllowlist, TopicUuidMap,
+};
 use crate::kafka::secure_channel_map::KafkaSecureChannelController;
 use crate::kafka::KAFKA_OUTLET_BOOTSTRAP_ADDRESS;
 
@@ -127,7 +129,16 @@ impl Worker for KafkaPortalWorker {
                     context.stop_worker(context.address()).await?;
                 }
             }
-            PortalMessage::Ping => self.forward(context, routed_message).await?,
+            PortalMessage::Ping | PortalMessage::PingFrom { .. } => {
+                self.forward(context, routed_message).await?
+            }
+
+            // Kafka messages are rewritten by this worker, so the number of bytes written on one
+            // side doesn't match the number of bytes read on the other side: the buffer size and
+            // the acknowledgements are not forwarded, and the portal runs without backpressure
+            PortalMessage::BufferSize(_) | PortalMessage::Ack(_) => {
+                trace!("dropping a portal flow control message");
+            }
 
             PortalMessage::Pong => {
                 match self.receiving {
@@ -386,6 +397,7 @@ impl KafkaPortalWorker {
         secure_channel_controller: Arc<dyn KafkaSecureChannelController>,
         uuid_to_name: TopicUuidMap,
         inlet_map: KafkaInletController,
+        topic_allowlist: TopicAllowlist,
         max_kafka_message_size: Option<u32>,
         flow_control_id: Option<FlowControlId>,
         inlet_responder_route: Route,
@@ -394,6 +406,7 @@ impl KafkaPortalWorker {
             secure_channel_controller,
             uuid_to_name,
             inlet_map,
+            topic_allowlist,
         ));
 
         let requests_worker_address = Address::random_tagged("KafkaPortalWorker.requests");
@@ -760,6 +773,7 @@ mod test {
             secure_channel_controller,
             Default::default(),
             inlet_map,
+            Default::default(),
             Some(TEST_MAX_KAFKA_MESSAGE_SIZE),
             None,
             route![context.address()],
@@ -828,6 +842,7 @@ mod test {
             secure_channel_controller,
             Default::default(),
             inlet_map.clone(),
+            Default::default(),
             None,
             None,
             route![context.address()],
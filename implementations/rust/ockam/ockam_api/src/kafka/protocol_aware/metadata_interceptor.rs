@@ -1,9 +1,12 @@
 use crate::kafka::outlet_controller::KafkaOutletController;
 use alloc::sync::Arc;
-use bytes::BytesMut;
+use bytes::{Bytes, BytesMut};
 
 use kafka_protocol::messages::request_header::RequestHeader;
-use kafka_protocol::messages::{ApiKey, MetadataResponse, ResponseHeader};
+use kafka_protocol::messages::{
+    ApiKey, FetchRequest, FetchResponse, MetadataResponse, ProduceRequest, ProduceResponse,
+    ResponseHeader,
+};
 use kafka_protocol::protocol::buf::ByteBuf;
 use kafka_protocol::protocol::Decodable;
 
@@ -21,29 +24,53 @@ use tokio::net::lookup_host;
 use tracing::warn;
 
 use crate::kafka::portal_worker::InterceptError;
-use crate::kafka::protocol_aware::utils::decode_body;
-use crate::kafka::protocol_aware::{CorrelationId, KafkaMessageInterceptor, RequestInfo};
+use crate::kafka::protocol_aware::utils::{decode_body, encode_request, encode_response};
+use crate::kafka::protocol_aware::{
+    CorrelationId, DeniedTopicsMap, KafkaMessageInterceptor, RequestInfo, TopicAllowlist,
+    TopicUuidMap,
+};
 
 /// Intercepts responses of type `Metadata` to extract the list of brokers
-/// then creates an outlet for each of them through [`KafkaOutletController`]
+/// then creates an outlet for each of them through [`KafkaOutletController`].
+///
+/// The topics which are not allowed by the [`TopicAllowlist`] are removed from the
+/// produce and fetch requests, and from the metadata responses.
 #[derive(Clone)]
 pub(crate) struct OutletInterceptorImpl {
     request_map: Arc<Mutex<HashMap<CorrelationId, RequestInfo>>>,
     outlet_controller: KafkaOutletController,
     flow_control_id: FlowControlId,
+    topic_allowlist: TopicAllowlist,
+    uuid_to_name: TopicUuidMap,
+    denied_topics: DeniedTopicsMap,
 }
 
 impl OutletInterceptorImpl {
     pub(crate) fn new(
         outlet_controller: KafkaOutletController,
         flow_control_id: FlowControlId,
+        topic_allowlist: TopicAllowlist,
+        uuid_to_name: TopicUuidMap,
     ) -> Self {
         Self {
             request_map: Arc::new(Mutex::new(HashMap::new())),
             outlet_controller,
             flow_control_id,
+            topic_allowlist,
+            uuid_to_name,
+            denied_topics: Arc::new(Mutex::new(HashMap::new())),
         }
     }
+
+    fn map_request(&self, header: &RequestHeader, api_key: ApiKey) {
+        self.request_map.lock().unwrap().insert(
+            header.correlation_id,
+            RequestInfo {
+                request_api_key: api_key,
+                request_api_version: header.request_api_version,
+            },
+        );
+    }
 }
 
 #[async_trait]
@@ -93,14 +120,54 @@ impl KafkaMessageInterceptor for OutletInterceptorImpl {
             api_key
         );
 
-        if api_key == ApiKey::MetadataKey {
-            self.request_map.lock().unwrap().insert(
-                header.correlation_id,
-                RequestInfo {
-                    request_api_key: ApiKey::MetadataKey,
-                    request_api_version: header.request_api_version,
-                },
-            );
+        match api_key {
+            ApiKey::MetadataKey => self.map_request(&header, api_key),
+
+            // the topics which are not allowed are not sent to the brokers,
+            // an authorization error is added to the response instead
+            ApiKey::ProduceKey if self.topic_allowlist.is_restricted() => {
+                let mut request: ProduceRequest =
+                    decode_body(&mut buffer, header.request_api_version)?;
+                if let Some(denied_topics) =
+                    self.topic_allowlist.filter_produce_request(&mut request)
+                {
+                    // there is no response when the producer doesn't require acknowledgements
+                    if request.acks != 0 {
+                        self.denied_topics
+                            .lock()
+                            .unwrap()
+                            .insert(header.correlation_id, denied_topics);
+                        self.map_request(&header, api_key);
+                    }
+                    return encode_request(&header, &request, header.request_api_version, api_key);
+                }
+            }
+            ApiKey::FetchKey if self.topic_allowlist.is_restricted() => {
+                let mut request: FetchRequest =
+                    decode_body(&mut buffer, header.request_api_version)?;
+                let denied_topics =
+                    self.topic_allowlist
+                        .filter_fetch_request(&mut request, |topic| {
+                            if header.request_api_version <= 12 {
+                                Some(topic.topic.0.to_string())
+                            } else {
+                                self.uuid_to_name
+                                    .lock()
+                                    .unwrap()
+                                    .get(&topic.topic_id.to_string())
+                                    .cloned()
+                            }
+                        });
+                if let Some(denied_topics) = denied_topics {
+                    self.denied_topics
+                        .lock()
+                        .unwrap()
+                        .insert(header.correlation_id, denied_topics);
+                    self.map_request(&header, api_key);
+                    return encode_request(&header, &request, header.request_api_version, api_key);
+                }
+            }
+            _ => {}
         }
 
         Ok(original)
@@ -134,7 +201,7 @@ impl KafkaMessageInterceptor for OutletInterceptorImpl {
                     .response_header_version(request_info.request_api_version),
             );
 
-            let _header = match result {
+            let header = match result {
                 Ok(header) => header,
                 Err(_) => {
                     // the error doesn't contain any useful information
@@ -151,35 +218,49 @@ impl KafkaMessageInterceptor for OutletInterceptorImpl {
                 request_info.request_api_key
             );
 
-            if request_info.request_api_key == ApiKey::MetadataKey {
-                let response: MetadataResponse =
-                    decode_body(&mut buffer, request_info.request_api_version)?;
-
-                for (broker_id, metadata) in response.brokers {
-                    let address = format!("{}:{}", metadata.host.as_str(), metadata.port);
-                    let socket_addr = lookup_host(&address)
-                        .await
-                        .ok()
-                        .and_then(|mut i| i.next())
-                        .ok_or_else(|| {
-                            InterceptError::Ockam(ockam_core::Error::new(
-                                Origin::Ockam,
-                                Kind::Invalid,
-                                format!("cannot resolve broker {broker_id:?} address {address}"),
-                            ))
-                        })?;
-
-                    let outlet_address = self
-                        .outlet_controller
-                        .assert_outlet_for_broker(context, broker_id.0, socket_addr)
-                        .await
-                        .map_err(InterceptError::Ockam)?;
-
-                    // allow the interceptor to reach the outlet
-                    context
-                        .flow_controls()
-                        .add_consumer(outlet_address, &self.flow_control_id);
+            match request_info.request_api_key {
+                ApiKey::MetadataKey => {
+                    return self
+                        .handle_metadata_response(
+                            context,
+                            original,
+                            &mut buffer,
+                            &request_info,
+                            &header,
+                        )
+                        .await;
+                }
+                ApiKey::ProduceKey => {
+                    let mut response: ProduceResponse =
+                        decode_body(&mut buffer, request_info.request_api_version)?;
+                    if let Some(denied_topics) =
+                        self.denied_topics.lock().unwrap().remove(&correlation_id)
+                    {
+                        denied_topics.add_to_produce_response(&mut response);
+                    }
+                    return encode_response(
+                        &header,
+                        &response,
+                        request_info.request_api_version,
+                        ApiKey::ProduceKey,
+                    );
                 }
+                ApiKey::FetchKey => {
+                    let mut response: FetchResponse =
+                        decode_body(&mut buffer, request_info.request_api_version)?;
+                    if let Some(denied_topics) =
+                        self.denied_topics.lock().unwrap().remove(&correlation_id)
+                    {
+                        denied_topics.add_to_fetch_response(&mut response);
+                    }
+                    return encode_response(
+                        &header,
+                        &response,
+                        request_info.request_api_version,
+                        ApiKey::FetchKey,
+                    );
+                }
+                _ => {}
             }
         } else {
             debug!(
@@ -191,3 +272,64 @@ impl KafkaMessageInterceptor for OutletInterceptorImpl {
         Ok(original)
     }
 }
+
+impl OutletInterceptorImpl {
+    // creates an outlet for each broker of the metadata response,
+    // and removes the topics which are not allowed
+    async fn handle_metadata_response(
+        &self,
+        context: &mut Context,
+        original: BytesMut,
+        buffer: &mut Bytes,
+        request_info: &RequestInfo,
+        header: &ResponseHeader,
+    ) -> Result<BytesMut, InterceptError> {
+        let mut response: MetadataResponse = decode_body(buffer, request_info.request_api_version)?;
+
+        for (broker_id, metadata) in &response.brokers {
+            let address = format!("{}:{}", metadata.host.as_str(), metadata.port);
+            let socket_addr = lookup_host(&address)
+                .await
+                .ok()
+                .and_then(|mut i| i.next())
+                .ok_or_else(|| {
+                    InterceptError::Ockam(ockam_core::Error::new(
+                        Origin::Ockam,
+                        Kind::Invalid,
+                        format!("cannot resolve broker {broker_id:?} address {address}"),
+                    ))
+                })?;
+
+            let outlet_address = self
+                .outlet_controller
+                .assert_outlet_for_broker(context, broker_id.0, socket_addr)
+                .await
+                .map_err(InterceptError::Ockam)?;
+
+            // allow the interceptor to reach the outlet
+            context
+                .flow_controls()
+                .add_consumer(outlet_address, &self.flow_control_id);
+        }
+
+        // fetch requests using version >= 13 only use topic ids
+        if request_info.request_api_version >= 10 {
+            for (topic_name, topic) in &response.topics {
+                self.uuid_to_name
+                    .lock()
+                    .unwrap()
+                    .insert(topic.topic_id.to_string(), topic_name.to_string());
+            }
+        }
+
+        if !self.topic_allowlist.filter_metadata_response(&mut response) {
+            return Ok(original);
+        }
+        encode_response(
+            header,
+            &response,
+            request_info.request_api_version,
+            ApiKey::MetadataKey,
+        )
+    }
+}
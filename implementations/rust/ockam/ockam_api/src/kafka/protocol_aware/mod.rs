@@ -16,9 +16,12 @@ mod metadata_interceptor;
 mod request;
 mod response;
 mod tests;
+mod topic_allowlist;
 
 pub(super) mod utils;
 pub(crate) use metadata_interceptor::OutletInterceptorImpl;
+pub(crate) use topic_allowlist::DeniedTopics;
+pub use topic_allowlist::TopicAllowlist;
 
 #[derive(Clone, Debug)]
 struct RequestInfo {
@@ -32,6 +35,9 @@ type CorrelationId = i32;
 /// only from one connection
 pub(super) type TopicUuidMap = Arc<Mutex<HashMap<String, String>>>;
 
+/// Responses to add for the topics removed from a request, since they are not allowed
+type DeniedTopicsMap = Arc<Mutex<HashMap<CorrelationId, DeniedTopics>>>;
+
 #[async_trait]
 pub(crate) trait KafkaMessageInterceptor: Send + Sync + 'static {
     async fn intercept_request(
@@ -53,6 +59,8 @@ pub(crate) struct InletInterceptorImpl {
     uuid_to_name: TopicUuidMap,
     secure_channel_controller: Arc<dyn KafkaSecureChannelController>,
     inlet_map: KafkaInletController,
+    topic_allowlist: TopicAllowlist,
+    denied_topics: DeniedTopicsMap,
 }
 
 #[async_trait]
@@ -88,12 +96,15 @@ impl InletInterceptorImpl {
         secure_channel_controller: Arc<dyn KafkaSecureChannelController>,
         uuid_to_name: TopicUuidMap,
         inlet_map: KafkaInletController,
+        topic_allowlist: TopicAllowlist,
     ) -> InletInterceptorImpl {
         Self {
             request_map: Arc::new(Mutex::new(Default::default())),
             uuid_to_name,
             secure_channel_controller,
             inlet_map,
+            topic_allowlist,
+            denied_topics: Arc::new(Mutex::new(Default::default())),
         }
     }
 }
@@ -69,8 +69,12 @@ impl InletInterceptorImpl {
                     .await;
             }
             ApiKey::FetchKey => {
-                self.handle_fetch_request(context, &mut buffer, &header)
-                    .await?;
+                if let Some(request) = self
+                    .handle_fetch_request(context, &mut buffer, &header)
+                    .await?
+                {
+                    return Ok(request);
+                }
             }
             ApiKey::MetadataKey | ApiKey::FindCoordinatorKey => {
                 self.request_map.lock().unwrap().insert(
@@ -99,13 +103,29 @@ impl InletInterceptorImpl {
         Ok(original)
     }
 
+    /// Start a relay for each fetched partition.
+    /// Return a new request if some topics are not allowed and were removed from the request
     async fn handle_fetch_request(
         &self,
         context: &mut Context,
         buffer: &mut Bytes,
         header: &RequestHeader,
-    ) -> Result<(), InterceptError> {
-        let request: FetchRequest = decode_body(buffer, header.request_api_version)?;
+    ) -> Result<Option<BytesMut>, InterceptError> {
+        let mut request: FetchRequest = decode_body(buffer, header.request_api_version)?;
+
+        let denied_topics = self
+            .topic_allowlist
+            .filter_fetch_request(&mut request, |topic| {
+                if header.request_api_version <= 12 {
+                    Some(topic.topic.0.to_string())
+                } else {
+                    self.uuid_to_name
+                        .lock()
+                        .unwrap()
+                        .get(&topic.topic_id.to_string())
+                        .cloned()
+                }
+            });
 
         // we intercept every partition interested in the kafka client
         // and create a relay for each
@@ -147,7 +167,23 @@ impl InletInterceptorImpl {
                 request_api_version: header.request_api_version,
             },
         );
-        Ok(())
+
+        match denied_topics {
+            Some(denied_topics) => {
+                self.denied_topics
+                    .lock()
+                    .unwrap()
+                    .insert(header.correlation_id, denied_topics);
+                encode_request(
+                    header,
+                    &request,
+                    header.request_api_version,
+                    ApiKey::FetchKey,
+                )
+                .map(Some)
+            }
+            None => Ok(None),
+        }
     }
 
     async fn handle_produce_request(
@@ -158,6 +194,25 @@ impl InletInterceptorImpl {
     ) -> Result<BytesMut, InterceptError> {
         let mut request: ProduceRequest = decode_body(buffer, header.request_api_version)?;
 
+        // the topics which are not allowed are not sent to the brokers,
+        // an authorization error is added to the response instead
+        if let Some(denied_topics) = self.topic_allowlist.filter_produce_request(&mut request) {
+            // there is no response when the producer doesn't require acknowledgements
+            if request.acks != 0 {
+                self.denied_topics
+                    .lock()
+                    .unwrap()
+                    .insert(header.correlation_id, denied_topics);
+                self.request_map.lock().unwrap().insert(
+                    header.correlation_id,
+                    RequestInfo {
+                        request_api_key: ApiKey::ProduceKey,
+                        request_api_version: header.request_api_version,
+                    },
+                );
+            }
+        }
+
         // the content can be set in multiple topics and partitions in a single message
         // for each we wrap the content and add the secure channel identifier of
         // the encrypted content
@@ -5,6 +5,7 @@ use bytes::{Bytes, BytesMut};
 use kafka_protocol::messages::fetch_response::FetchResponse;
 use kafka_protocol::messages::find_coordinator_response::FindCoordinatorResponse;
 use kafka_protocol::messages::metadata_response::MetadataResponse;
+use kafka_protocol::messages::produce_response::ProduceResponse;
 use kafka_protocol::messages::response_header::ResponseHeader;
 use kafka_protocol::messages::ApiKey;
 use kafka_protocol::protocol::buf::ByteBuf;
@@ -68,6 +69,10 @@ impl InletInterceptorImpl {
             );
 
             match request_info.request_api_key {
+                ApiKey::ProduceKey => {
+                    return self.handle_produce_response(&mut buffer, &request_info, &header);
+                }
+
                 ApiKey::FetchKey => {
                     return self
                         .handle_fetch_response(context, &mut buffer, &request_info, &header)
@@ -122,6 +127,9 @@ impl InletInterceptorImpl {
     ) -> Result<BytesMut, InterceptError> {
         let mut response: MetadataResponse = decode_body(buffer, request_info.request_api_version)?;
 
+        // the clients don't see the topics which are not allowed
+        self.topic_allowlist.filter_metadata_response(&mut response);
+
         // we need to keep a map of topic uuid to topic name since fetch
         // operations only use uuid
         if request_info.request_api_version >= 10 {
@@ -209,6 +217,33 @@ impl InletInterceptorImpl {
         )
     }
 
+    // the produce requests are only mapped when some topics were removed from the request,
+    // the authorization errors for those topics are added to the response
+    fn handle_produce_response(
+        &self,
+        buffer: &mut Bytes,
+        request_info: &RequestInfo,
+        header: &ResponseHeader,
+    ) -> Result<BytesMut, InterceptError> {
+        let mut response: ProduceResponse = decode_body(buffer, request_info.request_api_version)?;
+
+        if let Some(denied_topics) = self
+            .denied_topics
+            .lock()
+            .unwrap()
+            .remove(&header.correlation_id)
+        {
+            denied_topics.add_to_produce_response(&mut response);
+        }
+
+        encode_response(
+            header,
+            &response,
+            request_info.request_api_version,
+            ApiKey::ProduceKey,
+        )
+    }
+
     async fn handle_fetch_response(
         &self,
         context: &mut Context,
@@ -264,6 +299,15 @@ impl InletInterceptorImpl {
             }
         }
 
+        if let Some(denied_topics) = self
+            .denied_topics
+            .lock()
+            .unwrap()
+            .remove(&header.correlation_id)
+        {
+            denied_topics.add_to_fetch_response(&mut response);
+        }
+
         encode_response(
             header,
             &response,
@@ -1,16 +1,31 @@
 #[cfg(test)]
 mod test {
     use crate::kafka::inlet_controller::KafkaInletController;
+    use crate::kafka::protocol_aware::utils::decode_body;
     use crate::kafka::protocol_aware::utils::{encode_request, encode_response};
     use crate::kafka::protocol_aware::InletInterceptorImpl;
     use crate::kafka::protocol_aware::KafkaMessageInterceptor;
+    use crate::kafka::protocol_aware::TopicAllowlist;
     use crate::kafka::secure_channel_map::{KafkaEncryptedContent, KafkaSecureChannelController};
     use crate::port_range::PortRange;
+    use bytes::BytesMut;
+    use kafka_protocol::indexmap::IndexMap;
+    use kafka_protocol::messages::fetch_request::{FetchPartition, FetchTopic};
+    use kafka_protocol::messages::fetch_response::{FetchableTopicResponse, PartitionData};
+    use kafka_protocol::messages::metadata_response::MetadataResponseTopic;
+    use kafka_protocol::messages::produce_request::{PartitionProduceData, TopicProduceData};
+    use kafka_protocol::messages::produce_response::{
+        PartitionProduceResponse, TopicProduceResponse,
+    };
     use kafka_protocol::messages::ApiKey;
     use kafka_protocol::messages::BrokerId;
     use kafka_protocol::messages::{ApiVersionsRequest, MetadataRequest, MetadataResponse};
     use kafka_protocol::messages::{ApiVersionsResponse, RequestHeader, ResponseHeader};
-    use kafka_protocol::protocol::{Builder, StrBytes};
+    use kafka_protocol::messages::{
+        FetchRequest, FetchResponse, ProduceRequest, ProduceResponse, TopicName,
+    };
+    use kafka_protocol::protocol::{Builder, Decodable, StrBytes};
+    use kafka_protocol::ResponseError;
     use ockam_core::compat::sync::Arc;
     use ockam_core::route;
     use ockam_core::{async_trait, Address};
@@ -71,6 +86,7 @@ mod test {
             Arc::new(DummySecureChannelController {}),
             Default::default(),
             inlet_map,
+            Default::default(),
         );
 
         let mut correlation_id = 0;
@@ -204,4 +220,312 @@ mod test {
         }
         Ok(())
     }
+
+    fn topic_name(name: &'static str) -> TopicName {
+        TopicName(StrBytes::from_static_str(name))
+    }
+
+    fn request_header(api_key: ApiKey, api_version: i16, correlation_id: i32) -> RequestHeader {
+        RequestHeader::builder()
+            .request_api_version(api_version)
+            .correlation_id(correlation_id)
+            .request_api_key(api_key as i16)
+            .build()
+            .unwrap()
+    }
+
+    fn response_header(correlation_id: i32) -> ResponseHeader {
+        ResponseHeader::builder()
+            .correlation_id(correlation_id)
+            .build()
+            .unwrap()
+    }
+
+    fn decode_request<T: Decodable>(api_key: ApiKey, api_version: i16, request: BytesMut) -> T {
+        let mut buffer = request.freeze();
+        RequestHeader::decode(&mut buffer, api_key.request_header_version(api_version)).unwrap();
+        decode_body(&mut buffer, api_version).unwrap()
+    }
+
+    fn decode_response<T: Decodable>(api_key: ApiKey, api_version: i16, response: BytesMut) -> T {
+        let mut buffer = response.freeze();
+        ResponseHeader::decode(&mut buffer, api_key.response_header_version(api_version)).unwrap();
+        decode_body(&mut buffer, api_version).unwrap()
+    }
+
+    fn interceptor_with_allowed_topics(allowed_topics: Vec<String>) -> InletInterceptorImpl {
+        let inlet_map = KafkaInletController::new(
+            MultiAddr::default(),
+            route![],
+            route![],
+            [127, 0, 0, 1].into(),
+            PortRange::new(0, 0).unwrap(),
+            None,
+        );
+
+        InletInterceptorImpl::new(
+            Arc::new(DummySecureChannelController {}),
+            Default::default(),
+            inlet_map,
+            TopicAllowlist::new(Some(allowed_topics)).unwrap(),
+        )
+    }
+
+    #[allow(non_snake_case)]
+    #[ockam_macros::test(timeout = 5_000)]
+    async fn interceptor__produce_request_with_denied_topics__authorization_error_for_denied_topics(
+        context: &mut Context,
+    ) -> ockam::Result<()> {
+        let interceptor = interceptor_with_allowed_topics(vec!["orders-*".to_string()]);
+        let api_version = 7;
+
+        let mut topic_data = IndexMap::new();
+        for (name, partitions) in [("orders-eu", vec![0]), ("users", vec![0, 1])] {
+            topic_data.insert(
+                topic_name(name),
+                TopicProduceData::builder()
+                    .partition_data(
+                        partitions
+                            .into_iter()
+                            .map(|index| PartitionProduceData::builder().index(index).build())
+                            .collect::<Result<_, _>>()
+                            .unwrap(),
+                    )
+                    .build()
+                    .unwrap(),
+            );
+        }
+        let request = ProduceRequest::builder()
+            .acks(1)
+            .topic_data(topic_data)
+            .build()
+            .unwrap();
+
+        // only the allowed topic is sent to the brokers
+        let forwarded = interceptor
+            .intercept_request(
+                context,
+                encode_request(
+                    &request_header(ApiKey::ProduceKey, api_version, 1),
+                    &request,
+                    api_version,
+                    ApiKey::ProduceKey,
+                )
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+        let forwarded: ProduceRequest = decode_request(ApiKey::ProduceKey, api_version, forwarded);
+        assert_eq!(
+            forwarded.topic_data.keys().collect::<Vec<_>>(),
+            vec![&topic_name("orders-eu")]
+        );
+
+        // the client receives an authorization error for the other topic
+        let mut responses = IndexMap::new();
+        responses.insert(
+            topic_name("orders-eu"),
+            TopicProduceResponse::builder()
+                .partition_responses(vec![PartitionProduceResponse::builder()
+                    .index(0)
+                    .build()
+                    .unwrap()])
+                .build()
+                .unwrap(),
+        );
+        let response = ProduceResponse::builder()
+            .responses(responses)
+            .build()
+            .unwrap();
+        let response = interceptor
+            .intercept_response(
+                context,
+                encode_response(
+                    &response_header(1),
+                    &response,
+                    api_version,
+                    ApiKey::ProduceKey,
+                )
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+        let response: ProduceResponse = decode_response(ApiKey::ProduceKey, api_version, response);
+
+        let orders = &response.responses[&topic_name("orders-eu")];
+        assert_eq!(orders.partition_responses[0].error_code, 0);
+        let users = &response.responses[&topic_name("users")];
+        assert_eq!(
+            users
+                .partition_responses
+                .iter()
+                .map(|p| (p.index, p.error_code))
+                .collect::<Vec<_>>(),
+            vec![
+                (0, ResponseError::TopicAuthorizationFailed.code()),
+                (1, ResponseError::TopicAuthorizationFailed.code())
+            ]
+        );
+        Ok(())
+    }
+
+    #[allow(non_snake_case)]
+    #[ockam_macros::test(timeout = 5_000)]
+    async fn interceptor__fetch_request_with_denied_topics__authorization_error_for_denied_topics(
+        context: &mut Context,
+    ) -> ockam::Result<()> {
+        let interceptor = interceptor_with_allowed_topics(vec!["orders-*".to_string()]);
+        let api_version = 12;
+
+        let request = FetchRequest::builder()
+            .topics(
+                ["orders-eu", "users", "orders-us"]
+                    .into_iter()
+                    .map(|name| {
+                        FetchTopic::builder()
+                            .topic(topic_name(name))
+                            .partitions(vec![FetchPartition::builder()
+                                .partition(0)
+                                .build()
+                                .unwrap()])
+                            .build()
+                            .unwrap()
+                    })
+                    .collect(),
+            )
+            .build()
+            .unwrap();
+
+        let forwarded = interceptor
+            .intercept_request(
+                context,
+                encode_request(
+                    &request_header(ApiKey::FetchKey, api_version, 1),
+                    &request,
+                    api_version,
+                    ApiKey::FetchKey,
+                )
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+        let forwarded: FetchRequest = decode_request(ApiKey::FetchKey, api_version, forwarded);
+        assert_eq!(
+            forwarded
+                .topics
+                .iter()
+                .map(|t| t.topic.0.to_string())
+                .collect::<Vec<_>>(),
+            vec!["orders-eu", "orders-us"]
+        );
+
+        let response = FetchResponse::builder()
+            .responses(
+                ["orders-eu", "orders-us"]
+                    .into_iter()
+                    .map(|name| {
+                        FetchableTopicResponse::builder()
+                            .topic(topic_name(name))
+                            .partitions(vec![PartitionData::builder()
+                                .partition_index(0)
+                                .build()
+                                .unwrap()])
+                            .build()
+                            .unwrap()
+                    })
+                    .collect(),
+            )
+            .build()
+            .unwrap();
+        let response = interceptor
+            .intercept_response(
+                context,
+                encode_response(
+                    &response_header(1),
+                    &response,
+                    api_version,
+                    ApiKey::FetchKey,
+                )
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+        let response: FetchResponse = decode_response(ApiKey::FetchKey, api_version, response);
+        assert_eq!(
+            response
+                .responses
+                .iter()
+                .map(|t| (t.topic.0.to_string(), t.partitions[0].error_code))
+                .collect::<Vec<_>>(),
+            vec![
+                ("orders-eu".to_string(), 0),
+                ("orders-us".to_string(), 0),
+                (
+                    "users".to_string(),
+                    ResponseError::TopicAuthorizationFailed.code()
+                )
+            ]
+        );
+        Ok(())
+    }
+
+    #[allow(non_snake_case)]
+    #[ockam_macros::test(timeout = 5_000)]
+    async fn interceptor__metadata_response_with_denied_topics__topics_removed(
+        context: &mut Context,
+    ) -> ockam::Result<()> {
+        let interceptor = interceptor_with_allowed_topics(vec!["orders-*".to_string()]);
+        let api_version = 9;
+
+        interceptor
+            .intercept_request(
+                context,
+                encode_request(
+                    &request_header(ApiKey::MetadataKey, api_version, 1),
+                    &MetadataRequest::builder().build().unwrap(),
+                    api_version,
+                    ApiKey::MetadataKey,
+                )
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let mut topics = IndexMap::new();
+        for name in ["orders-eu", "users"] {
+            topics.insert(
+                topic_name(name),
+                MetadataResponseTopic::builder().build().unwrap(),
+            );
+        }
+        let response = MetadataResponse::builder().topics(topics).build().unwrap();
+        let response = interceptor
+            .intercept_response(
+                context,
+                encode_response(
+                    &response_header(1),
+                    &response,
+                    api_version,
+                    ApiKey::MetadataKey,
+                )
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+        let response: MetadataResponse =
+            decode_response(ApiKey::MetadataKey, api_version, response);
+        assert_eq!(
+            response.topics.keys().collect::<Vec<_>>(),
+            vec![&topic_name("orders-eu")]
+        );
+
+        // the allowlist can be updated while the interceptor is used
+        interceptor
+            .topic_allowlist
+            .set(Some(vec!["users".to_string()]))
+            .unwrap();
+        assert!(interceptor.topic_allowlist.is_allowed("users"));
+        assert!(!interceptor.topic_allowlist.is_allowed("orders-eu"));
+        Ok(())
+    }
 }
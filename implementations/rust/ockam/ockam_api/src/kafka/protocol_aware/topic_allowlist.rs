@@ -0,0 +1,224 @@
+use glob::Pattern;
+use kafka_protocol::messages::fetch_request::{FetchRequest, FetchTopic};
+use kafka_protocol::messages::fetch_response::{
+    FetchResponse, FetchableTopicResponse, PartitionData,
+};
+use kafka_protocol::messages::metadata_response::MetadataResponse;
+use kafka_protocol::messages::produce_request::ProduceRequest;
+use kafka_protocol::messages::produce_response::{
+    PartitionProduceResponse, ProduceResponse, TopicProduceResponse,
+};
+use kafka_protocol::messages::TopicName;
+use kafka_protocol::ResponseError;
+use ockam_core::compat::sync::{Arc, RwLock};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::Result;
+
+/// List of glob patterns, like `orders-*`, restricting the topics which can go through
+/// a kafka portal. It can be updated while the portal is used.
+///
+/// When no list is set, all the topics are allowed.
+#[derive(Clone, Debug, Default)]
+pub struct TopicAllowlist {
+    patterns: Arc<RwLock<Option<Vec<Pattern>>>>,
+}
+
+impl TopicAllowlist {
+    /// Create an allowlist, return an error if a pattern is not a valid glob pattern
+    pub fn new(patterns: Option<Vec<String>>) -> Result<Self> {
+        Ok(Self {
+            patterns: Arc::new(RwLock::new(Self::parse(patterns)?)),
+        })
+    }
+
+    /// Current list of patterns, None if all the topics are allowed
+    pub fn patterns(&self) -> Option<Vec<String>> {
+        self.patterns
+            .read()
+            .unwrap()
+            .as_ref()
+            .map(|patterns| patterns.iter().map(|p| p.to_string()).collect())
+    }
+
+    /// Replace the list of patterns
+    pub fn set(&self, patterns: Option<Vec<String>>) -> Result<()> {
+        *self.patterns.write().unwrap() = Self::parse(patterns)?;
+        Ok(())
+    }
+
+    /// Return true if a topic matches one of the patterns
+    pub fn is_allowed(&self, topic: &str) -> bool {
+        match self.patterns.read().unwrap().as_ref() {
+            Some(patterns) => patterns.iter().any(|p| p.matches(topic)),
+            None => true,
+        }
+    }
+
+    /// Return true if some topics are not allowed
+    pub(crate) fn is_restricted(&self) -> bool {
+        self.patterns.read().unwrap().is_some()
+    }
+
+    fn parse(patterns: Option<Vec<String>>) -> Result<Option<Vec<Pattern>>> {
+        patterns
+            .map(|patterns| {
+                patterns
+                    .iter()
+                    .map(|p| {
+                        Pattern::new(p).map_err(|e| {
+                            ockam_core::Error::new(
+                                Origin::Api,
+                                Kind::Invalid,
+                                format!("invalid topic pattern '{p}': {e}"),
+                            )
+                        })
+                    })
+                    .collect()
+            })
+            .transpose()
+    }
+}
+
+/// Responses to give back to the client for the topics which were removed from a request
+#[derive(Clone, Debug)]
+pub(crate) enum DeniedTopics {
+    Produce(Vec<(TopicName, TopicProduceResponse)>),
+    Fetch(Vec<FetchableTopicResponse>),
+}
+
+impl DeniedTopics {
+    /// Add the authorization errors of the denied topics to a produce response
+    pub(crate) fn add_to_produce_response(self, response: &mut ProduceResponse) {
+        if let DeniedTopics::Produce(denied) = self {
+            response.responses.extend(denied)
+        }
+    }
+
+    /// Add the authorization errors of the denied topics to a fetch response
+    pub(crate) fn add_to_fetch_response(self, response: &mut FetchResponse) {
+        if let DeniedTopics::Fetch(denied) = self {
+            response.responses.extend(denied)
+        }
+    }
+}
+
+impl TopicAllowlist {
+    /// Remove the topics which are not allowed from a produce request.
+    /// Return the error responses for those topics or None if all the topics are allowed
+    pub(crate) fn filter_produce_request(
+        &self,
+        request: &mut ProduceRequest,
+    ) -> Option<DeniedTopics> {
+        if !self.is_restricted() {
+            return None;
+        }
+        let mut denied = vec![];
+        request.topic_data.retain(|topic_name, topic| {
+            if self.is_allowed(topic_name) {
+                return true;
+            }
+            warn!(
+                "the topic {} is not allowed, the produce request for this topic is rejected",
+                topic_name.0.as_str()
+            );
+            let mut response = TopicProduceResponse::default();
+            for partition in &topic.partition_data {
+                let mut partition_response = PartitionProduceResponse::default();
+                partition_response.index = partition.index;
+                partition_response.error_code = ResponseError::TopicAuthorizationFailed.code();
+                partition_response.base_offset = -1;
+                response.partition_responses.push(partition_response);
+            }
+            denied.push((topic_name.clone(), response));
+            false
+        });
+        if denied.is_empty() {
+            None
+        } else {
+            Some(DeniedTopics::Produce(denied))
+        }
+    }
+
+    /// Remove the topics which are not allowed from a fetch request.
+    /// The topic name is provided by `topic_name` since recent versions of the protocol
+    /// only use topic ids. A topic with an unknown name is not allowed.
+    /// Return the error responses for those topics or None if all the topics are allowed
+    pub(crate) fn filter_fetch_request(
+        &self,
+        request: &mut FetchRequest,
+        topic_name: impl Fn(&FetchTopic) -> Option<String>,
+    ) -> Option<DeniedTopics> {
+        if !self.is_restricted() {
+            return None;
+        }
+        let mut denied = vec![];
+        request.topics.retain(|topic| {
+            let name = topic_name(topic);
+            if name
+                .as_deref()
+                .map(|name| self.is_allowed(name))
+                .unwrap_or(false)
+            {
+                return true;
+            }
+            warn!(
+                "the topic {} is not allowed, the fetch request for this topic is rejected",
+                name.unwrap_or_else(|| topic.topic_id.to_string())
+            );
+            let mut response = FetchableTopicResponse::default();
+            response.topic = topic.topic.clone();
+            response.topic_id = topic.topic_id;
+            for partition in &topic.partitions {
+                let mut partition_data = PartitionData::default();
+                partition_data.partition_index = partition.partition;
+                partition_data.error_code = ResponseError::TopicAuthorizationFailed.code();
+                response.partitions.push(partition_data);
+            }
+            denied.push(response);
+            false
+        });
+        if denied.is_empty() {
+            None
+        } else {
+            Some(DeniedTopics::Fetch(denied))
+        }
+    }
+
+    /// Remove the topics which are not allowed from a metadata response.
+    /// Return true if some topics were removed
+    pub(crate) fn filter_metadata_response(&self, response: &mut MetadataResponse) -> bool {
+        let count = response.topics.len();
+        response
+            .topics
+            .retain(|topic_name, _| self.is_allowed(topic_name));
+        count != response.topics.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_topic_allowlist() -> Result<()> {
+        let allowlist = TopicAllowlist::default();
+        assert!(allowlist.is_allowed("orders"));
+        assert_eq!(allowlist.patterns(), None);
+
+        allowlist.set(Some(vec!["orders-*".to_string(), "payments".to_string()]))?;
+        assert!(allowlist.is_allowed("orders-eu"));
+        assert!(allowlist.is_allowed("payments"));
+        assert!(!allowlist.is_allowed("payments-eu"));
+        assert!(!allowlist.is_allowed("users"));
+
+        // an empty list doesn't allow any topic
+        allowlist.set(Some(vec![]))?;
+        assert!(!allowlist.is_allowed("orders-eu"));
+
+        allowlist.set(None)?;
+        assert!(allowlist.is_allowed("users"));
+
+        assert!(TopicAllowlist::new(Some(vec!["orders-[".to_string()])).is_err());
+        Ok(())
+    }
+}
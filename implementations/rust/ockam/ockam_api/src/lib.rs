@@ -21,18 +21,23 @@ extern crate tracing;
 
 pub mod address;
 pub mod authenticator;
+pub mod bench_target;
 pub mod cli_state;
 pub mod cloud;
 pub mod config;
+pub mod discard;
 pub mod echoer;
 pub mod enroll;
 pub mod error;
 pub mod hop;
 pub mod kafka;
+#[cfg(feature = "telemetry")]
+pub mod metrics;
 pub mod minicbor_url;
 pub mod nodes;
 pub mod okta;
 pub mod port_range;
+pub mod tracer;
 pub mod uppercase;
 mod version;
 
@@ -49,6 +54,6 @@ mod util;
 pub use cli_state::*;
 pub use influxdb_token_lease::*;
 pub use nodes::service::default_address::*;
-pub use session::sessions::ConnectionStatus;
+pub use session::sessions::{ConnectionStatus, ConnectionTransition, SessionLiveness};
 pub use util::*;
 pub use version::*;
@@ -192,7 +192,7 @@ fn to_socket_addr(url: Url) -> Option<SocketAddr> {
 }
 
 /// Return the tracing endpoint, defined by an environment variable
-fn opentelemetry_endpoint() -> ockam_core::Result<Url> {
+pub(crate) fn opentelemetry_endpoint() -> ockam_core::Result<Url> {
     Ok(get_env_with_default(
         OCKAM_OPENTELEMETRY_ENDPOINT,
         UrlVar::new(ExportingConfiguration::default_opentelemetry_endpoint()?),
@@ -0,0 +1,166 @@
+use ockam_core::env::FromString;
+use ockam_core::errcode::{Kind, Origin};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::sync::{Mutex, OnceLock};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+use crate::logs::LoggingConfiguration;
+
+/// Filter for the log messages of a process. It is either:
+///
+///  - a level, for example `debug`, which applies to the selected crates
+///  - a list of directives, for example `info,ockam_api=debug`, as supported by an `EnvFilter`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogLevelFilter(String);
+
+impl LogLevelFilter {
+    /// Return the level of this filter, if it is not a list of directives
+    pub fn level(&self) -> Option<LevelFilter> {
+        LevelFilter::from_str(&self.0).ok()
+    }
+
+    /// Create an EnvFilter for this filter.
+    /// A level is only applied to the given crates, if any
+    pub(crate) fn env_filter(&self, crates: Option<&[String]>) -> EnvFilter {
+        match self.level() {
+            Some(level) => level_env_filter(level, crates),
+            None => EnvFilter::builder().parse_lossy(&self.0),
+        }
+    }
+}
+
+impl From<tracing_core::Level> for LogLevelFilter {
+    fn from(level: tracing_core::Level) -> Self {
+        LogLevelFilter(level.to_string().to_lowercase())
+    }
+}
+
+impl FromStr for LogLevelFilter {
+    type Err = ockam_core::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let invalid = |reason: String| {
+            ockam_core::Error::new(
+                Origin::Api,
+                Kind::Invalid,
+                format!("invalid log level '{s}': {reason}"),
+            )
+        };
+        if s.is_empty() {
+            return Err(invalid("it must not be empty".to_string()));
+        }
+        // a directive with a target only is valid for an EnvFilter, but it is
+        // more likely to be a misspelled level
+        for directive in s.split(',').map(|d| d.trim()) {
+            if !directive.contains('=') && LevelFilter::from_str(directive).is_err() {
+                return Err(invalid(format!(
+                    "'{directive}' is not one of off, error, warn, info, debug or trace"
+                )));
+            }
+        }
+        EnvFilter::builder()
+            .parse(s)
+            .map_err(|e| invalid(e.to_string()))?;
+        Ok(LogLevelFilter(s.to_string()))
+    }
+}
+
+impl FromString for LogLevelFilter {
+    fn from_string(s: &str) -> ockam_core::Result<Self> {
+        LogLevelFilter::from_str(s)
+    }
+}
+
+impl Display for LogLevelFilter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Create an EnvFilter keeping the log messages of the given crates, or of all crates,
+/// up to the given level
+pub(crate) fn level_env_filter(level: LevelFilter, crates: Option<&[String]>) -> EnvFilter {
+    match crates {
+        Some(crates) => EnvFilter::builder()
+            .with_default_directive(level.into())
+            .parse_lossy(
+                crates
+                    .iter()
+                    .map(|c| format!("{c}={level}"))
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+        None => EnvFilter::default().add_directive(level.into()),
+    }
+}
+
+/// Filter of the log messages of the current process, which can be replaced while it runs
+struct ReloadableLogFilter {
+    handle: reload::Handle<EnvFilter, Registry>,
+    crates: Option<Vec<String>>,
+    current: Mutex<LogLevelFilter>,
+}
+
+static RELOADABLE_LOG_FILTER: OnceLock<ReloadableLogFilter> = OnceLock::new();
+
+/// Create the layer filtering the log messages of the current process.
+/// Its filter can then be replaced with [`set_log_level`]
+pub(crate) fn reloadable_env_filter(
+    logging_configuration: &LoggingConfiguration,
+) -> reload::Layer<EnvFilter, Registry> {
+    let (layer, handle) = reload::Layer::new(logging_configuration.env_filter());
+    let _ = RELOADABLE_LOG_FILTER.set(ReloadableLogFilter {
+        handle,
+        crates: logging_configuration.crates(),
+        current: Mutex::new(logging_configuration.log_level()),
+    });
+    layer
+}
+
+/// Replace the filter of the log messages of the current process, without restarting it
+pub fn set_log_level(log_level: &LogLevelFilter) -> ockam_core::Result<()> {
+    let reloadable = RELOADABLE_LOG_FILTER.get().ok_or_else(|| {
+        ockam_core::Error::new(
+            Origin::Api,
+            Kind::Unsupported,
+            "logging is not enabled for this process",
+        )
+    })?;
+    let mut current = reloadable.current.lock().unwrap();
+    reloadable
+        .handle
+        .reload(log_level.env_filter(reloadable.crates.as_deref()))
+        .map_err(|e| ockam_core::Error::new(Origin::Api, Kind::Internal, e.to_string()))?;
+    *current = log_level.clone();
+    info!("the log level is now {log_level}");
+    Ok(())
+}
+
+/// Return the filter of the log messages of the current process, if logging was set up
+pub fn current_log_level() -> Option<LogLevelFilter> {
+    RELOADABLE_LOG_FILTER
+        .get()
+        .map(|reloadable| reloadable.current.lock().unwrap().clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_levels_are_validated() {
+        assert!(LogLevelFilter::from_str("debug").unwrap().level().is_some());
+        assert!(LogLevelFilter::from_str("OFF").unwrap().level().is_some());
+
+        let directives = LogLevelFilter::from_str("info,ockam_api=trace").unwrap();
+        assert_eq!(directives.level(), None);
+        assert_eq!(directives.to_string(), "info,ockam_api=trace");
+
+        assert!(LogLevelFilter::from_str("").is_err());
+        assert!(LogLevelFilter::from_str("debgu").is_err());
+        assert!(LogLevelFilter::from_str("info,ockam_api=verbose").is_err());
+    }
+}
@@ -1,6 +1,7 @@
 use crate::config::LevelVar;
 use crate::logs::default_values::*;
 use crate::logs::env_variables::*;
+use crate::logs::log_level::level_env_filter;
 use ockam_core::env::{get_env, get_env_with_default, is_set, FromString};
 use std::fmt::{Display, Formatter};
 use std::path::PathBuf;
@@ -8,7 +9,7 @@ use tracing_core::Level;
 use tracing_subscriber::EnvFilter;
 
 use super::{Colored, GlobalErrorHandler, LoggingEnabled};
-use crate::logs::LogFormat;
+use crate::logs::{LogFormat, LogLevelFilter};
 
 /// List of all the configuration parameters relevant for configuring the logs
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -17,6 +18,8 @@ pub struct LoggingConfiguration {
     enabled: LoggingEnabled,
     /// Verbosity required for a given span or log record
     level: Level,
+    /// Filter replacing the level for log records, for example `info,ockam_api=debug`
+    log_level: Option<LogLevelFilter>,
     /// This parameter specifies what to do when there are logging or tracing errors
     global_error_handler: GlobalErrorHandler,
     /// Maximum log file size in bytes
@@ -57,6 +60,7 @@ impl LoggingConfiguration {
         LoggingConfiguration {
             enabled,
             level,
+            log_level: None,
             global_error_handler: global_error_handler_enabled,
             max_size_bytes,
             max_files,
@@ -77,6 +81,14 @@ impl LoggingConfiguration {
         self.level
     }
 
+    /// Return the filter used for log records: either the filter which was set
+    /// on this configuration, or the logging level
+    pub fn log_level(&self) -> LogLevelFilter {
+        self.log_level
+            .clone()
+            .unwrap_or_else(|| LogLevelFilter::from(self.level))
+    }
+
     /// Return the desired global error handler
     pub fn global_error_handler(&self) -> GlobalErrorHandler {
         self.global_error_handler
@@ -120,6 +132,32 @@ impl LoggingConfiguration {
         }
     }
 
+    /// Set a filter for log records, replacing the logging level
+    pub fn set_log_level(self, log_level: LogLevelFilter) -> LoggingConfiguration {
+        LoggingConfiguration {
+            log_level: Some(log_level),
+            ..self
+        }
+    }
+
+    /// Set the format used for log lines
+    pub fn set_format(self, format: LogFormat) -> LoggingConfiguration {
+        LoggingConfiguration { format, ..self }
+    }
+
+    /// Set the maximum size of a log file before it is rotated
+    pub fn set_max_file_size_bytes(self, max_size_bytes: u64) -> LoggingConfiguration {
+        LoggingConfiguration {
+            max_size_bytes,
+            ..self
+        }
+    }
+
+    /// Set the maximum number of log files for a given node
+    pub fn set_max_files(self, max_files: u64) -> LoggingConfiguration {
+        LoggingConfiguration { max_files, ..self }
+    }
+
     /// Set some specific crates
     pub fn set_crates(self, crates: &[&str]) -> LoggingConfiguration {
         LoggingConfiguration {
@@ -148,21 +186,12 @@ impl LoggingConfiguration {
     ///
     ///  - for the configured level
     ///  - for the configured crates
+    ///
+    /// A filter made of several directives is used as it is
     pub fn env_filter(&self) -> EnvFilter {
-        match &self.crates {
-            Some(crates) => {
-                let builder = EnvFilter::builder();
-                builder
-                    .with_default_directive(self.level().into())
-                    .parse_lossy(
-                        crates
-                            .iter()
-                            .map(|c| format!("{c}={}", self.level()))
-                            .collect::<Vec<_>>()
-                            .join(","),
-                    )
-            }
-            None => EnvFilter::default().add_directive(self.level.into()),
+        match &self.log_level {
+            Some(log_level) => log_level.env_filter(self.crates.as_deref()),
+            None => level_env_filter(self.level.into(), self.crates.as_deref()),
         }
     }
 
@@ -225,6 +254,7 @@ impl Display for LoggingConfiguration {
         f.debug_struct("LoggingConfiguration")
             .field("enabled", &self.enabled.to_string())
             .field("level", &self.level().to_string())
+            .field("log_level", &self.log_level().to_string())
             .field(
                 "global_error_handler",
                 &self.global_error_handler.to_string(),
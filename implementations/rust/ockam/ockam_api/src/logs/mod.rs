@@ -14,16 +14,20 @@ mod default_values;
 mod env_variables;
 pub mod exporting_configuration;
 mod log_exporters;
+mod log_level;
 pub mod logging_configuration;
 mod logging_options;
+mod rotating_file;
 pub mod setup;
 mod span_exporters;
 mod tracing_guard;
 mod tracing_options;
 
 pub use current_span::*;
+pub use env_variables::OCKAM_OPENTELEMETRY_EXPORT;
 pub use exporting_configuration::*;
 pub use log_exporters::*;
+pub use log_level::{current_log_level, set_log_level, LogLevelFilter};
 pub use logging_configuration::*;
 pub use logging_options::*;
 pub use setup::*;
@@ -0,0 +1,140 @@
+use std::fs::{create_dir_all, remove_file, rename, File, OpenOptions};
+use std::io;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Log file which is rotated once it reaches a maximum size.
+///
+/// Log lines are written to `<prefix>.log`. When this file is rotated it becomes `<prefix>.1.log`,
+/// the previous `<prefix>.1.log` becomes `<prefix>.2.log` and so on, until the maximum number of files
+/// is reached and the oldest file is removed
+pub(crate) struct RotatingFile {
+    directory: PathBuf,
+    prefix: String,
+    max_size_bytes: u64,
+    max_files: u64,
+    file: File,
+    size: u64,
+}
+
+impl RotatingFile {
+    /// Open the current log file in the given directory, or create it.
+    /// A maximum size of 0 disables the rotation
+    pub(crate) fn create(
+        directory: &Path,
+        prefix: &str,
+        max_size_bytes: u64,
+        max_files: u64,
+    ) -> io::Result<Self> {
+        create_dir_all(directory)?;
+        let path = Self::file_path(directory, prefix, 0);
+        let file = Self::open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            directory: directory.to_path_buf(),
+            prefix: prefix.to_string(),
+            max_size_bytes,
+            max_files,
+            file,
+            size,
+        })
+    }
+
+    /// Return the path of the current log file for index 0, or of a rotated log file
+    fn file_path(directory: &Path, prefix: &str, index: u64) -> PathBuf {
+        if index == 0 {
+            directory.join(format!("{prefix}.log"))
+        } else {
+            directory.join(format!("{prefix}.{index}.log"))
+        }
+    }
+
+    fn open(path: &Path) -> io::Result<File> {
+        OpenOptions::new().create(true).append(true).open(path)
+    }
+
+    /// Shift the rotated files, and start a new current log file
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        let current = Self::file_path(&self.directory, &self.prefix, 0);
+        let rotated_files = self.max_files.saturating_sub(1);
+        if rotated_files == 0 {
+            self.file.set_len(0)?;
+            self.size = 0;
+            return Ok(());
+        }
+
+        let oldest = Self::file_path(&self.directory, &self.prefix, rotated_files);
+        if oldest.exists() {
+            remove_file(oldest)?;
+        }
+        for index in (1..rotated_files).rev() {
+            let path = Self::file_path(&self.directory, &self.prefix, index);
+            if path.exists() {
+                rename(
+                    path,
+                    Self::file_path(&self.directory, &self.prefix, index + 1),
+                )?;
+            }
+        }
+        rename(&current, Self::file_path(&self.directory, &self.prefix, 1))?;
+        self.file = Self::open(&current)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.max_size_bytes > 0
+            && self.size > 0
+            && self.size + buf.len() as u64 > self.max_size_bytes
+        {
+            self.rotate()?;
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{read_dir, read_to_string};
+    use tempfile::tempdir;
+
+    #[test]
+    fn log_files_are_rotated_when_they_reach_their_maximum_size() {
+        let directory = tempdir().unwrap();
+        let mut file = RotatingFile::create(directory.path(), "stdout", 10, 3).unwrap();
+        for line in ["line 1\n", "line 2\n", "line 3\n", "line 4\n"] {
+            file.write_all(line.as_bytes()).unwrap();
+        }
+        file.flush().unwrap();
+
+        // only the maximum number of files is kept
+        assert_eq!(read_dir(directory.path()).unwrap().count(), 3);
+        let content = |name: &str| read_to_string(directory.path().join(name)).unwrap();
+        assert_eq!(content("stdout.log"), "line 4\n");
+        assert_eq!(content("stdout.1.log"), "line 3\n");
+        assert_eq!(content("stdout.2.log"), "line 2\n");
+    }
+
+    #[test]
+    fn a_single_log_file_is_truncated() {
+        let directory = tempdir().unwrap();
+        let mut file = RotatingFile::create(directory.path(), "stdout", 10, 1).unwrap();
+        file.write_all(b"line 1\n").unwrap();
+        file.write_all(b"line 2\n").unwrap();
+        file.flush().unwrap();
+
+        assert_eq!(read_dir(directory.path()).unwrap().count(), 1);
+        let content = read_to_string(directory.path().join("stdout.log")).unwrap();
+        assert_eq!(content, "line 2\n");
+    }
+}
@@ -15,7 +15,6 @@ use std::io::{empty, stdout};
 use tonic::metadata::*;
 use tracing_appender::non_blocking::NonBlocking;
 use tracing_appender::non_blocking::WorkerGuard;
-use tracing_appender::rolling::{RollingFileAppender, Rotation};
 use tracing_core::Subscriber;
 use tracing_opentelemetry::OpenTelemetryLayer;
 use tracing_subscriber::fmt::format::{DefaultFields, Format};
@@ -27,6 +26,8 @@ use tracing_subscriber::{fmt, layer::SubscriberExt, registry};
 use ockam_node::Executor;
 
 use crate::journeys::APP_NAME;
+use crate::logs::log_level::reloadable_env_filter;
+use crate::logs::rotating_file::RotatingFile;
 use crate::logs::tracing_guard::TracingGuard;
 use crate::logs::{ExportingConfiguration, GlobalErrorHandler, LoggingConfiguration};
 use crate::logs::{LogFormat, OckamSpanExporter};
@@ -99,7 +100,7 @@ impl LoggingTracing {
 
         // initialize the tracing subscriber with all the layers
         let layers = registry()
-            .with(logging_configuration.env_filter())
+            .with(reloadable_env_filter(logging_configuration))
             .with(tracing_error::ErrorLayer::default())
             .with(tracing_layer)
             .with(logging_layer);
@@ -124,7 +125,7 @@ impl LoggingTracing {
     pub fn setup_local_logging_only(logging_configuration: &LoggingConfiguration) -> TracingGuard {
         let (appender, worker_guard) = make_logging_appender(logging_configuration);
         if logging_configuration.is_enabled() {
-            let layers = registry().with(logging_configuration.env_filter());
+            let layers = registry().with(reloadable_env_filter(logging_configuration));
             let result = match logging_configuration.format() {
                 LogFormat::Pretty => layers.with(appender.pretty()).try_init(),
                 LogFormat::Json => layers.with(appender.json()).try_init(),
@@ -158,7 +159,7 @@ impl LoggingTracing {
 
         // initialize the tracing subscriber with all the layers
         let result = registry()
-            .with(logging_configuration.env_filter())
+            .with(reloadable_env_filter(logging_configuration))
             .with(tracing_error::ErrorLayer::default())
             .with(tracing_layer)
             .try_init();
@@ -319,15 +320,15 @@ where
     let (writer, guard) = match logging_configuration.log_dir() {
         // If a node dir path is not provided, log to stdout.
         None => tracing_appender::non_blocking(stdout()),
-        // If a log directory is provided, log to a rolling file appender.
+        // If a log directory is provided, log to a file rotated when it reaches its maximum size.
         Some(log_dir) => {
-            let r = RollingFileAppender::builder()
-                .rotation(Rotation::DAILY)
-                .max_log_files(logging_configuration.max_files() as usize)
-                .filename_prefix("stdout")
-                .filename_suffix("log")
-                .build(log_dir)
-                .expect("Failed to create rolling file appender");
+            let r = RotatingFile::create(
+                &log_dir,
+                "stdout",
+                logging_configuration.max_file_size_bytes(),
+                logging_configuration.max_files(),
+            )
+            .expect("Failed to create the log file");
             tracing_appender::non_blocking(r)
         }
     };
@@ -376,7 +377,7 @@ fn create_tracer<S: SpanExporter + 'static>(
 
 /// Make a resource representing the current application being traced.
 /// The service name is used as a "dataset" by Honeycomb
-fn make_resource(app_name: String) -> Resource {
+pub(crate) fn make_resource(app_name: String) -> Resource {
     let host_name = gethostname().to_string_lossy().to_string();
     Resource::new(vec![
         KeyValue::new(
@@ -398,7 +399,7 @@ fn make_resource(app_name: String) -> Resource {
 /// Then the OCKAM_OPENTELEMETRY_HEADERS variable can be defined as:
 /// export OCKAM_OPENTELEMETRY_HEADERS="x-honeycomb-team=YOUR_API_KEY,x-honeycomb-dataset=YOUR_DATASET"
 ///
-fn get_otlp_headers() -> MetadataMap {
+pub(crate) fn get_otlp_headers() -> MetadataMap {
     match std::env::var("OCKAM_OPENTELEMETRY_HEADERS") {
         Ok(headers) => {
             match headers.split_once('=') {
@@ -0,0 +1,150 @@
+use std::sync::Arc;
+
+use opentelemetry::metrics::{Meter, MeterProvider, Unit};
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::metrics::SdkMeterProvider;
+
+use crate::error::ApiError;
+use crate::logs::setup::{get_otlp_headers, make_resource};
+use crate::metrics::{MetricsConfiguration, NodeMetrics};
+
+/// Label used to identify the node exporting a metric
+const NODE_NAME_LABEL: &str = "node_name";
+
+/// Label used to identify the resource (inlet, outlet, relay or project) concerned by a metric
+const ALIAS_LABEL: &str = "alias";
+
+/// Create a meter provider exporting metrics periodically to an OpenTelemetry collector using gRPC.
+/// This function must be called from a Tokio runtime.
+pub fn create_meter_provider(
+    configuration: &MetricsConfiguration,
+) -> ockam_core::Result<SdkMeterProvider> {
+    let exporter = opentelemetry_otlp::new_exporter()
+        .tonic()
+        .with_endpoint(configuration.opentelemetry_endpoint().to_string())
+        .with_timeout(configuration.export_timeout())
+        .with_metadata(get_otlp_headers());
+
+    opentelemetry_otlp::new_pipeline()
+        .metrics(opentelemetry_sdk::runtime::Tokio)
+        .with_exporter(exporter)
+        .with_period(configuration.export_interval())
+        .with_timeout(configuration.export_timeout())
+        .with_resource(make_resource("local node".to_string()))
+        .build()
+        .map_err(ApiError::core)
+}
+
+/// Register the instruments reading the metrics of a node.
+/// Their values are collected by the meter provider every time metrics are exported.
+pub fn register_node_metrics(
+    meter_provider: &SdkMeterProvider,
+    node_name: &str,
+    metrics: Arc<NodeMetrics>,
+) {
+    let meter = meter_provider.versioned_meter(
+        "ockam",
+        Some(env!("CARGO_PKG_VERSION")),
+        None::<&'static str>,
+        None,
+    );
+    register_portal_metrics(&meter, node_name, metrics.clone());
+    register_secure_channel_metrics(&meter, node_name, metrics.clone());
+    register_relay_metrics(&meter, node_name, metrics.clone());
+    register_credential_metrics(&meter, node_name, metrics);
+}
+
+fn register_portal_metrics(meter: &Meter, node_name: &str, metrics: Arc<NodeMetrics>) {
+    let name = node_name.to_string();
+    let connections_metrics = metrics.clone();
+    meter
+        .u64_observable_gauge("ockam.portal.connections")
+        .with_description("Number of open connections of an inlet or an outlet")
+        .with_callback(move |observer| {
+            for (kind, alias, count) in connections_metrics.portal_connections() {
+                observer.observe(count, &portal_labels(&name, &kind.to_string(), &alias));
+            }
+        })
+        .init();
+
+    let name = node_name.to_string();
+    meter
+        .u64_observable_counter("ockam.portal.forwarded_bytes")
+        .with_description("Number of bytes forwarded by the connections of an inlet or an outlet")
+        .with_unit(Unit::new("By"))
+        .with_callback(move |observer| {
+            for (kind, alias, bytes) in metrics.forwarded_bytes() {
+                observer.observe(bytes, &portal_labels(&name, &kind.to_string(), &alias));
+            }
+        })
+        .init();
+}
+
+fn register_secure_channel_metrics(meter: &Meter, node_name: &str, metrics: Arc<NodeMetrics>) {
+    let name = node_name.to_string();
+    meter
+        .u64_observable_counter("ockam.secure_channel.handshakes")
+        .with_description("Number of secure channel handshakes initiated by the node")
+        .with_callback(move |observer| {
+            let (succeeded, failed) = metrics.secure_channel_handshakes();
+            for (result, count) in [("success", succeeded), ("failure", failed)] {
+                observer.observe(
+                    count,
+                    &[
+                        KeyValue::new(NODE_NAME_LABEL, name.clone()),
+                        KeyValue::new("result", result),
+                    ],
+                );
+            }
+        })
+        .init();
+}
+
+fn register_relay_metrics(meter: &Meter, node_name: &str, metrics: Arc<NodeMetrics>) {
+    let name = node_name.to_string();
+    meter
+        .f64_observable_gauge("ockam.relay.heartbeat.round_trip_time")
+        .with_description("Round trip time of the last heartbeat sent to a relay")
+        .with_unit(Unit::new("s"))
+        .with_callback(move |observer| {
+            for (alias, round_trip_time) in metrics.relay_round_trip_times() {
+                observer.observe(
+                    round_trip_time.as_secs_f64(),
+                    &[
+                        KeyValue::new(NODE_NAME_LABEL, name.clone()),
+                        KeyValue::new(ALIAS_LABEL, alias),
+                    ],
+                );
+            }
+        })
+        .init();
+}
+
+fn register_credential_metrics(meter: &Meter, node_name: &str, metrics: Arc<NodeMetrics>) {
+    let name = node_name.to_string();
+    meter
+        .u64_observable_gauge("ockam.credential.expires_in")
+        .with_description("Number of seconds before the project credential of the node expires")
+        .with_unit(Unit::new("s"))
+        .with_callback(move |observer| {
+            if let Some((project, expires_in)) = metrics.credential_expires_in() {
+                observer.observe(
+                    expires_in,
+                    &[
+                        KeyValue::new(NODE_NAME_LABEL, name.clone()),
+                        KeyValue::new(ALIAS_LABEL, project),
+                    ],
+                );
+            }
+        })
+        .init();
+}
+
+fn portal_labels(node_name: &str, kind: &str, alias: &str) -> [KeyValue; 3] {
+    [
+        KeyValue::new(NODE_NAME_LABEL, node_name.to_string()),
+        KeyValue::new(ALIAS_LABEL, alias.to_string()),
+        KeyValue::new("portal", kind.to_string()),
+    ]
+}
@@ -0,0 +1,87 @@
+use crate::logs::exporting_configuration::opentelemetry_endpoint;
+use ockam_core::env::get_env_with_default;
+use std::time::Duration;
+use url::Url;
+
+/// Decides if the metrics of a node should be exported. Accepted values, see FromString<bool>. For example; true, false, 1, 0
+pub(crate) const OCKAM_METRICS_EXPORT: &str = "OCKAM_METRICS_EXPORT";
+
+/// Time between two exports of the metrics of a node. Accepted values, see DurationVar. For example: 30s
+pub(crate) const OCKAM_METRICS_EXPORT_INTERVAL: &str = "OCKAM_METRICS_EXPORT_INTERVAL";
+
+/// Timeout for trying to export metrics to the endpoint. Accepted values, see DurationVar. For example: 5s
+pub(crate) const OCKAM_METRICS_EXPORT_TIMEOUT: &str = "OCKAM_METRICS_EXPORT_TIMEOUT";
+
+/// Default time between two exports of the metrics of a node
+pub(crate) const DEFAULT_METRICS_EXPORT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Default timeout for exporting metrics
+pub(crate) const DEFAULT_METRICS_EXPORT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The metrics configuration contains the parameters needed to export the metrics of a node
+/// to an OpenTelemetry collector.
+///
+/// Metrics are sent to the same collector as spans and log records.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetricsConfiguration {
+    /// If true, the metrics of the node are exported
+    enabled: bool,
+    /// Time between two exports of the metrics
+    export_interval: Duration,
+    /// Maximum time for exporting the metrics
+    export_timeout: Duration,
+    /// Url of the OpenTelemetry collector
+    opentelemetry_endpoint: Url,
+}
+
+impl MetricsConfiguration {
+    /// Create a metrics configuration from the environment variables
+    pub fn from_env() -> ockam_core::Result<MetricsConfiguration> {
+        Ok(MetricsConfiguration {
+            enabled: get_env_with_default(OCKAM_METRICS_EXPORT, false)?,
+            export_interval: get_env_with_default(
+                OCKAM_METRICS_EXPORT_INTERVAL,
+                DEFAULT_METRICS_EXPORT_INTERVAL,
+            )?,
+            export_timeout: get_env_with_default(
+                OCKAM_METRICS_EXPORT_TIMEOUT,
+                DEFAULT_METRICS_EXPORT_TIMEOUT,
+            )?,
+            opentelemetry_endpoint: opentelemetry_endpoint()?,
+        })
+    }
+
+    /// Export the metrics, even if they are not enabled with an environment variable
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = self.enabled || enabled;
+        self
+    }
+
+    /// Set the time between two exports of the metrics
+    pub fn with_export_interval(mut self, export_interval: Option<Duration>) -> Self {
+        if let Some(export_interval) = export_interval {
+            self.export_interval = export_interval;
+        }
+        self
+    }
+
+    /// Return true if the metrics of the node must be exported
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Return the time between two exports of the metrics
+    pub fn export_interval(&self) -> Duration {
+        self.export_interval
+    }
+
+    /// Return the maximum time for exporting the metrics
+    pub fn export_timeout(&self) -> Duration {
+        self.export_timeout
+    }
+
+    /// Return the URL where to export the metrics
+    pub fn opentelemetry_endpoint(&self) -> Url {
+        self.opentelemetry_endpoint.clone()
+    }
+}
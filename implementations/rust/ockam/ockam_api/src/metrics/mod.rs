@@ -0,0 +1,15 @@
+/// This module exports the runtime metrics of a node to an OpenTelemetry collector.
+///
+/// It is only compiled when the `telemetry` feature is enabled:
+///
+///  - `NodeMetrics` accumulates the measurements of a node: portal connections, forwarded bytes,
+///    secure channel handshakes, relay round trip times and credential expiration.
+///  - `create_meter_provider` creates an OTLP pipeline, exporting those measurements periodically.
+///
+mod exporter;
+mod metrics_configuration;
+mod node_metrics;
+
+pub use exporter::*;
+pub use metrics_configuration::*;
+pub use node_metrics::*;
@@ -0,0 +1,213 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Display, Formatter};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Kind of portal resource owning a connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PortalKind {
+    Inlet,
+    Outlet,
+}
+
+impl Display for PortalKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PortalKind::Inlet => f.write_str("inlet"),
+            PortalKind::Outlet => f.write_str("outlet"),
+        }
+    }
+}
+
+/// Measurement of an open portal connection, attributed to the inlet or outlet which owns it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortalConnectionSample {
+    pub(crate) kind: PortalKind,
+    pub(crate) alias: String,
+    pub(crate) connection: String,
+    pub(crate) bytes: u64,
+}
+
+impl PortalConnectionSample {
+    pub fn new(kind: PortalKind, alias: &str, connection: &str, bytes: u64) -> Self {
+        Self {
+            kind,
+            alias: alias.to_string(),
+            connection: connection.to_string(),
+            bytes,
+        }
+    }
+}
+
+/// Runtime metrics of a node.
+///
+/// The secure channel handshakes are counted as they happen. The other values are
+/// sampled periodically by the node manager, since they are read from its registries,
+/// and the exported instruments read the last sample.
+#[derive(Debug, Default)]
+pub struct NodeMetrics {
+    successful_handshakes: AtomicU64,
+    failed_handshakes: AtomicU64,
+    sample: Mutex<NodeMetricsSample>,
+}
+
+#[derive(Debug, Default)]
+struct NodeMetricsSample {
+    portal_connections: BTreeMap<(PortalKind, String), u64>,
+    forwarded_bytes: BTreeMap<(PortalKind, String), u64>,
+    connection_bytes: HashMap<String, u64>,
+    relay_round_trip_times: BTreeMap<String, Duration>,
+    credential_expires_in: Option<(String, u64)>,
+}
+
+impl NodeMetrics {
+    /// Count the result of a secure channel handshake initiated by the node
+    pub fn record_secure_channel_handshake(&self, succeeded: bool) {
+        let counter = if succeeded {
+            &self.successful_handshakes
+        } else {
+            &self.failed_handshakes
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Update the connection counts and the forwarded bytes with the currently open connections.
+    ///
+    /// The forwarded bytes of a resource only increase: the bytes of each connection are added
+    /// to its total as the difference with the previous sample. The bytes forwarded by a
+    /// connection between its last sample and its closing are not counted.
+    pub fn update_portal_connections(&self, connections: Vec<PortalConnectionSample>) {
+        let mut sample = self.sample.lock().unwrap();
+        let mut connection_bytes = HashMap::with_capacity(connections.len());
+        for counts in sample.portal_connections.values_mut() {
+            *counts = 0;
+        }
+
+        for connection in connections {
+            let key = (connection.kind, connection.alias);
+            *sample.portal_connections.entry(key.clone()).or_default() += 1;
+
+            let previous_bytes = sample
+                .connection_bytes
+                .get(&connection.connection)
+                .copied()
+                .unwrap_or_default();
+            *sample.forwarded_bytes.entry(key).or_default() +=
+                connection.bytes.saturating_sub(previous_bytes);
+            connection_bytes.insert(connection.connection, connection.bytes);
+        }
+        sample.connection_bytes = connection_bytes;
+    }
+
+    /// Update the round trip time of the last heartbeat of each relay, indexed by relay alias
+    pub fn update_relay_round_trip_times(&self, round_trip_times: BTreeMap<String, Duration>) {
+        self.sample.lock().unwrap().relay_round_trip_times = round_trip_times;
+    }
+
+    /// Update the number of seconds before the project credential of the node expires
+    pub fn update_credential_expires_in(&self, credential_expires_in: Option<(String, u64)>) {
+        self.sample.lock().unwrap().credential_expires_in = credential_expires_in;
+    }
+
+    /// Return the number of secure channel handshakes which succeeded and failed
+    pub fn secure_channel_handshakes(&self) -> (u64, u64) {
+        (
+            self.successful_handshakes.load(Ordering::Relaxed),
+            self.failed_handshakes.load(Ordering::Relaxed),
+        )
+    }
+
+    /// Return the number of open connections for each inlet and outlet
+    pub fn portal_connections(&self) -> Vec<(PortalKind, String, u64)> {
+        let sample = self.sample.lock().unwrap();
+        sample
+            .portal_connections
+            .iter()
+            .map(|((kind, alias), count)| (*kind, alias.clone(), *count))
+            .collect()
+    }
+
+    /// Return the total number of bytes forwarded by each inlet and outlet
+    pub fn forwarded_bytes(&self) -> Vec<(PortalKind, String, u64)> {
+        let sample = self.sample.lock().unwrap();
+        sample
+            .forwarded_bytes
+            .iter()
+            .map(|((kind, alias), bytes)| (*kind, alias.clone(), *bytes))
+            .collect()
+    }
+
+    /// Return the round trip time of the last heartbeat of each relay
+    pub fn relay_round_trip_times(&self) -> Vec<(String, Duration)> {
+        let sample = self.sample.lock().unwrap();
+        sample
+            .relay_round_trip_times
+            .iter()
+            .map(|(alias, rtt)| (alias.clone(), *rtt))
+            .collect()
+    }
+
+    /// Return the project of the node credential and the number of seconds before it expires
+    pub fn credential_expires_in(&self) -> Option<(String, u64)> {
+        self.sample.lock().unwrap().credential_expires_in.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forwarded_bytes_only_increase() {
+        let metrics = NodeMetrics::default();
+        metrics.update_portal_connections(vec![
+            PortalConnectionSample::new(PortalKind::Inlet, "db", "c1", 100),
+            PortalConnectionSample::new(PortalKind::Inlet, "db", "c2", 50),
+            PortalConnectionSample::new(PortalKind::Outlet, "db", "c3", 10),
+        ]);
+        assert_eq!(
+            metrics.portal_connections(),
+            vec![
+                (PortalKind::Inlet, "db".to_string(), 2),
+                (PortalKind::Outlet, "db".to_string(), 1)
+            ]
+        );
+        assert_eq!(
+            metrics.forwarded_bytes(),
+            vec![
+                (PortalKind::Inlet, "db".to_string(), 150),
+                (PortalKind::Outlet, "db".to_string(), 10)
+            ]
+        );
+
+        // c1 is closed, c2 forwards more bytes and c4 is opened
+        metrics.update_portal_connections(vec![
+            PortalConnectionSample::new(PortalKind::Inlet, "db", "c2", 80),
+            PortalConnectionSample::new(PortalKind::Inlet, "db", "c4", 5),
+        ]);
+        assert_eq!(
+            metrics.portal_connections(),
+            vec![
+                (PortalKind::Inlet, "db".to_string(), 2),
+                (PortalKind::Outlet, "db".to_string(), 0)
+            ]
+        );
+        assert_eq!(
+            metrics.forwarded_bytes(),
+            vec![
+                (PortalKind::Inlet, "db".to_string(), 185),
+                (PortalKind::Outlet, "db".to_string(), 10)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_secure_channel_handshakes() {
+        let metrics = NodeMetrics::default();
+        metrics.record_secure_channel_handshake(true);
+        metrics.record_secure_channel_handshake(true);
+        metrics.record_secure_channel_handshake(false);
+        assert_eq!(metrics.secure_channel_handshakes(), (2, 1));
+    }
+}
@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::nodes::connection::{Changes, ConnectionBuilder, Instantiator};
+use crate::nodes::NodeManager;
+use crate::try_address_to_multiaddr;
+
+use ockam_core::{async_trait, Error, Route};
+use ockam_multiaddr::proto::Channel;
+use ockam_multiaddr::{Match, MultiAddr, Protocol};
+use ockam_node::Context;
+
+/// Replaces a `/channel/<name>` segment with the address of the secure channel
+/// kept under that name
+pub(crate) struct NamedSecureChannelInstantiator {}
+
+impl NamedSecureChannelInstantiator {
+    pub(crate) fn new() -> Self {
+        Self {}
+    }
+}
+
+#[async_trait]
+impl Instantiator for NamedSecureChannelInstantiator {
+    fn matches(&self) -> Vec<Match> {
+        vec![Channel::CODE.into()]
+    }
+
+    async fn instantiate(
+        &self,
+        ctx: Arc<Context>,
+        node_manager: &NodeManager,
+        _transport_route: Route,
+        extracted: (MultiAddr, MultiAddr, MultiAddr),
+    ) -> Result<Changes, Error> {
+        let (before, channel_piece, after) = extracted;
+        let name = channel_piece
+            .first()
+            .and_then(|p| p.cast::<Channel>().map(|name| name.to_string()))
+            .ok_or_else(|| ApiError::core("missing channel protocol in multiaddr"))?;
+
+        let sc = node_manager
+            .resolve_named_secure_channel(&ctx, &name)
+            .await?;
+        let channel_multiaddr = try_address_to_multiaddr(sc.encryptor_address())?;
+
+        // the channel is not owned by the connection, so it is not
+        // added to the encryptors which are deleted when the connection is closed
+        Ok(Changes {
+            current_multiaddr: ConnectionBuilder::combine(before, channel_multiaddr, after)?,
+            flow_control_id: Some(sc.flow_control_id().clone()),
+            secure_channel_encryptors: vec![],
+            tcp_connection: None,
+            websocket_connection: None,
+            udp_connection: None,
+        })
+    }
+}
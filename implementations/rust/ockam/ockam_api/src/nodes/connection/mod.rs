@@ -1,6 +1,9 @@
+mod channel;
 mod plain_tcp;
 mod project;
 mod secure;
+mod udp;
+mod websocket;
 
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::flow_control::FlowControlId;
@@ -15,11 +18,14 @@ use crate::error::ApiError;
 use crate::local_multiaddr_to_route;
 use crate::nodes::service::default_address::DefaultAddress;
 use crate::nodes::NodeManager;
+pub(crate) use channel::NamedSecureChannelInstantiator;
 pub(crate) use plain_tcp::PlainTcpInstantiator;
 pub(crate) use project::ProjectInstantiator;
 pub(crate) use secure::SecureChannelInstantiator;
 use std::fmt::{Debug, Formatter};
 use std::sync::Arc;
+pub(crate) use udp::UdpInstantiator;
+pub(crate) use websocket::WebSocketInstantiator;
 
 #[derive(Clone)]
 pub struct Connection {
@@ -37,6 +43,10 @@ pub struct Connection {
     pub(crate) secure_channel_encryptors: Vec<Address>,
     /// A TCP worker address if used when instantiating the connection
     pub(crate) tcp_connection: Option<TcpConnection>,
+    /// The address of the WebSocket sender if used when instantiating the connection
+    pub(crate) websocket_connection: Option<Address>,
+    /// The address of the UDP connection worker if used when instantiating the connection
+    pub(crate) udp_connection: Option<Address>,
     /// If a flow control was created
     flow_control_id: Option<FlowControlId>,
 }
@@ -54,7 +64,8 @@ impl Connection {
     pub fn add_default_consumers(&self, ctx: Arc<Context>) {
         self.add_consumer(ctx.clone(), &DefaultAddress::SECURE_CHANNEL_LISTENER.into());
         self.add_consumer(ctx.clone(), &DefaultAddress::UPPERCASE_SERVICE.into());
-        self.add_consumer(ctx, &DefaultAddress::ECHO_SERVICE.into());
+        self.add_consumer(ctx.clone(), &DefaultAddress::ECHO_SERVICE.into());
+        self.add_consumer(ctx, &DefaultAddress::TRACE_SERVICE.into());
     }
 
     pub fn transport_route(&self) -> Route {
@@ -105,6 +116,24 @@ impl Connection {
             }
         }
 
+        if let (Some(address), Some(websocket_transport)) = (
+            self.websocket_connection.as_ref(),
+            node_manager.websocket_transport.as_ref(),
+        ) {
+            if let Err(error) = websocket_transport.disconnect(address.clone()).await {
+                debug!("cannot disconnect websocket worker `{address}`: {error}");
+            }
+        }
+
+        if let (Some(address), Some(udp_transport)) = (
+            self.udp_connection.as_ref(),
+            node_manager.udp_transport.as_ref(),
+        ) {
+            if let Err(error) = udp_transport.disconnect(address).await {
+                debug!("cannot disconnect udp worker `{address}`: {error}");
+            }
+        }
+
         Ok(())
     }
 }
@@ -134,6 +163,8 @@ pub(crate) struct ConnectionBuilder {
     pub(crate) flow_control_id: Option<FlowControlId>,
     pub(crate) secure_channel_encryptors: Vec<Address>,
     pub(crate) tcp_connection: Option<TcpConnection>,
+    pub(crate) websocket_connection: Option<Address>,
+    pub(crate) udp_connection: Option<Address>,
 }
 
 impl Debug for ConnectionBuilder {
@@ -163,6 +194,10 @@ pub struct Changes {
     pub secure_channel_encryptors: Vec<Address>,
     /// Optional, to keep track of tcp worker when created for the connection
     pub tcp_connection: Option<TcpConnection>,
+    /// Optional, to keep track of the websocket sender when created for the connection
+    pub websocket_connection: Option<Address>,
+    /// Optional, to keep track of the udp connection worker when created for the connection
+    pub udp_connection: Option<Address>,
 }
 
 /// Takes in a [`MultiAddr`] and instantiate it, can be implemented for any protocol.
@@ -197,9 +232,31 @@ impl ConnectionBuilder {
             secure_channel_encryptors: vec![],
             flow_control_id: None,
             tcp_connection: None,
+            websocket_connection: None,
+            udp_connection: None,
         }
     }
 
+    /// Start from an existing connection, followed by the rest of the [`MultiAddr`].
+    /// The resources of the existing connection are not owned by the new connection,
+    /// so they are not deleted when the new connection is closed
+    pub fn reusing(
+        multi_addr: MultiAddr,
+        connection: &Connection,
+        rest: MultiAddr,
+    ) -> Result<Self, ockam_core::Error> {
+        Ok(ConnectionBuilder {
+            transport_route: connection.transport_route(),
+            original_multiaddr: multi_addr,
+            current_multiaddr: connection.normalized_addr.clone().concat(&rest)?,
+            secure_channel_encryptors: vec![],
+            flow_control_id: connection.flow_control_id.clone(),
+            tcp_connection: None,
+            websocket_connection: None,
+            udp_connection: None,
+        })
+    }
+
     pub fn build(self) -> Connection {
         Connection {
             transport_route: self.transport_route,
@@ -207,6 +264,8 @@ impl ConnectionBuilder {
             original_addr: self.original_multiaddr,
             secure_channel_encryptors: self.secure_channel_encryptors,
             tcp_connection: self.tcp_connection,
+            websocket_connection: self.websocket_connection,
+            udp_connection: self.udp_connection,
             flow_control_id: self.flow_control_id,
         }
     }
@@ -258,6 +317,28 @@ impl ConnectionBuilder {
                         self.tcp_connection = changes.tcp_connection;
                     }
 
+                    if changes.websocket_connection.is_some() {
+                        if self.websocket_connection.is_some() {
+                            return Err(ockam_core::Error::new(
+                                Origin::Transport,
+                                Kind::Unsupported,
+                                "multiple websocket connections created in a `MultiAddr`",
+                            ));
+                        }
+                        self.websocket_connection = changes.websocket_connection;
+                    }
+
+                    if changes.udp_connection.is_some() {
+                        if self.udp_connection.is_some() {
+                            return Err(ockam_core::Error::new(
+                                Origin::Transport,
+                                Kind::Unsupported,
+                                "multiple udp connections created in a `MultiAddr`",
+                            ));
+                        }
+                        self.udp_connection = changes.udp_connection;
+                    }
+
                     if changes.flow_control_id.is_some() {
                         self.flow_control_id = changes.flow_control_id;
                     }
@@ -276,6 +357,8 @@ impl ConnectionBuilder {
             current_multiaddr: self.current_multiaddr,
             flow_control_id: self.flow_control_id,
             tcp_connection: self.tcp_connection,
+            websocket_connection: self.websocket_connection,
+            udp_connection: self.udp_connection,
         })
     }
 
@@ -5,7 +5,7 @@ use std::sync::Arc;
 
 use crate::nodes::NodeManager;
 use ockam_core::{async_trait, Error, Route};
-use ockam_multiaddr::proto::{DnsAddr, Ip4, Ip6, Tcp};
+use ockam_multiaddr::proto::{Dns, DnsAddr, Ip4, Ip6, Tcp};
 use ockam_multiaddr::{Match, MultiAddr, Protocol};
 use ockam_node::Context;
 
@@ -23,7 +23,7 @@ impl Instantiator for PlainTcpInstantiator {
     fn matches(&self) -> Vec<Match> {
         vec![
             // matches any tcp address followed by a tcp protocol
-            Match::any([DnsAddr::CODE, Ip4::CODE, Ip6::CODE]),
+            Match::any([Dns::CODE, DnsAddr::CODE, Ip4::CODE, Ip6::CODE]),
             Tcp::CODE.into(),
         ]
     }
@@ -37,13 +37,12 @@ impl Instantiator for PlainTcpInstantiator {
     ) -> Result<Changes, Error> {
         let (before, tcp_piece, after) = extracted;
 
-        let mut tcp = multiaddr_to_route(&tcp_piece, &node_manager.tcp_transport)
-            .await
-            .ok_or_else(|| {
-                ApiError::core(format!(
-                    "Couldn't convert MultiAddr to route: tcp_piece={tcp_piece}"
-                ))
-            })?;
+        let mut tcp = multiaddr_to_route(
+            &tcp_piece,
+            &node_manager.tcp_transport,
+            node_manager.tcp_connection_options(),
+        )
+        .await?;
 
         let multiaddr = route_to_multiaddr(&tcp.route).ok_or_else(|| {
             ApiError::core(format!(
@@ -66,6 +65,8 @@ impl Instantiator for PlainTcpInstantiator {
             flow_control_id: tcp.flow_control_id,
             secure_channel_encryptors: vec![],
             tcp_connection: Some(tcp_connection),
+            websocket_connection: None,
+            udp_connection: None,
         })
     }
 }
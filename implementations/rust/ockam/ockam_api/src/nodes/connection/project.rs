@@ -54,13 +54,12 @@ impl Instantiator for ProjectInstantiator {
             node_manager.resolve_project(&project).await?;
 
         debug!(addr = %project_multiaddr, "creating secure channel");
-        let tcp = multiaddr_to_route(&project_multiaddr, &node_manager.tcp_transport)
-            .await
-            .ok_or_else(|| {
-                ApiError::core(format!(
-                    "Couldn't convert MultiAddr to route: project_multiaddr={project_multiaddr}"
-                ))
-            })?;
+        let tcp = multiaddr_to_route(
+            &project_multiaddr,
+            &node_manager.tcp_transport,
+            node_manager.tcp_connection_options(),
+        )
+        .await?;
 
         debug!("create a secure channel to the project {project_identifier}");
         let sc = node_manager
@@ -71,6 +70,7 @@ impl Instantiator for ProjectInstantiator {
                 Some(vec![project_identifier]),
                 None,
                 self.timeout,
+                None,
             )
             .await?;
 
@@ -84,6 +84,8 @@ impl Instantiator for ProjectInstantiator {
             current_multiaddr,
             secure_channel_encryptors: vec![sc.encryptor_address().clone()],
             tcp_connection: tcp.tcp_connection,
+            websocket_connection: None,
+            udp_connection: None,
         })
     }
 }
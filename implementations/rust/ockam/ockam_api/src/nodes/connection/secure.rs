@@ -16,6 +16,7 @@ pub(crate) struct SecureChannelInstantiator {
     identifier: Identifier,
     authorized_identities: Option<Vec<Identifier>>,
     timeout: Option<Duration>,
+    rekey_interval: Option<Duration>,
 }
 
 impl SecureChannelInstantiator {
@@ -28,8 +29,15 @@ impl SecureChannelInstantiator {
             identifier: identifier.clone(),
             authorized_identities,
             timeout,
+            rekey_interval: None,
         }
     }
+
+    /// Renew the keys of the secure channel once `rekey_interval` has elapsed
+    pub(crate) fn with_rekey_interval(mut self, rekey_interval: Option<Duration>) -> Self {
+        self.rekey_interval = rekey_interval;
+        self
+    }
 }
 
 #[async_trait]
@@ -60,6 +68,7 @@ impl Instantiator for SecureChannelInstantiator {
                 self.authorized_identities.clone(),
                 None,
                 self.timeout,
+                self.rekey_interval,
             )
             .await?;
 
@@ -73,6 +82,8 @@ impl Instantiator for SecureChannelInstantiator {
             flow_control_id: Some(sc.flow_control_id().clone()),
             secure_channel_encryptors: vec![sc.encryptor_address().clone()],
             tcp_connection: None,
+            websocket_connection: None,
+            udp_connection: None,
         })
     }
 }
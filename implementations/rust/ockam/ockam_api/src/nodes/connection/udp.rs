@@ -0,0 +1,78 @@
+use crate::error::ApiError;
+use crate::nodes::connection::{Changes, ConnectionBuilder, Instantiator};
+use crate::try_address_to_multiaddr;
+use std::sync::Arc;
+
+use crate::nodes::NodeManager;
+use ockam_core::{async_trait, Error, Route};
+use ockam_multiaddr::proto::{Dns, DnsAddr, Ip4, Ip6, Udp};
+use ockam_multiaddr::{Match, MultiAddr, Protocol};
+use ockam_node::Context;
+
+/// Creates a UDP connection for `/<host>/udp/<port>` addresses.
+pub(crate) struct UdpInstantiator {}
+
+impl UdpInstantiator {
+    pub(crate) fn new() -> Self {
+        Self {}
+    }
+}
+
+#[async_trait]
+impl Instantiator for UdpInstantiator {
+    fn matches(&self) -> Vec<Match> {
+        vec![
+            // matches any host followed by a udp protocol
+            Match::any([Dns::CODE, DnsAddr::CODE, Ip4::CODE, Ip6::CODE]),
+            Udp::CODE.into(),
+        ]
+    }
+
+    async fn instantiate(
+        &self,
+        _ctx: Arc<Context>,
+        node_manager: &NodeManager,
+        _transport_route: Route,
+        extracted: (MultiAddr, MultiAddr, MultiAddr),
+    ) -> Result<Changes, Error> {
+        let (before, udp_piece, after) = extracted;
+
+        let udp_transport = node_manager.udp_transport.as_ref().ok_or_else(|| {
+            ApiError::core(format!(
+                "The node doesn't support UDP connections: {udp_piece}"
+            ))
+        })?;
+
+        let invalid = || ApiError::core(format!("Invalid UDP address: {udp_piece}"));
+        let mut protocols = udp_piece.iter();
+        let host = match protocols.next() {
+            Some(p) if p.code() == Dns::CODE => p.cast::<Dns>().ok_or_else(invalid)?.to_string(),
+            Some(p) if p.code() == DnsAddr::CODE => {
+                p.cast::<DnsAddr>().ok_or_else(invalid)?.to_string()
+            }
+            Some(p) if p.code() == Ip4::CODE => p.cast::<Ip4>().ok_or_else(invalid)?.to_string(),
+            Some(p) if p.code() == Ip6::CODE => {
+                format!("[{}]", *p.cast::<Ip6>().ok_or_else(invalid)?)
+            }
+            _ => return Err(invalid()),
+        };
+        let port = protocols
+            .next()
+            .and_then(|p| p.cast::<Udp>())
+            .ok_or_else(invalid)?;
+
+        let connection = udp_transport.connect(format!("{host}:{}", *port)).await?;
+
+        let multiaddr = try_address_to_multiaddr(&connection)?;
+        let current_multiaddr = ConnectionBuilder::combine(before, multiaddr, after)?;
+
+        Ok(Changes {
+            current_multiaddr,
+            flow_control_id: None,
+            secure_channel_encryptors: vec![],
+            tcp_connection: None,
+            websocket_connection: None,
+            udp_connection: Some(connection),
+        })
+    }
+}
@@ -0,0 +1,81 @@
+use crate::error::ApiError;
+use crate::nodes::connection::{Changes, ConnectionBuilder, Instantiator};
+use crate::try_address_to_multiaddr;
+use std::sync::Arc;
+
+use crate::nodes::NodeManager;
+use ockam_core::{async_trait, Error, Route};
+use ockam_multiaddr::proto::{Tcp, Ws, Wss};
+use ockam_multiaddr::{Match, MultiAddr, Protocol};
+use ockam_node::Context;
+use ockam_transport_websocket::WebSocketConnectionOptions;
+
+/// Creates a WebSocket connection, possibly with TLS and through the HTTP proxy set
+/// with `HTTPS_PROXY`, for `/ws/<host>/tcp/<port>` and `/wss/<host>/tcp/<port>` addresses.
+pub(crate) struct WebSocketInstantiator {}
+
+impl WebSocketInstantiator {
+    pub(crate) fn new() -> Self {
+        Self {}
+    }
+}
+
+#[async_trait]
+impl Instantiator for WebSocketInstantiator {
+    fn matches(&self) -> Vec<Match> {
+        vec![
+            // matches any websocket host followed by a tcp protocol
+            Match::any([Ws::CODE, Wss::CODE]),
+            Tcp::CODE.into(),
+        ]
+    }
+
+    async fn instantiate(
+        &self,
+        _ctx: Arc<Context>,
+        node_manager: &NodeManager,
+        _transport_route: Route,
+        extracted: (MultiAddr, MultiAddr, MultiAddr),
+    ) -> Result<Changes, Error> {
+        let (before, ws_piece, after) = extracted;
+
+        let websocket_transport = node_manager.websocket_transport.as_ref().ok_or_else(|| {
+            ApiError::core(format!(
+                "The node doesn't support WebSocket connections: {ws_piece}"
+            ))
+        })?;
+
+        let invalid = || ApiError::core(format!("Invalid WebSocket address: {ws_piece}"));
+        let mut protocols = ws_piece.iter();
+        let (host, tls) = match protocols.next() {
+            Some(p) if p.code() == Ws::CODE => {
+                (p.cast::<Ws>().ok_or_else(invalid)?.to_string(), false)
+            }
+            Some(p) if p.code() == Wss::CODE => {
+                (p.cast::<Wss>().ok_or_else(invalid)?.to_string(), true)
+            }
+            _ => return Err(invalid()),
+        };
+        let port = protocols
+            .next()
+            .and_then(|p| p.cast::<Tcp>())
+            .ok_or_else(invalid)?;
+
+        let options = WebSocketConnectionOptions::new().with_tls(tls);
+        let sender_address = websocket_transport
+            .connect_with_options(format!("{host}:{}", *port), options)
+            .await?;
+
+        let multiaddr = try_address_to_multiaddr(&sender_address)?;
+        let current_multiaddr = ConnectionBuilder::combine(before, multiaddr, after)?;
+
+        Ok(Changes {
+            current_multiaddr,
+            flow_control_id: None,
+            secure_channel_encryptors: vec![],
+            tcp_connection: None,
+            websocket_connection: Some(sender_address),
+            udp_connection: None,
+        })
+    }
+}
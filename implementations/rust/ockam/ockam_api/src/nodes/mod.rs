@@ -5,6 +5,7 @@ pub mod service;
 
 pub use service::background_node_client::*;
 pub use service::in_memory_node::*;
+pub use service::in_memory_node_builder::*;
 pub use service::policy::*;
 /// The main node-manager service running on remote nodes
 pub use service::{NodeManager, NodeManagerWorker};
@@ -0,0 +1,20 @@
+//! Identity attributes request/response types
+
+use minicbor::{Decode, Encode};
+use ockam::identity::Identifier;
+
+/// Request body to remove the attributes cached by a node for some identities
+#[derive(Clone, Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct FlushAttributesRequest {
+    /// Identities whose attributes are removed. The attributes of all the identities
+    /// are removed when the list is empty
+    #[n(1)] pub identifiers: Vec<Identifier>,
+}
+
+impl FlushAttributesRequest {
+    pub fn new(identifiers: Vec<Identifier>) -> Self {
+        Self { identifiers }
+    }
+}
@@ -1,6 +1,7 @@
 //! Nodemanager API types
 
 use minicbor::{Decode, Encode};
+use serde::Serialize;
 
 ///////////////////-!  RESPONSE BODIES
 
@@ -13,6 +14,9 @@ pub struct NodeStatus {
     #[n(2)] pub status: String,
     #[n(3)] pub workers: u32,
     #[n(4)] pub pid: i32,
+    #[n(5)] pub resources: Option<NodeResources>,
+    /// Log level currently applied by the node, if it writes logs
+    #[n(6)] pub log_level: Option<String>,
 }
 
 impl NodeStatus {
@@ -27,6 +31,62 @@ impl NodeStatus {
             status: status.into(),
             workers,
             pid,
+            resources: None,
+            log_level: None,
+        }
+    }
+
+    pub fn with_resources(mut self, resources: NodeResources) -> Self {
+        self.resources = Some(resources);
+        self
+    }
+
+    pub fn with_log_level(mut self, log_level: Option<String>) -> Self {
+        self.log_level = log_level;
+        self
+    }
+}
+
+/// Runtime statistics of a node
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct NodeResources {
+    /// Resident memory of the node process in bytes, if it can be read on this platform
+    #[n(1)] pub memory: Option<u64>,
+    #[n(2)] pub workers: u32,
+    #[n(3)] pub tcp_connections: u32,
+    #[n(4)] pub tcp_listeners: u32,
+    #[n(5)] pub portal_connections: u32,
+    #[n(6)] pub secure_channels: u32,
+    /// Time since the node was started, in seconds
+    #[n(7)] pub uptime: u64,
+    /// Host name resolutions served from the DNS cache or from the static hosts
+    #[n(8)] pub dns_cache_hits: u64,
+    /// Host name resolutions sent to the DNS resolver
+    #[n(9)] pub dns_cache_misses: u64,
+    /// Host name resolutions which failed
+    #[n(10)] pub dns_resolution_failures: u64,
+    /// Maximum size of a message received on a TCP connection, unless a listener overrides it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[n(11)] pub max_message_size: Option<u64>,
+}
+
+///////////////////-!  REQUEST BODIES
+
+/// Request body to change the log level of a running node
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SetLogLevelRequest {
+    /// Log level, for example `debug`, or list of log filter directives, for example `info,ockam_api=trace`
+    #[n(1)] pub log_level: String,
+}
+
+impl SetLogLevelRequest {
+    pub fn new(log_level: impl Into<String>) -> Self {
+        Self {
+            log_level: log_level.into(),
         }
     }
 }
@@ -0,0 +1,58 @@
+//! Response types for the connections established when a node starts
+
+use std::fmt::{Display, Formatter};
+
+use minicbor::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+
+/// State of a connection which is established when the node starts
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Decode, Encode, Serialize, Deserialize)]
+#[rustfmt::skip]
+#[serde(rename_all = "lowercase")]
+pub enum WarmConnectionState {
+    /// The connection has not been established yet
+    #[n(0)] Pending,
+    /// The connection is established and can be reused
+    #[n(1)] Warmed,
+    /// The last attempt failed, the connection is retried in the background
+    #[n(2)] Failed,
+}
+
+impl Display for WarmConnectionState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WarmConnectionState::Pending => write!(f, "pending"),
+            WarmConnectionState::Warmed => write!(f, "warmed"),
+            WarmConnectionState::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+/// Response body describing a connection established when the node starts
+#[derive(Clone, Debug, Decode, Encode, Serialize, Deserialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct WarmConnectionStatus {
+    /// Address given to `--connect-on-start`
+    #[n(1)] pub addr: String,
+    #[n(2)] pub state: WarmConnectionState,
+    /// Normalized address of the connection, when it is warmed
+    #[n(3)] pub normalized_addr: Option<String>,
+    /// Number of attempts made since the connection was last established
+    #[n(4)] pub attempts: u32,
+    #[n(5)] pub last_failure: Option<String>,
+}
+
+/// Response body when returning the list of connections established when the node starts
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct WarmConnectionList {
+    #[n(1)] pub list: Vec<WarmConnectionStatus>
+}
+
+impl WarmConnectionList {
+    pub fn new(list: Vec<WarmConnectionStatus>) -> Self {
+        Self { list }
+    }
+}
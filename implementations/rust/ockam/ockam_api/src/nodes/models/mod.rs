@@ -2,7 +2,9 @@
 ///
 /// This module is only a type facade and should not have any logic of
 /// its own
+pub mod attributes;
 pub mod base;
+pub mod connection;
 pub mod credentials;
 pub mod flow_controls;
 pub mod policies;
@@ -11,4 +13,5 @@ pub mod relay;
 pub mod secure_channel;
 pub mod services;
 pub mod transport;
+pub mod trust;
 pub mod workers;
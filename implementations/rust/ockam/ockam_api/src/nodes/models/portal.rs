@@ -1,6 +1,9 @@
 //! Inlets and outlet request/response types
 
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
 use std::net::SocketAddr;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -10,11 +13,14 @@ use ockam::route;
 use ockam_abac::Expr;
 use ockam_core::{Address, IncomingAccessControl, Route};
 use ockam_multiaddr::MultiAddr;
+use ockam_transport_tcp::{
+    TcpInletConnectionLimits, TcpInletLoadBalancer, TcpInletLoadBalancing, TcpInletRouteStatus,
+};
 use serde::{Deserialize, Serialize};
 
 use crate::error::ApiError;
 use crate::route_to_multiaddr;
-use crate::session::sessions::ConnectionStatus;
+use crate::session::sessions::{ConnectionStatus, ConnectionTransition, SessionLiveness};
 
 /// Request body to create an inlet
 #[derive(Clone, Debug, Decode, Encode)]
@@ -29,10 +35,10 @@ pub struct CreateInlet {
     #[n(2)] pub(crate) outlet_addr: MultiAddr,
     /// A human-friendly alias for this portal endpoint
     #[b(3)] pub(crate) alias: String,
-    /// An authorised identity for secure channels.
+    /// The identities authorized for the secure channels to the outlets.
     /// Only set for non-project addresses as for projects the project's
-    /// authorised identity will be used.
-    #[n(4)] pub(crate) authorized: Option<Identifier>,
+    /// authorised identity will be used. Any identity is authorized if empty.
+    #[n(4)] pub(crate) authorized: Vec<Identifier>,
     /// A prefix route that will be applied before outlet_addr, and won't be used
     /// to monitor the state of the connection
     #[n(5)] pub(crate) prefix_route: Route,
@@ -47,6 +53,33 @@ pub struct CreateInlet {
     #[n(8)] pub(crate) policy_expression: Option<Expr>,
     /// Create the inlet and wait for the outlet to connect
     #[n(9)] pub(crate) wait_connection: bool,
+    /// Other outlets to distribute the connections to, along with `outlet_addr`
+    #[n(10)] pub(crate) additional_outlet_addrs: Vec<MultiAddr>,
+    /// The strategy used to distribute the connections across the outlets
+    #[n(11)] pub(crate) load_balancing: Option<InletLoadBalancing>,
+    /// The interval after which the keys of the secure channels to the outlets are renewed
+    #[n(12)] pub(crate) secure_channel_rekey_interval: Option<Duration>,
+    /// The maximum number of bytes sent on a connection and not yet acknowledged by the outlet
+    #[n(13)] pub(crate) buffer_size: Option<usize>,
+    /// The maximum number of new connections accepted per second
+    #[n(14)] pub(crate) max_connections_per_second: Option<u32>,
+    /// The maximum number of connections open at the same time
+    #[n(15)] pub(crate) max_concurrent_connections: Option<u64>,
+    /// Send the data read from the TCP clients right away, instead of batching small reads
+    #[n(16)] pub(crate) no_delay: bool,
+    /// The interval between two liveness probes sent through the secure channel of the inlet
+    #[n(17)] pub(crate) probe_interval: Option<Duration>,
+    /// The number of unanswered probes after which the secure channel is established again
+    #[n(18)] pub(crate) max_missed_probes: Option<u32>,
+    /// The name of the identity used for the secure channels to the outlets.
+    /// The identity of the node is used if not set
+    #[n(19)] pub(crate) identity: Option<String>,
+    /// The file mode of the Unix domain socket, when the inlet listens on "unix:<path>"
+    #[n(20)] pub(crate) unix_socket_mode: Option<u32>,
+    /// Replace the file which already exists at the path of the Unix domain socket
+    #[n(21)] pub(crate) replace_unix_socket: bool,
+    /// Read and strip a PROXY protocol v2 header at the start of each client connection
+    #[n(22)] pub(crate) expect_proxy_protocol: bool,
 }
 
 impl CreateInlet {
@@ -62,12 +95,25 @@ impl CreateInlet {
             listen_addr: listen,
             outlet_addr: to,
             alias,
-            authorized: None,
+            authorized: vec![],
             prefix_route,
             suffix_route,
             wait_for_outlet_duration: None,
             policy_expression: None,
             wait_connection,
+            additional_outlet_addrs: vec![],
+            load_balancing: None,
+            secure_channel_rekey_interval: None,
+            buffer_size: None,
+            max_connections_per_second: None,
+            max_concurrent_connections: None,
+            no_delay: false,
+            probe_interval: None,
+            max_missed_probes: None,
+            identity: None,
+            unix_socket_mode: None,
+            replace_unix_socket: false,
+            expect_proxy_protocol: false,
         }
     }
 
@@ -77,19 +123,32 @@ impl CreateInlet {
         alias: String,
         prefix_route: Route,
         suffix_route: Route,
-        auth: Option<Identifier>,
+        authorized: Vec<Identifier>,
         wait_connection: bool,
     ) -> Self {
         Self {
             listen_addr: listen,
             outlet_addr: to,
             alias,
-            authorized: auth,
+            authorized,
             prefix_route,
             suffix_route,
             wait_for_outlet_duration: None,
             policy_expression: None,
             wait_connection,
+            additional_outlet_addrs: vec![],
+            load_balancing: None,
+            secure_channel_rekey_interval: None,
+            buffer_size: None,
+            max_connections_per_second: None,
+            max_concurrent_connections: None,
+            no_delay: false,
+            probe_interval: None,
+            max_missed_probes: None,
+            identity: None,
+            unix_socket_mode: None,
+            replace_unix_socket: false,
+            expect_proxy_protocol: false,
         }
     }
 
@@ -101,6 +160,58 @@ impl CreateInlet {
         self.policy_expression = Some(expression);
     }
 
+    pub fn set_load_balancing(
+        &mut self,
+        additional_outlet_addrs: Vec<MultiAddr>,
+        load_balancing: Option<InletLoadBalancing>,
+    ) {
+        self.additional_outlet_addrs = additional_outlet_addrs;
+        self.load_balancing = load_balancing;
+    }
+
+    pub fn set_secure_channel_rekey_interval(&mut self, rekey_interval: Option<Duration>) {
+        self.secure_channel_rekey_interval = rekey_interval;
+    }
+
+    pub fn set_buffer_size(&mut self, buffer_size: Option<usize>) {
+        self.buffer_size = buffer_size;
+    }
+
+    pub fn set_connection_limits(
+        &mut self,
+        max_connections_per_second: Option<u32>,
+        max_concurrent_connections: Option<u64>,
+    ) {
+        self.max_connections_per_second = max_connections_per_second;
+        self.max_concurrent_connections = max_concurrent_connections;
+    }
+
+    pub fn set_no_delay(&mut self, no_delay: bool) {
+        self.no_delay = no_delay;
+    }
+
+    pub fn set_liveness(
+        &mut self,
+        probe_interval: Option<Duration>,
+        max_missed_probes: Option<u32>,
+    ) {
+        self.probe_interval = probe_interval;
+        self.max_missed_probes = max_missed_probes;
+    }
+
+    pub fn set_identity(&mut self, identity: Option<String>) {
+        self.identity = identity;
+    }
+
+    pub fn set_unix_socket(&mut self, mode: Option<u32>, replace: bool) {
+        self.unix_socket_mode = mode;
+        self.replace_unix_socket = replace;
+    }
+
+    pub fn set_expect_proxy_protocol(&mut self, expect_proxy_protocol: bool) {
+        self.expect_proxy_protocol = expect_proxy_protocol;
+    }
+
     pub fn listen_addr(&self) -> String {
         self.listen_addr.clone()
     }
@@ -109,8 +220,8 @@ impl CreateInlet {
         &self.outlet_addr
     }
 
-    pub fn authorized(&self) -> Option<Identifier> {
-        self.authorized.clone()
+    pub fn authorized(&self) -> &[Identifier] {
+        &self.authorized
     }
 
     pub fn alias(&self) -> String {
@@ -128,6 +239,100 @@ impl CreateInlet {
     pub fn wait_for_outlet_duration(&self) -> Option<Duration> {
         self.wait_for_outlet_duration
     }
+
+    pub fn additional_outlet_addrs(&self) -> &[MultiAddr] {
+        &self.additional_outlet_addrs
+    }
+
+    pub fn load_balancing(&self) -> Option<InletLoadBalancing> {
+        self.load_balancing
+    }
+
+    pub fn buffer_size(&self) -> Option<usize> {
+        self.buffer_size
+    }
+}
+
+/// Strategy used by an inlet to distribute its connections across several outlets
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Decode, Encode, Serialize, Deserialize)]
+#[rustfmt::skip]
+pub enum InletLoadBalancing {
+    #[n(0)] #[default] RoundRobin,
+    #[n(1)] LeastConnections,
+}
+
+impl From<InletLoadBalancing> for TcpInletLoadBalancing {
+    fn from(value: InletLoadBalancing) -> Self {
+        match value {
+            InletLoadBalancing::RoundRobin => TcpInletLoadBalancing::RoundRobin,
+            InletLoadBalancing::LeastConnections => TcpInletLoadBalancing::LeastConnections,
+        }
+    }
+}
+
+impl From<TcpInletLoadBalancing> for InletLoadBalancing {
+    fn from(value: TcpInletLoadBalancing) -> Self {
+        match value {
+            TcpInletLoadBalancing::RoundRobin => InletLoadBalancing::RoundRobin,
+            TcpInletLoadBalancing::LeastConnections => InletLoadBalancing::LeastConnections,
+        }
+    }
+}
+
+impl FromStr for InletLoadBalancing {
+    type Err = ockam_core::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(TcpInletLoadBalancing::from_str(s)?.into())
+    }
+}
+
+impl Display for InletLoadBalancing {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        TcpInletLoadBalancing::from(*self).fmt(f)
+    }
+}
+
+/// Connections and health of one of the outlet routes of a load-balanced inlet
+#[derive(Clone, Debug, Decode, Encode, Serialize, Deserialize, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct InletRouteStatus {
+    #[n(1)] pub route: String,
+    /// Number of connections currently open to the outlet
+    #[n(2)] pub active_connections: u64,
+    /// Number of connections opened to the outlet since the inlet was created
+    #[n(3)] pub total_connections: u64,
+    /// Number of connections which failed to reach the outlet
+    #[n(4)] pub failures: u64,
+    /// False if a connection failed recently, in which case the outlet is skipped
+    #[n(5)] pub healthy: bool,
+}
+
+impl Display for InletRouteStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} active, {} total, {} failed ({})",
+            self.route,
+            self.active_connections,
+            self.total_connections,
+            self.failures,
+            if self.healthy { "healthy" } else { "unhealthy" }
+        )
+    }
+}
+
+impl From<&TcpInletRouteStatus> for InletRouteStatus {
+    fn from(status: &TcpInletRouteStatus) -> Self {
+        Self {
+            route: status.route().to_string(),
+            active_connections: status.active_connections(),
+            total_connections: status.total_connections(),
+            failures: status.failures(),
+            healthy: status.is_healthy(),
+        }
+    }
 }
 
 /// Request body to create an outlet
@@ -146,6 +351,18 @@ pub struct CreateOutlet {
     /// If not set, the policy set for the [TCP outlet resource type](ockam_abac::ResourceType::TcpOutlet)
     /// will be used.
     #[n(4)] pub policy_expression: Option<Expr>,
+    /// The maximum number of bytes sent on a connection and not yet acknowledged by the inlet
+    #[n(5)] pub buffer_size: Option<usize>,
+    /// The `host:port` the outlet connects to. When set, the host name is resolved again
+    /// for each new connection, once its cached addresses have expired
+    #[n(6)] pub hostname_port: Option<String>,
+    /// The name of the identity whose secure channel listeners can reach the outlet.
+    /// The outlet is reachable from the default secure channel listener if not set
+    #[n(7)] pub identity: Option<String>,
+    /// The path of the Unix domain socket the outlet connects to, instead of a TCP server
+    #[n(8)] pub unix_socket_path: Option<String>,
+    /// Send a PROXY protocol v2 header with the address of the inlet client to the target
+    #[n(9)] pub proxy_protocol: bool,
 }
 
 impl CreateOutlet {
@@ -159,12 +376,54 @@ impl CreateOutlet {
             worker_addr,
             reachable_from_default_secure_channel,
             policy_expression: None,
+            buffer_size: None,
+            hostname_port: None,
+            identity: None,
+            unix_socket_path: None,
+            proxy_protocol: false,
         }
     }
 
     pub fn set_policy_expression(&mut self, expression: Expr) {
         self.policy_expression = Some(expression);
     }
+
+    pub fn set_buffer_size(&mut self, buffer_size: Option<usize>) {
+        self.buffer_size = buffer_size;
+    }
+
+    pub fn set_hostname_port(&mut self, hostname_port: Option<String>) {
+        self.hostname_port = hostname_port;
+    }
+
+    pub fn set_identity(&mut self, identity: Option<String>) {
+        self.identity = identity;
+    }
+
+    pub fn set_unix_socket_path(&mut self, unix_socket_path: Option<String>) {
+        self.unix_socket_path = unix_socket_path;
+    }
+
+    pub fn set_proxy_protocol(&mut self, proxy_protocol: bool) {
+        self.proxy_protocol = proxy_protocol;
+    }
+}
+
+/// Request body to rename an inlet or an outlet
+#[derive(Clone, Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct RenamePortal {
+    /// The new alias of the inlet, or the new worker address of the outlet
+    #[n(1)] pub new_name: String,
+}
+
+impl RenamePortal {
+    pub fn new(new_name: impl Into<String>) -> Self {
+        Self {
+            new_name: new_name.into(),
+        }
+    }
 }
 
 /// Response body when interacting with a portal endpoint
@@ -172,6 +431,7 @@ impl CreateOutlet {
 #[rustfmt::skip]
 #[cbor(map)]
 pub struct InletStatus {
+    /// The socket address of the inlet, or "unix:<path>" when it listens on a Unix domain socket
     #[n(1)] pub bind_addr: String,
     #[n(2)] pub worker_addr: Option<String>,
     #[n(3)] pub alias: String,
@@ -180,6 +440,102 @@ pub struct InletStatus {
     #[n(5)] pub outlet_route: Option<String>,
     #[n(6)] pub status: ConnectionStatus,
     #[n(7)] pub outlet_addr: String,
+    /// The strategy used to distribute the connections, when the inlet is load-balanced
+    #[n(8)] pub load_balancing: Option<InletLoadBalancing>,
+    /// The connections of each outlet route, when the inlet is load-balanced
+    #[n(9)] pub outlet_routes: Vec<InletRouteStatus>,
+    /// Number of times the open connections of the inlet stopped reading from their TCP client
+    /// because the outlet was not acknowledging the data fast enough
+    #[n(10)] pub backpressure_pauses: u64,
+    /// Last changes of the connection status, from the oldest to the most recent one
+    #[n(11)] pub transitions: Vec<ConnectionTransition>,
+    /// Utilization of the limits on the connections accepted by the inlet, when there are limits
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[n(12)] pub connection_limits: Option<InletConnectionLimitsStatus>,
+    /// True if the data read from the TCP clients is sent right away, without batching small reads
+    #[serde(default)]
+    #[n(13)] pub no_delay: bool,
+    /// Liveness probes of the secure channel of the inlet, when they are configured for the inlet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[n(14)] pub liveness: Option<InletLivenessStatus>,
+    /// Name of the identity used for the secure channels to the outlets, when it is not the node identity
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[n(15)] pub identity: Option<String>,
+    /// File mode of the Unix domain socket of the inlet, when it was set at creation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[n(16)] pub unix_socket_mode: Option<u32>,
+    /// True if the inlet expects a PROXY protocol v2 header at the start of each client connection
+    #[serde(default)]
+    #[n(17)] pub expect_proxy_protocol: bool,
+    /// Other options the inlet was created with
+    #[serde(default)]
+    #[n(18)] pub creation_options: InletCreationOptions,
+}
+
+/// Options an inlet was created with, which are not otherwise part of its status.
+/// They are used to create the inlet again, for example when its node is restarted
+#[derive(Clone, Debug, Default, Decode, Encode, Serialize, Deserialize, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct InletCreationOptions {
+    /// The identities authorized for the secure channels to the outlets
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[n(1)] pub authorized: Vec<Identifier>,
+    /// The expression of the access control policy given when creating the inlet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[n(2)] pub policy_expression: Option<String>,
+    /// Other outlets the connections are distributed to, along with the outlet address
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[n(3)] pub additional_outlet_addrs: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[n(4)] pub secure_channel_rekey_interval_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[n(5)] pub buffer_size: Option<u64>,
+}
+
+impl InletCreationOptions {
+    pub fn new(
+        authorized: Vec<Identifier>,
+        policy_expression: Option<&Expr>,
+        additional_outlet_addrs: &[MultiAddr],
+        secure_channel_rekey_interval: Option<Duration>,
+        buffer_size: Option<usize>,
+    ) -> Self {
+        Self {
+            authorized,
+            policy_expression: policy_expression.map(|e| e.to_string()),
+            additional_outlet_addrs: additional_outlet_addrs
+                .iter()
+                .map(|a| a.to_string())
+                .collect(),
+            secure_channel_rekey_interval_ms: secure_channel_rekey_interval
+                .map(|i| i.as_millis() as u64),
+            buffer_size: buffer_size.map(|s| s as u64),
+        }
+    }
+
+    pub fn policy_expression(&self) -> Result<Option<Expr>, ApiError> {
+        self.policy_expression
+            .as_deref()
+            .map(|e| Expr::from_str(e).map_err(|e| ApiError::core(e.to_string())))
+            .transpose()
+    }
+
+    pub fn additional_outlet_addrs(&self) -> Result<Vec<MultiAddr>, ApiError> {
+        self.additional_outlet_addrs
+            .iter()
+            .map(|a| MultiAddr::from_str(a).map_err(|e| ApiError::core(e.to_string())))
+            .collect()
+    }
+
+    pub fn secure_channel_rekey_interval(&self) -> Option<Duration> {
+        self.secure_channel_rekey_interval_ms
+            .map(Duration::from_millis)
+    }
+
+    pub fn buffer_size(&self) -> Option<usize> {
+        self.buffer_size.map(|s| s as usize)
+    }
 }
 
 impl InletStatus {
@@ -201,7 +557,177 @@ impl InletStatus {
             outlet_route: outlet_route.into(),
             status,
             outlet_addr: outlet_addr.into(),
+            load_balancing: None,
+            outlet_routes: vec![],
+            backpressure_pauses: 0,
+            transitions: vec![],
+            connection_limits: None,
+            no_delay: false,
+            liveness: None,
+            identity: None,
+            unix_socket_mode: None,
+            expect_proxy_protocol: false,
+            creation_options: InletCreationOptions::default(),
+        }
+    }
+
+    /// Return the path of the Unix domain socket of the inlet, if it doesn't listen on TCP
+    pub fn unix_socket_path(&self) -> Option<&str> {
+        self.bind_addr.strip_prefix("unix:")
+    }
+
+    /// Add the distribution of the connections of a load-balanced inlet
+    pub fn with_load_balancer(mut self, load_balancer: Option<&TcpInletLoadBalancer>) -> Self {
+        if let Some(load_balancer) = load_balancer {
+            self.load_balancing = Some(load_balancer.strategy().into());
+            self.outlet_routes = load_balancer
+                .statuses()
+                .iter()
+                .map(InletRouteStatus::from)
+                .collect();
+        }
+        self
+    }
+
+    /// Add the number of backpressure pauses of the inlet connections
+    pub fn with_backpressure_pauses(mut self, backpressure_pauses: u64) -> Self {
+        self.backpressure_pauses = backpressure_pauses;
+        self
+    }
+
+    /// Add the last changes of the connection status of the inlet
+    pub fn with_transitions(mut self, transitions: Vec<ConnectionTransition>) -> Self {
+        self.transitions = transitions;
+        self
+    }
+
+    /// Add whether the inlet sends the data read from its TCP clients without batching it
+    pub fn with_no_delay(mut self, no_delay: bool) -> Self {
+        self.no_delay = no_delay;
+        self
+    }
+
+    /// Add the name of the identity used by the inlet, if it is not the node identity
+    pub fn with_identity(mut self, identity: Option<String>) -> Self {
+        self.identity = identity;
+        self
+    }
+
+    /// Add the file mode of the Unix domain socket of the inlet, if it was set
+    pub fn with_unix_socket_mode(mut self, unix_socket_mode: Option<u32>) -> Self {
+        self.unix_socket_mode = unix_socket_mode;
+        self
+    }
+
+    /// Add whether the inlet expects a PROXY protocol v2 header from its clients
+    pub fn with_expect_proxy_protocol(mut self, expect_proxy_protocol: bool) -> Self {
+        self.expect_proxy_protocol = expect_proxy_protocol;
+        self
+    }
+
+    /// Add the other options the inlet was created with
+    pub fn with_creation_options(mut self, creation_options: InletCreationOptions) -> Self {
+        self.creation_options = creation_options;
+        self
+    }
+
+    /// Add the liveness probes of the inlet secure channel, if they are configured
+    pub fn with_liveness(
+        mut self,
+        liveness: Option<SessionLiveness>,
+        missed_probes: usize,
+    ) -> Self {
+        self.liveness = liveness.map(|liveness| InletLivenessStatus {
+            probe_interval_ms: liveness.probe_interval().as_millis() as u64,
+            max_missed_probes: liveness.max_missed_probes(),
+            missed_probes: missed_probes as u32,
+        });
+        self
+    }
+
+    /// Add the utilization of the connection limits of the inlet, if it has any
+    pub fn with_connection_limits(
+        mut self,
+        limits: &TcpInletConnectionLimits,
+        active_connections: u64,
+    ) -> Self {
+        if limits.max_connections_per_second().is_some()
+            || limits.max_concurrent_connections().is_some()
+        {
+            self.connection_limits = Some(InletConnectionLimitsStatus {
+                max_connections_per_second: limits.max_connections_per_second(),
+                connections_per_second: limits.connections_per_second(),
+                max_concurrent_connections: limits.max_concurrent_connections(),
+                active_connections,
+                rate_limited_connections: limits.rate_limited_connections(),
+                concurrency_limited_connections: limits.concurrency_limited_connections(),
+            });
+        }
+        self
+    }
+}
+
+/// Liveness probes sent through the secure channel of an inlet
+#[derive(Clone, Debug, Decode, Encode, Serialize, Deserialize, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct InletLivenessStatus {
+    #[n(1)] pub probe_interval_ms: u64,
+    #[n(2)] pub max_missed_probes: u32,
+    /// Number of probes sent since the last answer
+    #[n(3)] pub missed_probes: u32,
+}
+
+impl InletLivenessStatus {
+    pub fn probe_interval(&self) -> Duration {
+        Duration::from_millis(self.probe_interval_ms)
+    }
+}
+
+impl Display for InletLivenessStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "probe every {}ms, {}/{} probes unanswered",
+            self.probe_interval_ms, self.missed_probes, self.max_missed_probes
+        )
+    }
+}
+
+/// Limits on the connections accepted by an inlet, and how much they are used
+#[derive(Clone, Debug, Decode, Encode, Serialize, Deserialize, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct InletConnectionLimitsStatus {
+    #[n(1)] pub max_connections_per_second: Option<u32>,
+    /// Number of connections accepted during the last second, when their rate is limited
+    #[n(2)] pub connections_per_second: Option<u32>,
+    #[n(3)] pub max_concurrent_connections: Option<u64>,
+    /// Number of connections currently open
+    #[n(4)] pub active_connections: u64,
+    /// Number of connections closed because too many connections were accepted during the last second
+    #[n(5)] pub rate_limited_connections: u64,
+    /// Number of connections closed because too many connections were open
+    #[n(6)] pub concurrency_limited_connections: u64,
+}
+
+impl Display for InletConnectionLimitsStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut limits = vec![];
+        if let Some(max) = self.max_connections_per_second {
+            limits.push(format!(
+                "{}/{max} new connections per second, {} rejected",
+                self.connections_per_second.unwrap_or_default(),
+                self.rate_limited_connections
+            ));
+        }
+        if let Some(max) = self.max_concurrent_connections {
+            limits.push(format!(
+                "{}/{max} concurrent connections, {} rejected",
+                self.active_connections, self.concurrency_limited_connections
+            ));
         }
+        write!(f, "{}", limits.join(", "))
     }
 }
 
@@ -214,6 +740,18 @@ pub struct OutletStatus {
     #[n(2)] pub worker_addr: Address,
     /// An optional status payload
     #[n(3)] pub payload: Option<String>,
+    /// The `host:port` the outlet connects to, when it was created with a host name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[n(4)] pub hostname_port: Option<String>,
+    /// Name of the identity whose secure channel listeners can reach the outlet, if one was chosen
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[n(5)] pub identity: Option<String>,
+    /// The path of the Unix domain socket the outlet connects to, when it doesn't connect to a TCP server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[n(6)] pub unix_socket_path: Option<String>,
+    /// True if the outlet sends a PROXY protocol v2 header to its target
+    #[serde(default)]
+    #[n(7)] pub proxy_protocol: bool,
 }
 
 impl OutletStatus {
@@ -226,6 +764,40 @@ impl OutletStatus {
             socket_addr,
             worker_addr,
             payload: payload.into(),
+            hostname_port: None,
+            identity: None,
+            unix_socket_path: None,
+            proxy_protocol: false,
+        }
+    }
+
+    pub fn with_hostname_port(mut self, hostname_port: Option<String>) -> Self {
+        self.hostname_port = hostname_port;
+        self
+    }
+
+    pub fn with_identity(mut self, identity: Option<String>) -> Self {
+        self.identity = identity;
+        self
+    }
+
+    pub fn with_unix_socket_path(mut self, unix_socket_path: Option<String>) -> Self {
+        self.unix_socket_path = unix_socket_path;
+        self
+    }
+
+    pub fn with_proxy_protocol(mut self, proxy_protocol: bool) -> Self {
+        self.proxy_protocol = proxy_protocol;
+        self
+    }
+
+    /// Return the target of the outlet: its Unix domain socket as `unix:<path>`,
+    /// its host name if it has one, its socket address otherwise
+    pub fn to(&self) -> String {
+        match (&self.unix_socket_path, &self.hostname_port) {
+            (Some(path), _) => format!("unix:{path}"),
+            (None, Some(hostname_port)) => hostname_port.clone(),
+            (None, None) => self.socket_addr.to_string(),
         }
     }
 
@@ -271,6 +843,73 @@ impl OutletList {
     }
 }
 
+/// Response body describing a connection accepted by an inlet
+#[derive(Clone, Debug, Decode, Encode, Serialize, Deserialize, PartialEq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PortalConnection {
+    /// Address of the portal worker handling the connection
+    #[n(1)] pub id: String,
+    /// Address of the TCP client
+    #[n(2)] pub source_addr: SocketAddr,
+    /// Unix time, in seconds, when the connection was accepted
+    #[n(3)] pub started_at: u64,
+    /// Number of bytes sent to the TCP client
+    #[n(4)] pub bytes_sent: u64,
+    /// Number of bytes received from the TCP client
+    #[n(5)] pub bytes_received: u64,
+    /// Identifier of the other side of the portal, when it is reached via a secure channel
+    #[n(6)] pub remote_identifier: Option<Identifier>,
+    /// Attributes of the other side of the portal, attested by the project authority
+    #[n(7)] pub remote_attributes: Option<BTreeMap<String, String>>,
+    /// Number of messages sent to the outlet with the data received from the TCP client
+    #[serde(default)]
+    #[n(8)] pub payload_messages: u64,
+}
+
+impl PortalConnection {
+    pub fn new(
+        id: impl Into<String>,
+        source_addr: SocketAddr,
+        started_at: u64,
+        bytes_sent: u64,
+        bytes_received: u64,
+        remote_identifier: Option<Identifier>,
+        remote_attributes: Option<BTreeMap<String, String>>,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            source_addr,
+            started_at,
+            bytes_sent,
+            bytes_received,
+            remote_identifier,
+            remote_attributes,
+            payload_messages: 0,
+        }
+    }
+
+    /// Add the number of messages sent to the outlet
+    pub fn with_payload_messages(mut self, payload_messages: u64) -> Self {
+        self.payload_messages = payload_messages;
+        self
+    }
+}
+
+/// Response body when returning the list of connections of an inlet
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PortalConnectionList {
+    #[n(1)] pub list: Vec<PortalConnection>
+}
+
+impl PortalConnectionList {
+    pub fn new(list: Vec<PortalConnection>) -> Self {
+        Self { list }
+    }
+}
+
 #[derive(Debug)]
 pub enum OutletAccessControl {
     IncomingAccessControl(Arc<dyn IncomingAccessControl>),
@@ -1,7 +1,9 @@
+use std::time::Duration;
+
 use minicbor::{Decode, Encode};
 
 use ockam::identity::Identifier;
-use ockam::remote::RemoteRelayInfo;
+use ockam::remote::{RelayHeartbeatStatus, RemoteRelayInfo};
 use ockam::route;
 use ockam_core::flow_control::FlowControlId;
 use ockam_multiaddr::MultiAddr;
@@ -26,6 +28,10 @@ pub struct CreateRelay {
     #[n(4)] pub(crate) authorized: Option<Identifier>,
     /// Relay address.
     #[n(5)] pub(crate) relay_address: Option<String>,
+    /// Interval between two keepalive messages sent through the relay.
+    #[n(6)] pub(crate) heartbeat_interval: Option<Duration>,
+    /// Take over the relay name if it is already registered by another node.
+    #[n(7)] pub(crate) force: bool,
 }
 
 impl CreateRelay {
@@ -42,9 +48,16 @@ impl CreateRelay {
             at_rust_node,
             authorized: auth,
             relay_address,
+            heartbeat_interval: None,
+            force: false,
         }
     }
 
+    pub fn with_heartbeat_interval(mut self, heartbeat_interval: Option<Duration>) -> Self {
+        self.heartbeat_interval = heartbeat_interval;
+        self
+    }
+
     pub fn address(&self) -> &MultiAddr {
         &self.address
     }
@@ -64,8 +77,24 @@ impl CreateRelay {
     pub fn relay_address(&self) -> Option<&str> {
         self.relay_address.as_deref()
     }
+
+    pub fn with_force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
+    pub fn heartbeat_interval(&self) -> Option<Duration> {
+        self.heartbeat_interval
+    }
+
+    pub fn force(&self) -> bool {
+        self.force
+    }
 }
 
+/// Number of consecutive failed heartbeats after which a relay is considered degraded
+pub const MAX_FAILED_HEARTBEATS: u32 = 3;
+
 /// Response body when creating a relay
 #[derive(Debug, Clone, Decode, Encode, serde::Serialize, serde::Deserialize)]
 #[rustfmt::skip]
@@ -80,6 +109,12 @@ pub struct RelayInfo {
     #[n(7)] alias: String,
     #[n(8)] at_rust_node: bool,
     #[n(9)] last_failure: Option<String>,
+    /// Time, in seconds since the UNIX epoch, of the last successful heartbeat
+    #[n(10)] last_heartbeat_at: Option<u64>,
+    #[n(11)] round_trip_time_ms: Option<u64>,
+    #[n(12)] failed_heartbeats: u32,
+    /// Number of attempts made to register the relay again since it was lost
+    #[n(13)] reconnection_attempts: u32,
 }
 
 impl RelayInfo {
@@ -99,6 +134,10 @@ impl RelayInfo {
             flow_control_id: None,
             connection_status,
             last_failure: None,
+            last_heartbeat_at: None,
+            round_trip_time_ms: None,
+            failed_heartbeats: 0,
+            reconnection_attempts: 0,
         }
     }
 
@@ -113,6 +152,10 @@ impl RelayInfo {
             alias: self.alias,
             at_rust_node: self.at_rust_node,
             last_failure: self.last_failure,
+            last_heartbeat_at: self.last_heartbeat_at,
+            round_trip_time_ms: self.round_trip_time_ms,
+            failed_heartbeats: self.failed_heartbeats,
+            reconnection_attempts: self.reconnection_attempts,
         }
     }
 
@@ -127,7 +170,30 @@ impl RelayInfo {
             alias: self.alias,
             at_rust_node: self.at_rust_node,
             last_failure: Some(last_failure),
+            last_heartbeat_at: self.last_heartbeat_at,
+            round_trip_time_ms: self.round_trip_time_ms,
+            failed_heartbeats: self.failed_heartbeats,
+            reconnection_attempts: self.reconnection_attempts,
+        }
+    }
+
+    /// Set the heartbeat statistics of the relay. A relay which is up but
+    /// failed too many consecutive heartbeats is reported as degraded
+    pub fn with_heartbeats(mut self, status: RelayHeartbeatStatus) -> Self {
+        self.last_heartbeat_at = status.last_success_at().map(|ms| ms / 1000);
+        self.round_trip_time_ms = status.round_trip_time().map(|rtt| rtt.as_millis() as u64);
+        self.failed_heartbeats = status.consecutive_failures();
+        if self.connection_status == ConnectionStatus::Up
+            && self.failed_heartbeats >= MAX_FAILED_HEARTBEATS
+        {
+            self.connection_status = ConnectionStatus::Degraded;
         }
+        self
+    }
+
+    pub fn with_reconnection_attempts(mut self, reconnection_attempts: u32) -> Self {
+        self.reconnection_attempts = reconnection_attempts;
+        self
     }
 
     pub fn connection_status(&self) -> ConnectionStatus {
@@ -146,6 +212,26 @@ impl RelayInfo {
         self.at_rust_node
     }
 
+    pub fn last_heartbeat_at(&self) -> Option<u64> {
+        self.last_heartbeat_at
+    }
+
+    pub fn round_trip_time(&self) -> Option<Duration> {
+        self.round_trip_time_ms.map(Duration::from_millis)
+    }
+
+    pub fn failed_heartbeats(&self) -> u32 {
+        self.failed_heartbeats
+    }
+
+    pub fn reconnection_attempts(&self) -> u32 {
+        self.reconnection_attempts
+    }
+
+    pub fn last_failure(&self) -> Option<&str> {
+        self.last_failure.as_deref()
+    }
+
     pub fn forwarding_route(&self) -> &Option<String> {
         &self.forwarding_route
     }
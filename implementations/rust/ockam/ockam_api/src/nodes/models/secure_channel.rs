@@ -1,10 +1,10 @@
 use std::time::Duration;
 
 use minicbor::{Decode, Encode};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use ockam::identity::models::CredentialAndPurposeKey;
-use ockam::identity::{Identifier, SecureChannel, DEFAULT_TIMEOUT};
+use ockam::identity::{Identifier, SecureChannel, SecureChannelRegistryEntry, DEFAULT_TIMEOUT};
 use ockam_core::flow_control::FlowControlId;
 use ockam_core::{route, Address, Result};
 use ockam_multiaddr::MultiAddr;
@@ -25,6 +25,11 @@ pub struct CreateSecureChannelRequest {
     #[n(4)] pub timeout: Option<Duration>,
     #[n(5)] pub identity_name: Option<String>,
     #[n(6)] pub credential: Option<CredentialAndPurposeKey>,
+    #[n(7)] pub rekey_interval: Option<Duration>,
+    /// Name under which the channel is kept, to be used with `/channel/<name>`
+    #[n(8)] pub name: Option<String>,
+    /// Create the named channel again when it is found to be stale
+    #[n(9)] pub auto_reconnect: bool,
 }
 
 impl CreateSecureChannelRequest {
@@ -40,8 +45,22 @@ impl CreateSecureChannelRequest {
             timeout: Some(DEFAULT_TIMEOUT),
             identity_name,
             credential,
+            rekey_interval: None,
+            name: None,
+            auto_reconnect: false,
         }
     }
+
+    pub fn with_name(mut self, name: Option<String>, auto_reconnect: bool) -> Self {
+        self.name = name;
+        self.auto_reconnect = auto_reconnect;
+        self
+    }
+
+    pub fn with_rekey_interval(mut self, rekey_interval: Option<Duration>) -> Self {
+        self.rekey_interval = rekey_interval;
+        self
+    }
 }
 
 /// Request body when instructing a node to delete a Secure Channel
@@ -108,6 +127,34 @@ impl ShowSecureChannelListenerRequest {
     }
 }
 
+/// Request body to update the identifiers authorized by a Secure Channel Listener
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct UpdateSecureChannelListenerRequest {
+    #[n(1)] pub addr: Address,
+    /// Replace the authorized identifiers, before adding and removing the other ones
+    #[n(2)] pub set: Option<Vec<Identifier>>,
+    #[n(3)] pub add: Vec<Identifier>,
+    #[n(4)] pub remove: Vec<Identifier>,
+}
+
+impl UpdateSecureChannelListenerRequest {
+    pub fn new(
+        addr: &Address,
+        set: Option<Vec<Identifier>>,
+        add: Vec<Identifier>,
+        remove: Vec<Identifier>,
+    ) -> Self {
+        Self {
+            addr: addr.to_owned(),
+            set,
+            add,
+            remove,
+        }
+    }
+}
+
 // Responses
 
 /// Response body when instructing a node to create a Secure Channel
@@ -144,6 +191,8 @@ pub struct CreateSecureChannelListenerRequest {
     #[n(1)] pub addr: Address,
     #[n(2)] pub authorized_identifiers: Option<Vec<Identifier>>,
     #[n(3)] pub identity_name: Option<String>,
+    /// The maximum number of handshakes started per second
+    #[n(4)] pub max_handshakes_per_second: Option<u32>,
 }
 
 impl CreateSecureChannelListenerRequest {
@@ -156,8 +205,17 @@ impl CreateSecureChannelListenerRequest {
             addr: addr.to_owned(),
             authorized_identifiers,
             identity_name,
+            max_handshakes_per_second: None,
         }
     }
+
+    pub fn with_max_handshakes_per_second(
+        mut self,
+        max_handshakes_per_second: Option<u32>,
+    ) -> Self {
+        self.max_handshakes_per_second = max_handshakes_per_second;
+        self
+    }
 }
 
 /// Response body when deleting a Secure Channel Listener
@@ -181,13 +239,26 @@ impl DeleteSecureChannelListenerResponse {
 pub struct ShowSecureChannelListenerResponse {
     #[n(1)] pub addr: Address,
     #[n(2)] pub flow_control_id: FlowControlId,
+    #[n(3)] pub identifier: Option<Identifier>,
+    #[n(4)] pub authorized_identifiers: Option<Vec<Identifier>>,
+    #[n(5)] pub max_handshakes_per_second: Option<u32>,
+    /// Number of handshakes started during the last second, when their rate is limited
+    #[n(6)] pub handshakes_per_second: Option<u32>,
+    /// Number of handshake messages dropped because too many handshakes were started
+    #[n(7)] pub rate_limited_handshakes: Option<u64>,
 }
 
 impl ShowSecureChannelListenerResponse {
     pub(crate) fn new(info: &SecureChannelListenerInfo) -> Self {
+        let rate_limiter = info.handshake_rate_limiter();
         Self {
             addr: info.listener().address().to_string().into(),
             flow_control_id: info.listener().flow_control_id().clone(),
+            identifier: Some(info.identifier().clone()),
+            authorized_identifiers: info.authorized_identifiers(),
+            max_handshakes_per_second: rate_limiter.map(|l| l.per_second()),
+            handshakes_per_second: rate_limiter.map(|l| l.utilization()),
+            rate_limited_handshakes: rate_limiter.map(|l| l.rejections().total()),
         }
     }
 }
@@ -197,14 +268,22 @@ impl ShowSecureChannelListenerResponse {
 #[cbor(map)]
 pub struct DeleteSecureChannelResponse {
     #[n(1)] pub channel: Option<String>,
+    /// Resources which were using the channel and are establishing a new one
+    #[n(2)] pub failed_over: Vec<SecureChannelUser>,
 }
 
 impl DeleteSecureChannelResponse {
     pub fn new(channel: Option<Address>) -> Self {
         Self {
             channel: channel.map(|ch| ch.to_string()),
+            failed_over: vec![],
         }
     }
+
+    pub fn with_failed_over(mut self, failed_over: Vec<SecureChannelUser>) -> Self {
+        self.failed_over = failed_over;
+        self
+    }
 }
 
 #[derive(Debug, Clone, Decode, Encode, Serialize)]
@@ -215,10 +294,23 @@ pub struct ShowSecureChannelResponse {
     #[n(2)] pub route: Option<String>,
     #[n(3)] pub authorized_identifiers: Option<Vec<String>>,
     #[n(4)] pub flow_control_id: Option<FlowControlId>,
+    /// Identifier of the other party, verified during the handshake
+    #[n(5)] pub their_identifier: Option<String>,
+    /// Creation time of the channel, in seconds since the UNIX epoch
+    #[n(6)] pub created_at: Option<u64>,
+    /// True if the other party presented a credential verified with the project authority
+    #[n(7)] pub their_credential_verified: Option<bool>,
+    #[n(8)] pub is_initiator: Option<bool>,
+    /// Route to the decryptor of the other party, negotiated during the handshake
+    #[n(9)] pub negotiated_route: Option<String>,
+    #[n(10)] pub decryptor_address: Option<String>,
+    /// Number of times our encryption key was renewed
+    #[n(11)] pub rekeys: Option<u64>,
 }
 
 impl ShowSecureChannelResponse {
     pub fn new(info: Option<SecureChannelInfo>) -> Self {
+        let channel_info = info.as_ref().and_then(|info| info.sc().info());
         Self {
             channel: info
                 .clone()
@@ -232,11 +324,108 @@ impl ShowSecureChannelResponse {
                         .map(|ids| ids.iter().map(|iid| iid.to_string()).collect())
                 })
                 .unwrap_or(None),
-            flow_control_id: info.map(|info| info.sc().flow_control_id().clone()),
+            flow_control_id: info
+                .as_ref()
+                .map(|info| info.sc().flow_control_id().clone()),
+            their_identifier: channel_info
+                .as_ref()
+                .map(|i| i.their_identifier().to_string()),
+            created_at: channel_info.as_ref().map(|i| i.created_at().0),
+            their_credential_verified: channel_info.as_ref().map(|i| i.their_credential_verified()),
+            is_initiator: channel_info.as_ref().map(|i| i.is_initiator()),
+            negotiated_route: channel_info.as_ref().map(|i| i.route().to_string()),
+            decryptor_address: channel_info
+                .as_ref()
+                .map(|i| i.decryptor_address().to_string()),
+            rekeys: channel_info.as_ref().map(|i| i.rekeys()),
+        }
+    }
+}
+
+/// Secure channel established by a node, either initiated by the node or accepted by
+/// one of its secure channel listeners
+#[derive(Debug, Clone, Decode, Encode, Serialize, Deserialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SecureChannelStatus {
+    #[n(1)] pub encryptor_address: String,
+    #[n(2)] pub decryptor_address: String,
+    #[n(3)] pub is_initiator: bool,
+    /// Identifier of the other party, verified during the handshake
+    #[n(4)] pub their_identifier: String,
+    /// Route to the decryptor of the other party, negotiated during the handshake
+    #[n(5)] pub route: String,
+    /// Creation time of the channel, in seconds since the UNIX epoch
+    #[n(6)] pub created_at: u64,
+    #[n(7)] pub messages_sent: u64,
+    #[n(8)] pub bytes_sent: u64,
+    #[n(9)] pub messages_received: u64,
+    #[n(10)] pub bytes_received: u64,
+    /// Time of the last message sent or received, in seconds since the UNIX epoch
+    #[n(11)] pub last_activity_at: Option<u64>,
+    /// Resources of the node sending their messages through the channel
+    #[n(12)] pub used_by: Vec<SecureChannelUser>,
+}
+
+impl SecureChannelStatus {
+    pub fn new(entry: &SecureChannelRegistryEntry, used_by: Vec<SecureChannelUser>) -> Self {
+        let traffic = entry.traffic();
+        Self {
+            encryptor_address: entry.encryptor_messaging_address().to_string(),
+            decryptor_address: entry.decryptor_messaging_address().to_string(),
+            is_initiator: entry.is_initiator(),
+            their_identifier: entry.their_id().to_string(),
+            route: entry.their_route().to_string(),
+            created_at: entry.created_at().0,
+            messages_sent: traffic.messages_sent(),
+            bytes_sent: traffic.bytes_sent(),
+            messages_received: traffic.messages_received(),
+            bytes_received: traffic.bytes_received(),
+            last_activity_at: traffic.last_activity_at().map(|at| at.0),
+            used_by,
         }
     }
 }
 
+/// Resource using a secure channel, for example a TCP inlet created with the given alias
+#[derive(Debug, Clone, PartialEq, Eq, Decode, Encode, Serialize, Deserialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SecureChannelUser {
+    /// Kind of resource: "tcp-inlet", "relay", "connection" or "secure-channel"
+    #[n(1)] pub kind: String,
+    #[n(2)] pub alias: String,
+}
+
+impl SecureChannelUser {
+    pub fn new(kind: impl Into<String>, alias: impl Into<String>) -> Self {
+        Self {
+            kind: kind.into(),
+            alias: alias.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for SecureChannelUser {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.kind, self.alias)
+    }
+}
+
+/// Response body when returning the secure channels of a node
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SecureChannelList {
+    #[n(1)] pub list: Vec<SecureChannelStatus>,
+}
+
+impl SecureChannelList {
+    pub fn new(list: Vec<SecureChannelStatus>) -> Self {
+        Self { list }
+    }
+}
+
 #[derive(Debug, Clone, Decode, Encode)]
 #[rustfmt::skip]
 #[cbor(map)]
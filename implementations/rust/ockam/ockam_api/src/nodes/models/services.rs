@@ -1,10 +1,15 @@
 use minicbor::{Decode, Encode};
+use ockam::identity::TimestampInSeconds;
 use ockam_core::compat::net::SocketAddr;
 use ockam_core::Address;
 use ockam_multiaddr::MultiAddr;
 
 use serde::Serialize;
 
+use crate::bench_target::BenchTargetMode;
+use crate::kafka::{BrokerAuth, BrokerAuthValidation};
+use crate::nodes::service::default_address::DefaultAddress;
+
 #[derive(Debug, Clone, Decode, Encode)]
 #[rustfmt::skip]
 #[cbor(map)]
@@ -52,18 +57,47 @@ impl DeleteServiceRequest {
 #[cbor(map)]
 pub struct StartKafkaOutletRequest {
     #[n(1)] pub bootstrap_server_addr: SocketAddr,
+    #[n(2)] broker_auth: Option<BrokerAuth>,
+    #[n(3)] allowed_topics: Option<Vec<String>>,
 }
 
 impl StartKafkaOutletRequest {
     pub fn new(bootstrap_server_addr: SocketAddr) -> Self {
         Self {
             bootstrap_server_addr,
+            broker_auth: None,
+            allowed_topics: None,
+        }
+    }
+
+    /// Declare the authentication mode required by the brokers.
+    /// This mode is validated against the brokers when the outlet is created
+    pub fn with_broker_auth(self, broker_auth: Option<BrokerAuth>) -> Self {
+        Self {
+            broker_auth,
+            ..self
+        }
+    }
+
+    /// Restrict the topics going through the outlet to the topics matching one of these patterns
+    pub fn with_allowed_topics(self, allowed_topics: Option<Vec<String>>) -> Self {
+        Self {
+            allowed_topics,
+            ..self
         }
     }
 
     pub fn bootstrap_server_addr(&self) -> &SocketAddr {
         &self.bootstrap_server_addr
     }
+
+    pub fn broker_auth(&self) -> Option<BrokerAuth> {
+        self.broker_auth
+    }
+
+    pub fn allowed_topics(&self) -> Option<Vec<String>> {
+        self.allowed_topics.clone()
+    }
 }
 
 #[derive(Debug, Clone, Decode, Encode)]
@@ -73,6 +107,8 @@ pub struct StartKafkaRequest {
     #[n(1)] pub bootstrap_server_addr: SocketAddr,
     #[n(2)] brokers_port_range: (u16, u16),
     #[n(3)] project_route: MultiAddr,
+    #[n(4)] broker_auth: Option<BrokerAuth>,
+    #[n(5)] allowed_topics: Option<Vec<String>>,
 }
 
 impl StartKafkaRequest {
@@ -85,6 +121,24 @@ impl StartKafkaRequest {
             bootstrap_server_addr,
             brokers_port_range: brokers_port_range.into(),
             project_route,
+            broker_auth: None,
+            allowed_topics: None,
+        }
+    }
+
+    /// Declare the authentication mode required by the brokers behind the kafka outlet
+    pub fn with_broker_auth(self, broker_auth: Option<BrokerAuth>) -> Self {
+        Self {
+            broker_auth,
+            ..self
+        }
+    }
+
+    /// Restrict the topics used by the kafka clients to the topics matching one of these patterns
+    pub fn with_allowed_topics(self, allowed_topics: Option<Vec<String>>) -> Self {
+        Self {
+            allowed_topics,
+            ..self
         }
     }
 
@@ -97,6 +151,12 @@ impl StartKafkaRequest {
     pub fn project_route(&self) -> MultiAddr {
         self.project_route.clone()
     }
+    pub fn broker_auth(&self) -> Option<BrokerAuth> {
+        self.broker_auth
+    }
+    pub fn allowed_topics(&self) -> Option<Vec<String>> {
+        self.allowed_topics.clone()
+    }
 }
 
 #[derive(Debug, Clone, Decode, Encode)]
@@ -107,6 +167,8 @@ pub struct StartKafkaDirectRequest {
     #[n(2)] bootstrap_server_addr: SocketAddr,
     #[n(3)] brokers_port_range: (u16, u16),
     #[n(4)] consumer_route: Option<MultiAddr>,
+    #[n(5)] broker_auth: Option<BrokerAuth>,
+    #[n(6)] allowed_topics: Option<Vec<String>>,
 }
 
 impl StartKafkaDirectRequest {
@@ -121,6 +183,25 @@ impl StartKafkaDirectRequest {
             bootstrap_server_addr,
             brokers_port_range: brokers_port_range.into(),
             consumer_route,
+            broker_auth: None,
+            allowed_topics: None,
+        }
+    }
+
+    /// Declare the authentication mode required by the brokers.
+    /// This mode is validated against the brokers when the service is created
+    pub fn with_broker_auth(self, broker_auth: Option<BrokerAuth>) -> Self {
+        Self {
+            broker_auth,
+            ..self
+        }
+    }
+
+    /// Restrict the topics used by the kafka clients to the topics matching one of these patterns
+    pub fn with_allowed_topics(self, allowed_topics: Option<Vec<String>>) -> Self {
+        Self {
+            allowed_topics,
+            ..self
         }
     }
 
@@ -136,6 +217,31 @@ impl StartKafkaDirectRequest {
     pub fn consumer_route(&self) -> Option<MultiAddr> {
         self.consumer_route.clone()
     }
+    pub fn broker_auth(&self) -> Option<BrokerAuth> {
+        self.broker_auth
+    }
+    pub fn allowed_topics(&self) -> Option<Vec<String>> {
+        self.allowed_topics.clone()
+    }
+}
+
+/// Request body to update the topics allowed by a kafka service
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct UpdateKafkaAllowedTopicsRequest {
+    #[n(1)] pub addr: Address,
+    /// Patterns of the allowed topics, all the topics are allowed when None
+    #[n(2)] pub allowed_topics: Option<Vec<String>>,
+}
+
+impl UpdateKafkaAllowedTopicsRequest {
+    pub fn new(addr: &Address, allowed_topics: Option<Vec<String>>) -> Self {
+        Self {
+            addr: addr.to_owned(),
+            allowed_topics,
+        }
+    }
 }
 
 /// Request body when instructing a node to start an Uppercase service
@@ -180,12 +286,79 @@ impl StartHopServiceRequest {
     }
 }
 
+/// Request body when instructing a node to start an outlet to a bench target,
+/// used by `ockam bench portal`
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct StartBenchServiceRequest {
+    #[n(1)] pub addr: String,
+    #[n(2)] pub mode: BenchTargetMode,
+}
+
+impl StartBenchServiceRequest {
+    pub fn new(addr: impl Into<String>, mode: BenchTargetMode) -> Self {
+        Self {
+            addr: addr.into(),
+            mode,
+        }
+    }
+}
+
+/// Built-in service which can be started and stopped on a running node
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+pub enum BuiltinService {
+    #[n(0)] Uppercase,
+    #[n(1)] Echo,
+    #[n(2)] Hop,
+    #[n(3)] Discard,
+}
+
+impl BuiltinService {
+    /// Type of the service, as displayed in the list of services.
+    /// This is also the address of the service when none is given
+    pub fn service_type(&self) -> &'static str {
+        match self {
+            BuiltinService::Uppercase => DefaultAddress::UPPERCASE_SERVICE,
+            BuiltinService::Echo => DefaultAddress::ECHO_SERVICE,
+            BuiltinService::Hop => DefaultAddress::HOP_SERVICE,
+            BuiltinService::Discard => DefaultAddress::DISCARD_SERVICE,
+        }
+    }
+}
+
+/// Request body when instructing a node to start a built-in service
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct StartBuiltinServiceRequest {
+    #[n(1)] pub service: BuiltinService,
+    #[n(2)] pub addr: String,
+}
+
+impl StartBuiltinServiceRequest {
+    pub fn new(service: BuiltinService, addr: impl Into<String>) -> Self {
+        Self {
+            service,
+            addr: addr.into(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Decode, Encode)]
 #[rustfmt::skip]
 #[cbor(map)]
 pub struct ServiceStatus {
     #[n(2)] pub addr: String,
     #[n(3)] pub service_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[n(4)] pub broker_auth: Option<BrokerAuthValidation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[n(5)] pub allowed_topics: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[n(6)] pub started_at: Option<TimestampInSeconds>,
 }
 
 impl ServiceStatus {
@@ -193,6 +366,33 @@ impl ServiceStatus {
         Self {
             addr: addr.into(),
             service_type: service_type.into(),
+            broker_auth: None,
+            allowed_topics: None,
+            started_at: None,
+        }
+    }
+
+    /// Set the time when a built-in service was started
+    pub fn with_started_at(self, started_at: TimestampInSeconds) -> Self {
+        Self {
+            started_at: Some(started_at),
+            ..self
+        }
+    }
+
+    /// Set the validation result of the brokers authentication mode, for a kafka service
+    pub fn with_broker_auth(self, broker_auth: Option<BrokerAuthValidation>) -> Self {
+        Self {
+            broker_auth,
+            ..self
+        }
+    }
+
+    /// Set the patterns of the topics allowed by a kafka service
+    pub fn with_allowed_topics(self, allowed_topics: Option<Vec<String>>) -> Self {
+        Self {
+            allowed_topics,
+            ..self
         }
     }
 }
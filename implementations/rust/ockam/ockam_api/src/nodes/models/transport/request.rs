@@ -22,11 +22,22 @@ impl CreateTcpConnection {
 pub struct CreateTcpListener {
     /// The address payload for the transport
     #[n(1)] pub addr: String,
+    /// The maximum size of the messages received on the accepted connections,
+    /// instead of the limit of the node
+    #[n(2)] pub max_message_size: Option<u64>,
 }
 
 impl CreateTcpListener {
     pub fn new(addr: String) -> Self {
-        Self { addr }
+        Self {
+            addr,
+            max_message_size: None,
+        }
+    }
+
+    pub fn with_max_message_size(mut self, max_message_size: Option<u64>) -> Self {
+        self.max_message_size = max_message_size;
+        self
     }
 }
 
@@ -7,6 +7,7 @@ use ockam_core::{Error, Result};
 use ockam_multiaddr::proto::Worker;
 use ockam_multiaddr::MultiAddr;
 use ockam_transport_tcp::{TcpConnection, TcpListener, TcpListenerInfo, TcpSenderInfo};
+use ockam_transport_udp::UdpListener;
 use std::net::SocketAddrV4;
 
 /// Response body when interacting with a transport
@@ -26,6 +27,8 @@ pub struct TransportStatus {
     #[n(5)] pub processor_address: String,
     /// Corresponding flow control id
     #[n(6)] pub flow_control_id: FlowControlId,
+    /// IP family of a TCP connection
+    #[n(7)] pub ip_family: Option<String>,
 }
 
 impl TransportStatus {
@@ -60,6 +63,7 @@ impl From<ApiTransport> for TransportStatus {
             worker_addr: value.worker_address.clone(),
             processor_address: value.processor_address.clone(),
             flow_control_id: value.flow_control_id,
+            ip_family: None,
         }
     }
 }
@@ -73,6 +77,7 @@ impl From<TcpSenderInfo> for TransportStatus {
             worker_addr: value.address().to_string(),
             processor_address: value.receiver_address().to_string(),
             flow_control_id: value.flow_control_id().clone(),
+            ip_family: Some(value.ip_family().to_string()),
         }
     }
 }
@@ -86,6 +91,7 @@ impl From<TcpListenerInfo> for TransportStatus {
             worker_addr: "<none>".into(),
             processor_address: value.address().to_string(),
             flow_control_id: value.flow_control_id().clone(),
+            ip_family: None,
         }
     }
 }
@@ -99,6 +105,7 @@ impl From<TcpConnection> for TransportStatus {
             worker_addr: value.sender_address().to_string(),
             processor_address: value.receiver_address().to_string(),
             flow_control_id: value.flow_control_id().clone(),
+            ip_family: Some(value.ip_family().to_string()),
         }
     }
 }
@@ -112,6 +119,24 @@ impl From<TcpListener> for TransportStatus {
             worker_addr: "<none>".into(),
             processor_address: value.processor_address().to_string(),
             flow_control_id: value.flow_control_id().clone(),
+            ip_family: None,
+        }
+    }
+}
+
+impl From<UdpListener> for TransportStatus {
+    fn from(value: UdpListener) -> Self {
+        Self {
+            tt: TransportType::Udp,
+            tm: TransportMode::Listen,
+            socket_addr: value.socket_address().to_string(),
+            worker_addr: value.sender_address().to_string(),
+            processor_address: "<none>".into(),
+            flow_control_id: value
+                .flow_control_id()
+                .cloned()
+                .unwrap_or_else(|| FlowControlId::from("<none>".to_string())),
+            ip_family: None,
         }
     }
 }
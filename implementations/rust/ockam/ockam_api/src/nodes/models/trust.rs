@@ -0,0 +1,127 @@
+//! Response types describing the trust configured on a node
+
+use std::fmt::{Display, Formatter};
+
+use minicbor::{Decode, Encode};
+use ockam::identity::{Identifier, TimestampInSeconds};
+use serde::{Deserialize, Serialize};
+
+/// How a node retrieves the credential it presents to other nodes
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Decode, Encode, Serialize, Deserialize)]
+#[rustfmt::skip]
+#[serde(rename_all = "kebab-case")]
+pub enum CredentialRetrieval {
+    /// The node doesn't present any credential
+    #[n(0)] None,
+    /// The node only presents a credential which was already stored for it
+    #[n(1)] Cache,
+    /// The node requests its credential from the authority, and renews it before it expires
+    #[n(2)] Authority,
+    /// The node presents the credential it was started with
+    #[n(3)] InMemory,
+}
+
+impl Display for CredentialRetrieval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CredentialRetrieval::None => write!(f, "none"),
+            CredentialRetrieval::Cache => write!(f, "cached credential only"),
+            CredentialRetrieval::Authority => write!(f, "requested from the authority"),
+            CredentialRetrieval::InMemory => write!(f, "given when the node was started"),
+        }
+    }
+}
+
+/// Project the trust of a node derives from
+#[derive(Clone, Debug, PartialEq, Eq, Decode, Encode, Serialize, Deserialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TrustProject {
+    #[n(1)] pub name: String,
+    #[n(2)] pub id: String,
+}
+
+/// Credential held by a node. Only its validity is described, not its content
+#[derive(Clone, Debug, PartialEq, Eq, Decode, Encode, Serialize, Deserialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct HeldCredential {
+    #[n(1)] pub issuer: Identifier,
+    /// Expiration time of the credential, in seconds since the Unix epoch
+    #[n(2)] pub expires_at: u64,
+    #[n(3)] pub is_expired: bool,
+}
+
+impl HeldCredential {
+    pub fn new(
+        issuer: Identifier,
+        expires_at: TimestampInSeconds,
+        now: TimestampInSeconds,
+    ) -> Self {
+        Self {
+            issuer,
+            expires_at: expires_at.0,
+            is_expired: expires_at <= now,
+        }
+    }
+}
+
+/// Response body describing the trust of a node: the authority whose credentials it accepts,
+/// and how it retrieves its own credential
+#[derive(Clone, Debug, PartialEq, Eq, Decode, Encode, Serialize, Deserialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TrustContextStatus {
+    /// Identifier of the authority whose credentials are accepted, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[n(1)] pub authority_identifier: Option<Identifier>,
+    /// Address of the authority node, when the credentials are requested from it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[n(2)] pub authority_route: Option<String>,
+    #[n(3)] pub credential_retrieval: CredentialRetrieval,
+    /// Scope of the credential of the node, e.g. "project-member-<project id>"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[n(4)] pub credential_scope: Option<String>,
+    /// Project the authority comes from, when it was not given explicitly
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[n(5)] pub project: Option<TrustProject>,
+    /// Credential currently held by the node, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[n(6)] pub credential: Option<HeldCredential>,
+}
+
+impl TrustContextStatus {
+    pub fn new(
+        authority_identifier: Option<Identifier>,
+        credential_retrieval: CredentialRetrieval,
+    ) -> Self {
+        Self {
+            authority_identifier,
+            authority_route: None,
+            credential_retrieval,
+            credential_scope: None,
+            project: None,
+            credential: None,
+        }
+    }
+
+    pub fn with_authority_route(mut self, authority_route: Option<String>) -> Self {
+        self.authority_route = authority_route;
+        self
+    }
+
+    pub fn with_credential_scope(mut self, credential_scope: Option<String>) -> Self {
+        self.credential_scope = credential_scope;
+        self
+    }
+
+    pub fn with_project(mut self, project: Option<TrustProject>) -> Self {
+        self.project = project;
+        self
+    }
+
+    pub fn with_credential(mut self, credential: Option<HeldCredential>) -> Self {
+        self.credential = credential;
+        self
+    }
+}
@@ -1,18 +1,78 @@
 use minicbor::{Decode, Encode};
+use ockam_core::INTERNAL_ADDRESS_ATTRIBUTE;
+use ockam_node::WorkerInfo;
 
 #[derive(Debug, Clone, Decode, Encode)]
 #[rustfmt::skip]
 #[cbor(map)]
 pub struct WorkerStatus {
     #[n(2)] pub addr: String,
+    /// Only returned by the nodes with an enabled debug API
+    #[n(3)] pub details: Option<WorkerDetails>,
 }
 
 impl WorkerStatus {
     pub fn new(addr: impl Into<String>) -> Self {
-        Self { addr: addr.into() }
+        Self {
+            addr: addr.into(),
+            details: None,
+        }
     }
 }
 
+impl From<WorkerInfo> for WorkerStatus {
+    fn from(info: WorkerInfo) -> Self {
+        let kind = if info.processor {
+            "processor"
+        } else if info.detached {
+            "context"
+        } else {
+            "worker"
+        };
+        let internal = info.metadata.iter().find_map(|m| {
+            m.metadata
+                .attributes
+                .iter()
+                .find(|(key, _)| key == INTERNAL_ADDRESS_ATTRIBUTE)
+                .map(|(_, owner)| owner.clone())
+        });
+        Self {
+            addr: info.address.address().to_string(),
+            details: Some(WorkerDetails {
+                kind: kind.to_string(),
+                aliases: info
+                    .aliases
+                    .iter()
+                    .map(|a| a.address().to_string())
+                    .collect(),
+                routed_messages: info.routed_messages,
+                pending_messages: info.pending_messages as u64,
+                terminal: info.metadata.iter().any(|m| m.metadata.is_terminal),
+                internal,
+            }),
+        }
+    }
+}
+
+/// Details about a worker, to debug a node
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct WorkerDetails {
+    /// One of `worker`, `processor` or `context`
+    #[n(1)] pub kind: String,
+    /// Other addresses of the worker
+    #[n(2)] pub aliases: Vec<String>,
+    /// Number of messages routed to the worker since it was started
+    #[n(3)] pub routed_messages: u64,
+    /// Number of messages waiting in the mailbox of the worker
+    #[n(4)] pub pending_messages: u64,
+    /// True if the worker forwards messages to another node
+    #[n(5)] pub terminal: bool,
+    /// Owner of the worker, if it is only used internally by the node
+    #[n(6)] pub internal: Option<String>,
+}
+
 /// Response body for listing workers
 #[derive(Debug, Clone, Decode, Encode)]
 #[rustfmt::skip]
@@ -26,3 +86,40 @@ impl WorkerList {
         Self { list }
     }
 }
+
+/// Request body to send a raw message to a worker of a node
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SendWorkerMessage {
+    /// Local address of the worker
+    #[n(1)] pub to: String,
+    #[n(2)] pub payload: Vec<u8>,
+    /// Maximum time to wait for a reply, in milliseconds
+    #[n(3)] pub timeout: u64,
+}
+
+impl SendWorkerMessage {
+    pub fn new(to: impl Into<String>, payload: Vec<u8>, timeout: u64) -> Self {
+        Self {
+            to: to.into(),
+            payload,
+            timeout,
+        }
+    }
+}
+
+/// Response body for sending a raw message to a worker
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SendWorkerMessageReply {
+    /// Payload of the reply, unless no reply was received before the timeout
+    #[n(1)] pub reply: Option<Vec<u8>>,
+}
+
+impl SendWorkerMessageReply {
+    pub fn new(reply: Option<Vec<u8>>) -> Self {
+        Self { reply }
+    }
+}
@@ -1,15 +1,24 @@
+use crate::kafka::{BrokerAuthValidation, TopicAllowlist};
+use crate::nodes::models::portal::{InletCreationOptions, OutletStatus};
 use crate::nodes::models::relay::RelayInfo;
+use crate::nodes::models::services::BuiltinService;
 use crate::session::sessions::{ReplacerOutputKind, Session};
 use crate::{random_name, DefaultAddress};
-use ockam::identity::Identifier;
-use ockam::identity::{SecureChannel, SecureChannelListener};
+use ockam::identity::models::CredentialAndPurposeKey;
+use ockam::identity::{Identifier, TimestampInSeconds};
+use ockam::identity::{SecureChannel, SecureChannelListener, TrustAllowlistPolicy};
+use ockam::remote::RemoteRelayHeartbeats;
 use ockam_core::compat::collections::BTreeMap;
-use ockam_core::{Address, Route};
+use ockam_core::compat::sync::Arc;
+use ockam_core::{Address, IncomingAccessControl, RateLimiter, Route};
 use ockam_multiaddr::MultiAddr;
 use ockam_node::compat::asynchronous::RwLock;
+use ockam_node::tokio::sync::Notify;
+use ockam_transport_tcp::TcpInletConnectionLimits;
 use std::borrow::Borrow;
 use std::fmt::Display;
 use std::net::SocketAddr;
+use std::time::Duration;
 
 #[derive(Default)]
 pub(crate) struct SecureChannelRegistry {
@@ -80,32 +89,160 @@ impl SecureChannelInfo {
     }
 }
 
+/// Secure channel created with a name, which can be used in place of its
+/// address with a `/channel/<name>` segment
+#[derive(Clone, Debug)]
+pub struct NamedSecureChannelInfo {
+    addr: MultiAddr,
+    identity_name: Option<String>,
+    authorized_identifiers: Option<Vec<Identifier>>,
+    credential: Option<CredentialAndPurposeKey>,
+    timeout: Option<Duration>,
+    rekey_interval: Option<Duration>,
+    auto_reconnect: bool,
+    encryptor_address: Address,
+}
+
+impl NamedSecureChannelInfo {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        addr: MultiAddr,
+        identity_name: Option<String>,
+        authorized_identifiers: Option<Vec<Identifier>>,
+        credential: Option<CredentialAndPurposeKey>,
+        timeout: Option<Duration>,
+        rekey_interval: Option<Duration>,
+        auto_reconnect: bool,
+        encryptor_address: Address,
+    ) -> Self {
+        Self {
+            addr,
+            identity_name,
+            authorized_identifiers,
+            credential,
+            timeout,
+            rekey_interval,
+            auto_reconnect,
+            encryptor_address,
+        }
+    }
+
+    /// Route to the secure channel listener
+    pub fn addr(&self) -> &MultiAddr {
+        &self.addr
+    }
+
+    pub fn identity_name(&self) -> Option<String> {
+        self.identity_name.clone()
+    }
+
+    pub fn authorized_identifiers(&self) -> Option<Vec<Identifier>> {
+        self.authorized_identifiers.clone()
+    }
+
+    pub fn credential(&self) -> Option<CredentialAndPurposeKey> {
+        self.credential.clone()
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    pub fn rekey_interval(&self) -> Option<Duration> {
+        self.rekey_interval
+    }
+
+    /// True if the channel must be created again when it is found to be stale
+    pub fn auto_reconnect(&self) -> bool {
+        self.auto_reconnect
+    }
+
+    /// Address of the channel currently used for this name
+    pub fn encryptor_address(&self) -> &Address {
+        &self.encryptor_address
+    }
+
+    pub fn with_encryptor_address(mut self, encryptor_address: Address) -> Self {
+        self.encryptor_address = encryptor_address;
+        self
+    }
+}
+
 #[derive(Clone)]
 pub struct SecureChannelListenerInfo {
     listener: SecureChannelListener,
+    identifier: Identifier,
+    allowlist: TrustAllowlistPolicy,
+    handshake_rate_limiter: Option<RateLimiter>,
 }
 
 impl SecureChannelListenerInfo {
-    pub fn new(listener: SecureChannelListener) -> Self {
-        Self { listener }
+    pub fn new(
+        listener: SecureChannelListener,
+        identifier: Identifier,
+        allowlist: TrustAllowlistPolicy,
+        handshake_rate_limiter: Option<RateLimiter>,
+    ) -> Self {
+        Self {
+            listener,
+            identifier,
+            allowlist,
+            handshake_rate_limiter,
+        }
     }
 
     pub fn listener(&self) -> &SecureChannelListener {
         &self.listener
     }
+
+    /// Identifier of the identity used by the listener
+    pub fn identifier(&self) -> &Identifier {
+        &self.identifier
+    }
+
+    /// Identifiers allowed to initiate a secure channel with this listener, if restricted
+    pub fn authorized_identifiers(&self) -> Option<Vec<Identifier>> {
+        self.allowlist.identity_ids()
+    }
+
+    /// Trust policy checked by the listener during each handshake.
+    /// It can be updated while the listener is running
+    pub fn allowlist(&self) -> &TrustAllowlistPolicy {
+        &self.allowlist
+    }
+
+    /// Limiter of the handshakes started with the listener, if their rate is limited
+    pub fn handshake_rate_limiter(&self) -> Option<&RateLimiter> {
+        self.handshake_rate_limiter.as_ref()
+    }
 }
 
 #[derive(Default, Clone)]
 pub(crate) struct OktaIdentityProviderServiceInfo {}
 
-#[derive(Default, Clone)]
-pub(crate) struct UppercaseServiceInfo {}
+/// Built-in service started on the node
+#[derive(Clone)]
+pub(crate) struct ServiceInfo {
+    service: BuiltinService,
+    started_at: TimestampInSeconds,
+}
 
-#[derive(Default, Clone)]
-pub(crate) struct EchoerServiceInfo {}
+impl ServiceInfo {
+    pub fn new(service: BuiltinService, started_at: TimestampInSeconds) -> Self {
+        Self {
+            service,
+            started_at,
+        }
+    }
 
-#[derive(Default, Clone)]
-pub(crate) struct HopServiceInfo {}
+    pub fn service(&self) -> BuiltinService {
+        self.service
+    }
+
+    pub fn started_at(&self) -> TimestampInSeconds {
+        self.started_at
+    }
+}
 
 #[derive(Eq, PartialEq, Clone)]
 pub enum KafkaServiceKind {
@@ -126,19 +263,59 @@ impl Display for KafkaServiceKind {
     }
 }
 
+impl KafkaServiceKind {
+    /// Type of the service, as displayed in the list of services
+    pub fn service_type(&self) -> &'static str {
+        match self {
+            KafkaServiceKind::Consumer => DefaultAddress::KAFKA_CONSUMER,
+            KafkaServiceKind::Producer => DefaultAddress::KAFKA_PRODUCER,
+            KafkaServiceKind::Outlet => DefaultAddress::KAFKA_OUTLET,
+            KafkaServiceKind::Direct => DefaultAddress::KAFKA_DIRECT,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub(crate) struct KafkaServiceInfo {
     kind: KafkaServiceKind,
+    broker_auth: Option<BrokerAuthValidation>,
+    topic_allowlist: TopicAllowlist,
 }
 
 impl KafkaServiceInfo {
     pub fn new(kind: KafkaServiceKind) -> Self {
-        Self { kind }
+        Self {
+            kind,
+            broker_auth: None,
+            topic_allowlist: TopicAllowlist::default(),
+        }
+    }
+
+    pub fn with_broker_auth(self, broker_auth: Option<BrokerAuthValidation>) -> Self {
+        Self {
+            broker_auth,
+            ..self
+        }
     }
 
     pub fn kind(&self) -> &KafkaServiceKind {
         &self.kind
     }
+
+    pub fn broker_auth(&self) -> Option<BrokerAuthValidation> {
+        self.broker_auth.clone()
+    }
+
+    pub fn with_topic_allowlist(self, topic_allowlist: TopicAllowlist) -> Self {
+        Self {
+            topic_allowlist,
+            ..self
+        }
+    }
+
+    pub fn topic_allowlist(&self) -> &TopicAllowlist {
+        &self.topic_allowlist
+    }
 }
 
 #[derive(Clone)]
@@ -146,22 +323,85 @@ pub(crate) struct InletInfo {
     pub(crate) bind_addr: String,
     pub(crate) outlet_addr: MultiAddr,
     pub(crate) session: Session,
+    pub(crate) connection_limits: TcpInletConnectionLimits,
+    pub(crate) no_delay: bool,
+    pub(crate) identity: Option<String>,
+    pub(crate) unix_socket_mode: Option<u32>,
+    pub(crate) expect_proxy_protocol: bool,
+    pub(crate) creation_options: InletCreationOptions,
 }
 
 impl InletInfo {
-    pub(crate) fn new(bind_addr: &str, outlet_addr: MultiAddr, session: Session) -> Self {
+    pub(crate) fn new(
+        bind_addr: &str,
+        outlet_addr: MultiAddr,
+        session: Session,
+        connection_limits: TcpInletConnectionLimits,
+        no_delay: bool,
+    ) -> Self {
         Self {
             bind_addr: bind_addr.to_owned(),
             outlet_addr,
             session,
+            connection_limits,
+            no_delay,
+            identity: None,
+            unix_socket_mode: None,
+            expect_proxy_protocol: false,
+            creation_options: InletCreationOptions::default(),
         }
     }
+
+    /// Keep the name of the identity used by the inlet, when it is not the node identity
+    pub(crate) fn with_identity(mut self, identity: Option<String>) -> Self {
+        self.identity = identity;
+        self
+    }
+
+    /// Keep the file mode set on the Unix domain socket of the inlet
+    pub(crate) fn with_unix_socket_mode(mut self, unix_socket_mode: Option<u32>) -> Self {
+        self.unix_socket_mode = unix_socket_mode;
+        self
+    }
+
+    /// Keep whether the inlet expects a PROXY protocol header from its clients
+    pub(crate) fn with_expect_proxy_protocol(mut self, expect_proxy_protocol: bool) -> Self {
+        self.expect_proxy_protocol = expect_proxy_protocol;
+        self
+    }
+
+    /// Keep the other options the inlet was created with, to return them in its status
+    pub(crate) fn with_creation_options(mut self, creation_options: InletCreationOptions) -> Self {
+        self.creation_options = creation_options;
+        self
+    }
+}
+
+/// Connection established when the node starts, to be reused by the services created later
+#[derive(Clone)]
+pub(crate) struct WarmConnectionInfo {
+    pub(crate) addr: MultiAddr,
+    pub(crate) session: Session,
+}
+
+impl WarmConnectionInfo {
+    pub(crate) fn new(addr: MultiAddr, session: Session) -> Self {
+        Self { addr, session }
+    }
 }
 
 #[derive(Clone)]
 pub struct OutletInfo {
     pub(crate) socket_addr: SocketAddr,
     pub(crate) worker_addr: Address,
+    // options used to create the outlet, to create it again when it is renamed
+    pub(crate) reachable_from_default_secure_channel: bool,
+    pub(crate) incoming_access_control: Option<Arc<dyn IncomingAccessControl>>,
+    pub(crate) buffer_size: Option<usize>,
+    pub(crate) hostname_port: Option<String>,
+    pub(crate) identity: Option<String>,
+    pub(crate) unix_socket_path: Option<String>,
+    pub(crate) proxy_protocol: bool,
 }
 
 impl OutletInfo {
@@ -173,8 +413,62 @@ impl OutletInfo {
         Self {
             socket_addr: *socket_addr,
             worker_addr,
+            reachable_from_default_secure_channel: false,
+            incoming_access_control: None,
+            buffer_size: None,
+            hostname_port: None,
+            identity: None,
+            unix_socket_path: None,
+            proxy_protocol: false,
         }
     }
+
+    /// Keep the `host:port` the outlet connects to, when it was created with a host name
+    pub(crate) fn with_hostname_port(mut self, hostname_port: Option<String>) -> Self {
+        self.hostname_port = hostname_port;
+        self
+    }
+
+    /// Keep the name of the identity whose secure channel listeners can reach the outlet
+    pub(crate) fn with_identity(mut self, identity: Option<String>) -> Self {
+        self.identity = identity;
+        self
+    }
+
+    /// Keep the path of the Unix domain socket the outlet connects to, if it doesn't connect to a TCP server
+    pub(crate) fn with_unix_socket_path(mut self, unix_socket_path: Option<String>) -> Self {
+        self.unix_socket_path = unix_socket_path;
+        self
+    }
+
+    /// Keep whether the outlet sends a PROXY protocol header to its target
+    pub(crate) fn with_proxy_protocol(mut self, proxy_protocol: bool) -> Self {
+        self.proxy_protocol = proxy_protocol;
+        self
+    }
+
+    /// Return the status of the outlet
+    pub(crate) fn status(&self) -> OutletStatus {
+        OutletStatus::new(self.socket_addr, self.worker_addr.clone(), None)
+            .with_hostname_port(self.hostname_port.clone())
+            .with_identity(self.identity.clone())
+            .with_unix_socket_path(self.unix_socket_path.clone())
+            .with_proxy_protocol(self.proxy_protocol)
+    }
+
+    /// Keep the options used to create the outlet.
+    /// The incoming access control is only set when it is not created from a policy
+    pub(crate) fn with_options(
+        mut self,
+        reachable_from_default_secure_channel: bool,
+        incoming_access_control: Option<Arc<dyn IncomingAccessControl>>,
+        buffer_size: Option<usize>,
+    ) -> Self {
+        self.reachable_from_default_secure_channel = reachable_from_default_secure_channel;
+        self.incoming_access_control = incoming_access_control;
+        self.buffer_size = buffer_size;
+        self
+    }
 }
 
 #[derive(Clone)]
@@ -183,6 +477,7 @@ pub struct RegistryRelayInfo {
     pub(crate) alias: String,
     pub(crate) at_rust_node: bool,
     pub(crate) session: Session,
+    pub(crate) heartbeats: RemoteRelayHeartbeats,
 }
 
 impl From<RegistryRelayInfo> for RelayInfo {
@@ -192,17 +487,23 @@ impl From<RegistryRelayInfo> for RelayInfo {
             registry_relay_info.alias.clone(),
             registry_relay_info.at_rust_node,
             registry_relay_info.session.connection_status(),
-        );
+        )
+        .with_heartbeats(registry_relay_info.heartbeats.status())
+        .with_reconnection_attempts(registry_relay_info.session.replacement_attempts());
+        let relay_info = match registry_relay_info.session.last_failure() {
+            Some(last_failure) => relay_info.with_last_failure(last_failure),
+            None => relay_info,
+        };
 
         let current_relay_status =
             registry_relay_info
                 .session
                 .status()
                 .map(|info| match info.kind {
-                    ReplacerOutputKind::Inlet(_) => {
-                        panic!("InletInfo should not be in the registry")
-                    }
                     ReplacerOutputKind::Relay(info) => info,
+                    _ => {
+                        panic!("Only relays should be in the relays registry")
+                    }
                 });
 
         if let Some(current_relay_status) = current_relay_status {
@@ -216,14 +517,16 @@ impl From<RegistryRelayInfo> for RelayInfo {
 #[derive(Default)]
 pub(crate) struct Registry {
     pub(crate) secure_channels: SecureChannelRegistry,
+    pub(crate) named_secure_channels: RegistryOf<String, NamedSecureChannelInfo>,
     pub(crate) secure_channel_listeners: RegistryOf<Address, SecureChannelListenerInfo>,
-    pub(crate) uppercase_services: RegistryOf<Address, UppercaseServiceInfo>,
-    pub(crate) echoer_services: RegistryOf<Address, EchoerServiceInfo>,
+    pub(crate) services: RegistryOf<Address, ServiceInfo>,
     pub(crate) kafka_services: RegistryOf<Address, KafkaServiceInfo>,
-    pub(crate) hop_services: RegistryOf<Address, HopServiceInfo>,
     pub(crate) relays: RegistryOf<String, RegistryRelayInfo>,
     pub(crate) inlets: RegistryOf<String, InletInfo>,
     pub(crate) outlets: RegistryOf<Address, OutletInfo>,
+    pub(crate) warm_connections: RegistryOf<String, WarmConnectionInfo>,
+    /// Notified when a session must be replaced without waiting for the next check of the medic
+    pub(crate) session_replacements: Notify,
 }
 
 pub(crate) struct RegistryOf<K, V> {
@@ -288,6 +591,33 @@ impl<K: Clone, V: Clone> RegistryOf<K, V> {
         let map = self.map.read().await;
         map.contains_key(key)
     }
+
+    /// Move the value stored for a key to a new key.
+    /// Return None if there is no value for the key or if the new key is already used
+    pub async fn rename<Q: ?Sized>(&self, key: &Q, new_key: K) -> Option<V>
+    where
+        K: Borrow<Q> + Ord,
+        Q: Ord,
+    {
+        let mut map = self.map.write().await;
+        if map.contains_key(&new_key) {
+            return None;
+        }
+        let value = map.remove(key)?;
+        map.insert(new_key, value.clone());
+        Some(value)
+    }
+}
+
+impl RegistryOf<Address, ServiceInfo> {
+    /// Return the addresses of the started services of a given kind
+    pub async fn addresses_of(&self, service: BuiltinService) -> Vec<Address> {
+        let map = self.map.read().await;
+        map.iter()
+            .filter(|(_, info)| info.service == service)
+            .map(|(address, _)| address.clone())
+            .collect()
+    }
 }
 
 impl RegistryOf<Address, OutletInfo> {
@@ -385,6 +715,24 @@ mod tests {
         assert_ne!(worker_addr, DefaultAddress::OUTLET_SERVICE.into());
     }
 
+    #[tokio::test]
+    async fn registry_rename() {
+        let registry: RegistryOf<String, u32> = RegistryOf::default();
+        registry.insert("a".to_string(), 1).await;
+        registry.insert("b".to_string(), 2).await;
+
+        // the new key is already used
+        assert_eq!(registry.rename("a", "b".to_string()).await, None);
+        // there is no value for the key
+        assert_eq!(registry.rename("c", "d".to_string()).await, None);
+
+        assert_eq!(registry.rename("a", "c".to_string()).await, Some(1));
+        assert_eq!(
+            registry.keys().await,
+            vec!["b".to_string(), "c".to_string()]
+        );
+    }
+
     fn outlet_info(worker_addr: Address) -> OutletInfo {
         OutletInfo::new(&SocketAddr::from(([127, 0, 0, 1], 0)), Some(&worker_addr))
     }
@@ -6,19 +6,27 @@ use ockam::{Address, Result};
 use ockam_core::api::{RequestHeader, Response};
 use ockam_core::compat::string::String;
 
+mod attributes;
 pub(crate) mod background_node_client;
 pub mod default_address;
 mod flow_controls;
+mod health_check;
 pub(crate) mod in_memory_node;
+pub(crate) mod in_memory_node_builder;
 pub mod kafka_services;
+mod log_level;
 pub mod messages;
+#[cfg(feature = "telemetry")]
+mod metrics;
 mod node_services;
 pub(crate) mod policy;
+mod portal_access_control;
 pub mod portals;
 mod projects;
 pub mod relay;
 mod secure_channel;
 mod transport;
+mod warm_connections;
 pub mod workers;
 
 mod manager;
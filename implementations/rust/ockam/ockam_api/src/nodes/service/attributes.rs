@@ -0,0 +1,40 @@
+use std::sync::Arc;
+
+use ockam::identity::{Identifier, IdentitiesAttributes};
+use ockam::Result;
+use ockam_core::api::{Error, Response};
+
+use crate::nodes::models::attributes::FlushAttributesRequest;
+use crate::nodes::{NodeManager, NodeManagerWorker};
+
+impl NodeManagerWorker {
+    pub(super) async fn flush_attributes(
+        &self,
+        request: FlushAttributesRequest,
+    ) -> Result<Response<()>, Response<Error>> {
+        self.node_manager
+            .flush_attributes(&request.identifiers)
+            .await?;
+        Ok(Response::ok())
+    }
+}
+
+impl NodeManager {
+    /// Return the attributes of the identities which presented a credential to this node
+    pub fn identities_attributes(&self) -> Arc<IdentitiesAttributes> {
+        self.secure_channels.identities().identities_attributes()
+    }
+
+    /// Remove the attributes of the given identities, or of all the identities if none is given.
+    ///
+    /// The policies of the inlets, outlets and services of the node are evaluated again
+    /// with the remaining attributes, so that an identity is denied access until it presents
+    /// a new credential. Access granted during the last second can still be served from the
+    /// cache of the access controls
+    pub async fn flush_attributes(&self, identifiers: &[Identifier]) -> Result<()> {
+        info!(?identifiers, "flushing the identities attributes");
+        self.identities_attributes()
+            .flush_attributes(identifiers)
+            .await
+    }
+}
@@ -1,10 +1,14 @@
+use std::cmp::min;
+use std::future::Future;
 use std::sync::Arc;
 use std::time::Duration;
 
-use miette::{miette, IntoDiagnostic};
+use miette::{miette, Diagnostic, IntoDiagnostic};
 use minicbor::{Decode, Encode};
+use tokio_retry::RetryIf;
 
-use ockam_core::api::{Reply, Request};
+use ockam_core::api::{Reply, Request, Status};
+use ockam_core::errcode::Kind;
 use ockam_core::Route;
 use ockam_node::api::Client;
 use ockam_node::Context;
@@ -13,6 +17,9 @@ use ockam_transport_tcp::{TcpConnection, TcpConnectionOptions, TcpTransport};
 use crate::cli_state::CliState;
 use crate::nodes::NODEMANAGER_ADDR;
 
+/// Timeout of the requests sent to a background node, unless another timeout is configured
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
 /// This struct represents a Client to a node that has been started
 /// on the same machine with a given node name
 ///
@@ -39,10 +46,7 @@ impl BackgroundNodeClient {
         cli_state: &CliState,
         node_name: &Option<String>,
     ) -> miette::Result<BackgroundNodeClient> {
-        let node_name = match node_name.clone() {
-            Some(name) => name,
-            None => cli_state.get_default_node().await?.name(),
-        };
+        let node_name = cli_state.get_node_or_default(node_name).await?.name();
         Self::create_to_node(ctx, cli_state, &node_name).await
     }
 
@@ -73,7 +77,12 @@ impl BackgroundNodeClient {
             cli_state: cli_state.clone(),
             node_name: node_name.to_string(),
             to: NODEMANAGER_ADDR.into(),
-            timeout: Some(Duration::from_secs(30)),
+            timeout: Some(
+                cli_state
+                    .defaults()
+                    .request_timeout
+                    .unwrap_or(DEFAULT_REQUEST_TIMEOUT),
+            ),
             tcp_transport: Arc::new(tcp_transport.clone()),
         })
     }
@@ -103,6 +112,12 @@ impl BackgroundNodeClient {
         Self { timeout, ..self }
     }
 
+    /// Return a copy of this client using a specific timeout.
+    /// The timeout of this client is left unchanged
+    pub fn with_timeout(&self, timeout: Duration) -> Self {
+        self.clone().set_timeout(Some(timeout))
+    }
+
     pub fn cli_state(&self) -> &CliState {
         &self.cli_state
     }
@@ -113,10 +128,7 @@ impl BackgroundNodeClient {
         T: Encode<()>,
         R: for<'b> Decode<'b, ()>,
     {
-        self.ask_and_get_reply(ctx, req)
-            .await?
-            .success()
-            .into_diagnostic()
+        Ok(self.try_ask(ctx, req, self.timeout).await?)
     }
 
     /// Send a request and expect a decodable response and use a specific timeout
@@ -130,14 +142,64 @@ impl BackgroundNodeClient {
         T: Encode<()>,
         R: for<'b> Decode<'b, ()>,
     {
-        let (tcp_connection, client) = self.make_client_with_timeout(Some(timeout)).await?;
+        Ok(self.try_ask(ctx, req, Some(timeout)).await?)
+    }
+
+    /// Send the request created by `make_request` until a decodable response is received
+    /// or until the retry policy gives up
+    pub async fn ask_with_retry<T, R, F>(
+        &self,
+        ctx: &Context,
+        make_request: F,
+        retry_policy: &RetryPolicy,
+    ) -> miette::Result<R>
+    where
+        T: Encode<()>,
+        R: for<'b> Decode<'b, ()>,
+        F: Fn() -> Request<T>,
+    {
+        Ok(retry_policy
+            .retry(|| self.try_ask(ctx, make_request(), self.timeout))
+            .await?)
+    }
+
+    /// Send a request and expect a decodable response, with an optional timeout.
+    /// The returned error indicates if the node could not be reached, did not respond in time
+    /// or responded with a failure
+    pub async fn try_ask<T, R>(
+        &self,
+        ctx: &Context,
+        req: Request<T>,
+        timeout: Option<Duration>,
+    ) -> Result<R, NodeRequestError>
+    where
+        T: Encode<()>,
+        R: for<'b> Decode<'b, ()>,
+    {
+        let reply = self.try_ask_and_get_reply(ctx, req, timeout).await?;
+        NodeRequestError::check_reply(&self.node_name, reply)
+    }
 
+    /// Send a request and return the API reply, with an optional timeout.
+    /// The returned error indicates if the node could not be reached or did not respond in time
+    pub async fn try_ask_and_get_reply<T, R>(
+        &self,
+        ctx: &Context,
+        req: Request<T>,
+        timeout: Option<Duration>,
+    ) -> Result<Reply<R>, NodeRequestError>
+    where
+        T: Encode<()>,
+        R: for<'b> Decode<'b, ()>,
+    {
+        let (tcp_connection, client) = self
+            .make_client_with_timeout(timeout)
+            .await
+            .map_err(|e| NodeRequestError::unreachable(&self.node_name, e))?;
         let res = client
             .ask(ctx, req)
             .await
-            .into_diagnostic()?
-            .success()
-            .into_diagnostic();
+            .map_err(|e| NodeRequestError::from_core(&self.node_name, e, timeout));
 
         _ = tcp_connection.stop(ctx).await;
         res
@@ -154,11 +216,7 @@ impl BackgroundNodeClient {
         T: Encode<()>,
         R: for<'b> Decode<'b, ()>,
     {
-        let (tcp_connection, client) = self.make_client().await?;
-        let res = client.ask(ctx, req).await.into_diagnostic();
-
-        _ = tcp_connection.stop(ctx).await;
-        res
+        Ok(self.try_ask_and_get_reply(ctx, req, self.timeout).await?)
     }
 
     /// Send a request but don't decode the response
@@ -252,3 +310,248 @@ impl BackgroundNodeClient {
         Ok((tcp_connection, Client::new(&route, timeout)))
     }
 }
+
+/// Error returned when a request sent to a background node does not succeed
+#[derive(Debug, thiserror::Error, Diagnostic)]
+pub enum NodeRequestError {
+    /// The node could not be reached, for example because it is not running
+    #[error("The node {node_name} is unreachable: {reason}")]
+    Unreachable { node_name: String, reason: String },
+
+    /// The node did not respond before the request timeout
+    #[error("The node {node_name} did not respond within {timeout:?}")]
+    Timeout {
+        node_name: String,
+        timeout: Duration,
+    },
+
+    /// The node received the request and responded with a failure
+    #[error("The node {node_name} failed to process the request: {message}")]
+    Failed {
+        node_name: String,
+        message: String,
+        status: Option<Status>,
+    },
+}
+
+impl NodeRequestError {
+    pub fn unreachable(node_name: &str, reason: impl ToString) -> Self {
+        NodeRequestError::Unreachable {
+            node_name: node_name.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    fn from_core(node_name: &str, error: ockam_core::Error, timeout: Option<Duration>) -> Self {
+        match timeout {
+            Some(timeout) if error.code().kind == Kind::Timeout => NodeRequestError::Timeout {
+                node_name: node_name.to_string(),
+                timeout,
+            },
+            _ => NodeRequestError::unreachable(node_name, error),
+        }
+    }
+
+    /// Return the value of a successful reply, or a `Failed` error
+    pub fn check_reply<R>(node_name: &str, reply: Reply<R>) -> Result<R, NodeRequestError> {
+        match reply {
+            Reply::Successful(r) => Ok(r),
+            Reply::Failed(e, status) => Err(NodeRequestError::Failed {
+                node_name: node_name.to_string(),
+                message: e
+                    .message()
+                    .unwrap_or("no message defined for this error")
+                    .to_string(),
+                status,
+            }),
+        }
+    }
+
+    /// Recover the typed error from a report returned by one of the client methods.
+    /// Any other error is interpreted as the node being unreachable
+    pub fn from_report(node_name: &str, report: miette::Report) -> Self {
+        report
+            .downcast::<NodeRequestError>()
+            .unwrap_or_else(|r| NodeRequestError::unreachable(node_name, r))
+    }
+
+    /// Return true if the node could not be reached or did not respond in time
+    pub fn is_unreachable(&self) -> bool {
+        !matches!(self, NodeRequestError::Failed { .. })
+    }
+
+    /// Return true if sending the same request again could succeed.
+    /// Requests rejected because they are invalid or not authorized are not retried
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            NodeRequestError::Failed {
+                status: Some(status),
+                ..
+            } => !matches!(
+                status,
+                Status::BadRequest
+                    | Status::Unauthorized
+                    | Status::Forbidden
+                    | Status::MethodNotAllowed
+                    | Status::NotImplemented
+            ),
+            _ => true,
+        }
+    }
+}
+
+/// Policy used to send a request to a node again when it fails.
+/// The delay between two attempts doubles after each attempt, up to a maximum delay
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: Option<usize>,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::new(3, Duration::from_millis(500), Duration::from_secs(5))
+    }
+}
+
+impl RetryPolicy {
+    /// Make at most `max_attempts` attempts, with an exponential backoff between them
+    pub fn new(max_attempts: usize, initial_backoff: Duration, max_backoff: Duration) -> Self {
+        Self {
+            max_attempts: Some(max_attempts),
+            initial_backoff,
+            max_backoff: max_backoff.max(initial_backoff),
+        }
+    }
+
+    /// Make a single attempt
+    pub fn no_retry() -> Self {
+        Self::new(1, Duration::ZERO, Duration::ZERO)
+    }
+
+    /// Retry until the request succeeds or fails with a non-retryable error,
+    /// waiting for a fixed delay between attempts
+    pub fn forever(backoff: Duration) -> Self {
+        Self {
+            max_attempts: None,
+            initial_backoff: backoff,
+            max_backoff: backoff,
+        }
+    }
+
+    pub fn max_attempts(&self) -> Option<usize> {
+        self.max_attempts
+    }
+
+    /// Return the delays to wait before each retry
+    pub fn delays(&self) -> impl Iterator<Item = Duration> {
+        let max_backoff = self.max_backoff;
+        let retries = self
+            .max_attempts
+            .map(|n| n.saturating_sub(1))
+            .unwrap_or(usize::MAX);
+        std::iter::successors(Some(self.initial_backoff), move |d| {
+            Some(min(d.saturating_mul(2), max_backoff))
+        })
+        .take(retries)
+    }
+
+    /// Run an operation until it succeeds, fails with a non-retryable error,
+    /// or until the maximum number of attempts is reached
+    pub async fn retry<T, A, Fut>(&self, action: A) -> Result<T, NodeRequestError>
+    where
+        A: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, NodeRequestError>>,
+    {
+        self.retry_notify(action, |_| {}).await
+    }
+
+    /// Run an operation like `retry` and call `notify` with the error before each new attempt
+    pub async fn retry_notify<T, A, Fut, N>(
+        &self,
+        action: A,
+        mut notify: N,
+    ) -> Result<T, NodeRequestError>
+    where
+        A: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, NodeRequestError>>,
+        N: FnMut(&NodeRequestError),
+    {
+        RetryIf::spawn(self.delays(), action, |e: &NodeRequestError| {
+            let retry = e.is_retryable();
+            if retry {
+                notify(e)
+            }
+            retry
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn retry_delays_are_capped() {
+        let policy = RetryPolicy::new(5, Duration::from_secs(1), Duration::from_secs(3));
+        let delays: Vec<u64> = policy.delays().map(|d| d.as_secs()).collect();
+        assert_eq!(delays, vec![1, 2, 3, 3]);
+
+        assert_eq!(RetryPolicy::no_retry().delays().count(), 0);
+        assert_eq!(
+            RetryPolicy::forever(Duration::from_secs(1))
+                .delays()
+                .take(10)
+                .count(),
+            10
+        );
+    }
+
+    #[tokio::test]
+    async fn only_retryable_errors_are_retried() {
+        let policy = RetryPolicy::new(3, Duration::ZERO, Duration::ZERO);
+
+        let attempts = AtomicUsize::new(0);
+        let result: Result<(), _> = policy
+            .retry(|| async {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err(NodeRequestError::unreachable("n", "connection refused"))
+            })
+            .await;
+        assert!(result.unwrap_err().is_unreachable());
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+
+        let attempts = AtomicUsize::new(0);
+        let result: Result<(), _> = policy
+            .retry(|| async {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err(NodeRequestError::Failed {
+                    node_name: "n".to_string(),
+                    message: "invalid request".to_string(),
+                    status: Some(Status::BadRequest),
+                })
+            })
+            .await;
+        assert!(!result.unwrap_err().is_unreachable());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn typed_errors_are_recovered_from_reports() {
+        let report: miette::Report = NodeRequestError::Timeout {
+            node_name: "n".to_string(),
+            timeout: Duration::from_secs(1),
+        }
+        .into();
+        assert!(matches!(
+            NodeRequestError::from_report("n", report),
+            NodeRequestError::Timeout { .. }
+        ));
+
+        let report = miette!("some error");
+        assert!(NodeRequestError::from_report("n", report).is_unreachable());
+    }
+}
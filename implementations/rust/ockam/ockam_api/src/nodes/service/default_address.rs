@@ -5,12 +5,16 @@ impl DefaultAddress {
     pub const RELAY_SERVICE: &'static str = "forwarding_service";
     pub const UPPERCASE_SERVICE: &'static str = "uppercase";
     pub const ECHO_SERVICE: &'static str = "echo";
+    pub const TRACE_SERVICE: &'static str = "trace";
     pub const HOP_SERVICE: &'static str = "hop";
+    pub const DISCARD_SERVICE: &'static str = "discard";
+    pub const BENCH_SERVICE: &'static str = "bench";
     pub const SECURE_CHANNEL_LISTENER: &'static str = "api";
     pub const DIRECT_AUTHENTICATOR: &'static str = "direct_authenticator";
     pub const CREDENTIAL_ISSUER: &'static str = "credential_issuer";
     pub const ENROLLMENT_TOKEN_ISSUER: &'static str = "enrollment_token_issuer";
     pub const ENROLLMENT_TOKEN_ACCEPTOR: &'static str = "enrollment_token_acceptor";
+    pub const SERVICE_DIRECTORY: &'static str = "service_directory";
     pub const OKTA_IDENTITY_PROVIDER: &'static str = "okta";
     pub const KAFKA_OUTLET: &'static str = "kafka_outlet";
     pub const KAFKA_CONSUMER: &'static str = "kafka_consumer";
@@ -21,12 +25,16 @@ impl DefaultAddress {
         matches!(name, |Self::OUTLET_SERVICE| Self::RELAY_SERVICE
             | Self::UPPERCASE_SERVICE
             | Self::ECHO_SERVICE
+            | Self::TRACE_SERVICE
             | Self::HOP_SERVICE
+            | Self::DISCARD_SERVICE
+            | Self::BENCH_SERVICE
             | Self::SECURE_CHANNEL_LISTENER
             | Self::DIRECT_AUTHENTICATOR
             | Self::CREDENTIAL_ISSUER
             | Self::ENROLLMENT_TOKEN_ISSUER
             | Self::ENROLLMENT_TOKEN_ACCEPTOR
+            | Self::SERVICE_DIRECTORY
             | Self::OKTA_IDENTITY_PROVIDER
             | Self::KAFKA_CONSUMER
             | Self::KAFKA_PRODUCER
@@ -40,12 +48,16 @@ impl DefaultAddress {
             Self::RELAY_SERVICE,
             Self::UPPERCASE_SERVICE,
             Self::ECHO_SERVICE,
+            Self::TRACE_SERVICE,
             Self::HOP_SERVICE,
+            Self::DISCARD_SERVICE,
+            Self::BENCH_SERVICE,
             Self::SECURE_CHANNEL_LISTENER,
             Self::DIRECT_AUTHENTICATOR,
             Self::CREDENTIAL_ISSUER,
             Self::ENROLLMENT_TOKEN_ISSUER,
             Self::ENROLLMENT_TOKEN_ACCEPTOR,
+            Self::SERVICE_DIRECTORY,
             Self::OKTA_IDENTITY_PROVIDER,
             Self::KAFKA_CONSUMER,
             Self::KAFKA_PRODUCER,
@@ -68,7 +80,10 @@ mod test {
         assert!(DefaultAddress::is_valid(DefaultAddress::RELAY_SERVICE));
         assert!(DefaultAddress::is_valid(DefaultAddress::UPPERCASE_SERVICE));
         assert!(DefaultAddress::is_valid(DefaultAddress::ECHO_SERVICE));
+        assert!(DefaultAddress::is_valid(DefaultAddress::TRACE_SERVICE));
         assert!(DefaultAddress::is_valid(DefaultAddress::HOP_SERVICE));
+        assert!(DefaultAddress::is_valid(DefaultAddress::DISCARD_SERVICE));
+        assert!(DefaultAddress::is_valid(DefaultAddress::BENCH_SERVICE));
         assert!(DefaultAddress::is_valid(
             DefaultAddress::SECURE_CHANNEL_LISTENER
         ));
@@ -82,6 +97,7 @@ mod test {
         assert!(DefaultAddress::is_valid(
             DefaultAddress::ENROLLMENT_TOKEN_ACCEPTOR
         ));
+        assert!(DefaultAddress::is_valid(DefaultAddress::SERVICE_DIRECTORY));
         assert!(DefaultAddress::is_valid(
             DefaultAddress::OKTA_IDENTITY_PROVIDER
         ));
@@ -0,0 +1,268 @@
+use std::fmt::Write;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time::timeout;
+
+use ockam::Result;
+use ockam_core::AsyncTryClone;
+use ockam_node::Context;
+
+use crate::error::ApiError;
+use crate::nodes::models::base::NodeResources;
+
+use super::NodeManager;
+
+/// Maximum size of an HTTP request sent to the health check endpoint
+const MAX_REQUEST_SIZE: usize = 8 * 1024;
+
+/// Maximum time to receive an HTTP request once a client is connected
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Content type of the Prometheus text exposition format
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+impl NodeManager {
+    /// Start an HTTP endpoint serving:
+    ///
+    ///  - `/health`: a 200 response as long as the node is running
+    ///  - `/metrics`: the statistics of the node, in the Prometheus text exposition format
+    ///
+    /// Return the socket address the endpoint is bound to.
+    /// The endpoint stops serving requests once the node manager is dropped.
+    pub async fn start_health_check_endpoint(
+        self: &Arc<Self>,
+        ctx: &Context,
+        address: SocketAddr,
+    ) -> Result<SocketAddr> {
+        let listener = TcpListener::bind(address).await.map_err(ApiError::core)?;
+        let local_address = listener.local_addr().map_err(ApiError::core)?;
+        info!(%local_address, "serving the node health check and metrics");
+
+        let ctx = Arc::new(ctx.async_try_clone().await?);
+        let node_manager = Arc::downgrade(self);
+        tokio::spawn(async move {
+            loop {
+                let stream = match listener.accept().await {
+                    Ok((stream, _)) => stream,
+                    Err(e) => {
+                        warn!("cannot accept a health check connection: {e}");
+                        continue;
+                    }
+                };
+                let Some(node_manager) = node_manager.upgrade() else {
+                    break;
+                };
+                let ctx = ctx.clone();
+                // each request is served by its own task so that scrapes can run concurrently
+                tokio::spawn(async move {
+                    if let Err(e) = node_manager.serve_health_check(&ctx, stream).await {
+                        debug!("cannot serve a health check request: {e}");
+                    }
+                });
+            }
+        });
+        Ok(local_address)
+    }
+
+    /// Read one HTTP request and write its response before closing the connection
+    async fn serve_health_check(&self, ctx: &Context, mut stream: TcpStream) -> Result<()> {
+        let request = timeout(REQUEST_TIMEOUT, read_request_head(&mut stream))
+            .await
+            .map_err(ApiError::core)??;
+
+        let response = match request.split_whitespace().take(2).collect::<Vec<_>>()[..] {
+            ["GET", path] => match path.split('?').next() {
+                Some("/metrics") => {
+                    let workers = ctx.list_workers().await?.len() as u32;
+                    let metrics = prometheus_metrics(
+                        &self.node_name,
+                        &self.get_node_resources(workers),
+                        self.portal_bytes(),
+                    );
+                    http_response("200 OK", PROMETHEUS_CONTENT_TYPE, &metrics)
+                }
+                Some("/health") => http_response("200 OK", "text/plain", "OK\n"),
+                _ => http_response("404 Not Found", "text/plain", "Not Found\n"),
+            },
+            [_, _] => http_response(
+                "405 Method Not Allowed",
+                "text/plain",
+                "Method Not Allowed\n",
+            ),
+            _ => http_response("400 Bad Request", "text/plain", "Bad Request\n"),
+        };
+
+        stream
+            .write_all(response.as_bytes())
+            .await
+            .map_err(ApiError::core)?;
+        stream.shutdown().await.map_err(ApiError::core)?;
+        Ok(())
+    }
+
+    /// Return the number of bytes sent and received by the open portal connections
+    fn portal_bytes(&self) -> (u64, u64) {
+        self.tcp_transport
+            .registry()
+            .get_all_portal_connections()
+            .iter()
+            .fold((0, 0), |(sent, received), c| {
+                (sent + c.bytes_sent(), received + c.bytes_received())
+            })
+    }
+}
+
+/// Read an HTTP request until the end of its headers and return its first line.
+/// Requests sent to the endpoint have no body
+async fn read_request_head(stream: &mut TcpStream) -> Result<String> {
+    let mut request = Vec::new();
+    let mut buffer = [0u8; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+        let read = stream.read(&mut buffer).await.map_err(ApiError::core)?;
+        if read == 0 {
+            break;
+        }
+        request.extend_from_slice(&buffer[..read]);
+        if request.len() > MAX_REQUEST_SIZE {
+            return Err(ApiError::core("the health check request is too large"));
+        }
+    }
+    let request = String::from_utf8_lossy(&request);
+    Ok(request.lines().next().unwrap_or_default().to_string())
+}
+
+fn http_response(status: &str, content_type: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+/// Render the statistics of a node in the Prometheus text exposition format
+fn prometheus_metrics(
+    node_name: &str,
+    resources: &NodeResources,
+    portal_bytes: (u64, u64),
+) -> String {
+    let (bytes_sent, bytes_received) = portal_bytes;
+    let mut metrics = vec![
+        (
+            "ockam_node_workers",
+            "Number of workers running on the node",
+            "gauge",
+            resources.workers as u64,
+        ),
+        (
+            "ockam_node_tcp_connections",
+            "Number of open TCP connections",
+            "gauge",
+            resources.tcp_connections as u64,
+        ),
+        (
+            "ockam_node_tcp_listeners",
+            "Number of TCP listeners",
+            "gauge",
+            resources.tcp_listeners as u64,
+        ),
+        (
+            "ockam_node_portal_connections",
+            "Number of open portal connections",
+            "gauge",
+            resources.portal_connections as u64,
+        ),
+        (
+            "ockam_node_portal_bytes_sent",
+            "Number of bytes written by the open portal connections",
+            "gauge",
+            bytes_sent,
+        ),
+        (
+            "ockam_node_portal_bytes_received",
+            "Number of bytes read by the open portal connections",
+            "gauge",
+            bytes_received,
+        ),
+        (
+            "ockam_node_secure_channels",
+            "Number of secure channels",
+            "gauge",
+            resources.secure_channels as u64,
+        ),
+        (
+            "ockam_node_uptime_seconds",
+            "Time since the node was started, in seconds",
+            "gauge",
+            resources.uptime,
+        ),
+        (
+            "ockam_node_dns_cache_hits_total",
+            "Number of host name resolutions served from the DNS cache or from the static hosts",
+            "counter",
+            resources.dns_cache_hits,
+        ),
+        (
+            "ockam_node_dns_cache_misses_total",
+            "Number of host name resolutions sent to the DNS resolver",
+            "counter",
+            resources.dns_cache_misses,
+        ),
+        (
+            "ockam_node_dns_resolution_failures_total",
+            "Number of host name resolutions which failed",
+            "counter",
+            resources.dns_resolution_failures,
+        ),
+    ];
+    if let Some(memory) = resources.memory {
+        metrics.push((
+            "ockam_node_memory_bytes",
+            "Resident memory of the node process, in bytes",
+            "gauge",
+            memory,
+        ));
+    }
+
+    let node_name = node_name.replace('\\', "\\\\").replace('"', "\\\"");
+    let mut output = String::new();
+    for (name, help, metric_type, value) in metrics {
+        let _ = writeln!(output, "# HELP {name} {help}");
+        let _ = writeln!(output, "# TYPE {name} {metric_type}");
+        let _ = writeln!(output, "{name}{{node_name=\"{node_name}\"}} {value}");
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prometheus_metrics() {
+        let resources = NodeResources {
+            memory: None,
+            workers: 12,
+            tcp_connections: 2,
+            tcp_listeners: 1,
+            portal_connections: 3,
+            secure_channels: 4,
+            uptime: 60,
+            dns_cache_hits: 5,
+            dns_cache_misses: 2,
+            dns_resolution_failures: 1,
+            max_message_size: None,
+        };
+        let metrics = prometheus_metrics("n\"1", &resources, (100, 200));
+        assert!(metrics.contains("# TYPE ockam_node_workers gauge\n"));
+        assert!(metrics.contains("ockam_node_workers{node_name=\"n\\\"1\"} 12\n"));
+        assert!(metrics.contains("ockam_node_portal_bytes_received{node_name=\"n\\\"1\"} 200\n"));
+        assert!(metrics.contains("# TYPE ockam_node_dns_cache_hits_total counter\n"));
+        assert!(
+            metrics.contains("ockam_node_dns_resolution_failures_total{node_name=\"n\\\"1\"} 1\n")
+        );
+        assert!(!metrics.contains("ockam_node_memory_bytes"));
+    }
+}
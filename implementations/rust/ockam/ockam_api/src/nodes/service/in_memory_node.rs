@@ -5,11 +5,9 @@ use futures::executor;
 use miette::IntoDiagnostic;
 
 use ockam::identity::SecureChannels;
-use ockam::{Context, Result, TcpTransport};
+use ockam::{Context, Result};
 use ockam_core::compat::{string::String, sync::Arc};
 use ockam_core::errcode::Kind;
-use ockam_multiaddr::MultiAddr;
-use ockam_transport_tcp::TcpListenerOptions;
 
 use crate::cli_state::random_name;
 use crate::cli_state::CliState;
@@ -20,7 +18,7 @@ use crate::nodes::service::default_address::DefaultAddress;
 use crate::nodes::service::{
     NodeManagerGeneralOptions, NodeManagerTransportOptions, NodeManagerTrustOptions,
 };
-use crate::nodes::{NodeManager, NODEMANAGER_ADDR};
+use crate::nodes::{InMemoryNodeBuilder, NodeManager};
 
 /// An `InMemoryNode` represents a full running node
 /// In addition to a `NodeManager`, which is used to handle all the entities related to a node
@@ -67,9 +65,14 @@ impl Drop for InMemoryNode {
 }
 
 impl InMemoryNode {
+    /// Return a builder to configure and start an in memory node
+    pub fn builder() -> InMemoryNodeBuilder {
+        InMemoryNodeBuilder::default()
+    }
+
     /// Start an in memory node
     pub async fn start(ctx: &Context, cli_state: &CliState) -> miette::Result<Self> {
-        Self::start_with_project_name(ctx, cli_state, None).await
+        Self::builder().build(ctx, cli_state).await
     }
 
     /// Start an in memory node with some project
@@ -78,19 +81,10 @@ impl InMemoryNode {
         cli_state: &CliState,
         project_name: Option<String>,
     ) -> miette::Result<Self> {
-        let default_identity_name = cli_state
-            .get_or_create_default_named_identity()
-            .await?
-            .name();
-        Self::start_node(
-            ctx,
-            cli_state,
-            &default_identity_name,
-            project_name,
-            None,
-            None,
-        )
-        .await
+        Self::builder()
+            .with_project_name(project_name)
+            .build(ctx, cli_state)
+            .await
     }
 
     /// Start an in memory node with some project and identity
@@ -100,8 +94,11 @@ impl InMemoryNode {
         identity: Option<String>,
         project_name: Option<String>,
     ) -> miette::Result<Self> {
-        let identity = cli_state.get_identity_name_or_default(&identity).await?;
-        Self::start_node(ctx, cli_state, &identity, project_name, None, None).await
+        Self::builder()
+            .with_identity_name(identity)
+            .with_project_name(project_name)
+            .build(ctx, cli_state)
+            .await
     }
 
     /// Start an in memory node with a specific identity
@@ -110,56 +107,10 @@ impl InMemoryNode {
         cli_state: &CliState,
         identity_name: &str,
     ) -> miette::Result<InMemoryNode> {
-        Self::start_node(ctx, cli_state, identity_name, None, None, None).await
-    }
-
-    /// Start an in memory node
-    #[instrument(name = "start in-memory node", skip_all)]
-    pub async fn start_node(
-        ctx: &Context,
-        cli_state: &CliState,
-        identity_name: &str,
-        project_name: Option<String>,
-        authority_identity: Option<String>,
-        authority_route: Option<MultiAddr>,
-    ) -> miette::Result<InMemoryNode> {
-        let defaults = NodeManagerDefaults::default();
-
-        let tcp = TcpTransport::create(ctx).await.into_diagnostic()?;
-        let tcp_listener = tcp
-            .listen(
-                defaults.tcp_listener_address.as_str(),
-                TcpListenerOptions::new(),
-            )
-            .await
-            .into_diagnostic()?;
-
-        let node = cli_state
-            .start_node_with_optional_values(
-                &defaults.node_name,
-                &Some(identity_name.to_string()),
-                &project_name,
-                Some(&tcp_listener),
-            )
-            .await
-            .into_diagnostic()?;
-
-        let trust_options = cli_state
-            .retrieve_trust_options(&project_name, &authority_identity, &authority_route, &None)
+        Self::builder()
+            .identity_name(identity_name)
+            .build(ctx, cli_state)
             .await
-            .into_diagnostic()?;
-
-        let node_manager = Self::new(
-            ctx,
-            NodeManagerGeneralOptions::new(cli_state.clone(), node.name(), false, false),
-            NodeManagerTransportOptions::new(tcp_listener.flow_control_id().clone(), tcp),
-            trust_options,
-        )
-        .await
-        .into_diagnostic()?;
-        ctx.flow_controls()
-            .add_consumer(NODEMANAGER_ADDR, tcp_listener.flow_control_id());
-        Ok(node_manager)
     }
 
     /// Return a Controller client to send requests to the Controller
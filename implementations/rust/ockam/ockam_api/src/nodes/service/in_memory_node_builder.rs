@@ -0,0 +1,331 @@
+use std::time::Duration;
+
+use miette::{Diagnostic, IntoDiagnostic};
+
+use ockam::{Context, TcpTransport};
+use ockam_core::flow_control::FlowControls;
+use ockam_multiaddr::MultiAddr;
+use ockam_transport_tcp::TcpListenerOptions;
+
+use crate::cli_state::CliState;
+use crate::nodes::service::{NodeManagerGeneralOptions, NodeManagerTransportOptions};
+use crate::nodes::{InMemoryNode, NodeManagerDefaults, NODEMANAGER_ADDR};
+
+/// Builder for an [`InMemoryNode`].
+///
+/// By default the node:
+///
+///  - uses the default identity
+///  - trusts the authority of the default project, if there is one
+///  - starts a TCP listener on a random local port
+///
+/// Instead of a project, the node can be configured with an explicit authority.
+/// For example, in order to start a node requesting its credentials from a standalone
+/// authority node:
+///
+/// ```no_run
+/// # use ockam_api::cli_state::CliState;
+/// # use ockam_api::nodes::{CredentialRetrieval, InMemoryNode};
+/// # use ockam_multiaddr::MultiAddr;
+/// # use ockam::Context;
+/// # use std::str::FromStr;
+/// # use std::time::Duration;
+/// # async fn example(ctx: &Context, cli_state: &CliState, authority_identity: &str) -> miette::Result<()> {
+/// let node = InMemoryNode::builder()
+///     .identity_name("alice")
+///     .authority(authority_identity)
+///     .authority_route(MultiAddr::from_str("/dnsaddr/localhost/tcp/4000/service/api").unwrap())
+///     .credential_retrieval(CredentialRetrieval::Remote {
+///         scope: "project-member".to_string(),
+///     })
+///     .tcp_listener(false)
+///     .timeout(Duration::from_secs(10))
+///     .build(ctx, cli_state)
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct InMemoryNodeBuilder {
+    identity_name: Option<String>,
+    project_name: Option<String>,
+    authority_identity: Option<String>,
+    authority_route: Option<MultiAddr>,
+    credential_retrieval: CredentialRetrieval,
+    tcp_listener: bool,
+    timeout: Option<Duration>,
+}
+
+/// Way to obtain the credentials of the node identity when an explicit authority is used
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum CredentialRetrieval {
+    /// No credential is retrieved, the credentials of other identities are only verified
+    #[default]
+    None,
+    /// A credential with the given scope is expected to be stored in the local credentials cache
+    CacheOnly { scope: String },
+    /// A credential with the given scope is requested from the authority node
+    Remote { scope: String },
+}
+
+impl CredentialRetrieval {
+    fn scope(&self) -> Option<String> {
+        match self {
+            CredentialRetrieval::None => None,
+            CredentialRetrieval::CacheOnly { scope } | CredentialRetrieval::Remote { scope } => {
+                Some(scope.clone())
+            }
+        }
+    }
+}
+
+/// Invalid combination of options given to an [`InMemoryNodeBuilder`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error, Diagnostic)]
+pub enum InMemoryNodeBuilderError {
+    #[error("A project and an authority can't be both used to configure the node trust")]
+    #[diagnostic(code("OCK400"))]
+    ProjectAndAuthority,
+
+    #[error("An authority route was provided but the authority identity is unknown")]
+    #[diagnostic(code("OCK400"))]
+    AuthorityRouteWithoutIdentity,
+
+    #[error("Credentials can only be retrieved when an authority identity is provided")]
+    #[diagnostic(code("OCK400"))]
+    CredentialRetrievalWithoutAuthority,
+
+    #[error("Credentials can only be requested from an authority when its route is provided")]
+    #[diagnostic(code("OCK400"))]
+    RemoteCredentialRetrievalWithoutRoute,
+
+    #[error("An authority route is only used to request credentials from the authority")]
+    #[diagnostic(code("OCK400"))]
+    AuthorityRouteWithoutRemoteCredentialRetrieval,
+}
+
+impl Default for InMemoryNodeBuilder {
+    fn default() -> Self {
+        Self {
+            identity_name: None,
+            project_name: None,
+            authority_identity: None,
+            authority_route: None,
+            credential_retrieval: CredentialRetrieval::None,
+            tcp_listener: true,
+            timeout: None,
+        }
+    }
+}
+
+impl InMemoryNodeBuilder {
+    /// Use a specific identity instead of the default one
+    pub fn identity_name(mut self, identity_name: impl Into<String>) -> Self {
+        self.identity_name = Some(identity_name.into());
+        self
+    }
+
+    /// Use a specific identity if it is defined, the default identity otherwise
+    pub fn with_identity_name(mut self, identity_name: Option<String>) -> Self {
+        self.identity_name = identity_name;
+        self
+    }
+
+    /// Trust the authority of a specific project instead of the default project
+    pub fn project_name(mut self, project_name: impl Into<String>) -> Self {
+        self.project_name = Some(project_name.into());
+        self
+    }
+
+    /// Trust the authority of a specific project if it is defined, the default project otherwise
+    pub fn with_project_name(mut self, project_name: Option<String>) -> Self {
+        self.project_name = project_name;
+        self
+    }
+
+    /// Trust an authority given its hex-encoded identity, instead of a project authority
+    pub fn authority(mut self, authority_identity: impl Into<String>) -> Self {
+        self.authority_identity = Some(authority_identity.into());
+        self
+    }
+
+    /// Route to the authority node, used to request credentials
+    pub fn authority_route(mut self, authority_route: MultiAddr) -> Self {
+        self.authority_route = Some(authority_route);
+        self
+    }
+
+    /// Set the way credentials are obtained from an explicit authority
+    pub fn credential_retrieval(mut self, credential_retrieval: CredentialRetrieval) -> Self {
+        self.credential_retrieval = credential_retrieval;
+        self
+    }
+
+    /// Start a TCP listener for the node API. This is the default
+    pub fn tcp_listener(mut self, tcp_listener: bool) -> Self {
+        self.tcp_listener = tcp_listener;
+        self
+    }
+
+    /// Timeout used for the requests sent to the controller, projects and authorities
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Check that the options can be used together
+    pub fn validate(&self) -> Result<(), InMemoryNodeBuilderError> {
+        let has_authority = self.authority_identity.is_some() || self.authority_route.is_some();
+        if self.project_name.is_some() && has_authority {
+            return Err(InMemoryNodeBuilderError::ProjectAndAuthority);
+        }
+        if self.authority_route.is_some() && self.authority_identity.is_none() {
+            return Err(InMemoryNodeBuilderError::AuthorityRouteWithoutIdentity);
+        }
+        if self.credential_retrieval != CredentialRetrieval::None
+            && self.authority_identity.is_none()
+        {
+            return Err(InMemoryNodeBuilderError::CredentialRetrievalWithoutAuthority);
+        }
+        match (&self.credential_retrieval, &self.authority_route) {
+            (CredentialRetrieval::Remote { .. }, None) => {
+                Err(InMemoryNodeBuilderError::RemoteCredentialRetrievalWithoutRoute)
+            }
+            (CredentialRetrieval::None | CredentialRetrieval::CacheOnly { .. }, Some(_)) => {
+                Err(InMemoryNodeBuilderError::AuthorityRouteWithoutRemoteCredentialRetrieval)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Validate the options then start the node
+    #[instrument(name = "start in-memory node", skip_all)]
+    pub async fn build(self, ctx: &Context, cli_state: &CliState) -> miette::Result<InMemoryNode> {
+        self.validate()?;
+        let defaults = NodeManagerDefaults::default();
+        let identity_name = match &self.identity_name {
+            Some(identity_name) => identity_name.clone(),
+            None => cli_state
+                .get_or_create_default_named_identity()
+                .await?
+                .name(),
+        };
+
+        let tcp = TcpTransport::create(ctx).await.into_diagnostic()?;
+        let tcp_listener = if self.tcp_listener {
+            Some(
+                tcp.listen(
+                    defaults.tcp_listener_address.as_str(),
+                    TcpListenerOptions::new(),
+                )
+                .await
+                .into_diagnostic()?,
+            )
+        } else {
+            None
+        };
+
+        let node = cli_state
+            .start_node_with_optional_values(
+                &defaults.node_name,
+                &Some(identity_name),
+                &self.project_name,
+                tcp_listener.as_ref(),
+            )
+            .await
+            .into_diagnostic()?;
+
+        let trust_options = cli_state
+            .retrieve_trust_options(
+                &self.project_name,
+                &self.authority_identity,
+                &self.authority_route,
+                &self.credential_retrieval.scope(),
+            )
+            .await
+            .into_diagnostic()?;
+
+        // Without a listener, the node API is only reachable from the current process
+        let api_flow_control_id = match &tcp_listener {
+            Some(tcp_listener) => tcp_listener.flow_control_id().clone(),
+            None => FlowControls::generate_flow_control_id(),
+        };
+        let mut node_manager = InMemoryNode::new(
+            ctx,
+            NodeManagerGeneralOptions::new(cli_state.clone(), node.name(), false, false),
+            NodeManagerTransportOptions::new(api_flow_control_id.clone(), tcp),
+            trust_options,
+        )
+        .await
+        .into_diagnostic()?;
+        ctx.flow_controls()
+            .add_consumer(NODEMANAGER_ADDR, &api_flow_control_id);
+        if let Some(timeout) = self.timeout {
+            node_manager = node_manager.with_timeout(timeout);
+        }
+        Ok(node_manager)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_validate() {
+        let route = MultiAddr::from_str("/dnsaddr/localhost/tcp/4000/service/api").unwrap();
+        let remote = CredentialRetrieval::Remote {
+            scope: "project-member".to_string(),
+        };
+
+        assert!(InMemoryNodeBuilder::default().validate().is_ok());
+        assert!(InMemoryNodeBuilder::default()
+            .authority("0123")
+            .authority_route(route.clone())
+            .credential_retrieval(remote.clone())
+            .validate()
+            .is_ok());
+        assert!(InMemoryNodeBuilder::default()
+            .authority("0123")
+            .credential_retrieval(CredentialRetrieval::CacheOnly {
+                scope: "project-member".to_string()
+            })
+            .validate()
+            .is_ok());
+
+        assert_eq!(
+            InMemoryNodeBuilder::default()
+                .project_name("default")
+                .authority("0123")
+                .validate(),
+            Err(InMemoryNodeBuilderError::ProjectAndAuthority)
+        );
+        assert_eq!(
+            InMemoryNodeBuilder::default()
+                .authority_route(route.clone())
+                .credential_retrieval(remote.clone())
+                .validate(),
+            Err(InMemoryNodeBuilderError::AuthorityRouteWithoutIdentity)
+        );
+        assert_eq!(
+            InMemoryNodeBuilder::default()
+                .credential_retrieval(remote.clone())
+                .validate(),
+            Err(InMemoryNodeBuilderError::CredentialRetrievalWithoutAuthority)
+        );
+        assert_eq!(
+            InMemoryNodeBuilder::default()
+                .authority("0123")
+                .credential_retrieval(remote)
+                .validate(),
+            Err(InMemoryNodeBuilderError::RemoteCredentialRetrievalWithoutRoute)
+        );
+        assert_eq!(
+            InMemoryNodeBuilder::default()
+                .authority("0123")
+                .authority_route(route)
+                .validate(),
+            Err(InMemoryNodeBuilderError::AuthorityRouteWithoutRemoteCredentialRetrieval)
+        );
+    }
+}
@@ -11,18 +11,20 @@ use ockam_multiaddr::MultiAddr;
 use super::NodeManagerWorker;
 use crate::error::ApiError;
 use crate::kafka::{
-    kafka_default_policy_expression, kafka_policy_expression, ConsumerNodeAddr,
-    KafkaInletController, KafkaPortalListener, KafkaSecureChannelControllerImpl,
-    KAFKA_OUTLET_BOOTSTRAP_ADDRESS, KAFKA_OUTLET_INTERCEPTOR_ADDRESS,
+    kafka_default_policy_expression, kafka_policy_expression, validate_broker_auth, BrokerAuth,
+    BrokerAuthValidation, ConsumerNodeAddr, KafkaInletController, KafkaPortalListener,
+    KafkaSecureChannelControllerImpl, TopicAllowlist, KAFKA_OUTLET_BOOTSTRAP_ADDRESS,
+    KAFKA_OUTLET_INTERCEPTOR_ADDRESS,
 };
 use crate::kafka::{OutletManagerService, PrefixRelayService};
 use crate::nodes::models::portal::OutletAccessControl;
 use crate::nodes::models::services::{
-    DeleteServiceRequest, StartKafkaDirectRequest, StartKafkaOutletRequest, StartKafkaRequest,
-    StartServiceRequest,
+    DeleteServiceRequest, ServiceStatus, StartKafkaDirectRequest, StartKafkaOutletRequest,
+    StartKafkaRequest, StartServiceRequest, UpdateKafkaAllowedTopicsRequest,
 };
 use crate::nodes::registry::{KafkaServiceInfo, KafkaServiceKind};
 use crate::nodes::service::default_address::DefaultAddress;
+use crate::nodes::service::portals::{CreateInletOptions, CreateOutletOptions};
 use crate::nodes::InMemoryNode;
 use crate::nodes::NodeManager;
 use crate::port_range::PortRange;
@@ -40,6 +42,8 @@ impl NodeManagerWorker {
                 context,
                 Address::from_string(body.address()),
                 body.request().bootstrap_server_addr,
+                body.request().broker_auth(),
+                body.request().allowed_topics(),
             )
             .await
         {
@@ -64,6 +68,8 @@ impl NodeManagerWorker {
                 request.brokers_port_range(),
                 *request.bootstrap_server_addr(),
                 request.consumer_route(),
+                request.broker_auth(),
+                request.allowed_topics(),
             )
             .await
         {
@@ -88,6 +94,8 @@ impl NodeManagerWorker {
                 request.brokers_port_range(),
                 request.project_route(),
                 KafkaServiceKind::Consumer,
+                request.broker_auth(),
+                request.allowed_topics(),
             )
             .await
         {
@@ -117,6 +125,8 @@ impl NodeManagerWorker {
                 request.brokers_port_range(),
                 outlet_node_multiaddr,
                 KafkaServiceKind::Producer,
+                request.broker_auth(),
+                request.allowed_topics(),
             )
             .await
         {
@@ -125,6 +135,24 @@ impl NodeManagerWorker {
         }
     }
 
+    pub(super) async fn update_kafka_allowed_topics(
+        &self,
+        request: UpdateKafkaAllowedTopicsRequest,
+    ) -> Result<Response<ServiceStatus>, Response<Error>> {
+        match self
+            .node_manager
+            .update_kafka_allowed_topics(&request.addr, request.allowed_topics)
+            .await
+        {
+            Ok(Some(status)) => Ok(Response::ok().body(status)),
+            Ok(None) => Err(Response::not_found_no_request(&format!(
+                "Kafka service at address '{}' not found",
+                request.addr
+            ))),
+            Err(e) => Err(Response::bad_request_no_request(&e.to_string())),
+        }
+    }
+
     pub(crate) async fn delete_kafka_service(
         &self,
         ctx: &Context,
@@ -163,7 +191,12 @@ impl InMemoryNode {
         brokers_port_range: (u16, u16),
         bootstrap_server_addr: SocketAddr,
         consumer_route: Option<MultiAddr>,
+        broker_auth: Option<BrokerAuth>,
+        allowed_topics: Option<Vec<String>>,
     ) -> Result<()> {
+        let topic_allowlist = TopicAllowlist::new(allowed_topics)?;
+        let broker_auth = validate_declared_broker_auth(bootstrap_server_addr, broker_auth).await?;
+
         let default_secure_channel_listener_flow_control_id = context
             .flow_controls()
             .get_flow_control_with_spawner(&DefaultAddress::SECURE_CHANNEL_LISTENER.into())
@@ -198,14 +231,16 @@ impl InMemoryNode {
             project_authority.clone(),
             default_secure_channel_listener_flow_control_id,
             outlet_policy_expression.clone(),
+            topic_allowlist.clone(),
         )
         .await?;
         self.create_outlet(
             context,
-            bootstrap_server_addr,
-            Some(KAFKA_OUTLET_BOOTSTRAP_ADDRESS.into()),
-            false,
-            OutletAccessControl::PolicyExpression(outlet_policy_expression.clone()),
+            CreateOutletOptions::new(
+                bootstrap_server_addr,
+                OutletAccessControl::PolicyExpression(outlet_policy_expression.clone()),
+            )
+            .with_worker_addr(Some(KAFKA_OUTLET_BOOTSTRAP_ADDRESS.into())),
         )
         .await?;
 
@@ -235,18 +270,21 @@ impl InMemoryNode {
         // we need to call it directly
         self.create_inlet(
             context,
-            SocketAddr::new(bind_ip, server_bootstrap_port).to_string(),
-            route![local_interceptor_address.clone()],
-            route![
-                KAFKA_OUTLET_INTERCEPTOR_ADDRESS,
-                KAFKA_OUTLET_BOOTSTRAP_ADDRESS
-            ],
-            "/secure/api".parse()?,
-            random_name(),
-            outlet_policy_expression,
-            None,
-            None,
-            true,
+            CreateInletOptions::new(
+                &SocketAddr::new(bind_ip, server_bootstrap_port).to_string(),
+                &"/secure/api".parse()?,
+                &random_name(),
+            )
+            .with_routes(
+                route![local_interceptor_address.clone()],
+                route![
+                    KAFKA_OUTLET_INTERCEPTOR_ADDRESS,
+                    KAFKA_OUTLET_BOOTSTRAP_ADDRESS
+                ],
+            )
+            .with_policy_expression(outlet_policy_expression)
+            .with_no_delay(true)
+            .with_wait_connection(true),
         )
         .await?;
 
@@ -255,6 +293,7 @@ impl InMemoryNode {
             inlet_controller,
             secure_channel_controller.into_trait(),
             local_interceptor_address.clone(),
+            topic_allowlist.clone(),
         )
         .await?;
 
@@ -263,7 +302,9 @@ impl InMemoryNode {
                 .kafka_services
                 .insert(
                     local_interceptor_address,
-                    KafkaServiceInfo::new(KafkaServiceKind::Direct),
+                    KafkaServiceInfo::new(KafkaServiceKind::Direct)
+                        .with_broker_auth(broker_auth)
+                        .with_topic_allowlist(topic_allowlist),
                 )
                 .await;
         }
@@ -281,7 +322,11 @@ impl InMemoryNode {
         brokers_port_range: (u16, u16),
         outlet_node_multiaddr: MultiAddr,
         kind: KafkaServiceKind,
+        broker_auth: Option<BrokerAuth>,
+        allowed_topics: Option<Vec<String>>,
     ) -> Result<()> {
+        let topic_allowlist = TopicAllowlist::new(allowed_topics)?;
+
         debug!(
             "outlet_node_multiaddr: {}",
             outlet_node_multiaddr.to_string()
@@ -333,18 +378,21 @@ impl InMemoryNode {
         // we need to call it directly
         self.create_inlet(
             context,
-            SocketAddr::new(bind_ip, server_bootstrap_port).to_string(),
-            route![local_interceptor_address.clone()],
-            route![
-                KAFKA_OUTLET_INTERCEPTOR_ADDRESS,
-                KAFKA_OUTLET_BOOTSTRAP_ADDRESS
-            ],
-            outlet_node_multiaddr,
-            inlet_alias,
-            inlet_policy_expression,
-            None,
-            None,
-            true,
+            CreateInletOptions::new(
+                &SocketAddr::new(bind_ip, server_bootstrap_port).to_string(),
+                &outlet_node_multiaddr,
+                &inlet_alias,
+            )
+            .with_routes(
+                route![local_interceptor_address.clone()],
+                route![
+                    KAFKA_OUTLET_INTERCEPTOR_ADDRESS,
+                    KAFKA_OUTLET_BOOTSTRAP_ADDRESS
+                ],
+            )
+            .with_policy_expression(inlet_policy_expression)
+            .with_no_delay(true)
+            .with_wait_connection(true),
         )
         .await?;
 
@@ -353,13 +401,24 @@ impl InMemoryNode {
             inlet_controller,
             secure_channel_controller.into_trait(),
             local_interceptor_address.clone(),
+            topic_allowlist.clone(),
         )
         .await?;
 
         {
             self.registry
                 .kafka_services
-                .insert(local_interceptor_address, KafkaServiceInfo::new(kind))
+                .insert(
+                    local_interceptor_address,
+                    KafkaServiceInfo::new(kind)
+                        .with_broker_auth(broker_auth.map(|declared| {
+                            BrokerAuthValidation::unverified(
+                                declared,
+                                "the brokers are only reachable from the kafka outlet node",
+                            )
+                        }))
+                        .with_topic_allowlist(topic_allowlist),
+                )
                 .await;
         }
 
@@ -373,7 +432,12 @@ impl NodeManager {
         context: &Context,
         service_address: Address,
         bootstrap_server_addr: SocketAddr,
+        broker_auth: Option<BrokerAuth>,
+        allowed_topics: Option<Vec<String>>,
     ) -> Result<()> {
+        let topic_allowlist = TopicAllowlist::new(allowed_topics)?;
+        let broker_auth = validate_declared_broker_auth(bootstrap_server_addr, broker_auth).await?;
+
         let default_secure_channel_listener_flow_control_id = context
             .flow_controls()
             .get_flow_control_with_spawner(&DefaultAddress::SECURE_CHANNEL_LISTENER.into())
@@ -399,16 +463,18 @@ impl NodeManager {
             project_authority,
             default_secure_channel_listener_flow_control_id,
             outlet_policy_expression.clone(),
+            topic_allowlist.clone(),
         )
         .await?;
 
         if let Err(e) = self
             .create_outlet(
                 context,
-                bootstrap_server_addr,
-                Some(KAFKA_OUTLET_BOOTSTRAP_ADDRESS.into()),
-                false,
-                OutletAccessControl::PolicyExpression(outlet_policy_expression),
+                CreateOutletOptions::new(
+                    bootstrap_server_addr,
+                    OutletAccessControl::PolicyExpression(outlet_policy_expression),
+                )
+                .with_worker_addr(Some(KAFKA_OUTLET_BOOTSTRAP_ADDRESS.into())),
             )
             .await
         {
@@ -420,7 +486,9 @@ impl NodeManager {
                 .kafka_services
                 .insert(
                     service_address,
-                    KafkaServiceInfo::new(KafkaServiceKind::Outlet),
+                    KafkaServiceInfo::new(KafkaServiceKind::Outlet)
+                        .with_broker_auth(broker_auth)
+                        .with_topic_allowlist(topic_allowlist),
                 )
                 .await;
         }
@@ -428,6 +496,31 @@ impl NodeManager {
         Ok(())
     }
 
+    /// Replace the topics allowed by a Kafka service.
+    /// The new list applies to the next requests of the kafka clients already connected.
+    /// Return None if there is no kafka service at this address
+    pub async fn update_kafka_allowed_topics(
+        &self,
+        address: &Address,
+        allowed_topics: Option<Vec<String>>,
+    ) -> Result<Option<ServiceStatus>> {
+        let info = match self.registry.kafka_services.get(address).await {
+            Some(info) => info,
+            None => return Ok(None),
+        };
+        info.topic_allowlist().set(allowed_topics)?;
+        info!(
+            %address,
+            allowed_topics = ?info.topic_allowlist().patterns(),
+            "Updated the topics allowed by the kafka service"
+        );
+        Ok(Some(
+            ServiceStatus::new(address.address(), info.kind().service_type())
+                .with_broker_auth(info.broker_auth())
+                .with_allowed_topics(info.topic_allowlist().patterns()),
+        ))
+    }
+
     /// Delete a Kafka service from the registry.
     /// The expected kind must match the actual kind
     pub async fn delete_kafka_service(
@@ -456,6 +549,26 @@ impl NodeManager {
     }
 }
 
+/// If an authentication mode is declared for the brokers, check that it is the mode required
+/// by the brokers before starting a service connecting to them
+async fn validate_declared_broker_auth(
+    bootstrap_server_addr: SocketAddr,
+    broker_auth: Option<BrokerAuth>,
+) -> Result<Option<BrokerAuthValidation>> {
+    let declared = match broker_auth {
+        Some(declared) => declared,
+        None => return Ok(None),
+    };
+    let validation = validate_broker_auth(bootstrap_server_addr, declared).await?;
+    if validation.is_mismatch() {
+        return Err(ApiError::core(format!(
+            "The kafka brokers at {bootstrap_server_addr} do not match the declared authentication mode: {}",
+            validation.message()
+        )));
+    }
+    Ok(Some(validation))
+}
+
 pub enum DeleteKafkaServiceResult {
     ServiceDeleted,
     IncorrectKind {
@@ -0,0 +1,21 @@
+use std::str::FromStr;
+
+use ockam_core::api::{Error, Response};
+
+use crate::logs::{set_log_level, LogLevelFilter};
+use crate::nodes::models::base::SetLogLevelRequest;
+use crate::nodes::NodeManagerWorker;
+
+impl NodeManagerWorker {
+    /// Replace the log level of the node, without restarting it
+    pub(super) async fn set_log_level(
+        &self,
+        request: SetLogLevelRequest,
+    ) -> Result<Response<()>, Response<Error>> {
+        let log_level = LogLevelFilter::from_str(&request.log_level)
+            .map_err(|e| Response::bad_request_no_request(&e.to_string()))?;
+        set_log_level(&log_level)
+            .map_err(|e| Response::internal_error_no_request(&e.to_string()))?;
+        Ok(Response::ok())
+    }
+}
@@ -1,12 +1,15 @@
 use crate::cloud::project::Project;
 use crate::cloud::{AuthorityNodeClient, CredentialsEnabled, ProjectNodeClient};
 use crate::nodes::connection::{
-    Connection, ConnectionBuilder, PlainTcpInstantiator, ProjectInstantiator,
-    SecureChannelInstantiator,
+    Connection, ConnectionBuilder, NamedSecureChannelInstantiator, PlainTcpInstantiator,
+    ProjectInstantiator, SecureChannelInstantiator, UdpInstantiator, WebSocketInstantiator,
 };
-use crate::nodes::models::portal::{OutletList, OutletStatus};
+use crate::nodes::models::portal::OutletList;
+use crate::nodes::models::services::BuiltinService;
 use crate::nodes::models::transport::{TransportMode, TransportType};
+use crate::nodes::models::trust::TrustContextStatus;
 use crate::nodes::registry::Registry;
+use crate::nodes::service::portal_access_control::PortalAccessControl;
 use crate::nodes::service::{
     random_alias, CredentialRetrieverCreators, NodeManagerCredentialRetrieverOptions,
     NodeManagerTrustOptions,
@@ -20,16 +23,20 @@ use ockam::identity::{
 };
 use ockam::{RelayService, RelayServiceOptions};
 use ockam_abac::expr::str;
-use ockam_abac::{Action, Env, Expr, Resource};
+use ockam_abac::{Action, Env, Expr, PolicyAccessControl, Resource};
 use ockam_core::flow_control::FlowControlId;
 use ockam_core::{AllowAll, AsyncTryClone, IncomingAccessControl};
 use ockam_multiaddr::MultiAddr;
 use ockam_node::Context;
-use ockam_transport_tcp::TcpTransport;
+use ockam_transport_tcp::{
+    PortalType, TcpConnectionOptions, TcpKeepaliveOptions, TcpListenerOptions, TcpTransport,
+};
+use ockam_transport_udp::UdpTransport;
+use ockam_transport_websocket::WebSocketTransport;
 use std::collections::BTreeMap;
 use std::net::SocketAddr;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 /// Node manager provides high-level operations to
 ///  - send messages
@@ -42,11 +49,22 @@ pub struct NodeManager {
     pub(super) node_identifier: Identifier,
     pub(super) api_transport_flow_control_id: FlowControlId,
     pub(crate) tcp_transport: TcpTransport,
+    pub(crate) websocket_transport: Option<Arc<WebSocketTransport>>,
+    pub(crate) udp_transport: Option<Arc<UdpTransport>>,
     pub(crate) secure_channels: Arc<SecureChannels>,
     pub(crate) credential_retriever_creators: CredentialRetrieverCreators,
     pub(super) project_authority: Option<Identifier>,
+    /// Trust configured when the node was created
+    pub(super) trust_context: TrustContextStatus,
     pub(crate) registry: Arc<Registry>,
     pub(crate) medic_handle: MedicHandle,
+    pub(super) started_at: Instant,
+    pub(crate) prefer_ipv6: bool,
+    pub(crate) tcp_keepalive: Option<TcpKeepaliveOptions>,
+    pub(crate) tcp_user_timeout: Option<Duration>,
+    pub(crate) debug_api: bool,
+    #[cfg(feature = "telemetry")]
+    pub(crate) metrics: Arc<crate::metrics::NodeMetrics>,
 }
 
 impl NodeManager {
@@ -81,6 +99,30 @@ impl NodeManager {
         &self.tcp_transport
     }
 
+    /// Options for the TCP connections created by this node
+    pub(crate) fn tcp_connection_options(&self) -> TcpConnectionOptions {
+        let mut options = TcpConnectionOptions::new().with_prefer_ipv6(self.prefer_ipv6);
+        if let Some(keepalive) = self.tcp_keepalive {
+            options = options.with_keepalive(keepalive);
+        }
+        if let Some(user_timeout) = self.tcp_user_timeout {
+            options = options.with_user_timeout(user_timeout);
+        }
+        options
+    }
+
+    /// Options for the TCP listeners created by this node
+    pub(crate) fn tcp_listener_options(&self) -> TcpListenerOptions {
+        let mut options = TcpListenerOptions::new();
+        if let Some(keepalive) = self.tcp_keepalive {
+            options = options.with_keepalive(keepalive);
+        }
+        if let Some(user_timeout) = self.tcp_user_timeout {
+            options = options.with_user_timeout(user_timeout);
+        }
+        options
+    }
+
     pub async fn list_outlets(&self) -> OutletList {
         OutletList::new(
             self.registry
@@ -88,9 +130,7 @@ impl NodeManager {
                 .entries()
                 .await
                 .iter()
-                .map(|(_, info)| {
-                    OutletStatus::new(info.socket_addr, info.worker_addr.clone(), None)
-                })
+                .map(|(_, info)| info.status())
                 .collect(),
         )
     }
@@ -100,6 +140,36 @@ impl NodeManager {
         self.cli_state.remove_node(&self.node_name).await?;
         Ok(())
     }
+
+    /// Stop accepting new portal connections and new secure channel sessions.
+    /// The connections which are already established are kept open so that they can be drained.
+    pub async fn stop_accepting_connections(&self, ctx: &Context) -> ockam_core::Result<()> {
+        // Stop the medic first so that the inlets sessions are not re-created
+        self.medic_handle.stop_medic(ctx).await?;
+
+        let tcp_registry = self.tcp_transport.registry();
+        for addr in tcp_registry.get_all_inlet_listener_processors() {
+            if let Err(e) = ctx.stop_processor(addr.clone()).await {
+                warn!(%addr, %e, "Failed to stop the inlet listener");
+            }
+        }
+        for addr in tcp_registry.get_all_outlet_listener_workers() {
+            if let Err(e) = ctx.stop_worker(addr.clone()).await {
+                warn!(%addr, %e, "Failed to stop the outlet listener");
+            }
+        }
+        for addr in self.registry.secure_channel_listeners.keys().await {
+            if let Err(e) = ctx.stop_worker(addr.clone()).await {
+                warn!(%addr, %e, "Failed to stop the secure channel listener");
+            }
+        }
+        Ok(())
+    }
+
+    /// Return the number of portal connections which are currently open on this node
+    pub fn portal_connections_count(&self) -> usize {
+        self.tcp_transport.registry().get_all_portal_workers().len()
+    }
 }
 
 impl NodeManager {
@@ -135,6 +205,28 @@ impl NodeManager {
         .into_diagnostic()
     }
 
+    /// Return a client to a standalone authority node, given its identifier and route
+    pub async fn create_authority_client_with_route(
+        &self,
+        authority_identifier: &Identifier,
+        authority_route: &MultiAddr,
+        caller_identity_name: Option<String>,
+    ) -> miette::Result<AuthorityNodeClient> {
+        let caller_identifier = self
+            .get_identifier_by_name(caller_identity_name)
+            .await
+            .into_diagnostic()?;
+
+        self.make_authority_node_client(
+            authority_identifier,
+            authority_route,
+            &caller_identifier,
+            None,
+        )
+        .await
+        .into_diagnostic()
+    }
+
     pub async fn create_project_client(
         &self,
         project_identifier: &Identifier,
@@ -164,9 +256,59 @@ impl NodeManager {
         action: Action,
         expression: Option<Expr>,
     ) -> ockam_core::Result<Arc<dyn IncomingAccessControl>> {
+        match self
+            .policy_access_control(authority, resource, action, expression)
+            .await?
+        {
+            Some(policy_access_control) => Ok(cached_access_control(policy_access_control)),
+            None => Ok(Arc::new(AllowAll)),
+        }
+    }
+
+    /// Create the access control of a portal, reporting the connections accepted
+    /// or denied by its policy
+    pub(super) async fn portal_access_control(
+        &self,
+        authority: Option<Identifier>,
+        resource: Resource,
+        action: Action,
+        expression: Option<Expr>,
+        portal_type: PortalType,
+        alias: &str,
+    ) -> ockam_core::Result<Arc<dyn IncomingAccessControl>> {
+        let policy_access_control = self
+            .policy_access_control(authority, resource, action, expression)
+            .await?;
+        let access_control = match policy_access_control.clone() {
+            Some(policy_access_control) => cached_access_control(policy_access_control),
+            None => Arc::new(AllowAll),
+        };
+        Ok(Arc::new(PortalAccessControl::new(
+            access_control,
+            policy_access_control,
+            portal_type,
+            alias,
+            self.tcp_transport.registry().clone(),
+        )))
+    }
+
+    /// Create a policy access control for a resource and an action, when there is an authority
+    /// to attest the attributes of the identities accessing the resource
+    async fn policy_access_control(
+        &self,
+        authority: Option<Identifier>,
+        resource: Resource,
+        action: Action,
+        expression: Option<Expr>,
+    ) -> ockam_core::Result<Option<PolicyAccessControl>> {
         let resource_name_str = resource.resource_name.as_str();
         let resource_type_str = resource.resource_type.to_string();
         let action_str = action.as_ref();
+
+        // Keep track of the resource, even without an authority, so that its
+        // name can be listed from the local state
+        self.cli_state.store_resource(&resource).await?;
+
         if let Some(authority) = authority {
             // Populate environment with known attributes:
             let mut env = Env::new();
@@ -180,27 +322,18 @@ impl NodeManager {
                     .store_policy_for_resource_name(&resource.resource_name, &action, &expression)
                     .await?;
             }
-            self.cli_state.store_resource(&resource).await?;
 
             // Create the policy access control
             let policy_access_control = policies
                 .make_policy_access_control(
-                    self.cli_state.identities_attributes(),
+                    self.identities_attributes(),
                     resource,
                     action,
                     env,
                     authority,
                 )
                 .await?;
-            cfg_if::cfg_if! {
-                if #[cfg(feature = "std")] {
-                    let cached_policy_access_control = ockam_core::access_control::CachedIncomingAccessControl::new(
-                        Box::new(policy_access_control));
-                    Ok(Arc::new(cached_policy_access_control))
-                } else {
-                    Ok(Arc::new(policy_access_control))
-                }
-            }
+            Ok(Some(policy_access_control))
         } else {
             warn! {
                 resource_name = resource_name_str,
@@ -208,7 +341,21 @@ impl NodeManager {
                 action = action_str,
                 "no policy access control set"
             }
-            Ok(Arc::new(AllowAll))
+            Ok(None)
+        }
+    }
+}
+
+fn cached_access_control(
+    policy_access_control: PolicyAccessControl,
+) -> Arc<dyn IncomingAccessControl> {
+    cfg_if::cfg_if! {
+        if #[cfg(feature = "std")] {
+            let cached_policy_access_control = ockam_core::access_control::CachedIncomingAccessControl::new(
+                Box::new(policy_access_control));
+            Arc::new(cached_policy_access_control)
+        } else {
+            Arc::new(policy_access_control)
         }
     }
 }
@@ -219,6 +366,11 @@ pub struct NodeManagerGeneralOptions {
     pub(super) node_name: String,
     pub(super) start_default_services: bool,
     pub(super) persistent: bool,
+    pub(super) prefer_ipv6: bool,
+    pub(super) tcp_keepalive: Option<TcpKeepaliveOptions>,
+    pub(super) tcp_user_timeout: Option<Duration>,
+    pub(super) debug_api: bool,
+    pub(super) attributes_max_age: Option<Duration>,
 }
 
 impl NodeManagerGeneralOptions {
@@ -233,8 +385,44 @@ impl NodeManagerGeneralOptions {
             node_name,
             start_default_services,
             persistent,
+            prefer_ipv6: false,
+            tcp_keepalive: None,
+            tcp_user_timeout: None,
+            debug_api: false,
+            attributes_max_age: None,
         }
     }
+
+    /// Try the IPv6 addresses of host names before their IPv4 addresses when connecting
+    pub fn with_prefer_ipv6(mut self, prefer_ipv6: bool) -> Self {
+        self.prefer_ipv6 = prefer_ipv6;
+        self
+    }
+
+    /// Set the TCP keepalive of the connections created by the node
+    pub fn with_tcp_keepalive(mut self, tcp_keepalive: Option<TcpKeepaliveOptions>) -> Self {
+        self.tcp_keepalive = tcp_keepalive;
+        self
+    }
+
+    /// Set the TCP user timeout of the connections created by the node
+    pub fn with_tcp_user_timeout(mut self, tcp_user_timeout: Option<Duration>) -> Self {
+        self.tcp_user_timeout = tcp_user_timeout;
+        self
+    }
+
+    /// Enable the API used to debug the node, for example to send raw messages to its workers
+    pub fn with_debug_api(mut self, debug_api: bool) -> Self {
+        self.debug_api = debug_api;
+        self
+    }
+
+    /// Expire the attributes of the identities presenting a credential to the node once
+    /// they have been cached for this duration
+    pub fn with_attributes_max_age(mut self, attributes_max_age: Option<Duration>) -> Self {
+        self.attributes_max_age = attributes_max_age;
+        self
+    }
 }
 
 #[derive(Clone)]
@@ -258,6 +446,8 @@ pub struct ApiTransport {
 pub struct NodeManagerTransportOptions {
     api_transport_flow_control_id: FlowControlId,
     tcp_transport: TcpTransport,
+    websocket_transport: Option<Arc<WebSocketTransport>>,
+    udp_transport: Option<Arc<UdpTransport>>,
 }
 
 impl NodeManagerTransportOptions {
@@ -265,8 +455,22 @@ impl NodeManagerTransportOptions {
         Self {
             api_transport_flow_control_id,
             tcp_transport,
+            websocket_transport: None,
+            udp_transport: None,
         }
     }
+
+    /// Use a WebSocket transport to connect to `/ws` and `/wss` addresses
+    pub fn with_websocket_transport(mut self, websocket_transport: WebSocketTransport) -> Self {
+        self.websocket_transport = Some(Arc::new(websocket_transport));
+        self
+    }
+
+    /// Use a UDP transport to connect to `/udp` addresses
+    pub fn with_udp_transport(mut self, udp_transport: UdpTransport) -> Self {
+        self.udp_transport = Some(Arc::new(udp_transport));
+        self
+    }
 }
 
 impl NodeManager {
@@ -290,7 +494,10 @@ impl NodeManager {
         cli_state.set_node_name(general_options.node_name.clone());
 
         let secure_channels = cli_state
-            .secure_channels(&general_options.node_name)
+            .secure_channels(
+                &general_options.node_name,
+                general_options.attributes_max_age,
+            )
             .await?;
 
         let registry = Arc::new(Registry::default());
@@ -309,6 +516,7 @@ impl NodeManager {
             .store_default_resource_type_policies()
             .await?;
 
+        let trust_context = trust_options.trust_context();
         let project_member_credential_retriever_creator: Option<
             Arc<dyn CredentialRetrieverCreator>,
         > = match trust_options.project_member_credential_retriever_options {
@@ -371,11 +579,21 @@ impl NodeManager {
             node_identifier,
             api_transport_flow_control_id: transport_options.api_transport_flow_control_id,
             tcp_transport: transport_options.tcp_transport,
+            websocket_transport: transport_options.websocket_transport,
+            udp_transport: transport_options.udp_transport,
             secure_channels,
             credential_retriever_creators,
             project_authority: trust_options.project_authority,
+            trust_context,
             registry,
             medic_handle,
+            started_at: Instant::now(),
+            prefer_ipv6: general_options.prefer_ipv6,
+            tcp_keepalive: general_options.tcp_keepalive,
+            tcp_user_timeout: general_options.tcp_user_timeout,
+            debug_api: general_options.debug_api,
+            #[cfg(feature = "telemetry")]
+            metrics: Default::default(),
         };
 
         debug!("retrieve the node identifier");
@@ -394,8 +612,12 @@ impl NodeManager {
         // Start services
         ctx.flow_controls()
             .add_consumer(DefaultAddress::UPPERCASE_SERVICE, api_flow_control_id);
-        self.start_uppercase_service_impl(ctx, DefaultAddress::UPPERCASE_SERVICE.into())
-            .await?;
+        self.start_service(
+            ctx,
+            BuiltinService::Uppercase,
+            DefaultAddress::UPPERCASE_SERVICE.into(),
+        )
+        .await?;
 
         RelayService::create(
             ctx,
@@ -410,6 +632,7 @@ impl NodeManager {
             DefaultAddress::SECURE_CHANNEL_LISTENER.into(),
             None, // Not checking identifiers here in favor of credential check
             None,
+            None,
             ctx,
         )
         .await?;
@@ -433,7 +656,18 @@ impl NodeManager {
         // started unconditionally on every node. It's used for liveliness checks.
         ctx.flow_controls()
             .add_consumer(DefaultAddress::ECHO_SERVICE, &api_flow_control_id);
-        self.start_echoer_service(ctx, DefaultAddress::ECHO_SERVICE.into())
+        self.start_service(
+            ctx,
+            BuiltinService::Echo,
+            DefaultAddress::ECHO_SERVICE.into(),
+        )
+        .await?;
+
+        // The trace service is started on every node too, so that `ockam trace`
+        // can report all the nodes of a route
+        ctx.flow_controls()
+            .add_consumer(DefaultAddress::TRACE_SERVICE, &api_flow_control_id);
+        self.start_tracer_service(ctx, DefaultAddress::TRACE_SERVICE.into())
             .await?;
 
         Ok(())
@@ -446,24 +680,35 @@ impl NodeManager {
         identifier: Identifier,
         authorized: Option<Identifier>,
         timeout: Option<Duration>,
+        rekey_interval: Option<Duration>,
     ) -> ockam_core::Result<Connection> {
         let authorized = authorized.map(|authorized| vec![authorized]);
-        self.connect(ctx, addr, identifier, authorized, timeout)
+        self.connect(ctx, addr, identifier, authorized, timeout, rekey_interval)
             .await
     }
 
     /// Resolve project ID (if any), create secure channel (if needed) and create a tcp connection
     /// Returns [`Connection`]
-    async fn connect(
+    pub(super) async fn connect(
         &self,
         ctx: Arc<Context>,
         addr: &MultiAddr,
         identifier: Identifier,
         authorized: Option<Vec<Identifier>>,
         timeout: Option<Duration>,
+        rekey_interval: Option<Duration>,
     ) -> ockam_core::Result<Connection> {
         debug!(?timeout, "connecting to {}", &addr);
-        let connection = ConnectionBuilder::new(addr.clone())
+        let builder = match self.find_warm_connection(addr).await {
+            Some((connection, rest)) => {
+                debug!("reusing the warmed connection {connection:?} to connect to {addr}");
+                ConnectionBuilder::reusing(addr.clone(), &connection, rest)?
+            }
+            None => ConnectionBuilder::new(addr.clone()),
+        };
+        let connection = builder
+            .instantiate(ctx.clone(), self, NamedSecureChannelInstantiator::new())
+            .await?
             .instantiate(
                 ctx.clone(),
                 self,
@@ -472,10 +717,15 @@ impl NodeManager {
             .await?
             .instantiate(ctx.clone(), self, PlainTcpInstantiator::new())
             .await?
+            .instantiate(ctx.clone(), self, WebSocketInstantiator::new())
+            .await?
+            .instantiate(ctx.clone(), self, UdpInstantiator::new())
+            .await?
             .instantiate(
                 ctx.clone(),
                 self,
-                SecureChannelInstantiator::new(&identifier, timeout, authorized),
+                SecureChannelInstantiator::new(&identifier, timeout, authorized)
+                    .with_rekey_interval(rekey_interval),
             )
             .await?
             .build();
@@ -6,12 +6,18 @@ use tracing::trace;
 
 use minicbor::{Decode, Encode};
 
+use ockam::identity::models::CredentialAndPurposeKey;
+use ockam::identity::Identifier;
 use ockam_core::api::{Error, Request, Response};
-use ockam_core::{self, async_trait, AsyncTryClone, Result};
+use ockam_core::{self, async_trait, Address, AsyncTryClone, Result};
 use ockam_multiaddr::MultiAddr;
 use ockam_node::{Context, MessageSendReceiveOptions};
 
 use crate::error::ApiError;
+use crate::nodes::models::secure_channel::{
+    CreateSecureChannelRequest, CreateSecureChannelResponse, DeleteSecureChannelRequest,
+    DeleteSecureChannelResponse, ShowSecureChannelRequest, ShowSecureChannelResponse,
+};
 use crate::nodes::{BackgroundNodeClient, NodeManager, NodeManagerWorker};
 
 const TARGET: &str = "ockam_api::message";
@@ -40,7 +46,7 @@ impl Messages for NodeManager {
         let msg_length = message.len();
         let connection_ctx = Arc::new(ctx.async_try_clone().await.into_diagnostic()?);
         let connection = self
-            .make_connection(connection_ctx, to, self.identifier(), None, timeout)
+            .make_connection(connection_ctx, to, self.identifier(), None, timeout, None)
             .await
             .into_diagnostic()?;
         let route = connection.route().into_diagnostic()?;
@@ -75,6 +81,129 @@ impl Messages for BackgroundNodeClient {
     }
 }
 
+/// Secure channel created to send some messages, and deleted once they are sent
+#[derive(Clone, Debug)]
+pub struct EphemeralSecureChannel {
+    /// Address of the channel encryptor, used to route messages through the channel
+    pub address: Address,
+    /// Identifier of the other party, verified during the handshake
+    pub their_identifier: Identifier,
+}
+
+#[async_trait]
+pub trait EphemeralSecureChannels {
+    /// Create a secure channel to a listener, trusting any identity presented by the listener
+    async fn create_ephemeral_secure_channel(
+        &self,
+        ctx: &Context,
+        to: &MultiAddr,
+        identity_name: Option<String>,
+        credential: Option<CredentialAndPurposeKey>,
+        timeout: Option<Duration>,
+    ) -> miette::Result<EphemeralSecureChannel>;
+
+    async fn delete_ephemeral_secure_channel(
+        &self,
+        ctx: &Context,
+        address: &Address,
+    ) -> miette::Result<()>;
+}
+
+#[async_trait]
+impl EphemeralSecureChannels for NodeManager {
+    async fn create_ephemeral_secure_channel(
+        &self,
+        ctx: &Context,
+        to: &MultiAddr,
+        identity_name: Option<String>,
+        credential: Option<CredentialAndPurposeKey>,
+        timeout: Option<Duration>,
+    ) -> miette::Result<EphemeralSecureChannel> {
+        let secure_channel = self
+            .create_secure_channel(
+                ctx,
+                to.clone(),
+                identity_name,
+                None,
+                credential,
+                timeout,
+                None,
+            )
+            .await
+            .into_diagnostic()?;
+        Ok(EphemeralSecureChannel {
+            address: secure_channel.encryptor_address().clone(),
+            their_identifier: secure_channel.their_identifier().clone(),
+        })
+    }
+
+    async fn delete_ephemeral_secure_channel(
+        &self,
+        ctx: &Context,
+        address: &Address,
+    ) -> miette::Result<()> {
+        self.delete_secure_channel(ctx, address)
+            .await
+            .into_diagnostic()
+    }
+}
+
+#[async_trait]
+impl EphemeralSecureChannels for BackgroundNodeClient {
+    async fn create_ephemeral_secure_channel(
+        &self,
+        ctx: &Context,
+        to: &MultiAddr,
+        identity_name: Option<String>,
+        credential: Option<CredentialAndPurposeKey>,
+        timeout: Option<Duration>,
+    ) -> miette::Result<EphemeralSecureChannel> {
+        let body = CreateSecureChannelRequest::new(to, None, identity_name, credential);
+        let node = self.clone().set_timeout(timeout);
+        let response: CreateSecureChannelResponse = node
+            .ask(ctx, Request::post("/node/secure_channel").body(body))
+            .await?;
+
+        // The identifier of the other party is only returned when showing the channel
+        let show = Request::get("/node/show_secure_channel")
+            .body(ShowSecureChannelRequest::new(&response.addr));
+        let their_identifier = match node.ask::<_, ShowSecureChannelResponse>(ctx, show).await {
+            Ok(ShowSecureChannelResponse {
+                their_identifier: Some(their_identifier),
+                ..
+            }) => Identifier::from_str(&their_identifier).into_diagnostic(),
+            Ok(_) => Err(miette::miette!(
+                "The secure channel {} was deleted before it was used",
+                response.addr
+            )),
+            Err(e) => Err(e),
+        };
+        match their_identifier {
+            Ok(their_identifier) => Ok(EphemeralSecureChannel {
+                address: response.addr,
+                their_identifier,
+            }),
+            Err(e) => {
+                let _ = self
+                    .delete_ephemeral_secure_channel(ctx, &response.addr)
+                    .await;
+                Err(e)
+            }
+        }
+    }
+
+    async fn delete_ephemeral_secure_channel(
+        &self,
+        ctx: &Context,
+        address: &Address,
+    ) -> miette::Result<()> {
+        let request =
+            Request::delete("/node/secure_channel").body(DeleteSecureChannelRequest::new(address));
+        let _: DeleteSecureChannelResponse = self.ask(ctx, request).await?;
+        Ok(())
+    }
+}
+
 impl NodeManagerWorker {
     pub(crate) async fn send_message(
         &self,
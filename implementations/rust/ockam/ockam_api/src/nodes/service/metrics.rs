@@ -0,0 +1,139 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use ockam::identity::utils::now;
+use ockam::Result;
+use ockam_transport_tcp::PortalType;
+
+use crate::metrics::{
+    create_meter_provider, register_node_metrics, MetricsConfiguration, PortalConnectionSample,
+    PortalKind,
+};
+use crate::nodes::service::portals::is_inlet_connection;
+use crate::nodes::service::CredentialScope;
+
+use super::NodeManager;
+
+impl NodeManager {
+    /// Export the runtime metrics of the node to an OpenTelemetry collector.
+    ///
+    /// The metrics are sampled at each export interval, until the node manager is dropped.
+    pub fn start_metrics_exporter(
+        self: &Arc<Self>,
+        configuration: &MetricsConfiguration,
+    ) -> Result<()> {
+        let meter_provider = create_meter_provider(configuration)?;
+        register_node_metrics(&meter_provider, &self.node_name, self.metrics.clone());
+        info!(
+            "exporting the metrics of the node {} every {:?}",
+            self.node_name,
+            configuration.export_interval()
+        );
+
+        let node_manager = Arc::downgrade(self);
+        let export_interval = configuration.export_interval();
+        tokio::spawn(async move {
+            while let Some(node_manager) = node_manager.upgrade() {
+                node_manager.sample_metrics().await;
+                drop(node_manager);
+                tokio::time::sleep(export_interval).await;
+            }
+            if let Err(e) = meter_provider.shutdown() {
+                warn!("cannot shut down the metrics exporter: {e}");
+            }
+        });
+        Ok(())
+    }
+
+    /// Read the current values of the sampled metrics from the node registries
+    async fn sample_metrics(&self) {
+        self.metrics
+            .update_portal_connections(self.portal_connection_samples().await);
+
+        let round_trip_times = self
+            .registry
+            .relays
+            .entries()
+            .await
+            .into_iter()
+            .filter_map(|(alias, info)| {
+                info.heartbeats
+                    .status()
+                    .round_trip_time()
+                    .map(|rtt| (alias, rtt))
+            })
+            .collect::<BTreeMap<_, _>>();
+        self.metrics.update_relay_round_trip_times(round_trip_times);
+
+        self.metrics
+            .update_credential_expires_in(self.credential_expires_in().await);
+    }
+
+    /// Attribute each open portal connection to its inlet or its outlet
+    async fn portal_connection_samples(&self) -> Vec<PortalConnectionSample> {
+        let inlets = self
+            .registry
+            .inlets
+            .entries()
+            .await
+            .into_iter()
+            .map(|(alias, info)| (alias, info.bind_addr))
+            .collect::<Vec<_>>();
+        let outlets = self.registry.outlets.values().await;
+
+        let mut samples = vec![];
+        for connection in self.tcp_transport.registry().get_all_portal_connections() {
+            let owner = match connection.portal_type() {
+                PortalType::Inlet => inlets
+                    .iter()
+                    .find(|(_, bind_addr)| is_inlet_connection(&connection, bind_addr))
+                    .map(|(alias, _)| (PortalKind::Inlet, alias.clone())),
+                PortalType::Outlet => outlets
+                    .iter()
+                    .find(|outlet| {
+                        outlet.socket_addr == connection.peer_address()
+                            && outlet.unix_socket_path.as_deref().map(Path::new)
+                                == connection.unix_socket_path()
+                    })
+                    .map(|outlet| (PortalKind::Outlet, outlet.worker_addr.address().to_string())),
+            };
+            if let Some((kind, alias)) = owner {
+                samples.push(PortalConnectionSample::new(
+                    kind,
+                    &alias,
+                    &connection.address().to_string(),
+                    connection.bytes_sent() + connection.bytes_received(),
+                ));
+            }
+        }
+        samples
+    }
+
+    /// Return the name of the project of the node and the number of seconds
+    /// before its cached project member credential expires
+    async fn credential_expires_in(&self) -> Option<(String, u64)> {
+        let authority = self.project_authority.as_ref()?;
+        let project = self
+            .cli_state
+            .get_node_project(&self.node_name)
+            .await
+            .ok()?;
+        let scope = CredentialScope::ProjectMember {
+            project_id: project.project_id().to_string(),
+        };
+        let credential = self
+            .secure_channels
+            .identities()
+            .cached_credentials_repository()
+            .get(&self.node_identifier, authority, &scope.to_string())
+            .await
+            .ok()??;
+        let expires_at = credential.get_expires_at().ok()?;
+        let now = now().ok()?;
+        Some((
+            project.name().to_string(),
+            expires_at.0.saturating_sub(now.0),
+        ))
+    }
+}
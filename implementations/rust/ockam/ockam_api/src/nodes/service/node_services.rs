@@ -1,21 +1,30 @@
 use either::Either;
+use sysinfo::{Pid, System};
 
-use ockam::{Address, Context, Result};
+use ockam::identity::utils::now;
+use ockam::{Address, Context, Result, Worker};
 use ockam_abac::{Action, Resource, ResourceType};
 use ockam_core::api::{Error, Response};
 use ockam_node::WorkerBuilder;
 
+use crate::bench_target::{start_bench_target, BenchTargetMode};
+use crate::discard::Discard;
 use crate::echoer::Echoer;
 use crate::error::ApiError;
 use crate::hop::Hop;
-use crate::nodes::models::base::NodeStatus;
+use crate::logs::current_log_level;
+use crate::nodes::models::base::{NodeResources, NodeStatus};
+use crate::nodes::models::portal::{OutletAccessControl, OutletStatus};
 use crate::nodes::models::services::{
-    ServiceList, ServiceStatus, StartEchoerServiceRequest, StartHopServiceRequest,
+    BuiltinService, DeleteServiceRequest, ServiceList, ServiceStatus, StartBenchServiceRequest,
+    StartBuiltinServiceRequest, StartEchoerServiceRequest, StartHopServiceRequest,
     StartUppercaseServiceRequest,
 };
-use crate::nodes::registry::KafkaServiceKind;
+use crate::nodes::registry::ServiceInfo;
 use crate::nodes::service::default_address::DefaultAddress;
+use crate::nodes::service::portals::CreateOutletOptions;
 use crate::nodes::NodeManager;
+use crate::tracer::Tracer;
 use crate::uppercase::Uppercase;
 
 use super::NodeManagerWorker;
@@ -28,7 +37,7 @@ impl NodeManagerWorker {
     ) -> Result<Response, Response<Error>> {
         match self
             .node_manager
-            .start_uppercase_service_impl(ctx, request.addr.into())
+            .start_service(ctx, BuiltinService::Uppercase, request.addr.into())
             .await
         {
             Ok(_) => Ok(Response::ok()),
@@ -43,7 +52,7 @@ impl NodeManagerWorker {
     ) -> Result<Response, Response<Error>> {
         match self
             .node_manager
-            .start_echoer_service(ctx, request.addr.into())
+            .start_service(ctx, BuiltinService::Echo, request.addr.into())
             .await
         {
             Ok(_) => Ok(Response::ok()),
@@ -58,7 +67,7 @@ impl NodeManagerWorker {
     ) -> Result<Response, Response<Error>> {
         match self
             .node_manager
-            .start_hop_service(ctx, request.addr.into())
+            .start_service(ctx, BuiltinService::Hop, request.addr.into())
             .await
         {
             Ok(_) => Ok(Response::ok()),
@@ -66,6 +75,42 @@ impl NodeManagerWorker {
         }
     }
 
+    pub(super) async fn start_builtin_service(
+        &self,
+        ctx: &Context,
+        request: StartBuiltinServiceRequest,
+    ) -> Result<Response<ServiceStatus>, Response<Error>> {
+        match self
+            .node_manager
+            .start_service(ctx, request.service, request.addr.into())
+            .await
+        {
+            Ok(status) => Ok(Response::ok().body(status)),
+            Err(e) => Err(Response::internal_error_no_request(&e.to_string())),
+        }
+    }
+
+    pub(super) async fn stop_builtin_service(
+        &self,
+        ctx: &Context,
+        request: DeleteServiceRequest,
+    ) -> Result<Response, Response<Error>> {
+        match self
+            .node_manager
+            .stop_service(ctx, &request.address())
+            .await
+        {
+            Ok(StopServiceResult::ServiceStopped) => Ok(Response::ok()),
+            Ok(StopServiceResult::ServiceNotFound(address)) => Err(Response::not_found_no_request(
+                &format!("There is no service started at the address {address}"),
+            )),
+            Ok(StopServiceResult::Refused(message)) => {
+                Err(Response::bad_request_no_request(&message))
+            }
+            Err(e) => Err(Response::internal_error_no_request(&e.to_string())),
+        }
+    }
+
     pub(super) async fn list_services_of_type(
         &self,
         service_type: &str,
@@ -94,6 +139,21 @@ impl NodeManagerWorker {
             Err(e) => Err(Response::internal_error_no_request(&e.to_string())),
         }
     }
+
+    pub(super) async fn start_bench_service(
+        &self,
+        ctx: &Context,
+        request: StartBenchServiceRequest,
+    ) -> Result<Response<OutletStatus>, Response<Error>> {
+        match self
+            .node_manager
+            .start_bench_service(ctx, request.addr.into(), request.mode)
+            .await
+        {
+            Ok(outlet) => Ok(Response::ok().body(outlet)),
+            Err(e) => Err(Response::internal_error_no_request(&e.to_string())),
+        }
+    }
 }
 
 impl NodeManager {
@@ -118,37 +178,15 @@ impl NodeManager {
     pub async fn list_services(&self) -> Result<Vec<ServiceStatus>> {
         let mut list = Vec::new();
         self.registry
-            .uppercase_services
-            .keys()
-            .await
-            .iter()
-            .for_each(|addr| {
-                list.push(ServiceStatus::new(
-                    addr.address(),
-                    DefaultAddress::UPPERCASE_SERVICE,
-                ))
-            });
-        self.registry
-            .echoer_services
-            .keys()
-            .await
-            .iter()
-            .for_each(|addr| {
-                list.push(ServiceStatus::new(
-                    addr.address(),
-                    DefaultAddress::ECHO_SERVICE,
-                ))
-            });
-        self.registry
-            .hop_services
-            .keys()
+            .services
+            .entries()
             .await
             .iter()
-            .for_each(|addr| {
-                list.push(ServiceStatus::new(
-                    addr.address(),
-                    DefaultAddress::HOP_SERVICE,
-                ))
+            .for_each(|(address, info)| {
+                list.push(
+                    ServiceStatus::new(address.address(), info.service().service_type())
+                        .with_started_at(info.started_at()),
+                )
             });
         self.registry
             .kafka_services
@@ -156,91 +194,210 @@ impl NodeManager {
             .await
             .iter()
             .for_each(|(address, info)| {
-                list.push(ServiceStatus::new(
-                    address.address(),
-                    match info.kind() {
-                        KafkaServiceKind::Consumer => DefaultAddress::KAFKA_CONSUMER,
-                        KafkaServiceKind::Producer => DefaultAddress::KAFKA_PRODUCER,
-                        KafkaServiceKind::Outlet => DefaultAddress::KAFKA_OUTLET,
-                        KafkaServiceKind::Direct => DefaultAddress::KAFKA_DIRECT,
-                    },
-                ))
+                list.push(
+                    ServiceStatus::new(address.address(), info.kind().service_type())
+                        .with_broker_auth(info.broker_auth())
+                        .with_allowed_topics(info.topic_allowlist().patterns()),
+                )
             });
 
         Ok(list)
     }
 
-    pub(super) async fn start_uppercase_service_impl(
+    /// Start a built-in service at the given address.
+    /// Except for the uppercase service, its messages are checked with the policies of a
+    /// resource named after its address, so that they can be set with `ockam policy create`
+    pub(crate) async fn start_service(
         &self,
         ctx: &Context,
+        service: BuiltinService,
         addr: Address,
-    ) -> Result<()> {
-        if self.registry.uppercase_services.contains_key(&addr).await {
-            return Err(ApiError::core("Uppercase service exists at this address"));
+    ) -> Result<ServiceStatus> {
+        if self.registry.services.contains_key(&addr).await {
+            return Err(ApiError::core(format!(
+                "A service already exists at the address {addr}"
+            )));
         }
 
-        ctx.start_worker(addr.clone(), Uppercase).await?;
+        match service {
+            BuiltinService::Uppercase => ctx.start_worker(addr.clone(), Uppercase).await?,
+            BuiltinService::Echo => {
+                self.add_service_consumers(ctx, &addr).await;
+                self.start_service_worker(ctx, Echoer, &addr, ResourceType::Echoer)
+                    .await?
+            }
+            BuiltinService::Hop => {
+                // A hop service can only be reached from the transports of the node
+                ctx.flow_controls()
+                    .add_consumer(addr.clone(), &self.api_transport_flow_control_id);
+                self.start_service_worker(ctx, Hop, &addr, ResourceType::Service)
+                    .await?
+            }
+            BuiltinService::Discard => {
+                self.add_service_consumers(ctx, &addr).await;
+                self.start_service_worker(ctx, Discard, &addr, ResourceType::Service)
+                    .await?
+            }
+        };
 
+        let info = ServiceInfo::new(service, now()?);
         self.registry
-            .uppercase_services
-            .insert(addr.clone(), Default::default())
+            .services
+            .insert(addr.clone(), info.clone())
             .await;
-
-        Ok(())
+        Ok(ServiceStatus::new(addr.address(), service.service_type())
+            .with_started_at(info.started_at()))
     }
 
-    pub(super) async fn start_echoer_service(&self, ctx: &Context, addr: Address) -> Result<()> {
-        if self.registry.echoer_services.contains_key(&addr).await {
-            return Err(ApiError::core("Echoer service exists at this address"));
+    /// Stop a built-in service started on this node
+    pub(crate) async fn stop_service(
+        &self,
+        ctx: &Context,
+        addr: &Address,
+    ) -> Result<StopServiceResult> {
+        // The medic of the node sends messages to the default echo service
+        // to check that its connections are alive
+        if addr.address() == DefaultAddress::ECHO_SERVICE {
+            return Ok(StopServiceResult::Refused(format!(
+                "The service at the address {addr} is used to check the connections of the node and can't be stopped"
+            )));
+        }
+        if self.registry.services.remove(addr).await.is_none() {
+            return Ok(StopServiceResult::ServiceNotFound(addr.clone()));
         }
+        ctx.stop_worker(addr.clone()).await?;
+        Ok(StopServiceResult::ServiceStopped)
+    }
 
+    /// Start the worker of a built-in service, with an access control
+    /// using the policies of its resource
+    async fn start_service_worker<W>(
+        &self,
+        ctx: &Context,
+        worker: W,
+        addr: &Address,
+        resource_type: ResourceType,
+    ) -> Result<()>
+    where
+        W: Worker<Context = Context>,
+    {
         let ac = self
             .access_control(
                 self.project_authority(),
-                Resource::new(addr.address(), ResourceType::Echoer),
+                Resource::new(addr.address(), resource_type),
                 Action::HandleMessage,
                 None,
             )
             .await?;
 
-        WorkerBuilder::new(Echoer)
+        WorkerBuilder::new(worker)
             .with_address(addr.clone())
             .with_incoming_access_control_arc(ac)
             .start(ctx)
             .await?;
-
-        self.registry
-            .echoer_services
-            .insert(addr, Default::default())
-            .await;
-
         Ok(())
     }
 
-    pub(super) async fn start_hop_service(&self, ctx: &Context, addr: Address) -> Result<()> {
-        if self.registry.hop_services.contains_key(&addr).await {
-            return Err(ApiError::core("Hop service exists at this address"));
-        }
-
+    /// Let the messages received by the node transports and secure channel listeners
+    /// reach a service
+    async fn add_service_consumers(&self, ctx: &Context, addr: &Address) {
         ctx.flow_controls()
             .add_consumer(addr.clone(), &self.api_transport_flow_control_id);
+        for listener in self.registry.secure_channel_listeners.values().await {
+            ctx.flow_controls()
+                .add_consumer(addr.clone(), listener.listener().flow_control_id());
+        }
+    }
 
-        ctx.start_worker(addr.clone(), Hop).await?;
+    /// Start the service answering the probes of `ockam trace`.
+    /// Like the echoer, it is used to diagnose connectivity, so it shares its policies
+    pub(super) async fn start_tracer_service(&self, ctx: &Context, addr: Address) -> Result<()> {
+        let ac = self
+            .access_control(
+                self.project_authority(),
+                Resource::new(addr.address(), ResourceType::Echoer),
+                Action::HandleMessage,
+                None,
+            )
+            .await?;
 
-        self.registry
-            .hop_services
-            .insert(addr, Default::default())
-            .await;
+        WorkerBuilder::new(Tracer::new(&self.node_name, self.identifier()))
+            .with_address(addr)
+            .with_incoming_access_control_arc(ac)
+            .start(ctx)
+            .await?;
 
         Ok(())
     }
 
+    /// Start an outlet to a local TCP server which echoes or discards its data, as the
+    /// target of `ockam bench portal`. It can be deleted like any other outlet
+    pub(super) async fn start_bench_service(
+        &self,
+        ctx: &Context,
+        addr: Address,
+        mode: BenchTargetMode,
+    ) -> Result<OutletStatus> {
+        let socket_addr = start_bench_target(mode).await?;
+        self.create_outlet(
+            ctx,
+            CreateOutletOptions::new(socket_addr, OutletAccessControl::PolicyExpression(None))
+                .with_worker_addr(Some(addr))
+                .with_reachable_from_default_secure_channel(true),
+        )
+        .await
+    }
+
     pub async fn get_node_status(&self, ctx: &Context) -> Result<NodeStatus> {
+        let workers = ctx.list_workers().await?.len() as u32;
         Ok(NodeStatus::new(
             self.node_name.clone(),
             "Running",
-            ctx.list_workers().await?.len() as u32,
+            workers,
             std::process::id() as i32,
-        ))
+        )
+        .with_resources(self.get_node_resources(workers))
+        .with_log_level(current_log_level().map(|l| l.to_string())))
+    }
+
+    /// Collect the runtime statistics of the node
+    pub(super) fn get_node_resources(&self, workers: u32) -> NodeResources {
+        let tcp_registry = self.tcp_transport.registry();
+        let dns_stats = self.tcp_transport.resolver().stats();
+        NodeResources {
+            memory: process_memory(),
+            workers,
+            tcp_connections: tcp_registry.get_all_sender_workers().len() as u32,
+            tcp_listeners: tcp_registry.get_all_listeners().len() as u32,
+            portal_connections: tcp_registry.get_all_portal_workers().len() as u32,
+            secure_channels: self
+                .secure_channels
+                .secure_channel_registry()
+                .get_channel_list()
+                .len() as u32,
+            uptime: self.started_at.elapsed().as_secs(),
+            dns_cache_hits: dns_stats.hits,
+            dns_cache_misses: dns_stats.misses,
+            dns_resolution_failures: dns_stats.failures,
+            max_message_size: Some(self.tcp_transport.max_message_size() as u64),
+        }
+    }
+}
+
+/// Result of stopping a built-in service
+pub(crate) enum StopServiceResult {
+    ServiceStopped,
+    ServiceNotFound(Address),
+    Refused(String),
+}
+
+/// Return the resident memory of the current process, if it can be read
+fn process_memory() -> Option<u64> {
+    let pid = Pid::from_u32(std::process::id());
+    let mut sys = System::new();
+    if sys.refresh_process(pid) {
+        sys.process(pid).map(|p| p.memory())
+    } else {
+        None
     }
 }
@@ -1,4 +1,4 @@
-use ockam_abac::{Action, Expr};
+use ockam_abac::{Action, Expr, ResourceType};
 use ockam_core::api::{Error, Request, Response};
 use ockam_core::{async_trait, Result};
 use ockam_node::Context;
@@ -187,6 +187,19 @@ pub trait Policies {
         resource: &ResourceTypeOrName,
         action: &Action,
     ) -> miette::Result<()>;
+
+    /// Return true if a policy is stored on the node for a resource type,
+    /// so that the resources of that type created without a policy are still access controlled
+    async fn has_policy_for_resource_type(
+        &self,
+        ctx: &Context,
+        resource_type: &ResourceType,
+    ) -> miette::Result<bool> {
+        let policies = self
+            .list_policies(ctx, Some(&ResourceTypeOrName::Type(resource_type.clone())))
+            .await?;
+        Ok(!policies.resource_type_policies().is_empty())
+    }
 }
 
 #[async_trait]
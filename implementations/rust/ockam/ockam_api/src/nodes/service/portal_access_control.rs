@@ -0,0 +1,128 @@
+use std::fmt::{Debug, Formatter};
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use ockam::identity::{Identifier, IdentitySecureChannelLocalInfo};
+use ockam_abac::PolicyAccessControl;
+use ockam_core::{async_trait, Address, IncomingAccessControl, RelayMessage, Result};
+use ockam_transport_tcp::{PortalMessage, PortalType, TcpRegistry};
+
+/// Access control of an inlet or an outlet, recording which identity opened each connection.
+///
+/// A portal connection starts with a `Ping` message, sent by the inlet to the outlet,
+/// and a `Pong` message, sent back by the outlet. When one of these messages is received,
+/// the other side of the portal is authenticated over its secure channel and:
+///
+///  - a `portal.connection.accepted` event is emitted if it is authorized by the policy
+///  - a `portal.connection.denied` event is emitted otherwise, with the component of the
+///    policy expression which denied the access
+///
+pub(crate) struct PortalAccessControl {
+    access_control: Arc<dyn IncomingAccessControl>,
+    policy_access_control: Option<PolicyAccessControl>,
+    portal_type: PortalType,
+    alias: String,
+    registry: TcpRegistry,
+}
+
+impl PortalAccessControl {
+    pub(crate) fn new(
+        access_control: Arc<dyn IncomingAccessControl>,
+        policy_access_control: Option<PolicyAccessControl>,
+        portal_type: PortalType,
+        alias: &str,
+        registry: TcpRegistry,
+    ) -> Self {
+        Self {
+            access_control,
+            policy_access_control,
+            portal_type,
+            alias: alias.to_string(),
+            registry,
+        }
+    }
+
+    /// Return the address of the TCP client of an inlet connection.
+    /// The connection is identified by the address of the portal worker receiving the `Pong` message
+    fn source_addr(&self, worker_address: &Address) -> Option<SocketAddr> {
+        if self.portal_type != PortalType::Inlet {
+            return None;
+        }
+        self.registry
+            .get_all_portal_connections()
+            .iter()
+            .find(|c| c.address() == worker_address)
+            .map(|c| c.peer_address())
+    }
+
+    /// Return the reason why the other side of the portal is not authorized
+    async fn denial_reason(&self, identifier: &Option<Identifier>) -> Result<String> {
+        let Some(identifier) = identifier else {
+            return Ok("the message was not received via a secure channel".to_string());
+        };
+        let Some(policy_access_control) = &self.policy_access_control else {
+            return Ok("access is denied without a policy".to_string());
+        };
+        Ok(
+            match policy_access_control
+                .failing_component(identifier.clone())
+                .await?
+            {
+                Some(component) => component.to_string(),
+                None => "no policy found".to_string(),
+            },
+        )
+    }
+}
+
+impl Debug for PortalAccessControl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PortalAccessControl")
+            .field("portal_type", &self.portal_type)
+            .field("alias", &self.alias)
+            .field("access_control", &self.access_control)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl IncomingAccessControl for PortalAccessControl {
+    async fn is_authorized(&self, msg: &RelayMessage) -> Result<bool> {
+        let is_authorized = self.access_control.is_authorized(msg).await?;
+
+        // only the first message of a connection is reported
+        if !matches!(
+            PortalMessage::decode(msg.payload()),
+            Ok(PortalMessage::Ping) | Ok(PortalMessage::PingFrom { .. }) | Ok(PortalMessage::Pong)
+        ) {
+            return Ok(is_authorized);
+        }
+
+        let identifier = IdentitySecureChannelLocalInfo::find_info(msg.local_message())
+            .ok()
+            .map(|info| info.their_identity_id());
+        let source_addr = self.source_addr(msg.destination());
+        if is_authorized {
+            info!(
+                name: "portal.connection.accepted",
+                portal_type = self.portal_type.str(),
+                alias = %self.alias,
+                identifier = identifier.as_ref().map(tracing::field::display),
+                source_addr = source_addr.map(tracing::field::display),
+                "portal connection accepted"
+            );
+        } else {
+            let reason = self.denial_reason(&identifier).await?;
+            warn!(
+                name: "portal.connection.denied",
+                portal_type = self.portal_type.str(),
+                alias = %self.alias,
+                identifier = identifier.as_ref().map(tracing::field::display),
+                source_addr = source_addr.map(tracing::field::display),
+                failing = %reason,
+                "portal connection denied"
+            );
+        }
+        Ok(is_authorized)
+    }
+}
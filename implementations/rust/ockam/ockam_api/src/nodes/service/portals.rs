@@ -1,34 +1,49 @@
-use std::net::SocketAddr;
+use std::collections::{BTreeMap, HashMap};
+use std::net::{Ipv4Addr, SocketAddr};
 use std::str::FromStr;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, UNIX_EPOCH};
 
+use miette::IntoDiagnostic;
+use tokio::task::JoinHandle;
 use tokio::time::timeout;
 
 use crate::address::get_free_address_for;
-use ockam::identity::Identifier;
+use ockam::identity::{Identifier, IdentitySecureChannelLocalInfo};
 use ockam::{Address, Result};
-use ockam_abac::{Action, Expr, Resource, ResourceType};
-use ockam_core::api::{Error, Reply, Request, RequestHeader, Response};
+use ockam_abac::{Action, Expr, Resource, ResourceName, ResourceType};
+use ockam_core::api::{Error, Reply, Request, RequestHeader, Response, Status};
 use ockam_core::errcode::{Kind, Origin};
-use ockam_core::{async_trait, route, AsyncTryClone, Route};
+use ockam_core::flow_control::FlowControlId;
+use ockam_core::{async_trait, route, AsyncTryClone, IncomingAccessControl, Route};
 use ockam_multiaddr::proto::Project as ProjectProto;
 use ockam_multiaddr::{MultiAddr, Protocol};
 use ockam_node::Context;
-use ockam_transport_tcp::{TcpInletOptions, TcpOutletOptions};
+use ockam_transport_tcp::{
+    resolve_peer, unix_socket_path, PortalType, TcpInletConnectionLimits, TcpInletLoadBalancer,
+    TcpInletLoadBalancing, TcpInletOptions, TcpOutletOptions, TcpPortalBatching,
+    TcpPortalConnectionInfo,
+};
 
 use crate::error::ApiError;
+use crate::journeys::{
+    JourneyEvent, NODE_NAME, TCP_INLET_ALIAS, TCP_INLET_CONNECTION_FAILURE,
+    TCP_INLET_CONNECTION_STATUS, TCP_INLET_PREVIOUS_CONNECTION_STATUS,
+    TCP_INLET_RECONNECTION_ATTEMPTS,
+};
 use crate::nodes::connection::Connection;
 use crate::nodes::models::portal::{
-    CreateInlet, CreateOutlet, InletList, InletStatus, OutletAccessControl, OutletList,
-    OutletStatus,
+    CreateInlet, CreateOutlet, InletCreationOptions, InletList, InletLoadBalancing, InletStatus,
+    OutletAccessControl, OutletList, OutletStatus, PortalConnection, PortalConnectionList,
+    RenamePortal,
 };
 use crate::nodes::registry::{InletInfo, OutletInfo};
 use crate::nodes::service::default_address::DefaultAddress;
 use crate::nodes::{BackgroundNodeClient, InMemoryNode};
 use crate::session::sessions::{
-    ConnectionStatus, CurrentInletStatus, ReplacerOutcome, ReplacerOutputKind, Session,
-    SessionReplacer, MAX_CONNECT_TIME, MAX_RECOVERY_TIME,
+    ConnectionStatus, ConnectionTransition, CurrentInletStatus, ReplacerOutcome,
+    ReplacerOutputKind, Session, SessionLiveness, SessionReplacer, MAX_CONNECT_TIME,
+    MAX_RECOVERY_TIME,
 };
 use crate::session::MedicHandle;
 
@@ -47,31 +62,9 @@ impl NodeManagerWorker {
         ctx: &Context,
         create_inlet: CreateInlet,
     ) -> Result<Response<InletStatus>, Response<Error>> {
-        let CreateInlet {
-            listen_addr,
-            outlet_addr,
-            alias,
-            authorized,
-            prefix_route,
-            suffix_route,
-            wait_for_outlet_duration,
-            policy_expression,
-            wait_connection,
-        } = create_inlet;
         match self
             .node_manager
-            .create_inlet(
-                ctx,
-                listen_addr,
-                prefix_route,
-                suffix_route,
-                outlet_addr,
-                alias,
-                policy_expression,
-                wait_for_outlet_duration,
-                authorized,
-                wait_connection,
-            )
+            .create_inlet(ctx, create_inlet.into())
             .await
         {
             Ok(status) => Ok(Response::ok().body(status)),
@@ -89,6 +82,22 @@ impl NodeManagerWorker {
         }
     }
 
+    pub(super) async fn rename_inlet(
+        &self,
+        req: &RequestHeader,
+        alias: &str,
+        rename_inlet: RenamePortal,
+    ) -> Result<Response<InletStatus>, Response<Error>> {
+        match self
+            .node_manager
+            .rename_inlet(alias, &rename_inlet.new_name)
+            .await
+        {
+            Ok(status) => Ok(Response::ok().with_headers(req).body(status)),
+            Err(err) => Err(rename_error_response(req, err)),
+        }
+    }
+
     pub(super) async fn show_inlet(
         &self,
         alias: &str,
@@ -100,6 +109,36 @@ impl NodeManagerWorker {
             ))),
         }
     }
+
+    pub(super) async fn list_inlet_connections(
+        &self,
+        alias: &str,
+    ) -> Result<Response<PortalConnectionList>, Response<Error>> {
+        match self.node_manager.list_inlet_connections(alias).await {
+            Some(connections) => Ok(Response::ok().body(PortalConnectionList::new(connections))),
+            None => Err(Response::not_found_no_request(&format!(
+                "Inlet with alias {alias} not found"
+            ))),
+        }
+    }
+
+    pub(super) async fn close_inlet_connection(
+        &self,
+        alias: &str,
+        connection_id: &str,
+    ) -> Result<Response<PortalConnection>, Response<Error>> {
+        match self
+            .node_manager
+            .close_inlet_connection(alias, connection_id)
+            .await
+        {
+            Ok(Some(connection)) => Ok(Response::ok().body(connection)),
+            Ok(None) => Err(Response::not_found_no_request(&format!(
+                "Connection {connection_id} not found for the inlet with alias {alias}"
+            ))),
+            Err(e) => Err(Response::internal_error_no_request(&format!("{e:?}"))),
+        }
+    }
 }
 
 /// OUTLETS
@@ -110,22 +149,9 @@ impl NodeManagerWorker {
         ctx: &Context,
         create_outlet: CreateOutlet,
     ) -> Result<Response<OutletStatus>, Response<Error>> {
-        let CreateOutlet {
-            socket_addr,
-            worker_addr,
-            reachable_from_default_secure_channel,
-            policy_expression,
-        } = create_outlet;
-
         match self
             .node_manager
-            .create_outlet(
-                ctx,
-                socket_addr,
-                worker_addr,
-                reachable_from_default_secure_channel,
-                OutletAccessControl::PolicyExpression(policy_expression),
-            )
+            .create_outlet(ctx, create_outlet.into())
             .await
         {
             Ok(outlet_status) => Ok(Response::ok().body(outlet_status)),
@@ -139,11 +165,7 @@ impl NodeManagerWorker {
     ) -> Result<Response<OutletStatus>, Response<Error>> {
         match self.node_manager.delete_outlet(worker_addr).await {
             Ok(res) => match res {
-                Some(outlet_info) => Ok(Response::ok().body(OutletStatus::new(
-                    outlet_info.socket_addr,
-                    outlet_info.worker_addr.clone(),
-                    None,
-                ))),
+                Some(outlet_info) => Ok(Response::ok().body(outlet_info.status())),
                 None => Err(Response::bad_request_no_request(&format!(
                     "Outlet with address {worker_addr} not found"
                 ))),
@@ -152,6 +174,23 @@ impl NodeManagerWorker {
         }
     }
 
+    pub(super) async fn rename_outlet(
+        &self,
+        ctx: &Context,
+        req: &RequestHeader,
+        worker_addr: &Address,
+        rename_outlet: RenamePortal,
+    ) -> Result<Response<OutletStatus>, Response<Error>> {
+        match self
+            .node_manager
+            .rename_outlet(ctx, worker_addr, &rename_outlet.new_name.into())
+            .await
+        {
+            Ok(status) => Ok(Response::ok().with_headers(req).body(status)),
+            Err(err) => Err(rename_error_response(req, err)),
+        }
+    }
+
     pub(super) async fn show_outlet(
         &self,
         worker_addr: &Address,
@@ -173,17 +212,32 @@ impl NodeManagerWorker {
 
 /// OUTLETS
 impl NodeManager {
+    /// Create an outlet with the given options.
+    ///
+    /// When a `host:port` is given, the outlet resolves the host name again for each new
+    /// connection, once the addresses cached by the transport resolver have expired.
+    /// The socket address is then only reported in the outlet status.
+    ///
+    /// When a Unix domain socket path is given, the outlet connects to that socket instead of
+    /// a TCP server, and the socket address is unspecified.
     #[instrument(skip(self, ctx))]
-    #[allow(clippy::too_many_arguments)]
     #[instrument(skip_all)]
     pub async fn create_outlet(
         &self,
         ctx: &Context,
-        socket_addr: SocketAddr,
-        worker_addr: Option<Address>,
-        reachable_from_default_secure_channel: bool,
-        access_control: OutletAccessControl,
+        options: CreateOutletOptions,
     ) -> Result<OutletStatus> {
+        let CreateOutletOptions {
+            socket_addr,
+            worker_addr,
+            reachable_from_default_secure_channel,
+            access_control,
+            buffer_size,
+            hostname_port,
+            identity,
+            unix_socket_path,
+            proxy_protocol,
+        } = options;
         let worker_addr = self
             .registry
             .outlets
@@ -205,27 +259,51 @@ impl NodeManager {
             ));
         }
 
+        let identity_listeners = match &identity {
+            Some(identity) => Some(self.identity_secure_channel_listeners(identity).await?),
+            None => None,
+        };
+
+        let incoming_access_control = match &access_control {
+            OutletAccessControl::IncomingAccessControl(iac) => Some(iac.clone()),
+            OutletAccessControl::PolicyExpression(_) => None,
+        };
         let access_control = match access_control {
             OutletAccessControl::IncomingAccessControl(iac) => iac,
             OutletAccessControl::PolicyExpression(expression) => {
-                self.access_control(
+                self.portal_access_control(
                     self.project_authority(),
                     Resource::new(worker_addr.address(), ResourceType::TcpOutlet),
                     Action::HandleMessage,
                     expression,
+                    PortalType::Outlet,
+                    worker_addr.address(),
                 )
                 .await?
             }
         };
 
         let options = {
-            let options = TcpOutletOptions::new().with_incoming_access_control(access_control);
+            let options = TcpOutletOptions::new()
+                .with_incoming_access_control(access_control)
+                .with_proxy_protocol(proxy_protocol);
+            let options = match buffer_size {
+                Some(buffer_size) => options.with_buffer_size(buffer_size),
+                None => options,
+            };
             let options = if self.project_authority().is_none() {
                 options.as_consumer(&self.api_transport_flow_control_id)
             } else {
                 options
             };
-            if reachable_from_default_secure_channel {
+            if let Some(flow_control_ids) = &identity_listeners {
+                // Accept messages from the secure channel listeners of the chosen identity only
+                flow_control_ids
+                    .iter()
+                    .fold(options, |options, flow_control_id| {
+                        options.as_consumer(flow_control_id)
+                    })
+            } else if reachable_from_default_secure_channel {
                 // Accept messages from the default secure channel listener
                 if let Some(flow_control_id) = ctx
                     .flow_controls()
@@ -240,10 +318,22 @@ impl NodeManager {
             }
         };
 
-        let res = self
-            .tcp_transport
-            .create_tcp_outlet(worker_addr.clone(), socket_addr, options)
-            .await;
+        let res = match (&unix_socket_path, &hostname_port) {
+            (Some(path), _) => {
+                self.create_unix_outlet(worker_addr.clone(), path, options)
+                    .await
+            }
+            (None, Some(hostname_port)) => {
+                self.tcp_transport
+                    .create_outlet(worker_addr.clone(), hostname_port.clone(), options)
+                    .await
+            }
+            (None, None) => {
+                self.tcp_transport
+                    .create_tcp_outlet(worker_addr.clone(), socket_addr, options)
+                    .await
+            }
+        };
 
         Ok(match res {
             Ok(_) => {
@@ -252,11 +342,24 @@ impl NodeManager {
                     .outlets
                     .insert(
                         worker_addr.clone(),
-                        OutletInfo::new(&socket_addr, Some(&worker_addr)),
+                        OutletInfo::new(&socket_addr, Some(&worker_addr))
+                            .with_options(
+                                reachable_from_default_secure_channel,
+                                incoming_access_control,
+                                buffer_size,
+                            )
+                            .with_hostname_port(hostname_port.clone())
+                            .with_identity(identity.clone())
+                            .with_unix_socket_path(unix_socket_path.clone())
+                            .with_proxy_protocol(proxy_protocol),
                     )
                     .await;
 
                 OutletStatus::new(socket_addr, worker_addr, None)
+                    .with_hostname_port(hostname_port)
+                    .with_identity(identity)
+                    .with_unix_socket_path(unix_socket_path)
+                    .with_proxy_protocol(proxy_protocol)
             }
             Err(e) => {
                 warn!(at = %socket_addr, err = %e, "Failed to create TCP outlet");
@@ -270,6 +373,59 @@ impl NodeManager {
         })
     }
 
+    /// Create an outlet connecting to a Unix domain socket
+    #[cfg(unix)]
+    async fn create_unix_outlet(
+        &self,
+        worker_addr: Address,
+        path: &str,
+        options: TcpOutletOptions,
+    ) -> Result<()> {
+        self.tcp_transport
+            .create_unix_outlet(worker_addr, path, options)
+            .await
+    }
+
+    /// Unix domain sockets are not supported on this platform
+    #[cfg(not(unix))]
+    async fn create_unix_outlet(
+        &self,
+        _worker_addr: Address,
+        path: &str,
+        _options: TcpOutletOptions,
+    ) -> Result<()> {
+        Err(ockam_core::Error::new(
+            Origin::Node,
+            Kind::Unsupported,
+            format!("The outlet can't connect to the Unix domain socket {path}: Unix domain sockets are not supported on this platform"),
+        ))
+    }
+
+    /// Return the flow control ids of the secure channel listeners started with a given identity
+    async fn identity_secure_channel_listeners(
+        &self,
+        identity: &str,
+    ) -> Result<Vec<FlowControlId>> {
+        let identifier = self
+            .get_identifier_by_name(Some(identity.to_string()))
+            .await?;
+        let flow_control_ids: Vec<FlowControlId> = self
+            .list_secure_channel_listeners()
+            .await
+            .iter()
+            .filter(|listener| listener.identifier() == &identifier)
+            .map(|listener| listener.listener().flow_control_id().clone())
+            .collect();
+        if flow_control_ids.is_empty() {
+            return Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::NotFound,
+                format!("There is no secure channel listener for the identity {identity}. Create one with `ockam secure-channel-listener create --identity {identity}`"),
+            ));
+        }
+        Ok(flow_control_ids)
+    }
+
     pub async fn delete_outlet(&self, worker_addr: &Address) -> Result<Option<OutletInfo>> {
         info!(%worker_addr, "Handling request to delete outlet portal");
         if let Some(deleted_outlet) = self.registry.outlets.remove(worker_addr).await {
@@ -294,15 +450,77 @@ impl NodeManager {
         }
     }
 
+    /// Rename an outlet and move the policies stored for its previous worker address.
+    ///
+    /// The outlet worker is created again at the new address, with the same options,
+    /// and the outlet worker at the previous address is stopped.
+    pub async fn rename_outlet(
+        &self,
+        ctx: &Context,
+        worker_addr: &Address,
+        new_worker_addr: &Address,
+    ) -> Result<OutletStatus> {
+        info!(%worker_addr, %new_worker_addr, "Handling request to rename outlet portal");
+        if self.registry.outlets.contains_key(new_worker_addr).await {
+            let message = format!("A TCP outlet with address '{new_worker_addr}' already exists");
+            return Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::AlreadyExists,
+                message,
+            ));
+        }
+        let Some(outlet_info) = self.registry.outlets.get(worker_addr).await else {
+            let message = format!("Outlet with address {worker_addr} not found");
+            return Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::NotFound,
+                message,
+            ));
+        };
+
+        // Move the policies first so that the new outlet worker is created with them
+        let resource_name: ResourceName = worker_addr.address().into();
+        let new_resource_name: ResourceName = new_worker_addr.address().into();
+        self.cli_state
+            .rename_resource(&resource_name, &new_resource_name)
+            .await?;
+
+        let access_control = match outlet_info.incoming_access_control {
+            Some(iac) => OutletAccessControl::IncomingAccessControl(iac),
+            None => OutletAccessControl::PolicyExpression(None),
+        };
+        let options = CreateOutletOptions::new(outlet_info.socket_addr, access_control)
+            .with_worker_addr(Some(new_worker_addr.clone()))
+            .with_reachable_from_default_secure_channel(
+                outlet_info.reachable_from_default_secure_channel,
+            )
+            .with_buffer_size(outlet_info.buffer_size)
+            .with_hostname_port(outlet_info.hostname_port.clone())
+            .with_identity(outlet_info.identity.clone())
+            .with_unix_socket_path(outlet_info.unix_socket_path.clone())
+            .with_proxy_protocol(outlet_info.proxy_protocol);
+        let status = match self.create_outlet(ctx, options).await {
+            Ok(status) => status,
+            Err(e) => {
+                self.cli_state
+                    .rename_resource(&new_resource_name, &resource_name)
+                    .await?;
+                return Err(e);
+            }
+        };
+
+        self.registry.outlets.remove(worker_addr).await;
+        if let Err(e) = self.tcp_transport.stop_outlet(worker_addr.clone()).await {
+            warn!(%worker_addr, %e, "Failed to stop outlet worker");
+        }
+        Ok(status)
+    }
+
     pub(super) async fn show_outlet(&self, worker_addr: &Address) -> Option<OutletStatus> {
         info!(%worker_addr, "Handling request to show outlet portal");
         if let Some(outlet_to_show) = self.registry.outlets.get(worker_addr).await {
             debug!(%worker_addr, "Outlet not found in node registry");
-            Some(OutletStatus::new(
-                outlet_to_show.socket_addr,
-                outlet_to_show.worker_addr.clone(),
-                None,
-            ))
+            Some(outlet_to_show.status())
         } else {
             error!(%worker_addr, "Outlet not found in the node registry");
             None
@@ -312,34 +530,63 @@ impl NodeManager {
 
 /// INLETS
 impl NodeManager {
-    #[allow(clippy::too_many_arguments)]
+    /// Create an inlet with the given options.
+    ///
+    /// The connections are load-balanced when additional outlet addresses are given,
+    /// or when a load balancing strategy is set. The first outlet address is the one
+    /// used to select the authority of the inlet access control.
+    ///
+    /// The inlet listens on a Unix domain socket when its listen address is "unix:<path>".
     #[instrument(skip_all)]
     pub async fn create_inlet(
         self: &Arc<Self>,
         ctx: &Context,
-        listen_addr: String,
-        prefix_route: Route,
-        suffix_route: Route,
-        outlet_addr: MultiAddr,
-        alias: String,
-        policy_expression: Option<Expr>,
-        wait_for_outlet_duration: Option<Duration>,
-        authorized: Option<Identifier>,
-        wait_connection: bool,
+        options: CreateInletOptions,
     ) -> Result<InletStatus> {
         info!("Handling request to create inlet portal");
+        let connection_limits = options.connection_limits();
+        let liveness = options.liveness();
+        let CreateInletOptions {
+            listen_addr,
+            outlet_addr,
+            alias,
+            authorized_identifiers: authorized,
+            prefix_route,
+            suffix_route,
+            policy_expression,
+            wait_for_outlet_timeout: wait_for_outlet_duration,
+            wait_connection,
+            additional_outlet_addrs,
+            load_balancing,
+            secure_channel_rekey_interval,
+            buffer_size,
+            no_delay,
+            identity,
+            unix_socket_mode,
+            replace_unix_socket,
+            expect_proxy_protocol,
+            ..
+        } = options;
+        let load_balancing = if additional_outlet_addrs.is_empty() {
+            load_balancing
+        } else {
+            Some(load_balancing.unwrap_or_default())
+        };
         debug! {
             listen_addr = %listen_addr,
             prefix = %prefix_route,
             suffix = %suffix_route,
             outlet_addr = %outlet_addr,
+            additional_outlet_addrs = ?additional_outlet_addrs,
+            load_balancing = ?load_balancing,
             %alias,
             "Creating inlet portal"
         }
 
         // the port could be zero, to simplify the following code we
         // resolve the address to a full socket address
-        let listen_addr = if listen_addr.ends_with(":0") {
+        let listen_addr = if listen_addr.ends_with(":0") && unix_socket_path(&listen_addr).is_none()
+        {
             let socket_addr = SocketAddr::from_str(&listen_addr)
                 .map_err(|err| ockam_core::Error::new(Origin::Transport, Kind::Invalid, err))?;
             get_free_address_for(&socket_addr.ip().to_string())
@@ -363,7 +610,8 @@ impl NodeManager {
                 ));
             }
 
-            // Check that there is no entry in the registry with the same TCP bind address
+            // Check that there is no entry in the registry with the same TCP bind address,
+            // or with the same Unix domain socket
             if registry
                 .values()
                 .await
@@ -380,22 +628,44 @@ impl NodeManager {
             }
         }
 
+        // the secure channels to the outlets are created with the node identity, unless
+        // another identity is given, in which case its own credential is retrieved
+        let identifier = self.get_identifier_by_name(identity.clone()).await?;
+        let creation_options = InletCreationOptions::new(
+            authorized.clone(),
+            policy_expression.as_ref(),
+            &additional_outlet_addrs,
+            secure_channel_rekey_interval,
+            buffer_size,
+        );
+
         let replacer = InletSessionReplacer {
             node_manager: self.clone(),
+            identifier,
             context: Arc::new(ctx.async_try_clone().await?),
             listen_addr: listen_addr.clone(),
             outlet_addr: outlet_addr.clone(),
+            additional_outlet_addrs,
+            load_balancing,
             prefix_route,
             suffix_route,
             authorized,
             wait_for_outlet_duration: wait_for_outlet_duration.unwrap_or(MAX_CONNECT_TIME),
+            secure_channel_rekey_interval,
+            buffer_size,
+            connection_limits: connection_limits.clone(),
+            no_delay,
+            unix_socket_mode,
+            replace_unix_socket,
+            expect_proxy_protocol,
             resource: Resource::new(alias.clone(), ResourceType::TcpInlet),
             policy_expression,
-            connection: None,
+            connections: vec![],
+            reconnections: vec![],
             inlet_address: None,
         };
 
-        let mut session = Session::new(replacer);
+        let mut session = Session::new(replacer).with_liveness(liveness);
         let outcome = if wait_connection {
             let result =
                 MedicHandle::connect(&mut session)
@@ -422,7 +692,17 @@ impl NodeManager {
             .inlets
             .insert(
                 alias.clone(),
-                InletInfo::new(&listen_addr, outlet_addr.clone(), session),
+                InletInfo::new(
+                    &listen_addr,
+                    outlet_addr.clone(),
+                    session,
+                    connection_limits.clone(),
+                    no_delay,
+                )
+                .with_identity(identity.clone())
+                .with_unix_socket_mode(unix_socket_mode)
+                .with_expect_proxy_protocol(expect_proxy_protocol)
+                .with_creation_options(creation_options.clone()),
             )
             .await;
 
@@ -437,6 +717,17 @@ impl NodeManager {
                 .map(|s| s.connection_status)
                 .unwrap_or(ConnectionStatus::Down),
             outlet_addr.to_string(),
+        )
+        .with_load_balancer(outcome.as_ref().and_then(|s| s.load_balancer.as_ref()))
+        .with_no_delay(no_delay)
+        .with_identity(identity)
+        .with_unix_socket_mode(unix_socket_mode)
+        .with_expect_proxy_protocol(expect_proxy_protocol)
+        .with_creation_options(creation_options)
+        .with_liveness(liveness, 0)
+        .with_connection_limits(
+            &connection_limits,
+            self.inlet_active_connections(&listen_addr),
         ))
     }
 
@@ -466,12 +757,73 @@ impl NodeManager {
         }
     }
 
+    /// Rename an inlet and move the policies stored for its previous alias.
+    ///
+    /// An inlet which is up is created again so that its access control uses the policies
+    /// stored for the new alias. Its open connections are closed and must be established again.
+    pub async fn rename_inlet(&self, alias: &str, new_alias: &str) -> Result<InletStatus> {
+        info!(%alias, %new_alias, "Handling request to rename inlet portal");
+        if self.registry.inlets.contains_key(new_alias).await {
+            let message = format!("An inlet with alias {new_alias} already exists");
+            return Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::AlreadyExists,
+                message,
+            ));
+        }
+        let Some(inlet_info) = self
+            .registry
+            .inlets
+            .rename(alias, new_alias.to_string())
+            .await
+        else {
+            let message = format!("Inlet with alias {alias} not found");
+            return Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::NotFound,
+                message,
+            ));
+        };
+        debug!(%alias, %new_alias, "Successfully renamed inlet in the node registry");
+
+        if let Err(e) = self
+            .cli_state
+            .rename_resource(&alias.into(), &new_alias.into())
+            .await
+        {
+            self.registry
+                .inlets
+                .rename(new_alias, alias.to_string())
+                .await;
+            return Err(e.into());
+        }
+
+        let mut session = inlet_info.session;
+        session.rename(new_alias).await;
+        if session.connection_status() == ConnectionStatus::Up {
+            // The medic replaces the inlet later if it cannot be created again now
+            if let Err(e) = MedicHandle::connect(&mut session).await {
+                warn!(%new_alias, %e, "Failed to create the renamed inlet");
+                session.failed(e.to_string());
+            }
+        }
+
+        self.show_inlet(new_alias).await.ok_or_else(|| {
+            ockam_core::Error::new(
+                Origin::Node,
+                Kind::NotFound,
+                format!("Inlet with alias {new_alias} not found"),
+            )
+        })
+    }
+
     pub async fn show_inlet(&self, alias: &str) -> Option<InletStatus> {
         info!(%alias, "Handling request to show inlet portal");
         if let Some(inlet_info) = self.registry.inlets.get(alias).await {
-            if let Some(status) = inlet_info.session.status() {
+            let backpressure_pauses = self.inlet_backpressure_pauses(&inlet_info.bind_addr);
+            let inlet_status = if let Some(status) = inlet_info.session.status() {
                 if let ReplacerOutputKind::Inlet(status) = &status.kind {
-                    Some(InletStatus::new(
+                    InletStatus::new(
                         inlet_info.bind_addr.to_string(),
                         status.worker.address().to_string(),
                         alias,
@@ -479,12 +831,13 @@ impl NodeManager {
                         status.route.to_string(),
                         status.connection_status,
                         inlet_info.outlet_addr.to_string(),
-                    ))
+                    )
+                    .with_load_balancer(status.load_balancer.as_ref())
                 } else {
                     panic!("Unexpected outcome: {:?}", status.kind)
                 }
             } else {
-                Some(InletStatus::new(
+                InletStatus::new(
                     inlet_info.bind_addr.to_string(),
                     None,
                     alias,
@@ -492,14 +845,97 @@ impl NodeManager {
                     None,
                     ConnectionStatus::Down,
                     inlet_info.outlet_addr.to_string(),
-                ))
-            }
+                )
+            };
+            Some(
+                inlet_status
+                    .with_backpressure_pauses(backpressure_pauses)
+                    .with_transitions(inlet_info.session.transitions())
+                    .with_no_delay(inlet_info.no_delay)
+                    .with_identity(inlet_info.identity.clone())
+                    .with_unix_socket_mode(inlet_info.unix_socket_mode)
+                    .with_expect_proxy_protocol(inlet_info.expect_proxy_protocol)
+                    .with_creation_options(inlet_info.creation_options.clone())
+                    .with_liveness(
+                        inlet_info.session.liveness(),
+                        inlet_info.session.pings().len(),
+                    )
+                    .with_connection_limits(
+                        &inlet_info.connection_limits,
+                        self.inlet_active_connections(&inlet_info.bind_addr),
+                    ),
+            )
         } else {
             error!(%alias, "Inlet not found in the node registry");
             None
         }
     }
 
+    /// Return the connections currently accepted by an inlet, or None if the inlet doesn't exist
+    pub async fn list_inlet_connections(&self, alias: &str) -> Option<Vec<PortalConnection>> {
+        let inlet_info = self.registry.inlets.get(alias).await?;
+        let mut connections = vec![];
+        for connection in self
+            .tcp_transport
+            .registry()
+            .get_all_portal_connections()
+            .iter()
+            .filter(|c| is_inlet_connection(c, &inlet_info.bind_addr))
+        {
+            let remote_attributes = match remote_identifier(connection) {
+                Some(identifier) => self.attested_attributes(&identifier).await,
+                None => None,
+            };
+            connections.push(portal_connection(connection, remote_attributes));
+        }
+        Some(connections)
+    }
+
+    /// Return the attributes of an identity attested by the project authority, if they are known
+    async fn attested_attributes(
+        &self,
+        identifier: &Identifier,
+    ) -> Option<BTreeMap<String, String>> {
+        let authority = self.project_authority()?;
+        let entry = self
+            .identities_attributes()
+            .get_attributes(identifier, &authority)
+            .await
+            .ok()??;
+        Some(
+            entry
+                .attrs()
+                .iter()
+                .map(|(key, value)| {
+                    (
+                        String::from_utf8_lossy(key).to_string(),
+                        String::from_utf8_lossy(value).to_string(),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    /// Close a connection accepted by an inlet.
+    /// Return None if the inlet or the connection doesn't exist
+    pub async fn close_inlet_connection(
+        &self,
+        alias: &str,
+        connection_id: &str,
+    ) -> Result<Option<PortalConnection>> {
+        info!(%alias, %connection_id, "Handling request to close an inlet connection");
+        let connection = self
+            .list_inlet_connections(alias)
+            .await
+            .and_then(|connections| connections.into_iter().find(|c| c.id == connection_id));
+        if let Some(connection) = &connection {
+            self.tcp_transport
+                .close_portal_connection(Address::from_string(&connection.id))
+                .await?;
+        }
+        Ok(connection)
+    }
+
     pub async fn list_inlets(&self) -> InletList {
         InletList::new(
             self.registry
@@ -508,7 +944,7 @@ impl NodeManager {
                 .await
                 .iter()
                 .map(|(alias, info)| {
-                    if let Some(status) = info.session.status().as_ref() {
+                    let inlet_status = if let Some(status) = info.session.status().as_ref() {
                         match &status.kind {
                             ReplacerOutputKind::Inlet(status) => InletStatus::new(
                                 &info.bind_addr,
@@ -518,7 +954,8 @@ impl NodeManager {
                                 status.route.to_string(),
                                 status.connection_status,
                                 info.outlet_addr.to_string(),
-                            ),
+                            )
+                            .with_load_balancer(status.load_balancer.as_ref()),
                             _ => {
                                 panic!("Unexpected outcome: {:?}", status.kind)
                             }
@@ -533,63 +970,296 @@ impl NodeManager {
                             ConnectionStatus::Down,
                             info.outlet_addr.to_string(),
                         )
-                    }
+                    };
+                    inlet_status
+                        .with_backpressure_pauses(self.inlet_backpressure_pauses(&info.bind_addr))
+                        .with_no_delay(info.no_delay)
+                        .with_identity(info.identity.clone())
+                        .with_unix_socket_mode(info.unix_socket_mode)
+                        .with_expect_proxy_protocol(info.expect_proxy_protocol)
+                        .with_creation_options(info.creation_options.clone())
+                        .with_liveness(info.session.liveness(), info.session.pings().len())
+                        .with_connection_limits(
+                            &info.connection_limits,
+                            self.inlet_active_connections(&info.bind_addr),
+                        )
                 })
                 .collect(),
         )
     }
+
+    /// Return the number of times the open connections of the inlet listening at `bind_addr`
+    /// paused reading from their TCP client because of backpressure
+    fn inlet_backpressure_pauses(&self, bind_addr: &str) -> u64 {
+        self.tcp_transport
+            .registry()
+            .get_all_portal_connections()
+            .iter()
+            .filter(|c| is_inlet_connection(c, bind_addr))
+            .map(|c| c.backpressure_pauses())
+            .sum()
+    }
+
+    /// Return the number of open connections of the inlet listening at `bind_addr`
+    fn inlet_active_connections(&self, bind_addr: &str) -> u64 {
+        self.tcp_transport
+            .registry()
+            .get_all_portal_connections()
+            .iter()
+            .filter(|c| is_inlet_connection(c, bind_addr))
+            .count() as u64
+    }
+}
+
+/// Return true if a portal connection was accepted by the inlet listening at `bind_addr`,
+/// which is either a socket address or the path of a Unix domain socket prefixed with "unix:"
+pub(super) fn is_inlet_connection(connection: &TcpPortalConnectionInfo, bind_addr: &str) -> bool {
+    if connection.portal_type() != PortalType::Inlet {
+        return false;
+    }
+    if let Some(path) = unix_socket_path(bind_addr) {
+        return connection.unix_socket_path() == Some(path.as_path());
+    }
+    let Ok(bind_addr) = SocketAddr::from_str(bind_addr) else {
+        return false;
+    };
+    if connection.unix_socket_path().is_some() {
+        return false;
+    }
+    match connection.local_address() {
+        Some(local_address) => {
+            local_address.port() == bind_addr.port()
+                && (bind_addr.ip().is_unspecified() || local_address.ip() == bind_addr.ip())
+        }
+        None => false,
+    }
+}
+
+/// Return a not found response when the renamed portal doesn't exist
+/// and a conflict response when the new name is already used
+fn rename_error_response(req: &RequestHeader, err: ockam_core::Error) -> Response<Error> {
+    match err.code().kind {
+        Kind::NotFound => Response::not_found(req, &err.to_string()),
+        Kind::AlreadyExists => Response::error(req, &err.to_string(), Status::Conflict),
+        _ => Response::internal_error(req, &format!("Failed to rename the portal: {err}")),
+    }
+}
+
+/// Return the identifier of the other side of a portal connection, when it is reached via a secure channel
+fn remote_identifier(connection: &TcpPortalConnectionInfo) -> Option<Identifier> {
+    IdentitySecureChannelLocalInfo::find_info_from_list(connection.remote_local_info())
+        .ok()
+        .map(|info| info.their_identity_id())
+}
+
+fn portal_connection(
+    connection: &TcpPortalConnectionInfo,
+    remote_attributes: Option<BTreeMap<String, String>>,
+) -> PortalConnection {
+    PortalConnection::new(
+        connection.address().to_string(),
+        connection.peer_address(),
+        connection
+            .started_at()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default(),
+        connection.bytes_sent(),
+        connection.bytes_received(),
+        remote_identifier(connection),
+        remote_attributes,
+    )
+    .with_payload_messages(connection.payload_messages())
 }
 
 impl InMemoryNode {
-    #[allow(clippy::too_many_arguments)]
     #[instrument(skip_all)]
     pub async fn create_inlet(
         &self,
         ctx: &Context,
-        listen_addr: String,
-        prefix_route: Route,
-        suffix_route: Route,
-        outlet_addr: MultiAddr,
-        alias: String,
-        policy_expression: Option<Expr>,
-        wait_for_outlet_duration: Option<Duration>,
-        authorized: Option<Identifier>,
-        wait_connection: bool,
+        options: CreateInletOptions,
     ) -> Result<InletStatus> {
-        self.node_manager
-            .create_inlet(
-                ctx,
-                listen_addr.clone(),
-                prefix_route.clone(),
-                suffix_route.clone(),
-                outlet_addr.clone(),
-                alias,
-                policy_expression,
-                wait_for_outlet_duration,
-                authorized,
-                wait_connection,
-            )
-            .await
+        self.node_manager.create_inlet(ctx, options).await
     }
 }
 
 struct InletSessionReplacer {
     node_manager: Arc<NodeManager>,
+    identifier: Identifier,
     context: Arc<Context>,
     listen_addr: String,
     outlet_addr: MultiAddr,
+    additional_outlet_addrs: Vec<MultiAddr>,
+    load_balancing: Option<InletLoadBalancing>,
     prefix_route: Route,
     suffix_route: Route,
-    authorized: Option<Identifier>,
+    authorized: Vec<Identifier>,
     wait_for_outlet_duration: Duration,
+    secure_channel_rekey_interval: Option<Duration>,
+    buffer_size: Option<usize>,
+    connection_limits: TcpInletConnectionLimits,
+    no_delay: bool,
+    unix_socket_mode: Option<u32>,
+    /// Replace an existing file at the path of the Unix domain socket of the inlet.
+    /// Set once the inlet was created, since the socket file of the previous inlet
+    /// might not be removed yet when the inlet is replaced
+    replace_unix_socket: bool,
+    expect_proxy_protocol: bool,
     resource: Resource,
     policy_expression: Option<Expr>,
 
     // current status
-    connection: Option<Connection>,
+    connections: Vec<Connection>,
+    /// Tasks connecting to the outlets which could not be reached when the inlet was created
+    reconnections: Vec<JoinHandle<Option<Connection>>>,
     inlet_address: Option<Address>,
 }
 
+/// Interval between two attempts to connect to an outlet of a load balanced inlet
+/// which could not be reached. It is shorter than the cooldown of the load balancer
+/// so that the route of the outlet stays unhealthy while the outlet is unreachable
+const OUTLET_RECONNECTION_INTERVAL: Duration = Duration::from_secs(10);
+
+impl InletSessionReplacer {
+    fn outlet_addrs(&self) -> impl Iterator<Item = &MultiAddr> {
+        core::iter::once(&self.outlet_addr).chain(self.additional_outlet_addrs.iter())
+    }
+
+    fn authorized(&self) -> Option<Vec<Identifier>> {
+        // any identity is trusted when no identities are explicitly authorized
+        if self.authorized.is_empty() {
+            None
+        } else {
+            Some(self.authorized.clone())
+        }
+    }
+
+    async fn connect(&self, outlet_addr: &MultiAddr) -> Result<Connection> {
+        self.node_manager
+            .connect(
+                self.context.clone(),
+                outlet_addr,
+                self.identifier.clone(),
+                self.authorized(),
+                Some(self.wait_for_outlet_duration),
+                self.secure_channel_rekey_interval,
+            )
+            .await
+    }
+
+    /// Keep trying to connect to the outlet of the route at `index` of the load balancer.
+    /// The route is counted as failed after each unsuccessful attempt, and replaced by the
+    /// route of the connection once the outlet is reached. The connection is returned so
+    /// that it can be closed with the other connections of the inlet
+    fn reconnect(
+        &self,
+        index: usize,
+        outlet_addr: MultiAddr,
+        load_balancer: TcpInletLoadBalancer,
+    ) -> JoinHandle<Option<Connection>> {
+        let node_manager = self.node_manager.clone();
+        let context = self.context.clone();
+        let identifier = self.identifier.clone();
+        let authorized = self.authorized();
+        let wait_for_outlet_duration = self.wait_for_outlet_duration;
+        let rekey_interval = self.secure_channel_rekey_interval;
+        let prefix_route = self.prefix_route.clone();
+        let suffix_route = self.suffix_route.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(OUTLET_RECONNECTION_INTERVAL).await;
+                let connection = node_manager
+                    .connect(
+                        context.clone(),
+                        &outlet_addr,
+                        identifier.clone(),
+                        authorized.clone(),
+                        Some(wait_for_outlet_duration),
+                        rekey_interval,
+                    )
+                    .await;
+                match connection.and_then(|c| c.route().map(|route| (c, route))) {
+                    Ok((connection, route)) => {
+                        debug!(%outlet_addr, "the outlet can be reached again");
+                        load_balancer.replace_route(
+                            index,
+                            route![prefix_route.clone(), route, suffix_route.clone()],
+                        );
+                        return Some(connection);
+                    }
+                    Err(err) => {
+                        debug!(%outlet_addr, %err, "the outlet still cannot be reached");
+                        load_balancer.route_failed(index);
+                    }
+                }
+            }
+        })
+    }
+
+    fn inlet_options(&self, access_control: Arc<dyn IncomingAccessControl>) -> TcpInletOptions {
+        let options = TcpInletOptions::new()
+            .with_incoming_access_control(access_control)
+            .with_connection_limits(self.connection_limits.clone());
+        let options = if self.no_delay {
+            options
+        } else {
+            options.with_batching(TcpPortalBatching::new())
+        };
+        let options = match self.unix_socket_mode {
+            Some(mode) => options.with_unix_socket_mode(mode),
+            None => options,
+        };
+        let options = options
+            .with_replace_unix_socket(self.replace_unix_socket)
+            .with_expect_proxy_protocol(self.expect_proxy_protocol);
+        match self.buffer_size {
+            Some(buffer_size) => options.with_buffer_size(buffer_size),
+            None => options,
+        }
+    }
+
+    /// Create the inlet listening on the bind address or on the Unix domain socket of the inlet
+    async fn create_tcp_inlet(
+        &self,
+        load_balancer: TcpInletLoadBalancer,
+        options: TcpInletOptions,
+    ) -> Result<Address> {
+        let tcp_transport = &self.node_manager.tcp_transport;
+        let inlet_address = match unix_socket_path(&self.listen_addr) {
+            #[cfg(unix)]
+            Some(path) => {
+                tcp_transport
+                    .create_unix_inlet(path, load_balancer, options)
+                    .await?
+            }
+            #[cfg(not(unix))]
+            Some(_) => {
+                return Err(ockam_core::Error::new(
+                    Origin::Node,
+                    Kind::Unsupported,
+                    "Unix domain sockets are not supported on this platform",
+                ))
+            }
+            None => {
+                tcp_transport
+                    .create_load_balanced_inlet(self.listen_addr.clone(), load_balancer, options)
+                    .await?
+                    .1
+            }
+        };
+        Ok(inlet_address)
+    }
+
+    /// Route to the outlet through a connection. We expect a fully normalized MultiAddr
+    fn normalized_route(&self, connection: &Connection) -> Result<Route> {
+        Ok(route![
+            self.prefix_route.clone(),
+            connection.route()?,
+            self.suffix_route.clone()
+        ])
+    }
+}
+
 #[async_trait]
 impl SessionReplacer for InletSessionReplacer {
     async fn create(&mut self) -> std::result::Result<ReplacerOutcome, ockam_core::Error> {
@@ -628,53 +1298,97 @@ impl SessionReplacer for InletSessionReplacer {
             .or(self.node_manager.project_authority());
 
             self.node_manager
-                .access_control(
+                .portal_access_control(
                     authority,
                     self.resource.clone(),
                     Action::HandleMessage,
                     self.policy_expression.clone(),
+                    PortalType::Inlet,
+                    self.resource.resource_name.as_str(),
                 )
                 .await?
         };
 
         // The future that recreates the inlet:
         let future = async {
-            let connection = self
-                .node_manager
-                .make_connection(
-                    self.context.clone(),
-                    &self.outlet_addr,
-                    self.node_manager.identifier(),
-                    self.authorized.clone(),
-                    Some(self.wait_for_outlet_duration),
-                )
-                .await?;
+            let Some(load_balancing) = self.load_balancing else {
+                let connection = self.connect(&self.outlet_addr).await?;
+                self.connections.push(connection.clone());
+                let normalized_route = self.normalized_route(&connection)?;
+                let options = self.inlet_options(access_control);
 
-            let connection_route = connection.route()?;
+                // Finally, attempt to create a new inlet using the new route:
+                let load_balancer = TcpInletLoadBalancer::new(
+                    vec![normalized_route.clone()],
+                    TcpInletLoadBalancing::default(),
+                );
+                let inlet_address = self.create_tcp_inlet(load_balancer, options).await?;
+                self.inlet_address = Some(inlet_address.clone());
+                self.replace_unix_socket = true;
 
-            //we expect a fully normalized MultiAddr
-            let normalized_route = route![
-                self.prefix_route.clone(),
-                connection_route,
-                self.suffix_route.clone()
-            ];
-            let options = TcpInletOptions::new().with_incoming_access_control(access_control);
+                return Ok(ReplacerOutcome {
+                    ping_route: connection.transport_route(),
+                    kind: ReplacerOutputKind::Inlet(CurrentInletStatus {
+                        worker: inlet_address,
+                        route: normalized_route,
+                        connection_status: ConnectionStatus::Up,
+                        load_balancer: None,
+                    }),
+                });
+            };
+
+            // Connect to all the outlets. The outlets which can't be reached get a
+            // placeholder route, marked as unhealthy, until they can be reached again
+            let mut routes = vec![];
+            let mut unreachable = vec![];
+            let mut route = None;
+            let mut ping_route = None;
+            let outlet_addrs: Vec<MultiAddr> = self.outlet_addrs().cloned().collect();
+            for (index, outlet_addr) in outlet_addrs.into_iter().enumerate() {
+                match self.connect(&outlet_addr).await {
+                    Ok(connection) => {
+                        let normalized_route = self.normalized_route(&connection)?;
+                        route.get_or_insert(normalized_route.clone());
+                        ping_route.get_or_insert(connection.transport_route());
+                        routes.push(normalized_route);
+                        self.connections.push(connection);
+                    }
+                    Err(err) => {
+                        warn!(%outlet_addr, %err, "failed to connect to the outlet");
+                        routes.push(route![self.prefix_route.clone(), self.suffix_route.clone()]);
+                        unreachable.push((index, outlet_addr));
+                    }
+                }
+            }
+            let (Some(route), Some(ping_route)) = (route, ping_route) else {
+                return Err(ockam_core::Error::new(
+                    Origin::Node,
+                    Kind::NotReady,
+                    "none of the outlets of the inlet can be reached",
+                ));
+            };
 
-            // Finally, attempt to create a new inlet using the new route:
+            let load_balancer = TcpInletLoadBalancer::new(routes, load_balancing.into())
+                .with_outlet_timeout(self.wait_for_outlet_duration);
+            for (index, outlet_addr) in unreachable {
+                load_balancer.route_failed(index);
+                let reconnection = self.reconnect(index, outlet_addr, load_balancer.clone());
+                self.reconnections.push(reconnection);
+            }
+            let options = self.inlet_options(access_control);
             let inlet_address = self
-                .node_manager
-                .tcp_transport
-                .create_inlet(self.listen_addr.clone(), normalized_route.clone(), options)
-                .await?
-                .1;
+                .create_tcp_inlet(load_balancer.clone(), options)
+                .await?;
             self.inlet_address = Some(inlet_address.clone());
+            self.replace_unix_socket = true;
 
             Ok(ReplacerOutcome {
-                ping_route: connection.transport_route(),
+                ping_route,
                 kind: ReplacerOutputKind::Inlet(CurrentInletStatus {
                     worker: inlet_address,
-                    route: normalized_route,
+                    route,
                     connection_status: ConnectionStatus::Up,
+                    load_balancer: Some(load_balancer),
                 }),
             })
         };
@@ -693,7 +1407,18 @@ impl SessionReplacer for InletSessionReplacer {
         }
     }
     async fn close(&mut self) {
-        if let Some(connection) = self.connection.take() {
+        // Stop connecting to the unreachable outlets, and keep the connections
+        // established in the meantime in order to tear them down with the others
+        for reconnection in self.reconnections.drain(..) {
+            reconnection.abort();
+            if let Ok(Some(connection)) = reconnection.await {
+                self.connections.push(connection);
+            }
+        }
+
+        // Tear down the secure channels of the previous connections, they might not be
+        // responsive anymore and would otherwise be left behind when the inlet is replaced
+        for connection in self.connections.drain(..) {
             let result = connection.close(&self.context, &self.node_manager).await;
             if let Err(err) = result {
                 error!(?err, "Failed to close connection");
@@ -713,26 +1438,335 @@ impl SessionReplacer for InletSessionReplacer {
             }
         }
     }
+
+    async fn on_transition(&mut self, transition: ConnectionTransition) {
+        let alias = self.resource.resource_name.to_string();
+        let event = if transition.to == ConnectionStatus::Up {
+            JourneyEvent::TcpInletConnectionRestored
+        } else {
+            JourneyEvent::TcpInletConnectionLost
+        };
+        let mut attributes = HashMap::new();
+        attributes.insert(NODE_NAME, self.node_manager.node_name());
+        attributes.insert(TCP_INLET_ALIAS, alias.clone());
+        attributes.insert(TCP_INLET_CONNECTION_STATUS, transition.to.to_string());
+        attributes.insert(
+            TCP_INLET_PREVIOUS_CONNECTION_STATUS,
+            transition.from.to_string(),
+        );
+        attributes.insert(
+            TCP_INLET_RECONNECTION_ATTEMPTS,
+            transition.attempts.to_string(),
+        );
+        if let Some(reason) = transition.reason {
+            attributes.insert(TCP_INLET_CONNECTION_FAILURE, reason);
+        }
+        if let Err(err) = self
+            .node_manager
+            .cli_state
+            .add_journey_event(event, attributes)
+            .await
+        {
+            warn!(%alias, ?err, "Failed to add the inlet connection journey event");
+        }
+    }
+
+    async fn on_renamed(&mut self, name: &str) {
+        self.resource = Resource::new(name, ResourceType::TcpInlet);
+    }
+}
+
+/// Options of an inlet created with [`Inlets::create_inlet`] or [`NodeManager::create_inlet`]
+#[derive(Clone, Debug)]
+pub struct CreateInletOptions {
+    listen_addr: String,
+    outlet_addr: MultiAddr,
+    alias: String,
+    authorized_identifiers: Vec<Identifier>,
+    prefix_route: Route,
+    suffix_route: Route,
+    policy_expression: Option<Expr>,
+    wait_for_outlet_timeout: Option<Duration>,
+    wait_connection: bool,
+    additional_outlet_addrs: Vec<MultiAddr>,
+    load_balancing: Option<InletLoadBalancing>,
+    secure_channel_rekey_interval: Option<Duration>,
+    buffer_size: Option<usize>,
+    max_connections_per_second: Option<u32>,
+    max_concurrent_connections: Option<u64>,
+    no_delay: bool,
+    probe_interval: Option<Duration>,
+    max_missed_probes: Option<u32>,
+    identity: Option<String>,
+    unix_socket_mode: Option<u32>,
+    replace_unix_socket: bool,
+    expect_proxy_protocol: bool,
+}
+
+impl CreateInletOptions {
+    /// Create an inlet named `alias`, listening on `listen_addr` and sending its
+    /// traffic to the outlet at `outlet_addr`
+    pub fn new(listen_addr: &str, outlet_addr: &MultiAddr, alias: &str) -> Self {
+        Self {
+            listen_addr: listen_addr.to_string(),
+            outlet_addr: outlet_addr.clone(),
+            alias: alias.to_string(),
+            authorized_identifiers: vec![],
+            prefix_route: route![],
+            suffix_route: route![],
+            policy_expression: None,
+            wait_for_outlet_timeout: None,
+            wait_connection: false,
+            additional_outlet_addrs: vec![],
+            load_balancing: None,
+            secure_channel_rekey_interval: None,
+            buffer_size: None,
+            max_connections_per_second: None,
+            max_concurrent_connections: None,
+            no_delay: false,
+            probe_interval: None,
+            max_missed_probes: None,
+            identity: None,
+            unix_socket_mode: None,
+            replace_unix_socket: false,
+            expect_proxy_protocol: false,
+        }
+    }
+
+    /// Only accept the outlets with one of these identities. Any identity is accepted if empty.
+    /// This is not used when the outlet is reached via a project
+    pub fn with_authorized_identifiers(mut self, authorized_identifiers: &[Identifier]) -> Self {
+        self.authorized_identifiers = authorized_identifiers.to_vec();
+        self
+    }
+
+    /// Apply a prefix route before the outlet address and a suffix route after it.
+    /// Those routes are not used to monitor the connection to the outlet
+    pub fn with_routes(mut self, prefix_route: Route, suffix_route: Route) -> Self {
+        self.prefix_route = prefix_route;
+        self.suffix_route = suffix_route;
+        self
+    }
+
+    /// Set the policy checked by the inlet for the messages received from the outlet
+    pub fn with_policy_expression(mut self, policy_expression: Option<Expr>) -> Self {
+        self.policy_expression = policy_expression;
+        self
+    }
+
+    /// Set the maximum time to wait for the outlet to be reachable
+    pub fn with_wait_for_outlet_timeout(mut self, wait_for_outlet_timeout: Duration) -> Self {
+        self.wait_for_outlet_timeout = Some(wait_for_outlet_timeout);
+        self
+    }
+
+    /// Wait until the inlet is connected to its outlet before returning its status
+    pub fn with_wait_connection(mut self, wait_connection: bool) -> Self {
+        self.wait_connection = wait_connection;
+        self
+    }
+
+    /// Balance the connections of the inlet across the outlet and the additional outlets
+    pub fn with_load_balancing(
+        mut self,
+        additional_outlet_addrs: &[MultiAddr],
+        load_balancing: Option<InletLoadBalancing>,
+    ) -> Self {
+        self.additional_outlet_addrs = additional_outlet_addrs.to_vec();
+        self.load_balancing = load_balancing;
+        self
+    }
+
+    /// Renew the keys of the secure channels to the outlets after this interval
+    pub fn with_secure_channel_rekey_interval(mut self, rekey_interval: Option<Duration>) -> Self {
+        self.secure_channel_rekey_interval = rekey_interval;
+        self
+    }
+
+    /// Set the maximum number of bytes sent on a connection and not yet acknowledged by the outlet
+    pub fn with_buffer_size(mut self, buffer_size: Option<usize>) -> Self {
+        self.buffer_size = buffer_size;
+        self
+    }
+
+    /// Limit the number of connections accepted per second and open at the same time
+    pub fn with_connection_limits(
+        mut self,
+        max_connections_per_second: Option<u32>,
+        max_concurrent_connections: Option<u64>,
+    ) -> Self {
+        self.max_connections_per_second = max_connections_per_second;
+        self.max_concurrent_connections = max_concurrent_connections;
+        self
+    }
+
+    /// Send the data read from the TCP clients right away, instead of batching small reads
+    pub fn with_no_delay(mut self, no_delay: bool) -> Self {
+        self.no_delay = no_delay;
+        self
+    }
+
+    /// Set the probes used to check the secure channel of the inlet
+    pub fn with_liveness(
+        mut self,
+        probe_interval: Option<Duration>,
+        max_missed_probes: Option<u32>,
+    ) -> Self {
+        self.probe_interval = probe_interval;
+        self.max_missed_probes = max_missed_probes;
+        self
+    }
+
+    /// Use this identity for the secure channels to the outlets, instead of the node identity
+    pub fn with_identity(mut self, identity: Option<String>) -> Self {
+        self.identity = identity;
+        self
+    }
+
+    /// Set the file mode of the Unix domain socket of the inlet, and replace
+    /// the file which already exists at its path
+    pub fn with_unix_socket(mut self, mode: Option<u32>, replace: bool) -> Self {
+        self.unix_socket_mode = mode;
+        self.replace_unix_socket = replace;
+        self
+    }
+
+    /// Read and strip a PROXY protocol v2 header at the start of each client connection
+    pub fn with_expect_proxy_protocol(mut self, expect_proxy_protocol: bool) -> Self {
+        self.expect_proxy_protocol = expect_proxy_protocol;
+        self
+    }
+
+    /// Request sent to the node to create the inlet
+    fn request(self) -> CreateInlet {
+        let via_project = self.outlet_addr.matches(0, &[ProjectProto::CODE.into()]);
+        let mut payload = if via_project {
+            CreateInlet::via_project(
+                self.listen_addr,
+                self.outlet_addr,
+                self.alias,
+                self.prefix_route,
+                self.suffix_route,
+                self.wait_connection,
+            )
+        } else {
+            CreateInlet::to_node(
+                self.listen_addr,
+                self.outlet_addr,
+                self.alias,
+                self.prefix_route,
+                self.suffix_route,
+                self.authorized_identifiers,
+                self.wait_connection,
+            )
+        };
+        if let Some(e) = self.policy_expression {
+            payload.set_policy_expression(e)
+        }
+        if let Some(wait_for_outlet_timeout) = self.wait_for_outlet_timeout {
+            payload.set_wait_ms(wait_for_outlet_timeout.as_millis() as u64);
+        }
+        payload.set_load_balancing(self.additional_outlet_addrs, self.load_balancing);
+        payload.set_secure_channel_rekey_interval(self.secure_channel_rekey_interval);
+        payload.set_buffer_size(self.buffer_size);
+        payload.set_connection_limits(
+            self.max_connections_per_second,
+            self.max_concurrent_connections,
+        );
+        payload.set_no_delay(self.no_delay);
+        payload.set_liveness(self.probe_interval, self.max_missed_probes);
+        payload.set_identity(self.identity);
+        payload.set_unix_socket(self.unix_socket_mode, self.replace_unix_socket);
+        payload.set_expect_proxy_protocol(self.expect_proxy_protocol);
+        payload
+    }
+
+    /// Return the limits on the connections accepted by the inlet
+    fn connection_limits(&self) -> TcpInletConnectionLimits {
+        let limits = TcpInletConnectionLimits::new();
+        let limits = match self.max_connections_per_second {
+            Some(max) => limits.with_max_connections_per_second(max),
+            None => limits,
+        };
+        match self.max_concurrent_connections {
+            Some(max) => limits.with_max_concurrent_connections(max),
+            None => limits,
+        }
+    }
+
+    /// Return the liveness probes of the inlet session, if any is configured
+    fn liveness(&self) -> Option<SessionLiveness> {
+        if self.probe_interval.is_none() && self.max_missed_probes.is_none() {
+            None
+        } else {
+            Some(SessionLiveness::new(
+                self.probe_interval,
+                self.max_missed_probes,
+            ))
+        }
+    }
+}
+
+impl From<CreateInlet> for CreateInletOptions {
+    fn from(create_inlet: CreateInlet) -> Self {
+        Self {
+            listen_addr: create_inlet.listen_addr,
+            outlet_addr: create_inlet.outlet_addr,
+            alias: create_inlet.alias,
+            authorized_identifiers: create_inlet.authorized,
+            prefix_route: create_inlet.prefix_route,
+            suffix_route: create_inlet.suffix_route,
+            policy_expression: create_inlet.policy_expression,
+            wait_for_outlet_timeout: create_inlet.wait_for_outlet_duration,
+            wait_connection: create_inlet.wait_connection,
+            additional_outlet_addrs: create_inlet.additional_outlet_addrs,
+            load_balancing: create_inlet.load_balancing,
+            secure_channel_rekey_interval: create_inlet.secure_channel_rekey_interval,
+            buffer_size: create_inlet.buffer_size,
+            max_connections_per_second: create_inlet.max_connections_per_second,
+            max_concurrent_connections: create_inlet.max_concurrent_connections,
+            no_delay: create_inlet.no_delay,
+            probe_interval: create_inlet.probe_interval,
+            max_missed_probes: create_inlet.max_missed_probes,
+            identity: create_inlet.identity,
+            unix_socket_mode: create_inlet.unix_socket_mode,
+            replace_unix_socket: create_inlet.replace_unix_socket,
+            expect_proxy_protocol: create_inlet.expect_proxy_protocol,
+        }
+    }
 }
 
 #[async_trait]
 pub trait Inlets {
-    #[allow(clippy::too_many_arguments)]
     async fn create_inlet(
         &self,
         ctx: &Context,
-        listen_addr: &str,
-        outlet_addr: &MultiAddr,
-        alias: &str,
-        authorized_identifier: &Option<Identifier>,
-        policy_expression: &Option<Expr>,
-        wait_for_outlet_timeout: Duration,
-        validate: bool,
+        options: CreateInletOptions,
     ) -> miette::Result<Reply<InletStatus>>;
 
     async fn show_inlet(&self, ctx: &Context, alias: &str) -> miette::Result<Reply<InletStatus>>;
 
     async fn delete_inlet(&self, ctx: &Context, inlet_alias: &str) -> miette::Result<Reply<()>>;
+
+    async fn rename_inlet(
+        &self,
+        ctx: &Context,
+        alias: &str,
+        new_alias: &str,
+    ) -> miette::Result<Reply<InletStatus>>;
+
+    async fn list_inlet_connections(
+        &self,
+        ctx: &Context,
+        alias: &str,
+    ) -> miette::Result<Vec<PortalConnection>>;
+
+    async fn close_inlet_connection(
+        &self,
+        ctx: &Context,
+        alias: &str,
+        connection_id: &str,
+    ) -> miette::Result<PortalConnection>;
 }
 
 #[async_trait]
@@ -740,42 +1774,9 @@ impl Inlets for BackgroundNodeClient {
     async fn create_inlet(
         &self,
         ctx: &Context,
-        listen_addr: &str,
-        outlet_addr: &MultiAddr,
-        alias: &str,
-        authorized_identifier: &Option<Identifier>,
-        policy_expression: &Option<Expr>,
-        wait_for_outlet_timeout: Duration,
-        wait_connection: bool,
+        options: CreateInletOptions,
     ) -> miette::Result<Reply<InletStatus>> {
-        let request = {
-            let via_project = outlet_addr.matches(0, &[ProjectProto::CODE.into()]);
-            let mut payload = if via_project {
-                CreateInlet::via_project(
-                    listen_addr.into(),
-                    outlet_addr.clone(),
-                    alias.into(),
-                    route![],
-                    route![],
-                    wait_connection,
-                )
-            } else {
-                CreateInlet::to_node(
-                    listen_addr.into(),
-                    outlet_addr.clone(),
-                    alias.into(),
-                    route![],
-                    route![],
-                    authorized_identifier.clone(),
-                    wait_connection,
-                )
-            };
-            if let Some(e) = policy_expression.as_ref() {
-                payload.set_policy_expression(e.clone())
-            }
-            payload.set_wait_ms(wait_for_outlet_timeout.as_millis() as u64);
-            Request::post("/node/inlet").body(payload)
-        };
+        let request = Request::post("/node/inlet").body(options.request());
         self.ask_and_get_reply(ctx, request).await
     }
 
@@ -788,17 +1789,158 @@ impl Inlets for BackgroundNodeClient {
         let request = Request::delete(format!("/node/inlet/{inlet_alias}"));
         self.tell_and_get_reply(ctx, request).await
     }
+
+    async fn rename_inlet(
+        &self,
+        ctx: &Context,
+        alias: &str,
+        new_alias: &str,
+    ) -> miette::Result<Reply<InletStatus>> {
+        let request =
+            Request::put(format!("/node/inlet/{alias}")).body(RenamePortal::new(new_alias));
+        self.ask_and_get_reply(ctx, request).await
+    }
+
+    async fn list_inlet_connections(
+        &self,
+        ctx: &Context,
+        alias: &str,
+    ) -> miette::Result<Vec<PortalConnection>> {
+        let request = Request::get(format!("/node/inlet/{alias}/connections"));
+        let connections: PortalConnectionList = self.ask(ctx, request).await?;
+        Ok(connections.list)
+    }
+
+    async fn close_inlet_connection(
+        &self,
+        ctx: &Context,
+        alias: &str,
+        connection_id: &str,
+    ) -> miette::Result<PortalConnection> {
+        let request = Request::delete(format!("/node/inlet/{alias}/connections/{connection_id}"));
+        self.ask(ctx, request).await
+    }
+}
+
+/// Options of an outlet created with [`NodeManager::create_outlet`]
+#[derive(Debug)]
+pub struct CreateOutletOptions {
+    socket_addr: SocketAddr,
+    worker_addr: Option<Address>,
+    reachable_from_default_secure_channel: bool,
+    access_control: OutletAccessControl,
+    buffer_size: Option<usize>,
+    hostname_port: Option<String>,
+    identity: Option<String>,
+    unix_socket_path: Option<String>,
+    proxy_protocol: bool,
+}
+
+impl CreateOutletOptions {
+    /// Create an outlet sending its traffic to `socket_addr`, and checking the messages
+    /// it receives with `access_control`
+    pub fn new(socket_addr: SocketAddr, access_control: OutletAccessControl) -> Self {
+        Self {
+            socket_addr,
+            worker_addr: None,
+            reachable_from_default_secure_channel: false,
+            access_control,
+            buffer_size: None,
+            hostname_port: None,
+            identity: None,
+            unix_socket_path: None,
+            proxy_protocol: false,
+        }
+    }
+
+    /// Set the address of the outlet worker. A random address is generated if not set
+    pub fn with_worker_addr(mut self, worker_addr: Option<Address>) -> Self {
+        self.worker_addr = worker_addr;
+        self
+    }
+
+    /// Accept the messages coming from the default secure channel listener
+    pub fn with_reachable_from_default_secure_channel(mut self, reachable: bool) -> Self {
+        self.reachable_from_default_secure_channel = reachable;
+        self
+    }
+
+    /// Set the maximum number of bytes sent on a connection and not yet acknowledged by the inlet.
+    /// The default portal buffer size is used if not set
+    pub fn with_buffer_size(mut self, buffer_size: Option<usize>) -> Self {
+        self.buffer_size = buffer_size;
+        self
+    }
+
+    /// Connect to a `host:port`, resolved again for each new connection
+    pub fn with_hostname_port(mut self, hostname_port: Option<String>) -> Self {
+        self.hostname_port = hostname_port;
+        self
+    }
+
+    /// Only accept the messages coming from the secure channel listeners of this identity,
+    /// instead of the default secure channel listener
+    pub fn with_identity(mut self, identity: Option<String>) -> Self {
+        self.identity = identity;
+        self
+    }
+
+    /// Connect to the Unix domain socket at this path, instead of a TCP server
+    pub fn with_unix_socket_path(mut self, unix_socket_path: Option<String>) -> Self {
+        self.unix_socket_path = unix_socket_path;
+        self
+    }
+
+    /// Start each connection to the target with a PROXY protocol v2 header
+    /// carrying the address of the inlet client
+    pub fn with_proxy_protocol(mut self, proxy_protocol: bool) -> Self {
+        self.proxy_protocol = proxy_protocol;
+        self
+    }
+}
+
+impl From<CreateOutlet> for CreateOutletOptions {
+    fn from(create_outlet: CreateOutlet) -> Self {
+        Self {
+            socket_addr: create_outlet.socket_addr,
+            worker_addr: create_outlet.worker_addr,
+            reachable_from_default_secure_channel: create_outlet
+                .reachable_from_default_secure_channel,
+            access_control: OutletAccessControl::PolicyExpression(create_outlet.policy_expression),
+            buffer_size: create_outlet.buffer_size,
+            hostname_port: create_outlet.hostname_port,
+            identity: create_outlet.identity,
+            unix_socket_path: create_outlet.unix_socket_path,
+            proxy_protocol: create_outlet.proxy_protocol,
+        }
+    }
 }
 
 #[async_trait]
 pub trait Outlets {
+    /// Create an outlet sending traffic to a `host:port`, to a socket address, or to a
+    /// Unix domain socket given as `unix:<path>` or as an absolute path.
+    /// A host name is resolved by the node for each new connection, once its cached
+    /// addresses have expired. With `proxy_protocol`, each connection to the target starts
+    /// with a PROXY protocol v2 header
+    #[allow(clippy::too_many_arguments)]
     async fn create_outlet(
         &self,
         ctx: &Context,
-        to: &SocketAddr,
+        to: &str,
         from: Option<&Address>,
         policy_expression: Option<Expr>,
+        buffer_size: Option<usize>,
+        identity: Option<String>,
+        proxy_protocol: bool,
     ) -> miette::Result<OutletStatus>;
+
+    async fn rename_outlet(
+        &self,
+        ctx: &Context,
+        worker_addr: &Address,
+        new_worker_addr: &Address,
+    ) -> miette::Result<Reply<OutletStatus>>;
 }
 
 #[async_trait]
@@ -807,16 +1949,51 @@ impl Outlets for BackgroundNodeClient {
     async fn create_outlet(
         &self,
         ctx: &Context,
-        to: &SocketAddr,
+        to: &str,
         from: Option<&Address>,
         policy_expression: Option<Expr>,
+        buffer_size: Option<usize>,
+        identity: Option<String>,
+        proxy_protocol: bool,
     ) -> miette::Result<OutletStatus> {
-        let mut payload = CreateOutlet::new(*to, from.cloned(), true);
+        let mut payload = match unix_socket_path(to) {
+            Some(path) => {
+                let mut payload = CreateOutlet::new(
+                    SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)),
+                    from.cloned(),
+                    true,
+                );
+                payload.set_unix_socket_path(Some(path.display().to_string()));
+                payload
+            }
+            None => {
+                let socket_addr = resolve_peer(to.to_string()).into_diagnostic()?;
+                let mut payload = CreateOutlet::new(socket_addr, from.cloned(), true);
+                if SocketAddr::from_str(to).is_err() {
+                    payload.set_hostname_port(Some(to.to_string()));
+                }
+                payload
+            }
+        };
         if let Some(policy_expression) = policy_expression {
             payload.set_policy_expression(policy_expression);
         }
+        payload.set_buffer_size(buffer_size);
+        payload.set_identity(identity);
+        payload.set_proxy_protocol(proxy_protocol);
         let req = Request::post("/node/outlet").body(payload);
         let result: OutletStatus = self.ask(ctx, req).await?;
         Ok(result)
     }
+
+    async fn rename_outlet(
+        &self,
+        ctx: &Context,
+        worker_addr: &Address,
+        new_worker_addr: &Address,
+    ) -> miette::Result<Reply<OutletStatus>> {
+        let request = Request::put(format!("/node/outlet/{}", worker_addr.address()))
+            .body(RenamePortal::new(new_worker_addr.address()));
+        self.ask_and_get_reply(ctx, request).await
+    }
 }
@@ -31,22 +31,37 @@ impl ProjectsOrchestratorApi for InMemoryNode {
 
     #[instrument(skip_all, fields(project_id = project_id))]
     async fn get_project(&self, ctx: &Context, project_id: &str) -> miette::Result<Project> {
-        let controller = self.create_controller().await?;
+        let projects = self.cli_state.projects();
+
+        // an imported project is used as is, since the controller might not be reachable
+        // a project which was recently retrieved from the controller is used as is as well
+        if projects.is_project_imported(project_id).await?
+            || projects.is_project_cached(project_id).await?
+        {
+            return Ok(projects.get_project(project_id).await?);
+        }
 
         // try to refresh the project from the controller
-        match controller.get_project(ctx, project_id).await {
-            Ok(project) => Ok(self
-                .cli_state
-                .projects()
-                .import_and_store_project(project.clone())
-                .await?),
+        match self.refresh_project(ctx, project_id).await {
+            Ok(project) => Ok(project),
             Err(e) => {
                 warn!("could no get the project {project_id} from the controller: {e:?}");
-                Ok(self.cli_state.projects().get_project(project_id).await?)
+                Ok(projects.get_project(project_id).await?)
             }
         }
     }
 
+    #[instrument(skip_all, fields(project_id = project_id))]
+    async fn refresh_project(&self, ctx: &Context, project_id: &str) -> miette::Result<Project> {
+        let controller = self.create_controller().await?;
+        let project = controller.get_project(ctx, project_id).await?;
+        Ok(self
+            .cli_state
+            .projects()
+            .import_and_store_project(project)
+            .await?)
+    }
+
     #[instrument(skip_all, fields(project_name = project_name))]
     async fn get_project_by_name_or_default(
         &self,
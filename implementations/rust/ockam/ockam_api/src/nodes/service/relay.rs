@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -5,19 +6,24 @@ use miette::IntoDiagnostic;
 
 use ockam::identity::models::CredentialAndPurposeKey;
 use ockam::identity::Identifier;
-use ockam::remote::{RemoteRelay, RemoteRelayOptions};
+use ockam::remote::{RemoteRelay, RemoteRelayHeartbeats, RemoteRelayOptions};
 use ockam::Result;
 use ockam_core::api::{Error, Request, RequestHeader, Response};
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{async_trait, Address, AsyncTryClone};
+use ockam_multiaddr::proto::Project;
 use ockam_multiaddr::MultiAddr;
 use ockam_node::Context;
 
+use crate::cloud::relay::{ProjectRelays, RelayRegistration};
+use crate::cloud::CredentialsEnabled;
+use crate::journeys::{JourneyEvent, NODE_NAME, RELAY_NAME, RELAY_RECONNECTION_ATTEMPTS};
 use crate::nodes::connection::Connection;
 use crate::nodes::models::relay::{CreateRelay, RelayInfo};
 use crate::nodes::models::secure_channel::{
     CreateSecureChannelRequest, CreateSecureChannelResponse,
 };
+use crate::nodes::models::services::BuiltinService;
 use crate::nodes::registry::RegistryRelayInfo;
 use crate::nodes::service::in_memory_node::InMemoryNode;
 use crate::nodes::BackgroundNodeClient;
@@ -39,6 +45,8 @@ impl NodeManagerWorker {
             at_rust_node,
             authorized,
             relay_address,
+            heartbeat_interval,
+            force,
         } = create_relay;
         match self
             .node_manager
@@ -49,14 +57,22 @@ impl NodeManagerWorker {
                 at_rust_node,
                 authorized,
                 relay_address,
+                heartbeat_interval,
+                force,
             )
             .await
         {
             Ok(body) => Ok(Response::ok().with_headers(req).body(body)),
-            Err(err) => Err(Response::internal_error(
-                req,
-                &format!("Failed to create relay: {}", err),
-            )),
+            Err(err) => match err.code().kind {
+                Kind::AlreadyExists => Err(Response::bad_request(
+                    req,
+                    &format!("Failed to create relay: {}", err),
+                )),
+                _ => Err(Response::internal_error(
+                    req,
+                    &format!("Failed to create relay: {}", err),
+                )),
+            },
         }
     }
 
@@ -121,6 +137,7 @@ impl NodeManager {
     /// The Connection encapsulates the list of workers required on the relay route.
     /// This route is monitored in the `InMemoryNode` and the workers are restarted if necessary
     /// when the route is unresponsive
+    #[allow(clippy::too_many_arguments)]
     pub async fn create_relay(
         self: &Arc<Self>,
         ctx: &Context,
@@ -129,6 +146,8 @@ impl NodeManager {
         at_rust_node: bool,
         authorized: Option<Identifier>,
         relay_address: Option<String>,
+        heartbeat_interval: Option<Duration>,
+        force: bool,
     ) -> Result<RelayInfo> {
         if self.registry.relays.contains_key(&alias).await {
             let message = format!("A relay with the name '{alias}' already exists");
@@ -139,12 +158,26 @@ impl NodeManager {
             ));
         }
 
+        // Make sure that we don't take over the traffic of a relay registered
+        // in the project by another node
+        let project_relay_name = relay_address.clone().filter(|_| !at_rust_node);
+        if let Some(relay_name) = &project_relay_name {
+            let existing = self
+                .get_project_relay_registration(ctx, addr, relay_name)
+                .await;
+            RelayRegistration::check_collision(&existing, &self.identifier(), force)?;
+        }
+
+        let heartbeats = RemoteRelayHeartbeats::default();
         let replacer = RelaySessionReplacer {
             node_manager: self.clone(),
             context: Arc::new(ctx.async_try_clone().await?),
+            alias: alias.clone(),
             addr: addr.clone(),
             at_rust_node,
             relay_address,
+            heartbeat_interval,
+            heartbeats: heartbeats.clone(),
             connection: None,
             relay_worker_address: None,
             authorized,
@@ -161,11 +194,32 @@ impl NodeManager {
                     }
                 })?;
 
+        // The relay name could have been taken by another node between the check and the creation
+        if let Some(relay_name) = &project_relay_name {
+            // the registration was checked before the creation, so a failure to retrieve it
+            // again does not prevent the relay from being created
+            let registered = self
+                .get_project_relay_registration(ctx, addr, relay_name)
+                .await
+                .unwrap_or_else(|err| {
+                    warn!(%alias, %err, "Failed to check the registration of the created relay");
+                    None
+                });
+            if let Err(err) =
+                RelayRegistration::check_ownership(registered.as_ref(), &self.identifier())
+            {
+                warn!(%alias, %err, "The relay was registered by another node during its creation");
+                session.close().await?;
+                return Err(err);
+            }
+        }
+
         let registry_relay_info = RegistryRelayInfo {
             destination_address: addr.clone(),
             alias: alias.clone(),
             at_rust_node,
             session,
+            heartbeats,
         };
 
         self.registry
@@ -182,6 +236,33 @@ impl NodeManager {
         Ok(registry_relay_info.into())
     }
 
+    /// Return the registration of a relay in the project targeted by `addr`.
+    /// Return `None` if `addr` is not a project address, and an error if the project could not be queried.
+    async fn get_project_relay_registration(
+        &self,
+        ctx: &Context,
+        addr: &MultiAddr,
+        relay_name: &str,
+    ) -> Result<Option<RelayRegistration>> {
+        let Some(project_name) = addr
+            .first()
+            .and_then(|p| p.cast::<Project>().map(|p| p.to_string()))
+        else {
+            return Ok(None);
+        };
+
+        let (project_multiaddr, project_identifier) = self.resolve_project(&project_name).await?;
+        let client = self
+            .make_project_node_client(
+                &project_identifier,
+                &project_multiaddr,
+                &self.identifier(),
+                CredentialsEnabled::On,
+            )
+            .await?;
+        client.get_relay_registration(ctx, relay_name).await
+    }
+
     /// Delete a relay.
     ///
     /// This function removes a relay from the node registry and stops the relay worker.
@@ -229,6 +310,7 @@ impl NodeManager {
 }
 
 impl InMemoryNode {
+    #[allow(clippy::too_many_arguments)]
     pub async fn create_relay(
         &self,
         ctx: &Context,
@@ -237,9 +319,20 @@ impl InMemoryNode {
         at_rust_node: bool,
         authorized: Option<Identifier>,
         relay_address: Option<String>,
+        heartbeat_interval: Option<Duration>,
+        force: bool,
     ) -> Result<RelayInfo> {
         self.node_manager
-            .create_relay(ctx, address, alias, at_rust_node, authorized, relay_address)
+            .create_relay(
+                ctx,
+                address,
+                alias,
+                at_rust_node,
+                authorized,
+                relay_address,
+                heartbeat_interval,
+                force,
+            )
             .await
     }
 
@@ -251,7 +344,10 @@ impl InMemoryNode {
 struct RelaySessionReplacer {
     node_manager: Arc<NodeManager>,
     context: Arc<Context>,
+    alias: String,
     relay_address: Option<String>,
+    heartbeat_interval: Option<Duration>,
+    heartbeats: RemoteRelayHeartbeats,
 
     // current status
     connection: Option<Connection>,
@@ -273,18 +369,29 @@ impl SessionReplacer for RelaySessionReplacer {
                 self.node_manager.identifier(),
                 self.authorized.clone(),
                 None,
+                None,
             )
             .await?;
         connection.add_default_consumers(self.context.clone());
+        self.connection = Some(connection.clone());
 
         // Add all Hop workers as consumers for Demo purposes
         // Production nodes should not run any Hop workers
-        for hop in self.node_manager.registry.hop_services.keys().await {
+        for hop in self
+            .node_manager
+            .registry
+            .services
+            .addresses_of(BuiltinService::Hop)
+            .await
+        {
             connection.add_consumer(self.context.clone(), &hop);
         }
 
         let route = connection.route()?;
-        let options = RemoteRelayOptions::new();
+        let mut options = RemoteRelayOptions::new().with_heartbeats(self.heartbeats.clone());
+        if let Some(heartbeat_interval) = self.heartbeat_interval {
+            options = options.with_heartbeat_interval(heartbeat_interval);
+        }
 
         let relay_info = if self.at_rust_node {
             if let Some(relay_address) = self.relay_address.as_ref() {
@@ -331,6 +438,22 @@ impl SessionReplacer for RelaySessionReplacer {
             }
         }
     }
+
+    async fn on_recovered(&mut self, attempts: u32) {
+        info!(alias = %self.alias, %attempts, "Relay registered again");
+        let mut attributes = HashMap::new();
+        attributes.insert(NODE_NAME, self.node_manager.node_name());
+        attributes.insert(RELAY_NAME, self.alias.clone());
+        attributes.insert(RELAY_RECONNECTION_ATTEMPTS, attempts.to_string());
+        if let Err(err) = self
+            .node_manager
+            .cli_state
+            .add_journey_event(JourneyEvent::RelayReconnected, attributes)
+            .await
+        {
+            warn!(alias = %self.alias, ?err, "Failed to add the relay reconnection journey event");
+        }
+    }
 }
 
 #[async_trait]
@@ -343,7 +466,13 @@ pub trait Relays {
         authorized: Option<Identifier>,
         relay_address: Option<String>,
         at_rust_node: bool,
+        heartbeat_interval: Option<Duration>,
+        force: bool,
     ) -> miette::Result<RelayInfo>;
+
+    async fn show_relay(&self, ctx: &Context, alias: &str) -> miette::Result<RelayInfo>;
+
+    async fn list_relays(&self, ctx: &Context) -> miette::Result<Vec<RelayInfo>>;
 }
 
 #[async_trait]
@@ -356,6 +485,8 @@ impl Relays for BackgroundNodeClient {
         authorized: Option<Identifier>,
         relay_address: Option<String>,
         at_rust_node: bool,
+        heartbeat_interval: Option<Duration>,
+        force: bool,
     ) -> miette::Result<RelayInfo> {
         let body = CreateRelay::new(
             address.clone(),
@@ -363,9 +494,20 @@ impl Relays for BackgroundNodeClient {
             at_rust_node,
             authorized,
             relay_address,
-        );
+        )
+        .with_heartbeat_interval(heartbeat_interval)
+        .with_force(force);
         self.ask(ctx, Request::post("/node/relay").body(body)).await
     }
+
+    async fn show_relay(&self, ctx: &Context, alias: &str) -> miette::Result<RelayInfo> {
+        self.ask(ctx, Request::get(format!("/node/relay/{alias}")))
+            .await
+    }
+
+    async fn list_relays(&self, ctx: &Context) -> miette::Result<Vec<RelayInfo>> {
+        self.ask(ctx, Request::get("/node/relay")).await
+    }
 }
 
 #[async_trait]
@@ -400,6 +542,7 @@ impl SecureChannelsCreation for InMemoryNode {
                 Some(vec![authorized]),
                 credential,
                 timeout,
+                None,
             )
             .await
             .into_diagnostic()
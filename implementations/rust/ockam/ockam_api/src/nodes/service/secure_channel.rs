@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::time::Duration;
 
 use ockam::identity::models::CredentialAndPurposeKey;
@@ -5,10 +6,10 @@ use ockam::identity::TrustEveryonePolicy;
 use ockam::identity::Vault;
 use ockam::identity::{
     Identifier, Identities, SecureChannelListenerOptions, SecureChannelOptions, SecureChannels,
-    TrustMultiIdentifiersPolicy,
+    TrustAllowlistPolicy, TrustMultiIdentifiersPolicy,
 };
 use ockam::identity::{SecureChannel, SecureChannelListener};
-use ockam::{Address, Result, Route};
+use ockam::{route, Address, Result, Route};
 use ockam_core::api::{Error, Response};
 use ockam_core::compat::sync::Arc;
 use ockam_core::errcode::{Kind, Origin};
@@ -16,6 +17,10 @@ use ockam_core::AsyncTryClone;
 use ockam_multiaddr::MultiAddr;
 use ockam_node::Context;
 
+use crate::journeys::{
+    JourneyEvent, NODE_NAME, SECURE_CHANNEL_ADDRESS, SECURE_CHANNEL_THEIR_IDENTIFIER,
+    SECURE_CHANNEL_USED_BY,
+};
 use crate::nodes::models::secure_channel::CreateSecureChannelListenerRequest;
 use crate::nodes::models::secure_channel::CreateSecureChannelRequest;
 use crate::nodes::models::secure_channel::DeleteSecureChannelListenerRequest;
@@ -23,17 +28,24 @@ use crate::nodes::models::secure_channel::DeleteSecureChannelRequest;
 use crate::nodes::models::secure_channel::ListSecureChannelListenerResponse;
 use crate::nodes::models::secure_channel::ShowSecureChannelListenerRequest;
 use crate::nodes::models::secure_channel::ShowSecureChannelRequest;
+use crate::nodes::models::secure_channel::UpdateSecureChannelListenerRequest;
 use crate::nodes::models::secure_channel::{
     CreateSecureChannelResponse, DeleteSecureChannelListenerResponse, DeleteSecureChannelResponse,
-    ShowSecureChannelListenerResponse, ShowSecureChannelResponse,
+    SecureChannelList, SecureChannelStatus, SecureChannelUser, ShowSecureChannelListenerResponse,
+    ShowSecureChannelResponse,
+};
+use crate::nodes::registry::{
+    NamedSecureChannelInfo, SecureChannelInfo, SecureChannelListenerInfo,
 };
-use crate::nodes::registry::{SecureChannelInfo, SecureChannelListenerInfo};
 use crate::nodes::service::default_address::DefaultAddress;
 use crate::nodes::{NodeManager, NodeManagerWorker};
+use crate::session::sessions::{ConnectionStatus, ReplacerOutputKind, Session};
 
 /// SECURE CHANNELS
 impl NodeManagerWorker {
-    pub async fn list_secure_channels(&self) -> Result<Response<Vec<String>>, Response<Error>> {
+    pub async fn list_secure_channels(
+        &self,
+    ) -> Result<Response<SecureChannelList>, Response<Error>> {
         Ok(Response::ok().body(self.node_manager.list_secure_channels().await))
     }
 
@@ -48,24 +60,42 @@ impl NodeManagerWorker {
             timeout,
             identity_name: identity,
             credential,
-            ..
+            rekey_interval,
+            name,
+            auto_reconnect,
         } = create_secure_channel;
 
-        let response = self
-            .node_manager
-            .create_secure_channel(
-                ctx,
-                addr,
-                identity,
-                authorized_identifiers,
-                credential,
-                timeout,
-            )
-            .await
-            .map(|secure_channel| {
-                Response::ok().body(CreateSecureChannelResponse::new(secure_channel))
-            })?;
-        Ok(response)
+        let secure_channel = match name {
+            Some(name) => {
+                self.node_manager
+                    .create_named_secure_channel(
+                        ctx,
+                        &name,
+                        addr,
+                        identity,
+                        authorized_identifiers,
+                        credential,
+                        timeout,
+                        rekey_interval,
+                        auto_reconnect,
+                    )
+                    .await?
+            }
+            None => {
+                self.node_manager
+                    .create_secure_channel(
+                        ctx,
+                        addr,
+                        identity,
+                        authorized_identifiers,
+                        credential,
+                        timeout,
+                        rekey_interval,
+                    )
+                    .await?
+            }
+        };
+        Ok(Response::ok().body(CreateSecureChannelResponse::new(secure_channel)))
     }
 
     pub async fn delete_secure_channel(
@@ -79,10 +109,21 @@ impl NodeManagerWorker {
 
         let response = self
             .node_manager
-            .delete_secure_channel(ctx, &address)
-            .await
-            .map(|_| Response::ok().body(DeleteSecureChannelResponse::new(Some(address))))?;
-        Ok(response)
+            .force_delete_secure_channel(ctx, &address)
+            .await?;
+        Ok(Response::ok().body(response))
+    }
+
+    pub async fn delete_named_secure_channel(
+        &self,
+        ctx: &Context,
+        name: &str,
+    ) -> Result<Response<DeleteSecureChannelResponse>, Response<Error>> {
+        let address = self
+            .node_manager
+            .delete_named_secure_channel(ctx, name)
+            .await?;
+        Ok(Response::ok().body(DeleteSecureChannelResponse::new(Some(address))))
     }
 
     pub async fn show_secure_channel(
@@ -114,12 +155,18 @@ impl NodeManagerWorker {
             addr,
             authorized_identifiers,
             identity_name,
-            ..
+            max_handshakes_per_second,
         } = create_secure_channel_listener;
 
         let response = self
             .node_manager
-            .create_secure_channel_listener(addr, authorized_identifiers, identity_name, ctx)
+            .create_secure_channel_listener(
+                addr,
+                authorized_identifiers,
+                identity_name,
+                max_handshakes_per_second,
+                ctx,
+            )
             .await
             .map(|_| Response::ok())?;
         Ok(response)
@@ -155,6 +202,26 @@ impl NodeManagerWorker {
         Ok(response)
     }
 
+    pub async fn update_secure_channel_listener(
+        &self,
+        update_secure_channel_listener: UpdateSecureChannelListenerRequest,
+    ) -> Result<Response<ShowSecureChannelListenerResponse>, Response<Error>> {
+        let UpdateSecureChannelListenerRequest {
+            addr,
+            set,
+            add,
+            remove,
+        } = update_secure_channel_listener;
+        let response = self
+            .node_manager
+            .update_secure_channel_listener(&addr, set, add, remove)
+            .await
+            .map(|secure_channel_info| {
+                Response::ok().body(ShowSecureChannelListenerResponse::new(&secure_channel_info))
+            })?;
+        Ok(response)
+    }
+
     pub async fn list_secure_channel_listener(
         &self,
     ) -> Result<Response<ListSecureChannelListenerResponse>, Response<Error>> {
@@ -174,12 +241,20 @@ impl NodeManager {
         authorized_identifiers: Option<Vec<Identifier>>,
         credential: Option<CredentialAndPurposeKey>,
         timeout: Option<Duration>,
+        rekey_interval: Option<Duration>,
     ) -> Result<SecureChannel> {
         let identifier = self.get_identifier_by_name(identity_name.clone()).await?;
 
         let connection_ctx = Arc::new(ctx.async_try_clone().await?);
         let connection = self
-            .make_connection(connection_ctx, &addr, identifier.clone(), None, timeout)
+            .make_connection(
+                connection_ctx,
+                &addr,
+                identifier.clone(),
+                None,
+                timeout,
+                rekey_interval,
+            )
             .await?;
         let sc = self
             .create_secure_channel_internal(
@@ -189,6 +264,7 @@ impl NodeManager {
                 authorized_identifiers,
                 credential,
                 timeout,
+                rekey_interval,
             )
             .await?;
 
@@ -204,6 +280,7 @@ impl NodeManager {
         authorized_identifiers: Option<Vec<Identifier>>,
         credential: Option<CredentialAndPurposeKey>,
         timeout: Option<Duration>,
+        rekey_interval: Option<Duration>,
     ) -> Result<SecureChannel> {
         debug!(%sc_route, "Creating secure channel");
         let options = SecureChannelOptions::new();
@@ -214,6 +291,12 @@ impl NodeManager {
             options
         };
 
+        let options = if let Some(rekey_interval) = rekey_interval {
+            options.with_rekey_interval(rekey_interval)
+        } else {
+            options
+        };
+
         let options = match self.project_authority() {
             Some(project_authority) => options.with_authority(project_authority),
             None => options,
@@ -237,7 +320,10 @@ impl NodeManager {
         let sc = self
             .secure_channels
             .create_secure_channel(ctx, identifier, sc_route.clone(), options)
-            .await?;
+            .await;
+        #[cfg(feature = "telemetry")]
+        self.metrics.record_secure_channel_handshake(sc.is_ok());
+        let sc = sc?;
 
         debug!(%sc_route, %sc, "Created secure channel");
 
@@ -260,9 +346,267 @@ impl NodeManager {
         }
         self.secure_channels.stop_secure_channel(ctx, addr).await?;
         self.registry.secure_channels.remove_by_addr(addr).await;
+
+        // the name of a deleted channel can be reused
+        for (name, named) in self.registry.named_secure_channels.entries().await {
+            if named.encryptor_address() == addr {
+                self.registry.named_secure_channels.remove(&name).await;
+            }
+        }
         Ok(())
     }
 
+    /// Delete a secure channel initiated or accepted by the node, given its encryptor or its
+    /// decryptor address, even if it was created implicitly, for example for an inlet.
+    ///
+    /// The other party is notified that the channel is closed when it can still be reached.
+    /// The inlets, relays and connections using the channel start establishing a new one
+    /// right away, instead of waiting for their liveness probes to fail.
+    pub async fn force_delete_secure_channel(
+        &self,
+        ctx: &Context,
+        addr: &Address,
+    ) -> Result<DeleteSecureChannelResponse> {
+        let registry = self.secure_channels.secure_channel_registry();
+        let entry = registry
+            .get_channel_by_encryptor_address(addr)
+            .or_else(|| registry.get_channel_by_decryptor_address(addr))
+            .ok_or_else(|| {
+                ockam_core::Error::new(
+                    Origin::Api,
+                    Kind::NotFound,
+                    format!("Secure channel with address, {}, not found", addr),
+                )
+            })?;
+        let encryptor = entry.encryptor_messaging_address().clone();
+        let dependents: Vec<(SecureChannelUser, Option<Session>)> = self
+            .secure_channel_users()
+            .await
+            .into_iter()
+            .filter(|(address, _, _)| address == &encryptor)
+            .map(|(_, user, session)| (user, session))
+            .collect();
+        info!(%encryptor, their_identifier = %entry.their_id(), "forcibly deleting secure channel");
+
+        if self
+            .registry
+            .secure_channels
+            .get_by_addr(&encryptor)
+            .await
+            .is_some()
+        {
+            self.delete_secure_channel(ctx, &encryptor).await?;
+        } else {
+            self.secure_channels
+                .stop_secure_channel(ctx, &encryptor)
+                .await?;
+        }
+
+        let reason = format!("the secure channel {encryptor} was deleted");
+        let mut failed_over = vec![];
+        for (user, session) in dependents.iter() {
+            if let Some(session) = session {
+                info!(%encryptor, %user, "re-establishing the secure channel");
+                session.request_replacement(reason.clone());
+                failed_over.push(user.clone());
+            }
+        }
+        if !failed_over.is_empty() {
+            self.registry.session_replacements.notify_one();
+        }
+
+        let mut attributes = HashMap::new();
+        attributes.insert(NODE_NAME, self.node_name());
+        attributes.insert(SECURE_CHANNEL_ADDRESS, encryptor.to_string());
+        attributes.insert(
+            SECURE_CHANNEL_THEIR_IDENTIFIER,
+            entry.their_id().to_string(),
+        );
+        attributes.insert(
+            SECURE_CHANNEL_USED_BY,
+            dependents
+                .iter()
+                .map(|(user, _)| user.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+        );
+        if let Err(err) = self
+            .cli_state
+            .add_journey_event(JourneyEvent::SecureChannelDeleted, attributes)
+            .await
+        {
+            warn!(%encryptor, ?err, "Failed to add the secure channel deletion journey event");
+        }
+
+        Ok(DeleteSecureChannelResponse::new(Some(encryptor)).with_failed_over(failed_over))
+    }
+
+    /// Create a secure channel kept under a name, so that it can be used with a
+    /// `/channel/<name>` segment instead of creating a new channel for each request
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_named_secure_channel(
+        &self,
+        ctx: &Context,
+        name: &str,
+        addr: MultiAddr,
+        identity_name: Option<String>,
+        authorized_identifiers: Option<Vec<Identifier>>,
+        credential: Option<CredentialAndPurposeKey>,
+        timeout: Option<Duration>,
+        rekey_interval: Option<Duration>,
+        auto_reconnect: bool,
+    ) -> Result<SecureChannel> {
+        if self.registry.named_secure_channels.contains_key(name).await {
+            return Err(ockam_core::Error::new(
+                Origin::Api,
+                Kind::AlreadyExists,
+                format!("A secure channel named '{name}' already exists"),
+            ));
+        }
+
+        let sc = self
+            .create_secure_channel(
+                ctx,
+                addr.clone(),
+                identity_name.clone(),
+                authorized_identifiers.clone(),
+                credential.clone(),
+                timeout,
+                rekey_interval,
+            )
+            .await?;
+        self.registry
+            .named_secure_channels
+            .insert(
+                name.to_string(),
+                NamedSecureChannelInfo::new(
+                    addr,
+                    identity_name,
+                    authorized_identifiers,
+                    credential,
+                    timeout,
+                    rekey_interval,
+                    auto_reconnect,
+                    sc.encryptor_address().clone(),
+                ),
+            )
+            .await;
+        Ok(sc)
+    }
+
+    /// Return the secure channel kept under `name`.
+    ///
+    /// The channel is checked before being returned. When it is stale, because it was
+    /// closed or because its other side doesn't answer anymore, it is created again if
+    /// it was created with `auto_reconnect`. Otherwise an error is returned.
+    pub async fn resolve_named_secure_channel(
+        &self,
+        ctx: &Context,
+        name: &str,
+    ) -> Result<SecureChannel> {
+        let named = self
+            .registry
+            .named_secure_channels
+            .get(name)
+            .await
+            .ok_or_else(|| {
+                ockam_core::Error::new(
+                    Origin::Api,
+                    Kind::NotFound,
+                    format!("There is no secure channel named '{name}'"),
+                )
+            })?;
+
+        let address = named.encryptor_address();
+        if let Some(info) = self.registry.secure_channels.get_by_addr(address).await {
+            if self.is_secure_channel_alive(info.sc()).await {
+                return Ok(info.sc().clone());
+            }
+            debug!(%name, %address, "the named secure channel is stale");
+            let _ = self.secure_channels.stop_secure_channel(ctx, address).await;
+            self.registry.secure_channels.remove_by_addr(address).await;
+        }
+
+        if !named.auto_reconnect() {
+            return Err(ockam_core::Error::new(
+                Origin::Api,
+                Kind::Shutdown,
+                format!(
+                    "The secure channel '{name}' to {} is stale, its other side doesn't answer anymore. \
+                    Delete it and create it again, or create it with --auto-reconnect",
+                    named.addr()
+                ),
+            ));
+        }
+
+        info!(%name, addr = %named.addr(), "re-establishing the named secure channel");
+        let sc = self
+            .create_secure_channel(
+                ctx,
+                named.addr().clone(),
+                named.identity_name(),
+                named.authorized_identifiers(),
+                named.credential(),
+                named.timeout(),
+                named.rekey_interval(),
+            )
+            .await?;
+        self.registry
+            .named_secure_channels
+            .insert(
+                name.to_string(),
+                named.with_encryptor_address(sc.encryptor_address().clone()),
+            )
+            .await;
+        Ok(sc)
+    }
+
+    /// Delete the secure channel kept under `name` and return its address
+    pub async fn delete_named_secure_channel(&self, ctx: &Context, name: &str) -> Result<Address> {
+        let named = self
+            .registry
+            .named_secure_channels
+            .remove(name)
+            .await
+            .ok_or_else(|| {
+                ockam_core::Error::new(
+                    Origin::Api,
+                    Kind::NotFound,
+                    format!("There is no secure channel named '{name}'"),
+                )
+            })?;
+        let address = named.encryptor_address().clone();
+        if self
+            .registry
+            .secure_channels
+            .get_by_addr(&address)
+            .await
+            .is_some()
+        {
+            self.delete_secure_channel(ctx, &address).await?;
+        }
+        Ok(address)
+    }
+
+    /// Check that a secure channel can still be used, without sending any message on it.
+    /// The channel must not be closed, and the sessions of the inlets, relays and connections
+    /// using it must not have found its other side unresponsive
+    async fn is_secure_channel_alive(&self, sc: &SecureChannel) -> bool {
+        if sc.info().is_none() {
+            return false;
+        }
+        !self
+            .secure_channel_users()
+            .await
+            .iter()
+            .any(|(address, _, session)| {
+                address == sc.encryptor_address()
+                    && session
+                        .as_ref()
+                        .is_some_and(|s| s.connection_status() != ConnectionStatus::Up)
+            })
+    }
+
     pub async fn get_secure_channel(&self, addr: &Address) -> Result<SecureChannelInfo> {
         debug!(%addr, "On show secure channel");
         self.registry
@@ -276,13 +620,77 @@ impl NodeManager {
             ))
     }
 
-    pub async fn list_secure_channels(&self) -> Vec<String> {
-        let registry = &self.registry.secure_channels;
-        let secure_channel_list = registry.list().await;
-        secure_channel_list
-            .into_iter()
-            .map(|secure_channel| secure_channel.sc().encryptor_address().to_string())
-            .collect()
+    /// Return all the secure channels of the node, initiated or accepted, including the
+    /// channels created implicitly for its inlets and relays
+    pub async fn list_secure_channels(&self) -> SecureChannelList {
+        let users = self.secure_channel_users().await;
+        let list = self
+            .secure_channels
+            .secure_channel_registry()
+            .get_channel_list()
+            .iter()
+            .map(|entry| {
+                let used_by = users
+                    .iter()
+                    .filter(|(address, _, _)| address == entry.encryptor_messaging_address())
+                    .map(|(_, user, _)| user.clone())
+                    .collect();
+                SecureChannelStatus::new(entry, used_by)
+            })
+            .collect();
+        SecureChannelList::new(list)
+    }
+
+    /// Return the encryptor addresses found in the routes of the inlets, relays, connections
+    /// and named secure channels of the node, with the resource using them and its session
+    async fn secure_channel_users(&self) -> Vec<(Address, SecureChannelUser, Option<Session>)> {
+        let mut users: Vec<(Address, SecureChannelUser, Option<Session>)> = vec![];
+        let mut add = |routes: Vec<Route>, user: SecureChannelUser, session: Option<Session>| {
+            for address in routes.iter().flat_map(|route| route.iter()) {
+                if !users.iter().any(|(a, u, _)| a == address && u == &user) {
+                    users.push((address.clone(), user.clone(), session.clone()));
+                }
+            }
+        };
+
+        for (alias, info) in self.registry.inlets.entries().await {
+            let routes = match info.session.status().map(|outcome| outcome.kind) {
+                Some(ReplacerOutputKind::Inlet(status)) => {
+                    let mut routes = vec![status.route];
+                    if let Some(load_balancer) = status.load_balancer {
+                        routes.extend(load_balancer.statuses().iter().map(|s| s.route().clone()));
+                    }
+                    routes
+                }
+                _ => vec![],
+            };
+            add(
+                routes,
+                SecureChannelUser::new("tcp-inlet", alias),
+                Some(info.session),
+            );
+        }
+        for (alias, info) in self.registry.relays.entries().await {
+            let routes = info.session.ping_route().into_iter().collect();
+            add(
+                routes,
+                SecureChannelUser::new("relay", alias),
+                Some(info.session),
+            );
+        }
+        for (key, info) in self.registry.warm_connections.entries().await {
+            let routes = info.session.ping_route().into_iter().collect();
+            add(
+                routes,
+                SecureChannelUser::new("connection", key),
+                Some(info.session),
+            );
+        }
+        for (name, info) in self.registry.named_secure_channels.entries().await {
+            let routes = vec![route![info.encryptor_address().clone()]];
+            add(routes, SecureChannelUser::new("secure-channel", name), None);
+        }
+        users
     }
 }
 
@@ -293,6 +701,7 @@ impl NodeManager {
         address: Address,
         authorized_identifiers: Option<Vec<Identifier>>,
         identity_name: Option<String>,
+        max_handshakes_per_second: Option<u32>,
         ctx: &Context,
     ) -> Result<SecureChannelListener> {
         debug!(
@@ -320,10 +729,9 @@ impl NodeManager {
         let options =
             SecureChannelListenerOptions::new().as_consumer(&self.api_transport_flow_control_id);
 
-        let options = match authorized_identifiers {
-            Some(ids) => options.with_trust_policy(TrustMultiIdentifiersPolicy::new(ids)),
-            None => options.with_trust_policy(TrustEveryonePolicy),
-        };
+        // The allowlist is kept in the registry so that it can be updated later on
+        let allowlist = TrustAllowlistPolicy::new(authorized_identifiers);
+        let options = options.with_trust_policy(allowlist.clone());
 
         let options = match self.project_authority() {
             Some(project_authority) => options.with_authority(project_authority),
@@ -337,6 +745,15 @@ impl NodeManager {
             }
         };
 
+        let options = match max_handshakes_per_second {
+            Some(max_handshakes_per_second) => {
+                options.with_max_handshakes_per_second(max_handshakes_per_second)
+            }
+            None => options,
+        };
+        // The rate limiter is kept in the registry to show its utilization
+        let handshake_rate_limiter = options.handshake_rate_limiter();
+
         let listener = secure_channels
             .create_secure_channel_listener(ctx, &identifier, address.clone(), options)
             .await?;
@@ -345,7 +762,12 @@ impl NodeManager {
             .secure_channel_listeners
             .insert(
                 address.clone(),
-                SecureChannelListenerInfo::new(listener.clone()),
+                SecureChannelListenerInfo::new(
+                    listener.clone(),
+                    identifier.clone(),
+                    allowlist,
+                    handshake_rate_limiter,
+                ),
             )
             .await;
 
@@ -354,6 +776,9 @@ impl NodeManager {
         ctx.flow_controls()
             .add_consumer(DefaultAddress::ECHO_SERVICE, listener.flow_control_id());
 
+        ctx.flow_controls()
+            .add_consumer(DefaultAddress::TRACE_SERVICE, listener.flow_control_id());
+
         ctx.flow_controls().add_consumer(
             DefaultAddress::UPPERCASE_SERVICE,
             listener.flow_control_id(),
@@ -396,6 +821,37 @@ impl NodeManager {
             ))
     }
 
+    /// Update the identifiers allowed to initiate a secure channel with a listener.
+    ///
+    /// The changes are enforced for the next handshakes, the secure channels which
+    /// were already established with the listener are left untouched.
+    pub async fn update_secure_channel_listener(
+        &self,
+        addr: &Address,
+        set: Option<Vec<Identifier>>,
+        add: Vec<Identifier>,
+        remove: Vec<Identifier>,
+    ) -> Result<SecureChannelListenerInfo> {
+        debug!(%addr, "On update secure channel listener");
+        let info = self.get_secure_channel_listener(addr).await?;
+        let allowlist = info.allowlist();
+        if let Some(set) = set {
+            allowlist.set(Some(set));
+        }
+        for identifier in add {
+            allowlist.add(identifier);
+        }
+        for identifier in remove.iter() {
+            allowlist.remove(identifier);
+        }
+        info!(
+            %addr,
+            authorized_identifiers = ?allowlist.identity_ids(),
+            "Updated the authorized identifiers of the secure channel listener"
+        );
+        Ok(info)
+    }
+
     pub async fn list_secure_channel_listeners(&self) -> Vec<SecureChannelListenerInfo> {
         let registry = &self.registry.secure_channel_listeners;
         registry.values().await
@@ -3,9 +3,9 @@ use std::net::SocketAddr;
 use ockam::Result;
 use ockam_core::api::{Error, RequestHeader, Response};
 use ockam_node::Context;
-use ockam_transport_tcp::{TcpConnectionOptions, TcpListenerOptions};
 
 use super::{NodeManager, NodeManagerWorker};
+use crate::nodes::models::services::BuiltinService;
 use crate::nodes::models::transport::{
     CreateTcpConnection, CreateTcpListener, DeleteTransport, TransportList, TransportStatus,
 };
@@ -38,6 +38,18 @@ impl NodeManager {
         )
     }
 
+    fn get_udp_listeners(&self) -> TransportList {
+        TransportList::new(
+            self.udp_transport
+                .as_ref()
+                .map(|udp_transport| udp_transport.listeners())
+                .unwrap_or_default()
+                .into_iter()
+                .map(TransportStatus::from)
+                .collect(),
+        )
+    }
+
     fn get_tcp_listener(&self, address: String) -> Option<TransportStatus> {
         let listener = self.tcp_transport().find_listener(address.to_string())?;
         Some(listener.into())
@@ -48,11 +60,16 @@ impl NodeManager {
         address: String,
         ctx: &Context,
     ) -> Result<TransportStatus> {
-        let options = TcpConnectionOptions::new();
+        let options = self.tcp_connection_options();
 
         // Add all Hop workers as consumers for Demo purposes
         // Production nodes should not run any Hop workers
-        for hop in self.registry.hop_services.keys().await {
+        for hop in self
+            .registry
+            .services
+            .addresses_of(BuiltinService::Hop)
+            .await
+        {
             ctx.flow_controls()
                 .add_consumer(hop.clone(), &options.flow_control_id());
         }
@@ -61,8 +78,16 @@ impl NodeManager {
         Ok(connection.into())
     }
 
-    async fn create_tcp_listener(&self, address: String) -> Result<TransportStatus> {
-        let options = TcpListenerOptions::new();
+    async fn create_tcp_listener(
+        &self,
+        address: String,
+        max_message_size: Option<usize>,
+    ) -> Result<TransportStatus> {
+        let options = self.tcp_listener_options();
+        let options = match max_message_size {
+            Some(max_message_size) => options.with_max_message_size(max_message_size),
+            None => options,
+        };
         let listener = self.tcp_transport.listen(address, options).await?;
         Ok(listener.into())
     }
@@ -130,6 +155,12 @@ impl NodeManagerWorker {
             .body(self.node_manager.get_tcp_listeners())
     }
 
+    pub(super) async fn get_udp_listeners(&self, req: &RequestHeader) -> Response<TransportList> {
+        Response::ok()
+            .with_headers(req)
+            .body(self.node_manager.get_udp_listeners())
+    }
+
     pub(super) async fn get_tcp_listener(
         &self,
         address: String,
@@ -164,11 +195,14 @@ impl NodeManagerWorker {
         &self,
         create: CreateTcpListener,
     ) -> Result<Response<TransportStatus>, Response<Error>> {
-        let CreateTcpListener { addr, .. } = create;
+        let CreateTcpListener {
+            addr,
+            max_message_size,
+        } = create;
         info!("Handling request to create a new tcp listener: {addr}");
 
         self.node_manager
-            .create_tcp_listener(addr.to_string())
+            .create_tcp_listener(addr.to_string(), max_message_size.map(|s| s as usize))
             .await
             .map(|status| Response::ok().body(status))
             .map_err(|msg| {
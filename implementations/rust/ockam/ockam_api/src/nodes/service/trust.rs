@@ -1,6 +1,15 @@
+use crate::nodes::models::trust::{
+    CredentialRetrieval, HeldCredential, TrustContextStatus, TrustProject,
+};
+use crate::nodes::{NodeManager, NodeManagerWorker};
 use ockam::identity::models::CredentialAndPurposeKey;
-use ockam::identity::{CredentialRetrieverCreator, Identifier, RemoteCredentialRetrieverInfo};
+use ockam::identity::utils::now;
+use ockam::identity::{
+    CredentialRetrieverCreator, Identifier, RemoteCredentialRetrieverInfo, TimestampInSeconds,
+};
+use ockam_core::api::{Error, Response};
 use ockam_core::errcode::{Kind, Origin};
+use ockam_multiaddr::MultiAddr;
 use std::fmt::Display;
 use std::str::FromStr;
 use std::sync::Arc;
@@ -84,11 +93,33 @@ pub enum NodeManagerCredentialRetrieverOptions {
     InMemory(CredentialAndPurposeKey),
 }
 
+impl NodeManagerCredentialRetrieverOptions {
+    /// Return how the credential is retrieved, and its scope if it is stored
+    fn retrieval(&self) -> (CredentialRetrieval, Option<String>) {
+        match self {
+            NodeManagerCredentialRetrieverOptions::None => (CredentialRetrieval::None, None),
+            NodeManagerCredentialRetrieverOptions::CacheOnly { scope, .. } => {
+                (CredentialRetrieval::Cache, Some(scope.clone()))
+            }
+            NodeManagerCredentialRetrieverOptions::Remote { scope, .. } => {
+                (CredentialRetrieval::Authority, Some(scope.clone()))
+            }
+            NodeManagerCredentialRetrieverOptions::InMemory(_) => {
+                (CredentialRetrieval::InMemory, None)
+            }
+        }
+    }
+}
+
 pub struct NodeManagerTrustOptions {
     pub(super) project_member_credential_retriever_options: NodeManagerCredentialRetrieverOptions,
     pub(super) project_authority: Option<Identifier>,
     pub(super) project_admin_credential_retriever_options: NodeManagerCredentialRetrieverOptions,
     pub(super) _account_admin_credential_retriever_options: NodeManagerCredentialRetrieverOptions,
+    /// Address of the authority node, as it was configured
+    pub(super) authority_route: Option<MultiAddr>,
+    /// Project the authority comes from, when it was not given explicitly
+    pub(super) project: Option<TrustProject>,
 }
 
 impl NodeManagerTrustOptions {
@@ -103,6 +134,90 @@ impl NodeManagerTrustOptions {
             project_admin_credential_retriever_options,
             project_authority,
             _account_admin_credential_retriever_options: account_admin_credential_retriever_options,
+            authority_route: None,
+            project: None,
+        }
+    }
+
+    pub fn with_authority_route(mut self, authority_route: Option<MultiAddr>) -> Self {
+        self.authority_route = authority_route;
+        self
+    }
+
+    pub fn with_project(mut self, name: impl Into<String>, id: impl Into<String>) -> Self {
+        self.project = Some(TrustProject {
+            name: name.into(),
+            id: id.into(),
+        });
+        self
+    }
+
+    /// Return the trust configured with these options.
+    /// Only the credential given when the node starts is known at this point
+    pub(super) fn trust_context(&self) -> TrustContextStatus {
+        let (retrieval, scope) = self.project_member_credential_retriever_options.retrieval();
+        let credential = match (
+            &self.project_member_credential_retriever_options,
+            &self.project_authority,
+        ) {
+            (NodeManagerCredentialRetrieverOptions::InMemory(credential), Some(issuer)) => {
+                credential.get_credential_data().ok().map(|data| {
+                    HeldCredential::new(issuer.clone(), data.expires_at, TimestampInSeconds(0))
+                })
+            }
+            _ => None,
+        };
+        TrustContextStatus::new(self.project_authority.clone(), retrieval)
+            .with_authority_route(self.authority_route.as_ref().map(|r| r.to_string()))
+            .with_credential_scope(scope)
+            .with_project(self.project.clone())
+            .with_credential(credential)
+    }
+}
+
+impl NodeManagerWorker {
+    pub(super) async fn get_trust_context(
+        &self,
+    ) -> Result<Response<TrustContextStatus>, Response<Error>> {
+        match self.node_manager.trust_context().await {
+            Ok(trust_context) => Ok(Response::ok().body(trust_context)),
+            Err(e) => Err(Response::internal_error_no_request(&e.to_string())),
         }
     }
 }
+
+impl NodeManager {
+    /// Return the trust of the node, with the validity of the credential it currently holds.
+    /// A credential retrieved from the authority is only known once it was stored by the node
+    pub async fn trust_context(&self) -> ockam_core::Result<TrustContextStatus> {
+        let mut trust_context = self.trust_context.clone();
+        let now = now()?;
+        trust_context.credential = match trust_context.credential_retrieval {
+            CredentialRetrieval::None => None,
+            CredentialRetrieval::InMemory => trust_context.credential.map(|credential| {
+                HeldCredential::new(
+                    credential.issuer,
+                    TimestampInSeconds(credential.expires_at),
+                    now,
+                )
+            }),
+            CredentialRetrieval::Cache | CredentialRetrieval::Authority => {
+                match (
+                    &trust_context.authority_identifier,
+                    &trust_context.credential_scope,
+                ) {
+                    (Some(issuer), Some(scope)) => self
+                        .secure_channels
+                        .identities()
+                        .cached_credentials_repository()
+                        .get(&self.node_identifier, issuer, scope)
+                        .await?
+                        .and_then(|credential| credential.get_credential_data().ok())
+                        .map(|data| HeldCredential::new(issuer.clone(), data.expires_at, now)),
+                    _ => None,
+                }
+            }
+        };
+        Ok(trust_context)
+    }
+}
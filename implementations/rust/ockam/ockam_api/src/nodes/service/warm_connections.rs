@@ -0,0 +1,188 @@
+use std::sync::Arc;
+
+use tokio::time::timeout;
+
+use ockam::Result;
+use ockam_core::api::{RequestHeader, Response};
+use ockam_core::{async_trait, AsyncTryClone};
+use ockam_multiaddr::MultiAddr;
+use ockam_node::Context;
+
+use crate::error::ApiError;
+use crate::nodes::connection::Connection;
+use crate::nodes::models::connection::{
+    WarmConnectionList, WarmConnectionState, WarmConnectionStatus,
+};
+use crate::nodes::registry::WarmConnectionInfo;
+use crate::session::sessions::{
+    ConnectionStatus, ReplacerOutcome, ReplacerOutputKind, Session, SessionReplacer,
+    MAX_CONNECT_TIME, MAX_RECOVERY_TIME,
+};
+use crate::session::MedicHandle;
+
+use super::{NodeManager, NodeManagerWorker};
+
+impl NodeManagerWorker {
+    pub(super) async fn get_warm_connections(
+        &self,
+        req: &RequestHeader,
+    ) -> Response<WarmConnectionList> {
+        Response::ok()
+            .with_headers(req)
+            .body(self.node_manager.list_warm_connections().await)
+    }
+}
+
+impl NodeManager {
+    /// Establish a connection to each address in the background, so that the services
+    /// created later with one of these addresses as a prefix don't have to set it up.
+    ///
+    /// Connections which cannot be established are retried by the medic.
+    pub async fn warm_connections(
+        self: &Arc<Self>,
+        ctx: &Context,
+        addrs: Vec<MultiAddr>,
+    ) -> Result<()> {
+        for addr in addrs {
+            let key = addr.to_string();
+            if self.registry.warm_connections.contains_key(&key).await {
+                continue;
+            }
+
+            let replacer = WarmConnectionSessionReplacer {
+                node_manager: self.clone(),
+                context: Arc::new(ctx.async_try_clone().await?),
+                addr: addr.clone(),
+                connection: None,
+            };
+            let mut session = Session::new(replacer);
+            // the first attempt is made right away, so the medic must
+            // not start another one until it completes
+            session.degraded();
+            self.registry
+                .warm_connections
+                .insert(key, WarmConnectionInfo::new(addr.clone(), session.clone()))
+                .await;
+
+            tokio::spawn(async move {
+                if let Err(err) = MedicHandle::connect(&mut session).await {
+                    warn!(%addr, %err, "failed to warm the connection");
+                    session.failed(err.to_string());
+                }
+            });
+        }
+        Ok(())
+    }
+
+    pub async fn list_warm_connections(&self) -> WarmConnectionList {
+        WarmConnectionList::new(
+            self.registry
+                .warm_connections
+                .values()
+                .await
+                .iter()
+                .map(|info| {
+                    let session = &info.session;
+                    let last_failure = session.last_failure();
+                    let state = match (session.connection_status(), &last_failure) {
+                        (ConnectionStatus::Up, _) => WarmConnectionState::Warmed,
+                        (_, Some(_)) => WarmConnectionState::Failed,
+                        (_, None) => WarmConnectionState::Pending,
+                    };
+                    let normalized_addr = session.status().and_then(|outcome| match outcome.kind {
+                        ReplacerOutputKind::Connection(connection) => {
+                            Some(connection.normalized_addr.to_string())
+                        }
+                        _ => None,
+                    });
+                    WarmConnectionStatus {
+                        addr: info.addr.to_string(),
+                        state,
+                        normalized_addr,
+                        attempts: session.replacement_attempts(),
+                        last_failure,
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    /// Return a warmed connection whose address is a prefix of `addr`,
+    /// together with the rest of `addr`
+    pub(crate) async fn find_warm_connection(
+        &self,
+        addr: &MultiAddr,
+    ) -> Option<(Connection, MultiAddr)> {
+        for info in self.registry.warm_connections.values().await {
+            if info.addr.len() > addr.len()
+                || info.session.connection_status() != ConnectionStatus::Up
+            {
+                continue;
+            }
+            let (prefix, rest) = addr.split(info.addr.len());
+            if prefix != info.addr {
+                continue;
+            }
+            if let Some(ReplacerOutcome {
+                kind: ReplacerOutputKind::Connection(connection),
+                ..
+            }) = info.session.status()
+            {
+                return Some((connection, rest));
+            }
+        }
+        None
+    }
+}
+
+struct WarmConnectionSessionReplacer {
+    node_manager: Arc<NodeManager>,
+    context: Arc<Context>,
+    addr: MultiAddr,
+
+    // current status
+    connection: Option<Connection>,
+}
+
+#[async_trait]
+impl SessionReplacer for WarmConnectionSessionReplacer {
+    async fn create(&mut self) -> std::result::Result<ReplacerOutcome, ockam_core::Error> {
+        self.close().await;
+        debug!(%self.addr, "warming connection");
+
+        let future = self.node_manager.make_connection(
+            self.context.clone(),
+            &self.addr,
+            self.node_manager.identifier(),
+            None,
+            Some(MAX_CONNECT_TIME),
+            None,
+        );
+        let connection = match timeout(MAX_RECOVERY_TIME, future).await {
+            Err(_) => {
+                warn!(%self.addr, "timeout warming connection");
+                return Err(ApiError::core("timeout"));
+            }
+            Ok(Err(e)) => {
+                warn!(%self.addr, err = %e, "error warming connection");
+                return Err(e);
+            }
+            Ok(Ok(connection)) => connection,
+        };
+        self.connection = Some(connection.clone());
+
+        Ok(ReplacerOutcome {
+            ping_route: connection.transport_route(),
+            kind: ReplacerOutputKind::Connection(connection),
+        })
+    }
+
+    async fn close(&mut self) {
+        if let Some(connection) = self.connection.take() {
+            let result = connection.close(&self.context, &self.node_manager).await;
+            if let Err(err) = result {
+                error!(?err, "Failed to close connection");
+            }
+        }
+    }
+}
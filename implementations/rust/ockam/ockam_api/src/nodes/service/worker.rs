@@ -58,6 +58,9 @@ impl NodeManagerWorker {
             // ==*== Basic node information ==*==
             // TODO: create, delete, destroy remote nodes
             (Get, ["node"]) => encode_response(req, self.get_node_status(ctx).await)?,
+            (Put, ["node", "log_level"]) => {
+                encode_response(req, self.set_log_level(dec.decode()?).await)?
+            }
 
             // ==*== Tcp Connection ==*==
             (Get, ["node", "tcp", "connection"]) => self.get_tcp_connections(req).await.to_vec()?,
@@ -73,6 +76,7 @@ impl NodeManagerWorker {
 
             // ==*== Tcp Listeners ==*==
             (Get, ["node", "tcp", "listener"]) => self.get_tcp_listeners(req).await.to_vec()?,
+            (Get, ["node", "udp", "listener"]) => self.get_udp_listeners(req).await.to_vec()?,
             (Get, ["node", "tcp", "listener", address]) => {
                 encode_response(req, self.get_tcp_listener(address.to_string()).await)?
             }
@@ -83,6 +87,12 @@ impl NodeManagerWorker {
                 encode_response(req, self.delete_tcp_listener(dec.decode()?).await)?
             }
 
+            // ==*== Trust ==*==
+            (Get, ["node", "trust"]) => encode_response(req, self.get_trust_context().await)?,
+
+            // ==*== Connections established when the node starts ==*==
+            (Get, ["node", "warm_connection"]) => self.get_warm_connections(req).await.to_vec()?,
+
             // ==*== Secure channels ==*==
             (Get, ["node", "secure_channel"]) => {
                 encode_response(req, self.list_secure_channels().await)?
@@ -93,9 +103,15 @@ impl NodeManagerWorker {
             (Post, ["node", "secure_channel"]) => {
                 encode_response(req, self.create_secure_channel(dec.decode()?, ctx).await)?
             }
+            (Delete, ["node", "attributes"]) => {
+                encode_response(req, self.flush_attributes(dec.decode()?).await)?
+            }
             (Delete, ["node", "secure_channel"]) => {
                 encode_response(req, self.delete_secure_channel(dec.decode()?, ctx).await)?
             }
+            (Delete, ["node", "secure_channel", name]) => {
+                encode_response(req, self.delete_named_secure_channel(ctx, name).await)?
+            }
             (Get, ["node", "show_secure_channel"]) => {
                 encode_response(req, self.show_secure_channel(dec.decode()?).await)?
             }
@@ -112,6 +128,10 @@ impl NodeManagerWorker {
             (Get, ["node", "show_secure_channel_listener"]) => {
                 encode_response(req, self.show_secure_channel_listener(dec.decode()?).await)?
             }
+            (Put, ["node", "secure_channel_listener"]) => encode_response(
+                req,
+                self.update_secure_channel_listener(dec.decode()?).await,
+            )?,
 
             // ==*== Services ==*==
             (Post, ["node", "services", DefaultAddress::UPPERCASE_SERVICE]) => {
@@ -123,6 +143,9 @@ impl NodeManagerWorker {
             (Post, ["node", "services", DefaultAddress::HOP_SERVICE]) => {
                 encode_response(req, self.start_hop_service(ctx, dec.decode()?).await)?
             }
+            (Post, ["node", "services", DefaultAddress::BENCH_SERVICE]) => {
+                encode_response(req, self.start_bench_service(ctx, dec.decode()?).await)?
+            }
             (Post, ["node", "services", DefaultAddress::KAFKA_OUTLET]) => encode_response(
                 req,
                 self.start_kafka_outlet_service(ctx, dec.decode()?).await,
@@ -159,6 +182,15 @@ impl NodeManagerWorker {
                 self.delete_kafka_service(ctx, dec.decode()?, KafkaServiceKind::Direct)
                     .await,
             )?,
+            (Put, ["node", "services", "kafka_allowed_topics"]) => {
+                encode_response(req, self.update_kafka_allowed_topics(dec.decode()?).await)?
+            }
+            (Post, ["node", "services"]) => {
+                encode_response(req, self.start_builtin_service(ctx, dec.decode()?).await)?
+            }
+            (Delete, ["node", "services"]) => {
+                encode_response(req, self.stop_builtin_service(ctx, dec.decode()?).await)?
+            }
             (Get, ["node", "services"]) => encode_response(req, self.list_services().await)?,
             (Get, ["node", "services", service_type]) => {
                 encode_response(req, self.list_services_of_type(service_type).await)?
@@ -179,6 +211,9 @@ impl NodeManagerWorker {
             // ==*== Inlets & Outlets ==*==
             (Get, ["node", "inlet"]) => encode_response(req, self.get_inlets().await)?,
             (Get, ["node", "inlet", alias]) => encode_response(req, self.show_inlet(alias).await)?,
+            (Get, ["node", "inlet", alias, "connections"]) => {
+                encode_response(req, self.list_inlet_connections(alias).await)?
+            }
             (Get, ["node", "outlet"]) => self.get_outlets(req).await.to_vec()?,
             (Get, ["node", "outlet", addr]) => {
                 let addr: Address = addr.to_string().into();
@@ -190,6 +225,16 @@ impl NodeManagerWorker {
             (Post, ["node", "outlet"]) => {
                 encode_response(req, self.create_outlet(ctx, dec.decode()?).await)?
             }
+            (Put, ["node", "inlet", alias]) => {
+                encode_response(req, self.rename_inlet(req, alias, dec.decode()?).await)?
+            }
+            (Put, ["node", "outlet", addr]) => {
+                let addr: Address = addr.to_string().into();
+                encode_response(
+                    req,
+                    self.rename_outlet(ctx, req, &addr, dec.decode()?).await,
+                )?
+            }
             (Delete, ["node", "outlet", addr]) => {
                 let addr: Address = addr.to_string().into();
                 encode_response(req, self.delete_outlet(&addr).await)?
@@ -197,6 +242,9 @@ impl NodeManagerWorker {
             (Delete, ["node", "inlet", alias]) => {
                 encode_response(req, self.delete_inlet(alias).await)?
             }
+            (Delete, ["node", "inlet", alias, "connections", connection_id]) => {
+                encode_response(req, self.close_inlet_connection(alias, connection_id).await)?
+            }
             (Delete, ["node", "portal"]) => todo!(),
 
             // ==*== Flow Controls ==*==
@@ -206,6 +254,9 @@ impl NodeManagerWorker {
 
             // ==*== Workers ==*==
             (Get, ["node", "workers"]) => encode_response(req, self.list_workers(ctx).await)?,
+            (Post, ["node", "workers", "send"]) => {
+                encode_response(req, self.send_worker_message(ctx, req, dec.decode()?).await)?
+            }
 
             // ==*== Policies ==*==
             (Post, ["policy", action]) => {
@@ -1,25 +1,83 @@
-use crate::nodes::models::workers::{WorkerList, WorkerStatus};
+use crate::nodes::models::workers::{
+    SendWorkerMessage, SendWorkerMessageReply, WorkerList, WorkerStatus,
+};
 use crate::nodes::NodeManagerWorker;
-use ockam_core::api::{Error, Response};
-use ockam_core::Result;
-use ockam_node::Context;
+use core::time::Duration;
+use ockam_core::api::{Error, RequestHeader, Response};
+use ockam_core::errcode::Kind;
+use ockam_core::{route, Address, NeutralMessage, Result};
+use ockam_node::{Context, MessageSendReceiveOptions};
 
 impl NodeManagerWorker {
-    /// Return the current list of workers
+    /// Return the current list of workers.
+    /// The details of each worker are only returned when the debug API is enabled
     pub async fn list_workers(
         &self,
         ctx: &Context,
     ) -> Result<Response<WorkerList>, Response<Error>> {
-        let workers = match ctx.list_workers().await {
-            Err(e) => Err(Response::internal_error_no_request(&e.to_string())),
-            Ok(workers) => Ok(workers),
-        }?;
-
-        let list = workers
-            .into_iter()
-            .map(|addr| WorkerStatus::new(addr.address()))
-            .collect();
+        let list = if self.node_manager.debug_api {
+            ctx.list_workers_info()
+                .await
+                .map(|workers| workers.into_iter().map(WorkerStatus::from).collect())
+        } else {
+            ctx.list_workers().await.map(|workers| {
+                workers
+                    .into_iter()
+                    .map(|addr| WorkerStatus::new(addr.address()))
+                    .collect()
+            })
+        }
+        .map_err(|e| Response::internal_error_no_request(&e.to_string()))?;
 
         Ok(Response::ok().body(WorkerList::new(list)))
     }
+
+    /// Send a raw message to a worker of this node and return its reply, if any.
+    /// This is only available when the debug API is enabled
+    pub async fn send_worker_message(
+        &self,
+        ctx: &Context,
+        req: &RequestHeader,
+        request: SendWorkerMessage,
+    ) -> Result<Response<SendWorkerMessageReply>, Response<Error>> {
+        if !self.node_manager.debug_api {
+            return Err(Response::forbidden(
+                req,
+                "The debug API is disabled on this node. Create the node with --debug-api to enable it",
+            ));
+        }
+        let to = Address::from_string(&request.to);
+        if !to.is_local() {
+            return Err(Response::bad_request(
+                req,
+                &format!("{} is not the address of a local worker", request.to),
+            ));
+        }
+
+        debug!(to = %to, length = request.payload.len(), "sending a message to a worker");
+        let options =
+            MessageSendReceiveOptions::new().with_timeout(Duration::from_millis(request.timeout));
+        match ctx
+            .send_and_receive_extended::<NeutralMessage>(
+                route![to],
+                NeutralMessage::from(request.payload),
+                options,
+            )
+            .await
+        {
+            Ok(reply) => {
+                let reply = reply
+                    .into_body()
+                    .map_err(|e| Response::internal_error(req, &e.to_string()))?;
+                Ok(Response::ok().body(SendWorkerMessageReply::new(Some(reply.into_vec()))))
+            }
+            Err(e) if e.code().kind == Kind::Timeout => {
+                Ok(Response::ok().body(SendWorkerMessageReply::new(None)))
+            }
+            Err(e) => Err(Response::bad_request(
+                req,
+                &format!("The message could not be sent to {}: {e}", request.to),
+            )),
+        }
+    }
 }
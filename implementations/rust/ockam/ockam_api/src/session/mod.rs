@@ -2,6 +2,7 @@ use minicbor::{Decode, Encode};
 use tokio::task::JoinHandle;
 use tracing as log;
 
+use crate::nodes::models::relay::MAX_FAILED_HEARTBEATS;
 use crate::nodes::registry::Registry;
 use ockam::{LocalMessage, Worker};
 use ockam_core::compat::sync::Arc;
@@ -15,13 +16,17 @@ use ockam_node::Context;
 use ockam_node::{tokio, WorkerBuilder};
 
 use crate::nodes::service::default_address::DefaultAddress;
-use crate::session::sessions::{ConnectionStatus, Ping, ReplacerOutcome, Session};
+use crate::session::sessions::{
+    ConnectionStatus, Ping, ReplacerOutcome, Session, DEFAULT_MAX_MISSED_PROBES,
+    DEFAULT_PROBE_INTERVAL,
+};
 
 pub(crate) mod sessions;
 
-const MAX_FAILURES: usize = 3;
+const MAX_FAILURES: u32 = DEFAULT_MAX_MISSED_PROBES;
 const RETRY_DELAY: Duration = Duration::from_secs(5);
-const PING_INTERVAL: Duration = Duration::from_secs(10);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+const PING_INTERVAL: Duration = DEFAULT_PROBE_INTERVAL;
 
 pub struct Medic {
     retry_delay: Duration,
@@ -83,15 +88,28 @@ impl Medic {
         let ctx = Arc::new(ctx);
         loop {
             log::trace!("check sessions");
+            let mut check_interval = self.ping_interval;
             // explicitly scoping the lock to release it before the sleep
             {
                 let sessions = self.sessions().await;
+                let unresponsive_relays = self.unresponsive_relays().await;
 
                 for session in sessions {
                     let key = session.key().to_string();
-                    if session.pings().len() < MAX_FAILURES
+                    // sessions with their own liveness settings are probed at their own pace
+                    let (ping_interval, max_failures) = match session.liveness() {
+                        Some(liveness) => (liveness.probe_interval(), liveness.max_missed_probes()),
+                        None => (self.ping_interval, MAX_FAILURES),
+                    };
+                    check_interval = check_interval.min(ping_interval);
+                    if (session.pings().len() as u32) < max_failures
+                        && !session.replacement_requested()
                         && session.connection_status() == ConnectionStatus::Up
+                        && !unresponsive_relays.contains(&key)
                     {
+                        if !session.ping_due(ping_interval) {
+                            continue;
+                        }
                         let message = Message::new(session.key().to_string());
                         let ping = message.ping;
                         session.add_ping(ping);
@@ -144,11 +162,15 @@ impl Medic {
                         match session.connection_status() {
                             ConnectionStatus::Up | ConnectionStatus::Down => {
                                 log::warn!(%key, "session unresponsive");
-                                session.degraded();
+                                let transition = session.degraded();
                                 let replacer = session.replacer();
-                                log::info!(%key, "replacing session");
-                                let retry_delay = self.retry_delay;
+                                let attempts = session.replacement_started();
+                                let retry_delay = self.retry_delay(attempts);
+                                log::info!(%key, %attempts, ?retry_delay, "replacing session");
                                 self.replacements.spawn(async move {
+                                    if let Some(transition) = transition {
+                                        replacer.transitioned(transition).await;
+                                    }
                                     sleep(retry_delay).await;
                                     (key, replacer.recreate().await)
                                 });
@@ -161,7 +183,7 @@ impl Medic {
                 }
             }
 
-            let _ = timeout(self.ping_interval, self.get_results(&mut ping_receiver)).await;
+            let _ = timeout(check_interval, self.get_results(&mut ping_receiver)).await;
         }
     }
 
@@ -172,7 +194,33 @@ impl Medic {
         let relay_values = self.registry.relays.values().await;
         let relays = relay_values.iter().map(|info| info.session.clone());
 
-        inlets.chain(relays).collect()
+        let connection_values = self.registry.warm_connections.values().await;
+        let connections = connection_values.iter().map(|info| info.session.clone());
+
+        inlets.chain(relays).chain(connections).collect()
+    }
+
+    /// Keys of the relay sessions which are connected but whose relay
+    /// doesn't forward the heartbeats anymore, for example because it
+    /// was removed from the remote node
+    async fn unresponsive_relays(&self) -> Vec<String> {
+        self.registry
+            .relays
+            .values()
+            .await
+            .iter()
+            .filter(|info| info.heartbeats.status().consecutive_failures() >= MAX_FAILED_HEARTBEATS)
+            .map(|info| info.session.key().to_string())
+            .collect()
+    }
+
+    /// Delay before the given replacement attempt, doubling after each failed
+    /// attempt up to [`MAX_RETRY_DELAY`]
+    fn retry_delay(&self, attempts: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempts.saturating_sub(1));
+        self.retry_delay
+            .saturating_mul(factor)
+            .min(MAX_RETRY_DELAY.max(self.retry_delay))
     }
 
     async fn session(&self, key: &str) -> Option<Session> {
@@ -188,10 +236,19 @@ impl Medic {
             return Some(info.session.clone());
         }
 
+        let connection_values = self.registry.warm_connections.values().await;
+        let connections = connection_values
+            .iter()
+            .find(|info| info.session.key() == key);
+        if let Some(info) = connections {
+            return Some(info.session.clone());
+        }
+
         None
     }
 
     async fn get_results(&mut self, ping_receiver: &mut mpsc::Receiver<Message>) {
+        let registry = self.registry.clone();
         loop {
             tokio::select! {
                 p = self.pings.join_next(), if !self.pings.is_empty() => match p {
@@ -206,17 +263,28 @@ impl Medic {
                     Some(Ok((key, Err(err)))) => {
                         log::warn!(key = %key, err = %err, "replacing session failed");
                         if let Some(session) = self.session(&key).await {
-                           session.down();
+                            if let Some(transition) = session.failed(err.to_string()) {
+                                session.replacer().transitioned(transition).await;
+                            }
                         }
                     }
                     Some(Ok((key, Ok(replacer_outcome)))) => {
                         if let Some(session) = self.session(&key).await {
-                            log::info!(key = %key, ping_route = %replacer_outcome.ping_route, "replacement is up");
+                            let attempts = session.replacement_attempts();
+                            log::info!(key = %key, ping_route = %replacer_outcome.ping_route, %attempts, "replacement is up");
                             session.clear_pings();
-                            session.up(replacer_outcome);
+                            let transition = session.up(replacer_outcome);
+                            session.replacer().recovered(attempts).await;
+                            if let Some(transition) = transition {
+                                session.replacer().transitioned(transition).await;
+                            }
                         }
                     }
                 },
+                _ = registry.session_replacements.notified() => {
+                    log::debug!("session replacement requested");
+                    break
+                },
                 Some(message) = ping_receiver.recv() => {
                     log::trace!("received pong");
                     if let Some(session) = self.session(&message.key).await {
@@ -326,12 +394,15 @@ mod tests {
     use ockam_core::compat::sync::Arc;
     use ockam_core::{async_trait, AsyncTryClone, Error, Result};
     use ockam_multiaddr::MultiAddr;
+    use ockam_transport_tcp::TcpInletConnectionLimits;
 
     use crate::echoer::Echoer;
     use crate::hop::Hop;
+    use crate::nodes::models::relay::MAX_FAILED_HEARTBEATS;
     use crate::nodes::registry::Registry;
     use crate::session::sessions::{ConnectionStatus, ReplacerOutcome, SessionReplacer};
     use crate::session::sessions::{CurrentInletStatus, ReplacerOutputKind, Session};
+    use crate::session::sessions::{Ping, SessionLiveness, DEFAULT_PROBE_INTERVAL};
     use crate::session::Medic;
 
     #[derive(Clone)]
@@ -362,6 +433,7 @@ mod tests {
                     route: route!["hop"],
                     worker: Address::from_string("echo"),
                     connection_status: ConnectionStatus::Up,
+                    load_balancer: None,
                 }),
             })
         }
@@ -406,6 +478,7 @@ mod tests {
                 route: route!["broken_route"],
                 worker: Address::from_string("mock-address"),
                 connection_status: ConnectionStatus::Up,
+                load_balancer: None,
             }),
         });
 
@@ -416,11 +489,13 @@ mod tests {
             .inlets
             .insert(
                 "inlet-1".into(),
-                crate::nodes::registry::InletInfo {
-                    bind_addr: "127.0.0.1:10000".to_string(),
-                    outlet_addr: MultiAddr::default(),
-                    session: session.clone(),
-                },
+                crate::nodes::registry::InletInfo::new(
+                    "127.0.0.1:10000",
+                    MultiAddr::default(),
+                    session.clone(),
+                    TcpInletConnectionLimits::new(),
+                    false,
+                ),
             )
             .await;
 
@@ -452,4 +527,128 @@ mod tests {
         medic_task.abort();
         ctx.stop().await
     }
+
+    #[test]
+    fn test_retry_delay_backoff() {
+        let medic = Medic::new_extended(
+            Arc::new(Registry::default()),
+            Duration::from_secs(5),
+            Duration::from_secs(1),
+        );
+
+        assert_eq!(medic.retry_delay(1), Duration::from_secs(5));
+        assert_eq!(medic.retry_delay(2), Duration::from_secs(10));
+        assert_eq!(medic.retry_delay(3), Duration::from_secs(20));
+        assert_eq!(medic.retry_delay(5), Duration::from_secs(60));
+        assert_eq!(medic.retry_delay(100), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_replacement_attempts_are_reset_when_up() {
+        let session = Session::new(MockReplacer::new());
+        assert_eq!(session.replacement_started(), 1);
+        session.failed("connection refused".to_string());
+        assert_eq!(session.replacement_started(), 2);
+        assert_eq!(session.replacement_attempts(), 2);
+        assert_eq!(session.connection_status(), ConnectionStatus::Down);
+
+        session.up(ReplacerOutcome {
+            ping_route: route!["hop"],
+            kind: ReplacerOutputKind::Inlet(CurrentInletStatus {
+                route: route!["hop"],
+                worker: Address::from_string("echo"),
+                connection_status: ConnectionStatus::Up,
+                load_balancer: None,
+            }),
+        });
+        assert_eq!(session.replacement_attempts(), 0);
+        assert_eq!(
+            session.last_failure().as_deref(),
+            Some("connection refused")
+        );
+    }
+
+    #[test]
+    fn test_connection_transitions() {
+        let session = Session::new(MockReplacer::new());
+        let outcome = ReplacerOutcome {
+            ping_route: route!["hop"],
+            kind: ReplacerOutputKind::Inlet(CurrentInletStatus {
+                route: route!["hop"],
+                worker: Address::from_string("echo"),
+                connection_status: ConnectionStatus::Up,
+                load_balancer: None,
+            }),
+        };
+
+        // the session is created, then the outlet becomes unreachable
+        assert!(session.up(outcome.clone()).is_some());
+        assert!(session.degraded().is_some());
+        session.replacement_started();
+        let failed = session.failed("connection refused".to_string()).unwrap();
+        assert_eq!(failed.from, ConnectionStatus::Degraded);
+        assert_eq!(failed.to, ConnectionStatus::Down);
+        assert_eq!(failed.reason.as_deref(), Some("connection refused"));
+        assert_eq!(failed.attempts, 1);
+
+        // successive failed replacements are only recorded once
+        assert!(session.degraded().is_none());
+        session.replacement_started();
+        assert!(session.failed("connection refused".to_string()).is_none());
+
+        // the connection is restored
+        let restored = session.up(outcome).unwrap();
+        assert_eq!(restored.from, ConnectionStatus::Down);
+        assert_eq!(restored.attempts, 2);
+
+        let statuses: Vec<_> = session
+            .transitions()
+            .iter()
+            .map(|t| (t.from, t.to))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                (ConnectionStatus::Down, ConnectionStatus::Up),
+                (ConnectionStatus::Up, ConnectionStatus::Degraded),
+                (ConnectionStatus::Degraded, ConnectionStatus::Down),
+                (ConnectionStatus::Down, ConnectionStatus::Up),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_liveness_probes() {
+        let liveness = SessionLiveness::new(Some(Duration::from_secs(1)), Some(2));
+        let session = Session::new(MockReplacer::new()).with_liveness(Some(liveness));
+        assert_eq!(session.liveness(), Some(liveness));
+        session.up(ReplacerOutcome {
+            ping_route: route!["hop"],
+            kind: ReplacerOutputKind::Inlet(CurrentInletStatus {
+                route: route!["hop"],
+                worker: Address::from_string("echo"),
+                connection_status: ConnectionStatus::Up,
+                load_balancer: None,
+            }),
+        });
+
+        // a probe is only sent once per interval
+        assert!(session.ping_due(liveness.probe_interval()));
+        session.add_ping(Ping::default());
+        assert!(!session.ping_due(liveness.probe_interval()));
+        assert!(session.ping_due(Duration::ZERO));
+
+        // the unanswered probes are reported when the session is replaced
+        session.add_ping(Ping::default());
+        let degraded = session.degraded().unwrap();
+        assert_eq!(
+            degraded.reason.as_deref(),
+            Some("2 liveness probes were not answered")
+        );
+
+        // at least one probe must be missed to replace a session
+        let defaults = SessionLiveness::new(None, Some(0));
+        assert_eq!(defaults.probe_interval(), DEFAULT_PROBE_INTERVAL);
+        assert_eq!(defaults.max_missed_probes(), 1);
+    }
 }
@@ -1,7 +1,8 @@
 use core::fmt;
+use std::collections::VecDeque;
 use std::fmt::Formatter;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use minicbor::{Decode, Encode};
 use ockam::remote::RemoteRelayInfo;
@@ -9,8 +10,11 @@ use serde::{Deserialize, Serialize};
 use tokio::sync::Mutex;
 
 use crate::error::ApiError;
+use crate::nodes::connection::Connection;
 use ockam_core::compat::rand;
+use ockam_core::compat::time::now;
 use ockam_core::{async_trait, Address, Error, Route};
+use ockam_transport_tcp::TcpInletLoadBalancer;
 use rand::random;
 
 //most sessions replacer are dependent on the node manager, if many session
@@ -19,10 +23,67 @@ use rand::random;
 pub const MAX_RECOVERY_TIME: Duration = Duration::from_secs(30);
 pub const MAX_CONNECT_TIME: Duration = Duration::from_secs(15);
 
+/// Maximum number of connection transitions kept for a session
+pub const MAX_CONNECTION_TRANSITIONS: usize = 20;
+
+/// Default interval between two liveness probes sent through the connection of a session
+pub const DEFAULT_PROBE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Default number of consecutive unanswered liveness probes after which a session is replaced
+pub const DEFAULT_MAX_MISSED_PROBES: u32 = 3;
+
+/// Supervision of the connection of a session, for example the secure channel of an inlet.
+///
+/// A probe is sent every `probe_interval` to the echo service at the other end of the
+/// connection, through its secure channels. When `max_missed_probes` consecutive probes are
+/// not answered, the connection is torn down and established again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionLiveness {
+    probe_interval: Duration,
+    max_missed_probes: u32,
+}
+
+impl SessionLiveness {
+    /// Create a supervision with the default interval and threshold, for the missing values
+    pub fn new(probe_interval: Option<Duration>, max_missed_probes: Option<u32>) -> Self {
+        Self {
+            probe_interval: probe_interval.unwrap_or(DEFAULT_PROBE_INTERVAL),
+            max_missed_probes: max_missed_probes
+                .unwrap_or(DEFAULT_MAX_MISSED_PROBES)
+                .max(1),
+        }
+    }
+
+    /// Interval between two liveness probes
+    pub fn probe_interval(&self) -> Duration {
+        self.probe_interval
+    }
+
+    /// Number of consecutive unanswered probes after which the session is replaced
+    pub fn max_missed_probes(&self) -> u32 {
+        self.max_missed_probes
+    }
+}
+
+impl Default for SessionLiveness {
+    fn default() -> Self {
+        Self::new(None, None)
+    }
+}
+
 #[async_trait]
 pub trait SessionReplacer: Send + 'static {
     async fn create(&mut self) -> Result<ReplacerOutcome, Error>;
     async fn close(&mut self) -> ();
+
+    /// Called when the session is up again after `attempts` replacement attempts
+    async fn on_recovered(&mut self, _attempts: u32) {}
+
+    /// Called when the connection status of the session changes
+    async fn on_transition(&mut self, _transition: ConnectionTransition) {}
+
+    /// Called when the resource handled by the session is renamed
+    async fn on_renamed(&mut self, _name: &str) {}
 }
 
 #[derive(Debug, Clone)]
@@ -30,12 +91,16 @@ pub struct CurrentInletStatus {
     pub route: Route,
     pub worker: Address,
     pub connection_status: ConnectionStatus,
+    /// Distribution of the connections across the outlets, when the inlet is load-balanced
+    pub load_balancer: Option<TcpInletLoadBalancer>,
 }
 
 #[derive(Debug, Clone)]
 pub enum ReplacerOutputKind {
     Inlet(CurrentInletStatus),
     Relay(RemoteRelayInfo),
+    /// Connection established ahead of time, to be reused by other services
+    Connection(Connection),
 }
 
 #[derive(Debug, Clone)]
@@ -69,6 +134,18 @@ impl InnerSessionReplacer {
         self.close().await;
         self.create().await
     }
+
+    pub async fn recovered(&self, attempts: u32) {
+        self.inner.lock().await.on_recovered(attempts).await
+    }
+
+    pub async fn transitioned(&self, transition: ConnectionTransition) {
+        self.inner.lock().await.on_transition(transition).await
+    }
+
+    pub async fn renamed(&self, name: &str) {
+        self.inner.lock().await.on_renamed(name).await
+    }
 }
 
 #[derive(Clone)]
@@ -80,8 +157,14 @@ pub struct Session {
 pub struct InnerSession {
     connection: ConnectionStatus,
     replacer: Arc<InnerSessionReplacer>,
+    liveness: Option<SessionLiveness>,
     pings: Vec<Ping>,
+    last_ping_at: Option<Instant>,
+    replacement_requested: Option<String>,
     last_outcome: Option<ReplacerOutcome>,
+    replacement_attempts: u32,
+    last_failure: Option<String>,
+    transitions: VecDeque<ConnectionTransition>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
@@ -112,6 +195,39 @@ impl Default for SessionStatus {
     }
 }
 
+/// A change of the connection status of a session, for example when the outlet of an inlet
+/// becomes unreachable or when the connection is restored
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ConnectionTransition {
+    #[n(1)] pub from: ConnectionStatus,
+    #[n(2)] pub to: ConnectionStatus,
+    /// Unix time, in seconds, when the transition happened
+    #[n(3)] pub at: u64,
+    /// Reason of the transition, when the connection is lost
+    #[n(4)] pub reason: Option<String>,
+    /// Number of replacement attempts made since the session was last up
+    #[n(5)] pub attempts: u32,
+}
+
+impl ConnectionTransition {
+    fn new(
+        from: ConnectionStatus,
+        to: ConnectionStatus,
+        reason: Option<String>,
+        attempts: u32,
+    ) -> Self {
+        Self {
+            from,
+            to,
+            at: now().unwrap_or_default(),
+            reason,
+            attempts,
+        }
+    }
+}
+
 impl fmt::Display for ConnectionStatus {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
@@ -145,6 +261,9 @@ impl fmt::Debug for Session {
             .field("last_outcome", &inner.last_outcome)
             .field("status", &inner.connection)
             .field("pings", &inner.pings)
+            .field("replacement_attempts", &inner.replacement_attempts)
+            .field("last_failure", &inner.last_failure)
+            .field("transitions", &inner.transitions)
             .finish()
     }
 }
@@ -162,16 +281,35 @@ impl Session {
             inner: Arc::new(std::sync::Mutex::new(InnerSession {
                 connection: ConnectionStatus::Down,
                 replacer: Arc::new(InnerSessionReplacer::new(replacer)),
+                liveness: None,
                 pings: Vec::new(),
+                last_ping_at: None,
+                replacement_requested: None,
                 last_outcome: None,
+                replacement_attempts: 0,
+                last_failure: None,
+                transitions: VecDeque::new(),
             })),
         }
     }
 
+    /// Supervise the session with its own probe interval and threshold,
+    /// instead of the ones of the medic
+    pub fn with_liveness(self, liveness: Option<SessionLiveness>) -> Self {
+        self.inner.lock().unwrap().liveness = liveness;
+        self
+    }
+
     pub fn key(&self) -> &str {
         self.key.as_str()
     }
 
+    /// Probe interval and threshold of the session, if it doesn't use the ones of the medic
+    pub fn liveness(&self) -> Option<SessionLiveness> {
+        let inner = self.inner.lock().unwrap();
+        inner.liveness
+    }
+
     pub fn ping_route(&self) -> Option<Route> {
         let inner = self.inner.lock().unwrap();
         inner.last_outcome.as_ref().map(|o| o.ping_route.clone())
@@ -187,16 +325,54 @@ impl Session {
         inner.last_outcome.clone()
     }
 
-    pub fn degraded(&self) {
+    /// Mark the session as degraded while it is being replaced.
+    /// Return the transition if the session was up
+    pub fn degraded(&self) -> Option<ConnectionTransition> {
         let mut inner = self.inner.lock().unwrap();
+        let transition = if inner.connection == ConnectionStatus::Up {
+            let reason = match (inner.replacement_requested.take(), inner.pings.len()) {
+                (Some(reason), _) => reason,
+                (None, 0) => "the session is unresponsive".to_string(),
+                (None, missed) => format!("{missed} liveness probes were not answered"),
+            };
+            Some(inner.add_transition(ConnectionStatus::Degraded, Some(reason)))
+        } else {
+            None
+        };
+        inner.replacement_requested = None;
         inner.connection = ConnectionStatus::Degraded;
         inner.last_outcome = None;
+        transition
+    }
+
+    /// Ask the medic to replace the session at its next check, without waiting
+    /// for the liveness probes to fail, for example because its secure channel was deleted
+    pub fn request_replacement(&self, reason: String) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.connection != ConnectionStatus::Degraded {
+            inner.replacement_requested = Some(reason);
+        }
     }
 
-    pub fn up(&self, replacer_outcome: ReplacerOutcome) {
+    /// Return true if the session must be replaced at the next check of the medic
+    pub fn replacement_requested(&self) -> bool {
+        let inner = self.inner.lock().unwrap();
+        inner.replacement_requested.is_some()
+    }
+
+    /// Mark the session as up.
+    /// Return the transition if the session was not already up
+    pub fn up(&self, replacer_outcome: ReplacerOutcome) -> Option<ConnectionTransition> {
         let mut inner = self.inner.lock().unwrap();
+        let transition = if inner.connection != ConnectionStatus::Up {
+            Some(inner.add_transition(ConnectionStatus::Up, None))
+        } else {
+            None
+        };
         inner.connection = ConnectionStatus::Up;
         inner.last_outcome = Some(replacer_outcome);
+        inner.replacement_attempts = 0;
+        transition
     }
 
     pub fn down(&self) {
@@ -205,6 +381,53 @@ impl Session {
         inner.last_outcome = None;
     }
 
+    /// Mark the session as down after a failed replacement.
+    /// Return the transition unless the session was already down, so that successive
+    /// failed replacements are only recorded once
+    pub fn failed(&self, failure: String) -> Option<ConnectionTransition> {
+        let mut inner = self.inner.lock().unwrap();
+        let already_down = inner
+            .transitions
+            .back()
+            .map(|t| t.to == ConnectionStatus::Down)
+            .unwrap_or(false);
+        let transition = if already_down {
+            None
+        } else {
+            Some(inner.add_transition(ConnectionStatus::Down, Some(failure.clone())))
+        };
+        inner.connection = ConnectionStatus::Down;
+        inner.last_outcome = None;
+        inner.last_failure = Some(failure);
+        transition
+    }
+
+    /// Last connection transitions of the session, from the oldest to the most recent one
+    pub fn transitions(&self) -> Vec<ConnectionTransition> {
+        let inner = self.inner.lock().unwrap();
+        inner.transitions.iter().cloned().collect()
+    }
+
+    /// Count a new replacement attempt and return the number of attempts
+    /// made since the session was last up
+    pub(super) fn replacement_started(&self) -> u32 {
+        let mut inner = self.inner.lock().unwrap();
+        inner.replacement_attempts += 1;
+        inner.replacement_attempts
+    }
+
+    /// Number of replacement attempts made since the session was last up
+    pub fn replacement_attempts(&self) -> u32 {
+        let inner = self.inner.lock().unwrap();
+        inner.replacement_attempts
+    }
+
+    /// Error returned by the last failed replacement
+    pub fn last_failure(&self) -> Option<String> {
+        let inner = self.inner.lock().unwrap();
+        inner.last_failure.clone()
+    }
+
     pub async fn close(self) -> Result<(), Error> {
         let replacer = {
             let inner = self.inner.lock().unwrap();
@@ -217,6 +440,12 @@ impl Session {
         Ok(())
     }
 
+    /// Rename the resource handled by the session, for example an inlet.
+    /// The new name is used the next time the session is replaced
+    pub async fn rename(&self, name: &str) {
+        self.replacer().renamed(name).await
+    }
+
     pub(super) fn replacer(&self) -> Arc<InnerSessionReplacer> {
         let inner = self.inner.lock().unwrap();
         inner.replacer.clone()
@@ -230,6 +459,17 @@ impl Session {
     pub fn add_ping(&self, p: Ping) {
         let mut inner = self.inner.lock().unwrap();
         inner.pings.push(p);
+        inner.last_ping_at = Some(Instant::now());
+    }
+
+    /// Return true if no ping was sent during the last `interval`
+    pub fn ping_due(&self, interval: Duration) -> bool {
+        let inner = self.inner.lock().unwrap();
+        // the medic can check the sessions slightly earlier than the interval
+        inner
+            .last_ping_at
+            .map(|at| at.elapsed() + interval / 10 >= interval)
+            .unwrap_or(true)
     }
 
     pub fn clear_pings(&self) {
@@ -238,6 +478,23 @@ impl Session {
     }
 }
 
+impl InnerSession {
+    /// Record a transition from the current connection status
+    fn add_transition(
+        &mut self,
+        to: ConnectionStatus,
+        reason: Option<String>,
+    ) -> ConnectionTransition {
+        let transition =
+            ConnectionTransition::new(self.connection, to, reason, self.replacement_attempts);
+        if self.transitions.len() == MAX_CONNECTION_TRANSITIONS {
+            self.transitions.pop_front();
+        }
+        self.transitions.push_back(transition.clone());
+        transition
+    }
+}
+
 #[derive(Debug, Default, Copy, Clone, Encode, Decode, PartialEq, Eq)]
 #[cbor(transparent)]
 pub struct Ping(#[n(0)] u64);
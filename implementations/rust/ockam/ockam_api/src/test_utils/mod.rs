@@ -6,12 +6,13 @@ use sqlx::__rt::timeout;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::ops::Deref;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
 use tokio::net::{TcpListener, TcpSocket, TcpStream};
 use tokio::runtime::Runtime;
+use tokio::task::JoinHandle;
 use tracing::{error, info};
 
 use ockam::identity::utils::AttributesBuilder;
@@ -244,6 +245,17 @@ pub struct PassthroughServerHandle {
     pub chosen_addr: SocketAddr,
     pub destination: SocketAddr,
     close: Arc<AtomicBool>,
+    relays: Arc<Mutex<Vec<JoinHandle<()>>>>,
+}
+
+impl PassthroughServerHandle {
+    /// Close the connections currently relayed by the server, as if the network dropped them.
+    /// New connections are still accepted
+    pub fn kill_connections(&self) {
+        for relay in self.relays.lock().unwrap().drain(..) {
+            relay.abort();
+        }
+    }
 }
 
 impl Drop for PassthroughServerHandle {
@@ -282,9 +294,11 @@ pub async fn start_passthrough_server(
 
     let chosen_addr = listener.local_addr().unwrap();
     let close = Arc::new(AtomicBool::new(false));
+    let relays = Arc::new(Mutex::new(vec![]));
 
     {
         let close = close.clone();
+        let relays = relays.clone();
         tokio::spawn(async move {
             loop {
                 let result = match timeout(Duration::from_millis(200), listener.accept()).await {
@@ -298,6 +312,7 @@ pub async fn start_passthrough_server(
                 };
 
                 let (incoming_socket, _) = result.expect("Failed to accept connection");
+                let relays = relays.clone();
                 tokio::spawn(async move {
                     let outgoing_socket = match TcpStream::connect(destination).await {
                         Ok(s) => s,
@@ -309,8 +324,11 @@ pub async fn start_passthrough_server(
                     let (incoming_read, incoming_write) = incoming_socket.into_split();
                     let (outgoing_read, outgoing_write) = outgoing_socket.into_split();
 
-                    start_relay_for(outgoing_disruption, incoming_read, outgoing_write);
-                    start_relay_for(incoming_disruption, outgoing_read, incoming_write);
+                    let outgoing =
+                        start_relay_for(outgoing_disruption, incoming_read, outgoing_write);
+                    let incoming =
+                        start_relay_for(incoming_disruption, outgoing_read, incoming_write);
+                    relays.lock().unwrap().extend([outgoing, incoming]);
                 });
             }
         });
@@ -320,29 +338,30 @@ pub async fn start_passthrough_server(
         chosen_addr,
         destination,
         close,
+        relays,
     }
 }
 
-fn start_relay_for(disruption: Disruption, read: OwnedReadHalf, write: OwnedWriteHalf) {
+fn start_relay_for(
+    disruption: Disruption,
+    read: OwnedReadHalf,
+    write: OwnedWriteHalf,
+) -> JoinHandle<()> {
     match disruption {
         Disruption::None => {
-            tokio::spawn(async move { relay_stream_limit_bandwidth(read, write, None).await });
-        }
-        Disruption::LimitBandwidth(bytes_per_second) => {
-            tokio::spawn(async move {
-                relay_stream_limit_bandwidth(read, write, Some(bytes_per_second)).await
-            });
+            tokio::spawn(async move { relay_stream_limit_bandwidth(read, write, None).await })
         }
+        Disruption::LimitBandwidth(bytes_per_second) => tokio::spawn(async move {
+            relay_stream_limit_bandwidth(read, write, Some(bytes_per_second)).await
+        }),
         Disruption::DropPacketsAfter(drop_packets_after) => {
-            tokio::spawn(async move {
-                relay_stream_drop_packets(read, write, drop_packets_after).await
-            });
-        }
-        Disruption::PacketsOutOfOrderAfter(packet_out_of_order_after) => {
-            tokio::spawn(async move {
-                relay_stream_packets_out_of_order(read, write, packet_out_of_order_after).await
-            });
+            tokio::spawn(
+                async move { relay_stream_drop_packets(read, write, drop_packets_after).await },
+            )
         }
+        Disruption::PacketsOutOfOrderAfter(packet_out_of_order_after) => tokio::spawn(async move {
+            relay_stream_packets_out_of_order(read, write, packet_out_of_order_after).await
+        }),
     }
 }
 
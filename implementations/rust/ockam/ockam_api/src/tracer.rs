@@ -0,0 +1,115 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use minicbor::{Decode, Encode};
+use serde::Serialize;
+
+use ockam::identity::Identifier;
+use ockam::{Any, Context, Result, Routed, Worker};
+use ockam_core::{Decodable, Encodable};
+
+/// Default maximum number of trace services which can forward a probe
+pub const DEFAULT_TRACE_HOP_LIMIT: u8 = 16;
+
+/// Probe sent to the trace service of a node, in order to find out which node a route reaches.
+///
+/// When the onward route of the probe goes on after the trace service, the service records
+/// its hop and forwards the probe, as long as its hop limit is not exhausted. Otherwise
+/// it replies with a [`TraceReply`] containing all the recorded hops.
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TraceProbe {
+    /// Number of trace services which can still forward the probe
+    #[n(1)] pub hop_limit: u8,
+    /// Trace services which have forwarded the probe
+    #[n(2)] pub hops: Vec<TraceHop>,
+}
+
+impl TraceProbe {
+    pub fn new(hop_limit: u8) -> Self {
+        Self {
+            hop_limit,
+            hops: vec![],
+        }
+    }
+}
+
+/// A trace service which received a probe
+#[derive(Debug, Clone, Encode, Decode, Serialize, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TraceHop {
+    #[n(1)] pub node_name: String,
+    #[n(2)] pub identifier: String,
+    /// Unix time, in milliseconds, at which the probe was forwarded or answered
+    #[n(3)] pub timestamp_ms: u64,
+}
+
+/// Reply of the last trace service receiving a probe
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TraceReply {
+    /// Hops of the probe, the last one being the replying trace service
+    #[n(1)] pub hops: Vec<TraceHop>,
+    /// True if the probe was not forwarded because its hop limit was exhausted
+    #[n(2)] pub hop_limit_exceeded: bool,
+}
+
+/// Service answering the probes of `ockam trace` with the name and identity of its node
+pub struct Tracer {
+    node_name: String,
+    identifier: Identifier,
+}
+
+impl Tracer {
+    pub fn new(node_name: impl Into<String>, identifier: Identifier) -> Self {
+        Self {
+            node_name: node_name.into(),
+            identifier,
+        }
+    }
+
+    fn hop(&self) -> TraceHop {
+        TraceHop {
+            node_name: self.node_name.clone(),
+            identifier: self.identifier.to_string(),
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[ockam::worker]
+impl Worker for Tracer {
+    type Context = Context;
+    type Message = Any;
+
+    #[instrument(skip_all, name = "Tracer::handle_message")]
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<Any>) -> Result<()> {
+        // Probes are sent as byte arrays, like the messages of `ockam message send`
+        let mut probe: TraceProbe = minicbor::decode(&Vec::<u8>::decode(msg.payload())?)?;
+        probe.hops.push(self.hop());
+
+        let message = msg.into_local_message().pop_front_onward_route()?;
+        if message.has_next_on_onward_route() {
+            if probe.hop_limit > 0 {
+                probe.hop_limit -= 1;
+                debug!(next = %message.onward_route_ref(), "forwarding a trace probe");
+                return ctx
+                    .forward(message.set_payload(minicbor::to_vec(&probe)?.encode()?))
+                    .await;
+            }
+            debug!(next = %message.onward_route_ref(), "the hop limit of a trace probe is exhausted");
+        }
+
+        let reply = TraceReply {
+            hop_limit_exceeded: message.has_next_on_onward_route(),
+            hops: probe.hops,
+        };
+        ctx.send(message.return_route(), minicbor::to_vec(&reply)?)
+            .await
+    }
+}
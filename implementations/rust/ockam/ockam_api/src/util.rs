@@ -7,7 +7,7 @@ use ockam_core::errcode::{Kind, Origin};
 use ockam_core::flow_control::FlowControlId;
 use ockam_core::{Address, Error, Result, Route, TransportType, LOCAL};
 use ockam_multiaddr::proto::{
-    DnsAddr, Ip4, Ip6, Node, Project, Secure, Service, Space, Tcp, Worker,
+    Dns, DnsAddr, Ip4, Ip6, Node, Project, Secure, Service, Space, Tcp, Worker,
 };
 use ockam_multiaddr::{Code, MultiAddr, Protocol};
 use ockam_transport_tcp::{TcpConnection, TcpConnectionOptions, TCP};
@@ -53,7 +53,7 @@ pub fn local_multiaddr_to_route(ma: &MultiAddr) -> Result<Route> {
                 ));
             }
 
-            code @ (Ip4::CODE | Ip6::CODE | DnsAddr::CODE) => {
+            code @ (Ip4::CODE | Ip6::CODE | Dns::CODE | DnsAddr::CODE) => {
                 return Err(Error::new(
                     Origin::Api,
                     Kind::Invalid,
@@ -81,122 +81,117 @@ pub struct MultiAddrToRouteResult {
     pub tcp_connection: Option<TcpConnection>,
 }
 
+/// Create a route from a multi-address, connecting to its TCP address if it contains one.
+///
+/// Host names given with `/dns` or `/dnsaddr` are resolved here, when the connection
+/// is established, so that DNS changes are taken into account when reconnecting.
+/// The TCP connection is created with the given options, which set, for example,
+/// whether IPv6 addresses are tried before IPv4 addresses and the TCP keepalive.
 pub async fn multiaddr_to_route(
     ma: &MultiAddr,
     tcp: &TcpTransport,
-) -> Option<MultiAddrToRouteResult> {
+    options: TcpConnectionOptions,
+) -> Result<MultiAddrToRouteResult> {
     let mut rb = Route::new();
     let mut it = ma.iter().peekable();
 
     let mut flow_control_id = None;
-    let mut number_of_tcp_hops = 0;
     let mut tcp_connection = None;
+    // Only one TCP hop is allowed, so the options are used at most once
+    let mut options = Some(options);
+
+    let invalid = || {
+        Error::new(
+            Origin::Api,
+            Kind::Invalid,
+            format!("Couldn't convert MultiAddr to route: {ma}"),
+        )
+    };
 
     while let Some(p) = it.next() {
-        match p.code() {
+        let peer = match p.code() {
             Ip4::CODE => {
-                if number_of_tcp_hops >= 1 {
-                    return None; // Only 1 TCP hop is allowed
-                }
-
-                let ip4 = p.cast::<Ip4>()?;
-                let port = it.next()?.cast::<Tcp>()?;
-                let socket_addr = SocketAddrV4::new(*ip4, *port);
-
-                let options = TcpConnectionOptions::new();
-                flow_control_id = Some(options.flow_control_id().clone());
-
-                let connection = match tcp.connect(socket_addr.to_string(), options).await {
-                    Ok(c) => c,
-                    Err(error) => {
-                        error!(%error, %socket_addr, "Couldn't connect to Ip4 address");
-                        return None;
-                    }
-                };
-
-                number_of_tcp_hops += 1;
-                rb = rb.append(connection.sender_address().clone());
-
-                tcp_connection = Some(connection);
+                let ip4 = p.cast::<Ip4>().ok_or_else(invalid)?;
+                let port = it
+                    .next()
+                    .and_then(|p| p.cast::<Tcp>())
+                    .ok_or_else(invalid)?;
+                SocketAddrV4::new(*ip4, *port).to_string()
             }
             Ip6::CODE => {
-                if number_of_tcp_hops >= 1 {
-                    return None; // Only 1 TCP hop is allowed
-                }
-
-                let ip6 = p.cast::<Ip6>()?;
-                let port = it.next()?.cast::<Tcp>()?;
-                let socket_addr = SocketAddrV6::new(*ip6, *port, 0, 0);
-
-                let options = TcpConnectionOptions::new();
-                flow_control_id = Some(options.flow_control_id().clone());
-
-                let connection = match tcp.connect(socket_addr.to_string(), options).await {
-                    Ok(c) => c,
-                    Err(error) => {
-                        error!(%error, %socket_addr, "Couldn't connect to Ip6 address");
-                        return None;
-                    }
-                };
-
-                number_of_tcp_hops += 1;
-                rb = rb.append(connection.sender_address().clone());
-
-                tcp_connection = Some(connection);
+                let ip6 = p.cast::<Ip6>().ok_or_else(invalid)?;
+                let port = it
+                    .next()
+                    .and_then(|p| p.cast::<Tcp>())
+                    .ok_or_else(invalid)?;
+                SocketAddrV6::new(*ip6, *port, 0, 0).to_string()
             }
-            DnsAddr::CODE => {
-                if number_of_tcp_hops >= 1 {
-                    return None; // Only 1 TCP hop is allowed
-                }
-
-                let host = p.cast::<DnsAddr>()?;
-                if let Some(p) = it.peek() {
-                    if p.code() == Tcp::CODE {
-                        let port = p.cast::<Tcp>()?;
-
-                        let options = TcpConnectionOptions::new();
-                        flow_control_id = Some(options.flow_control_id().clone());
-                        let peer = format!("{}:{}", &*host, *port);
-
-                        let connection = match tcp.connect(&peer, options).await {
-                            Ok(c) => c,
-                            Err(error) => {
-                                error!(%error, %peer, "Couldn't connect to DNS address");
-                                return None;
-                            }
-                        };
-
-                        number_of_tcp_hops += 1;
-                        rb = rb.append(connection.sender_address().clone());
-
-                        tcp_connection = Some(connection);
-
-                        let _ = it.next();
-
-                        continue;
-                    }
+            code @ (Dns::CODE | DnsAddr::CODE) => {
+                let host = if code == Dns::CODE {
+                    p.cast::<Dns>().map(|h| h.to_string())
+                } else {
+                    p.cast::<DnsAddr>().map(|h| h.to_string())
                 }
+                .ok_or_else(invalid)?;
+                let port = it
+                    .next()
+                    .and_then(|p| p.cast::<Tcp>())
+                    .ok_or_else(invalid)?;
+                format!("{host}:{}", *port)
             }
             Worker::CODE => {
-                let local = p.cast::<Worker>()?;
-                rb = rb.append(Address::new(LOCAL, &*local))
+                let local = p.cast::<Worker>().ok_or_else(invalid)?;
+                rb = rb.append(Address::new(LOCAL, &*local));
+                continue;
             }
             Service::CODE => {
-                let local = p.cast::<Service>()?;
-                rb = rb.append(Address::new(LOCAL, &*local))
+                let local = p.cast::<Service>().ok_or_else(invalid)?;
+                rb = rb.append(Address::new(LOCAL, &*local));
+                continue;
             }
             Secure::CODE => {
-                let local = p.cast::<Secure>()?;
-                rb = rb.append(Address::new(LOCAL, &*local))
+                let local = p.cast::<Secure>().ok_or_else(invalid)?;
+                rb = rb.append(Address::new(LOCAL, &*local));
+                continue;
             }
             other => {
                 error!(target: "ockam_api", code = %other, "unsupported protocol");
-                return None;
+                return Err(Error::new(
+                    Origin::Api,
+                    Kind::Invalid,
+                    format!("unsupported protocol {other}"),
+                ));
             }
-        }
+        };
+
+        let Some(options) = options.take() else {
+            return Err(Error::new(
+                Origin::Api,
+                Kind::Invalid,
+                format!("Only one TCP hop is allowed: {ma}"),
+            ));
+        };
+
+        flow_control_id = Some(options.flow_control_id().clone());
+
+        let connection = match tcp.connect(&peer, options).await {
+            Ok(c) => c,
+            Err(error) => {
+                error!(%error, %peer, "Couldn't connect to the TCP address");
+                return Err(Error::new(
+                    Origin::Transport,
+                    Kind::Io,
+                    format!("Couldn't connect to {peer}: {error}"),
+                ));
+            }
+        };
+
+        rb = rb.append(connection.sender_address().clone());
+
+        tcp_connection = Some(connection);
     }
 
-    Some(MultiAddrToRouteResult {
+    Ok(MultiAddrToRouteResult {
         flow_control_id,
         tcp_connection,
         route: rb.into(),
@@ -225,6 +220,18 @@ pub fn multiaddr_to_transport_route(ma: &MultiAddr) -> Option<Route> {
                 let socket_addr = SocketAddrV6::new(*ip6, *port, 0, 0);
                 route = route.append(Address::new(TransportType::new(1), socket_addr.to_string()))
             }
+            Dns::CODE => {
+                let host = p.cast::<Dns>()?;
+                if let Some(p) = it.peek() {
+                    if p.code() == Tcp::CODE {
+                        let port = p.cast::<Tcp>()?;
+                        let addr = format!("{}:{}", &*host, *port);
+                        route = route.append(Address::new(TransportType::new(1), addr));
+                        let _ = it.next();
+                        continue;
+                    }
+                }
+            }
             DnsAddr::CODE => {
                 let host = p.cast::<DnsAddr>()?;
                 if let Some(p) = it.peek() {
@@ -323,7 +330,13 @@ pub fn is_local_node(ma: &MultiAddr) -> miette::Result<bool> {
             Node::CODE => {
                 at_rust_node = true;
             }
-            // A "/dnsaddr" will be local if it is "localhost"
+            // A "/dns" or "/dnsaddr" will be local if it is "localhost"
+            Dns::CODE => {
+                at_rust_node = p
+                    .cast::<Dns>()
+                    .map(|dns| (*dns).eq("localhost"))
+                    .ok_or_else(|| miette!("Invalid \"dns\" value"))?;
+            }
             DnsAddr::CODE => {
                 at_rust_node = p
                     .cast::<DnsAddr>()
@@ -361,6 +374,7 @@ pub fn local_worker(code: &Code) -> Result<bool> {
         Node::CODE
         | Space::CODE
         | Project::CODE
+        | Dns::CODE
         | DnsAddr::CODE
         | Ip4::CODE
         | Ip6::CODE
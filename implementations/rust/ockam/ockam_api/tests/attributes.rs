@@ -0,0 +1,49 @@
+use ockam::identity::AttributesEntry;
+use ockam_abac::AbacAccessControl;
+use ockam_api::test_utils::start_manager_for_tests;
+use ockam_core::Result;
+use ockam_node::Context;
+
+#[ockam_macros::test]
+async fn removed_member_is_denied_after_its_attributes_are_flushed(
+    context: &mut Context,
+) -> Result<()> {
+    let node_manager_handle = start_manager_for_tests(context, None, None).await?;
+    let node_manager = node_manager_handle.node_manager.clone();
+
+    // the node is its own project authority in tests
+    let authority = node_manager.identifier();
+    let member = node_manager_handle
+        .secure_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+
+    // the member presented a credential before being removed from the project
+    let identities_attributes = node_manager.identities_attributes();
+    identities_attributes
+        .put_attributes(
+            &member,
+            AttributesEntry::single(
+                b"role".to_vec(),
+                b"member".to_vec(),
+                None,
+                Some(authority.clone()),
+            )?,
+        )
+        .await?;
+
+    let access_control =
+        AbacAccessControl::create(identities_attributes, authority, "role", "member");
+    assert!(
+        access_control
+            .is_identity_authorized(member.clone())
+            .await?
+    );
+
+    node_manager.flush_attributes(&[member.clone()]).await?;
+    assert!(!access_control.is_identity_authorized(member).await?);
+
+    Ok(())
+}
@@ -6,6 +6,7 @@ use ockam::identity::{
     Identities, SecureChannelListenerOptions, SecureChannelOptions, SecureChannels,
 };
 use ockam::route;
+use ockam_api::authenticator::credential_issuer::types::IssueCredential;
 use ockam_api::authenticator::credential_issuer::CredentialIssuerWorker;
 use ockam_api::authenticator::{
     AuthorityMembersRepository, AuthorityMembersSqlxDatabase, PreTrustedIdentity,
@@ -16,6 +17,7 @@ use ockam_core::{Address, Result};
 use ockam_node::api::Client;
 use ockam_node::Context;
 use std::sync::Arc;
+use std::time::Duration;
 
 #[ockam_macros::test]
 async fn credential(ctx: &mut Context) -> Result<()> {
@@ -72,7 +74,7 @@ async fn credential(ctx: &mut Context) -> Result<()> {
         identities.credentials(),
         &auth_identifier,
         "test".to_string(),
-        None,
+        Some(Duration::from_secs(3600)),
         None,
         true,
     );
@@ -111,5 +113,32 @@ async fn credential(ctx: &mut Context) -> Result<()> {
             .map
             .get::<ByteSlice>(b"attr".as_slice().into())
     );
+    // Without a requested ttl, the configured ttl is used
+    assert_eq!(
+        data.credential_data.expires_at - data.credential_data.created_at,
+        3600.into()
+    );
+
+    // A shorter ttl can be requested
+    let credential: CredentialAndPurposeKey = client
+        .ask(
+            ctx,
+            Request::post("/").body(IssueCredential::new(Some(Duration::from_secs(60)))),
+        )
+        .await?
+        .success()?;
+    let data = credential.get_credential_data()?;
+    assert_eq!(data.expires_at - data.created_at, 60.into());
+
+    // A longer ttl is capped by the configured ttl
+    let credential: CredentialAndPurposeKey = client
+        .ask(
+            ctx,
+            Request::post("/").body(IssueCredential::new(Some(Duration::from_secs(7200)))),
+        )
+        .await?
+        .success()?;
+    let data = credential.get_credential_data()?;
+    assert_eq!(data.expires_at - data.created_at, 3600.into());
     Ok(())
 }
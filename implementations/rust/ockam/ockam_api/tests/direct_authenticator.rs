@@ -39,9 +39,6 @@ async fn admin_cant_delete_admin(ctx: &mut Context) -> Result<()> {
     let admin1 = &admins[0];
     let admin2 = &admins[1];
 
-    //TODO: should we return error if trying to remove a non-existing identity?, currently
-    //      we don't detect that. It could lead to problems if the enroller make a mistake with
-    //      the identifier to remove, and assume it was ok because the command didn't error out.
     let res = admin1
         .client
         .delete_member(ctx, admin2.identifier.clone())
@@ -222,6 +219,156 @@ async fn admin_can_delete_member(ctx: &mut Context) -> Result<()> {
     Ok(())
 }
 
+#[ockam_macros::test]
+async fn admin_cant_delete_non_member(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+
+    let AuthorityInfo { admins, .. } = start_authority(ctx, secure_channels.clone(), 1).await?;
+    let admin = &admins[0];
+
+    let identifier = secure_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    let deleted = admin
+        .client
+        .delete_member(ctx, identifier.clone())
+        .await
+        .unwrap();
+    assert!(!deleted);
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn admin_can_update_member(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+
+    let AuthorityInfo { admins, .. } = start_authority(ctx, secure_channels.clone(), 1).await?;
+    let admin = &admins[0];
+
+    let member = secure_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    let attributes = BTreeMap::from([
+        ("role".to_string(), "user".to_string()),
+        ("temp".to_string(), "1".to_string()),
+    ]);
+    admin
+        .client
+        .add_member(ctx, member.clone(), attributes.clone())
+        .await
+        .unwrap();
+
+    let updated = admin
+        .client
+        .update_member(
+            ctx,
+            member.clone(),
+            BTreeMap::from([("role".to_string(), "admin".to_string())]),
+            vec!["temp".to_string()],
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    let expected = BTreeMap::from([("role".to_string(), "admin".to_string())]);
+    assert_eq!(updated.before(), &attributes);
+    assert_eq!(updated.after(), &expected);
+
+    // the member keeps its enrollment information
+    let members = admin.client.list_members(ctx).await.unwrap();
+    let attrs = members.get(&member).unwrap();
+    assert_eq!(attrs.attested_by(), Some(admin.identifier.clone()));
+    assert_eq!(
+        attrs.attrs(),
+        &expected
+            .iter()
+            .map(|(k, v)| (k.as_bytes().to_vec(), v.as_bytes().to_vec()))
+            .collect()
+    );
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn admin_cant_update_non_member(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+
+    let AuthorityInfo { admins, .. } = start_authority(ctx, secure_channels.clone(), 1).await?;
+    let admin = &admins[0];
+
+    let identifier = secure_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    let updated = admin
+        .client
+        .update_member(
+            ctx,
+            identifier.clone(),
+            BTreeMap::from([("role".to_string(), "admin".to_string())]),
+            vec![],
+        )
+        .await
+        .unwrap();
+    assert!(updated.is_none());
+
+    let members = admin.client.list_member_ids(ctx).await.unwrap();
+    assert!(members.is_empty());
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn admin_can_list_members_by_page(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+
+    let AuthorityInfo { admins, .. } = start_authority(ctx, secure_channels.clone(), 1).await?;
+    let admin = &admins[0];
+
+    let mut members = vec![];
+    for _ in 0..3 {
+        let member = secure_channels
+            .identities()
+            .identities_creation()
+            .create_identity()
+            .await?;
+        admin
+            .client
+            .add_member(ctx, member.clone(), Default::default())
+            .await
+            .unwrap();
+        members.push(member);
+    }
+
+    let page1 = admin.client.list_members_page(ctx, 0, 2).await.unwrap();
+    assert_eq!(page1.members().len(), 2);
+    assert_eq!(page1.next_offset(), Some(2));
+
+    let page2 = admin.client.list_members_page(ctx, 2, 2).await.unwrap();
+    assert_eq!(page2.members().len(), 1);
+    assert_eq!(page2.next_offset(), None);
+
+    let mut listed: Vec<_> = page1
+        .members()
+        .iter()
+        .chain(page2.members())
+        .map(|(identifier, entry)| {
+            assert_eq!(entry.attested_by(), Some(admin.identifier.clone()));
+            identifier.clone()
+        })
+        .collect();
+    listed.sort();
+    members.sort();
+    assert_eq!(listed, members);
+
+    Ok(())
+}
+
 #[ockam_macros::test]
 async fn enroller_can_list_members(ctx: &mut Context) -> Result<()> {
     let secure_channels = secure_channels().await?;
@@ -562,6 +709,11 @@ async fn member_cant_do_anything(ctx: &mut Context) -> Result<()> {
     let res = member_client.delete_member(ctx, member2.clone()).await;
     assert!(res.is_err());
 
+    let res = member_client
+        .update_member(ctx, member2.clone(), Default::default(), vec![])
+        .await;
+    assert!(res.is_err());
+
     let res = member_client.list_members(ctx).await;
     assert!(res.is_err());
 
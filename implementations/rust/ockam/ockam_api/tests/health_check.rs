@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+
+use ockam_api::test_utils::start_manager_for_tests;
+use ockam_node::Context;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+#[ockam_macros::test]
+async fn health_check_endpoint_serves_prometheus_metrics(
+    context: &mut Context,
+) -> ockam::Result<()> {
+    let node_manager_handle = start_manager_for_tests(context, None, None).await?;
+    let address = node_manager_handle
+        .node_manager
+        .start_health_check_endpoint(context, "127.0.0.1:0".parse().unwrap())
+        .await?;
+
+    // scrape the metrics concurrently
+    let scrapes = (0..4)
+        .map(|_| tokio::spawn(get(address, "/metrics")))
+        .collect::<Vec<_>>();
+    for scrape in scrapes {
+        let (status, body) = scrape.await.unwrap();
+        assert_eq!(status, "HTTP/1.1 200 OK");
+
+        let metrics = parse_metrics(&body);
+        let node_name = node_manager_handle.node_manager.node_name();
+        let workers = metrics
+            .get(&format!("ockam_node_workers{{node_name=\"{node_name}\"}}"))
+            .unwrap();
+        assert!(*workers > 0.0);
+        for name in [
+            "ockam_node_tcp_connections",
+            "ockam_node_tcp_listeners",
+            "ockam_node_portal_connections",
+            "ockam_node_portal_bytes_sent",
+            "ockam_node_portal_bytes_received",
+            "ockam_node_secure_channels",
+            "ockam_node_uptime_seconds",
+        ] {
+            assert!(
+                metrics.contains_key(&format!("{name}{{node_name=\"{node_name}\"}}")),
+                "missing metric {name}"
+            );
+        }
+    }
+
+    let (status, body) = get(address, "/health").await;
+    assert_eq!(status, "HTTP/1.1 200 OK");
+    assert_eq!(body, "OK\n");
+
+    let (status, _) = get(address, "/unknown").await;
+    assert_eq!(status, "HTTP/1.1 404 Not Found");
+
+    Ok(())
+}
+
+/// Send a GET request and return the status line and the body of the response
+async fn get(address: SocketAddr, path: &str) -> (String, String) {
+    let mut stream = TcpStream::connect(address).await.unwrap();
+    stream
+        .write_all(format!("GET {path} HTTP/1.1\r\nHost: {address}\r\n\r\n").as_bytes())
+        .await
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head.lines().next().unwrap().to_string();
+    (status, body.to_string())
+}
+
+/// Parse the samples of a response in the Prometheus text exposition format,
+/// indexed by metric name and labels
+fn parse_metrics(body: &str) -> HashMap<String, f64> {
+    body.lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let (metric, value) = line.rsplit_once(' ').unwrap();
+            (metric.to_string(), value.parse::<f64>().unwrap())
+        })
+        .collect()
+}
@@ -7,6 +7,7 @@ use tokio::runtime::Runtime;
 use tokio::time::timeout;
 
 use ockam_api::nodes::models::portal::OutletAccessControl;
+use ockam_api::nodes::service::portals::{CreateInletOptions, CreateOutletOptions};
 use ockam_api::test_utils::{start_tcp_echo_server, TestNode};
 use ockam_core::env::FromString;
 use ockam_core::errcode::{Kind, Origin};
@@ -45,6 +46,7 @@ pub fn measure_message_latency_two_nodes() {
                     None,
                     None,
                     None,
+                    None,
                 )
                 .await
                 .unwrap();
@@ -130,10 +132,12 @@ pub fn measure_buffer_latency_two_nodes_portal() {
                 .node_manager
                 .create_outlet(
                     &second_node.context,
-                    echo_server_handle.chosen_addr,
-                    Some(Address::from_string("outlet")),
-                    true,
-                    OutletAccessControl::IncomingAccessControl(Arc::new(AllowAll)),
+                    CreateOutletOptions::new(
+                        echo_server_handle.chosen_addr,
+                        OutletAccessControl::IncomingAccessControl(Arc::new(AllowAll)),
+                    )
+                    .with_worker_addr(Some(Address::from_string("outlet")))
+                    .with_reachable_from_default_secure_channel(true),
                 )
                 .await?;
 
@@ -144,17 +148,15 @@ pub fn measure_buffer_latency_two_nodes_portal() {
                 .node_manager
                 .create_inlet(
                     &first_node.context,
-                    "127.0.0.1:0".to_string(),
-                    route![],
-                    route![],
-                    second_node_listen_address
-                        .multi_addr()?
-                        .concat(&MultiAddr::from_string("/secure/api/service/outlet")?)?,
-                    "inlet_alias".to_string(),
-                    None,
-                    None,
-                    None,
-                    true,
+                    CreateInletOptions::new(
+                        "127.0.0.1:0",
+                        &second_node_listen_address
+                            .multi_addr()?
+                            .concat(&MultiAddr::from_string("/secure/api/service/outlet")?)?,
+                        "inlet_alias",
+                    )
+                    .with_no_delay(true)
+                    .with_wait_connection(true),
                 )
                 .await?;
 
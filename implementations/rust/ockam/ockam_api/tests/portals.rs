@@ -1,12 +1,13 @@
 use ockam_api::config::lookup::InternetAddress;
 use ockam_api::nodes::models::portal::OutletAccessControl;
+use ockam_api::nodes::service::portals::{CreateInletOptions, CreateOutletOptions};
 use ockam_api::test_utils::{
     start_manager_for_tests, start_passthrough_server, start_tcp_echo_server, Disruption, TestNode,
 };
 use ockam_api::ConnectionStatus;
 use ockam_core::compat::rand::RngCore;
 use ockam_core::errcode::{Kind, Origin};
-use ockam_core::{route, Address, AllowAll, Error};
+use ockam_core::{Address, AllowAll, Error};
 use ockam_multiaddr::MultiAddr;
 use ockam_node::Context;
 use std::str::FromStr;
@@ -28,10 +29,12 @@ async fn inlet_outlet_local_successful(context: &mut Context) -> ockam::Result<(
         .node_manager
         .create_outlet(
             context,
-            echo_server_handle.chosen_addr,
-            Some(Address::from_string("outlet")),
-            true,
-            OutletAccessControl::IncomingAccessControl(Arc::new(AllowAll)),
+            CreateOutletOptions::new(
+                echo_server_handle.chosen_addr,
+                OutletAccessControl::IncomingAccessControl(Arc::new(AllowAll)),
+            )
+            .with_worker_addr(Some(Address::from_string("outlet")))
+            .with_reachable_from_default_secure_channel(true),
         )
         .await?;
 
@@ -42,15 +45,13 @@ async fn inlet_outlet_local_successful(context: &mut Context) -> ockam::Result<(
         .node_manager
         .create_inlet(
             context,
-            "127.0.0.1:0".to_string(),
-            route![],
-            route![],
-            MultiAddr::from_str("/secure/api/service/outlet")?,
-            "alias".to_string(),
-            None,
-            None,
-            None,
-            true,
+            CreateInletOptions::new(
+                "127.0.0.1:0",
+                &MultiAddr::from_str("/secure/api/service/outlet")?,
+                "alias",
+            )
+            .with_no_delay(true)
+            .with_wait_connection(true),
         )
         .await?;
 
@@ -71,6 +72,148 @@ async fn inlet_outlet_local_successful(context: &mut Context) -> ockam::Result<(
     Ok(())
 }
 
+#[ockam_macros::test]
+async fn inlet_outlet_rename(context: &mut Context) -> ockam::Result<()> {
+    let echo_server_handle = start_tcp_echo_server().await;
+    let node_manager_handle = start_manager_for_tests(context, None, None).await?;
+    let node_manager = node_manager_handle.node_manager.clone();
+
+    for address in ["outlet", "other"] {
+        node_manager
+            .create_outlet(
+                context,
+                CreateOutletOptions::new(
+                    echo_server_handle.chosen_addr,
+                    OutletAccessControl::IncomingAccessControl(Arc::new(AllowAll)),
+                )
+                .with_worker_addr(Some(Address::from_string(address)))
+                .with_reachable_from_default_secure_channel(true),
+            )
+            .await?;
+    }
+
+    // an outlet cannot be renamed with the address of another outlet
+    let result = node_manager
+        .rename_outlet(context, &"other".into(), &"outlet".into())
+        .await;
+    assert_eq!(result.unwrap_err().code().kind, Kind::AlreadyExists);
+
+    // an outlet is created again at its new address
+    let outlet_status = node_manager
+        .rename_outlet(context, &"other".into(), &"renamed_outlet".into())
+        .await?;
+    assert_eq!(outlet_status.worker_addr.address(), "renamed_outlet");
+    let outlets = node_manager.list_outlets().await.list;
+    let mut addresses = outlets
+        .iter()
+        .map(|o| o.worker_addr.address().to_string())
+        .collect::<Vec<_>>();
+    addresses.sort();
+    assert_eq!(addresses, vec!["outlet", "renamed_outlet"]);
+
+    node_manager
+        .create_inlet(
+            context,
+            CreateInletOptions::new(
+                "127.0.0.1:0",
+                &MultiAddr::from_str("/secure/api/service/renamed_outlet")?,
+                "alias",
+            )
+            .with_no_delay(true)
+            .with_wait_connection(true),
+        )
+        .await?;
+
+    // a missing inlet cannot be renamed
+    let result = node_manager.rename_inlet("missing", "renamed").await;
+    assert_eq!(result.unwrap_err().code().kind, Kind::NotFound);
+
+    // the renamed inlet is still connected to its outlet
+    let inlet_status = node_manager.rename_inlet("alias", "renamed").await?;
+    assert_eq!(inlet_status.alias, "renamed");
+    assert_eq!(inlet_status.status, ConnectionStatus::Up);
+    assert!(node_manager.show_inlet("alias").await.is_none());
+
+    let mut socket = TcpStream::connect(inlet_status.bind_addr).await.unwrap();
+    socket.write_all(b"hello").await.unwrap();
+    let mut buf = [0u8; 5];
+    socket.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"hello");
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn inlet_connections_are_listed_and_closed(context: &mut Context) -> ockam::Result<()> {
+    let echo_server_handle = start_tcp_echo_server().await;
+    let node_manager_handle = start_manager_for_tests(context, None, None).await?;
+    let node_manager = node_manager_handle.node_manager.clone();
+
+    node_manager
+        .create_outlet(
+            context,
+            CreateOutletOptions::new(
+                echo_server_handle.chosen_addr,
+                OutletAccessControl::IncomingAccessControl(Arc::new(AllowAll)),
+            )
+            .with_worker_addr(Some(Address::from_string("outlet")))
+            .with_reachable_from_default_secure_channel(true),
+        )
+        .await?;
+    let inlet_status = node_manager
+        .create_inlet(
+            context,
+            CreateInletOptions::new(
+                "127.0.0.1:0",
+                &MultiAddr::from_str("/secure/api/service/outlet")?,
+                "alias",
+            )
+            .with_no_delay(true)
+            .with_wait_connection(true),
+        )
+        .await?;
+
+    // the connections of a missing inlet cannot be listed
+    assert!(node_manager
+        .list_inlet_connections("missing")
+        .await
+        .is_none());
+    assert_eq!(
+        node_manager.list_inlet_connections("alias").await,
+        Some(vec![])
+    );
+
+    let mut socket = TcpStream::connect(&inlet_status.bind_addr).await.unwrap();
+    socket.write_all(b"hello").await.unwrap();
+    let mut buf = [0u8; 5];
+    socket.read_exact(&mut buf).await.unwrap();
+
+    let connections = node_manager.list_inlet_connections("alias").await.unwrap();
+    assert_eq!(connections.len(), 1);
+    let connection = &connections[0];
+    assert_eq!(connection.source_addr, socket.local_addr().unwrap());
+    assert_eq!(connection.bytes_received, 5);
+    assert_eq!(connection.bytes_sent, 5);
+
+    // an unknown connection is not closed
+    assert!(node_manager
+        .close_inlet_connection("alias", "unknown")
+        .await?
+        .is_none());
+
+    // the TCP client is disconnected when its connection is closed
+    let closed = node_manager
+        .close_inlet_connection("alias", &connection.id)
+        .await?;
+    assert_eq!(closed.map(|c| c.id), Some(connection.id.clone()));
+    let read = timeout(Duration::from_secs(5), socket.read(&mut buf))
+        .await
+        .expect("the connection should be closed");
+    assert!(matches!(read, Ok(0) | Err(_)));
+
+    Ok(())
+}
+
 #[test]
 fn portal_node_goes_down_reconnect() {
     // in this test we manually create three nodes with a shared runtime, then:
@@ -96,10 +239,12 @@ fn portal_node_goes_down_reconnect() {
                 .node_manager
                 .create_outlet(
                     &second_node.context,
-                    echo_server_handle.chosen_addr,
-                    Some(Address::from_string("outlet")),
-                    true,
-                    OutletAccessControl::IncomingAccessControl(Arc::new(AllowAll)),
+                    CreateOutletOptions::new(
+                        echo_server_handle.chosen_addr,
+                        OutletAccessControl::IncomingAccessControl(Arc::new(AllowAll)),
+                    )
+                    .with_worker_addr(Some(Address::from_string("outlet")))
+                    .with_reachable_from_default_secure_channel(true),
                 )
                 .await?;
 
@@ -110,17 +255,15 @@ fn portal_node_goes_down_reconnect() {
                 .node_manager
                 .create_inlet(
                     &first_node.context,
-                    "127.0.0.1:0".to_string(),
-                    route![],
-                    route![],
-                    second_node_listen_address
-                        .multi_addr()?
-                        .concat(&MultiAddr::from_str("/secure/api/service/outlet")?)?,
-                    "inlet_alias".to_string(),
-                    None,
-                    None,
-                    None,
-                    true,
+                    CreateInletOptions::new(
+                        "127.0.0.1:0",
+                        &second_node_listen_address
+                            .multi_addr()?
+                            .concat(&MultiAddr::from_str("/secure/api/service/outlet")?)?,
+                        "inlet_alias",
+                    )
+                    .with_no_delay(true)
+                    .with_wait_connection(true),
                 )
                 .await?;
 
@@ -160,10 +303,12 @@ fn portal_node_goes_down_reconnect() {
                 .node_manager
                 .create_outlet(
                     &third_node.context,
-                    echo_server_handle.chosen_addr,
-                    Some(Address::from_string("outlet")),
-                    true,
-                    OutletAccessControl::IncomingAccessControl(Arc::new(AllowAll)),
+                    CreateOutletOptions::new(
+                        echo_server_handle.chosen_addr,
+                        OutletAccessControl::IncomingAccessControl(Arc::new(AllowAll)),
+                    )
+                    .with_worker_addr(Some(Address::from_string("outlet")))
+                    .with_reachable_from_default_secure_channel(true),
                 )
                 .await?;
 
@@ -201,6 +346,264 @@ fn portal_node_goes_down_reconnect() {
     result.unwrap();
 }
 
+#[test]
+fn portal_secure_channel_is_re_established_when_probes_are_not_answered() {
+    // in this test we use two nodes, connected through a passthrough server:
+    //  - create an inlet probing its secure channel every second
+    //  - kill the TCP connection relayed by the passthrough server
+    //  - verify that the missed probes are detected and the inlet is degraded
+    //  - verify that the secure channel is established again and the portal restored
+
+    let runtime = Arc::new(Runtime::new().unwrap());
+    let handle = runtime.handle();
+    let runtime_cloned = runtime.clone();
+    std::env::set_var("OCKAM_LOG", "none");
+
+    let result: ockam::Result<()> = handle.block_on(async move {
+        let test_body = async move {
+            let echo_server_handle = start_tcp_echo_server().await;
+
+            let first_node = TestNode::create(runtime_cloned.clone(), None).await;
+            let second_node = TestNode::create(runtime_cloned, None).await;
+
+            let _outlet_status = second_node
+                .node_manager
+                .create_outlet(
+                    &second_node.context,
+                    CreateOutletOptions::new(
+                        echo_server_handle.chosen_addr,
+                        OutletAccessControl::IncomingAccessControl(Arc::new(AllowAll)),
+                    )
+                    .with_worker_addr(Some(Address::from_string("outlet")))
+                    .with_reachable_from_default_secure_channel(true),
+                )
+                .await?;
+
+            let second_node_listen_address = second_node.listen_address().await;
+            let passthrough_server_handle = start_passthrough_server(
+                &second_node_listen_address.to_string(),
+                Disruption::None,
+                Disruption::None,
+            )
+            .await;
+
+            let inlet_status = first_node
+                .node_manager
+                .create_inlet(
+                    &first_node.context,
+                    CreateInletOptions::new(
+                        "127.0.0.1:0",
+                        &InternetAddress::from(passthrough_server_handle.chosen_addr)
+                            .multi_addr()?
+                            .concat(&MultiAddr::from_str("/secure/api/service/outlet")?)?,
+                        "inlet_alias",
+                    )
+                    .with_no_delay(true)
+                    .with_liveness(Some(Duration::from_secs(1)), Some(2))
+                    .with_wait_connection(true),
+                )
+                .await?;
+            assert_eq!(inlet_status.liveness.unwrap().max_missed_probes, 2);
+
+            let mut socket = TcpStream::connect(inlet_status.bind_addr.clone())
+                .await
+                .unwrap();
+            socket.write_all(b"hello").await.unwrap();
+            let mut buf = [0u8; 5];
+            socket.read_exact(&mut buf).await.unwrap();
+            assert_eq!(&buf, b"hello");
+
+            passthrough_server_handle.kill_connections();
+
+            // the unanswered probes are detected and the inlet is replaced
+            loop {
+                let inlet_status = first_node
+                    .node_manager
+                    .show_inlet("inlet_alias")
+                    .await
+                    .unwrap();
+                if inlet_status.status != ConnectionStatus::Up {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(200)).await;
+            }
+
+            // then the secure channel is established again through the passthrough server
+            let inlet_status = loop {
+                let inlet_status = first_node
+                    .node_manager
+                    .show_inlet("inlet_alias")
+                    .await
+                    .unwrap();
+                if inlet_status.status == ConnectionStatus::Up {
+                    break inlet_status;
+                }
+                tokio::time::sleep(Duration::from_millis(500)).await;
+            };
+            let degraded = inlet_status
+                .transitions
+                .iter()
+                .find(|t| t.to == ConnectionStatus::Degraded)
+                .unwrap();
+            assert_eq!(
+                degraded.reason.as_deref(),
+                Some("2 liveness probes were not answered")
+            );
+
+            let mut socket = TcpStream::connect(inlet_status.bind_addr).await.unwrap();
+            socket.write_all(b"hello").await.unwrap();
+            let mut buf = [0u8; 5];
+            socket.read_exact(&mut buf).await.unwrap();
+            assert_eq!(&buf, b"hello");
+
+            second_node.context.stop().await?;
+            first_node.context.stop().await?;
+
+            Ok(())
+        };
+
+        timeout(Duration::from_secs(60), test_body)
+            .await
+            .unwrap_or_else(|_| Err(Error::new(Origin::Node, Kind::Timeout, "Test timed out")))
+    });
+
+    result.unwrap();
+}
+
+#[test]
+fn portal_secure_channel_is_re_established_when_it_is_deleted() {
+    // in this test we use two nodes:
+    //  - create an inlet which doesn't probe its secure channel during the test
+    //  - delete the secure channel of the inlet
+    //  - verify that the inlet establishes a new secure channel right away
+    //  - verify that deleting an unknown secure channel fails
+
+    let runtime = Arc::new(Runtime::new().unwrap());
+    let handle = runtime.handle();
+    let runtime_cloned = runtime.clone();
+    std::env::set_var("OCKAM_LOG", "none");
+
+    let result: ockam::Result<()> = handle.block_on(async move {
+        let test_body = async move {
+            let echo_server_handle = start_tcp_echo_server().await;
+
+            let first_node = TestNode::create(runtime_cloned.clone(), None).await;
+            let second_node = TestNode::create(runtime_cloned, None).await;
+
+            let _outlet_status = second_node
+                .node_manager
+                .create_outlet(
+                    &second_node.context,
+                    CreateOutletOptions::new(
+                        echo_server_handle.chosen_addr,
+                        OutletAccessControl::IncomingAccessControl(Arc::new(AllowAll)),
+                    )
+                    .with_worker_addr(Some(Address::from_string("outlet")))
+                    .with_reachable_from_default_secure_channel(true),
+                )
+                .await?;
+
+            let inlet_status = first_node
+                .node_manager
+                .create_inlet(
+                    &first_node.context,
+                    CreateInletOptions::new(
+                        "127.0.0.1:0",
+                        &second_node
+                            .listen_address()
+                            .await
+                            .multi_addr()?
+                            .concat(&MultiAddr::from_str("/secure/api/service/outlet")?)?,
+                        "inlet_alias",
+                    )
+                    .with_no_delay(true)
+                    .with_liveness(Some(Duration::from_secs(600)), None)
+                    .with_wait_connection(true),
+                )
+                .await?;
+
+            let channel = first_node
+                .node_manager
+                .list_secure_channels()
+                .await
+                .list
+                .into_iter()
+                .find(|c| c.used_by.iter().any(|u| u.alias == "inlet_alias"))
+                .unwrap();
+
+            // the channel can be deleted with its decryptor address
+            let response = first_node
+                .node_manager
+                .force_delete_secure_channel(
+                    &first_node.context,
+                    &Address::from_string(channel.decryptor_address.clone()),
+                )
+                .await?;
+            assert_eq!(response.channel, Some(channel.encryptor_address.clone()));
+            assert_eq!(response.failed_over.len(), 1);
+            assert_eq!(response.failed_over[0].kind, "tcp-inlet");
+
+            // the inlet is replaced without waiting for its liveness probes
+            let inlet_status = loop {
+                let inlet_status = first_node
+                    .node_manager
+                    .show_inlet("inlet_alias")
+                    .await
+                    .unwrap();
+                if inlet_status.status == ConnectionStatus::Up
+                    && inlet_status
+                        .transitions
+                        .iter()
+                        .any(|t| t.to == ConnectionStatus::Degraded)
+                {
+                    break inlet_status;
+                }
+                tokio::time::sleep(Duration::from_millis(200)).await;
+            };
+            let degraded = inlet_status
+                .transitions
+                .iter()
+                .find(|t| t.to == ConnectionStatus::Degraded)
+                .unwrap();
+            assert_eq!(
+                degraded.reason,
+                Some(format!(
+                    "the secure channel {} was deleted",
+                    channel.encryptor_address
+                ))
+            );
+
+            let mut socket = TcpStream::connect(inlet_status.bind_addr).await.unwrap();
+            socket.write_all(b"hello").await.unwrap();
+            let mut buf = [0u8; 5];
+            socket.read_exact(&mut buf).await.unwrap();
+            assert_eq!(&buf, b"hello");
+
+            // the deleted channel is not known anymore
+            let error = first_node
+                .node_manager
+                .force_delete_secure_channel(
+                    &first_node.context,
+                    &Address::from_string(channel.encryptor_address),
+                )
+                .await
+                .unwrap_err();
+            assert_eq!(error.code().kind, Kind::NotFound);
+
+            second_node.context.stop().await?;
+            first_node.context.stop().await?;
+
+            Ok(())
+        };
+
+        timeout(Duration::from_secs(60), test_body)
+            .await
+            .unwrap_or_else(|_| Err(Error::new(Origin::Node, Kind::Timeout, "Test timed out")))
+    });
+
+    result.unwrap();
+}
+
 #[test]
 fn portal_low_bandwidth_connection_keep_working_for_60s() {
     // in this test we use two nodes, connected through a passthrough server
@@ -232,10 +635,12 @@ fn portal_low_bandwidth_connection_keep_working_for_60s() {
                 .node_manager
                 .create_outlet(
                     &second_node.context,
-                    echo_server_handle.chosen_addr,
-                    Some(Address::from_string("outlet")),
-                    true,
-                    OutletAccessControl::IncomingAccessControl(Arc::new(AllowAll)),
+                    CreateOutletOptions::new(
+                        echo_server_handle.chosen_addr,
+                        OutletAccessControl::IncomingAccessControl(Arc::new(AllowAll)),
+                    )
+                    .with_worker_addr(Some(Address::from_string("outlet")))
+                    .with_reachable_from_default_secure_channel(true),
                 )
                 .await?;
 
@@ -258,17 +663,15 @@ fn portal_low_bandwidth_connection_keep_working_for_60s() {
                 .node_manager
                 .create_inlet(
                     &first_node.context,
-                    "127.0.0.1:0".to_string(),
-                    route![],
-                    route![],
-                    InternetAddress::from(passthrough_server_handle.chosen_addr)
-                        .multi_addr()?
-                        .concat(&MultiAddr::from_str("/secure/api/service/outlet")?)?,
-                    "inlet_alias".to_string(),
-                    None,
-                    None,
-                    None,
-                    true,
+                    CreateInletOptions::new(
+                        "127.0.0.1:0",
+                        &InternetAddress::from(passthrough_server_handle.chosen_addr)
+                            .multi_addr()?
+                            .concat(&MultiAddr::from_str("/secure/api/service/outlet")?)?,
+                        "inlet_alias",
+                    )
+                    .with_no_delay(true)
+                    .with_wait_connection(true),
                 )
                 .await?;
 
@@ -345,10 +748,12 @@ fn portal_heavy_load_exchanged() {
                 .node_manager
                 .create_outlet(
                     &second_node.context,
-                    echo_server_handle.chosen_addr,
-                    Some(Address::from_string("outlet")),
-                    true,
-                    OutletAccessControl::IncomingAccessControl(Arc::new(AllowAll)),
+                    CreateOutletOptions::new(
+                        echo_server_handle.chosen_addr,
+                        OutletAccessControl::IncomingAccessControl(Arc::new(AllowAll)),
+                    )
+                    .with_worker_addr(Some(Address::from_string("outlet")))
+                    .with_reachable_from_default_secure_channel(true),
                 )
                 .await?;
 
@@ -364,17 +769,15 @@ fn portal_heavy_load_exchanged() {
                 .node_manager
                 .create_inlet(
                     &first_node.context,
-                    "127.0.0.1:0".to_string(),
-                    route![],
-                    route![],
-                    second_node_listen_address
-                        .multi_addr()?
-                        .concat(&MultiAddr::from_str("/secure/api/service/outlet")?)?,
-                    "inlet_alias".to_string(),
-                    None,
-                    None,
-                    None,
-                    true,
+                    CreateInletOptions::new(
+                        "127.0.0.1:0",
+                        &second_node_listen_address
+                            .multi_addr()?
+                            .concat(&MultiAddr::from_str("/secure/api/service/outlet")?)?,
+                        "inlet_alias",
+                    )
+                    .with_no_delay(true)
+                    .with_wait_connection(true),
                 )
                 .await?;
 
@@ -483,10 +886,12 @@ fn test_portal_payload_transfer(outgoing_disruption: Disruption, incoming_disrup
                 .node_manager
                 .create_outlet(
                     &second_node.context,
-                    echo_server_handle.chosen_addr,
-                    Some(Address::from_string("outlet")),
-                    true,
-                    OutletAccessControl::IncomingAccessControl(Arc::new(AllowAll)),
+                    CreateOutletOptions::new(
+                        echo_server_handle.chosen_addr,
+                        OutletAccessControl::IncomingAccessControl(Arc::new(AllowAll)),
+                    )
+                    .with_worker_addr(Some(Address::from_string("outlet")))
+                    .with_reachable_from_default_secure_channel(true),
                 )
                 .await?;
 
@@ -509,17 +914,15 @@ fn test_portal_payload_transfer(outgoing_disruption: Disruption, incoming_disrup
                 .node_manager
                 .create_inlet(
                     &first_node.context,
-                    "127.0.0.1:0".to_string(),
-                    route![],
-                    route![],
-                    InternetAddress::from(passthrough_server_handle.chosen_addr)
-                        .multi_addr()?
-                        .concat(&MultiAddr::from_str("/secure/api/service/outlet")?)?,
-                    "inlet_alias".to_string(),
-                    None,
-                    None,
-                    None,
-                    true,
+                    CreateInletOptions::new(
+                        "127.0.0.1:0",
+                        &InternetAddress::from(passthrough_server_handle.chosen_addr)
+                            .multi_addr()?
+                            .concat(&MultiAddr::from_str("/secure/api/service/outlet")?)?,
+                        "inlet_alias",
+                    )
+                    .with_no_delay(true)
+                    .with_wait_connection(true),
                 )
                 .await?;
 
@@ -49,6 +49,52 @@ async fn admin_can_issue_token(ctx: &mut Context) -> Result<()> {
     Ok(())
 }
 
+#[ockam_macros::test]
+async fn admin_can_issue_ticket(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+
+    let AuthorityInfo {
+        admins,
+        authority_identifier,
+    } = start_authority(ctx, secure_channels.clone(), 1).await?;
+    let admin = &admins[0];
+
+    let mut attributes = BTreeMap::<String, String>::default();
+    attributes.insert("KEY".to_string(), "VALUE".to_string());
+    let ticket = admin
+        .client
+        .create_ticket(ctx, attributes, Some(Duration::from_secs(30)), Some(2))
+        .await
+        .unwrap();
+
+    assert_eq!(ticket.project_identifier(), "123456");
+    assert_eq!(ticket.usage_count(), 2);
+    assert!(ticket.expires_at() > now()?);
+    let authority = secure_channels
+        .identities()
+        .identities_verification()
+        .import(
+            None,
+            &hex::decode(ticket.authority_change_history()).unwrap(),
+        )
+        .await?;
+    assert_eq!(authority, authority_identifier);
+
+    // the one-time code of the ticket can be used to enroll
+    let member = secure_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    let member_client = change_client_identifier(&admin.client, &member, None);
+    member_client
+        .present_token(ctx, ticket.one_time_code().clone())
+        .await
+        .unwrap();
+
+    Ok(())
+}
+
 #[ockam_macros::test]
 async fn admin_can_accept_token(ctx: &mut Context) -> Result<()> {
     let secure_channels = secure_channels().await?;
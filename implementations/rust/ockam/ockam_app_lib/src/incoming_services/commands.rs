@@ -10,7 +10,7 @@ use ockam_api::address::get_free_address;
 use ockam_api::authenticator::direct::{
     OCKAM_ROLE_ATTRIBUTE_ENROLLER_VALUE, OCKAM_ROLE_ATTRIBUTE_KEY,
 };
-use ockam_api::nodes::service::portals::Inlets;
+use ockam_api::nodes::service::portals::{CreateInletOptions, Inlets};
 use ockam_api::ConnectionStatus;
 use ockam_core::api::Reply;
 use ockam_multiaddr::MultiAddr;
@@ -210,18 +210,15 @@ impl AppState {
             str(OCKAM_ROLE_ATTRIBUTE_ENROLLER_VALUE),
         ]);
 
+        let outlet_addr = MultiAddr::from_str(&service.service_route(Some(project_name.as_str())))
+            .into_diagnostic()?;
+        let options =
+            CreateInletOptions::new(&bind_address.to_string(), &outlet_addr, &inlet_alias)
+                .with_policy_expression(Some(expr))
+                .with_wait_for_outlet_timeout(Duration::from_secs(5))
+                .with_wait_connection(true);
         inlet_node
-            .create_inlet(
-                &self.context(),
-                &bind_address.to_string(),
-                &MultiAddr::from_str(&service.service_route(Some(project_name.as_str())))
-                    .into_diagnostic()?,
-                &inlet_alias,
-                &None,
-                &Some(expr),
-                Duration::from_secs(5),
-                true,
-            )
+            .create_inlet(&self.context(), options)
             .await
             .map_err(|err| {
                 warn!(
@@ -91,6 +91,8 @@ impl AppState {
                             false,
                             None,
                             Some(relay_alias),
+                            None,
+                            false,
                         )
                         .await
                         .into_diagnostic()?;
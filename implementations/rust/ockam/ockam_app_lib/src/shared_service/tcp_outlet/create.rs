@@ -3,6 +3,7 @@ use crate::Error;
 use miette::{IntoDiagnostic, WrapErr};
 use ockam_api::address::extract_address_value;
 use ockam_api::nodes::models::portal::OutletAccessControl;
+use ockam_api::nodes::service::portals::CreateOutletOptions;
 use ockam_core::Address;
 use ockam_transport_tcp::resolve_peer;
 use tracing::{debug, info};
@@ -26,15 +27,16 @@ impl AppState {
             .wrap_err("Invalid service address")?
             .into();
         let node_manager = self.node_manager().await;
+        let access_control = OutletAccessControl::IncomingAccessControl(
+            self.create_invitations_access_control(worker_addr.clone())
+                .await?,
+        );
         match node_manager
             .create_outlet(
                 &self.context(),
-                socket_addr,
-                Some(worker_addr.clone()),
-                true,
-                OutletAccessControl::IncomingAccessControl(
-                    self.create_invitations_access_control(worker_addr).await?,
-                ),
+                CreateOutletOptions::new(socket_addr, access_control)
+                    .with_worker_addr(Some(worker_addr))
+                    .with_reachable_from_default_secure_channel(true),
             )
             .await
         {
@@ -4,6 +4,7 @@ use tracing::{debug, error};
 use crate::incoming_services::PersistentIncomingService;
 use crate::state::{AppState, ModelState};
 use ockam_api::nodes::models::portal::{OutletAccessControl, OutletStatus};
+use ockam_api::nodes::service::portals::CreateOutletOptions;
 use ockam_core::Address;
 
 impl ModelState {
@@ -54,10 +55,12 @@ impl AppState {
             let _ = node_manager
                 .create_outlet(
                     &context,
-                    tcp_outlet.socket_addr,
-                    Some(tcp_outlet.worker_addr.clone()),
-                    true,
-                    OutletAccessControl::IncomingAccessControl(access_control),
+                    CreateOutletOptions::new(
+                        tcp_outlet.socket_addr,
+                        OutletAccessControl::IncomingAccessControl(access_control),
+                    )
+                    .with_worker_addr(Some(tcp_outlet.worker_addr.clone()))
+                    .with_reachable_from_default_secure_channel(true),
                 )
                 .await
                 .map_err(|e| {
@@ -116,6 +116,10 @@ impl TcpOutletStatusRow {
             socket_addr,
             worker_addr,
             payload: self.payload.clone(),
+            hostname_port: None,
+            identity: None,
+            unix_socket_path: None,
+            proxy_protocol: false,
         })
     }
 }
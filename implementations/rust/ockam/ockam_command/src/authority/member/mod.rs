@@ -0,0 +1,40 @@
+use clap::{Args, Subcommand};
+
+use crate::{docs, Command, CommandGlobalOpts};
+
+pub use update::UpdateCommand;
+
+mod update;
+
+const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
+
+/// Manage the members of an Authority node
+#[derive(Clone, Debug, Args)]
+#[command(
+arg_required_else_help = true,
+subcommand_required = true,
+long_about = docs::about(LONG_ABOUT),
+)]
+pub struct MemberCommand {
+    #[command(subcommand)]
+    pub subcommand: MemberSubcommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum MemberSubcommand {
+    Update(UpdateCommand),
+}
+
+impl MemberCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        match self.subcommand {
+            MemberSubcommand::Update(c) => c.run(opts),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match &self.subcommand {
+            MemberSubcommand::Update(c) => c.name(),
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use clap::{ArgGroup, Args};
+use miette::miette;
+
+use ockam::identity::Identifier;
+use ockam::Context;
+use ockam_api::authenticator::direct::Members;
+use ockam_api::nodes::InMemoryNode;
+
+use crate::authority::{authority_node_route, get_authority_node};
+use crate::error::{CommandError, ErrorCode};
+use crate::project_member::create_member_attributes;
+use crate::util::api::IdentityOpts;
+use crate::{color_primary, docs, fmt_log, fmt_ok, Command, CommandGlobalOpts, Error, Result};
+
+const LONG_ABOUT: &str = include_str!("./static/update/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/update/after_long_help.txt");
+
+/// Update the attributes of a member of an Authority node
+#[derive(Clone, Debug, Args)]
+#[command(
+arg_required_else_help = true,
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP),
+)]
+#[clap(group(ArgGroup::new("changes").required(true).multiple(true).args(["attributes", "removed_attributes"])))]
+pub struct UpdateCommand {
+    /// Identifier of the member
+    #[arg(value_name = "IDENTIFIER")]
+    member: Identifier,
+
+    /// Name of the Authority node storing the member
+    #[arg(long, value_name = "NODE_NAME", default_value = "authority")]
+    at: String,
+
+    #[command(flatten)]
+    identity_opts: IdentityOpts,
+
+    /// Attribute in `key=value` format to add to the member, or to replace. You can specify this option multiple times for multiple attributes
+    #[arg(short, long = "attribute", value_name = "ATTRIBUTE")]
+    attributes: Vec<String>,
+
+    /// Key of an attribute to remove from the member. You can specify this option multiple times for multiple attributes
+    #[arg(long = "remove-attribute", value_name = "KEY")]
+    removed_attributes: Vec<String>,
+}
+
+#[async_trait]
+impl Command for UpdateCommand {
+    const NAME: &'static str = "authority member update";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> Result<()> {
+        let authority_node = get_authority_node(&opts.state, &self.at).await?;
+        let authority_route = authority_node_route(&authority_node)?;
+        let attributes = create_member_attributes(&self.attributes, &None, false)?;
+
+        let node = InMemoryNode::builder()
+            .with_identity_name(self.identity_opts.identity.clone())
+            .tcp_listener(false)
+            .build(ctx, &opts.state)
+            .await?;
+        let authority_node_client = node
+            .create_authority_client_with_route(
+                &authority_node.identifier(),
+                &authority_route,
+                self.identity_opts.identity.clone(),
+            )
+            .await?;
+
+        let updated = authority_node_client
+            .update_member(
+                ctx,
+                self.member.clone(),
+                attributes,
+                self.removed_attributes.clone(),
+            )
+            .await
+            .map_err(Error::Retry)?;
+        let Some(updated) = updated else {
+            return Err(CommandError::with_code(
+                miette!(
+                    "The identifier {} is not a member of the Authority node {}",
+                    self.member,
+                    color_primary(&self.at)
+                ),
+                ErrorCode::NotFound,
+            ))?;
+        };
+
+        opts.terminal.write_line(&fmt_ok!(
+            "Updated the attributes of the member {}",
+            color_primary(self.member.to_string())
+        ))?;
+        opts.terminal
+            .clone()
+            .stdout()
+            .plain(format!(
+                "{}\n{}",
+                fmt_log!("Before: {}", attributes_output(updated.before())),
+                fmt_log!("After: {}", attributes_output(updated.after()))
+            ))
+            .json(serde_json::json!({
+                "identifier": updated.identifier(),
+                "before": updated.before(),
+                "after": updated.after(),
+            }))
+            .write_line()?;
+
+        Ok(())
+    }
+}
+
+fn attributes_output(attributes: &BTreeMap<String, String>) -> String {
+    attributes
+        .iter()
+        .map(|(k, v)| format!("{k}={v}"))
+        .collect::<Vec<_>>()
+        .join(",")
+}
@@ -1,9 +1,19 @@
-use crate::{docs, CommandGlobalOpts};
+use crate::{color_primary, docs, CommandGlobalOpts};
 use clap::Args;
 use clap::Subcommand;
 use create::CreateCommand;
+use member::MemberCommand;
+use miette::{miette, IntoDiagnostic};
+use ockam_api::cli_state::NodeInfo;
+use ockam_api::nodes::service::default_address::DefaultAddress;
+use ockam_api::CliState;
+use ockam_multiaddr::proto::Service;
+use ockam_multiaddr::MultiAddr;
+use ticket::TicketCommand;
 
 mod create;
+mod member;
+mod ticket;
 
 const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
 
@@ -23,12 +33,16 @@ impl AuthorityCommand {
     pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
         match self.subcommand {
             AuthoritySubcommand::Create(c) => c.run(opts),
+            AuthoritySubcommand::Ticket(c) => c.run(opts),
+            AuthoritySubcommand::Member(c) => c.run(opts),
         }
     }
 
     pub fn name(&self) -> String {
         match &self.subcommand {
             AuthoritySubcommand::Create(c) => c.name(),
+            AuthoritySubcommand::Ticket(c) => c.name(),
+            AuthoritySubcommand::Member(c) => c.name(),
         }
     }
 }
@@ -37,4 +51,32 @@ impl AuthorityCommand {
 pub enum AuthoritySubcommand {
     #[command(display_order = 800)]
     Create(CreateCommand),
+    #[command(display_order = 800)]
+    Ticket(TicketCommand),
+    #[command(display_order = 800)]
+    Member(MemberCommand),
+}
+
+/// Return a node given its name, checking that it is an Authority node
+pub(crate) async fn get_authority_node(
+    cli_state: &CliState,
+    node_name: &str,
+) -> crate::Result<NodeInfo> {
+    let node = cli_state.get_node(node_name).await?;
+    if !node.is_authority_node() {
+        return Err(miette!(
+            "The node {} is not an authority node",
+            color_primary(node_name)
+        ))?;
+    }
+    Ok(node)
+}
+
+/// Return the route to the secure channel listener of an Authority node, via its TCP listener
+pub(crate) fn authority_node_route(node: &NodeInfo) -> crate::Result<MultiAddr> {
+    let mut route = node.tcp_listener_multi_address()?;
+    route
+        .push_back(Service::new(DefaultAddress::SECURE_CHANNEL_LISTENER))
+        .into_diagnostic()?;
+    Ok(route)
 }
@@ -0,0 +1,121 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use clap::Args;
+use miette::IntoDiagnostic;
+
+use ockam::Context;
+use ockam_api::authenticator::enrollment_tokens::TokenIssuer;
+use ockam_api::nodes::InMemoryNode;
+use ockam_multiaddr::MultiAddr;
+
+use crate::authority::{authority_node_route, get_authority_node};
+use crate::output::human_readable_time;
+use crate::project_member::create_member_attributes;
+use crate::util::api::IdentityOpts;
+use crate::util::duration::duration_parser;
+use crate::{color_primary, docs, fmt_log, fmt_ok, Command, CommandGlobalOpts, Error, Result};
+
+const LONG_ABOUT: &str = include_str!("./static/create/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/create/after_long_help.txt");
+
+/// Create an enrollment ticket on an Authority node
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP),
+)]
+pub struct CreateCommand {
+    /// Name of the Authority node issuing the ticket
+    #[arg(long, value_name = "NODE_NAME", default_value = "authority")]
+    at: String,
+
+    #[command(flatten)]
+    identity_opts: IdentityOpts,
+
+    /// Route to the Authority node written in the ticket. By default, the route to the TCP listener of the node is used
+    #[arg(long, value_name = "ROUTE")]
+    authority_route: Option<MultiAddr>,
+
+    /// Attributes in `key=value` format to be attached to the member. You can specify this option multiple times for multiple attributes
+    #[arg(short, long = "attribute", value_name = "ATTRIBUTE")]
+    attributes: Vec<String>,
+
+    /// Duration for which the enrollment ticket is valid, if you don't specify this, the default is 10 minutes. Examples: 10000ms, 600s, 600, 10m, 1h, 1d. If you don't specify a length sigil, it is assumed to be seconds
+    #[arg(long = "expires-in", value_name = "DURATION", value_parser = duration_parser)]
+    expires_in: Option<Duration>,
+
+    /// Number of times the ticket can be used to enroll, the default is 1
+    #[arg(long = "usage-count", value_name = "USAGE_COUNT")]
+    usage_count: Option<u64>,
+
+    /// Name of the relay that the identity using the ticket will be allowed to create. This is shorthand for `--attribute ockam-relay=<name>`
+    #[arg(long = "relay", value_name = "ENROLLEE_ALLOWED_RELAY_NAME")]
+    allowed_relay_name: Option<String>,
+
+    /// Add the enroller role to the ticket. This is shorthand for `--attribute ockam-role=enroller`
+    #[arg(long = "enroller")]
+    enroller: bool,
+}
+
+#[async_trait]
+impl Command for CreateCommand {
+    const NAME: &'static str = "authority ticket create";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> Result<()> {
+        let authority_node = get_authority_node(&opts.state, &self.at).await?;
+        let authority_route = match &self.authority_route {
+            Some(authority_route) => authority_route.clone(),
+            None => authority_node_route(&authority_node)?,
+        };
+        let attributes =
+            create_member_attributes(&self.attributes, &self.allowed_relay_name, self.enroller)?;
+
+        let node = InMemoryNode::builder()
+            .with_identity_name(self.identity_opts.identity.clone())
+            .tcp_listener(false)
+            .build(ctx, &opts.state)
+            .await?;
+        let authority_node_client = node
+            .create_authority_client_with_route(
+                &authority_node.identifier(),
+                &authority_route,
+                self.identity_opts.identity.clone(),
+            )
+            .await?;
+
+        let ticket = authority_node_client
+            .create_ticket(ctx, attributes.clone(), self.expires_in, self.usage_count)
+            .await
+            .map_err(Error::Retry)?;
+        let ticket_serialized = ticket
+            .to_enrollment_ticket(&self.at, &authority_route)
+            .hex_encoded()
+            .into_diagnostic()?;
+
+        opts.terminal.write_line(&fmt_ok!(
+            "{}: {}",
+            "Created enrollment ticket. You can use it to enroll another machine using",
+            color_primary("ockam project enroll")
+        ))?;
+        opts.terminal.write_line(&fmt_log!(
+            "The ticket can be used {} time(s), until {}",
+            ticket.usage_count(),
+            human_readable_time(ticket.expires_at())
+        ))?;
+
+        opts.terminal
+            .clone()
+            .stdout()
+            .machine(&ticket_serialized)
+            .json(serde_json::json!({
+                "ticket": ticket_serialized,
+                "attributes": attributes,
+                "expires_at": ticket.expires_at().0,
+                "usage_count": ticket.usage_count(),
+            }))
+            .write_line()?;
+
+        Ok(())
+    }
+}
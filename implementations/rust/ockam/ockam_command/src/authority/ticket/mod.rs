@@ -0,0 +1,40 @@
+use clap::{Args, Subcommand};
+
+use crate::{docs, Command, CommandGlobalOpts};
+
+pub use create::CreateCommand;
+
+mod create;
+
+const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
+
+/// Manage the enrollment tickets issued by an Authority node
+#[derive(Clone, Debug, Args)]
+#[command(
+arg_required_else_help = true,
+subcommand_required = true,
+long_about = docs::about(LONG_ABOUT),
+)]
+pub struct TicketCommand {
+    #[command(subcommand)]
+    pub subcommand: TicketSubcommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum TicketSubcommand {
+    Create(CreateCommand),
+}
+
+impl TicketCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        match self.subcommand {
+            TicketSubcommand::Create(c) => c.run(opts),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match &self.subcommand {
+            TicketSubcommand::Create(c) => c.name(),
+        }
+    }
+}
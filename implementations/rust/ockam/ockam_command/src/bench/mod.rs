@@ -0,0 +1,33 @@
+use crate::{Command, CommandGlobalOpts};
+use clap::{Args, Subcommand};
+pub use portal::PortalCommand;
+
+mod portal;
+
+/// Measure the performance of the resources of a node
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true, subcommand_required = true)]
+pub struct BenchCommand {
+    #[command(subcommand)]
+    subcommand: BenchSubcommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum BenchSubcommand {
+    #[command(display_order = 800)]
+    Portal(PortalCommand),
+}
+
+impl BenchCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        match self.subcommand {
+            BenchSubcommand::Portal(c) => c.run(opts),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match &self.subcommand {
+            BenchSubcommand::Portal(c) => c.name(),
+        }
+    }
+}
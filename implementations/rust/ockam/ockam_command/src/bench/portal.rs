@@ -0,0 +1,319 @@
+use core::time::Duration;
+use std::io;
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Instant;
+
+use async_trait::async_trait;
+use clap::Args;
+use miette::{miette, IntoDiagnostic};
+use tokio::io::AsyncReadExt;
+use tokio::net::tcp::OwnedWriteHalf;
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, watch};
+use tracing::{info, warn};
+
+use ockam::Context;
+use ockam_api::address::extract_address_value;
+use ockam_api::bench_target::BenchTargetMode;
+use ockam_api::nodes::models::portal::InletStatus;
+use ockam_api::nodes::service::portals::{CreateInletOptions, Inlets};
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_api::random_name;
+use ockam_multiaddr::MultiAddr;
+
+use crate::output::models::{BenchConnectionOutput, BenchPortalOutput};
+use crate::output::Output;
+use crate::util::duration::duration_parser;
+use crate::util::parsers::byte_size_parser;
+use crate::{docs, fmt_log, shutdown, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/portal/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/portal/after_long_help.txt");
+
+/// Measure the throughput and the latency of a portal
+#[derive(Clone, Debug, Args)]
+#[command(
+arg_required_else_help = true,
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct PortalCommand {
+    /// Node on which to create the temporary TCP Inlet
+    #[arg(long, id = "NODE_NAME", value_parser = extract_address_value)]
+    pub at: Option<String>,
+
+    /// Route to a TCP Outlet leading to a bench service, for example `/node/n2/service/bench`
+    #[arg(long, value_name = "ROUTE")]
+    pub to: MultiAddr,
+
+    /// How long to send data through the portal
+    #[arg(long, value_name = "DURATION", default_value = "30s", value_parser = duration_parser)]
+    pub duration: Duration,
+
+    /// Number of TCP connections opened to the TCP Inlet
+    #[arg(long, value_name = "COUNT", default_value_t = 4, value_parser = clap::value_parser!(u16).range(1..))]
+    pub connections: u16,
+
+    /// Size of each payload written to a connection, for example `64KiB` or `1MiB`
+    #[arg(long, value_name = "SIZE", default_value = "64KiB", value_parser = byte_size_parser)]
+    pub payload_size: usize,
+
+    /// Only measure the throughput, when the bench service discards the data instead of echoing it
+    #[arg(long)]
+    pub discard: bool,
+
+    /// Create the TCP Inlet with `--no-delay`, to compare the number of messages it sends to
+    /// the TCP Outlet with and without batching
+    #[arg(long)]
+    pub no_delay: bool,
+
+    /// Maximum time to wait for the TCP Outlet to be reachable, and for each payload to be echoed
+    #[arg(long, value_name = "TIMEOUT", default_value = "10s", value_parser = duration_parser)]
+    pub timeout: Duration,
+}
+
+#[async_trait]
+impl Command for PortalCommand {
+    const NAME: &'static str = "bench portal";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        if self.payload_size == 0 {
+            Err(miette!("The payload size must be at least 1 byte"))?;
+        }
+        let mode = if self.discard {
+            BenchTargetMode::Discard
+        } else {
+            BenchTargetMode::Echo
+        };
+
+        // Ctrl+C stops the benchmark, so that the temporary inlet can still be deleted
+        let (interrupted_tx, interrupted) = watch::channel(false);
+        {
+            let terminal = opts.terminal.clone();
+            let quiet = opts.global_args.quiet;
+            tokio::spawn(async move {
+                let (tx, mut rx) = mpsc::channel(2);
+                if let Ok(Some(_)) = shutdown::wait(terminal, false, quiet, tx, &mut rx).await {
+                    let _ = interrupted_tx.send(true);
+                }
+            });
+        }
+
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.at).await?;
+        let alias = format!("bench-{}", random_name());
+        let options = CreateInletOptions::new("127.0.0.1:0", &self.to, &alias)
+            .with_wait_for_outlet_timeout(self.timeout)
+            .with_wait_connection(true)
+            .with_no_delay(self.no_delay);
+        let inlet = node
+            .create_inlet(ctx, options)
+            .await?
+            .miette_success("create a TCP inlet")?;
+        info!(%alias, bind_addr = %inlet.bind_addr, "created the bench inlet");
+
+        let result = self
+            .run_benchmark(&opts, ctx, &node, &inlet, mode, interrupted)
+            .await;
+        if let Err(e) = node.delete_inlet(ctx, &alias).await {
+            warn!(%e, %alias, "the bench inlet could not be deleted");
+        }
+        let output = result?;
+
+        opts.terminal
+            .stdout()
+            .plain(output.output()?)
+            .json(serde_json::to_string_pretty(&output).into_diagnostic()?)
+            .write_line()?;
+        if output.bytes == 0 && !output.interrupted {
+            Err(miette!("No data went through the portal to {}", self.to))?;
+        }
+        Ok(())
+    }
+}
+
+impl PortalCommand {
+    /// Send payloads through the inlet, from several connections, until the end of the
+    /// benchmark, an interruption, or the failure of all the connections
+    async fn run_benchmark(
+        &self,
+        opts: &CommandGlobalOpts,
+        ctx: &Context,
+        node: &BackgroundNodeClient,
+        inlet: &InletStatus,
+        mode: BenchTargetMode,
+        mut interrupted: watch::Receiver<bool>,
+    ) -> crate::Result<BenchPortalOutput> {
+        let bind_addr = SocketAddr::from_str(&inlet.bind_addr).into_diagnostic()?;
+        let payload: Arc<Vec<u8>> =
+            Arc::new((0..self.payload_size).map(|i| (i % 251) as u8).collect());
+        opts.terminal.write_line(fmt_log!(
+            "Sending {} bytes payloads over {} connections to {} for {:?}",
+            self.payload_size,
+            self.connections,
+            self.to,
+            self.duration
+        ))?;
+
+        let started_at = Instant::now();
+        let (stop_tx, stop) = watch::channel(false);
+        let tasks: Vec<_> = (1..=self.connections as usize)
+            .map(|connection| {
+                tokio::spawn(run_connection(
+                    connection,
+                    bind_addr,
+                    mode,
+                    payload.clone(),
+                    self.timeout,
+                    stop.clone(),
+                ))
+            })
+            .collect();
+        drop(stop);
+
+        let is_interrupted = tokio::select! {
+            _ = tokio::time::sleep(self.duration) => false,
+            Ok(_) = interrupted.wait_for(|interrupted| *interrupted) => true,
+            // all the connections failed
+            _ = stop_tx.closed() => false,
+        };
+        let elapsed = started_at.elapsed();
+
+        // The messages are counted while the connections are still open
+        let messages = match node.list_inlet_connections(ctx, &inlet.alias).await {
+            Ok(connections) => Some(connections.iter().map(|c| c.payload_messages).sum()),
+            Err(e) => {
+                warn!(%e, alias = %inlet.alias, "the bench inlet connections could not be listed");
+                None
+            }
+        };
+        let _ = stop_tx.send(true);
+
+        let mut connections = vec![];
+        let mut rtts = vec![];
+        for (index, task) in tasks.into_iter().enumerate() {
+            let stats = task.await.unwrap_or_else(|e| ConnectionStats {
+                error: Some(e.to_string()),
+                ..Default::default()
+            });
+            rtts.extend(stats.rtts);
+            connections.push(BenchConnectionOutput::new(
+                index + 1,
+                stats.bytes,
+                stats.write_blocked,
+                elapsed,
+                stats.error,
+            ));
+        }
+        Ok(BenchPortalOutput::new(
+            self.to.to_string(),
+            mode,
+            self.payload_size,
+            elapsed,
+            is_interrupted,
+            connections,
+            &rtts,
+        )
+        .with_messages(messages, self.no_delay))
+    }
+}
+
+/// Measurements of a single connection to the inlet
+#[derive(Default)]
+struct ConnectionStats {
+    bytes: u64,
+    rtts: Vec<Duration>,
+    write_blocked: Duration,
+    error: Option<String>,
+}
+
+async fn run_connection(
+    connection: usize,
+    bind_addr: SocketAddr,
+    mode: BenchTargetMode,
+    payload: Arc<Vec<u8>>,
+    timeout: Duration,
+    mut stop: watch::Receiver<bool>,
+) -> ConnectionStats {
+    let mut stats = ConnectionStats::default();
+    let result = tokio::select! {
+        _ = stop.changed() => Ok(()),
+        result = send_payloads(bind_addr, mode, &payload, timeout, &mut stats) => result,
+    };
+    if let Err(e) = result {
+        info!(%e, connection, "the bench connection failed");
+        stats.error = Some(e.to_string());
+    }
+    stats
+}
+
+/// Write payloads to the inlet until an error occurs. In echo mode, each payload must be
+/// echoed back before the next one is written
+async fn send_payloads(
+    bind_addr: SocketAddr,
+    mode: BenchTargetMode,
+    payload: &[u8],
+    timeout: Duration,
+    stats: &mut ConnectionStats,
+) -> io::Result<()> {
+    let stream = TcpStream::connect(bind_addr).await?;
+    stream.set_nodelay(true)?;
+    let (mut reader, writer) = stream.into_split();
+    let mut echo = vec![0u8; payload.len()];
+    loop {
+        let started_at = Instant::now();
+        match mode {
+            BenchTargetMode::Discard => {
+                write_payload(&writer, payload, &mut stats.write_blocked).await?;
+            }
+            BenchTargetMode::Echo => {
+                let round_trip = async {
+                    tokio::try_join!(
+                        write_payload(&writer, payload, &mut stats.write_blocked),
+                        reader.read_exact(&mut echo)
+                    )
+                };
+                tokio::time::timeout(timeout, round_trip)
+                    .await
+                    .map_err(|_| {
+                        io::Error::new(
+                            io::ErrorKind::TimedOut,
+                            format!("no echo received within {timeout:?}"),
+                        )
+                    })??;
+                if echo != payload {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "the echoed data differs from the data sent",
+                    ));
+                }
+                stats.rtts.push(started_at.elapsed());
+            }
+        }
+        stats.bytes += payload.len() as u64;
+    }
+}
+
+/// Write a payload, adding the time spent waiting for the socket send buffer to have
+/// some room to `blocked`
+async fn write_payload(
+    writer: &OwnedWriteHalf,
+    payload: &[u8],
+    blocked: &mut Duration,
+) -> io::Result<()> {
+    let mut written = 0;
+    while written < payload.len() {
+        match writer.try_write(&payload[written..]) {
+            Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+            Ok(n) => written += n,
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                let started_at = Instant::now();
+                writer.writable().await?;
+                *blocked += started_at.elapsed();
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
@@ -2,12 +2,10 @@
 // binary names. The issue is that we need to avoid the `ockam` binary colliding
 // with the `ockam` crate.
 
-use ockam_command::util::exitcode;
-
 fn main() {
     if let Err(e) = ockam_command::entry_point::run() {
         // initialization errors are displayed here
         eprintln!("{:?}", e);
-        std::process::exit(exitcode::SOFTWARE);
+        std::process::exit(ockam_command::exit_code(&e));
     }
 }
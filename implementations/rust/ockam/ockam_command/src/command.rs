@@ -1,15 +1,17 @@
 use crate::command_events::{add_command_error_event, add_command_event};
 use crate::command_global_opts::CommandGlobalOpts;
 use crate::docs;
+use crate::error::{CommandError, ErrorCode};
 use crate::fmt_warn;
 use crate::global_args::GlobalArgs;
+use crate::output::OutputFormat;
 use crate::subcommand::OckamSubcommand;
 use crate::upgrade::check_if_an_upgrade_is_available;
 use crate::version::Version;
 
 use clap::Parser;
 use colorful::Colorful;
-use miette::GraphicalReportHandler;
+use miette::{GraphicalReportHandler, Report};
 use ockam_core::OCKAM_TRACER_NAME;
 use opentelemetry::trace::{Link, SpanBuilder, TraceContextExt, Tracer};
 use opentelemetry::{global, Context};
@@ -54,6 +56,12 @@ impl OckamCommand {
             return Ok(());
         }
 
+        // Completion candidates are printed as soon as possible, without
+        // setting up the local state, logging or checking for upgrades
+        if let OckamSubcommand::Complete(c) = self.subcommand {
+            return c.run();
+        }
+
         // Sets a hook using our own Error Report Handler
         // This allows us to customize how we
         // format the error messages and their content.
@@ -98,13 +106,18 @@ impl OckamCommand {
                     self.run_command(options.clone(), &command_name, &arguments)
                 })
             };
+        // Errors are reported with a code identifying their cause
+        let result = result.map_err(|e| Report::new(CommandError::new(e)));
         if let Err(ref e) = result {
             add_command_error_event(
                 options.state.clone(),
                 &command_name,
                 &format!("{e}"),
                 arguments.join(" "),
-            )?
+            )?;
+            if options.global_args.output_format == OutputFormat::Json {
+                write_json_error(&options, e)?;
+            }
         };
         options.shutdown();
         result
@@ -121,3 +134,20 @@ impl OckamCommand {
         self.subcommand.run(opts)
     }
 }
+
+/// Write an error as a JSON object on stdout, so that it can be parsed by scripts:
+/// `{"error": {"code": "not_found", "message": "..."}}`
+fn write_json_error(opts: &CommandGlobalOpts, error: &Report) -> miette::Result<()> {
+    let code = match error.downcast_ref::<CommandError>() {
+        Some(error) => error.code(),
+        None => ErrorCode::from_report(error),
+    };
+    let json = serde_json::json!({
+        "error": {
+            "code": code,
+            "message": error.to_string(),
+        }
+    });
+    opts.terminal.clone().stdout().json(json).write_line()?;
+    Ok(())
+}
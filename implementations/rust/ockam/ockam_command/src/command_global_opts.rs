@@ -7,27 +7,32 @@ use tokio::runtime::Runtime;
 use tracing::{debug, info};
 use tracing_core::Level;
 
+use ockam_api::cli_state::set_vault_passphrase_provider;
 use ockam_api::logs::{
     crates_filter, logging_configuration, Colored, ExportingConfiguration, LoggingConfiguration,
     LoggingTracing, TracingGuard,
 };
 use ockam_api::CliState;
 
+use crate::config::{ConfigFiles, ResolvedConfig};
 use crate::subcommand::OckamSubcommand;
 use crate::terminal::color_primary;
 use crate::util::exitcode;
+use crate::vault::passphrase::PromptVaultPassphrase;
 use crate::version::Version;
 use crate::{fmt_err, fmt_log, fmt_ok, GlobalArgs, Terminal, TerminalStream};
 
 /// This struct contains the main structs used to implement commands:
 ///
 ///  - The arguments applicable to all commands
+///  - The configuration read from the configuration files
 ///  - The CliState, which provides an access to both the local state and interfaces to remote nodes
 ///  - The terminal used to output the command results
 ///
 #[derive(Clone, Debug)]
 pub struct CommandGlobalOpts {
     pub global_args: GlobalArgs,
+    pub config: ResolvedConfig,
     pub state: CliState,
     pub terminal: Terminal<TerminalStream<Term>>,
     pub rt: Arc<Runtime>,
@@ -37,6 +42,7 @@ pub struct CommandGlobalOpts {
 impl CommandGlobalOpts {
     /// Create new CommandGlobalOpts:
     ///
+    ///  - Apply the configuration files
     ///  - Instantiate logging + tracing
     ///  - Initialize the CliState
     ///  - Get the runtime
@@ -46,6 +52,11 @@ impl CommandGlobalOpts {
         global_args: &GlobalArgs,
         cmd: &OckamSubcommand,
     ) -> miette::Result<Self> {
+        // the command line arguments override the values of the configuration files
+        let config = ConfigFiles::load()?.resolve(global_args, arguments);
+        let global_args = &config.apply(global_args);
+        config.apply_telemetry();
+
         let terminal = Terminal::from(global_args);
         let logging_configuration =
             Self::make_logging_configuration(global_args, cmd, terminal.is_tty())?;
@@ -62,7 +73,9 @@ impl CommandGlobalOpts {
         );
 
         let state = match CliState::with_default_dir() {
-            Ok(state) => state.set_tracing_enabled(tracing_configuration.is_enabled()),
+            Ok(state) => state
+                .set_tracing_enabled(tracing_configuration.is_enabled())
+                .set_defaults(config.cli_defaults()),
             Err(err) => {
                 // If the user is trying to run `ockam reset` and the local state is corrupted,
                 // we can try to hard reset the local state.
@@ -71,6 +84,7 @@ impl CommandGlobalOpts {
                     terminal
                         .stdout()
                         .plain(fmt_ok!("Local Ockam configuration deleted"))
+                        .json(serde_json::json!({ "orchestrator_resources_deleted": false }))
                         .write_line()
                         .unwrap();
                     exit(exitcode::OK);
@@ -98,8 +112,13 @@ impl CommandGlobalOpts {
                 exit(exitcode::SOFTWARE);
             }
         };
+
+        // ask for the passphrase of encrypted vaults when the terminal is interactive
+        set_vault_passphrase_provider(Arc::new(PromptVaultPassphrase::new(terminal.clone())));
+
         Ok(Self {
             global_args: global_args.clone(),
+            config,
             state,
             terminal,
             rt: Arc::new(Runtime::new().expect("cannot initialize the tokio runtime")),
@@ -144,8 +163,8 @@ impl CommandGlobalOpts {
 
         let log_path = cmd.log_path();
         let crates = crates_filter().into_diagnostic()?;
-        if cmd.is_background_node() {
-            Ok(LoggingConfiguration::background(log_path, crates).into_diagnostic()?)
+        let logging_configuration = if cmd.is_background_node() {
+            LoggingConfiguration::background(log_path, crates).into_diagnostic()?
         } else {
             let preferred_log_level = verbose_log_level(global_args.verbose);
             let colored = if !global_args.no_color && is_tty {
@@ -153,11 +172,15 @@ impl CommandGlobalOpts {
             } else {
                 Colored::Off
             };
-            Ok(
-                logging_configuration(preferred_log_level, colored, log_path, crates)
-                    .into_diagnostic()?,
-            )
-        }
+            logging_configuration(preferred_log_level, colored, log_path, crates)
+                .into_diagnostic()?
+        };
+
+        // the logging options of a node override the environment variables
+        Ok(match cmd.node_logging() {
+            Some(node_logging) => node_logging.apply(logging_configuration),
+            None => logging_configuration,
+        })
     }
 
     /// Create the tracing configuration, depending on the command to execute
@@ -221,8 +244,10 @@ impl CommandGlobalOpts {
             global_args.no_input,
             global_args.output_format.clone(),
         );
+        let config = ConfigFiles::default().resolve(&global_args, &[]);
         Self {
             global_args,
+            config,
             state,
             terminal,
             rt: Arc::new(Runtime::new().expect("cannot initialize the tokio runtime")),
@@ -0,0 +1,159 @@
+use clap::{Args, ValueEnum};
+use std::io::{self, Write};
+
+use ockam_abac::ResourceType;
+use ockam_api::address::extract_address_value;
+use ockam_api::CliState;
+use ockam_node::Executor;
+
+/// Print the values which can be used to complete an argument.
+///
+/// This command is called by the scripts generated with `ockam completion`.
+/// The values are only read from the local state: no node is contacted and,
+/// if the state can't be read, nothing is printed.
+#[derive(Clone, Debug, Args)]
+pub struct CompleteCommand {
+    /// The kind of value to complete
+    kind: CompletionKind,
+
+    /// The node used to look up inlet and outlet aliases, instead of the default node
+    #[arg(long, value_name = "NODE_NAME")]
+    node: Option<String>,
+}
+
+/// Kinds of values which can be completed from the local state
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum CompletionKind {
+    Nodes,
+    Identities,
+    Vaults,
+    Projects,
+    Spaces,
+    Inlets,
+    Outlets,
+    Relays,
+}
+
+impl CompletionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CompletionKind::Nodes => "nodes",
+            CompletionKind::Identities => "identities",
+            CompletionKind::Vaults => "vaults",
+            CompletionKind::Projects => "projects",
+            CompletionKind::Spaces => "spaces",
+            CompletionKind::Inlets => "inlets",
+            CompletionKind::Outlets => "outlets",
+            CompletionKind::Relays => "relays",
+        }
+    }
+}
+
+impl CompleteCommand {
+    pub fn run(self) -> miette::Result<()> {
+        let mut stdout = io::stdout().lock();
+        for candidate in self.candidates().unwrap_or_default() {
+            if writeln!(stdout, "{candidate}").is_err() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn name(&self) -> String {
+        "__complete".to_string()
+    }
+
+    /// Return the candidates for the requested kind of value, or None if the
+    /// local state does not exist or can't be read
+    fn candidates(&self) -> Option<Vec<String>> {
+        // Don't create a state directory just to complete a value
+        let dir = CliState::default_dir().ok()?;
+        if !dir.exists() {
+            return None;
+        }
+        let state = CliState::new(&dir).ok()?;
+        let kind = self.kind;
+        let node = self
+            .node
+            .as_deref()
+            .filter(|n| !n.is_empty())
+            .and_then(|n| extract_address_value(n).ok());
+        Executor::execute_future(async move { get_candidates(&state, kind, node).await })
+            .ok()
+            .flatten()
+    }
+}
+
+async fn get_candidates(
+    state: &CliState,
+    kind: CompletionKind,
+    node: Option<String>,
+) -> Option<Vec<String>> {
+    let mut names = match kind {
+        CompletionKind::Nodes => state
+            .get_nodes()
+            .await
+            .ok()?
+            .iter()
+            .map(|n| n.name())
+            .collect(),
+        CompletionKind::Identities => state
+            .get_named_identities()
+            .await
+            .ok()?
+            .iter()
+            .map(|i| i.name())
+            .collect(),
+        CompletionKind::Vaults => state
+            .get_named_vaults()
+            .await
+            .ok()?
+            .iter()
+            .map(|v| v.name())
+            .collect(),
+        CompletionKind::Projects => state
+            .get_projects()
+            .await
+            .ok()?
+            .iter()
+            .map(|p| p.name().to_string())
+            .collect(),
+        CompletionKind::Spaces => state
+            .get_spaces()
+            .await
+            .ok()?
+            .into_iter()
+            .map(|s| s.name)
+            .collect(),
+        CompletionKind::Inlets => get_node_resources(state, node, ResourceType::TcpInlet).await?,
+        CompletionKind::Outlets => get_node_resources(state, node, ResourceType::TcpOutlet).await?,
+        // Relays are only known by the nodes hosting them, so the only name
+        // which can be suggested without contacting a node is the default one
+        CompletionKind::Relays => vec!["default".to_string()],
+    };
+    names.sort();
+    names.dedup();
+    Some(names)
+}
+
+/// Return the names of the resources of a given type created on a node
+async fn get_node_resources(
+    state: &CliState,
+    node: Option<String>,
+    resource_type: ResourceType,
+) -> Option<Vec<String>> {
+    let node_name = match node {
+        Some(node_name) => node_name,
+        None => state.get_default_node().await.ok()?.name(),
+    };
+    Some(
+        state
+            .get_node_resources(&node_name, &resource_type)
+            .await
+            .ok()?
+            .into_iter()
+            .map(|r| r.resource_name.as_str().to_string())
+            .collect(),
+    )
+}
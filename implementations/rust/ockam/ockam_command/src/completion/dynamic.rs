@@ -0,0 +1,415 @@
+use clap::{Arg, Command};
+use clap_complete::Shell;
+use std::collections::BTreeMap;
+
+use super::complete::CompletionKind;
+
+/// An argument which can be completed with values read from the local state
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct DynamicArgument {
+    /// Names of the subcommands leading to the argument, starting with `ockam`
+    pub(crate) path: String,
+    /// Long flag of the argument, or an empty string for the first positional argument
+    pub(crate) flag: String,
+    pub(crate) kind: CompletionKind,
+}
+
+/// Return the arguments of a command, and of all its subcommands, which can be
+/// completed with values read from the local state
+pub(crate) fn dynamic_arguments(command: &Command) -> Vec<DynamicArgument> {
+    let mut arguments = vec![];
+    collect_arguments(command, command.get_name(), None, &mut arguments);
+    arguments
+}
+
+/// Return the subcommands leading to some dynamic arguments, with all the names
+/// (including aliases) which can be used on the command line to select them
+fn subcommand_paths(
+    command: &Command,
+    arguments: &[DynamicArgument],
+) -> BTreeMap<String, Vec<String>> {
+    let mut paths = BTreeMap::new();
+    collect_paths(command, command.get_name(), &mut paths);
+    paths.retain(|path, _| {
+        arguments
+            .iter()
+            .any(|a| a.path == *path || a.path.starts_with(&format!("{path} ")))
+    });
+    paths
+}
+
+fn collect_arguments(
+    command: &Command,
+    path: &str,
+    parent: Option<&str>,
+    arguments: &mut Vec<DynamicArgument>,
+) {
+    for arg in command.get_arguments() {
+        if !arg.get_action().takes_values() {
+            continue;
+        }
+        let flag = match (arg.get_long(), arg.get_index()) {
+            (Some(long), _) => format!("--{long}"),
+            (None, Some(1)) => "".to_string(),
+            _ => continue,
+        };
+        if let Some(kind) = completion_kind(command.get_name(), parent, arg) {
+            arguments.push(DynamicArgument {
+                path: path.to_string(),
+                flag,
+                kind,
+            });
+        }
+    }
+    for subcommand in command.get_subcommands() {
+        if subcommand.get_name() == "help" || subcommand.get_name().starts_with("__") {
+            continue;
+        }
+        let subcommand_path = format!("{path} {}", subcommand.get_name());
+        collect_arguments(
+            subcommand,
+            &subcommand_path,
+            Some(command.get_name()),
+            arguments,
+        );
+    }
+}
+
+fn collect_paths(command: &Command, path: &str, paths: &mut BTreeMap<String, Vec<String>>) {
+    for subcommand in command.get_subcommands() {
+        let subcommand_path = format!("{path} {}", subcommand.get_name());
+        let names = std::iter::once(subcommand.get_name())
+            .chain(subcommand.get_all_aliases())
+            .map(|name| format!("{path} {name}"))
+            .collect();
+        collect_paths(subcommand, &subcommand_path, paths);
+        paths.insert(subcommand_path, names);
+    }
+}
+
+/// Return the kind of value expected by an argument, based on its id, its value name
+/// or, for the positional name of a resource, the command managing that resource
+fn completion_kind(command: &str, parent: Option<&str>, arg: &Arg) -> Option<CompletionKind> {
+    // The name of a resource being created can't be completed
+    if command == "create" && (arg.is_positional() || arg.get_id().as_str() == "ALIAS") {
+        return None;
+    }
+    let value_name = arg
+        .get_value_names()
+        .and_then(|names| names.first())
+        .map(|name| name.to_string())
+        .unwrap_or_else(|| arg.get_id().to_string().to_uppercase());
+    match (arg.get_id().as_str(), value_name.as_str()) {
+        ("at", _) | (_, "NODE") | (_, "NODE_NAME") => Some(CompletionKind::Nodes),
+        (_, "IDENTITY_NAME") => Some(CompletionKind::Identities),
+        (_, "VAULT_NAME") => Some(CompletionKind::Vaults),
+        (_, "PROJECT_NAME") => Some(CompletionKind::Projects),
+        (_, "SPACE_NAME") => Some(CompletionKind::Spaces),
+        ("RELAY_NAME", _) | (_, "RELAY_NAME") => Some(CompletionKind::Relays),
+        ("ALIAS", _) => match parent {
+            Some("tcp-inlet") => Some(CompletionKind::Inlets),
+            Some("tcp-outlet") => Some(CompletionKind::Outlets),
+            _ => None,
+        },
+        ("name", _) if arg.is_positional() => match parent {
+            Some("node") => Some(CompletionKind::Nodes),
+            Some("identity") => Some(CompletionKind::Identities),
+            Some("vault") => Some(CompletionKind::Vaults),
+            Some("project") => Some(CompletionKind::Projects),
+            Some("space") => Some(CompletionKind::Spaces),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Return the script completing the dynamic arguments of a command for a given shell.
+///
+/// The script is appended to the static script generated by `clap_complete`, and
+/// calls the hidden `__complete` subcommand to get the candidates.
+pub(crate) fn dynamic_script(shell: Shell, command: &Command) -> Option<String> {
+    let arguments = dynamic_arguments(command);
+    let paths = subcommand_paths(command, &arguments);
+    let name = command.get_name();
+    match shell {
+        Shell::Bash => Some(bash_script(name, &arguments, &paths)),
+        Shell::Zsh => Some(zsh_script(name, &arguments, &paths)),
+        Shell::Fish => Some(fish_script(name, &arguments, &paths)),
+        _ => None,
+    }
+}
+
+/// Return the `case` arms used to follow the subcommands typed on the command line
+fn path_arms(paths: &BTreeMap<String, Vec<String>>, indent: &str) -> String {
+    paths
+        .iter()
+        .map(|(path, names)| {
+            let patterns = names
+                .iter()
+                .map(|n| format!("\"{n}\""))
+                .collect::<Vec<_>>()
+                .join("|");
+            format!("{indent}{patterns}) next=\"{path}\" ;;\n")
+        })
+        .collect()
+}
+
+/// Return the `case` arms used to find the kind of value expected after a flag
+/// or as the first positional argument of a subcommand
+fn kind_arms(arguments: &[DynamicArgument], indent: &str, end: &str) -> String {
+    arguments
+        .iter()
+        .map(|a| {
+            format!(
+                "{indent}\"{}|{}\") echo {}{end}\n",
+                a.path,
+                a.flag,
+                a.kind.as_str()
+            )
+        })
+        .collect()
+}
+
+fn bash_script(
+    name: &str,
+    arguments: &[DynamicArgument],
+    paths: &BTreeMap<String, Vec<String>>,
+) -> String {
+    format!(
+        r#"
+_{name}_dynamic_kind() {{
+    local subcommand="{name}" prev="" next word
+    for word in "${{COMP_WORDS[@]:1:COMP_CWORD-1}}"; do
+        next=""
+        case "${{subcommand}} ${{word}}" in
+{path_arms}        esac
+        if [[ -n "${{next}}" ]]; then
+            subcommand="${{next}}"
+            prev=""
+        else
+            prev="${{word}}"
+        fi
+    done
+    [[ -z "${{prev}}" || "${{prev}}" == -* ]] || return 1
+    case "${{subcommand}}|${{prev}}" in
+{kind_arms}        *) return 1 ;;
+    esac
+}}
+
+_{name}_dynamic_node() {{
+    local i
+    for ((i = 1; i < COMP_CWORD - 1; i++)); do
+        if [[ "${{COMP_WORDS[i]}}" == "--at" ]]; then
+            echo "${{COMP_WORDS[i+1]}}"
+        fi
+    done
+}}
+
+_{name}_dynamic() {{
+    local kind
+    if kind="$(_{name}_dynamic_kind)"; then
+        COMPREPLY=($(compgen -W "$({name} __complete "${{kind}}" --node "$(_{name}_dynamic_node)" 2>/dev/null)" -- "${{COMP_WORDS[COMP_CWORD]}}"))
+        return 0
+    fi
+    _{name} "$@"
+}}
+
+if [[ "${{BASH_VERSINFO[0]}}" -eq 4 && "${{BASH_VERSINFO[1]}}" -ge 4 || "${{BASH_VERSINFO[0]}}" -gt 4 ]]; then
+    complete -F _{name}_dynamic -o nosort -o bashdefault -o default {name}
+else
+    complete -F _{name}_dynamic -o bashdefault -o default {name}
+fi
+"#,
+        path_arms = path_arms(paths, "            "),
+        kind_arms = kind_arms(arguments, "        ", " ;;"),
+    )
+}
+
+fn zsh_script(
+    name: &str,
+    arguments: &[DynamicArgument],
+    paths: &BTreeMap<String, Vec<String>>,
+) -> String {
+    format!(
+        r#"
+(( $+functions[_{name}_dynamic_kind] )) ||
+_{name}_dynamic_kind() {{
+    local subcommand="{name}" prev="" next word
+    for word in "${{(@)words[2,CURRENT-1]}}"; do
+        next=""
+        case "${{subcommand}} ${{word}}" in
+{path_arms}        esac
+        if [[ -n "${{next}}" ]]; then
+            subcommand="${{next}}"
+            prev=""
+        else
+            prev="${{word}}"
+        fi
+    done
+    [[ -z "${{prev}}" || "${{prev}}" == -* ]] || return 1
+    case "${{subcommand}}|${{prev}}" in
+{kind_arms}        *) return 1 ;;
+    esac
+}}
+
+(( $+functions[_{name}_dynamic_node] )) ||
+_{name}_dynamic_node() {{
+    local i
+    for ((i = 2; i < CURRENT - 1; i++)); do
+        if [[ "${{words[i]}}" == "--at" ]]; then
+            echo "${{words[i+1]}}"
+        fi
+    done
+}}
+
+(( $+functions[_{name}_dynamic] )) ||
+_{name}_dynamic() {{
+    local kind
+    local -a candidates
+    if kind="$(_{name}_dynamic_kind)"; then
+        candidates=("${{(@f)$({name} __complete "${{kind}}" --node "$(_{name}_dynamic_node)" 2>/dev/null)}}")
+        compadd -a candidates
+        return
+    fi
+    _{name} "$@"
+}}
+"#,
+        path_arms = path_arms(paths, "            "),
+        kind_arms = kind_arms(arguments, "        ", " ;;"),
+    )
+}
+
+fn fish_script(
+    name: &str,
+    arguments: &[DynamicArgument],
+    paths: &BTreeMap<String, Vec<String>>,
+) -> String {
+    let path_arms: String = paths
+        .iter()
+        .map(|(path, names)| {
+            let patterns = names
+                .iter()
+                .map(|n| format!("'{n}'"))
+                .collect::<Vec<_>>()
+                .join(" ");
+            format!("            case {patterns}\n                set next '{path}'\n")
+        })
+        .collect();
+    let kind_arms: String = arguments
+        .iter()
+        .map(|a| {
+            format!(
+                "        case '{}|{}'\n            echo {}\n            return 0\n",
+                a.path,
+                a.flag,
+                a.kind.as_str()
+            )
+        })
+        .collect();
+    let mut flags = arguments
+        .iter()
+        .filter_map(|a| a.flag.strip_prefix("--"))
+        .collect::<Vec<_>>();
+    flags.sort();
+    flags.dedup();
+    let flag_completions: String = flags
+        .iter()
+        .map(|flag| {
+            format!(
+                "complete -c {name} -n __fish_{name}_dynamic_kind -l {flag} -r -f -a '(__fish_{name}_dynamic_candidates)'\n"
+            )
+        })
+        .collect();
+    format!(
+        r#"
+function __fish_{name}_dynamic_kind
+    set -l tokens (commandline -opc)
+    set -l subcommand '{name}'
+    set -l prev ''
+    for word in $tokens[2..-1]
+        set -l next ''
+        switch "$subcommand $word"
+{path_arms}        end
+        if test -n "$next"
+            set subcommand $next
+            set prev ''
+        else
+            set prev $word
+        end
+    end
+    test -z "$prev"; or string match -q -- '-*' $prev; or return 1
+    switch "$subcommand|$prev"
+{kind_arms}    end
+    return 1
+end
+
+function __fish_{name}_dynamic_node
+    set -l tokens (commandline -opc)
+    set -l index (contains -i -- --at $tokens); and echo $tokens[(math $index + 1)]
+end
+
+function __fish_{name}_dynamic_candidates
+    set -l node (__fish_{name}_dynamic_node)
+    {name} __complete (__fish_{name}_dynamic_kind) --node "$node" 2>/dev/null
+end
+
+complete -c {name} -n __fish_{name}_dynamic_kind -f -a '(__fish_{name}_dynamic_candidates)'
+{flag_completions}"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OckamCommand;
+    use clap::CommandFactory;
+
+    fn find<'a>(
+        arguments: &'a [DynamicArgument],
+        path: &str,
+        flag: &str,
+    ) -> Option<&'a DynamicArgument> {
+        arguments.iter().find(|a| a.path == path && a.flag == flag)
+    }
+
+    #[test]
+    fn dynamic_arguments_are_found_from_the_command_definitions() {
+        let mut command = OckamCommand::command();
+        command.build();
+        let arguments = dynamic_arguments(&command);
+
+        let expected = [
+            ("ockam node show", "", CompletionKind::Nodes),
+            ("ockam tcp-inlet show", "--at", CompletionKind::Nodes),
+            ("ockam tcp-inlet show", "", CompletionKind::Inlets),
+            ("ockam tcp-inlet delete", "", CompletionKind::Inlets),
+            ("ockam tcp-outlet show", "", CompletionKind::Outlets),
+            ("ockam tcp-inlet create", "--via", CompletionKind::Relays),
+            ("ockam identity show", "", CompletionKind::Identities),
+            ("ockam project show", "", CompletionKind::Projects),
+            ("ockam project delete", "", CompletionKind::Spaces),
+        ];
+        for (path, flag, kind) in expected {
+            let argument = find(&arguments, path, flag)
+                .unwrap_or_else(|| panic!("no dynamic argument for '{path} {flag}'"));
+            assert_eq!(argument.kind, kind, "'{path} {flag}'");
+        }
+
+        // the names of new resources are not completed
+        assert!(find(&arguments, "ockam node create", "").is_none());
+        assert!(find(&arguments, "ockam tcp-inlet create", "--alias").is_none());
+        assert!(find(&arguments, "ockam project enroll", "").is_none());
+    }
+
+    #[test]
+    fn dynamic_scripts_are_generated_for_bash_zsh_and_fish() {
+        let mut command = OckamCommand::command();
+        command.build();
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish] {
+            let script = dynamic_script(shell, &command).unwrap();
+            assert!(script.contains("ockam __complete"), "{shell}");
+            assert!(script.contains("ockam tcp-inlet show|--at"), "{shell}");
+        }
+        assert!(dynamic_script(Shell::PowerShell, &command).is_none());
+    }
+}
@@ -1,7 +1,13 @@
 use crate::{docs, OckamCommand};
 use clap::{Args, CommandFactory};
 use clap_complete::{generate, Shell};
-use std::io;
+use miette::IntoDiagnostic;
+use std::io::{self, Write};
+
+pub(crate) mod complete;
+mod dynamic;
+
+pub(crate) use complete::CompleteCommand;
 
 const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
 const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
@@ -23,16 +29,37 @@ pub struct CompletionCommand {
 
 impl CompletionCommand {
     pub fn run(self) -> miette::Result<()> {
-        generate(
-            self.shell,
-            &mut OckamCommand::command(),
-            "ockam",
-            &mut io::stdout(),
-        );
-        Ok(())
+        let mut command = OckamCommand::command();
+        let mut script = vec![];
+        generate(self.shell, &mut command, "ockam", &mut script);
+        let script = String::from_utf8_lossy(&script);
+        let script = match dynamic::dynamic_script(self.shell, &command) {
+            Some(dynamic_script) => add_dynamic_script(self.shell, &script, &dynamic_script),
+            None => script.to_string(),
+        };
+        io::stdout().write_all(script.as_bytes()).into_diagnostic()
     }
 
     pub fn name(&self) -> String {
         "completion".to_string()
     }
 }
+
+/// Add the completion of values read from the local state to a generated script.
+///
+/// For zsh the functions must be defined before the script registers its completion
+/// function, since the whole file is used as the body of `_ockam` when it is autoloaded.
+fn add_dynamic_script(shell: Shell, script: &str, dynamic_script: &str) -> String {
+    const ZSH_REGISTRATION: &str = "if [ \"$funcstack[1]\" = \"_ockam\" ]; then";
+    match (shell, script.find(ZSH_REGISTRATION)) {
+        (Shell::Zsh, Some(index)) => {
+            let (definitions, registration) = script.split_at(index);
+            let registration = registration
+                .replace("    _ockam \"$@\"", "    _ockam_dynamic \"$@\"")
+                .replace("compdef _ockam ockam", "compdef _ockam_dynamic ockam");
+            format!("{definitions}{dynamic_script}\n{registration}")
+        }
+        (Shell::Zsh, None) => format!("{script}{dynamic_script}\ncompdef _ockam_dynamic ockam\n"),
+        _ => format!("{script}{dynamic_script}"),
+    }
+}
@@ -0,0 +1,427 @@
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use clap::ValueEnum;
+use miette::{miette, IntoDiagnostic};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use ockam_api::cli_state::CliDefaults;
+use ockam_api::logs::{is_exporting_set, OCKAM_OPENTELEMETRY_EXPORT};
+use ockam_api::nodes::DEFAULT_REQUEST_TIMEOUT;
+use ockam_core::env::{get_env, is_set, parse_duration};
+
+use crate::output::OutputFormat;
+use crate::GlobalArgs;
+
+/// Configuration file shared by all the users of the machine
+const SYSTEM_CONFIG_FILE: &str = "/etc/ockam/config.toml";
+
+/// Default values of the commands, read from a configuration file.
+/// All the values are optional
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    /// Node used by the commands when `--at` is not specified
+    pub node: Option<String>,
+    /// Identity used by the commands when `--identity` is not specified
+    pub identity: Option<String>,
+    /// Output format, `plain` or `json`
+    #[serde(default, deserialize_with = "deserialize_output_format")]
+    pub output: Option<OutputFormat>,
+    /// Set to false to output without any colors
+    pub color: Option<bool>,
+    /// Timeout of the requests sent to the local nodes, for example `30s`
+    #[serde(default, deserialize_with = "deserialize_duration")]
+    pub timeout: Option<Duration>,
+    /// Set to false to disable the export of traces and logs to OpenTelemetry
+    pub telemetry: Option<bool>,
+}
+
+impl ConfigFile {
+    /// Parse the content of a configuration file.
+    /// The error names the file and the line of the invalid entry
+    pub fn parse(path: &Path, content: &str) -> miette::Result<Self> {
+        toml::from_str(content).map_err(|e| match e.span() {
+            Some(span) => miette!(
+                "Invalid configuration file {}, line {}: {}",
+                path.display(),
+                content[..span.start].matches('\n').count() + 1,
+                e.message()
+            ),
+            None => miette!(
+                "Invalid configuration file {}: {}",
+                path.display(),
+                e.message()
+            ),
+        })
+    }
+}
+
+fn deserialize_output_format<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<OutputFormat>, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    OutputFormat::from_str(&value, true)
+        .map(Some)
+        .map_err(|_| D::Error::custom(format!("invalid output '{value}', expected plain or json")))
+}
+
+fn deserialize_duration<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Duration>, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    parse_duration(&value).map(Some).map_err(|_| {
+        D::Error::custom(format!(
+            "invalid duration '{value}', expected for example 30s or 2m"
+        ))
+    })
+}
+
+/// Configuration files read when a command starts.
+/// The values of a file override the values of the files before it
+#[derive(Debug, Clone, Default)]
+pub struct ConfigFiles {
+    files: Vec<(PathBuf, ConfigFile)>,
+}
+
+impl ConfigFiles {
+    /// Return the paths of the configuration files, in the order they are applied:
+    /// the file of the machine, then the file of the user
+    pub fn paths() -> Vec<PathBuf> {
+        let mut paths = vec![PathBuf::from(SYSTEM_CONFIG_FILE)];
+        let config_dir = match get_env::<String>("XDG_CONFIG_HOME").ok().flatten() {
+            Some(config_dir) => Some(PathBuf::from(config_dir)),
+            None => get_env::<String>("HOME")
+                .ok()
+                .flatten()
+                .map(|home| PathBuf::from(home).join(".config")),
+        };
+        if let Some(config_dir) = config_dir {
+            paths.push(config_dir.join("ockam").join("config.toml"));
+        }
+        paths
+    }
+
+    /// Read the existing configuration files
+    pub fn load() -> miette::Result<Self> {
+        let mut files = vec![];
+        for path in Self::paths() {
+            if !path.exists() {
+                continue;
+            }
+            let content = std::fs::read_to_string(&path).into_diagnostic()?;
+            let file = ConfigFile::parse(&path, &content)?;
+            files.push((path, file));
+        }
+        Ok(Self { files })
+    }
+
+    /// Return the last value set by a file, with the path of that file
+    fn value<T>(&self, get: impl Fn(&ConfigFile) -> Option<T>) -> Option<(T, ConfigSource)> {
+        self.files
+            .iter()
+            .rev()
+            .find_map(|(path, file)| get(file).map(|v| (v, ConfigSource::File(path.clone()))))
+    }
+
+    /// Resolve the configuration of a command: the command line arguments and the environment
+    /// variables override the values of the configuration files
+    pub fn resolve(&self, global_args: &GlobalArgs, arguments: &[String]) -> ResolvedConfig {
+        let node = match self.value(|f| f.node.clone()) {
+            Some((node, source)) => Resolved::new(Some(node), source),
+            None => Resolved::new(None, ConfigSource::Default),
+        };
+        let identity = match self.value(|f| f.identity.clone()) {
+            Some((identity, source)) => Resolved::new(Some(identity), source),
+            None => Resolved::new(None, ConfigSource::Default),
+        };
+
+        let output = if has_argument(arguments, "--output") {
+            Resolved::new(global_args.output_format.clone(), ConfigSource::CommandLine)
+        } else {
+            match self.value(|f| f.output.clone()) {
+                Some((output, source)) => Resolved::new(output, source),
+                None => Resolved::new(global_args.output_format.clone(), ConfigSource::Default),
+            }
+        };
+
+        let color = if has_argument(arguments, "--no-color") {
+            Resolved::new(false, ConfigSource::CommandLine)
+        } else if is_set::<String>("NO_COLOR").unwrap_or(false) {
+            Resolved::new(!global_args.no_color, ConfigSource::Environment("NO_COLOR"))
+        } else {
+            match self.value(|f| f.color) {
+                Some((color, source)) => Resolved::new(color, source),
+                None => Resolved::new(true, ConfigSource::Default),
+            }
+        };
+
+        let timeout = match self.value(|f| f.timeout) {
+            Some((timeout, source)) => Resolved::new(timeout, source),
+            None => Resolved::new(DEFAULT_REQUEST_TIMEOUT, ConfigSource::Default),
+        };
+
+        let telemetry = if is_set::<String>(OCKAM_OPENTELEMETRY_EXPORT).unwrap_or(false) {
+            Resolved::new(
+                is_exporting_set().unwrap_or(false),
+                ConfigSource::Environment(OCKAM_OPENTELEMETRY_EXPORT),
+            )
+        } else {
+            match self.value(|f| f.telemetry) {
+                Some((telemetry, source)) => Resolved::new(telemetry, source),
+                None => Resolved::new(is_exporting_set().unwrap_or(false), ConfigSource::Default),
+            }
+        };
+
+        ResolvedConfig {
+            node,
+            identity,
+            output,
+            color,
+            timeout,
+            telemetry,
+        }
+    }
+}
+
+/// Return true if an argument is present on the command line, as `--name` or `--name=value`
+fn has_argument(arguments: &[String], name: &str) -> bool {
+    arguments
+        .iter()
+        .any(|a| a == name || a.starts_with(&format!("{name}=")))
+}
+
+/// Where a configuration value comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    Default,
+    File(PathBuf),
+    Environment(&'static str),
+    CommandLine,
+}
+
+impl Display for ConfigSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigSource::Default => write!(f, "default"),
+            ConfigSource::File(path) => write!(f, "{}", path.display()),
+            ConfigSource::Environment(name) => write!(f, "environment variable {name}"),
+            ConfigSource::CommandLine => write!(f, "command line"),
+        }
+    }
+}
+
+impl Serialize for ConfigSource {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// A configuration value, with its source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resolved<T> {
+    pub value: T,
+    pub source: ConfigSource,
+}
+
+impl<T> Resolved<T> {
+    fn new(value: T, source: ConfigSource) -> Self {
+        Self { value, source }
+    }
+
+    fn is_from_file(&self) -> bool {
+        matches!(self.source, ConfigSource::File(_))
+    }
+}
+
+/// Effective configuration of a command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedConfig {
+    pub node: Resolved<Option<String>>,
+    pub identity: Resolved<Option<String>>,
+    pub output: Resolved<OutputFormat>,
+    pub color: Resolved<bool>,
+    pub timeout: Resolved<Duration>,
+    pub telemetry: Resolved<bool>,
+}
+
+impl ResolvedConfig {
+    /// Set the output format and colors of the global arguments
+    pub fn apply(&self, global_args: &GlobalArgs) -> GlobalArgs {
+        let mut global_args = global_args.clone();
+        global_args.output_format = self.output.value.clone();
+        global_args.no_color = !self.color.value;
+        global_args
+    }
+
+    /// Export the telemetry setting of a configuration file as an environment variable, so that
+    /// it also applies to the nodes started by the command
+    pub fn apply_telemetry(&self) {
+        if self.telemetry.is_from_file() {
+            std::env::set_var(OCKAM_OPENTELEMETRY_EXPORT, self.telemetry.value.to_string());
+        }
+    }
+
+    /// Return the values used by the local state when a command does not specify them
+    pub fn cli_defaults(&self) -> CliDefaults {
+        CliDefaults {
+            node_name: self.node.value.clone(),
+            identity_name: self.identity.value.clone(),
+            request_timeout: self.timeout.is_from_file().then_some(self.timeout.value),
+        }
+    }
+
+    /// Return each configuration value as a name, a displayable value and a source
+    pub fn entries(&self) -> Vec<ConfigEntry> {
+        let entry = |name: &str, value: String, source: &ConfigSource| ConfigEntry {
+            name: name.to_string(),
+            value,
+            source: source.clone(),
+        };
+        vec![
+            entry(
+                "node",
+                self.node
+                    .value
+                    .clone()
+                    .unwrap_or("(the default node)".to_string()),
+                &self.node.source,
+            ),
+            entry(
+                "identity",
+                self.identity
+                    .value
+                    .clone()
+                    .unwrap_or("(the default identity)".to_string()),
+                &self.identity.source,
+            ),
+            entry(
+                "output",
+                self.output
+                    .value
+                    .to_possible_value()
+                    .map(|v| v.get_name().to_string())
+                    .unwrap_or_default(),
+                &self.output.source,
+            ),
+            entry("color", self.color.value.to_string(), &self.color.source),
+            entry(
+                "timeout",
+                format!("{}s", self.timeout.value.as_secs_f64()),
+                &self.timeout.source,
+            ),
+            entry(
+                "telemetry",
+                self.telemetry.value.to_string(),
+                &self.telemetry.source,
+            ),
+        ]
+    }
+}
+
+/// Configuration value, as displayed by `ockam config show --resolved`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConfigEntry {
+    pub name: String,
+    pub value: String,
+    pub source: ConfigSource,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_configuration_file_can_be_parsed() {
+        let file = ConfigFile::parse(
+            Path::new("config.toml"),
+            r#"
+            node = "n1"
+            identity = "alice"
+            output = "json"
+            color = false
+            timeout = "1m"
+            telemetry = false
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            file,
+            ConfigFile {
+                node: Some("n1".to_string()),
+                identity: Some("alice".to_string()),
+                output: Some(OutputFormat::Json),
+                color: Some(false),
+                timeout: Some(Duration::from_secs(60)),
+                telemetry: Some(false),
+            }
+        );
+    }
+
+    #[test]
+    fn an_invalid_configuration_file_is_reported_with_its_line() {
+        let path = Path::new("/etc/ockam/config.toml");
+        let error = ConfigFile::parse(path, "node = \"n1\"\ntimeout = \"soon\"\n").unwrap_err();
+        assert!(error.to_string().starts_with(
+            "Invalid configuration file /etc/ockam/config.toml, line 2: invalid duration 'soon'"
+        ));
+
+        let error =
+            ConfigFile::parse(path, "node = \"n1\"\n\ndefault_node = \"n2\"\n").unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("Invalid configuration file /etc/ockam/config.toml, line 3: unknown field `default_node`"));
+    }
+
+    #[test]
+    fn later_files_and_command_line_arguments_take_precedence() {
+        let system = PathBuf::from("/etc/ockam/config.toml");
+        let user = PathBuf::from("/home/user/.config/ockam/config.toml");
+        let files = ConfigFiles {
+            files: vec![
+                (
+                    system.clone(),
+                    ConfigFile {
+                        node: Some("gateway".to_string()),
+                        identity: Some("team".to_string()),
+                        output: Some(OutputFormat::Json),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    user.clone(),
+                    ConfigFile {
+                        identity: Some("alice".to_string()),
+                        timeout: Some(Duration::from_secs(5)),
+                        ..Default::default()
+                    },
+                ),
+            ],
+        };
+
+        let config = files.resolve(&GlobalArgs::default(), &["ockam".to_string()]);
+        assert_eq!(
+            config.node,
+            Resolved::new(Some("gateway".to_string()), ConfigSource::File(system))
+        );
+        assert_eq!(
+            config.identity,
+            Resolved::new(Some("alice".to_string()), ConfigSource::File(user.clone()))
+        );
+        assert_eq!(config.output.value, OutputFormat::Json);
+        assert_eq!(
+            config.cli_defaults().request_timeout,
+            Some(Duration::from_secs(5))
+        );
+
+        // the default output format is plain
+        let arguments = ["ockam", "node", "list", "--output", "plain"].map(|a| a.to_string());
+        let config = files.resolve(&GlobalArgs::default(), &arguments);
+        assert_eq!(
+            config.output,
+            Resolved::new(OutputFormat::Plain, ConfigSource::CommandLine)
+        );
+    }
+}
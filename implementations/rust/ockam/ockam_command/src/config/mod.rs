@@ -0,0 +1,44 @@
+mod file;
+mod show;
+
+pub use file::*;
+pub use show::ShowCommand;
+
+use clap::{Args, Subcommand};
+
+use crate::{docs, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/after_long_help.txt");
+
+/// Show the configuration files setting the default values of the commands
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    subcommand_required = true,
+    long_about = docs::about(LONG_ABOUT),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct ConfigCommand {
+    #[command(subcommand)]
+    pub subcommand: ConfigSubcommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum ConfigSubcommand {
+    Show(ShowCommand),
+}
+
+impl ConfigCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        match self.subcommand {
+            ConfigSubcommand::Show(c) => c.run(opts),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match &self.subcommand {
+            ConfigSubcommand::Show(c) => c.name(),
+        }
+    }
+}
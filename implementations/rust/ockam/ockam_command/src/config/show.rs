@@ -0,0 +1,73 @@
+use std::fmt::Write;
+
+use async_trait::async_trait;
+use clap::Args;
+
+use ockam_node::Context;
+
+use crate::config::ConfigFiles;
+use crate::{color_primary, fmt_log, Command, CommandGlobalOpts};
+
+/// Show the configuration files, or the effective configuration of the commands
+#[derive(Clone, Debug, Args)]
+pub struct ShowCommand {
+    /// Show the effective value of each setting, and where it comes from: a configuration file,
+    /// an environment variable, the command line or the default value
+    #[arg(long)]
+    pub resolved: bool,
+}
+
+#[async_trait]
+impl Command for ShowCommand {
+    const NAME: &'static str = "config show";
+
+    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        if self.resolved {
+            let entries = opts.config.entries();
+            let width = entries
+                .iter()
+                .map(|e| e.name.len())
+                .max()
+                .unwrap_or_default();
+            let mut plain = fmt_log!("Effective configuration:\n");
+            for entry in &entries {
+                let _ = writeln!(
+                    plain,
+                    "{}",
+                    fmt_log!(
+                        "  {:width$}  {} ({})",
+                        entry.name,
+                        color_primary(&entry.value),
+                        entry.source
+                    )
+                );
+            }
+            opts.terminal
+                .stdout()
+                .plain(plain)
+                .json(serde_json::json!(entries))
+                .write_line()?;
+        } else {
+            let paths = ConfigFiles::paths();
+            let mut plain =
+                fmt_log!("Configuration files, the later ones override the earlier ones:\n");
+            for path in &paths {
+                let status = if path.exists() { "" } else { " (not found)" };
+                let _ = writeln!(
+                    plain,
+                    "{}",
+                    fmt_log!("  {}{status}", color_primary(path.display().to_string()))
+                );
+            }
+            opts.terminal
+                .stdout()
+                .plain(plain)
+                .json(serde_json::json!(paths
+                    .iter()
+                    .map(|p| serde_json::json!({ "path": p, "exists": p.exists() }))
+                    .collect::<Vec<_>>()))
+                .write_line()?;
+        }
+        Ok(())
+    }
+}
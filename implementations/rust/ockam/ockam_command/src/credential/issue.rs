@@ -6,10 +6,12 @@ use ockam::identity::Identifier;
 use ockam_api::authenticator::credential_issuer::PROJECT_MEMBER_SCHEMA;
 use ockam_core::compat::collections::HashMap;
 
-use crate::output::{CredentialAndPurposeKeyDisplay, EncodeFormat};
+use crate::output::{human_readable_time, CredentialAndPurposeKeyDisplay, EncodeFormat};
 use crate::util::async_cmd;
 use crate::util::duration::duration_parser;
-use crate::{util::parsers::identity_identifier_parser, CommandGlobalOpts, Result};
+use crate::{
+    color_primary, fmt_log, util::parsers::identity_identifier_parser, CommandGlobalOpts, Result,
+};
 
 #[derive(Clone, Debug, Args)]
 pub struct IssueCommand {
@@ -32,7 +34,8 @@ pub struct IssueCommand {
     #[arg(long = "encoding", value_enum, default_value = "plain")]
     encode_format: EncodeFormat,
 
-    #[arg(long, value_name = "TTL", default_value = "30m", value_parser = duration_parser)]
+    /// Time-to-live of the credential, for example 15m or 1h
+    #[arg(long, visible_alias = "credential-ttl", value_name = "TTL", default_value = "30m", value_parser = duration_parser)]
     ttl: std::time::Duration,
 }
 
@@ -90,6 +93,11 @@ impl IssueCommand {
             .await
             .into_diagnostic()?;
 
+        let expires_at = credential.get_expires_at().into_diagnostic()?;
+        opts.terminal.write_line(&fmt_log!(
+            "The credential expires at {}.",
+            color_primary(human_readable_time(expires_at))
+        ))?;
         self.encode_format
             .println_value(&CredentialAndPurposeKeyDisplay(credential))?;
 
@@ -4,8 +4,8 @@ use miette::IntoDiagnostic;
 
 use ockam::identity::{CredentialSqlxDatabase, Identifier};
 
-use crate::credential::CredentialOutput;
 use crate::node::NodeOpts;
+use crate::output::CredentialOutput;
 use crate::util::async_cmd;
 use crate::util::parsers::identity_identifier_parser;
 use crate::Result;
@@ -63,7 +63,9 @@ impl ListCommand {
             ),
         )?;
 
-        opts.terminal.stdout().plain(list).write_line()?;
+        let json = serde_json::to_string_pretty(&credentials).into_diagnostic()?;
+
+        opts.terminal.stdout().plain(list).json(json).write_line()?;
 
         Ok(())
     }
@@ -1,32 +1,55 @@
+use std::collections::BTreeMap;
 use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::Arc;
 
-use clap::Args;
+use clap::{ArgGroup, Args};
 use colorful::Colorful;
 use miette::{miette, IntoDiagnostic};
 use tokio::{sync::Mutex, try_join};
 
-use ockam::identity::models::CredentialAndPurposeKey;
+use ockam::identity::models::{CredentialAndPurposeKey, CredentialData};
+use ockam::identity::utils::now;
 use ockam::identity::{
     ChangeHistoryRepository, ChangeHistorySqlxDatabase, CredentialsVerification, Identifier,
-    PurposeKeyVerification,
+    IdentitiesVerification, PurposeKeyVerification, TimestampInSeconds,
 };
 use ockam_vault::{SoftwareVaultForVerifyingSignatures, VaultForVerifyingSignatures};
 
+use crate::error::{CommandError, ErrorCode};
+use crate::output::human_readable_time;
 use crate::util::async_cmd;
-use crate::util::parsers::identity_identifier_parser;
-use crate::{fmt_err, fmt_log, fmt_ok, CommandGlobalOpts};
+use crate::util::parsers::timestamp_parser;
+use crate::{color_primary, docs, fmt_log, fmt_ok, CommandGlobalOpts};
 
+const LONG_ABOUT: &str = include_str!("./static/verify/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/verify/after_long_help.txt");
+
+/// Verify a credential issued by a given identity
 #[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP),
+)]
+#[clap(group(ArgGroup::new("credential_value").required(true).args(["credential", "credential_path"])))]
 pub struct VerifyCommand {
-    #[arg(long = "issuer", value_name = "IDENTIFIER", value_parser = identity_identifier_parser)]
-    pub issuer: Identifier,
+    /// Identifier of the issuer, when its identity is known locally. Otherwise, the hex-encoded
+    /// identity of the issuer, or `@` followed by the path of a file containing it
+    #[arg(long = "issuer", value_name = "IDENTIFIER_OR_IDENTITY")]
+    pub issuer: String,
 
-    #[arg(group = "credential_value", value_name = "CREDENTIAL_STRING", long)]
+    /// Hex-encoded credential, or `@` followed by the path of a file containing it
+    #[arg(value_name = "CREDENTIAL", long)]
     pub credential: Option<String>,
 
-    #[arg(group = "credential_value", value_name = "CREDENTIAL_FILE", long)]
+    /// Path of a file containing the hex-encoded credential
+    #[arg(value_name = "CREDENTIAL_FILE", long)]
     pub credential_path: Option<PathBuf>,
+
+    /// Time at which the credential must be valid, instead of the current time.
+    /// Either an RFC 3339 date, for example 2024-01-31T10:00:00Z, or a Unix timestamp in seconds
+    #[arg(long, value_name = "TIME", value_parser = timestamp_parser)]
+    pub at_time: Option<TimestampInSeconds>,
 }
 
 impl VerifyCommand {
@@ -40,35 +63,245 @@ impl VerifyCommand {
         "credential verify".into()
     }
 
-    pub fn issuer(&self) -> &Identifier {
-        &self.issuer
-    }
-
     async fn async_run(&self, opts: CommandGlobalOpts) -> miette::Result<()> {
-        let (is_valid, plain_text) = match verify_credential(
-            &opts,
-            self.issuer(),
-            &self.credential,
-            &self.credential_path,
+        let credential_as_str = read_credential(&self.credential, &self.credential_path).await?;
+        let credential = decode_credential(&credential_as_str).map_err(|e| {
+            CommandError::with_code(
+                e.wrap_err("The credential could not be decoded"),
+                ErrorCode::InvalidInput,
+            )
+        })?;
+        let (change_history_repository, issuer) = self.issuer_identity(&opts).await?;
+        let verifying_vault = SoftwareVaultForVerifyingSignatures::create();
+
+        let invalid_credential = |e: ockam_core::Error| {
+            CommandError::with_code(
+                miette!("The credential could not be decoded: {e}"),
+                ErrorCode::InvalidInput,
+            )
+        };
+        let purpose_key_data = credential
+            .purpose_key_attestation
+            .get_attestation_data()
+            .map_err(invalid_credential)?;
+        if purpose_key_data.subject != issuer {
+            return Err(CommandError::with_code(
+                miette!(
+                    "The credential was issued by {}, not by {}",
+                    color_primary(purpose_key_data.subject.to_string()),
+                    color_primary(issuer.to_string())
+                ),
+                ErrorCode::Unauthorized,
+            ))?;
+        }
+
+        // The signatures are checked at the creation time of the credential, so that an
+        // expired credential is reported as such, and not as a credential with a bad signature
+        let created_at = credential
+            .credential
+            .get_credential_data()
+            .map_err(invalid_credential)?
+            .created_at;
+        let credential_data = CredentialsVerification::verify_credential_static_at(
+            Arc::new(PurposeKeyVerification::new(
+                verifying_vault.clone(),
+                change_history_repository,
+            )),
+            verifying_vault,
+            None,
+            &[issuer.clone()],
+            &credential,
+            created_at,
         )
         .await
-        {
-            Ok(_) => (true, fmt_ok!("Credential is valid")),
-            Err(e) => (
-                false,
-                fmt_err!("Credential is not valid\n") + &fmt_log!("{}", e),
-            ),
+        .map_err(|e| {
+            CommandError::with_code(
+                miette!("The signature of the credential is not valid: {e}"),
+                ErrorCode::InvalidSignature,
+            )
+        })?
+        .credential_data;
+
+        let at_time = match self.at_time {
+            Some(at_time) => at_time,
+            None => now().into_diagnostic()?,
         };
+        check_validity_period(&credential_data, at_time)?;
 
+        let subject = credential_data
+            .subject
+            .map(|s| s.to_string())
+            .unwrap_or_default();
+        let attributes = attributes_output(&credential_data);
         opts.terminal
             .stdout()
-            .plain(plain_text)
-            .json(serde_json::json!({ "is_valid": is_valid }))
-            .machine(is_valid.to_string())
+            .plain(
+                fmt_ok!("The credential is valid\n")
+                    + &fmt_log!("Subject: {}\n", color_primary(&subject))
+                    + &fmt_log!("Issuer: {}\n", color_primary(issuer.to_string()))
+                    + &fmt_log!(
+                        "Attributes: {}\n",
+                        attributes
+                            .iter()
+                            .map(|(k, v)| format!("{k}={v}"))
+                            .collect::<Vec<_>>()
+                            .join(",")
+                    )
+                    + &fmt_log!(
+                        "Issued at: {}\n",
+                        human_readable_time(credential_data.created_at)
+                    )
+                    + &fmt_log!(
+                        "Expires at: {}",
+                        human_readable_time(credential_data.expires_at)
+                    ),
+            )
+            .json(serde_json::json!({
+                "is_valid": true,
+                "subject": subject,
+                "issuer": issuer,
+                "attributes": attributes,
+                "created_at": credential_data.created_at.0,
+                "expires_at": credential_data.expires_at.0,
+            }))
+            .machine(true.to_string())
             .write_line()?;
 
         Ok(())
     }
+
+    /// Return the identifier of the issuer and a repository containing its identity.
+    /// An identity passed inline is only stored in memory
+    async fn issuer_identity(
+        &self,
+        opts: &CommandGlobalOpts,
+    ) -> miette::Result<(Arc<dyn ChangeHistoryRepository>, Identifier)> {
+        let identity = match self.issuer.strip_prefix('@') {
+            Some(path) => tokio::fs::read_to_string(path)
+                .await
+                .into_diagnostic()?
+                .trim()
+                .to_string(),
+            None => {
+                if let Ok(identifier) = Identifier::from_str(&self.issuer) {
+                    let repository = ChangeHistorySqlxDatabase::new(opts.state.database());
+                    if repository
+                        .get_change_history(&identifier)
+                        .await
+                        .into_diagnostic()?
+                        .is_none()
+                    {
+                        return Err(CommandError::with_code(
+                            miette!(
+                                "The issuer {} is unknown. Provide its identity with --issuer @<identity-file>",
+                                color_primary(identifier.to_string())
+                            ),
+                            ErrorCode::NotFound,
+                        ))?;
+                    }
+                    return Ok((Arc::new(repository), identifier));
+                }
+                self.issuer.clone()
+            }
+        };
+
+        let repository = Arc::new(
+            ChangeHistorySqlxDatabase::create()
+                .await
+                .into_diagnostic()?,
+        );
+        let identity = hex::decode(identity).map_err(|_| {
+            CommandError::with_code(
+                miette!("The issuer identity must be hex-encoded"),
+                ErrorCode::InvalidInput,
+            )
+        })?;
+        let identifier = IdentitiesVerification::new(
+            repository.clone(),
+            SoftwareVaultForVerifyingSignatures::create(),
+        )
+        .import(None, &identity)
+        .await
+        .map_err(|e| {
+            CommandError::with_code(
+                miette!("The issuer identity is not valid: {e}"),
+                ErrorCode::InvalidInput,
+            )
+        })?;
+        Ok((repository, identifier))
+    }
+}
+
+/// Check that a credential can be used at a given time
+fn check_validity_period(
+    credential_data: &CredentialData,
+    at_time: TimestampInSeconds,
+) -> miette::Result<()> {
+    if at_time < credential_data.created_at {
+        return Err(CommandError::with_code(
+            miette!(
+                "The credential is not valid before {}",
+                human_readable_time(credential_data.created_at)
+            ),
+            ErrorCode::Expired,
+        ))?;
+    }
+    if at_time > credential_data.expires_at {
+        return Err(CommandError::with_code(
+            miette!(
+                "The credential expired at {}",
+                human_readable_time(credential_data.expires_at)
+            ),
+            ErrorCode::Expired,
+        ))?;
+    }
+    Ok(())
+}
+
+/// Return the attributes of a credential, skipping the attributes which are not valid UTF-8 strings
+fn attributes_output(credential_data: &CredentialData) -> BTreeMap<String, String> {
+    credential_data
+        .subject_attributes
+        .map
+        .iter()
+        .filter_map(|(k, v)| {
+            match (
+                String::from_utf8(k.as_slice().to_vec()),
+                String::from_utf8(v.as_slice().to_vec()),
+            ) {
+                (Ok(k), Ok(v)) => Some((k, v)),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Return the hex-encoded credential passed as an argument, or read from a file
+async fn read_credential(
+    credential: &Option<String>,
+    credential_path: &Option<PathBuf>,
+) -> miette::Result<String> {
+    let path = match (credential, credential_path) {
+        (_, Some(credential_path)) => credential_path.clone(),
+        (Some(credential), _) => match credential.strip_prefix('@') {
+            Some(path) => PathBuf::from(path),
+            None => return Ok(credential.trim().to_string()),
+        },
+        _ => {
+            return Err(miette!(
+                "Credential or Credential Path argument must be provided"
+            ))
+        }
+    };
+    Ok(tokio::fs::read_to_string(path)
+        .await
+        .into_diagnostic()?
+        .trim()
+        .to_string())
+}
+
+fn decode_credential(credential_as_str: &str) -> miette::Result<CredentialAndPurposeKey> {
+    minicbor::decode(&hex::decode(credential_as_str).into_diagnostic()?).into_diagnostic()
 }
 
 pub async fn verify_credential(
@@ -83,18 +316,11 @@ pub async fn verify_credential(
     let is_finished: Mutex<bool> = Mutex::new(false);
 
     let send_req = async {
-        let credential_as_str = match (&credential, &credential_path) {
-            (_, Some(credential_path)) => tokio::fs::read_to_string(credential_path)
-                .await
-                .into_diagnostic()?
-                .trim()
-                .to_string(),
-            (Some(credential), _) => credential.clone(),
-            _ => {
+        let credential_as_str = match read_credential(credential, credential_path).await {
+            Ok(credential_as_str) => credential_as_str,
+            Err(e) => {
                 *is_finished.lock().await = true;
-                return Err(miette!(
-                    "Credential or Credential Path argument must be provided"
-                ));
+                return Err(e);
             }
         };
 
@@ -129,8 +355,7 @@ async fn validate_encoded_credential(
     issuer: &Identifier,
     credential_as_str: &str,
 ) -> miette::Result<CredentialAndPurposeKey> {
-    let credential_and_purpose_key: CredentialAndPurposeKey =
-        minicbor::decode(&hex::decode(credential_as_str).into_diagnostic()?).into_diagnostic()?;
+    let credential_and_purpose_key = decode_credential(credential_as_str)?;
     CredentialsVerification::verify_credential_static(
         Arc::new(PurposeKeyVerification::new(
             verifying_vault.clone(),
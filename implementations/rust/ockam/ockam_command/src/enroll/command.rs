@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::process;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use clap::Args;
 use colorful::Colorful;
@@ -11,6 +12,7 @@ use tokio::try_join;
 use tracing::{error, info, instrument, warn};
 
 use ockam::Context;
+use ockam_api::cli_state::enrollments::EnrollmentTicket;
 use ockam_api::cli_state::random_name;
 use ockam_api::cloud::enroll::auth0::*;
 use ockam_api::cloud::project::Project;
@@ -22,15 +24,19 @@ use ockam_api::enroll::oidc_service::OidcService;
 use ockam_api::journeys::{JourneyEvent, USER_EMAIL, USER_NAME};
 use ockam_api::nodes::InMemoryNode;
 use ockam_api::CliState;
+use ockam_core::env::get_env;
 
 use crate::enroll::OidcServiceExt;
-use crate::error::Error;
+use crate::error::{CommandError, Error, ErrorCode};
 use crate::operation::util::check_for_project_completion;
-use crate::output::OutputFormat;
+use crate::output::{human_readable_time, OutputFormat};
 use crate::progress_display::ProgressDisplay;
+use crate::project::enroll::enroll_with_ticket;
 use crate::project::util::check_project_readiness;
 use crate::terminal::{color_primary, color_uri, OckamColor};
 use crate::util::async_cmd;
+use crate::util::duration::duration_parser;
+use crate::value_parsers::parse_enrollment_ticket;
 use crate::{docs, fmt_heading, fmt_log, fmt_ok, fmt_warn, CommandGlobalOpts, Result};
 
 use r3bl_rs_utils_core::UnicodeString;
@@ -41,6 +47,9 @@ use r3bl_tui::{
 const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
 const AFTER_LONG_HELP: &str = include_str!("./static/after_long_help.txt");
 
+/// Environment variable which can be used instead of the `--ticket` argument
+const OCKAM_ENROLLMENT_TICKET: &str = "OCKAM_ENROLLMENT_TICKET";
+
 /// Enroll your Ockam Identity with Ockam Orchestrator
 #[derive(Clone, Debug, Args)]
 #[command(
@@ -76,6 +85,20 @@ pub struct EnrollCommand {
     /// will continue without creating them.
     #[arg(hide = true, long = "skip-resource-creation", conflicts_with = "force")]
     pub skip_orchestrator_resources_creation: bool,
+
+    /// Path, URL or inlined hex-encoded enrollment ticket. When a ticket is given, the Identity
+    /// is enrolled with the project of the ticket, without opening a web browser. This is useful
+    /// to set up a new machine, like a server. The ticket can also be given with the
+    /// `OCKAM_ENROLLMENT_TICKET` environment variable
+    #[arg(long, value_name = "ENROLLMENT TICKET", value_parser = parse_enrollment_ticket, conflicts_with = "authorization_code_flow")]
+    pub ticket: Option<EnrollmentTicket>,
+
+    /// Time-to-live of the credential issued when enrolling with an enrollment ticket, for
+    /// example 15m or 1h. By default, the time-to-live configured on the project authority is
+    /// used. A longer time-to-live than the maximum allowed by the authority is reduced to that
+    /// maximum
+    #[arg(long = "credential-ttl", value_name = "DURATION", value_parser = duration_parser)]
+    pub credential_ttl: Option<Duration>,
 }
 
 impl EnrollCommand {
@@ -90,11 +113,18 @@ impl EnrollCommand {
     }
 
     async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        if let Some(ticket) = self.enrollment_ticket()? {
+            return self.enroll_with_ticket(ctx, &opts, ticket).await;
+        }
         if opts.global_args.output_format == OutputFormat::Json {
-            return Err(miette::miette!(
-            "This command is interactive and requires you to open a web browser to complete enrollment. \
-            Please try running it again without '--output json'."
-        ));
+            return Err(CommandError::with_code(
+                miette!(
+                    "This command is interactive and requires you to open a web browser to complete enrollment. \
+                    Please try running it again without '--output json'."
+                ),
+                ErrorCode::Usage,
+            )
+            .into());
         }
         self.run_impl(ctx, opts.clone()).await?;
         Ok(())
@@ -127,7 +157,10 @@ impl EnrollCommand {
 
         let identity_name = identity.name();
         let identifier = identity.identifier();
-        let node = InMemoryNode::start_with_identity(ctx, &opts.state, &identity_name).await?;
+        let node = InMemoryNode::builder()
+            .identity_name(&identity_name)
+            .build(ctx, &opts.state)
+            .await?;
 
         let user_info = self.enroll_identity(ctx, &opts, &node).await?;
 
@@ -199,6 +232,107 @@ impl EnrollCommand {
         Ok(())
     }
 
+    /// Return the enrollment ticket given as an argument, or with the `OCKAM_ENROLLMENT_TICKET`
+    /// environment variable
+    fn enrollment_ticket(&self) -> miette::Result<Option<EnrollmentTicket>> {
+        if let Some(ticket) = &self.ticket {
+            return Ok(Some(ticket.clone()));
+        }
+        match get_env::<String>(OCKAM_ENROLLMENT_TICKET).into_diagnostic()? {
+            Some(ticket) => Ok(Some(parse_enrollment_ticket(&ticket).wrap_err(format!(
+                "The {OCKAM_ENROLLMENT_TICKET} environment variable is not a valid enrollment ticket"
+            ))?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Enroll the Identity with the project of an enrollment ticket, without any interaction:
+    ///
+    ///  1. create the default Vault and Identity, if they don't exist yet
+    ///  2. import the project of the ticket
+    ///  3. present the one-time code of the ticket to the project authority and retrieve a
+    ///     credential, like `ockam project enroll`
+    ///
+    /// Every step can be run again after a failure. In particular, the one-time code is not
+    /// used again if the Identity is already a member of the project
+    #[instrument(skip_all, fields(enroller = ? self.identity))]
+    async fn enroll_with_ticket(
+        &self,
+        ctx: &Context,
+        opts: &CommandGlobalOpts,
+        ticket: EnrollmentTicket,
+    ) -> miette::Result<()> {
+        let retry = |step: &str| {
+            format!(
+                "Failed to {step}. Your local state was kept, please run {} again to resume the enrollment",
+                color_primary("ockam enroll --ticket")
+            )
+        };
+
+        let identity = opts
+            .state
+            .get_named_identity_or_default(&self.identity)
+            .await
+            .wrap_err(retry("create the Identity to enroll"))?;
+
+        let project = ticket
+            .project
+            .clone()
+            .ok_or(miette!("The enrollment ticket does not contain a project"))?;
+        let project = opts
+            .state
+            .projects()
+            .import_and_store_project(project)
+            .await
+            .wrap_err(retry("import the project of the enrollment ticket"))?;
+
+        let node = InMemoryNode::builder()
+            .identity_name(&identity.name())
+            .project_name(project.name())
+            .build(ctx, &opts.state)
+            .await
+            .wrap_err(retry("start a node to enroll with the project"))?;
+        let authority_node_client = node
+            .create_authority_client(&project, Some(identity.name()))
+            .await
+            .wrap_err(retry("connect to the project authority"))?;
+        let credential = enroll_with_ticket(
+            ctx,
+            opts,
+            &authority_node_client,
+            &ticket,
+            self.credential_ttl,
+        )
+        .await
+        .wrap_err(retry("enroll with the project authority"))?;
+        let expires_at = credential
+            .get_credential_data()
+            .into_diagnostic()?
+            .expires_at;
+
+        opts.terminal
+            .stdout()
+            .plain(
+                fmt_ok!(
+                    "Your Identity {}, with Identifier {} is now enrolled with the {} project.\n",
+                    color_primary(identity.name()),
+                    color_primary(identity.identifier().to_string()),
+                    color_primary(project.name())
+                ) + &fmt_log!(
+                    "Its credential expires at {}.",
+                    color_primary(human_readable_time(expires_at))
+                ),
+            )
+            .json(serde_json::json!({
+                "identity": identity.name(),
+                "identifier": identity.identifier().to_string(),
+                "project": project.name(),
+                "credential_expires_at": human_readable_time(expires_at),
+            }))
+            .write_line()?;
+        Ok(())
+    }
+
     /// Check if the identity is already enrolled and display a message to the user.
     async fn is_already_enrolled(
         &self,
@@ -351,7 +485,9 @@ async fn retrieve_user_space_and_project(
     let space = get_user_space(opts, ctx, node, skip_orchestrator_resources_creation)
         .await
         .wrap_err("Unable to retrieve and set a Space as default")?
-        .ok_or(miette!("No Space was found"))?;
+        .ok_or_else(|| {
+            CommandError::with_code(miette!("No Space was found"), ErrorCode::NotFound)
+        })?;
     let project = get_user_project(
         opts,
         ctx,
@@ -364,7 +500,7 @@ async fn retrieve_user_space_and_project(
         "Unable to retrieve and set a Project as default with Space {}",
         color_primary(&space.name)
     ))?
-    .ok_or(miette!("No Project was found"))?;
+    .ok_or_else(|| CommandError::with_code(miette!("No Project was found"), ErrorCode::NotFound))?;
     opts.terminal.write_line(fmt_heading!(""))?;
     Ok(project)
 }
@@ -15,7 +15,7 @@ use ockam_api::enroll::oidc_service::OidcService;
 
 use crate::{
     fmt_err, fmt_heading, fmt_log,
-    terminal::{color_email, color_uri},
+    terminal::{color_email, color_uri, no_input_error},
     CommandGlobalOpts, Result, Terminal, TerminalStream,
 };
 use crate::{fmt_ok, terminal::OckamColor};
@@ -55,6 +55,13 @@ pub trait OidcServiceExt {
 impl OidcServiceExt for OidcService {
     #[instrument(skip_all)]
     async fn get_token_interactively(&self, opts: &CommandGlobalOpts) -> Result<OidcToken> {
+        // The user has to sign in with a browser, which can't be done if the user input is
+        // disabled. An enrollment ticket can be used instead
+        if opts.terminal.is_input_disabled() {
+            return Err(no_input_error(
+                "an enrollment ticket, with `ockam enroll --ticket <ticket>`,",
+            ))?;
+        }
         let device_code = self.device_code().await?;
 
         // On Linux, the clipboard is cleared when the record goes out of scope, so
@@ -246,3 +253,27 @@ impl OidcServiceExt for OidcService {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GlobalArgs;
+    use ockam_api::cli_state::CliState;
+
+    #[test]
+    fn the_token_is_not_retrieved_interactively_when_the_input_is_disabled() -> miette::Result<()> {
+        let rt = tokio::runtime::Runtime::new().into_diagnostic()?;
+        for quiet in [false, true] {
+            let state = rt.block_on(CliState::test())?;
+            let mut global_args = GlobalArgs::default();
+            global_args.quiet = quiet;
+            global_args.no_input = true;
+            let opts = CommandGlobalOpts::new_for_test(global_args, state);
+            let error = rt
+                .block_on(OidcService::default().get_token_interactively(&opts))
+                .unwrap_err();
+            assert!(error.to_string().contains("ockam enroll --ticket"));
+        }
+        Ok(())
+    }
+}
@@ -2,9 +2,15 @@ use crate::util::exitcode::{self, ExitCode};
 use crate::version::Version;
 use crate::{fmt_heading, fmt_log};
 use colorful::Colorful;
+use console::Term;
 use miette::Diagnostic;
 use miette::{miette, Report};
-use std::fmt::{Debug, Formatter};
+use ockam_api::cli_state::CliStateError;
+use ockam_api::nodes::NodeRequestError;
+use ockam_core::api::Status;
+use ockam_core::errcode::Kind;
+use serde::Serialize;
+use std::fmt::{Debug, Display, Formatter, Write};
 
 pub type Result<T> = miette::Result<T, Error>;
 
@@ -64,6 +70,7 @@ pub enum Error {
     InternalError {
         error_message: String,
         exit_code: ExitCode,
+        error_code: ErrorCode,
     },
 
     // Unavailable
@@ -78,6 +85,7 @@ pub enum Error {
         resource_name: String,
     },
     // ==== End 5xx Errors ====
+    /// An error after which the command can be retried. Its error code is the code of the wrapped report
     #[error("{0}")]
     Retry(Report),
 }
@@ -86,9 +94,15 @@ impl Error {
     #[track_caller]
     pub fn new(code: ExitCode, err: miette::ErrReport) -> Self {
         assert_ne!(code, 0, "Error's exit code can't be OK");
+        let error_code = match ErrorCode::from_report(&err) {
+            ErrorCode::Internal if code == exitcode::USAGE => ErrorCode::Usage,
+            ErrorCode::Internal if code == exitcode::DATAERR => ErrorCode::InvalidInput,
+            error_code => error_code,
+        };
         Error::InternalError {
             error_message: err.to_string(),
             exit_code: code,
+            error_code,
         }
     }
 
@@ -104,7 +118,52 @@ impl Error {
         Self::new(exitcode::SOFTWARE, miette!(msg.to_string()))
     }
 
+    /// Set the error code of an error which is not specific to a resource
+    pub fn with_code(self, error_code: ErrorCode) -> Self {
+        match self {
+            Error::InternalError {
+                error_message,
+                exit_code,
+                ..
+            } => Error::InternalError {
+                error_message,
+                exit_code,
+                error_code,
+            },
+            error => error,
+        }
+    }
+
+    /// Return the code identifying the cause of this error
+    pub fn error_code(&self) -> ErrorCode {
+        match self {
+            Error::NotFound { .. } => ErrorCode::NotFound,
+            Error::Unauthorized { .. } => ErrorCode::Unauthorized,
+            Error::NotEnrolled => ErrorCode::NotEnrolled,
+            Error::Conflict { .. } => ErrorCode::Conflict,
+            Error::InternalError { error_code, .. } => *error_code,
+            Error::Unavailable { .. } => ErrorCode::Connectivity,
+            Error::Retry(report) => ErrorCode::from_report(report),
+        }
+    }
+
+    /// Return true if running the command again could succeed.
+    ///
+    /// This is the case for errors marked as retryable by a command, unless they are
+    /// caused by invalid arguments or by a missing resource, and for connectivity failures
+    pub fn is_retryable(&self) -> bool {
+        let error_code = self.error_code();
+        match self {
+            Error::Retry(_) => !error_code.is_terminal(),
+            _ => error_code == ErrorCode::Connectivity,
+        }
+    }
+
+    /// Return the exit code of the process when the command fails with this error
     pub fn code(&self) -> ExitCode {
+        if let Some(exit_code) = self.error_code().exit_code() {
+            return exit_code;
+        }
         match self {
             Error::NotFound { .. } => exitcode::SOFTWARE,
             Error::Unauthorized { .. } => exitcode::NOPERM,
@@ -117,6 +176,198 @@ impl Error {
     }
 }
 
+/// Code identifying the cause of a command failure, so that scripts can react to it.
+///
+/// The code is displayed with the error message, set in the `error.code` field of the
+/// JSON output with `--output json`, and some codes have a dedicated process exit code:
+///
+///  - 2: `usage`, the command arguments are invalid
+///  - 3: `not_enrolled`, the command requires an enrollment to Ockam Orchestrator
+///  - 4: `not_found`, a node, a project or another resource does not exist
+///  - 5: `connectivity`, a node, a project or a service could not be reached
+///
+/// Other failures exit with one of the codes defined in `exitcode`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    Usage,
+    NotEnrolled,
+    NotFound,
+    Connectivity,
+    Unauthorized,
+    Conflict,
+    InvalidInput,
+    InvalidSignature,
+    Expired,
+    Internal,
+}
+
+impl ErrorCode {
+    /// Return the exit code reserved for this error code, if any
+    pub fn exit_code(&self) -> Option<ExitCode> {
+        match self {
+            ErrorCode::Usage => Some(exitcode::INVALID_USAGE),
+            ErrorCode::NotEnrolled => Some(exitcode::NOT_ENROLLED),
+            ErrorCode::NotFound => Some(exitcode::RESOURCE_NOT_FOUND),
+            ErrorCode::Connectivity => Some(exitcode::CONNECTIVITY),
+            _ => None,
+        }
+    }
+
+    /// Return true if a command failing with this code fails again when it is retried
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            ErrorCode::Usage
+                | ErrorCode::NotEnrolled
+                | ErrorCode::NotFound
+                | ErrorCode::Unauthorized
+                | ErrorCode::Conflict
+                | ErrorCode::InvalidInput
+                | ErrorCode::InvalidSignature
+                | ErrorCode::Expired
+        )
+    }
+
+    /// Return the code of the first error of a report, or of its causes, which has a specific code
+    pub fn from_report(report: &Report) -> ErrorCode {
+        Self::find_in_report(report).unwrap_or(ErrorCode::Internal)
+    }
+
+    fn find_in_report(report: &Report) -> Option<ErrorCode> {
+        report.chain().find_map(|error| {
+            if let Some(error) = error.downcast_ref::<CommandError>() {
+                Some(error.code)
+            } else if let Some(error) = error.downcast_ref::<Error>() {
+                Some(error.error_code())
+            } else if let Some(error) = error.downcast_ref::<NodeRequestError>() {
+                Some(Self::from_node_request_error(error))
+            } else if let Some(error) = error.downcast_ref::<CliStateError>() {
+                Self::from_cli_state_error(error)
+            } else {
+                error
+                    .downcast_ref::<ockam_core::Error>()
+                    .and_then(Self::from_ockam_error)
+            }
+            .filter(|code| *code != ErrorCode::Internal)
+        })
+    }
+
+    /// Return the code of a failed request to a node, using the status of its response if any
+    pub fn from_node_request_error(error: &NodeRequestError) -> ErrorCode {
+        match error {
+            NodeRequestError::Unreachable { .. } | NodeRequestError::Timeout { .. } => {
+                ErrorCode::Connectivity
+            }
+            NodeRequestError::Failed { status, .. } => match status {
+                Some(Status::NotFound) => ErrorCode::NotFound,
+                Some(Status::Conflict) => ErrorCode::Conflict,
+                Some(Status::BadRequest) => ErrorCode::InvalidInput,
+                Some(Status::Unauthorized) | Some(Status::Forbidden) => ErrorCode::Unauthorized,
+                _ => ErrorCode::Internal,
+            },
+        }
+    }
+
+    fn from_cli_state_error(error: &CliStateError) -> Option<ErrorCode> {
+        match error {
+            CliStateError::ResourceNotFound { .. } => Some(ErrorCode::NotFound),
+            CliStateError::AlreadyExists { .. } => Some(ErrorCode::Conflict),
+            CliStateError::Ockam(error) => Self::from_ockam_error(error),
+            _ => None,
+        }
+    }
+
+    fn from_ockam_error(error: &ockam_core::Error) -> Option<ErrorCode> {
+        match error.code().kind {
+            Kind::NotFound => Some(ErrorCode::NotFound),
+            Kind::AlreadyExists | Kind::Conflict => Some(ErrorCode::Conflict),
+            Kind::Invalid | Kind::Serialization => Some(ErrorCode::InvalidInput),
+            Kind::Io | Kind::Timeout => Some(ErrorCode::Connectivity),
+            _ => None,
+        }
+    }
+}
+
+impl Display for ErrorCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let code = match self {
+            ErrorCode::Usage => "usage",
+            ErrorCode::NotEnrolled => "not_enrolled",
+            ErrorCode::NotFound => "not_found",
+            ErrorCode::Connectivity => "connectivity",
+            ErrorCode::Unauthorized => "unauthorized",
+            ErrorCode::Conflict => "conflict",
+            ErrorCode::InvalidInput => "invalid_input",
+            ErrorCode::InvalidSignature => "invalid_signature",
+            ErrorCode::Expired => "expired",
+            ErrorCode::Internal => "internal",
+        };
+        f.write_str(code)
+    }
+}
+
+/// Error returned by a command, displayed with its error code
+#[derive(Debug)]
+pub struct CommandError {
+    report: Report,
+    code: ErrorCode,
+}
+
+impl CommandError {
+    pub fn new(report: Report) -> Self {
+        let code = ErrorCode::from_report(&report);
+        Self { report, code }
+    }
+
+    /// Set the error code of a report, without changing how it is displayed
+    pub fn with_code(report: Report, code: ErrorCode) -> Self {
+        Self { report, code }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        self.code
+    }
+
+    /// Return the exit code of the process when the command fails with this error
+    pub fn exit_code(&self) -> ExitCode {
+        self.code
+            .exit_code()
+            .unwrap_or_else(|| sysexits_code(&self.report))
+    }
+}
+
+impl Display for CommandError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.report, f)
+    }
+}
+
+impl std::error::Error for CommandError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.report.source()
+    }
+}
+
+/// The report of a command error is the report of the underlying error, with the command error code
+impl Diagnostic for CommandError {
+    fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        Some(Box::new(self.code))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.report.help()
+    }
+
+    fn url<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.report.url()
+    }
+
+    fn diagnostic_source(&self) -> Option<&dyn Diagnostic> {
+        self.report.diagnostic_source()
+    }
+}
+
 pub struct ErrorReportHandler;
 
 impl ErrorReportHandler {
@@ -166,7 +417,9 @@ impl miette::ReportHandler for ErrorReportHandler {
             return Debug::fmt(error, f);
         }
 
-        writeln!(f, "\n{}\n", fmt_heading!("{}", "Error:".red()))?;
+        let mut report = String::new();
+
+        writeln!(report, "\n{}\n", fmt_heading!("{}", "Error:".red()))?;
 
         // Try to extract the source message from the error, and disregard the rest. If
         // possible replace the new lines w/ fmt_log! outputs.
@@ -175,11 +428,11 @@ impl miette::ReportHandler for ErrorReportHandler {
             None => format!("{}", error),
         };
         error_message.lines().for_each(|line| {
-            let _ = writeln!(f, "{}", fmt_log!("{}", line));
+            let _ = writeln!(report, "{}", fmt_log!("{}", line));
         });
 
         if let Some(help) = error.help() {
-            writeln!(f, "{}", fmt_log!("{}", help))?;
+            writeln!(report, "{}", fmt_log!("{}", help))?;
         }
 
         // TODO: wait until we have the dedicated documentation page for errors
@@ -200,11 +453,11 @@ impl miette::ReportHandler for ErrorReportHandler {
         let version_message = format!("version: {}", Version::short()).dark_gray();
         let footer_message = format!("\n{}\n{}", code_message, version_message);
         footer_message.split('\n').for_each(|line| {
-            let _ = writeln!(f, "{}", fmt_log!("{}", line));
+            let _ = writeln!(report, "{}", fmt_log!("{}", line));
         });
 
         writeln!(
-            f,
+            report,
             "\n{}\n{}",
             fmt_log!(
                 "{}",
@@ -217,12 +470,35 @@ impl miette::ReportHandler for ErrorReportHandler {
             )
         )?;
 
-        write!(f, "\n{}", fmt_heading!("{}", ""))?;
+        write!(report, "\n{}", fmt_heading!("{}", ""))?;
 
-        Ok(())
+        // Don't decorate the report with colors if stderr can't display them,
+        // for example when it is redirected to a file
+        if !Term::stderr().features().colors_supported() {
+            report = String::from_utf8_lossy(&strip_ansi_escapes::strip(&report)).to_string();
+        }
+        f.write_str(&report)
+    }
+}
+
+/// Return the exit code of the `ockam` process for an error returned by a command
+pub fn exit_code(report: &Report) -> ExitCode {
+    match report.downcast_ref::<CommandError>() {
+        Some(error) => error.exit_code(),
+        None => ErrorCode::from_report(report)
+            .exit_code()
+            .unwrap_or_else(|| sysexits_code(report)),
     }
 }
 
+/// Return the exit code of the first command error of a report, or a generic failure code
+fn sysexits_code(report: &Report) -> ExitCode {
+    report
+        .chain()
+        .find_map(|error| error.downcast_ref::<Error>().map(|error| error.code()))
+        .unwrap_or(exitcode::SOFTWARE)
+}
+
 macro_rules! gen_from_impl {
     ($t:ty, $c:ident) => {
         impl From<$t> for Error {
@@ -233,6 +509,20 @@ macro_rules! gen_from_impl {
             }
         }
     };
+    // keep the error code of errors which are stringified
+    ($t:ty, $c:ident, $error_code:path) => {
+        impl From<$t> for Error {
+            #[track_caller]
+            fn from(e: $t) -> Self {
+                use miette::miette;
+                let error = Error::new(exitcode::$c, miette!(e.to_string()));
+                match $error_code(&e) {
+                    Some(error_code) => error.with_code(error_code),
+                    None => error,
+                }
+            }
+        }
+    };
 }
 
 gen_from_impl!(std::io::Error, IOERR);
@@ -244,10 +534,102 @@ gen_from_impl!(serde_json::Error, DATAERR);
 gen_from_impl!(serde_yaml::Error, DATAERR);
 gen_from_impl!(minicbor::encode::Error<std::convert::Infallible>, DATAERR);
 gen_from_impl!(minicbor::decode::Error, DATAERR);
-gen_from_impl!(ockam::Error, SOFTWARE);
-gen_from_impl!(ockam_api::cli_state::CliStateError, SOFTWARE);
+gen_from_impl!(ockam::Error, SOFTWARE, ErrorCode::from_ockam_error);
+gen_from_impl!(CliStateError, SOFTWARE, ErrorCode::from_cli_state_error);
 gen_from_impl!(ockam_api::error::ApiError, SOFTWARE);
 gen_from_impl!(ockam_multiaddr::Error, SOFTWARE);
-gen_from_impl!(miette::ErrReport, SOFTWARE);
 gen_from_impl!(time::error::Parse, DATAERR);
 gen_from_impl!(dialoguer::Error, DATAERR);
+
+impl From<miette::ErrReport> for Error {
+    #[track_caller]
+    fn from(report: miette::ErrReport) -> Self {
+        // keep the errors marked as retryable, so that the command can be retried
+        let report = match report.downcast::<Error>() {
+            Ok(Error::Retry(inner)) => return Error::Retry(inner),
+            Ok(error) => miette::Report::new(error),
+            Err(report) => report,
+        };
+        let error = Error::new(exitcode::SOFTWARE, miette::miette!(report.to_string()));
+        match ErrorCode::find_in_report(&report) {
+            Some(error_code) => error.with_code(error_code),
+            None => error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_codes_have_documented_exit_codes() {
+        let error = Error::arg_validation("--to", "/node/n1", None);
+        assert_eq!(error.error_code(), ErrorCode::Usage);
+        assert_eq!(error.code(), exitcode::INVALID_USAGE);
+
+        assert_eq!(Error::NotEnrolled.code(), exitcode::NOT_ENROLLED);
+
+        let report = Report::new(CommandError::with_code(
+            miette!("The node n1 was not found"),
+            ErrorCode::NotFound,
+        ));
+        assert_eq!(exit_code(&report), exitcode::RESOURCE_NOT_FOUND);
+
+        let report = Report::new(NodeRequestError::Timeout {
+            node_name: "n1".to_string(),
+            timeout: std::time::Duration::from_secs(1),
+        });
+        assert_eq!(ErrorCode::from_report(&report), ErrorCode::Connectivity);
+        assert_eq!(exit_code(&report), exitcode::CONNECTIVITY);
+
+        let report = Report::new(Error::new_internal_error("unexpected"));
+        assert_eq!(ErrorCode::from_report(&report), ErrorCode::Internal);
+        assert_eq!(exit_code(&report), exitcode::SOFTWARE);
+    }
+
+    #[test]
+    fn test_error_code_is_kept_when_errors_are_converted() {
+        let error: Error = CliStateError::ResourceNotFound {
+            resource: "node".to_string(),
+            name: "n1".to_string(),
+        }
+        .into();
+        assert_eq!(error.error_code(), ErrorCode::NotFound);
+
+        let report = Report::new(CommandError::with_code(
+            miette!("--authorized can not be used with project addresses"),
+            ErrorCode::Usage,
+        ));
+        let error: Error = report.into();
+        assert_eq!(error.error_code(), ErrorCode::Usage);
+
+        // a retried command returns the code of its last failure
+        let retry = Error::Retry(Report::new(Error::NotEnrolled));
+        assert_eq!(retry.error_code(), ErrorCode::NotEnrolled);
+        let report = Report::new(CommandError::new(retry.into()));
+        assert_eq!(exit_code(&report), exitcode::NOT_ENROLLED);
+    }
+
+    #[test]
+    fn test_retryable_errors() {
+        // an error marked as retryable stays retryable when converted to a report
+        let retry = Error::Retry(miette!("the enrollment failed"));
+        let error: Error = Report::new(retry).into();
+        assert!(matches!(error, Error::Retry(_)));
+        assert!(error.is_retryable());
+
+        // unless its cause won't be fixed by retrying
+        let retry = Error::Retry(Report::new(Error::NotEnrolled));
+        assert!(!retry.is_retryable());
+
+        // connectivity failures can always be retried
+        let error: Error = Report::new(NodeRequestError::Timeout {
+            node_name: "n1".to_string(),
+            timeout: std::time::Duration::from_secs(1),
+        })
+        .into();
+        assert!(error.is_retryable());
+        assert!(!Error::new_internal_error("unexpected").is_retryable());
+    }
+}
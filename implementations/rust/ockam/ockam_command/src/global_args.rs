@@ -18,7 +18,7 @@ pub struct GlobalArgs {
     )]
     help: Option<bool>,
 
-    /// Do not print any log messages and disable confirmation prompts. This is useful for scripting and automation, where you don't want the process to block on stdin
+    /// Do not print any log messages or progress spinners and disable confirmation prompts. This is useful for scripting and automation, where you don't want the process to block on stdin
     #[arg(global = true, long, short, default_value_t = quiet_default_value())]
     pub quiet: bool,
 
@@ -37,11 +37,11 @@ pub struct GlobalArgs {
     #[arg(global = true, long, default_value_t = no_color_default_value())]
     pub no_color: bool,
 
-    /// Disable tty functionality
+    /// Disable tty functionality. The commands which would need to prompt for user input fail instead, and name the argument to use to avoid the prompt
     #[arg(global = true, long, default_value_t = no_input_default_value())]
     pub no_input: bool,
 
-    /// Output format
+    /// Output format. With `json`, the commands which can't write their result as JSON return an error
     #[arg(global = true, long = "output", value_enum, default_value = "plain")]
     pub output_format: OutputFormat,
 
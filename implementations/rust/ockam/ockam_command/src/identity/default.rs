@@ -44,6 +44,7 @@ impl DefaultCommand {
                         .stdout()
                         .plain(fmt_ok!("The identity named '{}' is now the default", &name))
                         .machine(name)
+                        .json(serde_json::json!({ "name": name }))
                         .write_line()?;
                 }
             }
@@ -55,6 +56,8 @@ impl DefaultCommand {
                         "The name of the default identity is '{}'",
                         identity.name()
                     ))
+                    .machine(identity.name())
+                    .json(serde_json::json!({ "name": identity.name() }))
                     .write_line()?;
             }
         };
@@ -1,3 +1,4 @@
+use crate::identity::membership::{get_project_memberships, ProjectMembershipOutput};
 use crate::output::Output;
 use crate::terminal::OckamColor;
 use crate::util::async_cmd;
@@ -5,6 +6,7 @@ use crate::{docs, CommandGlobalOpts};
 
 use clap::Args;
 use colorful::Colorful;
+use ockam::Context;
 
 use serde::Serialize;
 use serde_json::json;
@@ -21,12 +23,16 @@ const AFTER_LONG_HELP: &str = include_str!("./static/list/after_long_help.txt");
     before_help = docs::before_help(PREVIEW_TAG),
     after_long_help = docs::after_help(AFTER_LONG_HELP)
 )]
-pub struct ListCommand {}
+pub struct ListCommand {
+    /// Show the enrollment and credential status of each identity in the known projects
+    #[arg(long)]
+    detailed: bool,
+}
 
 impl ListCommand {
     pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
-        async_cmd(&self.name(), opts.clone(), |_ctx| async move {
-            self.async_run(opts).await
+        async_cmd(&self.name(), opts.clone(), |ctx| async move {
+            self.async_run(&ctx, opts).await
         })
     }
 
@@ -34,16 +40,21 @@ impl ListCommand {
         "identity list".into()
     }
 
-    async fn async_run(&self, opts: CommandGlobalOpts) -> miette::Result<()> {
+    async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
         let mut identities_list: Vec<IdentityListOutput> = Vec::new();
 
         let identities = opts.state.get_named_identities().await?;
         for identity in identities.iter() {
-            let identity_output = IdentityListOutput::new(
+            let mut identity_output = IdentityListOutput::new(
                 identity.name(),
                 identity.identifier().to_string(),
                 identity.is_default(),
             );
+            if self.detailed {
+                let memberships =
+                    get_project_memberships(ctx, &opts, &identity.name(), None).await?;
+                identity_output = identity_output.with_projects(memberships);
+            }
             identities_list.push(identity_output);
         }
 
@@ -53,11 +64,12 @@ impl ListCommand {
             "No identities found on this system.",
         )?;
 
-        opts.terminal
-            .stdout()
-            .plain(list)
-            .json(json!(&identities))
-            .write_line()?;
+        let json = if self.detailed {
+            json!(&identities_list)
+        } else {
+            json!(&identities)
+        };
+        opts.terminal.stdout().plain(list).json(json).write_line()?;
         Ok(())
     }
 }
@@ -67,6 +79,8 @@ pub struct IdentityListOutput {
     pub name: String,
     pub identifier: String,
     pub is_default: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub projects: Option<Vec<ProjectMembershipOutput>>,
 }
 
 impl IdentityListOutput {
@@ -75,8 +89,14 @@ impl IdentityListOutput {
             name,
             identifier,
             is_default,
+            projects: None,
         }
     }
+
+    pub fn with_projects(mut self, projects: Vec<ProjectMembershipOutput>) -> Self {
+        self.projects = Some(projects);
+        self
+    }
 }
 
 impl Output for IdentityListOutput {
@@ -99,6 +119,9 @@ impl Output for IdentityListOutput {
                 .to_string()
                 .color(OckamColor::PrimaryResource.color())
         )?;
+        for project in self.projects.iter().flatten() {
+            write!(output, "\n{}", project.to_string().trim_end())?;
+        }
         Ok(output)
     }
 }
@@ -0,0 +1,238 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use colorful::Colorful;
+use serde::Serialize;
+use tracing::warn;
+
+use ockam::identity::TimestampInSeconds;
+use ockam::Context;
+use ockam_api::cli_state::ProjectMembership;
+use ockam_api::cloud::project::Project;
+use ockam_api::enroll::enrollment::Enrollment;
+use ockam_api::nodes::InMemoryNode;
+
+use crate::output::human_readable_time;
+use crate::terminal::color_primary;
+use crate::{CommandGlobalOpts, Result};
+
+/// A project credential expiring within this duration is displayed as a warning
+const CREDENTIAL_EXPIRY_WARNING: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Enrollment and credential status of an identity in a project
+#[derive(Serialize)]
+pub struct ProjectMembershipOutput {
+    project: String,
+    project_id: String,
+    enrolled: bool,
+    /// Time, in seconds since the UNIX epoch, of the enrollment of a project administrator
+    #[serde(skip_serializing_if = "Option::is_none")]
+    enrolled_at: Option<u64>,
+    /// Expiration time, in seconds since the UNIX epoch, of the cached credential
+    #[serde(skip_serializing_if = "Option::is_none")]
+    credential_expires_at: Option<u64>,
+    attributes: BTreeMap<String, String>,
+    /// Result of the verification of the membership by the project authority
+    #[serde(skip_serializing_if = "Option::is_none")]
+    check: Option<MembershipCheck>,
+    #[serde(skip)]
+    now: u64,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "status", content = "error", rename_all = "snake_case")]
+pub enum MembershipCheck {
+    /// The project authority issued a credential to the identity
+    Member,
+    /// The project authority refused to issue a credential, or could not be contacted
+    Failed(String),
+    /// The project authority did not reply in time
+    Timeout,
+}
+
+impl ProjectMembershipOutput {
+    fn new(membership: &ProjectMembership, now: u64) -> Self {
+        let project = membership.project();
+        Self {
+            project: project.name().to_string(),
+            project_id: project.project_id().to_string(),
+            enrolled: membership.is_enrolled(),
+            enrolled_at: membership
+                .enrolled_at()
+                .map(|enrolled_at| enrolled_at.unix_timestamp() as u64),
+            credential_expires_at: membership
+                .credential()
+                .map(|credential| credential.expires_at.0),
+            attributes: membership.credential_attributes(),
+            check: None,
+            now,
+        }
+    }
+}
+
+/// Return the membership of an identity in each known project.
+///
+/// If `check_timeout` is set, each project authority is asked to issue a credential to the
+/// identity, in order to verify its membership. Each project is given at most `check_timeout`
+/// to reply, so that an unreachable project does not delay the other ones indefinitely.
+pub async fn get_project_memberships(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    identity_name: &str,
+    check_timeout: Option<Duration>,
+) -> Result<Vec<ProjectMembershipOutput>> {
+    let now = ockam_core::compat::time::now()?;
+    let memberships = opts.state.get_project_memberships(identity_name).await?;
+    let mut outputs: Vec<ProjectMembershipOutput> = memberships
+        .iter()
+        .map(|membership| ProjectMembershipOutput::new(membership, now))
+        .collect();
+
+    let Some(check_timeout) = check_timeout else {
+        return Ok(outputs);
+    };
+    if memberships.is_empty() {
+        return Ok(outputs);
+    }
+
+    // The in-memory node is only needed to talk to the project authorities
+    let node = InMemoryNode::builder()
+        .identity_name(identity_name)
+        .timeout(check_timeout)
+        .build(ctx, &opts.state)
+        .await?;
+    for (membership, output) in memberships.iter().zip(outputs.iter_mut()) {
+        output.check = Some(
+            check_membership(
+                ctx,
+                &node,
+                identity_name,
+                membership.project(),
+                check_timeout,
+            )
+            .await,
+        );
+    }
+    Ok(outputs)
+}
+
+async fn check_membership(
+    ctx: &Context,
+    node: &InMemoryNode,
+    identity_name: &str,
+    project: &Project,
+    check_timeout: Duration,
+) -> MembershipCheck {
+    let check = async {
+        node.create_authority_client(project, Some(identity_name.to_string()))
+            .await?
+            .issue_credential(ctx, None)
+            .await
+    };
+    match tokio::time::timeout(check_timeout, check).await {
+        Ok(Ok(_)) => MembershipCheck::Member,
+        Ok(Err(e)) => {
+            warn!(%e, project = %project.name(), "Failed to verify the project membership");
+            MembershipCheck::Failed(e.to_string())
+        }
+        Err(_) => {
+            warn!(project = %project.name(), "Timeout while verifying the project membership");
+            MembershipCheck::Timeout
+        }
+    }
+}
+
+impl Display for ProjectMembershipOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "  Project {}", color_primary(&self.project))?;
+        match (self.enrolled, self.enrolled_at) {
+            (true, Some(enrolled_at)) => writeln!(
+                f,
+                "    Enrolled: yes, since {}",
+                human_readable_time(TimestampInSeconds(enrolled_at))
+            )?,
+            (true, None) => writeln!(f, "    Enrolled: yes")?,
+            (false, _) => writeln!(f, "    Enrolled: no")?,
+        }
+        if let Some(expires_at) = self.credential_expires_at {
+            let expiry = format!(
+                "{} ({})",
+                human_readable_time(TimestampInSeconds(expires_at)),
+                expires_in(expires_at, self.now)
+            );
+            let expiry = if expires_at <= self.now {
+                expiry.light_red().to_string()
+            } else if expires_at - self.now <= CREDENTIAL_EXPIRY_WARNING.as_secs() {
+                expiry.light_yellow().to_string()
+            } else {
+                expiry
+            };
+            writeln!(f, "    Credential: {expiry}")?;
+            let attributes = self
+                .attributes
+                .iter()
+                .map(|(key, value)| format!("{key}={value}"))
+                .collect::<Vec<_>>();
+            writeln!(f, "    Attributes: {}", attributes.join(", "))?;
+        }
+        if let Some(check) = &self.check {
+            let check = match check {
+                MembershipCheck::Member => "verified by the project authority"
+                    .light_green()
+                    .to_string(),
+                MembershipCheck::Failed(e) => format!("not verified: {e}").light_red().to_string(),
+                MembershipCheck::Timeout => "the project authority did not reply in time"
+                    .light_yellow()
+                    .to_string(),
+            };
+            writeln!(f, "    Membership: {check}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Return the time left before an expiration, using its largest unit, e.g. "expires in 2d"
+fn expires_in(expires_at: u64, now: u64) -> String {
+    if expires_at <= now {
+        return "expired".to_string();
+    }
+    let left = expires_at - now;
+    let (value, unit) = match left {
+        l if l >= 24 * 60 * 60 => (l / (24 * 60 * 60), "d"),
+        l if l >= 60 * 60 => (l / (60 * 60), "h"),
+        l if l >= 60 => (l / 60, "m"),
+        l => (l, "s"),
+    };
+    format!("expires in {value}{unit}")
+}
+
+/// Render the memberships of an identity as a plain text section
+pub fn memberships_section(memberships: &[ProjectMembershipOutput]) -> String {
+    if memberships.is_empty() {
+        return String::new();
+    }
+    let mut section = "Projects\n".to_string();
+    for membership in memberships {
+        section.push_str(&membership.to_string());
+    }
+    section
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expires_in() {
+        assert_eq!(expires_in(100, 100), "expired");
+        assert_eq!(expires_in(100, 200), "expired");
+        assert_eq!(expires_in(130, 100), "expires in 30s");
+        assert_eq!(expires_in(100 + 5 * 60 + 10, 100), "expires in 5m");
+        assert_eq!(expires_in(100 + 3 * 60 * 60, 100), "expires in 3h");
+        assert_eq!(
+            expires_in(100 + 2 * 24 * 60 * 60 + 60 * 60, 100),
+            "expires in 2d"
+        );
+    }
+}
@@ -1,8 +1,10 @@
 use clap::{Args, Subcommand};
+use miette::miette;
 
 pub use create::CreateCommand;
 pub(crate) use delete::DeleteCommand;
 pub(crate) use list::ListCommand;
+pub(crate) use rotate::RotateCommand;
 pub(crate) use show::ShowCommand;
 
 use crate::identity::default::DefaultCommand;
@@ -12,6 +14,8 @@ mod create;
 mod default;
 mod delete;
 mod list;
+mod membership;
+mod rotate;
 mod show;
 
 const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
@@ -35,6 +39,7 @@ pub enum IdentitySubcommand {
     List(ListCommand),
     Default(DefaultCommand),
     Delete(DeleteCommand),
+    Rotate(RotateCommand),
 }
 
 impl IdentityCommand {
@@ -45,6 +50,7 @@ impl IdentityCommand {
             IdentitySubcommand::List(c) => c.run(opts),
             IdentitySubcommand::Delete(c) => c.run(opts),
             IdentitySubcommand::Default(c) => c.run(opts),
+            IdentitySubcommand::Rotate(c) => c.run(opts),
         }
     }
 
@@ -55,7 +61,27 @@ impl IdentityCommand {
             IdentitySubcommand::List(c) => c.name(),
             IdentitySubcommand::Delete(c) => c.name(),
             IdentitySubcommand::Default(c) => c.name(),
+            IdentitySubcommand::Rotate(c) => c.name(),
         }
         .to_string()
     }
 }
+
+/// Fail if there is no identity with the given name, listing the existing identities
+pub(crate) async fn check_identity_exists(
+    opts: &CommandGlobalOpts,
+    identity_name: &str,
+) -> miette::Result<()> {
+    let identities = opts.state.get_named_identities().await?;
+    if identities.iter().any(|i| i.name() == identity_name) {
+        return Ok(());
+    }
+    let names = identities
+        .iter()
+        .map(|i| i.name())
+        .collect::<Vec<_>>()
+        .join(", ");
+    Err(miette!(
+        "The identity {identity_name} doesn't exist. The available identities are: {names}"
+    ))
+}
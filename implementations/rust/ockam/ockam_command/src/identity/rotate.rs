@@ -0,0 +1,419 @@
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::{miette, IntoDiagnostic, WrapErr};
+
+use ockam::identity::Identifier;
+use ockam::{Context, TcpTransport};
+use ockam_api::cli_state::enrollments::EnrollmentTicket;
+use ockam_api::cli_state::CliState;
+use ockam_api::cli_state::NodeInfo;
+use ockam_api::cloud::project::Project;
+use ockam_api::enroll::enrollment::{EnrollStatus, Enrollment};
+use ockam_api::nodes::models::secure_channel::{
+    CreateSecureChannelListenerRequest, DeleteSecureChannelListenerResponse,
+    ListSecureChannelListenerResponse, ShowSecureChannelListenerResponse,
+};
+use ockam_api::nodes::{BackgroundNodeClient, InMemoryNode, NodeRequestError};
+use ockam_api::NamedIdentity;
+use ockam_core::api::Request;
+
+use crate::util::api;
+use crate::value_parsers::parse_enrollment_ticket;
+use crate::{color_primary, docs, fmt_log, fmt_ok, fmt_warn, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/rotate/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/rotate/after_long_help.txt");
+
+/// Rotate the key of an identity and update the resources depending on it
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct RotateCommand {
+    /// Name of the identity to rotate
+    name: String,
+
+    /// Re-enroll the identity with the projects it is enrolled in and re-issue its credentials
+    #[arg(long)]
+    re_enroll: bool,
+
+    /// Path, URL or inlined hex-encoded enrollment ticket to present when re-enrolling
+    #[arg(long, value_name = "ENROLLMENT TICKET", value_parser = parse_enrollment_ticket, requires = "re_enroll")]
+    enrollment_ticket: Option<EnrollmentTicket>,
+
+    /// Display the changes which would be made, without applying them
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[async_trait]
+impl Command for RotateCommand {
+    const NAME: &'static str = "identity rotate";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let identity = opts.state.get_named_identity(&self.name).await?;
+        let plan = RotationPlan::create(&opts.state, &identity, self.re_enroll).await?;
+
+        if self.dry_run {
+            opts.terminal
+                .stdout()
+                .plain(plan.to_string())
+                .json(plan.to_json())
+                .write_line()?;
+            return Ok(());
+        }
+
+        opts.state.rotate_identity_by_name(&self.name).await?;
+        let mut summary = RotationSummary::default();
+        summary.updated.push(format!(
+            "Rotated the key of the identity {}",
+            identity.name()
+        ));
+
+        // The in-memory node is only needed to talk to the project authorities
+        let in_memory_node = if plan.projects.is_empty() {
+            None
+        } else {
+            Some(
+                InMemoryNode::start_with_project_name_and_identity(
+                    ctx,
+                    &opts.state,
+                    Some(identity.name()),
+                    None,
+                )
+                .await?,
+            )
+        };
+
+        if let Some(node) = &in_memory_node {
+            for project in &plan.projects {
+                match self.re_enroll(ctx, node, &identity, project).await {
+                    Ok(()) => summary.updated.push(format!(
+                        "Re-enrolled with the project {} and re-issued a credential",
+                        project.name()
+                    )),
+                    Err(e) => summary.attention.push(format!(
+                        "Could not re-enroll with the project {}: {e}",
+                        project.name()
+                    )),
+                }
+            }
+        }
+
+        let tcp = match &in_memory_node {
+            Some(node) => node.tcp_transport().clone(),
+            None => TcpTransport::create(ctx).await.into_diagnostic()?,
+        };
+        for node in &plan.nodes {
+            if !node.is_running() {
+                summary.attention.push(format!(
+                    "The node {} is not running. Restart it to use the rotated key",
+                    node.name()
+                ));
+                continue;
+            }
+            match restart_listeners(ctx, &opts, &tcp, node, &identity).await {
+                Ok(count) => summary.updated.push(format!(
+                    "Restarted {count} secure channel listener(s) on the node {}",
+                    node.name()
+                )),
+                Err(e) => summary.attention.push(format!(
+                    "Could not restart the secure channel listeners on the node {}: {e}",
+                    node.name()
+                )),
+            }
+        }
+
+        opts.terminal
+            .stdout()
+            .plain(summary.to_string())
+            .json(summary.to_json())
+            .write_line()?;
+        Ok(())
+    }
+}
+
+impl RotateCommand {
+    /// Present the enrollment ticket if it was issued for this project, then re-issue a credential.
+    /// Without a matching ticket we rely on the identity being already a member of the project.
+    async fn re_enroll(
+        &self,
+        ctx: &Context,
+        node: &InMemoryNode,
+        identity: &NamedIdentity,
+        project: &Project,
+    ) -> miette::Result<()> {
+        let authority_node_client = node
+            .create_authority_client(project, Some(identity.name()))
+            .await?;
+
+        let ticket = self.enrollment_ticket.as_ref().filter(|t| {
+            t.project
+                .as_ref()
+                .map(|p| p.name == project.name())
+                .unwrap_or(false)
+        });
+        if let Some(ticket) = ticket {
+            match authority_node_client
+                .present_token(ctx, &ticket.one_time_code)
+                .await?
+            {
+                EnrollStatus::EnrolledSuccessfully | EnrollStatus::AlreadyEnrolled => {}
+                EnrollStatus::FailedNoStatus(msg) => return Err(miette!("{msg}")),
+                EnrollStatus::UnexpectedStatus(msg, status) => {
+                    return Err(miette!("{msg} {status}"))
+                }
+            }
+        }
+
+        authority_node_client.issue_credential(ctx, None).await?;
+        Ok(())
+    }
+}
+
+/// Delete and recreate the secure channel listeners of a node which use the rotated identity
+async fn restart_listeners(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    tcp: &TcpTransport,
+    node: &NodeInfo,
+    identity: &NamedIdentity,
+) -> miette::Result<usize> {
+    let client =
+        BackgroundNodeClient::create_to_node_with_tcp(tcp, &opts.state, &node.name()).await?;
+    let listeners: ListSecureChannelListenerResponse =
+        client.ask(ctx, api::list_secure_channel_listener()).await?;
+
+    let listeners = listeners_using_identity(&listeners.list, &identity.identifier());
+    for listener in &listeners {
+        let _: DeleteSecureChannelListenerResponse = client
+            .ask(ctx, api::delete_secure_channel_listener(&listener.addr))
+            .await
+            .wrap_err(format!(
+                "Failed to delete the secure channel listener {}",
+                listener.addr
+            ))?;
+        let request = Request::post("/node/secure_channel_listener").body(
+            CreateSecureChannelListenerRequest::new(
+                &listener.addr,
+                listener.authorized_identifiers.clone(),
+                Some(identity.name()),
+            )
+            .with_max_handshakes_per_second(listener.max_handshakes_per_second),
+        );
+        let reply = client.tell_and_get_reply(ctx, request).await?;
+        NodeRequestError::check_reply(&node.name(), reply).map_err(|e| {
+            miette!(
+                "The secure channel listener {} was deleted but could not be created again: {e}",
+                listener.addr
+            )
+        })?;
+    }
+    Ok(listeners.len())
+}
+
+/// Return the listeners created with the rotated identity.
+/// The listeners which don't report their identity are left untouched
+fn listeners_using_identity<'a>(
+    listeners: &'a [ShowSecureChannelListenerResponse],
+    identifier: &Identifier,
+) -> Vec<&'a ShowSecureChannelListenerResponse> {
+    listeners
+        .iter()
+        .filter(|l| l.identifier.as_ref() == Some(identifier))
+        .collect()
+}
+
+/// List of resources which depend on the rotated identity
+struct RotationPlan {
+    identity: NamedIdentity,
+    projects: Vec<Project>,
+    nodes: Vec<NodeInfo>,
+}
+
+impl RotationPlan {
+    /// The identity is only re-enrolled with the projects it administers or holds a credential for
+    async fn create(
+        state: &CliState,
+        identity: &NamedIdentity,
+        re_enroll: bool,
+    ) -> miette::Result<Self> {
+        let projects = if re_enroll {
+            state
+                .get_project_memberships(&identity.name())
+                .await?
+                .into_iter()
+                .filter(|m| m.is_enrolled())
+                .map(|m| m.project().clone())
+                .collect()
+        } else {
+            vec![]
+        };
+        let nodes = state.get_nodes_by_identity_name(&identity.name()).await?;
+        Ok(Self {
+            identity: identity.clone(),
+            projects,
+            nodes,
+        })
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "identity": self.identity.name(),
+            "identifier": self.identity.identifier().to_string(),
+            "projects": self.projects.iter().map(|p| p.name()).collect::<Vec<_>>(),
+            "nodes": self.nodes.iter().map(|n| n.name()).collect::<Vec<_>>(),
+        })
+    }
+}
+
+impl std::fmt::Display for RotationPlan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{}",
+            fmt_log!(
+                "The key of the identity {} would be rotated",
+                color_primary(self.identity.name())
+            )
+        )?;
+        for project in &self.projects {
+            writeln!(
+                f,
+                "{}",
+                fmt_log!(
+                    "The identity would be re-enrolled with the project {}",
+                    color_primary(project.name())
+                )
+            )?;
+        }
+        for node in &self.nodes {
+            writeln!(
+                f,
+                "{}",
+                fmt_log!(
+                    "The secure channel listeners of the node {} would be restarted",
+                    color_primary(node.name())
+                )
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Result of the rotation: what was updated and what requires a manual intervention
+#[derive(Default)]
+struct RotationSummary {
+    updated: Vec<String>,
+    attention: Vec<String>,
+}
+
+impl RotationSummary {
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "updated": self.updated,
+            "needs_attention": self.attention,
+        })
+    }
+}
+
+impl std::fmt::Display for RotationSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for line in &self.updated {
+            writeln!(f, "{}", fmt_ok!("{line}"))?;
+        }
+        for line in &self.attention {
+            writeln!(f, "{}", fmt_warn!("{line}"))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_api::cloud::email_address::EmailAddress;
+    use ockam_api::cloud::project::models::{ProjectModel, ProjectUserRole};
+    use ockam_api::cloud::share::{RoleInShare, ShareScope};
+    use ockam_core::flow_control::FlowControlId;
+    use std::str::FromStr;
+
+    async fn project(name: &str, admin: Option<&str>) -> Project {
+        Project::import(ProjectModel {
+            id: format!("{name}-id"),
+            name: name.to_string(),
+            user_roles: admin
+                .map(|email| ProjectUserRole {
+                    email: email.try_into().unwrap(),
+                    id: 1,
+                    role: RoleInShare::Admin,
+                    scope: ShareScope::Project,
+                })
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn only_the_projects_of_the_identity_are_re_enrolled() -> miette::Result<()> {
+        let state = CliState::test().await?;
+        let alice = state.create_identity_with_name("alice").await?;
+        let email = EmailAddress::parse("alice@example.com").unwrap();
+        state
+            .set_identifier_as_enrolled(&alice.identifier(), &email)
+            .await?;
+        let projects = state.projects();
+        projects
+            .store_project(project("p1", Some("alice@example.com")).await)
+            .await?;
+        projects
+            .store_project(project("p2", Some("bob@example.com")).await)
+            .await?;
+        projects.store_project(project("p3", None).await).await?;
+
+        let plan = RotationPlan::create(&state, &alice, true).await?;
+        let names: Vec<String> = plan.projects.iter().map(|p| p.name().to_string()).collect();
+        assert_eq!(names, vec!["p1"]);
+
+        let plan = RotationPlan::create(&state, &alice, false).await?;
+        assert!(plan.projects.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn only_the_listeners_of_the_identity_are_restarted() {
+        let alice = Identifier::from_str(
+            "I0000000000000000000000000000000000000000000000000000000000000001",
+        )
+        .unwrap();
+        let bob = Identifier::from_str(
+            "I0000000000000000000000000000000000000000000000000000000000000002",
+        )
+        .unwrap();
+        let listener =
+            |addr: &str, identifier: Option<&Identifier>| ShowSecureChannelListenerResponse {
+                addr: addr.into(),
+                flow_control_id: FlowControlId::from(addr.to_string()),
+                identifier: identifier.cloned(),
+                authorized_identifiers: None,
+                max_handshakes_per_second: None,
+                handshakes_per_second: None,
+                rate_limited_handshakes: None,
+            };
+        let listeners = vec![
+            listener("api", Some(&alice)),
+            listener("bob", Some(&bob)),
+            listener("unknown", None),
+        ];
+        let restarted: Vec<String> = listeners_using_identity(&listeners, &alice)
+            .iter()
+            .map(|l| l.addr.to_string())
+            .collect();
+        assert_eq!(restarted, vec!["api"]);
+    }
+}
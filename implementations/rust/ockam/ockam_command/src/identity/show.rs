@@ -1,13 +1,17 @@
 use std::fmt::Display;
+use std::time::Duration;
 
 use crate::identity::list::IdentityListOutput;
+use crate::identity::membership::{get_project_memberships, memberships_section};
 use crate::output::{EncodeFormat, IdentifierDisplay, Output, VerifyingPublicKeyDisplay};
 use crate::util::async_cmd;
+use crate::util::duration::duration_parser;
 use crate::{docs, CommandGlobalOpts};
 use clap::Args;
 use miette::IntoDiagnostic;
 use ockam::identity::verified_change::VerifiedChange;
 use ockam::identity::{Identifier, Identity};
+use ockam::Context;
 use ockam_api::NamedIdentity;
 use serde::Serialize;
 use serde_json::{json, to_string_pretty};
@@ -37,22 +41,29 @@ pub struct ShowCommand {
     //      for `full` (change history) identity.
     #[arg(long, value_enum, requires = "full")]
     encoding: Option<EncodeFormat>,
+
+    /// Verify the membership of the identity with each project authority
+    #[arg(long, conflicts_with = "encoding")]
+    check: bool,
+
+    /// Maximum time spent verifying the membership with each project authority
+    #[arg(long, default_value = "5", value_parser = duration_parser, requires = "check")]
+    check_timeout: Duration,
 }
 
 impl ShowCommand {
     pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
-        async_cmd(&self.name(), opts.clone(), |_ctx| async move {
-            self.async_run(opts).await
+        async_cmd(&self.name(), opts.clone(), |ctx| async move {
+            self.async_run(&ctx, opts).await
         })
     }
     pub fn name(&self) -> String {
         "identity show".into()
     }
 
-    async fn async_run(&self, opts: CommandGlobalOpts) -> miette::Result<()> {
+    async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
         if self.name.is_some() || !opts.terminal.can_ask_for_user_input() {
-            ShowCommand::show_single_identity(&opts, &self.name, self.full, self.encoding.clone())
-                .await?;
+            self.show_single_identity(ctx, &opts, &self.name).await?;
             return Ok(());
         }
 
@@ -66,13 +77,8 @@ impl ShowCommand {
                     .write_line()?;
             }
             1 => {
-                ShowCommand::show_single_identity(
-                    &opts,
-                    &identities_names.first().cloned(),
-                    self.full,
-                    self.encoding.clone(),
-                )
-                .await?;
+                self.show_single_identity(ctx, &opts, &identities_names.first().cloned())
+                    .await?;
             }
             _ => {
                 let selected_names = opts.terminal.select_multiple(
@@ -102,38 +108,61 @@ impl ShowCommand {
 }
 
 impl ShowCommand {
+    /// Show an identity, followed by its enrollment and credential status in each project
     async fn show_single_identity(
+        &self,
+        ctx: &Context,
         opts: &CommandGlobalOpts,
         name: &Option<String>,
-        full: bool,
-        encoding: Option<EncodeFormat>,
     ) -> miette::Result<()> {
-        let identity = opts.state.get_identity_by_optional_name(name).await?;
-
-        let (plain, json) = if full {
-            if Some(EncodeFormat::Hex) == encoding {
-                let change_history = identity.change_history();
-                let encoded = change_history.export_as_string().into_diagnostic()?;
-                let json = to_string_pretty(&json!({"encoded": &encoded}));
-                (encoded, json)
-            } else {
-                let identity: ShowIdentity = identity.into();
-                (identity.to_string(), to_string_pretty(&identity))
-            }
+        let name = opts.state.get_identity_name_or_default(name).await?;
+        let identity = opts
+            .state
+            .get_identity_by_optional_name(&Some(name.clone()))
+            .await?;
+
+        if self.full && Some(EncodeFormat::Hex) == self.encoding {
+            let change_history = identity.change_history();
+            let encoded = change_history.export_as_string().into_diagnostic()?;
+            opts.terminal
+                .clone()
+                .stdout()
+                .plain(&encoded)
+                .json(to_string_pretty(&json!({"encoded": &encoded})).into_diagnostic()?)
+                .machine(&encoded)
+                .write_line()?;
+            return Ok(());
+        }
+
+        let (machine, mut plain, mut json) = if self.full {
+            let identity: ShowIdentity = identity.into();
+            (
+                identity.to_string(),
+                identity.to_string(),
+                serde_json::to_value(&identity).into_diagnostic()?,
+            )
         } else {
             let identifier_display = IdentifierDisplay(identity.identifier().clone());
             (
                 identifier_display.to_string(),
-                to_string_pretty(&json!({"identifier": &identifier_display})),
+                format!("{identifier_display}\n"),
+                json!({"identifier": &identifier_display}),
             )
         };
 
+        let check_timeout = self.check.then_some(self.check_timeout);
+        let memberships = get_project_memberships(ctx, opts, &name, check_timeout).await?;
+        if !memberships.is_empty() {
+            plain = format!("{plain}\n{}", memberships_section(&memberships));
+        }
+        json["projects"] = json!(memberships);
+
         opts.terminal
             .clone()
             .stdout()
-            .plain(&plain)
-            .json(json.into_diagnostic()?)
-            .machine(&plain)
+            .plain(plain.trim_end())
+            .json(to_string_pretty(&json).into_diagnostic()?)
+            .machine(machine.trim_end())
             .write_line()?;
         Ok(())
     }
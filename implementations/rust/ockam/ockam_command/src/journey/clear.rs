@@ -0,0 +1,98 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+
+use ockam_node::Context;
+
+use crate::util::duration::duration_parser;
+use crate::{docs, fmt_ok, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/clear/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/clear/after_long_help.txt");
+
+/// Delete the journey events recorded on this machine
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct ClearCommand {
+    /// Only delete the events older than this duration, for example 30d
+    #[arg(long, value_name = "DURATION", value_parser = duration_parser)]
+    before: Option<Duration>,
+
+    /// Confirm the deletion of all the events without prompting
+    #[arg(display_order = 901, long, short)]
+    yes: bool,
+}
+
+#[async_trait]
+impl Command for ClearCommand {
+    const NAME: &'static str = "journey clear";
+
+    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let now = Utc::now();
+        let before = match self.deleted_before(now)? {
+            Some(before) => before,
+            None => {
+                if !opts.terminal.confirmed_with_flag_or_prompt(
+                    self.yes,
+                    "Are you sure you want to delete all the journey events?",
+                )? {
+                    return Ok(());
+                }
+                now
+            }
+        };
+
+        let deleted = opts.state.delete_journey_events_before(before).await?;
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!("Deleted {deleted} journey events"))
+            .machine(deleted.to_string())
+            .json(serde_json::json!({ "deleted": deleted }))
+            .write_line()?;
+        Ok(())
+    }
+}
+
+impl ClearCommand {
+    /// Return the time before which the events are deleted,
+    /// or None if all the events must be deleted
+    fn deleted_before(&self, now: DateTime<Utc>) -> crate::Result<Option<DateTime<Utc>>> {
+        match self.before {
+            Some(before) => Ok(Some(
+                now - chrono::Duration::from_std(before).into_diagnostic()?,
+            )),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_events_older_than_the_given_duration_are_deleted() {
+        let now = Utc::now();
+        let cmd = ClearCommand {
+            before: Some(Duration::from_secs(30 * 24 * 3600)),
+            yes: false,
+        };
+        assert_eq!(
+            cmd.deleted_before(now).unwrap(),
+            Some(now - chrono::Duration::days(30))
+        );
+
+        let cmd = ClearCommand {
+            before: None,
+            yes: true,
+        };
+        assert_eq!(cmd.deleted_before(now).unwrap(), None);
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, SecondsFormat, Utc};
+use clap::builder::PossibleValuesParser;
+use clap::Args;
+use miette::IntoDiagnostic;
+use serde::Serialize;
+
+use ockam_api::journeys::{
+    attribute_display_name, JourneyEvent, JourneyEventsFilter, StoredJourneyEvent,
+};
+use ockam_node::Context;
+
+use crate::output::Output;
+use crate::util::duration::duration_parser;
+use crate::{color_primary, docs, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/list/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/list/after_long_help.txt");
+
+/// List the journey events recorded on this machine
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct ListCommand {
+    /// Only list the events which happened during this duration, for example 2h or 30d
+    #[arg(long, value_name = "DURATION", value_parser = duration_parser)]
+    since: Option<Duration>,
+
+    /// Only list the events with this name
+    #[arg(long, value_name = "EVENT", value_parser = PossibleValuesParser::new(JourneyEvent::names()))]
+    event: Option<String>,
+
+    /// Only list the events concerning this node
+    #[arg(long, value_name = "NODE_NAME")]
+    node: Option<String>,
+}
+
+#[async_trait]
+impl Command for ListCommand {
+    const NAME: &'static str = "journey list";
+
+    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let events = opts
+            .state
+            .get_journey_events(self.filter()?)
+            .await?
+            .into_iter()
+            .map(JourneyEventOutput::new)
+            .collect::<Vec<_>>();
+
+        let plain = opts.terminal.build_list(
+            &events,
+            "Journey events",
+            "No journey events found on this machine",
+        )?;
+        let json = serde_json::to_string(&events).into_diagnostic()?;
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .json(json)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+impl ListCommand {
+    fn filter(&self) -> crate::Result<JourneyEventsFilter> {
+        let mut filter = JourneyEventsFilter::default();
+        if let Some(since) = self.since {
+            let since = chrono::Duration::from_std(since).into_diagnostic()?;
+            filter = filter.with_since(Utc::now() - since);
+        }
+        if let Some(event) = &self.event {
+            filter = filter.with_event(event);
+        }
+        if let Some(node) = &self.node {
+            filter = filter.with_node_name(node);
+        }
+        Ok(filter)
+    }
+}
+
+#[derive(Serialize)]
+struct JourneyEventOutput {
+    timestamp: String,
+    event: String,
+    description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    node_name: Option<String>,
+    attributes: BTreeMap<String, String>,
+}
+
+impl JourneyEventOutput {
+    fn new(event: StoredJourneyEvent) -> Self {
+        Self {
+            timestamp: event
+                .timestamp()
+                .to_rfc3339_opts(SecondsFormat::Millis, true),
+            event: event.event(),
+            description: event.description(),
+            node_name: event.node_name(),
+            attributes: event.attributes(),
+        }
+    }
+
+    fn human_timestamp(&self) -> String {
+        DateTime::parse_from_rfc3339(&self.timestamp)
+            .map(|t| t.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+            .unwrap_or_else(|_| self.timestamp.clone())
+    }
+}
+
+impl Output for JourneyEventOutput {
+    fn output(&self) -> crate::Result<String> {
+        let mut output = String::new();
+        writeln!(
+            output,
+            "{}  {} ({})",
+            self.human_timestamp(),
+            color_primary(&self.description),
+            self.event
+        )?;
+        for (key, value) in &self.attributes {
+            writeln!(output, "    {}: {}", attribute_display_name(key), value)?;
+        }
+        Ok(output.trim_end().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_are_filtered_with_the_command_options() {
+        let cmd = ListCommand {
+            since: Some(Duration::from_secs(7200)),
+            event: Some("tcp-inlet-created".to_string()),
+            node: Some("n1".to_string()),
+        };
+        let before = Utc::now();
+        let filter = cmd.filter().unwrap();
+        let after = Utc::now();
+        let since = filter.since().unwrap();
+        assert!(since >= before - chrono::Duration::hours(2));
+        assert!(since <= after - chrono::Duration::hours(2));
+        assert_eq!(filter.event(), Some("tcp-inlet-created".to_string()));
+        assert_eq!(filter.node_name(), Some("n1".to_string()));
+
+        let cmd = ListCommand {
+            since: None,
+            event: None,
+            node: None,
+        };
+        assert_eq!(cmd.filter().unwrap(), JourneyEventsFilter::default());
+    }
+
+    #[test]
+    fn events_are_displayed_with_their_timestamp_and_attributes() {
+        let timestamp = DateTime::parse_from_rfc3339("2024-06-01T10:20:30.123Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let event = StoredJourneyEvent::new(
+            "tcp-inlet-created",
+            "Created a TCP inlet",
+            Some("n1".to_string()),
+            BTreeMap::from([("app.node_name".to_string(), "n1".to_string())]),
+            timestamp,
+        );
+        let output = JourneyEventOutput::new(event);
+        assert_eq!(output.timestamp, "2024-06-01T10:20:30.123Z");
+        assert_eq!(output.human_timestamp(), "2024-06-01 10:20:30 UTC");
+
+        let plain = output.output().unwrap();
+        assert!(plain.starts_with("2024-06-01 10:20:30 UTC"));
+        assert!(plain.contains("(tcp-inlet-created)"));
+        assert!(plain.contains(&format!("{}: n1", attribute_display_name("app.node_name"))));
+    }
+}
@@ -0,0 +1,44 @@
+mod clear;
+mod list;
+
+use crate::journey::clear::ClearCommand;
+use crate::journey::list::ListCommand;
+use crate::{docs, Command, CommandGlobalOpts};
+
+use clap::{Args, Subcommand};
+
+const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
+
+/// Inspect the journey events recorded on this machine
+#[derive(Clone, Debug, Args)]
+#[command(
+arg_required_else_help = true,
+subcommand_required = true,
+long_about = docs::about(LONG_ABOUT),
+)]
+pub struct JourneyCommand {
+    #[command(subcommand)]
+    pub subcommand: JourneySubcommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum JourneySubcommand {
+    List(ListCommand),
+    Clear(ClearCommand),
+}
+
+impl JourneyCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        match self.subcommand {
+            JourneySubcommand::List(cmd) => cmd.run(opts),
+            JourneySubcommand::Clear(cmd) => cmd.run(opts),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match &self.subcommand {
+            JourneySubcommand::List(c) => c.name(),
+            JourneySubcommand::Clear(c) => c.name(),
+        }
+    }
+}
@@ -1,9 +1,11 @@
 use std::net::SocketAddr;
 
 use colorful::Colorful;
+use miette::IntoDiagnostic;
 use tokio::{sync::Mutex, try_join};
 
 use ockam::Context;
+use ockam_api::kafka::BrokerAuth;
 use ockam_api::nodes::models::services::{StartKafkaDirectRequest, StartServiceRequest};
 use ockam_api::nodes::BackgroundNodeClient;
 use ockam_api::port_range::PortRange;
@@ -12,6 +14,7 @@ use ockam_multiaddr::MultiAddr;
 
 use crate::node::util::initialize_default_node;
 use crate::node::NodeOpts;
+use crate::output::KafkaServiceOutput;
 use crate::service::start::start_service_impl;
 use crate::terminal::OckamColor;
 use crate::util::process_nodes_multiaddr;
@@ -26,6 +29,8 @@ pub struct ArgOpts {
     pub brokers_port_range: PortRange,
     pub consumer_route: Option<MultiAddr>,
     pub bootstrap_server: SocketAddr,
+    pub broker_auth: Option<BrokerAuth>,
+    pub allowed_topics: Option<Vec<String>>,
 }
 
 pub async fn async_run(
@@ -43,6 +48,8 @@ pub async fn async_run(
         brokers_port_range,
         consumer_route,
         bootstrap_server,
+        broker_auth,
+        allowed_topics,
     } = args;
 
     opts.terminal
@@ -63,7 +70,9 @@ pub async fn async_run(
             bootstrap_server,
             brokers_port_range,
             consumer_route,
-        );
+        )
+        .with_broker_auth(broker_auth)
+        .with_allowed_topics(allowed_topics.clone());
         let payload = StartServiceRequest::new(payload, &addr);
         let req = Request::post(endpoint).body(payload);
         start_service_impl(ctx, &node, &kafka_entity, req).await?;
@@ -112,6 +121,16 @@ pub async fn async_run(
                     .color(OckamColor::PrimaryResource.color())
             ),
         )
+        .json(
+            serde_json::to_string_pretty(
+                &KafkaServiceOutput::new(&kafka_entity, &addr, bootstrap_server)
+                    .with_bind_address(bind_address)
+                    .with_brokers_port_range(brokers_port_range)
+                    .with_broker_auth(broker_auth)
+                    .with_allowed_topics(allowed_topics),
+            )
+            .into_diagnostic()?,
+        )
         .write_line()?;
 
     Ok(())
@@ -5,13 +5,15 @@ use crate::kafka::util::make_brokers_port_range;
 use crate::util::async_cmd;
 use crate::{
     kafka::{
-        kafka_default_consumer_server, kafka_default_outlet_server, kafka_direct_default_addr,
+        allowed_topics, kafka_default_consumer_server, kafka_default_outlet_server,
+        kafka_direct_default_addr,
     },
     node::NodeOpts,
     util::parsers::socket_addr_parser,
     CommandGlobalOpts,
 };
 use clap::{command, Args};
+use ockam_api::kafka::BrokerAuth;
 use ockam_api::port_range::PortRange;
 use ockam_multiaddr::MultiAddr;
 
@@ -37,6 +39,15 @@ pub struct CreateCommand {
     /// The route to another kafka consumer node
     #[arg(long)]
     consumer_route: Option<MultiAddr>,
+    /// The authentication mode required by the Kafka brokers: none, sasl-plain, sasl-scram or tls.
+    /// When set, the brokers are checked when the service is created and
+    /// the creation fails if they require a different mode
+    #[arg(long, value_name = "MODE")]
+    broker_auth: Option<BrokerAuth>,
+    /// Only allow the topics matching this pattern, for example 'orders-*'.
+    /// This option can be repeated. All the topics are allowed when it is not set
+    #[arg(long = "allow-topic", value_name = "PATTERN")]
+    allowed_topics: Vec<String>,
 }
 
 impl CreateCommand {
@@ -53,6 +64,8 @@ impl CreateCommand {
                 .unwrap_or_else(|| make_brokers_port_range(&self.bind_address)),
             consumer_route: self.consumer_route,
             bootstrap_server: self.bootstrap_server,
+            broker_auth: self.broker_auth,
+            allowed_topics: allowed_topics(self.allowed_topics),
         };
         async_cmd(&cmd_name, opts.clone(), |ctx| async move {
             async_run(&ctx, opts, args_opts).await
@@ -45,6 +45,7 @@ impl DeleteCommand {
                 "Kafka consumer with address `{}` successfully deleted",
                 self.address
             ))
+            .json(serde_json::json!({ "address": &self.address }))
             .write_line()?;
 
         Ok(())
@@ -1,5 +1,6 @@
 use clap::Args;
 use colorful::Colorful;
+use miette::IntoDiagnostic;
 
 use ockam_api::nodes::models::services::ServiceList;
 use ockam_api::nodes::service::default_address::DefaultAddress;
@@ -7,6 +8,7 @@ use ockam_api::nodes::BackgroundNodeClient;
 use ockam_core::api::Request;
 use ockam_node::Context;
 
+use crate::node::models::services::ShowServiceStatus;
 use crate::node::NodeOpts;
 use crate::util::async_cmd;
 use crate::{docs, fmt_err, CommandGlobalOpts};
@@ -44,18 +46,38 @@ impl ListCommand {
                 Request::get(format!("/node/services/{}", DefaultAddress::KAFKA_DIRECT)),
             )
             .await?;
+        let json = serde_json::to_string_pretty(
+            &services
+                .list
+                .iter()
+                .cloned()
+                .map(ShowServiceStatus::from)
+                .collect::<Vec<_>>(),
+        )
+        .into_diagnostic()?;
         if services.list.is_empty() {
             opts.terminal
                 .stdout()
                 .plain(fmt_err!("No Kafka Direct Client found on this node"))
+                .json(json)
                 .write_line()?;
         } else {
             let mut buf = String::new();
             buf.push_str("Kafka Direct Clients:\n");
             for service in services.list {
                 buf.push_str(&format!("{:2}Address: {}\n", "", service.addr));
+                if let Some(broker_auth) = &service.broker_auth {
+                    buf.push_str(&format!("{:2}Broker auth: {}\n", "", broker_auth));
+                }
+                if let Some(allowed_topics) = &service.allowed_topics {
+                    buf.push_str(&format!(
+                        "{:2}Allowed topics: {}\n",
+                        "",
+                        allowed_topics.join(", ")
+                    ));
+                }
             }
-            opts.terminal.stdout().plain(buf).write_line()?;
+            opts.terminal.stdout().plain(buf).json(json).write_line()?;
         }
         Ok(())
     }
@@ -13,6 +13,15 @@ const KAFKA_DEFAULT_PROJECT_ROUTE: &str = "/project/default";
 const KAFKA_DEFAULT_CONSUMER_SERVER: &str = "127.0.0.1:4000";
 const KAFKA_DEFAULT_PRODUCER_SERVER: &str = "127.0.0.1:5000";
 
+/// Return the patterns of the allowed topics, or None if all the topics are allowed
+fn allowed_topics(patterns: Vec<String>) -> Option<Vec<String>> {
+    if patterns.is_empty() {
+        None
+    } else {
+        Some(patterns)
+    }
+}
+
 fn kafka_default_outlet_addr() -> String {
     DefaultAddress::KAFKA_OUTLET.to_string()
 }
@@ -2,19 +2,22 @@ use std::net::SocketAddr;
 
 use clap::{command, Args};
 use colorful::Colorful;
+use miette::IntoDiagnostic;
 use tokio::{sync::Mutex, try_join};
 
 use ockam::Context;
+use ockam_api::kafka::BrokerAuth;
 use ockam_api::nodes::models::services::StartKafkaOutletRequest;
 use ockam_api::nodes::models::services::StartServiceRequest;
 use ockam_api::nodes::BackgroundNodeClient;
 use ockam_core::api::Request;
 
 use crate::node::util::initialize_default_node;
+use crate::output::KafkaServiceOutput;
 use crate::util::async_cmd;
 use crate::{
     fmt_log, fmt_ok,
-    kafka::{kafka_default_outlet_addr, kafka_default_outlet_server},
+    kafka::{allowed_topics, kafka_default_outlet_addr, kafka_default_outlet_server},
     node::NodeOpts,
     service::start::start_service_impl,
     terminal::OckamColor,
@@ -32,6 +35,15 @@ pub struct CreateCommand {
     /// The address of the kafka bootstrap broker
     #[arg(long, default_value_t = kafka_default_outlet_server())]
     bootstrap_server: SocketAddr,
+    /// The authentication mode required by the Kafka brokers: none, sasl-plain, sasl-scram or tls.
+    /// When set, the brokers are checked when the service is created and
+    /// the creation fails if they require a different mode
+    #[arg(long, value_name = "MODE")]
+    broker_auth: Option<BrokerAuth>,
+    /// Only allow the topics matching this pattern, for example 'orders-*'.
+    /// This option can be repeated. All the topics are allowed when it is not set
+    #[arg(long = "allow-topic", value_name = "PATTERN")]
+    allowed_topics: Vec<String>,
 }
 
 impl CreateCommand {
@@ -51,7 +63,9 @@ impl CreateCommand {
             .write_line(&fmt_log!("Creating KafkaOutlet service"))?;
         let is_finished = Mutex::new(false);
         let send_req = async {
-            let payload = StartKafkaOutletRequest::new(self.bootstrap_server);
+            let payload = StartKafkaOutletRequest::new(self.bootstrap_server)
+                .with_broker_auth(self.broker_auth)
+                .with_allowed_topics(allowed_topics(self.allowed_topics.clone()));
             let payload = StartServiceRequest::new(payload, &self.addr);
             let req = Request::post("/node/services/kafka_outlet").body(payload);
             let node =
@@ -91,6 +105,14 @@ impl CreateCommand {
                     .to_string()
                     .color(OckamColor::PrimaryResource.color())
             ))
+            .json(
+                serde_json::to_string_pretty(
+                    &KafkaServiceOutput::new("KafkaOutlet", &self.addr, self.bootstrap_server)
+                        .with_broker_auth(self.broker_auth)
+                        .with_allowed_topics(allowed_topics(self.allowed_topics.clone())),
+                )
+                .into_diagnostic()?,
+            )
             .write_line()?;
 
         Ok(())
@@ -2,6 +2,7 @@ use std::net::SocketAddr;
 
 use clap::{command, Args};
 
+use ockam_api::kafka::BrokerAuth;
 use ockam_api::port_range::PortRange;
 use ockam_multiaddr::MultiAddr;
 
@@ -9,7 +10,8 @@ use crate::kafka::util::{async_run, make_brokers_port_range, ArgOpts};
 use crate::util::async_cmd;
 use crate::{
     kafka::{
-        kafka_default_producer_server, kafka_default_project_route, kafka_producer_default_addr,
+        allowed_topics, kafka_default_producer_server, kafka_default_project_route,
+        kafka_producer_default_addr,
     },
     node::NodeOpts,
     util::parsers::socket_addr_parser,
@@ -35,6 +37,15 @@ pub struct CreateCommand {
     /// The route to the project in ockam orchestrator, expected something like /project/<name>
     #[arg(long, default_value_t = kafka_default_project_route())]
     project_route: MultiAddr,
+    /// The authentication mode required by the Kafka brokers: none, sasl-plain, sasl-scram or tls.
+    /// The brokers are only reachable from the kafka outlet node, so the mode is recorded
+    /// and can be checked with `ockam kafka-producer list`
+    #[arg(long, value_name = "MODE")]
+    broker_auth: Option<BrokerAuth>,
+    /// Only allow the topics matching this pattern, for example 'orders-*'.
+    /// This option can be repeated. All the topics are allowed when it is not set
+    #[arg(long = "allow-topic", value_name = "PATTERN")]
+    allowed_topics: Vec<String>,
 }
 
 impl CreateCommand {
@@ -50,6 +61,8 @@ impl CreateCommand {
                 .brokers_port_range
                 .unwrap_or_else(|| make_brokers_port_range(&self.bootstrap_server)),
             project_route: self.project_route,
+            broker_auth: self.broker_auth,
+            allowed_topics: allowed_topics(self.allowed_topics),
         };
         async_cmd(&cmd_name, opts.clone(), |ctx| async move {
             async_run(&ctx, opts, arg_opts).await
@@ -1,9 +1,11 @@
 use std::net::SocketAddr;
 
 use colorful::Colorful;
+use miette::IntoDiagnostic;
 use tokio::{sync::Mutex, try_join};
 
 use ockam::Context;
+use ockam_api::kafka::BrokerAuth;
 use ockam_api::nodes::models::services::{StartKafkaRequest, StartServiceRequest};
 use ockam_api::nodes::BackgroundNodeClient;
 use ockam_api::port_range::PortRange;
@@ -12,6 +14,7 @@ use ockam_multiaddr::MultiAddr;
 
 use crate::node::util::initialize_default_node;
 use crate::node::NodeOpts;
+use crate::output::KafkaServiceOutput;
 use crate::service::start::start_service_impl;
 use crate::terminal::OckamColor;
 use crate::util::process_nodes_multiaddr;
@@ -25,6 +28,8 @@ pub struct ArgOpts {
     pub bootstrap_server: SocketAddr,
     pub brokers_port_range: PortRange,
     pub project_route: MultiAddr,
+    pub broker_auth: Option<BrokerAuth>,
+    pub allowed_topics: Option<Vec<String>>,
 }
 
 /// Return a range of 100 ports after the bootstrap server port
@@ -48,6 +53,8 @@ pub async fn async_run(
         bootstrap_server,
         brokers_port_range,
         project_route,
+        broker_auth,
+        allowed_topics,
     } = args;
 
     opts.terminal
@@ -63,7 +70,9 @@ pub async fn async_run(
             bootstrap_server.to_owned(),
             brokers_port_range,
             project_route,
-        );
+        )
+        .with_broker_auth(broker_auth)
+        .with_allowed_topics(allowed_topics.clone());
         let payload = StartServiceRequest::new(payload, &addr);
         let req = Request::post(endpoint).body(payload);
         start_service_impl(ctx, &node, &kafka_entity, req).await?;
@@ -127,6 +136,15 @@ pub async fn async_run(
                 script_to_run.color(OckamColor::Success.color())
             ),
         )
+        .json(
+            serde_json::to_string_pretty(
+                &KafkaServiceOutput::new(&kafka_entity, &addr, bootstrap_server)
+                    .with_brokers_port_range(brokers_port_range)
+                    .with_broker_auth(broker_auth)
+                    .with_allowed_topics(allowed_topics),
+            )
+            .into_diagnostic()?,
+        )
         .write_line()?;
 
     Ok(())
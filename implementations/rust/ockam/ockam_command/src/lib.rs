@@ -19,10 +19,12 @@
 mod admin;
 mod arguments;
 mod authority;
+mod bench;
 mod command;
 mod command_events;
 mod command_global_opts;
 mod completion;
+mod config;
 mod credential;
 mod docs;
 pub mod enroll;
@@ -32,6 +34,7 @@ pub mod error;
 mod flow_control;
 mod global_args;
 pub mod identity;
+mod journey;
 mod kafka;
 mod lease;
 mod manpages;
@@ -41,12 +44,14 @@ pub mod node;
 mod operation;
 mod output;
 pub mod pager;
+mod ping;
 mod policy;
 mod progress_display;
 mod project;
 mod project_member;
 mod relay;
 mod reset;
+mod restore;
 mod run;
 mod secure_channel;
 mod service;
@@ -55,11 +60,13 @@ mod share;
 pub mod shutdown;
 mod sidecar;
 mod space;
+mod state;
 mod status;
 mod subcommand;
 mod subscription;
 pub mod tcp;
 mod terminal;
+mod trace;
 mod upgrade;
 pub mod util;
 pub mod value_parsers;
@@ -1,4 +1,4 @@
-use crate::CommandGlobalOpts;
+use crate::{Command, CommandGlobalOpts};
 use clap::{Args, Subcommand};
 pub use send::SendCommand;
 
@@ -1,23 +1,32 @@
 use core::time::Duration;
+use std::path::PathBuf;
+use std::time::Instant;
 
+use async_trait::async_trait;
 use clap::Args;
-use miette::{Context as _, IntoDiagnostic};
-use tracing::info;
+use miette::{miette, Context as _, IntoDiagnostic};
+use tracing::{info, warn};
 
-use ockam::Context;
+use ockam::identity::models::CredentialAndPurposeKey;
+use ockam::identity::Identifier;
+use ockam::{route, Context};
 use ockam_api::address::extract_address_value;
-use ockam_api::nodes::service::messages::Messages;
-use ockam_api::nodes::BackgroundNodeClient;
-use ockam_api::nodes::InMemoryNode;
+use ockam_api::enroll::enrollment::Enrollment;
+use ockam_api::nodes::service::default_address::DefaultAddress;
+use ockam_api::nodes::service::messages::{EphemeralSecureChannels, Messages};
+use ockam_api::nodes::{BackgroundNodeClient, InMemoryNode, NodeManager};
+use ockam_api::route_to_multiaddr;
+use ockam_multiaddr::proto::{Channel, Service};
 use ockam_multiaddr::MultiAddr;
 
 use crate::project::util::{
     clean_projects_multiaddr, get_projects_secure_channels_from_config_lookup,
 };
 use crate::util::api::{IdentityOpts, TrustOpts};
+use crate::util::clean_nodes_multiaddr;
 use crate::util::duration::duration_parser;
-use crate::util::{async_cmd, clean_nodes_multiaddr};
-use crate::{docs, CommandGlobalOpts};
+use crate::util::retry::{notify_retry, RetryExecutor, RetryStrategy};
+use crate::{docs, Command, CommandGlobalOpts, Error};
 
 const LONG_ABOUT: &str = include_str!("./static/send/long_about.txt");
 const AFTER_LONG_HELP: &str = include_str!("./static/send/after_long_help.txt");
@@ -38,15 +47,52 @@ pub struct SendCommand {
     #[arg(short, long, value_name = "ROUTE")]
     pub to: MultiAddr,
 
-    /// Flag to indicate that the message is hex encoded
+    /// Send the message through the secure channel created with this name on the `--from` node
+    #[arg(long, value_name = "NAME", requires = "from")]
+    pub via_channel: Option<String>,
+
+    /// Send the message through a secure channel created for this message only.
+    /// The channel is created to the secure channel listener of the node reached by the
+    /// route without its last hop, and it is deleted once the message is sent
+    #[arg(long, conflicts_with = "via_channel")]
+    pub secure: bool,
+
+    /// Present a credential issued by the project authority when creating the secure channel,
+    /// so that the attributes of the identity can be checked by the policies of the receiver
+    #[arg(long, requires = "secure")]
+    pub credential: bool,
+
+    /// Flag to indicate that the message is hex encoded.
+    /// The reply is then also printed hex encoded
     #[arg(long)]
     pub hex: bool,
 
+    /// Read the message from a file, as raw bytes, instead of the command line
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["message", "hex"])]
+    pub file: Option<PathBuf>,
+
+    /// Print the reply hex encoded, for replies which are not valid UTF-8 strings
+    #[arg(long)]
+    pub expect_hex: bool,
+
+    /// Write the reply, as raw bytes, to a file instead of printing it
+    #[arg(long, value_name = "PATH")]
+    pub output_file: Option<PathBuf>,
+
     /// Override default timeout
     #[arg(long, value_name = "TIMEOUT", default_value = "10s", value_parser = duration_parser)]
     pub timeout: Duration,
 
-    pub message: String,
+    /// Number of times to send the message again if no reply is received
+    #[arg(long, value_name = "COUNT", default_value_t = 0)]
+    pub retries: u32,
+
+    /// Delay between two attempts to send the message
+    #[arg(long, value_name = "DELAY", default_value = "1s", value_parser = duration_parser)]
+    pub retry_delay: Duration,
+
+    #[arg(required_unless_present = "file")]
+    pub message: Option<String>,
 
     #[command(flatten)]
     identity_opts: IdentityOpts,
@@ -55,38 +101,65 @@ pub struct SendCommand {
     pub trust_opts: TrustOpts,
 }
 
-impl SendCommand {
-    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
-        async_cmd(&self.name(), opts.clone(), |ctx| async move {
-            self.async_run(&ctx, opts).await
-        })
-    }
-
-    pub fn name(&self) -> String {
-        "message send".into()
-    }
+#[async_trait]
+impl Command for SendCommand {
+    const NAME: &'static str = "message send";
 
-    async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
         // Process `--to` Multiaddr
-        let (to, meta) = clean_nodes_multiaddr(&self.to, &opts.state)
+        let to = match &self.via_channel {
+            Some(name) => {
+                let mut to = MultiAddr::default();
+                to.push_back(Channel::new(name)).into_diagnostic()?;
+                to.try_extend(self.to.iter()).into_diagnostic()?;
+                to
+            }
+            None => self.to.clone(),
+        };
+        let (to, meta) = clean_nodes_multiaddr(&to, &opts.state)
             .await
             .context("Argument '--to' is invalid")?;
 
-        let msg_bytes = if self.hex {
-            hex::decode(self.message.clone())
-                .into_diagnostic()
-                .context("The message is not a valid hex string")?
-        } else {
-            self.message.as_bytes().to_vec()
-        };
+        let msg_bytes = self.message_bytes()?;
 
         // Setup environment depending on whether we are sending the message from a background node
         // or an in-memory node
-        let response: Vec<u8> = if let Some(node) = &self.from {
-            BackgroundNodeClient::create_to_node(ctx, &opts.state, node.as_str())
-                .await?
-                .send_message(ctx, &to, msg_bytes, Some(self.timeout))
-                .await?
+        let (reply, their_identifier) = if let Some(node) = &self.from {
+            let node =
+                BackgroundNodeClient::create_to_node(ctx, &opts.state, node.as_str()).await?;
+            let credential = if self.credential {
+                let identity_name = match &self.identity_opts.identity {
+                    Some(identity_name) => identity_name.clone(),
+                    None => {
+                        let node_info = opts.state.get_node(node.node_name()).await?;
+                        opts.state
+                            .get_named_identity_by_identifier(&node_info.identifier())
+                            .await?
+                            .name()
+                    }
+                };
+                let in_memory_node = self
+                    .trust_opts
+                    .in_memory_node_builder(&identity_name)
+                    .build(ctx, &opts.state)
+                    .await?;
+                Some(
+                    self.project_credential(ctx, &opts, &in_memory_node, &identity_name)
+                        .await?,
+                )
+            } else {
+                None
+            };
+            self.send(
+                ctx,
+                &opts,
+                &node,
+                &to,
+                msg_bytes,
+                self.identity_opts.identity.clone(),
+                credential,
+            )
+            .await?
         } else {
             let identity_name = opts
                 .state
@@ -95,15 +168,11 @@ impl SendCommand {
 
             info!("starting an in memory node to send a message");
 
-            let node_manager = InMemoryNode::start_node(
-                ctx,
-                &opts.state,
-                &identity_name,
-                self.trust_opts.project_name.clone(),
-                self.trust_opts.authority_identity.clone(),
-                self.trust_opts.authority_route.clone(),
-            )
-            .await?;
+            let node_manager = self
+                .trust_opts
+                .in_memory_node_builder(&identity_name)
+                .build(ctx, &opts.state)
+                .await?;
             info!("started an in memory node to send a message");
 
             // Replace `/project/<name>` occurrences with their respective secure channel addresses
@@ -117,21 +186,235 @@ impl SendCommand {
             )
             .await?;
             let to = clean_projects_multiaddr(to, projects_sc)?;
+            let credential = if self.credential {
+                Some(
+                    self.project_credential(ctx, &opts, &node_manager, &identity_name)
+                        .await?,
+                )
+            } else {
+                None
+            };
             info!("sending to {to}");
-            node_manager
-                .send_message(ctx, &to, msg_bytes, Some(self.timeout))
-                .await?
+            self.send(
+                ctx,
+                &opts,
+                &**node_manager,
+                &to,
+                msg_bytes,
+                Some(identity_name),
+                credential,
+            )
+            .await?
         };
 
-        let result = if self.hex {
-            hex::encode(response)
-        } else {
-            String::from_utf8(response)
+        let reply_text = if let Some(path) = &self.output_file {
+            std::fs::write(path, &reply.message)
                 .into_diagnostic()
-                .context("Received content is not a valid utf8 string")?
+                .context(format!("Failed to write the reply to {}", path.display()))?;
+            None
+        } else if self.hex || self.expect_hex {
+            Some(hex::encode(&reply.message))
+        } else {
+            Some(String::from_utf8(reply.message.clone()).map_err(|_| {
+                miette!(
+                    help = "Use --expect-hex to print the reply hex encoded, or --output-file to write it to a file",
+                    "The reply of {} bytes is not a valid UTF-8 string",
+                    reply.message.len()
+                )
+            })?)
         };
 
-        opts.terminal.stdout().plain(result).write_line()?;
+        let plain = match &self.output_file {
+            Some(path) => format!(
+                "Wrote a reply of {} bytes to {}",
+                reply.message.len(),
+                path.display()
+            ),
+            None => reply_text.clone().unwrap_or_default(),
+        };
+        opts.terminal
+            .stdout()
+            .plain(&plain)
+            .machine(&plain)
+            .json(serde_json::json!({
+                "reply": reply_text,
+                "reply_length": reply.message.len(),
+                "output_file": self.output_file,
+                "attempts": reply.attempts,
+                "latency_ms": reply.latency.as_millis() as u64,
+                "secure_channel": their_identifier.is_some(),
+                "their_identifier": their_identifier.map(|i| i.to_string()),
+            }))
+            .write_line()?;
         Ok(())
     }
 }
+
+impl SendCommand {
+    /// Return the bytes of the message to send, read from the command line or from a file
+    fn message_bytes(&self) -> miette::Result<Vec<u8>> {
+        if let Some(path) = &self.file {
+            return std::fs::read(path).into_diagnostic().context(format!(
+                "Failed to read the message from {}",
+                path.display()
+            ));
+        }
+        let message = self.message.clone().unwrap_or_default();
+        if self.hex {
+            hex::decode(message)
+                .into_diagnostic()
+                .context("The message is not a valid hex string")
+        } else {
+            Ok(message.into_bytes())
+        }
+    }
+
+    /// Return a credential issued by the project authority to the identity sending the message
+    async fn project_credential(
+        &self,
+        ctx: &Context,
+        opts: &CommandGlobalOpts,
+        node: &NodeManager,
+        identity_name: &str,
+    ) -> miette::Result<CredentialAndPurposeKey> {
+        let project = opts
+            .state
+            .projects()
+            .get_project_by_name_or_default(&self.trust_opts.project_name)
+            .await
+            .context("A project is required to present a credential")?;
+        node.create_authority_client(&project, Some(identity_name.to_string()))
+            .await?
+            .issue_credential(ctx, None)
+            .await
+            .context("Failed to get a credential from the project authority")
+    }
+
+    /// Send the message, through an ephemeral secure channel if `--secure` is used.
+    /// The channel is deleted even when no reply was received.
+    /// Return the reply and the identifier of the other side of the channel
+    #[allow(clippy::too_many_arguments)]
+    async fn send<M: Messages + EphemeralSecureChannels + Sync>(
+        &self,
+        ctx: &Context,
+        opts: &CommandGlobalOpts,
+        sender: &M,
+        to: &MultiAddr,
+        message: Vec<u8>,
+        identity_name: Option<String>,
+        credential: Option<CredentialAndPurposeKey>,
+    ) -> crate::Result<(Reply, Option<Identifier>)> {
+        if !self.secure {
+            let reply = self
+                .send_with_retries(ctx, opts, sender, to, message)
+                .await?;
+            return Ok((reply, None));
+        }
+
+        let (listener, service) = split_last_hop(to)?;
+        let channel = sender
+            .create_ephemeral_secure_channel(
+                ctx,
+                &listener,
+                identity_name,
+                credential,
+                Some(self.timeout),
+            )
+            .await
+            .context(format!("Failed to create a secure channel to {listener}"))?;
+        info!(%listener, their_identifier = %channel.their_identifier, "created an ephemeral secure channel");
+
+        let reply = match route_to_multiaddr(&route![channel.address.to_string()]) {
+            Some(mut to) => match to.try_extend(service.iter()) {
+                Ok(()) => {
+                    self.send_with_retries(ctx, opts, sender, &to, message)
+                        .await
+                }
+                Err(e) => Err(miette!(e).into()),
+            },
+            None => Err(miette!("Invalid secure channel address {}", channel.address).into()),
+        };
+        if let Err(e) = sender
+            .delete_ephemeral_secure_channel(ctx, &channel.address)
+            .await
+        {
+            warn!(%e, address = %channel.address, "failed to delete the ephemeral secure channel");
+        }
+        Ok((reply?, Some(channel.their_identifier)))
+    }
+
+    /// Send the message and wait for its reply, sending it again up to `--retries` times,
+    /// every `--retry-delay`, when no reply is received
+    async fn send_with_retries<M: Messages + Sync>(
+        &self,
+        ctx: &Context,
+        opts: &CommandGlobalOpts,
+        sender: &M,
+        to: &MultiAddr,
+        message: Vec<u8>,
+    ) -> crate::Result<Reply> {
+        let retry_strategy = RetryStrategy::new(Some(self.retries + 1), self.retry_delay)
+            .with_max_delay(self.retry_delay)
+            .without_jitter();
+        RetryExecutor::new(retry_strategy)
+            .run(
+                |attempt| {
+                    let message = message.clone();
+                    async move {
+                        let started_at = Instant::now();
+                        let message = sender
+                            .send_message(ctx, to, message, Some(self.timeout))
+                            .await
+                            .map_err(Error::Retry)?;
+                        Ok(Reply {
+                            message,
+                            latency: started_at.elapsed(),
+                            attempts: attempt.number(),
+                        })
+                    }
+                },
+                |error, next, delay| notify_retry(opts, error, next, delay),
+            )
+            .await
+    }
+}
+
+/// Split a route into the route to the secure channel listener of its last-but-one hop,
+/// and the last hop, which is reached through the secure channel
+fn split_last_hop(to: &MultiAddr) -> miette::Result<(MultiAddr, MultiAddr)> {
+    if to.len() < 2 {
+        return Err(miette!(
+            help = "Use a route to a service on another node, e.g. /node/n2/service/echo",
+            "The route {to} has no hop before the service receiving the message"
+        ));
+    }
+    let (mut listener, service) = to.split(to.len() - 1);
+    listener
+        .push_back(Service::new(DefaultAddress::SECURE_CHANNEL_LISTENER))
+        .into_diagnostic()?;
+    Ok((listener, service))
+}
+
+/// Reply to a message, with the time it took to receive it
+struct Reply {
+    message: Vec<u8>,
+    latency: Duration,
+    attempts: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn the_secure_channel_is_created_to_the_last_but_one_hop() {
+        let to = MultiAddr::from_str("/ip4/127.0.0.1/tcp/4000/service/echo").unwrap();
+        let (listener, service) = split_last_hop(&to).unwrap();
+        assert_eq!(listener.to_string(), "/ip4/127.0.0.1/tcp/4000/service/api");
+        assert_eq!(service.to_string(), "/service/echo");
+
+        let to = MultiAddr::from_str("/service/echo").unwrap();
+        assert!(split_last_hop(&to).is_err());
+    }
+}
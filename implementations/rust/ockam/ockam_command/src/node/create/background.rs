@@ -12,7 +12,8 @@ use ockam_api::nodes::BackgroundNodeClient;
 use ockam_core::OpenTelemetryContext;
 
 use crate::node::show::is_node_up;
-use crate::node::util::spawn_node;
+use crate::node::system_service::SystemServiceDefinition;
+use crate::node::util::{node_process_args, spawn_node, spawn_watchdog_if_supervised};
 use crate::node::CreateCommand;
 use crate::terminal::OckamColor;
 use crate::CommandGlobalOpts;
@@ -26,6 +27,9 @@ impl CreateCommand {
         ctx: &Context,
         opts: CommandGlobalOpts,
     ) -> miette::Result<()> {
+        if self.report_existing_node(&opts).await? {
+            return Ok(());
+        }
         if !self.skip_is_running_check {
             self.guard_node_is_not_already_running(&opts).await?;
         }
@@ -48,6 +52,8 @@ impl CreateCommand {
                 "Cannot create a background node from another background node"
             ));
         }
+        self.store_node_proxy(&opts).await?;
+        self.store_node_logging(&opts).await?;
 
         let is_finished: Mutex<bool> = Mutex::new(false);
 
@@ -61,7 +67,11 @@ impl CreateCommand {
         };
 
         let send_req = async {
-            cmd_with_trace_context.spawn_background_node(&opts).await?;
+            if self.as_system_service {
+                self.clone().install_system_service(&opts).await?;
+            } else {
+                cmd_with_trace_context.spawn_background_node(&opts).await?;
+            }
             let mut node =
                 BackgroundNodeClient::create_to_node(ctx, &opts.state, &node_name).await?;
             let is_node_up = is_node_up(ctx, &mut node, true).await?;
@@ -81,6 +91,13 @@ impl CreateCommand {
 
         let (_response, _) = try_join!(send_req, progress_output)?;
 
+        if self.restart_on_failure {
+            opts.state
+                .set_node_supervision(&node_name, self.max_restarts, self.max_restart_backoff)
+                .await?;
+            spawn_watchdog_if_supervised(&opts, &node_name).await?;
+        }
+
         let mut attributes = HashMap::new();
         attributes.insert(NODE_NAME, node_name.clone());
         opts.state
@@ -100,6 +117,8 @@ impl CreateCommand {
                         "ockam node show".color(OckamColor::PrimaryResource.color())
                     ),
             )
+            .machine(&node_name)
+            .json(serde_json::json!({ "name": &node_name }))
             .write_line()?;
 
         Ok(())
@@ -120,4 +139,23 @@ impl CreateCommand {
 
         Ok(())
     }
+
+    /// Install a system service running this node in foreground mode, and record it
+    /// so that the service can be removed when the node is deleted
+    async fn install_system_service(self, opts: &CommandGlobalOpts) -> miette::Result<()> {
+        let node_name = self.name.clone();
+        // the service outlives this command, so its trace context is not propagated
+        let args = node_process_args(
+            opts,
+            CreateCommand {
+                opentelemetry_context: None,
+                ..self
+            },
+        )
+        .await?;
+        let system_service =
+            SystemServiceDefinition::new(&opts.state, &node_name, args)?.install()?;
+        opts.state.set_node_system_service(&system_service).await?;
+        Ok(())
+    }
 }
@@ -1,24 +1,40 @@
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+use ockam_abac::{Action, ResourceType};
+use ockam_api::nodes::models::policies::ResourceTypeOrName;
+use ockam_api::nodes::service::portals::Inlets;
+use ockam_api::nodes::{BackgroundNodeClient, Policies as _};
+use ockam_api::{random_name, DefaultAddress};
+use ockam_core::api::Request;
+use ockam_node::Context;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
 use crate::node::CreateCommand;
 use crate::run::parser::building_blocks::ArgValue;
 use crate::run::parser::config::ConfigParser;
 use crate::run::parser::resource::*;
 use crate::run::parser::Version;
 use crate::value_parsers::async_parse_path_or_url;
-use crate::CommandGlobalOpts;
-use ockam_api::random_name;
-use ockam_node::Context;
-use serde::{Deserialize, Serialize};
+use crate::{color_primary, fmt_ok, fmt_warn, CommandGlobalOpts};
 
 impl CreateCommand {
     pub async fn run_config(self, ctx: &Context, opts: &CommandGlobalOpts) -> miette::Result<()> {
-        let contents = async_parse_path_or_url(&self.name).await?;
+        let contents = async_parse_path_or_url(self.config_path()).await?;
         // Set environment variables from the cli command args
         for (key, value) in &self.variables {
             std::env::set_var(key, value);
         }
+        let keep_partial = self.keep_partial;
         let mut config = NodeConfig::new(&contents)?;
         let node_name = config.merge(self)?;
-        config.run(ctx, opts.clone(), &node_name).await?;
+        config
+            .run(ctx, opts.clone(), &node_name, keep_partial)
+            .await?;
         Ok(())
     }
 }
@@ -64,8 +80,14 @@ impl NodeConfig {
         }
 
         // Merge the node arguments from the config with the cli command args.
+        // The name argument is a node name only if the config file was given with `--configuration`
         if self.node.name.is_none() {
-            self.node.name = Some(ArgValue::String(random_name()));
+            let name = if cli_args.configuration.is_some() {
+                cli_args.name.clone()
+            } else {
+                random_name()
+            };
+            self.node.name = Some(ArgValue::String(name));
         }
         if self.node.skip_is_running_check.is_none() {
             self.node.skip_is_running_check = Some(ArgValue::Bool(cli_args.skip_is_running_check));
@@ -82,33 +104,252 @@ impl NodeConfig {
         if self.node.project.is_none() {
             self.node.project = cli_args.trust_opts.project_name.map(ArgValue::String);
         }
+        if self.node.connect_on_start.is_none() {
+            self.node.connect_on_start = cli_args.connect_on_start.map(ArgValue::String);
+        }
 
         let node_name = self.node.name.as_ref().unwrap().to_string();
         Ok(node_name)
     }
 
+    /// Create the resources declared in the config file, in dependency order:
+    /// the node first, then the policies protecting the portals, the outlets, the relays
+    /// and finally the inlets, which may be routed through a relay.
+    ///
+    /// If a resource fails to be created, the resources already created are deleted,
+    /// unless `keep_partial` is set. A summary of all the resources is displayed at the end.
     pub async fn run(
         self,
         ctx: &Context,
         opts: CommandGlobalOpts,
         node_name: &str,
+        keep_partial: bool,
     ) -> miette::Result<()> {
         let overrides = &ValuesOverrides::default().with_override_node_name(node_name);
+        let node_existed = opts.state.get_node(node_name).await.is_ok();
 
         // Build commands and return validation errors before running any command.
-        let commands: Vec<ParsedCommands> = vec![
-            self.project_enroll.parse_commands(overrides)?.into(),
-            self.node.parse_commands(overrides)?.into(),
-            self.relays.parse_commands(overrides)?.into(),
-            self.policies.parse_commands(overrides)?.into(),
-            self.tcp_outlets.parse_commands(overrides)?.into(),
-            self.tcp_inlets.parse_commands(overrides)?.into(),
-        ];
+        let mut resources: Vec<ConfigResource> = vec![];
+        for cmd in self.project_enroll.parse_commands(overrides)? {
+            resources.push(ConfigResource::new(ResourceKind::Enrollment, "ticket", cmd));
+        }
+        for cmd in self.node.parse_commands(overrides)? {
+            let name = cmd.name.clone();
+            resources.push(ConfigResource::new(ResourceKind::Node, name, cmd));
+        }
+        for cmd in self.policies.parse_commands(overrides)? {
+            let name = ResourceTypeOrName::new(cmd.resource_type.as_ref(), cmd.resource.as_ref())
+                .into_diagnostic()?
+                .to_string();
+            resources.push(ConfigResource::new(ResourceKind::Policy, name, cmd));
+        }
+        for cmd in self.tcp_outlets.parse_commands(overrides)? {
+            let name = cmd
+                .from
+                .clone()
+                .unwrap_or(DefaultAddress::OUTLET_SERVICE.to_string());
+            resources.push(ConfigResource::new(ResourceKind::TcpOutlet, name, cmd));
+        }
+        for cmd in self.relays.parse_commands(overrides)? {
+            let name = cmd.relay_name.clone();
+            resources.push(ConfigResource::new(ResourceKind::Relay, name, cmd));
+        }
+        for cmd in self.tcp_inlets.parse_commands(overrides)? {
+            let name = cmd.alias.clone();
+            resources.push(ConfigResource::new(ResourceKind::TcpInlet, name, cmd));
+        }
 
         // Run commands
-        for cmd in commands {
-            cmd.run(ctx, &opts).await?
+        let mut failure = None;
+        for resource in resources.iter_mut() {
+            if let Err(e) = resource.create(ctx, &opts).await {
+                failure = Some(e);
+                break;
+            }
         }
+
+        if let Some(e) = failure {
+            if !keep_partial {
+                rollback(ctx, &opts, node_name, node_existed, &mut resources).await;
+            }
+            ConfigResource::display_summary(&opts, &resources)?;
+            return Err(e);
+        }
+        ConfigResource::display_summary(&opts, &resources)
+    }
+}
+
+/// Delete the resources created from the config file.
+///
+/// If the node was created by the config file, deleting it is enough to remove all its resources.
+/// Otherwise each created resource is deleted, in the reverse order of creation.
+async fn rollback(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    node_name: &str,
+    node_existed: bool,
+    resources: &mut [ConfigResource],
+) {
+    let node_was_created = resources
+        .iter()
+        .any(|r| r.kind == ResourceKind::Node && r.status == ResourceStatus::Created);
+    if node_was_created && !node_existed {
+        if let Err(e) = opts.state.delete_node(node_name, true).await {
+            warn!(%node_name, %e, "Failed to delete the node while rolling back its config");
+            return;
+        }
+        for resource in resources.iter_mut() {
+            if resource.status == ResourceStatus::Created
+                && resource.kind != ResourceKind::Enrollment
+            {
+                resource.status = ResourceStatus::RolledBack;
+            }
+        }
+        return;
+    }
+
+    let node = match BackgroundNodeClient::create_to_node(ctx, &opts.state, node_name).await {
+        Ok(node) => node,
+        Err(e) => {
+            warn!(%node_name, %e, "Failed to connect to the node while rolling back its config");
+            return;
+        }
+    };
+    for resource in resources.iter_mut().rev() {
+        if resource.status != ResourceStatus::Created {
+            continue;
+        }
+        let result = match resource.kind {
+            // An enrollment cannot be undone and the node was not created by this config
+            ResourceKind::Enrollment | ResourceKind::Node => continue,
+            ResourceKind::Policy => {
+                let resource_name = match ResourceType::from_str(&resource.name) {
+                    Ok(resource_type) => ResourceTypeOrName::Type(resource_type),
+                    Err(_) => ResourceTypeOrName::Name(resource.name.as_str().into()),
+                };
+                node.delete_policy(ctx, &resource_name, &Action::HandleMessage)
+                    .await
+            }
+            ResourceKind::TcpOutlet => {
+                let request = Request::delete(format!("/node/outlet/{}", resource.name));
+                node.tell(ctx, request).await
+            }
+            ResourceKind::Relay => {
+                let request = Request::delete(format!("/node/relay/{}", resource.name));
+                node.tell(ctx, request).await
+            }
+            ResourceKind::TcpInlet => node.delete_inlet(ctx, &resource.name).await.map(|_| ()),
+        };
+        match result {
+            Ok(()) => resource.status = ResourceStatus::RolledBack,
+            Err(e) => warn!(name = %resource.name, %e, "Failed to roll back a resource"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum ResourceKind {
+    Enrollment,
+    Node,
+    Policy,
+    TcpOutlet,
+    Relay,
+    TcpInlet,
+}
+
+impl Display for ResourceKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ResourceKind::Enrollment => "project enrollment",
+            ResourceKind::Node => "node",
+            ResourceKind::Policy => "policy",
+            ResourceKind::TcpOutlet => "tcp-outlet",
+            ResourceKind::Relay => "relay",
+            ResourceKind::TcpInlet => "tcp-inlet",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum ResourceStatus {
+    Pending,
+    Skipped,
+    Created,
+    Failed,
+    RolledBack,
+}
+
+impl Display for ResourceStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ResourceStatus::Pending => "not created",
+            ResourceStatus::Skipped => "skipped",
+            ResourceStatus::Created => "created",
+            ResourceStatus::Failed => "failed",
+            ResourceStatus::RolledBack => "rolled back",
+        })
+    }
+}
+
+/// A resource declared in the config file, with the command used to create it
+#[derive(Serialize)]
+struct ConfigResource {
+    kind: ResourceKind,
+    name: String,
+    status: ResourceStatus,
+    #[serde(skip)]
+    command: Arc<dyn ParsedCommand>,
+}
+
+impl ConfigResource {
+    fn new(kind: ResourceKind, name: impl Into<String>, command: impl ParsedCommand) -> Self {
+        Self {
+            kind,
+            name: name.into(),
+            status: ResourceStatus::Pending,
+            command: Arc::new(command),
+        }
+    }
+
+    async fn create(&mut self, ctx: &Context, opts: &CommandGlobalOpts) -> miette::Result<()> {
+        if !self.command.is_valid(ctx, opts).await? {
+            self.status = ResourceStatus::Skipped;
+            return Ok(());
+        }
+        match self.command.run(ctx, opts).await {
+            Ok(()) => {
+                self.status = ResourceStatus::Created;
+                Ok(())
+            }
+            Err(e) => {
+                self.status = ResourceStatus::Failed;
+                Err(e)
+            }
+        }
+    }
+
+    fn display_summary(
+        opts: &CommandGlobalOpts,
+        resources: &[ConfigResource],
+    ) -> miette::Result<()> {
+        let plain = resources
+            .iter()
+            .map(|r| {
+                let line = format!("{} {}: {}", r.kind, color_primary(&r.name), r.status);
+                match r.status {
+                    ResourceStatus::Created | ResourceStatus::Skipped => fmt_ok!("{line}"),
+                    _ => fmt_warn!("{line}"),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .json(serde_json::to_string_pretty(resources).into_diagnostic()?)
+            .write_line()?;
         Ok(())
     }
 }
@@ -1,24 +1,35 @@
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::sync::Arc;
 
 use colorful::Colorful;
-use miette::{miette, IntoDiagnostic};
-use tokio::time::{sleep, Duration};
-use tracing::{debug, instrument};
+use miette::{miette, Context as _, IntoDiagnostic};
+use tokio::time::{sleep, Duration, Instant};
+use tracing::{debug, info, instrument, warn};
 
-use ockam::{Address, TcpListenerOptions};
+use ockam::{Address, TcpKeepaliveOptions, TcpListenerOptions};
 use ockam::{Context, TcpTransport};
+#[cfg(feature = "telemetry")]
+use ockam_api::metrics::MetricsConfiguration;
 use ockam_api::nodes::InMemoryNode;
 use ockam_api::nodes::{
     service::{NodeManagerGeneralOptions, NodeManagerTransportOptions},
     NodeManagerWorker, NODEMANAGER_ADDR,
 };
 use ockam_core::{route, LOCAL};
+use ockam_multiaddr::MultiAddr;
+use ockam_transport_tcp::{TcpResolver, TcpTransportOptions};
+use ockam_transport_udp::{UdpListenerOptions, UdpTransport};
+use ockam_transport_websocket::WebSocketTransport;
 
-use crate::fmt_ok;
+use crate::error::{CommandError, ErrorCode};
 use crate::node::CreateCommand;
+use crate::{fmt_log, fmt_ok, fmt_warn};
 
 use crate::secure_channel::listener::create as secure_channel_listener;
 use crate::service::config::Config;
+use crate::shutdown::ShutdownEvent;
+use crate::util::process_nodes_multiaddr;
 use crate::{shutdown, CommandGlobalOpts};
 
 impl CreateCommand {
@@ -28,6 +39,9 @@ impl CreateCommand {
         ctx: &Context,
         opts: CommandGlobalOpts,
     ) -> miette::Result<()> {
+        if self.report_existing_node(&opts).await? {
+            return Ok(());
+        }
         self.guard_node_is_not_already_running(&opts).await?;
 
         let node_name = self.name.clone();
@@ -41,12 +55,22 @@ impl CreateCommand {
             .map(|n| n.is_running())
             .unwrap_or(false)
         {
-            return Err(miette!("Node {} is already running", &node_name));
+            return Err(CommandError::with_code(
+                miette!("Node {} is already running", &node_name),
+                ErrorCode::Conflict,
+            ))?;
         };
 
-        let tcp = TcpTransport::create(ctx).await.into_diagnostic()?;
+        // A background node uses the proxy stored by the command which spawned it
+        if !self.child_process {
+            self.store_node_proxy(&opts).await?;
+            self.store_node_logging(&opts).await?;
+        }
+        let tcp = TcpTransport::create_with_options(ctx, self.tcp_transport_options(&opts).await?)
+            .await
+            .into_diagnostic()?;
         let tcp_listener = tcp
-            .listen(&self.tcp_listener_address, TcpListenerOptions::new())
+            .listen(&self.tcp_listener_address, self.tcp_listener_options())
             .await
             .into_diagnostic()?;
 
@@ -55,6 +79,30 @@ impl CreateCommand {
             tcp_listener.socket_address()
         );
 
+        // The WebSocket transport is always created so that the node can connect to `/ws` addresses
+        let websocket = WebSocketTransport::create(ctx).await.into_diagnostic()?;
+        if let Some(ws) = &self.ws {
+            let ws_address = websocket.listen(ws).await.into_diagnostic()?;
+            debug!("set the node {node_name} websocket listener address to {ws_address}");
+        }
+
+        // The UDP transport is always created so that the node can connect to `/udp` addresses.
+        // Messages received by the UDP listener are delivered to the same services as the
+        // messages received by the TCP listener
+        let udp = UdpTransport::create(ctx).await.into_diagnostic()?;
+        if let Some(udp_address) = &self.udp {
+            let options = UdpListenerOptions::new()
+                .with_spawner_flow_control_id(tcp_listener.flow_control_id().clone());
+            let udp_listener = udp
+                .listen_with_options(udp_address, options)
+                .await
+                .into_diagnostic()?;
+            debug!(
+                "set the node {node_name} udp listener address to {}",
+                udp_listener.socket_address()
+            );
+        }
+
         // Set node_name so that node can isolate its data in the storage from other nodes
         let mut state = opts.state.clone();
         state.set_node_name(&node_name);
@@ -87,13 +135,21 @@ impl CreateCommand {
                 node_name.clone(),
                 self.launch_config.is_none(),
                 true,
-            ),
-            NodeManagerTransportOptions::new(tcp_listener.flow_control_id().clone(), tcp),
+            )
+            .with_prefer_ipv6(self.prefer_ipv6)
+            .with_tcp_keepalive(self.tcp_keepalive_options())
+            .with_tcp_user_timeout(self.tcp_user_timeout)
+            .with_debug_api(self.debug_api)
+            .with_attributes_max_age(self.attributes_max_age),
+            NodeManagerTransportOptions::new(tcp_listener.flow_control_id().clone(), tcp)
+                .with_websocket_transport(websocket)
+                .with_udp_transport(udp),
             trust_options,
         )
         .await
         .into_diagnostic()?;
-        let node_manager_worker = NodeManagerWorker::new(Arc::new(node_man));
+        let node_man = Arc::new(node_man);
+        let node_manager_worker = NodeManagerWorker::new(node_man.clone());
 
         ctx.flow_controls()
             .add_consumer(NODEMANAGER_ADDR, tcp_listener.flow_control_id());
@@ -101,6 +157,25 @@ impl CreateCommand {
             .await
             .into_diagnostic()?;
 
+        if let Some(health_check_port) = self.health_check_port {
+            let address = node_man
+                .start_health_check_endpoint(ctx, health_check_port)
+                .await
+                .into_diagnostic()?;
+            debug!("set the node {node_name} health check address to {address}");
+        }
+
+        #[cfg(feature = "telemetry")]
+        self.start_metrics_exporter(&node_man)?;
+
+        let connect_on_start = self.connect_on_start_addrs(&opts).await?;
+        if !connect_on_start.is_empty() {
+            node_man
+                .warm_connections(ctx, connect_on_start)
+                .await
+                .into_diagnostic()?;
+        }
+
         if let Some(config) = &self.launch_config {
             if start_services(ctx, config).await.is_err() {
                 //TODO: Process should terminate on any error during its setup phase,
@@ -117,9 +192,15 @@ impl CreateCommand {
             }
         }
 
+        // The pid file is removed when the node stops, or if it fails to stop gracefully
+        let _pid_file = match &self.pid_file {
+            Some(path) => Some(PidFile::create(path)?),
+            None => None,
+        };
+
         // Create a channel for communicating back to the main thread
         let (tx, mut rx) = tokio::sync::mpsc::channel(2);
-        shutdown::wait(
+        let event = shutdown::wait(
             opts.terminal.clone(),
             self.exit_on_eof,
             opts.global_args.quiet,
@@ -128,6 +209,9 @@ impl CreateCommand {
         )
         .await?;
 
+        if Self::must_drain(&opts, &node_name, event).await {
+            self.drain(ctx, &opts, &node_man).await?;
+        }
         opts.shutdown();
 
         // Try to stop node; it might have already been stopped or deleted (e.g. when running `node delete --all`)
@@ -139,6 +223,169 @@ impl CreateCommand {
 
         Ok(())
     }
+
+    /// The connections are only drained when the node is stopped by a signal which was not sent
+    /// by `ockam node stop`, `ockam node delete` or `ockam node restart`. Those commands remove
+    /// the node pid from the state before sending the signal, and wait for the node to exit.
+    async fn must_drain(
+        opts: &CommandGlobalOpts,
+        node_name: &str,
+        event: Option<ShutdownEvent>,
+    ) -> bool {
+        if event != Some(ShutdownEvent::Signal) {
+            return false;
+        }
+        match opts.state.get_node(node_name).await {
+            Ok(node) => node.pid().is_some(),
+            Err(_) => false,
+        }
+    }
+
+    /// Stop accepting new connections then wait for the open portal connections to be closed,
+    /// for at most the shutdown grace period
+    async fn drain(
+        &self,
+        ctx: &Context,
+        opts: &CommandGlobalOpts,
+        node_man: &InMemoryNode,
+    ) -> miette::Result<()> {
+        node_man
+            .stop_accepting_connections(ctx)
+            .await
+            .into_diagnostic()?;
+
+        let deadline = Instant::now() + self.shutdown_grace;
+        let mut last_count = 0;
+        loop {
+            let count = node_man.portal_connections_count();
+            if count == 0 {
+                break;
+            }
+            if Instant::now() >= deadline {
+                warn!("{count} portal connections still open after the shutdown grace period");
+                opts.terminal.write_line(fmt_warn!(
+                    "Closing {count} connections still open after {}s",
+                    self.shutdown_grace.as_secs()
+                ))?;
+                break;
+            }
+            if count != last_count {
+                info!("{count} connections draining");
+                opts.terminal
+                    .write_line(fmt_log!("{count} connections draining"))?;
+                last_count = count;
+            }
+            sleep(Duration::from_millis(500)).await;
+        }
+        Ok(())
+    }
+
+    /// Export the node metrics if they are enabled with `--export-metrics`
+    /// or with the OCKAM_METRICS_EXPORT environment variable
+    #[cfg(feature = "telemetry")]
+    fn start_metrics_exporter(&self, node_man: &InMemoryNode) -> miette::Result<()> {
+        let configuration = MetricsConfiguration::from_env()
+            .into_diagnostic()?
+            .with_enabled(self.export_metrics)
+            .with_export_interval(self.metrics_export_interval);
+        if configuration.is_enabled() {
+            node_man
+                .start_metrics_exporter(&configuration)
+                .into_diagnostic()?;
+        }
+        Ok(())
+    }
+
+    /// Routes to connect to when the node starts, set with `--connect-on-start`.
+    /// Node names are resolved like in the routes given to the inlets so that they can match
+    async fn connect_on_start_addrs(
+        &self,
+        opts: &CommandGlobalOpts,
+    ) -> miette::Result<Vec<MultiAddr>> {
+        let mut addrs = vec![];
+        for route in self.connect_on_start.iter().flat_map(|r| r.split(',')) {
+            let addr = MultiAddr::from_str(route.trim()).into_diagnostic()?;
+            addrs.push(process_nodes_multiaddr(&addr, &opts.state).await?);
+        }
+        Ok(addrs)
+    }
+
+    /// TCP keepalive of the node connections, set with `--tcp-keepalive`
+    fn tcp_keepalive_options(&self) -> Option<TcpKeepaliveOptions> {
+        self.tcp_keepalive.map(|idle| {
+            if idle.is_zero() {
+                TcpKeepaliveOptions::disabled()
+            } else {
+                TcpKeepaliveOptions::new(idle)
+            }
+        })
+    }
+
+    /// Options of the TCP transport of the node, with the proxy stored for the node, if any
+    async fn tcp_transport_options(
+        &self,
+        opts: &CommandGlobalOpts,
+    ) -> miette::Result<TcpTransportOptions> {
+        let mut options = TcpTransportOptions::new().with_resolver(self.tcp_resolver());
+        if let Some(max_message_size) = self.max_message_size {
+            options = options.with_max_message_size(max_message_size as usize);
+        }
+        if let Some(node_proxy) = opts.state.get_node_proxy(&self.name).await? {
+            let proxy = node_proxy.tcp_proxy().into_diagnostic()?;
+            info!("the node {} connects through the proxy {proxy}", self.name);
+            options = options.with_proxy(proxy);
+        }
+        Ok(options)
+    }
+
+    /// Resolver used by the node for the host names of its TCP connections and outlets
+    fn tcp_resolver(&self) -> TcpResolver {
+        let mut resolver = TcpResolver::default();
+        if let Some(dns_cache_ttl) = self.dns_cache_ttl {
+            resolver = resolver.with_cache_ttl(dns_cache_ttl);
+        }
+        for (hostname, ip) in &self.static_hosts {
+            resolver = resolver.with_static_host(hostname, *ip);
+        }
+        resolver
+    }
+
+    /// Options of the node TCP listener, which apply to the connections it accepts
+    fn tcp_listener_options(&self) -> TcpListenerOptions {
+        let mut options = TcpListenerOptions::new();
+        if let Some(keepalive) = self.tcp_keepalive_options() {
+            options = options.with_keepalive(keepalive);
+        }
+        if let Some(user_timeout) = self.tcp_user_timeout {
+            options = options.with_user_timeout(user_timeout);
+        }
+        options
+    }
+}
+
+/// File containing the process id of a foreground node, removed when it is dropped
+struct PidFile {
+    path: PathBuf,
+}
+
+impl PidFile {
+    fn create(path: &Path) -> miette::Result<Self> {
+        std::fs::write(path, format!("{}\n", std::process::id()))
+            .into_diagnostic()
+            .wrap_err(format!("Failed to write the pid file {}", path.display()))?;
+        debug!("wrote the pid file {}", path.display());
+        Ok(Self {
+            path: path.to_path_buf(),
+        })
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.path) {
+            warn!(%e, "Failed to remove the pid file {}", self.path.display());
+        }
+    }
 }
 
 async fn start_services(ctx: &Context, cfg: &Config) -> miette::Result<()> {
@@ -156,7 +403,8 @@ async fn start_services(ctx: &Context, cfg: &Config) -> miette::Result<()> {
             let ids = cfg.authorized_identifiers;
             let identity = cfg.identity;
             println!("starting secure-channel listener ...");
-            secure_channel_listener::create_listener(ctx, adr, ids, identity, route![]).await?;
+            secure_channel_listener::create_listener(ctx, adr, ids, identity, None, route![])
+                .await?;
         }
     }
 
@@ -2,6 +2,7 @@ use clap::Args;
 use colorful::Colorful;
 use miette::miette;
 
+use crate::error::{CommandError, ErrorCode};
 use crate::util::async_cmd;
 use crate::{docs, fmt_ok, CommandGlobalOpts};
 
@@ -40,13 +41,17 @@ impl DefaultCommand {
                 .map(|n| n.is_default())
                 .unwrap_or(false)
             {
-                return Err(miette!("The node '{node_name}' is already the default"));
+                return Err(CommandError::with_code(
+                    miette!("The node '{node_name}' is already the default"),
+                    ErrorCode::Conflict,
+                ))?;
             } else {
                 opts.state.set_default_node(node_name).await?;
                 opts.terminal
                     .stdout()
                     .plain(fmt_ok!("The node '{node_name}' is now the default"))
                     .machine(node_name)
+                    .json(serde_json::json!({ "name": node_name }))
                     .write_line()?;
             }
         } else {
@@ -55,6 +60,8 @@ impl DefaultCommand {
                 .terminal
                 .stdout()
                 .plain(fmt_ok!("The default node is '{default_node_name}'"))
+                .machine(&default_node_name)
+                .json(serde_json::json!({ "name": &default_node_name }))
                 .write_line();
         }
         Ok(())
@@ -1,15 +1,30 @@
+use std::fmt::Write;
+use std::sync::Arc;
+use std::time::Duration;
+
 use clap::Args;
 use colorful::Colorful;
 use console::Term;
+use miette::{miette, IntoDiagnostic};
+use serde::Serialize;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use ockam_api::CliState;
 
+use crate::node::system_service::remove_node_system_service;
 use crate::terminal::tui::DeleteCommandTui;
 use crate::terminal::PluralTerm;
 use crate::util::async_cmd;
-use crate::{docs, fmt_ok, CommandGlobalOpts, Terminal, TerminalStream};
+use crate::util::duration::duration_parser;
+use crate::{docs, fmt_ok, fmt_warn, CommandGlobalOpts, Terminal, TerminalStream};
 
 const LONG_ABOUT: &str = include_str!("./static/delete/long_about.txt");
 const AFTER_LONG_HELP: &str = include_str!("./static/delete/after_long_help.txt");
 
+/// Maximum number of nodes deleted at the same time
+const MAX_CONCURRENT_DELETIONS: usize = 8;
+
 /// Delete nodes
 #[derive(Clone, Debug, Args)]
 #[command(
@@ -32,6 +47,10 @@ pub struct DeleteCommand {
     /// Confirm the deletion without prompting
     #[arg(display_order = 901, long, short)]
     yes: bool,
+
+    /// Time given to each node process to stop before it is killed
+    #[arg(display_order = 901, long, value_name = "DURATION", default_value = "5s", value_parser = duration_parser)]
+    stop_timeout: Duration,
 }
 
 impl DeleteCommand {
@@ -94,6 +113,8 @@ impl DeleteCommandTui for DeleteTui {
     }
 
     async fn delete_single(&self, item_name: &str) -> miette::Result<()> {
+        // stop the service first, otherwise it would restart the node
+        remove_node_system_service(&self.opts.state, item_name).await?;
         self.opts
             .state
             .delete_node(item_name, self.cmd.force)
@@ -109,4 +130,166 @@ impl DeleteCommandTui for DeleteTui {
             .write_line()?;
         Ok(())
     }
+
+    /// Delete the nodes concurrently and display a summary of the deletions
+    async fn delete_multiple(&self, items_names: Vec<String>) -> miette::Result<()> {
+        let deletions = delete_nodes(
+            &self.opts.state,
+            items_names,
+            self.cmd.force,
+            self.cmd.stop_timeout,
+        )
+        .await?;
+        let failed = deletions
+            .iter()
+            .filter(|d| d.status == NodeDeletionStatus::Failed)
+            .count();
+        self.terminal()
+            .stdout()
+            .plain(deletions_summary(&deletions, self.cmd.stop_timeout))
+            .json(serde_json::json!(deletions))
+            .write_line()?;
+        if failed > 0 {
+            return Err(miette!("{failed} nodes could not be deleted"));
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of the deletion of a node
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum NodeDeletionStatus {
+    Deleted,
+    /// The node was deleted after its process was killed, because it did not stop in time
+    Killed,
+    Failed,
+}
+
+#[derive(Serialize, Debug)]
+struct NodeDeletion {
+    name: String,
+    status: NodeDeletionStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Delete nodes concurrently, at most `MAX_CONCURRENT_DELETIONS` at a time,
+/// and return the outcome of each deletion in the order of the node names
+async fn delete_nodes(
+    state: &CliState,
+    node_names: Vec<String>,
+    force: bool,
+    stop_timeout: Duration,
+) -> miette::Result<Vec<NodeDeletion>> {
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_DELETIONS));
+    let mut tasks = JoinSet::new();
+    for (index, name) in node_names.into_iter().enumerate() {
+        let state = state.clone();
+        let semaphore = semaphore.clone();
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let result = match remove_node_system_service(&state, &name).await {
+                Err(e) => Err(e.to_string()),
+                Ok(_) if force => state
+                    .delete_node(&name, true)
+                    .await
+                    .map(|_| false)
+                    .map_err(|e| e.to_string()),
+                Ok(_) => state
+                    .delete_node_with_timeout(&name, stop_timeout)
+                    .await
+                    .map_err(|e| e.to_string()),
+            };
+            let deletion = match result {
+                Ok(false) => NodeDeletion {
+                    name,
+                    status: NodeDeletionStatus::Deleted,
+                    error: None,
+                },
+                Ok(true) => NodeDeletion {
+                    name,
+                    status: NodeDeletionStatus::Killed,
+                    error: None,
+                },
+                Err(e) => NodeDeletion {
+                    name,
+                    status: NodeDeletionStatus::Failed,
+                    error: Some(e),
+                },
+            };
+            (index, deletion)
+        });
+    }
+
+    let mut deletions = vec![];
+    while let Some(result) = tasks.join_next().await {
+        deletions.push(result.into_diagnostic()?);
+    }
+    deletions.sort_by_key(|(index, _)| *index);
+    Ok(deletions
+        .into_iter()
+        .map(|(_, deletion)| deletion)
+        .collect())
+}
+
+/// Return a table with the outcome of the deletion of each node
+fn deletions_summary(deletions: &[NodeDeletion], stop_timeout: Duration) -> String {
+    let failed = deletions
+        .iter()
+        .filter(|d| d.status == NodeDeletionStatus::Failed)
+        .count();
+    let deleted = deletions.len() - failed;
+    let mut summary = if failed == 0 {
+        fmt_ok!("Deleted {deleted} nodes")
+    } else {
+        fmt_warn!("Deleted {deleted} nodes, {failed} failed")
+    };
+    summary.push('\n');
+
+    let width = deletions
+        .iter()
+        .map(|d| d.name.len())
+        .max()
+        .unwrap_or_default()
+        .max("NODE".len());
+    let _ = writeln!(summary, "  {:width$}  STATUS", "NODE");
+    for deletion in deletions {
+        let status = match deletion.status {
+            NodeDeletionStatus::Deleted => "deleted".to_string(),
+            NodeDeletionStatus::Killed => {
+                format!("deleted, killed after {}s", stop_timeout.as_secs_f64())
+            }
+            NodeDeletionStatus::Failed => {
+                format!("failed: {}", deletion.error.clone().unwrap_or_default())
+            }
+        };
+        let _ = writeln!(summary, "  {:width$}  {status}", deletion.name);
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deletions_summary() {
+        let deletion = |name: &str, status, error: Option<&str>| NodeDeletion {
+            name: name.to_string(),
+            status,
+            error: error.map(|e| e.to_string()),
+        };
+        let deletions = vec![
+            deletion("n1", NodeDeletionStatus::Deleted, None),
+            deletion("node-2", NodeDeletionStatus::Killed, None),
+            deletion("n3", NodeDeletionStatus::Failed, Some("database is locked")),
+        ];
+        let summary = deletions_summary(&deletions, Duration::from_secs(5));
+        assert!(summary.contains("Deleted 2 nodes, 1 failed"));
+        assert!(summary.contains("  NODE    STATUS\n"));
+        assert!(summary.contains("  n1      deleted\n"));
+        assert!(summary.contains("  node-2  deleted, killed after 5s\n"));
+        assert!(summary.contains("  n3      failed: database is locked\n"));
+    }
 }
@@ -0,0 +1,67 @@
+use async_trait::async_trait;
+use clap::Args;
+
+use ockam::identity::Identifier;
+use ockam::Context;
+use ockam_api::nodes::BackgroundNodeClient;
+
+use crate::fmt_ok;
+use crate::util::api;
+use crate::util::parsers::identity_identifier_parser;
+use crate::{docs, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/flush_attributes/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/flush_attributes/after_long_help.txt");
+
+/// Flush the attributes of identities cached by a node
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct FlushAttributesCommand {
+    /// Name of the node. Defaults to the default node
+    node_name: Option<String>,
+
+    /// Identifier of an identity whose attributes must be flushed.
+    /// The attributes of all the identities are flushed if no identifier is given
+    #[arg(long = "identifier", value_name = "IDENTIFIER", value_parser = identity_identifier_parser)]
+    identifiers: Vec<Identifier>,
+}
+
+#[async_trait]
+impl Command for FlushAttributesCommand {
+    const NAME: &'static str = "node flush-attributes";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.node_name).await?;
+        node.tell(ctx, api::flush_attributes(self.identifiers.clone()))
+            .await?;
+
+        let message = if self.identifiers.is_empty() {
+            format!(
+                "The attributes of all the identities were flushed on the node {}",
+                node.node_name()
+            )
+        } else {
+            format!(
+                "The attributes of {} were flushed on the node {}",
+                self.identifiers
+                    .iter()
+                    .map(|i| i.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                node.node_name()
+            )
+        };
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!("{message}"))
+            .json(serde_json::json!({
+                "node": node.node_name(),
+                "identifiers": self.identifiers.iter().map(|i| i.to_string()).collect::<Vec<_>>(),
+            }))
+            .write_line()?;
+        Ok(())
+    }
+}
@@ -1,15 +1,28 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::str::FromStr;
+
 use clap::Args;
 use colorful::Colorful;
+use miette::{miette, IntoDiagnostic};
+use tokio::time::{sleep, Duration};
+use tracing::Level;
 
-use crate::fmt_ok;
 use crate::util::async_cmd;
-use crate::{docs, CommandGlobalOpts};
+use crate::{color_primary, docs, fmt_log, fmt_ok, CommandGlobalOpts};
 
 const LONG_ABOUT: &str = include_str!("./static/logs/long_about.txt");
 const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
 const AFTER_LONG_HELP: &str = include_str!("./static/logs/after_long_help.txt");
 
-/// Get the stdout/stderr log file of a node
+/// Number of lines displayed when the content of the log file is displayed
+const DEFAULT_LINES: usize = 100;
+
+/// Size of the first block read from the end of the log file to find its last lines
+const TAIL_BLOCK_SIZE: u64 = 64 * 1024;
+
+/// Return the path of the log file of a node, or display its logs
 #[derive(Clone, Debug, Args)]
 #[command(
 long_about = docs::about(LONG_ABOUT),
@@ -19,6 +32,24 @@ after_long_help = docs::after_help(AFTER_LONG_HELP)
 pub struct LogCommand {
     /// Name of the node to retrieve the logs from.
     node_name: Option<String>,
+
+    /// Display the last lines of the log file instead of its path
+    #[arg(long, short)]
+    show: bool,
+
+    /// Keep displaying the new log lines as they are written by the node. Implies `--show`
+    #[arg(long, short)]
+    follow: bool,
+
+    /// Number of lines to display, starting from the end of the log file. Implies `--show`.
+    /// [default: 100]
+    #[arg(long, short = 'n', value_name = "N")]
+    lines: Option<usize>,
+
+    /// Only display the log lines with this level or a more severe one: error, warn, info, debug, trace.
+    /// Implies `--show`
+    #[arg(long, value_name = "LEVEL", value_parser = Level::from_str)]
+    level: Option<Level>,
 }
 
 impl LogCommand {
@@ -37,13 +68,373 @@ impl LogCommand {
             .get_node_or_default(&self.node_name)
             .await?
             .name();
-        let log_path = opts.state.stdout_logs(&node_name)?.display().to_string();
-        opts.terminal
-            .stdout()
-            .plain(fmt_ok!("The path for the log file is: {log_path}"))
-            .machine(&log_path)
-            .json(serde_json::json!({ "path": log_path }))
-            .write_line()?;
+
+        let log_path = match opts.state.stdout_logs(&node_name) {
+            Ok(log_path) => log_path,
+            Err(_) if self.follow => {
+                opts.terminal.write_line(fmt_log!(
+                    "The node {} has not written any logs yet. Waiting for new logs...",
+                    color_primary(&node_name)
+                ))?;
+                self.wait_for_log_file(&opts, &node_name).await?
+            }
+            Err(_) => {
+                return Err(miette!(
+                    "The node {node_name} has not written any logs yet. It might have been started in the foreground, in which case its logs are written to its standard output"
+                ))
+            }
+        };
+
+        if !self.show_content() {
+            let log_path = log_path.display().to_string();
+            opts.terminal
+                .stdout()
+                .plain(fmt_ok!("The path for the log file is: {log_path}"))
+                .machine(&log_path)
+                .json(serde_json::json!({ "path": log_path }))
+                .write_line()?;
+            return Ok(());
+        }
+
+        let mut log_file = LogFile::open(log_path)?;
+        let lines = log_file.read_last_lines(self.lines.unwrap_or(DEFAULT_LINES), self.level)?;
+        for line in &lines {
+            write_log_line(&opts, line)?;
+        }
+
+        if self.follow {
+            let mut filter = LevelFilter::new(self.level);
+            if lines.is_empty() {
+                opts.terminal.write_line(fmt_log!(
+                    "The node {} has not written any logs yet. Waiting for new logs...",
+                    color_primary(&node_name)
+                ))?;
+            }
+            loop {
+                sleep(Duration::from_millis(500)).await;
+                // The log files are rotated daily, in which case a new file is created
+                if let Ok(current_path) = opts.state.stdout_logs(&node_name) {
+                    if log_file.was_rotated(&current_path) {
+                        log_file = LogFile::open(current_path)?;
+                    }
+                }
+                for line in log_file.read_new_lines()? {
+                    if filter.keep(&line) {
+                        write_log_line(&opts, &line)?;
+                    }
+                }
+            }
+        }
         Ok(())
     }
+
+    fn show_content(&self) -> bool {
+        self.show || self.follow || self.lines.is_some() || self.level.is_some()
+    }
+
+    async fn wait_for_log_file(
+        &self,
+        opts: &CommandGlobalOpts,
+        node_name: &str,
+    ) -> miette::Result<PathBuf> {
+        loop {
+            if let Ok(log_path) = opts.state.stdout_logs(node_name) {
+                return Ok(log_path);
+            }
+            sleep(Duration::from_millis(500)).await;
+        }
+    }
+}
+
+/// Display a human-readable version of a log line, or the raw line for machine outputs
+fn write_log_line(opts: &CommandGlobalOpts, line: &str) -> miette::Result<()> {
+    opts.terminal
+        .clone()
+        .stdout()
+        .plain(pretty_log_line(line))
+        .machine(line)
+        .json(line)
+        .write_line()?;
+    Ok(())
+}
+
+/// Log file being read, which can be reopened when it is rotated
+struct LogFile {
+    path: PathBuf,
+    reader: BufReader<File>,
+    position: u64,
+    #[cfg(unix)]
+    inode: u64,
+}
+
+impl LogFile {
+    fn open(path: PathBuf) -> miette::Result<Self> {
+        let file = File::open(&path).into_diagnostic()?;
+        #[cfg(unix)]
+        let inode = {
+            use std::os::unix::fs::MetadataExt;
+            file.metadata().into_diagnostic()?.ino()
+        };
+        Ok(Self {
+            path,
+            reader: BufReader::new(file),
+            position: 0,
+            #[cfg(unix)]
+            inode,
+        })
+    }
+
+    /// Return true if the log file has been replaced or truncated since it was opened
+    fn was_rotated(&self, current_path: &PathBuf) -> bool {
+        if current_path != &self.path {
+            return true;
+        }
+        let metadata = match std::fs::metadata(current_path) {
+            Ok(metadata) => metadata,
+            Err(_) => return false,
+        };
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            if metadata.ino() != self.inode {
+                return true;
+            }
+        }
+        metadata.len() < self.position
+    }
+
+    /// Return the last `count` complete lines kept by the level filter, and position the file
+    /// after them. The file is read backwards from its end, with blocks of increasing size,
+    /// so that large log files are not read entirely.
+    fn read_last_lines(
+        &mut self,
+        count: usize,
+        level: Option<Level>,
+    ) -> miette::Result<Vec<String>> {
+        let end = self.reader.seek(SeekFrom::End(0)).into_diagnostic()?;
+        let mut block_size = TAIL_BLOCK_SIZE;
+        loop {
+            let start = end.saturating_sub(block_size);
+            self.reader.seek(SeekFrom::Start(start)).into_diagnostic()?;
+            let mut buffer = vec![0; (end - start) as usize];
+            self.reader.read_exact(&mut buffer).into_diagnostic()?;
+
+            // Only keep complete lines: skip the line cut by the start of the block
+            // and a partially written last line
+            let complete_end = buffer
+                .iter()
+                .rposition(|b| *b == b'\n')
+                .map_or(0, |i| i + 1);
+            let complete_start = if start == 0 {
+                0
+            } else {
+                buffer[..complete_end]
+                    .iter()
+                    .position(|b| *b == b'\n')
+                    .map_or(complete_end, |i| i + 1)
+            };
+            let mut filter = LevelFilter::new(level);
+            let lines: Vec<String> = String::from_utf8_lossy(&buffer[complete_start..complete_end])
+                .lines()
+                .filter(|l| filter.keep(l))
+                .map(|l| l.trim_end().to_string())
+                .collect();
+
+            if lines.len() >= count || start == 0 {
+                self.position = start + complete_end as u64;
+                self.reader
+                    .seek(SeekFrom::Start(self.position))
+                    .into_diagnostic()?;
+                let skip = lines.len().saturating_sub(count);
+                return Ok(lines.into_iter().skip(skip).collect());
+            }
+            block_size *= 2;
+        }
+    }
+
+    /// Read the complete lines written since the last read
+    fn read_new_lines(&mut self) -> miette::Result<Vec<String>> {
+        let mut lines = vec![];
+        loop {
+            let mut line = String::new();
+            let read = self.reader.read_line(&mut line).into_diagnostic()?;
+            if read == 0 {
+                break;
+            }
+            // Wait for the end of a partially written line
+            if !line.ends_with('\n') {
+                self.reader
+                    .seek(SeekFrom::Start(self.position))
+                    .into_diagnostic()?;
+                break;
+            }
+            self.position += read as u64;
+            lines.push(line.trim_end().to_string());
+        }
+        Ok(lines)
+    }
+}
+
+/// Filter log lines by level.
+/// Lines without a level (the continuation of a multi-line entry) follow the previous entry
+struct LevelFilter {
+    level: Option<Level>,
+    keep_previous: bool,
+}
+
+impl LevelFilter {
+    fn new(level: Option<Level>) -> Self {
+        Self {
+            level,
+            keep_previous: true,
+        }
+    }
+
+    fn keep(&mut self, line: &str) -> bool {
+        let max_level = match self.level {
+            Some(level) => level,
+            None => return true,
+        };
+        if let Some(level) = line_level(line) {
+            // More verbose levels are greater: ERROR < WARN < INFO < DEBUG < TRACE
+            self.keep_previous = level <= max_level;
+        }
+        self.keep_previous
+    }
+}
+
+/// Return the level of a log line, either formatted as JSON or with the default tracing format
+fn line_level(line: &str) -> Option<Level> {
+    if let Ok(json) = serde_json::from_str::<serde_json::Value>(line) {
+        return json
+            .get("level")
+            .and_then(|l| l.as_str())
+            .and_then(|l| Level::from_str(l).ok());
+    }
+    line.split_whitespace()
+        .take(3)
+        .find_map(|token| Level::from_str(strip_ansi(token).trim()).ok())
+}
+
+/// Make a JSON log line human-readable. Other lines are returned as they are
+fn pretty_log_line(line: &str) -> String {
+    let json = match serde_json::from_str::<serde_json::Value>(line) {
+        Ok(serde_json::Value::Object(json)) => json,
+        _ => return line.to_string(),
+    };
+    let get = |key: &str| json.get(key).and_then(|v| v.as_str()).unwrap_or_default();
+
+    let mut message = String::new();
+    let mut fields = vec![];
+    if let Some(serde_json::Value::Object(values)) = json.get("fields") {
+        for (key, value) in values {
+            let value = match value {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            if key == "message" {
+                message = value;
+            } else {
+                fields.push(format!("{key}={value}"));
+            }
+        }
+    }
+
+    let mut pretty = format!(
+        "{} {:>5} {}: {message}",
+        get("timestamp"),
+        get("level"),
+        get("target")
+    );
+    if !fields.is_empty() {
+        pretty.push(' ');
+        pretty.push_str(&fields.join(" "));
+    }
+    pretty
+}
+
+fn strip_ansi(token: &str) -> String {
+    let mut result = String::new();
+    let mut chars = token.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // skip the escape sequence up to its final letter
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn the_last_lines_are_read_from_the_end_of_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("stdout.log");
+        let mut file = File::create(&path).unwrap();
+        // more than one block, with a warning every 10 lines
+        for i in 0..10_000 {
+            let level = if i % 10 == 0 { "WARN" } else { "INFO" };
+            writeln!(
+                file,
+                "2024-03-01T10:00:00.000000Z  {level} ockam_api: line {i}"
+            )
+            .unwrap();
+        }
+        write!(file, "2024-03-01T10:00:00.000000Z  INFO ockam_api: partial").unwrap();
+
+        let mut log_file = LogFile::open(path.clone()).unwrap();
+        let lines = log_file.read_last_lines(3, None).unwrap();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].ends_with("line 9997"));
+        assert!(lines[2].ends_with("line 9999"));
+
+        let mut log_file = LogFile::open(path.clone()).unwrap();
+        let lines = log_file.read_last_lines(2_000, Some(Level::WARN)).unwrap();
+        assert_eq!(lines.len(), 1_000);
+        assert!(lines[0].ends_with("line 0"));
+        assert!(lines[999].ends_with("line 9990"));
+
+        // the partial line is read once it is complete
+        writeln!(file, " line").unwrap();
+        assert_eq!(
+            log_file.read_new_lines().unwrap(),
+            vec!["2024-03-01T10:00:00.000000Z  INFO ockam_api: partial line"]
+        );
+    }
+
+    #[test]
+    fn lines_are_filtered_by_level() {
+        let mut filter = LevelFilter::new(Some(Level::WARN));
+        assert!(!filter.keep("2024-03-01T10:00:00.000000Z  INFO ockam_api: started"));
+        assert!(filter.keep("2024-03-01T10:00:00.000000Z  WARN ockam_api: slow"));
+        assert!(filter.keep("    at ockam_api/src/nodes/service.rs:10"));
+        assert!(filter.keep(
+            r#"{"timestamp":"2024-03-01T10:00:00.000000Z","level":"ERROR","fields":{"message":"failed"},"target":"ockam_api"}"#
+        ));
+        assert!(!filter.keep(
+            r#"{"timestamp":"2024-03-01T10:00:00.000000Z","level":"DEBUG","fields":{"message":"details"},"target":"ockam_api"}"#
+        ));
+    }
+
+    #[test]
+    fn json_lines_are_made_readable() {
+        let line = r#"{"timestamp":"2024-03-01T10:00:00.000000Z","level":"INFO","fields":{"message":"inlet created","alias":"i1"},"target":"ockam_api"}"#;
+        assert_eq!(
+            pretty_log_line(line),
+            "2024-03-01T10:00:00.000000Z  INFO ockam_api: inlet created alias=i1"
+        );
+
+        let line = "2024-03-01T10:00:00.000000Z  INFO ockam_api: started";
+        assert_eq!(pretty_log_line(line), line);
+    }
 }
@@ -5,24 +5,37 @@ pub use create::CreateCommand;
 pub use create::*;
 use default::DefaultCommand;
 use delete::DeleteCommand;
+use flush_attributes::FlushAttributesCommand;
 use list::ListCommand;
 use logs::LogCommand;
+use rename::RenameCommand;
+use restart::RestartCommand;
+use set_log_level::SetLogLevelCommand;
 use show::ShowCommand;
 use start::StartCommand;
 use stop::StopCommand;
+use uninstall_service::UninstallServiceCommand;
+use watchdog::WatchdogCommand;
 
 use crate::{docs, Command, CommandGlobalOpts};
 
 mod create;
 mod default;
 mod delete;
+mod flush_attributes;
 mod list;
 mod logs;
-mod models;
+pub(crate) mod models;
+mod rename;
+mod restart;
+mod set_log_level;
 mod show;
 mod start;
 mod stop;
+mod system_service;
+mod uninstall_service;
 pub mod util;
+mod watchdog;
 
 const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
 const AFTER_LONG_HELP: &str = include_str!("./static/after_long_help.txt");
@@ -57,13 +70,25 @@ pub enum NodeSubcommand {
     List(ListCommand),
     #[command(display_order = 800)]
     Logs(LogCommand),
+    #[command(display_order = 800)]
+    Rename(RenameCommand),
+    #[command(display_order = 800)]
+    Restart(RestartCommand),
+    #[command(display_order = 800)]
+    SetLogLevel(SetLogLevelCommand),
     Show(ShowCommand),
     #[command(display_order = 800)]
     Start(StartCommand),
     #[command(display_order = 800)]
     Stop(StopCommand),
     #[command(display_order = 800)]
+    UninstallService(UninstallServiceCommand),
+    #[command(display_order = 800)]
     Default(DefaultCommand),
+    #[command(display_order = 800)]
+    FlushAttributes(FlushAttributesCommand),
+    #[command(hide = true)]
+    Watchdog(WatchdogCommand),
 }
 
 impl NodeSubcommand {
@@ -73,10 +98,16 @@ impl NodeSubcommand {
             NodeSubcommand::Delete(c) => c.name(),
             NodeSubcommand::List(c) => c.name(),
             NodeSubcommand::Logs(c) => c.name(),
+            NodeSubcommand::Rename(c) => c.name(),
+            NodeSubcommand::Restart(c) => c.name(),
+            NodeSubcommand::SetLogLevel(c) => c.name(),
             NodeSubcommand::Show(c) => c.name(),
             NodeSubcommand::Start(c) => c.name(),
             NodeSubcommand::Stop(c) => c.name(),
+            NodeSubcommand::UninstallService(c) => c.name(),
             NodeSubcommand::Default(c) => c.name(),
+            NodeSubcommand::FlushAttributes(c) => c.name(),
+            NodeSubcommand::Watchdog(c) => c.name(),
         }
     }
 }
@@ -90,8 +121,14 @@ impl NodeCommand {
             NodeSubcommand::Show(c) => c.run(opts),
             NodeSubcommand::Start(c) => c.run(opts),
             NodeSubcommand::Stop(c) => c.run(opts),
+            NodeSubcommand::UninstallService(c) => c.run(opts),
             NodeSubcommand::Logs(c) => c.run(opts),
+            NodeSubcommand::Rename(c) => c.run(opts),
+            NodeSubcommand::Restart(c) => c.run(opts),
+            NodeSubcommand::SetLogLevel(c) => c.run(opts),
             NodeSubcommand::Default(c) => c.run(opts),
+            NodeSubcommand::FlushAttributes(c) => c.run(opts),
+            NodeSubcommand::Watchdog(c) => c.run(opts),
         }
     }
 }
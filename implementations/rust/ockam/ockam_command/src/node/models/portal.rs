@@ -34,6 +34,10 @@ impl From<InletStatus> for ShowInletStatus {
 pub struct ShowOutletStatus {
     pub forward_address: SocketAddr,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub forward_hostname: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub forward_unix_socket: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub address: Option<MultiAddr>,
 }
 
@@ -41,6 +45,8 @@ impl From<OutletStatus> for ShowOutletStatus {
     fn from(value: OutletStatus) -> Self {
         Self {
             forward_address: value.socket_addr,
+            forward_hostname: value.hostname_port,
+            forward_unix_socket: value.unix_socket_path,
             address: addr_to_multiaddr(value.worker_addr),
         }
     }
@@ -1,3 +1,4 @@
+use ockam::identity::Identifier;
 use ockam_api::{
     addr_to_multiaddr, nodes::models::secure_channel::ShowSecureChannelListenerResponse,
 };
@@ -5,12 +6,23 @@ use ockam_core::flow_control::FlowControlId;
 use ockam_multiaddr::MultiAddr;
 use serde::Serialize;
 
-/// Information to display of the secure channel listeners in the `ockam node show` command
+/// Information to display of the secure channel listeners in the `ockam node show`
+/// and `ockam secure-channel-listener` commands
 #[derive(Debug, Serialize)]
 pub struct ShowSecureChannelListener {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub address: Option<MultiAddr>,
     pub flow_control: FlowControlId,
+    /// Identities allowed to initiate a secure channel. Any identity is allowed if `None`
+    pub authorized_identifiers: Option<Vec<Identifier>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_handshakes_per_second: Option<u32>,
+    /// Handshakes started during the last second, when their rate is limited
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub handshakes_per_second: Option<u32>,
+    /// Handshake messages dropped because the rate limit was reached
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_limited_handshakes: Option<u64>,
 }
 
 impl From<ShowSecureChannelListenerResponse> for ShowSecureChannelListener {
@@ -18,6 +30,10 @@ impl From<ShowSecureChannelListenerResponse> for ShowSecureChannelListener {
         Self {
             address: addr_to_multiaddr(value.addr),
             flow_control: value.flow_control_id,
+            authorized_identifiers: value.authorized_identifiers,
+            max_handshakes_per_second: value.max_handshakes_per_second,
+            handshakes_per_second: value.handshakes_per_second,
+            rate_limited_handshakes: value.rate_limited_handshakes,
         }
     }
 }
@@ -1,3 +1,4 @@
+use ockam_api::kafka::BrokerAuthValidation;
 use ockam_api::{addr_to_multiaddr, nodes::models::services::ServiceStatus};
 use ockam_multiaddr::MultiAddr;
 use serde::Serialize;
@@ -9,6 +10,9 @@ pub struct ShowServiceStatus {
     pub address: Option<MultiAddr>,
     #[serde(rename = "type")]
     pub service_type: String,
+    /// Result of the validation of the Kafka brokers authentication, for the kafka services
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub broker_auth: Option<BrokerAuthValidation>,
 }
 
 impl From<ServiceStatus> for ShowServiceStatus {
@@ -16,6 +20,7 @@ impl From<ServiceStatus> for ShowServiceStatus {
         Self {
             address: addr_to_multiaddr(value.addr),
             service_type: value.service_type,
+            broker_auth: value.broker_auth,
         }
     }
 }
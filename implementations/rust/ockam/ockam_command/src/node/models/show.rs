@@ -2,13 +2,18 @@ use std::fmt::Display;
 
 use colorful::Colorful;
 
+use ockam::identity::TimestampInSeconds;
+use ockam_api::cli_state::{NodeSupervision, NodeSystemService};
+use ockam_api::nodes::models::base::NodeResources;
+use ockam_api::nodes::models::connection::WarmConnectionStatus;
+use ockam_api::nodes::models::trust::TrustContextStatus;
 use ockam_multiaddr::{
     proto::{DnsAddr, Node, Tcp},
     MultiAddr,
 };
 use serde::Serialize;
 
-use crate::output::Output;
+use crate::output::{human_readable_time, Output};
 
 use super::{
     portal::{ShowInletStatus, ShowOutletStatus},
@@ -25,6 +30,9 @@ pub struct ShowNodeResponse {
     pub is_up: bool,
     pub node_pid: Option<u32>,
     pub route: RouteToNode,
+    /// Log level currently applied by the node
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_level: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub identity: Option<String>,
     pub transports: Vec<ShowTransportStatus>,
@@ -32,6 +40,20 @@ pub struct ShowNodeResponse {
     pub inlets: Vec<ShowInletStatus>,
     pub outlets: Vec<ShowOutletStatus>,
     pub services: Vec<ShowServiceStatus>,
+    /// Connections established when the node starts, with `--connect-on-start`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warm_connections: Vec<WarmConnectionStatus>,
+    /// Authority trusted by the node and credential held by the node.
+    /// The content of the credential is never included
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trust: Option<TrustContextStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resources: Option<NodeResources>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub supervision: Option<NodeSupervision>,
+    /// System service running the node, when it was created with `--as-system-service`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_service: Option<NodeSystemService>,
 }
 #[derive(Debug, Serialize)]
 pub struct RouteToNode {
@@ -68,12 +90,18 @@ impl ShowNodeResponse {
             is_up,
             node_pid,
             route: RouteToNode { short, verbose },
+            log_level: None,
             identity: None,
             transports: Default::default(),
             secure_channel_listeners: Default::default(),
             inlets: Default::default(),
             outlets: Default::default(),
             services: Default::default(),
+            warm_connections: Default::default(),
+            trust: None,
+            resources: None,
+            supervision: None,
+            system_service: None,
         }
     }
 }
@@ -113,6 +141,10 @@ impl Display for ShowNodeResponse {
             writeln!(buffer, "  Identity: {}", identity)?;
         }
 
+        if let Some(log_level) = &self.log_level {
+            writeln!(buffer, "  Log Level: {log_level}")?;
+        }
+
         writeln!(buffer, "  Transports:")?;
         for e in &self.transports {
             writeln!(buffer, "    Transport:")?;
@@ -120,6 +152,9 @@ impl Display for ShowNodeResponse {
             writeln!(buffer, "      Mode: {}", &e.mode)?;
             writeln!(buffer, "      Socket: {}", &e.socket)?;
             writeln!(buffer, "      Worker: {}", &e.worker)?;
+            if let Some(ip_family) = &e.ip_family {
+                writeln!(buffer, "      IP Family: {ip_family}")?;
+            }
             writeln!(buffer, "      FlowControlId: {}", &e.flow_control)?;
         }
 
@@ -144,7 +179,13 @@ impl Display for ShowNodeResponse {
         writeln!(buffer, "  Outlets:")?;
         for e in &self.outlets {
             writeln!(buffer, "    Outlet:")?;
-            writeln!(buffer, "      Forward Address: {}", e.forward_address)?;
+            match &e.forward_unix_socket {
+                Some(path) => writeln!(buffer, "      Forward Unix Socket: {path}")?,
+                None => writeln!(buffer, "      Forward Address: {}", e.forward_address)?,
+            }
+            if let Some(hostname) = &e.forward_hostname {
+                writeln!(buffer, "      Forward Hostname: {hostname}")?;
+            }
             if let Some(ma) = &e.address {
                 writeln!(buffer, "      Address: {ma}")?;
             }
@@ -157,14 +198,193 @@ impl Display for ShowNodeResponse {
             if let Some(ma) = &e.address {
                 writeln!(buffer, "      Address: {ma}")?;
             }
+            if let Some(broker_auth) = &e.broker_auth {
+                writeln!(buffer, "      Broker Auth: {broker_auth}")?;
+            }
+        }
+
+        if !self.warm_connections.is_empty() {
+            writeln!(buffer, "  Connections On Start:")?;
+            for e in &self.warm_connections {
+                writeln!(buffer, "    Connection:")?;
+                writeln!(buffer, "      Route: {}", e.addr)?;
+                writeln!(buffer, "      Status: {}", e.state)?;
+                if let Some(normalized_addr) = &e.normalized_addr {
+                    writeln!(buffer, "      Connected Via: {normalized_addr}")?;
+                }
+                if e.attempts > 0 {
+                    writeln!(buffer, "      Attempts: {}", e.attempts)?;
+                }
+                if let Some(last_failure) = &e.last_failure {
+                    writeln!(buffer, "      Last Failure: {last_failure}")?;
+                }
+            }
+        }
+
+        if let Some(trust) = &self.trust {
+            writeln!(buffer, "  Trust:")?;
+            match &trust.authority_identifier {
+                Some(authority) => writeln!(buffer, "    Authority: {authority}")?,
+                None => writeln!(buffer, "    Authority: none")?,
+            }
+            if let Some(authority_route) = &trust.authority_route {
+                writeln!(buffer, "    Authority Route: {authority_route}")?;
+            }
+            if let Some(project) = &trust.project {
+                writeln!(buffer, "    Project: {} ({})", project.name, project.id)?;
+            }
+            writeln!(
+                buffer,
+                "    Credential Retrieval: {}",
+                trust.credential_retrieval
+            )?;
+            if let Some(scope) = &trust.credential_scope {
+                writeln!(buffer, "    Credential Scope: {scope}")?;
+            }
+            match &trust.credential {
+                Some(credential) => {
+                    let expires_at = human_readable_time(TimestampInSeconds(credential.expires_at));
+                    writeln!(
+                        buffer,
+                        "    Credential: issued by {}, {}",
+                        credential.issuer,
+                        match credential.is_expired {
+                            true => format!("{} at {expires_at}", "EXPIRED".light_red()),
+                            false => format!("expires at {expires_at}"),
+                        }
+                    )?;
+                }
+                None => writeln!(buffer, "    Credential: none")?,
+            }
+        }
+
+        if let Some(resources) = &self.resources {
+            writeln!(buffer, "  Resources:")?;
+            let memory = resources
+                .memory
+                .map(|m| format!("{:.1} MB", m as f64 / (1024.0 * 1024.0)))
+                .unwrap_or("unavailable".to_string());
+            writeln!(buffer, "    Memory: {memory}")?;
+            writeln!(buffer, "    Workers: {}", resources.workers)?;
+            writeln!(buffer, "    TCP Connections: {}", resources.tcp_connections)?;
+            writeln!(buffer, "    TCP Listeners: {}", resources.tcp_listeners)?;
+            writeln!(
+                buffer,
+                "    Portal Connections: {}",
+                resources.portal_connections
+            )?;
+            writeln!(buffer, "    Secure Channels: {}", resources.secure_channels)?;
+            writeln!(buffer, "    Uptime: {}", format_uptime(resources.uptime))?;
+            writeln!(
+                buffer,
+                "    DNS Resolutions: {} cached, {} resolved, {} failed",
+                resources.dns_cache_hits,
+                resources.dns_cache_misses,
+                resources.dns_resolution_failures
+            )?;
+            if let Some(max_message_size) = resources.max_message_size {
+                writeln!(buffer, "    Max Message Size: {max_message_size} bytes")?;
+            }
+        }
+
+        if let Some(supervision) = &self.supervision {
+            writeln!(buffer, "  Supervision:")?;
+            writeln!(
+                buffer,
+                "    Status: {}",
+                match supervision.is_failed() {
+                    true => format!(
+                        "{} (crashed more than {} times in a row)",
+                        "NOT RESTARTED".light_red(),
+                        supervision.max_restarts()
+                    ),
+                    false => format!("{}", "RESTART ON FAILURE".light_green()),
+                }
+            )?;
+            writeln!(buffer, "    Restarts: {}", supervision.restart_count())?;
+            if let Some(last_crash_at) = supervision.last_crash_at() {
+                writeln!(
+                    buffer,
+                    "    Last Crash: {}",
+                    human_readable_time(TimestampInSeconds(last_crash_at))
+                )?;
+            }
+        }
+
+        if let Some(system_service) = &self.system_service {
+            writeln!(buffer, "  System Service: {system_service}")?;
         }
 
         Ok(())
     }
 }
 
+/// Format a number of seconds as days, hours, minutes and seconds
+fn format_uptime(seconds: u64) -> String {
+    let (days, hours, minutes, seconds) = (
+        seconds / 86400,
+        seconds % 86400 / 3600,
+        seconds % 3600 / 60,
+        seconds % 60,
+    );
+    if days > 0 {
+        format!("{days}d {hours}h {minutes}m {seconds}s")
+    } else if hours > 0 {
+        format!("{hours}h {minutes}m {seconds}s")
+    } else if minutes > 0 {
+        format!("{minutes}m {seconds}s")
+    } else {
+        format!("{seconds}s")
+    }
+}
+
 impl Output for ShowNodeResponse {
     fn output(&self) -> crate::error::Result<String> {
         Ok(self.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uptime_is_human_readable() {
+        assert_eq!(format_uptime(42), "42s");
+        assert_eq!(format_uptime(125), "2m 5s");
+        assert_eq!(format_uptime(3 * 3600 + 65), "3h 1m 5s");
+        assert_eq!(format_uptime(2 * 86400 + 3600), "2d 1h 0m 0s");
+    }
+
+    #[test]
+    fn trust_shows_the_validity_of_the_credential() {
+        use ockam::identity::Identifier;
+        use ockam_api::nodes::models::trust::{CredentialRetrieval, HeldCredential};
+        use std::str::FromStr;
+
+        let authority = Identifier::from_str(
+            "Ie92f183eb4c324804ef4d62962dea94cf095a265a1b2c3d4e5f6a6b5c4d3e2f1",
+        )
+        .unwrap();
+        let mut show_node = ShowNodeResponse::new(false, "n1", true, None, None);
+        show_node.trust = Some(
+            TrustContextStatus::new(Some(authority.clone()), CredentialRetrieval::Authority)
+                .with_credential_scope(Some("project-member-1".to_string()))
+                .with_credential(Some(HeldCredential::new(
+                    authority.clone(),
+                    TimestampInSeconds(10),
+                    TimestampInSeconds(20),
+                ))),
+        );
+
+        let output = show_node.to_string();
+        assert!(output.contains(&format!("    Authority: {authority}")));
+        assert!(output.contains("    Credential Scope: project-member-1"));
+        assert!(output.contains("EXPIRED"));
+
+        let json = serde_json::to_value(&show_node).unwrap();
+        assert_eq!(json["trust"]["credential_retrieval"], "authority");
+        assert_eq!(json["trust"]["credential"]["is_expired"], true);
+        assert!(json["trust"].get("project").is_none());
+    }
+}
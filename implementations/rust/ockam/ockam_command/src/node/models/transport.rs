@@ -11,6 +11,8 @@ pub struct ShowTransportStatus {
     pub socket: String,
     pub worker: String,
     pub flow_control: FlowControlId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip_family: Option<String>,
 }
 
 impl From<TransportStatus> for ShowTransportStatus {
@@ -21,6 +23,7 @@ impl From<TransportStatus> for ShowTransportStatus {
             socket: value.socket_addr,
             worker: value.worker_addr,
             flow_control: value.flow_control_id,
+            ip_family: value.ip_family,
         }
     }
 }
@@ -0,0 +1,113 @@
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::miette;
+
+use ockam::Context;
+use ockam_api::cli_state::CliState;
+
+use crate::error::{CommandError, ErrorCode};
+use crate::{color_primary, docs, fmt_ok, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/rename/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/rename/after_long_help.txt");
+
+/// Rename a node
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct RenameCommand {
+    /// Current name of the node
+    #[arg(required = true)]
+    pub node_name: String,
+
+    /// New name of the node
+    #[arg(required = true)]
+    pub new_node_name: String,
+}
+
+#[async_trait]
+impl Command for RenameCommand {
+    const NAME: &'static str = "node rename";
+
+    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        check_node_can_be_renamed(&opts.state, &self.node_name).await?;
+        let node = opts
+            .state
+            .rename_node(&self.node_name, &self.new_node_name)
+            .await?;
+
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "The node {} has been renamed to {}",
+                color_primary(&self.node_name),
+                color_primary(node.name())
+            ))
+            .machine(node.name())
+            .json(serde_json::json!({ "name": node.name(), "previous_name": self.node_name }))
+            .write_line()?;
+        Ok(())
+    }
+}
+
+/// A node can only be renamed when it is stopped and not run by a system service
+async fn check_node_can_be_renamed(state: &CliState, node_name: &str) -> crate::Result<()> {
+    if state.get_node(node_name).await?.is_running() {
+        return Err(CommandError::with_code(
+            miette!(
+                "The node {} is running. Please stop it with `ockam node stop {}` before renaming it",
+                color_primary(node_name),
+                node_name
+            ),
+            ErrorCode::Conflict,
+        ))?;
+    }
+    if let Some(system_service) = state.get_node_system_service(node_name).await? {
+        return Err(CommandError::with_code(
+            miette!(
+                "The node {} is run by a system service: {}. Please uninstall it with `ockam node uninstall-service {}` before renaming the node",
+                color_primary(node_name),
+                system_service,
+                node_name
+            ),
+            ErrorCode::Conflict,
+        ))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_api::cli_state::{NodeSystemService, SystemServiceManager};
+
+    #[tokio::test]
+    async fn only_stopped_nodes_without_system_service_can_be_renamed() -> miette::Result<()> {
+        let state = CliState::test().await?;
+        // a node is created with the pid of the current process, which is running
+        state.create_node("n1").await?;
+        let error = check_node_can_be_renamed(&state, "n1").await.unwrap_err();
+        assert!(error.to_string().contains("is running"));
+
+        state.stop_node("n1", false).await?;
+        assert!(check_node_can_be_renamed(&state, "n1").await.is_ok());
+
+        state.create_node("n2").await?;
+        state.stop_node("n2", false).await?;
+        state
+            .set_node_system_service(&NodeSystemService::new(
+                "n2",
+                SystemServiceManager::Systemd,
+                "/etc/systemd/user/ockam-node-n2.service",
+            ))
+            .await?;
+        let error = check_node_can_be_renamed(&state, "n2").await.unwrap_err();
+        assert!(error.to_string().contains("is run by a system service"));
+
+        assert!(check_node_can_be_renamed(&state, "unknown").await.is_err());
+        Ok(())
+    }
+}
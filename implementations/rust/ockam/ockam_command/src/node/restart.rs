@@ -0,0 +1,487 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::{miette, IntoDiagnostic};
+use serde::Serialize;
+use tokio::time::{sleep, Duration, Instant};
+
+use ockam::{Context, TcpTransport};
+use ockam_api::cli_state::NodeInfo;
+use ockam_api::nodes::models::policies::Policy;
+use ockam_api::nodes::models::portal::{InletList, InletStatus, OutletList, OutletStatus};
+use ockam_api::nodes::models::relay::RelayInfo;
+use ockam_api::nodes::models::secure_channel::{
+    CreateSecureChannelListenerRequest, ListSecureChannelListenerResponse,
+    ShowSecureChannelListenerResponse,
+};
+use ockam_api::nodes::service::portals::{CreateInletOptions, Inlets, Outlets};
+use ockam_api::nodes::service::relay::Relays;
+use ockam_api::nodes::{BackgroundNodeClient, NodeRequestError, Policies};
+use ockam_core::api::{Reply, Request};
+use ockam_multiaddr::MultiAddr;
+
+use crate::node::show::is_node_up;
+use crate::node::util::{spawn_node_with_stored_options, spawn_watchdog_if_supervised};
+use crate::util::api;
+use crate::{color_primary, docs, fmt_log, fmt_ok, fmt_warn, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/restart/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/restart/after_long_help.txt");
+
+/// Maximum time to wait for the node process to stop before killing it
+const STOP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Restart a running node and recreate its resources
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+before_help = docs::before_help(PREVIEW_TAG),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct RestartCommand {
+    /// Name of the node to restart
+    node_name: Option<String>,
+
+    /// Display the resources which would be recreated, without restarting the node
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[async_trait]
+impl Command for RestartCommand {
+    const NAME: &'static str = "node restart";
+
+    async fn async_run(self, ctx: &Context, mut opts: CommandGlobalOpts) -> crate::Result<()> {
+        let node_info = opts.state.get_node_or_default(&self.node_name).await?;
+        let node_name = node_info.name();
+        if !node_info.is_running() {
+            return Err(miette!(
+                "The node {node_name} is not running. You can start it with `ockam node start {node_name}`"
+            ))?;
+        }
+
+        let tcp = TcpTransport::create(ctx).await.into_diagnostic()?;
+        let node =
+            BackgroundNodeClient::create_to_node_with_tcp(&tcp, &opts.state, &node_name).await?;
+        let snapshot = NodeSnapshot::create(ctx, &node).await?;
+
+        if self.dry_run {
+            opts.terminal
+                .stdout()
+                .plain(&snapshot)
+                .json(serde_json::to_string_pretty(&snapshot.plan()).into_diagnostic()?)
+                .write_line()?;
+            return Ok(());
+        }
+
+        opts.global_args.verbose = node_info.verbosity();
+        stop_node(&opts, &node_info).await?;
+        opts.terminal
+            .write_line(fmt_log!("Node {} stopped", color_primary(&node_name)))?;
+
+        spawn_node_with_stored_options(&opts, &node_info).await?;
+
+        let mut node =
+            BackgroundNodeClient::create_to_node_with_tcp(&tcp, &opts.state, &node_name).await?;
+        if !is_node_up(ctx, &mut node, true).await? {
+            return Err(miette!(
+                "The node {node_name} could not be restarted. Check its logs with `ockam node logs {node_name}`"
+            ))?;
+        }
+        opts.terminal
+            .write_line(fmt_log!("Node {} started", color_primary(&node_name)))?;
+
+        let results = snapshot.replay(ctx, &opts, &node).await;
+        spawn_watchdog_if_supervised(&opts, &node_name).await?;
+        opts.terminal
+            .stdout()
+            .plain(RestartSummary(&results))
+            .json(serde_json::to_string_pretty(&results).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+/// Stop the node process and wait for it to exit, then kill it if it did not exit in time
+pub(super) async fn stop_node(
+    opts: &CommandGlobalOpts,
+    node_info: &NodeInfo,
+) -> miette::Result<()> {
+    opts.state.stop_node(&node_info.name(), false).await?;
+    let deadline = Instant::now() + STOP_TIMEOUT;
+    while node_info.is_running() {
+        if Instant::now() >= deadline {
+            opts.state.stop_node(&node_info.name(), true).await?;
+            break;
+        }
+        sleep(Duration::from_millis(200)).await;
+    }
+    Ok(())
+}
+
+/// Resources of a node, retrieved with the node list APIs before the node is stopped
+pub(super) struct NodeSnapshot {
+    policies: Vec<Policy>,
+    secure_channel_listeners: Vec<ShowSecureChannelListenerResponse>,
+    outlets: Vec<OutletStatus>,
+    relays: Vec<RelayInfo>,
+    inlets: Vec<InletStatus>,
+}
+
+impl NodeSnapshot {
+    pub(super) async fn create(ctx: &Context, node: &BackgroundNodeClient) -> miette::Result<Self> {
+        let policies = node.list_policies(ctx, None).await?.all();
+        let listeners: ListSecureChannelListenerResponse =
+            node.ask(ctx, api::list_secure_channel_listener()).await?;
+        let outlets: OutletList = node.ask(ctx, api::list_outlets()).await?;
+        let relays: Vec<RelayInfo> = node.ask(ctx, Request::get("/node/relay")).await?;
+        let inlets: InletList = node.ask(ctx, api::list_inlets()).await?;
+        Ok(Self {
+            policies,
+            secure_channel_listeners: listeners.list,
+            outlets: outlets.list,
+            relays,
+            inlets: inlets.list,
+        })
+    }
+
+    /// Return the list of resources to recreate, in their creation order
+    fn plan(&self) -> Vec<RestartedResource> {
+        let mut plan = vec![];
+        for policy in &self.policies {
+            plan.push(RestartedResource::new(
+                "policy",
+                format!("{} {}", policy.resource(), policy.action()),
+            ));
+        }
+        for listener in &self.secure_channel_listeners {
+            plan.push(RestartedResource::new(
+                "secure-channel-listener",
+                listener.addr.address(),
+            ));
+        }
+        for outlet in &self.outlets {
+            plan.push(RestartedResource::new(
+                "tcp-outlet",
+                outlet.worker_addr.address(),
+            ));
+        }
+        for relay in &self.relays {
+            plan.push(RestartedResource::new("relay", relay.alias()));
+        }
+        for inlet in &self.inlets {
+            plan.push(RestartedResource::new("tcp-inlet", &inlet.alias));
+        }
+        plan
+    }
+
+    /// Recreate all the resources on the restarted node.
+    /// A failure does not prevent the other resources from being recreated
+    pub(super) async fn replay(
+        &self,
+        ctx: &Context,
+        opts: &CommandGlobalOpts,
+        node: &BackgroundNodeClient,
+    ) -> Vec<RestartedResource> {
+        let mut results = vec![];
+
+        for policy in &self.policies {
+            let result = node
+                .add_policy(ctx, policy.resource(), policy.action(), policy.expression())
+                .await;
+            results.push(
+                RestartedResource::new(
+                    "policy",
+                    format!("{} {}", policy.resource(), policy.action()),
+                )
+                .with_result(result),
+            );
+        }
+
+        // The default secure channel listener is started with the node
+        let existing_listeners: Vec<String> = node
+            .ask::<(), ListSecureChannelListenerResponse>(ctx, api::list_secure_channel_listener())
+            .await
+            .map(|l| {
+                l.list
+                    .iter()
+                    .map(|l| l.addr.address().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        for listener in &self.secure_channel_listeners {
+            if existing_listeners.contains(&listener.addr.address().to_string()) {
+                continue;
+            }
+            let result = create_secure_channel_listener(ctx, opts, node, listener).await;
+            results.push(
+                RestartedResource::new("secure-channel-listener", listener.addr.address())
+                    .with_result(result),
+            );
+        }
+
+        for outlet in &self.outlets {
+            let result = node
+                .create_outlet(
+                    ctx,
+                    &outlet.to(),
+                    Some(&outlet.worker_addr),
+                    None,
+                    None,
+                    outlet.identity.clone(),
+                    outlet.proxy_protocol,
+                )
+                .await
+                .map(|_| ());
+            results.push(
+                RestartedResource::new("tcp-outlet", outlet.worker_addr.address())
+                    .with_result(result),
+            );
+        }
+
+        for relay in &self.relays {
+            let result = node
+                .create_relay(
+                    ctx,
+                    relay.destination_address(),
+                    relay.alias().to_string(),
+                    None,
+                    None,
+                    relay.at_rust_node(),
+                    None,
+                    false,
+                )
+                .await
+                .map(|_| ());
+            results.push(RestartedResource::new("relay", relay.alias()).with_result(result));
+        }
+
+        for inlet in &self.inlets {
+            let result = create_inlet(ctx, node, inlet).await;
+            results.push(RestartedResource::new("tcp-inlet", &inlet.alias).with_result(result));
+        }
+
+        results
+    }
+}
+
+async fn create_secure_channel_listener(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    node: &BackgroundNodeClient,
+    listener: &ShowSecureChannelListenerResponse,
+) -> miette::Result<()> {
+    let identity_name = match &listener.identifier {
+        Some(identifier) => Some(
+            opts.state
+                .get_named_identity_by_identifier(identifier)
+                .await?
+                .name(),
+        ),
+        None => None,
+    };
+    let request = Request::post("/node/secure_channel_listener").body(
+        CreateSecureChannelListenerRequest::new(
+            &listener.addr,
+            listener.authorized_identifiers.clone(),
+            identity_name,
+        )
+        .with_max_handshakes_per_second(listener.max_handshakes_per_second),
+    );
+    let reply = node.tell_and_get_reply(ctx, request).await?;
+    Ok(NodeRequestError::check_reply(&node.node_name(), reply)?)
+}
+
+async fn create_inlet(
+    ctx: &Context,
+    node: &BackgroundNodeClient,
+    inlet: &InletStatus,
+) -> miette::Result<()> {
+    let outlet_addr = MultiAddr::from_str(&inlet.outlet_addr).into_diagnostic()?;
+    let options = &inlet.creation_options;
+    let limits = inlet.connection_limits.as_ref();
+    let create_inlet = CreateInletOptions::new(&inlet.bind_addr, &outlet_addr, &inlet.alias)
+        .with_authorized_identifiers(&options.authorized)
+        .with_policy_expression(options.policy_expression()?)
+        .with_wait_for_outlet_timeout(Duration::from_secs(5))
+        .with_load_balancing(&options.additional_outlet_addrs()?, inlet.load_balancing)
+        .with_secure_channel_rekey_interval(options.secure_channel_rekey_interval())
+        .with_buffer_size(options.buffer_size())
+        .with_connection_limits(
+            limits.and_then(|l| l.max_connections_per_second),
+            limits.and_then(|l| l.max_concurrent_connections),
+        )
+        .with_no_delay(inlet.no_delay)
+        .with_liveness(
+            inlet.liveness.as_ref().map(|l| l.probe_interval()),
+            inlet.liveness.as_ref().map(|l| l.max_missed_probes),
+        )
+        .with_identity(inlet.identity.clone())
+        // the socket file of a Unix domain socket inlet is left behind if the node was killed
+        .with_unix_socket(inlet.unix_socket_mode, true)
+        .with_expect_proxy_protocol(inlet.expect_proxy_protocol);
+    match node.create_inlet(ctx, create_inlet).await? {
+        Reply::Successful(_) => Ok(()),
+        Reply::Failed(e, _) => Err(miette!(e
+            .message()
+            .unwrap_or("the inlet could not be created")
+            .to_string())),
+    }
+}
+
+/// Resource recreated after a restart
+#[derive(Serialize)]
+pub(super) struct RestartedResource {
+    kind: &'static str,
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl RestartedResource {
+    fn new(kind: &'static str, name: impl Into<String>) -> Self {
+        Self {
+            kind,
+            name: name.into(),
+            error: None,
+        }
+    }
+
+    fn with_result(mut self, result: miette::Result<()>) -> Self {
+        self.error = result.err().map(|e| e.to_string());
+        self
+    }
+}
+
+impl Display for NodeSnapshot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let plan = self.plan();
+        if plan.is_empty() {
+            return writeln!(
+                f,
+                "{}",
+                fmt_log!("The node would be restarted without resources")
+            );
+        }
+        writeln!(
+            f,
+            "{}",
+            fmt_log!("The node would be restarted and the following resources recreated:")
+        )?;
+        for resource in plan {
+            writeln!(
+                f,
+                "{}",
+                fmt_log!("  {} {}", resource.kind, color_primary(&resource.name))
+            )?;
+        }
+        Ok(())
+    }
+}
+
+struct RestartSummary<'a>(&'a [RestartedResource]);
+
+impl Display for RestartSummary<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for resource in self.0.iter().filter(|r| r.error.is_none()) {
+            writeln!(
+                f,
+                "{}",
+                fmt_ok!(
+                    "Recreated the {} {}",
+                    resource.kind,
+                    color_primary(&resource.name)
+                )
+            )?;
+        }
+        let failed: Vec<&RestartedResource> = self.0.iter().filter(|r| r.error.is_some()).collect();
+        if !failed.is_empty() {
+            writeln!(
+                f,
+                "{}",
+                fmt_warn!("The following resources could not be recreated:")
+            )?;
+            for resource in failed {
+                writeln!(
+                    f,
+                    "{}",
+                    fmt_log!(
+                        "  {} {}: {}",
+                        resource.kind,
+                        color_primary(&resource.name),
+                        resource.error.clone().unwrap_or_default()
+                    )
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam::identity::Identifier;
+    use ockam_abac::{Action, Expr, ResourceType, ResourceTypePolicy};
+    use ockam_api::session::sessions::ConnectionStatus;
+    use ockam_core::flow_control::FlowControlId;
+
+    #[test]
+    fn resources_are_recreated_in_dependency_order() {
+        let identifier = Identifier::from_str(
+            "I0000000000000000000000000000000000000000000000000000000000000001",
+        )
+        .unwrap();
+        let snapshot = NodeSnapshot {
+            policies: vec![ResourceTypePolicy::new(
+                ResourceType::TcpInlet,
+                Action::HandleMessage,
+                Expr::from_str("(= subject.component \"db\")").unwrap(),
+            )
+            .into()],
+            secure_channel_listeners: vec![ShowSecureChannelListenerResponse {
+                addr: "listener".into(),
+                flow_control_id: FlowControlId::from("fc".to_string()),
+                identifier: Some(identifier),
+                authorized_identifiers: None,
+                max_handshakes_per_second: None,
+                handshakes_per_second: None,
+                rate_limited_handshakes: None,
+            }],
+            outlets: vec![OutletStatus::new(
+                "127.0.0.1:5000".parse().unwrap(),
+                "outlet".into(),
+                None,
+            )],
+            relays: vec![],
+            inlets: vec![InletStatus::new(
+                "127.0.0.1:6000",
+                None,
+                "inlet",
+                None,
+                None,
+                ConnectionStatus::Up,
+                "/service/outlet",
+            )],
+        };
+
+        let plan: Vec<(&str, String)> = snapshot
+            .plan()
+            .into_iter()
+            .map(|r| (r.kind, r.name))
+            .collect();
+        assert_eq!(
+            plan,
+            vec![
+                ("policy", "tcp-inlet handle_message".to_string()),
+                ("secure-channel-listener", "listener".to_string()),
+                ("tcp-outlet", "outlet".to_string()),
+                ("tcp-inlet", "inlet".to_string()),
+            ]
+        );
+    }
+}
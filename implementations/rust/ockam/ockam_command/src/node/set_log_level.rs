@@ -0,0 +1,58 @@
+use async_trait::async_trait;
+use clap::Args;
+
+use ockam::Context;
+use ockam_api::logs::LogLevelFilter;
+use ockam_api::nodes::BackgroundNodeClient;
+
+use crate::fmt_ok;
+use crate::util::api;
+use crate::util::parsers::log_level_parser;
+use crate::{docs, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/set_log_level/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/set_log_level/after_long_help.txt");
+
+/// Change the log level of a running node
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct SetLogLevelCommand {
+    /// Name of the node
+    node_name: String,
+
+    /// Log level, for example `debug`, or comma-separated log filter directives,
+    /// for example `info,ockam_api=trace`
+    #[arg(value_name = "LEVEL", value_parser = log_level_parser)]
+    log_level: LogLevelFilter,
+}
+
+#[async_trait]
+impl Command for SetLogLevelCommand {
+    const NAME: &'static str = "node set-log-level";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let node =
+            BackgroundNodeClient::create(ctx, &opts.state, &Some(self.node_name.clone())).await?;
+        node.tell(ctx, api::set_log_level(&self.log_level)).await?;
+        opts.state
+            .set_node_log_level(&node.node_name(), &self.log_level)
+            .await?;
+
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "The log level of the node {} is now {}",
+                node.node_name(),
+                self.log_level
+            ))
+            .json(serde_json::json!({
+                "node": node.node_name(),
+                "log_level": self.log_level.to_string(),
+            }))
+            .write_line()?;
+        Ok(())
+    }
+}
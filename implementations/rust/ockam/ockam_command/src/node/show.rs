@@ -8,9 +8,11 @@ use tokio_retry::strategy::FibonacciBackoff;
 use tracing::{info, trace, warn};
 
 use ockam_api::nodes::models::base::NodeStatus;
+use ockam_api::nodes::models::connection::WarmConnectionList;
 use ockam_api::nodes::models::portal::{InletList, OutletList};
 use ockam_api::nodes::models::services::ServiceList;
 use ockam_api::nodes::models::transport::TransportList;
+use ockam_api::nodes::models::trust::TrustContextStatus;
 use ockam_api::nodes::BackgroundNodeClient;
 use ockam_core::AsyncTryClone;
 use ockam_node::Context;
@@ -136,7 +138,7 @@ pub async fn print_query_status(
     let node_name = node.node_name();
     let node_info = cli_state.get_node(&node_name).await?;
 
-    let show_node = if !is_node_up(ctx, node, wait_until_ready).await? {
+    let mut show_node = if !is_node_up(ctx, node, wait_until_ready).await? {
         // it is expected to not be able to open an arbitrary TCP connection on an authority node
         // so in that case we display an UP status
         let is_authority_node = cli_state
@@ -161,6 +163,15 @@ pub async fn print_query_status(
             node_info.tcp_listener_port(),
             node_info.pid(),
         );
+        // Get the runtime statistics of the node
+        let status: NodeStatus = node.ask(ctx, api::query_status()).await?;
+        show_node.resources = status.resources;
+        show_node.log_level = status.log_level;
+
+        // Get the trust configured on the node, and the credential it holds
+        let trust: TrustContextStatus = node.ask(ctx, api::query_trust_context()).await?;
+        show_node.trust = Some(trust);
+
         // Get list of services for the node
         let services: ServiceList = node.ask(ctx, api::list_services()).await?;
         show_node.services = services
@@ -169,11 +180,15 @@ pub async fn print_query_status(
             .map(ShowServiceStatus::from)
             .collect();
 
-        // Get list of TCP listeners for node
+        // Get list of TCP and UDP listeners, and TCP connections, for node
         let transports: TransportList = node.ask(ctx, api::list_tcp_listeners()).await?;
+        let udp_transports: TransportList = node.ask(ctx, api::list_udp_listeners()).await?;
+        let tcp_connections: TransportList = node.ask(ctx, api::list_tcp_connections()).await?;
         show_node.transports = transports
             .list
             .into_iter()
+            .chain(udp_transports.list)
+            .chain(tcp_connections.list)
             .map(ShowTransportStatus::from)
             .collect();
 
@@ -198,8 +213,15 @@ pub async fn print_query_status(
             .map(ShowOutletStatus::from)
             .collect();
 
+        // Get the connections established when the node started
+        let warm_connections: WarmConnectionList =
+            node.ask(ctx, api::list_warm_connections()).await?;
+        show_node.warm_connections = warm_connections.list;
+
         show_node
     };
+    show_node.supervision = cli_state.get_node_supervision(&node_name).await?;
+    show_node.system_service = cli_state.get_node_system_service(&node_name).await?;
 
     opts.terminal
         .clone()
@@ -5,8 +5,7 @@ use ockam_api::nodes::BackgroundNodeClient;
 use ockam_node::Context;
 
 use crate::node::show::print_query_status;
-use crate::node::util::spawn_node;
-use crate::node::CreateCommand;
+use crate::node::util::{spawn_node_with_stored_options, spawn_watchdog_if_supervised};
 use crate::util::async_cmd;
 use crate::{docs, fmt_err, fmt_info, fmt_log, fmt_ok, fmt_warn, CommandGlobalOpts, OckamColor};
 
@@ -56,6 +55,7 @@ impl StartCommand {
                     .plain(fmt_info!(
                         "All the nodes are already started, nothing to do. Exiting gratefully"
                     ))
+                    .json(serde_json::json!([]))
                     .write_line()?;
             }
             1 => {
@@ -70,6 +70,7 @@ impl StartCommand {
                         opts.terminal
                             .stdout()
                             .plain(fmt_info!("No node selected, exiting gratefully!"))
+                            .json(serde_json::json!([]))
                             .write_line()?;
                     }
                     1 => start_single_node(&selected_nodes[0], opts, ctx).await?,
@@ -81,16 +82,18 @@ impl StartCommand {
                             opts.terminal
                                 .stdout()
                                 .plain(fmt_info!("No node selected, exiting gratefully!"))
+                                .json(serde_json::json!([]))
                                 .write_line()?;
                             return Ok(());
                         }
 
-                        let formatted_starts_result =
+                        let (formatted_starts_result, json) =
                             start_multiple_nodes(ctx, &opts, &selected_nodes).await?;
 
                         opts.terminal
                             .stdout()
                             .plain(formatted_starts_result.join("\n"))
+                            .json(json)
                             .write_line()?;
                     }
                 }
@@ -118,6 +121,7 @@ async fn start_single_node(
                 "The node '{node_name}' is already running. If you want to restart it you can \
                     call `ockam node stop {node_name}` and then `ockam node start {node_name}`"
             ))
+            .json(serde_json::json!({ "name": node_name, "started": false }))
             .write_line()?;
         return Ok(());
     }
@@ -129,26 +133,33 @@ async fn start_single_node(
 
 /// Start multiples nodes and return a formatted result in the form as a list.
 /// Eventually append info on how to find error logs if there are.
+/// The result is also returned as a JSON list of `{ "name", "started" }` objects.
 async fn start_multiple_nodes(
     ctx: &Context,
     opts: &CommandGlobalOpts,
     node_selected: &[String],
-) -> miette::Result<Vec<String>> {
+) -> miette::Result<(Vec<String>, serde_json::Value)> {
     let mut node_error_flag: bool = false;
     let mut node_starts_output: Vec<String> = vec![];
+    let mut node_starts_json = vec![];
     for node_name in node_selected {
-        match run_node(node_name, ctx, opts).await {
-            Ok(_) => node_starts_output.push(fmt_ok!("{node_name}")),
+        let started = match run_node(node_name, ctx, opts).await {
+            Ok(_) => {
+                node_starts_output.push(fmt_ok!("{node_name}"));
+                true
+            }
             Err(_) => {
                 node_error_flag = true;
-                node_starts_output.push(fmt_warn!("{node_name}"))
+                node_starts_output.push(fmt_warn!("{node_name}"));
+                false
             }
-        }
+        };
+        node_starts_json.push(serde_json::json!({ "name": node_name, "started": started }));
     }
     if node_error_flag {
         append_info_if_errors(&mut node_starts_output);
     };
-    Ok(node_starts_output)
+    Ok((node_starts_output, serde_json::json!(node_starts_json)))
 }
 
 /// Run a single node. Return the BackgroundNode instance of the created node or error
@@ -159,20 +170,10 @@ async fn run_node(
 ) -> miette::Result<BackgroundNodeClient> {
     let node_info = opts.state.get_node(node_name).await?;
     opts.state.stop_node(node_name, false).await?;
-    let node_address = node_info
-        .tcp_listener_address()
-        .map(|a| a.to_string())
-        .unwrap_or("no transport address".to_string());
 
     // Restart node
-    #[allow(clippy::field_reassign_with_default)]
-    let cmd = {
-        let mut cmd = CreateCommand::default();
-        cmd.name = node_name.to_string();
-        cmd.tcp_listener_address = node_address;
-        cmd
-    };
-    spawn_node(opts, cmd).await?;
+    spawn_node_with_stored_options(opts, &node_info).await?;
+    spawn_watchdog_if_supervised(opts, node_name).await?;
 
     let node = BackgroundNodeClient::create_to_node(ctx, &opts.state, node_name).await?;
     Ok(node)
@@ -1,3 +1,4 @@
+use crate::error::{CommandError, ErrorCode};
 use crate::util::async_cmd;
 use crate::{color, docs, fmt_info, fmt_ok, fmt_warn, CommandGlobalOpts, OckamColor};
 
@@ -49,6 +50,7 @@ impl StopCommand {
             opts.terminal
                 .stdout()
                 .plain(fmt_info!("There are no nodes running"))
+                .json(serde_json::json!([]))
                 .write_line()?;
             return Ok(());
         }
@@ -60,10 +62,10 @@ impl StopCommand {
                 .await?
                 .name();
             if !running_nodes.contains(&node_name) {
-                return Err(miette!(
-                    "The node {} was not found",
-                    node_name.light_magenta()
-                ));
+                return Err(CommandError::with_code(
+                    miette!("The node {} was not found", node_name.light_magenta()),
+                    ErrorCode::NotFound,
+                ))?;
             }
             stop_node(opts, &node_name, self.force).await?;
             return Ok(());
@@ -87,6 +89,7 @@ impl StopCommand {
                         opts.terminal
                             .stdout()
                             .plain(fmt_info!("No nodes selected to stop"))
+                            .json(serde_json::json!([]))
                             .write_line()?;
                     }
                     1 => {
@@ -107,6 +110,7 @@ impl StopCommand {
 
 async fn stop_node(opts: CommandGlobalOpts, node_name: &str, force: bool) -> miette::Result<()> {
     let res = opts.state.stop_node(node_name, force).await;
+    let json = serde_json::json!({ "name": node_name, "stopped": res.is_ok() });
     let output = if res.is_ok() {
         fmt_ok!(
             "Node with name {} was stopped",
@@ -118,6 +122,10 @@ async fn stop_node(opts: CommandGlobalOpts, node_name: &str, force: bool) -> mie
             color!(node_name, OckamColor::PrimaryResource)
         )
     };
-    opts.terminal.stdout().plain(output).write_line()?;
+    opts.terminal
+        .stdout()
+        .plain(output)
+        .json(json)
+        .write_line()?;
     Ok(())
 }
@@ -0,0 +1,468 @@
+use std::env::current_exe;
+use std::fs::{create_dir_all, remove_file, write};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use miette::miette;
+use tracing::{debug, info};
+
+use ockam_api::cli_state::{NodeSystemService, SystemServiceManager};
+use ockam_api::CliState;
+use ockam_core::env::{get_env, get_env_with_default};
+
+/// System service running a node in foreground mode, with the arguments recorded
+/// when the node was created:
+///
+///  - on Linux, a systemd user unit
+///  - on macOS, a launchd agent
+///  - on Windows, a task started at boot by the Task Scheduler, running a script
+///
+pub(crate) struct SystemServiceDefinition {
+    node_name: String,
+    manager: SystemServiceManager,
+    /// Path of the unit, plist or script file
+    unit: PathBuf,
+    /// Program and arguments started by the service
+    command: Vec<String>,
+    ockam_home: PathBuf,
+}
+
+impl SystemServiceDefinition {
+    /// Create the definition of the service running a node, for the current operating system
+    pub(crate) fn new(
+        state: &CliState,
+        node_name: &str,
+        args: Vec<String>,
+    ) -> miette::Result<Self> {
+        let manager = SystemServiceManager::current().ok_or(miette!(
+            "Running a node as a system service is not supported on this operating system"
+        ))?;
+        let ockam_exe = current_exe().unwrap_or_else(|_| {
+            get_env_with_default("OCKAM", "ockam".to_string())
+                .unwrap()
+                .into()
+        });
+        let mut command = vec![ockam_exe.to_string_lossy().to_string()];
+        command.extend(args);
+        Ok(Self {
+            node_name: node_name.to_string(),
+            manager,
+            unit: unit_path(manager, state, node_name)?,
+            command,
+            ockam_home: state.dir(),
+        })
+    }
+
+    /// Write the service definition and start the service.
+    /// When this fails, the error lists the steps to install the service manually
+    pub(crate) fn install(&self) -> miette::Result<NodeSystemService> {
+        info!(
+            "installing the {} service of the node {}",
+            self.manager, self.node_name
+        );
+        let contents = self.contents();
+        let written = match self.unit.parent() {
+            Some(parent) => create_dir_all(parent),
+            None => Ok(()),
+        }
+        .and_then(|_| write(&self.unit, &contents));
+        if let Err(e) = written {
+            return Err(self.install_error(
+                &format!("Cannot write the file {}: {e}", self.unit.display()),
+                e.kind() == ErrorKind::PermissionDenied,
+            ));
+        }
+
+        for command in install_commands(self.manager, &self.node_name, &self.unit) {
+            if let Err(error) = run_command(&command) {
+                return Err(self.install_error(
+                    &format!("`{}` failed: {error}", command.join(" ")),
+                    is_missing_privileges(&error),
+                ));
+            }
+        }
+        Ok(NodeSystemService::new(
+            &self.node_name,
+            self.manager,
+            &self.unit.to_string_lossy(),
+        ))
+    }
+
+    /// Return the content of the unit, plist or script file
+    fn contents(&self) -> String {
+        let ockam_home = self.ockam_home.to_string_lossy();
+        match self.manager {
+            SystemServiceManager::Systemd => format!(
+                "[Unit]\n\
+                 Description=Ockam node {}\n\
+                 After=network-online.target\n\
+                 \n\
+                 [Service]\n\
+                 Type=simple\n\
+                 Environment={}\n\
+                 ExecStart={}\n\
+                 Restart=on-failure\n\
+                 RestartSec=5\n\
+                 \n\
+                 [Install]\n\
+                 WantedBy=default.target\n",
+                self.node_name,
+                systemd_quote(&format!("OCKAM_HOME={ockam_home}")),
+                self.command
+                    .iter()
+                    .map(|a| systemd_quote(a))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+            SystemServiceManager::Launchd => format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+                 <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+                 <plist version=\"1.0\">\n\
+                 <dict>\n\
+                 \x20 <key>Label</key>\n\
+                 \x20 <string>{}</string>\n\
+                 \x20 <key>ProgramArguments</key>\n\
+                 \x20 <array>\n\
+                 {}\
+                 \x20 </array>\n\
+                 \x20 <key>EnvironmentVariables</key>\n\
+                 \x20 <dict>\n\
+                 \x20   <key>OCKAM_HOME</key>\n\
+                 \x20   <string>{}</string>\n\
+                 \x20 </dict>\n\
+                 \x20 <key>RunAtLoad</key>\n\
+                 \x20 <true/>\n\
+                 \x20 <key>KeepAlive</key>\n\
+                 \x20 <dict>\n\
+                 \x20   <key>SuccessfulExit</key>\n\
+                 \x20   <false/>\n\
+                 \x20 </dict>\n\
+                 </dict>\n\
+                 </plist>\n",
+                xml_escape(&service_name(self.manager, &self.node_name)),
+                self.command
+                    .iter()
+                    .map(|a| format!("    <string>{}</string>\n", xml_escape(a)))
+                    .collect::<String>(),
+                xml_escape(&ockam_home)
+            ),
+            SystemServiceManager::Windows => format!(
+                "@echo off\r\nset \"OCKAM_HOME={}\"\r\n{}\r\n",
+                ockam_home.replace('%', "%%"),
+                self.command
+                    .iter()
+                    .map(|a| windows_quote(a).replace('%', "%%"))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+        }
+    }
+
+    /// Return an error explaining why the service could not be installed,
+    /// with the steps to install it manually
+    fn install_error(&self, reason: &str, missing_privileges: bool) -> miette::Report {
+        let commands = install_commands(self.manager, &self.node_name, &self.unit)
+            .iter()
+            .map(|c| format!("  {}", c.join(" ")))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let reason = if missing_privileges {
+            format!("You don't have the privileges needed to install a system service. {reason}")
+        } else {
+            reason.to_string()
+        };
+        miette!(
+            "{reason}\n\nTo install the service manually, write the following content to {}:\n\n{}\nThen run:\n\n{commands}",
+            self.unit.display(),
+            self.contents()
+        )
+    }
+}
+
+/// Uninstall the system service running a node, if there is one.
+/// Return false if the node is not run by a system service
+pub(crate) async fn remove_node_system_service(
+    state: &CliState,
+    node_name: &str,
+) -> miette::Result<bool> {
+    match state.get_node_system_service(node_name).await? {
+        Some(system_service) => {
+            uninstall_system_service(&system_service)?;
+            state.delete_node_system_service(node_name).await?;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// Stop the service running a node and remove its definition
+fn uninstall_system_service(system_service: &NodeSystemService) -> miette::Result<()> {
+    let node_name = system_service.node_name();
+    let manager = system_service.manager();
+    let unit = PathBuf::from(system_service.unit());
+    info!("uninstalling the {manager} service of the node {node_name}");
+
+    let commands = uninstall_commands(manager, &node_name, &unit);
+    let uninstall_error = |reason: &str, missing_privileges: bool| {
+        let reason = if missing_privileges {
+            format!("You don't have the privileges needed to uninstall a system service. {reason}")
+        } else {
+            reason.to_string()
+        };
+        miette!(
+            "{reason}\n\nTo uninstall the service manually, run:\n\n{}\n\nThen delete the file {}",
+            commands
+                .iter()
+                .map(|c| format!("  {}", c.join(" ")))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            unit.display()
+        )
+    };
+
+    for command in &commands {
+        if let Err(error) = run_command(command) {
+            // the service might already be stopped, or removed
+            if is_missing_privileges(&error) {
+                return Err(uninstall_error(
+                    &format!("`{}` failed: {error}", command.join(" ")),
+                    true,
+                ));
+            }
+            debug!("`{}` failed: {error}", command.join(" "));
+        }
+    }
+    match remove_file(&unit) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(uninstall_error(
+            &format!("Cannot delete the file {}: {e}", unit.display()),
+            e.kind() == ErrorKind::PermissionDenied,
+        )),
+    }?;
+    if manager == SystemServiceManager::Systemd {
+        let _ = run_command(&systemctl(&["daemon-reload"]));
+    }
+    Ok(())
+}
+
+/// Name of the service, or label of the launchd agent, running a node
+fn service_name(manager: SystemServiceManager, node_name: &str) -> String {
+    match manager {
+        SystemServiceManager::Launchd => format!("io.ockam.node.{node_name}"),
+        SystemServiceManager::Systemd | SystemServiceManager::Windows => {
+            format!("ockam-node-{node_name}")
+        }
+    }
+}
+
+/// Return the path of the file defining the service of a node
+fn unit_path(
+    manager: SystemServiceManager,
+    state: &CliState,
+    node_name: &str,
+) -> miette::Result<PathBuf> {
+    let name = service_name(manager, node_name);
+    let home = || -> miette::Result<PathBuf> {
+        get_env::<String>("HOME")
+            .ok()
+            .flatten()
+            .map(PathBuf::from)
+            .ok_or(miette!("The HOME environment variable is not set"))
+    };
+    Ok(match manager {
+        SystemServiceManager::Systemd => {
+            let config_dir = match get_env::<String>("XDG_CONFIG_HOME").ok().flatten() {
+                Some(config_dir) => PathBuf::from(config_dir),
+                None => home()?.join(".config"),
+            };
+            config_dir
+                .join("systemd")
+                .join("user")
+                .join(format!("{name}.service"))
+        }
+        SystemServiceManager::Launchd => home()?
+            .join("Library")
+            .join("LaunchAgents")
+            .join(format!("{name}.plist")),
+        SystemServiceManager::Windows => state.dir().join("services").join(format!("{name}.cmd")),
+    })
+}
+
+fn install_commands(
+    manager: SystemServiceManager,
+    node_name: &str,
+    unit: &Path,
+) -> Vec<Vec<String>> {
+    let name = service_name(manager, node_name);
+    let unit = unit.to_string_lossy().to_string();
+    match manager {
+        SystemServiceManager::Systemd => vec![
+            systemctl(&["daemon-reload"]),
+            systemctl(&["enable", "--now", &format!("{name}.service")]),
+        ],
+        SystemServiceManager::Launchd => vec![to_strings(&["launchctl", "load", "-w", &unit])],
+        SystemServiceManager::Windows => vec![
+            to_strings(&[
+                "schtasks", "/Create", "/F", "/TN", &name, "/TR", &unit, "/SC", "ONSTART", "/RU",
+                "SYSTEM",
+            ]),
+            to_strings(&["schtasks", "/Run", "/TN", &name]),
+        ],
+    }
+}
+
+fn uninstall_commands(
+    manager: SystemServiceManager,
+    node_name: &str,
+    unit: &Path,
+) -> Vec<Vec<String>> {
+    let name = service_name(manager, node_name);
+    let unit = unit.to_string_lossy().to_string();
+    match manager {
+        SystemServiceManager::Systemd => {
+            vec![systemctl(&["disable", "--now", &format!("{name}.service")])]
+        }
+        SystemServiceManager::Launchd => vec![to_strings(&["launchctl", "unload", "-w", &unit])],
+        SystemServiceManager::Windows => vec![
+            to_strings(&["schtasks", "/End", "/TN", &name]),
+            to_strings(&["schtasks", "/Delete", "/F", "/TN", &name]),
+        ],
+    }
+}
+
+fn systemctl(args: &[&str]) -> Vec<String> {
+    let mut command = to_strings(&["systemctl", "--user"]);
+    command.extend(to_strings(args));
+    command
+}
+
+fn to_strings(args: &[&str]) -> Vec<String> {
+    args.iter().map(|a| a.to_string()).collect()
+}
+
+/// Run a command of the service manager, and return its error output if it fails
+fn run_command(command: &[String]) -> Result<(), String> {
+    debug!("running `{}`", command.join(" "));
+    let output = Command::new(&command[0])
+        .args(&command[1..])
+        .output()
+        .map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        if stderr.is_empty() {
+            Err(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        } else {
+            Err(stderr)
+        }
+    }
+}
+
+/// Return true if the error output of a service manager command is caused by missing privileges
+fn is_missing_privileges(error: &str) -> bool {
+    let error = error.to_lowercase();
+    [
+        "access is denied",
+        "permission denied",
+        "operation not permitted",
+        "interactive authentication required",
+        "failed to connect to bus",
+    ]
+    .iter()
+    .any(|e| error.contains(e))
+}
+
+/// Quote an argument of a systemd `ExecStart` or `Environment` directive
+fn systemd_quote(arg: &str) -> String {
+    let escaped = arg.replace('%', "%%").replace('$', "$$");
+    if !escaped.is_empty()
+        && !escaped
+            .chars()
+            .any(|c| c.is_whitespace() || c == '"' || c == '\'' || c == '\\' || c == ';')
+    {
+        return escaped;
+    }
+    format!("\"{}\"", escaped.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Quote an argument of a Windows command line
+fn windows_quote(arg: &str) -> String {
+    if !arg.is_empty() && !arg.chars().any(|c| c.is_whitespace() || c == '"') {
+        return arg.to_string();
+    }
+    let mut quoted = String::from("\"");
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            '"' => {
+                quoted.push_str(&"\\".repeat(backslashes * 2 + 1));
+                quoted.push('"');
+                backslashes = 0;
+            }
+            c => {
+                quoted.push_str(&"\\".repeat(backslashes));
+                quoted.push(c);
+                backslashes = 0;
+            }
+        }
+    }
+    quoted.push_str(&"\\".repeat(backslashes * 2));
+    quoted.push('"');
+    quoted
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arguments_are_quoted_for_systemd() {
+        assert_eq!(systemd_quote("--foreground"), "--foreground");
+        assert_eq!(systemd_quote("my node"), "\"my node\"");
+        assert_eq!(systemd_quote(""), "\"\"");
+        assert_eq!(
+            systemd_quote("{\"name\":\"n1\"}"),
+            "\"{\\\"name\\\":\\\"n1\\\"}\""
+        );
+        assert_eq!(systemd_quote("100%"), "100%%");
+        assert_eq!(systemd_quote("$HOME"), "$$HOME");
+    }
+
+    #[test]
+    fn arguments_are_quoted_for_windows() {
+        assert_eq!(windows_quote("--foreground"), "--foreground");
+        assert_eq!(
+            windows_quote("C:\\Program Files\\ockam.exe"),
+            "\"C:\\Program Files\\ockam.exe\""
+        );
+        assert_eq!(
+            windows_quote("{\"name\":\"n1\"}"),
+            "\"{\\\"name\\\":\\\"n1\\\"}\""
+        );
+        assert_eq!(windows_quote("a dir\\"), "\"a dir\\\\\"");
+    }
+
+    #[test]
+    fn missing_privileges_are_detected() {
+        assert!(is_missing_privileges("ERROR: Access is denied."));
+        assert!(is_missing_privileges(
+            "Failed to connect to bus: No medium found"
+        ));
+        assert!(!is_missing_privileges(
+            "Unit ockam-node-n1.service not found."
+        ));
+    }
+}
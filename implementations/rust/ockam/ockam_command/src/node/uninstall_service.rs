@@ -0,0 +1,78 @@
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::miette;
+
+use ockam::Context;
+use ockam_api::cli_state::CliState;
+
+use crate::error::{CommandError, ErrorCode};
+use crate::node::system_service::remove_node_system_service;
+use crate::{color_primary, docs, fmt_ok, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/uninstall_service/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/uninstall_service/after_long_help.txt");
+
+/// Uninstall the system service running a node
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct UninstallServiceCommand {
+    /// Name of the node run by the service
+    #[arg(required = true)]
+    pub node_name: String,
+}
+
+#[async_trait]
+impl Command for UninstallServiceCommand {
+    const NAME: &'static str = "node uninstall-service";
+
+    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        uninstall_node_service(&opts.state, &self.node_name).await?;
+
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "The system service of the node {} has been uninstalled",
+                color_primary(&self.node_name)
+            ))
+            .machine(&self.node_name)
+            .json(serde_json::json!({ "name": self.node_name }))
+            .write_line()?;
+        Ok(())
+    }
+}
+
+/// Uninstall the system service running a node, or fail if the node is not run by a service
+async fn uninstall_node_service(state: &CliState, node_name: &str) -> crate::Result<()> {
+    // fail if the node does not exist
+    state.get_node(node_name).await?;
+    if !remove_node_system_service(state, node_name).await? {
+        return Err(CommandError::with_code(
+            miette!(
+                "The node {} is not run by a system service",
+                color_primary(node_name)
+            ),
+            ErrorCode::NotFound,
+        ))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn a_node_without_system_service_cannot_be_uninstalled() -> miette::Result<()> {
+        let state = CliState::test().await?;
+        assert!(uninstall_node_service(&state, "n1").await.is_err());
+
+        state.create_node("n1").await?;
+        let error = uninstall_node_service(&state, "n1").await.unwrap_err();
+        assert!(error.to_string().contains("is not run by a system service"));
+        Ok(())
+    }
+}
@@ -4,9 +4,12 @@ use std::process::{Command, Stdio};
 use miette::IntoDiagnostic;
 use miette::{miette, Context as _};
 use rand::random;
+use tracing::info;
 
+use ockam_api::cli_state::{entered_vault_passphrase, NodeInfo, OCKAM_VAULT_PASSWORD};
 use ockam_api::nodes::BackgroundNodeClient;
 use ockam_core::env::get_env_with_default;
+use ockam_core::OpenTelemetryContext;
 use ockam_node::Context;
 
 use crate::node::show::is_node_up;
@@ -61,17 +64,89 @@ pub async fn initialize_default_node(
     Ok(())
 }
 
-/// A utility function to spawn a new node into foreground mode
-#[allow(clippy::too_many_arguments)]
+/// A utility function to spawn a new node into foreground mode.
+/// The options of the node are recorded so that it can be started again with the same options
 pub async fn spawn_node(opts: &CommandGlobalOpts, cmd: CreateCommand) -> miette::Result<()> {
+    let creation_args = node_creation_args(opts, &cmd);
+    opts.state
+        .set_node_arguments(&cmd.name, &creation_args)
+        .await?;
+    run_ockam(
+        node_process_args_with(opts, &cmd.name, creation_args, cmd.opentelemetry_context).await?,
+    )
+    .await
+}
+
+/// Return the arguments of the ockam command running a node in foreground mode,
+/// as a child process of the command which created it
+pub async fn node_process_args(
+    opts: &CommandGlobalOpts,
+    cmd: CreateCommand,
+) -> miette::Result<Vec<String>> {
+    let creation_args = node_creation_args(opts, &cmd);
+    node_process_args_with(opts, &cmd.name, creation_args, cmd.opentelemetry_context).await
+}
+
+/// Complete the creation arguments of a node with its current logging configuration,
+/// the trace context of the current command and the node name
+async fn node_process_args_with(
+    opts: &CommandGlobalOpts,
+    name: &str,
+    mut args: Vec<String>,
+    opentelemetry_context: Option<OpenTelemetryContext>,
+) -> miette::Result<Vec<String>> {
+    // The logging configuration is stored when the node is created, and applied again
+    // when the node is restarted
+    if let Some(logging) = opts.state.get_node_logging(name).await? {
+        if let Some(log_level) = logging.log_level() {
+            args.push("--log-level".to_string());
+            args.push(log_level.to_string());
+        }
+        if let Some(log_format) = logging.log_format() {
+            args.push("--log-format".to_string());
+            args.push(log_format.to_string());
+        }
+        if let Some(max_size_bytes) = logging.max_size_bytes() {
+            args.push("--log-max-size".to_string());
+            args.push(format!("{max_size_bytes}B"));
+        }
+        if let Some(max_files) = logging.max_files() {
+            args.push("--log-max-files".to_string());
+            args.push(max_files.to_string());
+        }
+    }
+
+    if let Some(opentelemetry_context) = opentelemetry_context {
+        args.push("--opentelemetry-context".to_string());
+        args.push(opentelemetry_context.to_string());
+    }
+
+    args.push(name.to_owned());
+    Ok(args)
+}
+
+/// Return the arguments setting the options of a node in the ockam command running it.
+/// The node name, its logging configuration and the trace context are not included
+fn node_creation_args(opts: &CommandGlobalOpts, cmd: &CreateCommand) -> Vec<String> {
     let CreateCommand {
         skip_is_running_check,
-        name,
         identity: identity_name,
         tcp_listener_address: address,
         launch_config,
         trust_opts,
-        opentelemetry_context,
+        shutdown_grace,
+        prefer_ipv6,
+        dns_cache_ttl,
+        static_hosts,
+        tcp_keepalive,
+        tcp_user_timeout,
+        max_message_size,
+        debug_api,
+        attributes_max_age,
+        ws,
+        udp,
+        connect_on_start,
+        health_check_port,
         ..
     } = cmd;
     let TrustOpts {
@@ -92,39 +167,111 @@ pub async fn spawn_node(opts: &CommandGlobalOpts, cmd: CreateCommand) -> miette:
         address.to_string(),
         "--foreground".to_string(),
         "--child-process".to_string(),
+        "--shutdown-grace".to_string(),
+        format!("{}ms", shutdown_grace.as_millis()),
     ];
 
     if let Some(credential_scope) = credential_scope {
         args.push("--credential-scope".to_string());
-        args.push(credential_scope)
+        args.push(credential_scope.clone())
     }
 
-    if skip_is_running_check {
+    if *skip_is_running_check {
         args.push("--skip-is-running-check".to_string());
     }
 
+    if *prefer_ipv6 {
+        args.push("--prefer-ipv6".to_string());
+    }
+
+    if let Some(dns_cache_ttl) = dns_cache_ttl {
+        args.push("--dns-cache-ttl".to_string());
+        args.push(format!("{}ms", dns_cache_ttl.as_millis()));
+    }
+
+    for (hostname, ip) in static_hosts {
+        args.push("--static-host".to_string());
+        args.push(format!("{hostname}={ip}"));
+    }
+
+    if let Some(tcp_keepalive) = tcp_keepalive {
+        args.push("--tcp-keepalive".to_string());
+        args.push(format!("{}ms", tcp_keepalive.as_millis()));
+    }
+
+    if let Some(tcp_user_timeout) = tcp_user_timeout {
+        args.push("--tcp-user-timeout".to_string());
+        args.push(format!("{}ms", tcp_user_timeout.as_millis()));
+    }
+
+    if *debug_api {
+        args.push("--debug-api".to_string());
+    }
+
+    if let Some(attributes_max_age) = attributes_max_age {
+        args.push("--attributes-max-age".to_string());
+        args.push(format!("{}ms", attributes_max_age.as_millis()));
+    }
+
+    if let Some(max_message_size) = max_message_size {
+        args.push("--max-message-size".to_string());
+        args.push(max_message_size.to_string());
+    }
+
+    if let Some(ws) = ws {
+        args.push("--ws".to_string());
+        args.push(ws.clone());
+    }
+
+    if let Some(udp) = udp {
+        args.push("--udp".to_string());
+        args.push(udp.clone());
+    }
+
+    if let Some(connect_on_start) = connect_on_start {
+        args.push("--connect-on-start".to_string());
+        args.push(connect_on_start.clone());
+    }
+
+    if let Some(health_check_port) = health_check_port {
+        args.push("--health-check-port".to_string());
+        args.push(health_check_port.to_string());
+    }
+
+    #[cfg(feature = "telemetry")]
+    {
+        if cmd.export_metrics {
+            args.push("--export-metrics".to_string());
+        }
+
+        if let Some(metrics_export_interval) = cmd.metrics_export_interval {
+            args.push("--metrics-export-interval".to_string());
+            args.push(format!("{}ms", metrics_export_interval.as_millis()));
+        }
+    }
+
     if !opts.terminal.is_tty() {
         args.push("--no-color".to_string());
     }
 
     if let Some(identity_name) = identity_name {
         args.push("--identity".to_string());
-        args.push(identity_name);
+        args.push(identity_name.clone());
     }
 
     if let Some(config) = launch_config {
         args.push("--launch-config".to_string());
-        args.push(serde_json::to_string(&config).unwrap());
+        args.push(serde_json::to_string(config).unwrap());
     }
 
     if let Some(project_name) = project_name {
         args.push("--project".to_string());
-        args.push(project_name);
+        args.push(project_name.clone());
     }
 
     if let Some(authority_identity) = authority_identity {
         args.push("--authority-identity".to_string());
-        args.push(authority_identity);
+        args.push(authority_identity.clone());
     }
 
     if let Some(authority_route) = authority_route {
@@ -132,14 +279,83 @@ pub async fn spawn_node(opts: &CommandGlobalOpts, cmd: CreateCommand) -> miette:
         args.push(authority_route.to_string());
     }
 
-    if let Some(opentelemetry_context) = opentelemetry_context {
-        args.push("--opentelemetry-context".to_string());
-        args.push(opentelemetry_context.to_string());
+    args
+}
+
+/// Spawn a node with the options recorded when it was created.
+/// The node listens again on the TCP address it was using, in case it was created with a random port
+pub async fn spawn_node_with_stored_options(
+    opts: &CommandGlobalOpts,
+    node_info: &NodeInfo,
+) -> miette::Result<()> {
+    let node_name = node_info.name();
+    let tcp_listener_address = node_info.tcp_listener_address().map(|a| a.to_string());
+    if let Some(args) =
+        stored_node_process_args(opts, &node_name, tcp_listener_address.clone()).await?
+    {
+        return run_ockam(args).await;
     }
 
-    args.push(name.to_owned());
+    // The options of nodes created by previous versions are not recorded
+    let project_name = opts
+        .state
+        .get_node_project(&node_name)
+        .await
+        .ok()
+        .map(|p| p.name().to_string());
+    #[allow(clippy::field_reassign_with_default)]
+    let cmd = {
+        let mut cmd = CreateCommand::default();
+        cmd.name = node_name;
+        cmd.tcp_listener_address = tcp_listener_address.unwrap_or(cmd.tcp_listener_address);
+        cmd.trust_opts.project_name = project_name;
+        cmd
+    };
+    spawn_node(opts, cmd).await
+}
 
-    run_ockam(args).await
+/// Return the arguments of the ockam command running a node with the options it was created with,
+/// if they were recorded
+async fn stored_node_process_args(
+    opts: &CommandGlobalOpts,
+    node_name: &str,
+    tcp_listener_address: Option<String>,
+) -> miette::Result<Option<Vec<String>>> {
+    let Some(mut args) = opts.state.get_node_arguments(node_name).await? else {
+        return Ok(None);
+    };
+    if let Some(address) = tcp_listener_address {
+        replace_argument_value(&mut args, "--tcp-listener-address", address);
+    }
+    Ok(Some(
+        node_process_args_with(opts, node_name, args, None).await?,
+    ))
+}
+
+/// Replace the value following an option in a list of arguments, if the option is present
+fn replace_argument_value(args: &mut [String], option: &str, value: String) {
+    if let Some(i) = args.iter().position(|a| a == option) {
+        if let Some(current) = args.get_mut(i + 1) {
+            *current = value;
+        }
+    }
+}
+
+/// Spawn a watchdog process restarting the node when it crashes, if the node is supervised
+pub async fn spawn_watchdog_if_supervised(
+    opts: &CommandGlobalOpts,
+    node_name: &str,
+) -> miette::Result<()> {
+    if opts.state.get_node_supervision(node_name).await?.is_some() {
+        info!("spawning a watchdog for the node {node_name}");
+        run_ockam(vec![
+            "node".to_string(),
+            "watchdog".to_string(),
+            node_name.to_string(),
+        ])
+        .await?;
+    }
+    Ok(())
 }
 
 /// Run the ockam command line with specific arguments
@@ -152,7 +368,13 @@ pub async fn run_ockam(args: Vec<String>) -> miette::Result<()> {
             .unwrap()
             .into()
     });
-    Command::new(ockam_exe)
+    let mut command = Command::new(ockam_exe);
+    // pass the passphrase entered to unlock an encrypted vault
+    // since the child process cannot prompt for it
+    if let Some(passphrase) = entered_vault_passphrase() {
+        command.env(OCKAM_VAULT_PASSWORD, passphrase);
+    }
+    command
         .args(args)
         .stdout(Stdio::null())
         .stderr(Stdio::null())
@@ -162,3 +384,74 @@ pub async fn run_ockam(args: Vec<String>) -> miette::Result<()> {
         .context("failed to spawn node")?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node::{NodeCommand, NodeSubcommand};
+    use crate::{GlobalArgs, OckamCommand, OckamSubcommand};
+    use clap::Parser;
+    use ockam_api::cli_state::CliState;
+    use ockam_api::cli_state::NodeLogging;
+    use ockam_api::logs::LogFormat;
+    use std::time::Duration;
+
+    fn parse_node_create(args: Vec<String>) -> CreateCommand {
+        let args = std::iter::once("ockam".to_string()).chain(args);
+        match OckamCommand::try_parse_from(args).unwrap().subcommand {
+            OckamSubcommand::Node(cmd) => match cmd.subcommand {
+                NodeSubcommand::Create(cmd) => cmd,
+                _ => panic!("unexpected command"),
+            },
+            _ => panic!("unexpected command"),
+        }
+    }
+
+    #[test]
+    fn a_node_is_started_again_with_the_options_it_was_created_with() -> miette::Result<()> {
+        let rt = tokio::runtime::Runtime::new().into_diagnostic()?;
+        let state = rt.block_on(CliState::test())?;
+        let opts = CommandGlobalOpts::new_for_test(GlobalArgs::default(), state);
+        let cmd = CreateCommand {
+            name: "n1".to_string(),
+            tcp_listener_address: "127.0.0.1:0".to_string(),
+            identity: Some("alice".to_string()),
+            prefer_ipv6: true,
+            dns_cache_ttl: Some(Duration::from_secs(30)),
+            debug_api: true,
+            max_message_size: Some(1000),
+            ..Default::default()
+        };
+
+        // the options are recorded when the node is spawned
+        let creation_args = node_creation_args(&opts, &cmd);
+        rt.block_on(opts.state.set_node_arguments("n1", &creation_args))?;
+        // the logging configuration can change after the node creation
+        let logging = NodeLogging::new("n1").with_log_format(Some(LogFormat::Json));
+        rt.block_on(opts.state.set_node_logging(&logging))?;
+
+        let args = rt
+            .block_on(stored_node_process_args(
+                &opts,
+                "n1",
+                Some("127.0.0.1:4000".to_string()),
+            ))?
+            .unwrap();
+        let restarted = parse_node_create(args);
+        assert_eq!(restarted.name, "n1");
+        assert_eq!(restarted.tcp_listener_address, "127.0.0.1:4000");
+        assert_eq!(restarted.identity, cmd.identity);
+        assert!(restarted.prefer_ipv6);
+        assert_eq!(restarted.dns_cache_ttl, cmd.dns_cache_ttl);
+        assert!(restarted.debug_api);
+        assert_eq!(restarted.max_message_size, cmd.max_message_size);
+        assert_eq!(restarted.log_format, Some(LogFormat::Json));
+        assert!(restarted.foreground);
+        assert!(restarted.child_process);
+
+        // the options of a node created by a previous version are not known
+        let args = rt.block_on(stored_node_process_args(&opts, "n2", None))?;
+        assert!(args.is_none());
+        Ok(())
+    }
+}
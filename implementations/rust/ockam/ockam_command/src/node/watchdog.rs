@@ -0,0 +1,192 @@
+use std::cmp::min;
+
+use async_trait::async_trait;
+use clap::Args;
+use miette::IntoDiagnostic;
+use tokio::time::{sleep, Duration, Instant};
+use tracing::{info, warn};
+
+use ockam::identity::utils::now;
+use ockam::{Context, TcpTransport};
+use ockam_api::cli_state::NodeSupervision;
+use ockam_api::nodes::BackgroundNodeClient;
+
+use crate::node::restart::NodeSnapshot;
+use crate::node::show::is_node_up;
+use crate::node::util::spawn_node_with_stored_options;
+use crate::{Command, CommandGlobalOpts};
+
+/// Time between two checks of the node process
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Time between two snapshots of the node resources
+const SNAPSHOT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Supervise a background node and restart it when its process crashes
+#[derive(Clone, Debug, Args)]
+pub struct WatchdogCommand {
+    /// Name of the node to supervise
+    node_name: String,
+}
+
+#[async_trait]
+impl Command for WatchdogCommand {
+    const NAME: &'static str = "node watchdog";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let node_name = self.node_name;
+        let watchdog_pid = std::process::id();
+        match opts.state.get_node_supervision(&node_name).await? {
+            Some(supervision) => {
+                opts.state
+                    .update_node_supervision(&supervision.set_watchdog_pid(watchdog_pid))
+                    .await?
+            }
+            None => return Ok(()),
+        };
+        info!(%node_name, %watchdog_pid, "start supervising the node");
+
+        let tcp = TcpTransport::create(ctx).await.into_diagnostic()?;
+        let mut snapshot: Option<NodeSnapshot> = None;
+        let mut last_snapshot_at: Option<Instant> = None;
+        let mut up_since = Instant::now();
+        let mut consecutive_crashes = 0;
+
+        loop {
+            sleep(CHECK_INTERVAL).await;
+
+            // Stop supervising the node if it has been deleted, if its supervision
+            // has been taken over by another watchdog, or if it has been stopped on purpose
+            let node_info = match opts.state.get_node(&node_name).await {
+                Ok(node_info) => node_info,
+                Err(_) => return Ok(()),
+            };
+            let supervision = match opts.state.get_node_supervision(&node_name).await? {
+                Some(supervision) if supervision.watchdog_pid() == Some(watchdog_pid) => {
+                    supervision
+                }
+                _ => return Ok(()),
+            };
+            if node_info.pid().is_none() {
+                info!(%node_name, "the node was stopped, stop supervising it");
+                return Ok(());
+            }
+
+            if node_info.is_running() {
+                if up_since.elapsed() > supervision.max_backoff() {
+                    consecutive_crashes = 0;
+                }
+                if last_snapshot_at
+                    .map(|t| t.elapsed() > SNAPSHOT_INTERVAL)
+                    .unwrap_or(true)
+                {
+                    if let Ok(node) =
+                        BackgroundNodeClient::create_to_node_with_tcp(&tcp, &opts.state, &node_name)
+                            .await
+                    {
+                        if let Ok(s) = NodeSnapshot::create(ctx, &node).await {
+                            snapshot = Some(s);
+                        }
+                    }
+                    last_snapshot_at = Some(Instant::now());
+                }
+                continue;
+            }
+
+            // The node process exited without being stopped
+            consecutive_crashes += 1;
+            let (supervision, backoff) =
+                handle_crash(supervision, consecutive_crashes, now().into_diagnostic()?.0);
+            warn!(%node_name, restart_count = supervision.restart_count(), "the node crashed");
+            opts.state.update_node_supervision(&supervision).await?;
+            let Some(backoff) = backoff else {
+                warn!(%node_name, %consecutive_crashes, "the node crashed too many times in a row, stop restarting it");
+                return Ok(());
+            };
+            info!(%node_name, ?backoff, "restarting the node");
+            sleep(backoff).await;
+
+            spawn_node_with_stored_options(&opts, &node_info).await?;
+            up_since = Instant::now();
+            let mut node =
+                BackgroundNodeClient::create_to_node_with_tcp(&tcp, &opts.state, &node_name)
+                    .await?;
+            if !is_node_up(ctx, &mut node, true).await? {
+                warn!(%node_name, "the restarted node is not up");
+                continue;
+            }
+            if let Some(snapshot) = &snapshot {
+                let results = snapshot.replay(ctx, &opts, &node).await;
+                info!(
+                    %node_name,
+                    resources = %serde_json::to_string(&results).unwrap_or_default(),
+                    "the node resources have been recreated"
+                );
+            }
+        }
+    }
+}
+
+/// Record a crash of the supervised node.
+/// Return the updated supervision and the time to wait before restarting the node,
+/// or None if the node crashed too many times in a row and must not be restarted
+fn handle_crash(
+    supervision: NodeSupervision,
+    consecutive_crashes: u32,
+    now: u64,
+) -> (NodeSupervision, Option<Duration>) {
+    let supervision = supervision.record_crash(now);
+    if consecutive_crashes > supervision.max_restarts() {
+        return (supervision.set_failed(), None);
+    }
+    let backoff = restart_backoff(consecutive_crashes, supervision.max_backoff());
+    (supervision, Some(backoff))
+}
+
+/// Return the time to wait before restarting a node: 1s after the first crash,
+/// then doubled after each consecutive crash, up to the maximum backoff
+fn restart_backoff(consecutive_crashes: u32, max_backoff: Duration) -> Duration {
+    min(
+        Duration::from_secs(1 << min(consecutive_crashes.saturating_sub(1), 16)),
+        max_backoff,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_restart_backoff_doubles_up_to_its_maximum() {
+        let max_backoff = Duration::from_secs(10);
+        let backoffs: Vec<u64> = (1..=6)
+            .map(|crashes| restart_backoff(crashes, max_backoff).as_secs())
+            .collect();
+        assert_eq!(backoffs, vec![1, 2, 4, 8, 10, 10]);
+
+        // the shift does not overflow after many crashes
+        assert_eq!(
+            restart_backoff(100, Duration::from_secs(u64::MAX)),
+            Duration::from_secs(1 << 16)
+        );
+    }
+
+    #[test]
+    fn a_node_is_not_restarted_after_too_many_consecutive_crashes() {
+        let mut supervision = NodeSupervision::new("n", 3, Duration::from_secs(60));
+        let mut backoffs = vec![];
+        for crashes in 1..=3 {
+            let (updated, backoff) = handle_crash(supervision, crashes, 1000 + crashes as u64);
+            assert!(!updated.is_failed());
+            backoffs.push(backoff.unwrap().as_secs());
+            supervision = updated;
+        }
+        assert_eq!(backoffs, vec![1, 2, 4]);
+
+        let (supervision, backoff) = handle_crash(supervision, 4, 1004);
+        assert!(backoff.is_none());
+        assert!(supervision.is_failed());
+        assert_eq!(supervision.restart_count(), 4);
+        assert_eq!(supervision.last_crash_at(), Some(1004));
+    }
+}
@@ -1,8 +1,10 @@
 mod encode_format;
+mod models;
 #[allow(clippy::module_inception)]
 pub(crate) mod output;
 mod output_format;
 
 pub use encode_format::*;
+pub use models::*;
 pub use output::*;
 pub use output_format::*;
@@ -0,0 +1,1375 @@
+//! Data written by the commands when they are called with `--output json`.
+//!
+//! These structures are not the models exchanged with the nodes or the Orchestrator:
+//! their fields are part of the interface of the command line, and the tests below
+//! make sure that they are not renamed by mistake. New fields can be added, but
+//! existing fields must not be removed or renamed.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use colorful::core::StrMarker;
+use colorful::Colorful;
+use indoc::formatdoc;
+use serde::Serialize;
+use serde_json::json;
+
+use ockam::identity::models::CredentialAndPurposeKey;
+use ockam::identity::{AttributesEntry, Identifier, TimestampInSeconds};
+use ockam_api::bench_target::BenchTargetMode;
+use ockam_api::cloud::addon::Addon;
+use ockam_api::cloud::project::models::{InfluxDBTokenLeaseManagerConfig, OktaConfig};
+use ockam_api::kafka::BrokerAuth;
+use ockam_api::nodes::models::relay::RelayInfo;
+use ockam_api::nodes::models::workers::WorkerStatus;
+use ockam_api::port_range::PortRange;
+use ockam_api::ConnectionStatus;
+use ockam_multiaddr::MultiAddr;
+
+use crate::error::{Error, ErrorCode};
+use crate::output::{human_readable_time, Output};
+use crate::project::util::{decode_pem_certificates, validate_okta_tenant_url};
+use crate::terminal::OckamColor;
+use crate::util::colorize_connection_status;
+use crate::{fmt_warn, Result};
+
+/// Credential, in the `ockam credential list` command
+#[derive(Serialize)]
+pub struct CredentialOutput {
+    credential: String,
+    scope: String,
+    subject: Identifier,
+    issuer: Identifier,
+    created_at: TimestampInSeconds,
+    expires_at: TimestampInSeconds,
+    is_verified: bool,
+    schema: u64,
+    attributes: BTreeMap<String, String>,
+}
+
+impl CredentialOutput {
+    pub fn from_credential(
+        credential: CredentialAndPurposeKey,
+        scope: String,
+        is_verified: bool,
+    ) -> Result<Self> {
+        let str = hex::encode(credential.encode_as_cbor_bytes()?);
+        let credential_data = credential.credential.get_credential_data()?;
+        let purpose_key_data = credential.purpose_key_attestation.get_attestation_data()?;
+
+        let subject = credential_data.subject.ok_or(Error::InternalError {
+            error_message: "credential subject is missing".to_str(),
+            exit_code: 1,
+            error_code: ErrorCode::Internal,
+        })?;
+
+        let mut attributes = BTreeMap::<String, String>::default();
+        for (k, v) in &credential_data.subject_attributes.map {
+            match (
+                String::from_utf8(k.as_slice().to_vec()),
+                String::from_utf8(v.as_slice().to_vec()),
+            ) {
+                (Ok(k), Ok(v)) => _ = attributes.insert(k, v),
+                _ => continue,
+            }
+        }
+
+        let s = Self {
+            credential: str,
+            scope,
+            subject,
+            issuer: purpose_key_data.subject,
+            created_at: credential_data.created_at,
+            expires_at: credential_data.expires_at,
+            is_verified,
+            schema: credential_data.subject_attributes.schema.0,
+            attributes,
+        };
+
+        Ok(s)
+    }
+}
+
+impl Output for CredentialOutput {
+    fn output(&self) -> Result<String> {
+        let is_verified = if self.is_verified {
+            "✔︎".light_green()
+        } else {
+            "✕".light_red()
+        };
+
+        let attributes = json!(self.attributes).to_string();
+
+        let output = format!(
+            "Credential:\n\
+            \tscope:       {scope}\n\
+            \tsubject:     {subject}\n\
+            \tissuer:      {issuer}\n\
+            \tis_verified: {is_verified}\n\
+            \tcreated_at:  {created_at}\n\
+            \texpires_at:  {expires_at}\n\
+            \tschema:      {schema}\n\
+            \tattributes:  {attributes}\n\
+            \tbinary:      {credential}",
+            scope = self.scope,
+            subject = self.subject,
+            issuer = self.issuer,
+            is_verified = is_verified,
+            created_at = self.created_at.0,
+            expires_at = self.expires_at.0,
+            schema = self.schema,
+            attributes = attributes,
+            credential = self.credential
+        );
+
+        Ok(output)
+    }
+}
+
+/// Relay, in the `ockam relay create`, `list` and `show` commands
+#[derive(Serialize)]
+pub struct RelayOutput {
+    pub alias: String,
+    pub destination: MultiAddr,
+    pub connection_status: ConnectionStatus,
+    pub relay_route: Option<String>,
+    /// Address of the relay on the remote node, e.g. "forward_to_blue"
+    pub remote_address: Option<String>,
+    pub remote_address_ma: Option<MultiAddr>,
+    pub worker_address: Option<MultiAddr>,
+    pub last_heartbeat_at: Option<u64>,
+    pub round_trip_time_ms: Option<u64>,
+    pub failed_heartbeats: u32,
+    pub reconnection_attempts: u32,
+    pub last_failure: Option<String>,
+}
+
+impl From<RelayInfo> for RelayOutput {
+    fn from(r: RelayInfo) -> Self {
+        Self {
+            alias: r.alias().to_string(),
+            destination: r.destination_address().clone(),
+            connection_status: r.connection_status(),
+            relay_route: r.forwarding_route().clone(),
+            remote_address: r.remote_address().clone(),
+            remote_address_ma: r.remote_address_ma().ok().flatten(),
+            worker_address: r.worker_address_ma().ok().flatten(),
+            last_heartbeat_at: r.last_heartbeat_at(),
+            round_trip_time_ms: r.round_trip_time().map(|rtt| rtt.as_millis() as u64),
+            failed_heartbeats: r.failed_heartbeats(),
+            reconnection_attempts: r.reconnection_attempts(),
+            last_failure: r.last_failure().map(|f| f.to_string()),
+        }
+    }
+}
+
+impl Output for RelayOutput {
+    fn output(&self) -> Result<String> {
+        Ok(formatdoc!(
+            r#"
+        Relay:
+            Alias: {alias}
+            Destination: {destination_address}
+            Status: {connection_status}
+            Relay Route: {route}
+            Remote Address: {remote_addr}
+            Worker Address: {worker_addr}
+            Last Heartbeat: {last_heartbeat}
+            Round-trip Time: {round_trip_time}
+            Failed Heartbeats: {failed_heartbeats}
+            Reconnection Attempts: {reconnection_attempts}
+            Last Failure: {last_failure}
+        "#,
+            alias = self.alias,
+            connection_status = colorize_connection_status(self.connection_status),
+            destination_address = self.destination.to_string(),
+            route = self.relay_route.as_deref().unwrap_or("N/A"),
+            remote_addr = self
+                .remote_address_ma
+                .as_ref()
+                .map(|x| x.to_string())
+                .unwrap_or("N/A".into()),
+            worker_addr = self
+                .worker_address
+                .as_ref()
+                .map(|x| x.to_string())
+                .unwrap_or("N/A".into()),
+            last_heartbeat = self
+                .last_heartbeat_at
+                .map(|t| human_readable_time(TimestampInSeconds(t)))
+                .unwrap_or("N/A".into()),
+            round_trip_time = self
+                .round_trip_time_ms
+                .map(|ms| format!("{ms}ms"))
+                .unwrap_or("N/A".into()),
+            failed_heartbeats = self.failed_heartbeats,
+            reconnection_attempts = self.reconnection_attempts,
+            last_failure = self.last_failure.as_deref().unwrap_or("N/A"),
+        ))
+    }
+
+    fn list_output(&self) -> Result<String> {
+        Ok(formatdoc!(
+            r#"
+            Alias: {alias}
+            Status: {connection_status}
+            Remote Address: {remote_address}"#,
+            alias = self
+                .alias
+                .as_str()
+                .color(OckamColor::PrimaryResource.color()),
+            connection_status = colorize_connection_status(self.connection_status),
+            remote_address = self
+                .remote_address_ma
+                .as_ref()
+                .map(|x| x.to_string())
+                .unwrap_or("N/A".into())
+                .color(OckamColor::PrimaryResource.color()),
+        ))
+    }
+}
+
+/// Secure channel, in the `ockam secure-channel list` command
+#[derive(Serialize)]
+pub struct SecureChannelListOutput {
+    pub from: String,
+    pub to: String,
+    pub at: String,
+    /// "initiated" if the node created the channel, "accepted" if one of its listeners did
+    pub direction: String,
+    pub their_identifier: String,
+    pub created_at: TimestampInSeconds,
+    pub age_seconds: u64,
+    pub messages_sent: u64,
+    pub bytes_sent: u64,
+    pub messages_received: u64,
+    pub bytes_received: u64,
+    pub last_activity_at: Option<TimestampInSeconds>,
+    /// Inlets, relays and named channels of the node using the channel, e.g. "tcp-inlet db"
+    pub used_by: Vec<String>,
+}
+
+impl Output for SecureChannelListOutput {
+    fn output(&self) -> Result<String> {
+        let mut output = String::new();
+        writeln!(
+            output,
+            "From {} to {} ",
+            self.from
+                .to_string()
+                .color(OckamColor::PrimaryResource.color()),
+            self.to
+                .to_string()
+                .color(OckamColor::PrimaryResource.color())
+        )?;
+        writeln!(
+            output,
+            "At {}",
+            self.at
+                .to_string()
+                .color(OckamColor::PrimaryResource.color())
+        )?;
+        writeln!(
+            output,
+            "{} with {}, {}s ago",
+            self.direction,
+            self.their_identifier
+                .to_string()
+                .color(OckamColor::PrimaryResource.color()),
+            self.age_seconds
+        )?;
+        write!(
+            output,
+            "Sent {} messages ({} bytes), received {} messages ({} bytes), last active at {}",
+            self.messages_sent,
+            self.bytes_sent,
+            self.messages_received,
+            self.bytes_received,
+            self.last_activity_at
+                .map(human_readable_time)
+                .unwrap_or("N/A".to_string())
+        )?;
+        if !self.used_by.is_empty() {
+            write!(output, "\nUsed by {}", self.used_by.join(", "))?;
+        }
+
+        Ok(output)
+    }
+}
+
+/// Project member, in the `ockam project member list` command
+#[derive(Serialize)]
+pub struct MemberOutput {
+    pub identifier: Identifier,
+    pub attributes: BTreeMap<String, String>,
+    pub added_at: TimestampInSeconds,
+    pub expires_at: Option<TimestampInSeconds>,
+    /// Identifier of the enroller who added the member
+    pub attested_by: Option<Identifier>,
+}
+
+impl MemberOutput {
+    pub fn new(identifier: Identifier, entry: AttributesEntry) -> Self {
+        let attributes = entry
+            .attrs()
+            .iter()
+            .filter_map(|(k, v)| {
+                Some((
+                    String::from_utf8(k.clone()).ok()?,
+                    String::from_utf8(v.clone()).ok()?,
+                ))
+            })
+            .collect();
+        Self {
+            identifier,
+            attributes,
+            added_at: entry.added_at(),
+            expires_at: entry.expires_at(),
+            attested_by: entry.attested_by(),
+        }
+    }
+}
+
+impl Output for MemberOutput {
+    fn output(&self) -> Result<String> {
+        let attributes = self
+            .attributes
+            .iter()
+            .map(|(k, v)| format!("{k}={v}"))
+            .collect::<Vec<_>>()
+            .join(",");
+        let mut output = format!("{}: {}", self.identifier, attributes);
+        match &self.attested_by {
+            Some(attested_by) => write!(
+                output,
+                "\n  Added by {} at {}",
+                attested_by,
+                human_readable_time(self.added_at)
+            )?,
+            None => write!(
+                output,
+                "\n  Added at {}",
+                human_readable_time(self.added_at)
+            )?,
+        }
+        Ok(output)
+    }
+}
+
+/// Worker, in the `ockam worker list` command.
+/// The details are only available on the nodes with an enabled debug API
+#[derive(Serialize)]
+pub struct WorkerOutput {
+    pub address: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aliases: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub routed_messages: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pending_messages: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub terminal: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub internal: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+}
+
+impl From<WorkerStatus> for WorkerOutput {
+    fn from(w: WorkerStatus) -> Self {
+        let details = w.details;
+        Self {
+            address: w.addr,
+            kind: details.as_ref().map(|d| d.kind.clone()),
+            aliases: details.as_ref().map(|d| d.aliases.clone()),
+            routed_messages: details.as_ref().map(|d| d.routed_messages),
+            pending_messages: details.as_ref().map(|d| d.pending_messages),
+            terminal: details.as_ref().map(|d| d.terminal),
+            internal: details.as_ref().map(|d| d.internal.is_some()),
+            owner: details.and_then(|d| d.internal),
+        }
+    }
+}
+
+/// Project addon, in the `ockam project addon list` command
+#[derive(Serialize)]
+pub struct AddonOutput {
+    pub id: String,
+    pub description: String,
+    pub enabled: bool,
+}
+
+impl From<Addon> for AddonOutput {
+    fn from(a: Addon) -> Self {
+        Self {
+            id: a.id,
+            description: a.description,
+            enabled: a.enabled,
+        }
+    }
+}
+
+/// Okta addon of a project, in the `ockam project addon configure okta` command.
+/// The configuration returned by the Orchestrator can be incomplete: the `problems`
+/// list what is missing or invalid
+#[derive(Serialize)]
+pub struct OktaAddonOutput {
+    pub tenant_base_url: String,
+    pub client_id: String,
+    pub attributes: Vec<String>,
+    /// Number of certificates in the certificate chain of the tenant
+    pub certificates: usize,
+    pub problems: Vec<String>,
+}
+
+impl From<&OktaConfig> for OktaAddonOutput {
+    fn from(config: &OktaConfig) -> Self {
+        let mut problems = vec![];
+        if let Err(e) = validate_okta_tenant_url(&config.tenant_base_url) {
+            problems.push(e.to_string());
+        }
+        if config.client_id.trim().is_empty() {
+            problems.push("the client id is missing".to_string());
+        }
+        let certificates = match decode_pem_certificates(&config.certificate) {
+            Ok(certificates) => certificates,
+            Err(e) => {
+                problems.push(e.to_string());
+                0
+            }
+        };
+        Self {
+            tenant_base_url: config.tenant_base_url.to_string(),
+            client_id: config.client_id.clone(),
+            attributes: config.attributes.clone(),
+            certificates,
+            problems,
+        }
+    }
+}
+
+impl Output for OktaAddonOutput {
+    fn output(&self) -> Result<String> {
+        let mut w = String::new();
+        writeln!(w, "Okta addon:")?;
+        writeln!(w, "  Tenant: {}", or_not_set(&self.tenant_base_url))?;
+        writeln!(w, "  Client ID: {}", or_not_set(&self.client_id))?;
+        writeln!(
+            w,
+            "  Attributes: {}",
+            or_not_set(&self.attributes.join(", "))
+        )?;
+        writeln!(w, "  Certificates: {}", self.certificates)?;
+        for problem in &self.problems {
+            writeln!(w, "{}", fmt_warn!("{problem}"))?;
+        }
+        Ok(w)
+    }
+}
+
+/// InfluxDB addon of a project, in the `ockam project addon configure influxdb` command.
+/// The token given to the Orchestrator is not part of the output
+#[derive(Serialize)]
+pub struct InfluxDBAddonOutput {
+    pub endpoint: String,
+    pub org_id: String,
+    pub permissions: serde_json::Value,
+    pub max_ttl_secs: i32,
+}
+
+impl From<&InfluxDBTokenLeaseManagerConfig> for InfluxDBAddonOutput {
+    fn from(config: &InfluxDBTokenLeaseManagerConfig) -> Self {
+        Self {
+            endpoint: config.endpoint.clone(),
+            org_id: config.org_id.clone(),
+            permissions: serde_json::from_str(&config.permissions)
+                .unwrap_or_else(|_| json!(config.permissions)),
+            max_ttl_secs: config.max_ttl_secs,
+        }
+    }
+}
+
+impl Output for InfluxDBAddonOutput {
+    fn output(&self) -> Result<String> {
+        let permissions = match &self.permissions {
+            serde_json::Value::Array(permissions) => permissions.len(),
+            _ => 0,
+        };
+        let mut w = String::new();
+        writeln!(w, "InfluxDB addon:")?;
+        writeln!(w, "  Endpoint: {}", self.endpoint)?;
+        writeln!(w, "  Organization ID: {}", self.org_id)?;
+        writeln!(w, "  Permissions: {permissions}")?;
+        writeln!(w, "  Tokens max TTL: {}s", self.max_ttl_secs)?;
+        Ok(w)
+    }
+}
+
+fn or_not_set(value: &str) -> String {
+    if value.trim().is_empty() {
+        "not set".light_red().to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Kafka service started by the `ockam kafka-*` create commands
+#[derive(Serialize)]
+pub struct KafkaServiceOutput {
+    /// Type of the service, for example `KafkaProducer`
+    pub service_type: String,
+    pub address: String,
+    /// Address where the Kafka clients connect to, for the direct services
+    pub bind_address: Option<SocketAddr>,
+    pub bootstrap_server: SocketAddr,
+    /// Range of the ports used by the brokers, formatted as `<start>-<end>`
+    pub brokers_port_range: Option<String>,
+    /// Authentication mode declared for the Kafka brokers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub broker_auth: Option<BrokerAuth>,
+    /// Patterns of the topics allowed by the service
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_topics: Option<Vec<String>>,
+}
+
+impl KafkaServiceOutput {
+    pub fn new(
+        service_type: impl Into<String>,
+        address: impl Into<String>,
+        bootstrap_server: SocketAddr,
+    ) -> Self {
+        Self {
+            service_type: service_type.into(),
+            address: address.into(),
+            bind_address: None,
+            bootstrap_server,
+            brokers_port_range: None,
+            broker_auth: None,
+            allowed_topics: None,
+        }
+    }
+
+    pub fn with_bind_address(mut self, bind_address: SocketAddr) -> Self {
+        self.bind_address = Some(bind_address);
+        self
+    }
+
+    pub fn with_brokers_port_range(mut self, brokers_port_range: PortRange) -> Self {
+        self.brokers_port_range = Some(brokers_port_range.to_string());
+        self
+    }
+
+    pub fn with_broker_auth(mut self, broker_auth: Option<BrokerAuth>) -> Self {
+        self.broker_auth = broker_auth;
+        self
+    }
+
+    pub fn with_allowed_topics(mut self, allowed_topics: Option<Vec<String>>) -> Self {
+        self.allowed_topics = allowed_topics;
+        self
+    }
+}
+
+/// Hop of a route, in the `ockam trace` command
+#[derive(Serialize)]
+pub struct TraceHopOutput {
+    /// Position of the hop in the route, starting at 1
+    pub hop: usize,
+    /// Part of the route leading to this hop from the previous one
+    pub address: String,
+    pub status: TraceHopStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identifier: Option<String>,
+    /// Round-trip time of the probe, in milliseconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rtt_ms: Option<u64>,
+    /// Unix time, in milliseconds, at which the hop answered the probe
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp_ms: Option<u64>,
+    /// Reason why the first hop without an answer did not answer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Status of a hop, in the `ockam trace` command
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TraceHopStatus {
+    /// The trace service of the hop answered the probe
+    Answered,
+    /// The hop didn't answer, but a later hop did, or it is the destination of the route
+    Opaque,
+    /// First hop without an answer, after which no hop answered
+    NoAnswer,
+    /// Hop after the first hop without an answer
+    NotReached,
+}
+
+/// Result of the `ockam ping` command
+#[derive(Serialize)]
+pub struct PingOutput {
+    pub to: String,
+    pub sent: usize,
+    pub received: usize,
+    pub loss_percent: f64,
+    /// Time spent setting up the connection of the route, in milliseconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub setup_ms: Option<f64>,
+    /// Round-trip time statistics, in milliseconds, over the messages which got a reply
+    pub min_ms: Option<f64>,
+    pub avg_ms: Option<f64>,
+    pub max_ms: Option<f64>,
+    pub p95_ms: Option<f64>,
+    /// Round-trip time of each message, or nothing if it was not answered in time
+    pub rtts_ms: Vec<Option<f64>>,
+}
+
+impl PingOutput {
+    pub fn new(to: impl Into<String>, rtts: &[Option<Duration>], setup: Option<Duration>) -> Self {
+        let mut received: Vec<f64> = rtts.iter().flatten().map(|rtt| millis(*rtt)).collect();
+        received.sort_by(f64::total_cmp);
+        let sent = rtts.len();
+        let loss_percent = match sent {
+            0 => 0.0,
+            _ => (sent - received.len()) as f64 * 100.0 / sent as f64,
+        };
+        Self {
+            to: to.into(),
+            sent,
+            received: received.len(),
+            loss_percent,
+            setup_ms: setup.map(millis),
+            min_ms: received.first().copied(),
+            avg_ms: match received.len() {
+                0 => None,
+                n => Some(received.iter().sum::<f64>() / n as f64),
+            },
+            max_ms: received.last().copied(),
+            p95_ms: percentile(&received, 95.0),
+            rtts_ms: rtts.iter().map(|rtt| rtt.map(millis)).collect(),
+        }
+    }
+}
+
+/// Convert a duration to milliseconds, with a microsecond precision
+fn millis(duration: Duration) -> f64 {
+    duration.as_micros() as f64 / 1000.0
+}
+
+/// Nearest-rank percentile of a list of sorted values
+fn percentile(sorted: &[f64], percent: f64) -> Option<f64> {
+    let index = ((sorted.len() as f64 * percent / 100.0).ceil() as usize).saturating_sub(1);
+    sorted.get(index).copied()
+}
+
+impl Output for PingOutput {
+    fn output(&self) -> Result<String> {
+        let mut output = formatdoc!(
+            "
+            --- {} ping statistics ---
+            {} messages sent, {} received, {:.1}% lost",
+            self.to,
+            self.sent,
+            self.received,
+            self.loss_percent
+        );
+        if let (Some(min), Some(avg), Some(max), Some(p95)) =
+            (self.min_ms, self.avg_ms, self.max_ms, self.p95_ms)
+        {
+            write!(
+                output,
+                "\nround-trip min/avg/max/p95 = {min:.3}/{avg:.3}/{max:.3}/{p95:.3} ms"
+            )?;
+        }
+        if let Some(setup) = self.setup_ms {
+            write!(output, "\nconnection setup = {setup:.3} ms")?;
+        }
+        Ok(output)
+    }
+}
+
+/// Result of the `ockam bench portal` command
+#[derive(Serialize)]
+pub struct BenchPortalOutput {
+    pub to: String,
+    pub mode: BenchTargetMode,
+    pub payload_size: usize,
+    pub duration_ms: f64,
+    /// True if the benchmark was stopped before the end of its duration
+    pub interrupted: bool,
+    /// Number of bytes which went through the portal, over all the connections
+    pub bytes: u64,
+    pub bytes_per_second: f64,
+    /// Jain's fairness index of the throughputs of the connections, from 1/n when a single
+    /// connection got all the traffic, to 1.0 when all the connections got the same share
+    pub fairness: Option<f64>,
+    /// Round-trip time statistics of the payloads, in milliseconds. Only measured in echo mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<BenchLatencyOutput>,
+    /// Share of the time spent waiting for the local socket send buffers to have some room.
+    /// A high value means that the portal does not take the data as fast as it is written
+    pub write_blocked_percent: f64,
+    /// True if the inlet sent the data as soon as it was read, without batching small reads
+    pub no_delay: bool,
+    /// Number of messages sent by the inlet to the outlet, over the connections still open
+    /// at the end of the benchmark
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub messages: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub messages_per_second: Option<f64>,
+    pub connections: Vec<BenchConnectionOutput>,
+}
+
+#[derive(Serialize)]
+pub struct BenchLatencyOutput {
+    pub min: f64,
+    pub p50: f64,
+    pub p95: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+#[derive(Serialize)]
+pub struct BenchConnectionOutput {
+    pub connection: usize,
+    pub bytes: u64,
+    pub bytes_per_second: f64,
+    pub write_blocked_percent: f64,
+    /// Error which stopped the connection before the end of the benchmark
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl BenchConnectionOutput {
+    pub fn new(
+        connection: usize,
+        bytes: u64,
+        write_blocked: Duration,
+        elapsed: Duration,
+        error: Option<String>,
+    ) -> Self {
+        Self {
+            connection,
+            bytes,
+            bytes_per_second: per_second(bytes as f64, elapsed),
+            write_blocked_percent: per_second(write_blocked.as_secs_f64() * 100.0, elapsed),
+            error,
+        }
+    }
+}
+
+impl BenchPortalOutput {
+    pub fn new(
+        to: impl Into<String>,
+        mode: BenchTargetMode,
+        payload_size: usize,
+        elapsed: Duration,
+        interrupted: bool,
+        connections: Vec<BenchConnectionOutput>,
+        rtts: &[Duration],
+    ) -> Self {
+        let bytes = connections.iter().map(|c| c.bytes).sum::<u64>();
+        let squares = connections
+            .iter()
+            .map(|c| (c.bytes as f64).powi(2))
+            .sum::<f64>();
+        let fairness =
+            (squares > 0.0).then(|| (bytes as f64).powi(2) / (connections.len() as f64 * squares));
+        let mut rtts: Vec<f64> = rtts.iter().map(|rtt| millis(*rtt)).collect();
+        rtts.sort_by(f64::total_cmp);
+        let latency_ms = match (rtts.first(), rtts.last()) {
+            (Some(min), Some(max)) => Some(BenchLatencyOutput {
+                min: *min,
+                p50: percentile(&rtts, 50.0).unwrap_or(*min),
+                p95: percentile(&rtts, 95.0).unwrap_or(*max),
+                p99: percentile(&rtts, 99.0).unwrap_or(*max),
+                max: *max,
+            }),
+            _ => None,
+        };
+        let write_blocked_percent = match connections.len() {
+            0 => 0.0,
+            n => {
+                connections
+                    .iter()
+                    .map(|c| c.write_blocked_percent)
+                    .sum::<f64>()
+                    / n as f64
+            }
+        };
+        Self {
+            to: to.into(),
+            mode,
+            payload_size,
+            duration_ms: millis(elapsed),
+            interrupted,
+            bytes,
+            bytes_per_second: per_second(bytes as f64, elapsed),
+            fairness,
+            latency_ms,
+            write_blocked_percent,
+            no_delay: false,
+            messages: None,
+            messages_per_second: None,
+            connections,
+        }
+    }
+
+    /// Add the number of messages sent by the inlet, and whether it batched small reads
+    pub fn with_messages(mut self, messages: Option<u64>, no_delay: bool) -> Self {
+        let elapsed = Duration::from_secs_f64(self.duration_ms / 1000.0);
+        self.no_delay = no_delay;
+        self.messages = messages;
+        self.messages_per_second = messages.map(|m| per_second(m as f64, elapsed));
+        self
+    }
+}
+
+/// Divide a quantity by a duration in seconds
+fn per_second(quantity: f64, elapsed: Duration) -> f64 {
+    match elapsed.as_secs_f64() {
+        secs if secs > 0.0 => quantity / secs,
+        _ => 0.0,
+    }
+}
+
+/// Format a throughput in MiB/s and in Mbit/s
+fn throughput(bytes_per_second: f64) -> String {
+    format!(
+        "{:.2} MiB/s ({:.1} Mbit/s)",
+        bytes_per_second / (1 << 20) as f64,
+        bytes_per_second * 8.0 / 1_000_000.0
+    )
+}
+
+impl Output for BenchPortalOutput {
+    fn output(&self) -> Result<String> {
+        let mut output = formatdoc!(
+            "
+            --- {} portal benchmark ---
+            {} connections, {} bytes payloads, {} mode, {:.1} s{}
+            throughput = {}",
+            self.to,
+            self.connections.len(),
+            self.payload_size,
+            self.mode,
+            self.duration_ms / 1000.0,
+            if self.interrupted {
+                " (interrupted)"
+            } else {
+                ""
+            },
+            throughput(self.bytes_per_second)
+        );
+        if let Some(fairness) = self.fairness {
+            write!(output, ", fairness = {fairness:.3}")?;
+        }
+        if let Some(latency) = &self.latency_ms {
+            write!(
+                output,
+                "\nround-trip min/p50/p95/p99/max = {:.3}/{:.3}/{:.3}/{:.3}/{:.3} ms",
+                latency.min, latency.p50, latency.p95, latency.p99, latency.max
+            )?;
+        }
+        if let (Some(messages), Some(messages_per_second)) =
+            (self.messages, self.messages_per_second)
+        {
+            write!(
+                output,
+                "\n{messages} messages sent by the inlet ({messages_per_second:.0} messages/s), {}",
+                if self.no_delay {
+                    "no delay"
+                } else {
+                    "small reads batched"
+                }
+            )?;
+        }
+        write!(
+            output,
+            "\nlocal socket buffers full {:.1}% of the time",
+            self.write_blocked_percent
+        )?;
+        for connection in &self.connections {
+            write!(
+                output,
+                "\n  connection {}: {}, buffers full {:.1}% of the time",
+                connection.connection,
+                throughput(connection.bytes_per_second),
+                connection.write_blocked_percent
+            )?;
+            if let Some(error) = &connection.error {
+                write!(output, ", stopped: {error}")?;
+            }
+        }
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use ockam_api::nodes::models::transport::{TransportMode, TransportType};
+    use ockam_api::nodes::models::workers::WorkerDetails;
+    use ockam_core::flow_control::FlowControlId;
+    use serde_json::Value;
+
+    use super::*;
+    use crate::node::models::secure_channel::ShowSecureChannelListener;
+    use crate::node::models::services::ShowServiceStatus;
+    use crate::node::models::transport::ShowTransportStatus;
+
+    fn identifier() -> Identifier {
+        Identifier::from_str("Ie92f183eb4c324804ef4d62962dea94cf095a265a1b2c3d4e5f6a6b5c4d3e2f1")
+            .unwrap()
+    }
+
+    fn field_names(value: impl Serialize) -> Vec<String> {
+        match serde_json::to_value(value).unwrap() {
+            Value::Object(map) => {
+                let mut names: Vec<String> = map.keys().cloned().collect();
+                names.sort();
+                names
+            }
+            other => panic!("expected an object, got {other}"),
+        }
+    }
+
+    fn assert_fields(value: impl Serialize, expected: &[&str]) {
+        let mut expected: Vec<String> = expected.iter().map(|s| s.to_string()).collect();
+        expected.sort();
+        assert_eq!(field_names(value), expected);
+    }
+
+    #[test]
+    fn credential_output_fields() {
+        let credential = CredentialOutput {
+            credential: "81a2".to_string(),
+            scope: "project-member".to_string(),
+            subject: identifier(),
+            issuer: identifier(),
+            created_at: TimestampInSeconds(1),
+            expires_at: TimestampInSeconds(2),
+            is_verified: true,
+            schema: 1,
+            attributes: BTreeMap::from([("role".to_string(), "member".to_string())]),
+        };
+        assert_fields(
+            &credential,
+            &[
+                "credential",
+                "scope",
+                "subject",
+                "issuer",
+                "created_at",
+                "expires_at",
+                "is_verified",
+                "schema",
+                "attributes",
+            ],
+        );
+        let json = serde_json::to_value(&credential).unwrap();
+        assert_eq!(json["expires_at"], 2);
+        assert_eq!(json["attributes"]["role"], "member");
+    }
+
+    #[test]
+    fn relay_output_fields() {
+        let relay = RelayOutput {
+            alias: "default".to_string(),
+            destination: MultiAddr::from_str("/node/n1").unwrap(),
+            connection_status: ConnectionStatus::Up,
+            relay_route: None,
+            remote_address: Some("forward_to_blue".to_string()),
+            remote_address_ma: None,
+            worker_address: None,
+            last_heartbeat_at: Some(10),
+            round_trip_time_ms: None,
+            failed_heartbeats: 0,
+            reconnection_attempts: 0,
+            last_failure: None,
+        };
+        assert_fields(
+            &relay,
+            &[
+                "alias",
+                "destination",
+                "connection_status",
+                "relay_route",
+                "remote_address",
+                "remote_address_ma",
+                "worker_address",
+                "last_heartbeat_at",
+                "round_trip_time_ms",
+                "failed_heartbeats",
+                "reconnection_attempts",
+                "last_failure",
+            ],
+        );
+        let json = serde_json::to_value(&relay).unwrap();
+        assert_eq!(json["connection_status"], "Up");
+        assert_eq!(json["destination"], "/node/n1");
+        assert_eq!(json["remote_address"], "forward_to_blue");
+    }
+
+    #[test]
+    fn secure_channel_list_output_fields() {
+        let channel = SecureChannelListOutput {
+            from: "n1".to_string(),
+            to: "/service/api".to_string(),
+            at: "/service/abc".to_string(),
+            direction: "initiated".to_string(),
+            their_identifier: identifier().to_string(),
+            created_at: TimestampInSeconds(1),
+            age_seconds: 10,
+            messages_sent: 2,
+            bytes_sent: 20,
+            messages_received: 1,
+            bytes_received: 10,
+            last_activity_at: Some(TimestampInSeconds(5)),
+            used_by: vec!["tcp-inlet db".to_string()],
+        };
+        assert_fields(
+            &channel,
+            &[
+                "from",
+                "to",
+                "at",
+                "direction",
+                "their_identifier",
+                "created_at",
+                "age_seconds",
+                "messages_sent",
+                "bytes_sent",
+                "messages_received",
+                "bytes_received",
+                "last_activity_at",
+                "used_by",
+            ],
+        );
+    }
+
+    #[test]
+    fn member_output_fields() {
+        let member = MemberOutput {
+            identifier: identifier(),
+            attributes: BTreeMap::new(),
+            added_at: TimestampInSeconds(1),
+            expires_at: None,
+            attested_by: Some(identifier()),
+        };
+        assert_fields(
+            &member,
+            &[
+                "identifier",
+                "attributes",
+                "added_at",
+                "expires_at",
+                "attested_by",
+            ],
+        );
+    }
+
+    #[test]
+    fn worker_and_addon_output_fields() {
+        assert_fields(WorkerOutput::from(WorkerStatus::new("api")), &["address"]);
+        let worker = WorkerStatus {
+            addr: "decryptor".to_string(),
+            details: Some(WorkerDetails {
+                kind: "worker".to_string(),
+                aliases: vec!["decryptor.api".to_string()],
+                routed_messages: 3,
+                pending_messages: 0,
+                terminal: false,
+                internal: Some("secure channel decryptor".to_string()),
+            }),
+        };
+        assert_fields(
+            WorkerOutput::from(worker),
+            &[
+                "address",
+                "kind",
+                "aliases",
+                "routed_messages",
+                "pending_messages",
+                "terminal",
+                "internal",
+                "owner",
+            ],
+        );
+        let addon = AddonOutput {
+            id: "okta".to_string(),
+            description: "Okta".to_string(),
+            enabled: false,
+        };
+        assert_fields(addon, &["id", "description", "enabled"]);
+    }
+
+    #[test]
+    fn okta_addon_output_fields() {
+        let config = OktaConfig::new(
+            ockam_api::minicbor_url::Url::parse("http://okta.example.com").unwrap(),
+            "",
+            "",
+            vec!["email".to_string()],
+        );
+        let okta = OktaAddonOutput::from(&config);
+        assert_eq!(okta.certificates, 0);
+        // the scheme, the client id and the certificate are reported
+        assert_eq!(okta.problems.len(), 3);
+        assert_fields(
+            okta,
+            &[
+                "tenant_base_url",
+                "client_id",
+                "attributes",
+                "certificates",
+                "problems",
+            ],
+        );
+
+        let config = InfluxDBTokenLeaseManagerConfig::new(
+            "https://influxdb.example.com",
+            "token",
+            "org",
+            r#"[{"action":"read"}]"#,
+            3600,
+            None,
+            None,
+        );
+        assert_fields(
+            InfluxDBAddonOutput::from(&config),
+            &["endpoint", "org_id", "permissions", "max_ttl_secs"],
+        );
+    }
+
+    #[test]
+    fn kafka_service_output_fields() {
+        let service = KafkaServiceOutput::new(
+            "KafkaProducer",
+            "kafka_producer",
+            SocketAddr::from_str("127.0.0.1:9092").unwrap(),
+        )
+        .with_brokers_port_range(PortRange::new(9093, 9192).unwrap());
+        assert_fields(
+            &service,
+            &[
+                "service_type",
+                "address",
+                "bind_address",
+                "bootstrap_server",
+                "brokers_port_range",
+            ],
+        );
+        let json = serde_json::to_value(&service).unwrap();
+        assert_eq!(json["bootstrap_server"], "127.0.0.1:9092");
+        assert_eq!(json["bind_address"], Value::Null);
+
+        // the declared broker authentication is only displayed when it is set
+        let service = service.with_broker_auth(Some(BrokerAuth::SaslScram));
+        let json = serde_json::to_value(&service).unwrap();
+        assert_eq!(json["broker_auth"], "sasl-scram");
+    }
+
+    #[test]
+    fn node_models_fields() {
+        let transport = ShowTransportStatus {
+            tt: TransportType::Tcp,
+            mode: TransportMode::Listen,
+            socket: "127.0.0.1:4000".to_string(),
+            worker: "0#abc".to_string(),
+            flow_control: FlowControlId::from("fc".to_string()),
+            ip_family: None,
+        };
+        assert_fields(
+            transport,
+            &["type", "mode", "socket", "worker", "flow_control"],
+        );
+        let listener = ShowSecureChannelListener {
+            address: Some(MultiAddr::from_str("/service/api").unwrap()),
+            flow_control: FlowControlId::from("fc".to_string()),
+            authorized_identifiers: None,
+        };
+        assert_fields(
+            listener,
+            &["address", "flow_control", "authorized_identifiers"],
+        );
+        let service = ShowServiceStatus {
+            address: Some(MultiAddr::from_str("/service/api").unwrap()),
+            service_type: "Echoer".to_string(),
+            broker_auth: None,
+        };
+        assert_fields(service, &["address", "type"]);
+    }
+
+    #[test]
+    fn trace_hop_output_fields() {
+        let hop = TraceHopOutput {
+            hop: 1,
+            address: "/node/n1".to_string(),
+            status: TraceHopStatus::Answered,
+            node_name: Some("n1".to_string()),
+            identifier: Some(identifier().to_string()),
+            rtt_ms: Some(3),
+            timestamp_ms: Some(1_700_000_000_000),
+            error: None,
+        };
+        assert_fields(
+            hop,
+            &[
+                "hop",
+                "address",
+                "status",
+                "node_name",
+                "identifier",
+                "rtt_ms",
+                "timestamp_ms",
+            ],
+        );
+        let hop = TraceHopOutput {
+            hop: 2,
+            address: "/service/forward_to_n2".to_string(),
+            status: TraceHopStatus::NoAnswer,
+            node_name: None,
+            identifier: None,
+            rtt_ms: None,
+            timestamp_ms: None,
+            error: Some("timeout".to_string()),
+        };
+        let json = serde_json::to_value(&hop).unwrap();
+        assert_eq!(json["status"], "no_answer");
+        assert_fields(hop, &["hop", "address", "status", "error"]);
+    }
+
+    #[test]
+    fn ping_output_statistics() {
+        let rtts: Vec<Option<Duration>> = (1..=19)
+            .map(|ms| Some(Duration::from_millis(ms)))
+            .chain([None])
+            .collect();
+        let ping = PingOutput::new("/node/n1", &rtts, None);
+        assert_eq!(ping.sent, 20);
+        assert_eq!(ping.received, 19);
+        assert_eq!(ping.loss_percent, 5.0);
+        assert_eq!(ping.min_ms, Some(1.0));
+        assert_eq!(ping.avg_ms, Some(10.0));
+        assert_eq!(ping.max_ms, Some(19.0));
+        assert_eq!(ping.p95_ms, Some(19.0));
+        assert_eq!(ping.rtts_ms[19], None);
+        assert_fields(
+            &ping,
+            &[
+                "to",
+                "sent",
+                "received",
+                "loss_percent",
+                "min_ms",
+                "avg_ms",
+                "max_ms",
+                "p95_ms",
+                "rtts_ms",
+            ],
+        );
+
+        // all the messages are lost
+        let ping = PingOutput::new("/node/n1", &[None, None], Some(Duration::from_micros(1500)));
+        assert_eq!(ping.loss_percent, 100.0);
+        assert_eq!(ping.p95_ms, None);
+        assert_eq!(ping.setup_ms, Some(1.5));
+        let json = serde_json::to_value(&ping).unwrap();
+        assert_eq!(json["avg_ms"], Value::Null);
+    }
+
+    #[test]
+    fn bench_portal_output_statistics() {
+        let elapsed = Duration::from_secs(2);
+        let connections = vec![
+            BenchConnectionOutput::new(1, 4000, Duration::from_secs(1), elapsed, None),
+            BenchConnectionOutput::new(
+                2,
+                0,
+                Duration::ZERO,
+                elapsed,
+                Some("connection reset".to_string()),
+            ),
+        ];
+        let rtts: Vec<Duration> = (1..=100).map(Duration::from_millis).collect();
+        let bench = BenchPortalOutput::new(
+            "/node/n1/service/bench",
+            BenchTargetMode::Echo,
+            1024,
+            elapsed,
+            false,
+            connections,
+            &rtts,
+        )
+        .with_messages(Some(500), false);
+        assert_eq!(bench.bytes, 4000);
+        assert_eq!(bench.messages_per_second, Some(250.0));
+        assert_eq!(bench.bytes_per_second, 2000.0);
+        // a single connection got all the traffic
+        assert_eq!(bench.fairness, Some(0.5));
+        assert_eq!(bench.write_blocked_percent, 25.0);
+        let latency = bench.latency_ms.as_ref().unwrap();
+        assert_eq!(
+            (
+                latency.min,
+                latency.p50,
+                latency.p95,
+                latency.p99,
+                latency.max
+            ),
+            (1.0, 50.0, 95.0, 99.0, 100.0)
+        );
+        let json = serde_json::to_value(&bench).unwrap();
+        assert_eq!(json["mode"], "echo");
+        assert_fields(
+            &json["connections"][1],
+            &[
+                "connection",
+                "bytes",
+                "bytes_per_second",
+                "write_blocked_percent",
+                "error",
+            ],
+        );
+        assert_fields(
+            &bench,
+            &[
+                "to",
+                "mode",
+                "payload_size",
+                "duration_ms",
+                "interrupted",
+                "bytes",
+                "bytes_per_second",
+                "fairness",
+                "latency_ms",
+                "write_blocked_percent",
+                "no_delay",
+                "messages",
+                "messages_per_second",
+                "connections",
+            ],
+        );
+
+        // the latency is not measured in discard mode
+        let bench = BenchPortalOutput::new(
+            "/node/n1/service/bench",
+            BenchTargetMode::Discard,
+            1024,
+            elapsed,
+            true,
+            vec![],
+            &[],
+        );
+        assert_eq!(bench.fairness, None);
+        assert!(bench.latency_ms.is_none());
+    }
+}
@@ -16,7 +16,7 @@ use ockam::identity::{Credential, Identifier, Identity, TimestampInSeconds};
 use ockam_api::cli_state::vaults::NamedVault;
 use ockam_api::cloud::project::Project;
 use ockam_api::cloud::space::Space;
-use ockam_api::nodes::models::portal::{InletStatus, OutletStatus};
+use ockam_api::nodes::models::portal::{InletStatus, OutletStatus, PortalConnection};
 use ockam_api::nodes::models::secure_channel::{
     CreateSecureChannelResponse, ShowSecureChannelResponse,
 };
@@ -81,6 +81,20 @@ impl Output for &str {
     }
 }
 
+/// Fields of a resource selected with `--field`, displayed in a list as `<name> <value>` lines
+pub struct SelectedFields(pub Vec<(&'static str, String)>);
+
+impl Output for SelectedFields {
+    fn output(&self) -> Result<String> {
+        let lines: Vec<String> = self
+            .0
+            .iter()
+            .map(|(name, value)| format!("{name} {}", color_primary(value)))
+            .collect();
+        Ok(lines.join("\n"))
+    }
+}
+
 impl Output for Space {
     fn output(&self) -> Result<String> {
         let mut w = String::new();
@@ -187,7 +201,7 @@ impl Output for ShowSecureChannelResponse {
     fn output(&self) -> Result<String> {
         let s = match &self.channel {
             Some(addr) => {
-                format!(
+                let mut s = format!(
                     "\n  Secure Channel:\n{} {}\n{} {}\n{} {}",
                     "  •         At: ".light_magenta(),
                     route_to_multiaddr(&route![addr.to_string()])
@@ -204,7 +218,52 @@ impl Output for ShowSecureChannelResponse {
                         .map(|id| id.clone().light_yellow().to_string())
                         .collect::<Vec<String>>()
                         .join("\n\t")
-                )
+                );
+                if let Some(their_identifier) = &self.their_identifier {
+                    writeln!(s)?;
+                    writeln!(
+                        s,
+                        "{} {}",
+                        "  •       Peer: ".light_magenta(),
+                        their_identifier.clone().light_yellow()
+                    )?;
+                    writeln!(
+                        s,
+                        "{} {}",
+                        "  • Credential: ".light_magenta(),
+                        if self.their_credential_verified.unwrap_or(false) {
+                            "verified"
+                        } else {
+                            "none"
+                        }
+                        .light_yellow()
+                    )?;
+                    if let Some(created_at) = self.created_at {
+                        writeln!(
+                            s,
+                            "{} {}",
+                            "  •    Created: ".light_magenta(),
+                            human_readable_time(TimestampInSeconds(created_at)).light_yellow()
+                        )?;
+                    }
+                    if let Some(rekeys) = self.rekeys {
+                        writeln!(
+                            s,
+                            "{} {}",
+                            "  •     Rekeys: ".light_magenta(),
+                            rekeys.to_string().light_yellow()
+                        )?;
+                    }
+                    if let Some(decryptor_address) = &self.decryptor_address {
+                        write!(
+                            s,
+                            "{} {}",
+                            "  •  Decryptor: ".light_magenta(),
+                            decryptor_address.clone().light_yellow()
+                        )?;
+                    }
+                }
+                s
             }
             None => format!("{}", "Channel not found".red()),
         };
@@ -215,24 +274,39 @@ impl Output for ShowSecureChannelResponse {
 
 impl Output for OutletStatus {
     fn output(&self) -> Result<String> {
-        let output = format!(
+        let target = match &self.unix_socket_path {
+            Some(path) => format!("Unix Socket:    {path}"),
+            None => format!("TCP Address:    {}", self.to()),
+        };
+        let mut output = format!(
             r#"
 Outlet:
-    TCP Address:    {}
+    {target}
     Worker Address: {}
 "#,
-            self.socket_addr,
             self.worker_address()?
         );
+        if let Some(identity) = &self.identity {
+            writeln!(output, "    Identity:       {identity}")?;
+        }
+        if self.proxy_protocol {
+            writeln!(
+                output,
+                "    PROXY Protocol: v2 header sent to the TCP server"
+            )?;
+        }
 
         Ok(output)
     }
 
     fn list_output(&self) -> Result<String> {
+        let target = match &self.unix_socket_path {
+            Some(path) => format!("Unix socket {}", color_primary(path)),
+            None => format!("TCP server {}", color_primary(self.to())),
+        };
         let output = format!(
-            r#"From address {} to TCP server {}"#,
+            r#"From address {} to {target}"#,
             color_primary(self.worker_address()?.to_string()),
-            color_primary(self.socket_addr.to_string()),
         );
 
         Ok(output)
@@ -255,12 +329,12 @@ impl Output for InletStatus {
             .map(|addr| addr.to_string())
             .unwrap_or("N/A".to_string());
 
-        let output = format!(
+        let mut output = format!(
             r#"
 Inlet
     Alias: {alias}
     Status: {status}
-    TCP Address: {bind_addr}
+    {bind_addr}
     Outlet Address: {outlet_route}
     Outlet Destination: {outlet_addr}
             "#,
@@ -272,13 +346,58 @@ Inlet
                 .status
                 .to_string()
                 .color(OckamColor::PrimaryResource.color()),
-            bind_addr = self
-                .bind_addr
-                .to_string()
-                .color(OckamColor::PrimaryResource.color()),
+            bind_addr = match self.unix_socket_path() {
+                Some(path) => format!(
+                    "Unix Socket: {}",
+                    path.color(OckamColor::PrimaryResource.color())
+                ),
+                None => format!(
+                    "TCP Address: {}",
+                    self.bind_addr.color(OckamColor::PrimaryResource.color())
+                ),
+            },
             outlet_route = outlet.color(OckamColor::PrimaryResource.color()),
             outlet_addr = self.outlet_addr,
         );
+        if let Some(load_balancing) = self.load_balancing {
+            output.truncate(output.trim_end().len());
+            writeln!(output)?;
+            writeln!(output, "    Load Balancing: {load_balancing}")?;
+            for route in &self.outlet_routes {
+                writeln!(output, "        {route}")?;
+            }
+        }
+        if let Some(connection_limits) = &self.connection_limits {
+            output.truncate(output.trim_end().len());
+            writeln!(output)?;
+            writeln!(output, "    Connection Limits: {connection_limits}")?;
+        }
+        if self.no_delay {
+            output.truncate(output.trim_end().len());
+            writeln!(output)?;
+            writeln!(
+                output,
+                "    No Delay: the data is sent as soon as it is read"
+            )?;
+        }
+        if self.expect_proxy_protocol {
+            output.truncate(output.trim_end().len());
+            writeln!(output)?;
+            writeln!(
+                output,
+                "    PROXY Protocol: a v2 header is expected from the TCP clients"
+            )?;
+        }
+        if let Some(liveness) = &self.liveness {
+            output.truncate(output.trim_end().len());
+            writeln!(output)?;
+            writeln!(output, "    Liveness: {liveness}")?;
+        }
+        if let Some(identity) = &self.identity {
+            output.truncate(output.trim_end().len());
+            writeln!(output)?;
+            writeln!(output, "    Identity: {}", color_primary(identity))?;
+        }
 
         Ok(output)
     }
@@ -304,6 +423,40 @@ From {} to {}"#,
     }
 }
 
+impl Output for PortalConnection {
+    fn output(&self) -> Result<String> {
+        let mut output = String::new();
+        writeln!(output, "Connection {}", color_primary(&self.id))?;
+        writeln!(
+            output,
+            "From {} since {}",
+            color_primary(self.source_addr.to_string()),
+            human_readable_time(TimestampInSeconds(self.started_at))
+        )?;
+        writeln!(
+            output,
+            "Sent {} bytes, received {} bytes, forwarded in {} messages",
+            self.bytes_sent, self.bytes_received, self.payload_messages
+        )?;
+        write!(
+            output,
+            "Remote identifier: {}",
+            self.remote_identifier
+                .as_ref()
+                .map(|i| color_primary(i.to_string()).to_string())
+                .unwrap_or("N/A".to_string())
+        )?;
+        if let Some(attributes) = &self.remote_attributes {
+            let attributes = attributes
+                .iter()
+                .map(|(key, value)| format!("{key}={value}"))
+                .collect::<Vec<_>>();
+            write!(output, "\nRemote attributes: {}", attributes.join(", "))?;
+        }
+        Ok(output)
+    }
+}
+
 impl Output for NamedVault {
     fn output(&self) -> Result<String> {
         let mut output = String::new();
@@ -320,7 +473,7 @@ impl Output for NamedVault {
     }
 }
 
-fn human_readable_time(time: TimestampInSeconds) -> String {
+pub(crate) fn human_readable_time(time: TimestampInSeconds) -> String {
     use time::format_description::well_known::iso8601::*;
     use time::Error::Format;
     use time::OffsetDateTime;
@@ -51,7 +51,7 @@ fn paginate_with(pager_binary_path: PathBuf, help: clap::Error) -> miette::Resul
     }
     let _ = pager_process.wait();
     let code = if help.use_stderr() {
-        exitcode::USAGE
+        exitcode::INVALID_USAGE
     } else {
         exitcode::OK
     };
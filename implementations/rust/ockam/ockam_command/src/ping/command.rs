@@ -0,0 +1,219 @@
+use core::time::Duration;
+use std::sync::Arc;
+use std::time::Instant;
+
+use async_trait::async_trait;
+use clap::Args;
+use miette::{miette, Context as _, IntoDiagnostic};
+use tracing::info;
+
+use ockam::{Context, MessageSendReceiveOptions};
+use ockam_api::nodes::service::default_address::DefaultAddress;
+use ockam_core::AsyncTryClone;
+use ockam_multiaddr::proto::Service;
+use ockam_multiaddr::MultiAddr;
+
+use crate::output::models::PingOutput;
+use crate::output::Output;
+use crate::project::util::{
+    clean_projects_multiaddr, get_projects_secure_channels_from_config_lookup,
+};
+use crate::util::api::{IdentityOpts, TrustOpts};
+use crate::util::clean_nodes_multiaddr;
+use crate::util::duration::duration_parser;
+use crate::{docs, fmt_log, fmt_warn, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/after_long_help.txt");
+
+/// Measure the round-trip time of the messages sent over a route
+#[derive(Clone, Debug, Args)]
+#[command(
+arg_required_else_help = true,
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct PingCommand {
+    /// The route to the node to ping. The messages are sent to the echo service of that node
+    #[arg(long, value_name = "ROUTE")]
+    pub to: MultiAddr,
+
+    /// Number of messages to send
+    #[arg(long, short, value_name = "COUNT", default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+    pub count: u32,
+
+    /// Delay between two messages
+    #[arg(long, value_name = "INTERVAL", default_value = "1s", value_parser = duration_parser)]
+    pub interval: Duration,
+
+    /// Maximum time to wait for the reply to each message, and for the setup of the connection
+    #[arg(long, value_name = "TIMEOUT", default_value = "5s", value_parser = duration_parser)]
+    pub timeout: Duration,
+
+    /// Report the time spent setting up the connection of the route
+    #[arg(long)]
+    pub include_setup: bool,
+
+    #[command(flatten)]
+    identity_opts: IdentityOpts,
+
+    #[command(flatten)]
+    pub trust_opts: TrustOpts,
+}
+
+#[async_trait]
+impl Command for PingCommand {
+    const NAME: &'static str = "ping";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let to = echo_route(&self.to)?;
+        let (to, meta) = clean_nodes_multiaddr(&to, &opts.state)
+            .await
+            .context("Argument '--to' is invalid")?;
+
+        let identity_name = opts
+            .state
+            .get_identity_name_or_default(&self.identity_opts.identity)
+            .await?;
+        info!("starting an in memory node to ping a route");
+        let node_manager = self
+            .trust_opts
+            .in_memory_node_builder(&identity_name)
+            .build(ctx, &opts.state)
+            .await?;
+
+        // The connection is set up once and reused by all the messages,
+        // so that its cost is not included in their round-trip times
+        let started_at = Instant::now();
+        let projects_sc = get_projects_secure_channels_from_config_lookup(
+            &opts,
+            ctx,
+            &node_manager,
+            &meta,
+            Some(identity_name),
+            Some(self.timeout),
+        )
+        .await?;
+        let to = clean_projects_multiaddr(to, projects_sc)?;
+        let connection = node_manager
+            .make_connection(
+                Arc::new(ctx.async_try_clone().await.into_diagnostic()?),
+                &to,
+                node_manager.identifier(),
+                None,
+                Some(self.timeout),
+                None,
+            )
+            .await?;
+        let setup = started_at.elapsed();
+        let route = connection.route()?;
+        info!(%route, "pinging");
+        if self.include_setup {
+            opts.terminal.write_line(fmt_log!(
+                "Connection to {} set up in {} ms",
+                self.to,
+                setup.as_millis()
+            ))?;
+        }
+
+        let mut rtts = vec![];
+        for seq in 1..=self.count {
+            if seq > 1 {
+                tokio::time::sleep(self.interval).await;
+            }
+            let message = format!("ping {seq}").into_bytes();
+            let started_at = Instant::now();
+            let reply = ctx
+                .send_and_receive_extended::<Vec<u8>>(
+                    route.clone(),
+                    message.clone(),
+                    MessageSendReceiveOptions::new().with_timeout(self.timeout),
+                )
+                .await
+                .and_then(|reply| reply.into_body());
+            let rtt = match reply {
+                Ok(reply) if reply == message => {
+                    let rtt = started_at.elapsed();
+                    opts.terminal.write_line(fmt_log!(
+                        "Reply from {}: seq={} time={:.3} ms",
+                        self.to,
+                        seq,
+                        rtt.as_micros() as f64 / 1000.0
+                    ))?;
+                    Some(rtt)
+                }
+                Ok(_) => {
+                    opts.terminal.write_line(fmt_warn!(
+                        "Unexpected reply from {}: seq={}",
+                        self.to,
+                        seq
+                    ))?;
+                    None
+                }
+                Err(e) => {
+                    info!(%e, seq, "no reply");
+                    opts.terminal.write_line(fmt_warn!(
+                        "No reply from {} within {:?}: seq={}",
+                        self.to,
+                        self.timeout,
+                        seq
+                    ))?;
+                    None
+                }
+            };
+            rtts.push(rtt);
+        }
+        connection.close(ctx, &node_manager).await?;
+
+        let output = PingOutput::new(
+            self.to.to_string(),
+            &rtts,
+            self.include_setup.then_some(setup),
+        );
+        opts.terminal
+            .stdout()
+            .plain(output.output()?)
+            .json(serde_json::to_string_pretty(&output).into_diagnostic()?)
+            .write_line()?;
+        if output.received == 0 {
+            Err(miette!("No reply was received from {}", self.to))?;
+        }
+        Ok(())
+    }
+}
+
+/// Return the route to the echo service of the node reached by a route
+fn echo_route(to: &MultiAddr) -> crate::Result<MultiAddr> {
+    let is_echo = to
+        .last()
+        .as_ref()
+        .and_then(|p| p.cast::<Service>())
+        .is_some_and(|s| &*s == DefaultAddress::ECHO_SERVICE);
+    let mut route = to.clone();
+    if !is_echo {
+        route.push_back(Service::new(DefaultAddress::ECHO_SERVICE))?;
+    }
+    Ok(route)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn ping_the_echo_service() {
+        let route = |s: &str| {
+            echo_route(&MultiAddr::from_str(s).unwrap())
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(route("/node/n1"), "/node/n1/service/echo");
+        assert_eq!(
+            route("/project/p/service/forward_to_n1/secure/api"),
+            "/project/p/service/forward_to_n1/secure/api/service/echo"
+        );
+        assert_eq!(route("/node/n1/service/echo"), "/node/n1/service/echo");
+    }
+}
@@ -0,0 +1,3 @@
+pub use command::PingCommand;
+
+mod command;
@@ -66,6 +66,11 @@ impl Command for CreateCommand {
                 "Policy created at node {}",
                 color_primary(node.node_name())
             ))
+            .json(serde_json::json!({
+                "resource": resource.to_string(),
+                "expression": self.expression.to_string(),
+                "node": node.node_name(),
+            }))
             .write_line()?;
         Ok(())
     }
@@ -126,6 +126,10 @@ impl DeleteCommandTui for DeleteTui {
                 "Policy for {resource_kind} {} has been deleted",
                 color_primary(resource.to_string())
             ))
+            .json(serde_json::json!({
+                "resource": resource.to_string(),
+                "node": self.node.node_name(),
+            }))
             .write_line()?;
         Ok(())
     }
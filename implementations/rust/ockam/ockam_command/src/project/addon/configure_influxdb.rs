@@ -3,13 +3,15 @@ use std::path::PathBuf;
 use clap::builder::NonEmptyStringValueParser;
 use clap::Args;
 use colorful::Colorful;
-use miette::{miette, IntoDiagnostic};
+use miette::{miette, Context as _, IntoDiagnostic};
 
 use ockam::Context;
 use ockam_api::cloud::addon::Addons;
 use ockam_api::cloud::project::models::InfluxDBTokenLeaseManagerConfig;
+use ockam_api::minicbor_url::Url;
 use ockam_api::nodes::InMemoryNode;
 
+use crate::output::{InfluxDBAddonOutput, Output};
 use crate::project::addon::check_configuration_completion;
 use crate::util::async_cmd;
 use crate::{docs, fmt_ok, CommandGlobalOpts};
@@ -142,6 +144,23 @@ impl AddonConfigureInfluxdbSubcommand {
             }
         };
 
+        // Validate the configuration before submitting it
+        let endpoint = Url::parse(&self.endpoint_url)
+            .into_diagnostic()
+            .context("could not parse the InfluxDB endpoint url")?;
+        if !["http", "https"].contains(&endpoint.scheme()) || endpoint.host_str().is_none() {
+            return Err(miette!(
+                "The InfluxDB endpoint {endpoint} must be an http or https url"
+            ));
+        }
+        match serde_json::from_str(&perms) {
+            Ok(serde_json::Value::Array(_)) => {}
+            Ok(_) => return Err(miette!("The InfluxDB permissions must be a JSON array")),
+            Err(e) => {
+                return Err(miette!("The InfluxDB permissions are not valid JSON: {e}"));
+            }
+        }
+
         let config = InfluxDBTokenLeaseManagerConfig::new(
             self.endpoint_url.clone(),
             self.token.clone(),
@@ -155,6 +174,7 @@ impl AddonConfigureInfluxdbSubcommand {
         let node = InMemoryNode::start(ctx, &opts.state).await?;
         let controller = node.create_controller().await?;
 
+        let output = InfluxDBAddonOutput::from(&config);
         let response = controller
             .configure_influxdb_addon(ctx, &project_id, config)
             .await?;
@@ -163,6 +183,11 @@ impl AddonConfigureInfluxdbSubcommand {
 
         opts.terminal
             .write_line(&fmt_ok!("InfluxDB addon configured successfully"))?;
+        opts.terminal
+            .stdout()
+            .plain(output.output()?)
+            .json(serde_json::to_string_pretty(&output).into_diagnostic()?)
+            .write_line()?;
         Ok(())
     }
 }
@@ -17,13 +17,15 @@ use ockam_api::enroll::okta_oidc_provider::OktaOidcProvider;
 use ockam_api::minicbor_url::Url;
 use ockam_api::nodes::InMemoryNode;
 
+use crate::output::{OktaAddonOutput, Output};
 use crate::project::addon::check_configuration_completion;
+use crate::project::util::{decode_pem_certificates, validate_okta_tenant_url};
 use crate::util::async_cmd;
-use crate::{docs, fmt_ok, CommandGlobalOpts, Result};
+use crate::{docs, fmt_ok, fmt_warn, CommandGlobalOpts, Result};
 
-const LONG_ABOUT: &str = include_str!("./static/configure_influxdb/long_about.txt");
+const LONG_ABOUT: &str = include_str!("./static/configure_okta/long_about.txt");
 const PREVIEW_TAG: &str = include_str!("../../static/preview_tag.txt");
-const AFTER_LONG_HELP: &str = include_str!("./static/configure_influxdb/after_long_help.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/configure_okta/after_long_help.txt");
 
 /// Configure the Okta addon for a project
 #[derive(Clone, Debug, Args)]
@@ -52,9 +54,11 @@ pub struct AddonConfigureOktaSubcommand {
     )]
     tenant: String,
 
-    /// Okta Certificate. Use either this or --cert-path
+    /// Okta Certificate, or @<path> to read it from a file. Use either this or --cert-path.
+    /// The certificate chain of the tenant is retrieved if no certificate is given
     #[arg(
         long = "cert",
+        visible_alias = "certificate",
         group = "cert",
         value_name = "CERTIFICATE",
         value_parser(NonEmptyStringValueParser::new())
@@ -102,15 +106,20 @@ impl AddonConfigureOktaSubcommand {
         let base_url = Url::parse(self.tenant.as_str())
             .into_diagnostic()
             .context("could not parse tenant url")?;
+        validate_okta_tenant_url(&base_url)?;
         let domain = base_url
             .host_str()
             .ok_or(miette!("could not read domain from tenant url"))?;
 
         let certificate = match (&self.certificate, &self.certificate_path) {
-            (Some(c), _) => c.to_string(),
+            (Some(c), _) => match c.strip_prefix('@') {
+                Some(path) => std::fs::read_to_string(path).into_diagnostic()?,
+                None => c.to_string(),
+            },
             (_, Some(p)) => std::fs::read_to_string(p).into_diagnostic()?,
             _ => query_certificate_chain(domain)?,
         };
+        decode_pem_certificates(&certificate)?;
 
         let okta_config = OktaConfig::new(
             base_url,
@@ -130,11 +139,28 @@ impl AddonConfigureOktaSubcommand {
         let response = controller
             .configure_okta_addon(ctx, &project_id, okta_config)
             .await?;
-        check_configuration_completion(&opts, ctx, &node, &project_id, &response.operation_id)
-            .await?;
+        let project =
+            check_configuration_completion(&opts, ctx, &node, &project_id, &response.operation_id)
+                .await?;
 
         opts.terminal
             .write_line(&fmt_ok!("Okta addon configured successfully"))?;
+        match &project.model().okta_config {
+            Some(okta_config) => {
+                let output = OktaAddonOutput::from(okta_config);
+                opts.terminal
+                    .stdout()
+                    .plain(output.output()?)
+                    .json(serde_json::to_string_pretty(&output).into_diagnostic()?)
+                    .write_line()?;
+            }
+            None => {
+                opts.terminal.write_line(&fmt_warn!(
+                    "The Okta configuration is not yet part of the project {}",
+                    self.project_name
+                ))?;
+            }
+        }
 
         Ok(())
     }
@@ -1,10 +1,12 @@
 use clap::builder::NonEmptyStringValueParser;
 use clap::Args;
+use miette::IntoDiagnostic;
 
 use ockam::Context;
 use ockam_api::cloud::addon::Addons;
 use ockam_api::nodes::InMemoryNode;
 
+use crate::output::AddonOutput;
 use crate::util::async_cmd;
 use crate::CommandGlobalOpts;
 
@@ -51,7 +53,18 @@ impl AddonListSubcommand {
             &format!("Addons for project {project_name}"),
             &format!("No addons enabled for project {project_name}"),
         )?;
-        opts.terminal.stdout().plain(output).write_line()?;
+        let json = serde_json::to_string_pretty(
+            &addons
+                .into_iter()
+                .map(AddonOutput::from)
+                .collect::<Vec<_>>(),
+        )
+        .into_diagnostic()?;
+        opts.terminal
+            .stdout()
+            .plain(output)
+            .json(json)
+            .write_line()?;
         Ok(())
     }
 }
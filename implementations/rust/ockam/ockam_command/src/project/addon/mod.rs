@@ -3,7 +3,7 @@ use core::fmt::Write;
 use clap::{Args, Subcommand};
 
 use ockam_api::cloud::addon::Addon;
-use ockam_api::cloud::project::ProjectsOrchestratorApi;
+use ockam_api::cloud::project::{Project, ProjectsOrchestratorApi};
 use ockam_api::nodes::InMemoryNode;
 use ockam_node::Context;
 
@@ -117,16 +117,16 @@ impl Output for Addon {
     }
 }
 
+/// Wait for the addon to be configured and return the project, as stored after its refresh
 async fn check_configuration_completion(
     opts: &CommandGlobalOpts,
     ctx: &Context,
     node: &InMemoryNode,
     project_id: &str,
     operation_id: &str,
-) -> Result<()> {
+) -> Result<Project> {
     check_for_operation_completion(opts, ctx, node, operation_id, "the addon configuration")
         .await?;
     let project = node.get_project(ctx, project_id).await?;
-    let _ = check_project_readiness(opts, ctx, node, project).await?;
-    Ok(())
+    check_project_readiness(opts, ctx, node, project).await
 }
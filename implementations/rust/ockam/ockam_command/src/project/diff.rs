@@ -0,0 +1,258 @@
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+use colorful::Colorful;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Fields of a project which change over time, without any change to its configuration
+pub(crate) const DEFAULT_IGNORED_FIELDS: &[&str] = &[
+    "version",
+    "running",
+    "operation_id",
+    "project_change_history",
+    "user_roles.id",
+];
+
+/// Difference between the expected document and the actual one, at a given path.
+/// The path of an array item is the path of the array followed by `[]`
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub(crate) enum Difference {
+    Added {
+        path: String,
+        actual: Value,
+    },
+    Removed {
+        path: String,
+        expected: Value,
+    },
+    Changed {
+        path: String,
+        expected: Value,
+        actual: Value,
+    },
+}
+
+/// Remove the ignored fields and the null values of a document, and sort its arrays,
+/// so that the comparison of two documents doesn't depend on the order of their items.
+///
+/// An ignored field is a path of field names separated by dots, e.g. `user_roles.id`.
+/// The path of the fields of array items is the path of the array
+pub(crate) fn normalize(value: Value, ignored: &[String]) -> Value {
+    let ignored: Vec<Vec<&str>> = ignored.iter().map(|f| f.split('.').collect()).collect();
+    normalize_at(value, &ignored, &mut vec![])
+}
+
+fn normalize_at(value: Value, ignored: &[Vec<&str>], path: &mut Vec<String>) -> Value {
+    match value {
+        Value::Object(fields) => {
+            let mut fields: Vec<(String, Value)> = fields.into_iter().collect();
+            fields.sort_by(|(n1, _), (n2, _)| n1.cmp(n2));
+            let mut normalized = Map::new();
+            for (name, value) in fields {
+                path.push(name.clone());
+                if !value.is_null() && !ignored.iter().any(|f| *f == *path) {
+                    normalized.insert(name, normalize_at(value, ignored, path));
+                }
+                path.pop();
+            }
+            Value::Object(normalized)
+        }
+        Value::Array(items) => {
+            let mut items: Vec<Value> = items
+                .into_iter()
+                .map(|item| normalize_at(item, ignored, path))
+                .collect();
+            items.sort_by_key(|item| item.to_string());
+            Value::Array(items)
+        }
+        value => value,
+    }
+}
+
+/// Return the differences between two normalized documents.
+/// Arrays are compared as sets: their items are either added or removed
+pub(crate) fn diff(expected: &Value, actual: &Value) -> Vec<Difference> {
+    let mut differences = vec![];
+    diff_at(".", expected, actual, &mut differences);
+    differences
+}
+
+fn diff_at(path: &str, expected: &Value, actual: &Value, differences: &mut Vec<Difference>) {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            let names: BTreeSet<&String> = expected.keys().chain(actual.keys()).collect();
+            for name in names {
+                let path = if path == "." {
+                    name.to_string()
+                } else {
+                    format!("{path}.{name}")
+                };
+                match (expected.get(name), actual.get(name)) {
+                    (Some(expected), Some(actual)) => diff_at(&path, expected, actual, differences),
+                    (Some(expected), None) => differences.push(Difference::Removed {
+                        path,
+                        expected: expected.clone(),
+                    }),
+                    (None, Some(actual)) => differences.push(Difference::Added {
+                        path,
+                        actual: actual.clone(),
+                    }),
+                    (None, None) => {}
+                }
+            }
+        }
+        (Value::Array(expected), Value::Array(actual)) => {
+            let path = format!("{path}[]");
+            for item in expected.iter().filter(|item| !actual.contains(item)) {
+                differences.push(Difference::Removed {
+                    path: path.clone(),
+                    expected: item.clone(),
+                });
+            }
+            for item in actual.iter().filter(|item| !expected.contains(item)) {
+                differences.push(Difference::Added {
+                    path: path.clone(),
+                    actual: item.clone(),
+                });
+            }
+        }
+        (expected, actual) if expected != actual => differences.push(Difference::Changed {
+            path: path.to_string(),
+            expected: expected.clone(),
+            actual: actual.clone(),
+        }),
+        _ => {}
+    }
+}
+
+/// Display the differences, with the expected values in red and the actual values in green
+pub(crate) fn format_differences(differences: &[Difference]) -> String {
+    let mut output = String::new();
+    for difference in differences {
+        let (path, expected, actual) = match difference {
+            Difference::Added { path, actual } => (path, None, Some(actual)),
+            Difference::Removed { path, expected } => (path, Some(expected), None),
+            Difference::Changed {
+                path,
+                expected,
+                actual,
+            } => (path, Some(expected), Some(actual)),
+        };
+        if let Some(expected) = expected {
+            let _ = writeln!(output, "{}", format!("- {path}: {expected}").red());
+        }
+        if let Some(actual) = actual {
+            let _ = writeln!(output, "{}", format!("+ {path}: {actual}").green());
+        }
+    }
+    output.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn default_ignored_fields() -> Vec<String> {
+        DEFAULT_IGNORED_FIELDS
+            .iter()
+            .map(|f| f.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn identical_projects_have_no_differences() {
+        let expected = json!({
+            "name": "default",
+            "users": ["alice@example.com", "bob@example.com"],
+            "version": "1",
+            "okta_config": null,
+            "user_roles": [
+                {"email": "alice@example.com", "id": 1, "role": "admin", "scope": "project"},
+                {"email": "bob@example.com", "id": 2, "role": "enroller", "scope": "project"},
+            ],
+        });
+        // the arrays are in a different order, and the ignored fields have changed
+        let actual = json!({
+            "name": "default",
+            "users": ["bob@example.com", "alice@example.com"],
+            "version": "2",
+            "user_roles": [
+                {"email": "bob@example.com", "id": 12, "role": "enroller", "scope": "project"},
+                {"email": "alice@example.com", "id": 11, "role": "admin", "scope": "project"},
+            ],
+        });
+
+        let ignored = default_ignored_fields();
+        let differences = diff(&normalize(expected, &ignored), &normalize(actual, &ignored));
+        assert!(differences.is_empty(), "{differences:?}");
+    }
+
+    #[test]
+    fn drifted_projects_have_differences() {
+        let expected = json!({
+            "name": "default",
+            "authority_identity": "81a201",
+            "users": ["alice@example.com"],
+            "okta_config": {"client_id": "123"},
+        });
+        let actual = json!({
+            "name": "default",
+            "authority_identity": "81a202",
+            "users": ["alice@example.com", "mallory@example.com"],
+            "version": "2",
+        });
+
+        let ignored = default_ignored_fields();
+        let differences = diff(&normalize(expected, &ignored), &normalize(actual, &ignored));
+        assert_eq!(
+            differences,
+            vec![
+                Difference::Changed {
+                    path: "authority_identity".to_string(),
+                    expected: json!("81a201"),
+                    actual: json!("81a202"),
+                },
+                Difference::Removed {
+                    path: "okta_config".to_string(),
+                    expected: json!({"client_id": "123"}),
+                },
+                Difference::Added {
+                    path: "users[]".to_string(),
+                    actual: json!("mallory@example.com"),
+                },
+            ]
+        );
+
+        let output = strip_ansi_escapes::strip_str(format_differences(&differences));
+        assert_eq!(
+            output,
+            [
+                r#"- authority_identity: "81a201""#,
+                r#"+ authority_identity: "81a202""#,
+                r#"- okta_config: {"client_id":"123"}"#,
+                r#"+ users[]: "mallory@example.com""#,
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn the_ignored_fields_can_be_overridden() {
+        let expected = json!({"name": "default", "version": "1", "running": true});
+        let actual = json!({"name": "default", "version": "2", "running": false});
+
+        let ignored = vec!["running".to_string()];
+        let differences = diff(&normalize(expected, &ignored), &normalize(actual, &ignored));
+        assert_eq!(
+            differences,
+            vec![Difference::Changed {
+                path: "version".to_string(),
+                expected: json!("1"),
+                actual: json!("2"),
+            }]
+        );
+    }
+}
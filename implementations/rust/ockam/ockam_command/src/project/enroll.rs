@@ -1,25 +1,31 @@
 use async_trait::async_trait;
 use std::sync::Arc;
+use std::time::Duration;
 
 use clap::Args;
 use colorful::Colorful;
-use miette::miette;
 use miette::Context as _;
+use miette::{miette, IntoDiagnostic};
 
+use ockam::identity::models::CredentialAndPurposeKey;
 use ockam::Context;
 use ockam_api::cli_state::enrollments::EnrollmentTicket;
 use ockam_api::cloud::project::models::OktaAuth0;
-use ockam_api::cloud::project::Project;
+use ockam_api::cloud::project::{Project, ProjectsOrchestratorApi};
+use ockam_api::cloud::AuthorityNodeClient;
 use ockam_api::enroll::enrollment::{EnrollStatus, Enrollment};
 use ockam_api::enroll::oidc_service::OidcService;
 use ockam_api::enroll::okta_oidc_provider::OktaOidcProvider;
 use ockam_api::nodes::InMemoryNode;
 
 use crate::enroll::OidcServiceExt;
-use crate::output::{CredentialAndPurposeKeyDisplay, OutputFormat};
+use crate::output::{human_readable_time, CredentialAndPurposeKeyDisplay, OutputFormat};
 use crate::util::api::{IdentityOpts, RetryOpts, TrustOpts};
+use crate::util::duration::duration_parser;
 use crate::value_parsers::parse_enrollment_ticket;
-use crate::{color_primary, docs, fmt_log, fmt_ok, Command, CommandGlobalOpts, Error, Result};
+use crate::{
+    color_primary, docs, fmt_log, fmt_ok, fmt_warn, Command, CommandGlobalOpts, Error, Result,
+};
 
 const LONG_ABOUT: &str = include_str!("./static/enroll/long_about.txt");
 const AFTER_LONG_HELP: &str = include_str!("./static/enroll/after_long_help.txt");
@@ -46,6 +52,11 @@ pub struct EnrollCommand {
     #[arg(display_order = 900, long = "okta", group = "authentication_method")]
     pub okta: bool,
 
+    /// Time-to-live of the issued credential, for example 15m or 1h. By default, the time-to-live configured on the project authority is used.
+    /// A longer time-to-live than the maximum allowed by the authority is reduced to that maximum
+    #[arg(display_order = 900, long = "credential-ttl", value_name = "DURATION", value_parser = duration_parser)]
+    pub credential_ttl: Option<Duration>,
+
     #[command(flatten)]
     pub retry_opts: RetryOpts,
 }
@@ -72,61 +83,47 @@ impl Command for EnrollCommand {
         let project = self.store_project(&opts).await?;
 
         // Create secure channel to the project's authority node
-        let node = InMemoryNode::start_with_project_name(
-            ctx,
-            &opts.state,
-            Some(project.name().to_string()),
-        )
-        .await?;
+        let node = InMemoryNode::builder()
+            .project_name(project.name())
+            .build(ctx, &opts.state)
+            .await?;
         let authority_node_client = node
             .create_authority_client(&project, Some(identity.name()))
             .await?;
 
-        // Enroll
-        if let Some(tkn) = self.enrollment_ticket.as_ref() {
-            match authority_node_client
-                .present_token(ctx, &tkn.one_time_code)
-                .await?
-            {
-                EnrollStatus::EnrolledSuccessfully => {}
-                EnrollStatus::AlreadyEnrolled => {
-                    opts.terminal
-                        .write_line(&fmt_ok!("Identity is already enrolled with the project"))?;
-                    return Ok(());
-                }
-                EnrollStatus::FailedNoStatus(msg) => {
-                    return Err(Error::Retry(miette!(
-                        "Failed to enroll identity with project. {msg}"
-                    )))
-                }
-                EnrollStatus::UnexpectedStatus(msg, status) => {
-                    return Err(Error::Retry(miette!(
-                        "Failed to enroll identity with project. {msg} {status}"
-                    )))
-                }
+        // Enroll and issue credential
+        let credential = if let Some(tkn) = self.enrollment_ticket.as_ref() {
+            enroll_with_ticket(ctx, &opts, &authority_node_client, tkn, self.credential_ttl).await?
+        } else {
+            if self.okta {
+                // The addon might have been configured since the project was stored locally,
+                // in which case the project is refreshed to get its Okta configuration
+                let okta_config = match project.model().okta_config.clone() {
+                    Some(okta_config) => okta_config,
+                    None => node
+                        .get_project(ctx, project.project_id())
+                        .await?
+                        .model()
+                        .okta_config
+                        .clone()
+                        .ok_or(miette!(
+                            "Okta addon not configured. Run 'ockam project addon configure okta' first"
+                        ))?,
+                };
+
+                // Get auth0 token
+                let okta_config: OktaAuth0 = okta_config.into();
+
+                let auth0 = OidcService::new(Arc::new(OktaOidcProvider::new(okta_config)));
+                let token = auth0.get_token_interactively(&opts).await?;
+                authority_node_client
+                    .enroll_with_oidc_token_okta(ctx, token)
+                    .await
+                    .map_err(Error::Retry)?;
             }
-        } else if self.okta {
-            // Get auth0 token
-            let okta_config: OktaAuth0 = project
-                .model()
-                .okta_config
-                .clone()
-                .ok_or(miette!("Okta addon not configured"))?
-                .into();
-
-            let auth0 = OidcService::new(Arc::new(OktaOidcProvider::new(okta_config)));
-            let token = auth0.get_token_interactively(&opts).await?;
-            authority_node_client
-                .enroll_with_oidc_token_okta(ctx, token)
-                .await
-                .map_err(Error::Retry)?;
+            issue_credential(ctx, &opts, &authority_node_client, self.credential_ttl).await?
         };
-
-        // Issue credential
-        let credential = authority_node_client
-            .issue_credential(ctx)
-            .await
-            .map_err(Error::Retry)?;
+        let credential_data = credential.get_credential_data().into_diagnostic()?;
 
         // Get the project name to display to the user.
         let project_name = {
@@ -151,6 +148,10 @@ impl Command for EnrollCommand {
             "{}.",
             "The attributes below are attested by the project's membership authority"
         ))?;
+        opts.terminal.write_line(&fmt_log!(
+            "The credential expires at {}.",
+            color_primary(human_readable_time(credential_data.expires_at))
+        ))?;
 
         // Output the credential and purpose keys to stdout.
         opts.terminal
@@ -190,6 +191,69 @@ impl EnrollCommand {
     }
 }
 
+/// Present the one-time code of an enrollment ticket to the project authority, then retrieve
+/// a credential for the enrolled identity.
+///
+/// When the identity is already a member of the project, the one-time code is not used
+/// and a credential is still retrieved, so that an interrupted enrollment can be resumed
+pub async fn enroll_with_ticket(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    authority_node_client: &AuthorityNodeClient,
+    ticket: &EnrollmentTicket,
+    credential_ttl: Option<Duration>,
+) -> Result<CredentialAndPurposeKey> {
+    match authority_node_client
+        .present_token(ctx, &ticket.one_time_code)
+        .await?
+    {
+        EnrollStatus::EnrolledSuccessfully => {}
+        EnrollStatus::AlreadyEnrolled => {
+            opts.terminal
+                .write_line(&fmt_log!("Identity is already enrolled with the project"))?;
+        }
+        EnrollStatus::FailedNoStatus(msg) => {
+            return Err(Error::Retry(miette!(
+                "Failed to enroll identity with project. {msg}"
+            )))
+        }
+        EnrollStatus::UnexpectedStatus(msg, status) => {
+            return Err(Error::Retry(miette!(
+                "Failed to enroll identity with project. {msg} {status}"
+            )))
+        }
+    }
+    issue_credential(ctx, opts, authority_node_client, credential_ttl).await
+}
+
+/// Retrieve a credential from the project authority, and warn the user if the authority
+/// reduced its time-to-live
+async fn issue_credential(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    authority_node_client: &AuthorityNodeClient,
+    credential_ttl: Option<Duration>,
+) -> Result<CredentialAndPurposeKey> {
+    let credential = authority_node_client
+        .issue_credential(ctx, credential_ttl)
+        .await
+        .map_err(|e| {
+            Error::Retry(e.wrap_err("Failed to retrieve a credential from the project authority"))
+        })?;
+    if let Some(credential_ttl) = credential_ttl {
+        let credential_data = credential.get_credential_data().into_diagnostic()?;
+        let ttl = *(credential_data.expires_at - credential_data.created_at);
+        if ttl < credential_ttl.as_secs() {
+            opts.terminal.write_line(&fmt_warn!(
+                "The requested credential time-to-live of {}s exceeds the maximum allowed by the project authority. It was reduced to {}s",
+                credential_ttl.as_secs(),
+                ttl
+            ))?;
+        }
+    }
+    Ok(credential)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,69 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+
+use ockam::Context;
+use ockam_api::cloud::project::ProjectsOrchestratorApi;
+use ockam_api::nodes::InMemoryNode;
+
+use crate::util::api::IdentityOpts;
+use crate::{color_primary, docs, fmt_ok, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/export/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/export/after_long_help.txt");
+
+/// Export a Project to a file, to import it on another machine
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP),
+)]
+pub struct ExportCommand {
+    /// Name of the project. Defaults to the default project
+    #[arg(display_order = 1001)]
+    pub name: Option<String>,
+
+    /// File where the project is written. The project is written to the standard output if no file is given
+    #[arg(long, value_name = "PATH")]
+    pub file: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub identity_opts: IdentityOpts,
+}
+
+#[async_trait]
+impl Command for ExportCommand {
+    const NAME: &'static str = "project export";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let node = InMemoryNode::start(ctx, &opts.state).await?;
+        let project = node.get_project_by_name_or_default(ctx, &self.name).await?;
+        let json = serde_json::to_string_pretty(project.model()).into_diagnostic()?;
+
+        match &self.file {
+            Some(file) => {
+                std::fs::write(file, &json).into_diagnostic()?;
+                opts.terminal
+                    .stdout()
+                    .plain(fmt_ok!(
+                        "The project {} was exported to {}",
+                        color_primary(project.name()),
+                        color_primary(file.display().to_string())
+                    ))
+                    .json(serde_json::json!({ "name": project.name(), "file": file }))
+                    .write_line()?;
+            }
+            None => {
+                opts.terminal
+                    .stdout()
+                    .plain(&json)
+                    .json(&json)
+                    .write_line()?;
+            }
+        }
+        Ok(())
+    }
+}
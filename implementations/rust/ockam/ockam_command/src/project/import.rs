@@ -1,25 +1,23 @@
-use clap::ArgGroup;
 use clap::Args;
 use colorful::Colorful;
-use miette::IntoDiagnostic;
+use miette::{Context as _, IntoDiagnostic};
 use ockam_api::cloud::project::models::ProjectModel;
 
 use crate::util::async_cmd;
-use crate::{docs, fmt_err, fmt_ok, CommandGlobalOpts};
+use crate::{color_primary, docs, fmt_ok, CommandGlobalOpts};
 
 const LONG_ABOUT: &str = include_str!("./static/import/long_about.txt");
 const AFTER_LONG_HELP: &str = include_str!("./static/import/after_long_help.txt");
 
-/// Import a Project
+/// Import a Project from a file
 #[derive(Clone, Debug, Args)]
 #[command(
 long_about = docs::about(LONG_ABOUT),
 after_long_help = docs::after_help(AFTER_LONG_HELP),
 )]
-#[clap(group(ArgGroup::new("detailed").required(false)))]
 pub struct ImportCommand {
-    /// Project file
-    #[arg(long, value_name = "PATH")]
+    /// Project file, as produced by `ockam project export`
+    #[arg(long, visible_alias = "file", value_name = "PATH")]
     pub project_file: String,
 }
 
@@ -36,29 +34,30 @@ impl ImportCommand {
 
     async fn async_run(&self, opts: CommandGlobalOpts) -> miette::Result<()> {
         let file_content = std::fs::read_to_string(&self.project_file).into_diagnostic()?;
-        let project: ProjectModel = serde_json::from_str(&file_content).into_diagnostic()?;
-        let result = opts
+        let project: ProjectModel = serde_json::from_str(&file_content)
+            .into_diagnostic()
+            .wrap_err(format!(
+                "The file {} does not contain a valid project",
+                self.project_file
+            ))?;
+        let project = opts
             .state
             .projects()
-            .import_and_store_project(project.clone())
-            .await;
+            .import_project(project)
+            .await
+            .wrap_err(format!(
+                "The project {} could not be imported",
+                self.project_file
+            ))?;
 
-        match result {
-            Ok(_) => opts
-                .terminal
-                .stdout()
-                .plain(fmt_ok!("Successfully imported project {}", &project.name))
-                .write_line()?,
-            Err(e) => opts
-                .terminal
-                .stdout()
-                .plain(fmt_err!(
-                    "The project {} could not be imported: {}",
-                    &self.project_file,
-                    e.to_string()
-                ))
-                .write_line()?,
-        };
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "Successfully imported project {}",
+                color_primary(project.name())
+            ))
+            .json(serde_json::json!({ "name": project.name(), "id": project.project_id() }))
+            .write_line()?;
         Ok(())
     }
 }
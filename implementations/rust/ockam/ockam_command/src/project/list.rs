@@ -1,14 +1,15 @@
-use clap::Args;
+use clap::{Args, ValueEnum};
 use miette::IntoDiagnostic;
 use opentelemetry::trace::FutureExt;
 use tokio::sync::Mutex;
 use tokio::try_join;
 
 use ockam::Context;
-use ockam_api::cloud::project::ProjectsOrchestratorApi;
+use ockam_api::cloud::project::{Project, ProjectsOrchestratorApi};
 use ockam_api::nodes::InMemoryNode;
 
-use crate::util::api::IdentityOpts;
+use crate::output::SelectedFields;
+use crate::util::api::{IdentityOpts, PaginationOpts, SortBy};
 use crate::util::async_cmd;
 use crate::{docs, CommandGlobalOpts};
 
@@ -26,6 +27,56 @@ after_long_help = docs::after_help(AFTER_LONG_HELP),
 pub struct ListCommand {
     #[command(flatten)]
     pub identity_opts: IdentityOpts,
+
+    #[command(flatten)]
+    pub pagination_opts: PaginationOpts,
+
+    /// Comma-separated list of the fields to display for each project, for example `name,id,route`
+    #[arg(
+        long = "field",
+        value_name = "FIELDS",
+        value_enum,
+        value_delimiter = ','
+    )]
+    pub fields: Vec<ProjectField>,
+}
+
+/// Field of a project which can be displayed by `ockam project list`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ProjectField {
+    Id,
+    Name,
+    Space,
+    Route,
+    Identifier,
+    Version,
+    Running,
+}
+
+impl ProjectField {
+    fn select(&self, project: &Project) -> (&'static str, String) {
+        match self {
+            ProjectField::Id => ("Id", project.project_id().to_string()),
+            ProjectField::Name => ("Project", project.name().to_string()),
+            ProjectField::Space => ("Space", project.space_name().to_string()),
+            ProjectField::Route => ("Route", project.model().access_route.clone()),
+            ProjectField::Identifier => (
+                "Identifier",
+                project
+                    .project_identifier()
+                    .map(|i| i.to_string())
+                    .unwrap_or("N/A".to_string()),
+            ),
+            ProjectField::Version => (
+                "Version",
+                project.model().version.clone().unwrap_or("N/A".to_string()),
+            ),
+            ProjectField::Running => (
+                "Running",
+                project.model().running.unwrap_or(false).to_string(),
+            ),
+        }
+    }
 }
 
 impl ListCommand {
@@ -54,12 +105,31 @@ impl ListCommand {
             .terminal
             .progress_output(&output_messages, &is_finished);
 
-        let (projects, _) = try_join!(get_projects, progress_output)?;
+        let (mut projects, _) = try_join!(get_projects, progress_output)?;
 
-        let plain = &opts
-            .terminal
-            .build_list(&projects, "Projects", "No projects found")?;
-        let json = serde_json::to_string_pretty(&projects).into_diagnostic()?;
+        if self.pagination_opts.sort == SortBy::Name {
+            projects.sort_by(|p1, p2| p1.name().cmp(p2.name()));
+        }
+        let pagination = self.pagination_opts.pagination();
+        let page = pagination.paginate(projects);
+
+        let plain = if self.fields.is_empty() {
+            opts.terminal
+                .build_list(&page.items, "Projects", "No projects found")?
+        } else {
+            let selected: Vec<SelectedFields> = page
+                .items
+                .iter()
+                .map(|p| SelectedFields(self.fields.iter().map(|f| f.select(p)).collect()))
+                .collect();
+            opts.terminal
+                .build_list(&selected, "Projects", "No projects found")?
+        };
+        let json = if pagination.is_paginated() {
+            serde_json::to_string_pretty(&page).into_diagnostic()?
+        } else {
+            serde_json::to_string_pretty(&page.items).into_diagnostic()?
+        };
 
         opts.terminal
             .stdout()
@@ -5,24 +5,32 @@ pub use addon::AddonCommand;
 pub use create::CreateCommand;
 pub use delete::DeleteCommand;
 pub use enroll::EnrollCommand;
+pub use export::ExportCommand;
 pub use import::ImportCommand;
 pub use info::InfoCommand;
 pub use list::ListCommand;
+pub use refresh::RefreshCommand;
 pub use show::ShowCommand;
 pub use ticket::TicketCommand;
+pub use ticket_template::TicketTemplateCommand;
 pub use version::VersionCommand;
 
+use crate::project_member::ProjectMemberCommand;
 use crate::CommandGlobalOpts;
 
 mod addon;
 mod create;
 mod delete;
+mod diff;
 pub(crate) mod enroll;
+mod export;
 mod import;
 mod info;
 mod list;
+mod refresh;
 mod show;
 mod ticket;
+mod ticket_template;
 pub mod util;
 mod version;
 
@@ -44,14 +52,18 @@ pub struct ProjectCommand {
 pub enum ProjectSubcommand {
     Create(CreateCommand),
     Import(ImportCommand),
+    Export(ExportCommand),
     Delete(DeleteCommand),
     List(ListCommand),
     Show(ShowCommand),
+    Refresh(RefreshCommand),
     Version(VersionCommand),
     Information(InfoCommand),
     Ticket(TicketCommand),
+    TicketTemplate(TicketTemplateCommand),
     Addon(AddonCommand),
     Enroll(Box<EnrollCommand>),
+    Member(ProjectMemberCommand),
 }
 
 impl ProjectCommand {
@@ -59,14 +71,18 @@ impl ProjectCommand {
         match self.subcommand {
             ProjectSubcommand::Create(c) => c.run(opts),
             ProjectSubcommand::Import(c) => c.run(opts),
+            ProjectSubcommand::Export(c) => c.run(opts),
             ProjectSubcommand::Delete(c) => c.run(opts),
             ProjectSubcommand::List(c) => c.run(opts),
             ProjectSubcommand::Show(c) => c.run(opts),
+            ProjectSubcommand::Refresh(c) => c.run(opts),
             ProjectSubcommand::Version(c) => c.run(opts),
             ProjectSubcommand::Ticket(c) => c.run(opts),
+            ProjectSubcommand::TicketTemplate(c) => c.run(opts),
             ProjectSubcommand::Information(c) => c.run(opts),
             ProjectSubcommand::Addon(c) => c.run(opts),
             ProjectSubcommand::Enroll(c) => c.run(opts),
+            ProjectSubcommand::Member(c) => c.run(opts),
         }
     }
 
@@ -76,12 +92,16 @@ impl ProjectCommand {
             ProjectSubcommand::Delete(c) => c.name(),
             ProjectSubcommand::List(c) => c.name(),
             ProjectSubcommand::Show(c) => c.name(),
+            ProjectSubcommand::Refresh(c) => c.name(),
             ProjectSubcommand::Import(c) => c.name(),
+            ProjectSubcommand::Export(c) => c.name(),
             ProjectSubcommand::Version(c) => c.name(),
             ProjectSubcommand::Information(c) => c.name(),
             ProjectSubcommand::Ticket(c) => c.name(),
+            ProjectSubcommand::TicketTemplate(c) => c.name(),
             ProjectSubcommand::Addon(c) => c.name(),
             ProjectSubcommand::Enroll(c) => c.name(),
+            ProjectSubcommand::Member(c) => c.name(),
         }
     }
 }
@@ -0,0 +1,63 @@
+use async_trait::async_trait;
+use clap::Args;
+use miette::{miette, IntoDiagnostic};
+
+use ockam::Context;
+use ockam_api::cloud::project::ProjectsOrchestratorApi;
+use ockam_api::nodes::InMemoryNode;
+
+use crate::output::{Output, ProjectConfigCompact};
+use crate::util::api::IdentityOpts;
+use crate::{color_primary, docs, fmt_ok, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/refresh/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/refresh/after_long_help.txt");
+
+/// Retrieve a Project from the Orchestrator and update its local copy
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP),
+)]
+pub struct RefreshCommand {
+    /// Name of the project. Defaults to the default project
+    #[arg(display_order = 1001)]
+    pub name: Option<String>,
+
+    #[command(flatten)]
+    pub identity_opts: IdentityOpts,
+}
+
+#[async_trait]
+impl Command for RefreshCommand {
+    const NAME: &'static str = "project refresh";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let projects = opts.state.projects();
+        let project = projects.get_project_by_name_or_default(&self.name).await?;
+        if projects.is_project_imported(project.project_id()).await? {
+            Err(miette!(
+                "The project {} was imported from a file and can't be refreshed. Import it again to update it",
+                project.name()
+            ))?
+        }
+
+        let node = InMemoryNode::start(ctx, &opts.state).await?;
+        let project = node.refresh_project(ctx, project.project_id()).await?;
+        let project_output = ProjectConfigCompact(project);
+
+        opts.terminal
+            .stdout()
+            .plain(format!(
+                "{}\n{}",
+                fmt_ok!(
+                    "The project {} was refreshed",
+                    color_primary(project_output.0.name())
+                ),
+                project_output.output()?
+            ))
+            .json(serde_json::to_string_pretty(&project_output).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
@@ -1,6 +1,10 @@
+use std::path::{Path, PathBuf};
+
 use async_trait::async_trait;
 use clap::Args;
+use miette::Context as _;
 use miette::IntoDiagnostic;
+use serde_json::Value;
 
 use crate::terminal::tui::ShowCommandTui;
 
@@ -10,9 +14,11 @@ use ockam_api::cloud::project::ProjectsOrchestratorApi;
 use ockam_api::nodes::InMemoryNode;
 
 use crate::output::{Output, ProjectConfigCompact};
-use crate::terminal::PluralTerm;
+use crate::project::diff::{diff, format_differences, normalize, DEFAULT_IGNORED_FIELDS};
+use crate::terminal::{color_primary, PluralTerm};
 use crate::util::api::{IdentityOpts, RetryOpts};
-use crate::{docs, Command, CommandGlobalOpts, Error};
+use crate::util::exitcode;
+use crate::{docs, fmt_ok, Command, CommandGlobalOpts, Error};
 use ockam_core::AsyncTryClone;
 
 use tracing::instrument;
@@ -33,6 +39,17 @@ pub struct ShowCommand {
     #[arg(display_order = 1001)]
     pub name: Option<String>,
 
+    /// Compare the live configuration of the project with the JSON file written by
+    /// `ockam project show --output json`. The command exits with the code 3 if they differ
+    #[arg(long, value_name = "EXPECTED_FILE")]
+    pub diff: Option<PathBuf>,
+
+    /// Field to exclude from the comparison, e.g. `user_roles.id`. This argument can be repeated.
+    /// It replaces the default ignored fields: version, running, operation_id,
+    /// project_change_history and user_roles.id
+    #[arg(long, value_name = "PATH", requires = "diff")]
+    pub ignore: Vec<String>,
+
     #[command(flatten)]
     pub identity_opts: IdentityOpts,
 
@@ -49,6 +66,9 @@ impl Command for ShowCommand {
     }
 
     async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        if let Some(expected_file) = &self.diff {
+            return Ok(self.show_diff(ctx, &opts, expected_file).await?);
+        }
         Ok(ShowTui::run(
             ctx.async_try_clone().await.into_diagnostic()?,
             opts,
@@ -58,6 +78,79 @@ impl Command for ShowCommand {
     }
 }
 
+impl ShowCommand {
+    /// Display the differences between the live project and the expected one,
+    /// and exit with the `DRIFTED` code if there are any
+    async fn show_diff(
+        &self,
+        ctx: &Context,
+        opts: &CommandGlobalOpts,
+        expected_file: &Path,
+    ) -> miette::Result<()> {
+        let expected = std::fs::read_to_string(expected_file)
+            .into_diagnostic()
+            .wrap_err(format!("Failed to read {}", expected_file.display()))?;
+        let expected: Value =
+            serde_json::from_str(&expected)
+                .into_diagnostic()
+                .wrap_err(format!(
+                    "{} is not a valid JSON file",
+                    expected_file.display()
+                ))?;
+
+        let project_name = match &self.name {
+            Some(name) => name.clone(),
+            None => opts
+                .state
+                .projects()
+                .get_default_project()
+                .await?
+                .name()
+                .to_string(),
+        };
+        let node = InMemoryNode::start(ctx, &opts.state).await?;
+        let project = node
+            .get_project_by_name(ctx, &project_name)
+            .await
+            .map_err(Error::Retry)?;
+        let actual = serde_json::to_value(&project).into_diagnostic()?;
+
+        let ignored: Vec<String> = if self.ignore.is_empty() {
+            DEFAULT_IGNORED_FIELDS
+                .iter()
+                .map(|f| f.to_string())
+                .collect()
+        } else {
+            self.ignore.clone()
+        };
+        let differences = diff(&normalize(expected, &ignored), &normalize(actual, &ignored));
+
+        let plain = if differences.is_empty() {
+            fmt_ok!(
+                "The project {} matches {}",
+                color_primary(&project_name),
+                color_primary(expected_file.display().to_string())
+            )
+        } else {
+            format_differences(&differences)
+        };
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .json(serde_json::json!({
+                "project": project_name,
+                "drifted": !differences.is_empty(),
+                "differences": differences,
+            }))
+            .write_line()?;
+
+        if !differences.is_empty() {
+            std::process::exit(exitcode::DRIFTED);
+        }
+        Ok(())
+    }
+}
+
 pub struct ShowTui {
     ctx: Context,
     opts: CommandGlobalOpts,
@@ -15,13 +15,9 @@ use ockam_api::cli_state::enrollments::EnrollmentTicket;
 use ockam_api::nodes::InMemoryNode;
 use ockam_multiaddr::MultiAddr;
 
-use crate::util::api::RetryOpts;
+use crate::util::api::{IdentityOpts, RetryOpts, TrustOpts};
 use crate::{docs, CommandGlobalOpts, Error, Result};
 use crate::{fmt_ok, Command};
-use crate::{
-    output::OutputFormat,
-    util::api::{IdentityOpts, TrustOpts},
-};
 use crate::{terminal::color_primary, util::duration::duration_parser};
 use tracing::debug;
 
@@ -71,6 +67,10 @@ pub struct TicketCommand {
     #[arg(long = "enroller")]
     enroller: bool,
 
+    /// Name of a ticket template, created with `ockam project ticket-template create`. The attributes, usage count and expiration of the template are used, unless they are given as arguments
+    #[arg(long = "template", value_name = "TEMPLATE_NAME")]
+    template: Option<String>,
+
     #[command(flatten)]
     retry_opts: RetryOpts,
 }
@@ -84,14 +84,10 @@ impl Command for TicketCommand {
     }
 
     async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> Result<()> {
-        if opts.global_args.output_format == OutputFormat::Json {
-            return Err(miette::miette!(
-                "This command only outputs a hex encoded string for 'ockam project enroll' to use. \
-                Please try running it again without '--output json'."
-            )
-            .into());
-        }
-
+        let template = match &self.template {
+            Some(name) => Some(opts.state.get_ticket_template(name).await?),
+            None => None,
+        };
         let project = crate::project_member::get_project(&opts.state, &self.to).await?;
 
         let node = InMemoryNode::start_with_project_name(
@@ -110,13 +106,25 @@ impl Command for TicketCommand {
             .create_authority_client(&project, Some(identity))
             .await?;
 
-        let attributes = self.attributes()?;
-        debug!(attributes = ?attributes, "Attributes passed");
+        // the template values are overridden by the ones passed as arguments
+        let (attributes, expires_in, usage_count) = match &template {
+            Some(template) => {
+                let merged =
+                    template.with_overrides(self.attributes()?, self.usage_count, self.expires_in);
+                (
+                    merged.attributes().clone(),
+                    merged.expires_in(),
+                    merged.usage_count(),
+                )
+            }
+            None => (self.attributes()?, self.expires_in, self.usage_count),
+        };
+        debug!(attributes = ?attributes, template = ?self.template, "Attributes passed");
 
         // Request an enrollment token that a future member can use to get a
         // credential.
         let token = authority_node_client
-            .create_token(ctx, attributes, self.expires_in, self.usage_count)
+            .create_token(ctx, attributes.clone(), expires_in, usage_count)
             .await
             .map_err(Error::Retry)?;
 
@@ -132,7 +140,12 @@ impl Command for TicketCommand {
         opts.terminal
             .clone()
             .stdout()
-            .machine(ticket_serialized)
+            .machine(&ticket_serialized)
+            .json(serde_json::json!({
+                "ticket": ticket_serialized,
+                "template": self.template,
+                "attributes": attributes,
+            }))
             .write_line()?;
 
         Ok(())
@@ -0,0 +1,69 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use clap::Args;
+
+use ockam::Context;
+use ockam_api::cli_state::TicketTemplate;
+
+use crate::project_member::create_member_attributes;
+use crate::util::duration::duration_parser;
+use crate::{color_primary, docs, fmt_ok, Command, CommandGlobalOpts, Result};
+
+const LONG_ABOUT: &str = include_str!("./static/create/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/create/after_long_help.txt");
+
+/// Create a template for enrollment tickets
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP),
+)]
+pub struct CreateCommand {
+    /// Name of the template
+    name: String,
+
+    /// Attributes in `key=value` format to be attached to the members enrolled with a ticket. You can specify this option multiple times for multiple attributes
+    #[arg(short, long = "attribute", value_name = "ATTRIBUTE")]
+    attributes: Vec<String>,
+
+    /// Duration for which the enrollment tickets are valid. Examples: 10000ms, 600s, 600, 10m, 1h, 1d. If you don't specify a length sigil, it is assumed to be seconds
+    #[arg(long = "expires-in", value_name = "DURATION", value_parser = duration_parser)]
+    expires_in: Option<Duration>,
+
+    /// Number of times the tickets can be used to enroll
+    #[arg(long = "usage-count", value_name = "USAGE_COUNT")]
+    usage_count: Option<u64>,
+
+    /// Name of the relay that the identities using the tickets will be allowed to create. This is shorthand for `--attribute ockam-relay=<name>`
+    #[arg(long = "relay", value_name = "ENROLLEE_ALLOWED_RELAY_NAME")]
+    allowed_relay_name: Option<String>,
+
+    /// Add the enroller role to the tickets. This is shorthand for `--attribute ockam-role=enroller`
+    #[arg(long = "enroller")]
+    enroller: bool,
+}
+
+#[async_trait]
+impl Command for CreateCommand {
+    const NAME: &'static str = "project ticket-template create";
+
+    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> Result<()> {
+        let attributes =
+            create_member_attributes(&self.attributes, &self.allowed_relay_name, self.enroller)?;
+        let template =
+            TicketTemplate::new(&self.name, attributes, self.usage_count, self.expires_in);
+        opts.state.create_ticket_template(&template).await?;
+
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "Created the ticket template {}",
+                color_primary(&self.name)
+            ))
+            .machine(&self.name)
+            .json(serde_json::json!(template))
+            .write_line()?;
+        Ok(())
+    }
+}
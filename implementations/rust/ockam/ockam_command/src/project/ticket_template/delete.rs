@@ -0,0 +1,55 @@
+use async_trait::async_trait;
+use clap::Args;
+
+use ockam::Context;
+
+use crate::{color_primary, docs, fmt_ok, Command, CommandGlobalOpts, Result};
+
+const LONG_ABOUT: &str = include_str!("./static/delete/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/delete/after_long_help.txt");
+
+/// Delete a template for enrollment tickets
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP),
+)]
+pub struct DeleteCommand {
+    /// Name of the template
+    name: String,
+
+    /// Confirm the deletion without prompting
+    #[arg(long, short)]
+    yes: bool,
+}
+
+#[async_trait]
+impl Command for DeleteCommand {
+    const NAME: &'static str = "project ticket-template delete";
+
+    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> Result<()> {
+        // fail before prompting if the template does not exist
+        opts.state.get_ticket_template(&self.name).await?;
+        if !opts.terminal.confirmed_with_flag_or_prompt(
+            self.yes,
+            format!(
+                "Are you sure you want to delete the ticket template {}?",
+                self.name
+            ),
+        )? {
+            return Ok(());
+        }
+        opts.state.delete_ticket_template(&self.name).await?;
+
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "Deleted the ticket template {}",
+                color_primary(&self.name)
+            ))
+            .machine(&self.name)
+            .json(serde_json::json!({ "name": &self.name }))
+            .write_line()?;
+        Ok(())
+    }
+}
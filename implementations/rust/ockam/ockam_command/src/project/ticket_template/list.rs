@@ -0,0 +1,63 @@
+use std::fmt::Write;
+
+use async_trait::async_trait;
+use clap::Args;
+use miette::IntoDiagnostic;
+
+use ockam::Context;
+use ockam_api::cli_state::TicketTemplate;
+
+use crate::output::Output;
+use crate::{color_primary, docs, Command, CommandGlobalOpts, Result};
+
+const LONG_ABOUT: &str = include_str!("./static/list/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/list/after_long_help.txt");
+
+/// List the templates for enrollment tickets
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP),
+)]
+pub struct ListCommand;
+
+#[async_trait]
+impl Command for ListCommand {
+    const NAME: &'static str = "project ticket-template list";
+
+    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> Result<()> {
+        let templates = opts.state.get_ticket_templates().await?;
+        let plain = opts.terminal.build_list(
+            &templates,
+            "Ticket templates",
+            "No ticket templates found.",
+        )?;
+        let json = serde_json::to_string_pretty(&templates).into_diagnostic()?;
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .json(json)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+impl Output for TicketTemplate {
+    fn output(&self) -> Result<String> {
+        let mut w = String::new();
+        write!(w, "Template {}", color_primary(self.name()))?;
+        let attributes = self
+            .attributes()
+            .iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect::<Vec<_>>();
+        write!(w, "\n  Attributes: {}", attributes.join(", "))?;
+        if let Some(usage_count) = self.usage_count() {
+            write!(w, "\n  Usage count: {usage_count}")?;
+        }
+        if let Some(expires_in) = self.expires_in() {
+            write!(w, "\n  Expires in: {}s", expires_in.as_secs())?;
+        }
+        Ok(w)
+    }
+}
@@ -0,0 +1,50 @@
+use clap::{Args, Subcommand};
+
+use crate::{docs, Command, CommandGlobalOpts};
+
+pub use create::CreateCommand;
+pub use delete::DeleteCommand;
+pub use list::ListCommand;
+
+mod create;
+mod delete;
+mod list;
+
+const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
+
+/// Manage the templates used to create enrollment tickets
+#[derive(Clone, Debug, Args)]
+#[command(
+arg_required_else_help = true,
+subcommand_required = true,
+long_about = docs::about(LONG_ABOUT),
+)]
+pub struct TicketTemplateCommand {
+    #[command(subcommand)]
+    pub subcommand: TicketTemplateSubcommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum TicketTemplateSubcommand {
+    Create(CreateCommand),
+    List(ListCommand),
+    Delete(DeleteCommand),
+}
+
+impl TicketTemplateCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        match self.subcommand {
+            TicketTemplateSubcommand::Create(c) => c.run(opts),
+            TicketTemplateSubcommand::List(c) => c.run(opts),
+            TicketTemplateSubcommand::Delete(c) => c.run(opts),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match &self.subcommand {
+            TicketTemplateSubcommand::Create(c) => c.name(),
+            TicketTemplateSubcommand::List(c) => c.name(),
+            TicketTemplateSubcommand::Delete(c) => c.name(),
+        }
+    }
+}
@@ -1,16 +1,18 @@
 use indicatif::ProgressBar;
 use miette::miette;
 use miette::Context as _;
+use std::future::Future;
 use std::iter::Take;
 use std::time::Duration;
 use tokio_retry::strategy::FixedInterval;
 use tokio_retry::Retry;
-use tracing::debug;
+use tracing::{debug, warn};
 
 use ockam_api::cloud::project::{Project, ProjectsOrchestratorApi};
 use ockam_api::cloud::{CredentialsEnabled, ORCHESTRATOR_AWAIT_TIMEOUT};
 use ockam_api::config::lookup::LookupMeta;
 use ockam_api::error::ApiError;
+use ockam_api::minicbor_url::Url;
 use ockam_api::nodes::service::relay::SecureChannelsCreation;
 use ockam_api::nodes::InMemoryNode;
 
@@ -97,6 +99,89 @@ pub async fn get_projects_secure_channels_from_config_lookup(
     Ok(sc)
 }
 
+/// Check that the tenant URL of an Okta addon is an https URL with a domain
+pub fn validate_okta_tenant_url(url: &Url) -> Result<()> {
+    if url.scheme() != "https" {
+        return Err(miette!(
+            "the Okta tenant URL {url} must use the https scheme"
+        ))?;
+    }
+    if url.host_str().map(|h| h.is_empty()).unwrap_or(true) {
+        return Err(miette!("the Okta tenant URL {url} has no domain"))?;
+    }
+    Ok(())
+}
+
+/// Decode a chain of PEM-encoded certificates and return the number of certificates
+pub fn decode_pem_certificates(pem: &str) -> Result<usize> {
+    let mut count = 0;
+    for block in pem.split_inclusive("-----END CERTIFICATE-----") {
+        let block = block.trim();
+        if block.is_empty() {
+            continue;
+        }
+        let (label, _) = pem_rfc7468::decode_vec(block.as_bytes()).map_err(|e| {
+            miette!(
+                "the certificate #{} is not a valid PEM document: {e}",
+                count + 1
+            )
+        })?;
+        if label != "CERTIFICATE" {
+            return Err(miette!(
+                "the certificate #{} is a {label} instead of a CERTIFICATE",
+                count + 1
+            ))?;
+        }
+        count += 1;
+    }
+    if count == 0 {
+        return Err(miette!("the certificate chain is empty"))?;
+    }
+    Ok(count)
+}
+
+/// Run `f` with a project and, if it fails, refresh the project from the Orchestrator
+/// and run `f` once more with the refreshed project.
+///
+/// This handles a local copy of the project which is stale, for example when the route
+/// of the project authority has changed. The original error is returned if the project
+/// is imported, can't be refreshed, or has not changed.
+pub async fn with_refreshed_project<T, F, Fut>(
+    opts: &CommandGlobalOpts,
+    ctx: &Context,
+    node: &InMemoryNode,
+    project: Project,
+    f: F,
+) -> Result<T>
+where
+    F: Fn(Project) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let error = match f(project.clone()).await {
+        Ok(result) => return Ok(result),
+        Err(error) => error,
+    };
+
+    let projects = opts.state.projects();
+    if projects.is_project_imported(project.project_id()).await? {
+        return Err(error);
+    }
+    match node.refresh_project(ctx, project.project_id()).await {
+        Ok(refreshed) if refreshed.model() != project.model() => {
+            debug!(
+                "retrying with the refreshed project {} after an error: {error:?}",
+                project.name()
+            );
+            f(refreshed).await
+        }
+        Ok(_) => Err(error),
+        Err(e) => {
+            warn!("could not refresh the project {}: {e:?}", project.name());
+            Err(error)
+        }
+    }
+}
+
 pub async fn check_project_readiness(
     opts: &CommandGlobalOpts,
     ctx: &Context,
@@ -8,6 +8,7 @@ use ockam_api::authenticator::direct::Members;
 use ockam_api::nodes::InMemoryNode;
 use ockam_multiaddr::MultiAddr;
 
+use crate::project::util::with_refreshed_project;
 use crate::project_member::{create_authority_client, create_member_attributes, get_project};
 use crate::util::api::{IdentityOpts, RetryOpts};
 use crate::{docs, fmt_ok, Command, CommandGlobalOpts, Error};
@@ -70,26 +71,32 @@ impl Command for AddCommand {
         )
         .await?;
 
-        let authority_node_client =
-            create_authority_client(&node, &opts.state, &self.identity_opts, &project).await?;
-
-        authority_node_client
-            .add_member(
-                ctx,
-                self.member.clone(),
-                create_member_attributes(
-                    &self.attributes,
-                    &self.allowed_relay_name,
-                    self.enroller,
-                )?,
-            )
-            .await
-            .map_err(Error::Retry)?;
-
-        opts.terminal.stdout().plain(fmt_ok!(
-            "Identifier {} is now a Project member. It can get a credential and access Project resources, like portals of other members",
-            self.member
-        ));
+        let attributes =
+            create_member_attributes(&self.attributes, &self.allowed_relay_name, self.enroller)?;
+
+        // the project is refreshed and the member added again if the authority can't be reached
+        let (node, state, identity_opts) = (&node, &opts.state, &self.identity_opts);
+        let (member, attributes) = (&self.member, &attributes);
+        with_refreshed_project(&opts, ctx, node, project, |project| async move {
+            let authority_node_client =
+                create_authority_client(node, state, identity_opts, &project).await?;
+            authority_node_client
+                .add_member(ctx, member.clone(), attributes.clone())
+                .await
+                .map_err(Error::Retry)?;
+            Ok(())
+        })
+        .await?;
+
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "Identifier {} is now a Project member. It can get a credential and access Project resources, like portals of other members",
+                self.member
+            ))
+            .machine(&self.member)
+            .json(serde_json::json!({ "identifier": &self.member }))
+            .write_line()?;
 
         Ok(())
     }
@@ -9,6 +9,7 @@ use ockam_api::nodes::InMemoryNode;
 use ockam_multiaddr::MultiAddr;
 
 use super::{create_authority_client, get_project};
+use crate::error::{CommandError, ErrorCode};
 use crate::util::api::IdentityOpts;
 use crate::util::async_cmd;
 use crate::{color_primary, docs, fmt_err, fmt_ok, CommandGlobalOpts};
@@ -16,7 +17,7 @@ use crate::{color_primary, docs, fmt_err, fmt_ok, CommandGlobalOpts};
 const LONG_ABOUT: &str = include_str!("./static/delete/long_about.txt");
 const AFTER_LONG_HELP: &str = include_str!("./static/delete/after_long_help.txt");
 
-/// Add members to a Project, as an authorized enroller, directly, or via an enrollment ticket
+/// Revoke the membership of identities to a Project
 #[derive(Clone, Debug, Args)]
 #[command(
 long_about = docs::about(LONG_ABOUT),
@@ -36,6 +37,10 @@ pub struct DeleteCommand {
     /// Delete all members of the project except the default identity
     #[arg(long, conflicts_with = "member")]
     all: bool,
+
+    /// Confirm the deletion without prompting
+    #[arg(long, short)]
+    yes: bool,
 }
 
 impl DeleteCommand {
@@ -57,6 +62,23 @@ impl DeleteCommand {
 
         let project = get_project(&opts.state, &self.to).await?;
 
+        let prompt = match &self.member {
+            Some(member) => format!(
+                "Are you sure you want to revoke the membership of {member} to the Project {}?",
+                project.name()
+            ),
+            None => format!(
+                "Are you sure you want to revoke the membership of all the members of the Project {}?",
+                project.name()
+            ),
+        };
+        if !opts
+            .terminal
+            .confirmed_with_flag_or_prompt(self.yes, prompt)?
+        {
+            return Ok(());
+        }
+
         let node = InMemoryNode::start_with_project_name(
             ctx,
             &opts.state,
@@ -69,13 +91,27 @@ impl DeleteCommand {
 
         match (&self.member, self.all) {
             (Some(member), _) => {
-                authority_node_client
+                if !authority_node_client
                     .delete_member(ctx, member.clone())
-                    .await?;
-                opts.terminal.stdout().plain(fmt_ok!(
-                    "Identifier {} is no longer a member of the Project. It won't be able to get a credential and access Project resources, like portals of other members",
-                    color_primary(member.to_string())
-                ));
+                    .await?
+                {
+                    return Err(CommandError::with_code(
+                        miette!(
+                            "The identifier {member} is not a member of the Project {}",
+                            project.name()
+                        ),
+                        ErrorCode::NotFound,
+                    ))?;
+                }
+                opts.terminal
+                    .stdout()
+                    .plain(fmt_ok!(
+                        "Identifier {} is no longer a member of the Project. It won't be able to get a credential and access Project resources, like portals of other members",
+                        color_primary(member.to_string())
+                    ))
+                    .machine(&member)
+                    .json(serde_json::json!({ "identifier": member }))
+                    .write_line()?;
             }
             (None, true) => {
                 if !opts
@@ -90,10 +126,14 @@ impl DeleteCommand {
                 authority_node_client
                     .delete_all_members(ctx, identity.identifier())
                     .await?;
-                opts.terminal.stdout().plain(fmt_ok!(
-                    "All identifiers except {} are no longer members of the Project.",
-                    color_primary(identity.identifier().to_string())
-                ));
+                opts.terminal
+                    .stdout()
+                    .plain(fmt_ok!(
+                        "All identifiers except {} are no longer members of the Project.",
+                        color_primary(identity.identifier().to_string())
+                    ))
+                    .json(serde_json::json!({ "all_except": identity.identifier() }))
+                    .write_line()?;
             }
             _ => unreachable!(),
         }
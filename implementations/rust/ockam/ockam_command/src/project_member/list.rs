@@ -1,23 +1,28 @@
 use async_trait::async_trait;
 use clap::Args;
+use miette::IntoDiagnostic;
 
-use ockam::identity::{AttributesEntry, Identifier};
 use ockam::Context;
 use ockam_api::authenticator::direct::Members;
 use ockam_api::nodes::InMemoryNode;
 use ockam_multiaddr::MultiAddr;
 
 use super::{create_authority_client, get_project};
-use crate::output::Output;
+use crate::output::MemberOutput;
 use crate::util::api::IdentityOpts;
-use crate::{docs, Command, CommandGlobalOpts, Result};
+use crate::{docs, fmt_log, Command, CommandGlobalOpts, Result};
 
 const LONG_ABOUT: &str = include_str!("./static/list/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/list/after_long_help.txt");
+
+/// Number of members requested at once to the project authority
+const PAGE_SIZE: u64 = 100;
 
 /// List members of a Project
 #[derive(Clone, Debug, Args)]
 #[command(
 long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP),
 )]
 pub struct ListCommand {
     #[command(flatten)]
@@ -26,6 +31,14 @@ pub struct ListCommand {
     /// Which project members to request
     #[arg(long, short, value_name = "ROUTE_TO_PROJECT")]
     to: Option<MultiAddr>,
+
+    /// Number of members to skip, sorted by the time they were added
+    #[arg(long, default_value_t = 0)]
+    offset: u64,
+
+    /// Maximum number of members to list. All the members are listed by default
+    #[arg(long)]
+    limit: Option<u64>,
 }
 
 #[async_trait]
@@ -45,35 +58,59 @@ impl Command for ListCommand {
         let authority_node_client =
             create_authority_client(&node, &opts.state, &self.identity_opts, &project).await?;
 
-        let members = authority_node_client
-            .list_members(ctx)
-            .await?
-            .into_iter()
-            .map(|i| MemberOutput(i.0, i.1))
-            .collect();
-
-        print_members(&opts, members)?;
+        // the members are retrieved by pages, to support projects with many members
+        let mut members = vec![];
+        let mut offset = Some(self.offset);
+        while let Some(current) = offset {
+            let page_size = match self.limit {
+                Some(limit) => (limit - members.len() as u64).min(PAGE_SIZE),
+                None => PAGE_SIZE,
+            };
+            if page_size == 0 {
+                break;
+            }
+            let page = authority_node_client
+                .list_members_page(ctx, current, page_size)
+                .await?;
+            offset = page.next_offset();
+            members.extend(
+                page.into_members()
+                    .into_iter()
+                    .map(|(identifier, entry)| MemberOutput::new(identifier, entry)),
+            );
+        }
+
+        print_members(&opts, members, offset)?;
 
         Ok(())
     }
 }
 
-struct MemberOutput(Identifier, AttributesEntry);
-
-impl Output for MemberOutput {
-    fn output(&self) -> Result<String> {
-        Ok(format!("{}: {}", self.0, self.1))
-    }
-}
-
-fn print_members(opts: &CommandGlobalOpts, member_ids: Vec<MemberOutput>) -> miette::Result<()> {
-    let plain = opts.terminal.build_list(
+fn print_members(
+    opts: &CommandGlobalOpts,
+    member_ids: Vec<MemberOutput>,
+    next_offset: Option<u64>,
+) -> miette::Result<()> {
+    let mut plain = opts.terminal.build_list(
         &member_ids,
         "Members",
         "No members found on that Authority node.",
     )?;
+    if let Some(next_offset) = next_offset {
+        plain.push_str(&format!(
+            "\n{}",
+            fmt_log!("There are more members. Use --offset {next_offset} to list them")
+        ));
+    }
+
+    let json = serde_json::to_string_pretty(&member_ids).into_diagnostic()?;
 
-    opts.terminal.clone().stdout().plain(plain).write_line()?;
+    opts.terminal
+        .clone()
+        .stdout()
+        .plain(plain)
+        .json(json)
+        .write_line()?;
 
     Ok(())
 }
@@ -1,5 +1,7 @@
 use async_trait::async_trait;
 use clap::Args;
+use miette::IntoDiagnostic;
+use serde::Serialize;
 
 use ockam::identity::Identifier;
 use ockam::Context;
@@ -9,6 +11,7 @@ use ockam_multiaddr::MultiAddr;
 
 use super::{create_authority_client, get_project};
 use crate::output::Output;
+use crate::project::util::with_refreshed_project;
 use crate::util::api::IdentityOpts;
 use crate::{docs, Command, CommandGlobalOpts, Result};
 
@@ -42,15 +45,16 @@ impl Command for ListIdsCommand {
         )
         .await?;
 
-        let authority_node_client =
-            create_authority_client(&node, &opts.state, &self.identity_opts, &project).await?;
-
-        let member_ids = authority_node_client
-            .list_member_ids(ctx)
-            .await?
-            .into_iter()
-            .map(IdentifierOutput)
-            .collect();
+        let (node, state, identity_opts) = (&node, &opts.state, &self.identity_opts);
+        let member_ids = with_refreshed_project(&opts, ctx, node, project, |project| async move {
+            let authority_node_client =
+                create_authority_client(node, state, identity_opts, &project).await?;
+            Ok(authority_node_client.list_member_ids(ctx).await?)
+        })
+        .await?
+        .into_iter()
+        .map(IdentifierOutput)
+        .collect();
 
         print_member_ids(&opts, member_ids)?;
 
@@ -58,6 +62,8 @@ impl Command for ListIdsCommand {
     }
 }
 
+#[derive(Serialize)]
+#[serde(transparent)]
 struct IdentifierOutput(Identifier);
 
 impl Output for IdentifierOutput {
@@ -76,7 +82,14 @@ fn print_member_ids(
         "No members found on that Authority node.",
     )?;
 
-    opts.terminal.clone().stdout().plain(plain).write_line()?;
+    let json = serde_json::to_string_pretty(&member_ids).into_diagnostic()?;
+
+    opts.terminal
+        .clone()
+        .stdout()
+        .plain(plain)
+        .json(json)
+        .write_line()?;
 
     Ok(())
 }
@@ -24,10 +24,9 @@ mod list_ids;
 
 const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
 
-/// Manage Authority nodes
+/// Manage the members of a Project
 #[derive(Clone, Debug, Args)]
 #[command(
-    hide = docs::hide(),
     arg_required_else_help = true,
     subcommand_required = true,
     long_about = docs::about(LONG_ABOUT),
@@ -1,5 +1,6 @@
 use async_trait::async_trait;
 use std::str::FromStr;
+use std::time::Duration;
 
 use clap::Args;
 use colorful::Colorful;
@@ -8,19 +9,22 @@ use tokio::sync::Mutex;
 use tokio::try_join;
 use tracing::info;
 
-use ockam::identity::Identifier;
+use ockam::identity::{Identifier, TimestampInSeconds};
 use ockam::Context;
 use ockam_api::address::extract_address_value;
 use ockam_api::nodes::models::relay::RelayInfo;
 use ockam_api::nodes::service::relay::Relays;
-use ockam_api::nodes::BackgroundNodeClient;
-use ockam_api::CliState;
+use ockam_api::nodes::{BackgroundNodeClient, NodeRequestError};
+use ockam_api::{CliState, DefaultAddress};
 use ockam_multiaddr::proto::Project;
 use ockam_multiaddr::{MultiAddr, Protocol};
 
-use crate::output::Output;
+use crate::output::{human_readable_time, Output, RelayOutput};
+use crate::service::directory::{node_identity_name, publish_service, published_outlet_route};
 use crate::terminal::OckamColor;
 use crate::util::api::RetryOpts;
+use crate::util::duration::duration_parser;
+use crate::util::existing_resource::{report_existing_resource, ParametersDiff};
 use crate::util::{colorize_connection_status, process_nodes_multiaddr};
 use crate::{docs, fmt_log, fmt_ok, Command, CommandGlobalOpts, Error, Result};
 use crate::{node::util::initialize_default_node, terminal::color_primary};
@@ -61,6 +65,31 @@ pub struct CreateCommand {
     #[arg(long)]
     project_relay: bool,
 
+    /// Interval between two keepalive messages sent through the relay to check that it is
+    /// still alive. Defaults to the relay registration refresh interval.
+    #[arg(long, value_name = "DURATION", value_parser = duration_parser)]
+    heartbeat_interval: Option<Duration>,
+
+    /// Create the relay even if a relay with the same name is already registered in the
+    /// project by another node. The traffic sent to that name will be relayed to this node.
+    #[arg(long)]
+    force: bool,
+
+    /// Succeed without creating the relay if a relay with the same name already exists on the
+    /// node with the same route. Fail with the differing parameters otherwise
+    #[arg(long)]
+    pub if_not_exists: bool,
+
+    /// Publish the relay under this name in the service directory of its project, so that
+    /// project members can find it with `ockam service discover`. The published route reaches
+    /// the TCP Outlet created with the default address, `outlet`, on the node of the relay
+    #[arg(long, value_name = "NAME")]
+    pub publish: Option<String>,
+
+    /// Description of the published relay, listed by `ockam service discover`
+    #[arg(long, requires = "publish")]
+    pub description: Option<String>,
+
     #[command(flatten)]
     retry_opts: RetryOpts,
 }
@@ -82,11 +111,30 @@ impl Command for CreateCommand {
         let cmd = self.parse_args(&opts).await?;
         let at = cmd.at();
         let alias = cmd.relay_name();
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &cmd.to).await?;
+        let project_name = at
+            .first()
+            .and_then(|p| p.cast::<Project>().map(|p| p.to_string()));
+        if cmd.publish.is_some() && project_name.is_none() {
+            return Err(miette!(
+                "--publish can only be used with a relay created at a project"
+            ))?;
+        }
+        if cmd.if_not_exists {
+            let relays = node.list_relays(ctx).await?;
+            if let Some(relay) = relays.iter().find(|r| r.alias() == alias) {
+                return report_existing_resource(
+                    &opts,
+                    "Relay",
+                    &alias,
+                    cmd.parameters_diff(relay),
+                );
+            }
+        }
 
         opts.terminal.write_line(&fmt_log!("Creating Relay...\n"))?;
         let is_finished: Mutex<bool> = Mutex::new(false);
 
-        let node = BackgroundNodeClient::create(ctx, &opts.state, &cmd.to).await?;
         let get_relay_info = async {
             let relay_info = {
                 if at.starts_with(Project::CODE) && cmd.authorized.is_some() {
@@ -99,12 +147,22 @@ impl Command for CreateCommand {
                     ctx,
                     &at,
                     alias.clone(),
-                    cmd.authorized,
-                    Some(cmd.relay_address.unwrap_or(alias)),
+                    cmd.authorized.clone(),
+                    Some(cmd.relay_address.clone().unwrap_or(alias)),
                     !cmd.project_relay,
+                    cmd.heartbeat_interval,
+                    cmd.force,
                 )
                 .await
-                .map_err(Error::Retry)?
+                .map_err(|err| {
+                    // A relay name collision won't be solved by retrying
+                    let err = NodeRequestError::from_report(&node.node_name(), err);
+                    if err.is_retryable() {
+                        Error::Retry(err.into())
+                    } else {
+                        Error::from(miette::Report::from(err))
+                    }
+                })?
             };
             *is_finished.lock().await = true;
             Ok(relay_info)
@@ -149,7 +207,36 @@ impl Command for CreateCommand {
             .map(|x| x.to_string())
             .unwrap_or("N/A".into());
 
-        let json = serde_json::to_string_pretty(&relay).into_diagnostic()?;
+        let published_route = match (&cmd.publish, relay.remote_address()) {
+            (Some(name), Some(remote_address)) => {
+                let project_name = project_name.unwrap_or_default();
+                let route = published_outlet_route(
+                    &project_name,
+                    remote_address,
+                    DefaultAddress::OUTLET_SERVICE,
+                )?;
+                let project = opts
+                    .state
+                    .projects()
+                    .get_project_by_name(&project_name)
+                    .await?;
+                let identity_name = node_identity_name(&opts, &node.node_name()).await?;
+                publish_service(
+                    ctx,
+                    &opts,
+                    &project,
+                    &identity_name,
+                    name,
+                    &route,
+                    cmd.description.clone(),
+                )
+                .await?;
+                Some((name, route))
+            }
+            _ => None,
+        };
+
+        let json = serde_json::to_string_pretty(&RelayOutput::from(relay)).into_diagnostic()?;
         opts.terminal
             .stdout()
             .plain(plain)
@@ -157,6 +244,14 @@ impl Command for CreateCommand {
             .json(json)
             .write_line()?;
 
+        if let Some((name, route)) = published_route {
+            opts.terminal.write_line(&fmt_ok!(
+                "Published the relay as {} with the route {}",
+                color_primary(name),
+                color_primary(route.to_string())
+            ))?;
+        }
+
         Ok(())
     }
 }
@@ -170,6 +265,11 @@ impl CreateCommand {
         self.relay_name.clone()
     }
 
+    /// Return the parameters of an existing relay which differ from the requested ones
+    fn parameters_diff(&self, relay: &RelayInfo) -> ParametersDiff {
+        ParametersDiff::new().compare("at", relay.destination_address(), self.at())
+    }
+
     async fn parse_args(mut self, opts: &CommandGlobalOpts) -> Result<Self> {
         let default_project_name = &opts
             .state
@@ -228,7 +328,8 @@ Relay:
         let output = format!(
             r#"Alias: {alias}
 Status: {connection_status}
-Remote Address: {remote_address}"#,
+Remote Address: {remote_address}
+Last Heartbeat: {last_heartbeat}"#,
             alias = self.alias().color(OckamColor::PrimaryResource.color()),
             connection_status = colorize_connection_status(self.connection_status()),
             remote_address = self
@@ -236,6 +337,10 @@ Remote Address: {remote_address}"#,
                 .map(|x| x.to_string())
                 .unwrap_or("N/A".into())
                 .color(OckamColor::PrimaryResource.color()),
+            last_heartbeat = self
+                .last_heartbeat_at()
+                .map(|t| human_readable_time(TimestampInSeconds(t)))
+                .unwrap_or("N/A".into()),
         );
 
         Ok(output)
@@ -245,8 +350,11 @@ Remote Address: {remote_address}"#,
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::relay::{RelayCommand, RelaySubCommand};
     use crate::run::parser::resource::utils::parse_cmd_from_args;
+    use crate::OckamSubcommand;
     use ockam_api::nodes::InMemoryNode;
+    use ockam_api::ConnectionStatus;
 
     #[test]
     fn command_can_be_parsed_from_name() {
@@ -296,6 +404,41 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn parameters_diff_with_an_existing_relay() {
+        let cmd = parse_cmd_from_args(
+            CreateCommand::NAME,
+            &[
+                "r1".to_string(),
+                "--at".to_string(),
+                "/project/p1".to_string(),
+                "--if-not-exists".to_string(),
+            ],
+        )
+        .unwrap();
+        let OckamSubcommand::Relay(RelayCommand {
+            subcommand: RelaySubCommand::Create(cmd),
+        }) = cmd
+        else {
+            panic!("unexpected command")
+        };
+        assert!(cmd.if_not_exists);
+
+        let existing = |at: &str| {
+            RelayInfo::new(
+                MultiAddr::from_str(at).unwrap(),
+                "r1".to_string(),
+                false,
+                ConnectionStatus::Up,
+            )
+        };
+        assert!(cmd.parameters_diff(&existing("/project/p1")).is_empty());
+        assert_eq!(
+            cmd.parameters_diff(&existing("/project/p2")).to_string(),
+            "  --at: /project/p2 (existing) != /project/p1 (requested)\n"
+        );
+    }
+
     #[tokio::test]
     async fn test_parse_arg_relay_name() {
         // `--at` is a local route
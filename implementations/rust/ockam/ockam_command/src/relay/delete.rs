@@ -122,6 +122,7 @@ impl DeleteCommandTui for DeleteTui {
                 color!(relay_name, OckamColor::PrimaryResource),
                 color!(node_name, OckamColor::PrimaryResource)
             ))
+            .json(serde_json::json!({ "name": relay_name, "node": node_name }))
             .write_line()?;
         Ok(())
     }
@@ -11,6 +11,7 @@ use ockam_api::nodes::models::relay::RelayInfo;
 use ockam_api::nodes::BackgroundNodeClient;
 use ockam_core::api::Request;
 
+use crate::output::RelayOutput;
 use crate::terminal::OckamColor;
 use crate::util::async_cmd;
 use crate::{docs, CommandGlobalOpts};
@@ -71,7 +72,13 @@ impl ListCommand {
             &format!("Relays on Node {}", node.node_name()),
             &format!("No Relays found on node {}.", node.node_name()),
         )?;
-        let json = serde_json::to_string_pretty(&relays).into_diagnostic()?;
+        let json = serde_json::to_string_pretty(
+            &relays
+                .into_iter()
+                .map(RelayOutput::from)
+                .collect::<Vec<_>>(),
+        )
+        .into_diagnostic()?;
 
         opts.terminal
             .stdout()
@@ -1,26 +1,18 @@
 use clap::Args;
-use colorful::Colorful;
 use console::Term;
-use indoc::formatdoc;
 use miette::{miette, IntoDiagnostic};
 
 use ockam::Context;
 use ockam_api::address::extract_address_value;
-use ockam_api::nodes::models::relay::RelayInfo;
+use ockam_api::nodes::service::relay::Relays;
 use ockam_api::nodes::BackgroundNodeClient;
-use ockam_core::api::Request;
-use ockam_multiaddr::MultiAddr;
-
-use ockam_api::ConnectionStatus;
 use ockam_core::AsyncTryClone;
-use serde::Serialize;
 
-use crate::output::Output;
+use crate::output::{Output, RelayOutput};
 use crate::terminal::tui::ShowCommandTui;
 use crate::terminal::PluralTerm;
 use crate::util::async_cmd;
-use crate::util::colorize_connection_status;
-use crate::{docs, CommandGlobalOpts, OckamColor, Terminal, TerminalStream};
+use crate::{docs, CommandGlobalOpts, Terminal, TerminalStream};
 
 const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
 const AFTER_LONG_HELP: &str = include_str!("./static/show/after_long_help.txt");
@@ -104,19 +96,13 @@ impl ShowCommandTui for ShowTui {
     }
 
     async fn list_items_names(&self) -> miette::Result<Vec<String>> {
-        let relays: Vec<RelayInfo> = self
-            .node
-            .ask(&self.ctx, Request::get("/node/relay"))
-            .await?;
+        let relays = self.node.list_relays(&self.ctx).await?;
         Ok(relays.into_iter().map(|i| i.alias().to_string()).collect())
     }
 
     async fn show_single(&self, item_name: &str) -> miette::Result<()> {
-        let relay: RelayInfo = self
-            .node
-            .ask(&self.ctx, Request::get(format!("/node/relay/{item_name}")))
-            .await?;
-        let relay = RelayShowOutput::from(relay);
+        let relay = self.node.show_relay(&self.ctx, item_name).await?;
+        let relay = RelayOutput::from(relay);
         self.terminal()
             .stdout()
             .plain(relay.output()?)
@@ -126,76 +112,3 @@ impl ShowCommandTui for ShowTui {
         Ok(())
     }
 }
-
-#[derive(Serialize)]
-struct RelayShowOutput {
-    pub alias: String,
-    pub destination: MultiAddr,
-    pub connection_status: ConnectionStatus,
-    pub relay_route: Option<String>,
-    pub remote_address: Option<MultiAddr>,
-    pub worker_address: Option<MultiAddr>,
-}
-
-impl From<RelayInfo> for RelayShowOutput {
-    fn from(r: RelayInfo) -> Self {
-        Self {
-            alias: r.alias().to_string(),
-            destination: r.destination_address().clone(),
-            connection_status: r.connection_status(),
-            relay_route: r.forwarding_route().clone(),
-            remote_address: r.remote_address_ma().into_diagnostic().unwrap(),
-            worker_address: r.worker_address_ma().into_diagnostic().unwrap(),
-        }
-    }
-}
-
-impl Output for RelayShowOutput {
-    fn output(&self) -> crate::error::Result<String> {
-        Ok(formatdoc!(
-            r#"
-        Relay:
-            Alias: {alias}
-            Destination: {destination_address}
-            Status: {connection_status}
-            Relay Route: {route}
-            Remote Address: {remote_addr}
-            Worker Address: {worker_addr}
-        "#,
-            alias = self.alias,
-            connection_status = colorize_connection_status(self.connection_status),
-            destination_address = self.destination.to_string(),
-            route = self.relay_route.as_deref().unwrap_or("N/A"),
-            remote_addr = self
-                .remote_address
-                .as_ref()
-                .map(|x| x.to_string())
-                .unwrap_or("N/A".into()),
-            worker_addr = self
-                .worker_address
-                .as_ref()
-                .map(|x| x.to_string())
-                .unwrap_or("N/A".into()),
-        ))
-    }
-
-    fn list_output(&self) -> crate::error::Result<String> {
-        Ok(formatdoc!(
-            r#"
-            Alias: {alias}
-            Status: {connection_status}
-            Remote Address: {remote_address}"#,
-            alias = self
-                .alias
-                .as_str()
-                .color(OckamColor::PrimaryResource.color()),
-            connection_status = colorize_connection_status(self.connection_status),
-            remote_address = self
-                .remote_address
-                .as_ref()
-                .map(|x| x.to_string())
-                .unwrap_or("N/A".into())
-                .color(OckamColor::PrimaryResource.color()),
-        ))
-    }
-}
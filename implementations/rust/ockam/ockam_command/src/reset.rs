@@ -1,18 +1,25 @@
+use std::fmt::Write;
+use std::path::PathBuf;
+
 use clap::Args;
 use colorful::Colorful;
-use miette::{miette, WrapErr};
+use miette::{IntoDiagnostic, WrapErr};
 use ockam_api::cloud::space::Spaces;
-use ockam_api::CliState;
+use ockam_api::{CliState, ResetSelection, ResetSummary};
 use tracing::error;
 
 use ockam_api::nodes::InMemoryNode;
 use ockam_node::Context;
 
-use crate::terminal::ConfirmResult;
+use crate::terminal::{no_input_error, ConfirmResult};
 use crate::util::async_cmd;
-use crate::{color, fmt_ok, CommandGlobalOpts, OckamColor};
+use crate::{color, fmt_log, fmt_ok, CommandGlobalOpts, OckamColor};
 
 /// Removes the local Ockam configuration including all Identities and Nodes
+///
+/// Use the `--nodes`, `--identities`, `--projects` and `--spaces` flags to only remove a part of
+/// the local configuration. With `--backup`, the local configuration is first saved to an
+/// archive which can be restored with `ockam restore`.
 #[derive(Clone, Debug, Args)]
 pub struct ResetCommand {
     /// Confirm the reset without prompting
@@ -22,6 +29,26 @@ pub struct ResetCommand {
     /// Remove your spaces from the Orchestrator
     #[arg(long)]
     all: bool,
+
+    /// Only remove the nodes. Running nodes are stopped first
+    #[arg(long)]
+    nodes: bool,
+
+    /// Only remove the identities. The nodes using them must be removed as well
+    #[arg(long)]
+    identities: bool,
+
+    /// Only remove the projects
+    #[arg(long)]
+    projects: bool,
+
+    /// Only remove the spaces
+    #[arg(long)]
+    spaces: bool,
+
+    /// Write a tar.gz archive of the local configuration to this path before removing anything
+    #[arg(long, value_name = "PATH")]
+    backup: Option<PathBuf>,
 }
 
 impl ResetCommand {
@@ -41,25 +68,46 @@ impl ResetCommand {
         }
     }
 
+    fn selection(&self) -> ResetSelection {
+        ResetSelection {
+            nodes: self.nodes,
+            identities: self.identities,
+            projects: self.projects,
+            spaces: self.spaces,
+        }
+    }
+
     async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let selection = self.selection();
         let delete_orchestrator_resources =
             self.all && opts.state.is_enrolled().await.unwrap_or_default();
         if !self.yes {
+            let deleted = if selection.is_everything() {
+                "the local Ockam configuration".to_string()
+            } else {
+                format!("the local {}", selected_names(&selection).join(", "))
+            };
             let msg = if delete_orchestrator_resources {
-                "This will delete the local Ockam configuration and remove your spaces from the Orchestrator. Are you sure?"
+                format!("This will delete {deleted} and remove your spaces from the Orchestrator. Are you sure?")
             } else {
-                "This will delete the local Ockam configuration. Are you sure?"
+                format!("This will delete {deleted}. Are you sure?")
             };
-            match opts.terminal.confirm(msg)? {
+            match opts.terminal.confirm(&msg)? {
                 ConfirmResult::Yes => {}
                 ConfirmResult::No => {
                     return Ok(());
                 }
                 ConfirmResult::NonTTY => {
-                    return Err(miette!("Use --yes to confirm"));
+                    return Err(no_input_error("--yes"));
                 }
             }
         }
+        if let Some(path) = &self.backup {
+            opts.state
+                .backup(path)
+                .await
+                .wrap_err(format!("Failed to back up the local state to {path:?}"))?;
+        }
         if delete_orchestrator_resources {
             if let Err(e) = delete_orchestrator_resources_impl(ctx, opts.clone()).await {
                 match opts.terminal.confirm(
@@ -72,15 +120,79 @@ impl ResetCommand {
                 }
             }
         }
-        opts.state.reset().await?;
+        let summary = opts.state.reset_selection(&selection).await?;
+
+        let mut plain = if selection.is_everything() {
+            fmt_ok!("Local Ockam configuration deleted")
+        } else {
+            fmt_ok!("Local {} deleted", selected_names(&selection).join(", "))
+        };
+        for (kind, names) in summary_entries(&summary) {
+            if !names.is_empty() {
+                write!(
+                    plain,
+                    "\n{}",
+                    fmt_log!(
+                        "Deleted {kind}: {}",
+                        names
+                            .iter()
+                            .map(|n| color!(n, OckamColor::PrimaryResource).to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )
+                )
+                .into_diagnostic()?;
+            }
+        }
+        if let Some(path) = &self.backup {
+            write!(
+                plain,
+                "\n{}",
+                fmt_log!(
+                    "The previous configuration can be restored with {}",
+                    color!(
+                        format!("ockam restore {}", path.display()),
+                        OckamColor::PrimaryResource
+                    )
+                )
+            )
+            .into_diagnostic()?;
+        }
         opts.terminal
             .stdout()
-            .plain(fmt_ok!("Local Ockam configuration deleted"))
+            .plain(plain)
+            .json(serde_json::json!({
+                "orchestrator_resources_deleted": delete_orchestrator_resources,
+                "deleted": summary,
+                "backup": self.backup,
+            }))
             .write_line()?;
         Ok(())
     }
 }
 
+/// Return the names of the selected parts of the local state
+fn selected_names(selection: &ResetSelection) -> Vec<&'static str> {
+    [
+        (selection.nodes, "nodes"),
+        (selection.identities, "identities"),
+        (selection.projects, "projects"),
+        (selection.spaces, "spaces"),
+    ]
+    .into_iter()
+    .filter_map(|(selected, name)| selected.then_some(name))
+    .collect()
+}
+
+fn summary_entries(summary: &ResetSummary) -> [(&'static str, &Vec<String>); 4] {
+    [
+        ("nodes", &summary.nodes),
+        ("identities", &summary.identities),
+        ("projects", &summary.projects),
+        ("spaces", &summary.spaces),
+    ]
+}
+
 async fn delete_orchestrator_resources_impl(
     ctx: &Context,
     opts: CommandGlobalOpts,
@@ -0,0 +1,65 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use colorful::Colorful;
+use miette::WrapErr;
+
+use crate::terminal::{no_input_error, ConfirmResult};
+use crate::util::async_cmd;
+use crate::{color, fmt_ok, CommandGlobalOpts, OckamColor};
+
+/// Restore the local Ockam configuration from an archive created with `ockam reset --backup`
+///
+/// The running nodes are stopped and the current local configuration is replaced by the content
+/// of the archive.
+#[derive(Clone, Debug, Args)]
+pub struct RestoreCommand {
+    /// Path of the backup archive
+    #[arg(value_name = "PATH")]
+    path: PathBuf,
+
+    /// Confirm the restoration without prompting
+    #[arg(long, short)]
+    yes: bool,
+}
+
+impl RestoreCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |_ctx| async move {
+            self.async_run(opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "restore".into()
+    }
+
+    async fn async_run(&self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        if !self.yes {
+            match opts.terminal.confirm(
+                "This will stop the running nodes and replace the local Ockam configuration. Are you sure?",
+            )? {
+                ConfirmResult::Yes => {}
+                ConfirmResult::No => {
+                    return Ok(());
+                }
+                ConfirmResult::NonTTY => {
+                    return Err(no_input_error("--yes"));
+                }
+            }
+        }
+        opts.state.restore(&self.path).await.wrap_err(format!(
+            "Failed to restore the local state from {:?}",
+            self.path
+        ))?;
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "Local Ockam configuration restored from {}",
+                color!(self.path.display(), OckamColor::PrimaryResource)
+            ))
+            .json(serde_json::json!({ "restored_from": self.path }))
+            .write_line()?;
+        Ok(())
+    }
+}
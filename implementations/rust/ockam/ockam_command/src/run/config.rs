@@ -1,6 +1,7 @@
 use crate::run::parser::config::ConfigParser;
 use crate::run::parser::resource::*;
 use crate::run::parser::Version;
+use crate::run::teardown::RecordedCommand;
 use crate::CommandGlobalOpts;
 
 use ockam_node::Context;
@@ -48,22 +49,26 @@ impl Config {
     ///
     /// For more details about the parsing, see the [parser](crate::run::parser) module.
     /// You can also check examples of valid configuration files in the demo folder of this module.
+    ///
+    /// If a run id is given, the nodes, relays, TCP outlets and TCP inlets created by the commands
+    /// are recorded so that they can be deleted with `ockam run --teardown`.
     pub async fn run(
         self,
         ctx: &Context,
         opts: &CommandGlobalOpts,
         overrides: &ValuesOverrides,
+        run_id: Option<&str>,
     ) -> miette::Result<()> {
         // Build commands and return validation errors before running any command.
         let commands: Vec<ParsedCommands> = vec![
             self.vaults.parse_commands(overrides)?.into(),
             self.identities.parse_commands(overrides)?.into(),
             self.project_enroll.parse_commands(overrides)?.into(),
-            self.nodes.parse_commands(overrides)?.into(),
-            self.relays.parse_commands(overrides)?.into(),
+            RecordedCommand::record(self.nodes.parse_commands(overrides)?, run_id),
+            RecordedCommand::record(self.relays.parse_commands(overrides)?, run_id),
             self.policies.parse_commands(overrides)?.into(),
-            self.tcp_outlets.parse_commands(overrides)?.into(),
-            self.tcp_inlets.parse_commands(overrides)?.into(),
+            RecordedCommand::record(self.tcp_outlets.parse_commands(overrides)?, run_id),
+            RecordedCommand::record(self.tcp_inlets.parse_commands(overrides)?, run_id),
         ];
 
         // Run commands
@@ -80,7 +85,7 @@ impl Config {
         contents: &str,
     ) -> miette::Result<()> {
         let config = Config::parse(&Config::resolve(contents)?)?;
-        config.run(ctx, &opts, &overrides).await
+        config.run(ctx, &opts, &overrides, None).await
     }
 }
 
@@ -1,15 +1,19 @@
 mod config;
 pub mod parser;
+mod teardown;
 
 pub use config::Config;
 
+use crate::run::parser::config::ConfigParser;
 use crate::run::parser::resource::ValuesOverrides;
 use crate::util::async_cmd;
+use crate::value_parsers::parse_key_val;
 use crate::{docs, CommandGlobalOpts};
 use clap::Args;
 use miette::Context as _;
 use miette::{miette, IntoDiagnostic};
 use ockam::Context;
+use ockam_api::cli_state::Run;
 use std::path::PathBuf;
 
 /// Create nodes given a declarative configuration file
@@ -29,6 +33,20 @@ pub struct RunCommand {
     /// To be used with docker or kubernetes.
     #[arg(long)]
     pub blocking: bool,
+
+    /// Key-value pairs defining variables used by the recipe.
+    /// They take precedence over the environment and the `variables` section of the recipe.
+    #[arg(long = "var", value_name = "VARIABLE", value_parser = parse_key_val::<String, String>)]
+    pub variables: Vec<(String, String)>,
+
+    /// Print the recipe with all its variables resolved, without running it
+    #[arg(long)]
+    pub render: bool,
+
+    /// Delete the nodes, relays, TCP outlets and TCP inlets created by previous runs of the recipe.
+    /// If the recipe was modified since it was run, the resources it declares are deleted instead
+    #[arg(long, conflicts_with = "render")]
+    pub teardown: bool,
 }
 
 impl RunCommand {
@@ -43,8 +61,29 @@ impl RunCommand {
     }
 
     async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
-        let contents = match &self.inline {
-            Some(contents) => contents.to_string(),
+        let (run_id, contents) = self.read_recipe()?;
+        self.set_variables();
+        if self.render {
+            return Self::render(&opts, &contents);
+        }
+        if self.teardown {
+            return teardown::teardown(ctx, &opts, &run_id, &contents).await;
+        }
+        let config = Config::parse(&Config::resolve(&contents)?)?;
+        opts.state.start_run(&run_id, &contents).await?;
+        config
+            .run(ctx, &opts, &ValuesOverrides::default(), Some(&run_id))
+            .await
+    }
+
+    /// Return the identifier of the recipe, used to record the resources it creates, and its contents.
+    /// A recipe file is identified by its absolute path and an inline recipe by the hash of its contents
+    fn read_recipe(&self) -> miette::Result<(String, String)> {
+        match &self.inline {
+            Some(contents) => Ok((
+                format!("inline:{}", Run::hash_contents(contents)),
+                contents.to_string(),
+            )),
             None => {
                 let path = match &self.recipe {
                     Some(path) => path.clone(),
@@ -71,9 +110,102 @@ impl RunCommand {
                         path
                     }
                 };
-                std::fs::read_to_string(path).into_diagnostic()?
+                let contents = std::fs::read_to_string(&path).into_diagnostic()?;
+                // the resources of a recipe are recorded under its absolute path
+                let path = path.canonicalize().into_diagnostic()?;
+                Ok((path.to_string_lossy().to_string(), contents))
             }
+        }
+    }
+
+    /// Set the variables given on the command line as environment variables,
+    /// so that they take precedence when the recipe is resolved
+    fn set_variables(&self) {
+        for (key, value) in &self.variables {
+            std::env::set_var(key, value);
+        }
+    }
+
+    /// Print the resolved recipe, once checked that it can be parsed
+    fn render(opts: &CommandGlobalOpts, contents: &str) -> miette::Result<()> {
+        let resolved = Config::resolve(contents)?;
+        Config::parse(&resolved)?;
+        let json = serde_yaml::from_str::<serde_json::Value>(&resolved).into_diagnostic()?;
+        opts.terminal
+            .clone()
+            .stdout()
+            .plain(&resolved)
+            .machine(&resolved)
+            .json(serde_json::to_string_pretty(&json).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn run_command() -> RunCommand {
+        RunCommand {
+            recipe: None,
+            inline: None,
+            blocking: false,
+            variables: vec![],
+            render: false,
+            teardown: false,
+        }
+    }
+
+    #[test]
+    fn command_line_variables_take_precedence() {
+        std::env::set_var("run_cmd_relay", "environment");
+        let cmd = RunCommand {
+            variables: vec![("run_cmd_relay".to_string(), "command_line".to_string())],
+            ..run_command()
+        };
+        cmd.set_variables();
+
+        let resolved = Config::resolve(
+            r#"
+            variables:
+              run_cmd_relay: recipe
+
+            relays:
+              - ${run_cmd_relay}
+            "#,
+        )
+        .unwrap();
+        assert!(resolved.contains("- command_line"));
+    }
+
+    #[test]
+    fn recipes_are_identified_by_their_path_or_contents() {
+        let contents = "nodes:\n  - n1\n";
+        let cmd = RunCommand {
+            inline: Some(contents.to_string()),
+            ..run_command()
+        };
+        let (run_id, read_contents) = cmd.read_recipe().unwrap();
+        assert_eq!(run_id, format!("inline:{}", Run::hash_contents(contents)));
+        assert_eq!(read_contents, contents);
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+        let cmd = RunCommand {
+            recipe: Some(file.path().to_path_buf()),
+            ..run_command()
         };
-        Config::parse_and_run(ctx, opts, ValuesOverrides::default(), &contents).await
+        let (run_id, read_contents) = cmd.read_recipe().unwrap();
+        assert_eq!(
+            run_id,
+            file.path()
+                .canonicalize()
+                .unwrap()
+                .to_string_lossy()
+                .to_string()
+        );
+        assert_eq!(read_contents, contents);
     }
 }
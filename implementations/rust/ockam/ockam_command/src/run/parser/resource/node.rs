@@ -20,6 +20,8 @@ pub struct Node {
     pub tcp_listener_address: Option<ArgValue>,
     pub identity: Option<ArgValue>,
     pub project: Option<ArgValue>,
+    #[serde(alias = "connect-on-start")]
+    pub connect_on_start: Option<ArgValue>,
 }
 
 impl Node {
@@ -63,6 +65,9 @@ impl CommandsParser<CreateCommand> for Node {
         if let Some(project) = self.project {
             args.insert("project".to_string(), project);
         }
+        if let Some(connect_on_start) = self.connect_on_start {
+            args.insert("connect-on-start".to_string(), connect_on_start);
+        }
         if args.is_empty() {
             return Ok(vec![]);
         }
@@ -106,6 +111,21 @@ mod tests {
         "#;
         test(config);
 
+        // Connections established when the node starts
+        let config = r#"
+            name: n1
+            connect-on-start: /project/default,/dnsaddr/localhost/tcp/4000/secure/api
+        "#;
+        let parsed: Node = serde_yaml::from_str(config).unwrap();
+        let cmd = parsed
+            .parse_commands(&ValuesOverrides::default())
+            .unwrap()
+            .remove(0);
+        assert_eq!(
+            cmd.connect_on_start.as_deref(),
+            Some("/project/default,/dnsaddr/localhost/tcp/4000/secure/api")
+        );
+
         // With other sections
         let config = r#"
             relays: r1
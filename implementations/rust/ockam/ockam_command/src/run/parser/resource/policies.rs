@@ -30,9 +30,17 @@ impl Policies {
 
 #[async_trait]
 impl CommandsParser<CreateCommand> for Policies {
-    fn parse_commands(self, _overrides: &ValuesOverrides) -> Result<Vec<CreateCommand>> {
+    fn parse_commands(self, overrides: &ValuesOverrides) -> Result<Vec<CreateCommand>> {
         match self.policies {
-            Some(c) => c.into_commands(Self::get_subcommand),
+            Some(c) => {
+                let mut cmds = c.into_commands(Self::get_subcommand)?;
+                if let Some(node_name) = overrides.override_node_name.as_ref() {
+                    for cmd in cmds.iter_mut() {
+                        cmd.at = Some(node_name.clone());
+                    }
+                };
+                Ok(cmds)
+            }
             None => Ok(vec![]),
         }
     }
@@ -106,4 +114,22 @@ mod tests {
             "(= subject.component \"c3\")"
         );
     }
+
+    #[test]
+    fn policy_config_with_node_name_override() {
+        let config = r#"
+            policies:
+              - resource: r1
+                expression: (= subject.component "c1")
+              - at: n2
+                resource-type: tcp-inlet
+                expression: (= subject.component "c2")
+        "#;
+        let parsed: Policies = serde_yaml::from_str(config).unwrap();
+        let overrides = ValuesOverrides::default().with_override_node_name("n1");
+        let cmds = parsed.parse_commands(&overrides).unwrap();
+        assert_eq!(cmds.len(), 2);
+        assert_eq!(cmds[0].at.as_ref().unwrap(), "n1");
+        assert_eq!(cmds[1].at.as_ref().unwrap(), "n1");
+    }
 }
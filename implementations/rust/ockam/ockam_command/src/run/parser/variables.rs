@@ -4,6 +4,7 @@ use colorful::Colorful;
 use miette::{miette, IntoDiagnostic, Result};
 use ockam_api::color_primary;
 use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
 use std::collections::BTreeMap;
 use tracing::warn;
 
@@ -16,6 +17,7 @@ impl Variables {
     pub fn resolve(contents: &str) -> Result<String> {
         let self_ = serde_yaml::from_str::<Variables>(contents).into_diagnostic()?;
         self_.load()?;
+        Self::check_unresolved_variables(contents)?;
         shellexpand::env(&contents)
             .map(|c| c.to_string())
             .map_err(|e| {
@@ -27,6 +29,26 @@ impl Variables {
             })
     }
 
+    /// Return an error listing all the variables which are neither set nor have a default value,
+    /// with the path of each value referencing them, for example `tcp-inlets.web-inlet.from`
+    fn check_unresolved_variables(contents: &str) -> Result<()> {
+        let value = serde_yaml::from_str::<Value>(contents).into_diagnostic()?;
+        let mut unresolved = BTreeMap::new();
+        collect_unresolved_variables(&value, "", &mut unresolved);
+        if unresolved.is_empty() {
+            return Ok(());
+        }
+        let variables = unresolved
+            .iter()
+            .map(|(name, paths)| format!("- {} used by {}", color_primary(name), paths.join(", ")))
+            .collect::<Vec<_>>()
+            .join("\n");
+        Err(miette!(
+            "The following variables could not be resolved:\n{variables}\n\
+            Set them in the environment, in the `variables` section or with `--var NAME=VALUE`"
+        ))
+    }
+
     /// Loads the variables into the environment, giving preference to variables set externally.
     /// That is, if one of the variables already exists, it will use the existing value.
     fn load(&self) -> Result<()> {
@@ -45,6 +67,61 @@ impl Variables {
     }
 }
 
+/// Add the unresolved variables of a YAML value to a map of variables names to value paths
+fn collect_unresolved_variables(
+    value: &Value,
+    path: &str,
+    unresolved: &mut BTreeMap<String, Vec<String>>,
+) {
+    match value {
+        Value::String(value) => {
+            for name in unresolved_variables(value) {
+                unresolved.entry(name).or_default().push(path.to_string());
+            }
+        }
+        Value::Sequence(items) => {
+            for (index, item) in items.iter().enumerate() {
+                collect_unresolved_variables(item, &format!("{path}[{index}]"), unresolved);
+            }
+        }
+        Value::Mapping(entries) => {
+            for (key, item) in entries {
+                let key_name = key
+                    .as_str()
+                    .map(|k| k.to_string())
+                    .unwrap_or_else(|| format!("{key:?}"));
+                let path = if path.is_empty() {
+                    key_name
+                } else {
+                    format!("{path}.{key_name}")
+                };
+                collect_unresolved_variables(key, &path, unresolved);
+                collect_unresolved_variables(item, &path, unresolved);
+            }
+        }
+        Value::Tagged(tagged) => collect_unresolved_variables(&tagged.value, path, unresolved),
+        _ => (),
+    }
+}
+
+/// Return the variables of a string which are not set and have no default value.
+/// The expansion stops at the first unresolved variable, so it is retried until all of them are found
+fn unresolved_variables(value: &str) -> Vec<String> {
+    let mut unresolved: Vec<String> = vec![];
+    loop {
+        let expanded = shellexpand::env_with_context(value, |name| {
+            if unresolved.iter().any(|u| u == name) {
+                return Ok(Some(String::new()));
+            }
+            std::env::var(name).map(Some).map_err(|_| ())
+        });
+        match expanded {
+            Ok(_) => return unresolved,
+            Err(e) => unresolved.push(e.var_name),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -111,4 +188,32 @@ mod tests {
         let resolved = Variables::resolve(input);
         assert!(resolved.is_err());
     }
+
+    #[test]
+    fn list_all_unresolved_variables() {
+        std::env::set_var("INLET_PORT", "4000");
+        let input = r#"
+            variables:
+              node_name: web
+
+            nodes:
+              - ${node_name}
+
+            tcp-inlets:
+              web-inlet:
+                from: ${INLET_PORT}
+                at: ${node_name}
+                via: ${RELAY_NAME}
+                to: ${OUTLET_NAME:-db-outlet}
+
+            relays:
+              - ${RELAY_NAME}-${RELAY_SUFFIX}
+        "#;
+        let error = Variables::resolve(input).unwrap_err().to_string();
+        assert!(error.contains("RELAY_NAME"));
+        assert!(error.contains("tcp-inlets.web-inlet.via, relays[0]"));
+        assert!(error.contains("RELAY_SUFFIX"));
+        assert!(!error.contains("OUTLET_NAME"));
+        assert!(!error.contains("INLET_PORT"));
+    }
 }
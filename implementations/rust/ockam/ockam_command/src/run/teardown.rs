@@ -0,0 +1,316 @@
+use async_trait::async_trait;
+use miette::{miette, Result};
+use serde::Serialize;
+
+use ockam::Context;
+use ockam_api::cli_state::{RunResource, RunResourceKind};
+use ockam_api::nodes::models::portal::{InletList, OutletList};
+use ockam_api::nodes::models::relay::RelayInfo;
+use ockam_api::nodes::service::portals::Inlets;
+use ockam_api::nodes::{BackgroundNodeClient, NodeRequestError};
+use ockam_api::DefaultAddress;
+use ockam_core::api::Request;
+
+use crate::run::parser::config::ConfigParser;
+use crate::run::parser::resource::{
+    CommandsParser, ParsedCommand, ParsedCommands, ValuesOverrides,
+};
+use crate::run::Config;
+use crate::{color_primary, fmt_log, fmt_ok, fmt_warn, node, relay, tcp, CommandGlobalOpts};
+
+/// A command creating a resource which can be deleted with `ockam run --teardown`
+#[async_trait]
+pub trait RunResourceCommand: Send + Sync {
+    /// Return the resource created by this command
+    async fn run_resource(&self, opts: &CommandGlobalOpts) -> Result<RunResource>;
+}
+
+#[async_trait]
+impl RunResourceCommand for node::CreateCommand {
+    async fn run_resource(&self, _opts: &CommandGlobalOpts) -> Result<RunResource> {
+        Ok(RunResource::new(
+            RunResourceKind::Node,
+            &self.name,
+            &self.name,
+        ))
+    }
+}
+
+#[async_trait]
+impl RunResourceCommand for relay::CreateCommand {
+    async fn run_resource(&self, opts: &CommandGlobalOpts) -> Result<RunResource> {
+        let node_name = opts.state.get_node_or_default(&self.to).await?.name();
+        Ok(RunResource::new(
+            RunResourceKind::Relay,
+            &self.relay_name,
+            &node_name,
+        ))
+    }
+}
+
+#[async_trait]
+impl RunResourceCommand for tcp::outlet::create::CreateCommand {
+    async fn run_resource(&self, opts: &CommandGlobalOpts) -> Result<RunResource> {
+        let node_name = opts.state.get_node_or_default(&self.at).await?.name();
+        let address = self
+            .from
+            .clone()
+            .unwrap_or_else(|| DefaultAddress::OUTLET_SERVICE.to_string());
+        Ok(RunResource::new(
+            RunResourceKind::TcpOutlet,
+            &address,
+            &node_name,
+        ))
+    }
+}
+
+#[async_trait]
+impl RunResourceCommand for tcp::inlet::create::CreateCommand {
+    async fn run_resource(&self, opts: &CommandGlobalOpts) -> Result<RunResource> {
+        let node_name = opts.state.get_node_or_default(&self.at).await?.name();
+        Ok(RunResource::new(
+            RunResourceKind::TcpInlet,
+            &self.alias,
+            &node_name,
+        ))
+    }
+}
+
+/// A command recording the resource it creates, once it has been successfully executed
+pub struct RecordedCommand<C> {
+    command: C,
+    run_id: String,
+}
+
+impl<C> RecordedCommand<C>
+where
+    C: RunResourceCommand + ParsedCommand,
+{
+    /// Record the resources created by a list of commands, if they are executed for a run
+    pub fn record(commands: Vec<C>, run_id: Option<&str>) -> ParsedCommands {
+        match run_id {
+            Some(run_id) => ParsedCommands::new(
+                commands
+                    .into_iter()
+                    .map(|command| RecordedCommand {
+                        command,
+                        run_id: run_id.to_string(),
+                    })
+                    .collect(),
+            ),
+            None => commands.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl<C> ParsedCommand for RecordedCommand<C>
+where
+    C: RunResourceCommand + ParsedCommand,
+{
+    async fn is_valid(&self, ctx: &Context, opts: &CommandGlobalOpts) -> Result<bool> {
+        self.command.is_valid(ctx, opts).await
+    }
+
+    async fn run(&self, ctx: &Context, opts: &CommandGlobalOpts) -> Result<()> {
+        self.command.run(ctx, opts).await?;
+        let resource = self.command.run_resource(opts).await?;
+        opts.state.add_run_resource(&self.run_id, &resource).await?;
+        Ok(())
+    }
+}
+
+impl Config {
+    /// Return the resources declared in the configuration which can be deleted, in teardown order.
+    /// The resources hosted by a node which does not exist anymore are skipped
+    pub async fn declared_resources(self, opts: &CommandGlobalOpts) -> Result<Vec<RunResource>> {
+        let overrides = ValuesOverrides::default();
+        let mut resources = vec![];
+        for command in self.nodes.parse_commands(&overrides)? {
+            resources.extend(command.run_resource(opts).await.ok());
+        }
+        for command in self.relays.parse_commands(&overrides)? {
+            resources.extend(command.run_resource(opts).await.ok());
+        }
+        for command in self.tcp_outlets.parse_commands(&overrides)? {
+            resources.extend(command.run_resource(opts).await.ok());
+        }
+        for command in self.tcp_inlets.parse_commands(&overrides)? {
+            resources.extend(command.run_resource(opts).await.ok());
+        }
+        resources.sort();
+        Ok(resources)
+    }
+}
+
+/// Outcome of the deletion of a resource
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum TeardownStatus {
+    Deleted,
+    AlreadyDeleted,
+    Failed,
+}
+
+#[derive(Serialize, Debug)]
+struct TeardownResult {
+    #[serde(flatten)]
+    resource: RunResource,
+    status: TeardownStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Delete the resources created by the executions of a configuration file,
+/// in the reverse order of their dependencies, and report a summary.
+///
+/// If the file was modified since its last execution, or if it was never executed,
+/// the resources declared in the file are deleted instead.
+pub async fn teardown(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    run_id: &str,
+    contents: &str,
+) -> Result<()> {
+    let resources = match opts.state.get_run(run_id).await? {
+        Some(run) if run.has_contents(contents) => run.resources_in_teardown_order(),
+        run => {
+            let reason = if run.is_some() {
+                "The file was modified since it was run."
+            } else {
+                "No resources were recorded for this file."
+            };
+            opts.terminal.write_line(fmt_warn!(
+                "{reason} Deleting the resources declared in the file"
+            ))?;
+            Config::parse(&Config::resolve(contents)?)?
+                .declared_resources(opts)
+                .await?
+        }
+    };
+
+    let mut results = vec![];
+    for resource in resources {
+        let description = format!(
+            "{} {} on node {}",
+            resource.kind(),
+            color_primary(resource.name()),
+            color_primary(resource.node_name())
+        );
+        let result = match delete_resource(ctx, opts, &resource).await {
+            Ok(true) => {
+                opts.terminal
+                    .write_line(fmt_ok!("Deleted the {description}"))?;
+                TeardownResult {
+                    resource,
+                    status: TeardownStatus::Deleted,
+                    error: None,
+                }
+            }
+            Ok(false) => {
+                opts.terminal
+                    .write_line(fmt_log!("The {description} was already deleted"))?;
+                TeardownResult {
+                    resource,
+                    status: TeardownStatus::AlreadyDeleted,
+                    error: None,
+                }
+            }
+            Err(e) => {
+                opts.terminal
+                    .write_line(fmt_warn!("Failed to delete the {description}: {e}"))?;
+                TeardownResult {
+                    resource,
+                    status: TeardownStatus::Failed,
+                    error: Some(e.to_string()),
+                }
+            }
+        };
+        results.push(result);
+    }
+
+    let count = |status| results.iter().filter(|r| r.status == status).count();
+    let (deleted, already_deleted, failed) = (
+        count(TeardownStatus::Deleted),
+        count(TeardownStatus::AlreadyDeleted),
+        count(TeardownStatus::Failed),
+    );
+    opts.terminal
+        .clone()
+        .stdout()
+        .plain(fmt_ok!(
+            "Teardown complete: {deleted} deleted, {already_deleted} already deleted, {failed} failed"
+        ))
+        .json(serde_json::json!(results))
+        .write_line()?;
+
+    if failed > 0 {
+        return Err(miette!(
+            "{failed} resources could not be deleted. Run the teardown again to retry"
+        ));
+    }
+    opts.state.delete_run(run_id).await?;
+    Ok(())
+}
+
+/// Delete a resource and return true, or return false if it was already deleted
+async fn delete_resource(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    resource: &RunResource,
+) -> Result<bool> {
+    let node_name = resource.node_name();
+    let Ok(node_info) = opts.state.get_node(node_name).await else {
+        return Ok(false);
+    };
+    if resource.kind() == RunResourceKind::Node {
+        opts.state.delete_node(node_name, false).await?;
+        return Ok(true);
+    }
+    // the resources of a node are not running anymore once the node is stopped
+    if !node_info.is_running() {
+        return Ok(false);
+    }
+
+    let node = BackgroundNodeClient::create_to_node(ctx, &opts.state, node_name).await?;
+    let name = resource.name();
+    match resource.kind() {
+        RunResourceKind::TcpInlet => {
+            let inlets: InletList = node.ask(ctx, Request::get("/node/inlet")).await?;
+            if !inlets.list.iter().any(|inlet| inlet.alias == name) {
+                return Ok(false);
+            }
+            let reply = node.delete_inlet(ctx, name).await?;
+            NodeRequestError::check_reply(node_name, reply)?;
+        }
+        RunResourceKind::TcpOutlet => {
+            let outlets: OutletList = node.ask(ctx, Request::get("/node/outlet")).await?;
+            if !outlets
+                .list
+                .iter()
+                .any(|outlet| outlet.worker_addr.address() == name)
+            {
+                return Ok(false);
+            }
+            node.tell(ctx, Request::delete(format!("/node/outlet/{name}")))
+                .await?;
+        }
+        RunResourceKind::Relay => {
+            // the relays created at a node are prefixed with `forward_to_`
+            let relays: Vec<RelayInfo> = node.ask(ctx, Request::get("/node/relay")).await?;
+            let Some(relay) = relays
+                .iter()
+                .find(|r| r.alias() == name || r.alias() == format!("forward_to_{name}"))
+            else {
+                return Ok(false);
+            };
+            node.tell(
+                ctx,
+                Request::delete(format!("/node/relay/{}", relay.alias())),
+            )
+            .await?;
+        }
+        RunResourceKind::Node => (),
+    }
+    Ok(true)
+}
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use clap::Args;
 use colorful::Colorful;
 use miette::{miette, IntoDiagnostic, WrapErr};
@@ -21,6 +23,7 @@ use crate::project::util::{
     clean_projects_multiaddr, get_projects_secure_channels_from_config_lookup,
 };
 use crate::util::api::IdentityOpts;
+use crate::util::duration::duration_parser;
 use crate::util::{async_cmd, clean_nodes_multiaddr};
 use crate::{
     docs, error::Error, fmt_log, fmt_ok, terminal::OckamColor, util::exitcode, CommandGlobalOpts,
@@ -57,6 +60,21 @@ pub struct CreateCommand {
     )]
     pub credential: Option<String>,
 
+    /// Renew the keys of the secure channel at this interval, e.g. "1h".
+    /// The secure channel is kept open while its keys are renewed.
+    #[arg(value_name = "DURATION", long, display_order = 803, value_parser = duration_parser)]
+    pub rekey_every: Option<Duration>,
+
+    /// Keep the secure channel under this name, so that it can be reused by other commands
+    /// with a `/channel/<NAME>` segment, e.g. `ockam message send --via-channel <NAME>`
+    #[arg(value_name = "NAME", long, display_order = 804)]
+    pub name: Option<String>,
+
+    /// Create the named secure channel again when it is used after its other side stopped
+    /// answering. Otherwise, using a stale channel returns an error
+    #[arg(long, display_order = 805, requires = "name")]
+    pub auto_reconnect: bool,
+
     #[command(flatten)]
     identity_opts: IdentityOpts,
 }
@@ -134,7 +152,9 @@ impl CreateCommand {
                 authorized_identifiers,
                 Some(identity_name),
                 credential,
-            );
+            )
+            .with_rekey_interval(self.rekey_every)
+            .with_name(self.name.clone(), self.auto_reconnect);
             let request = Request::post("/node/secure_channel").body(payload);
             let response: CreateSecureChannelResponse = node.ask(ctx, request).await?;
             *is_finished.lock().await = true;
@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use clap::Parser;
+use clap::{ArgGroup, Parser};
 use colorful::Colorful;
 use serde_json::json;
 
@@ -25,14 +25,29 @@ arg_required_else_help = true,
 long_about = docs::about(LONG_ABOUT),
 after_long_help = docs::after_help(AFTER_LONG_HELP),
 )]
+#[clap(group(ArgGroup::new("channel").required(true).args(["address", "address_option", "name"])))]
 pub struct DeleteCommand {
-    /// Node at which the secure channel was initiated
+    /// Node at which the secure channel was initiated or accepted
     #[arg(value_name = "NODE", long, display_order = 800, value_parser = extract_address_value)]
     at: Option<String>,
 
     /// Address at which the channel to be deleted is running
     #[arg(value_parser(parse_address), display_order = 800)]
-    address: Address,
+    address: Option<Address>,
+
+    /// Encryptor or decryptor address of the channel to be deleted
+    #[arg(
+        id = "address_option",
+        long = "address",
+        value_name = "ADDRESS",
+        value_parser(parse_address),
+        display_order = 800
+    )]
+    address_option: Option<Address>,
+
+    /// Name of the channel to be deleted, given when it was created
+    #[arg(long, value_name = "NAME", display_order = 800)]
+    name: Option<String>,
 
     /// Confirm the deletion without prompting
     #[arg(display_order = 901, long, short)]
@@ -53,10 +68,15 @@ impl DeleteCommand {
     fn print_output(
         &self,
         node_name: &String,
-        address: &Address,
+        channel: &str,
         options: &CommandGlobalOpts,
         response: DeleteSecureChannelResponse,
     ) {
+        let failed_over: Vec<String> = response
+            .failed_over
+            .iter()
+            .map(|user| user.to_string())
+            .collect();
         match response.channel {
             Some(address) => {
                 let route = &route![address];
@@ -70,7 +90,10 @@ impl DeleteCommand {
 
                         // if output format is json, write json to stdout.
                         if options.global_args.output_format == OutputFormat::Json {
-                            let json = json!([{ "address": multiaddr.to_string() }]);
+                            let json = json!([{
+                                "address": multiaddr.to_string(),
+                                "failed_over": failed_over,
+                            }]);
                             println!("{json}");
                         }
 
@@ -83,7 +106,10 @@ impl DeleteCommand {
                             if options.global_args.no_color {
                                 eprintln!("\n  Deleted Secure Channel:");
                                 eprintln!("  •        At: /node/{}", &node_name);
-                                eprintln!("  •   Address: {}", &self.address);
+                                eprintln!("  •   Address: {}", &multiaddr);
+                                for user in &failed_over {
+                                    eprintln!("  • Restoring: {user}");
+                                }
                             } else {
                                 eprintln!("\n  Deleted Secure Channel:");
 
@@ -93,7 +119,13 @@ impl DeleteCommand {
 
                                 // Address:
                                 eprintln!("{}", "  •   Address: ".light_magenta());
-                                eprintln!("{}", &self.address.to_string().light_yellow());
+                                eprintln!("{}", &multiaddr.to_string().light_yellow());
+
+                                // Resources establishing a new secure channel:
+                                for user in &failed_over {
+                                    eprintln!("{}", "  • Restoring: ".light_magenta());
+                                    eprintln!("{}", user.as_str().light_yellow());
+                                }
                             }
                         }
                     }
@@ -123,12 +155,12 @@ impl DeleteCommand {
                     && options.global_args.output_format == OutputFormat::Plain
                 {
                     eprintln!(
-                        "Could not find secure channel with address {} at node {}",
-                        address, &node_name
+                        "Could not find secure channel {} at node {}",
+                        channel, &node_name
                     );
                 }
 
-                println!("channel with address {address} not found")
+                println!("channel {channel} not found")
             }
         }
     }
@@ -139,10 +171,22 @@ impl DeleteCommand {
             "Are you sure you want to delete this secure channel?",
         )? {
             let node = BackgroundNodeClient::create(ctx, &opts.state, &self.at).await?;
-            let address = &self.address;
-            let response: DeleteSecureChannelResponse =
-                node.ask(ctx, api::delete_secure_channel(address)).await?;
-            self.print_output(&node.node_name(), address, &opts, response);
+            let (channel, response): (String, DeleteSecureChannelResponse) = match (
+                self.address.as_ref().or(self.address_option.as_ref()),
+                &self.name,
+            ) {
+                (Some(address), _) => (
+                    format!("with address {address}"),
+                    node.ask(ctx, api::delete_secure_channel(address)).await?,
+                ),
+                (None, Some(name)) => (
+                    format!("named {name}"),
+                    node.ask(ctx, api::delete_named_secure_channel(name))
+                        .await?,
+                ),
+                (None, None) => unreachable!("an address or a name is required"),
+            };
+            self.print_output(&node.node_name(), &channel, &opts, response);
         }
         Ok(())
     }
@@ -1,19 +1,17 @@
-use std::fmt::Write;
-
 use clap::Args;
-use colorful::Colorful;
-use miette::miette;
+use miette::IntoDiagnostic;
 use tokio::sync::Mutex;
 use tokio::try_join;
 
+use ockam::identity::utils::now;
+use ockam::identity::TimestampInSeconds;
 use ockam::Context;
-use ockam_api::nodes::models::secure_channel::ShowSecureChannelResponse;
+use ockam_api::nodes::models::secure_channel::{SecureChannelList, SecureChannelStatus};
 use ockam_api::nodes::BackgroundNodeClient;
 use ockam_api::route_to_multiaddr;
-use ockam_core::{route, Address};
+use ockam_core::Route;
 
-use crate::output::Output;
-use crate::terminal::OckamColor;
+use crate::output::SecureChannelListOutput;
 use crate::util::async_cmd;
 use crate::{docs, util::api, CommandGlobalOpts};
 
@@ -30,7 +28,7 @@ before_help = docs::before_help(PREVIEW_TAG),
 after_long_help = docs::after_help(AFTER_LONG_HELP),
 )]
 pub struct ListCommand {
-    /// Node at which the returned secure channels were initiated
+    /// Node at which the returned secure channels were initiated or accepted
     #[arg(value_name = "NODE_NAME", long, display_order = 800)]
     at: Option<String>,
 }
@@ -49,121 +47,71 @@ impl ListCommand {
     fn build_output(
         &self,
         node_name: &str,
-        channel_address: &str,
-        show_response: ShowSecureChannelResponse,
-    ) -> crate::Result<SecureChannelListOutput> {
-        let from = node_name.to_string();
-        let at = {
-            let channel_route = &route![channel_address];
-            let channel_multiaddr = route_to_multiaddr(channel_route).ok_or(miette!(
-                "Failed to convert route {channel_route} to multi-address"
-            ))?;
-            channel_multiaddr.to_string()
-        };
-
-        let to = {
-            let show_route = show_response.route.ok_or(miette!(
-                "Failed to retrieve route from show channel response"
-            ))?;
-            show_route
-                .split(" => ")
-                .map(|p| {
-                    let r = route![p];
-                    route_to_multiaddr(&r)
-                        .ok_or(miette!("Failed to convert route {r} to multi-address"))
-                })
-                .collect::<Result<Vec<_>, _>>()?
-                .iter()
-                .map(ToString::to_string)
-                .collect::<Vec<_>>()
-                .join("")
-        };
-
-        Ok(SecureChannelListOutput { from, to, at })
+        channel: SecureChannelStatus,
+        now: TimestampInSeconds,
+    ) -> SecureChannelListOutput {
+        SecureChannelListOutput {
+            from: node_name.to_string(),
+            to: to_multiaddr(&channel.route),
+            at: to_multiaddr(&channel.encryptor_address),
+            direction: if channel.is_initiator {
+                "initiated".to_string()
+            } else {
+                "accepted".to_string()
+            },
+            their_identifier: channel.their_identifier,
+            created_at: TimestampInSeconds(channel.created_at),
+            age_seconds: now.0.saturating_sub(channel.created_at),
+            messages_sent: channel.messages_sent,
+            bytes_sent: channel.bytes_sent,
+            messages_received: channel.messages_received,
+            bytes_received: channel.bytes_received,
+            last_activity_at: channel.last_activity_at.map(TimestampInSeconds),
+            used_by: channel.used_by.iter().map(|u| u.to_string()).collect(),
+        }
     }
 
     async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
         let node = BackgroundNodeClient::create(ctx, &opts.state, &self.at).await?;
 
         let is_finished: Mutex<bool> = Mutex::new(false);
-        let get_secure_channel_identifiers = async {
-            let secure_channel_identifiers: Vec<String> =
+        let get_secure_channels = async {
+            let secure_channels: SecureChannelList =
                 node.ask(ctx, api::list_secure_channels()).await?;
             *is_finished.lock().await = true;
-            Ok(secure_channel_identifiers)
+            Ok(secure_channels)
         };
 
-        let output_messages = vec!["Retrieving secure channel identifiers...\n".to_string()];
+        let output_messages = vec!["Retrieving secure channels...\n".to_string()];
         let progress_output = opts
             .terminal
             .progress_output(&output_messages, &is_finished);
 
-        let (channel_identifiers, _) = try_join!(get_secure_channel_identifiers, progress_output)?;
+        let (secure_channels, _) = try_join!(get_secure_channels, progress_output)?;
 
-        let mut responses = Vec::with_capacity(channel_identifiers.len());
-        for channel_addr in &channel_identifiers {
-            let is_finished: Mutex<bool> = Mutex::new(false);
-            let get_secure_channel_output = async {
-                let request = api::show_secure_channel(&Address::from(channel_addr));
-                let show_response: ShowSecureChannelResponse = node.ask(ctx, request).await?;
-                let secure_channel_output =
-                    self.build_output(&node.node_name(), channel_addr, show_response)?;
-                *is_finished.lock().await = true;
-                Ok(secure_channel_output)
-            };
-            let output_messages = vec![format!(
-                "Retrieving secure channel {}...\n",
-                channel_addr
-                    .to_string()
-                    .color(OckamColor::PrimaryResource.color())
-            )];
-            let progress_output = opts
-                .terminal
-                .progress_output(&output_messages, &is_finished);
-
-            let (secure_channel_output, _) = try_join!(get_secure_channel_output, progress_output)?;
-
-            responses.push(secure_channel_output);
-        }
+        let now = now()?;
+        let responses: Vec<_> = secure_channels
+            .list
+            .into_iter()
+            .map(|channel| self.build_output(&node.node_name(), channel, now))
+            .collect();
 
         let list = opts.terminal.build_list(
             &responses,
             &format!("Secure Channels on {}", node.node_name()),
             &format!("No secure channels found on {}", node.node_name()),
         )?;
-        opts.terminal.stdout().plain(list).write_line()?;
+        let json = serde_json::to_string_pretty(&responses).into_diagnostic()?;
+        opts.terminal.stdout().plain(list).json(json).write_line()?;
 
         Ok(())
     }
 }
 
-pub struct SecureChannelListOutput {
-    pub from: String,
-    pub to: String,
-    pub at: String,
-}
-
-impl Output for SecureChannelListOutput {
-    fn output(&self) -> crate::Result<String> {
-        let mut output = String::new();
-        writeln!(
-            output,
-            "From {} to {} ",
-            self.from
-                .to_string()
-                .color(OckamColor::PrimaryResource.color()),
-            self.to
-                .to_string()
-                .color(OckamColor::PrimaryResource.color())
-        )?;
-        write!(
-            output,
-            "At {}",
-            self.at
-                .to_string()
-                .color(OckamColor::PrimaryResource.color())
-        )?;
-
-        Ok(output)
-    }
+/// Display a route as a multi-address when possible, e.g. `/service/<address>`
+fn to_multiaddr(route: &str) -> String {
+    Route::parse(route)
+        .and_then(|route| route_to_multiaddr(&route))
+        .map(|multiaddr| multiaddr.to_string())
+        .unwrap_or_else(|| route.to_string())
 }
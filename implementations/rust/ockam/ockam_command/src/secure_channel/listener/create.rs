@@ -39,6 +39,11 @@ pub struct CreateCommand {
     /// If it is different from the default node identity
     #[arg(value_name = "IDENTITY_NAME", long)]
     identity: Option<String>,
+
+    /// Maximum number of secure channel handshakes started per second.
+    /// The handshake messages exceeding this rate are dropped
+    #[arg(long, value_name = "HANDSHAKES_PER_SECOND", value_parser = clap::value_parser!(u32).range(1..))]
+    max_handshakes_per_second: Option<u32>,
 }
 
 impl CreateCommand {
@@ -60,7 +65,8 @@ impl CreateCommand {
                 &self.address,
                 self.authorized.clone(),
                 self.identity.clone(),
-            ),
+            )
+            .with_max_handshakes_per_second(self.max_handshakes_per_second),
         );
         let result = node.tell(ctx, req).await;
         match result {
@@ -98,12 +104,18 @@ pub async fn create_listener(
     addr: Address,
     authorized_identifiers: Option<Vec<Identifier>>,
     identity: Option<String>,
+    max_handshakes_per_second: Option<u32>,
     mut base_route: Route,
 ) -> miette::Result<()> {
     let resp: Vec<u8> = ctx
         .send_and_receive(
             base_route.modify().append(NODEMANAGER_ADDR),
-            api::create_secure_channel_listener(&addr, authorized_identifiers, identity)?,
+            api::create_secure_channel_listener(
+                &addr,
+                authorized_identifiers,
+                identity,
+                max_handshakes_per_second,
+            )?,
         )
         .await
         .into_diagnostic()?;
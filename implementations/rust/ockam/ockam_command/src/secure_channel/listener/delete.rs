@@ -50,7 +50,8 @@ impl DeleteCommand {
                 "Deleted secure-channel listener with address '{addr}' on node '{}'",
                 node.node_name()
             ))
-            .machine(addr)
+            .machine(&addr)
+            .json(serde_json::json!({ "address": addr.to_string(), "node": node.node_name() }))
             .write_line()?;
         Ok(())
     }
@@ -1,6 +1,6 @@
 use clap::Args;
 use colorful::Colorful;
-use miette::miette;
+use miette::{miette, IntoDiagnostic};
 use tokio::sync::Mutex;
 use tokio::try_join;
 
@@ -12,6 +12,7 @@ use ockam_api::nodes::BackgroundNodeClient;
 use ockam_api::route_to_multiaddr;
 use ockam_core::route;
 
+use crate::node::models::secure_channel::ShowSecureChannelListener;
 use crate::node::NodeOpts;
 use crate::output::Output;
 use crate::terminal::OckamColor;
@@ -76,7 +77,15 @@ impl ListCommand {
                 node.node_name()
             ),
         )?;
-        opts.terminal.stdout().plain(list).write_line()?;
+        let json = serde_json::to_string_pretty(
+            &secure_channel_listeners
+                .list
+                .into_iter()
+                .map(ShowSecureChannelListener::from)
+                .collect::<Vec<_>>(),
+        )
+        .into_diagnostic()?;
+        opts.terminal.stdout().plain(list).json(json).write_line()?;
 
         Ok(())
     }
@@ -93,6 +102,35 @@ impl Output for ShowSecureChannelListenerResponse {
         }
         .color(OckamColor::PrimaryResource.color());
 
-        Ok(format!("Address {addr}"))
+        let mut output = format!("Address {addr}\n{}", authorized_identifiers_output(self));
+        if let Some(max_handshakes_per_second) = self.max_handshakes_per_second {
+            output.push_str(&format!(
+                "\nHandshakes: {}/{max_handshakes_per_second} per second, {} dropped",
+                self.handshakes_per_second.unwrap_or_default(),
+                self.rate_limited_handshakes.unwrap_or_default()
+            ));
+        }
+        Ok(output)
+    }
+}
+
+/// Describe the identities which can initiate a secure channel with a listener
+pub(crate) fn authorized_identifiers_output(
+    listener: &ShowSecureChannelListenerResponse,
+) -> String {
+    match &listener.authorized_identifiers {
+        None => "Authorized identities: any".to_string(),
+        Some(identifiers) if identifiers.is_empty() => "Authorized identities: none".to_string(),
+        Some(identifiers) => format!(
+            "Authorized identities: {}",
+            identifiers
+                .iter()
+                .map(|i| i
+                    .to_string()
+                    .color(OckamColor::PrimaryResource.color())
+                    .to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
     }
 }
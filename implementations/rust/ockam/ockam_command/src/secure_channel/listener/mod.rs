@@ -2,11 +2,13 @@ pub mod create;
 pub mod delete;
 pub mod list;
 pub mod show;
+pub mod update;
 
 pub(crate) use create::CreateCommand;
 pub(crate) use delete::DeleteCommand;
 pub(crate) use list::ListCommand;
 pub(crate) use show::ShowCommand;
+pub(crate) use update::UpdateCommand;
 
 use crate::CommandGlobalOpts;
 use clap::{Args, Subcommand};
@@ -29,6 +31,8 @@ pub enum SecureChannelListenerSubcommand {
     List(ListCommand),
     #[command(display_order = 803)]
     Show(ShowCommand),
+    #[command(display_order = 804)]
+    Update(UpdateCommand),
 }
 
 impl SecureChannelListenerCommand {
@@ -38,6 +42,7 @@ impl SecureChannelListenerCommand {
             SecureChannelListenerSubcommand::Delete(c) => c.run(opts),
             SecureChannelListenerSubcommand::List(c) => c.run(opts),
             SecureChannelListenerSubcommand::Show(c) => c.run(opts),
+            SecureChannelListenerSubcommand::Update(c) => c.run(opts),
         }
     }
 
@@ -47,6 +52,7 @@ impl SecureChannelListenerCommand {
             SecureChannelListenerSubcommand::Delete(c) => c.name(),
             SecureChannelListenerSubcommand::List(c) => c.name(),
             SecureChannelListenerSubcommand::Show(c) => c.name(),
+            SecureChannelListenerSubcommand::Update(c) => c.name(),
         }
     }
 }
@@ -1,10 +1,14 @@
 use clap::Args;
+use miette::IntoDiagnostic;
 
 use ockam::Context;
+use ockam_api::nodes::models::secure_channel::ShowSecureChannelListenerResponse;
 use ockam_api::nodes::BackgroundNodeClient;
 use ockam_core::Address;
 
+use crate::node::models::secure_channel::ShowSecureChannelListener;
 use crate::node::NodeOpts;
+use crate::output::Output;
 use crate::util::{api, async_cmd};
 use crate::{docs, CommandGlobalOpts};
 
@@ -42,10 +46,15 @@ impl ShowCommand {
         let node = BackgroundNodeClient::create(ctx, &opts.state, &self.node_opts.at_node).await?;
         let address = &self.address;
         let req = api::show_secure_channel_listener(address);
-        node.tell(ctx, req).await?;
+        let listener: ShowSecureChannelListenerResponse = node.ask(ctx, req).await?;
         opts.terminal
             .stdout()
-            .plain(format!("/service/{}", self.address.address()))
+            .plain(listener.output()?)
+            .machine(format!("/service/{}", self.address.address()))
+            .json(
+                serde_json::to_string_pretty(&ShowSecureChannelListener::from(listener))
+                    .into_diagnostic()?,
+            )
             .write_line()?;
         Ok(())
     }
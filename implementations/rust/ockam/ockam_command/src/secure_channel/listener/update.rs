@@ -0,0 +1,86 @@
+use clap::{ArgGroup, Args};
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+
+use ockam::identity::Identifier;
+use ockam::Context;
+use ockam_api::nodes::models::secure_channel::ShowSecureChannelListenerResponse;
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_core::Address;
+
+use crate::node::models::secure_channel::ShowSecureChannelListener;
+use crate::node::NodeOpts;
+use crate::secure_channel::listener::list::authorized_identifiers_output;
+use crate::terminal::OckamColor;
+use crate::util::{api, async_cmd};
+use crate::{docs, fmt_log, fmt_ok, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/update/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/update/after_long_help.txt");
+
+/// Update the identities authorized by a Secure Channel Listener
+#[derive(Clone, Debug, Args)]
+#[command(
+arg_required_else_help = true,
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP),
+)]
+#[clap(group(ArgGroup::new("changes").required(true).multiple(true).args(["set", "add", "remove"])))]
+pub struct UpdateCommand {
+    /// Address of the channel listener
+    address: Address,
+
+    #[command(flatten)]
+    node_opts: NodeOpts,
+
+    /// Replace the authorized Identifiers of secure channel initiators
+    #[arg(long, value_name = "IDENTIFIERS")]
+    set: Option<Vec<Identifier>>,
+
+    /// Authorize an additional Identifier
+    #[arg(long, value_name = "IDENTIFIER")]
+    add: Vec<Identifier>,
+
+    /// Stop authorizing an Identifier
+    #[arg(long, value_name = "IDENTIFIER")]
+    remove: Vec<Identifier>,
+}
+
+impl UpdateCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |ctx| async move {
+            self.async_run(&ctx, opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "update secure channel listener".into()
+    }
+
+    async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.node_opts.at_node).await?;
+        let req = api::update_secure_channel_listener(
+            &self.address,
+            self.set.clone(),
+            self.add.clone(),
+            self.remove.clone(),
+        );
+        let response: ShowSecureChannelListenerResponse = node.ask(ctx, req).await?;
+        let address = format!("/service/{}", self.address.address());
+        opts.terminal
+            .stdout()
+            .plain(
+                fmt_ok!(
+                    "Secure Channel Listener at {} updated successfully\n",
+                    address.color(OckamColor::PrimaryResource.color())
+                ) + &fmt_log!("{}", authorized_identifiers_output(&response)),
+            )
+            .machine(address)
+            .json(
+                serde_json::to_string_pretty(&ShowSecureChannelListener::from(response))
+                    .into_diagnostic()?,
+            )
+            .write_line()?;
+        Ok(())
+    }
+}
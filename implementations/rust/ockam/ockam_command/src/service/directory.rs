@@ -0,0 +1,179 @@
+use std::str::FromStr;
+
+use miette::{miette, IntoDiagnostic};
+
+use ockam::Context;
+use ockam_api::authenticator::service_directory::ServiceDirectory;
+use ockam_api::authenticator::PublishedService;
+use ockam_api::cloud::project::Project;
+use ockam_api::nodes::InMemoryNode;
+use ockam_multiaddr::MultiAddr;
+
+use crate::CommandGlobalOpts;
+
+/// Prefix of a route given as the name of a service published in the project directory,
+/// e.g. `discover:postgres-prod`
+pub(crate) const DISCOVER_PREFIX: &str = "discover:";
+
+/// Return the route used by project members to reach an outlet through a relay in the project
+pub(crate) fn published_outlet_route(
+    project_name: &str,
+    relay_address: &str,
+    outlet_address: &str,
+) -> miette::Result<MultiAddr> {
+    MultiAddr::from_str(&format!(
+        "/project/{project_name}/service/{relay_address}/secure/api/service/{outlet_address}"
+    ))
+    .into_diagnostic()
+}
+
+/// Return the name of the identity used by a node
+pub(crate) async fn node_identity_name(
+    opts: &CommandGlobalOpts,
+    node_name: &str,
+) -> miette::Result<String> {
+    let identifier = opts.state.get_node(node_name).await?.identifier();
+    Ok(opts
+        .state
+        .get_named_identity_by_identifier(&identifier)
+        .await?
+        .name())
+}
+
+/// Publish a service in the directory of the project authority, for the given identity
+pub(crate) async fn publish_service(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    project: &Project,
+    identity_name: &str,
+    name: &str,
+    route: &MultiAddr,
+    description: Option<String>,
+) -> miette::Result<()> {
+    let node = InMemoryNode::builder()
+        .project_name(project.name())
+        .build(ctx, &opts.state)
+        .await?;
+    node.create_authority_client(project, Some(identity_name.to_string()))
+        .await?
+        .publish_service(ctx, name, route, description)
+        .await
+}
+
+/// Return the services published in the directory of the project authority
+pub(crate) async fn list_published_services(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    project: &Project,
+    identity_name: Option<String>,
+) -> miette::Result<Vec<PublishedService>> {
+    let node = InMemoryNode::builder()
+        .project_name(project.name())
+        .build(ctx, &opts.state)
+        .await?;
+    node.create_authority_client(project, identity_name)
+        .await?
+        .list_published_services(ctx)
+        .await
+}
+
+/// Return the route of the service published under a name in the default project
+pub(crate) async fn resolve_published_service(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    name: &str,
+    identity_name: Option<String>,
+) -> miette::Result<MultiAddr> {
+    let project = opts.state.projects().get_default_project().await?;
+    let services = list_published_services(ctx, opts, &project, identity_name).await?;
+    select_published_service(&services, name, project.name())
+}
+
+/// Return the route of the only service published under a name, or an error if no service,
+/// or several services published by different identities, have this name
+fn select_published_service(
+    services: &[PublishedService],
+    name: &str,
+    project_name: &str,
+) -> miette::Result<MultiAddr> {
+    let matching: Vec<&PublishedService> = services.iter().filter(|s| s.name() == name).collect();
+    match matching.as_slice() {
+        [] => Err(miette!(
+            help = format!("Run `ockam service discover --project {project_name}` to list the published services"),
+            "No service named {name} is published in the project {project_name}"
+        )),
+        [service] => MultiAddr::from_str(service.route()).into_diagnostic(),
+        services => Err(miette!(
+            help = format!(
+                "Use the route of one of these services instead:\n{}",
+                services
+                    .iter()
+                    .map(|s| format!("{} (published by {})", s.route(), s.identifier()))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ),
+            "The name {name} is published by {} identities in the project {project_name}",
+            services.len()
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam::identity::{Identifier, TimestampInSeconds};
+
+    fn published_service(name: &str, identifier: &str, route: &str) -> PublishedService {
+        PublishedService::new(
+            name,
+            Identifier::from_str(identifier).unwrap(),
+            route,
+            None,
+            TimestampInSeconds(0),
+        )
+    }
+
+    #[test]
+    fn the_route_of_a_published_outlet_goes_through_a_project_relay() {
+        let route = published_outlet_route("p", "forward_to_db", "postgres").unwrap();
+        assert_eq!(
+            route.to_string(),
+            "/project/p/service/forward_to_db/secure/api/service/postgres"
+        );
+    }
+
+    #[test]
+    fn a_published_service_is_selected_by_its_name() {
+        let alice = "I0000000000000000000000000000000000000000000000000000000000000001";
+        let bob = "I0000000000000000000000000000000000000000000000000000000000000002";
+        let services = vec![
+            published_service(
+                "api",
+                alice,
+                "/project/p/service/forward_to_api/secure/api/service/outlet",
+            ),
+            published_service(
+                "db",
+                alice,
+                "/project/p/service/forward_to_db1/secure/api/service/outlet",
+            ),
+            published_service(
+                "db",
+                bob,
+                "/project/p/service/forward_to_db2/secure/api/service/outlet",
+            ),
+        ];
+
+        let route = select_published_service(&services, "api", "p").unwrap();
+        assert_eq!(
+            route.to_string(),
+            "/project/p/service/forward_to_api/secure/api/service/outlet"
+        );
+
+        let error = select_published_service(&services, "missing", "p").unwrap_err();
+        assert!(error.to_string().contains("No service named missing"));
+
+        let error = select_published_service(&services, "db", "p").unwrap_err();
+        assert!(error.to_string().contains("published by 2 identities"));
+    }
+}
@@ -0,0 +1,102 @@
+use std::fmt::Write;
+
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+
+use ockam::Context;
+use ockam_api::authenticator::PublishedService;
+
+use crate::output::{human_readable_time, Output};
+use crate::service::directory::list_published_services;
+use crate::terminal::OckamColor;
+use crate::util::api::IdentityOpts;
+use crate::util::async_cmd;
+use crate::CommandGlobalOpts;
+
+/// List the services published in the directory of a project,
+/// with `ockam tcp-outlet create --publish` or `ockam relay create --publish`
+#[derive(Clone, Debug, Args)]
+pub struct DiscoverCommand {
+    /// Only list the services published under this name
+    #[arg(value_name = "NAME")]
+    pub name: Option<String>,
+
+    /// Project name to use for the command, defaults to the default project
+    #[arg(long = "project", value_name = "PROJECT_NAME")]
+    pub project_name: Option<String>,
+
+    #[command(flatten)]
+    pub identity_opts: IdentityOpts,
+}
+
+impl DiscoverCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |ctx| async move {
+            self.async_run(&ctx, opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "service discover".into()
+    }
+
+    async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let project = opts
+            .state
+            .projects()
+            .get_project_by_name_or_default(&self.project_name)
+            .await?;
+        let services: Vec<PublishedService> =
+            list_published_services(ctx, &opts, &project, self.identity_opts.identity.clone())
+                .await?
+                .into_iter()
+                .filter(|s| self.name.as_ref().map_or(true, |name| s.name() == name))
+                .collect();
+
+        let plain = opts.terminal.build_list(
+            &services,
+            &format!("Services published in the project {}", project.name()),
+            &format!("No services published in the project {}", project.name()),
+        )?;
+        let json = serde_json::to_string_pretty(&services).into_diagnostic()?;
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .json(json)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+impl Output for PublishedService {
+    fn output(&self) -> crate::Result<String> {
+        let mut output = String::new();
+        writeln!(
+            output,
+            "Service {}",
+            self.name().color(OckamColor::PrimaryResource.color())
+        )?;
+        writeln!(
+            output,
+            "Route {}",
+            self.route().color(OckamColor::PrimaryResource.color())
+        )?;
+        writeln!(
+            output,
+            "Published by {}",
+            self.identifier()
+                .to_string()
+                .color(OckamColor::PrimaryResource.color())
+        )?;
+        if let Some(description) = self.description() {
+            writeln!(output, "Description {description}")?;
+        }
+        write!(
+            output,
+            "Published at {}",
+            human_readable_time(self.published_at()).color(OckamColor::PrimaryResource.color())
+        )?;
+        Ok(output)
+    }
+}
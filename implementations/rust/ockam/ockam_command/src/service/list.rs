@@ -11,7 +11,7 @@ use ockam_api::nodes::models::services::{ServiceList, ServiceStatus};
 use ockam_api::nodes::BackgroundNodeClient;
 
 use crate::node::NodeOpts;
-use crate::output::Output;
+use crate::output::{human_readable_time, Output};
 use crate::terminal::OckamColor;
 use crate::util::{api, async_cmd};
 use crate::CommandGlobalOpts;
@@ -92,6 +92,16 @@ impl Output for ServiceStatus {
                 .to_string()
                 .color(OckamColor::PrimaryResource.color())
         )?;
+        if let Some(started_at) = self.started_at {
+            write!(
+                output,
+                "\nStarted at {}",
+                human_readable_time(started_at).color(OckamColor::PrimaryResource.color())
+            )?;
+        }
+        if let Some(broker_auth) = &self.broker_auth {
+            write!(output, "\nBroker auth {broker_auth}")?;
+        }
 
         Ok(output)
     }
@@ -1,6 +1,9 @@
 pub(crate) mod config;
+pub(crate) mod directory;
+pub(crate) mod discover;
 pub(crate) mod list;
 pub(crate) mod start;
+pub(crate) mod stop;
 pub(crate) mod util;
 
 pub(crate) use start::StartCommand;
@@ -9,7 +12,9 @@ use crate::docs;
 use crate::CommandGlobalOpts;
 use clap::{Args, Subcommand};
 
+use discover::DiscoverCommand;
 use list::ListCommand;
+use stop::StopCommand;
 
 #[derive(Clone, Debug, Args)]
 #[command(hide = docs::hide())]
@@ -24,6 +29,10 @@ pub enum ServiceSubcommand {
     Start(StartCommand),
     #[command(display_order = 901)]
     List(ListCommand),
+    #[command(display_order = 902)]
+    Stop(StopCommand),
+    #[command(display_order = 903)]
+    Discover(DiscoverCommand),
 }
 
 impl ServiceCommand {
@@ -31,6 +40,8 @@ impl ServiceCommand {
         match self.subcommand {
             ServiceSubcommand::Start(c) => c.run(opts),
             ServiceSubcommand::List(c) => c.run(opts),
+            ServiceSubcommand::Stop(c) => c.run(opts),
+            ServiceSubcommand::Discover(c) => c.run(opts),
         }
     }
 
@@ -38,6 +49,8 @@ impl ServiceCommand {
         match &self.subcommand {
             ServiceSubcommand::Start(c) => c.name(),
             ServiceSubcommand::List(c) => c.name(),
+            ServiceSubcommand::Stop(c) => c.name(),
+            ServiceSubcommand::Discover(c) => c.name(),
         }
     }
 }
@@ -4,6 +4,8 @@ use miette::miette;
 use minicbor::Encode;
 
 use ockam::Context;
+use ockam_api::bench_target::BenchTargetMode;
+use ockam_api::nodes::models::services::{BuiltinService, ServiceStatus};
 use ockam_api::nodes::service::default_address::DefaultAddress;
 use ockam_api::nodes::BackgroundNodeClient;
 use ockam_core::api::Request;
@@ -25,16 +27,49 @@ pub struct StartCommand {
 
 #[derive(Clone, Debug, Subcommand)]
 pub enum StartSubCommand {
+    /// Start a service which sends back the messages it receives
+    Echo {
+        #[arg(long, default_value_t = echo_default_addr())]
+        addr: String,
+    },
+    /// Start a service which forwards the messages it receives to the next address of their route
     Hop {
         #[arg(long, default_value_t = hop_default_addr())]
         addr: String,
     },
+    /// Start a service which drops the messages it receives, to measure the throughput of a route
+    Discard {
+        #[arg(long, default_value_t = discard_default_addr())]
+        addr: String,
+    },
+    /// Start a TCP outlet to a local server which echoes the data it receives,
+    /// to be used as the target of `ockam bench portal`
+    Bench {
+        #[arg(long, default_value_t = bench_default_addr())]
+        addr: String,
+
+        /// Discard the data instead of echoing it
+        #[arg(long)]
+        discard: bool,
+    },
+}
+
+fn echo_default_addr() -> String {
+    DefaultAddress::ECHO_SERVICE.to_string()
 }
 
 fn hop_default_addr() -> String {
     DefaultAddress::HOP_SERVICE.to_string()
 }
 
+fn discard_default_addr() -> String {
+    DefaultAddress::DISCARD_SERVICE.to_string()
+}
+
+fn bench_default_addr() -> String {
+    DefaultAddress::BENCH_SERVICE.to_string()
+}
+
 impl StartCommand {
     pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
         async_cmd(&self.name(), opts.clone(), |ctx| async move {
@@ -49,13 +84,33 @@ impl StartCommand {
     async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
         let node = BackgroundNodeClient::create(ctx, &opts.state, &self.node_opts.at_node).await?;
         let addr = match &self.create_subcommand {
-            StartSubCommand::Hop { addr, .. } => {
-                start_hop_service(ctx, &node, addr).await?;
+            StartSubCommand::Echo { addr } => {
+                start_builtin_service(ctx, &node, BuiltinService::Echo, addr).await?;
+                addr
+            }
+            StartSubCommand::Hop { addr } => {
+                start_builtin_service(ctx, &node, BuiltinService::Hop, addr).await?;
                 opts.terminal.write_line(&fmt_warn!(
                     "SECURITY WARNING: Don't use Hop service in production nodes"
                 ))?;
                 addr
             }
+            StartSubCommand::Discard { addr } => {
+                start_builtin_service(ctx, &node, BuiltinService::Discard, addr).await?;
+                addr
+            }
+            StartSubCommand::Bench { addr, discard } => {
+                let mode = if *discard {
+                    BenchTargetMode::Discard
+                } else {
+                    BenchTargetMode::Echo
+                };
+                start_bench_service(ctx, &node, addr, mode).await?;
+                opts.terminal.write_line(&fmt_warn!(
+                    "SECURITY WARNING: Don't use Bench service in production nodes"
+                ))?;
+                addr
+            }
         };
 
         opts.terminal.write_line(&fmt_ok!(
@@ -83,12 +138,25 @@ where
         .map_err(|e| miette!("Failed to start {} service: {e:?}", serv_name))?)
 }
 
-/// Public so `ockam_command::node::create` can use it.
-pub async fn start_hop_service(
+/// Start a built-in service, which can then be stopped with `ockam service stop`
+pub async fn start_builtin_service(
+    ctx: &Context,
+    node: &BackgroundNodeClient,
+    service: BuiltinService,
+    serv_addr: &str,
+) -> Result<ServiceStatus> {
+    Ok(node
+        .ask(ctx, api::start_builtin_service(service, serv_addr))
+        .await
+        .map_err(|e| miette!("Failed to start {} service: {e:?}", service.service_type()))?)
+}
+
+pub async fn start_bench_service(
     ctx: &Context,
     node: &BackgroundNodeClient,
     serv_addr: &str,
+    mode: BenchTargetMode,
 ) -> Result<()> {
-    let req = api::start_hop_service(serv_addr);
-    start_service_impl(ctx, node, "Hop", req).await
+    let req = api::start_bench_service(serv_addr, mode);
+    start_service_impl(ctx, node, "Bench", req).await
 }
@@ -0,0 +1,52 @@
+use clap::Args;
+use colorful::Colorful;
+
+use ockam::Context;
+use ockam_api::nodes::BackgroundNodeClient;
+
+use crate::node::NodeOpts;
+use crate::terminal::OckamColor;
+use crate::util::{api, async_cmd};
+use crate::{fmt_ok, CommandGlobalOpts};
+
+/// Stop a service started with `ockam service start`
+#[derive(Clone, Debug, Args)]
+pub struct StopCommand {
+    /// Address of the service
+    #[arg(long)]
+    pub addr: String,
+
+    #[command(flatten)]
+    pub node_opts: NodeOpts,
+}
+
+impl StopCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |ctx| async move {
+            self.async_run(&ctx, opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "service stop".into()
+    }
+
+    async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.node_opts.at_node).await?;
+        node.tell(ctx, api::stop_builtin_service(&self.addr))
+            .await?;
+
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "Service at address {} stopped",
+                self.addr
+                    .to_string()
+                    .color(OckamColor::PrimaryResource.color())
+            ))
+            .json(serde_json::json!({ "address": &self.addr }))
+            .write_line()?;
+
+        Ok(())
+    }
+}
@@ -6,26 +6,36 @@ use std::io::Read;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use tokio::sync::mpsc::{Receiver, Sender};
-use tracing::info;
+use tracing::{info, warn};
+
+/// Event which triggered a shutdown
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShutdownEvent {
+    /// SIGINT, SIGTERM or SIGHUP
+    Signal,
+    /// STDIN was closed
+    Eof,
+}
 
 /// Waits for CTRL+C, EOF or a signal to exit, can provide extra shutdown events by
-/// sending a message through the channel
+/// sending a message through the channel.
+/// Once this function has returned, any further signal terminates the process immediately
 pub async fn wait(
     terminal: Terminal<TerminalStream<Term>>,
     exit_on_eof: bool,
     quiet: bool,
-    tx: Sender<()>,
-    rx: &mut Receiver<()>,
-) -> miette::Result<bool> {
+    tx: Sender<ShutdownEvent>,
+    rx: &mut Receiver<ShutdownEvent>,
+) -> miette::Result<Option<ShutdownEvent>> {
     // Register a handler for SIGINT, SIGTERM, SIGHUP
     {
         let tx = tx.clone();
         let terminal = terminal.clone();
-        // avoid printing CTRL+C multiple times
+        // the first signal starts a graceful shutdown, a second one forces an immediate exit
         let flag = Arc::new(AtomicBool::new(true));
         ctrlc::set_handler(move || {
             if flag.load(std::sync::atomic::Ordering::Relaxed) {
-                let _ = tx.blocking_send(());
+                let _ = tx.blocking_send(ShutdownEvent::Signal);
                 info!("Ctrl+C signal received");
                 if !quiet {
                     let _ = terminal.write_line(
@@ -33,32 +43,58 @@ pub async fn wait(
                     );
                 }
                 flag.store(false, std::sync::atomic::Ordering::Relaxed);
+            } else {
+                // the handler is also called for SIGTERM and SIGHUP, so the signal is not named
+                info!("Second stop signal received, exiting immediately");
+                if !quiet {
+                    let _ = terminal.write_line(
+                        format!(
+                            "{} Second stop signal received, exiting immediately",
+                            "!".light_yellow()
+                        )
+                        .as_str(),
+                    );
+                }
+                std::process::exit(1);
             }
         })
         .expect("Error setting Ctrl+C handler");
     }
 
     if exit_on_eof {
-        // Spawn a thread to monitor STDIN for EOF
-        {
-            let tx = tx.clone();
-            let terminal = terminal.clone();
-            std::thread::spawn(move || {
-                let mut buffer = Vec::new();
-                let mut handle = io::stdin().lock();
-                handle
-                    .read_to_end(&mut buffer)
-                    .expect("Error reading from stdin");
-                let _ = tx.blocking_send(());
-                info!("EOF received");
-                if !quiet {
-                    let _ = terminal
-                        .write_line(format!("{} EOF received", "!".light_yellow()).as_str());
-                }
-            });
-        }
+        // Spawn a thread to monitor STDIN for EOF.
+        // This function is called once the node is started, after the arguments read
+        // from STDIN. The input is discarded as it is read, so that it is not buffered until EOF
+        let tx = tx.clone();
+        let terminal = terminal.clone();
+        std::thread::spawn(move || {
+            wait_for_eof();
+            let _ = tx.blocking_send(ShutdownEvent::Eof);
+            info!("EOF received");
+            if !quiet {
+                let _ =
+                    terminal.write_line(format!("{} EOF received", "!".light_yellow()).as_str());
+            }
+        });
     }
 
     // Shutdown on SIGINT, SIGTERM, SIGHUP or EOF
-    Ok(rx.recv().await.is_some())
+    Ok(rx.recv().await)
+}
+
+/// Read STDIN until it is closed. A read error is handled like a closed input
+fn wait_for_eof() {
+    let mut buffer = [0u8; 1024];
+    let mut stdin = io::stdin().lock();
+    loop {
+        match stdin.read(&mut buffer) {
+            Ok(0) => return,
+            Ok(_) => continue,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => {
+                warn!(%e, "Failed to read from stdin, the input is considered closed");
+                return;
+            }
+        }
+    }
 }
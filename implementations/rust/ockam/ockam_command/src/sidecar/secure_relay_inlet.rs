@@ -29,6 +29,11 @@ pub struct SecureRelayInlet {
     #[arg(long, display_order = 900, id = "SOCKET_ADDRESS", default_value_t = default_from_addr(), value_parser = socket_addr_parser)]
     from: SocketAddr,
 
+    /// Accept connections on an address reachable from other machines, such as 0.0.0.0,
+    /// even though no policy restricts the access to the inlet
+    #[arg(long)]
+    allow_insecure_bind: bool,
+
     /// Just print the recipe and exit
     #[arg(long)]
     dry_run: bool,
@@ -103,13 +108,18 @@ impl SecureRelayInlet {
             "".to_string()
         };
 
+        let allow_insecure_bind = if self.allow_insecure_bind {
+            "\n    allow-insecure-bind: true"
+        } else {
+            ""
+        };
         let recipe: String = formatdoc! {
             r#"
             {projects}
             tcp-inlets:
               {service_name}:
                 from: {from}
-                to: {service_name}
+                to: {service_name}{allow_insecure_bind}
             "#,
             from = self.from,
             service_name = self.service_name,
@@ -133,9 +143,10 @@ mod tests {
         let enrollment_ticket = EnrollmentTicket::new(OneTimeCode::new(), None);
         let enrollment_ticket_hex = enrollment_ticket.hex_encoded().unwrap();
 
-        let cmd = SecureRelayInlet {
+        let mut cmd = SecureRelayInlet {
             service_name: "service_name".to_string(),
             from: SocketAddr::from_str("127.0.0.1:8080").unwrap(),
+            allow_insecure_bind: false,
             dry_run: false,
             enroll: Enroll {
                 enroll_ticket: Some(enrollment_ticket_hex),
@@ -146,6 +157,18 @@ mod tests {
         let config = Config::parse(config_recipe.as_str()).unwrap();
         let overrides = ValuesOverrides::default();
         config.project_enroll.parse_commands(&overrides).unwrap();
-        config.tcp_inlets.parse_commands(&overrides).unwrap();
+        let inlets = config.tcp_inlets.parse_commands(&overrides).unwrap();
+        assert!(!inlets[0].allow_insecure_bind);
+
+        // an inlet reachable from other machines requires an explicit opt-in
+        cmd.from = SocketAddr::from_str("0.0.0.0:8080").unwrap();
+        let config = Config::parse(cmd.create_config_recipe().as_str()).unwrap();
+        let inlets = config.tcp_inlets.parse_commands(&overrides).unwrap();
+        assert!(!inlets[0].allow_insecure_bind);
+
+        cmd.allow_insecure_bind = true;
+        let config = Config::parse(cmd.create_config_recipe().as_str()).unwrap();
+        let inlets = config.tcp_inlets.parse_commands(&overrides).unwrap();
+        assert!(inlets[0].allow_insecure_bind);
     }
 }
@@ -1,16 +1,18 @@
-use clap::Args;
+use clap::{Args, ValueEnum};
 use miette::IntoDiagnostic;
 use opentelemetry::trace::FutureExt;
 use tokio::sync::Mutex;
 use tokio::try_join;
 
 use ockam::Context;
-use ockam_api::cloud::space::Spaces;
+use ockam_api::cloud::space::{Space, Spaces};
 
 use ockam_api::nodes::InMemoryNode;
 
-use crate::util::api::IdentityOpts;
+use crate::output::SelectedFields;
+use crate::util::api::{IdentityOpts, PaginationOpts, SortBy};
 use crate::util::async_cmd;
+use crate::util::comma_separated;
 use crate::{docs, CommandGlobalOpts};
 
 const LONG_ABOUT: &str = include_str!("./static/list/long_about.txt");
@@ -27,6 +29,36 @@ const AFTER_LONG_HELP: &str = include_str!("./static/list/after_long_help.txt");
 pub struct ListCommand {
     #[command(flatten)]
     pub identity_opts: IdentityOpts,
+
+    #[command(flatten)]
+    pub pagination_opts: PaginationOpts,
+
+    /// Comma-separated list of the fields to display for each space, for example `name,id`
+    #[arg(
+        long = "field",
+        value_name = "FIELDS",
+        value_enum,
+        value_delimiter = ','
+    )]
+    pub fields: Vec<SpaceField>,
+}
+
+/// Field of a space which can be displayed by `ockam space list`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum SpaceField {
+    Id,
+    Name,
+    Users,
+}
+
+impl SpaceField {
+    fn select(&self, space: &Space) -> (&'static str, String) {
+        match self {
+            SpaceField::Id => ("Id", space.id.clone()),
+            SpaceField::Name => ("Space", space.name.clone()),
+            SpaceField::Users => ("Users", comma_separated(&space.users)),
+        }
+    }
 }
 
 impl ListCommand {
@@ -57,14 +89,32 @@ impl ListCommand {
             .terminal
             .progress_output(&output_messages, &is_finished);
 
-        let (spaces, _) = try_join!(get_spaces, progress_output)?;
+        let (mut spaces, _) = try_join!(get_spaces, progress_output)?;
+
+        if self.pagination_opts.sort == SortBy::Name {
+            spaces.sort_by(|s1, s2| s1.name.cmp(&s2.name));
+        }
+        let pagination = self.pagination_opts.pagination();
+        let page = pagination.paginate(spaces);
 
-        let plain = opts.terminal.build_list(
-            &spaces,
-            "Spaces",
-            "No spaces found. Run 'ockam enroll' to get a space and a project",
-        )?;
-        let json = serde_json::to_string_pretty(&spaces).into_diagnostic()?;
+        let empty_message = "No spaces found. Run 'ockam enroll' to get a space and a project";
+        let plain = if self.fields.is_empty() {
+            opts.terminal
+                .build_list(&page.items, "Spaces", empty_message)?
+        } else {
+            let selected: Vec<SelectedFields> = page
+                .items
+                .iter()
+                .map(|s| SelectedFields(self.fields.iter().map(|f| f.select(s)).collect()))
+                .collect();
+            opts.terminal
+                .build_list(&selected, "Spaces", empty_message)?
+        };
+        let json = if pagination.is_paginated() {
+            serde_json::to_string_pretty(&page).into_diagnostic()?
+        } else {
+            serde_json::to_string_pretty(&page.items).into_diagnostic()?
+        };
 
         opts.terminal
             .stdout()
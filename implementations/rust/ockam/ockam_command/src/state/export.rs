@@ -0,0 +1,102 @@
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::{miette, IntoDiagnostic};
+
+use ockam_api::cli_state::{CliState, StateArchive};
+use ockam_node::Context;
+
+use crate::state::get_passphrase;
+use crate::{color, fmt_ok, fmt_warn, Command, CommandGlobalOpts, OckamColor};
+
+/// Export the local identities, vault secrets, spaces, projects and nodes configuration to a file
+///
+/// The file can be imported on another machine with `ockam state import`. Since it contains the
+/// identities secret keys, it should be encrypted with `--encrypt`.
+#[derive(Clone, Debug, Args)]
+pub struct ExportCommand {
+    /// Path of the file to create
+    #[arg(value_name = "PATH")]
+    pub path: PathBuf,
+
+    /// Encrypt the file with a passphrase, read from the OCKAM_STATE_PASSPHRASE environment
+    /// variable or prompted
+    #[arg(long)]
+    pub encrypt: bool,
+
+    /// Replace the file if it already exists
+    #[arg(long)]
+    pub overwrite: bool,
+}
+
+#[async_trait]
+impl Command for ExportCommand {
+    const NAME: &'static str = "state export";
+
+    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        if self.path.exists() && !self.overwrite {
+            return Err(miette!(
+                "The file {:?} already exists. Use --overwrite to replace it",
+                self.path
+            ))?;
+        }
+        let passphrase = if self.encrypt {
+            Some(get_passphrase(&opts, true)?)
+        } else {
+            opts.terminal.write_line(fmt_warn!(
+                "The exported file contains secret keys and is not encrypted"
+            ))?;
+            None
+        };
+
+        export_to_file(&opts.state, &self.path, passphrase.as_deref()).await?;
+
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "The local state was exported to {}",
+                color!(self.path.display(), OckamColor::PrimaryResource)
+            ))
+            .json(serde_json::json!({ "path": self.path, "encrypted": self.encrypt }))
+            .write_line()?;
+        Ok(())
+    }
+}
+
+/// Write the local state to a file, encrypted if a passphrase is given
+pub(super) async fn export_to_file(
+    state: &CliState,
+    path: &Path,
+    passphrase: Option<&str>,
+) -> crate::Result<()> {
+    let archive = StateArchive::new(state.export_state().await?, passphrase)?;
+    std::fs::write(path, archive.to_bytes()?).into_diagnostic()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn the_exported_file_is_only_encrypted_with_a_passphrase() -> miette::Result<()> {
+        let state = CliState::test().await?;
+        state.create_identity_with_name("alice").await?;
+        let directory = tempfile::tempdir().into_diagnostic()?;
+
+        let plain = directory.path().join("plain.ockam");
+        export_to_file(&state, &plain, None).await?;
+        let archive = StateArchive::from_bytes(&std::fs::read(&plain).into_diagnostic()?)?;
+        assert!(!archive.is_encrypted());
+
+        let encrypted = directory.path().join("encrypted.ockam");
+        export_to_file(&state, &encrypted, Some("passphrase")).await?;
+        let contents = std::fs::read(&encrypted).into_diagnostic()?;
+        let archive = StateArchive::from_bytes(&contents)?;
+        assert!(archive.is_encrypted());
+        assert!(!String::from_utf8_lossy(&contents).contains("alice"));
+        Ok(())
+    }
+}
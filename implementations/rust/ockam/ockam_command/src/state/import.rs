@@ -0,0 +1,113 @@
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+
+use ockam_api::cli_state::{CliState, StateArchive, StateImportSummary};
+use ockam_node::Context;
+
+use crate::state::get_passphrase;
+use crate::{color, fmt_log, fmt_ok, fmt_warn, Command, CommandGlobalOpts, OckamColor};
+
+/// Import a file created with `ockam state export` into the local state
+///
+/// Identities, spaces, projects and nodes which already exist locally are skipped, unless
+/// `--overwrite` is used. The local default identity, project, space and node are left unchanged.
+#[derive(Clone, Debug, Args)]
+pub struct ImportCommand {
+    /// Path of the file to import
+    #[arg(value_name = "PATH")]
+    pub path: PathBuf,
+
+    /// Replace the local identities, spaces, projects and nodes having the same names
+    #[arg(long)]
+    pub overwrite: bool,
+}
+
+#[async_trait]
+impl Command for ImportCommand {
+    const NAME: &'static str = "state import";
+
+    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let summary = import_from_file(&opts.state, &self.path, self.overwrite, || {
+            get_passphrase(&opts, false)
+        })
+        .await?;
+
+        let mut plain = fmt_ok!(
+            "The local state was imported from {}",
+            color!(self.path.display(), OckamColor::PrimaryResource)
+        );
+        for imported in &summary.imported {
+            write!(plain, "\n{}", fmt_log!("Imported {imported}"))?;
+        }
+        for overwritten in &summary.overwritten {
+            write!(plain, "\n{}", fmt_log!("Overwritten {overwritten}"))?;
+        }
+        for skipped in &summary.skipped {
+            write!(plain, "\n{}", fmt_warn!("Skipped {skipped}"))?;
+        }
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .json(serde_json::to_value(&summary)?)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+/// Import the state archived in a file.
+/// The passphrase is only requested if the file is encrypted
+async fn import_from_file(
+    state: &CliState,
+    path: &Path,
+    overwrite: bool,
+    passphrase: impl FnOnce() -> miette::Result<String>,
+) -> crate::Result<StateImportSummary> {
+    let bytes = std::fs::read(path).into_diagnostic()?;
+    let archive = StateArchive::from_bytes(&bytes)?;
+    let passphrase = if archive.is_encrypted() {
+        Some(passphrase()?)
+    } else {
+        None
+    };
+    let exported = archive.state(passphrase.as_deref())?;
+    Ok(state.import_state(&exported, overwrite).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::export::export_to_file;
+    use miette::miette;
+
+    #[tokio::test]
+    async fn an_exported_state_is_imported_on_another_machine() -> miette::Result<()> {
+        let exporting = CliState::test().await?;
+        let alice = exporting.create_identity_with_name("alice").await?;
+        let directory = tempfile::tempdir().into_diagnostic()?;
+        let path = directory.path().join("state.ockam");
+
+        // the passphrase is not requested for a plain file
+        export_to_file(&exporting, &path, None).await?;
+        let importing = CliState::test().await?;
+        import_from_file(&importing, &path, false, || {
+            Err(miette!("the passphrase should not be requested"))
+        })
+        .await?;
+        let imported = importing.get_named_identity("alice").await?;
+        assert_eq!(imported.identifier(), alice.identifier());
+
+        // an encrypted file can only be imported with its passphrase
+        export_to_file(&exporting, &path, Some("passphrase")).await?;
+        let importing = CliState::test().await?;
+        let result = import_from_file(&importing, &path, false, || Ok("wrong".to_string())).await;
+        assert!(result.is_err());
+        import_from_file(&importing, &path, false, || Ok("passphrase".to_string())).await?;
+        assert!(importing.get_named_identity("alice").await.is_ok());
+        Ok(())
+    }
+}
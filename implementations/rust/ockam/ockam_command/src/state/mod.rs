@@ -0,0 +1,61 @@
+mod export;
+mod import;
+
+pub use export::ExportCommand;
+pub use import::ImportCommand;
+
+use clap::{Args, Subcommand};
+use ockam_core::env::get_env;
+
+use crate::terminal::no_input_error;
+use crate::{Command, CommandGlobalOpts};
+
+/// Environment variable which can be used to provide the passphrase of a state archive
+const PASSPHRASE_ENV: &str = "OCKAM_STATE_PASSPHRASE";
+
+/// Export and import the local Ockam configuration, to migrate it to another machine
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true, subcommand_required = true)]
+pub struct StateCommand {
+    #[command(subcommand)]
+    pub subcommand: StateSubcommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum StateSubcommand {
+    Export(ExportCommand),
+    Import(ImportCommand),
+}
+
+impl StateCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        match self.subcommand {
+            StateSubcommand::Export(c) => c.run(opts),
+            StateSubcommand::Import(c) => c.run(opts),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match &self.subcommand {
+            StateSubcommand::Export(c) => c.name(),
+            StateSubcommand::Import(c) => c.name(),
+        }
+    }
+}
+
+/// Return the passphrase of a state archive, from the OCKAM_STATE_PASSPHRASE environment variable
+/// or from a user prompt
+fn get_passphrase(opts: &CommandGlobalOpts, confirmation: bool) -> miette::Result<String> {
+    if let Ok(Some(passphrase)) = get_env::<String>(PASSPHRASE_ENV) {
+        return Ok(passphrase);
+    }
+    match opts
+        .terminal
+        .password("Enter the passphrase of the state archive", confirmation)?
+    {
+        Some(passphrase) => Ok(passphrase),
+        None => Err(no_input_error(format!(
+            "the {PASSPHRASE_ENV} environment variable"
+        ))),
+    }
+}
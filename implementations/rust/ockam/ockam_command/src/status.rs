@@ -1,22 +1,43 @@
 use std::fmt::Write;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 
 use clap::Args;
+use colorful::Colorful;
 use miette::IntoDiagnostic;
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinSet;
 use tracing::warn;
 
-use ockam::identity::{Identifier, TimestampInSeconds};
-use ockam::Context;
-use ockam_api::cli_state::{EnrollmentStatus, IdentityEnrollment};
+use ockam::identity::{CredentialSqlxDatabase, Identifier, TimestampInSeconds};
+use ockam::{Context, TcpTransport};
+use ockam_api::cli_state::{EnrollmentStatus, IdentityEnrollment, NodeInfo};
 use ockam_api::cloud::project::models::OrchestratorVersionInfo;
 use ockam_api::nodes::models::base::NodeStatus as NodeStatusModel;
+use ockam_api::nodes::models::portal::{InletList, OutletList};
+use ockam_api::nodes::models::transport::TransportList;
+use ockam_api::nodes::service::relay::Relays;
+use ockam_api::nodes::service::CredentialScope;
 use ockam_api::nodes::{BackgroundNodeClient, InMemoryNode};
+use ockam_api::ConnectionStatus;
+use ockam_core::AsyncTryClone;
 
+use crate::output::human_readable_time;
 use crate::util::{api, async_cmd, duration::duration_parser};
 use crate::CommandGlobalOpts;
 use crate::Result;
 
-/// Display information about the system's status
+/// A project credential expiring within this duration is reported as a warning
+const CREDENTIAL_EXPIRY_WARNING: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Display the health of the identities, nodes, portals and relays
+///
+/// Each node is queried concurrently for its inlets, outlets and relays. Components which are
+/// not fully up are displayed in yellow or red, and the command then exits with the code 1.
+/// With `--output json`, the same information is returned with a stable schema, to be used
+/// by monitoring scripts.
 #[derive(Clone, Debug, Args)]
 pub struct StatusCommand {
     /// Show status for all identities; default: enrolled only
@@ -26,22 +47,38 @@ pub struct StatusCommand {
     /// Override the default timeout
     #[arg(long, default_value = "5", value_parser = duration_parser)]
     timeout: Duration,
+
+    /// Maximum time spent querying each node. Nodes which don't reply in time are reported as unreachable
+    #[arg(long, default_value = "2", value_parser = duration_parser)]
+    node_timeout: Duration,
 }
 
 impl StatusCommand {
     pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let degraded = Arc::new(AtomicBool::new(false));
+        let is_degraded = degraded.clone();
         async_cmd(&self.name(), opts.clone(), |ctx| async move {
-            self.async_run(&ctx, opts).await
-        })
+            let status = self.async_run(&ctx, opts).await?;
+            is_degraded.store(status.health.is_degraded(), Ordering::Relaxed);
+            Ok(())
+        })?;
+        if degraded.load(Ordering::Relaxed) {
+            std::process::exit(1);
+        }
+        Ok(())
     }
 
     pub fn name(&self) -> String {
         "status".to_string()
     }
 
-    async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+    async fn async_run(
+        &self,
+        ctx: &Context,
+        opts: CommandGlobalOpts,
+    ) -> miette::Result<StatusData> {
         let identities_details = get_identities_details(&opts, self.all).await?;
-        let nodes_details = get_nodes_details(ctx, &opts).await?;
+        let nodes = get_nodes_health(ctx, &opts, self.node_timeout).await?;
 
         let node = InMemoryNode::start(ctx, &opts.state)
             .await?
@@ -53,48 +90,111 @@ impl StatusCommand {
             .map_err(|e| warn!(%e, "Failed to retrieve orchestrator version"))
             .unwrap_or_default();
 
-        let status =
-            StatusData::from_parts(orchestrator_version, identities_details, nodes_details)?;
+        let status = StatusData::from_parts(orchestrator_version, identities_details, nodes)?;
         opts.terminal
             .stdout()
             .plain(build_plain_output(self, &status).await?)
             .json(serde_json::to_string(&status).into_diagnostic()?)
             .write_line()?;
-        Ok(())
+        Ok(status)
     }
 }
 
-async fn get_nodes_details(ctx: &Context, opts: &CommandGlobalOpts) -> Result<Vec<NodeDetails>> {
-    let mut node_details: Vec<NodeDetails> = vec![];
-
+/// Query all the nodes concurrently. Each node is given at most `node_timeout` to reply
+async fn get_nodes_health(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    node_timeout: Duration,
+) -> Result<Vec<NodeHealth>> {
     let nodes = opts.state.get_nodes().await?;
     if nodes.is_empty() {
-        return Ok(node_details);
+        return Ok(vec![]);
     }
-    let default_node_name = opts.state.get_default_node().await?.name();
-    let mut node_client =
-        BackgroundNodeClient::create_to_node(ctx, &opts.state, &default_node_name).await?;
-    node_client.set_timeout_mut(Duration::from_millis(200));
+    let tcp = TcpTransport::create(ctx).await.into_diagnostic()?;
+    let now = ockam_core::compat::time::now()?;
 
-    for node in nodes {
-        node_client.set_node_name(&node.name());
-        let node_infos = NodeDetails {
-            identifier: node.identifier(),
-            name: node.name(),
-            status: get_node_status(ctx, &node_client).await?,
-        };
-        node_details.push(node_infos);
+    let mut queries = JoinSet::new();
+    for (index, node) in nodes.into_iter().enumerate() {
+        let ctx = ctx.async_try_clone().await.into_diagnostic()?;
+        let tcp = tcp.clone();
+        let opts = opts.clone();
+        queries.spawn(async move {
+            let health = get_node_health(&ctx, &opts, &tcp, &node, node_timeout, now).await;
+            (index, health)
+        });
     }
 
-    Ok(node_details)
+    let mut nodes_health = vec![];
+    while let Some(result) = queries.join_next().await {
+        nodes_health.push(result.into_diagnostic()?);
+    }
+    nodes_health.sort_by_key(|(index, _)| *index);
+    Ok(nodes_health.into_iter().map(|(_, health)| health).collect())
 }
 
-async fn get_node_status(ctx: &Context, node: &BackgroundNodeClient) -> Result<String> {
-    let node_status_model: miette::Result<NodeStatusModel> =
-        node.ask(ctx, api::query_status()).await;
-    Ok(node_status_model
-        .map(|m| m.status)
-        .unwrap_or("Stopped".to_string()))
+async fn get_node_health(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    tcp: &TcpTransport,
+    node: &NodeInfo,
+    node_timeout: Duration,
+    now: u64,
+) -> NodeHealth {
+    let mut health = NodeHealth::new(node);
+    health.credential = get_credential_health(opts, node, now).await;
+
+    if node.is_running() {
+        let query = async {
+            let mut client =
+                BackgroundNodeClient::create_to_node_with_tcp(tcp, &opts.state, &node.name())
+                    .await?;
+            client.set_timeout_mut(node_timeout);
+            health.query(ctx, &client).await
+        };
+        let result = tokio::time::timeout(node_timeout, query).await;
+        health.state = match result {
+            Ok(Ok(())) => NodeState::Running,
+            Ok(Err(e)) => {
+                warn!(%e, node = %node.name(), "Failed to query the node");
+                NodeState::Unreachable
+            }
+            Err(_) => {
+                warn!(node = %node.name(), "Timeout while querying the node");
+                NodeState::Unreachable
+            }
+        };
+    }
+    health.update_health();
+    health
+}
+
+/// Return the project credential of the node expiring last
+async fn get_credential_health(
+    opts: &CommandGlobalOpts,
+    node: &NodeInfo,
+    now: u64,
+) -> Option<CredentialHealth> {
+    let mut database = opts.state.database();
+    database.set_node_name(&node.name());
+    let credentials = CredentialSqlxDatabase::new(database)
+        .get_all()
+        .await
+        .map_err(|e| warn!(%e, node = %node.name(), "Failed to retrieve the node credentials"))
+        .ok()?;
+
+    credentials
+        .into_iter()
+        .filter(|(_, scope)| {
+            matches!(
+                CredentialScope::from_str(scope),
+                Ok(CredentialScope::ProjectMember { .. })
+            )
+        })
+        .filter_map(|(credential, scope)| {
+            let expires_at = credential.get_expires_at().ok()?;
+            Some(CredentialHealth::new(scope, expires_at, now))
+        })
+        .max_by_key(|credential| credential.expires_at)
 }
 
 async fn get_identities_details(
@@ -135,7 +235,6 @@ async fn build_plain_output(cmd: &StatusCommand, status: &StatusData) -> Result<
                 Also consider running `ockam enroll` to enroll an identity.",
             )?;
         }
-        return Ok(plain);
     };
 
     for (i_idx, i) in status.identities.iter().enumerate() {
@@ -148,63 +247,154 @@ async fn build_plain_output(cmd: &StatusCommand, status: &StatusData) -> Result<
         }
         writeln!(plain, "{:2}Identifier: {}", "", i.identifier())?;
         writeln!(plain, "{:2}Enrolled: {}", "", i.is_enrolled())?;
-
         if !i.nodes.is_empty() {
-            writeln!(plain, "{:2}Linked Nodes:", "")?;
-            for (n_idx, node) in i.nodes.iter().enumerate() {
-                writeln!(plain, "{:4}Node[{}]:", "", n_idx)?;
-                writeln!(plain, "{:6}Name: {}", "", node.name)?;
-                writeln!(plain, "{:6}Status: {}", "", node.status)?;
+            writeln!(plain, "{:2}Linked Nodes: {}", "", i.nodes.join(", "))?;
+        }
+    }
+
+    if status.nodes.is_empty() {
+        return Ok(plain);
+    }
+    writeln!(plain, "Nodes: {}", status.health.colored(status.health))?;
+    for node in &status.nodes {
+        let default = if node.is_default { " (default)" } else { "" };
+        writeln!(
+            plain,
+            "{:2}{} {}{} {}",
+            "",
+            node.health.dot(),
+            node.name,
+            default,
+            node.health.colored(node.state)
+        )?;
+        if !node.listeners.is_empty() {
+            writeln!(plain, "{:4}Listeners: {}", "", node.listeners.join(", "))?;
+        }
+        if !node.inlets.is_empty() {
+            writeln!(plain, "{:4}Inlets:", "")?;
+            for inlet in &node.inlets {
+                writeln!(
+                    plain,
+                    "{:6}{} {} {} => {} {}",
+                    "",
+                    inlet.health.dot(),
+                    inlet.alias,
+                    inlet.bind_address,
+                    inlet.outlet_address.as_deref().unwrap_or("-"),
+                    inlet
+                        .health
+                        .colored(format!("{:?}", inlet.connection_status))
+                )?;
+            }
+        }
+        if !node.outlets.is_empty() {
+            writeln!(plain, "{:4}Outlets:", "")?;
+            for outlet in &node.outlets {
+                writeln!(
+                    plain,
+                    "{:6}{} {} => {}",
+                    "",
+                    outlet.health.dot(),
+                    outlet.worker_address,
+                    outlet.socket_address
+                )?;
             }
         }
+        if !node.relays.is_empty() {
+            writeln!(plain, "{:4}Relays:", "")?;
+            for relay in &node.relays {
+                let last_heartbeat = match relay.last_heartbeat_at {
+                    Some(at) => format!("last heartbeat {}s ago", status.now.saturating_sub(at)),
+                    None => "no heartbeat".to_string(),
+                };
+                writeln!(
+                    plain,
+                    "{:6}{} {} => {} {}, {}",
+                    "",
+                    relay.health.dot(),
+                    relay.alias,
+                    relay.destination_address,
+                    relay
+                        .health
+                        .colored(format!("{:?}", relay.connection_status)),
+                    last_heartbeat
+                )?;
+            }
+        }
+        if let Some(credential) = &node.credential {
+            writeln!(
+                plain,
+                "{:4}Credential: {} {} {}",
+                "",
+                credential.health.dot(),
+                credential.scope,
+                credential.health.colored(format!(
+                    "expires {}",
+                    human_readable_time(TimestampInSeconds(credential.expires_at))
+                ))
+            )?;
+        }
     }
     Ok(plain)
 }
 
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Serialize, Deserialize)]
 struct StatusData {
     #[serde(flatten)]
     orchestrator_version: OrchestratorVersionInfo,
     identities: Vec<IdentityWithLinkedNodes>,
+    nodes: Vec<NodeHealth>,
+    /// Worst health of all the nodes
+    health: Health,
+    /// Time of the status, in seconds since the UNIX epoch
+    now: u64,
 }
 
 impl StatusData {
     fn from_parts(
         orchestrator_version: OrchestratorVersionInfo,
         identities_details: Vec<IdentityEnrollment>,
-        mut nodes_details: Vec<NodeDetails>,
+        nodes: Vec<NodeHealth>,
     ) -> Result<Self> {
-        let mut identities = vec![];
-        for identity in identities_details.into_iter() {
-            let mut identity_status = IdentityWithLinkedNodes {
+        let identities = identities_details
+            .into_iter()
+            .map(|identity| IdentityWithLinkedNodes {
                 identifier: identity.identifier().clone(),
                 name: identity.name().clone(),
                 is_default: identity.is_default(),
                 enrolled_at: identity
                     .enrolled_at()
                     .map(|o| TimestampInSeconds::from(o.unix_timestamp() as u64)),
-                nodes: vec![],
-            };
-            nodes_details.retain(|nd| nd.identifier == identity_status.identifier());
-            if !nodes_details.is_empty() {
-                identity_status.nodes = nodes_details.clone();
-            }
-            identities.push(identity_status);
-        }
+                nodes: nodes
+                    .iter()
+                    .filter(|node| &node.identifier == identity.identifier())
+                    .map(|node| node.name.clone())
+                    .collect(),
+            })
+            .collect();
+        let health = nodes
+            .iter()
+            .map(|node| node.health)
+            .max()
+            .unwrap_or(Health::Healthy);
         Ok(Self {
             orchestrator_version,
             identities,
+            nodes,
+            health,
+            now: ockam_core::compat::time::now()?,
         })
     }
 }
 
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Serialize, Deserialize)]
 struct IdentityWithLinkedNodes {
     identifier: Identifier,
     name: Option<String>,
     is_default: bool,
     enrolled_at: Option<TimestampInSeconds>,
-    nodes: Vec<NodeDetails>,
+    /// Names of the nodes using this identity
+    nodes: Vec<String>,
 }
 
 impl IdentityWithLinkedNodes {
@@ -223,16 +413,228 @@ impl IdentityWithLinkedNodes {
     fn is_enrolled(&self) -> bool {
         self.enrolled_at.is_some()
     }
+}
+
+/// Health of a component, displayed in green, yellow or red
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+enum Health {
+    Healthy,
+    Warning,
+    Critical,
+}
+
+impl Health {
+    fn is_degraded(&self) -> bool {
+        *self != Health::Healthy
+    }
+
+    fn colored(&self, text: impl ToString) -> String {
+        let text = text.to_string();
+        match self {
+            Health::Healthy => text.light_green().to_string(),
+            Health::Warning => text.light_yellow().to_string(),
+            Health::Critical => text.light_red().to_string(),
+        }
+    }
 
-    #[allow(unused)]
-    fn nodes(&self) -> &Vec<NodeDetails> {
-        &self.nodes
+    fn dot(&self) -> String {
+        self.colored("●")
     }
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Clone)]
-pub struct NodeDetails {
-    identifier: Identifier,
+impl std::fmt::Display for Health {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Health::Healthy => write!(f, "healthy"),
+            Health::Warning => write!(f, "warning"),
+            Health::Critical => write!(f, "critical"),
+        }
+    }
+}
+
+impl From<ConnectionStatus> for Health {
+    fn from(status: ConnectionStatus) -> Self {
+        match status {
+            ConnectionStatus::Up => Health::Healthy,
+            ConnectionStatus::Degraded => Health::Warning,
+            ConnectionStatus::Down => Health::Critical,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum NodeState {
+    Running,
+    Stopped,
+    /// The node is running but did not reply in time
+    Unreachable,
+}
+
+impl std::fmt::Display for NodeState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NodeState::Running => write!(f, "running"),
+            NodeState::Stopped => write!(f, "stopped"),
+            NodeState::Unreachable => write!(f, "unreachable"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct NodeHealth {
     name: String,
-    status: String,
+    identifier: Identifier,
+    is_default: bool,
+    state: NodeState,
+    /// Worst health of the node and its components
+    health: Health,
+    listeners: Vec<String>,
+    inlets: Vec<InletHealth>,
+    outlets: Vec<OutletHealth>,
+    relays: Vec<RelayHealth>,
+    credential: Option<CredentialHealth>,
+}
+
+impl NodeHealth {
+    fn new(node: &NodeInfo) -> Self {
+        Self {
+            name: node.name(),
+            identifier: node.identifier(),
+            is_default: node.is_default(),
+            state: NodeState::Stopped,
+            health: Health::Healthy,
+            listeners: vec![],
+            inlets: vec![],
+            outlets: vec![],
+            relays: vec![],
+            credential: None,
+        }
+    }
+
+    /// Retrieve the listeners, portals and relays of a running node
+    async fn query(&mut self, ctx: &Context, client: &BackgroundNodeClient) -> miette::Result<()> {
+        let _: NodeStatusModel = client.ask(ctx, api::query_status()).await?;
+
+        for request in [api::list_tcp_listeners(), api::list_udp_listeners()] {
+            let transports: TransportList = client.ask(ctx, request).await?;
+            self.listeners.extend(
+                transports
+                    .list
+                    .into_iter()
+                    .map(|t| format!("{} {}", t.tt, t.socket_addr)),
+            );
+        }
+
+        let inlets: InletList = client.ask(ctx, api::list_inlets()).await?;
+        self.inlets = inlets
+            .list
+            .into_iter()
+            .map(|inlet| InletHealth {
+                health: inlet.status.into(),
+                alias: inlet.alias,
+                bind_address: inlet.bind_addr,
+                outlet_address: inlet.outlet_route,
+                connection_status: inlet.status,
+            })
+            .collect();
+
+        let outlets: OutletList = client.ask(ctx, api::list_outlets()).await?;
+        self.outlets = outlets
+            .list
+            .into_iter()
+            .map(|outlet| OutletHealth {
+                worker_address: outlet.worker_addr.to_string(),
+                socket_address: outlet.socket_addr.to_string(),
+                health: Health::Healthy,
+            })
+            .collect();
+
+        self.relays = client
+            .list_relays(ctx)
+            .await?
+            .into_iter()
+            .map(|relay| RelayHealth {
+                alias: relay.alias().to_string(),
+                destination_address: relay.destination_address().to_string(),
+                connection_status: relay.connection_status(),
+                last_heartbeat_at: relay.last_heartbeat_at(),
+                failed_heartbeats: relay.failed_heartbeats(),
+                health: relay.connection_status().into(),
+            })
+            .collect();
+
+        Ok(())
+    }
+
+    /// Set the health of the node from its state and the health of its components
+    fn update_health(&mut self) {
+        let state = match self.state {
+            NodeState::Running => Health::Healthy,
+            NodeState::Stopped => Health::Warning,
+            NodeState::Unreachable => Health::Critical,
+        };
+        self.health = [state]
+            .into_iter()
+            .chain(self.inlets.iter().map(|i| i.health))
+            .chain(self.outlets.iter().map(|o| o.health))
+            .chain(self.relays.iter().map(|r| r.health))
+            .chain(self.credential.iter().map(|c| c.health))
+            .max()
+            .unwrap_or(state);
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct InletHealth {
+    alias: String,
+    bind_address: String,
+    outlet_address: Option<String>,
+    connection_status: ConnectionStatus,
+    health: Health,
+}
+
+#[derive(Serialize, Deserialize)]
+struct OutletHealth {
+    worker_address: String,
+    socket_address: String,
+    health: Health,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RelayHealth {
+    alias: String,
+    destination_address: String,
+    connection_status: ConnectionStatus,
+    /// Time, in seconds since the UNIX epoch, of the last successful heartbeat
+    last_heartbeat_at: Option<u64>,
+    failed_heartbeats: u32,
+    health: Health,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CredentialHealth {
+    scope: String,
+    /// Expiration time, in seconds since the UNIX epoch
+    expires_at: u64,
+    health: Health,
+}
+
+impl CredentialHealth {
+    fn new(scope: String, expires_at: TimestampInSeconds, now: u64) -> Self {
+        let expires_at = expires_at.0;
+        let health = if expires_at <= now {
+            Health::Critical
+        } else if expires_at - now <= CREDENTIAL_EXPIRY_WARNING.as_secs() {
+            Health::Warning
+        } else {
+            Health::Healthy
+        };
+        Self {
+            scope,
+            expires_at,
+            health,
+        }
+    }
 }
@@ -1,25 +1,24 @@
 use async_trait::async_trait;
 use clap::Subcommand;
-use colorful::Colorful;
-use std::ops::Add;
 use std::path::PathBuf;
-use std::time::Duration;
-use tokio_retry::strategy::jitter;
-use tracing::warn;
 
+use ockam_api::cli_state::NodeLogging;
 use ockam_api::CliState;
 use ockam_core::OpenTelemetryContext;
 use ockam_node::Context;
 
 use crate::admin::AdminCommand;
 use crate::authority::{AuthorityCommand, AuthoritySubcommand};
+use crate::bench::BenchCommand;
 use crate::command_global_opts::CommandGlobalOpts;
-use crate::completion::CompletionCommand;
+use crate::completion::{CompleteCommand, CompletionCommand};
+use crate::config::ConfigCommand;
 use crate::credential::CredentialCommand;
 use crate::enroll::EnrollCommand;
 use crate::environment::EnvironmentCommand;
 use crate::flow_control::FlowControlCommand;
 use crate::identity::IdentityCommand;
+use crate::journey::JourneyCommand;
 use crate::kafka::consumer::KafkaConsumerCommand;
 use crate::kafka::direct::KafkaDirectCommand;
 use crate::kafka::outlet::KafkaOutletCommand;
@@ -30,11 +29,13 @@ use crate::markdown::MarkdownCommand;
 use crate::message::MessageCommand;
 use crate::node::NodeCommand;
 use crate::node::NodeSubcommand;
+use crate::ping::PingCommand;
 use crate::policy::PolicyCommand;
 use crate::project::ProjectCommand;
 use crate::project_member::ProjectMemberCommand;
 use crate::relay::RelayCommand;
 use crate::reset::ResetCommand;
+use crate::restore::RestoreCommand;
 use crate::run::RunCommand;
 use crate::secure_channel::listener::SecureChannelListenerCommand;
 use crate::secure_channel::SecureChannelCommand;
@@ -43,17 +44,20 @@ use crate::service::ServiceCommand;
 use crate::share::ShareCommand;
 use crate::sidecar::SidecarCommand;
 use crate::space::SpaceCommand;
+use crate::state::StateCommand;
 use crate::status::StatusCommand;
 use crate::subscription::SubscriptionCommand;
 use crate::tcp::connection::TcpConnectionCommand;
 use crate::tcp::inlet::TcpInletCommand;
 use crate::tcp::listener::TcpListenerCommand;
 use crate::tcp::outlet::TcpOutletCommand;
+use crate::trace::TraceCommand;
 use crate::util::api::RetryOpts;
 use crate::util::async_cmd;
+use crate::util::retry::{notify_retry, RetryExecutor, RetryStrategy};
 use crate::vault::VaultCommand;
 use crate::worker::WorkerCommand;
-use crate::{fmt_log, fmt_warn, Error, Result};
+use crate::{docs, Result};
 
 /// List of commands which can be executed with `ockam`
 #[derive(Clone, Debug, Subcommand)]
@@ -62,6 +66,7 @@ pub enum OckamSubcommand {
     Enroll(EnrollCommand),
     Space(SpaceCommand),
     Project(ProjectCommand),
+    #[command(hide = docs::hide())]
     ProjectMember(ProjectMemberCommand),
     Sidecar(SidecarCommand),
     Admin(AdminCommand),
@@ -73,6 +78,9 @@ pub enum OckamSubcommand {
     Worker(WorkerCommand),
     Service(ServiceCommand),
     Message(MessageCommand),
+    Trace(TraceCommand),
+    Ping(PingCommand),
+    Bench(BenchCommand),
     Relay(RelayCommand),
 
     TcpListener(TcpListenerCommand),
@@ -99,9 +107,15 @@ pub enum OckamSubcommand {
 
     Run(RunCommand),
     Status(StatusCommand),
+    Config(ConfigCommand),
     Reset(ResetCommand),
+    Restore(RestoreCommand),
+    State(StateCommand),
+    Journey(JourneyCommand),
 
     Completion(CompletionCommand),
+    #[command(name = "__complete", hide = true)]
+    Complete(CompleteCommand),
     Markdown(MarkdownCommand),
     Manpages(ManpagesCommand),
     Environment(EnvironmentCommand),
@@ -126,6 +140,9 @@ impl OckamSubcommand {
             OckamSubcommand::Worker(c) => c.run(opts),
             OckamSubcommand::Service(c) => c.run(opts),
             OckamSubcommand::Message(c) => c.run(opts),
+            OckamSubcommand::Trace(c) => c.run(opts),
+            OckamSubcommand::Ping(c) => c.run(opts),
+            OckamSubcommand::Bench(c) => c.run(opts),
             OckamSubcommand::Relay(c) => c.run(opts),
 
             OckamSubcommand::KafkaOutlet(c) => c.run(opts),
@@ -152,9 +169,14 @@ impl OckamSubcommand {
 
             OckamSubcommand::Run(c) => c.run(opts),
             OckamSubcommand::Status(c) => c.run(opts),
+            OckamSubcommand::Config(c) => c.run(opts),
             OckamSubcommand::Reset(c) => c.run(opts),
+            OckamSubcommand::Restore(c) => c.run(opts),
+            OckamSubcommand::State(c) => c.run(opts),
+            OckamSubcommand::Journey(c) => c.run(opts),
 
             OckamSubcommand::Completion(c) => c.run(),
+            OckamSubcommand::Complete(c) => c.run(),
             OckamSubcommand::Markdown(c) => c.run(),
             OckamSubcommand::Manpages(c) => c.run(),
             OckamSubcommand::Environment(c) => c.run(),
@@ -185,6 +207,7 @@ impl OckamSubcommand {
             },
             OckamSubcommand::Authority(cmd) => match &cmd.subcommand {
                 AuthoritySubcommand::Create(cmd) => cmd.child_process,
+                _ => false,
             },
             _ => false,
         }
@@ -211,6 +234,19 @@ impl OckamSubcommand {
                         None
                     }
                 }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Return the logging configuration requested for the node run by an ockam node create command,
+    /// when the node runs in the current process
+    pub fn node_logging(&self) -> Option<NodeLogging> {
+        match self {
+            OckamSubcommand::Node(cmd) => match &cmd.subcommand {
+                NodeSubcommand::Create(cmd) if cmd.foreground => Some(cmd.node_logging()),
+                _ => None,
             },
             _ => None,
         }
@@ -237,6 +273,7 @@ impl OckamSubcommand {
                         None
                     }
                 }
+                _ => None,
             },
             _ => None,
         }
@@ -257,6 +294,9 @@ impl OckamSubcommand {
             OckamSubcommand::Worker(c) => c.name(),
             OckamSubcommand::Service(c) => c.name(),
             OckamSubcommand::Message(c) => c.name(),
+            OckamSubcommand::Trace(c) => c.name(),
+            OckamSubcommand::Ping(c) => c.name(),
+            OckamSubcommand::Bench(c) => c.name(),
             OckamSubcommand::Relay(c) => c.name(),
             OckamSubcommand::TcpListener(c) => c.name(),
             OckamSubcommand::TcpConnection(c) => c.name(),
@@ -276,8 +316,13 @@ impl OckamSubcommand {
             OckamSubcommand::Lease(c) => c.name(),
             OckamSubcommand::Run(c) => c.name(),
             OckamSubcommand::Status(c) => c.name(),
+            OckamSubcommand::Config(c) => c.name(),
             OckamSubcommand::Reset(c) => c.name(),
+            OckamSubcommand::Restore(c) => c.name(),
+            OckamSubcommand::State(c) => c.name(),
+            OckamSubcommand::Journey(c) => c.name(),
             OckamSubcommand::Completion(c) => c.name(),
+            OckamSubcommand::Complete(c) => c.name(),
             OckamSubcommand::Markdown(c) => c.name(),
             OckamSubcommand::Manpages(c) => c.name(),
             OckamSubcommand::Environment(c) => c.name(),
@@ -304,50 +349,31 @@ pub trait Command: Clone + Sized + Send + Sync + 'static {
         })
     }
 
+    /// Return the strategy used to run the command again when it fails with a retryable error.
+    /// By default, the strategy is configured by the retry options of the command, if any
+    fn retry_strategy(&self) -> Option<RetryStrategy> {
+        self.retry_opts()
+            .as_ref()
+            .and_then(RetryStrategy::from_opts)
+    }
+
     async fn async_run_with_retry(
         self,
         ctx: &Context,
         opts: CommandGlobalOpts,
     ) -> miette::Result<()> {
-        if let Some(retry_opts) = self.retry_opts() {
-            let (mut retry_count, retry_delay) =
-                match (retry_opts.retry_count(), retry_opts.retry_delay()) {
-                    (Some(count), Some(delay)) => (count, delay),
-                    (Some(count), None) => (count, Duration::from_secs(5)),
-                    (None, Some(delay)) => (3, delay),
-                    (None, None) => {
-                        self.async_run(ctx, opts).await?;
-                        return Ok(());
-                    }
-                };
-            let retry_delay_jitter = Duration::from_secs(2);
-            while retry_count > 0 {
-                let cmd = self.clone();
-                match cmd.async_run(ctx, opts.clone()).await {
-                    Ok(_) => break,
-                    Err(Error::Retry(inner)) => {
-                        retry_count -= 1;
-                        let delay = retry_delay.add(jitter(retry_delay_jitter));
-                        warn!(
-                            "Command failed, retrying in {} seconds: {inner:?}",
-                            delay.as_secs()
-                        );
-                        opts.terminal
-                            .write_line(&fmt_warn!("Command failed with error:"))?;
-                        opts.terminal.write_line(&fmt_log!("{inner:#}\n"))?;
-                        opts.terminal
-                            .write_line(&fmt_log!("Will retry in {} seconds", delay.as_secs()))?;
-                        tokio::time::sleep(delay).await;
-                        opts.terminal.write_line(&fmt_log!("Retrying...\n"))?;
-                    }
-                    Err(e) => return Err(e.into()),
-                }
-            }
-            Ok(())
-        } else {
+        let Some(retry_strategy) = self.retry_strategy() else {
             self.async_run(ctx, opts).await?;
-            Ok(())
-        }
+            return Ok(());
+        };
+        // the last failure is returned with the error code of its cause
+        RetryExecutor::new(retry_strategy)
+            .run(
+                |_| self.clone().async_run(ctx, opts.clone()),
+                |error, next, delay| notify_retry(&opts, error, next, delay),
+            )
+            .await?;
+        Ok(())
     }
 
     async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> Result<()>;
@@ -2,6 +2,7 @@ use std::fmt::Write;
 
 use clap::Args;
 use colorful::Colorful;
+use miette::IntoDiagnostic;
 use tokio::sync::Mutex;
 use tokio::try_join;
 
@@ -10,6 +11,7 @@ use ockam_api::nodes::BackgroundNodeClient;
 use ockam_core::api::Request;
 use ockam_node::Context;
 
+use crate::node::models::transport::ShowTransportStatus;
 use crate::node::NodeOpts;
 use crate::output::Output;
 use crate::terminal::OckamColor;
@@ -70,8 +72,15 @@ impl ListCommand {
                 node.node_name().color(OckamColor::PrimaryResource.color())
             ),
         )?;
-
-        opts.terminal.stdout().plain(list).write_line()?;
+        let json = serde_json::to_string_pretty(
+            &transports
+                .list
+                .into_iter()
+                .map(ShowTransportStatus::from)
+                .collect::<Vec<_>>(),
+        )
+        .into_diagnostic()?;
+        opts.terminal.stdout().plain(list).json(json).write_line()?;
 
         Ok(())
     }
@@ -105,6 +114,14 @@ impl Output for TransportStatus {
                 .color(OckamColor::PrimaryResource.color())
         )?;
 
+        if let Some(ip_family) = &self.ip_family {
+            write!(
+                output,
+                "\nIP Family {}",
+                ip_family.color(OckamColor::PrimaryResource.color())
+            )?;
+        }
+
         Ok(output)
     }
 }
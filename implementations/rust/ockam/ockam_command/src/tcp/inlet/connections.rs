@@ -0,0 +1,58 @@
+use async_trait::async_trait;
+use clap::Args;
+use miette::IntoDiagnostic;
+
+use ockam::Context;
+use ockam_api::nodes::service::portals::Inlets;
+use ockam_api::nodes::BackgroundNodeClient;
+
+use crate::node::NodeOpts;
+use crate::tcp::util::alias_parser;
+use crate::{docs, Command, CommandGlobalOpts};
+
+const PREVIEW_TAG: &str = include_str!("../../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/connections/after_long_help.txt");
+
+/// List the active connections of a TCP Inlet
+#[derive(Clone, Debug, Args)]
+#[command(
+before_help = docs::before_help(PREVIEW_TAG),
+after_long_help = docs::after_help(AFTER_LONG_HELP))]
+pub struct ConnectionsCommand {
+    /// Name of the inlet
+    #[arg(display_order = 900, required = true, id = "ALIAS", value_parser = alias_parser)]
+    alias: String,
+
+    /// Node on which the inlet was started
+    #[command(flatten)]
+    node_opts: NodeOpts,
+}
+
+#[async_trait]
+impl Command for ConnectionsCommand {
+    const NAME: &'static str = "tcp-inlet connections";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.node_opts.at_node).await?;
+        let connections = node.list_inlet_connections(ctx, &self.alias).await?;
+
+        let plain = opts.terminal.build_list(
+            &connections,
+            &format!("Connections of the TCP Inlet {}", self.alias),
+            &format!("No active connections for the TCP Inlet {}", self.alias),
+        )?;
+        let machine = connections
+            .iter()
+            .map(|c| c.id.clone())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let json = serde_json::to_string_pretty(&connections).into_diagnostic()?;
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .machine(machine)
+            .json(json)
+            .write_line()?;
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 use async_trait::async_trait;
 use std::collections::HashMap;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
 use std::str::FromStr;
 use std::time::Duration;
 
@@ -13,26 +14,32 @@ use tracing::trace;
 
 use ockam::identity::Identifier;
 use ockam::Context;
-use ockam_abac::Expr;
+use ockam_abac::{Expr, ResourceType};
 use ockam_api::address::extract_address_value;
 use ockam_api::cli_state::CliState;
 use ockam_api::journeys::{
     JourneyEvent, NODE_NAME, TCP_INLET_ALIAS, TCP_INLET_AT, TCP_INLET_CONNECTION_STATUS,
     TCP_INLET_FROM, TCP_INLET_TO,
 };
-use ockam_api::nodes::models::portal::InletStatus;
-use ockam_api::nodes::service::portals::Inlets;
-use ockam_api::nodes::BackgroundNodeClient;
+use ockam_api::nodes::models::portal::{InletList, InletLoadBalancing, InletStatus};
+use ockam_api::nodes::service::portals::{CreateInletOptions, Inlets};
+use ockam_api::nodes::{BackgroundNodeClient, NodeRequestError, Policies};
 use ockam_api::{random_name, ConnectionStatus};
-use ockam_core::api::{Reply, Status};
+use ockam_core::api::Request;
 use ockam_multiaddr::proto;
 use ockam_multiaddr::{MultiAddr, Protocol as _};
 
+use crate::error::{CommandError, ErrorCode};
+use crate::identity::check_identity_exists;
 use crate::node::util::initialize_default_node;
+use crate::service::directory::{resolve_published_service, DISCOVER_PREFIX};
 use crate::tcp::util::alias_parser;
 use crate::terminal::OckamColor;
+use crate::util::api::RetryOpts;
 use crate::util::duration::duration_parser;
-use crate::util::parsers::socket_addr_parser;
+use crate::util::existing_resource::{report_existing_resource, ParametersDiff};
+use crate::util::parsers::{file_mode_parser, socket_addr_parser};
+use crate::util::retry::RetryStrategy;
 use crate::util::{find_available_port, port_is_free_guard, process_nodes_multiaddr};
 use crate::{docs, fmt_info, fmt_log, fmt_ok, fmt_warn, Command, CommandGlobalOpts, Error};
 
@@ -50,6 +57,25 @@ pub struct CreateCommand {
     #[arg(long, display_order = 900, id = "SOCKET_ADDRESS", hide_default_value = true, default_value_t = default_from_addr(), value_parser = socket_addr_parser)]
     pub from: SocketAddr,
 
+    /// Path of a Unix domain socket on which to accept connections, instead of a TCP address.
+    /// The socket file is removed when the TCP Inlet is deleted or when its node stops
+    #[arg(
+        long,
+        display_order = 900,
+        id = "SOCKET_PATH",
+        conflicts_with = "SOCKET_ADDRESS"
+    )]
+    pub from_unix: Option<PathBuf>,
+
+    /// File mode of the Unix domain socket given with `--from-unix`, in octal, e.g. "0660".
+    /// If you don't provide it, the mode is set by the umask of the node
+    #[arg(long, display_order = 900, id = "MODE", requires = "SOCKET_PATH", value_parser = file_mode_parser)]
+    pub socket_mode: Option<u32>,
+
+    /// Replace the file which already exists at the path given with `--from-unix`
+    #[arg(long, display_order = 900, requires = "SOCKET_PATH")]
+    pub force: bool,
+
     /// Route to a TCP Outlet or the name of the TCP Outlet service you want to connect to.
     ///
     /// If you are connecting to a local node, you can provide the route as `/node/n/service/outlet`.
@@ -59,9 +85,23 @@ pub struct CreateCommand {
     /// or just the name of the service as `outlet` or `/service/outlet`.
     /// If you are passing just the service name, consider using `--via` to specify the
     /// relay name (e.g. `ockam tcp-inlet create --to outlet --via myrelay`).
+    ///
+    /// A TCP Outlet published in the service directory of the default project, with
+    /// `ockam tcp-outlet create --publish` or `ockam relay create --publish`, can be given by its
+    /// name as `discover:<NAME>`. The published services are listed by `ockam service discover`.
+    ///
+    /// Several routes can be separated by commas, in which case the TCP connections
+    /// are distributed across their TCP Outlets (see `--load-balance`).
     #[arg(long, display_order = 900, id = "ROUTE", default_value_t = default_to_addr())]
     pub to: String,
 
+    /// Strategy used to distribute the TCP connections when `--to` has several routes:
+    /// `round-robin` (the default) or `least-connections`.
+    ///
+    /// The routes whose TCP Outlet recently failed to answer are skipped for a while.
+    #[arg(long, display_order = 900, id = "STRATEGY", value_parser = InletLoadBalancing::from_str)]
+    pub load_balance: Option<InletLoadBalancing>,
+
     /// Name of the relay that this TCP Inlet will use to connect to the TCP Outlet.
     ///
     /// Use this flag when you are using `--to` to specify the service name of a TCP Outlet
@@ -70,9 +110,17 @@ pub struct CreateCommand {
     #[arg(long, display_order = 900, id = "RELAY_NAME")]
     pub via: Option<String>,
 
-    /// Authorized identity for secure channel connection
+    /// Identifier, or name of a local identity, authorized for the secure channels to the TCP Outlets.
+    /// This argument can be repeated to authorize several identities.
+    /// It can't be used with project addresses, where the project identity is authorized
     #[arg(long, name = "AUTHORIZED", display_order = 900)]
-    pub authorized: Option<Identifier>,
+    pub authorized: Vec<String>,
+
+    /// Name of the identity used to establish the secure channels to the TCP Outlets.
+    /// Its own project credential is presented, so that other policies can apply to this TCP Inlet.
+    /// If you don't provide it, the identity of the node is used
+    #[arg(long, display_order = 900, id = "IDENTITY_NAME")]
+    pub identity: Option<String>,
 
     /// Assign a name to this TCP Inlet.
     #[arg(long, display_order = 900, id = "ALIAS", value_parser = alias_parser, default_value_t = random_name(), hide_default_value = true)]
@@ -85,21 +133,83 @@ pub struct CreateCommand {
     #[arg(hide = true, long = "allow", display_order = 900, id = "EXPRESSION")]
     pub policy_expression: Option<Expr>,
 
+    /// Create the TCP Inlet on an address reachable from other machines, such as 0.0.0.0,
+    /// even though no policy restricts its access: no `--allow` or `--authorized` argument,
+    /// and no policy stored on the node for the "tcp-inlet" resource type
+    #[arg(long, display_order = 900)]
+    pub allow_insecure_bind: bool,
+
     /// Time to wait for the outlet to be available.
     #[arg(long, display_order = 900, id = "WAIT", default_value = "5s", value_parser = duration_parser)]
     pub connection_wait: Duration,
 
-    /// Time to wait before retrying to connect to the TCP Outlet.
+    /// Time to wait before retrying to connect to the TCP Outlet, when no retry option is set.
+    /// The connection is retried while the outlet is not available, for at most 5 minutes.
+    /// Use "0s" to fail on the first attempt.
     #[arg(long, display_order = 900, id = "RETRY", default_value = "20s", value_parser = duration_parser)]
     pub retry_wait: Duration,
 
-    /// Override default timeout
+    /// Override the default timeout of the inlet creation request
     #[arg(long, value_parser = duration_parser)]
     pub timeout: Option<Duration>,
 
+    /// Renew the keys of the secure channels to the TCP Outlets at this interval, e.g. "1h".
+    /// The secure channels are kept open while their keys are renewed.
+    #[arg(long, display_order = 900, id = "REKEY_INTERVAL", value_parser = duration_parser)]
+    pub rekey_every: Option<Duration>,
+
+    /// Maximum number of bytes, per TCP connection, sent to the TCP Outlet and not yet
+    /// written to its TCP server. The TCP Inlet stops reading from a TCP client when this
+    /// limit is reached. If you don't provide it, 4 MiB are used.
+    #[arg(long, display_order = 900, id = "BYTES")]
+    pub buffer_size: Option<usize>,
+
+    /// Maximum number of TCP connections accepted per second.
+    /// The TCP connections exceeding this rate are closed as soon as they are accepted
+    #[arg(long, display_order = 900, id = "CONNECTIONS_PER_SECOND", value_parser = clap::value_parser!(u32).range(1..))]
+    pub max_connections_per_second: Option<u32>,
+
+    /// Maximum number of TCP connections open at the same time.
+    /// The TCP connections exceeding this number are closed as soon as they are accepted
+    #[arg(long, display_order = 900, id = "CONNECTIONS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub max_concurrent_connections: Option<u64>,
+
+    /// Send the data read from the TCP clients right away.
+    /// By default, small reads are batched for up to 1ms to send fewer messages to the TCP Outlet
+    #[arg(long, display_order = 900)]
+    pub no_delay: bool,
+
+    /// Expect a PROXY protocol v2 header at the start of each TCP connection, when the TCP Inlet
+    /// is behind a load balancer such as HAProxy or an AWS Network Load Balancer. The header is
+    /// stripped before the data is sent to the TCP Outlet, and the client address it carries is
+    /// passed on to TCP Outlets created with `--proxy-protocol`.
+    ///
+    /// The TCP connections which don't start with a valid header within 5 seconds are closed,
+    /// so the load balancer in front of the TCP Inlet must be configured to send it
+    #[arg(long, display_order = 900)]
+    pub expect_proxy_protocol: bool,
+
+    /// Send a liveness probe through the secure channel to the TCP Outlet at this interval, e.g. "2s".
+    /// If you don't provide it, a probe is sent every 10s
+    #[arg(long, display_order = 900, id = "PROBE_INTERVAL", value_parser = duration_parser)]
+    pub probe_interval: Option<Duration>,
+
+    /// Number of consecutive liveness probes left unanswered before the secure channel to the
+    /// TCP Outlet is torn down and established again. If you don't provide it, 3 probes are used
+    #[arg(long, display_order = 900, id = "PROBES", value_parser = clap::value_parser!(u32).range(1..))]
+    pub max_missed_probes: Option<u32>,
+
     /// Create the TCP Inlet without waiting for the TCP Outlet to connect
     #[arg(long, default_value = "false")]
     no_connection_wait: bool,
+
+    /// Succeed without creating the TCP Inlet if a TCP Inlet with the same alias already exists
+    /// with the same bind address and route. Fail with the differing parameters otherwise
+    #[arg(long)]
+    pub if_not_exists: bool,
+
+    #[command(flatten)]
+    retry_opts: RetryOpts,
 }
 
 pub(crate) fn default_from_addr() -> SocketAddr {
@@ -115,73 +225,132 @@ fn default_to_addr() -> String {
 impl Command for CreateCommand {
     const NAME: &'static str = "tcp-inlet create";
 
+    fn retry_opts(&self) -> Option<RetryOpts> {
+        Some(self.retry_opts.clone())
+    }
+
+    fn retry_strategy(&self) -> Option<RetryStrategy> {
+        if let Some(strategy) = RetryStrategy::from_opts(&self.retry_opts) {
+            return Some(strategy);
+        }
+        // without retry options, wait for the outlet at a fixed interval
+        if self.retry_wait.is_zero() {
+            None
+        } else {
+            Some(RetryStrategy::new(None, self.retry_wait).with_max_delay(self.retry_wait))
+        }
+    }
+
     async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
         initialize_default_node(ctx, &opts).await?;
-        let cmd = self.parse_args(&opts).await?;
+        let cmd = self.parse_args(ctx, &opts).await?;
+        let authorized = cmd.authorized_identifiers(&opts).await?;
+        if cmd.if_not_exists {
+            let node = BackgroundNodeClient::create(ctx, &opts.state, &cmd.at).await?;
+            let inlets: InletList = node.ask(ctx, Request::get("/node/inlet")).await?;
+            if let Some(inlet) = inlets.list.iter().find(|i| i.alias == cmd.alias) {
+                return report_existing_resource(
+                    &opts,
+                    "TCP Inlet",
+                    &cmd.alias,
+                    cmd.parameters_diff(inlet),
+                );
+            }
+        }
         opts.terminal.write_line(&fmt_log!(
             "Creating TCP Inlet at {}...\n",
-            cmd.from
-                .to_string()
-                .color(OckamColor::PrimaryResource.color())
+            cmd.bind_addr().color(OckamColor::PrimaryResource.color())
         ))?;
 
-        let mut node = BackgroundNodeClient::create(ctx, &opts.state, &cmd.at).await?;
-        cmd.timeout.map(|t| node.set_timeout_mut(t));
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &cmd.at).await?;
+        cmd.guard_insecure_bind(ctx, &opts, &node, &authorized)
+            .await?;
+        // The timeout only applies to the inlet creation request
+        let inlet_node = match cmd.timeout {
+            Some(timeout) => node.with_timeout(timeout),
+            None => node.clone(),
+        };
 
         let is_finished: Mutex<bool> = Mutex::new(false);
         let progress_bar = opts.terminal.progress_spinner();
         let create_inlet = async {
-            port_is_free_guard(&cmd.from)?;
-            if cmd.to().matches(0, &[proto::Project::CODE.into()]) && cmd.authorized.is_some() {
-                return Err(miette!(
-                    "--authorized can not be used with project addresses"
-                ))?;
+            match &cmd.from_unix {
+                Some(path) => {
+                    if !cmd.force && path.symlink_metadata().is_ok() {
+                        return Err(CommandError::with_code(
+                            miette!(
+                                "The file {} already exists. Use --force to replace it with the Unix domain socket of the TCP Inlet",
+                                path.display()
+                            ),
+                            ErrorCode::Usage,
+                        ))?;
+                    }
+                }
+                None => port_is_free_guard(&cmd.from)?,
+            }
+            if let Some(project_addr) = cmd
+                .outlet_addrs()
+                .iter()
+                .find(|to| to.matches(0, &[proto::Project::CODE.into()]))
+            {
+                if !cmd.authorized.is_empty() {
+                    let errors = cmd
+                        .authorized
+                        .iter()
+                        .map(|a| format!("--authorized {a} can not be used with the project address {project_addr}"))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    return Err(CommandError::with_code(miette!(errors), ErrorCode::Usage))?;
+                }
             }
 
-            let inlet = loop {
-                let result: Reply<InletStatus> = node
-                    .create_inlet(
-                        ctx,
-                        &cmd.from.to_string(),
-                        &cmd.to(),
-                        &cmd.alias,
-                        &cmd.authorized,
-                        &cmd.policy_expression,
-                        cmd.connection_wait,
-                        !cmd.no_connection_wait,
-                    )
-                    .await?;
-
-                match result {
-                    Reply::Successful(inlet_status) => {
-                        *is_finished.lock().await = true;
-                        break inlet_status;
-                    }
-                    Reply::Failed(_, s) => {
-                        if let Some(status) = s {
-                            if status == Status::BadRequest {
-                                Err(miette!("Bad request when creating an inlet"))?
-                            }
-                        };
-                        trace!("the inlet creation returned a non-OK status: {s:?}");
-
-                        if cmd.retry_wait.as_millis() == 0 {
-                            return Err(miette!("Failed to create TCP inlet"))?;
-                        }
+            let node_name = inlet_node.node_name();
+            let options = CreateInletOptions::new(&cmd.bind_addr(), &cmd.to(), &cmd.alias)
+                .with_authorized_identifiers(&authorized)
+                .with_policy_expression(cmd.policy_expression.clone())
+                .with_wait_for_outlet_timeout(cmd.connection_wait)
+                .with_wait_connection(!cmd.no_connection_wait)
+                .with_load_balancing(&cmd.outlet_addrs()[1..], cmd.load_balance)
+                .with_secure_channel_rekey_interval(cmd.rekey_every)
+                .with_buffer_size(cmd.buffer_size)
+                .with_connection_limits(
+                    cmd.max_connections_per_second,
+                    cmd.max_concurrent_connections,
+                )
+                .with_no_delay(cmd.no_delay)
+                .with_liveness(cmd.probe_interval, cmd.max_missed_probes)
+                .with_identity(cmd.identity.clone())
+                .with_unix_socket(cmd.socket_mode, cmd.force)
+                .with_expect_proxy_protocol(cmd.expect_proxy_protocol);
+            let result = match inlet_node.create_inlet(ctx, options).await {
+                Ok(reply) => NodeRequestError::check_reply(&node_name, reply),
+                Err(e) => Err(NodeRequestError::from_report(&node_name, e)),
+            };
 
-                        if let Some(spinner) = progress_bar.as_ref() {
-                            spinner.set_message(format!(
-                                "Waiting for inlet {} to be available... Retrying momentarily",
-                                &cmd.to
-                                    .to_string()
-                                    .color(OckamColor::PrimaryResource.color())
-                            ));
+            let inlet = match result {
+                Ok(inlet_status) => inlet_status,
+                Err(e) => {
+                    trace!("the inlet creation failed: {e}");
+                    let code = ErrorCode::from_node_request_error(&e);
+                    let retryable = is_retryable(&e);
+                    let report = match e {
+                        NodeRequestError::Failed { .. } => {
+                            miette!("Failed to create TCP inlet: {e}")
                         }
-                        tokio::time::sleep(cmd.retry_wait).await
+                        _ => miette!(
+                            "Failed to create TCP inlet. {e}. Check that the node is running with `ockam node show {node_name}`"
+                        ),
+                    };
+                    let error = CommandError::with_code(report, code);
+                    // the outlet may not be available yet, the inlet creation is retried
+                    if retryable {
+                        Err(Error::Retry(error.into()))?
+                    } else {
+                        Err(error)?
                     }
                 }
             };
-
+            *is_finished.lock().await = true;
             Ok(inlet)
         };
 
@@ -190,12 +359,20 @@ impl Command for CreateCommand {
                 "Creating TCP Inlet on {}...",
                 &node.node_name().color(OckamColor::PrimaryResource.color())
             ),
-            format!(
-                "Hosting TCP Socket at {}...",
-                &cmd.from
-                    .to_string()
-                    .color(OckamColor::PrimaryResource.color())
-            ),
+            match &cmd.from_unix {
+                Some(path) => format!(
+                    "Hosting Unix Socket at {}...",
+                    path.display()
+                        .to_string()
+                        .color(OckamColor::PrimaryResource.color())
+                ),
+                None => format!(
+                    "Hosting TCP Socket at {}...",
+                    &cmd.from
+                        .to_string()
+                        .color(OckamColor::PrimaryResource.color())
+                ),
+            },
             format!(
                 "Establishing connection to outlet {}...",
                 &cmd.to
@@ -214,44 +391,56 @@ impl Command for CreateCommand {
         cmd.add_inlet_created_event(&opts, &node_name, &inlet)
             .await?;
 
-        opts.terminal
-            .stdout()
-            .plain(if cmd.no_connection_wait {
-                fmt_ok!(
+        let authorized_output = if authorized.is_empty() {
+            "".to_string()
+        } else {
+            "\n".to_string()
+                + &fmt_log!(
+                    "Only the outlets with the identities {} are trusted",
+                    authorized
+                        .iter()
+                        .map(|i| i
+                            .to_string()
+                            .color(OckamColor::PrimaryResource.color())
+                            .to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+        };
+        let plain = if cmd.no_connection_wait {
+            fmt_ok!(
                     "The inlet {} on node {} will automatically connect when the outlet at {} is available\n",
-                    &cmd.from
-                        .to_string()
+                    &cmd.bind_addr()
                         .color(OckamColor::PrimaryResource.color()),
                     &node.node_name().color(OckamColor::PrimaryResource.color()),
                     &cmd.to
                         .to_string()
                         .color(OckamColor::PrimaryResource.color())
                 )
-            } else if inlet.status == ConnectionStatus::Up {
-                fmt_ok!(
-                    "TCP inlet {} on node {} is now sending traffic\n",
-                    &cmd.from
-                        .to_string()
-                        .color(OckamColor::PrimaryResource.color()),
-                    &node.node_name().color(OckamColor::PrimaryResource.color())
-                ) + &fmt_log!(
-                    "to the outlet at {}",
-                    &cmd.to
-                        .to_string()
-                        .color(OckamColor::PrimaryResource.color())
-                )
-            } else {
-                fmt_warn!(
-                    "TCP inlet {} on node {} failed to connect to the outlet at {}\n",
-                    &cmd.from
-                        .to_string()
-                        .color(OckamColor::PrimaryResource.color()),
-                    &node.node_name().color(OckamColor::PrimaryResource.color()),
-                    &cmd.to
-                        .to_string()
-                        .color(OckamColor::PrimaryResource.color())
-                ) + &fmt_info!("TCP inlet will retry to connect automatically")
-            })
+        } else if inlet.status == ConnectionStatus::Up {
+            fmt_ok!(
+                "TCP inlet {} on node {} is now sending traffic\n",
+                &cmd.bind_addr().color(OckamColor::PrimaryResource.color()),
+                &node.node_name().color(OckamColor::PrimaryResource.color())
+            ) + &fmt_log!(
+                "to the outlet at {}",
+                &cmd.to
+                    .to_string()
+                    .color(OckamColor::PrimaryResource.color())
+            )
+        } else {
+            fmt_warn!(
+                "TCP inlet {} on node {} failed to connect to the outlet at {}\n",
+                &cmd.bind_addr().color(OckamColor::PrimaryResource.color()),
+                &node.node_name().color(OckamColor::PrimaryResource.color()),
+                &cmd.to
+                    .to_string()
+                    .color(OckamColor::PrimaryResource.color())
+            ) + &fmt_info!("TCP inlet will retry to connect automatically")
+        };
+        opts.terminal
+            .stdout()
+            .plain(plain + &authorized_output)
             .machine(inlet.bind_addr.to_string())
             .json(serde_json::json!(&inlet))
             .write_line()?;
@@ -262,7 +451,99 @@ impl Command for CreateCommand {
 
 impl CreateCommand {
     fn to(&self) -> MultiAddr {
-        MultiAddr::from_str(&self.to).unwrap()
+        self.outlet_addrs().remove(0)
+    }
+
+    /// Address on which the inlet accepts connections: its TCP address,
+    /// or the path of its Unix domain socket as `unix:<path>`
+    fn bind_addr(&self) -> String {
+        match &self.from_unix {
+            Some(path) => format!("unix:{}", path.display()),
+            None => self.from.to_string(),
+        }
+    }
+
+    /// Return true if the inlet accepts TCP connections from other machines, and no argument
+    /// restricts its access. A policy might still be stored on the node for all the inlets
+    fn has_unrestricted_bind(&self, authorized: &[Identifier]) -> bool {
+        self.from_unix.is_none()
+            && !self.from.ip().is_loopback()
+            && self.policy_expression.is_none()
+            && authorized.is_empty()
+    }
+
+    /// Refuse to create an inlet reachable from other machines without any policy,
+    /// unless `--allow-insecure-bind` is used
+    async fn guard_insecure_bind(
+        &self,
+        ctx: &Context,
+        opts: &CommandGlobalOpts,
+        node: &BackgroundNodeClient,
+        authorized: &[Identifier],
+    ) -> miette::Result<()> {
+        if !self.has_unrestricted_bind(authorized) {
+            return Ok(());
+        }
+        let has_policy = node
+            .has_policy_for_resource_type(ctx, &ResourceType::TcpInlet)
+            .await?;
+        self.check_insecure_bind(&node.node_name(), authorized, has_policy)?;
+        if !has_policy {
+            opts.terminal.write_line(fmt_warn!(
+                "The TCP Inlet accepts connections on {} from any machine which can reach this address, without any policy",
+                self.from
+            ))?;
+        }
+        Ok(())
+    }
+
+    /// Return an error if the inlet is reachable from other machines, no policy is stored
+    /// on the node for the TCP Inlets, and `--allow-insecure-bind` is not used
+    fn check_insecure_bind(
+        &self,
+        node_name: &str,
+        authorized: &[Identifier],
+        has_policy: bool,
+    ) -> miette::Result<()> {
+        if has_policy || self.allow_insecure_bind || !self.has_unrestricted_bind(authorized) {
+            return Ok(());
+        }
+        Err(CommandError::with_code(
+            miette!(
+                "The TCP Inlet would accept connections on {} from any machine which can reach this address, \
+                and no policy restricts its access.\n\
+                Restrict its access with `--allow <expression>` or `--authorized <identifier>`, \
+                or create a policy for all the TCP Inlets of the node with \
+                `ockam policy create --at {} --resource-type tcp-inlet --expression <expression>`.\n\
+                You can also accept connections from this machine only, with `--from 127.0.0.1:{}`, \
+                or create the TCP Inlet anyway with `--allow-insecure-bind`",
+                self.from,
+                node_name,
+                self.from.port()
+            ),
+            ErrorCode::Usage,
+        )
+        .into())
+    }
+
+    /// Return the parameters of an existing inlet which differ from the requested ones
+    fn parameters_diff(&self, inlet: &InletStatus) -> ParametersDiff {
+        ParametersDiff::new()
+            .compare("from", &inlet.bind_addr, self.bind_addr())
+            .compare("to", &inlet.outlet_addr, self.to())
+            .compare(
+                "expect-proxy-protocol",
+                inlet.expect_proxy_protocol,
+                self.expect_proxy_protocol,
+            )
+    }
+
+    /// Routes to the outlets, the connections are distributed across them if there are several
+    fn outlet_addrs(&self) -> Vec<MultiAddr> {
+        self.to
+            .split(',')
+            .map(|to| MultiAddr::from_str(to).unwrap())
+            .collect()
     }
 
     async fn add_inlet_created_event(
@@ -273,7 +554,7 @@ impl CreateCommand {
     ) -> miette::Result<()> {
         let mut attributes = HashMap::new();
         attributes.insert(TCP_INLET_AT, node_name.to_string());
-        attributes.insert(TCP_INLET_FROM, self.from.to_string());
+        attributes.insert(TCP_INLET_FROM, self.bind_addr());
         attributes.insert(TCP_INLET_TO, self.to.clone());
         attributes.insert(TCP_INLET_ALIAS, inlet.alias.clone());
         attributes.insert(TCP_INLET_CONNECTION_STATUS, inlet.status.to_string());
@@ -284,8 +565,42 @@ impl CreateCommand {
             .await?)
     }
 
-    async fn parse_args(mut self, opts: &CommandGlobalOpts) -> miette::Result<Self> {
-        self.to = Self::parse_arg_to(&opts.state, self.to, self.via.as_ref()).await?;
+    /// Resolve the `--authorized` values, which are either identifiers or names of local identities
+    async fn authorized_identifiers(
+        &self,
+        opts: &CommandGlobalOpts,
+    ) -> miette::Result<Vec<Identifier>> {
+        let mut identifiers: Vec<Identifier> = vec![];
+        for authorized in &self.authorized {
+            let identifier = match Identifier::from_str(authorized) {
+                Ok(identifier) => identifier,
+                Err(_) => {
+                    check_identity_exists(opts, authorized).await?;
+                    opts.state.get_identifier_by_name(authorized).await?
+                }
+            };
+            if !identifiers.contains(&identifier) {
+                identifiers.push(identifier);
+            }
+        }
+        Ok(identifiers)
+    }
+
+    async fn parse_args(mut self, ctx: &Context, opts: &CommandGlobalOpts) -> miette::Result<Self> {
+        if let Some(identity) = &self.identity {
+            check_identity_exists(opts, identity).await?;
+        }
+        let mut routes = vec![];
+        for to in self.to.split(',').map(str::trim) {
+            let route = match to.strip_prefix(DISCOVER_PREFIX) {
+                Some(name) => resolve_published_service(ctx, opts, name, self.identity.clone())
+                    .await?
+                    .to_string(),
+                None => Self::parse_arg_to(&opts.state, to, self.via.as_ref()).await?,
+            };
+            routes.push(route);
+        }
+        self.to = routes.join(",");
         Ok(self)
     }
 
@@ -353,10 +668,19 @@ impl CreateCommand {
     }
 }
 
+/// Return true if the inlet creation can succeed when it is retried,
+/// for example when the outlet is not available yet.
+/// A node which can't be reached is not waited for
+fn is_retryable(error: &NodeRequestError) -> bool {
+    error.is_retryable() && !matches!(error, NodeRequestError::Unreachable { .. })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::run::parser::resource::utils::parse_cmd_from_args;
+    use crate::tcp::inlet::{TcpInletCommand, TcpInletSubCommand};
+    use crate::OckamSubcommand;
     use ockam_api::cloud::project::models::ProjectModel;
     use ockam_api::cloud::project::Project;
     use ockam_api::nodes::InMemoryNode;
@@ -367,6 +691,198 @@ mod tests {
         assert!(cmd.is_ok());
     }
 
+    #[test]
+    fn an_unreachable_node_is_not_retried() {
+        assert!(!is_retryable(&NodeRequestError::unreachable(
+            "n1",
+            "connection refused"
+        )));
+        assert!(is_retryable(&NodeRequestError::Timeout {
+            node_name: "n1".to_string(),
+            timeout: Duration::from_secs(10),
+        }));
+        assert!(is_retryable(&NodeRequestError::Failed {
+            node_name: "n1".to_string(),
+            message: "the outlet is not available".to_string(),
+            status: Some(ockam_core::api::Status::InternalServerError),
+        }));
+    }
+
+    #[test]
+    fn the_outlet_is_waited_for_a_bounded_time() {
+        let cmd = parse_cmd_from_args(
+            CreateCommand::NAME,
+            &["--retry-wait".to_string(), "10s".to_string()],
+        )
+        .unwrap();
+        let OckamSubcommand::TcpInlet(TcpInletCommand {
+            subcommand: TcpInletSubCommand::Create(cmd),
+        }) = cmd
+        else {
+            panic!("unexpected command")
+        };
+        let expected = RetryStrategy::new(None, Duration::from_secs(10))
+            .with_max_delay(Duration::from_secs(10));
+        assert_eq!(cmd.retry_strategy(), Some(expected));
+    }
+
+    #[test]
+    fn parameters_diff_with_an_existing_inlet() {
+        let cmd = parse_cmd_from_args(
+            CreateCommand::NAME,
+            &[
+                "--from".to_string(),
+                "127.0.0.1:4000".to_string(),
+                "--to".to_string(),
+                "/node/n1/service/outlet".to_string(),
+                "--alias".to_string(),
+                "web".to_string(),
+                "--if-not-exists".to_string(),
+            ],
+        )
+        .unwrap();
+        let OckamSubcommand::TcpInlet(TcpInletCommand {
+            subcommand: TcpInletSubCommand::Create(cmd),
+        }) = cmd
+        else {
+            panic!("unexpected command")
+        };
+        assert!(cmd.if_not_exists);
+
+        let existing = |bind_addr: &str, outlet_addr: &str| {
+            InletStatus::new(
+                bind_addr,
+                None,
+                "web",
+                None,
+                None,
+                ConnectionStatus::Up,
+                outlet_addr,
+            )
+        };
+        assert!(cmd
+            .parameters_diff(&existing("127.0.0.1:4000", "/node/n1/service/outlet"))
+            .is_empty());
+        let diff = cmd.parameters_diff(&existing("127.0.0.1:5000", "/node/n1/service/outlet"));
+        assert_eq!(
+            diff.to_string(),
+            "  --from: 127.0.0.1:5000 (existing) != 127.0.0.1:4000 (requested)\n"
+        );
+        let diff = cmd.parameters_diff(
+            &existing("127.0.0.1:4000", "/node/n1/service/outlet").with_expect_proxy_protocol(true),
+        );
+        assert_eq!(
+            diff.to_string(),
+            "  --expect-proxy-protocol: true (existing) != false (requested)\n"
+        );
+    }
+
+    #[test]
+    fn from_unix_socket() {
+        let args = |args: &[&str]| args.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+        let cmd = parse_cmd_from_args(
+            CreateCommand::NAME,
+            &args(&["--from-unix", "/tmp/inlet.sock", "--socket-mode", "0660"]),
+        )
+        .unwrap();
+        let OckamSubcommand::TcpInlet(TcpInletCommand {
+            subcommand: TcpInletSubCommand::Create(cmd),
+        }) = cmd
+        else {
+            panic!("unexpected command")
+        };
+        assert_eq!(cmd.bind_addr(), "unix:/tmp/inlet.sock");
+        assert_eq!(cmd.socket_mode, Some(0o660));
+        assert!(!cmd.force);
+
+        // --from-unix can't be used with --from, and --socket-mode requires --from-unix
+        assert!(parse_cmd_from_args(
+            CreateCommand::NAME,
+            &args(&["--from", "127.0.0.1:4000", "--from-unix", "/tmp/inlet.sock"]),
+        )
+        .is_err());
+        assert!(
+            parse_cmd_from_args(CreateCommand::NAME, &args(&["--socket-mode", "0660"])).is_err()
+        );
+    }
+
+    #[test]
+    fn only_the_binds_reachable_from_other_machines_require_a_policy() {
+        let parse = |args: &[&str]| {
+            let args = args.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+            let OckamSubcommand::TcpInlet(TcpInletCommand {
+                subcommand: TcpInletSubCommand::Create(cmd),
+            }) = parse_cmd_from_args(CreateCommand::NAME, &args).unwrap()
+            else {
+                panic!("unexpected command")
+            };
+            cmd
+        };
+        let authorized = [Identifier::from_str(
+            "Ie92f183eb4c324804ef4d62962dea94cf095a265a1b2c3d4e5f6a6b5c4d3e2f1",
+        )
+        .unwrap()];
+
+        // loopback addresses and unix sockets are only reachable from this machine
+        assert!(!parse(&["--from", "127.0.0.1:4000"]).has_unrestricted_bind(&[]));
+        assert!(!parse(&["--from", "[::1]:4000"]).has_unrestricted_bind(&[]));
+        assert!(!parse(&["--from-unix", "/tmp/inlet.sock"]).has_unrestricted_bind(&[]));
+
+        // other addresses require --allow or --authorized, or a policy stored on the node
+        assert!(parse(&["--from", "0.0.0.0:4000"]).has_unrestricted_bind(&[]));
+        assert!(parse(&["--from", "192.168.1.10:4000"]).has_unrestricted_bind(&[]));
+        assert!(!parse(&[
+            "--from",
+            "0.0.0.0:4000",
+            "--allow",
+            "(= subject.role \"db\")"
+        ])
+        .has_unrestricted_bind(&[]));
+        assert!(!parse(&["--from", "0.0.0.0:4000"]).has_unrestricted_bind(&authorized));
+
+        let cmd = parse(&["--from", "0.0.0.0:4000", "--allow-insecure-bind"]);
+        assert!(cmd.allow_insecure_bind);
+    }
+
+    #[test]
+    fn the_binds_reachable_from_other_machines_are_refused_without_a_policy() {
+        let parse = |args: &[&str]| {
+            let args = args.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+            let OckamSubcommand::TcpInlet(TcpInletCommand {
+                subcommand: TcpInletSubCommand::Create(cmd),
+            }) = parse_cmd_from_args(CreateCommand::NAME, &args).unwrap()
+            else {
+                panic!("unexpected command")
+            };
+            cmd
+        };
+
+        // refused when nothing restricts the access to the inlet
+        let cmd = parse(&["--from", "0.0.0.0:4000"]);
+        let error = cmd.check_insecure_bind("n1", &[], false).unwrap_err();
+        assert!(error.to_string().contains("--allow-insecure-bind"));
+        assert!(error.to_string().contains("--at n1"));
+
+        // accepted with a policy stored on the node, with --allow, or with an explicit opt-in
+        assert!(cmd.check_insecure_bind("n1", &[], true).is_ok());
+        assert!(parse(&[
+            "--from",
+            "0.0.0.0:4000",
+            "--allow",
+            "(= subject.role \"db\")"
+        ])
+        .check_insecure_bind("n1", &[], false)
+        .is_ok());
+        assert!(parse(&["--from", "0.0.0.0:4000", "--allow-insecure-bind"])
+            .check_insecure_bind("n1", &[], false)
+            .is_ok());
+
+        // always accepted when the inlet is only reachable from this machine
+        assert!(parse(&["--from", "127.0.0.1:4000"])
+            .check_insecure_bind("n1", &[], false)
+            .is_ok());
+    }
+
     #[ockam_macros::test]
     async fn parse_arg_to(ctx: &mut Context) -> ockam_core::Result<()> {
         // Setup
@@ -459,4 +975,34 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn parse_arg_to_with_imported_project() {
+        let state = CliState::test().await.unwrap();
+        state
+            .projects()
+            .import_project(ProjectModel {
+                id: "p1-id".to_string(),
+                identity: Some(
+                    Identifier::from_str(
+                        "Ie92f183eb4c324804ef4d62962dea94cf095a265a1b2c3d4e5f6a6b5c4d3e2f1",
+                    )
+                    .unwrap(),
+                ),
+                name: "p1".to_string(),
+                access_route: "/dnsaddr/p1.example.com/tcp/4000/service/api".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        // the imported project is the default project
+        let res = CreateCommand::parse_arg_to(&state, "myoutlet", None)
+            .await
+            .unwrap();
+        assert_eq!(
+            res,
+            "/project/p1/service/forward_to_default/secure/api/service/myoutlet".to_string()
+        );
+    }
 }
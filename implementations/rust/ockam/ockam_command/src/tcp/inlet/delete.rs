@@ -124,6 +124,7 @@ impl DeleteCommandTui for DeleteTui {
                 color!(item_name, OckamColor::PrimaryResource),
                 color!(node_name, OckamColor::PrimaryResource)
             ))
+            .json(serde_json::json!({ "alias": item_name, "node": node_name }))
             .write_line()?;
         Ok(())
     }
@@ -0,0 +1,58 @@
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+
+use ockam::Context;
+use ockam_api::nodes::service::portals::Inlets;
+use ockam_api::nodes::BackgroundNodeClient;
+
+use crate::node::NodeOpts;
+use crate::tcp::util::alias_parser;
+use crate::{color_primary, docs, fmt_ok, Command, CommandGlobalOpts};
+
+const PREVIEW_TAG: &str = include_str!("../../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/disconnect/after_long_help.txt");
+
+/// Close one of the active connections of a TCP Inlet
+#[derive(Clone, Debug, Args)]
+#[command(
+before_help = docs::before_help(PREVIEW_TAG),
+after_long_help = docs::after_help(AFTER_LONG_HELP))]
+pub struct DisconnectCommand {
+    /// Name of the inlet
+    #[arg(display_order = 900, required = true, id = "ALIAS", value_parser = alias_parser)]
+    alias: String,
+
+    /// Identifier of the connection to close, as displayed by `ockam tcp-inlet connections`
+    #[arg(long, value_name = "CONNECTION_ID")]
+    connection: String,
+
+    /// Node on which the inlet was started
+    #[command(flatten)]
+    node_opts: NodeOpts,
+}
+
+#[async_trait]
+impl Command for DisconnectCommand {
+    const NAME: &'static str = "tcp-inlet disconnect";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.node_opts.at_node).await?;
+        let connection = node
+            .close_inlet_connection(ctx, &self.alias, &self.connection)
+            .await?;
+
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "The connection {} from {} to the TCP Inlet {} has been closed",
+                color_primary(&connection.id),
+                color_primary(connection.source_addr.to_string()),
+                color_primary(&self.alias)
+            ))
+            .machine(&connection.id)
+            .json(serde_json::json!(&connection))
+            .write_line()?;
+        Ok(())
+    }
+}
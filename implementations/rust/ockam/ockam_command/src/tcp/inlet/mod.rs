@@ -1,13 +1,19 @@
+mod connections;
 pub(crate) mod create;
 mod delete;
+mod disconnect;
 mod list;
+mod rename;
 mod show;
 
 use crate::{docs, Command, CommandGlobalOpts};
 use clap::{Args, Subcommand};
+use connections::ConnectionsCommand;
 use create::CreateCommand;
 use delete::DeleteCommand;
+use disconnect::DisconnectCommand;
 pub(crate) use list::ListCommand;
+use rename::RenameCommand;
 pub(crate) use show::ShowCommand;
 
 const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
@@ -32,6 +38,9 @@ pub enum TcpInletSubCommand {
     Delete(DeleteCommand),
     List(ListCommand),
     Show(ShowCommand),
+    Rename(RenameCommand),
+    Connections(ConnectionsCommand),
+    Disconnect(DisconnectCommand),
 }
 
 impl TcpInletCommand {
@@ -41,6 +50,9 @@ impl TcpInletCommand {
             TcpInletSubCommand::Delete(c) => c.run(opts),
             TcpInletSubCommand::List(c) => c.run(opts),
             TcpInletSubCommand::Show(c) => c.run(opts),
+            TcpInletSubCommand::Rename(c) => c.run(opts),
+            TcpInletSubCommand::Connections(c) => c.run(opts),
+            TcpInletSubCommand::Disconnect(c) => c.run(opts),
         }
     }
 
@@ -50,6 +62,9 @@ impl TcpInletCommand {
             TcpInletSubCommand::Delete(c) => c.name(),
             TcpInletSubCommand::List(c) => c.name(),
             TcpInletSubCommand::Show(c) => c.name(),
+            TcpInletSubCommand::Rename(c) => c.name(),
+            TcpInletSubCommand::Connections(c) => c.name(),
+            TcpInletSubCommand::Disconnect(c) => c.name(),
         }
     }
 }
@@ -0,0 +1,58 @@
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+
+use ockam::Context;
+use ockam_api::nodes::service::portals::Inlets;
+use ockam_api::nodes::{BackgroundNodeClient, NodeRequestError};
+
+use crate::node::NodeOpts;
+use crate::tcp::util::alias_parser;
+use crate::{color_primary, docs, fmt_ok, Command, CommandGlobalOpts};
+
+const AFTER_LONG_HELP: &str = include_str!("./static/rename/after_long_help.txt");
+
+/// Rename a TCP Inlet.
+///
+/// The policies set for the inlet are kept. If the inlet is connected to its outlet,
+/// it is created again and its open connections are closed.
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = docs::after_help(AFTER_LONG_HELP))]
+pub struct RenameCommand {
+    /// Current alias of the inlet
+    #[arg(display_order = 900, required = true, id = "ALIAS", value_parser = alias_parser)]
+    pub alias: String,
+
+    /// New alias of the inlet
+    #[arg(display_order = 901, required = true, id = "NEW_ALIAS", value_parser = alias_parser)]
+    pub new_alias: String,
+
+    /// Node on which the inlet was started
+    #[command(flatten)]
+    pub node_opts: NodeOpts,
+}
+
+#[async_trait]
+impl Command for RenameCommand {
+    const NAME: &'static str = "tcp-inlet rename";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.node_opts.at_node).await?;
+        let reply = node.rename_inlet(ctx, &self.alias, &self.new_alias).await?;
+        let inlet_status = NodeRequestError::check_reply(&node.node_name(), reply)
+            .map_err(miette::Report::from)?;
+
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "The TCP Inlet {} on node {} has been renamed to {}",
+                color_primary(&self.alias),
+                color_primary(node.node_name()),
+                color_primary(&self.new_alias)
+            ))
+            .machine(&self.new_alias)
+            .json(serde_json::json!(&inlet_status))
+            .write_line()?;
+        Ok(())
+    }
+}
@@ -3,12 +3,14 @@ use colorful::Colorful;
 use indoc::formatdoc;
 use miette::IntoDiagnostic;
 
+use ockam::identity::TimestampInSeconds;
 use ockam::Context;
 use ockam_api::nodes::models::portal::InletStatus;
 use ockam_api::nodes::service::portals::Inlets;
 use ockam_api::nodes::BackgroundNodeClient;
 
 use crate::node::NodeOpts;
+use crate::output::human_readable_time;
 use crate::tcp::util::alias_parser;
 use crate::util::async_cmd;
 use crate::{docs, fmt_ok, CommandGlobalOpts};
@@ -29,6 +31,10 @@ pub struct ShowCommand {
     /// Node on which the inlet was started
     #[command(flatten)]
     node_opts: NodeOpts,
+
+    /// Number of recent connection status changes to display
+    #[arg(long, value_name = "COUNT", default_value_t = 5)]
+    transitions: usize,
 }
 
 impl ShowCommand {
@@ -57,18 +63,72 @@ impl ShowCommand {
             outlet_route,
             status,
             outlet_addr,
+            load_balancing,
+            outlet_routes,
+            backpressure_pauses,
+            transitions,
+            connection_limits,
+            no_delay,
+            liveness,
+            identity,
+            unix_socket_mode,
+            expect_proxy_protocol,
             ..
         } = inlet_status;
 
         let outlet_route = outlet_route.unwrap_or("N/A".to_string());
-        let plain = formatdoc! {r#"
+        let identity = identity.unwrap_or("the node identity".to_string());
+        let listen = match bind_addr.strip_prefix("unix:") {
+            Some(path) => match unix_socket_mode {
+                Some(mode) => format!("Unix Socket: {path} (mode {mode:04o})"),
+                None => format!("Unix Socket: {path}"),
+            },
+            None => format!("TCP Address: {bind_addr}"),
+        };
+        let mut plain = formatdoc! {r#"
         Inlet:
           Alias: {alias}
           Status: {status}
-          TCP Address: {bind_addr}
+          {listen}
           Outlet Route: {outlet_route}
           Outlet Destination: {outlet_addr}
+          Identity: {identity}
+          Paused Due To Backpressure: {backpressure_pauses} times
     "#};
+        if let Some(load_balancing) = load_balancing {
+            plain.push_str(&format!("  Load Balancing: {load_balancing}\n"));
+            for route in outlet_routes {
+                plain.push_str(&format!("    {route}\n"));
+            }
+        }
+        if let Some(connection_limits) = connection_limits {
+            plain.push_str(&format!("  Connection Limits: {connection_limits}\n"));
+        }
+        if no_delay {
+            plain.push_str("  No Delay: the data is sent as soon as it is read\n");
+        }
+        if expect_proxy_protocol {
+            plain.push_str("  PROXY Protocol: a v2 header is expected from the TCP clients\n");
+        }
+        if let Some(liveness) = liveness {
+            plain.push_str(&format!("  Liveness: {liveness}\n"));
+        }
+        if self.transitions > 0 && !transitions.is_empty() {
+            plain.push_str("  Connection Transitions:\n");
+            let skipped = transitions.len().saturating_sub(self.transitions);
+            for transition in transitions.into_iter().skip(skipped) {
+                plain.push_str(&format!(
+                    "    {}: {} -> {}",
+                    human_readable_time(TimestampInSeconds(transition.at)),
+                    transition.from,
+                    transition.to
+                ));
+                if let Some(reason) = transition.reason {
+                    plain.push_str(&format!(" ({reason})"));
+                }
+                plain.push('\n');
+            }
+        }
         let machine = bind_addr;
         opts.terminal
             .stdout()
@@ -9,6 +9,7 @@ use ockam_multiaddr::proto::{DnsAddr, Tcp};
 use ockam_multiaddr::MultiAddr;
 use ockam_node::Context;
 
+use crate::node::models::transport::ShowTransportStatus;
 use crate::node::util::initialize_default_node;
 use crate::util::async_cmd;
 use crate::{docs, CommandGlobalOpts};
@@ -26,6 +27,11 @@ pub struct CreateCommand {
 
     /// Address for this listener (eg. 127.0.0.1:7000)
     pub address: String,
+
+    /// Close the accepted connections receiving a message larger than this number of bytes,
+    /// instead of using the limit of the node
+    #[arg(long, value_name = "BYTES")]
+    pub max_message_size: Option<u64>,
 }
 
 impl CreateCommand {
@@ -45,8 +51,10 @@ impl CreateCommand {
         let transport_status: TransportStatus = node
             .ask(
                 ctx,
-                Request::post("/node/tcp/listener")
-                    .body(CreateTcpListener::new(self.address.clone())),
+                Request::post("/node/tcp/listener").body(
+                    CreateTcpListener::new(self.address.clone())
+                        .with_max_message_size(self.max_message_size),
+                ),
             )
             .await?;
 
@@ -64,6 +72,11 @@ impl CreateCommand {
                 fmt_ok!("Tcp listener created! You can send messages to it via this route:\n")
                     + &fmt_log!("{multiaddr}"),
             )
+            .machine(&multiaddr)
+            .json(
+                serde_json::to_string_pretty(&ShowTransportStatus::from(transport_status))
+                    .into_diagnostic()?,
+            )
             .write_line()?;
 
         Ok(())
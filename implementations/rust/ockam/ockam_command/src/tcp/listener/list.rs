@@ -1,5 +1,6 @@
 use clap::Args;
 use colorful::Colorful;
+use miette::IntoDiagnostic;
 use tokio::sync::Mutex;
 use tokio::try_join;
 
@@ -7,6 +8,7 @@ use ockam::Context;
 use ockam_api::nodes::models::transport::TransportList;
 use ockam_api::nodes::BackgroundNodeClient;
 
+use crate::node::models::transport::ShowTransportStatus;
 use crate::node::NodeOpts;
 use crate::terminal::OckamColor;
 use crate::util::{api, async_cmd};
@@ -65,7 +67,15 @@ impl ListCommand {
                 node.node_name().color(OckamColor::PrimaryResource.color())
             ),
         )?;
-        opts.terminal.stdout().plain(list).write_line()?;
+        let json = serde_json::to_string_pretty(
+            &transports
+                .list
+                .into_iter()
+                .map(ShowTransportStatus::from)
+                .collect::<Vec<_>>(),
+        )
+        .into_diagnostic()?;
+        opts.terminal.stdout().plain(list).json(json).write_line()?;
         Ok(())
     }
 }
@@ -6,6 +6,7 @@ use ockam_api::nodes::models::transport::TransportStatus;
 use ockam_api::nodes::BackgroundNodeClient;
 use ockam_core::api::Request;
 
+use crate::node::models::transport::ShowTransportStatus;
 use crate::node::NodeOpts;
 use crate::output::Output;
 use crate::util::async_cmd;
@@ -49,7 +50,10 @@ impl ShowCommand {
         opts.terminal
             .stdout()
             .plain(transport_status.output()?)
-            .json(serde_json::to_string(&transport_status).into_diagnostic()?)
+            .json(
+                serde_json::to_string_pretty(&ShowTransportStatus::from(transport_status))
+                    .into_diagnostic()?,
+            )
             .write_line()?;
         Ok(())
     }
@@ -1,6 +1,5 @@
 use async_trait::async_trait;
 use std::collections::HashMap;
-use std::net::SocketAddr;
 
 use clap::Args;
 use colorful::Colorful;
@@ -13,13 +12,19 @@ use ockam::Context;
 use ockam_abac::Expr;
 use ockam_api::address::extract_address_value;
 use ockam_api::journeys::{JourneyEvent, NODE_NAME, TCP_OUTLET_AT, TCP_OUTLET_FROM, TCP_OUTLET_TO};
+use ockam_api::nodes::models::portal::{OutletList, OutletStatus};
 use ockam_api::nodes::service::portals::Outlets;
 use ockam_api::nodes::BackgroundNodeClient;
+use ockam_api::DefaultAddress;
+use ockam_core::api::Request;
 use ockam_core::Address;
 
+use crate::identity::check_identity_exists;
 use crate::node::util::initialize_default_node;
+use crate::service::directory::{node_identity_name, publish_service, published_outlet_route};
 
-use crate::util::parsers::socket_addr_parser;
+use crate::util::existing_resource::{report_existing_resource, ParametersDiff};
+use crate::util::parsers::outlet_target_parser;
 use crate::{docs, fmt_info, fmt_ok, Command, CommandGlobalOpts};
 use crate::{fmt_log, terminal::color_primary};
 
@@ -33,9 +38,15 @@ const LONG_ABOUT: &str = include_str!("./static/create/long_about.txt");
     after_long_help = docs::after_help(AFTER_LONG_HELP)
 )]
 pub struct CreateCommand {
-    /// TCP address where your TCP server is running. Your Outlet will send raw TCP traffic to it
-    #[arg(long, display_order = 900, id = "SOCKET_ADDRESS", value_parser = socket_addr_parser)]
-    pub to: SocketAddr,
+    /// TCP address where your TCP server is running. Your Outlet will send raw TCP traffic to it.
+    /// When a host name is used, for example `db.internal:5432`, it is resolved again by the node
+    /// for new connections, once the addresses it cached have expired.
+    ///
+    /// The path of a Unix domain socket can be used instead, as `unix:/var/run/postgresql/.s.PGSQL.5432`
+    /// or as an absolute path, for servers that only listen on a Unix domain socket.
+    /// The node must be allowed to connect to it. Unix domain sockets are not supported on Windows
+    #[arg(long, display_order = 900, id = "SOCKET_ADDRESS", value_parser = outlet_target_parser)]
+    pub to: String,
 
     /// Address of your TCP Outlet, which is part of a route that is used in other
     /// commands. This address must be unique. This address identifies the TCP Outlet
@@ -57,6 +68,49 @@ pub struct CreateCommand {
     /// You can check the fallback policy with `ockam policy show --resource-type tcp-outlet`.
     #[arg(hide = true, long = "allow", display_order = 904, id = "EXPRESSION")]
     pub policy_expression: Option<Expr>,
+
+    /// Maximum number of bytes, per TCP connection, sent to the TCP Inlet and not yet
+    /// written to its TCP client. The TCP Outlet stops reading from the TCP server when this
+    /// limit is reached. If you don't provide it, 4 MiB are used.
+    #[arg(long, display_order = 905, id = "BYTES")]
+    pub buffer_size: Option<usize>,
+
+    /// Name of the identity whose secure channel listeners can reach the TCP Outlet, for example
+    /// to accept TCP Inlets presenting a different credential than the node. The listeners must be
+    /// created first with `ockam secure-channel-listener create --identity`.
+    /// If you don't provide it, the TCP Outlet is reachable from the default secure channel listener
+    #[arg(long, display_order = 906, id = "IDENTITY_NAME")]
+    pub identity: Option<String>,
+
+    /// Start each connection to the TCP server with a PROXY protocol v2 header, carrying the
+    /// address of the client of the TCP Inlet. Servers such as HAProxy, nginx or PostgreSQL
+    /// behind a proxy can then see the original client address instead of the outlet address.
+    ///
+    /// The TCP server must be configured to expect the header, for example with `accept-proxy` for
+    /// HAProxy or `proxy_protocol` for nginx. A server which doesn't expect it reads it as invalid data,
+    /// and usually closes the connection right away
+    #[arg(long, display_order = 908)]
+    pub proxy_protocol: bool,
+
+    /// Succeed without creating the TCP Outlet if a TCP Outlet with the same address already
+    /// exists and sends traffic to the same TCP server. Fail with the differing parameters otherwise
+    #[arg(long, display_order = 907)]
+    pub if_not_exists: bool,
+
+    /// Publish the TCP Outlet under this name in the service directory of the default project,
+    /// so that project members can find it with `ockam service discover` and create a TCP Inlet
+    /// to it with `ockam tcp-inlet create --to discover:<NAME>`
+    #[arg(long, display_order = 909, id = "NAME")]
+    pub publish: Option<String>,
+
+    /// Description of the published TCP Outlet, listed by `ockam service discover`
+    #[arg(long, display_order = 910, requires = "NAME")]
+    pub description: Option<String>,
+
+    /// Name of the project relay, created with `ockam relay create`, through which the
+    /// published TCP Outlet is reached. If you don't provide it, 'default' will be used
+    #[arg(long, display_order = 911, id = "RELAY_NAME", requires = "NAME")]
+    pub via: Option<String>,
 }
 
 #[async_trait]
@@ -64,15 +118,42 @@ impl Command for CreateCommand {
     const NAME: &'static str = "tcp-outlet create";
 
     async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        if let Some(identity) = &self.identity {
+            check_identity_exists(&opts, identity).await?;
+        }
         initialize_default_node(ctx, &opts).await?;
         let node = BackgroundNodeClient::create(ctx, &opts.state, &self.at).await?;
         let node_name = node.node_name();
+        if self.if_not_exists {
+            let outlets: OutletList = node.ask(ctx, Request::get("/node/outlet")).await?;
+            let address = self.outlet_address();
+            if let Some(outlet) = outlets
+                .list
+                .iter()
+                .find(|o| o.worker_addr.address() == address)
+            {
+                return report_existing_resource(
+                    &opts,
+                    "TCP Outlet",
+                    &address,
+                    self.parameters_diff(outlet),
+                );
+            }
+        }
         let is_finished: Mutex<bool> = Mutex::new(false);
 
         let send_req = async {
             let from = self.from.map(Address::from);
             let res = node
-                .create_outlet(ctx, &self.to, from.as_ref(), self.policy_expression)
+                .create_outlet(
+                    ctx,
+                    &self.to,
+                    from.as_ref(),
+                    self.policy_expression,
+                    self.buffer_size,
+                    self.identity.clone(),
+                    self.proxy_protocol,
+                )
                 .await?;
             *is_finished.lock().await = true;
             Ok(res)
@@ -82,7 +163,7 @@ impl Command for CreateCommand {
         let output_messages = vec![
             format!(
                 "Attempting to create TCP Outlet to {}...",
-                color_primary(self.to.to_string())
+                color_primary(&self.to)
             ),
             format!(
                 "Creating outlet service on node {}...",
@@ -103,7 +184,7 @@ impl Command for CreateCommand {
         let mut attributes = HashMap::new();
         attributes.insert(TCP_OUTLET_AT, node_name.clone());
         attributes.insert(TCP_OUTLET_FROM, worker_addr.to_string().clone());
-        attributes.insert(TCP_OUTLET_TO, self.to.to_string());
+        attributes.insert(TCP_OUTLET_TO, self.to.clone());
         attributes.insert(NODE_NAME, node_name.clone());
         opts.state
             .add_journey_event(JourneyEvent::TcpOutletCreated, attributes)
@@ -118,7 +199,18 @@ impl Command for CreateCommand {
                         "  Outlet Address: {}\n",
                         color_primary(outlet_status.worker_addr.address())
                     )
-                    + &fmt_log!("  Socket Address: {}\n", color_primary(self.to.to_string()))
+                    + &match &outlet_status.unix_socket_path {
+                        Some(path) => fmt_log!("  Unix Socket: {}\n", color_primary(path)),
+                        None => fmt_log!("  Socket Address: {}\n", color_primary(&self.to)),
+                    }
+                    + &if outlet_status.proxy_protocol {
+                        fmt_log!(
+                            "  PROXY protocol: {}\n",
+                            color_primary("v2 header sent to the TCP server")
+                        )
+                    } else {
+                        String::new()
+                    }
                     + &fmt_info!(
                         "You may want to take a look at the {}, {}, {} commands next",
                         color_primary("ockam relay"),
@@ -130,14 +222,74 @@ impl Command for CreateCommand {
             .json(json)
             .write_line()?;
 
+        if let Some(name) = &self.publish {
+            self.publish(ctx, &opts, &node_name, name).await?;
+        }
+
         Ok(())
     }
 }
 
+impl CreateCommand {
+    /// Address of the outlet worker, which identifies the outlet on its node
+    fn outlet_address(&self) -> String {
+        self.from
+            .clone()
+            .unwrap_or_else(|| DefaultAddress::OUTLET_SERVICE.to_string())
+    }
+
+    /// Publish the outlet in the service directory of the default project, with the identity
+    /// reaching the outlet
+    async fn publish(
+        &self,
+        ctx: &Context,
+        opts: &CommandGlobalOpts,
+        node_name: &str,
+        name: &str,
+    ) -> miette::Result<()> {
+        let project = opts.state.projects().get_default_project().await?;
+        let identity_name = match &self.identity {
+            Some(identity) => identity.clone(),
+            None => node_identity_name(opts, node_name).await?,
+        };
+        let relay_name = self.via.clone().unwrap_or("default".to_string());
+        let route = published_outlet_route(
+            project.name(),
+            &format!("forward_to_{relay_name}"),
+            &self.outlet_address(),
+        )?;
+        publish_service(
+            ctx,
+            opts,
+            &project,
+            &identity_name,
+            name,
+            &route,
+            self.description.clone(),
+        )
+        .await?;
+        opts.terminal.write_line(&fmt_ok!(
+            "Published the TCP Outlet as {} with the route {}",
+            color_primary(name),
+            color_primary(route.to_string())
+        ))?;
+        Ok(())
+    }
+
+    /// Return the parameters of an existing outlet which differ from the requested ones
+    fn parameters_diff(&self, outlet: &OutletStatus) -> ParametersDiff {
+        ParametersDiff::new()
+            .compare("to", outlet.to(), &self.to)
+            .compare("proxy-protocol", outlet.proxy_protocol, self.proxy_protocol)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::run::parser::resource::utils::parse_cmd_from_args;
+    use crate::tcp::outlet::{TcpOutletCommand, TcpOutletSubCommand};
+    use crate::OckamSubcommand;
 
     #[test]
     fn command_can_be_parsed_from_name() {
@@ -147,4 +299,46 @@ mod tests {
         );
         assert!(cmd.is_ok());
     }
+
+    #[test]
+    fn parameters_diff_with_an_existing_outlet() {
+        let cmd = parse_cmd_from_args(
+            CreateCommand::NAME,
+            &[
+                "--to".to_string(),
+                "127.0.0.1:5000".to_string(),
+                "--if-not-exists".to_string(),
+            ],
+        )
+        .unwrap();
+        let OckamSubcommand::TcpOutlet(TcpOutletCommand {
+            subcommand: TcpOutletSubCommand::Create(cmd),
+        }) = cmd
+        else {
+            panic!("unexpected command")
+        };
+        assert!(cmd.if_not_exists);
+        assert_eq!(cmd.outlet_address(), "outlet");
+
+        let existing = |socket_addr: &str| {
+            OutletStatus::new(socket_addr.parse().unwrap(), Address::from("outlet"), None)
+        };
+        assert!(cmd.parameters_diff(&existing("127.0.0.1:5000")).is_empty());
+        assert_eq!(
+            cmd.parameters_diff(&existing("127.0.0.1:6000")).to_string(),
+            "  --to: 127.0.0.1:6000 (existing) != 127.0.0.1:5000 (requested)\n"
+        );
+        assert_eq!(
+            cmd.parameters_diff(
+                &existing("127.0.0.1:5000").with_hostname_port(Some("localhost:5000".to_string()))
+            )
+            .to_string(),
+            "  --to: localhost:5000 (existing) != 127.0.0.1:5000 (requested)\n"
+        );
+        assert_eq!(
+            cmd.parameters_diff(&existing("127.0.0.1:5000").with_proxy_protocol(true))
+                .to_string(),
+            "  --proxy-protocol: true (existing) != false (requested)\n"
+        );
+    }
 }
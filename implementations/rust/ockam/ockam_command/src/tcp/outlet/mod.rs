@@ -1,6 +1,7 @@
 pub mod create;
 mod delete;
 pub mod list;
+mod rename;
 mod show;
 
 use crate::{docs, Command, CommandGlobalOpts};
@@ -8,6 +9,7 @@ use clap::{Args, Subcommand};
 use create::CreateCommand;
 use delete::DeleteCommand;
 use list::ListCommand;
+use rename::RenameCommand;
 use show::ShowCommand;
 
 const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
@@ -32,6 +34,7 @@ pub enum TcpOutletSubCommand {
     Delete(DeleteCommand),
     List(ListCommand),
     Show(ShowCommand),
+    Rename(RenameCommand),
 }
 
 impl TcpOutletCommand {
@@ -41,6 +44,7 @@ impl TcpOutletCommand {
             TcpOutletSubCommand::Delete(c) => c.run(opts),
             TcpOutletSubCommand::List(c) => c.run(opts),
             TcpOutletSubCommand::Show(c) => c.run(opts),
+            TcpOutletSubCommand::Rename(c) => c.run(opts),
         }
     }
 
@@ -50,6 +54,7 @@ impl TcpOutletCommand {
             TcpOutletSubCommand::Delete(c) => c.name(),
             TcpOutletSubCommand::List(c) => c.name(),
             TcpOutletSubCommand::Show(c) => c.name(),
+            TcpOutletSubCommand::Rename(c) => c.name(),
         }
     }
 }
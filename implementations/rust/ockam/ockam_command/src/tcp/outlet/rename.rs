@@ -0,0 +1,65 @@
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+
+use ockam::Context;
+use ockam_api::nodes::service::portals::Outlets;
+use ockam_api::nodes::{BackgroundNodeClient, NodeRequestError};
+use ockam_core::Address;
+
+use crate::node::NodeOpts;
+use crate::tcp::util::alias_parser;
+use crate::{color_primary, docs, fmt_ok, Command, CommandGlobalOpts};
+
+const AFTER_LONG_HELP: &str = include_str!("./static/rename/after_long_help.txt");
+
+/// Rename a TCP Outlet.
+///
+/// The outlet is created again at its new address, with the policies set for its previous address.
+/// The inlets connected to the previous address must be updated to use the new one.
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = docs::after_help(AFTER_LONG_HELP))]
+pub struct RenameCommand {
+    /// Current address of the outlet
+    #[arg(display_order = 900, required = true, id = "ALIAS", value_parser = alias_parser)]
+    pub alias: String,
+
+    /// New address of the outlet
+    #[arg(display_order = 901, required = true, id = "NEW_ALIAS", value_parser = alias_parser)]
+    pub new_alias: String,
+
+    /// Node on which the outlet was started
+    #[command(flatten)]
+    pub node_opts: NodeOpts,
+}
+
+#[async_trait]
+impl Command for RenameCommand {
+    const NAME: &'static str = "tcp-outlet rename";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.node_opts.at_node).await?;
+        let reply = node
+            .rename_outlet(
+                ctx,
+                &Address::from_string(&self.alias),
+                &Address::from_string(&self.new_alias),
+            )
+            .await?;
+        let outlet_status = NodeRequestError::check_reply(&node.node_name(), reply)
+            .map_err(miette::Report::from)?;
+
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "The TCP Outlet {} on node {} has been renamed to {}",
+                color_primary(&self.alias),
+                color_primary(node.node_name()),
+                color_primary(&self.new_alias)
+            ))
+            .machine(&self.new_alias)
+            .json(serde_json::json!(&outlet_status))
+            .write_line()?;
+        Ok(())
+    }
+}
@@ -70,6 +70,13 @@ struct OutletInformation {
     node_name: String,
     worker_addr: MultiAddr,
     socket_addr: SocketAddr,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hostname_port: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unix_socket_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    identity: Option<String>,
+    proxy_protocol: bool,
 }
 
 impl Output for OutletInformation {
@@ -78,7 +85,22 @@ impl Output for OutletInformation {
         write!(w, "Outlet")?;
         write!(w, "\n  On Node: {}", self.node_name)?;
         write!(w, "\n  From address: {}", self.worker_addr)?;
-        write!(w, "\n  To TCP server: {}", self.socket_addr)?;
+        match (&self.unix_socket_path, &self.hostname_port) {
+            (Some(path), _) => write!(w, "\n  To Unix socket: {path}")?,
+            (None, Some(hostname_port)) => write!(
+                w,
+                "\n  To TCP server: {} ({})",
+                hostname_port, self.socket_addr
+            )?,
+            (None, None) => write!(w, "\n  To TCP server: {}", self.socket_addr)?,
+        }
+        match &self.identity {
+            Some(identity) => write!(w, "\n  Reachable from the listeners of: {identity}")?,
+            None => write!(w, "\n  Reachable from: the default secure channel listener")?,
+        }
+        if self.proxy_protocol {
+            write!(w, "\n  Sends a PROXY protocol v2 header to the TCP server")?;
+        }
         Ok(w)
     }
 }
@@ -155,6 +177,10 @@ impl ShowCommandTui for ShowTui {
             node_name: self.node.node_name().to_string(),
             worker_addr: outlet_status.worker_address().into_diagnostic()?,
             socket_addr: outlet_status.socket_addr,
+            hostname_port: outlet_status.hostname_port,
+            unix_socket_path: outlet_status.unix_socket_path,
+            identity: outlet_status.identity,
+            proxy_protocol: outlet_status.proxy_protocol,
         };
         self.terminal()
             .stdout()
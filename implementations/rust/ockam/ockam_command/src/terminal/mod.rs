@@ -13,13 +13,13 @@ use tokio::time::sleep;
 
 pub use colors::*;
 use mode::*;
+use ockam::identity::utils::now;
 use ockam_core::env::{get_env, get_env_with_default, FromString};
 use ockam_core::errcode::Kind;
 use r3bl_rs_utils_core::*;
 use r3bl_tuify::*;
-use tracing::warn;
 
-use crate::output::OutputFormat;
+use crate::output::{human_readable_time, OutputFormat};
 use crate::{fmt_info, fmt_list, fmt_log, fmt_warn, GlobalArgs, Result};
 pub mod colors;
 pub mod fmt;
@@ -248,6 +248,23 @@ impl<W: TerminalWriter + Debug> Terminal<W> {
         ))
     }
 
+    /// Prompt the user for a secret value, without echoing it.
+    /// The value is asked twice if `confirmation` is true.
+    /// Return `None` if the user can't be prompted.
+    pub fn password(&self, msg: impl AsRef<str>, confirmation: bool) -> Result<Option<String>> {
+        if !self.can_ask_for_user_input() {
+            return Ok(None);
+        }
+        let mut prompt = dialoguer::Password::new().with_prompt(fmt_log!("{}", msg.as_ref()));
+        if confirmation {
+            prompt = prompt.with_confirmation(
+                fmt_log!("Please enter it again"),
+                fmt_warn!("The values don't match"),
+            );
+        }
+        Ok(Some(prompt.interact()?))
+    }
+
     pub fn confirmed_with_flag_or_prompt(
         &self,
         flag: bool,
@@ -260,12 +277,15 @@ impl<W: TerminalWriter + Debug> Terminal<W> {
             match self.confirm(prompt_msg)? {
                 ConfirmResult::Yes => Ok(true),
                 ConfirmResult::No => Ok(false),
-                ConfirmResult::NonTTY => Err(miette!("Use --yes to confirm"))?,
+                ConfirmResult::NonTTY => Err(no_input_error("--yes"))?,
             }
         }
     }
 
     pub fn confirm_interactively(&self, header: String) -> bool {
+        if !self.can_ask_for_user_input() {
+            return false;
+        }
         let user_input = select_from_list(
             header,
             ["YES", "NO"].iter().map(|it| it.to_string()).collect(),
@@ -307,6 +327,11 @@ impl<W: TerminalWriter + Debug> Terminal<W> {
         !self.no_input && self.stderr.is_tty() && !self.quiet
     }
 
+    /// Return true if the user input was explicitly disabled with `--no-input` or `NO_INPUT`
+    pub fn is_input_disabled(&self) -> bool {
+        self.no_input
+    }
+
     fn should_disable_color(no_color: bool) -> bool {
         // If global argument `--no-color` is passed or the `NO_COLOR` env var is set, colors
         // will be stripped out from output messages. Otherwise, let the terminal decide.
@@ -326,6 +351,14 @@ impl<W: TerminalWriter + Debug> Terminal<W> {
     }
 }
 
+/// Return the error used when a command would need to prompt the user but can't,
+/// naming the argument which makes the prompt unnecessary
+pub fn no_input_error(argument: impl Display) -> miette::Report {
+    miette!(
+        "This command can't prompt for user input in a non-interactive session. Use {argument} to run it without a prompt"
+    )
+}
+
 // Logging mode
 impl<W: TerminalWriter + Debug> Terminal<W, ToStdErr> {
     pub fn write(&self, msg: impl AsRef<str>) -> Result<()> {
@@ -476,10 +509,12 @@ impl<W: TerminalWriter + Debug> Terminal<W, ToStdOut> {
             }
             OutputFormat::Json => match json {
                 Some(json) => json,
-                // If not set, no fallback is provided
+                // If not set, no fallback is provided: the plain output must not be
+                // written instead, since it would be parsed as JSON by the caller
                 None => {
-                    warn!("JSON output is not defined for this command");
-                    return Ok(());
+                    return Err(miette!(
+                        "This command does not support JSON output. Please try running it again without '--output json'."
+                    ))?;
                 }
             },
         };
@@ -490,7 +525,7 @@ impl<W: TerminalWriter + Debug> Terminal<W, ToStdOut> {
 // Extensions
 impl<W: TerminalWriter + Debug> Terminal<W> {
     pub fn progress_spinner(&self) -> Option<ProgressBar> {
-        if self.quiet || !self.stderr.is_tty() {
+        if self.quiet || self.is_progress_degraded() {
             return None;
         }
         let ticker = [
@@ -530,7 +565,12 @@ impl<W: TerminalWriter + Debug> Terminal<W> {
     ) -> miette::Result<()> {
         let progress_bar = match progress_bar {
             Some(pb) => pb,
-            None => return Ok(()),
+            None if self.quiet => return Ok(()),
+            None => {
+                return self
+                    .progress_output_as_log_lines(output_messages, is_finished)
+                    .await
+            }
         };
 
         loop {
@@ -551,6 +591,37 @@ impl<W: TerminalWriter + Debug> Terminal<W> {
 
         Ok(())
     }
+
+    /// Write each progress message once, as a plain log line starting with a timestamp,
+    /// then wait until the action is finished, as it is done with a spinner
+    async fn progress_output_as_log_lines(
+        &self,
+        output_messages: &[String],
+        is_finished: &Mutex<bool>,
+    ) -> miette::Result<()> {
+        for message in output_messages {
+            if *is_finished.lock().await {
+                return Ok(());
+            }
+            let timestamp = now().map(human_readable_time).unwrap_or_default();
+            self.stderr
+                .write_line(fmt_log!("{timestamp} {}", message.trim_end()))?;
+            sleep(Duration::from_millis(500)).await;
+        }
+        while !*is_finished.lock().await {
+            sleep(Duration::from_millis(100)).await;
+        }
+        Ok(())
+    }
+
+    /// Return true if a spinner can't be displayed, because stderr is not a TTY
+    /// or because the terminal doesn't support cursor movements
+    fn is_progress_degraded(&self) -> bool {
+        !self.stderr.is_tty()
+            || std::env::var("TERM")
+                .map(|term| term == "dumb")
+                .unwrap_or(false)
+    }
 }
 
 pub enum PluralTerm {
@@ -594,3 +665,75 @@ impl PluralTerm {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex as SyncMutex;
+
+    /// Lines written to the stderr of a `FakeWriter`
+    static STDERR_LINES: SyncMutex<Vec<String>> = SyncMutex::new(Vec::new());
+
+    /// Writer capturing the lines written to stderr, which is not a TTY
+    #[derive(Clone, Debug)]
+    struct FakeWriter {
+        is_stderr: bool,
+    }
+
+    impl TerminalWriter for FakeWriter {
+        fn stdout(_no_color: bool) -> Self {
+            Self { is_stderr: false }
+        }
+
+        fn stderr(_no_color: bool) -> Self {
+            Self { is_stderr: true }
+        }
+
+        fn is_tty(&self) -> bool {
+            false
+        }
+
+        fn write(&mut self, s: impl AsRef<str>) -> Result<()> {
+            self.write_line(s)
+        }
+
+        fn rewrite(&mut self, s: impl AsRef<str>) -> Result<()> {
+            self.write_line(s)
+        }
+
+        fn write_line(&self, s: impl AsRef<str>) -> Result<()> {
+            if self.is_stderr {
+                STDERR_LINES.lock().unwrap().push(s.as_ref().to_string());
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_progress_output_without_tty() {
+        let terminal: Terminal<FakeWriter> =
+            Terminal::new(false, false, false, OutputFormat::Plain);
+        assert!(terminal.progress_spinner().is_none());
+
+        let is_finished = Mutex::new(false);
+        let messages = vec!["Creating...\n".to_string(), "Connecting...".to_string()];
+        let action = async {
+            sleep(Duration::from_millis(1_200)).await;
+            *is_finished.lock().await = true;
+            Ok::<_, miette::Report>("done")
+        };
+        let (result, _) =
+            tokio::try_join!(action, terminal.progress_output(&messages, &is_finished)).unwrap();
+        assert_eq!(result, "done");
+
+        // Each message is written once, as a log line with a timestamp, without any control characters
+        let lines = STDERR_LINES.lock().unwrap().clone();
+        assert_eq!(lines.len(), 2, "{lines:?}");
+        for (line, message) in lines.iter().zip(["Creating...", "Connecting..."]) {
+            assert!(line.ends_with(&format!(" {message}")), "{line}");
+            assert!(!line.contains('\r') && !line.contains('\u{1b}'), "{line:?}");
+            let timestamp = line.trim_start().split(' ').next().unwrap();
+            assert!(timestamp.ends_with('Z'), "{line}");
+        }
+    }
+}
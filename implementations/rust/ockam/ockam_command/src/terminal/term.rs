@@ -66,4 +66,19 @@ mod tests {
             .write_line()
             .unwrap();
     }
+
+    #[test]
+    fn test_confirmation_without_user_input() {
+        let sut: Terminal<TerminalStream<Term>> =
+            Terminal::new(false, false, true, OutputFormat::Plain);
+        assert!(!sut.can_ask_for_user_input());
+        assert!(sut.confirmed_with_flag_or_prompt(true, "Confirm?").unwrap());
+
+        // Without the flag, the error names the flag avoiding the prompt
+        let error = sut
+            .confirmed_with_flag_or_prompt(false, "Confirm?")
+            .unwrap_err();
+        assert!(error.to_string().contains("--yes"));
+        assert!(!sut.confirm_interactively("Confirm?".to_string()));
+    }
 }
@@ -183,8 +183,14 @@ pub trait DeleteCommandTui {
                 )? {
                     self.delete_single(&item_name).await?;
                 }
+                return Ok(());
             }
-            return Ok(());
+            // The items to delete can't be selected without a prompt
+            return Err(miette!(
+                "The {} to delete can't be selected in a non-interactive session. Please provide the name of the {} to delete, or use --all",
+                Self::ITEM_NAME.plural(),
+                Self::ITEM_NAME.singular()
+            ));
         }
 
         match items_names.len() {
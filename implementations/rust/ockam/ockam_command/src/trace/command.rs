@@ -0,0 +1,423 @@
+use core::time::Duration;
+use std::fmt::Write;
+use std::time::Instant;
+
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::{Context as _, IntoDiagnostic};
+use tracing::info;
+
+use ockam::Context;
+use ockam_api::address::extract_address_value;
+use ockam_api::nodes::service::default_address::DefaultAddress;
+use ockam_api::nodes::service::messages::Messages;
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_api::tracer::{TraceHop, TraceProbe, TraceReply, DEFAULT_TRACE_HOP_LIMIT};
+use ockam_multiaddr::proto::{Dns, DnsAddr, Ip4, Ip6, Service, Worker};
+use ockam_multiaddr::{MultiAddr, Protocol};
+
+use crate::output::models::{TraceHopOutput, TraceHopStatus};
+use crate::project::util::{
+    clean_projects_multiaddr, get_projects_secure_channels_from_config_lookup,
+};
+use crate::util::api::{IdentityOpts, TrustOpts};
+use crate::util::clean_nodes_multiaddr;
+use crate::util::duration::duration_parser;
+use crate::{docs, fmt_warn, Command, CommandGlobalOpts, OckamColor};
+
+const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/after_long_help.txt");
+
+/// Trace a route hop by hop, and report the first hop which can't be reached
+#[derive(Clone, Debug, Args)]
+#[command(
+arg_required_else_help = true,
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct TraceCommand {
+    /// The route to trace
+    #[arg(value_name = "ROUTE")]
+    pub to: MultiAddr,
+
+    /// The node to send the probes from
+    #[arg(short, long, value_name = "NODE", value_parser = extract_address_value)]
+    from: Option<String>,
+
+    /// Maximum time to wait for the answer of each hop
+    #[arg(long, value_name = "TIMEOUT", default_value = "5s", value_parser = duration_parser)]
+    pub timeout: Duration,
+
+    /// Maximum number of hops to trace. The probes can't be forwarded by more trace services
+    #[arg(long, value_name = "COUNT", default_value_t = DEFAULT_TRACE_HOP_LIMIT)]
+    pub max_hops: u8,
+
+    #[command(flatten)]
+    identity_opts: IdentityOpts,
+
+    #[command(flatten)]
+    pub trust_opts: TrustOpts,
+}
+
+#[async_trait]
+impl Command for TraceCommand {
+    const NAME: &'static str = "trace";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let hops = split_hops(&self.to)?;
+        let traced = hops.len().min(self.max_hops as usize);
+        if traced < hops.len() {
+            opts.terminal.write_line(fmt_warn!(
+                "Only the first {} hops of the route are traced, use --max-hops to trace more hops",
+                traced
+            ))?;
+        }
+        let hops = &hops[..traced];
+
+        let (_, meta) = clean_nodes_multiaddr(&self.to, &opts.state)
+            .await
+            .context("Argument 'ROUTE' is invalid")?;
+
+        // Route of the probe of each hop, or nothing if the hop is the destination of the route
+        let mut routes = vec![];
+        for route in probe_routes(hops)? {
+            routes.push(match route {
+                Some(route) => Some(clean_nodes_multiaddr(&route, &opts.state).await?.0),
+                None => None,
+            });
+        }
+
+        // Setup environment depending on whether we are sending the probes from a background node
+        // or an in-memory node
+        let answers = if let Some(node) = &self.from {
+            let node =
+                BackgroundNodeClient::create_to_node(ctx, &opts.state, node.as_str()).await?;
+            self.probe(ctx, &node, routes).await?
+        } else {
+            let identity_name = opts
+                .state
+                .get_identity_name_or_default(&self.identity_opts.identity)
+                .await?;
+
+            info!("starting an in memory node to trace a route");
+            let node_manager = self
+                .trust_opts
+                .in_memory_node_builder(&identity_name)
+                .build(ctx, &opts.state)
+                .await?;
+
+            // Replace `/project/<name>` occurrences with their respective secure channel addresses
+            let projects_sc = get_projects_secure_channels_from_config_lookup(
+                &opts,
+                ctx,
+                &node_manager,
+                &meta,
+                Some(identity_name),
+                Some(self.timeout),
+            )
+            .await?;
+            let routes = routes
+                .into_iter()
+                .map(|route| {
+                    route
+                        .map(|route| clean_projects_multiaddr(route, projects_sc.clone()))
+                        .transpose()
+                })
+                .collect::<crate::Result<Vec<_>>>()?;
+            self.probe(ctx, &**node_manager, routes).await?
+        };
+
+        let output = hop_outputs(hops, answers);
+        opts.terminal
+            .stdout()
+            .plain(plain_output(&output)?)
+            .json(serde_json::to_string_pretty(&output).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+impl TraceCommand {
+    /// Send a probe to the trace service of each hop, one hop after the other,
+    /// and return the answer of each hop
+    async fn probe<M: Messages + Sync>(
+        &self,
+        ctx: &Context,
+        sender: &M,
+        routes: Vec<Option<MultiAddr>>,
+    ) -> crate::Result<Vec<Answer>> {
+        let probe = minicbor::to_vec(TraceProbe::new(self.max_hops)).into_diagnostic()?;
+        let mut answers = vec![];
+        for route in routes {
+            let Some(route) = route else {
+                answers.push(Answer::NotProbed);
+                continue;
+            };
+            info!("sending a trace probe to {route}");
+            let started_at = Instant::now();
+            let answer = match sender
+                .send_message(ctx, &route, probe.clone(), Some(self.timeout))
+                .await
+            {
+                Ok(reply) => match minicbor::decode::<TraceReply>(&reply) {
+                    // the last hop of the reply is the trace service which answered
+                    Ok(reply) => match reply.hops.into_iter().last() {
+                        Some(hop) => Answer::Answered {
+                            hop,
+                            rtt: started_at.elapsed(),
+                        },
+                        None => Answer::Failed("the answer contains no hops".to_string()),
+                    },
+                    Err(e) => Answer::Failed(format!("the answer is not a trace reply: {e}")),
+                },
+                Err(e) => Answer::Failed(e.to_string()),
+            };
+            answers.push(answer);
+        }
+        Ok(answers)
+    }
+}
+
+/// Answer of a hop to its probe
+enum Answer {
+    Answered {
+        hop: TraceHop,
+        rtt: Duration,
+    },
+    Failed(String),
+    /// The destination of the route is not probed, since it is not a trace service
+    NotProbed,
+}
+
+/// Split a route into hops. Each hop is a single protocol of the route,
+/// except for the transport addresses, which are made of a host and a port
+fn split_hops(to: &MultiAddr) -> miette::Result<Vec<MultiAddr>> {
+    let mut hops = vec![];
+    let mut hop = MultiAddr::default();
+    for p in to.iter() {
+        hop.push_back_value(&p).into_diagnostic()?;
+        if !matches!(p.code(), Ip4::CODE | Ip6::CODE | Dns::CODE | DnsAddr::CODE) {
+            hops.push(std::mem::take(&mut hop));
+        }
+    }
+    if !hop.is_empty() {
+        hops.push(hop);
+    }
+    Ok(hops)
+}
+
+/// Return, for each hop, the route to its trace service: the route leading to the hop,
+/// followed by the address of the trace service.
+/// The destination of the route is not probed when it is a service: the probe would be
+/// sent to that service and not to a trace service
+fn probe_routes(hops: &[MultiAddr]) -> miette::Result<Vec<Option<MultiAddr>>> {
+    let mut routes = vec![];
+    let mut route = MultiAddr::default();
+    for (i, hop) in hops.iter().enumerate() {
+        route.concat_mut(hop).into_diagnostic()?;
+        let is_destination_service = i == hops.len() - 1
+            && matches!(
+                hop.last().map(|p| p.code()),
+                Some(Service::CODE | Worker::CODE)
+            );
+        if is_destination_service {
+            routes.push(None);
+        } else {
+            let mut probe_route = route.clone();
+            probe_route
+                .push_back(Service::new(DefaultAddress::TRACE_SERVICE))
+                .into_diagnostic()?;
+            routes.push(Some(probe_route));
+        }
+    }
+    Ok(routes)
+}
+
+/// Return the status of each hop, given the answers to the probes.
+/// A hop without an answer is opaque if a later hop answered. Otherwise the route is
+/// broken and the first hop without an answer is reported as such
+fn hop_outputs(hops: &[MultiAddr], answers: Vec<Answer>) -> Vec<TraceHopOutput> {
+    let first_unanswered = answers
+        .iter()
+        .rposition(|a| matches!(a, Answer::Answered { .. }))
+        .map_or(0, |i| i + 1);
+    hops.iter()
+        .zip(answers)
+        .enumerate()
+        .map(|(i, (address, answer))| {
+            let mut output = TraceHopOutput {
+                hop: i + 1,
+                address: address.to_string(),
+                status: TraceHopStatus::NotReached,
+                node_name: None,
+                identifier: None,
+                rtt_ms: None,
+                timestamp_ms: None,
+                error: None,
+            };
+            match answer {
+                Answer::Answered { hop, rtt } => {
+                    output.status = TraceHopStatus::Answered;
+                    output.node_name = Some(hop.node_name);
+                    output.identifier = Some(hop.identifier);
+                    output.rtt_ms = Some(rtt.as_millis() as u64);
+                    output.timestamp_ms = Some(hop.timestamp_ms);
+                }
+                Answer::Failed(_) if i < first_unanswered => {
+                    output.status = TraceHopStatus::Opaque;
+                }
+                Answer::Failed(error) if i == first_unanswered => {
+                    output.status = TraceHopStatus::NoAnswer;
+                    output.error = Some(error);
+                }
+                // the destination of the route can't be probed, so its status is unknown
+                Answer::NotProbed if i <= first_unanswered => {
+                    output.status = TraceHopStatus::Opaque;
+                }
+                Answer::Failed(_) | Answer::NotProbed => {}
+            }
+            output
+        })
+        .collect()
+}
+
+/// Display one line per hop, with the node which answered the probe and its round-trip time
+fn plain_output(hops: &[TraceHopOutput]) -> crate::Result<String> {
+    let width = hops
+        .iter()
+        .map(|h| h.address.len())
+        .max()
+        .unwrap_or_default();
+    let mut output = String::new();
+    for hop in hops {
+        write!(output, "{:>3}  {:<width$}  ", hop.hop, hop.address)?;
+        match hop.status {
+            TraceHopStatus::Answered => write!(
+                output,
+                "{} ({})  {} ms",
+                hop.node_name
+                    .clone()
+                    .unwrap_or_default()
+                    .color(OckamColor::PrimaryResource.color()),
+                hop.identifier.clone().unwrap_or_default(),
+                hop.rtt_ms.unwrap_or_default()
+            )?,
+            TraceHopStatus::Opaque => write!(output, "opaque")?,
+            TraceHopStatus::NoAnswer => write!(
+                output,
+                "{}",
+                format!(
+                    "no answer, the route is broken here: {}",
+                    hop.error.clone().unwrap_or_default()
+                )
+                .color(OckamColor::Failure.color())
+            )?,
+            TraceHopStatus::NotReached => write!(output, "not reached")?,
+        }
+        writeln!(output)?;
+    }
+    Ok(output.trim_end().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn hops(route: &str) -> Vec<MultiAddr> {
+        split_hops(&MultiAddr::from_str(route).unwrap()).unwrap()
+    }
+
+    fn answered(node_name: &str) -> Answer {
+        Answer::Answered {
+            hop: TraceHop {
+                node_name: node_name.to_string(),
+                identifier: "I1".to_string(),
+                timestamp_ms: 1,
+            },
+            rtt: Duration::from_millis(2),
+        }
+    }
+
+    #[test]
+    fn split_a_route_into_hops() {
+        let hops = hops("/ip4/127.0.0.1/tcp/4000/service/forward_to_n2/secure/api/service/outlet");
+        let hops: Vec<String> = hops.iter().map(|h| h.to_string()).collect();
+        assert_eq!(
+            hops,
+            vec![
+                "/ip4/127.0.0.1/tcp/4000",
+                "/service/forward_to_n2",
+                "/secure/api",
+                "/service/outlet"
+            ]
+        );
+    }
+
+    #[test]
+    fn the_destination_service_is_not_probed() {
+        let routes = probe_routes(&hops("/node/n1/secure/api/service/outlet")).unwrap();
+        let routes: Vec<Option<String>> = routes
+            .into_iter()
+            .map(|r| r.map(|r| r.to_string()))
+            .collect();
+        assert_eq!(
+            routes,
+            vec![
+                Some("/node/n1/service/trace".to_string()),
+                Some("/node/n1/secure/api/service/trace".to_string()),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn hops_without_answer_are_opaque_or_broken() {
+        let hops = hops("/node/n1/service/hop/secure/api/service/outlet");
+
+        // the hop service doesn't answer, but the next node does
+        let outputs = hop_outputs(
+            &hops,
+            vec![
+                answered("n1"),
+                Answer::Failed("timeout".to_string()),
+                answered("n2"),
+                Answer::NotProbed,
+            ],
+        );
+        let statuses: Vec<TraceHopStatus> = outputs.iter().map(|o| o.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                TraceHopStatus::Answered,
+                TraceHopStatus::Opaque,
+                TraceHopStatus::Answered,
+                TraceHopStatus::Opaque
+            ]
+        );
+
+        // the secure channel can't be created
+        let outputs = hop_outputs(
+            &hops,
+            vec![
+                answered("n1"),
+                Answer::Failed("timeout".to_string()),
+                Answer::Failed("timeout".to_string()),
+                Answer::NotProbed,
+            ],
+        );
+        let statuses: Vec<TraceHopStatus> = outputs.iter().map(|o| o.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                TraceHopStatus::Answered,
+                TraceHopStatus::NoAnswer,
+                TraceHopStatus::NotReached,
+                TraceHopStatus::NotReached
+            ]
+        );
+        assert_eq!(outputs[1].error, Some("timeout".to_string()));
+    }
+}
@@ -0,0 +1,3 @@
+pub use command::TraceCommand;
+
+mod command;
@@ -1,5 +1,5 @@
 //! API shim to make it nicer to interact with the ockam messaging API
-use clap::Args;
+use clap::{Args, ValueEnum};
 use miette::miette;
 use std::time::Duration;
 // TODO: maybe we can remove this cross-dependency inside the CLI?
@@ -7,8 +7,16 @@ use minicbor::Decoder;
 use regex::Regex;
 
 use ockam::identity::Identifier;
+use ockam_api::bench_target::BenchTargetMode;
+use ockam_api::cloud::pagination::Pagination;
+use ockam_api::logs::LogLevelFilter;
+use ockam_api::nodes::models::attributes::FlushAttributesRequest;
+use ockam_api::nodes::models::base::SetLogLevelRequest;
 use ockam_api::nodes::models::flow_controls::AddConsumer;
-use ockam_api::nodes::models::services::StartHopServiceRequest;
+use ockam_api::nodes::models::services::{
+    BuiltinService, DeleteServiceRequest, StartBenchServiceRequest, StartBuiltinServiceRequest,
+};
+use ockam_api::nodes::models::workers::SendWorkerMessage;
 use ockam_api::nodes::service::default_address::DefaultAddress;
 use ockam_api::nodes::*;
 use ockam_core::api::Request;
@@ -28,11 +36,36 @@ pub(crate) fn query_status() -> Request<()> {
     Request::get("/node")
 }
 
+/// Construct a request to change the log level of a node
+pub(crate) fn set_log_level(log_level: &LogLevelFilter) -> Request<SetLogLevelRequest> {
+    Request::put("/node/log_level").body(SetLogLevelRequest::new(log_level.to_string()))
+}
+
 /// Construct a request to query node tcp listeners
 pub(crate) fn list_tcp_listeners() -> Request<()> {
     Request::get("/node/tcp/listener")
 }
 
+/// Construct a request to query node tcp connections
+pub(crate) fn list_tcp_connections() -> Request<()> {
+    Request::get("/node/tcp/connection")
+}
+
+/// Construct a request to query node udp listeners
+pub(crate) fn list_udp_listeners() -> Request<()> {
+    Request::get("/node/udp/listener")
+}
+
+/// Construct a request to query the trust configured on the node
+pub(crate) fn query_trust_context() -> Request<()> {
+    Request::get("/node/trust")
+}
+
+/// Construct a request to query the connections established when the node started
+pub(crate) fn list_warm_connections() -> Request<()> {
+    Request::get("/node/warm_connection")
+}
+
 /// Construct a request to print a list of services for the given node
 pub(crate) fn list_services() -> Request<()> {
     Request::get("/node/services")
@@ -58,6 +91,24 @@ pub(crate) fn list_workers() -> Request<()> {
     Request::get("/node/workers")
 }
 
+/// Construct a request to send a raw message to a worker on the given node
+pub(crate) fn send_worker_message(
+    to: &str,
+    payload: Vec<u8>,
+    timeout: Duration,
+) -> Request<SendWorkerMessage> {
+    Request::post("/node/workers/send").body(SendWorkerMessage::new(
+        to,
+        payload,
+        timeout.as_millis() as u64,
+    ))
+}
+
+/// Construct a request to flush the attributes of some identities, or of all of them
+pub(crate) fn flush_attributes(identifiers: Vec<Identifier>) -> Request<FlushAttributesRequest> {
+    Request::delete("/node/attributes").body(FlushAttributesRequest::new(identifiers))
+}
+
 pub(crate) fn delete_secure_channel(
     addr: &Address,
 ) -> Request<models::secure_channel::DeleteSecureChannelRequest> {
@@ -65,6 +116,11 @@ pub(crate) fn delete_secure_channel(
     Request::delete("/node/secure_channel").body(payload)
 }
 
+/// Construct a request to delete the secure channel created with a name
+pub(crate) fn delete_named_secure_channel(name: &str) -> Request<()> {
+    Request::delete(format!("/node/secure_channel/{name}"))
+}
+
 pub(crate) fn show_secure_channel(
     addr: &Address,
 ) -> Request<models::secure_channel::ShowSecureChannelRequest> {
@@ -77,12 +133,14 @@ pub(crate) fn create_secure_channel_listener(
     addr: &Address,
     authorized_identifiers: Option<Vec<Identifier>>,
     identity_name: Option<String>,
+    max_handshakes_per_second: Option<u32>,
 ) -> Result<Vec<u8>> {
     let payload = models::secure_channel::CreateSecureChannelListenerRequest::new(
         addr,
         authorized_identifiers,
         identity_name,
-    );
+    )
+    .with_max_handshakes_per_second(max_handshakes_per_second);
 
     let mut buf = vec![];
     Request::post("/node/secure_channel_listener")
@@ -111,10 +169,40 @@ pub(crate) fn show_secure_channel_listener(
     Request::get("/node/show_secure_channel_listener").body(payload)
 }
 
-/// Construct a request to start a Hop Service
-pub(crate) fn start_hop_service(addr: &str) -> Request<StartHopServiceRequest> {
-    let payload = StartHopServiceRequest::new(addr);
-    Request::post(node_service(DefaultAddress::HOP_SERVICE)).body(payload)
+/// Construct a request to update the identifiers authorized by a Secure Channel Listener
+pub(crate) fn update_secure_channel_listener(
+    addr: &Address,
+    set: Option<Vec<Identifier>>,
+    add: Vec<Identifier>,
+    remove: Vec<Identifier>,
+) -> Request<models::secure_channel::UpdateSecureChannelListenerRequest> {
+    let payload =
+        models::secure_channel::UpdateSecureChannelListenerRequest::new(addr, set, add, remove);
+    Request::put("/node/secure_channel_listener").body(payload)
+}
+
+/// Construct a request to start a built-in service, like an echo or a hop service
+pub(crate) fn start_builtin_service(
+    service: BuiltinService,
+    addr: &str,
+) -> Request<StartBuiltinServiceRequest> {
+    let payload = StartBuiltinServiceRequest::new(service, addr);
+    Request::post("/node/services").body(payload)
+}
+
+/// Construct a request to stop a built-in service
+pub(crate) fn stop_builtin_service(addr: &str) -> Request<DeleteServiceRequest> {
+    let payload = DeleteServiceRequest::new(addr);
+    Request::delete("/node/services").body(payload)
+}
+
+/// Construct a request to start a Bench Service
+pub(crate) fn start_bench_service(
+    addr: &str,
+    mode: BenchTargetMode,
+) -> Request<StartBenchServiceRequest> {
+    let payload = StartBenchServiceRequest::new(addr, mode);
+    Request::post(node_service(DefaultAddress::BENCH_SERVICE)).body(payload)
 }
 
 pub(crate) fn add_consumer(id: FlowControlId, address: MultiAddr) -> Request<AddConsumer> {
@@ -163,15 +251,42 @@ pub struct TrustOpts {
     pub credential_scope: Option<String>,
 }
 
+impl TrustOpts {
+    /// Return a builder for an in memory node using the given identity and trusting
+    /// the authority configured by these options
+    pub fn in_memory_node_builder(&self, identity_name: &str) -> InMemoryNodeBuilder {
+        let mut builder = InMemoryNode::builder()
+            .identity_name(identity_name)
+            .with_project_name(self.project_name.clone());
+        if let Some(authority_identity) = &self.authority_identity {
+            builder = builder.authority(authority_identity);
+        }
+        if let Some(authority_route) = &self.authority_route {
+            builder = builder.authority_route(authority_route.clone());
+        }
+        if let Some(scope) = self.credential_scope.clone() {
+            builder = builder.credential_retrieval(match self.authority_route {
+                Some(_) => CredentialRetrieval::Remote { scope },
+                None => CredentialRetrieval::CacheOnly { scope },
+            });
+        }
+        builder
+    }
+}
+
 #[derive(Clone, Debug, Args, Default, PartialEq)]
 pub struct RetryOpts {
     /// Number of times to retry the command
     #[arg(hide = true, long)]
     retry_count: Option<u32>,
 
-    /// Delay between retries
+    /// Delay before the first retry. The delay doubles after each attempt
     #[arg(hide = true, long, value_parser = duration_parser)]
     pub retry_delay: Option<Duration>,
+
+    /// Maximum time spent retrying the command
+    #[arg(hide = true, long, value_parser = duration_parser)]
+    retry_max_elapsed: Option<Duration>,
 }
 
 impl RetryOpts {
@@ -189,7 +304,7 @@ impl RetryOpts {
         }
     }
 
-    /// Get the delay between retries
+    /// Get the delay before the first retry
     ///
     /// If the value is not set, it will try to get the value from
     /// the `OCKAM_COMMAND_RETRY_DELAY` environment variable
@@ -202,6 +317,53 @@ impl RetryOpts {
                 .and_then(|v| duration_parser(&v).ok()),
         }
     }
+
+    /// Get the maximum time spent retrying the command
+    ///
+    /// If the value is not set, it will try to get the value from
+    /// the `OCKAM_COMMAND_RETRY_MAX_ELAPSED` environment variable
+    pub fn retry_max_elapsed(&self) -> Option<Duration> {
+        match self.retry_max_elapsed {
+            Some(max_elapsed) => Some(max_elapsed),
+            None => get_env::<String>("OCKAM_COMMAND_RETRY_MAX_ELAPSED")
+                .ok()
+                .flatten()
+                .and_then(|v| duration_parser(&v).ok()),
+        }
+    }
+}
+
+/// Options to sort a list of resources and to display it page by page
+#[derive(Clone, Debug, Args, Default, PartialEq)]
+pub struct PaginationOpts {
+    /// Maximum number of items to display. All the items are displayed if not set.
+    /// With --output json, the items are then returned with the pagination metadata
+    #[arg(long, value_name = "LIMIT", value_parser = clap::value_parser!(u32).range(1..))]
+    pub limit: Option<u32>,
+
+    /// Page to display, starting at 1
+    #[arg(long, value_name = "PAGE", default_value_t = 1, requires = "limit", value_parser = clap::value_parser!(u32).range(1..))]
+    pub page: u32,
+
+    /// Order of the items
+    #[arg(long, value_name = "ORDER", value_enum, default_value_t = SortBy::Created)]
+    pub sort: SortBy,
+}
+
+impl PaginationOpts {
+    pub fn pagination(&self) -> Pagination {
+        Pagination::new(self.limit.map(|l| l as usize), self.page as usize)
+    }
+}
+
+/// Order of a list of resources
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum SortBy {
+    /// Sort by name
+    Name,
+    /// Keep the order in which the resources were created, as returned by the Orchestrator
+    #[default]
+    Created,
 }
 
 ////////////// !== validators
@@ -0,0 +1,124 @@
+use std::fmt::{Display, Formatter};
+
+use miette::miette;
+use serde::Serialize;
+
+use crate::error::{CommandError, ErrorCode};
+use crate::terminal::color_primary;
+use crate::{fmt_ok, CommandGlobalOpts, Result};
+
+/// Parameters of an existing resource which differ from the parameters requested
+/// by a create command used with `--if-not-exists`
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ParametersDiff {
+    differences: Vec<ParameterDiff>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct ParameterDiff {
+    name: String,
+    existing: String,
+    requested: String,
+}
+
+impl ParametersDiff {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compare a parameter of the existing resource with the requested value
+    pub fn compare(mut self, name: &str, existing: impl Display, requested: impl Display) -> Self {
+        let (existing, requested) = (existing.to_string(), requested.to_string());
+        if existing != requested {
+            self.differences.push(ParameterDiff {
+                name: name.to_string(),
+                existing,
+                requested,
+            });
+        }
+        self
+    }
+
+    /// Compare a parameter of the existing resource with the requested value,
+    /// only if a value was explicitly requested
+    pub fn compare_if_requested(
+        self,
+        name: &str,
+        existing: impl Display,
+        requested: Option<impl Display>,
+    ) -> Self {
+        match requested {
+            Some(requested) => self.compare(name, existing, requested),
+            None => self,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.differences.is_empty()
+    }
+}
+
+impl Display for ParametersDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for d in &self.differences {
+            writeln!(
+                f,
+                "  --{}: {} (existing) != {} (requested)",
+                d.name, d.existing, d.requested
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Report a resource found by a create command used with `--if-not-exists`.
+///
+/// The command succeeds if the resource has the requested parameters,
+/// and fails with the list of the differing parameters otherwise.
+pub fn report_existing_resource(
+    opts: &CommandGlobalOpts,
+    resource_type: &str,
+    name: &str,
+    diff: ParametersDiff,
+) -> Result<()> {
+    if !diff.is_empty() {
+        return Err(CommandError::with_code(
+            miette!("The {resource_type} {name} already exists with different parameters:\n{diff}"),
+            ErrorCode::Conflict,
+        ))?;
+    }
+    opts.terminal
+        .clone()
+        .stdout()
+        .plain(fmt_ok!(
+            "The {resource_type} {} already exists",
+            color_primary(name)
+        ))
+        .machine(name)
+        .json(serde_json::json!({ "name": name, "status": "already_exists" }))
+        .write_line()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parameters_diff() {
+        let diff = ParametersDiff::new()
+            .compare("from", "127.0.0.1:4000", "127.0.0.1:4000")
+            .compare_if_requested("identity", "i1", None::<&str>);
+        assert!(diff.is_empty());
+
+        let diff = diff
+            .compare("to", "/service/outlet", "/service/db")
+            .compare_if_requested("identity", "i1", Some("i2"));
+        assert!(!diff.is_empty());
+        assert_eq!(
+            diff.to_string(),
+            "  --to: /service/outlet (existing) != /service/db (requested)\n  \
+            --identity: i1 (existing) != i2 (requested)\n"
+        );
+    }
+}
@@ -5,6 +5,24 @@ pub type ExitCode = i32;
 /// Successful exit
 pub const OK: ExitCode = 0;
 
+/// The command arguments are invalid.
+/// This is also the exit code used when the command line cannot be parsed.
+pub const INVALID_USAGE: ExitCode = 2;
+
+/// The command requires the user to be enrolled to Ockam Orchestrator.
+pub const NOT_ENROLLED: ExitCode = 3;
+
+/// The live configuration of a resource differs from its expected configuration.
+/// This is the same code as `NOT_ENROLLED`, which `--diff` commands can't return
+/// once they could fetch the configuration.
+pub const DRIFTED: ExitCode = 3;
+
+/// A node, a project, or another resource used by the command does not exist.
+pub const RESOURCE_NOT_FOUND: ExitCode = 4;
+
+/// A node, a project or a service used by the command could not be reached.
+pub const CONNECTIVITY: ExitCode = 5;
+
 /// The command was used incorrectly, e.g., with the
 /// wrong number of arguments, a bad flag, a bad syntax
 /// in a parameter, etc.
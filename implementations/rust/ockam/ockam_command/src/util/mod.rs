@@ -25,8 +25,10 @@ use crate::{CommandGlobalOpts, OckamColor, Result};
 
 pub mod api;
 pub mod duration;
+pub mod existing_resource;
 pub mod exitcode;
 pub mod parsers;
+pub mod retry;
 
 /// A simple wrapper for shutting down the local embedded node (for
 /// the client side of the CLI).  Swallows errors and turns them into
@@ -150,6 +152,10 @@ pub fn print_path(p: &Path) -> String {
 /// Example:
 ///     if n1 has address of 127.0.0.1:1234
 ///     `/node/n1` -> `/ip4/127.0.0.1/tcp/1234`
+///
+/// Host names, as in `/dns/outlet.internal/tcp/5432`, WebSocket addresses, as in
+/// `/wss/relay.example.com/tcp/443`, and UDP addresses, as in `/ip4/10.0.0.1/udp/4500`,
+/// are left untouched: they are only resolved when a connection is established.
 pub async fn process_nodes_multiaddr(
     addr: &MultiAddr,
     cli_state: &CliState,
@@ -266,6 +272,18 @@ mod tests {
             ),
             (MultiAddr::from_str("/project/p1")?, Ok("/project/p1")),
             (MultiAddr::from_str("/service/s1")?, Ok("/service/s1")),
+            (
+                MultiAddr::from_str("/dns/outlet.internal/tcp/5432/service/outlet")?,
+                Ok("/dns/outlet.internal/tcp/5432/service/outlet"),
+            ),
+            (
+                MultiAddr::from_str("/ws/relay.example.com/tcp/443/service/outlet")?,
+                Ok("/ws/relay.example.com/tcp/443/service/outlet"),
+            ),
+            (
+                MultiAddr::from_str("/ip4/10.0.0.1/udp/4500/service/outlet")?,
+                Ok("/ip4/10.0.0.1/udp/4500/service/outlet"),
+            ),
             (
                 MultiAddr::from_str("/project/p1/node/n1/service/echo")?,
                 Ok("/project/p1/ip4/127.0.0.0/tcp/4000/service/echo"),
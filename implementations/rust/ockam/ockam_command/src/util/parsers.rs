@@ -3,9 +3,10 @@ use std::str::FromStr;
 
 use miette::miette;
 
-use ockam::identity::Identifier;
+use ockam::identity::{Identifier, TimestampInSeconds};
 use ockam_api::config::lookup::InternetAddress;
-use ockam_transport_tcp::resolve_peer;
+use ockam_api::logs::{LogFormat, LogLevelFilter};
+use ockam_transport_tcp::{resolve_peer, unix_socket_path};
 
 use crate::util::api;
 use crate::Result;
@@ -26,6 +27,33 @@ pub(crate) fn socket_addr_parser(input: &str) -> Result<SocketAddr> {
         .map_err(|e| miette!("cannot parse the address {address} as a socket address: {e}"))?)
 }
 
+/// Helper function for parsing a `host:port` from user input, checking that it can be resolved.
+/// The host name is kept so that it can be resolved again later.
+/// It is possible to just input a `port`. In that case the address will be assumed to be
+/// 127.0.0.1:<port>
+pub(crate) fn hostname_port_parser(input: &str) -> Result<String> {
+    let address = if input.contains(':') {
+        input.to_string()
+    } else {
+        format!("127.0.0.1:{input}")
+    };
+    socket_addr_parser(&address)?;
+    Ok(address)
+}
+
+/// Helper function for parsing the target of a TCP Outlet: either a `host:port`, as parsed by
+/// [`hostname_port_parser`], or the path of a Unix domain socket given as `unix:<path>` or as an
+/// absolute path. A Unix domain socket path is returned as `unix:<path>`
+pub(crate) fn outlet_target_parser(input: &str) -> Result<String> {
+    match unix_socket_path(input) {
+        Some(path) if cfg!(unix) => Ok(format!("unix:{}", path.display())),
+        Some(_) => Err(miette!(
+            "The Unix domain socket {input} can't be used: Unix domain sockets are not supported on this platform"
+        ))?,
+        None => hostname_port_parser(input),
+    }
+}
+
 /// Helper fn for parsing an identifier from user input by using
 /// [`ockam_identity::Identifier::from_str()`]
 pub(crate) fn identity_identifier_parser(input: &str) -> Result<Identifier> {
@@ -38,6 +66,102 @@ pub(crate) fn internet_address_parser(input: &str) -> Result<InternetAddress> {
     Ok(InternetAddress::new(input).ok_or_else(|| miette!("Invalid address: {input}"))?)
 }
 
+/// Helper fn for parsing a point in time from user input.
+/// The time is either an RFC 3339 date, for example `2024-01-31T10:00:00Z`, or a number of
+/// seconds since the Unix epoch
+pub(crate) fn timestamp_parser(input: &str) -> Result<TimestampInSeconds> {
+    if let Ok(seconds) = input.parse::<u64>() {
+        return Ok(TimestampInSeconds(seconds));
+    }
+    let time = chrono::DateTime::parse_from_rfc3339(input).map_err(|_| {
+        miette!("Invalid time: {input}. Use an RFC 3339 date, for example 2024-01-31T10:00:00Z, or a Unix timestamp in seconds")
+    })?;
+    let seconds = u64::try_from(time.timestamp()).map_err(|_| {
+        miette!("Invalid time: {input}. The time can't be before 1970-01-01T00:00:00Z")
+    })?;
+    Ok(TimestampInSeconds(seconds))
+}
+
+/// Helper fn for parsing a number of bytes from user input.
+/// The number can be followed by one of the units `B`, `KiB`, `MiB` or `GiB`, for example `64KiB`
+pub(crate) fn byte_size_parser(input: &str) -> Result<usize> {
+    let input = input.trim();
+    let split_at = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    let (number, unit) = input.split_at(split_at);
+    let multiplier: usize = match unit.trim() {
+        "" | "B" => 1,
+        "KiB" => 1 << 10,
+        "MiB" => 1 << 20,
+        "GiB" => 1 << 30,
+        _ => Err(miette!(
+            "Invalid size: {input}. Use a number of bytes, optionally followed by B, KiB, MiB or GiB"
+        ))?,
+    };
+    number
+        .parse::<usize>()
+        .ok()
+        .and_then(|n| n.checked_mul(multiplier))
+        .ok_or_else(|| miette!("Invalid size: {input}").into())
+}
+
+/// Helper fn for parsing the maximum size of a log file, for example `50MB`.
+/// A number without a unit is a number of megabytes, as with the `OCKAM_LOG_MAX_SIZE_MB` variable.
+/// `KB`, `MB` and `GB` are binary units, like `KiB`, `MiB` and `GiB`
+pub(crate) fn log_size_parser(input: &str) -> Result<u64> {
+    let input = input.trim();
+    let split_at = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    let (number, unit) = input.split_at(split_at);
+    let multiplier: u64 = match unit.trim() {
+        "B" => 1,
+        "KB" | "KiB" => 1 << 10,
+        "" | "MB" | "MiB" => 1 << 20,
+        "GB" | "GiB" => 1 << 30,
+        _ => Err(miette!(
+            "Invalid log file size: {input}. Use a number of megabytes, or a number followed by B, KB, MB or GB"
+        ))?,
+    };
+    number
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(multiplier))
+        .filter(|n| *n > 0)
+        .ok_or_else(|| miette!("Invalid log file size: {input}").into())
+}
+
+/// Helper fn for parsing a log level, for example `debug`, or a list of log filter directives,
+/// for example `info,ockam_api=trace`
+pub(crate) fn log_level_parser(input: &str) -> Result<LogLevelFilter> {
+    Ok(LogLevelFilter::from_str(input).map_err(|e| miette!("{e}"))?)
+}
+
+/// Helper fn for parsing the format of log lines
+pub(crate) fn log_format_parser(input: &str) -> Result<LogFormat> {
+    match input.trim() {
+        "default" => Ok(LogFormat::Default),
+        "pretty" => Ok(LogFormat::Pretty),
+        "json" => Ok(LogFormat::Json),
+        _ => Err(miette!(
+            "Invalid log format: {input}. Use default, pretty or json"
+        ))?,
+    }
+}
+
+/// Helper fn for parsing a file mode given in octal, for example `0660` or `660`
+pub(crate) fn file_mode_parser(input: &str) -> Result<u32> {
+    let input = input.trim();
+    let digits = input.strip_prefix("0o").unwrap_or(input);
+    match u32::from_str_radix(digits, 8) {
+        Ok(mode) if mode <= 0o7777 => Ok(mode),
+        _ => Err(miette!(
+            "Invalid file mode: {input}. Use an octal number, for example 0660"
+        ))?,
+    }
+}
+
 pub(crate) fn validate_project_name(s: &str) -> Result<String> {
     match api::validate_cloud_resource_name(s) {
         Ok(_) => Ok(s.to_string()),
@@ -102,6 +226,63 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_hostname_port_parser() {
+        assert_eq!(hostname_port_parser("9000").unwrap(), "127.0.0.1:9000");
+        assert_eq!(
+            hostname_port_parser("localhost:9000").unwrap(),
+            "localhost:9000"
+        );
+        assert_eq!(hostname_port_parser("[::1]:9000").unwrap(), "[::1]:9000");
+        assert!(hostname_port_parser("localhost:invalid").is_err());
+    }
+
+    #[test]
+    fn test_outlet_target_parser() {
+        assert_eq!(outlet_target_parser("9000").unwrap(), "127.0.0.1:9000");
+        assert_eq!(
+            outlet_target_parser("localhost:9000").unwrap(),
+            "localhost:9000"
+        );
+        let result = outlet_target_parser("unix:/var/run/postgresql/.s.PGSQL.5432");
+        let absolute_path = outlet_target_parser("/var/run/docker.sock");
+        if cfg!(unix) {
+            assert_eq!(result.unwrap(), "unix:/var/run/postgresql/.s.PGSQL.5432");
+            assert_eq!(absolute_path.unwrap(), "unix:/var/run/docker.sock");
+        } else {
+            assert!(result.is_err());
+            assert!(absolute_path.is_err());
+        }
+    }
+
+    #[test]
+    fn test_file_mode_parser() {
+        assert_eq!(file_mode_parser("0660").unwrap(), 0o660);
+        assert_eq!(file_mode_parser("600").unwrap(), 0o600);
+        assert_eq!(file_mode_parser("0o755").unwrap(), 0o755);
+        assert!(file_mode_parser("0690").is_err());
+        assert!(file_mode_parser("17777").is_err());
+        assert!(file_mode_parser("rw").is_err());
+    }
+
+    #[test]
+    fn test_timestamp_parser() {
+        assert_eq!(
+            timestamp_parser("1706695200").unwrap(),
+            TimestampInSeconds(1706695200)
+        );
+        assert_eq!(
+            timestamp_parser("2024-01-31T10:00:00Z").unwrap(),
+            TimestampInSeconds(1706695200)
+        );
+        assert_eq!(
+            timestamp_parser("2024-01-31T11:00:00+01:00").unwrap(),
+            TimestampInSeconds(1706695200)
+        );
+        assert!(timestamp_parser("1969-12-31T23:59:59Z").is_err());
+        assert!(timestamp_parser("yesterday").is_err());
+    }
+
     #[test]
     fn test_invalid_inputs() {
         // Test case 3: Any other format will throw an error
@@ -116,4 +297,37 @@ mod tests {
         let invalid_input = "192,166,0.1:9999";
         assert!(socket_addr_parser(invalid_input).is_err());
     }
+
+    #[test]
+    fn test_byte_size_parser() {
+        assert_eq!(byte_size_parser("512").unwrap(), 512);
+        assert_eq!(byte_size_parser("512B").unwrap(), 512);
+        assert_eq!(byte_size_parser("64KiB").unwrap(), 64 * 1024);
+        assert_eq!(byte_size_parser("2 MiB").unwrap(), 2 * 1024 * 1024);
+        assert_eq!(byte_size_parser("1GiB").unwrap(), 1024 * 1024 * 1024);
+        assert!(byte_size_parser("").is_err());
+        assert!(byte_size_parser("KiB").is_err());
+        assert!(byte_size_parser("64KB").is_err());
+        assert!(byte_size_parser("-1").is_err());
+    }
+
+    #[test]
+    fn test_log_size_parser() {
+        assert_eq!(log_size_parser("50").unwrap(), 50 * 1024 * 1024);
+        assert_eq!(log_size_parser("50MB").unwrap(), 50 * 1024 * 1024);
+        assert_eq!(log_size_parser("512KB").unwrap(), 512 * 1024);
+        assert_eq!(log_size_parser("1GiB").unwrap(), 1024 * 1024 * 1024);
+        assert_eq!(log_size_parser("1024B").unwrap(), 1024);
+        assert!(log_size_parser("0MB").is_err());
+        assert!(log_size_parser("50TB").is_err());
+        assert!(log_size_parser("MB").is_err());
+    }
+
+    #[test]
+    fn test_log_level_parser() {
+        assert!(log_level_parser("debug").is_ok());
+        assert!(log_level_parser("info,ockam_api=trace").is_ok());
+        let error = log_level_parser("verbose").unwrap_err().to_string();
+        assert!(error.contains("invalid log level 'verbose'"), "{error}");
+    }
 }
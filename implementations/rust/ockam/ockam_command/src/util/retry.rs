@@ -0,0 +1,427 @@
+use std::fmt::{Display, Formatter};
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use colorful::Colorful;
+use tokio_retry::strategy::jitter;
+use tracing::warn;
+
+use crate::util::api::RetryOpts;
+use crate::{fmt_log, fmt_warn, CommandGlobalOpts, Error, Result};
+
+/// Number of attempts made when only a retry delay is given
+const DEFAULT_RETRY_COUNT: u32 = 3;
+
+/// Delay before the first retry when only a retry count is given
+const DEFAULT_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Maximum delay between two attempts, once the delay stops doubling
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Maximum time spent retrying a command, when no maximum is given
+const DEFAULT_RETRY_MAX_ELAPSED: Duration = Duration::from_secs(5 * 60);
+
+/// Strategy used to run a command again when it fails with a retryable error.
+///
+/// The delay before each retry doubles after each attempt, up to a maximum delay,
+/// and a random jitter of at most a quarter of the delay is added to it.
+/// The retries stop when the maximum number of attempts is reached, or when the
+/// next attempt would start after the maximum elapsed time, 5 minutes by default.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryStrategy {
+    max_attempts: Option<u32>,
+    initial_delay: Duration,
+    max_delay: Duration,
+    max_elapsed: Duration,
+    jitter: bool,
+}
+
+impl RetryStrategy {
+    /// Make at most `max_attempts` attempts, or retry until the maximum elapsed time
+    /// if there is no maximum number of attempts
+    pub fn new(max_attempts: Option<u32>, initial_delay: Duration) -> Self {
+        Self {
+            max_attempts,
+            initial_delay,
+            max_delay: MAX_RETRY_DELAY.max(initial_delay),
+            max_elapsed: DEFAULT_RETRY_MAX_ELAPSED,
+            jitter: true,
+        }
+    }
+
+    /// Return the strategy configured by the retry options of a command,
+    /// or None if the command must not be retried
+    pub fn from_opts(opts: &RetryOpts) -> Option<Self> {
+        let (count, delay, max_elapsed) = (
+            opts.retry_count(),
+            opts.retry_delay(),
+            opts.retry_max_elapsed(),
+        );
+        if count.is_none() && delay.is_none() && max_elapsed.is_none() {
+            return None;
+        }
+        Some(
+            Self::new(
+                Some(count.unwrap_or(DEFAULT_RETRY_COUNT)),
+                delay.unwrap_or(DEFAULT_RETRY_DELAY),
+            )
+            .with_max_elapsed(max_elapsed.unwrap_or(DEFAULT_RETRY_MAX_ELAPSED)),
+        )
+    }
+
+    /// Cap the delay between two attempts. A delay equal to the initial delay
+    /// makes a fixed delay between attempts
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay.max(self.initial_delay);
+        self
+    }
+
+    /// Stop retrying if the next attempt would start after this time
+    pub fn with_max_elapsed(mut self, max_elapsed: Duration) -> Self {
+        self.max_elapsed = max_elapsed;
+        self
+    }
+
+    /// Use exact delays, without a random jitter
+    pub fn without_jitter(mut self) -> Self {
+        self.jitter = false;
+        self
+    }
+
+    /// Return the delay to wait after a failed attempt, before the next one
+    pub fn delay(&self, failed_attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(failed_attempt.saturating_sub(1));
+        let delay = self
+            .initial_delay
+            .saturating_mul(factor)
+            .min(self.max_delay);
+        if self.jitter {
+            delay + jitter(delay / 4)
+        } else {
+            delay
+        }
+    }
+}
+
+/// Number of an attempt, displayed as "attempt 2/5"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Attempt {
+    number: u32,
+    max_attempts: Option<u32>,
+}
+
+impl Attempt {
+    fn first(max_attempts: Option<u32>) -> Self {
+        Self {
+            number: 1,
+            max_attempts,
+        }
+    }
+
+    fn next(&self) -> Self {
+        Self {
+            number: self.number + 1,
+            max_attempts: self.max_attempts,
+        }
+    }
+
+    fn is_last(&self) -> bool {
+        self.max_attempts.is_some_and(|max| self.number >= max)
+    }
+
+    /// Number of this attempt, starting at 1
+    pub fn number(&self) -> u32 {
+        self.number
+    }
+}
+
+impl Display for Attempt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.max_attempts {
+            Some(max_attempts) => write!(f, "attempt {}/{max_attempts}", self.number),
+            None => write!(f, "attempt {}", self.number),
+        }
+    }
+}
+
+/// Source of time used to wait between attempts
+#[async_trait]
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+
+    async fn sleep(&self, duration: Duration);
+}
+
+/// Clock using the tokio timer
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+#[async_trait]
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        tokio::time::sleep(duration).await
+    }
+}
+
+/// Run an action until it succeeds, fails with an error which is not retryable,
+/// or until the retry strategy gives up
+pub struct RetryExecutor<C: Clock = SystemClock> {
+    strategy: RetryStrategy,
+    clock: C,
+}
+
+impl RetryExecutor<SystemClock> {
+    pub fn new(strategy: RetryStrategy) -> Self {
+        Self::with_clock(strategy, SystemClock)
+    }
+}
+
+impl<C: Clock> RetryExecutor<C> {
+    pub fn with_clock(strategy: RetryStrategy, clock: C) -> Self {
+        Self { strategy, clock }
+    }
+
+    /// Run the action with the number of each attempt, and call `notify` with the error
+    /// of a failed attempt, the next attempt and the delay before it, before each retry.
+    ///
+    /// The error of the last attempt is returned when the retries stop
+    pub async fn run<T, A, Fut, N>(&self, mut action: A, mut notify: N) -> Result<T>
+    where
+        A: FnMut(Attempt) -> Fut + Send,
+        Fut: Future<Output = Result<T>> + Send,
+        N: FnMut(&Error, Attempt, Duration) -> Result<()> + Send,
+    {
+        let started_at = self.clock.now();
+        let mut attempt = Attempt::first(self.strategy.max_attempts);
+        loop {
+            let error = match action(attempt).await {
+                Ok(result) => return Ok(result),
+                Err(error) => error,
+            };
+            if !error.is_retryable() || attempt.is_last() {
+                return Err(error);
+            }
+            let delay = self.strategy.delay(attempt.number);
+            if self.clock.now().duration_since(started_at) + delay > self.strategy.max_elapsed {
+                return Err(error);
+            }
+            let next = attempt.next();
+            notify(&error, next, delay)?;
+            self.clock.sleep(delay).await;
+            attempt = next;
+        }
+    }
+}
+
+/// Display the failure of an attempt and the delay before the next one
+pub fn notify_retry(
+    opts: &CommandGlobalOpts,
+    error: &Error,
+    next: Attempt,
+    delay: Duration,
+) -> Result<()> {
+    let failed = Attempt {
+        number: next.number - 1,
+        ..next
+    };
+    warn!("The {failed} failed, retrying in {delay:?}: {error:?}");
+    opts.terminal
+        .write_line(&fmt_warn!("Command failed with error ({failed}):"))?;
+    opts.terminal.write_line(&fmt_log!("{error:#}\n"))?;
+    opts.terminal.write_line(&fmt_log!(
+        "Retrying in {} ({next})...\n",
+        format_delay(delay)
+    ))?;
+    Ok(())
+}
+
+/// Format a delay in milliseconds below one second, and in seconds otherwise
+fn format_delay(delay: Duration) -> String {
+    if delay < Duration::from_secs(1) {
+        format!("{}ms", delay.as_millis())
+    } else {
+        format!("{:.1}s", delay.as_secs_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{CommandError, ErrorCode};
+    use miette::miette;
+    use std::sync::{Arc, Mutex};
+
+    /// Clock which advances instantly when sleeping, recording each sleep
+    #[derive(Clone)]
+    struct FakeClock {
+        started_at: Instant,
+        elapsed: Arc<Mutex<Duration>>,
+        sleeps: Arc<Mutex<Vec<Duration>>>,
+    }
+
+    impl FakeClock {
+        fn new() -> Self {
+            Self {
+                started_at: Instant::now(),
+                elapsed: Arc::new(Mutex::new(Duration::ZERO)),
+                sleeps: Arc::new(Mutex::new(vec![])),
+            }
+        }
+
+        fn sleeps(&self) -> Vec<u64> {
+            self.sleeps
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|d| d.as_secs())
+                .collect()
+        }
+    }
+
+    #[async_trait]
+    impl Clock for FakeClock {
+        fn now(&self) -> Instant {
+            self.started_at + *self.elapsed.lock().unwrap()
+        }
+
+        async fn sleep(&self, duration: Duration) {
+            *self.elapsed.lock().unwrap() += duration;
+            self.sleeps.lock().unwrap().push(duration);
+        }
+    }
+
+    fn connectivity_error() -> Error {
+        Error::Retry(
+            CommandError::with_code(miette!("node unreachable"), ErrorCode::Connectivity).into(),
+        )
+    }
+
+    #[test]
+    fn delays_are_exponential_and_capped() {
+        let strategy = RetryStrategy::new(Some(6), Duration::from_secs(1))
+            .with_max_delay(Duration::from_secs(5))
+            .without_jitter();
+        let delays: Vec<u64> = (1..6).map(|n| strategy.delay(n).as_secs()).collect();
+        assert_eq!(delays, vec![1, 2, 4, 5, 5]);
+
+        // the jitter adds at most a quarter of the delay
+        let strategy = RetryStrategy::new(Some(3), Duration::from_secs(4));
+        let delay = strategy.delay(1);
+        assert!(delay >= Duration::from_secs(4) && delay <= Duration::from_secs(5));
+    }
+
+    #[test]
+    fn attempts_are_displayed_with_their_maximum() {
+        let attempt = Attempt::first(Some(5)).next();
+        assert_eq!(attempt.to_string(), "attempt 2/5");
+        assert_eq!(Attempt::first(None).to_string(), "attempt 1");
+    }
+
+    #[tokio::test]
+    async fn retryable_errors_are_retried_until_the_last_attempt() {
+        let clock = FakeClock::new();
+        let strategy = RetryStrategy::new(Some(4), Duration::from_secs(1)).without_jitter();
+        let executor = RetryExecutor::with_clock(strategy, clock.clone());
+
+        let mut attempts = vec![];
+        let mut notified = vec![];
+        let result: Result<()> = executor
+            .run(
+                |attempt| {
+                    attempts.push(attempt.number());
+                    async { Err(connectivity_error()) }
+                },
+                |_, next, _| {
+                    notified.push(next.to_string());
+                    Ok(())
+                },
+            )
+            .await;
+        assert_eq!(result.unwrap_err().error_code(), ErrorCode::Connectivity);
+        assert_eq!(attempts, vec![1, 2, 3, 4]);
+        assert_eq!(notified, vec!["attempt 2/4", "attempt 3/4", "attempt 4/4"]);
+        assert_eq!(clock.sleeps(), vec![1, 2, 4]);
+    }
+
+    #[tokio::test]
+    async fn the_executor_stops_on_success_or_terminal_errors() {
+        let clock = FakeClock::new();
+        let strategy = RetryStrategy::new(Some(5), Duration::from_secs(1)).without_jitter();
+        let executor = RetryExecutor::with_clock(strategy, clock.clone());
+
+        let result = executor
+            .run(
+                |attempt| async move {
+                    if attempt.number() < 3 {
+                        Err(connectivity_error())
+                    } else {
+                        Ok(attempt.number())
+                    }
+                },
+                |_, _, _| Ok(()),
+            )
+            .await;
+        assert_eq!(result.unwrap(), 3);
+
+        // an invalid argument won't be fixed by retrying
+        let mut attempts = 0;
+        let result: Result<()> = executor
+            .run(
+                |_| {
+                    attempts += 1;
+                    async { Err(Error::arg_validation("--to", "/node/n1", None)) }
+                },
+                |_, _, _| Ok(()),
+            )
+            .await;
+        assert_eq!(result.unwrap_err().error_code(), ErrorCode::Usage);
+        assert_eq!(attempts, 1);
+    }
+
+    #[tokio::test]
+    async fn the_executor_stops_after_the_maximum_elapsed_time() {
+        let clock = FakeClock::new();
+        let strategy = RetryStrategy::new(None, Duration::from_secs(10))
+            .without_jitter()
+            .with_max_elapsed(Duration::from_secs(100));
+        let executor = RetryExecutor::with_clock(strategy, clock.clone());
+
+        let result: Result<()> = executor
+            .run(|_| async { Err(connectivity_error()) }, |_, _, _| Ok(()))
+            .await;
+        assert!(result.is_err());
+        // 10 + 20 + 40 = 70s, the next delay of 60s would exceed 100s
+        assert_eq!(clock.sleeps(), vec![10, 20, 40]);
+    }
+
+    #[tokio::test]
+    async fn the_retries_always_stop_after_a_default_maximum_elapsed_time() {
+        let clock = FakeClock::new();
+        let strategy = RetryStrategy::new(None, Duration::from_secs(20))
+            .with_max_delay(Duration::from_secs(20))
+            .without_jitter();
+        let executor = RetryExecutor::with_clock(strategy, clock.clone());
+
+        let result: Result<()> = executor
+            .run(|_| async { Err(connectivity_error()) }, |_, _, _| Ok(()))
+            .await;
+        assert!(result.is_err());
+        // 15 retries of 20s fit in the default maximum of 5 minutes
+        assert_eq!(clock.sleeps(), vec![20; 15]);
+    }
+
+    #[test]
+    fn the_maximum_elapsed_time_of_the_retry_options_has_a_default() {
+        let opts = RetryOpts {
+            retry_delay: Some(Duration::from_secs(1)),
+            ..Default::default()
+        };
+        let strategy = RetryStrategy::from_opts(&opts).unwrap();
+        assert_eq!(strategy.max_elapsed, DEFAULT_RETRY_MAX_ELAPSED);
+    }
+}
@@ -4,6 +4,7 @@ use colorful::Colorful;
 use ockam_node::Context;
 use std::path::PathBuf;
 
+use crate::vault::passphrase::get_new_vault_passphrase;
 use crate::{docs, fmt_info, fmt_ok, Command, CommandGlobalOpts};
 
 const LONG_ABOUT: &str = include_str!("./static/create/long_about.txt");
@@ -22,8 +23,20 @@ pub struct CreateCommand {
     #[arg(long)]
     pub path: Option<PathBuf>,
 
+    /// Store the identity keys in AWS KMS. Only the signing operations are delegated to
+    /// the KMS, the secure channel keys are stored locally
     #[arg(long, default_value = "false")]
     pub aws_kms: bool,
+
+    /// The id, or ARN, of an existing AWS KMS key to use for the identities created with this vault.
+    /// If not set, a new key is created in the KMS for each new identity
+    #[arg(long, value_name = "KEY_ARN", requires = "aws_kms")]
+    pub key_arn: Option<String>,
+
+    /// Encrypt the vault secrets with a key derived from a passphrase.
+    /// The passphrase is read from the OCKAM_VAULT_PASSWORD environment variable or prompted
+    #[arg(long, default_value = "false", conflicts_with = "aws_kms")]
+    pub encrypted: bool,
 }
 
 #[async_trait]
@@ -37,7 +50,14 @@ impl Command for CreateCommand {
         ))?;
         }
         let vault = if self.aws_kms {
-            opts.state.create_kms_vault(&self.name, &self.path).await?
+            opts.state
+                .create_kms_vault(&self.name, &self.path, &self.key_arn)
+                .await?
+        } else if self.encrypted {
+            let passphrase = get_new_vault_passphrase(&opts)?;
+            opts.state
+                .create_encrypted_vault(&self.name, &self.path, &passphrase)
+                .await?
         } else {
             opts.state
                 .create_named_vault(&self.name, &self.path)
@@ -64,4 +84,35 @@ mod tests {
         let cmd = parse_cmd_from_args(CreateCommand::NAME, &[]);
         assert!(cmd.is_ok());
     }
+
+    #[test]
+    fn key_arn_requires_aws_kms() {
+        let cmd = parse_cmd_from_args(
+            CreateCommand::NAME,
+            &["--key-arn".to_string(), "arn".to_string()],
+        );
+        assert!(cmd.is_err());
+
+        let cmd = parse_cmd_from_args(
+            CreateCommand::NAME,
+            &[
+                "--aws-kms".to_string(),
+                "--key-arn".to_string(),
+                "arn".to_string(),
+            ],
+        );
+        assert!(cmd.is_ok());
+    }
+
+    #[test]
+    fn encrypted_conflicts_with_aws_kms() {
+        let cmd = parse_cmd_from_args(CreateCommand::NAME, &["--encrypted".to_string()]);
+        assert!(cmd.is_ok());
+
+        let cmd = parse_cmd_from_args(
+            CreateCommand::NAME,
+            &["--aws-kms".to_string(), "--encrypted".to_string()],
+        );
+        assert!(cmd.is_err());
+    }
 }
@@ -0,0 +1,86 @@
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+
+use ockam_api::cli_state::{CliState, NamedVault};
+use ockam_node::Context;
+
+use crate::vault::passphrase::get_new_vault_passphrase;
+use crate::{color_primary, docs, fmt_ok, fmt_warn, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/encrypt/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/encrypt/after_long_help.txt");
+
+/// Encrypt the secrets of an existing vault with a passphrase
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct EncryptCommand {
+    /// Name of the vault to encrypt
+    name: String,
+}
+
+#[async_trait]
+impl Command for EncryptCommand {
+    const NAME: &'static str = "vault encrypt";
+
+    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let vault =
+            encrypt_vault(&opts.state, &self.name, || get_new_vault_passphrase(&opts)).await?;
+
+        let mut plain = fmt_ok!(
+            "The secrets of the vault {} are now encrypted\n",
+            color_primary(vault.name())
+        );
+        plain.push_str(&fmt_warn!(
+            "The passphrase will be required to use the vault. Set the OCKAM_VAULT_PASSWORD environment variable for non-interactive sessions\n"
+        ));
+
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .machine(vault.name())
+            .json(serde_json::json!({ "name": vault.name(), "is_encrypted": true }))
+            .write_line()?;
+        Ok(())
+    }
+}
+
+/// Encrypt the secrets of a vault.
+/// The passphrase is only requested once checked that the vault exists
+async fn encrypt_vault(
+    state: &CliState,
+    name: &str,
+    passphrase: impl FnOnce() -> miette::Result<String>,
+) -> crate::Result<NamedVault> {
+    state.get_named_vault(name).await?;
+    let passphrase = passphrase()?;
+    Ok(state.encrypt_vault(name, &passphrase).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miette::miette;
+
+    #[tokio::test]
+    async fn the_passphrase_is_only_requested_for_an_existing_vault() -> miette::Result<()> {
+        let state = CliState::test().await?;
+        let result = encrypt_vault(&state, "missing", || {
+            Err(miette!("the passphrase should not be requested"))
+        })
+        .await;
+        assert!(!result
+            .unwrap_err()
+            .to_string()
+            .contains("should not be requested"));
+
+        state.create_named_vault(&Some("v".into()), &None).await?;
+        let vault = encrypt_vault(&state, "v", || Ok("passphrase".to_string())).await?;
+        assert!(vault.is_encrypted());
+        assert!(state.get_named_vault("v").await?.is_encrypted());
+        Ok(())
+    }
+}
@@ -1,13 +1,18 @@
 mod create;
 mod delete;
+mod encrypt;
 mod list;
+mod move_identity;
 mod move_vault;
+pub(crate) mod passphrase;
 mod show;
 mod util;
 
 pub use crate::vault::create::CreateCommand;
 use crate::vault::delete::DeleteCommand;
+use crate::vault::encrypt::EncryptCommand;
 use crate::vault::list::ListCommand;
+use crate::vault::move_identity::MoveIdentityCommand;
 use crate::vault::move_vault::MoveCommand;
 use crate::vault::show::ShowCommand;
 use crate::{docs, Command, CommandGlobalOpts};
@@ -32,6 +37,8 @@ pub struct VaultCommand {
 pub enum VaultSubcommand {
     Create(CreateCommand),
     Move(MoveCommand),
+    MoveIdentity(MoveIdentityCommand),
+    Encrypt(EncryptCommand),
     Show(ShowCommand),
     Delete(DeleteCommand),
     List(ListCommand),
@@ -42,6 +49,8 @@ impl VaultCommand {
         match self.subcommand {
             VaultSubcommand::Create(cmd) => cmd.run(opts),
             VaultSubcommand::Move(cmd) => cmd.run(opts),
+            VaultSubcommand::MoveIdentity(cmd) => cmd.run(opts),
+            VaultSubcommand::Encrypt(cmd) => cmd.run(opts),
             VaultSubcommand::Show(cmd) => cmd.run(opts),
             VaultSubcommand::List(cmd) => cmd.run(opts),
             VaultSubcommand::Delete(cmd) => cmd.run(opts),
@@ -52,6 +61,8 @@ impl VaultCommand {
         match &self.subcommand {
             VaultSubcommand::Create(c) => c.name(),
             VaultSubcommand::Move(c) => c.name(),
+            VaultSubcommand::MoveIdentity(c) => c.name(),
+            VaultSubcommand::Encrypt(c) => c.name(),
             VaultSubcommand::Show(c) => c.name(),
             VaultSubcommand::Delete(c) => c.name(),
             VaultSubcommand::List(c) => c.name(),
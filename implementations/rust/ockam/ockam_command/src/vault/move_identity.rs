@@ -0,0 +1,152 @@
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+
+use ockam_api::cli_state::vault_keys::IdentityMove;
+use ockam_api::cli_state::CliState;
+use ockam_node::Context;
+
+use crate::{color_primary, docs, fmt_log, fmt_ok, fmt_warn, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/move_identity/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/move_identity/after_long_help.txt");
+
+/// Move an identity and its keys to another vault
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct MoveIdentityCommand {
+    /// Name of the identity to move
+    identity: String,
+
+    /// Name of the destination vault
+    #[arg(long, value_name = "VAULT_NAME")]
+    to: String,
+
+    /// Display the keys which would be moved, without moving them
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[async_trait]
+impl Command for MoveIdentityCommand {
+    const NAME: &'static str = "vault move-identity";
+
+    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let result = opts
+            .state
+            .move_identity(&self.identity, &self.to, self.dry_run)
+            .await?;
+
+        let mut plain = if self.dry_run {
+            fmt_log!(
+                "The identity {} would be moved from the vault {} to the vault {}\n",
+                color_primary(result.identity_name()),
+                color_primary(result.from_vault()),
+                color_primary(result.to_vault())
+            )
+        } else {
+            fmt_ok!(
+                "Moved the identity {} from the vault {} to the vault {}\n",
+                color_primary(result.identity_name()),
+                color_primary(result.from_vault()),
+                color_primary(result.to_vault())
+            )
+        };
+        plain.push_str(&Self::keys_output(&result));
+
+        if !self.dry_run {
+            let running_nodes = running_nodes_using(&opts.state, &self.identity).await?;
+            if !running_nodes.is_empty() {
+                plain.push_str(&fmt_warn!(
+                    "Restart the node(s) {} to use the moved identity\n",
+                    running_nodes.join(", ")
+                ));
+            }
+        }
+
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .json(serde_json::to_string(&result).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+impl MoveIdentityCommand {
+    fn keys_output(result: &IdentityMove) -> String {
+        result
+            .keys()
+            .iter()
+            .map(|key| {
+                let usage = key.usage().map(|u| format!(": {u}")).unwrap_or_default();
+                format!(
+                    "{}\n",
+                    fmt_log!(
+                        "{} ({}){}",
+                        color_primary(key.key_id()),
+                        key.key_type(),
+                        usage
+                    )
+                )
+            })
+            .collect()
+    }
+}
+
+/// Return the names of the running nodes using an identity.
+/// They need to be restarted to use the identity keys from their new vault
+async fn running_nodes_using(state: &CliState, identity_name: &str) -> crate::Result<Vec<String>> {
+    Ok(state
+        .get_nodes_by_identity_name(identity_name)
+        .await?
+        .into_iter()
+        .filter(|n| n.is_running())
+        .map(|n| n.name())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn the_keys_to_move_are_listed_without_moving_them() -> miette::Result<()> {
+        let state = CliState::test().await?;
+        let alice = state.create_identity_with_name("alice").await?;
+        state.create_named_vault(&Some("v2".into()), &None).await?;
+
+        let result = state.move_identity("alice", "v2", true).await?;
+        let output = MoveIdentityCommand::keys_output(&result);
+        assert!(!result.keys().is_empty());
+        for key in result.keys() {
+            assert!(output.contains(&key.key_id()));
+        }
+        assert_eq!(
+            state.get_named_identity("alice").await?.vault_name(),
+            alice.vault_name()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn only_running_nodes_need_to_be_restarted() -> miette::Result<()> {
+        let state = CliState::test().await?;
+        let alice = state.create_identity_with_name("alice").await?;
+        state
+            .create_node_with_identifier("n1", &alice.identifier())
+            .await?;
+        state
+            .create_node_with_identifier("n2", &alice.identifier())
+            .await?;
+        // the nodes are created with the pid of the current process, which is running
+        state.stop_node("n2", false).await?;
+
+        assert_eq!(running_nodes_using(&state, "alice").await?, vec!["n1"]);
+        Ok(())
+    }
+}
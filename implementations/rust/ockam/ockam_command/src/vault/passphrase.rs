@@ -0,0 +1,49 @@
+use console::Term;
+
+use ockam_api::cli_state::{VaultPassphraseProvider, OCKAM_VAULT_PASSWORD};
+use ockam_core::env::get_env;
+
+use crate::terminal::no_input_error;
+use crate::{CommandGlobalOpts, Terminal, TerminalStream};
+
+/// Ask the user for the passphrase of an encrypted vault when the terminal is interactive.
+/// Once a vault is unlocked, its passphrase is not asked again during the same command
+#[derive(Debug)]
+pub struct PromptVaultPassphrase {
+    terminal: Terminal<TerminalStream<Term>>,
+}
+
+impl PromptVaultPassphrase {
+    pub fn new(terminal: Terminal<TerminalStream<Term>>) -> Self {
+        Self { terminal }
+    }
+}
+
+impl VaultPassphraseProvider for PromptVaultPassphrase {
+    fn passphrase(&self, vault_name: &str) -> Option<String> {
+        self.terminal
+            .password(
+                format!("Enter the passphrase of the vault {vault_name}"),
+                false,
+            )
+            .ok()
+            .flatten()
+    }
+}
+
+/// Return the passphrase used to encrypt a vault, from the OCKAM_VAULT_PASSWORD environment variable
+/// or from a user prompt
+pub(crate) fn get_new_vault_passphrase(opts: &CommandGlobalOpts) -> miette::Result<String> {
+    if let Ok(Some(passphrase)) = get_env::<String>(OCKAM_VAULT_PASSWORD) {
+        return Ok(passphrase);
+    }
+    match opts
+        .terminal
+        .password("Enter a passphrase to encrypt the vault", true)?
+    {
+        Some(passphrase) => Ok(passphrase),
+        None => Err(no_input_error(format!(
+            "the {OCKAM_VAULT_PASSWORD} environment variable"
+        ))),
+    }
+}
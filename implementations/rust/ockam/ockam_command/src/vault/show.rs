@@ -23,6 +23,10 @@ after_long_help = docs::after_help(AFTER_LONG_HELP)
 pub struct ShowCommand {
     /// Name of the vault
     pub name: Option<String>,
+
+    /// List the keys stored in the vault, with the identities using them
+    #[arg(long)]
+    pub keys: bool,
 }
 
 impl ShowCommand {
@@ -44,6 +48,7 @@ impl ShowCommand {
 pub struct ShowTui {
     opts: CommandGlobalOpts,
     vault_name: Option<String>,
+    keys: bool,
 }
 
 impl ShowTui {
@@ -51,6 +56,7 @@ impl ShowTui {
         let tui = Self {
             opts,
             vault_name: cmd.name,
+            keys: cmd.keys,
         };
         tui.show().await
     }
@@ -92,7 +98,10 @@ impl ShowCommandTui for ShowTui {
     }
 
     async fn show_single(&self, item_name: &str) -> miette::Result<()> {
-        let vault = VaultOutput::new(&self.opts.state.get_named_vault(item_name).await?);
+        let mut vault = VaultOutput::new(&self.opts.state.get_named_vault(item_name).await?);
+        if self.keys {
+            vault = vault.with_keys(self.opts.state.get_vault_keys(item_name).await?);
+        }
         self.terminal()
             .stdout()
             .plain(vault.output()?)
@@ -1,6 +1,7 @@
 use colorful::Colorful;
 use indoc::formatdoc;
 
+use ockam_api::cli_state::vault_keys::VaultKey;
 use ockam_api::cli_state::vaults::NamedVault;
 
 use crate::output::Output;
@@ -9,23 +10,44 @@ use crate::OckamColor;
 #[derive(serde::Serialize)]
 pub struct VaultOutput {
     vault: NamedVault,
+    is_encrypted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keys: Option<Vec<VaultKey>>,
 }
 
 impl VaultOutput {
     pub fn new(vault: &NamedVault) -> Self {
         Self {
             vault: vault.clone(),
+            is_encrypted: vault.is_encrypted(),
+            keys: None,
+        }
+    }
+
+    pub fn with_keys(self, keys: Vec<VaultKey>) -> Self {
+        Self {
+            keys: Some(keys),
+            ..self
         }
     }
 
     pub fn name(&self) -> String {
         self.vault.name().clone()
     }
+
+    fn vault_type(&self) -> String {
+        match (self.vault.is_kms(), self.is_encrypted) {
+            (true, _) => "AWS KMS",
+            (false, true) => "OCKAM (encrypted)",
+            (false, false) => "OCKAM",
+        }
+        .to_string()
+    }
 }
 
 impl Output for VaultOutput {
     fn output(&self) -> crate::error::Result<String> {
-        Ok(formatdoc!(
+        let mut output = formatdoc!(
             r#"
             Vault:
                 Name: {name}
@@ -37,17 +59,33 @@ impl Output for VaultOutput {
                 .name()
                 .to_string()
                 .color(OckamColor::PrimaryResource.color()),
-            vault_type = match self.vault.is_kms() {
-                true => "AWS KMS",
-                false => "OCKAM",
-            }
-            .to_string()
-            .color(OckamColor::PrimaryResource.color()),
+            vault_type = self.vault_type().color(OckamColor::PrimaryResource.color()),
             vault_path = self
                 .vault
                 .path_as_string()
                 .color(OckamColor::PrimaryResource.color()),
-        ))
+        );
+        if let Some(keys) = &self.keys {
+            output.push_str("    Keys:\n");
+            if keys.is_empty() {
+                output.push_str("        No keys\n");
+            }
+            for key in keys {
+                output.push_str(&format!(
+                    "        {} ({})",
+                    key.key_id().color(OckamColor::PrimaryResource.color()),
+                    key.key_type()
+                ));
+                if let (Some(identity_name), Some(usage)) = (key.identity_name(), key.usage()) {
+                    output.push_str(&format!(
+                        ": {usage} of the identity {}",
+                        identity_name.color(OckamColor::PrimaryResource.color())
+                    ));
+                }
+                output.push('\n');
+            }
+        }
+        Ok(output)
     }
 
     fn list_output(&self) -> crate::error::Result<String> {
@@ -60,12 +98,7 @@ impl Output for VaultOutput {
                 .name()
                 .to_string()
                 .color(OckamColor::PrimaryResource.color()),
-            vault_type = match self.vault.is_kms() {
-                true => "AWS KMS",
-                false => "OCKAM",
-            }
-            .to_string()
-            .color(OckamColor::PrimaryResource.color()),
+            vault_type = self.vault_type().color(OckamColor::PrimaryResource.color()),
             vault_path = self
                 .vault
                 .path_as_string()
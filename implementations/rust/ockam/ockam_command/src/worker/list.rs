@@ -1,5 +1,8 @@
+use std::fmt::Write;
+
 use clap::Args;
 use colorful::Colorful;
+use miette::IntoDiagnostic;
 use tokio::sync::Mutex;
 use tokio::try_join;
 
@@ -8,7 +11,7 @@ use ockam_api::address::extract_address_value;
 use ockam_api::nodes::models::workers::{WorkerList, WorkerStatus};
 use ockam_api::nodes::BackgroundNodeClient;
 
-use crate::output::Output;
+use crate::output::{Output, WorkerOutput};
 use crate::terminal::OckamColor;
 use crate::util::{api, async_cmd};
 use crate::{docs, CommandGlobalOpts};
@@ -67,7 +70,15 @@ impl ListCommand {
             &format!("Workers on {}", node.node_name()),
             &format!("No workers found on {}.", node.node_name()),
         )?;
-        opts.terminal.stdout().plain(list).write_line()?;
+        let json = serde_json::to_string_pretty(
+            &workers
+                .list
+                .into_iter()
+                .map(WorkerOutput::from)
+                .collect::<Vec<_>>(),
+        )
+        .into_diagnostic()?;
+        opts.terminal.stdout().plain(list).json(json).write_line()?;
 
         Ok(())
     }
@@ -75,11 +86,28 @@ impl ListCommand {
 
 impl Output for WorkerStatus {
     fn output(&self) -> crate::Result<String> {
-        Ok(format!(
+        let mut output = format!(
             "Worker {}",
             self.addr
                 .to_string()
                 .color(OckamColor::PrimaryResource.color())
-        ))
+        );
+        if let Some(details) = &self.details {
+            write!(
+                output,
+                "\n  Type: {}, routed messages: {}, pending messages: {}",
+                details.kind, details.routed_messages, details.pending_messages
+            )?;
+            if !details.aliases.is_empty() {
+                write!(output, "\n  Aliases: {}", details.aliases.join(", "))?;
+            }
+            if details.terminal {
+                write!(output, "\n  Forwards messages to another node")?;
+            }
+            if let Some(owner) = &details.internal {
+                write!(output, "\n  Internal address of a {owner}")?;
+            }
+        }
+        Ok(output)
     }
 }
@@ -1,9 +1,11 @@
-use crate::{docs, CommandGlobalOpts};
+use crate::{docs, Command, CommandGlobalOpts};
 use clap::{Args, Subcommand};
 
 use list::ListCommand;
+use send::SendCommand;
 
 mod list;
+mod send;
 
 const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
 
@@ -23,18 +25,22 @@ pub struct WorkerCommand {
 pub enum WorkerSubcommand {
     #[command(display_order = 800)]
     List(ListCommand),
+    #[command(display_order = 800)]
+    Send(SendCommand),
 }
 
 impl WorkerCommand {
     pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
         match self.subcommand {
             WorkerSubcommand::List(c) => c.run(opts),
+            WorkerSubcommand::Send(c) => c.run(opts),
         }
     }
 
     pub fn name(&self) -> String {
         match &self.subcommand {
             WorkerSubcommand::List(c) => c.name(),
+            WorkerSubcommand::Send(c) => c.name(),
         }
     }
 }
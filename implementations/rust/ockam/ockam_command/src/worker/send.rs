@@ -0,0 +1,81 @@
+use core::time::Duration;
+
+use async_trait::async_trait;
+use clap::Args;
+use miette::{miette, Context as _, IntoDiagnostic};
+
+use ockam::Context;
+use ockam_api::address::extract_address_value;
+use ockam_api::nodes::models::workers::SendWorkerMessageReply;
+use ockam_api::nodes::BackgroundNodeClient;
+
+use crate::util::api;
+use crate::util::duration::duration_parser;
+use crate::{docs, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/send/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/send/after_long_help.txt");
+
+/// Time given to the node to answer, on top of the time spent waiting for the reply of the worker
+const REQUEST_TIMEOUT_MARGIN: Duration = Duration::from_secs(5);
+
+/// Send a raw message to a worker of a node
+#[derive(Clone, Debug, Args)]
+#[command(
+arg_required_else_help = true,
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct SendCommand {
+    /// Node on which the worker is running
+    #[arg(value_name = "NODE_NAME", long, display_order = 800, value_parser = extract_address_value)]
+    at: Option<String>,
+
+    /// Address of the worker, as displayed by `ockam worker list`
+    #[arg(long, value_name = "ADDRESS")]
+    to: String,
+
+    /// Hex-encoded payload of the message
+    #[arg(long, value_name = "HEX")]
+    payload: String,
+
+    /// Maximum time to wait for a reply of the worker
+    #[arg(long, value_name = "TIMEOUT", default_value = "5s", value_parser = duration_parser)]
+    timeout: Duration,
+}
+
+#[async_trait]
+impl Command for SendCommand {
+    const NAME: &'static str = "worker send";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let payload = hex::decode(self.payload.trim())
+            .into_diagnostic()
+            .context("The payload is not a valid hex string")?;
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.at)
+            .await?
+            .set_timeout(Some(self.timeout + REQUEST_TIMEOUT_MARGIN));
+        let response: SendWorkerMessageReply = node
+            .ask(
+                ctx,
+                api::send_worker_message(&self.to, payload, self.timeout),
+            )
+            .await?;
+
+        let reply = match response.reply {
+            Some(reply) => hex::encode(reply),
+            None => Err(miette!(
+                "The worker {} did not reply within {:?}",
+                self.to,
+                self.timeout
+            ))?,
+        };
+        opts.terminal
+            .stdout()
+            .plain(&reply)
+            .machine(&reply)
+            .json(serde_json::json!({ "to": self.to, "reply": reply }))
+            .write_line()?;
+        Ok(())
+    }
+}
@@ -186,6 +186,105 @@ EOF
   assert_output --partial "m3_member"
 }
 
+@test "authority - enrollment tickets issued by a standalone authority" {
+  port="$(random_port)"
+
+  run "$OCKAM" identity create authority
+  run "$OCKAM" identity create enroller
+  run "$OCKAM" identity create m1
+  run "$OCKAM" identity create m2
+  run "$OCKAM" identity create m3
+
+  enroller_identifier=$($OCKAM identity show enroller)
+
+  trusted="{\"$enroller_identifier\": {\"ockam-role\": \"enroller\"}}"
+  run_success "$OCKAM" authority create --tcp-listener-address="127.0.0.1:$port" --project-identifier 1 --trusted-identities "$trusted"
+  sleep 1 # wait for authority to start TCP listener
+
+  # The ticket contains the authority identity and route, no project needs to be imported
+  ticket=$($OCKAM authority ticket create --at authority --identity enroller --attribute sample_attr=members_group --usage-count 2 --expires-in 1d)
+  run_success "$OCKAM" project enroll $ticket --identity m1
+  assert_output --partial "members_group"
+
+  run_success "$OCKAM" project enroll $ticket --identity m2
+  assert_output --partial "members_group"
+
+  # The usage count of the ticket is exhausted
+  run_failure "$OCKAM" project enroll $ticket --identity m3
+
+  # A non-enroller can't create tickets
+  run_failure "$OCKAM" authority ticket create --at authority --identity m1
+
+  # An expired ticket is rejected
+  ticket=$($OCKAM authority ticket create --at authority --identity enroller --expires-in 1s)
+  sleep 2
+  run_failure "$OCKAM" project enroll $ticket --identity m3
+  assert_output --partial "Expired enrollment token"
+}
+
+@test "authority - update the attributes of a member" {
+  port="$(random_port)"
+
+  run "$OCKAM" identity create authority
+  run "$OCKAM" identity create enroller
+  run "$OCKAM" identity create m1
+  run "$OCKAM" identity create m2
+
+  enroller_identifier=$($OCKAM identity show enroller)
+  m1_identifier=$($OCKAM identity show m1)
+  m2_identifier=$($OCKAM identity show m2)
+
+  trusted="{\"$enroller_identifier\": {\"ockam-role\": \"enroller\"}}"
+  run_success "$OCKAM" authority create --tcp-listener-address="127.0.0.1:$port" --project-identifier 1 --trusted-identities "$trusted"
+  sleep 1 # wait for authority to start TCP listener
+
+  ticket=$($OCKAM authority ticket create --at authority --identity enroller --attribute role=user --attribute temp=1)
+  run_success "$OCKAM" project enroll $ticket --identity m1
+  assert_output --partial "temp"
+
+  update=$($OCKAM authority member update $m1_identifier --at authority --identity enroller \
+    --attribute role=admin --remove-attribute temp --output json)
+  run_success bash -c "echo '$update' | jq -e '.before == {\"role\": \"user\", \"temp\": \"1\"}'"
+  run_success bash -c "echo '$update' | jq -e '.after == {\"role\": \"admin\"}'"
+
+  # A new credential contains the updated attributes
+  run_success "$OCKAM" project enroll --identity m1
+  assert_output --partial "admin"
+  refute_output --partial "temp"
+
+  # Only members can be updated
+  run_failure "$OCKAM" authority member update $m2_identifier --at authority --identity enroller --attribute role=admin
+  assert_output --partial "is not a member"
+
+  # A member can't update other members
+  run_failure "$OCKAM" authority member update $m1_identifier --at authority --identity m1 --attribute role=enroller
+}
+
+@test "authority - credential ttl requested when enrolling" {
+  port="$(random_port)"
+
+  run "$OCKAM" identity create authority
+  run "$OCKAM" identity create enroller
+  run "$OCKAM" identity create m1
+
+  enroller_identifier=$($OCKAM identity show enroller)
+
+  # The credentials issued by the authority are valid for at most 1 hour
+  trusted="{\"$enroller_identifier\": {\"ockam-role\": \"enroller\"}}"
+  export CREDENTIAL_TTL_SECS=3600
+  run_success "$OCKAM" authority create --tcp-listener-address="127.0.0.1:$port" --project-identifier 1 --trusted-identities "$trusted"
+  sleep 1 # wait for authority to start TCP listener
+
+  ticket=$($OCKAM authority ticket create --at authority --identity enroller --usage-count 2)
+  run_success "$OCKAM" project enroll $ticket --identity m1 --credential-ttl 15m
+  assert_output --partial "The credential expires at"
+  refute_output --partial "exceeds the maximum"
+
+  # A longer ttl is reduced to the maximum ttl of the authority
+  run_success "$OCKAM" project enroll --identity m1 --credential-ttl 1d
+  assert_output --partial "It was reduced to 3600s"
+}
+
 @test "authority - legacy enrollers as admins" {
   port="$(random_port)"
 
@@ -307,5 +406,12 @@ EOF
   assert_output --partial "ockam-relay=*"
   assert_output --partial "attested_by: \"$enroller_identifier\""
 
-  run_success "$OCKAM" project-member delete "$m_identifier" --identity enroller
+  run_success "$OCKAM" project member list --identity enroller --limit 1
+  assert_output --partial "--offset 1"
+
+  run_success "$OCKAM" project-member delete "$m_identifier" --identity enroller --yes
+
+  # deleting an identifier which is not a member fails with the "not found" exit code
+  run "$OCKAM" project member delete "$m_identifier" --identity enroller --yes
+  assert_equal "$status" 4
 }
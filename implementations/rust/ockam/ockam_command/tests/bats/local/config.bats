@@ -0,0 +1,55 @@
+#!/bin/bash
+
+# ===== SETUP
+
+setup() {
+  load ../load/base.bash
+  load_bats_ext
+  setup_home_dir
+  export XDG_CONFIG_HOME="$BATS_TEST_TMPDIR/config"
+  mkdir -p "$XDG_CONFIG_HOME/ockam"
+}
+
+teardown() {
+  teardown_home_dir
+}
+
+# ===== TESTS
+
+@test "config - the configuration file sets the default values of the commands" {
+  n="$(random_str)"
+  i="$(random_str)"
+  run_success "$OCKAM" identity create "$i"
+  run_success "$OCKAM" node create "$n" --identity "$i"
+  run_success "$OCKAM" node create "$(random_str)"
+
+  cat >"$XDG_CONFIG_HOME/ockam/config.toml" <<EOT
+node = "$n"
+identity = "$i"
+output = "json"
+timeout = "1m"
+EOT
+
+  # the configured node is used when --at is not specified
+  run_success bash -c "$OCKAM node show | jq -e '.name == \"$n\"'"
+
+  # the command line arguments override the configuration file
+  run_success "$OCKAM" config show --resolved --output plain
+  assert_output --regexp "node .*$n .*config.toml"
+  assert_output --regexp "output .*plain .*command line"
+  assert_output --regexp "color .*true .*default"
+  assert_output --regexp "timeout .*60s .*config.toml"
+}
+
+@test "config - an invalid configuration file is reported with its line" {
+  cat >"$XDG_CONFIG_HOME/ockam/config.toml" <<EOT
+node = "n1"
+timeout = "soon"
+EOT
+  run_failure "$OCKAM" node list
+  assert_output --partial "config.toml, line 2: invalid duration 'soon'"
+
+  echo 'default_node = "n1"' >"$XDG_CONFIG_HOME/ockam/config.toml"
+  run_failure "$OCKAM" config show
+  assert_output --partial "line 1: unknown field \`default_node\`"
+}
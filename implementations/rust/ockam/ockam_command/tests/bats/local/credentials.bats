@@ -40,9 +40,42 @@ teardown() {
   # create an invalid credential
   echo "aabbcc" >"$OCKAM_HOME/bad_credential"
 
-  run_success "$OCKAM" credential verify --issuer "$idt1_short" --credential-path "$OCKAM_HOME/bad_credential"
-  assert_output --partial "false"
+  run_failure "$OCKAM" credential verify --issuer "$idt1_short" --credential-path "$OCKAM_HOME/bad_credential"
+  assert_output --partial "The credential could not be decoded"
 
   run_failure "$OCKAM" credential store --issuer "$idt1_short" --credential-path "$OCKAM_HOME/bad_credential" --scope "test"
   assert_output --partial "Credential is not verified"
 }
+
+@test "credential - verify a credential received out of band" {
+  run_success "$OCKAM" identity create i1
+  idt1_short=$($OCKAM identity show i1)
+  $OCKAM identity show i1 --full --encoding hex >"$OCKAM_HOME/issuer"
+
+  run_success "$OCKAM" identity create i2
+  idt2_short=$($OCKAM identity show i2)
+
+  "$OCKAM" credential issue --as i1 --for "$idt2_short" --attribute city="New York" --ttl 1h --encoding hex >"$OCKAM_HOME/credential"
+
+  # the issuer is known locally
+  run_success "$OCKAM" credential verify --issuer "$idt1_short" --credential "@$OCKAM_HOME/credential" --output json
+  run_success bash -c "echo '$output' | jq -e '.is_valid == true and .subject == \"$idt2_short\" and .attributes.city == \"New York\"'"
+
+  # the issuer identity is provided inline, from a file
+  run_success "$OCKAM" credential verify --issuer "@$OCKAM_HOME/issuer" --credential "$(cat "$OCKAM_HOME/credential")"
+  assert_output --partial "true"
+
+  # the credential is expired
+  run_failure "$OCKAM" credential verify --issuer "@$OCKAM_HOME/issuer" --credential "@$OCKAM_HOME/credential" --at-time "$(($(date +%s) + 7200))"
+  assert_output --partial "expired"
+
+  # the issuer is unknown
+  run_failure "$OCKAM" credential verify --issuer "I0000000000000000000000000000000000000000000000000000000000000000" --credential "@$OCKAM_HOME/credential"
+  assert_output --partial "is unknown"
+
+  # the credential was issued by another identity
+  run_success "$OCKAM" identity create i4
+  idt4_short=$($OCKAM identity show i4)
+  run_failure "$OCKAM" credential verify --issuer "$idt4_short" --credential "@$OCKAM_HOME/credential"
+  assert_output --partial "not by"
+}
@@ -74,6 +74,21 @@ teardown() {
   assert_output --partial "[]"
 }
 
+@test "identity - delete without user input" {
+  i="$(random_str)"
+  run_success "$OCKAM" identity create "$i"
+
+  # The deletion can't be confirmed with a prompt
+  run_failure "$OCKAM" identity delete "$i" --no-input
+  assert_output --partial "--yes"
+
+  # The identity to delete can't be selected with a prompt
+  run_failure "$OCKAM" identity delete --no-input
+  assert_output --partial "--all"
+
+  run_success "$OCKAM" identity delete "$i" --no-input --yes
+}
+
 @test "identity - set default" {
   i=$(random_str)
 
@@ -107,3 +122,25 @@ teardown() {
   run_success "$OCKAM" identity show --full --encoding hex
   assert_output "$exported"
 }
+
+@test "identity - rotate" {
+  i=$(random_str)
+  n=$(random_str)
+  run_success "$OCKAM" identity create "${i}"
+  run_success "$OCKAM" node create "${n}" --identity "${i}"
+
+  # A dry run shows the plan but doesn't rotate the key
+  run_success "$OCKAM" identity rotate "${i}" --dry-run
+  assert_output --partial "${n}"
+  run_success "$OCKAM" identity show "${i}" --full
+  refute_output --partial "Change[1]:"
+
+  # The identifier is kept and a change is added to the change history
+  identifier=$($OCKAM identity show "${i}")
+  run_success "$OCKAM" identity rotate "${i}"
+  assert_output --partial "${n}"
+  run_success "$OCKAM" identity show "${i}"
+  assert_output "${identifier}"
+  run_success "$OCKAM" identity show "${i}" --full
+  assert_output --partial "Change[1]:"
+}
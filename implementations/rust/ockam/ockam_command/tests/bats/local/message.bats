@@ -51,3 +51,92 @@ teardown() {
               | $OCKAM message send $msg --from /node/n1 --to -/service/echo"
   assert_output "$msg"
 }
+
+@test "message - send a message through an ephemeral secure channel" {
+  run_success "$OCKAM" identity create i1
+  run_success "$OCKAM" identity create i2
+  idt2=$($OCKAM identity show i2)
+  run_success "$OCKAM" node create n1 --identity i1
+  run_success "$OCKAM" node create n2 --identity i2
+
+  # From a temporary node, with a specific identity
+  msg=$(random_str)
+  run_success "$OCKAM" message send "$msg" --secure --identity i1 --timeout 5 --to /node/n2/service/uppercase
+  assert_output "$(to_uppercase "$msg")"
+
+  # From a background node, the JSON output contains the identifier of the other side of the channel
+  run_success "$OCKAM" message send hello --secure --timeout 5 --from n1 --to /node/n2/service/echo --output json
+  assert_output --partial "\"secure_channel\": true"
+  assert_output --partial "\"their_identifier\": \"$idt2\""
+
+  # The channel is deleted once the message is sent
+  run_success "$OCKAM" secure-channel list --at n1 --output json
+  refute_output --partial "$idt2"
+
+  # The channel is also deleted when no reply is received
+  run_failure "$OCKAM" message send hello --secure --timeout 1 --from n1 --to /node/n2/service/missing
+  run_success "$OCKAM" secure-channel list --at n1 --output json
+  refute_output --partial "$idt2"
+
+  # A route without a hop before the service can't be secured
+  run_failure "$OCKAM" message send hello --secure --from n1 --to /service/echo
+}
+
+@test "message - send binary payloads and retry when no reply is received" {
+  run_success "$OCKAM" node create n1
+
+  # Hex encoded payload, hex encoded reply
+  run_success "$OCKAM" message send 00ff10 --hex --timeout 5 --from n1 --to /node/n1/service/echo
+  assert_output "00ff10"
+
+  # Binary payload read from a file, the reply is not a valid UTF-8 string
+  printf '\x00\xff\x10' >"$OCKAM_HOME/payload.bin"
+  run_failure "$OCKAM" message send --file "$OCKAM_HOME/payload.bin" --timeout 5 --from n1 --to /node/n1/service/echo
+  assert_output --partial "--expect-hex"
+
+  run_success "$OCKAM" message send --file "$OCKAM_HOME/payload.bin" --expect-hex --timeout 5 --from n1 --to /node/n1/service/echo
+  assert_output "00ff10"
+
+  run_success "$OCKAM" message send --file "$OCKAM_HOME/payload.bin" --output-file "$OCKAM_HOME/reply.bin" --timeout 5 --from n1 --to /node/n1/service/echo
+  run cmp "$OCKAM_HOME/payload.bin" "$OCKAM_HOME/reply.bin"
+  assert_success
+
+  # The JSON output contains the round-trip latency
+  run_success "$OCKAM" message send hello --timeout 5 --from n1 --to /node/n1/service/echo --output json
+  assert_output --partial "\"latency_ms\""
+  assert_output --partial "\"attempts\": 1"
+
+  # No reply from a service which doesn't exist
+  run_failure "$OCKAM" message send hello --timeout 1 --retries 2 --retry-delay 100ms --from n1 --to /node/n1/service/missing
+  assert_output --partial "attempt 3/3"
+}
+
+@test "message - trace the hops of a route" {
+  run_success "$OCKAM" node create n1
+  run_success "$OCKAM" node create n2
+
+  # Every node answers the probes, the destination service is not probed
+  run_success bash -c "$OCKAM trace /node/n1/service/hop/secure/api/service/echo --output json \
+              | jq -e 'map(.status) == [\"answered\", \"answered\", \"answered\", \"opaque\"] and .[0].node_name == \"n1\"'"
+
+  # Same, from a background node
+  run_success bash -c "$OCKAM trace /node/n2/secure/api/service/echo --from n1 --output json \
+              | jq -e 'map(.node_name) == [\"n2\", \"n2\", null]'"
+
+  # The route is broken after the first node
+  run_success bash -c "$OCKAM trace /node/n1/service/missing/secure/api/service/echo --timeout 1s --output json \
+              | jq -e 'map(.status) == [\"answered\", \"no_answer\", \"not_reached\", \"not_reached\"]'"
+}
+
+@test "message - ping the echo service of a node" {
+  run_success "$OCKAM" node create n1
+
+  run_success "$OCKAM" ping --to /node/n1/secure/api --count 3 --interval 100ms --include-setup --output json
+  assert_output --partial "\"sent\": 3"
+  assert_output --partial "\"received\": 3"
+  assert_output --partial "\"setup_ms\""
+
+  # No reply from a route which doesn't lead to an echo service
+  run_failure "$OCKAM" ping --to /node/n1/service/missing --count 2 --interval 100ms --timeout 1s --output json
+  assert_output --partial "\"loss_percent\": 100.0"
+}
@@ -46,6 +46,18 @@ force_kill_node() {
   assert_output --partial "/service/uppercase"
 }
 
+@test "node - show the resources used by a node" {
+  n="$(random_str)"
+  run_success "$OCKAM" node create "$n"
+
+  run_success "$OCKAM" node show "$n"
+  assert_output --partial "Resources:"
+  assert_output --partial "Uptime:"
+
+  run_success bash -c "$OCKAM node show $n --output json | jq -e '.resources.workers > 0'"
+  run_success bash -c "$OCKAM node show $n --output json | jq -e '.resources.tcp_listeners >= 1'"
+}
+
 @test "node - start services" {
   run_success "$OCKAM" node create n1
 
@@ -54,6 +66,30 @@ force_kill_node() {
   run_failure "$OCKAM" service start hop --addr my_hop --at n1
 }
 
+@test "node - start, list and stop services" {
+  run_success "$OCKAM" node create n1
+  run_success "$OCKAM" node create n2
+
+  run_success "$OCKAM" service start echo --addr my_echo --at n1
+  run_success "$OCKAM" service start discard --addr my_discard --at n1
+  run_success "$OCKAM" service list --at n1 --output json
+  assert_output --partial "\"addr\":\"my_echo\""
+  assert_output --partial "\"service_type\":\"discard\""
+  assert_output --partial "started_at"
+
+  msg=$(random_str)
+  run_success "$OCKAM" message send "$msg" --from n2 --to "/node/n1/service/my_echo"
+  assert_output "$msg"
+
+  # The service is stopped, and can't be stopped twice
+  run_success "$OCKAM" service stop --addr my_echo --at n1
+  run_failure "$OCKAM" service stop --addr my_echo --at n1
+  run_failure "$OCKAM" message send "$msg" --from n2 --to "/node/n1/service/my_echo" --timeout 2
+
+  # The default echo service is used to check the connections of the node
+  run_failure "$OCKAM" service stop --addr echo --at n1
+}
+
 @test "node - is restarted with default services" {
   n="$(random_str)"
   # Create node, check that it has one of the default services running
@@ -72,6 +108,20 @@ force_kill_node() {
   run_failure "$OCKAM" node create "$n"
 }
 
+@test "node - delete all nodes concurrently" {
+  for i in 1 2 3; do
+    run_success "$OCKAM" node create "n$i"
+  done
+
+  run_success "$OCKAM" node delete --all --yes --output json
+  assert_output --partial "\"name\": \"n1\""
+  assert_output --partial "\"status\": \"deleted\""
+  refute_output --partial "\"status\": \"failed\""
+
+  run_success "$OCKAM" node list --output json
+  refute_output --partial "\"name\": \"n1\""
+}
+
 @test "node - can recreate a background node after it was gracefully stopped" {
   n="$(random_str)"
   run_success "$OCKAM" node create "$n"
@@ -137,6 +187,21 @@ force_kill_node() {
   run_success "$OCKAM" node show "$n"
 }
 
+@test "node - a foreground node writes a pid file and stops when its stdin is closed" {
+  n="$(random_str)"
+  pid_file="$OCKAM_HOME/$n.pid"
+
+  # the node stdin is closed when the sleep command exits
+  sleep 5 | "$OCKAM" node create "$n" -f --exit-on-eof --pid-file "$pid_file" >/dev/null 2>&1 &
+  sleep 2
+  run_success "$OCKAM" node show "$n"
+  run_success cat "$pid_file"
+  assert_output --regexp "^[0-9]+$"
+
+  sleep 6
+  run_failure test -f "$pid_file"
+}
+
 @test "node - background node logs to file" {
   QUIET=0
   n="$(random_str)"
@@ -145,6 +210,18 @@ force_kill_node() {
   assert_output --partial "stdout"
 }
 
+@test "node - display the logs of a background node" {
+  n="$(random_str)"
+  run_success "$OCKAM" node create $n -vv
+  run_success "$OCKAM" node logs $n --lines 5
+  assert_output --partial "INFO"
+
+  run_success "$OCKAM" node logs $n
+  assert_output --partial "$OCKAM_HOME/nodes/$n"
+
+  run_failure "$OCKAM" node logs non-existing-node
+}
+
 @test "node - foreground node logs to stdout only" {
   n="$(random_str)"
   run_success "$OCKAM" node create $n -vv -f &
@@ -152,3 +229,161 @@ force_kill_node() {
   # It should even create the node directory
   run_failure ls -l "$OCKAM_HOME/nodes/$n"
 }
+
+@test "node - restart a node and recreate its resources" {
+  n="$(random_str)"
+  outlet_port="$(random_port)"
+  inlet_port="$(random_port)"
+  run_success "$OCKAM" node create "$n"
+  run_success "$OCKAM" tcp-outlet create --at "$n" --to "127.0.0.1:$outlet_port" --from outlet
+  run_success "$OCKAM" tcp-inlet create --at "$n" --from "127.0.0.1:$inlet_port" --to "/node/$n/service/outlet" --alias inlet
+
+  run_success "$OCKAM" node restart "$n" --dry-run
+  assert_output --partial "tcp-outlet"
+  assert_output --partial "tcp-inlet"
+
+  run_success "$OCKAM" node restart "$n"
+  assert_output --partial "Recreated the tcp-outlet"
+  assert_output --partial "Recreated the tcp-inlet"
+
+  run_success "$OCKAM" tcp-outlet show outlet --at "$n"
+  run_success "$OCKAM" tcp-inlet show inlet --at "$n"
+}
+
+@test "node - restart a node with the options of the node and of its inlets" {
+  n="$(random_str)"
+  outlet_port="$(random_port)"
+  inlet_port="$(random_port)"
+  run_success "$OCKAM" identity create i1
+  i1_identifier=$($OCKAM identity show i1)
+  run_success "$OCKAM" node create "$n" --identity i1 --debug-api
+  run_success "$OCKAM" tcp-outlet create --at "$n" --to "127.0.0.1:$outlet_port" --from outlet
+  run_success "$OCKAM" tcp-inlet create --at "$n" --from "127.0.0.1:$inlet_port" --to "/node/$n/service/outlet" --alias inlet \
+    --authorized "$i1_identifier" --allow '(= subject.component "db")'
+
+  run_success "$OCKAM" node restart "$n"
+  assert_output --partial "Recreated the tcp-inlet"
+
+  # The node still uses its identity
+  run_success "$OCKAM" node show "$n" --output json
+  assert_output --partial "$i1_identifier"
+
+  # The inlet is created again with its authorized identity and its policy
+  run_success "$OCKAM" tcp-inlet show inlet --at "$n" --output json
+  assert_output --partial "\"authorized\":[\"$i1_identifier\"]"
+  assert_output --partial "subject.component"
+}
+
+@test "node - a supervised node is restarted after a crash" {
+  n="$(random_str)"
+  run_success "$OCKAM" node create "$n" --restart-on-failure --max-restarts 3 --max-restart-backoff 2s
+  run_success "$OCKAM" node show "$n"
+  assert_output --partial "RESTART ON FAILURE"
+
+  force_kill_node "$n"
+  sleep 5
+
+  run_success "$OCKAM" node show "$n" --output json
+  assert_output --partial "\"restart_count\": 1"
+  assert_output --partial "\"is_up\": true"
+
+  # A node stopped on purpose is not restarted
+  run_success "$OCKAM" node stop "$n"
+  sleep 3
+  run_success "$OCKAM" node show "$n" --output json
+  assert_output --partial "\"is_up\": false"
+}
+
+@test "node - create nodes with TCP keepalive and user timeout options" {
+  n1="$(random_str)"
+  n2="$(random_str)"
+  run_success "$OCKAM" node create "$n1" --tcp-keepalive 30s --tcp-user-timeout 1m
+  run_success "$OCKAM" node create "$n2" --tcp-keepalive 0s
+
+  msg=$(random_str)
+  run_success "$OCKAM" message send "$msg" --from "$n1" --to "/node/$n2/service/uppercase"
+  assert_output "$(to_uppercase "$msg")"
+
+  run_failure "$OCKAM" node create "$(random_str)" --tcp-keepalive invalid
+}
+
+@test "node - complete node names from the local state" {
+  n1="$(random_str)"
+  n2="$(random_str)"
+  run_success "$OCKAM" node create "$n1"
+  run_success "$OCKAM" node create "$n2"
+
+  run_success "$OCKAM" __complete nodes
+  assert_line "$n1"
+  assert_line "$n2"
+
+  # Nothing is printed, and no state is created, when the state directory doesn't exist
+  missing="$(mktemp -d)/missing"
+  run_success env OCKAM_HOME="$missing" "$OCKAM" __complete nodes
+  assert_output ""
+  [ ! -e "$missing" ]
+
+  run_success "$OCKAM" completion --shell bash
+  assert_output --partial "ockam __complete"
+}
+
+@test "node - inspect and message the workers of a node with the debug API" {
+  n1="$(random_str)"
+  n2="$(random_str)"
+  run_success "$OCKAM" node create "$n1" --debug-api
+  run_success "$OCKAM" node create "$n2"
+
+  run_success bash -c "$OCKAM worker list --at $n1 --output json | jq -e 'map(select(.address == \"echo\" and .kind == \"worker\")) | length == 1'"
+  run_success bash -c "$OCKAM worker list --at $n2 --output json | jq -e 'map(select(has(\"kind\"))) | length == 0'"
+
+  # "hello", hex-encoded
+  run_success "$OCKAM" worker send --at "$n1" --to echo --payload 68656c6c6f
+  assert_output "68656c6c6f"
+
+  # the debug API is disabled by default
+  run_failure "$OCKAM" worker send --at "$n2" --to echo --payload 68656c6c6f
+}
+
+@test "node - flush the attributes of identities" {
+  n="$(random_str)"
+  run_success "$OCKAM" node create "$n" --attributes-max-age 1h
+  run_success "$OCKAM" identity create i1
+  i1=$($OCKAM identity show i1)
+
+  run_success "$OCKAM" node flush-attributes "$n" --identifier "$i1"
+  assert_output --partial "$i1"
+
+  run_success "$OCKAM" node flush-attributes "$n"
+  assert_output --partial "all the identities"
+}
+
+@test "node - change the log level of a running node" {
+  n="$(random_str)"
+  run_success "$OCKAM" node create "$n" --log-level info --log-format json --log-max-size 10MB --log-max-files 2
+  run_success bash -c "$OCKAM node show $n --output json | jq -e '.log_level == \"info\"'"
+
+  run_success "$OCKAM" node set-log-level "$n" "warn,ockam_api=debug"
+  run_success bash -c "$OCKAM node show $n --output json | jq -e '.log_level == \"warn,ockam_api=debug\"'"
+
+  # the log level is kept when the node is restarted
+  run_success "$OCKAM" node stop "$n"
+  run_success "$OCKAM" node start "$n"
+  run_success bash -c "$OCKAM node show $n --output json | jq -e '.log_level == \"warn,ockam_api=debug\"'"
+
+  # invalid log levels are rejected
+  run_failure "$OCKAM" node set-log-level "$n" verbose
+  assert_output --partial "invalid log level 'verbose'"
+  run_failure "$OCKAM" node create "$(random_str)" --log-level "info,ockam_api=loud"
+  assert_output --partial "invalid log level"
+}
+
+@test "node - a node which is not run by a system service cannot be uninstalled" {
+  n="$(random_str)"
+  run_success "$OCKAM" node create "$n"
+  run_failure "$OCKAM" node uninstall-service "$n"
+  assert_output --partial "is not run by a system service"
+
+  # a node run by a system service is started by the service manager, not in foreground
+  run_failure "$OCKAM" node create "$(random_str)" --as-system-service --foreground
+  run_failure "$OCKAM" node create "$(random_str)" --as-system-service --restart-on-failure
+}
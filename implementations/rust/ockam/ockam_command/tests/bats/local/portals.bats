@@ -60,7 +60,13 @@ teardown() {
   assert_output --partial "\"alias\":\"test-inlet\""
   assert_output --partial "\"bind_addr\":\"127.0.0.1:$inlet_port\""
 
+  # The inlet aliases can be completed from the local state
+  run_success $OCKAM __complete inlets --node /node/n2
+  assert_line "test-inlet"
+
   run_success $OCKAM tcp-inlet delete "test-inlet" --at /node/n2 --yes
+  run_success $OCKAM __complete inlets --node n2
+  refute_line "test-inlet"
 
   # Test deletion of a previously deleted TCP inlet
   run_failure $OCKAM tcp-inlet delete "test-inlet" --at /node/n2 --yes
@@ -155,6 +161,152 @@ teardown() {
   run_success curl --fail --head --retry-connrefused --retry-delay 5 --retry 10 --max-time 5 "127.0.0.1:$port"
 }
 
+@test "portals - create an inlet/outlet pair reached with a dns address and move tcp traffic through it" {
+  port="$(random_port)"
+  node_port="$(random_port)"
+  run_success "$OCKAM" node create n1 --tcp-listener-address "127.0.0.1:$node_port"
+  run_success "$OCKAM" node create n2 --prefer-ipv6
+
+  run_success "$OCKAM" tcp-outlet create --at /node/n1 --to 127.0.0.1:$PYTHON_SERVER_PORT
+  run_success "$OCKAM" tcp-inlet create --at /node/n2 --from "127.0.0.1:$port" --to "/dns/localhost/tcp/$node_port/service/outlet"
+
+  run_success curl --fail --head --retry-connrefused --retry-delay 5 --retry 10 --max-time 5 "127.0.0.1:$port"
+}
+
+@test "portals - create an outlet to a host name and reach it through a static host" {
+  port="$(random_port)"
+  node_port="$(random_port)"
+  run_success "$OCKAM" node create n1 --tcp-listener-address "127.0.0.1:$node_port" --dns-cache-ttl 1s
+  run_success "$OCKAM" node create n2 --static-host "outlet.internal=127.0.0.1"
+
+  run_success "$OCKAM" tcp-outlet create --at /node/n1 --to localhost:$PYTHON_SERVER_PORT
+  run_success "$OCKAM" tcp-outlet show outlet --at /node/n1 --output json
+  assert_output --partial "\"hostname_port\":\"localhost:$PYTHON_SERVER_PORT\""
+
+  run_success "$OCKAM" tcp-inlet create --at /node/n2 --from "127.0.0.1:$port" --to "/dns/outlet.internal/tcp/$node_port/service/outlet"
+  run_success curl --fail --head --retry-connrefused --retry-delay 5 --retry 10 --max-time 5 "127.0.0.1:$port"
+}
+
+@test "portals - create an outlet to a unix domain socket" {
+  socket_path="$BATS_TEST_TMPDIR/server.sock"
+  run_success "$OCKAM" node create n1
+
+  # the socket path is validated when the outlet is created
+  run_failure "$OCKAM" tcp-outlet create --at /node/n1 --to "unix:$socket_path"
+  assert_output --partial "failed to access the Unix domain socket"
+  touch "$BATS_TEST_TMPDIR/not-a-socket"
+  run_failure "$OCKAM" tcp-outlet create --at /node/n1 --to "$BATS_TEST_TMPDIR/not-a-socket"
+  assert_output --partial "is not a Unix domain socket"
+
+  python3 -c "import socket; socket.socket(socket.AF_UNIX).bind('$socket_path')"
+  run_success "$OCKAM" tcp-outlet create --at /node/n1 --to "unix:$socket_path"
+  run_success "$OCKAM" tcp-outlet show outlet --at /node/n1
+  assert_output --partial "To Unix socket: $socket_path"
+  run_success "$OCKAM" tcp-outlet show outlet --at /node/n1 --output json
+  assert_output --partial "\"unix_socket_path\":\"$socket_path\""
+  run_success "$OCKAM" tcp-outlet list --at /node/n1
+  assert_output --partial "Unix socket"
+}
+
+@test "portals - create an inlet on a unix domain socket and move tcp traffic through it" {
+  socket_path="$BATS_TEST_TMPDIR/inlet.sock"
+  run_success "$OCKAM" node create n1
+  run_success "$OCKAM" node create n2
+  run_success "$OCKAM" tcp-outlet create --at /node/n1 --to 127.0.0.1:$PYTHON_SERVER_PORT
+
+  # an existing file is only replaced with --force
+  touch "$socket_path"
+  run_failure "$OCKAM" tcp-inlet create --at /node/n2 --from-unix "$socket_path" --to /node/n1/service/outlet
+  assert_output --partial "Use --force"
+  run_success "$OCKAM" tcp-inlet create --at /node/n2 --from-unix "$socket_path" --socket-mode 0600 --force --to /node/n1/service/outlet --alias unix-inlet
+  run_success curl --fail --head --retry 10 --max-time 5 --unix-socket "$socket_path" http://localhost/
+  run_success python3 -c "import os; print(oct(os.stat('$socket_path').st_mode & 0o777))"
+  assert_output "0o600"
+
+  run_success "$OCKAM" tcp-inlet show unix-inlet --at /node/n2
+  assert_output --partial "Unix Socket: $socket_path"
+  run_success "$OCKAM" tcp-inlet list --at /node/n2 --output json
+  assert_output --partial "\"bind_addr\":\"unix:$socket_path\""
+
+  # the socket file is removed with the inlet
+  run_success "$OCKAM" tcp-inlet delete unix-inlet --at /node/n2 --yes
+  sleep 1
+  [ ! -e "$socket_path" ]
+}
+
+@test "portals - create an inlet and an outlet using the proxy protocol" {
+  inlet_port="$(random_port)"
+  run_success "$OCKAM" node create n1
+  run_success "$OCKAM" node create n2
+  run_success "$OCKAM" tcp-outlet create --at /node/n1 --to 127.0.0.1:$PYTHON_SERVER_PORT --proxy-protocol
+  run_success "$OCKAM" tcp-outlet show outlet --at /node/n1
+  assert_output --partial "Sends a PROXY protocol v2 header to the TCP server"
+
+  run_success "$OCKAM" tcp-inlet create --at /node/n2 --from $inlet_port --to /node/n1/service/outlet --alias proxied --expect-proxy-protocol
+  run_success "$OCKAM" tcp-inlet show proxied --at /node/n2
+  assert_output --partial "PROXY Protocol: a v2 header is expected from the TCP clients"
+
+  # a client which doesn't send the header is disconnected by the inlet
+  run_failure curl --fail --head --max-time 10 "127.0.0.1:$inlet_port"
+}
+
+@test "portals - create an inlet/outlet pair over a websocket connection and move tcp traffic through it" {
+  port="$(random_port)"
+  ws_port="$(random_port)"
+  run_success "$OCKAM" node create n1 --ws "127.0.0.1:$ws_port"
+  run_success "$OCKAM" node create n2
+
+  run_success "$OCKAM" tcp-outlet create --at /node/n1 --to 127.0.0.1:$PYTHON_SERVER_PORT
+  run_success "$OCKAM" tcp-inlet create --at /node/n2 --from "127.0.0.1:$port" --to "/ws/localhost/tcp/$ws_port/secure/api/service/outlet"
+
+  run_success curl --fail --head --retry-connrefused --retry-delay 5 --retry 10 --max-time 5 "127.0.0.1:$port"
+}
+
+@test "portals - create an inlet/outlet pair over a udp connection and move tcp traffic through it" {
+  port="$(random_port)"
+  udp_port="$(random_port)"
+  run_success "$OCKAM" node create n1 --udp "127.0.0.1:$udp_port"
+  run_success "$OCKAM" node create n2
+
+  run_success "$OCKAM" node show n1 --output json
+  assert_output --partial "127.0.0.1:$udp_port"
+
+  run_success "$OCKAM" tcp-outlet create --at /node/n1 --to 127.0.0.1:$PYTHON_SERVER_PORT
+  run_success "$OCKAM" tcp-inlet create --at /node/n2 --from "127.0.0.1:$port" --to "/ip4/127.0.0.1/udp/$udp_port/secure/api/service/outlet"
+
+  run_success curl --fail --head --retry-connrefused --retry-delay 5 --retry 10 --max-time 5 "127.0.0.1:$port"
+}
+
+@test "portals - create an inlet through a connection established when the node starts" {
+  port="$(random_port)"
+  run_success "$OCKAM" node create n1
+  run_success "$OCKAM" tcp-outlet create --at /node/n1 --to 127.0.0.1:$PYTHON_SERVER_PORT
+  run_success "$OCKAM" node create n2 --connect-on-start /node/n1/secure/api
+
+  sleep 2 # wait for the connections to be established
+  run_success bash -c "$OCKAM node show n2 --output json | jq -e '.warm_connections[0].state == \"warmed\"'"
+
+  run_success "$OCKAM" tcp-inlet create --at /node/n2 --from "127.0.0.1:$port" --to /node/n1/secure/api/service/outlet
+  run_success curl --fail --head --retry-connrefused --retry-delay 5 --retry 10 --max-time 5 "127.0.0.1:$port"
+}
+
+@test "portals - fail to create a tcp inlet to a dns address which can't be resolved" {
+  port="$(random_port)"
+  run_success "$OCKAM" node create n1
+
+  run_failure "$OCKAM" tcp-inlet create --at /node/n1 --from "127.0.0.1:$port" --to "/dns/outlet.invalid/tcp/4000/service/outlet" --retry-wait 0s
+  assert_output --partial "outlet.invalid"
+}
+
+@test "portals - retry the creation of a tcp inlet a limited number of times" {
+  port="$(random_port)"
+  run_success "$OCKAM" node create n1
+
+  run_failure "$OCKAM" tcp-inlet create --at /node/n1 --from "127.0.0.1:$port" --to "/dns/outlet.invalid/tcp/4000/service/outlet" --retry-count 2 --retry-delay 100ms
+  assert_output --partial "attempt 2/2"
+  assert_output --partial "outlet.invalid"
+}
+
 @test "portals - create an inlet/outlet pair with relay through a relay and move tcp traffic through it" {
   port="$(random_port)"
   run_success "$OCKAM" node create relay
@@ -214,6 +366,25 @@ teardown() {
   run_failure "$OCKAM" tcp-inlet create --at "$n" --from "127.0.0.1:$port" --to "/node/$n/service/outlet"
 }
 
+@test "portals - a tcp inlet reachable from other machines requires a policy" {
+  n="$(random_str)"
+  run_success "$OCKAM" node create "$n"
+  run_success "$OCKAM" tcp-outlet create --at "$n" --to "127.0.0.1:$(random_port)"
+
+  # a loopback address doesn't require a policy
+  run_success "$OCKAM" tcp-inlet create --at "$n" --from "127.0.0.1:$(random_port)" --to "/node/$n/service/outlet"
+
+  # any other address requires a policy, or --allow-insecure-bind
+  run_failure "$OCKAM" tcp-inlet create --at "$n" --from "0.0.0.0:$(random_port)" --to "/node/$n/service/outlet"
+  assert_output --partial "no policy restricts its access"
+  assert_output --partial "--allow-insecure-bind"
+  run_success "$OCKAM" tcp-inlet create --at "$n" --from "0.0.0.0:$(random_port)" --to "/node/$n/service/outlet" --allow-insecure-bind
+
+  # a policy stored for all the inlets of the node is enough
+  run_success "$OCKAM" policy create --at "$n" --resource-type tcp-inlet --expression "(= subject.component \"db\")"
+  run_success "$OCKAM" tcp-inlet create --at "$n" --from "0.0.0.0:$(random_port)" --to "/node/$n/service/outlet"
+}
+
 @test "portals - local inlet and outlet, removing and re-creating the outlet" {
   port="$(random_port)"
   node_port="$(random_port)"
@@ -334,3 +505,238 @@ teardown() {
   # Consequent attempt fails
   run_failure curl --fail --max-time 30 -O "http://127.0.0.1:$inlet_port/$file_name"
 }
+
+@test "portals - foreground node drains open connections on SIGTERM" {
+  n="$(random_str)"
+  outlet_port="$(random_port)"
+  inlet_port="$(random_port)"
+
+  "$OCKAM" node create "$n" -f --shutdown-grace 10s >"$OCKAM_HOME/$n.log" 2>&1 &
+  node_pid=$!
+  sleep 1
+
+  # A TCP server which keeps the connection open
+  nc -l 127.0.0.1 "$outlet_port" >/dev/null &
+  run_success "$OCKAM" tcp-outlet create --at "/node/$n" --to "127.0.0.1:$outlet_port"
+  run_success "$OCKAM" tcp-inlet create --at "/node/$n" --from "127.0.0.1:$inlet_port" --to "/node/$n/service/outlet"
+
+  # A long-lived client connection, closed after 3 seconds
+  sleep 3 | nc 127.0.0.1 "$inlet_port" &
+  sleep 1
+
+  kill -TERM "$node_pid"
+  sleep 0.5
+  # New connections are refused while draining
+  run_failure curl --fail --head --max-time 1 "127.0.0.1:$inlet_port"
+
+  # The node waits for the open connection to be closed and exits successfully
+  wait "$node_pid"
+  assert_equal "$?" "0"
+  run_success cat "$OCKAM_HOME/$n.log"
+  assert_output --partial "connections draining"
+  assert_output --partial "Node stopped successfully"
+}
+
+@test "portals - foreground node stopped with ockam node stop does not drain its connections" {
+  n="$(random_str)"
+  outlet_port="$(random_port)"
+  inlet_port="$(random_port)"
+
+  "$OCKAM" node create "$n" -f --shutdown-grace 30s >"$OCKAM_HOME/$n.log" 2>&1 &
+  node_pid=$!
+  sleep 1
+
+  nc -l 127.0.0.1 "$outlet_port" >/dev/null &
+  run_success "$OCKAM" tcp-outlet create --at "/node/$n" --to "127.0.0.1:$outlet_port"
+  run_success "$OCKAM" tcp-inlet create --at "/node/$n" --from "127.0.0.1:$inlet_port" --to "/node/$n/service/outlet"
+
+  # A long-lived client connection, kept open longer than the node stop
+  sleep 20 | nc 127.0.0.1 "$inlet_port" &
+  sleep 1
+
+  run_success "$OCKAM" node stop "$n"
+  run_success timeout 5 tail --pid="$node_pid" -f /dev/null
+  run_success cat "$OCKAM_HOME/$n.log"
+  refute_output --partial "connections draining"
+}
+
+@test "portals - list and close the connections of a tcp inlet" {
+  n="$(random_str)"
+  outlet_port="$(random_port)"
+  inlet_port="$(random_port)"
+  run_success "$OCKAM" node create "$n"
+
+  # A TCP server which keeps the connection open
+  nc -l 127.0.0.1 "$outlet_port" >/dev/null &
+  run_success "$OCKAM" tcp-outlet create --at "/node/$n" --to "127.0.0.1:$outlet_port"
+  run_success "$OCKAM" tcp-inlet create --at "/node/$n" --from "127.0.0.1:$inlet_port" --to "/node/$n/service/outlet" --alias "i1"
+
+  run_success "$OCKAM" tcp-inlet connections i1 --at "$n"
+  assert_output --partial "No active connections"
+
+  # A long-lived client connection
+  sleep 30 | nc 127.0.0.1 "$inlet_port" &
+  sleep 1
+
+  run_success "$OCKAM" tcp-inlet connections i1 --at "$n" --output json
+  assert_output --partial "\"source_addr\": \"127.0.0.1:"
+  connection_id=$($OCKAM tcp-inlet connections i1 --at "$n" --output json | jq -r '.[0].id')
+
+  run_success "$OCKAM" tcp-inlet disconnect i1 --at "$n" --connection "$connection_id"
+  sleep 3
+  run_success "$OCKAM" tcp-inlet connections i1 --at "$n"
+  assert_output --partial "No active connections"
+
+  run_failure "$OCKAM" tcp-inlet disconnect i1 --at "$n" --connection "$connection_id"
+}
+
+@test "portals - tcp inlet distributes its connections across several outlets" {
+  port="$(random_port)"
+  run_success "$OCKAM" node create blue
+  run_success "$OCKAM" node create green
+  run_success "$OCKAM" tcp-outlet create --at /node/blue --to 127.0.0.1:$PYTHON_SERVER_PORT
+  run_success "$OCKAM" tcp-outlet create --at /node/green --to 127.0.0.1:$PYTHON_SERVER_PORT
+
+  run_success "$OCKAM" tcp-inlet create --from "127.0.0.1:$port" --alias lb \
+    --to "/node/blue/service/outlet,/node/green/service/outlet" --load-balance round-robin
+
+  run_success curl --fail --head --retry-connrefused --retry-delay 5 --retry 10 --max-time 5 "127.0.0.1:$port"
+  run_success curl --fail --head --max-time 5 "127.0.0.1:$port"
+
+  run_success "$OCKAM" tcp-inlet show lb --output json
+  assert_output --partial "\"load_balancing\":\"RoundRobin\""
+  assert_output --partial "\"total_connections\":1"
+  refute_output --partial "\"total_connections\":2"
+
+  run_failure "$OCKAM" tcp-inlet create --from "127.0.0.1:$(random_port)" \
+    --to "/node/blue/service/outlet,/node/green/service/outlet" --load-balance random
+}
+
+@test "portals - tcp inlet probes the liveness of its secure channel" {
+  port="$(random_port)"
+  run_success "$OCKAM" node create n1
+  run_success "$OCKAM" node create n2
+  run_success "$OCKAM" tcp-outlet create --at /node/n1 --to 127.0.0.1:$PYTHON_SERVER_PORT
+  run_success "$OCKAM" tcp-inlet create --at /node/n2 --from "127.0.0.1:$port" --alias probed \
+    --to /node/n1/secure/api/service/outlet --probe-interval 2s --max-missed-probes 2
+
+  run_success curl --fail --head --retry-connrefused --retry-delay 5 --retry 10 --max-time 5 "127.0.0.1:$port"
+
+  run_success "$OCKAM" tcp-inlet show probed --at n2 --output json
+  assert [ "$(echo "$output" | jq '.liveness.probe_interval_ms')" == "2000" ]
+  assert [ "$(echo "$output" | jq '.liveness.max_missed_probes')" == "2" ]
+
+  run_failure "$OCKAM" tcp-inlet create --at /node/n2 --from "127.0.0.1:$(random_port)" \
+    --to /node/n1/secure/api/service/outlet --max-missed-probes 0
+}
+
+@test "portals - tcp inlet re-establishes its secure channel when it is deleted" {
+  port="$(random_port)"
+  run_success "$OCKAM" node create n1
+  run_success "$OCKAM" node create n2
+  run_success "$OCKAM" tcp-outlet create --at /node/n1 --to 127.0.0.1:$PYTHON_SERVER_PORT
+  run_success "$OCKAM" tcp-inlet create --at /node/n2 --from "127.0.0.1:$port" --alias failover \
+    --to /node/n1/secure/api/service/outlet
+  run_success curl --fail --head --retry-connrefused --retry-delay 5 --retry 10 --max-time 5 "127.0.0.1:$port"
+
+  channel=$($OCKAM secure-channel list --at n2 --output json | jq -r '.[] | select(.used_by | index("tcp-inlet failover")) | .at')
+  run_success "$OCKAM" secure-channel delete --address "$channel" --at n2 --yes --output json
+  assert_output --partial "tcp-inlet failover"
+
+  # the channel is gone and the inlet uses a new one
+  run_failure "$OCKAM" secure-channel delete --address "$channel" --at n2 --yes
+  run_success curl --fail --head --retry-connrefused --retry-delay 2 --retry 10 --max-time 5 "127.0.0.1:$port"
+  run_success "$OCKAM" secure-channel list --at n2 --output json
+  assert_output --partial "tcp-inlet failover"
+  refute_output --partial "$channel\""
+}
+
+@test "portals - create an inlet/outlet pair using dedicated identities" {
+  port="$(random_port)"
+  run_success "$OCKAM" identity create reader
+  run_success "$OCKAM" identity create outlet-owner
+  run_success "$OCKAM" node create n1
+  run_success "$OCKAM" node create n2
+
+  # an unknown identity is rejected before contacting the node
+  run_failure "$OCKAM" tcp-inlet create --at /node/n2 --from "127.0.0.1:$port" --to /node/n1/service/outlet --identity unknown
+  assert_output --partial "The available identities are"
+  assert_output --partial "reader"
+
+  # the outlet needs a secure channel listener for its identity
+  run_failure "$OCKAM" tcp-outlet create --at /node/n1 --to 127.0.0.1:$PYTHON_SERVER_PORT --identity outlet-owner
+  run_success "$OCKAM" secure-channel-listener create owner-listener --at /node/n1 --identity outlet-owner
+  run_success "$OCKAM" tcp-outlet create --at /node/n1 --to 127.0.0.1:$PYTHON_SERVER_PORT --identity outlet-owner
+  run_success bash -c "$OCKAM tcp-outlet show outlet --at /node/n1 --output json | jq -e '.identity == \"outlet-owner\"'"
+
+  run_success "$OCKAM" tcp-inlet create --at /node/n2 --from "127.0.0.1:$port" --alias reader-inlet \
+    --to /node/n1/secure/owner-listener/service/outlet --identity reader
+  run_success curl --fail --head --retry-connrefused --retry-delay 5 --retry 10 --max-time 5 "127.0.0.1:$port"
+
+  run_success "$OCKAM" tcp-inlet show reader-inlet --at /node/n2 --output json
+  assert_output --partial "\"identity\":\"reader\""
+}
+
+@test "portals - create an inlet authorizing several identifiers and identity names" {
+  port="$(random_port)"
+  run_success "$OCKAM" identity create outlet-owner
+  run_success "$OCKAM" identity create other
+  other_identifier=$($OCKAM identity show other)
+  run_success "$OCKAM" node create n1
+  run_success "$OCKAM" node create n2
+  run_success "$OCKAM" secure-channel-listener create owner-listener --at /node/n1 --identity outlet-owner
+  run_success "$OCKAM" tcp-outlet create --at /node/n1 --to 127.0.0.1:$PYTHON_SERVER_PORT
+
+  # an unknown identity name is rejected
+  run_failure "$OCKAM" tcp-inlet create --at /node/n2 --from "127.0.0.1:$port" \
+    --to /node/n1/secure/owner-listener/service/outlet --authorized unknown
+  assert_output --partial "The available identities are"
+
+  # each authorized value is rejected with a project address
+  run_failure "$OCKAM" tcp-inlet create --at /node/n2 --from "127.0.0.1:$port" \
+    --to /project/default/service/forward_to_n1/secure/api/service/outlet --authorized "$other_identifier" --authorized outlet-owner
+  assert_output --partial "--authorized $other_identifier can not be used with the project address"
+  assert_output --partial "--authorized outlet-owner can not be used with the project address"
+
+  run_success "$OCKAM" tcp-inlet create --at /node/n2 --from "127.0.0.1:$port" \
+    --to /node/n1/secure/owner-listener/service/outlet --authorized "$other_identifier" --authorized outlet-owner
+  assert_output --partial "$other_identifier"
+  assert_output --partial "$($OCKAM identity show outlet-owner)"
+  run_success curl --fail --head --retry-connrefused --retry-delay 5 --retry 10 --max-time 5 "127.0.0.1:$port"
+}
+
+@test "portals - tcp inlet create with --quiet writes nothing on stderr" {
+  outlet_port="$(random_port)"
+  inlet_port="$(random_port)"
+  run_success "$OCKAM" node create n1
+  run_success $OCKAM tcp-outlet create --at /node/n1 --to "127.0.0.1:$outlet_port"
+
+  # Only keep stderr, which must not contain any progress message, spinner or color code
+  run_success bash -c "QUIET=0 $OCKAM tcp-inlet create --at /node/n1 --from 127.0.0.1:$inlet_port --to /node/n1/service/outlet --quiet 2>&1 >/dev/null"
+  assert_output ""
+}
+
+@test "portals - benchmark a portal to a bench service" {
+  run_success "$OCKAM" node create n1
+  run_success "$OCKAM" node create n2
+  run_success "$OCKAM" service start bench --at n2
+  run_success "$OCKAM" service start bench --at n2 --addr bench-discard --discard
+
+  run_success "$OCKAM" bench portal --at n1 --to /node/n2/service/bench --duration 2s --connections 2 --payload-size 4KiB --output json
+  assert [ "$(echo "$output" | jq '.connections | length')" == "2" ]
+  assert [ "$(echo "$output" | jq '.bytes > 0')" == "true" ]
+  assert [ "$(echo "$output" | jq '.latency_ms.p99 > 0')" == "true" ]
+  assert [ "$(echo "$output" | jq '.messages > 0')" == "true" ]
+  assert [ "$(echo "$output" | jq '.no_delay')" == "false" ]
+
+  run_success "$OCKAM" bench portal --at n1 --to /node/n2/service/bench-discard --discard --duration 2s --output json
+  assert [ "$(echo "$output" | jq '.mode')" == '"discard"' ]
+  assert [ "$(echo "$output" | jq 'has("latency_ms")')" == "false" ]
+
+  run_success "$OCKAM" bench portal --at n1 --to /node/n2/service/bench --duration 2s --payload-size 512B --no-delay --output json
+  assert [ "$(echo "$output" | jq '.no_delay')" == "true" ]
+
+  # The temporary inlets are deleted
+  run_success "$OCKAM" tcp-inlet list --at n1 --output json
+  refute_output --partial "bench-"
+}
@@ -42,4 +42,38 @@ EOF
 
   run_success "$OCKAM" project import --project-file $OCKAM_HOME/project.json
   assert_output --partial "Successfully imported project awesome"
+
+  # an imported project is exported without contacting the Orchestrator
+  run_success "$OCKAM" project export awesome --file $OCKAM_HOME/exported.json
+  run_success bash -c "jq -e '.id == \"66529571-169f-44c6-8a6f-5282c1eda44c\"' $OCKAM_HOME/exported.json"
+
+  # importing the project again updates it
+  run_success "$OCKAM" project import --file $OCKAM_HOME/exported.json
+  assert_output --partial "Successfully imported project awesome"
+
+  # an imported project can't be refreshed from the Orchestrator
+  run_failure "$OCKAM" project refresh awesome
+
+  # a project without a route can't be imported
+  echo '{"id": "1", "name": "incomplete", "space_id": "s", "space_name": "s", "access_route": "", "users": [], "user_roles": []}' >"$OCKAM_HOME/incomplete.json"
+  run_failure "$OCKAM" project import --file $OCKAM_HOME/incomplete.json
+}
+
+@test "projects - ticket templates can be created, listed and deleted" {
+  run_success "$OCKAM" project ticket-template create db-reader --attribute component=db --attribute access=read --usage-count 1 --expires-in 2d
+
+  # a template can't be created twice
+  run_failure "$OCKAM" project ticket-template create db-reader --attribute component=db
+
+  run_success "$OCKAM" project ticket-template list --output json
+  assert_output --partial "\"name\": \"db-reader\""
+  assert_output --partial "\"component\": \"db\""
+  assert_output --partial "\"expires_in\": 172800"
+
+  run_success "$OCKAM" project ticket-template delete db-reader --yes
+  run_success "$OCKAM" project ticket-template list
+  assert_output --partial "No ticket templates found"
+
+  # a missing template can't be deleted
+  run_failure "$OCKAM" project ticket-template delete db-reader --yes
 }
@@ -55,7 +55,8 @@ teardown() {
   run_success "$OCKAM" relay create blue --at /node/n1 --to /node/n2
   run_success "$OCKAM" relay show forward_to_blue --at /node/n2 --output json
   assert_output --regexp "\"relay_route\".* => 0#forward_to_blue"
-  assert_output --partial "\"remote_address\":\"/service/forward_to_blue\""
+  assert_output --partial "\"remote_address\":\"forward_to_blue\""
+  assert_output --partial "\"remote_address_ma\":\"/service/forward_to_blue\""
   assert_output --regexp "\"worker_address\":\"/service/.*"
 
   ## Try to show a non-existing relay
@@ -77,3 +78,20 @@ teardown() {
   ## Try to delete twice
   run_failure "$OCKAM" relay delete -y forward_to_blue --at /node/n2
 }
+
+@test "relay - show the heartbeats of a relay" {
+  run_success --separate-stderr "$OCKAM" node create n1
+  run_success --separate-stderr "$OCKAM" node create n2
+
+  run_success "$OCKAM" relay create blue --at /node/n1 --to /node/n2 --heartbeat-interval 100ms
+  sleep 1
+
+  run_success "$OCKAM" relay show forward_to_blue --at /node/n2 --output json
+  assert_output --regexp "\"last_heartbeat_at\":[0-9]+"
+  assert_output --regexp "\"round_trip_time_ms\":[0-9]+"
+  assert_output --partial "\"failed_heartbeats\":0"
+  assert_output --partial "\"connection_status\":\"Up\""
+
+  run_success "$OCKAM" relay list --to /node/n2 --output json
+  assert_output --regexp "\"last_heartbeat_at\": [0-9]+"
+}
@@ -47,3 +47,54 @@ env'
 bin
 env'
 }
+
+@test "reset - fail without a prompt when the user input is disabled" {
+  n="$(random_str)"
+  run_success "$OCKAM" node create "$n"
+
+  # The error names the flag avoiding the prompt and contains no color codes
+  run_failure "$OCKAM" reset --no-input
+  assert_output --partial "--yes"
+  refute_output --regexp $'\e\\['
+
+  # Nothing was deleted
+  run_success "$OCKAM" node show "$n" --output json
+  assert_output --partial "\"name\": \"$n\""
+}
+
+@test "reset - only delete the selected nodes and keep the identities" {
+  n="$(random_str)"
+  i="$(random_str)"
+  run_success "$OCKAM" identity create "$i"
+  run_success "$OCKAM" node create "$n" --identity "$i"
+
+  # The identity can't be deleted while a node uses it
+  run_failure "$OCKAM" reset --identities --yes
+
+  run_success "$OCKAM" reset --nodes --yes
+  assert_output --partial "Deleted nodes: $n"
+  refute_output --partial "Deleted identities"
+
+  run_success "$OCKAM" node list --output json
+  refute_output --partial "\"name\": \"$n\""
+  run_success "$OCKAM" identity show "$i"
+}
+
+@test "reset - restore a backup archive" {
+  n="$(random_str)"
+  run_success "$OCKAM" node create "$n"
+  run_success "$OCKAM" node stop "$n"
+
+  run_success "$OCKAM" reset --yes --backup "$BATS_TEST_TMPDIR/backup.tar.gz"
+  assert_output --partial "ockam restore"
+  run_failure "$OCKAM" node show "$n"
+
+  run_success "$OCKAM" restore "$BATS_TEST_TMPDIR/backup.tar.gz" --yes
+  run_success "$OCKAM" node show "$n" --output json
+  assert_output --partial "\"name\": \"$n\""
+
+  # An invalid archive is rejected and the state is left untouched
+  run_success touch "$BATS_TEST_TMPDIR/invalid.tar.gz"
+  run_failure "$OCKAM" restore "$BATS_TEST_TMPDIR/invalid.tar.gz" --yes
+  run_success "$OCKAM" node show "$n" --output json
+}
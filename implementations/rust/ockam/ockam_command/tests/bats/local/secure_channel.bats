@@ -46,3 +46,117 @@ teardown() {
   run_success "$OCKAM" message send "$msg" --timeout 5 --from /node/n1 --to "/node/n2/secure/api/service/uppercase"
   assert_output "$(to_uppercase "$msg")"
 }
+
+@test "secure channel - update the identities authorized by a listener" {
+  run_success "$OCKAM" identity create i1
+  idt1=$($OCKAM identity show i1)
+  run_success "$OCKAM" identity create i2
+  idt2=$($OCKAM identity show i2)
+
+  run_success "$OCKAM" node create n1 --identity i1
+  run_success "$OCKAM" node create n2
+  run_success "$OCKAM" secure-channel-listener create l --at n2
+  run_success "$OCKAM" secure-channel-listener show l --at n2
+  assert_output --partial "Authorized identities: any"
+
+  # Only i2 is authorized, the handshake initiated by i1 is rejected
+  run_success "$OCKAM" secure-channel-listener update l --at n2 --add "$idt2"
+  run_failure "$OCKAM" message send hello --timeout 5 --from /node/n1 --to /node/n2/secure/l/service/uppercase
+
+  # Once i1 is authorized, a secure channel can be created without recreating the listener
+  run_success "$OCKAM" secure-channel-listener update l --at n2 --add "$idt1" --remove "$idt2"
+  run_success "$OCKAM" secure-channel-listener show l --at n2
+  assert_output --partial "$idt1"
+  refute_output --partial "$idt2"
+
+  msg=$(random_str)
+  run_success bash -c "$OCKAM secure-channel create --from /node/n1 --to /node/n2/service/l \
+    | $OCKAM message send $msg --from /node/n1 --to -/service/uppercase"
+  assert_output "$(to_uppercase "$msg")"
+}
+
+@test "secure channel - show the metadata of a secure channel" {
+  run_success "$OCKAM" identity create i2
+  idt2=$($OCKAM identity show i2)
+  run_success "$OCKAM" node create n1
+  run_success "$OCKAM" node create n2 --identity i2
+
+  channel=$($OCKAM secure-channel create --from /node/n1 --to /node/n2/service/api | sed 's|/service/||')
+  run_success "$OCKAM" secure-channel show "$channel" --at n1 --output json
+  assert_output --partial "\"their_identifier\": \"$idt2\""
+  assert_output --partial "\"is_initiator\": true"
+  assert_output --partial "\"decryptor_address\""
+}
+
+@test "secure channel - renew the keys of a secure channel periodically" {
+  run_success "$OCKAM" node create n1
+  run_success "$OCKAM" node create n2
+
+  channel=$($OCKAM secure-channel create --from /node/n1 --to /node/n2/service/api --rekey-every 1s | sed 's|/service/||')
+  run_success "$OCKAM" message send hello --from /node/n1 --to "/service/$channel/service/uppercase"
+  assert_output "HELLO"
+
+  # the key is renewed with the first message sent after the interval, the channel is kept open
+  sleep 2
+  run_success "$OCKAM" message send hello --from /node/n1 --to "/service/$channel/service/uppercase"
+  assert_output "HELLO"
+
+  run_success "$OCKAM" secure-channel show "$channel" --at n1 --output json
+  assert_output --partial "\"rekeys\""
+  refute_output --partial "\"rekeys\": 0"
+}
+
+@test "secure channel - reuse a named secure channel" {
+  run_success "$OCKAM" node create n1
+  run_success "$OCKAM" node create n2
+
+  run_success "$OCKAM" secure-channel create --from /node/n1 --to /node/n2/service/api --name api
+  run_success "$OCKAM" message send hello --from /node/n1 --via-channel api --to /service/uppercase
+  assert_output "HELLO"
+  run_success "$OCKAM" message send hello --from /node/n1 --to /channel/api/service/uppercase
+  assert_output "HELLO"
+
+  # the name can only be used once
+  run_failure "$OCKAM" secure-channel create --from /node/n1 --to /node/n2/service/api --name api
+
+  run_success "$OCKAM" secure-channel delete --name api --at n1 --yes
+  run_failure "$OCKAM" message send hello --from /node/n1 --via-channel api --to /service/uppercase
+}
+
+@test "secure channel - a stale named secure channel is reported or re-established" {
+  run_success "$OCKAM" node create n1
+  run_success "$OCKAM" node create n2
+
+  run_success "$OCKAM" secure-channel create --from /node/n1 --to /node/n2/service/api --name api
+  run_success "$OCKAM" node stop n2
+  run_success "$OCKAM" node start n2
+  run_failure "$OCKAM" message send hello --from /node/n1 --via-channel api --to /service/uppercase
+  assert_output --partial "stale"
+
+  run_success "$OCKAM" secure-channel create --from /node/n1 --to /node/n2/service/api --name api-auto --auto-reconnect
+  run_success "$OCKAM" node stop n2
+  run_success "$OCKAM" node start n2
+  run_success "$OCKAM" message send hello --from /node/n1 --via-channel api-auto --to /service/uppercase
+  assert_output "HELLO"
+}
+
+@test "secure channel - list the secure channels of a node with their traffic" {
+  run_success "$OCKAM" identity create i2
+  idt2=$($OCKAM identity show i2)
+  run_success "$OCKAM" node create n1
+  run_success "$OCKAM" node create n2 --identity i2
+
+  run_success "$OCKAM" secure-channel create --from /node/n1 --to /node/n2/service/api --name api
+  run_success "$OCKAM" message send hello --from /node/n1 --via-channel api --to /service/uppercase
+  assert_output "HELLO"
+
+  run_success "$OCKAM" secure-channel list --at n1 --output json
+  assert_output --partial "\"their_identifier\": \"$idt2\""
+  assert_output --partial "\"direction\": \"initiated\""
+  assert_output --partial "\"secure-channel api\""
+  refute_output --partial "\"messages_sent\": 0"
+
+  # the channel is also listed on the accepting node
+  run_success "$OCKAM" secure-channel list --at n2 --output json
+  assert_output --partial "\"direction\": \"accepted\""
+}
@@ -0,0 +1,52 @@
+#!/bin/bash
+
+# ===== SETUP
+
+setup() {
+  load ../load/base.bash
+  load_bats_ext
+  setup_home_dir
+}
+
+teardown() {
+  teardown_home_dir
+}
+
+# ===== TESTS
+
+@test "state - export an encrypted state and import it in another home directory" {
+  i="$(random_str)"
+  n="$(random_str)"
+  run_success "$OCKAM" identity create "$i"
+  run_success "$OCKAM" node create "$n" --identity "$i"
+  run_success "$OCKAM" node stop "$n"
+  identifier=$($OCKAM identity show "$i")
+
+  export OCKAM_STATE_PASSPHRASE="$(random_str)"
+  run_success "$OCKAM" state export "$BATS_TEST_TMPDIR/state.ockam" --encrypt
+  refute_output --partial "$identifier"
+
+  # import the state in a new home directory with its own default identity
+  setup_home_dir
+  run_success "$OCKAM" identity create local
+  run_success "$OCKAM" state import "$BATS_TEST_TMPDIR/state.ockam"
+  assert_output --partial "identity $i"
+  assert_output --partial "node $n"
+
+  run_success "$OCKAM" identity show "$i"
+  assert_output "$identifier"
+  run_success "$OCKAM" identity default
+  assert_output --partial "local"
+
+  # existing entities are skipped unless they are overwritten
+  run_success "$OCKAM" state import "$BATS_TEST_TMPDIR/state.ockam"
+  assert_output --partial "Skipped identity $i"
+  run_success "$OCKAM" state import "$BATS_TEST_TMPDIR/state.ockam" --overwrite
+  assert_output --partial "Overwritten identity $i"
+}
+
+@test "state - fail to import an encrypted state with a wrong passphrase" {
+  run_success "$OCKAM" identity create "$(random_str)"
+  OCKAM_STATE_PASSPHRASE=right run_success "$OCKAM" state export "$BATS_TEST_TMPDIR/state.ockam" --encrypt
+  OCKAM_STATE_PASSPHRASE=wrong run_failure "$OCKAM" state import "$BATS_TEST_TMPDIR/state.ockam"
+}
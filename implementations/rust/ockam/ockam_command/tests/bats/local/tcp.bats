@@ -73,3 +73,14 @@ teardown() {
   run_success "$OCKAM" node create n1 --tcp-listener-address "$addr"
   run_success "$OCKAM" tcp-connection create --from n1 --to "$addr" --output json
 }
+
+@test "tcp listener - list as json" {
+  port="$(random_port)"
+  addr="127.0.0.1:$port"
+
+  run_success "$OCKAM" node create n1 --tcp-listener-address "$addr"
+
+  run_success bash -c "$OCKAM tcp-listener list --at n1 --output json | jq -e '.[0].socket == \"$addr\"'"
+  run_success bash -c "$OCKAM tcp-listener list --at n1 --output json | jq -e '.[0] | has(\"type\", \"mode\", \"worker\", \"flow_control\")'"
+  run_success bash -c "$OCKAM worker list --at n1 --output json | jq -e 'map(.address) | length > 0'"
+}
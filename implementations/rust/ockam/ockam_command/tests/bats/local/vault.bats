@@ -81,3 +81,62 @@ teardown() {
   run_success "$OCKAM" vault show --output json "${v}"
   assert_output --partial new-vault-path
 }
+
+@test "vault - show keys and move an identity to another vault" {
+  v1=$(random_str)
+  v2=$(random_str)
+  i=$(random_str)
+  run_success "$OCKAM" vault create "${v1}"
+  run_success "$OCKAM" vault create "${v2}"
+  run_success "$OCKAM" identity create "${i}" --vault "${v1}"
+
+  run_success "$OCKAM" vault show "${v1}" --keys --output json
+  assert_output --partial "\"identity_name\":\"${i}\""
+  assert_output --partial "\"usage\":\"identity\""
+
+  # A dry run does not move the identity
+  run_success "$OCKAM" vault move-identity "${i}" --to "${v2}" --dry-run
+  run_success "$OCKAM" vault show "${v1}" --keys --output json
+  assert_output --partial "\"identity_name\":\"${i}\""
+
+  run_success "$OCKAM" vault move-identity "${i}" --to "${v2}"
+  run_success "$OCKAM" vault show "${v2}" --keys --output json
+  assert_output --partial "\"identity_name\":\"${i}\""
+  run_success "$OCKAM" vault show "${v1}" --keys --output json
+  refute_output --partial "\"identity_name\":\"${i}\""
+
+  # The identity can still be used once moved
+  run_success "$OCKAM" node create n --identity "${i}"
+
+  # An identity can't be moved to the vault it is already using
+  run_failure "$OCKAM" vault move-identity "${i}" --to "${v2}"
+}
+
+@test "vault - encrypted vault" {
+  v1=$(random_str)
+  v2=$(random_str)
+  i=$(random_str)
+  export OCKAM_VAULT_PASSWORD="passphrase"
+  run_success "$OCKAM" vault create "${v1}" --encrypted
+  run_success "$OCKAM" identity create "${i}" --vault "${v1}"
+  run_success "$OCKAM" vault show "${v1}"
+  assert_output --partial "encrypted"
+
+  # The vault can't be used without its passphrase in a non-interactive session
+  unset OCKAM_VAULT_PASSWORD
+  run_failure "$OCKAM" identity create "$(random_str)" --vault "${v1}"
+  assert_output --partial "locked"
+
+  OCKAM_VAULT_PASSWORD="wrong" run_failure "$OCKAM" identity create "$(random_str)" --vault "${v1}"
+  assert_output --partial "incorrect"
+
+  # An existing vault can be encrypted
+  run_success "$OCKAM" vault create "${v2}"
+  run_success "$OCKAM" identity create "$(random_str)" --vault "${v2}"
+  OCKAM_VAULT_PASSWORD="passphrase2" run_success "$OCKAM" vault encrypt "${v2}"
+  run_failure "$OCKAM" identity create "$(random_str)" --vault "${v2}"
+  OCKAM_VAULT_PASSWORD="passphrase2" run_success "$OCKAM" identity create "$(random_str)" --vault "${v2}"
+
+  # A vault can't be encrypted twice
+  OCKAM_VAULT_PASSWORD="passphrase2" run_failure "$OCKAM" vault encrypt "${v2}"
+}
@@ -33,10 +33,10 @@ teardown() {
   run_success "$OCKAM" project-member list
 
   assert_output --partial "$m_identifier"
-  assert_output --partial "key=value"
+  assert_output --partial "\"key\": \"value\""
   assert_output --partial "ockam-relay=*"
 
-  run_success "$OCKAM" project-member delete "$m_identifier"
+  run_success "$OCKAM" project-member delete "$m_identifier" --yes
 }
 
 @test "project authority - test api authorization rules" {
@@ -63,7 +63,7 @@ teardown() {
   run_failure "$OCKAM" project-member list --identity m
   run_failure "$OCKAM" project-member list-ids --identity m
   run_failure "$OCKAM" project-member add "$t_identifier" --identity m
-  run_failure "$OCKAM" project-member delete "$m_identifier" --identity m
+  run_failure "$OCKAM" project-member delete "$m_identifier" --identity m --yes
 
-  run_success "$OCKAM" project-member delete "$m_identifier" --identity e
+  run_success "$OCKAM" project-member delete "$m_identifier" --identity e --yes
 }
@@ -285,3 +285,25 @@ teardown() {
 
   kill -QUIT $socat_pid
 }
+
+@test "portals - publish an outlet in the project service directory and create an inlet to it by name" {
+  port="$(random_port)"
+  service_name="$(random_str)"
+  relay_name="$(random_str)"
+
+  run_success "$OCKAM" node create blue
+  run_success "$OCKAM" relay create "$relay_name" --to /node/blue
+  run_success "$OCKAM" tcp-outlet create --at /node/blue --to 127.0.0.1:$PYTHON_SERVER_PORT \
+    --publish "$service_name" --via "$relay_name" --description "python server"
+
+  run_success "$OCKAM" service discover "$service_name" --output json
+  assert_output --partial "\"route\": \"/project/default/service/forward_to_$relay_name/secure/api/service/outlet\""
+  assert_output --partial "\"description\": \"python server\""
+
+  run_success "$OCKAM" node create green
+  run_success "$OCKAM" tcp-inlet create --at /node/green --from "127.0.0.1:$port" --to "discover:$service_name"
+  run_success curl --fail --head --retry-connrefused --retry-delay 5 --retry 10 --max-time 5 "127.0.0.1:$port"
+
+  run_failure "$OCKAM" tcp-inlet create --at /node/green --to "discover:$(random_str)"
+  assert_output --partial "No service named"
+}
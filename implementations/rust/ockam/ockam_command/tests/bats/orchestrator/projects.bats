@@ -33,6 +33,36 @@ teardown() {
   run_failure "$OCKAM" project enroll "INVALID_TICKET" --test-argument-parser
 }
 
+@test "projects - create an enrollment ticket with a template" {
+  run_success "$OCKAM" project ticket-template create db-reader --attribute component=db --attribute access=read --usage-count 1
+
+  # the template attributes are merged with the attributes passed as arguments
+  run_success "$OCKAM" project ticket --template db-reader --attribute access=write --attribute extra=1 --output json
+  assert_output --partial "\"template\":\"db-reader\""
+  assert_output --partial "\"component\":\"db\""
+  assert_output --partial "\"access\":\"write\""
+  assert_output --partial "\"extra\":\"1\""
+
+  run_failure "$OCKAM" project ticket --template missing
+}
+
+@test "projects - compare a project with its expected configuration" {
+  EXPECTED_PATH="$OCKAM_HOME/expected.json"
+  run_success bash -c "$OCKAM project show --output json >$EXPECTED_PATH"
+
+  run_success "$OCKAM" project show --diff $EXPECTED_PATH
+  assert_output --partial "matches"
+
+  # a drift exits with the code 3
+  jq '.name = "other"' $EXPECTED_PATH >"$OCKAM_HOME/drifted.json"
+  run "$OCKAM" project show --diff "$OCKAM_HOME/drifted.json" --output json
+  assert_equal "$status" 3
+  assert_output --partial "\"drifted\":true"
+
+  # the drifted field can be ignored
+  run_success "$OCKAM" project show --diff "$OCKAM_HOME/drifted.json" --ignore name --ignore version --ignore running --ignore operation_id --ignore project_change_history --ignore user_roles.id
+}
+
 @test "projects - enrollment with controller" {
   ENROLLED_OCKAM_HOME=$OCKAM_HOME
 
@@ -74,6 +104,25 @@ teardown() {
   assert_output "hello"
 }
 
+@test "projects - enroll a new machine with an enrollment ticket" {
+  ticket=$($OCKAM project ticket --usage-count 1 --attribute role=member)
+
+  # Start from an empty home directory, without any identity or project
+  setup_home_dir
+  run_success "$OCKAM" enroll --ticket "$ticket"
+  assert_output --partial "is now enrolled with the"
+  run_success "$OCKAM" identity show
+  run_success "$OCKAM" project show
+
+  # Enrolling again resumes from the existing state, without using the ticket again
+  run_success "$OCKAM" enroll --ticket "$ticket"
+
+  # The ticket can also be given with an environment variable
+  setup_home_dir
+  run_failure env OCKAM_ENROLLMENT_TICKET="INVALID_TICKET" "$OCKAM" enroll
+  assert_output --partial "OCKAM_ENROLLMENT_TICKET"
+}
+
 @test "projects - access requiring credential" {
   ENROLLED_OCKAM_HOME=$OCKAM_HOME
 
@@ -136,5 +185,5 @@ teardown() {
 
 @test "projects - list addons" {
   run_success "$OCKAM" project addon list --project default
-  assert_output --partial "Id: okta"
+  assert_output --partial "\"id\": \"okta\""
 }
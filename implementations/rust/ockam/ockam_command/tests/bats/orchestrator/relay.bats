@@ -62,3 +62,33 @@ teardown() {
   run_success "$OCKAM" relay create $relay_name_blue --to /node/admin_node
   run_success "$OCKAM" relay create $relay_name_green --to /node/admin_node
 }
+
+@test "relay - fail to create a relay already registered by another node unless forced" {
+  run_success "$OCKAM" identity create blue
+  run_success "$OCKAM" identity create green
+  blue_identifier=$($OCKAM identity show blue)
+  green_identifier=$($OCKAM identity show green)
+  run_success "$OCKAM" project-member add "$blue_identifier" --attribute role=member --relay="*"
+  run_success "$OCKAM" project-member add "$green_identifier" --attribute role=member --relay="*"
+  sleep 2
+
+  run_success "$OCKAM" node create blue --identity blue
+  run_success "$OCKAM" node create green --identity green
+
+  relay_name="$(random_str)"
+  run_success "$OCKAM" relay create $relay_name --to /node/blue
+
+  # The relay name is already registered by blue
+  run_failure "$OCKAM" relay create $relay_name --to /node/green
+  assert_output --partial "already registered in the project by $blue_identifier"
+  assert_output --partial "--force"
+
+  # Registering it again from the same node is fine
+  run_success "$OCKAM" relay delete -y $relay_name --at /node/blue
+  run_success "$OCKAM" relay create $relay_name --to /node/blue
+
+  # Green takes the relay over
+  run_success "$OCKAM" relay create $relay_name --to /node/green --force
+  run_success "$OCKAM" relay show $relay_name --at /node/green --output json
+  assert_output --partial "\"connection_status\":\"Up\""
+}
@@ -13,7 +13,7 @@ setup_suite() {
   get_project_data
 
   # Remove all project members except for the enrolled identity
-  OCKAM_HOME=$OCKAM_HOME_BASE $OCKAM project-member delete --all
+  OCKAM_HOME=$OCKAM_HOME_BASE $OCKAM project-member delete --all --yes
 
   # Remove all nodes from the root OCKAM_HOME directory
   OCKAM_HOME=$OCKAM_HOME_BASE $OCKAM node delete --all --force --yes
@@ -285,12 +285,35 @@ pub mod sync {
             &mut self.0
         }
     }
+
+    /// Wrap `spin::Once` to provide the same interface as `std::sync::OnceLock`.
+    pub struct OnceLock<T>(spin::Once<T>);
+    impl<T> OnceLock<T> {
+        /// Creates a new empty cell.
+        pub const fn new() -> Self {
+            OnceLock(spin::Once::new())
+        }
+        /// Gets the value of the cell, if it has been initialized.
+        pub fn get(&self) -> Option<&T> {
+            self.0.get()
+        }
+        /// Gets the value of the cell, initializing it with `f` if the cell is empty.
+        /// Concurrent callers wait until the value is initialized.
+        pub fn get_or_init<F: FnOnce() -> T>(&self, f: F) -> &T {
+            self.0.call_once(f)
+        }
+    }
+    impl<T> Default for OnceLock<T> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
 }
 /// Provides `std::sync` for `std` targets.
 #[cfg(feature = "std")]
 pub mod sync {
     pub use std::sync::Arc;
-    pub use std::sync::{Mutex, RwLock};
+    pub use std::sync::{Mutex, OnceLock, RwLock};
 }
 
 /// Provides `std::task` for `no_std` targets.
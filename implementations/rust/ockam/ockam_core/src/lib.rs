@@ -82,6 +82,8 @@ mod cbor;
 mod error;
 mod message;
 mod processor;
+#[cfg(feature = "std")]
+mod rate_limiter;
 mod routing;
 mod uint;
 mod worker;
@@ -91,6 +93,8 @@ pub use cbor::*;
 pub use error::*;
 pub use message::*;
 pub use processor::*;
+#[cfg(feature = "std")]
+pub use rate_limiter::*;
 pub use routing::*;
 pub use uint::*;
 pub use worker::*;
@@ -0,0 +1,174 @@
+use core::time::Duration;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Instant;
+
+/// Minimum time between two reports of the rejections counted by a [`RejectionCounter`]
+pub const REJECTIONS_REPORT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Token bucket limiting the number of events per second, for example the connections
+/// accepted by a listener.
+///
+/// The bucket holds up to `per_second` tokens and is continuously refilled at the same rate.
+/// Each accepted event takes a token, and the events arriving when the bucket is empty
+/// are rejected and counted. The limiter can be cloned in order to inspect its utilization
+/// while it is used.
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    per_second: u32,
+    bucket: Arc<Mutex<Bucket>>,
+    rejections: RejectionCounter,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// Create a limiter accepting up to `per_second` events per second. The limit is at least 1
+    pub fn new(per_second: u32) -> Self {
+        let per_second = per_second.max(1);
+        Self {
+            per_second,
+            bucket: Arc::new(Mutex::new(Bucket {
+                tokens: per_second as f64,
+                last_refill: Instant::now(),
+            })),
+            rejections: RejectionCounter::default(),
+        }
+    }
+
+    /// Maximum number of events per second
+    pub fn per_second(&self) -> u32 {
+        self.per_second
+    }
+
+    /// Take a token for a new event. Return false, and count a rejection, if the limit is reached
+    pub fn try_acquire(&self) -> bool {
+        let mut bucket = self.refilled_bucket();
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            drop(bucket);
+            self.rejections.record();
+            false
+        }
+    }
+
+    /// Number of tokens currently taken from the bucket, from 0 to [`RateLimiter::per_second`].
+    /// This is roughly the number of events accepted during the last second
+    pub fn utilization(&self) -> u32 {
+        let bucket = self.refilled_bucket();
+        self.per_second - bucket.tokens.floor() as u32
+    }
+
+    /// Events rejected because the limit was reached
+    pub fn rejections(&self) -> &RejectionCounter {
+        &self.rejections
+    }
+
+    fn refilled_bucket(&self) -> MutexGuard<'_, Bucket> {
+        let mut bucket = self.bucket.lock().unwrap();
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens =
+            (bucket.tokens + elapsed * self.per_second as f64).min(self.per_second as f64);
+        bucket.last_refill = now;
+        bucket
+    }
+}
+
+/// Counter of rejected events, for example connections refused because of a limit.
+///
+/// Rejections can happen in bursts, so instead of logging each of them, callers log
+/// the number returned by [`RejectionCounter::take_report`], which is available at most
+/// once every [`REJECTIONS_REPORT_INTERVAL`].
+#[derive(Clone, Debug, Default)]
+pub struct RejectionCounter {
+    state: Arc<Mutex<RejectionCounterState>>,
+}
+
+#[derive(Debug, Default)]
+struct RejectionCounterState {
+    total: u64,
+    unreported: u64,
+    last_report: Option<Instant>,
+}
+
+impl RejectionCounter {
+    /// Count a rejection
+    pub fn record(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.total += 1;
+        state.unreported += 1;
+    }
+
+    /// Total number of rejections
+    pub fn total(&self) -> u64 {
+        self.state.lock().unwrap().total
+    }
+
+    /// Return the number of rejections which have not been reported yet, unless
+    /// a report was already returned during the last [`REJECTIONS_REPORT_INTERVAL`]
+    pub fn take_report(&self) -> Option<u64> {
+        let mut state = self.state.lock().unwrap();
+        let can_report = state
+            .last_report
+            .map(|last_report| last_report.elapsed() >= REJECTIONS_REPORT_INTERVAL)
+            .unwrap_or(true);
+        if state.unreported == 0 || !can_report {
+            return None;
+        }
+        state.last_report = Some(Instant::now());
+        Some(core::mem::take(&mut state.unreported))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limiter() {
+        let limiter = RateLimiter::new(2);
+        assert_eq!(limiter.utilization(), 0);
+        for _ in 0..2 {
+            assert!(limiter.try_acquire());
+        }
+        assert!(!limiter.try_acquire());
+        assert!(!limiter.try_acquire());
+        assert_eq!(limiter.utilization(), 2);
+        assert_eq!(limiter.rejections().total(), 2);
+
+        // a token is available again after half a second
+        std::thread::sleep(Duration::from_millis(600));
+        assert!(limiter.try_acquire());
+        assert!(!limiter.try_acquire());
+
+        // the clones of the limiter share its state
+        assert!(!limiter.clone().try_acquire());
+        assert_eq!(limiter.rejections().total(), 4);
+    }
+
+    #[test]
+    fn test_rejections_are_reported_at_most_once_per_interval() {
+        let counter = RejectionCounter::default();
+        assert_eq!(counter.take_report(), None);
+
+        counter.record();
+        counter.record();
+        assert_eq!(counter.take_report(), Some(2));
+
+        counter.record();
+        assert_eq!(counter.take_report(), None);
+        assert_eq!(counter.total(), 3);
+
+        // the next report contains the rejections which were not reported
+        counter.state.lock().unwrap().last_report =
+            Some(Instant::now() - REJECTIONS_REPORT_INTERVAL);
+        counter.record();
+        assert_eq!(counter.take_report(), Some(2));
+    }
+}
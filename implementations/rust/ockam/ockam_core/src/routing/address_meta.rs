@@ -2,6 +2,11 @@ use crate::compat::string::String;
 use crate::compat::vec::Vec;
 use crate::Address;
 
+/// Key of the attribute marking an address which is only used internally by a node,
+/// for example the address of a secure channel decryptor. The value describes the owner
+/// of the address
+pub const INTERNAL_ADDRESS_ATTRIBUTE: &str = "INTERNAL_ADDRESS";
+
 /// Additional metadata for address
 #[derive(Debug, Clone, Eq, PartialEq, Default)]
 pub struct AddressMetadata {
@@ -0,0 +1,66 @@
+//! Cooperative cancellation of the tasks spawned with
+//! [`Executor::spawn_cancellable`](crate::executor::Executor::spawn_cancellable)
+
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::compat::vec::Vec;
+
+/// Token used to ask a task to stop. The task checks it with
+/// [`is_cancelled`](CancellationToken::is_cancelled), or awaits
+/// [`cancelled`](CancellationToken::cancelled), and decides when to return
+#[derive(Clone, Default)]
+pub struct CancellationToken(Arc<Mutex<TokenState>>);
+
+#[derive(Default)]
+struct TokenState {
+    cancelled: bool,
+    /// Wakers of the tasks awaiting the cancellation
+    wakers: Vec<Waker>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the token and wake the tasks awaiting it
+    pub fn cancel(&self) {
+        let wakers = {
+            let mut state = self.0.lock().unwrap();
+            state.cancelled = true;
+            core::mem::take(&mut state.wakers)
+        };
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.lock().unwrap().cancelled
+    }
+
+    /// Return a future completing once the token is cancelled
+    pub fn cancelled(&self) -> Cancelled {
+        Cancelled(self.clone())
+    }
+}
+
+/// Future returned by [`CancellationToken::cancelled`]
+pub struct Cancelled(CancellationToken);
+
+impl Future for Cancelled {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = (self.0).0.lock().unwrap();
+        if state.cancelled {
+            return Poll::Ready(());
+        }
+        if !state.wakers.iter().any(|w| w.will_wake(cx.waker())) {
+            state.wakers.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
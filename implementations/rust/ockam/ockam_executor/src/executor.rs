@@ -1,66 +1,134 @@
-use core::cell::UnsafeCell;
+use core::fmt;
 use core::future::Future;
-use core::mem::MaybeUninit;
 use core::pin::Pin;
-use core::sync::atomic::{self, AtomicBool, AtomicUsize, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use core::task::{Context, Poll, Waker};
 
-use crossbeam_queue::SegQueue;
 use ockam_core::compat::boxed::Box;
 use ockam_core::compat::collections::BTreeMap;
-use ockam_core::compat::sync::Arc;
+use ockam_core::compat::sync::{Arc, Mutex, OnceLock, RwLock};
 use ockam_core::compat::task::Wake;
+use pin_project_lite::pin_project;
+
+use crate::cancellation::CancellationToken;
+use crate::idle::Idle;
+#[cfg(feature = "instrumentation")]
+use crate::instrumentation::Instrumentation;
+use crate::queue::TaskQueue;
+use crate::runtime::{JoinHandle, SharedJoinHandle};
+use crate::time::{Clock, Duration, Sleep, Timeout};
+use crate::timer::Timers;
+
+pub use crate::queue::{OverflowPolicy, Priority, STARVATION_GUARD};
+
+static EXECUTOR: OnceLock<Executor<'static>> = OnceLock::new();
 
 /// Returns current executor.
-/// WARNING: TODO this is not thread-safe
 pub fn current() -> &'static Executor<'static> {
-    static INIT: AtomicBool = AtomicBool::new(false);
-    static mut EXECUTOR: UnsafeCell<MaybeUninit<Executor>> = UnsafeCell::new(MaybeUninit::uninit());
-
-    if INIT.load(Ordering::Relaxed) {
-        unsafe { &*(EXECUTOR.get() as *const Executor) }
-    } else {
-        unsafe {
-            let executorp = EXECUTOR.get() as *mut Executor;
-            executorp.write(Executor::new());
-            atomic::compiler_fence(Ordering::Release);
-            INIT.store(true, Ordering::Relaxed);
-            &*executorp
-        }
-    }
+    EXECUTOR.get_or_init(Executor::new)
+}
+
+/// Create the current executor with a bounded task queue, see [`Executor::with_queue_capacity`].
+/// Return false if the current executor was already created
+pub fn init_current(capacity: usize, policy: OverflowPolicy) -> bool {
+    let mut created = false;
+    EXECUTOR.get_or_init(|| {
+        created = true;
+        Executor::with_queue_capacity(capacity, policy)
+    });
+    created
 }
 
+/// A spawned task. It is set to `None` once it has completed so that
+/// it is not polled again by a thread which was waiting to poll it
+type TaskSlot = Arc<Mutex<Option<Box<Task>>>>;
+
 /// Executor
 pub struct Executor<'a> {
-    tasks: UnsafeCell<BTreeMap<TaskId, Box<Task>>>,
-    waker_cache: UnsafeCell<BTreeMap<TaskId, Waker>>,
-    // TODO tasks: Arc<Mutex<BTreeMap<TaskId, Box<Task>>>>,
-    // TODO waker_cache: Arc<Mutex<BTreeMap<TaskId, Waker>>>,
-    task_queue: Arc<SegQueue<TaskId>>,
+    tasks: RwLock<BTreeMap<TaskId, TaskSlot>>,
+    waker_cache: Mutex<BTreeMap<TaskId, Waker>>,
+    task_queue: Arc<TaskQueue<Scheduled>>,
+    idle: Arc<Idle>,
+    timers: Arc<Timers>,
+    /// Set by [`Executor::shutdown`], while the task map is locked
+    shut_down: AtomicBool,
+    #[cfg(feature = "instrumentation")]
+    instrumentation: OnceLock<Box<dyn Instrumentation>>,
     marker: core::marker::PhantomData<&'a ()>,
 }
 
 impl<'a> Executor<'a> {
     pub fn new() -> Self {
+        Self::with_queue(TaskQueue::unbounded())
+    }
+
+    /// Create an executor whose task queue holds at most `capacity` tasks of each [`Priority`].
+    /// A task is queued at most once at a time, so a queue only overflows if
+    /// more than `capacity` tasks are ready to be polled; `policy` is then applied
+    pub fn with_queue_capacity(capacity: usize, policy: OverflowPolicy) -> Self {
+        Self::with_queue(TaskQueue::bounded(capacity, policy))
+    }
+
+    fn with_queue(task_queue: TaskQueue<Scheduled>) -> Self {
+        let idle = Arc::new(Idle::new());
         Self {
-            tasks: UnsafeCell::new(BTreeMap::new()),
-            waker_cache: UnsafeCell::new(BTreeMap::new()),
-            // TODO tasks: Arc::new(Mutex::new(BTreeMap::new())),
-            // TODO waker_cache: Arc::new(Mutex::new(BTreeMap::new())),
-            task_queue: Arc::new(SegQueue::new()),
+            tasks: RwLock::new(BTreeMap::new()),
+            waker_cache: Mutex::new(BTreeMap::new()),
+            task_queue: Arc::new(task_queue),
+            timers: Arc::new(Timers::new(idle.clone())),
+            idle,
+            shut_down: AtomicBool::new(false),
+            #[cfg(feature = "instrumentation")]
+            instrumentation: OnceLock::new(),
             marker: core::marker::PhantomData,
         }
     }
 
-    pub fn block_on<T>(&self, future: impl Future<Output = T>) -> T {
+    /// Set the clock driving the timers of the executor. It must be set before
+    /// any timer is created, and defaults to [`StdClock`](crate::time::StdClock)
+    /// with the `"std"` feature.
+    /// Return false if the executor already has a clock
+    pub fn set_clock(&self, clock: impl Clock + 'static) -> bool {
+        self.timers.set_clock(Box::new(clock))
+    }
+
+    /// Register the callbacks called when tasks are spawned and polled.
+    /// Return false if the executor already has an instrumentation
+    #[cfg(feature = "instrumentation")]
+    pub fn set_instrumentation(&self, instrumentation: impl Instrumentation + 'static) -> bool {
+        let mut instrumentation: Option<Box<dyn Instrumentation>> = Some(Box::new(instrumentation));
+        self.instrumentation
+            .get_or_init(|| instrumentation.take().unwrap());
+        instrumentation.is_none()
+    }
+
+    /// Return a future completing once `duration` has elapsed
+    pub fn sleep(&self, duration: Duration) -> Sleep {
+        Sleep::new(self.timers.clone(), duration)
+    }
+
+    /// Return a future failing with [`Elapsed`](crate::time::error::Elapsed)
+    /// if `future` does not complete before `duration` has elapsed
+    pub fn timeout<F: Future>(&self, duration: Duration, future: F) -> Timeout<F> {
+        Timeout::new(future, self.sleep(duration))
+    }
+
+    /// Run `future` to completion, polling the spawned tasks while it is pending.
+    /// Return an error if the executor is shut down before `future` completes
+    pub fn block_on<T>(&self, future: impl Future<Output = T>) -> Result<T, ShutdownError> {
         let mut node = Node {
             id: TaskId::new(),
             _name: "Node",
-            future: UnsafeCell::new(future),
+            future: Box::pin(future),
         };
-        let node_waker = NodeWaker::new(node.id);
+        let node_waker = NodeWaker::new(node.id, self.idle.clone());
 
         let result = loop {
+            if self.is_shut_down() {
+                return Err(ShutdownError(()));
+            }
+            self.timers.wake_expired();
+
             // progress on main task
             let mut context = Context::from_waker(&node_waker);
             if let Poll::Ready(result) = node.poll(&mut context) {
@@ -70,17 +138,23 @@ impl<'a> Executor<'a> {
 
             let mut last_task = node.id.0;
             let mut task_budget = self.task_queue.len();
+            #[cfg(feature = "instrumentation")]
+            if let Some(instrumentation) = self.instrumentation.get() {
+                instrumentation.on_queue_depth(task_budget);
+            }
 
-            while let Some(task_id) = self.task_queue.pop() {
-                // yield to looping tasks
-                if (task_id.0) == last_task {
-                    self.task_queue.push(task_id);
+            // the tasks are taken by priority, see `TaskQueue::pop`
+            while let Some(scheduled) = self.task_queue.pop() {
+                // yield to looping tasks, which are then queued behind
+                // the other ready tasks of the same priority
+                if (scheduled.task_id.0) == last_task {
+                    scheduled.requeue(&self.task_queue);
                     break;
                 } else {
-                    last_task = task_id.0;
+                    last_task = scheduled.task_id.0;
                 }
 
-                self.poll_task(task_id);
+                self.poll_task(scheduled);
 
                 // don't loop through all tasks more than once without running main
                 if task_budget == 0 {
@@ -90,80 +164,228 @@ impl<'a> Executor<'a> {
             }
             self.sleep_if_idle();
         };
-        result
+        Ok(result)
     }
 
-    /// poll_task
-    fn poll_task(&self, task_id: TaskId) {
-        let tasks = unsafe {
-            let tasksp = self.tasks.get();
-            &mut (*tasksp)
+    /// Stop accepting new tasks and drop the futures of all the spawned tasks.
+    /// A task which is being polled by another thread is dropped once that poll
+    /// returns, so this function must not be called from a spawned task.
+    ///
+    /// The tasks awaiting the [`JoinHandle`] of a dropped task get a
+    /// [`JoinError`](crate::runtime::JoinError), and [`Executor::block_on`]
+    /// returns a [`ShutdownError`]
+    pub fn shutdown(&self) {
+        let slots = {
+            let mut tasks = self.tasks.write().unwrap();
+            self.shut_down.store(true, Ordering::SeqCst);
+            core::mem::take(&mut *tasks)
         };
-        let task = match tasks.get_mut(&task_id) {
-            Some(task) => {
-                //let task_count = NEXT_ID.load(Ordering::Relaxed);
-                //trace!("poll task: {}@{} / {}", task.name, task.id.0, task_count);
-                task
-            }
+        debug!("shutting down executor, dropping {} tasks", slots.len());
+        for slot in slots.into_values() {
+            // the task is dropped without holding any lock since its
+            // destructor can wake other tasks or spawn new ones
+            let task = slot.lock().unwrap().take();
+            drop(task);
+        }
+        self.waker_cache.lock().unwrap().clear();
+        while self.task_queue.pop().is_some() {}
+
+        // interrupt the threads waiting in `block_on`
+        self.idle.notify();
+    }
+
+    /// Return true once [`Executor::shutdown`] has been called
+    pub fn is_shut_down(&self) -> bool {
+        self.shut_down.load(Ordering::SeqCst)
+    }
+
+    /// poll_task
+    ///
+    /// The locks on the task maps are only held to look up the task and its waker,
+    /// so that the task can spawn other tasks, or be woken, while it is polled
+    fn poll_task(&self, scheduled: Scheduled) {
+        let task_id = scheduled.task_id;
+        let slot = match self.tasks.read().unwrap().get(&task_id) {
+            Some(slot) => slot.clone(),
+            // the task was dropped by `shutdown`
+            None if self.is_shut_down() => return,
             None => {
                 warn!("No task for id: {:?}", task_id);
                 return;
             }
         };
 
-        let waker_cache = unsafe {
-            let waker_cachep = self.waker_cache.get();
-            &mut (*waker_cachep)
+        let waker = match self.waker_cache.lock().unwrap().get(&task_id) {
+            Some(waker) => waker.clone(),
+            // the task completed after being queued
+            None => return,
         };
-        let waker = waker_cache
-            .entry(task_id)
-            .or_insert_with(|| TaskWaker::new(task_id, self.task_queue.clone()));
-
-        let mut context = Context::from_waker(waker);
-        match task.poll(&mut context) {
-            Poll::Ready(()) => {
-                // task completed, remove it and its cached waker
-                if let Some(task) = tasks.remove(&task_id) {
-                    drop(task);
+
+        // the task is queued again if it is woken while it is polled
+        scheduled.queued.store(false, Ordering::SeqCst);
+
+        // this lock is only contended if the task is being polled by another thread
+        let mut task = slot.lock().unwrap();
+        let completed = match task.as_mut() {
+            Some(node) => {
+                #[cfg(feature = "instrumentation")]
+                let start = self.instrumentation.get().map(|instrumentation| {
+                    instrumentation.on_poll_start(task_id.0, node._name);
+                    (instrumentation, self.timers.now())
+                });
+
+                let mut context = Context::from_waker(&waker);
+                let ready = node.poll(&mut context).is_ready();
+
+                #[cfg(feature = "instrumentation")]
+                if let Some((instrumentation, start)) = start {
+                    let duration = self.timers.elapsed_since(start);
+                    instrumentation.on_poll_end(task_id.0, node._name, duration);
                 }
-                waker_cache.remove(&task_id);
+                ready
             }
-            Poll::Pending => (),
+            // the task completed while waiting for the lock
+            None => {
+                self.waker_cache.lock().unwrap().remove(&task_id);
+                return;
+            }
+        };
+
+        if completed {
+            // task completed, remove it and its cached waker
+            drop(task.take());
+            drop(task);
+            self.tasks.write().unwrap().remove(&task_id);
+            self.waker_cache.lock().unwrap().remove(&task_id);
         }
     }
 
     /// spawn
-    pub fn spawn(&self, future: impl Future + 'static) {
-        let task = Task::allocate(future);
-        debug!("spawning task: {}", task.id.0);
-        self.task_queue.push(task.id);
-        let tasks = unsafe {
-            let tasksp = self.tasks.get();
-            &mut (*tasksp)
-        };
-        if tasks.insert(task.id, task).is_some() {
-            panic!("task with same id already exists");
+    pub fn spawn<F>(&self, future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.spawn_with_name("Task", future)
+    }
+
+    /// Spawn a task which is polled before the ready tasks of a lower priority
+    pub fn spawn_with_priority<F>(&self, priority: Priority, future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let (handle, task) = Self::join_task("Task", future);
+        if let Err(err) = self.insert_task(task, priority) {
+            warn!("dropping task: {}", err);
         }
+        handle
     }
 
-    pub fn spawn_with_name(&self, name: &'static str, future: impl Future + 'static) {
-        let task = Task::allocate_with_name(name, future);
-        debug!("spawning task: {}@{}", name, task.id.0);
-        self.task_queue.push(task.id);
-        let tasks = unsafe {
-            let tasksp = self.tasks.get();
-            &mut (*tasksp)
+    /// Spawn a task named `name`. If the task cannot be spawned, see [`SpawnError`],
+    /// it is dropped and awaiting its [`JoinHandle`] returns an error
+    pub fn spawn_with_name<F>(&self, name: &'static str, future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let (handle, task) = Self::join_task(name, future);
+        if let Err(err) = self.insert_task(task, Priority::Normal) {
+            warn!("dropping task {}: {}", name, err);
+        }
+        handle
+    }
+
+    /// Spawn a task, or return an error if the executor is shut down or
+    /// if it rejects new tasks because its task queue is full
+    pub fn try_spawn<F>(&self, future: F) -> Result<JoinHandle<F::Output>, SpawnError>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let (handle, task) = Self::join_task("Task", future);
+        self.insert_task(task, Priority::Normal)?;
+        Ok(handle)
+    }
+
+    fn join_task<F>(name: &'static str, future: F) -> (JoinHandle<F::Output>, Box<Task>)
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let handle = JoinHandle::new();
+        let joined = Joined {
+            future,
+            shared: handle.0.clone(),
+        };
+        (handle, Task::allocate_with_name(name, joined))
+    }
+
+    /// Spawn the future returned by `task`, which is given a [`CancellationToken`]
+    /// to check whether it should stop. The token is also returned to cancel the task
+    pub fn spawn_cancellable<F, Fut>(&self, task: F) -> (JoinHandle<Fut::Output>, CancellationToken)
+    where
+        F: FnOnce(CancellationToken) -> Fut,
+        Fut: Future + Send + 'static,
+        Fut::Output: Send + 'static,
+    {
+        let token = CancellationToken::new();
+        let handle = self.spawn_with_name("CancellableTask", task(token.clone()));
+        (handle, token)
+    }
+
+    /// Insert and queue the task. If the task cannot be spawned, it is dropped,
+    /// which makes its [`JoinHandle`] return an error
+    fn insert_task(&self, task: Box<Task>, priority: Priority) -> Result<(), SpawnError> {
+        debug!("spawning task: {}@{}", task._name, task.id.0);
+        #[cfg(feature = "instrumentation")]
+        let name = task._name;
+        let scheduled = Scheduled {
+            task_id: task.id,
+            priority,
+            queued: Arc::new(AtomicBool::new(true)),
         };
-        if tasks.insert(task.id, task).is_some() {
-            panic!("task with same id already exists");
+        {
+            let mut tasks = self.tasks.write().unwrap();
+            if self.is_shut_down() {
+                return Err(SpawnError::ShutDown);
+            }
+            if let Some(max_tasks) = self.task_queue.max_tasks() {
+                if tasks.len() >= max_tasks {
+                    return Err(SpawnError::QueueFull);
+                }
+            }
+            let previous = tasks.insert(scheduled.task_id, Arc::new(Mutex::new(Some(task))));
+            if previous.is_some() {
+                panic!("task with same id already exists");
+            }
+            let waker = TaskWaker::new(
+                scheduled.clone(),
+                self.task_queue.clone(),
+                self.idle.clone(),
+            );
+            self.waker_cache
+                .lock()
+                .unwrap()
+                .insert(scheduled.task_id, waker);
+        }
+        // the task is queued once it can be found, so that it is not
+        // dropped by another thread polling the queue concurrently
+        #[cfg(feature = "instrumentation")]
+        if let Some(instrumentation) = self.instrumentation.get() {
+            instrumentation.on_task_spawned(scheduled.task_id.0, name);
         }
+        scheduled.requeue(&self.task_queue);
+        self.idle.notify();
+        Ok(())
     }
 
+    /// Wait until a task, or the main task, is woken, or until the earliest timer
+    /// deadline, if there is no task to poll.
+    /// The queue is checked again once a wake up cannot be missed anymore
     fn sleep_if_idle(&self) {
-        // TODO disable interrupts
-        if self.task_queue.is_empty() {
-            // TODO sleep
-        }
+        let timeout = self.timers.prepare_wait();
+        self.idle.wait(|| self.task_queue.is_empty(), timeout);
     }
 }
 
@@ -175,7 +397,7 @@ impl<'a> Default for Executor<'a> {
 
 // - Task ---------------------------------------------------------------------
 
-type Task = Node<dyn Future<Output = ()> + 'static>;
+type Task = Node<dyn Future<Output = ()> + Send + 'static>;
 
 /// Node
 pub struct Node<F>
@@ -184,8 +406,7 @@ where
 {
     id: TaskId,
     _name: &'static str,
-    future: UnsafeCell<F>,
-    // TODO future: Pin<Box<F>>,
+    future: Pin<Box<F>>,
 }
 
 impl<F> Drop for Node<F>
@@ -202,37 +423,98 @@ where
     F: ?Sized + Future<Output = T>,
 {
     fn poll(&mut self, context: &mut Context) -> Poll<T> {
-        let future = unsafe {
-            let futurep = self.future.get();
-            &mut (*futurep)
-        };
-        unsafe { Pin::new_unchecked(future).poll(context) }
+        self.future.as_mut().poll(context)
     }
 }
 
 impl Task {
-    fn allocate(future: impl Future + 'static) -> Box<Task> {
+    fn allocate_with_name(
+        name: &'static str,
+        future: impl Future<Output = ()> + Send + 'static,
+    ) -> Box<Task> {
         Box::new(Node {
             id: TaskId::new(),
-            _name: "Task",
-            future: UnsafeCell::new(async {
-                // task terminating
-                future.await;
-            }),
-            // TODO future: Box::pin(future),
+            _name: name,
+            future: Box::pin(future),
         })
     }
+}
 
-    fn allocate_with_name(name: &'static str, future: impl Future + 'static) -> Box<Task> {
-        Box::new(Node {
-            id: TaskId::new(),
-            _name: name,
-            future: UnsafeCell::new(async {
-                // task terminating
-                future.await;
-            }),
-            // TODO future: Box::pin(future),
-        })
+pin_project! {
+    /// Future of a spawned task, which stores the output of the task
+    /// for its [`JoinHandle`], and completes early if the task is aborted
+    struct Joined<F: Future> {
+        #[pin]
+        future: F,
+        shared: Arc<Mutex<SharedJoinHandle<F::Output>>>,
+    }
+
+    impl<F: Future> PinnedDrop for Joined<F> {
+        // the task can be dropped before completing when the executor is shut down
+        fn drop(this: Pin<&mut Self>) {
+            let mut shared = this.shared.lock().unwrap();
+            if !shared.finished {
+                shared.complete(None);
+            }
+        }
+    }
+}
+
+impl<F: Future> Future for Joined<F> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.project();
+        {
+            let mut shared = this.shared.lock().unwrap();
+            if shared.aborted {
+                // the task future is dropped when the task is removed
+                shared.complete(None);
+                return Poll::Ready(());
+            }
+            shared.task_waker = Some(cx.waker().clone());
+        }
+
+        match this.future.poll(cx) {
+            Poll::Ready(value) => {
+                this.shared.lock().unwrap().complete(Some(value));
+                Poll::Ready(())
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+// - ShutdownError ------------------------------------------------------------
+
+/// Error returned by [`Executor::block_on`] when the executor is shut down
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShutdownError(());
+
+impl fmt::Display for ShutdownError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the executor was shut down")
+    }
+}
+
+// - SpawnError ---------------------------------------------------------------
+
+/// Error returned by [`Executor::try_spawn`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpawnError {
+    /// The executor was shut down
+    ShutDown,
+    /// The executor has as many tasks as the capacity of its task queue,
+    /// and its overflow policy is [`OverflowPolicy::Reject`]
+    QueueFull,
+}
+
+impl fmt::Display for SpawnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpawnError::ShutDown => write!(f, "the executor was shut down"),
+            SpawnError::QueueFull => write!(f, "the task queue is full"),
+        }
     }
 }
 
@@ -248,39 +530,79 @@ impl TaskId {
     }
 }
 
+/// Entry of the task queue. The `queued` flag is shared by all the entries
+/// of a task, and is set while the task is in the queue, so that waking
+/// a task which is already queued does not queue it again
+#[derive(Clone)]
+struct Scheduled {
+    task_id: TaskId,
+    priority: Priority,
+    queued: Arc<AtomicBool>,
+}
+
+impl Scheduled {
+    /// Push the entry, whose `queued` flag is already set, to the queue
+    fn requeue(self, task_queue: &TaskQueue<Scheduled>) {
+        if let Some(dropped) = task_queue.push(self.priority, self) {
+            // the dropped task can be queued again when it is woken
+            dropped.queued.store(false, Ordering::SeqCst);
+            warn!(
+                "the task queue is full, dropping a wake up of task: {}",
+                dropped.task_id.0
+            );
+        }
+    }
+}
+
 // - Waker --------------------------------------------------------------------
 
-struct NodeWaker;
+/// The main task is polled on each iteration of the executor loop,
+/// so its waker only needs to interrupt the executor when it is idle
+struct NodeWaker {
+    idle: Arc<Idle>,
+}
+
 impl NodeWaker {
     #[allow(clippy::new_ret_no_self)]
-    fn new(_task_id: TaskId) -> Waker {
-        Waker::from(Arc::new(NodeWaker {}))
+    fn new(_task_id: TaskId, idle: Arc<Idle>) -> Waker {
+        Waker::from(Arc::new(NodeWaker { idle }))
     }
 }
 
 impl Wake for NodeWaker {
     fn wake(self: Arc<Self>) {
-        // no-op
+        self.idle.notify();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.idle.notify();
     }
 }
 
 struct TaskWaker<'a> {
-    task_id: TaskId,
-    task_queue: Arc<SegQueue<TaskId>>,
+    scheduled: Scheduled,
+    task_queue: Arc<TaskQueue<Scheduled>>,
+    idle: Arc<Idle>,
     marker: core::marker::PhantomData<&'a ()>,
 }
 
 impl<'a> TaskWaker<'a> {
-    fn new(task_id: TaskId, task_queue: Arc<SegQueue<TaskId>>) -> Waker {
+    fn new(scheduled: Scheduled, task_queue: Arc<TaskQueue<Scheduled>>, idle: Arc<Idle>) -> Waker {
         Waker::from(Arc::new(TaskWaker {
-            task_id,
+            scheduled,
             task_queue,
+            idle,
             marker: core::marker::PhantomData,
         }))
     }
 
     fn reschedule_task(&self) {
-        self.task_queue.push(self.task_id);
+        // the task is already queued
+        if self.scheduled.queued.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        self.scheduled.clone().requeue(&self.task_queue);
+        self.idle.notify();
     }
 }
 
@@ -293,3 +615,548 @@ impl<'a> Wake for TaskWaker<'a> {
         self.reschedule_task();
     }
 }
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use core::sync::atomic::AtomicBool;
+    use std::thread;
+    use std::time::Duration;
+
+    const SPAWNING_THREADS: usize = 4;
+    const TASKS_PER_THREAD: usize = 50;
+
+    /// A future which is woken from another thread the first time it is polled
+    struct WokenFromThread {
+        woken: Arc<AtomicBool>,
+        delay: Duration,
+    }
+
+    impl WokenFromThread {
+        fn after(delay: Duration) -> Self {
+            Self {
+                woken: Arc::new(AtomicBool::new(false)),
+                delay,
+            }
+        }
+    }
+
+    impl Future for WokenFromThread {
+        type Output = ();
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            if self.woken.load(Ordering::Acquire) {
+                return Poll::Ready(());
+            }
+            let woken = self.woken.clone();
+            let waker = cx.waker().clone();
+            let delay = self.delay;
+            thread::spawn(move || {
+                thread::sleep(delay);
+                woken.store(true, Ordering::Release);
+                waker.wake();
+            });
+            Poll::Pending
+        }
+    }
+
+    #[test]
+    fn concurrent_spawn_and_wake() {
+        let executor = Executor::new();
+        let completed = Arc::new(AtomicUsize::new(0));
+        let expected = SPAWNING_THREADS * TASKS_PER_THREAD;
+
+        thread::scope(|s| {
+            for _ in 0..SPAWNING_THREADS {
+                s.spawn(|| {
+                    for _ in 0..TASKS_PER_THREAD {
+                        let completed = completed.clone();
+                        executor.spawn(async move {
+                            WokenFromThread::after(Duration::from_millis(1)).await;
+                            completed.fetch_add(1, Ordering::SeqCst);
+                        });
+                    }
+                });
+            }
+
+            // drive the tasks while they are being spawned and woken by other threads
+            executor
+                .block_on(futures::future::poll_fn(|cx| {
+                    if completed.load(Ordering::SeqCst) == expected {
+                        Poll::Ready(())
+                    } else {
+                        // check the number of completed tasks again after polling the tasks
+                        cx.waker().wake_by_ref();
+                        Poll::Pending
+                    }
+                }))
+                .unwrap();
+        });
+
+        assert_eq!(completed.load(Ordering::SeqCst), expected);
+        assert!(executor.tasks.read().unwrap().is_empty());
+        assert!(executor.waker_cache.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn task_woken_from_another_thread_resumes_without_spinning() {
+        let executor = Executor::new();
+        let (tx, mut rx) = futures::channel::oneshot::channel();
+        executor.spawn(async move {
+            WokenFromThread::after(Duration::from_millis(50)).await;
+            let _ = tx.send(());
+        });
+
+        // the main task is polled once before waiting, once when the task is
+        // woken from the other thread, and once when the task has completed
+        let mut polls = 0;
+        let started = std::time::Instant::now();
+        executor
+            .block_on(futures::future::poll_fn(|cx| {
+                polls += 1;
+                Pin::new(&mut rx).poll(cx).map(|_| ())
+            }))
+            .unwrap();
+
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert!(
+            polls <= 3,
+            "the executor polled the main task {polls} times"
+        );
+    }
+
+    #[test]
+    fn join_a_task_before_it_completes() {
+        let executor = Executor::new();
+        let handle = executor.spawn(async {
+            WokenFromThread::after(Duration::from_millis(10)).await;
+            42
+        });
+        assert!(!handle.is_finished());
+
+        // the handle can be awaited from another task
+        let joiner = executor.spawn(async move { handle.await.unwrap() * 2 });
+        assert_eq!(executor.block_on(joiner).unwrap(), Ok(84));
+    }
+
+    #[test]
+    fn join_a_task_after_it_completes() {
+        let executor = Executor::new();
+        let handle = executor.spawn(async { 7 });
+        executor
+            .block_on(futures::future::poll_fn(|cx| {
+                if handle.is_finished() {
+                    Poll::Ready(())
+                } else {
+                    cx.waker().wake_by_ref();
+                    Poll::Pending
+                }
+            }))
+            .unwrap();
+
+        assert_eq!(executor.block_on(handle).unwrap(), Ok(7));
+        assert!(executor.tasks.read().unwrap().is_empty());
+    }
+
+    #[test]
+    fn abort_a_pending_task() {
+        struct DropGuard(Arc<AtomicBool>);
+        impl Drop for DropGuard {
+            fn drop(&mut self) {
+                self.0.store(true, Ordering::SeqCst);
+            }
+        }
+
+        let executor = Executor::new();
+        let dropped = Arc::new(AtomicBool::new(false));
+        let guard = DropGuard(dropped.clone());
+        let mut handle = executor.spawn(async move {
+            let _guard = guard;
+            futures::future::pending::<()>().await
+        });
+
+        // let the task be polled once, it then stays pending
+        executor.block_on(crate::runtime::yield_now()).unwrap();
+        assert!(!handle.is_finished());
+        assert!(!dropped.load(Ordering::SeqCst));
+
+        handle.abort();
+        let result = executor.block_on(&mut handle).unwrap();
+        assert!(result.unwrap_err().is_cancelled());
+        assert!(handle.is_finished());
+        assert!(dropped.load(Ordering::SeqCst));
+        assert!(executor.tasks.read().unwrap().is_empty());
+    }
+
+    /// Clock whose ticks are set by the test
+    struct ManualClock(Arc<core::sync::atomic::AtomicU32>);
+
+    impl Clock for ManualClock {
+        fn now(&self) -> u32 {
+            self.0.load(Ordering::SeqCst)
+        }
+
+        fn frequency(&self) -> u32 {
+            1000
+        }
+    }
+
+    #[test]
+    fn sleep_waits_for_the_deadline_without_spinning() {
+        let executor = Executor::new();
+        let mut sleep = executor.sleep(Duration::from_millis(30));
+
+        let mut polls = 0;
+        let started = std::time::Instant::now();
+        executor
+            .block_on(futures::future::poll_fn(|cx| {
+                polls += 1;
+                Pin::new(&mut sleep).poll(cx)
+            }))
+            .unwrap();
+
+        assert!(started.elapsed() >= Duration::from_millis(30));
+        assert!(
+            polls <= 3,
+            "the executor polled the main task {polls} times"
+        );
+    }
+
+    #[test]
+    fn sleep_in_a_spawned_task() {
+        let executor = Executor::new();
+        let sleep = executor.sleep(Duration::from_millis(10));
+        let handle = executor.spawn(async move {
+            sleep.await;
+            1
+        });
+        assert_eq!(executor.block_on(handle).unwrap(), Ok(1));
+        assert!(executor.timers.prepare_wait().is_none());
+    }
+
+    #[test]
+    fn timeout_of_a_pending_future() {
+        let executor = Executor::new();
+        let started = std::time::Instant::now();
+        let result = executor
+            .block_on(executor.timeout(Duration::from_millis(20), futures::future::pending::<()>()))
+            .unwrap();
+        assert_eq!(result, Err(crate::time::error::Elapsed::new()));
+        assert!(started.elapsed() >= Duration::from_millis(20));
+
+        let result = executor
+            .block_on(executor.timeout(Duration::from_secs(10), async { 3 }))
+            .unwrap();
+        assert_eq!(result, Ok(3));
+        // the timer of the completed future is removed
+        assert!(executor.timers.prepare_wait().is_none());
+    }
+
+    #[test]
+    fn deadline_after_a_wrap_around_of_the_clock() {
+        let ticks = Arc::new(core::sync::atomic::AtomicU32::new(u32::MAX - 5));
+        let executor = Executor::new();
+        assert!(executor.set_clock(ManualClock(ticks.clone())));
+        assert!(!executor.set_clock(ManualClock(ticks.clone())));
+
+        let mut sleep = executor.sleep(Duration::from_millis(10));
+        let waker = futures::task::noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert!(Pin::new(&mut sleep).poll(&mut cx).is_pending());
+
+        // the counter wraps around, 8 ticks have elapsed
+        ticks.store(2, Ordering::SeqCst);
+        assert!(Pin::new(&mut sleep).poll(&mut cx).is_pending());
+        assert_eq!(
+            executor.timers.prepare_wait(),
+            Some(Duration::from_millis(2))
+        );
+
+        ticks.store(4, Ordering::SeqCst);
+        assert!(Pin::new(&mut sleep).poll(&mut cx).is_ready());
+    }
+
+    /// Increments a counter when dropped
+    struct DropGuard(Arc<AtomicUsize>);
+
+    impl Drop for DropGuard {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn shutdown_drops_the_tasks_once() {
+        use futures::FutureExt;
+
+        let executor = Executor::new();
+        let drops = Arc::new(AtomicUsize::new(0));
+        let mut handles = vec![];
+        for _ in 0..3 {
+            let guard = DropGuard(drops.clone());
+            handles.push(executor.spawn(async move {
+                let _guard = guard;
+                futures::future::pending::<()>().await
+            }));
+        }
+        // let the tasks be polled once
+        executor.block_on(crate::runtime::yield_now()).unwrap();
+
+        let result = executor.block_on(async {
+            executor.shutdown();
+            crate::runtime::yield_now().await;
+        });
+        assert_eq!(result, Err(ShutdownError(())));
+        assert_eq!(drops.load(Ordering::SeqCst), 3);
+        assert!(executor.tasks.read().unwrap().is_empty());
+        for handle in handles {
+            assert!(handle.now_or_never().unwrap().unwrap_err().is_cancelled());
+        }
+
+        // tasks spawned after the shutdown are dropped right away
+        let guard = DropGuard(drops.clone());
+        let handle = executor.spawn(async move {
+            let _guard = guard;
+        });
+        assert!(handle.is_finished());
+        assert_eq!(drops.load(Ordering::SeqCst), 4);
+
+        // a second shutdown has no effect
+        executor.shutdown();
+        assert_eq!(drops.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn shutdown_interrupts_block_on() {
+        let executor = Executor::new();
+        thread::scope(|s| {
+            s.spawn(|| {
+                thread::sleep(Duration::from_millis(20));
+                executor.shutdown();
+            });
+            let result = executor.block_on(futures::future::pending::<()>());
+            assert_eq!(result, Err(ShutdownError(())));
+        });
+    }
+
+    #[test]
+    fn cancel_a_cancellable_task() {
+        let executor = Executor::new();
+        let (handle, token) = executor.spawn_cancellable(|token| async move {
+            let mut iterations = 0;
+            while !token.is_cancelled() {
+                iterations += 1;
+                futures::future::select(token.cancelled(), Box::pin(crate::runtime::yield_now()))
+                    .await;
+            }
+            iterations
+        });
+
+        executor.block_on(crate::runtime::yield_now()).unwrap();
+        assert!(!handle.is_finished());
+
+        token.cancel();
+        assert!(executor.block_on(handle).unwrap().unwrap() >= 1);
+    }
+
+    /// Return a future counting its polls, which completes after `polls_before_ready` polls
+    fn counting_polls(
+        polls: Arc<AtomicUsize>,
+        polls_before_ready: usize,
+    ) -> impl Future<Output = ()> + Send {
+        futures::future::poll_fn(move |_| {
+            if polls.fetch_add(1, Ordering::SeqCst) + 1 == polls_before_ready {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+    }
+
+    fn task_waker(executor: &Executor, index: usize) -> Waker {
+        let wakers = executor.waker_cache.lock().unwrap();
+        wakers.values().nth(index).unwrap().clone()
+    }
+
+    #[test]
+    fn wake_a_task_a_million_times_queues_it_once() {
+        let executor = Executor::new();
+        let polls = Arc::new(AtomicUsize::new(0));
+        let handle = executor.spawn(counting_polls(polls.clone(), 2));
+        executor.block_on(crate::runtime::yield_now()).unwrap();
+        assert_eq!(polls.load(Ordering::SeqCst), 1);
+
+        let waker = task_waker(&executor, 0);
+        for _ in 0..1_000_000 {
+            waker.wake_by_ref();
+        }
+        assert_eq!(executor.task_queue.len(), 1);
+        assert_eq!(executor.waker_cache.lock().unwrap().len(), 1);
+
+        executor.block_on(handle).unwrap().unwrap();
+        assert_eq!(polls.load(Ordering::SeqCst), 2);
+        assert!(executor.task_queue.is_empty());
+    }
+
+    #[test]
+    fn self_waking_task_fits_in_a_queue_of_one_task() {
+        let executor = Executor::with_queue_capacity(1, OverflowPolicy::Panic);
+        let handle = executor.spawn(async {
+            for _ in 0..100_000 {
+                crate::runtime::yield_now().await;
+            }
+        });
+        executor.block_on(handle).unwrap().unwrap();
+        assert!(executor.tasks.read().unwrap().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "the task queue is full")]
+    fn full_queue_with_the_panic_policy() {
+        let executor = Executor::with_queue_capacity(1, OverflowPolicy::Panic);
+        executor.spawn(futures::future::pending::<()>());
+        executor.spawn(futures::future::pending::<()>());
+    }
+
+    #[test]
+    fn full_queue_with_the_reject_policy() {
+        let executor = Executor::with_queue_capacity(2, OverflowPolicy::Reject);
+        executor
+            .try_spawn(futures::future::pending::<()>())
+            .unwrap();
+        executor
+            .try_spawn(futures::future::pending::<()>())
+            .unwrap();
+        assert!(matches!(
+            executor.try_spawn(async {}),
+            Err(SpawnError::QueueFull)
+        ));
+
+        let handle = executor.spawn(async {});
+        assert!(handle.is_finished());
+        assert_eq!(executor.tasks.read().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn full_queue_with_the_drop_oldest_policy() {
+        let executor = Executor::with_queue_capacity(1, OverflowPolicy::DropOldest);
+        let first_polls = Arc::new(AtomicUsize::new(0));
+        let second_polls = Arc::new(AtomicUsize::new(0));
+        executor.spawn(counting_polls(first_polls.clone(), 0));
+        executor.spawn(counting_polls(second_polls.clone(), 0));
+        assert_eq!(executor.task_queue.len(), 1);
+
+        // the wake up of the first task was dropped
+        executor.block_on(crate::runtime::yield_now()).unwrap();
+        assert_eq!(first_polls.load(Ordering::SeqCst), 0);
+        assert_eq!(second_polls.load(Ordering::SeqCst), 1);
+
+        // the first task can be queued again
+        task_waker(&executor, 0).wake();
+        executor.block_on(crate::runtime::yield_now()).unwrap();
+        assert_eq!(first_polls.load(Ordering::SeqCst), 1);
+    }
+
+    #[cfg(feature = "instrumentation")]
+    #[test]
+    fn instrumentation_reports_named_tasks_and_poll_durations() {
+        use crate::instrumentation::Instrumentation;
+        use std::sync::Mutex as StdMutex;
+
+        #[derive(Default)]
+        struct Recorder {
+            spawned: StdMutex<Vec<&'static str>>,
+            polls: StdMutex<Vec<(&'static str, Duration)>>,
+            max_depth: AtomicUsize,
+        }
+
+        impl Instrumentation for Arc<Recorder> {
+            fn on_task_spawned(&self, _id: usize, name: &'static str) {
+                self.spawned.lock().unwrap().push(name);
+            }
+
+            fn on_poll_end(&self, _id: usize, name: &'static str, duration: Duration) {
+                self.polls.lock().unwrap().push((name, duration));
+            }
+
+            fn on_queue_depth(&self, depth: usize) {
+                self.max_depth.fetch_max(depth, Ordering::SeqCst);
+            }
+        }
+
+        let executor = Executor::new();
+        let recorder = Arc::new(Recorder::default());
+        assert!(executor.set_instrumentation(recorder.clone()));
+
+        let blocking = executor.spawn_with_name("blocking", async {
+            thread::sleep(Duration::from_millis(20));
+        });
+        let quick = executor.spawn_with_name("quick", async {});
+        executor.block_on(blocking).unwrap().unwrap();
+        executor.block_on(quick).unwrap().unwrap();
+
+        assert_eq!(*recorder.spawned.lock().unwrap(), vec!["blocking", "quick"]);
+        let polls = recorder.polls.lock().unwrap();
+        assert_eq!(polls.len(), 2);
+        assert_eq!(polls[0].0, "blocking");
+        assert!(polls[0].1 >= Duration::from_millis(20));
+        assert_eq!(recorder.max_depth.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn high_priority_task_preempts_low_priority_tasks() {
+        let executor = Executor::new();
+        let polled = Arc::new(Mutex::new(vec![]));
+        let mut handles = vec![];
+        for i in 0..20 {
+            let polled = polled.clone();
+            handles.push(executor.spawn_with_priority(Priority::Low, async move {
+                polled.lock().unwrap().push(format!("low {i}"));
+            }));
+        }
+        let polled_high = polled.clone();
+        let high = executor.spawn_with_priority(Priority::High, async move {
+            polled_high.lock().unwrap().push("high".to_string());
+        });
+
+        executor.block_on(high).unwrap().unwrap();
+        assert_eq!(polled.lock().unwrap()[0], "high");
+        for handle in handles {
+            executor.block_on(handle).unwrap().unwrap();
+        }
+        assert_eq!(polled.lock().unwrap().len(), 21);
+    }
+
+    #[test]
+    fn starvation_guard_lets_low_priority_tasks_progress() {
+        let executor = Executor::new();
+        let high_polls = Arc::new(AtomicUsize::new(0));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        // a high priority task which is always ready
+        let (polls, stopped) = (high_polls.clone(), stop.clone());
+        let high = executor.spawn_with_priority(Priority::High, async move {
+            while !stopped.load(Ordering::SeqCst) {
+                polls.fetch_add(1, Ordering::SeqCst);
+                crate::runtime::yield_now().await;
+            }
+        });
+
+        let (polls, stopped) = (high_polls.clone(), stop.clone());
+        let low = executor.spawn_with_priority(Priority::Low, async move {
+            stopped.store(true, Ordering::SeqCst);
+            polls.load(Ordering::SeqCst)
+        });
+
+        let high_polls_before_low = executor.block_on(low).unwrap().unwrap();
+        assert!(high_polls_before_low >= 1);
+        assert!(high_polls_before_low <= STARVATION_GUARD + 1);
+        executor.block_on(high).unwrap().unwrap();
+    }
+
+    #[test]
+    fn current_executor_is_shared_across_threads() {
+        let other = thread::spawn(current).join().unwrap();
+        assert!(core::ptr::eq(current(), other));
+    }
+}
@@ -0,0 +1,84 @@
+//! Idle strategy of the executor: the executor thread waits while no task
+//! is ready to be polled, and the wakers notify it when a task is scheduled.
+//!
+//!  - with the `"std"` feature, the thread is parked on a condition variable
+//!  - with the `"cortex-m"` feature, the core waits for an event (`WFE`),
+//!    which is signalled by the wakers (`SEV`)
+//!  - otherwise the executor keeps polling, as there is no wait primitive
+//!
+//! When a timer is pending, the executor waits at most until its deadline. Without
+//! the `"std"` feature, the [`Clock`](crate::time::Clock) signals the deadline.
+
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::sync::{Condvar, Mutex};
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+/// Wait primitive shared by the executor and its wakers
+#[derive(Default)]
+pub(crate) struct Idle {
+    /// Set when a waker is called, and reset when the executor stops waiting,
+    /// so that a notification sent before the executor waits is not lost
+    #[cfg(feature = "std")]
+    notified: Mutex<bool>,
+    #[cfg(feature = "std")]
+    condvar: Condvar,
+}
+
+impl Idle {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Signal the executor that a task can make progress
+    pub(crate) fn notify(&self) {
+        #[cfg(feature = "std")]
+        {
+            *self.notified.lock().unwrap() = true;
+            self.condvar.notify_all();
+        }
+
+        #[cfg(all(not(feature = "std"), feature = "cortex-m"))]
+        cortex_m::asm::sev();
+    }
+
+    /// Wait until `notify` is called, unless it was already called since the last wait,
+    /// or until `timeout` has elapsed.
+    /// `is_idle` is checked again before waiting, once a notification cannot be missed anymore
+    #[allow(unused_variables)]
+    pub(crate) fn wait(&self, is_idle: impl Fn() -> bool, timeout: Option<Duration>) {
+        #[cfg(feature = "std")]
+        {
+            let deadline = timeout.map(|timeout| Instant::now() + timeout);
+            let mut notified = self.notified.lock().unwrap();
+            while !*notified && is_idle() {
+                notified = match deadline {
+                    None => self.condvar.wait(notified).unwrap(),
+                    Some(deadline) => {
+                        let now = Instant::now();
+                        if now >= deadline {
+                            break;
+                        }
+                        self.condvar
+                            .wait_timeout(notified, deadline - now)
+                            .unwrap()
+                            .0
+                    }
+                };
+            }
+            *notified = false;
+        }
+
+        #[cfg(all(not(feature = "std"), feature = "cortex-m"))]
+        if is_idle() {
+            // returns immediately if `SEV` was executed since the last `WFE`
+            cortex_m::asm::wfe();
+        }
+
+        #[cfg(all(not(feature = "std"), not(feature = "cortex-m")))]
+        if is_idle() {
+            core::hint::spin_loop();
+        }
+    }
+}
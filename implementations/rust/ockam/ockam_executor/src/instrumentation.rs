@@ -0,0 +1,50 @@
+//! Hooks called by the executor when tasks are spawned and polled, to find the
+//! tasks which block the executor loop. Enabled with the `"instrumentation"` feature.
+//!
+//! Poll durations are measured with the [`Clock`](crate::time::Clock) of the executor.
+
+use core::time::Duration;
+
+/// Callbacks registered with [`Executor::set_instrumentation`](crate::executor::Executor::set_instrumentation).
+/// Tasks are identified by their id, and by the name given to
+/// [`Executor::spawn_with_name`](crate::executor::Executor::spawn_with_name)
+pub trait Instrumentation: Send + Sync {
+    /// A task was spawned
+    fn on_task_spawned(&self, _id: usize, _name: &'static str) {}
+
+    /// A task is about to be polled
+    fn on_poll_start(&self, _id: usize, _name: &'static str) {}
+
+    /// A task was polled for `duration`
+    fn on_poll_end(&self, _id: usize, _name: &'static str, _duration: Duration) {}
+
+    /// Number of tasks ready to be polled, reported on each iteration of the executor loop
+    fn on_queue_depth(&self, _depth: usize) {}
+}
+
+/// Instrumentation logging the spawned tasks, and warning about
+/// the polls which take longer than a budget
+pub struct LogInstrumentation {
+    poll_budget: Duration,
+}
+
+impl LogInstrumentation {
+    pub fn new(poll_budget: Duration) -> Self {
+        Self { poll_budget }
+    }
+}
+
+impl Instrumentation for LogInstrumentation {
+    fn on_task_spawned(&self, id: usize, name: &'static str) {
+        debug!("spawned task: {}@{}", name, id);
+    }
+
+    fn on_poll_end(&self, id: usize, name: &'static str, duration: Duration) {
+        if duration > self.poll_budget {
+            warn!(
+                "task {}@{} blocked the executor for {:?} (budget: {:?})",
+                name, id, duration, self.poll_budget
+            );
+        }
+    }
+}
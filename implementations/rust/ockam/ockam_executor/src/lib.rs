@@ -25,10 +25,16 @@ extern crate alloc;
 #[macro_use]
 extern crate tracing;
 
+pub mod cancellation;
 pub mod channel;
 pub mod executor;
+mod idle;
+#[cfg(feature = "instrumentation")]
+pub mod instrumentation;
+mod queue;
 pub mod runtime;
 pub mod time;
+mod timer;
 
 pub mod tokio {
     pub use crate::runtime;
@@ -43,7 +49,7 @@ pub mod tokio {
     pub mod task {
         pub use crate::runtime;
         pub use runtime::yield_now;
-        pub use runtime::JoinHandle;
+        pub use runtime::{JoinError, JoinHandle};
     }
     pub use crate::time;
 }
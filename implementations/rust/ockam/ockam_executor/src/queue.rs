@@ -0,0 +1,182 @@
+//! Queues of the tasks which are ready to be polled, one per [`Priority`].
+//!
+//! A task is queued at most once at a time, see `TaskWaker`, so the queues never
+//! hold more entries than there are tasks. On memory-constrained targets, the
+//! queues can also be given a fixed capacity, together with an [`OverflowPolicy`].
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+use crossbeam_queue::{ArrayQueue, SegQueue};
+
+/// Number of times in a row a higher priority task can be taken from the queues
+/// while lower priority tasks are ready, after which a lower priority task is
+/// polled, so that it is not starved
+pub const STARVATION_GUARD: usize = 8;
+
+/// Priority of a spawned task. Ready tasks are polled in priority order,
+/// but a lower priority task is polled after [`STARVATION_GUARD`]
+/// higher priority polls
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    High,
+    Normal,
+    Low,
+}
+
+impl Default for Priority {
+    fn default() -> Self {
+        Priority::Normal
+    }
+}
+
+impl Priority {
+    fn index(self) -> usize {
+        match self {
+            Priority::High => 0,
+            Priority::Normal => 1,
+            Priority::Low => 2,
+        }
+    }
+}
+
+/// What to do when a task is scheduled while the bounded task queue is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Panic
+    Panic,
+    /// Drop the oldest wake up to make room for the new one. The task whose wake up
+    /// is dropped is only polled again once it is woken again
+    DropOldest,
+    /// Return an error from [`Executor::try_spawn`](crate::executor::Executor::try_spawn)
+    /// when there are as many tasks as the capacity of the queues, so that a queue
+    /// can never be full
+    Reject,
+}
+
+pub(crate) struct TaskQueue<T> {
+    /// Queues indexed by priority
+    queues: [Queue<T>; 3],
+    /// Number of consecutive pops from a queue while a lower priority queue was not empty
+    bypassed: AtomicUsize,
+}
+
+impl<T> TaskQueue<T> {
+    pub(crate) fn unbounded() -> Self {
+        Self::new(Queue::unbounded)
+    }
+
+    /// Each priority has its own queue holding at most `capacity` tasks
+    pub(crate) fn bounded(capacity: usize, policy: OverflowPolicy) -> Self {
+        Self::new(|| Queue::bounded(capacity, policy))
+    }
+
+    fn new(queue: impl Fn() -> Queue<T>) -> Self {
+        Self {
+            queues: [queue(), queue(), queue()],
+            bypassed: AtomicUsize::new(0),
+        }
+    }
+
+    /// Maximum number of tasks accepted by the executor, if it rejects new tasks
+    pub(crate) fn max_tasks(&self) -> Option<usize> {
+        self.queues[0].max_tasks()
+    }
+
+    /// Push an entry, and return the entry which was dropped if the queue was full
+    pub(crate) fn push(&self, priority: Priority, entry: T) -> Option<T> {
+        self.queues[priority.index()].push(entry)
+    }
+
+    /// Pop an entry from the highest priority queue which is not empty, unless
+    /// lower priority entries were bypassed [`STARVATION_GUARD`] times in a row
+    pub(crate) fn pop(&self) -> Option<T> {
+        let highest = self.queues.iter().position(|queue| !queue.is_empty())?;
+        let lower = (highest + 1..self.queues.len()).find(|&i| !self.queues[i].is_empty());
+        let index = match lower {
+            Some(lower) if self.bypassed.fetch_add(1, Ordering::Relaxed) >= STARVATION_GUARD => {
+                self.bypassed.store(0, Ordering::Relaxed);
+                lower
+            }
+            Some(_) => highest,
+            None => {
+                self.bypassed.store(0, Ordering::Relaxed);
+                highest
+            }
+        };
+        // the queue may have been emptied by another thread in the meantime
+        self.queues[index]
+            .pop()
+            .or_else(|| self.queues.iter().find_map(Queue::pop))
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.queues.iter().map(Queue::len).sum()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.queues.iter().all(Queue::is_empty)
+    }
+}
+
+enum Queue<T> {
+    Unbounded(SegQueue<T>),
+    Bounded(ArrayQueue<T>, OverflowPolicy),
+}
+
+impl<T> Queue<T> {
+    fn unbounded() -> Self {
+        Queue::Unbounded(SegQueue::new())
+    }
+
+    fn bounded(capacity: usize, policy: OverflowPolicy) -> Self {
+        Queue::Bounded(ArrayQueue::new(capacity), policy)
+    }
+
+    fn max_tasks(&self) -> Option<usize> {
+        match self {
+            Queue::Bounded(queue, OverflowPolicy::Reject) => Some(queue.capacity()),
+            _ => None,
+        }
+    }
+
+    /// Push an entry, and return the entry which was dropped if the queue was full
+    fn push(&self, entry: T) -> Option<T> {
+        match self {
+            Queue::Unbounded(queue) => {
+                queue.push(entry);
+                None
+            }
+            Queue::Bounded(queue, policy) => match policy {
+                OverflowPolicy::Panic => {
+                    if queue.push(entry).is_err() {
+                        panic!("the task queue is full ({} tasks)", queue.capacity());
+                    }
+                    None
+                }
+                OverflowPolicy::DropOldest => queue.force_push(entry),
+                // the number of tasks is limited when they are spawned
+                OverflowPolicy::Reject => queue.push(entry).err(),
+            },
+        }
+    }
+
+    fn pop(&self) -> Option<T> {
+        match self {
+            Queue::Unbounded(queue) => queue.pop(),
+            Queue::Bounded(queue, _) => queue.pop(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Queue::Unbounded(queue) => queue.len(),
+            Queue::Bounded(queue, _) => queue.len(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        match self {
+            Queue::Unbounded(queue) => queue.is_empty(),
+            Queue::Bounded(queue, _) => queue.is_empty(),
+        }
+    }
+}
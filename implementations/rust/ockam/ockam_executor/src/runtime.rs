@@ -1,6 +1,7 @@
 #![allow(missing_docs)]
 #![allow(clippy::needless_lifetimes)]
 
+use core::fmt;
 use core::future::Future;
 use core::pin::Pin;
 use core::task::{Context, Poll, Waker};
@@ -15,7 +16,9 @@ where
     F: Future<Output = ()> + Send,
     F::Output: Send,
 {
-    executor::current().block_on(future)
+    if executor::current().block_on(future).is_err() {
+        warn!("the executor was shut down while executing the main future");
+    }
 }
 
 /// block_future
@@ -61,8 +64,7 @@ impl Runtime {
         F: Future + Send + 'static,
         F::Output: Send + 'static,
     {
-        executor::current().spawn(future);
-        JoinHandle::new()
+        executor::current().spawn(future)
     }
 }
 
@@ -76,18 +78,36 @@ impl Handle {
         F: Future + Send + 'static,
         F::Output: Send + 'static,
     {
-        executor::current().spawn(future);
-        JoinHandle::new()
+        executor::current().spawn(future)
     }
 }
 
-/// SharedJoinHandle
+/// State shared by a spawned task and its [`JoinHandle`]
 pub struct SharedJoinHandle<T> {
+    /// Output of the task, until it is retrieved by the [`JoinHandle`]
     pub value: Option<T>,
+    /// Waker of the task awaiting the [`JoinHandle`]
     pub waker: Option<Waker>,
+    /// Waker of the spawned task, to poll it again once it is aborted
+    pub task_waker: Option<Waker>,
+    /// Set when the task has completed, or has been dropped after being aborted
+    pub finished: bool,
+    /// Set by [`JoinHandle::abort`]
+    pub aborted: bool,
+}
+
+impl<T> SharedJoinHandle<T> {
+    /// Store the output of the task and wake the task awaiting it
+    pub(crate) fn complete(&mut self, value: Option<T>) {
+        self.value = value;
+        self.finished = true;
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
 }
 
-/// JoinHandle
+/// Handle to await the output of a spawned task, or to abort it
 pub struct JoinHandle<T>(pub Arc<Mutex<SharedJoinHandle<T>>>);
 
 impl<T: Send> Default for SharedJoinHandle<T> {
@@ -95,17 +115,23 @@ impl<T: Send> Default for SharedJoinHandle<T> {
         Self {
             value: None,
             waker: None,
+            task_waker: None,
+            finished: false,
+            aborted: false,
         }
     }
 }
 
 impl<T: Send> Future for JoinHandle<T> {
-    type Output = T;
+    type Output = Result<T, JoinError>;
 
-    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let mut guard = self.0.lock().unwrap();
         if let Some(value) = guard.value.take() {
-            return Poll::Ready(value);
+            return Poll::Ready(Ok(value));
+        }
+        if guard.finished {
+            return Poll::Ready(Err(JoinError(())));
         }
         guard.waker = Some(cx.waker().clone());
         Poll::Pending
@@ -117,6 +143,28 @@ impl<T: Send> JoinHandle<T> {
         let inner = Arc::new(Mutex::new(SharedJoinHandle::default()));
         JoinHandle(inner)
     }
+
+    /// Return true if the task has completed, or has been dropped after being aborted
+    pub fn is_finished(&self) -> bool {
+        self.0.lock().unwrap().finished
+    }
+
+    /// Abort the task. Its future is dropped, without being polled,
+    /// the next time the executor would poll it.
+    /// Awaiting the handle of an aborted task returns a [`JoinError`]
+    pub fn abort(&self) {
+        let task_waker = {
+            let mut guard = self.0.lock().unwrap();
+            if guard.finished {
+                return;
+            }
+            guard.aborted = true;
+            guard.task_waker.take()
+        };
+        if let Some(task_waker) = task_waker {
+            task_waker.wake();
+        }
+    }
 }
 
 impl<T: Send> Default for JoinHandle<T> {
@@ -125,6 +173,23 @@ impl<T: Send> Default for JoinHandle<T> {
     }
 }
 
+/// Error returned when awaiting a task which was aborted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JoinError(());
+
+impl JoinError {
+    /// Return true, since tasks can only fail by being aborted
+    pub fn is_cancelled(&self) -> bool {
+        true
+    }
+}
+
+impl fmt::Display for JoinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "task was cancelled")
+    }
+}
+
 /// yield_now
 pub async fn yield_now() {
     #[allow(dead_code)]
@@ -1,22 +1,145 @@
+use core::fmt;
 use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
 pub use core::time::Duration;
+use ockam_core::compat::sync::Arc;
 use pin_project_lite::pin_project;
 
+use crate::executor;
+use crate::timer::{TimerKey, Timers};
+
+/// Monotonic tick counter provided by the platform, used by the executor timers
+///
+/// The counter is expected to wrap around when it overflows. The executor reads
+/// it at least once per half period while timers are pending, so that no
+/// wrap-around is missed.
+pub trait Clock: Send + Sync {
+    /// Current value of the tick counter
+    fn now(&self) -> u32;
+
+    /// Number of ticks per second
+    fn frequency(&self) -> u32;
+
+    /// Signal the executor at the given tick, for example by setting up a
+    /// timer interrupt, so that it can leave a wait for event (`WFE`).
+    /// The default implementation does nothing
+    fn wake_at(&self, _ticks: u32) {}
+}
+
+/// Clock counting microseconds since its creation, used by default with the `"std"` feature
+#[cfg(feature = "std")]
+pub struct StdClock {
+    start: std::time::Instant,
+}
+
+#[cfg(feature = "std")]
+impl StdClock {
+    pub fn new() -> Self {
+        Self {
+            start: std::time::Instant::now(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for StdClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Clock for StdClock {
+    fn now(&self) -> u32 {
+        // truncating the number of microseconds makes the counter wrap around
+        self.start.elapsed().as_micros() as u32
+    }
+
+    fn frequency(&self) -> u32 {
+        1_000_000
+    }
+}
+
+/// Future completing once a duration has elapsed, see [`sleep`]
+pub struct Sleep {
+    timers: Arc<Timers>,
+    deadline: u64,
+    key: Option<TimerKey>,
+}
+
+impl Sleep {
+    pub(crate) fn new(timers: Arc<Timers>, duration: Duration) -> Self {
+        let deadline = timers.deadline(duration);
+        Self {
+            timers,
+            deadline,
+            key: None,
+        }
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = &mut *self;
+        if this
+            .timers
+            .poll_deadline(&mut this.key, this.deadline, cx.waker())
+        {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl fmt::Debug for Sleep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sleep")
+            .field("deadline", &self.deadline)
+            .finish()
+    }
+}
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            self.timers.cancel(key);
+        }
+    }
+}
+
+/// Wait until `duration` has elapsed, using the timers of the current executor
+pub fn sleep(duration: Duration) -> Sleep {
+    executor::current().sleep(duration)
+}
+
 pin_project! {
+    /// Future returning the output of a future, or an error
+    /// if it does not complete before a duration, see [`timeout`]
     #[derive(Debug)]
     pub struct Timeout<F> {
-        #[pin]
-        duration: Duration,
         #[pin]
         future: F,
+        delay: Sleep,
     }
 }
 
+impl<F> Timeout<F> {
+    pub(crate) fn new(future: F, delay: Sleep) -> Self {
+        Self { future, delay }
+    }
+}
+
+/// Require `future` to complete before `duration` has elapsed,
+/// using the timers of the current executor
 pub fn timeout<F>(duration: Duration, future: F) -> Timeout<F>
 where
     F: Future,
 {
-    Timeout { duration, future }
+    executor::current().timeout(duration, future)
 }
 
 impl<F> Future for Timeout<F>
@@ -25,32 +148,21 @@ where
 {
     type Output = Result<F::Output, error::Elapsed>;
 
-    fn poll(
-        self: core::pin::Pin<&mut Self>,
-        cx: &mut core::task::Context<'_>,
-    ) -> core::task::Poll<Self::Output> {
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let timeout = self.project();
 
         // try polling the future
-        if let core::task::Poll::Ready(v) = timeout.future.poll(cx) {
-            return core::task::Poll::Ready(Ok(v));
+        if let Poll::Ready(v) = timeout.future.poll(cx) {
+            return Poll::Ready(Ok(v));
         }
 
-        // TODO check the timer
-        /*match timeout.delay.poll(cx) {
-            Poll::Ready(()) => Poll::Ready(Err(Elapsed::new())),
+        match Pin::new(timeout.delay).poll(cx) {
+            Poll::Ready(()) => Poll::Ready(Err(error::Elapsed::new())),
             Poll::Pending => Poll::Pending,
-        }*/
-        core::task::Poll::Pending
+        }
     }
 }
 
-pub struct Sleep;
-
-pub async fn sleep(_duration: Duration) -> Sleep {
-    unimplemented!();
-}
-
 pub mod error {
     use core::fmt;
     use ockam_core::compat::{error, io};
@@ -59,7 +171,6 @@ pub mod error {
     pub struct Elapsed(());
 
     impl Elapsed {
-        #![allow(dead_code)]
         pub(crate) fn new() -> Self {
             Elapsed(())
         }
@@ -0,0 +1,187 @@
+//! Timer queue of the executor: the deadlines of the [`Sleep`](crate::time::Sleep)
+//! futures are kept sorted, and their tasks are woken by the executor loop once
+//! the [`Clock`] of the executor shows that the deadline has passed.
+//!
+//! The tick counter of the clock can wrap around: the executor keeps its own
+//! 64 bits count of the elapsed ticks, which is updated each time the clock is read.
+
+use core::task::Waker;
+use core::time::Duration;
+
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::sync::{Arc, Mutex, OnceLock};
+
+use crate::idle::Idle;
+use crate::time::Clock;
+
+/// The executor never waits longer than half the wrap-around period of
+/// the clock, so that the number of elapsed ticks is never underestimated
+const MAX_WAIT_TICKS: u64 = (u32::MAX / 2) as u64;
+
+/// Deadline, in elapsed ticks, and identifier of a registered timer
+pub(crate) type TimerKey = (u64, u64);
+
+pub(crate) struct Timers {
+    clock: OnceLock<Box<dyn Clock>>,
+    state: Mutex<State>,
+    idle: Arc<Idle>,
+}
+
+#[derive(Default)]
+struct State {
+    /// Last value read from the clock
+    last_ticks: Option<u32>,
+    /// Number of ticks elapsed since the clock was first read
+    elapsed: u64,
+    next_id: u64,
+    wakers: BTreeMap<TimerKey, Waker>,
+}
+
+impl Timers {
+    pub(crate) fn new(idle: Arc<Idle>) -> Self {
+        Self {
+            clock: OnceLock::new(),
+            state: Mutex::new(State::default()),
+            idle,
+        }
+    }
+
+    /// Set the clock used by the timers. Return false if a clock was already set
+    pub(crate) fn set_clock(&self, clock: Box<dyn Clock>) -> bool {
+        let mut clock = Some(clock);
+        self.clock.get_or_init(|| clock.take().unwrap());
+        clock.is_none()
+    }
+
+    fn clock(&self) -> &dyn Clock {
+        #[cfg(feature = "std")]
+        let clock = self
+            .clock
+            .get_or_init(|| Box::new(crate::time::StdClock::new()));
+        #[cfg(not(feature = "std"))]
+        let clock = self
+            .clock
+            .get()
+            .expect("a clock must be set with `Executor::set_clock` to use timers");
+        clock.as_ref()
+    }
+
+    /// Return the deadline, in elapsed ticks, which is `duration` from now
+    pub(crate) fn deadline(&self, duration: Duration) -> u64 {
+        let clock = self.clock();
+        let ticks = duration_to_ticks(duration, clock.frequency());
+        let mut state = self.state.lock().unwrap();
+        state.now(clock).saturating_add(ticks)
+    }
+
+    /// Return the number of elapsed ticks
+    #[cfg(feature = "instrumentation")]
+    pub(crate) fn now(&self) -> u64 {
+        self.state.lock().unwrap().now(self.clock())
+    }
+
+    /// Return the duration elapsed since `start`, in elapsed ticks
+    #[cfg(feature = "instrumentation")]
+    pub(crate) fn elapsed_since(&self, start: u64) -> Duration {
+        let ticks = self.now().saturating_sub(start);
+        ticks_to_duration(ticks, self.clock().frequency())
+    }
+
+    /// Return true if the deadline has passed. Otherwise, register the waker
+    /// of the timer, allocating a new timer the first time it is polled
+    pub(crate) fn poll_deadline(
+        &self,
+        key: &mut Option<TimerKey>,
+        deadline: u64,
+        waker: &Waker,
+    ) -> bool {
+        let clock = self.clock();
+        let mut state = self.state.lock().unwrap();
+        if state.now(clock) >= deadline {
+            if let Some(key) = key.take() {
+                state.wakers.remove(&key);
+            }
+            return true;
+        }
+
+        let key = *key.get_or_insert_with(|| {
+            state.next_id += 1;
+            (deadline, state.next_id)
+        });
+        state.wakers.insert(key, waker.clone());
+        let earliest = state.wakers.keys().next() == Some(&key);
+        drop(state);
+
+        // the executor may be waiting for a later deadline
+        if earliest {
+            self.idle.notify();
+        }
+        false
+    }
+
+    /// Remove a timer which is dropped before its deadline
+    pub(crate) fn cancel(&self, key: TimerKey) {
+        self.state.lock().unwrap().wakers.remove(&key);
+    }
+
+    /// Wake the tasks whose deadline has passed
+    pub(crate) fn wake_expired(&self) {
+        let expired = {
+            let mut state = self.state.lock().unwrap();
+            if state.wakers.is_empty() {
+                return;
+            }
+            let now = state.now(self.clock());
+            let pending = state.wakers.split_off(&(now.saturating_add(1), 0));
+            core::mem::replace(&mut state.wakers, pending)
+        };
+        // the wakers are called without holding the lock since they
+        // can poll the timers again
+        for waker in expired.into_values() {
+            waker.wake();
+        }
+    }
+
+    /// Return how long the executor can wait before the earliest deadline,
+    /// after asking the clock to signal that deadline
+    pub(crate) fn prepare_wait(&self) -> Option<Duration> {
+        let mut state = self.state.lock().unwrap();
+        let deadline = state.wakers.keys().next()?.0;
+        let clock = self.clock();
+        let ticks = deadline
+            .saturating_sub(state.now(clock))
+            .min(MAX_WAIT_TICKS);
+        let last_ticks = state.last_ticks.unwrap_or_default();
+        drop(state);
+
+        clock.wake_at(last_ticks.wrapping_add(ticks as u32));
+        Some(ticks_to_duration(ticks, clock.frequency()))
+    }
+}
+
+impl State {
+    /// Read the clock and return the number of elapsed ticks. The clock is read
+    /// while the state is locked so that its successive values are increasing
+    fn now(&mut self, clock: &dyn Clock) -> u64 {
+        let ticks = clock.now();
+        if let Some(last_ticks) = self.last_ticks {
+            // a wrap-around of the counter results in a small difference
+            self.elapsed += ticks.wrapping_sub(last_ticks) as u64;
+        }
+        self.last_ticks = Some(ticks);
+        self.elapsed
+    }
+}
+
+/// Convert a duration to a number of ticks, rounding up so
+/// that a timer never expires before its duration
+fn duration_to_ticks(duration: Duration, frequency: u32) -> u64 {
+    let ticks = (duration.as_nanos() * frequency as u128 + 999_999_999) / 1_000_000_000;
+    ticks.min(u64::MAX as u128) as u64
+}
+
+fn ticks_to_duration(ticks: u64, frequency: u32) -> Duration {
+    let nanos = ticks as u128 * 1_000_000_000 / frequency.max(1) as u128;
+    Duration::from_nanos(nanos.min(u64::MAX as u128) as u64)
+}
@@ -67,12 +67,33 @@ impl CredentialsVerification {
         expected_subject: Option<&Identifier>,
         authorities: &[Identifier],
         credential_and_purpose_key: &CredentialAndPurposeKey,
+    ) -> Result<CredentialAndPurposeKeyData> {
+        Self::verify_credential_static_at(
+            purpose_keys_verification,
+            verifying_vault,
+            expected_subject,
+            authorities,
+            credential_and_purpose_key,
+            now()?,
+        )
+        .await
+    }
+
+    /// Verify a [`Credential`], checking that it is valid at a given time
+    pub async fn verify_credential_static_at(
+        purpose_keys_verification: Arc<PurposeKeyVerification>,
+        verifying_vault: Arc<dyn VaultForVerifyingSignatures>,
+        expected_subject: Option<&Identifier>,
+        authorities: &[Identifier],
+        credential_and_purpose_key: &CredentialAndPurposeKey,
+        now: TimestampInSeconds,
     ) -> Result<CredentialAndPurposeKeyData> {
         debug!("verify purpose key attestation");
         let purpose_key_data = purpose_keys_verification
-            .verify_purpose_key_attestation(
+            .verify_purpose_key_attestation_at(
                 None,
                 &credential_and_purpose_key.purpose_key_attestation,
+                now,
             )
             .await?;
 
@@ -149,8 +170,6 @@ impl CredentialsVerification {
             return Err(IdentityError::CredentialVerificationFailed)?;
         }
 
-        let now = now()?;
-
         if credential_data.created_at > now
             && credential_data.created_at - now > MAX_ALLOWED_TIME_DRIFT
         {
@@ -68,6 +68,8 @@ pub enum IdentityError {
     AddressIsNotSubscribedForThatCredentialRetriever,
     /// Credential retriever couldn't return a credential
     NoCredential,
+    /// The payload of a secure channel message is too large
+    PayloadTooLarge,
 }
 
 impl ockam_core::compat::error::Error for IdentityError {}
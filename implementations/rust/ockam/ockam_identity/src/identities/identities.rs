@@ -1,3 +1,4 @@
+use core::time::Duration;
 use ockam_core::compat::sync::Arc;
 use ockam_core::compat::vec::Vec;
 use ockam_core::Result;
@@ -32,6 +33,7 @@ pub struct Identities {
     identity_attributes_repository: Arc<dyn IdentityAttributesRepository>,
     purpose_keys_repository: Arc<dyn PurposeKeysRepository>,
     cached_credentials_repository: Arc<dyn CredentialRepository>,
+    attributes_max_age: Option<Duration>,
 }
 
 impl Identities {
@@ -76,9 +78,10 @@ impl Identities {
 
     /// Return the service responsible for managing identities attributes
     pub fn identities_attributes(&self) -> Arc<IdentitiesAttributes> {
-        Arc::new(IdentitiesAttributes::new(
-            self.identity_attributes_repository.clone(),
-        ))
+        Arc::new(
+            IdentitiesAttributes::new(self.identity_attributes_repository.clone())
+                .with_max_age(self.attributes_max_age),
+        )
     }
 
     /// Return the [`PurposeKeys`] instance
@@ -143,9 +146,17 @@ impl Identities {
             identity_attributes_repository,
             purpose_keys_repository,
             cached_credentials_repository,
+            attributes_max_age: None,
         }
     }
 
+    /// Expire the attributes of the identities once they have been stored for longer
+    /// than `attributes_max_age`, even if the credentials which attested them are still valid
+    pub fn with_attributes_max_age(mut self, attributes_max_age: Option<Duration>) -> Self {
+        self.attributes_max_age = attributes_max_age;
+        self
+    }
+
     /// Return a default builder for identities
     #[cfg(feature = "storage")]
     pub async fn builder() -> Result<IdentitiesBuilder> {
@@ -165,6 +176,7 @@ impl Identities {
             )),
             purpose_keys_repository: Arc::new(PurposeKeysSqlxDatabase::new(database.clone())),
             cached_credentials_repository: Arc::new(CredentialSqlxDatabase::new(database)),
+            attributes_max_age: None,
         }
     }
 }
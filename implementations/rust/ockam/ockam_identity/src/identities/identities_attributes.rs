@@ -1,5 +1,6 @@
 use crate::utils::now;
-use crate::{AttributesEntry, Identifier, IdentityAttributesRepository};
+use crate::{AttributesEntry, Identifier, IdentityAttributesRepository, TimestampInSeconds};
+use core::time::Duration;
 use ockam_core::compat::sync::Arc;
 use ockam_core::Result;
 use tracing_attributes::instrument;
@@ -10,17 +11,30 @@ use tracing_attributes::instrument;
 ///
 /// - Setting the time at which a given attribute is persisted
 /// - Deleting expired attributes from storage. This deletion is performed every time the
-///   repository is accessed to retrieve attributes
+///   repository is accessed to retrieve attributes. Attributes expire with the credential
+///   which attested them or, when a maximum age is set, once they have been stored for longer
+///   than that age
 ///
 #[derive(Clone)]
 pub struct IdentitiesAttributes {
     repository: Arc<dyn IdentityAttributesRepository>,
+    max_age: Option<Duration>,
 }
 
 impl IdentitiesAttributes {
     /// Return a new IdentitiesAttributes struct
     pub fn new(repository: Arc<dyn IdentityAttributesRepository>) -> IdentitiesAttributes {
-        IdentitiesAttributes { repository }
+        IdentitiesAttributes {
+            repository,
+            max_age: None,
+        }
+    }
+
+    /// Expire the attributes once they have been stored for longer than `max_age`,
+    /// even if the credential which attested them is still valid
+    pub fn with_max_age(mut self, max_age: Option<Duration>) -> IdentitiesAttributes {
+        self.max_age = max_age;
+        self
     }
 
     /// Return the attributes for a given pair subject/attesting authority
@@ -32,7 +46,14 @@ impl IdentitiesAttributes {
         subject: &Identifier,
         attested_by: &Identifier,
     ) -> Result<Option<AttributesEntry>> {
-        self.repository.delete_expired_attributes(now()?).await?;
+        let now = now()?;
+        self.repository.delete_expired_attributes(now).await?;
+        if let Some(max_age) = self.max_age {
+            let added_before = TimestampInSeconds(now.0.saturating_sub(max_age.as_secs()));
+            self.repository
+                .delete_attributes_added_before(added_before)
+                .await?;
+        }
         self.repository.get_attributes(subject, attested_by).await
     }
 
@@ -42,6 +63,20 @@ impl IdentitiesAttributes {
     pub async fn put_attributes(&self, subject: &Identifier, entry: AttributesEntry) -> Result<()> {
         self.repository.put_attributes(subject, entry).await
     }
+
+    /// Remove the attributes of the given identities, or of all the identities if none is given.
+    /// An identity is only authorized again, based on its attributes, once it presents a new
+    /// credential
+    #[instrument(skip_all, fields(subjects = ?subjects))]
+    pub async fn flush_attributes(&self, subjects: &[Identifier]) -> Result<()> {
+        if subjects.is_empty() {
+            return self.repository.delete_all_attributes().await;
+        }
+        for subject in subjects {
+            self.repository.delete_attributes(subject).await?;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -93,6 +128,72 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_identities_attributes_max_age() -> Result<()> {
+        let identities_attributes = create_identities_attributes()
+            .await?
+            .with_max_age(Some(Duration::from_secs(2)));
+
+        // the attributes expire after the maximum age even if the credential is still valid
+        let identifier = create_identity().await?;
+        let attributes = create_attributes_entry(&identifier, now()?, 100.into()).await?;
+        identities_attributes
+            .put_attributes(&identifier, attributes.clone())
+            .await?;
+
+        let result = identities_attributes
+            .get_attributes(&identifier, &identifier)
+            .await?;
+        assert_eq!(result, Some(attributes));
+
+        tokio::time::sleep(Duration::from_secs(4)).await;
+
+        let result = identities_attributes
+            .get_attributes(&identifier, &identifier)
+            .await?;
+        assert_eq!(result, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_flush_identities_attributes() -> Result<()> {
+        let identities_attributes = create_identities_attributes().await?;
+
+        let identifier1 = create_identity().await?;
+        let identifier2 = create_identity().await?;
+        let attributes1 = create_attributes_entry(&identifier1, now()?, 100.into()).await?;
+        let attributes2 = create_attributes_entry(&identifier2, now()?, 100.into()).await?;
+        identities_attributes
+            .put_attributes(&identifier1, attributes1)
+            .await?;
+        identities_attributes
+            .put_attributes(&identifier2, attributes2.clone())
+            .await?;
+
+        // flush the attributes of one identity
+        identities_attributes
+            .flush_attributes(&[identifier1.clone()])
+            .await?;
+        let result = identities_attributes
+            .get_attributes(&identifier1, &identifier1)
+            .await?;
+        assert_eq!(result, None);
+        let result = identities_attributes
+            .get_attributes(&identifier2, &identifier2)
+            .await?;
+        assert_eq!(result, Some(attributes2));
+
+        // flush the attributes of all the identities
+        identities_attributes.flush_attributes(&[]).await?;
+        let result = identities_attributes
+            .get_attributes(&identifier2, &identifier2)
+            .await?;
+        assert_eq!(result, None);
+
+        Ok(())
+    }
+
     /// HELPERS
     async fn create_attributes_entry(
         identifier: &Identifier,
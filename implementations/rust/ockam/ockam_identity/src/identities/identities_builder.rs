@@ -1,3 +1,4 @@
+use core::time::Duration;
 use ockam_core::compat::sync::Arc;
 #[cfg(feature = "storage")]
 use ockam_core::Result;
@@ -18,6 +19,7 @@ pub struct IdentitiesBuilder {
     pub(crate) identity_attributes_repository: Arc<dyn IdentityAttributesRepository>,
     pub(crate) purpose_keys_repository: Arc<dyn PurposeKeysRepository>,
     pub(crate) cached_credentials_repository: Arc<dyn CredentialRepository>,
+    pub(crate) attributes_max_age: Option<Duration>,
 }
 
 /// Return a default identities
@@ -81,14 +83,23 @@ impl IdentitiesBuilder {
         self
     }
 
+    /// Set a maximum age for the attributes of the identities
+    pub fn with_attributes_max_age(mut self, attributes_max_age: Option<Duration>) -> Self {
+        self.attributes_max_age = attributes_max_age;
+        self
+    }
+
     /// Build identities
     pub fn build(self) -> Arc<Identities> {
-        Arc::new(Identities::new(
-            self.vault,
-            self.change_history_repository,
-            self.identity_attributes_repository,
-            self.purpose_keys_repository,
-            self.cached_credentials_repository,
-        ))
+        Arc::new(
+            Identities::new(
+                self.vault,
+                self.change_history_repository,
+                self.identity_attributes_repository,
+                self.purpose_keys_repository,
+                self.cached_credentials_repository,
+            )
+            .with_attributes_max_age(self.attributes_max_age),
+        )
     }
 }
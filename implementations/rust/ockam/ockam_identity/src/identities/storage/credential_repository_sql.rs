@@ -54,6 +54,23 @@ impl CredentialSqlxDatabase {
 
         Ok(res)
     }
+
+    /// Return all the cached credentials issued to a given subject, for any node
+    pub async fn get_all_for_subject(
+        &self,
+        subject: &Identifier,
+    ) -> Result<Vec<(CredentialAndPurposeKey, String)>> {
+        let query = query_as("SELECT credential, scope FROM credential WHERE subject_identifier=?")
+            .bind(subject.to_sql());
+
+        let cached_credential: Vec<CachedCredentialAndScopeRow> =
+            query.fetch_all(&*self.database.pool).await.into_core()?;
+
+        cached_credential
+            .into_iter()
+            .map(|c| Ok((c.credential()?, c.scope().to_string())))
+            .collect::<Result<Vec<_>>>()
+    }
 }
 
 #[async_trait]
@@ -205,7 +222,11 @@ mod tests {
         let all = repository.get_all().await?;
         assert_eq!(all.len(), 1);
         let credential4 = repository.get(&subject, &issuer, &scope).await?;
-        assert_eq!(credential4, Some(credential3));
+        assert_eq!(credential4, Some(credential3.clone()));
+
+        let for_subject = repository.get_all_for_subject(&subject).await?;
+        assert_eq!(for_subject, vec![(credential3, scope.clone())]);
+        assert!(repository.get_all_for_subject(&issuer).await?.is_empty());
 
         repository.delete(&subject, &issuer, &scope).await?;
         let result = repository.get(&subject, &issuer, &scope).await?;
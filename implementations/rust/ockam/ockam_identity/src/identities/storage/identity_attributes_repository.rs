@@ -19,4 +19,14 @@ pub trait IdentityAttributesRepository: Send + Sync + 'static {
 
     /// Remove all expired attributes
     async fn delete_expired_attributes(&self, now: TimestampInSeconds) -> Result<()>;
+
+    /// Remove all the attributes stored before the given time
+    async fn delete_attributes_added_before(&self, added_before: TimestampInSeconds) -> Result<()>;
+
+    /// Remove the attributes associated with the given identity identifier,
+    /// whichever authority attested them
+    async fn delete_attributes(&self, subject: &Identifier) -> Result<()>;
+
+    /// Remove the attributes associated with all the identities
+    async fn delete_all_attributes(&self) -> Result<()>;
 }
@@ -79,6 +79,26 @@ impl IdentityAttributesRepository for IdentityAttributesSqlxDatabase {
             .bind(self.database.node_name()?.to_sql());
         query.execute(&*self.database.pool).await.void()
     }
+
+    async fn delete_attributes_added_before(&self, added_before: TimestampInSeconds) -> Result<()> {
+        let query = query("DELETE FROM identity_attributes WHERE added<? AND node_name=?")
+            .bind(added_before.to_sql())
+            .bind(self.database.node_name()?.to_sql());
+        query.execute(&*self.database.pool).await.void()
+    }
+
+    async fn delete_attributes(&self, subject: &Identifier) -> Result<()> {
+        let query = query("DELETE FROM identity_attributes WHERE identifier=? AND node_name=?")
+            .bind(subject.to_sql())
+            .bind(self.database.node_name()?.to_sql());
+        query.execute(&*self.database.pool).await.void()
+    }
+
+    async fn delete_all_attributes(&self) -> Result<()> {
+        let query = query("DELETE FROM identity_attributes WHERE node_name=?")
+            .bind(self.database.node_name()?.to_sql());
+        query.execute(&*self.database.pool).await.void()
+    }
 }
 
 // Database serialization / deserialization
@@ -230,6 +250,59 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_delete_attributes() -> Result<()> {
+        let repository = create_repository().await?;
+        let now = now()?;
+
+        let identifier1 = create_identity().await?;
+        let identifier2 = create_identity().await?;
+        let identifier3 = create_identity().await?;
+        let attributes1 = create_attributes_entry(&identifier1, now, None).await?;
+        let attributes2 = create_attributes_entry(&identifier2, now, None).await?;
+        let attributes3 =
+            create_attributes_entry(&identifier3, TimestampInSeconds(now.0 - 100), None).await?;
+
+        repository
+            .put_attributes(&identifier1, attributes1.clone())
+            .await?;
+        repository
+            .put_attributes(&identifier2, attributes2.clone())
+            .await?;
+        repository
+            .put_attributes(&identifier3, attributes3.clone())
+            .await?;
+
+        // only the attributes added more than 10 seconds ago are deleted
+        repository
+            .delete_attributes_added_before(TimestampInSeconds(now.0 - 10))
+            .await?;
+        let result = repository
+            .get_attributes(&identifier3, &identifier3)
+            .await?;
+        assert_eq!(result, None);
+
+        // the attributes of a single identity can be deleted
+        repository.delete_attributes(&identifier1).await?;
+        let result = repository
+            .get_attributes(&identifier1, &identifier1)
+            .await?;
+        assert_eq!(result, None);
+        let result = repository
+            .get_attributes(&identifier2, &identifier2)
+            .await?;
+        assert_eq!(result, Some(attributes2));
+
+        // or the attributes of all the identities
+        repository.delete_all_attributes().await?;
+        let result = repository
+            .get_attributes(&identifier2, &identifier2)
+            .await?;
+        assert_eq!(result, None);
+
+        Ok(())
+    }
+
     /// HELPERS
     async fn create_attributes_entry(
         identifier: &Identifier,
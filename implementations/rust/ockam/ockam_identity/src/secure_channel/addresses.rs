@@ -2,6 +2,9 @@ use ockam_core::Address;
 
 use crate::secure_channel::role::Role;
 
+/// Owner of the decryptor addresses, which are marked as internal addresses of the node
+pub(crate) const SECURE_CHANNEL_DECRYPTOR: &str = "secure channel decryptor";
+
 // Previously there were regular ephemeral secure channel encryptor&decryptor
 // and identity secure channel encryptor&decryptor.
 // Now this logic is merged into one encryptor&decryptor pair, but for backwards
@@ -6,7 +6,7 @@ use ockam_core::{Decodable, LocalMessage};
 use ockam_node::Context;
 
 use crate::models::Identifier;
-use crate::secure_channel::encryptor::{Encryptor, KEY_RENEWAL_INTERVAL};
+use crate::secure_channel::encryptor::{Encryptor, KEY_RENEWAL_INTERVAL, SIZE_OF_ENCRYPT_OVERHEAD};
 use crate::secure_channel::handshake::handshake_state_machine::CommonStateMachine;
 use crate::secure_channel::key_tracker::KeyTracker;
 use crate::secure_channel::nonce_tracker::NonceTracker;
@@ -95,6 +95,8 @@ impl DecryptorHandler {
         ctx: &mut Context,
         mut msg: PlaintextPayloadMessage<'_>,
     ) -> Result<()> {
+        self.shared_state.traffic.add_received(msg.payload.len());
+
         // Add encryptor hop in the return_route (instead of our address)
         msg.return_route
             .modify()
@@ -203,6 +205,10 @@ impl DecryptorHandler {
     }
 }
 
+/// Maximum size of the payload of a message received by a secure channel, once decrypted.
+/// Larger messages are rejected before being decrypted and handed to the workers
+pub const MAX_SECURE_CHANNEL_PAYLOAD_SIZE: usize = 1024 * 1024;
+
 pub(crate) struct Decryptor {
     vault: Arc<dyn VaultForSecureChannels>,
     key_tracker: KeyTracker,
@@ -232,6 +238,14 @@ impl Decryptor {
         if payload.len() < 8 {
             return Err(IdentityError::InvalidNonce)?;
         }
+        // The decrypted payload is smaller than the encrypted one by the size of the nonce and tag
+        if payload.len() > MAX_SECURE_CHANNEL_PAYLOAD_SIZE + SIZE_OF_ENCRYPT_OVERHEAD {
+            warn!(
+                size = payload.len(),
+                "Rejecting a secure channel message larger than {MAX_SECURE_CHANNEL_PAYLOAD_SIZE} bytes"
+            );
+            return Err(IdentityError::PayloadTooLarge)?;
+        }
 
         let (nonce, nonce_buffer) = Self::convert_nonce_from_small(&payload[..8])?;
         let nonce_tracker = self.nonce_tracker.mark(nonce)?;
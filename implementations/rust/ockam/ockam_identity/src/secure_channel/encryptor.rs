@@ -1,16 +1,24 @@
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::time::Duration;
 use ockam_core::compat::sync::Arc;
 use ockam_core::compat::vec::Vec;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{Error, Result};
 use ockam_vault::{AeadSecretKeyHandle, VaultForSecureChannels};
+use tracing::debug;
 use tracing_attributes::instrument;
 
+use crate::models::TimestampInSeconds;
+use crate::utils::now;
 use crate::IdentityError;
 
 pub(crate) struct Encryptor {
     key: AeadSecretKeyHandle,
     nonce: u64,
     vault: Arc<dyn VaultForSecureChannels>,
+    rekey_interval: Option<Duration>,
+    last_rekey_at: TimestampInSeconds,
+    rekeys: Arc<AtomicUsize>,
 }
 
 // To simplify the implementation we use the same constant for the size of the message
@@ -57,16 +65,31 @@ impl Encryptor {
     #[instrument(skip_all)]
     pub async fn encrypt(&mut self, destination: &mut Vec<u8>, payload: &[u8]) -> Result<()> {
         let current_nonce = self.nonce;
+
+        // When the rekey interval has elapsed, skip the rest of the current nonce interval,
+        // so that the key is renewed now. The other side renews its key when it receives
+        // the first nonce of the next interval, and keeps the previous key for the messages
+        // which are still in flight
+        let current_nonce = if self.rekey_is_due()? && current_nonce % KEY_RENEWAL_INTERVAL != 0 {
+            (current_nonce / KEY_RENEWAL_INTERVAL + 1)
+                .checked_mul(KEY_RENEWAL_INTERVAL)
+                .ok_or(IdentityError::NonceOverflow)?
+        } else {
+            current_nonce
+        };
         if current_nonce == u64::MAX {
             return Err(IdentityError::NonceOverflow)?;
         }
 
-        self.nonce += 1;
+        self.nonce = current_nonce + 1;
 
         if current_nonce > 0 && current_nonce % KEY_RENEWAL_INTERVAL == 0 {
             let new_key = Self::rekey(&self.vault, &self.key).await?;
             let old_key = core::mem::replace(&mut self.key, new_key);
             self.vault.delete_aead_secret_key(old_key).await?;
+            self.last_rekey_at = now()?;
+            let rekeys = self.rekeys.fetch_add(1, Ordering::Relaxed) + 1;
+            debug!("The secure channel key was renewed at nonce {current_nonce} ({rekeys} rekeys)");
         }
 
         let (small_nonce, nonce) = Self::convert_nonce_from_u64(current_nonce);
@@ -84,7 +107,38 @@ impl Encryptor {
         nonce: u64,
         vault: Arc<dyn VaultForSecureChannels>,
     ) -> Self {
-        Self { key, nonce, vault }
+        Self {
+            key,
+            nonce,
+            vault,
+            rekey_interval: None,
+            last_rekey_at: TimestampInSeconds(0),
+            rekeys: Default::default(),
+        }
+    }
+
+    /// Renew the key when `rekey_interval` has elapsed since the last renewal,
+    /// even if fewer than [`KEY_RENEWAL_INTERVAL`] messages were sent with it.
+    /// The key is renewed when the next message is encrypted
+    pub fn with_rekey_interval(mut self, rekey_interval: Duration) -> Result<Self> {
+        self.rekey_interval = Some(rekey_interval);
+        self.last_rekey_at = now()?;
+        Ok(self)
+    }
+
+    /// Number of times the key was renewed, shared with the registry of secure channels
+    pub fn rekeys(&self) -> Arc<AtomicUsize> {
+        self.rekeys.clone()
+    }
+
+    fn rekey_is_due(&self) -> Result<bool> {
+        match self.rekey_interval {
+            Some(rekey_interval) => {
+                let elapsed = now()?.0.saturating_sub(self.last_rekey_at.0);
+                Ok(elapsed >= rekey_interval.as_secs())
+            }
+            None => Ok(false),
+        }
     }
 
     #[instrument(skip_all)]
@@ -10,6 +10,7 @@ use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{async_trait, Decodable, Error, LocalMessage, Route};
 use ockam_core::{Any, Result, Routed, Worker};
 use ockam_node::Context;
+use ockam_transport_core::BestEffortDelivery;
 
 use crate::models::CredentialAndPurposeKey;
 use crate::secure_channel::addresses::Addresses;
@@ -17,7 +18,7 @@ use crate::secure_channel::api::{EncryptionRequest, EncryptionResponse};
 use crate::secure_channel::encryptor::{Encryptor, SIZE_OF_ENCRYPT_OVERHEAD};
 use crate::{
     ChangeHistoryRepository, CredentialRetriever, Identifier, IdentityError,
-    PlaintextPayloadMessage, RefreshCredentialsMessage, SecureChannelMessage,
+    PlaintextPayloadMessage, RefreshCredentialsMessage, SecureChannelMessage, SecureChannelTraffic,
 };
 
 #[derive(Debug, Clone)]
@@ -25,6 +26,8 @@ pub(crate) struct SecureChannelSharedState {
     /// Allows Decryptor to flag that we're closing the channel because we received a Close message from the other side,
     /// therefore, we don't need to send that message again to the other side
     pub(crate) should_send_close: Arc<AtomicBool>,
+    /// Messages and bytes exchanged through the channel, in both directions
+    pub(crate) traffic: SecureChannelTraffic,
 }
 
 pub(crate) struct EncryptorWorker {
@@ -157,11 +160,13 @@ impl EncryptorWorker {
 
         let mut onward_route = msg.onward_route();
         let return_route = msg.return_route();
+        let best_effort = BestEffortDelivery::is_marked(msg.local_message());
 
         // Remove our address
         let _ = onward_route.step();
 
         let payload = msg.into_payload();
+        self.shared_state.traffic.add_sent(payload.len());
         let msg = PlaintextPayloadMessage {
             onward_route,
             return_route,
@@ -201,10 +206,17 @@ impl EncryptorWorker {
             buffer
         };
 
-        // Decryptor doesn't need the return_route since it has `self.remote_route` as well
+        // Decryptor doesn't need the return_route since it has `self.remote_route` as well.
+        // The encrypted message keeps the delivery marked by the sender of the payload,
+        // the other messages must be delivered reliably
         let msg = LocalMessage::new()
             .with_payload(payload)
             .with_onward_route(self.remote_route.clone());
+        let msg = if best_effort {
+            msg.with_local_info(vec![BestEffortDelivery::local_info()])
+        } else {
+            msg
+        };
 
         // Send the message to the decryptor on the other side
         ctx.forward_from_address(msg, self.addresses.encryptor.clone())
@@ -1,3 +1,4 @@
+use core::time::Duration;
 use minicbor::{Decode, Encode};
 use tracing::{debug, warn};
 
@@ -61,6 +62,10 @@ pub(super) struct HandshakeResults {
     pub(super) handshake_keys: HandshakeKeys,
     pub(super) their_identifier: Identifier,
     pub(super) presented_credential: Option<CredentialAndPurposeKey>,
+    /// True if the other party presented a credential which was verified with our authority
+    pub(super) their_credential_verified: bool,
+    /// Interval after which both parties renew their encryption key
+    pub(super) rekey_interval: Option<Duration>,
 }
 
 /// This struct implements functions common to both initiator and the responder state machines
@@ -72,7 +77,10 @@ pub(crate) struct CommonStateMachine {
     pub(super) trust_policy: Arc<dyn TrustPolicy>,
     pub(super) authority: Option<Identifier>, // TODO: Replace with ABAC
     pub(super) presented_credential: Option<CredentialAndPurposeKey>,
+    rekey_interval: Option<Duration>,
     their_identifier: Option<Identifier>,
+    their_credential_verified: bool,
+    their_rekey_interval: Option<Duration>,
 }
 
 impl CommonStateMachine {
@@ -83,6 +91,7 @@ impl CommonStateMachine {
         credential_retriever: Option<Arc<dyn CredentialRetriever>>,
         trust_policy: Arc<dyn TrustPolicy>,
         authority: Option<Identifier>,
+        rekey_interval: Option<Duration>,
     ) -> Self {
         Self {
             identities,
@@ -92,7 +101,10 @@ impl CommonStateMachine {
             trust_policy,
             authority,
             presented_credential: None,
+            rekey_interval,
             their_identifier: None,
+            their_credential_verified: false,
+            their_rekey_interval: None,
         }
     }
 
@@ -102,6 +114,7 @@ impl CommonStateMachine {
     ///  - the current Identity Change History
     ///  - the current Secure Channel Purpose Key Attestation
     ///  - the Identity Credentials and corresponding Credentials Purpose Key Attestations
+    ///  - the interval after which the keys must be renewed, if any
     ///
     pub(super) async fn make_identity_payload(&mut self) -> Result<Vec<u8>> {
         // prepare the payload that will be sent either in message 2 or message 3
@@ -118,6 +131,7 @@ impl CommonStateMachine {
             change_history,
             purpose_key_attestation: self.purpose_key_attestation.clone(),
             credentials,
+            rekey_interval: self.rekey_interval.map(|interval| interval.as_secs()),
         };
         Ok(minicbor::to_vec(payload)?)
     }
@@ -130,6 +144,8 @@ impl CommonStateMachine {
         peer: IdentityAndCredentials,
         peer_public_key: X25519PublicKey,
     ) -> Result<()> {
+        // the credentials are verified when there is an authority, and fail the handshake if invalid
+        let their_credential_verified = self.authority.is_some() && !peer.credentials.is_empty();
         let identifier = Self::process_identity_payload_static(
             self.identities.clone(),
             Some(self.trust_policy.clone()),
@@ -142,6 +158,8 @@ impl CommonStateMachine {
         .await?;
 
         self.their_identifier = Some(identifier);
+        self.their_credential_verified = their_credential_verified;
+        self.their_rekey_interval = peer.rekey_interval.map(Duration::from_secs);
 
         Ok(())
     }
@@ -158,10 +176,21 @@ impl CommonStateMachine {
                 their_identifier,
                 handshake_keys,
                 presented_credential: self.presented_credential.clone(),
+                their_credential_verified: self.their_credential_verified,
+                rekey_interval: self.negotiated_rekey_interval(),
             }),
             _ => None,
         }
     }
+
+    /// Both parties renew their encryption key with the shortest of their rekey intervals,
+    /// so that the interval configured on either side applies to both directions of the channel
+    fn negotiated_rekey_interval(&self) -> Option<Duration> {
+        match (self.rekey_interval, self.their_rekey_interval) {
+            (Some(ours), Some(theirs)) => Some(ours.min(theirs)),
+            (ours, theirs) => ours.or(theirs),
+        }
+    }
 }
 
 impl CommonStateMachine {
@@ -287,4 +316,7 @@ pub(super) struct IdentityAndCredentials {
     /// Credentials associated to the identity along with corresponding Credentials Purpose Keys
     /// to verify those Credentials
     #[n(2)] pub(super) credentials: Vec<CredentialAndPurposeKey>,
+    /// Interval, in seconds, after which the sender renews its encryption key.
+    /// Parties which don't renew their keys periodically don't send it
+    #[n(3)] pub(super) rekey_interval: Option<u64>,
 }
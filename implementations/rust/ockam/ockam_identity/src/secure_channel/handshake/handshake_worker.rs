@@ -6,7 +6,7 @@ use ockam_core::compat::{boxed::Box, vec::Vec};
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{
     AllowAll, Any, Decodable, DenyAll, Error, Mailbox, Mailboxes, OutgoingAccessControl, Route,
-    Routed,
+    Routed, INTERNAL_ADDRESS_ATTRIBUTE,
 };
 use ockam_core::{AllowOnwardAddress, Result, Worker};
 use ockam_node::callback::CallbackSender;
@@ -27,10 +27,12 @@ use crate::secure_channel::handshake::handshake_state_machine::{
 };
 use crate::secure_channel::handshake::initiator_state_machine::InitiatorStateMachine;
 use crate::secure_channel::handshake::responder_state_machine::ResponderStateMachine;
-use crate::secure_channel::{Addresses, Role};
+use crate::secure_channel::{Addresses, Role, SECURE_CHANNEL_DECRYPTOR};
+use crate::utils::now;
 use crate::{
     ChangeHistoryRepository, CredentialRetriever, IdentityError, SecureChannelPurposeKey,
-    SecureChannelRegistryEntry, SecureChannels, TrustPolicy, IDENTITY_SECURE_CHANNEL_IDENTIFIER,
+    SecureChannelRegistryEntry, SecureChannelTraffic, SecureChannels, TrustPolicy,
+    IDENTITY_SECURE_CHANNEL_IDENTIFIER,
 };
 
 /// This struct implements a Worker receiving and sending messages
@@ -134,6 +136,7 @@ impl HandshakeWorker {
         authority: Option<Identifier>,
         remote_route: Option<Route>,
         timeout: Option<Duration>,
+        rekey_interval: Option<Duration>,
         role: Role,
     ) -> Result<()> {
         let vault = secure_channels.identities.vault().secure_channel_vault;
@@ -149,6 +152,7 @@ impl HandshakeWorker {
                     credential_retriever.clone(),
                     trust_policy,
                     authority.clone(),
+                    rekey_interval,
                 )
                 .await?,
             )
@@ -162,6 +166,7 @@ impl HandshakeWorker {
                     credential_retriever.clone(),
                     trust_policy,
                     authority.clone(),
+                    rekey_interval,
                 )
                 .await?,
             )
@@ -176,6 +181,7 @@ impl HandshakeWorker {
 
         let shared_state = SecureChannelSharedState {
             should_send_close: Arc::new(AtomicBool::new(true)),
+            traffic: SecureChannelTraffic::default(),
         };
         let worker = Self {
             secure_channels,
@@ -197,6 +203,21 @@ impl HandshakeWorker {
                 &addresses,
                 decryptor_outgoing_access_control,
             ))
+            .with_metadata_attribute(
+                addresses.decryptor_remote.clone(),
+                INTERNAL_ADDRESS_ATTRIBUTE,
+                SECURE_CHANNEL_DECRYPTOR,
+            )
+            .with_metadata_attribute(
+                addresses.decryptor_internal.clone(),
+                INTERNAL_ADDRESS_ATTRIBUTE,
+                SECURE_CHANNEL_DECRYPTOR,
+            )
+            .with_metadata_attribute(
+                addresses.decryptor_api.clone(),
+                INTERNAL_ADDRESS_ATTRIBUTE,
+                SECURE_CHANNEL_DECRYPTOR,
+            )
             .start(context)
             .await?;
 
@@ -351,17 +372,23 @@ impl HandshakeWorker {
             self.shared_state.clone(),
         );
 
+        let mut encryptor = Encryptor::new(
+            handshake_results.handshake_keys.encryption_key,
+            0,
+            self.secure_channels.identities.vault().secure_channel_vault,
+        );
+        if let Some(rekey_interval) = handshake_results.rekey_interval {
+            encryptor = encryptor.with_rekey_interval(rekey_interval)?;
+        }
+        let rekeys = encryptor.rekeys();
+
         // create a separate encryptor worker which will be started independently
         {
             let encryptor = EncryptorWorker::new(
                 self.role.str(),
                 self.addresses.clone(),
                 self.remote_route()?,
-                Encryptor::new(
-                    handshake_results.handshake_keys.encryption_key,
-                    0,
-                    self.secure_channels.identities.vault().secure_channel_vault,
-                ),
+                encryptor,
                 self.identifier.clone(),
                 self.change_history_repository.clone(),
                 self.credential_retriever.clone(),
@@ -411,8 +438,8 @@ impl HandshakeWorker {
             &self.addresses.decryptor_remote
         );
 
-        let their_decryptor_address = self
-            .remote_route()?
+        let their_route = self.remote_route()?;
+        let their_decryptor_address = their_route
             .iter()
             .last()
             .expect("the remote route should not be empty")
@@ -427,6 +454,11 @@ impl HandshakeWorker {
             self.identifier.clone(),
             handshake_results.their_identifier,
             their_decryptor_address,
+            their_route,
+            now()?,
+            handshake_results.their_credential_verified,
+            rekeys,
+            self.shared_state.traffic.clone(),
         );
 
         self.secure_channels
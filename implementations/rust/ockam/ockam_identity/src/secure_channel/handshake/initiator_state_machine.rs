@@ -1,3 +1,4 @@
+use core::time::Duration;
 use delegate::delegate;
 use ockam_core::async_trait;
 use ockam_core::compat::sync::Arc;
@@ -105,6 +106,7 @@ impl InitiatorStateMachine {
         credential_retriever: Option<Arc<dyn CredentialRetriever>>,
         trust_policy: Arc<dyn TrustPolicy>,
         authority: Option<Identifier>,
+        rekey_interval: Option<Duration>,
     ) -> Result<InitiatorStateMachine> {
         let common = CommonStateMachine::new(
             identities,
@@ -113,6 +115,7 @@ impl InitiatorStateMachine {
             credential_retriever,
             trust_policy,
             authority,
+            rekey_interval,
         );
 
         Ok(InitiatorStateMachine {
@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use core::time::Duration;
 use delegate::delegate;
 use ockam_core::compat::sync::Arc;
 use ockam_core::compat::{boxed::Box, vec::Vec};
@@ -108,6 +109,7 @@ impl ResponderStateMachine {
         credential_retriever: Option<Arc<dyn CredentialRetriever>>,
         trust_policy: Arc<dyn TrustPolicy>,
         authority: Option<Identifier>,
+        rekey_interval: Option<Duration>,
     ) -> Result<ResponderStateMachine> {
         let common = CommonStateMachine::new(
             identities,
@@ -116,6 +118,7 @@ impl ResponderStateMachine {
             credential_retriever,
             trust_policy,
             authority,
+            rekey_interval,
         );
 
         Ok(ResponderStateMachine {
@@ -2,6 +2,8 @@ use ockam_core::compat::boxed::Box;
 use ockam_core::compat::sync::Arc;
 use ockam_core::{Address, Any, Result, Routed, Worker};
 use ockam_node::Context;
+#[cfg(feature = "std")]
+use tracing::warn;
 
 use crate::models::Identifier;
 use crate::secure_channel::addresses::Addresses;
@@ -56,6 +58,20 @@ impl Worker for SecureChannelListenerWorker {
         ctx: &mut Self::Context,
         message: Routed<Self::Message>,
     ) -> Result<()> {
+        #[cfg(feature = "std")]
+        if let Some(rate_limiter) = &self.options.handshake_rate_limiter {
+            if !rate_limiter.try_acquire() {
+                if let Some(count) = rate_limiter.rejections().take_report() {
+                    warn!(
+                        address = %ctx.address(),
+                        "dropped {count} handshake message(s) exceeding the limit of {} handshakes per second",
+                        rate_limiter.per_second()
+                    );
+                }
+                return Ok(());
+            }
+        }
+
         let addresses = Addresses::generate(Role::Responder);
         let flow_control_id = self.options.setup_flow_control_for_channel(
             ctx.flow_controls(),
@@ -98,6 +114,7 @@ impl Worker for SecureChannelListenerWorker {
             self.options.authority.clone(),
             None,
             None,
+            self.options.rekey_interval,
             Role::Responder,
         )
         .await?;
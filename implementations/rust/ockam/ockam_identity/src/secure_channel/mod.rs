@@ -14,6 +14,7 @@ mod nonce_tracker;
 mod options;
 mod registry;
 mod role;
+mod traffic;
 
 /// List of trust policies to setup ABAC controls
 pub mod trust_policy;
@@ -21,6 +22,7 @@ pub mod trust_policy;
 pub use access_control::*;
 pub(crate) use addresses::*;
 pub use api::*;
+pub use decryptor::MAX_SECURE_CHANNEL_PAYLOAD_SIZE;
 pub(crate) use handshake::*;
 pub(crate) use listener::*;
 pub use local_info::*;
@@ -28,11 +30,15 @@ pub use message::*;
 pub use options::*;
 pub use registry::*;
 pub(crate) use role::*;
+pub use traffic::*;
 pub use trust_policy::*;
 
 #[cfg(test)]
 mod tests {
-    use crate::secure_channel::{decryptor::Decryptor, encryptor::Encryptor};
+    use crate::secure_channel::decryptor::{Decryptor, MAX_SECURE_CHANNEL_PAYLOAD_SIZE};
+    use crate::secure_channel::encryptor::Encryptor;
+    use core::sync::atomic::Ordering;
+    use core::time::Duration;
     use ockam_core::compat::rand::RngCore;
     use ockam_core::Result;
     use ockam_vault::{SoftwareVaultForSecureChannels, VaultForSecureChannels};
@@ -67,6 +73,28 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_encrypt_decrypt_with_rekey_interval() {
+        let (encryptor, mut decryptor) = create_encryptor_decryptor().await.unwrap();
+        // the key is renewed for each message after the first one
+        let mut encryptor = encryptor.with_rekey_interval(Duration::ZERO).unwrap();
+        let rekeys = encryptor.rekeys();
+
+        let mut in_flight = Vec::new();
+        for n in 0..10 {
+            let msg = vec![n];
+            let mut ciphertext = Vec::new();
+            encryptor.encrypt(&mut ciphertext, &msg).await.unwrap();
+            in_flight.push((msg, ciphertext));
+        }
+        assert_eq!(rekeys.load(Ordering::Relaxed), 9);
+
+        // the messages encrypted before a rekey are still decrypted after it
+        for (plaintext, ciphertext) in in_flight.iter() {
+            assert_eq!(plaintext, &decryptor.decrypt(ciphertext).await.unwrap());
+        }
+    }
+
     #[tokio::test]
     async fn test_encrypt_decrypt_out_of_order() {
         let (mut encryptor, mut decryptor) = create_encryptor_decryptor().await.unwrap();
@@ -128,6 +156,27 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_decrypt_rejects_oversized_payloads() {
+        let (mut encryptor, mut decryptor) = create_encryptor_decryptor().await.unwrap();
+
+        let msg = vec![0; MAX_SECURE_CHANNEL_PAYLOAD_SIZE];
+        let mut ciphertext = Vec::new();
+        encryptor.encrypt(&mut ciphertext, &msg).await.unwrap();
+        assert_eq!(msg, decryptor.decrypt(&ciphertext).await.unwrap());
+
+        let msg = vec![0; MAX_SECURE_CHANNEL_PAYLOAD_SIZE + 1];
+        let mut ciphertext = Vec::new();
+        encryptor.encrypt(&mut ciphertext, &msg).await.unwrap();
+        assert!(decryptor.decrypt(&ciphertext).await.is_err());
+
+        // The decryptor can still decrypt the next messages
+        let msg = vec![1, 2, 3];
+        let mut ciphertext = Vec::new();
+        encryptor.encrypt(&mut ciphertext, &msg).await.unwrap();
+        assert_eq!(msg, decryptor.decrypt(&ciphertext).await.unwrap());
+    }
+
     async fn create_encryptor_decryptor() -> Result<(Encryptor, Decryptor)> {
         let vault1 = SoftwareVaultForSecureChannels::create().await?;
         let vault2 = SoftwareVaultForSecureChannels::create().await?;
@@ -1,6 +1,8 @@
 use ockam_core::compat::sync::Arc;
 use ockam_core::compat::vec::Vec;
 use ockam_core::flow_control::{FlowControlId, FlowControlOutgoingAccessControl, FlowControls};
+#[cfg(feature = "std")]
+use ockam_core::RateLimiter;
 use ockam_core::{Address, OutgoingAccessControl, Result};
 
 use crate::models::CredentialAndPurposeKey;
@@ -26,6 +28,8 @@ pub struct SecureChannelOptions {
     // To obtain our credentials
     pub(crate) credential_retriever_creator: Option<Arc<dyn CredentialRetrieverCreator>>,
     pub(crate) timeout: Duration,
+    // To renew the keys of the channel periodically
+    pub(crate) rekey_interval: Option<Duration>,
 }
 
 impl fmt::Debug for SecureChannelOptions {
@@ -48,6 +52,7 @@ impl SecureChannelOptions {
             authority: None,
             credential_retriever_creator: None,
             timeout: DEFAULT_TIMEOUT,
+            rekey_interval: None,
         }
     }
 
@@ -57,6 +62,15 @@ impl SecureChannelOptions {
         self
     }
 
+    /// Renew the encryption key of the channel once `rekey_interval` has elapsed,
+    /// in addition to the renewal which happens after a fixed number of messages
+    /// The interval is sent during the handshake and both parties renew their keys with the
+    /// shortest of their intervals
+    pub fn with_rekey_interval(mut self, rekey_interval: Duration) -> Self {
+        self.rekey_interval = Some(rekey_interval);
+        self
+    }
+
     /// Set [`CredentialRetrieverCreator`]
     pub fn with_credential_retriever_creator(
         mut self,
@@ -144,6 +158,11 @@ pub struct SecureChannelListenerOptions {
     pub(crate) authority: Option<Identifier>,
     // To obtain our credentials
     pub(crate) credential_retriever_creator: Option<Arc<dyn CredentialRetrieverCreator>>,
+    // To renew the keys of the spawned channels periodically
+    pub(crate) rekey_interval: Option<Duration>,
+    // To drop the handshakes exceeding a rate limit
+    #[cfg(feature = "std")]
+    pub(crate) handshake_rate_limiter: Option<RateLimiter>,
 }
 
 impl fmt::Debug for SecureChannelListenerOptions {
@@ -164,6 +183,9 @@ impl SecureChannelListenerOptions {
             trust_policy: Arc::new(TrustEveryonePolicy),
             authority: None,
             credential_retriever_creator: None,
+            rekey_interval: None,
+            #[cfg(feature = "std")]
+            handshake_rate_limiter: None,
         }
     }
 
@@ -207,6 +229,29 @@ impl SecureChannelListenerOptions {
         self
     }
 
+    /// Renew the encryption key of the spawned channels once `rekey_interval` has elapsed,
+    /// in addition to the renewal which happens after a fixed number of messages
+    /// The interval is sent during the handshake and both parties renew their keys with the
+    /// shortest of their intervals
+    pub fn with_rekey_interval(mut self, rekey_interval: Duration) -> Self {
+        self.rekey_interval = Some(rekey_interval);
+        self
+    }
+
+    /// Start at most `max_handshakes_per_second` new channels per second.
+    /// The first handshake messages exceeding this limit are dropped
+    #[cfg(feature = "std")]
+    pub fn with_max_handshakes_per_second(mut self, max_handshakes_per_second: u32) -> Self {
+        self.handshake_rate_limiter = Some(RateLimiter::new(max_handshakes_per_second));
+        self
+    }
+
+    /// Limiter of the handshakes rate, which can be kept to inspect its utilization
+    #[cfg(feature = "std")]
+    pub fn handshake_rate_limiter(&self) -> Option<RateLimiter> {
+        self.handshake_rate_limiter.clone()
+    }
+
     /// Freshly generated [`FlowControlId`]
     pub fn spawner_flow_control_id(&self) -> FlowControlId {
         self.flow_control_id.clone()
@@ -1,10 +1,11 @@
+use core::sync::atomic::{AtomicUsize, Ordering};
 use ockam_core::compat::collections::BTreeMap;
 use ockam_core::compat::sync::{Arc, RwLock};
 use ockam_core::compat::vec::Vec;
-use ockam_core::{Address, Result};
+use ockam_core::{Address, Result, Route};
 
-use crate::models::Identifier;
-use crate::IdentityError;
+use crate::models::{Identifier, TimestampInSeconds};
+use crate::{IdentityError, SecureChannelTraffic};
 
 /// Known information about particular SecureChannel
 #[derive(Clone, Debug)]
@@ -17,6 +18,11 @@ pub struct SecureChannelRegistryEntry {
     my_id: Identifier,
     their_id: Identifier,
     their_decryptor_address: Address,
+    their_route: Route,
+    created_at: TimestampInSeconds,
+    their_credential_verified: bool,
+    rekeys: Arc<AtomicUsize>,
+    traffic: SecureChannelTraffic,
 }
 
 impl SecureChannelRegistryEntry {
@@ -31,6 +37,11 @@ impl SecureChannelRegistryEntry {
         my_id: Identifier,
         their_id: Identifier,
         their_decryptor_address: Address,
+        their_route: Route,
+        created_at: TimestampInSeconds,
+        their_credential_verified: bool,
+        rekeys: Arc<AtomicUsize>,
+        traffic: SecureChannelTraffic,
     ) -> Self {
         Self {
             encryptor_messaging_address,
@@ -41,6 +52,11 @@ impl SecureChannelRegistryEntry {
             my_id,
             their_id,
             their_decryptor_address,
+            their_route,
+            created_at,
+            their_credential_verified,
+            rekeys,
+            traffic,
         }
     }
 
@@ -83,10 +99,35 @@ impl SecureChannelRegistryEntry {
     pub fn their_decryptor_address(&self) -> Address {
         self.their_decryptor_address.clone()
     }
+
+    /// Route to their `Decryptor`, negotiated during the handshake
+    pub fn their_route(&self) -> &Route {
+        &self.their_route
+    }
+
+    /// Time of the creation of the channel
+    pub fn created_at(&self) -> TimestampInSeconds {
+        self.created_at
+    }
+
+    /// True if they presented a credential which was verified with our authority
+    pub fn their_credential_verified(&self) -> bool {
+        self.their_credential_verified
+    }
+
+    /// Number of times our encryption key was renewed since the creation of the channel
+    pub fn rekeys(&self) -> u64 {
+        self.rekeys.load(Ordering::Relaxed) as u64
+    }
+
+    /// Messages and bytes exchanged through the channel since its creation
+    pub fn traffic(&self) -> &SecureChannelTraffic {
+        &self.traffic
+    }
 }
 
 /// Registry of all known Secure Channels
-#[derive(Clone, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct SecureChannelRegistry {
     // Encryptor address is used as a key
     registry: Arc<RwLock<BTreeMap<Address, SecureChannelRegistryEntry>>>,
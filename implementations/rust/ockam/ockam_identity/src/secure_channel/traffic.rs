@@ -0,0 +1,80 @@
+use core::sync::atomic::{AtomicUsize, Ordering};
+use ockam_core::compat::sync::Arc;
+
+use crate::models::TimestampInSeconds;
+use crate::utils::now;
+
+/// Number of messages and bytes exchanged through a secure channel.
+///
+/// Only the payloads sent by the workers using the channel are counted, not the messages
+/// exchanged by the channel itself to close it or to refresh the credentials.
+/// The counters are shared by the encryptor and the decryptor of the channel.
+#[derive(Clone, Debug, Default)]
+pub struct SecureChannelTraffic {
+    counters: Arc<TrafficCounters>,
+}
+
+#[derive(Debug, Default)]
+struct TrafficCounters {
+    messages_sent: AtomicUsize,
+    bytes_sent: AtomicUsize,
+    messages_received: AtomicUsize,
+    bytes_received: AtomicUsize,
+    last_activity_at: AtomicUsize,
+}
+
+impl SecureChannelTraffic {
+    /// Count a payload of `bytes` bytes sent to the other party
+    pub(crate) fn add_sent(&self, bytes: usize) {
+        self.counters.messages_sent.fetch_add(1, Ordering::Relaxed);
+        self.counters.bytes_sent.fetch_add(bytes, Ordering::Relaxed);
+        self.touch();
+    }
+
+    /// Count a payload of `bytes` bytes received from the other party
+    pub(crate) fn add_received(&self, bytes: usize) {
+        self.counters
+            .messages_received
+            .fetch_add(1, Ordering::Relaxed);
+        self.counters
+            .bytes_received
+            .fetch_add(bytes, Ordering::Relaxed);
+        self.touch();
+    }
+
+    fn touch(&self) {
+        if let Ok(now) = now() {
+            self.counters
+                .last_activity_at
+                .store(now.0 as usize, Ordering::Relaxed);
+        }
+    }
+
+    /// Number of payloads sent to the other party
+    pub fn messages_sent(&self) -> u64 {
+        self.counters.messages_sent.load(Ordering::Relaxed) as u64
+    }
+
+    /// Number of bytes sent to the other party, before encryption
+    pub fn bytes_sent(&self) -> u64 {
+        self.counters.bytes_sent.load(Ordering::Relaxed) as u64
+    }
+
+    /// Number of payloads received from the other party
+    pub fn messages_received(&self) -> u64 {
+        self.counters.messages_received.load(Ordering::Relaxed) as u64
+    }
+
+    /// Number of bytes received from the other party, once decrypted
+    pub fn bytes_received(&self) -> u64 {
+        self.counters.bytes_received.load(Ordering::Relaxed) as u64
+    }
+
+    /// Time of the last payload sent or received, if any
+    pub fn last_activity_at(&self) -> Option<TimestampInSeconds> {
+        match self.counters.last_activity_at.load(Ordering::Relaxed) {
+            0 => None,
+            at => Some(TimestampInSeconds(at as u64)),
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::string::String;
+use ockam_core::compat::string::ToString;
+use ockam_core::compat::sync::{Arc, RwLock};
+use ockam_core::{async_trait, compat::vec::Vec, Result};
+use tracing::info;
+
+use crate::models::Identifier;
+use crate::trust_policy::{SecureChannelTrustInfo, TrustPolicy};
+
+/// `TrustPolicy` based on a list of `Identifier`s which can be updated while it is used.
+///
+/// When no list is set, any participant is trusted. The changes apply to the handshakes
+/// which are checked after them, the secure channels which were already established are
+/// not affected.
+#[derive(Clone, Default)]
+pub struct TrustAllowlistPolicy {
+    identity_ids: Arc<RwLock<Option<Vec<Identifier>>>>,
+}
+
+impl TrustAllowlistPolicy {
+    /// Constructor
+    pub fn new(identity_ids: Option<Vec<Identifier>>) -> Self {
+        Self {
+            identity_ids: Arc::new(RwLock::new(identity_ids)),
+        }
+    }
+
+    /// Current list of trusted `Identifier`s, None if any participant is trusted
+    pub fn identity_ids(&self) -> Option<Vec<Identifier>> {
+        self.identity_ids.read().unwrap().clone()
+    }
+
+    /// Replace the list of trusted `Identifier`s
+    pub fn set(&self, identity_ids: Option<Vec<Identifier>>) {
+        *self.identity_ids.write().unwrap() = identity_ids;
+    }
+
+    /// Trust an additional `Identifier`.
+    /// If any participant was trusted, only this `Identifier` is trusted from now on
+    pub fn add(&self, identity_id: Identifier) {
+        let mut identity_ids = self.identity_ids.write().unwrap();
+        let identity_ids = identity_ids.get_or_insert_with(Vec::new);
+        if !identity_ids.contains(&identity_id) {
+            identity_ids.push(identity_id)
+        }
+    }
+
+    /// Stop trusting an `Identifier`
+    pub fn remove(&self, identity_id: &Identifier) {
+        if let Some(identity_ids) = self.identity_ids.write().unwrap().as_mut() {
+            identity_ids.retain(|i| i != identity_id)
+        }
+    }
+}
+
+#[async_trait]
+impl TrustPolicy for TrustAllowlistPolicy {
+    async fn check(&self, trust_info: &SecureChannelTrustInfo) -> Result<bool> {
+        let identity_ids = self.identity_ids.read().unwrap();
+        match identity_ids.as_ref() {
+            Some(identity_ids) if !identity_ids.contains(trust_info.their_identity_id()) => {
+                info!(
+                    "{} is not one of the trusted identifiers {}",
+                    trust_info.their_identity_id(),
+                    identity_ids
+                        .iter()
+                        .map(|i| i.to_string())
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                );
+                Ok(false)
+            }
+            _ => Ok(true),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test() {
+        let alice = Identifier::try_from(
+            "Iabababababababababababababababababababababababababababababababab",
+        )
+        .unwrap();
+        let bob = Identifier::try_from(
+            "Icdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcd",
+        )
+        .unwrap();
+        let alice_info = SecureChannelTrustInfo::new(alice.clone());
+        let bob_info = SecureChannelTrustInfo::new(bob.clone());
+
+        // no list, everyone is trusted
+        let policy = TrustAllowlistPolicy::new(None);
+        assert!(policy.check(&alice_info).await.unwrap());
+        assert!(policy.check(&bob_info).await.unwrap());
+
+        // the changes are visible from the clones used by the listener
+        let used = policy.clone();
+        policy.add(alice.clone());
+        assert!(used.check(&alice_info).await.unwrap());
+        assert!(!used.check(&bob_info).await.unwrap());
+
+        policy.add(bob.clone());
+        policy.remove(&alice);
+        assert_eq!(policy.identity_ids(), Some(vec![bob.clone()]));
+        assert!(!used.check(&alice_info).await.unwrap());
+        assert!(used.check(&bob_info).await.unwrap());
+
+        policy.set(Some(vec![]));
+        assert!(!used.check(&bob_info).await.unwrap());
+        policy.set(None);
+        assert!(used.check(&bob_info).await.unwrap());
+    }
+}
@@ -1,7 +1,10 @@
 use core::fmt;
 use core::fmt::Formatter;
 use ockam_core::flow_control::FlowControlId;
-use ockam_core::Address;
+use ockam_core::{Address, Route};
+
+use crate::models::{Identifier, TimestampInSeconds};
+use crate::{SecureChannelRegistry, SecureChannelRegistryEntry};
 
 /// Result of [`super::SecureChannels::create_secure_channel()`] call.
 #[derive(Debug, Clone)]
@@ -9,6 +12,7 @@ pub struct SecureChannel {
     encryptor_address: Address,
     encryptor_api_address: Address,
     flow_control_id: FlowControlId,
+    registry: SecureChannelRegistry,
 }
 
 impl From<SecureChannel> for Address {
@@ -33,11 +37,13 @@ impl SecureChannel {
         encryptor_address: Address,
         encryptor_api_address: Address,
         flow_control_id: FlowControlId,
+        registry: SecureChannelRegistry,
     ) -> Self {
         Self {
             encryptor_address,
             encryptor_api_address,
             flow_control_id,
+            registry,
         }
     }
     /// [`Address`] of the corresponding`EncryptorWorker` Worker that can be used in a route
@@ -54,6 +60,126 @@ impl SecureChannel {
     pub fn encryptor_api_address(&self) -> &Address {
         &self.encryptor_api_address
     }
+    /// Metadata of the channel, established during the handshake.
+    /// Return None if the channel was stopped
+    pub fn info(&self) -> Option<SecureChannelInfo> {
+        self.registry
+            .get_channel_by_encryptor_address(&self.encryptor_address)
+            .map(SecureChannelInfo::from)
+    }
+}
+
+/// Metadata of an established [`SecureChannel`], returned by [`SecureChannel::info()`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecureChannelInfo {
+    my_identifier: Identifier,
+    their_identifier: Identifier,
+    route: Route,
+    created_at: TimestampInSeconds,
+    is_initiator: bool,
+    their_credential_verified: bool,
+    rekeys: u64,
+    messages_sent: u64,
+    bytes_sent: u64,
+    messages_received: u64,
+    bytes_received: u64,
+    last_activity_at: Option<TimestampInSeconds>,
+    encryptor_address: Address,
+    encryptor_api_address: Address,
+    decryptor_address: Address,
+    decryptor_api_address: Address,
+}
+
+impl From<SecureChannelRegistryEntry> for SecureChannelInfo {
+    fn from(entry: SecureChannelRegistryEntry) -> Self {
+        Self {
+            my_identifier: entry.my_id().clone(),
+            their_identifier: entry.their_id().clone(),
+            route: entry.their_route().clone(),
+            created_at: entry.created_at(),
+            is_initiator: entry.is_initiator(),
+            their_credential_verified: entry.their_credential_verified(),
+            rekeys: entry.rekeys(),
+            messages_sent: entry.traffic().messages_sent(),
+            bytes_sent: entry.traffic().bytes_sent(),
+            messages_received: entry.traffic().messages_received(),
+            bytes_received: entry.traffic().bytes_received(),
+            last_activity_at: entry.traffic().last_activity_at(),
+            encryptor_address: entry.encryptor_messaging_address().clone(),
+            encryptor_api_address: entry.encryptor_api_address().clone(),
+            decryptor_address: entry.decryptor_messaging_address().clone(),
+            decryptor_api_address: entry.decryptor_api_address().clone(),
+        }
+    }
+}
+
+impl SecureChannelInfo {
+    /// [`Identifier`] of our side of the channel
+    pub fn my_identifier(&self) -> &Identifier {
+        &self.my_identifier
+    }
+    /// [`Identifier`] of the other party, verified during the handshake
+    pub fn their_identifier(&self) -> &Identifier {
+        &self.their_identifier
+    }
+    /// [`Route`] to the `Decryptor` of the other party
+    pub fn route(&self) -> &Route {
+        &self.route
+    }
+    /// Time of the creation of the channel
+    pub fn created_at(&self) -> TimestampInSeconds {
+        self.created_at
+    }
+    /// True if we initiated the channel
+    pub fn is_initiator(&self) -> bool {
+        self.is_initiator
+    }
+    /// True if the other party presented a credential which was verified with the
+    /// authority of the channel, e.g. the authority of a project
+    pub fn their_credential_verified(&self) -> bool {
+        self.their_credential_verified
+    }
+    /// Number of times our encryption key was renewed, either after a fixed number of
+    /// messages or after the rekey interval of the channel
+    pub fn rekeys(&self) -> u64 {
+        self.rekeys
+    }
+    /// Number of payloads sent to the other party
+    pub fn messages_sent(&self) -> u64 {
+        self.messages_sent
+    }
+    /// Number of bytes sent to the other party, before encryption
+    pub fn bytes_sent(&self) -> u64 {
+        self.bytes_sent
+    }
+    /// Number of payloads received from the other party
+    pub fn messages_received(&self) -> u64 {
+        self.messages_received
+    }
+    /// Number of bytes received from the other party, once decrypted
+    pub fn bytes_received(&self) -> u64 {
+        self.bytes_received
+    }
+    /// Time of the last payload sent or received through the channel, if any
+    pub fn last_activity_at(&self) -> Option<TimestampInSeconds> {
+        self.last_activity_at
+    }
+    /// [`Address`] of the `EncryptorWorker`
+    pub fn encryptor_address(&self) -> &Address {
+        &self.encryptor_address
+    }
+    /// API [`Address`] of the `EncryptorWorker`
+    pub fn encryptor_api_address(&self) -> &Address {
+        &self.encryptor_api_address
+    }
+    /// [`Address`] of the `Decryptor`, receiving the messages of the other party
+    pub fn decryptor_address(&self) -> &Address {
+        &self.decryptor_address
+    }
+    /// API [`Address`] of the `Decryptor`
+    pub fn decryptor_api_address(&self) -> &Address {
+        &self.decryptor_api_address
+    }
 }
 
 /// Result of [`super::SecureChannels::create_secure_channel_listener()`] call.
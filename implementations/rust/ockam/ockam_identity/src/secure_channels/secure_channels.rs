@@ -134,6 +134,7 @@ impl SecureChannels {
             options.authority,
             Some(route),
             Some(options.timeout),
+            options.rekey_interval,
             Role::Initiator,
         )
         .await?;
@@ -142,6 +143,7 @@ impl SecureChannels {
             addresses.encryptor,
             addresses.encryptor_api,
             flow_control_id,
+            self.secure_channel_registry(),
         ))
     }
 
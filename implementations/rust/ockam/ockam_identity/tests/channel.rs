@@ -158,7 +158,7 @@ async fn test_channel_send_credentials(context: &mut Context) -> Result<()> {
         )
         .await?;
 
-    let _alice_channel = secure_channels
+    let alice_channel = secure_channels
         .create_secure_channel(
             context,
             &alice,
@@ -169,6 +169,10 @@ async fn test_channel_send_credentials(context: &mut Context) -> Result<()> {
         )
         .await?;
 
+    let alice_channel_info = alice_channel.info().unwrap();
+    assert_eq!(alice_channel_info.their_identifier(), &bob);
+    assert!(alice_channel_info.their_credential_verified());
+
     context.sleep(Duration::from_millis(250)).await;
 
     let alice_attributes = secure_channels
@@ -328,6 +332,177 @@ async fn test_channel_send_multiple_messages_both_directions(ctx: &mut Context)
     Ok(())
 }
 
+#[ockam_macros::test]
+async fn test_channel_rekey_interval(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+    let identities_creation = secure_channels.identities().identities_creation();
+
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    let bob_options = SecureChannelListenerOptions::new();
+    let sc_listener_flow_control_id = bob_options.spawner_flow_control_id();
+    secure_channels
+        .create_secure_channel_listener(ctx, &bob, "bob_listener", bob_options)
+        .await?;
+
+    // renew the key of alice for each message
+    let alice_options = SecureChannelOptions::new().with_rekey_interval(Duration::ZERO);
+    let alice_channel = secure_channels
+        .create_secure_channel(ctx, &alice, route!["bob_listener"], alice_options)
+        .await?;
+    assert_eq!(alice_channel.info().unwrap().rekeys(), 0);
+
+    let mut child_ctx = ctx
+        .new_detached_with_mailboxes(Mailboxes::main(
+            "child",
+            Arc::new(AllowAll),
+            Arc::new(AllowAll),
+        ))
+        .await?;
+    child_ctx
+        .flow_controls()
+        .add_consumer(child_ctx.address(), &sc_listener_flow_control_id);
+
+    // send all the messages before receiving them, so that they are in flight during the rekeys
+    for n in 0..10 {
+        child_ctx
+            .send(
+                route![alice_channel.clone(), child_ctx.address()],
+                format!("Hello, Bob! {}", n),
+            )
+            .await?;
+    }
+    let mut return_route = route![];
+    for n in 0..10 {
+        let message = child_ctx.receive::<String>().await?;
+        return_route = message.return_route();
+        assert_eq!(format!("Hello, Bob! {}", n), message.into_body()?);
+    }
+
+    assert!(alice_channel.info().unwrap().rekeys() >= 9);
+
+    // the rekey interval of alice also applies to the keys of bob
+    child_ctx
+        .flow_controls()
+        .add_consumer(child_ctx.address(), alice_channel.flow_control_id());
+    for n in 0..10 {
+        child_ctx
+            .send(return_route.clone(), format!("Hello, Alice! {}", n))
+            .await?;
+    }
+    for n in 0..10 {
+        let message = child_ctx.receive::<String>().await?;
+        assert_eq!(format!("Hello, Alice! {}", n), message.into_body()?);
+    }
+
+    let bob_channel = return_route.next().unwrap().clone();
+    let bob_channel = secure_channels
+        .secure_channel_registry()
+        .get_channel_by_encryptor_address(&bob_channel)
+        .unwrap();
+    assert!(bob_channel.rekeys() >= 9);
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn test_channel_traffic_counters(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+    let identities_creation = secure_channels.identities().identities_creation();
+
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    let bob_options = SecureChannelListenerOptions::new();
+    let sc_listener_flow_control_id = bob_options.spawner_flow_control_id();
+    secure_channels
+        .create_secure_channel_listener(ctx, &bob, "bob_listener", bob_options)
+        .await?;
+
+    let alice_channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            &alice,
+            route!["bob_listener"],
+            SecureChannelOptions::new(),
+        )
+        .await?;
+    let info = alice_channel.info().unwrap();
+    assert_eq!(info.messages_sent(), 0);
+    assert_eq!(info.last_activity_at(), None);
+
+    let mut child_ctx = ctx
+        .new_detached_with_mailboxes(Mailboxes::main(
+            "child",
+            Arc::new(AllowAll),
+            Arc::new(AllowAll),
+        ))
+        .await?;
+    child_ctx
+        .flow_controls()
+        .add_consumer(child_ctx.address(), &sc_listener_flow_control_id);
+
+    for n in 0..3 {
+        child_ctx
+            .send(
+                route![alice_channel.clone(), child_ctx.address()],
+                format!("Hello, Bob! {}", n),
+            )
+            .await?;
+        child_ctx.receive::<String>().await?;
+    }
+
+    let alice_info = alice_channel.info().unwrap();
+    assert_eq!(alice_info.messages_sent(), 3);
+    assert!(alice_info.bytes_sent() > 0);
+    assert_eq!(alice_info.messages_received(), 0);
+    assert!(alice_info.last_activity_at().is_some());
+
+    // the channel accepted by bob counts the same messages as received
+    let bob_entry = secure_channels
+        .secure_channel_registry()
+        .get_channel_list()
+        .into_iter()
+        .find(|entry| !entry.is_initiator())
+        .unwrap();
+    assert_eq!(bob_entry.traffic().messages_received(), 3);
+    assert_eq!(
+        bob_entry.traffic().bytes_received(),
+        alice_info.bytes_sent()
+    );
+    assert_eq!(bob_entry.traffic().messages_sent(), 0);
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn test_channel_listener_handshake_rate_limit(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+    let identities_creation = secure_channels.identities().identities_creation();
+
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    let bob_options = SecureChannelListenerOptions::new().with_max_handshakes_per_second(1);
+    let rate_limiter = bob_options.handshake_rate_limiter().unwrap();
+    secure_channels
+        .create_secure_channel_listener(ctx, &bob, "bob_listener", bob_options)
+        .await?;
+
+    let options = || SecureChannelOptions::new().with_timeout(Duration::from_millis(500));
+    secure_channels
+        .create_secure_channel(ctx, &alice, route!["bob_listener"], options())
+        .await?;
+    assert_eq!(rate_limiter.utilization(), 1);
+
+    // the first message of the second handshake is dropped
+    let result = secure_channels
+        .create_secure_channel(ctx, &alice, route!["bob_listener"], options())
+        .await;
+    assert!(result.is_err());
+    assert!(rate_limiter.rejections().total() >= 1);
+    Ok(())
+}
+
 #[ockam_macros::test]
 async fn test_channel_registry(ctx: &mut Context) -> Result<()> {
     let secure_channels = secure_channels().await?;
@@ -363,6 +538,20 @@ async fn test_channel_registry(ctx: &mut Context) -> Result<()> {
     assert_eq!(alice_channel_data.my_id(), &alice);
     assert_eq!(alice_channel_data.their_id(), &bob);
 
+    let alice_channel_info = alice_channel.info().unwrap();
+    assert!(alice_channel_info.is_initiator());
+    assert_eq!(alice_channel_info.my_identifier(), &alice);
+    assert_eq!(alice_channel_info.their_identifier(), &bob);
+    assert!(!alice_channel_info.their_credential_verified());
+    assert_eq!(
+        alice_channel_info.encryptor_address(),
+        alice_channel.encryptor_address()
+    );
+    assert_eq!(
+        alice_channel_info.route(),
+        &route![alice_channel_data.their_decryptor_address()]
+    );
+
     let mut bob_ctx = ctx
         .new_detached_with_mailboxes(Mailboxes::main(
             "bob",
@@ -6,10 +6,10 @@ use ockam_core::{async_trait, Any, DenyAll};
 use ockam_core::{route, Result, Routed, Worker};
 use ockam_identity::models::CredentialSchemaIdentifier;
 use ockam_identity::secure_channels::secure_channels;
-use ockam_identity::utils::AttributesBuilder;
+use ockam_identity::utils::{now, AttributesBuilder};
 use ockam_identity::{
-    CredentialAccessControl, SecureChannelListenerOptions, SecureChannelOptions,
-    TrustIdentifierPolicy,
+    identities, CredentialAccessControl, CredentialsVerification, SecureChannelListenerOptions,
+    SecureChannelOptions, TrustIdentifierPolicy,
 };
 use ockam_node::{Context, WorkerBuilder};
 
@@ -71,6 +71,45 @@ async fn full_flow_oneway(ctx: &mut Context) -> Result<()> {
     Ok(())
 }
 
+#[ockam_macros::test]
+async fn verify_credential_at_a_given_time(_ctx: &mut Context) -> Result<()> {
+    let identities = identities().await?;
+    let identities_creation = identities.identities_creation();
+    let credentials = identities.credentials();
+
+    let authority = identities_creation.create_identity().await?;
+    let client = identities_creation.create_identity().await?;
+
+    let credential = credentials
+        .credentials_creation()
+        .issue_credential(
+            &authority,
+            &client,
+            AttributesBuilder::with_schema(CredentialSchemaIdentifier(0)).build(),
+            Duration::from_secs(60 * 60),
+        )
+        .await?;
+
+    let authorities = [authority];
+    let verify_at = |time| {
+        CredentialsVerification::verify_credential_static_at(
+            credentials.purpose_keys().purpose_keys_verification(),
+            identities.vault().verifying_vault,
+            Some(&client),
+            &authorities,
+            &credential,
+            time,
+        )
+    };
+
+    let now = now()?;
+    assert!(verify_at(now).await.is_ok());
+    assert!(verify_at(now + 30 * 60).await.is_ok());
+    assert!(verify_at(now + 2 * 60 * 60).await.is_err());
+
+    Ok(())
+}
+
 #[ockam_macros::test]
 async fn full_flow_twoway(ctx: &mut Context) -> Result<()> {
     let secure_channels = secure_channels().await?;
@@ -27,7 +27,7 @@ use once_cell::race::OnceBox;
 use std::net::{SocketAddrV4, SocketAddrV6};
 use tinyvec::{Array, ArrayVec, TinyVec};
 
-use crate::proto::{DnsAddr, Ip4, Ip6, Tcp};
+use crate::proto::{Dns, DnsAddr, Ip4, Ip6, Tcp};
 pub use error::Error;
 use ockam_core::env::FromString;
 pub use registry::{Registry, RegistryBuilder};
@@ -609,6 +609,15 @@ impl MultiAddr {
                     let port = it.next().unwrap().cast::<Tcp>().unwrap();
                     return Ok(SocketAddrV6::new(*ip6, *port, 0, 0).to_string());
                 }
+                Dns::CODE => {
+                    let host = p.cast::<Dns>().unwrap();
+                    if let Some(p) = it.peek() {
+                        if p.code() == Tcp::CODE {
+                            let port = p.cast::<Tcp>().unwrap();
+                            return Ok(format!("{}:{}", &*host, *port));
+                        }
+                    }
+                }
                 DnsAddr::CODE => {
                     let host = p.cast::<DnsAddr>().unwrap();
                     if let Some(p) = it.peek() {
@@ -798,8 +807,26 @@ where
 
 #[cfg(test)]
 mod tests {
+    use crate::proto::{Dns, Tcp};
+    use crate::MultiAddr;
+    use core::str::FromStr;
     use tinyvec::TinyVec;
 
+    #[test]
+    fn dns() {
+        let addr = MultiAddr::from_str("/dns/outlet.internal/tcp/5432/service/outlet").unwrap();
+        assert_eq!(
+            addr.to_string(),
+            "/dns/outlet.internal/tcp/5432/service/outlet"
+        );
+        assert_eq!(
+            &*addr.first().unwrap().cast::<Dns>().unwrap(),
+            "outlet.internal"
+        );
+        assert_eq!(*addr.iter().nth(1).unwrap().cast::<Tcp>().unwrap(), 5432);
+        assert_eq!(addr.to_socket_addr().unwrap(), "outlet.internal:5432");
+    }
+
     #[test]
     fn split_off() {
         let mut t: TinyVec<[u8; 5]> = TinyVec::new();
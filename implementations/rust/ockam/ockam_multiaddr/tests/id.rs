@@ -1,5 +1,7 @@
 use core::fmt;
-use ockam_multiaddr::proto::{DnsAddr, Ip4, Ip6, Node, Project, Secure, Service, Space, Tcp};
+use ockam_multiaddr::proto::{
+    Channel, Dns, DnsAddr, Ip4, Ip6, Node, Project, Secure, Service, Space, Tcp, Udp, Ws, Wss,
+};
 use ockam_multiaddr::{Code, Match, MultiAddr, Protocol};
 use quickcheck::{quickcheck, Arbitrary, Gen};
 use rand::distributions::{Alphanumeric, DistString};
@@ -100,6 +102,14 @@ quickcheck! {
                         addr.push_back(Tcp::new(0)).unwrap();
                         prot.push_back(Tcp::CODE);
                     }
+                    Udp::CODE => {
+                        addr.push_back(Udp::new(0)).unwrap();
+                        prot.push_back(Udp::CODE);
+                    }
+                    Dns::CODE => {
+                        addr.push_back(Dns::new("localhost")).unwrap();
+                        prot.push_back(Dns::CODE);
+                    }
                     DnsAddr::CODE => {
                         addr.push_back(DnsAddr::new("localhost")).unwrap();
                         prot.push_back(DnsAddr::CODE);
@@ -116,6 +126,18 @@ quickcheck! {
                         addr.push_back(Secure::new("secure")).unwrap();
                         prot.push_back(Secure::CODE)
                     }
+                    Channel::CODE => {
+                        addr.push_back(Channel::new("channel")).unwrap();
+                        prot.push_back(Channel::CODE)
+                    }
+                    Ws::CODE => {
+                        addr.push_back(Ws::new("localhost")).unwrap();
+                        prot.push_back(Ws::CODE)
+                    }
+                    Wss::CODE => {
+                        addr.push_back(Wss::new("localhost")).unwrap();
+                        prot.push_back(Wss::CODE)
+                    }
                     Service::CODE => {
                         addr.push_back(Service::new("service")).unwrap();
                         prot.push_back(Service::CODE);
@@ -145,10 +167,15 @@ quickcheck! {
 
 const PROTOS: &[Code] = &[
     Tcp::CODE,
+    Udp::CODE,
+    Dns::CODE,
     DnsAddr::CODE,
     Ip4::CODE,
     Ip6::CODE,
     Secure::CODE,
+    Channel::CODE,
+    Ws::CODE,
+    Wss::CODE,
     Service::CODE,
     Node::CODE,
     Project::CODE,
@@ -161,10 +188,15 @@ impl Arbitrary for Addr {
         for _ in 0..g.size() {
             match *g.choose(PROTOS).unwrap() {
                 Tcp::CODE => a.push_back(Tcp::new(u16::arbitrary(g))).unwrap(),
+                Udp::CODE => a.push_back(Udp::new(u16::arbitrary(g))).unwrap(),
+                Dns::CODE => a.push_back(Dns::new(gen_hostname())).unwrap(),
                 DnsAddr::CODE => a.push_back(DnsAddr::new(gen_hostname())).unwrap(),
                 Ip4::CODE => a.push_back(Ip4::new(Ipv4Addr::arbitrary(g))).unwrap(),
                 Ip6::CODE => a.push_back(Ip6::new(Ipv6Addr::arbitrary(g))).unwrap(),
                 Secure::CODE => a.push_back(Secure::new(gen_string())).unwrap(),
+                Channel::CODE => a.push_back(Channel::new(gen_string())).unwrap(),
+                Ws::CODE => a.push_back(Ws::new(gen_hostname())).unwrap(),
+                Wss::CODE => a.push_back(Wss::new(gen_hostname())).unwrap(),
                 Service::CODE => a.push_back(Service::new(gen_string())).unwrap(),
                 Project::CODE => a.push_back(Project::new(gen_string())).unwrap(),
                 Space::CODE => a.push_back(Space::new(gen_string())).unwrap(),
@@ -29,6 +29,8 @@ pub enum NodeMessage {
     },
     /// Return a list of all worker addresses
     ListWorkers(SmallSender<NodeReplyResult>),
+    /// Return the information about all workers and processors
+    ListWorkersInfo(SmallSender<NodeReplyResult>),
     /// Add an existing address to a cluster
     SetCluster(Address, String, SmallSender<NodeReplyResult>),
     /// Stop an existing worker
@@ -71,6 +73,7 @@ impl fmt::Display for NodeMessage {
         match self {
             NodeMessage::StartWorker { .. } => write!(f, "StartWorker"),
             NodeMessage::ListWorkers(_) => write!(f, "ListWorkers"),
+            NodeMessage::ListWorkersInfo(_) => write!(f, "ListWorkersInfo"),
             NodeMessage::SetCluster(_, _, _) => write!(f, "SetCluster"),
             NodeMessage::StopWorker(_, _, _) => write!(f, "StopWorker"),
             NodeMessage::StartProcessor { .. } => write!(f, "StartProcessor"),
@@ -148,6 +151,12 @@ impl NodeMessage {
         (Self::ListWorkers(tx), rx)
     }
 
+    /// Create a list workers info message and reply receiver
+    pub fn list_workers_info() -> (Self, SmallReceiver<NodeReplyResult>) {
+        let (tx, rx) = small_channel();
+        (Self::ListWorkersInfo(tx), rx)
+    }
+
     /// Create a set cluster message and reply receiver
     pub fn set_cluster(addr: Address, label: String) -> (Self, SmallReceiver<NodeReplyResult>) {
         let (tx, rx) = small_channel();
@@ -206,6 +215,8 @@ pub enum RouterReply {
     Ok,
     /// A list of worker addresses
     Workers(Vec<Address>),
+    /// A list of worker information
+    WorkersInfo(Vec<WorkerInfo>),
     /// Message sender to a specific worker
     Sender {
         /// The address a message is being sent to
@@ -221,6 +232,25 @@ pub enum RouterReply {
     Metadata(Option<AddressMetadata>),
 }
 
+/// Information about a worker or a processor registered in the router
+#[derive(Debug, Clone)]
+pub struct WorkerInfo {
+    /// Primary address
+    pub address: Address,
+    /// Additional addresses, for example the addresses of the other mailboxes of the worker
+    pub aliases: Vec<Address>,
+    /// True for a processor
+    pub processor: bool,
+    /// True for a detached context, which is not backed by a worker
+    pub detached: bool,
+    /// Number of messages routed to the worker since it was started
+    pub routed_messages: u64,
+    /// Number of messages waiting in the mailbox of the worker
+    pub pending_messages: usize,
+    /// Metadata of the addresses of the worker
+    pub metadata: Vec<AddressAndMetadata>,
+}
+
 /// Specify the type of node shutdown
 ///
 /// For most users `ShutdownType::Graceful()` is recommended.  The
@@ -310,6 +340,11 @@ impl RouterReply {
         Ok(Self::Workers(v))
     }
 
+    /// Return [RouterReply::WorkersInfo] for the given information
+    pub fn workers_info(v: Vec<WorkerInfo>) -> NodeReplyResult {
+        Ok(Self::WorkersInfo(v))
+    }
+
     /// Returns [RouterReply::TerminalAddress] for the given address
     pub fn terminal_address(address: Option<AddressAndMetadata>) -> NodeReplyResult {
         Ok(Self::TerminalAddress(address))
@@ -341,6 +376,14 @@ impl RouterReply {
         }
     }
 
+    /// Consume the wrapper and return [RouterReply::WorkersInfo]
+    pub fn take_workers_info(self) -> Result<Vec<WorkerInfo>> {
+        match self {
+            Self::WorkersInfo(w) => Ok(w),
+            _ => Err(NodeError::NodeState(NodeReason::Unknown).internal()),
+        }
+    }
+
     /// Consume the wrapper and return [RouterReply::State]
     pub fn take_state(self) -> Result<bool> {
         match self {
@@ -2,7 +2,7 @@ use crate::channel_types::{MessageSender, SmallSender};
 use crate::relay::CtrlSignal;
 use crate::{
     error::{NodeError, NodeReason},
-    NodeReplyResult, RouterReply,
+    NodeReplyResult, RouterReply, WorkerInfo,
 };
 use core::sync::atomic::{AtomicUsize, Ordering};
 use ockam_core::{
@@ -58,10 +58,6 @@ impl InternalMap {
         self.address_records_map.clear()
     }
 
-    pub(super) fn get_address_record(&self, primary_address: &Address) -> Option<&AddressRecord> {
-        self.address_records_map.get(primary_address)
-    }
-
     pub(super) fn get_address_record_mut(
         &mut self,
         primary_address: &Address,
@@ -250,6 +246,38 @@ impl InternalMap {
             .collect()
     }
 
+    /// Return the information about all the registered workers and processors
+    pub(super) fn workers_info(&self) -> Vec<WorkerInfo> {
+        self.address_records_map
+            .iter()
+            .map(|(primary, record)| WorkerInfo {
+                address: primary.clone(),
+                aliases: record
+                    .address_set
+                    .iter()
+                    .filter(|a| *a != primary)
+                    .cloned()
+                    .collect(),
+                processor: record.meta.processor,
+                detached: record.meta.detached,
+                routed_messages: record.routed_msg_count,
+                pending_messages: record.msg_count.load(Ordering::Relaxed),
+                metadata: record
+                    .address_set
+                    .iter()
+                    .filter_map(|address| {
+                        self.address_metadata_map
+                            .get(address)
+                            .map(|metadata| AddressAndMetadata {
+                                address: address.clone(),
+                                metadata: metadata.clone(),
+                            })
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
     /// Permanently free all remaining resources associated to a particular address
     pub(super) fn free_address(&mut self, primary: Address) {
         self.stopping.remove(&primary);
@@ -278,6 +306,7 @@ pub struct AddressRecord {
     ready: ReadyState,
     meta: WorkerMeta,
     msg_count: Arc<AtomicUsize>,
+    routed_msg_count: u64,
 }
 
 impl AddressRecord {
@@ -307,13 +336,15 @@ impl AddressRecord {
             state: AddressState::Running,
             ready: ReadyState::Initialising(vec![]),
             msg_count,
+            routed_msg_count: 0,
             meta,
         }
     }
 
     #[inline]
-    pub fn increment_msg_count(&self) {
+    pub fn increment_msg_count(&mut self) {
         self.msg_count.fetch_add(1, Ordering::Relaxed);
+        self.routed_msg_count += 1;
     }
 
     /// Signal this worker to stop -- it will no longer be able to receive messages
@@ -415,6 +446,34 @@ mod test {
     }
 
     /// HELPERS
+    #[test]
+    fn test_workers_info() {
+        let mut map = InternalMap::new(&FlowControls::new());
+        let mut record = create_address_record("worker");
+        record.address_set.push("worker.api".into());
+        record.increment_msg_count();
+        record.increment_msg_count();
+        map.address_records_map.insert("worker".into(), record);
+        map.set_address_metadata(AddressAndMetadata {
+            address: "worker.api".into(),
+            metadata: AddressMetadata {
+                is_terminal: false,
+                attributes: vec![("key".into(), "value".into())],
+            },
+        });
+
+        let info = map.workers_info();
+        assert_eq!(info.len(), 1);
+        assert_eq!(info[0].address, "worker".into());
+        assert_eq!(info[0].aliases, vec!["worker.api".into()]);
+        assert!(!info[0].processor);
+        assert_eq!(info[0].routed_messages, 2);
+        // the record is created with one message in its mailbox
+        assert_eq!(info[0].pending_messages, 3);
+        assert_eq!(info[0].metadata.len(), 1);
+        assert_eq!(info[0].metadata[0].address, "worker.api".into());
+    }
+
     fn create_address_record(primary: &str) -> AddressRecord {
         let (tx1, _) = small_channel();
         let (tx2, _) = small_channel();
@@ -19,7 +19,8 @@ pub(super) async fn resolve(
     let base = format!("Resolving worker address '{}'...", addr);
 
     let address_record = if let Some(primary_address) = router.map.get_primary_address(&addr) {
-        router.map.get_address_record(primary_address)
+        let primary_address = primary_address.clone();
+        router.map.get_address_record_mut(&primary_address)
     } else {
         trace!("{} FAILED; no such worker", base);
         reply
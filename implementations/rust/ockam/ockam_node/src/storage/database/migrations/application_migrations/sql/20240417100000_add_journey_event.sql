@@ -0,0 +1,12 @@
+-- This table stores the journey events recorded on this host so that they can be listed locally.
+-- The attributes of an event are stored as a JSON object and the timestamp as a number of milliseconds
+-- since the Unix epoch.
+CREATE TABLE journey_event (
+    event           TEXT    NOT NULL,
+    description     TEXT    NOT NULL,
+    node_name       TEXT,
+    attributes      TEXT    NOT NULL,
+    event_timestamp INTEGER NOT NULL
+);
+
+CREATE INDEX journey_event_timestamp_index ON journey_event (event_timestamp);
@@ -0,0 +1,12 @@
+-- This table stores the supervision settings and state of background nodes
+-- which must be restarted when their process exits unexpectedly
+CREATE TABLE node_supervision
+(
+    node_name     TEXT PRIMARY KEY, -- Node name
+    max_restarts  INTEGER NOT NULL, -- Maximum number of consecutive restarts before giving up
+    max_backoff   INTEGER NOT NULL, -- Maximum delay, in seconds, between two restarts
+    restart_count INTEGER NOT NULL, -- Total number of restarts after a crash
+    last_crash_at INTEGER,          -- Timestamp, in seconds, of the last crash
+    is_failed     INTEGER NOT NULL, -- 1 if the node crashed too many times in a row and is not restarted anymore
+    watchdog_pid  INTEGER           -- Process id of the watchdog supervising the node
+);
@@ -0,0 +1,4 @@
+-- This column stores the id, or ARN, of the KMS key used by a KMS vault to sign data.
+-- When it is not set, the keys are discovered by listing the keys of the KMS
+ALTER TABLE vault
+    ADD COLUMN kms_key_id TEXT;
@@ -0,0 +1,7 @@
+-- These columns store the parameters used to encrypt the secrets of a vault with a passphrase.
+-- The salt is used to derive the encryption key from the passphrase and the check value is used to
+-- verify that a passphrase is correct. Both are NULL when the vault secrets are stored in plain text
+ALTER TABLE vault
+    ADD COLUMN encryption_salt BLOB;
+ALTER TABLE vault
+    ADD COLUMN encryption_check BLOB;
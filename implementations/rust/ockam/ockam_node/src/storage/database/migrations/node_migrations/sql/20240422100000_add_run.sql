@@ -0,0 +1,16 @@
+-- These tables store the resources created by the execution of a configuration file with `ockam run`,
+-- so that they can be deleted with `ockam run --teardown`
+CREATE TABLE run
+(
+    run_id        TEXT PRIMARY KEY, -- Identifier of the configuration file: its canonical path, or the hash of inline contents
+    contents_hash TEXT NOT NULL     -- Hash of the configuration file contents when it was last executed
+);
+
+CREATE TABLE run_resource
+(
+    run_id        TEXT NOT NULL, -- Identifier of the run which created the resource
+    resource_type TEXT NOT NULL, -- Type of the resource: node, relay, tcp-outlet or tcp-inlet
+    resource_name TEXT NOT NULL, -- Node name, relay name, outlet address or inlet alias
+    node_name     TEXT NOT NULL, -- Name of the node hosting the resource
+    PRIMARY KEY (run_id, resource_type, resource_name, node_name)
+);
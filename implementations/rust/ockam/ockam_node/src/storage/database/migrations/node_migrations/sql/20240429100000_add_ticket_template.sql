@@ -0,0 +1,8 @@
+-- This table stores named templates for the enrollment tickets created with `ockam project ticket`
+CREATE TABLE ticket_template
+(
+    name        TEXT PRIMARY KEY, -- Name of the template
+    attributes  TEXT    NOT NULL, -- JSON map of the attributes given to the identities enrolled with a ticket
+    usage_count INTEGER,          -- Number of times a ticket can be used to enroll, the authority default is used if NULL
+    expires_in  INTEGER           -- Validity of a ticket, in seconds, the authority default is used if NULL
+);
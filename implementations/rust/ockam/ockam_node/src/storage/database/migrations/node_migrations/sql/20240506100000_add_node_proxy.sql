@@ -0,0 +1,7 @@
+-- This table stores the HTTP proxy used by a node for its outgoing TCP connections
+CREATE TABLE node_proxy
+(
+    node_name TEXT PRIMARY KEY, -- Node name
+    proxy_url TEXT NOT NULL,    -- URL of the proxy, possibly with credentials
+    no_proxy  TEXT              -- Comma-separated list of the hosts and networks reached without the proxy
+);
@@ -0,0 +1,4 @@
+-- This column indicates if a project was imported from a file, for example on a machine
+-- which can't reach the Controller, rather than retrieved from the Controller
+ALTER TABLE project
+    ADD COLUMN is_imported INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,4 @@
+-- This column stores the last time a project was retrieved from the Controller.
+-- It is used to avoid fetching the project again while its local copy is recent enough
+ALTER TABLE project
+    ADD COLUMN fetched_at INTEGER;
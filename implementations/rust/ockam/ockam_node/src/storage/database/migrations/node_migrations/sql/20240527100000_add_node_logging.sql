@@ -0,0 +1,9 @@
+-- This table stores the logging configuration of nodes, applied when their process starts
+CREATE TABLE node_logging
+(
+    node_name      TEXT PRIMARY KEY, -- Node name
+    log_level      TEXT,             -- Log level, or comma-separated list of log filter directives
+    log_format     TEXT,             -- Format of the log lines: default, pretty or json
+    max_size_bytes INTEGER,          -- Maximum size of a log file before it is rotated
+    max_files      INTEGER           -- Maximum number of log files kept for the node
+);
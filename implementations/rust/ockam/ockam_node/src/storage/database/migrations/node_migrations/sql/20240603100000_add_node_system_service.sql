@@ -0,0 +1,8 @@
+-- This table stores the system services running nodes, for example a systemd unit,
+-- so that they can be removed when the nodes are deleted
+CREATE TABLE node_system_service
+(
+    node_name TEXT PRIMARY KEY, -- Node name
+    manager   TEXT NOT NULL,    -- System service manager: systemd, launchd or windows
+    unit      TEXT NOT NULL     -- Path of the service definition, or name of the service
+);
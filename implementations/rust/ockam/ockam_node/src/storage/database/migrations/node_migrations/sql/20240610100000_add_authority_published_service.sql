@@ -0,0 +1,11 @@
+-- This table stores the services published in the directory of an Authority node,
+-- so that project members can discover the route to a service from its name
+CREATE TABLE authority_published_service
+(
+    name         TEXT NOT NULL,    -- Human-readable name of the service
+    identifier   TEXT NOT NULL,    -- Identifier of the project member who published the service
+    route        TEXT NOT NULL,    -- Full route to the service, as a multiaddr
+    description  TEXT,             -- Optional description of the service
+    published_at INTEGER NOT NULL, -- Publication time, in seconds since the UNIX epoch
+    PRIMARY KEY (name, identifier)
+);
@@ -0,0 +1,6 @@
+-- This table stores the command line options of nodes, to start them again with the same options
+CREATE TABLE node_arguments
+(
+    node_name TEXT PRIMARY KEY, -- Node name
+    arguments TEXT NOT NULL     -- JSON array of the arguments of the command running the node
+);
@@ -0,0 +1,26 @@
+use ockam_core::compat::string::ToString;
+use ockam_core::compat::vec::Vec;
+use ockam_core::{LocalInfo, LocalMessage};
+
+/// BestEffortDelivery LocalInfo unique Identifier
+pub const BEST_EFFORT_DELIVERY_IDENTIFIER: &str = "BEST_EFFORT_DELIVERY";
+
+/// Marker for messages which can be lost by a transport without harm
+///
+/// Transports without delivery guarantees, like UDP, can skip acknowledgements and
+/// retransmissions for such messages. Other messages must be delivered reliably.
+pub struct BestEffortDelivery;
+
+impl BestEffortDelivery {
+    /// `LocalInfo` marking a message for a best-effort delivery
+    pub fn local_info() -> LocalInfo {
+        LocalInfo::new(BEST_EFFORT_DELIVERY_IDENTIFIER.to_string(), Vec::new())
+    }
+
+    /// Return true if the message is marked for a best-effort delivery
+    pub fn is_marked(msg: &LocalMessage) -> bool {
+        msg.local_info_ref()
+            .iter()
+            .any(|info| info.type_identifier() == BEST_EFFORT_DELIVERY_IDENTIFIER)
+    }
+}
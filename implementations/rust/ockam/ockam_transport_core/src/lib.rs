@@ -13,8 +13,10 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+mod delivery;
 mod error;
 mod transport;
 
+pub use delivery::*;
 pub use error::TransportError;
 pub use transport::*;
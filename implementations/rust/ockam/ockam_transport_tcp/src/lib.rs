@@ -24,8 +24,16 @@ mod registry;
 mod transport;
 
 use ockam_core::TransportType;
-pub use options::{TcpConnectionOptions, TcpListenerOptions};
-pub use portal::{PortalInternalMessage, PortalMessage, MAX_PAYLOAD_SIZE};
+pub use options::{
+    TcpConnectionOptions, TcpKeepaliveOptions, TcpListenerOptions, TcpTransportOptions,
+    DEFAULT_HAPPY_EYEBALLS_DELAY, DEFAULT_MAX_MESSAGE_SIZE,
+};
+pub use portal::{
+    PortalInternalMessage, PortalMessage, PortalType, ProxyProtocolHeader,
+    TcpInletConnectionLimits, TcpInletLoadBalancer, TcpInletLoadBalancing, TcpInletRouteStatus,
+    TcpPortalBatching, DEFAULT_PORTAL_BATCHING_DELAY, DEFAULT_PORTAL_BATCHING_THRESHOLD,
+    DEFAULT_PORTAL_BUFFER_SIZE, DEFAULT_UNHEALTHY_ROUTE_COOLDOWN, MAX_CHUNK_SIZE, MAX_PAYLOAD_SIZE,
+};
 pub use registry::*;
 pub use transport::common::*;
 pub use transport::*;
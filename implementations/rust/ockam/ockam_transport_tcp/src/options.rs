@@ -1,7 +1,13 @@
 use crate::workers::Addresses;
+use crate::{TcpProxy, TcpResolver};
+use cfg_if::cfg_if;
+use core::time::Duration;
 use ockam_core::compat::sync::Arc;
 use ockam_core::flow_control::{FlowControlId, FlowControlOutgoingAccessControl, FlowControls};
 use ockam_core::{Address, AllowAll, IncomingAccessControl, OutgoingAccessControl};
+use socket2::{SockRef, TcpKeepalive};
+use tokio::net::TcpStream;
+use tracing::warn;
 
 pub(crate) struct TcpConnectionAccessControl {
     pub sender_incoming_access_control: Arc<dyn IncomingAccessControl>,
@@ -13,8 +19,19 @@ pub(crate) struct TcpConnectionAccessControl {
 pub struct TcpConnectionOptions {
     pub(super) consumer: Vec<FlowControlId>,
     pub(crate) flow_control_id: FlowControlId,
+    pub(crate) prefer_ipv6: bool,
+    pub(crate) happy_eyeballs_delay: Duration,
+    pub(crate) socket_options: TcpSocketOptions,
 }
 
+/// Default maximum size, in bytes, of a message received on a TCP connection.
+/// The length of each message is sent on 2 bytes, so no message can be larger
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = u16::MAX as usize;
+
+/// Delay after which a connection attempt to the next address of a peer is started,
+/// when the previous attempts are still pending
+pub const DEFAULT_HAPPY_EYEBALLS_DELAY: Duration = Duration::from_millis(250);
+
 impl TcpConnectionOptions {
     #[allow(clippy::new_without_default)]
     /// Mark this Tcp Receiver as a Producer with a random [`FlowControlId`]
@@ -22,9 +39,39 @@ impl TcpConnectionOptions {
         Self {
             consumer: vec![],
             flow_control_id: FlowControls::generate_flow_control_id(),
+            prefer_ipv6: false,
+            happy_eyeballs_delay: DEFAULT_HAPPY_EYEBALLS_DELAY,
+            socket_options: TcpSocketOptions::default(),
         }
     }
 
+    /// Set the TCP keepalive of the connection socket
+    pub fn with_keepalive(mut self, keepalive: TcpKeepaliveOptions) -> Self {
+        self.socket_options.keepalive = keepalive;
+        self
+    }
+
+    /// Set the maximum time that sent data can remain unacknowledged before the connection
+    /// is closed (`TCP_USER_TIMEOUT`). This option is only supported on Linux
+    pub fn with_user_timeout(mut self, user_timeout: Duration) -> Self {
+        self.socket_options.user_timeout = Some(user_timeout);
+        self
+    }
+
+    /// Try the IPv6 addresses of the peer before its IPv4 addresses
+    pub fn with_prefer_ipv6(mut self, prefer_ipv6: bool) -> Self {
+        self.prefer_ipv6 = prefer_ipv6;
+        self
+    }
+
+    /// Set the delay after which the next address of the peer is tried, when the connection
+    /// attempts to the previous addresses are still pending.
+    /// The default is [`DEFAULT_HAPPY_EYEBALLS_DELAY`]
+    pub fn with_happy_eyeballs_delay(mut self, delay: Duration) -> Self {
+        self.happy_eyeballs_delay = delay;
+        self
+    }
+
     /// Mark that this Connection is a Consumer for to the given [`FlowControlId`]
     pub fn as_consumer(mut self, id: &FlowControlId) -> Self {
         self.consumer.push(id.clone());
@@ -67,10 +114,49 @@ impl TcpConnectionOptions {
     }
 }
 
+/// Options of a [`TcpTransport`](crate::TcpTransport), shared by all its connections and portals
+#[derive(Debug, Default)]
+pub struct TcpTransportOptions {
+    pub(crate) resolver: TcpResolver,
+    pub(crate) proxy: Option<TcpProxy>,
+    pub(crate) max_message_size: Option<usize>,
+}
+
+impl TcpTransportOptions {
+    /// Use the default resolver and connect to the peers directly
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolve the host names of the peers with a specific resolver
+    pub fn with_resolver(mut self, resolver: TcpResolver) -> Self {
+        self.resolver = resolver;
+        self
+    }
+
+    /// Connect to the peers through an HTTP proxy, except the peers excluded by the proxy
+    /// configuration. This applies to the outgoing connections and to the connections
+    /// of the outlets to their targets
+    pub fn with_proxy(mut self, proxy: TcpProxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Close the connections receiving a message larger than `max_message_size` bytes.
+    /// Listeners can override this limit for the connections they accept.
+    /// The default is [`DEFAULT_MAX_MESSAGE_SIZE`]
+    pub fn with_max_message_size(mut self, max_message_size: usize) -> Self {
+        self.max_message_size = Some(max_message_size);
+        self
+    }
+}
+
 /// Trust Options for a TCP listener
 #[derive(Debug)]
 pub struct TcpListenerOptions {
     pub(crate) flow_control_id: FlowControlId,
+    pub(crate) socket_options: TcpSocketOptions,
+    pub(crate) max_message_size: Option<usize>,
 }
 
 impl TcpListenerOptions {
@@ -81,9 +167,31 @@ impl TcpListenerOptions {
     pub fn new() -> Self {
         Self {
             flow_control_id: FlowControls::generate_flow_control_id(),
+            socket_options: TcpSocketOptions::default(),
+            max_message_size: None,
         }
     }
 
+    /// Close the accepted connections receiving a message larger than `max_message_size` bytes,
+    /// instead of using the limit of the transport
+    pub fn with_max_message_size(mut self, max_message_size: usize) -> Self {
+        self.max_message_size = Some(max_message_size);
+        self
+    }
+
+    /// Set the TCP keepalive of the sockets of accepted connections
+    pub fn with_keepalive(mut self, keepalive: TcpKeepaliveOptions) -> Self {
+        self.socket_options.keepalive = keepalive;
+        self
+    }
+
+    /// Set the maximum time that sent data can remain unacknowledged before an accepted
+    /// connection is closed (`TCP_USER_TIMEOUT`). This option is only supported on Linux
+    pub fn with_user_timeout(mut self, user_timeout: Duration) -> Self {
+        self.socket_options.user_timeout = Some(user_timeout);
+        self
+    }
+
     /// Getter for freshly generated [`FlowControlId`]
     pub fn spawner_flow_control_id(&self) -> FlowControlId {
         self.flow_control_id.clone()
@@ -131,3 +239,189 @@ impl TcpListenerOptions {
         }
     }
 }
+
+/// TCP keepalive settings of a connection socket.
+///
+/// Keepalive probes are sent once the connection has been idle for `idle`, then every
+/// `interval`, and the connection is closed after `count` unanswered probes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TcpKeepaliveOptions {
+    enabled: bool,
+    idle: Duration,
+    interval: Duration,
+    count: u32,
+}
+
+impl Default for TcpKeepaliveOptions {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            idle: Duration::from_secs(300),
+            interval: Duration::from_secs(75),
+            count: 2,
+        }
+    }
+}
+
+impl TcpKeepaliveOptions {
+    /// Keepalive probes sent after the given idle time, then at the same interval
+    pub fn new(idle: Duration) -> Self {
+        Self {
+            idle,
+            interval: idle,
+            ..Default::default()
+        }
+    }
+
+    /// Don't send keepalive probes
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            ..Default::default()
+        }
+    }
+
+    /// Set the idle time before the first keepalive probe is sent
+    pub fn with_idle(mut self, idle: Duration) -> Self {
+        self.idle = idle;
+        self
+    }
+
+    /// Set the interval between two keepalive probes
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Set the number of unanswered keepalive probes after which the connection is closed.
+    /// This option is ignored on Windows
+    pub fn with_count(mut self, count: u32) -> Self {
+        self.count = count;
+        self
+    }
+
+    /// Return true if keepalive probes are sent
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Idle time before the first keepalive probe is sent
+    pub fn idle(&self) -> Duration {
+        self.idle
+    }
+
+    /// Interval between two keepalive probes
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Number of unanswered keepalive probes after which the connection is closed
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+}
+
+/// Options applied to the socket of each TCP connection
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TcpSocketOptions {
+    pub(crate) keepalive: TcpKeepaliveOptions,
+    pub(crate) user_timeout: Option<Duration>,
+}
+
+impl TcpSocketOptions {
+    /// Set the options on the socket of a connection.
+    ///
+    /// Failing to set an option, or an option not supported on this platform, is logged
+    /// and doesn't prevent the connection from being used
+    pub(crate) fn apply(&self, stream: &TcpStream) {
+        let socket = SockRef::from(stream);
+
+        if self.keepalive.enabled {
+            let mut keepalive = TcpKeepalive::new()
+                .with_time(self.keepalive.idle)
+                .with_interval(self.keepalive.interval);
+
+            cfg_if! {
+                if #[cfg(unix)] {
+                    keepalive = keepalive.with_retries(self.keepalive.count);
+                }
+            }
+
+            if let Err(e) = socket.set_tcp_keepalive(&keepalive) {
+                warn!(err = %e, "Failed to set the TCP keepalive of the connection");
+            }
+        } else if let Err(e) = socket.set_keepalive(false) {
+            warn!(err = %e, "Failed to disable the TCP keepalive of the connection");
+        }
+
+        if let Some(user_timeout) = self.user_timeout {
+            cfg_if! {
+                if #[cfg(any(target_os = "android", target_os = "fuchsia", target_os = "linux"))] {
+                    if let Err(e) = socket.set_tcp_user_timeout(Some(user_timeout)) {
+                        warn!(err = %e, "Failed to set the TCP user timeout of the connection");
+                    }
+                } else {
+                    warn!(
+                        user_timeout = ?user_timeout,
+                        "The TCP user timeout is not supported on this platform, it is ignored"
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use ockam_core::Result;
+    use tokio::net::TcpListener;
+
+    async fn connected_stream() -> Result<(TcpStream, TcpStream)> {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let (client, server) = tokio::join!(TcpStream::connect(address), listener.accept());
+        Ok((client.unwrap(), server.unwrap().0))
+    }
+
+    #[tokio::test]
+    async fn socket_options_are_applied() -> Result<()> {
+        let (client, _server) = connected_stream().await?;
+
+        let options = TcpConnectionOptions::new()
+            .with_keepalive(
+                TcpKeepaliveOptions::new(Duration::from_secs(30))
+                    .with_interval(Duration::from_secs(10))
+                    .with_count(4),
+            )
+            .with_user_timeout(Duration::from_secs(45));
+        options.socket_options.apply(&client);
+
+        let socket = SockRef::from(&client);
+        assert!(socket.keepalive().unwrap());
+        assert_eq!(socket.keepalive_time().unwrap(), Duration::from_secs(30));
+        assert_eq!(
+            socket.keepalive_interval().unwrap(),
+            Duration::from_secs(10)
+        );
+        assert_eq!(socket.keepalive_retries().unwrap(), 4);
+        assert_eq!(
+            socket.tcp_user_timeout().unwrap(),
+            Some(Duration::from_secs(45))
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn keepalive_can_be_disabled() -> Result<()> {
+        let (client, _server) = connected_stream().await?;
+
+        let options = TcpListenerOptions::new().with_keepalive(TcpKeepaliveOptions::disabled());
+        options.socket_options.apply(&client);
+
+        let socket = SockRef::from(&client);
+        assert!(!socket.keepalive().unwrap());
+        assert_eq!(socket.tcp_user_timeout().unwrap(), None);
+        Ok(())
+    }
+}
@@ -1,13 +1,16 @@
 use ockam_core::Address;
 
 /// Enumerate all portal types
-#[derive(Debug, Clone)]
-pub(super) enum PortalType {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortalType {
+    /// Side of the portal accepting TCP connections
     Inlet,
+    /// Side of the portal connecting to a TCP server
     Outlet,
 }
 
 impl PortalType {
+    /// Name of the portal type
     pub fn str(&self) -> &'static str {
         match self {
             PortalType::Inlet => "inlet",
@@ -0,0 +1,100 @@
+use core::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::Notify;
+
+/// Default maximum number of bytes read from the TCP stream of a portal connection which
+/// the other side of the portal didn't write to its own TCP stream yet
+pub const DEFAULT_PORTAL_BUFFER_SIZE: usize = 4 * 1024 * 1024;
+
+/// Bytes sent to the other side of a portal connection and not acknowledged yet.
+///
+/// Once the other side has announced its buffer size, reads from the TCP stream are paused
+/// while the unacknowledged bytes exceed it, so that a slow reader on the other side
+/// slows down the writer on this side instead of making the messages pile up in memory.
+#[derive(Debug, Default)]
+pub(crate) struct PortalBackpressure {
+    /// Buffer size announced by the other side, 0 until it is announced
+    buffer_size: AtomicU64,
+    in_flight: AtomicU64,
+    drained: Notify,
+}
+
+impl PortalBackpressure {
+    /// Start limiting the unacknowledged bytes to the buffer size of the other side
+    pub(crate) fn set_buffer_size(&self, buffer_size: u64) {
+        self.buffer_size.store(buffer_size, Ordering::Relaxed);
+        self.drained.notify_one();
+    }
+
+    /// Count bytes sent to the other side
+    pub(crate) fn add_in_flight(&self, count: usize) {
+        self.in_flight.fetch_add(count as u64, Ordering::Relaxed);
+    }
+
+    /// Count bytes written by the other side to its TCP stream
+    pub(crate) fn acknowledge(&self, count: u64) {
+        let _ = self
+            .in_flight
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |in_flight| {
+                Some(in_flight.saturating_sub(count))
+            });
+        self.drained.notify_one();
+    }
+
+    /// Return true if no more bytes should be sent until some are acknowledged
+    pub(crate) fn is_full(&self) -> bool {
+        let buffer_size = self.buffer_size.load(Ordering::Relaxed);
+        buffer_size != 0 && self.in_flight.load(Ordering::Relaxed) >= buffer_size
+    }
+
+    /// Wait until bytes can be sent again
+    pub(crate) async fn wait_until_drained(&self) {
+        while self.is_full() {
+            self.drained.notified().await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::time::Duration;
+    use ockam_core::compat::sync::Arc;
+
+    #[test]
+    fn backpressure_is_only_applied_once_the_buffer_size_is_known() {
+        let backpressure = PortalBackpressure::default();
+        backpressure.add_in_flight(10_000);
+        assert!(!backpressure.is_full());
+
+        backpressure.set_buffer_size(1_000);
+        assert!(backpressure.is_full());
+
+        backpressure.acknowledge(9_500);
+        assert!(!backpressure.is_full());
+
+        // Acknowledging more than what was sent doesn't underflow
+        backpressure.acknowledge(1_000);
+        backpressure.add_in_flight(999);
+        assert!(!backpressure.is_full());
+    }
+
+    #[tokio::test]
+    async fn waiting_stops_when_bytes_are_acknowledged() {
+        let backpressure = Arc::new(PortalBackpressure::default());
+        backpressure.set_buffer_size(100);
+        backpressure.add_in_flight(150);
+
+        let waiting = tokio::spawn({
+            let backpressure = backpressure.clone();
+            async move { backpressure.wait_until_drained().await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiting.is_finished());
+
+        backpressure.acknowledge(100);
+        tokio::time::timeout(Duration::from_secs(1), waiting)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+}
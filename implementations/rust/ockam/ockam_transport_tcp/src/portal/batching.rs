@@ -0,0 +1,59 @@
+use core::time::Duration;
+
+/// Default time during which the data read from a TCP stream is coalesced into a single payload
+pub const DEFAULT_PORTAL_BATCHING_DELAY: Duration = Duration::from_millis(1);
+
+/// Default number of bytes after which a batched payload is sent without waiting any longer
+pub const DEFAULT_PORTAL_BATCHING_THRESHOLD: usize = 16 * 1024;
+
+/// Coalescing of the small reads of a TCP stream into fewer payload messages.
+///
+/// Once some data has been read, the portal keeps reading from the TCP stream for at most
+/// `delay`, or until `size_threshold` bytes are read, before sending them in a single message.
+/// The data is sent right away when the other side of the portal can't accept more data
+/// or when the TCP stream is closed, and is always sent in the order it was read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TcpPortalBatching {
+    delay: Duration,
+    size_threshold: usize,
+}
+
+impl TcpPortalBatching {
+    /// Batch payloads for [`DEFAULT_PORTAL_BATCHING_DELAY`],
+    /// up to [`DEFAULT_PORTAL_BATCHING_THRESHOLD`] bytes
+    pub fn new() -> Self {
+        Self {
+            delay: DEFAULT_PORTAL_BATCHING_DELAY,
+            size_threshold: DEFAULT_PORTAL_BATCHING_THRESHOLD,
+        }
+    }
+
+    /// Set the maximum time to wait for more data once some data was read
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Set the number of bytes after which a payload is sent without waiting for more data.
+    /// The payloads are never bigger than the chunk size of the portal
+    pub fn with_size_threshold(mut self, size_threshold: usize) -> Self {
+        self.size_threshold = size_threshold;
+        self
+    }
+
+    /// Maximum time to wait for more data once some data was read
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    /// Number of bytes after which a payload is sent without waiting for more data
+    pub fn size_threshold(&self) -> usize {
+        self.size_threshold
+    }
+}
+
+impl Default for TcpPortalBatching {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,128 @@
+use ockam_core::{RateLimiter, RejectionCounter};
+
+/// Limits on the connections accepted by an inlet.
+///
+/// The connections exceeding a limit are closed as soon as they are accepted. The limits can be
+/// cloned and kept by the caller in order to inspect their utilization while the inlet uses them.
+#[derive(Clone, Debug, Default)]
+pub struct TcpInletConnectionLimits {
+    rate_limiter: Option<RateLimiter>,
+    max_concurrent_connections: Option<u64>,
+    concurrency_rejections: RejectionCounter,
+}
+
+/// Reason for closing a connection accepted by an inlet
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ConnectionRejection {
+    /// Too many connections were accepted during the last second
+    Rate,
+    /// Too many connections are open
+    Concurrency,
+}
+
+impl TcpInletConnectionLimits {
+    /// No limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accept at most `max_connections_per_second` new connections per second
+    pub fn with_max_connections_per_second(mut self, max_connections_per_second: u32) -> Self {
+        self.rate_limiter = Some(RateLimiter::new(max_connections_per_second));
+        self
+    }
+
+    /// Keep at most `max_concurrent_connections` connections open at the same time
+    pub fn with_max_concurrent_connections(mut self, max_concurrent_connections: u64) -> Self {
+        self.max_concurrent_connections = Some(max_concurrent_connections);
+        self
+    }
+
+    /// Maximum number of new connections per second
+    pub fn max_connections_per_second(&self) -> Option<u32> {
+        self.rate_limiter.as_ref().map(|l| l.per_second())
+    }
+
+    /// Number of connections accepted during the last second, when their rate is limited
+    pub fn connections_per_second(&self) -> Option<u32> {
+        self.rate_limiter.as_ref().map(|l| l.utilization())
+    }
+
+    /// Maximum number of connections open at the same time
+    pub fn max_concurrent_connections(&self) -> Option<u64> {
+        self.max_concurrent_connections
+    }
+
+    /// Number of connections closed because too many connections were accepted during the last second
+    pub fn rate_limited_connections(&self) -> u64 {
+        self.rate_limiter
+            .as_ref()
+            .map(|l| l.rejections().total())
+            .unwrap_or(0)
+    }
+
+    /// Number of connections closed because too many connections were open
+    pub fn concurrency_limited_connections(&self) -> u64 {
+        self.concurrency_rejections.total()
+    }
+
+    /// Check if a new connection can be accepted, given the number of open connections.
+    /// Return the reason for rejecting it otherwise, and the number of rejections to log, if any
+    pub(crate) fn check(
+        &self,
+        active_connections: u64,
+    ) -> Result<(), (ConnectionRejection, Option<u64>)> {
+        if let Some(max_concurrent_connections) = self.max_concurrent_connections {
+            if active_connections >= max_concurrent_connections {
+                self.concurrency_rejections.record();
+                return Err((
+                    ConnectionRejection::Concurrency,
+                    self.concurrency_rejections.take_report(),
+                ));
+            }
+        }
+        if let Some(rate_limiter) = &self.rate_limiter {
+            if !rate_limiter.try_acquire() {
+                return Err((
+                    ConnectionRejection::Rate,
+                    rate_limiter.rejections().take_report(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connection_limits() {
+        let limits = TcpInletConnectionLimits::new()
+            .with_max_connections_per_second(2)
+            .with_max_concurrent_connections(3);
+
+        // the concurrency limit is checked first, and doesn't take a rate token
+        assert_eq!(
+            limits.check(3),
+            Err((ConnectionRejection::Concurrency, Some(1)))
+        );
+        assert_eq!(limits.check(0), Ok(()));
+        assert_eq!(limits.check(1), Ok(()));
+        // the first rejection is reported immediately, the next ones are reported later
+        assert_eq!(limits.check(2), Err((ConnectionRejection::Rate, Some(1))));
+        assert_eq!(limits.check(2), Err((ConnectionRejection::Rate, None)));
+        assert_eq!(
+            limits.check(5),
+            Err((ConnectionRejection::Concurrency, None))
+        );
+
+        assert_eq!(limits.connections_per_second(), Some(2));
+        assert_eq!(limits.rate_limited_connections(), 2);
+        assert_eq!(limits.concurrency_limited_connections(), 2);
+
+        // without limits, all the connections are accepted
+        assert_eq!(TcpInletConnectionLimits::new().check(1000), Ok(()));
+    }
+}
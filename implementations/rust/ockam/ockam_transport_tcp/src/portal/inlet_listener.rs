@@ -1,36 +1,72 @@
 use crate::portal::addresses::{Addresses, PortalType};
-use crate::{portal::TcpPortalWorker, TcpInletOptions, TcpRegistry};
-use ockam_core::compat::net::SocketAddr;
+use crate::portal::{ConnectionRejection, PortalStream};
+use crate::{portal::TcpPortalWorker, TcpInletLoadBalancer, TcpInletOptions, TcpRegistry};
+use ockam_core::compat::net::{Ipv4Addr, SocketAddr};
 use ockam_core::{async_trait, compat::boxed::Box};
-use ockam_core::{Address, Processor, Result, Route};
+use ockam_core::{Address, Processor, Result};
 use ockam_node::Context;
 use ockam_transport_core::TransportError;
+use std::path::PathBuf;
 use tokio::net::TcpListener;
-use tracing::{debug, error, instrument};
+use tracing::{debug, error, instrument, warn};
+
+/// Listener accepting the connections of an inlet
+enum InletListener {
+    Tcp(TcpListener),
+    /// Listener on a Unix domain socket, the path of its socket file and the inode
+    /// of that file, which is removed when the inlet stops
+    #[cfg(unix)]
+    Unix(tokio::net::UnixListener, PathBuf, u64),
+}
+
+impl InletListener {
+    /// Accept a new connection and return its stream, the address of the peer,
+    /// and the path of the Unix domain socket of the inlet, if any.
+    /// The peer address is unspecified for a Unix domain socket
+    async fn accept(&self) -> Result<(PortalStream, SocketAddr, Option<PathBuf>)> {
+        match self {
+            InletListener::Tcp(listener) => {
+                let (stream, peer) = listener.accept().await.map_err(TransportError::from)?;
+                Ok((PortalStream::tcp(stream), peer, None))
+            }
+            #[cfg(unix)]
+            InletListener::Unix(listener, path, _) => {
+                let (stream, _) = listener.accept().await.map_err(TransportError::from)?;
+                Ok((
+                    PortalStream::unix(stream),
+                    SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)),
+                    Some(path.clone()),
+                ))
+            }
+        }
+    }
+}
 
 /// A TCP Portal Inlet listen processor
 ///
 /// TCP Portal Inlet listen processors are created by `TcpTransport`
 /// after a call is made to
-/// [`TcpTransport::create_inlet`](crate::TcpTransport::create_inlet).
+/// [`TcpTransport::create_inlet`](crate::TcpTransport::create_inlet),
+/// or to [`TcpTransport::create_unix_inlet`](crate::TcpTransport::create_unix_inlet)
+/// to accept the connections of a Unix domain socket.
 pub(crate) struct TcpInletListenProcessor {
     registry: TcpRegistry,
-    inner: TcpListener,
-    outlet_listener_route: Route,
+    inner: InletListener,
+    outlet_routes: TcpInletLoadBalancer,
     options: TcpInletOptions,
 }
 
 impl TcpInletListenProcessor {
-    pub fn new(
+    fn new(
         registry: TcpRegistry,
-        inner: TcpListener,
-        outlet_listener_route: Route,
+        inner: InletListener,
+        outlet_routes: TcpInletLoadBalancer,
         options: TcpInletOptions,
     ) -> Self {
         Self {
             registry,
             inner,
-            outlet_listener_route,
+            outlet_routes,
             options,
         }
     }
@@ -40,7 +76,7 @@ impl TcpInletListenProcessor {
     pub(crate) async fn start(
         ctx: &Context,
         registry: TcpRegistry,
-        outlet_listener_route: Route,
+        outlet_routes: TcpInletLoadBalancer,
         addr: SocketAddr,
         options: TcpInletOptions,
     ) -> Result<(SocketAddr, Address)> {
@@ -55,13 +91,76 @@ impl TcpInletListenProcessor {
             }
         };
         let socket_addr = inner.local_addr().map_err(TransportError::from)?;
-        let processor = Self::new(registry, inner, outlet_listener_route, options);
+        let processor = Self::new(registry, InletListener::Tcp(inner), outlet_routes, options);
 
         ctx.start_processor(processor_address.clone(), processor)
             .await?;
 
         Ok((socket_addr, processor_address))
     }
+
+    /// Start a new `TcpInletListenProcessor` accepting the connections of a Unix domain socket
+    #[cfg(unix)]
+    #[instrument(skip_all, name = "TcpInletListenProcessor::start_unix")]
+    pub(crate) async fn start_unix(
+        ctx: &Context,
+        registry: TcpRegistry,
+        outlet_routes: TcpInletLoadBalancer,
+        path: PathBuf,
+        options: TcpInletOptions,
+    ) -> Result<Address> {
+        use crate::transport::common::unix_socket_error;
+        use ockam_core::errcode::{Kind, Origin};
+        use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+        let processor_address = Address::random_tagged("TcpInletListenProcessor");
+
+        debug!("Binding TcpPortalListenerWorker to {}", path.display());
+        if path.symlink_metadata().is_ok() {
+            if !options.replace_unix_socket {
+                return Err(ockam_core::Error::new(
+                    Origin::Transport,
+                    Kind::AlreadyExists,
+                    format!(
+                        "'{}' already exists and is not replaced by the Unix domain socket of the inlet",
+                        path.display()
+                    ),
+                ));
+            }
+            std::fs::remove_file(&path).map_err(|e| unix_socket_error(&path, e))?;
+        }
+        let inner = match tokio::net::UnixListener::bind(&path) {
+            Ok(inner) => inner,
+            Err(err) => {
+                error!(path = %path.display(), %err, "could not bind to the unix domain socket");
+                return Err(unix_socket_error(&path, err));
+            }
+        };
+        let setup = |path: &PathBuf| {
+            if let Some(mode) = options.unix_socket_mode {
+                std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))?;
+            }
+            path.symlink_metadata().map(|metadata| metadata.ino())
+        };
+        let inode = match setup(&path) {
+            Ok(inode) => inode,
+            Err(err) => {
+                let _ = std::fs::remove_file(&path);
+                return Err(unix_socket_error(&path, err));
+            }
+        };
+        let processor = Self::new(
+            registry,
+            InletListener::Unix(inner, path, inode),
+            outlet_routes,
+            options,
+        );
+
+        ctx.start_processor(processor_address.clone(), processor)
+            .await?;
+
+        Ok(processor_address)
+    }
 }
 
 #[async_trait]
@@ -80,31 +179,81 @@ impl Processor for TcpInletListenProcessor {
         self.registry
             .remove_inlet_listener_processor(&ctx.address());
 
+        // The socket file is only removed if it was not replaced in the meantime,
+        // for example by the listener of a new inlet bound to the same path
+        #[cfg(unix)]
+        if let InletListener::Unix(_, path, inode) = &self.inner {
+            use std::os::unix::fs::MetadataExt;
+            let is_same_file = path
+                .symlink_metadata()
+                .is_ok_and(|metadata| metadata.ino() == *inode);
+            if is_same_file {
+                if let Err(err) = std::fs::remove_file(path) {
+                    warn!(path = %path.display(), %err, "could not remove the unix domain socket file");
+                }
+            }
+        }
+
         Ok(())
     }
 
     #[instrument(skip_all, name = "TcpInletListenProcessor::process")]
     async fn process(&mut self, ctx: &mut Self::Context) -> Result<bool> {
-        let addresses = Addresses::generate(PortalType::Inlet);
-        let outlet_listener_route = self.outlet_listener_route.clone();
+        let (stream, peer, unix_socket_path) = self.inner.accept().await?;
 
-        self.options.setup_flow_control(
-            ctx.flow_controls(),
-            &addresses,
-            outlet_listener_route.next()?,
-        );
+        // The stream is dropped, and the connection closed, when a limit is exceeded
+        let limits = &self.options.connection_limits;
+        if let Err((rejection, report)) = limits.check(self.outlet_routes.active_connections()) {
+            debug!(%peer, ?rejection, "closing a connection exceeding the inlet limits");
+            if let Some(count) = report {
+                match rejection {
+                    ConnectionRejection::Rate => warn!(
+                        address = %ctx.address(),
+                        "closed {count} connection(s) exceeding the limit of {} new connections per second",
+                        limits.max_connections_per_second().unwrap_or_default()
+                    ),
+                    ConnectionRejection::Concurrency => warn!(
+                        address = %ctx.address(),
+                        "closed {count} connection(s) exceeding the limit of {} concurrent connections",
+                        limits.max_concurrent_connections().unwrap_or_default()
+                    ),
+                }
+            }
+            return Ok(true);
+        }
 
-        let (stream, peer) = self.inner.accept().await.map_err(TransportError::from)?;
-        TcpPortalWorker::start_new_inlet(
+        let route = match self.outlet_routes.next_route() {
+            Some(route) => route,
+            None => {
+                error!("no outlet route to forward the connection from {peer} to");
+                return Ok(true);
+            }
+        };
+
+        let addresses = Addresses::generate(PortalType::Inlet);
+        self.options
+            .setup_flow_control(ctx.flow_controls(), &addresses, route.route().next()?);
+
+        if let Err(err) = TcpPortalWorker::start_new_inlet(
             ctx,
             self.registry.clone(),
             stream,
             peer,
-            outlet_listener_route,
+            unix_socket_path,
+            route.clone(),
             addresses,
             self.options.incoming_access_control.clone(),
+            self.options.buffer_size,
+            self.options.chunk_size,
+            self.options.batching,
+            self.options.expect_proxy_protocol,
         )
-        .await?;
+        .await
+        {
+            route.connection_failed();
+            route.connection_closed();
+            return Err(err);
+        }
 
         Ok(true)
     }
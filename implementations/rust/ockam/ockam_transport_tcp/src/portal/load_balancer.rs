@@ -0,0 +1,407 @@
+use core::fmt;
+use core::str::FromStr;
+use core::time::Duration;
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Route};
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+/// Duration during which an outlet route is skipped after a failed connection
+pub const DEFAULT_UNHEALTHY_ROUTE_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// Strategy used by an inlet to pick the outlet route of each new TCP connection
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TcpInletLoadBalancing {
+    /// Use the outlet routes one after the other
+    #[default]
+    RoundRobin,
+    /// Use the outlet route with the fewest open connections
+    LeastConnections,
+}
+
+impl fmt::Display for TcpInletLoadBalancing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TcpInletLoadBalancing::RoundRobin => write!(f, "round-robin"),
+            TcpInletLoadBalancing::LeastConnections => write!(f, "least-connections"),
+        }
+    }
+}
+
+impl FromStr for TcpInletLoadBalancing {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "round-robin" => Ok(TcpInletLoadBalancing::RoundRobin),
+            "least-connections" => Ok(TcpInletLoadBalancing::LeastConnections),
+            other => Err(Error::new(
+                Origin::Transport,
+                Kind::Invalid,
+                format!("unknown load balancing strategy '{other}', expected 'round-robin' or 'least-connections'"),
+            )),
+        }
+    }
+}
+
+/// Connections and health of one of the outlet routes of an inlet
+#[derive(Clone, Debug)]
+pub struct TcpInletRouteStatus {
+    route: Route,
+    active_connections: u64,
+    total_connections: u64,
+    failures: u64,
+    healthy: bool,
+}
+
+impl TcpInletRouteStatus {
+    /// Route to the outlet
+    pub fn route(&self) -> &Route {
+        &self.route
+    }
+    /// Number of connections currently open through this route
+    pub fn active_connections(&self) -> u64 {
+        self.active_connections
+    }
+    /// Number of connections opened through this route since the inlet was created
+    pub fn total_connections(&self) -> u64 {
+        self.total_connections
+    }
+    /// Number of connections which failed to reach the outlet
+    pub fn failures(&self) -> u64 {
+        self.failures
+    }
+    /// False if a connection failed recently, in which case the route is skipped
+    pub fn is_healthy(&self) -> bool {
+        self.healthy
+    }
+}
+
+#[derive(Debug)]
+struct RouteState {
+    route: Route,
+    active_connections: u64,
+    total_connections: u64,
+    failures: u64,
+    last_failure: Option<Instant>,
+}
+
+impl RouteState {
+    fn is_healthy(&self, cooldown: Duration) -> bool {
+        self.last_failure
+            .map(|last_failure| last_failure.elapsed() >= cooldown)
+            .unwrap_or(true)
+    }
+}
+
+#[derive(Debug)]
+struct LoadBalancerState {
+    routes: Vec<RouteState>,
+    next: usize,
+}
+
+/// Distribution of the connections accepted by an inlet across its outlet routes.
+///
+/// The load balancer can be cloned and kept by the caller in order to inspect
+/// the distribution of the connections while the inlet keeps updating it.
+#[derive(Clone, Debug)]
+pub struct TcpInletLoadBalancer {
+    strategy: TcpInletLoadBalancing,
+    cooldown: Duration,
+    outlet_timeout: Option<Duration>,
+    state: Arc<Mutex<LoadBalancerState>>,
+}
+
+impl TcpInletLoadBalancer {
+    /// Create a load balancer for the given outlet routes
+    pub fn new(routes: Vec<Route>, strategy: TcpInletLoadBalancing) -> Self {
+        let routes = routes
+            .into_iter()
+            .map(|route| RouteState {
+                route,
+                active_connections: 0,
+                total_connections: 0,
+                failures: 0,
+                last_failure: None,
+            })
+            .collect();
+        Self {
+            strategy,
+            cooldown: DEFAULT_UNHEALTHY_ROUTE_COOLDOWN,
+            outlet_timeout: None,
+            state: Arc::new(Mutex::new(LoadBalancerState { routes, next: 0 })),
+        }
+    }
+
+    /// Set the duration during which a route is skipped after a failed connection
+    pub fn with_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Close the connections whose outlet doesn't answer within `outlet_timeout`,
+    /// and count them as failures of their route
+    pub fn with_outlet_timeout(mut self, outlet_timeout: Duration) -> Self {
+        self.outlet_timeout = Some(outlet_timeout);
+        self
+    }
+
+    /// Strategy used to pick the route of a new connection
+    pub fn strategy(&self) -> TcpInletLoadBalancing {
+        self.strategy
+    }
+
+    /// Return the connections and health of each route
+    pub fn statuses(&self) -> Vec<TcpInletRouteStatus> {
+        let state = self.state.lock().unwrap();
+        state
+            .routes
+            .iter()
+            .map(|r| TcpInletRouteStatus {
+                route: r.route.clone(),
+                active_connections: r.active_connections,
+                total_connections: r.total_connections,
+                failures: r.failures,
+                healthy: r.is_healthy(self.cooldown),
+            })
+            .collect()
+    }
+
+    /// Number of connections currently open through all the routes
+    pub fn active_connections(&self) -> u64 {
+        let state = self.state.lock().unwrap();
+        state.routes.iter().map(|r| r.active_connections).sum()
+    }
+
+    /// Count a failure of the route at `index`, for example when its outlet
+    /// could not be reached, so that the route is skipped during the cooldown
+    pub fn route_failed(&self, index: usize) {
+        self.update(index, |r| {
+            r.failures += 1;
+            r.last_failure = Some(Instant::now());
+        })
+    }
+
+    /// Replace the route at `index` once its outlet can be reached again.
+    /// The new route is healthy, the connections of the previous route are still counted
+    pub fn replace_route(&self, index: usize, route: Route) {
+        self.update(index, |r| {
+            r.route = route;
+            r.last_failure = None;
+        })
+    }
+
+    /// Pick the route of a new connection and count the connection on it.
+    ///
+    /// Unhealthy routes are skipped, unless none of the routes is healthy.
+    pub(crate) fn next_route(&self) -> Option<TcpInletRouteHandle> {
+        let mut state = self.state.lock().unwrap();
+        let count = state.routes.len();
+        if count == 0 {
+            return None;
+        }
+
+        // Candidates, in round-robin order starting from the next route
+        let ordered: Vec<usize> = (0..count).map(|i| (state.next + i) % count).collect();
+        let healthy: Vec<usize> = ordered
+            .iter()
+            .copied()
+            .filter(|i| state.routes[*i].is_healthy(self.cooldown))
+            .collect();
+        let candidates = if healthy.is_empty() { ordered } else { healthy };
+
+        let index = match self.strategy {
+            TcpInletLoadBalancing::RoundRobin => candidates[0],
+            TcpInletLoadBalancing::LeastConnections => *candidates
+                .iter()
+                .min_by_key(|i| state.routes[**i].active_connections)
+                .unwrap_or(&candidates[0]),
+        };
+
+        state.next = (index + 1) % count;
+        let route = &mut state.routes[index];
+        route.active_connections += 1;
+        route.total_connections += 1;
+
+        Some(TcpInletRouteHandle {
+            load_balancer: self.clone(),
+            index,
+            route: route.route.clone(),
+        })
+    }
+
+    fn update(&self, index: usize, f: impl FnOnce(&mut RouteState)) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(route) = state.routes.get_mut(index) {
+            f(route)
+        }
+    }
+}
+
+/// Route picked for a connection accepted by an inlet, used to report
+/// the outcome of the connection to the load balancer
+#[derive(Clone, Debug)]
+pub(crate) struct TcpInletRouteHandle {
+    load_balancer: TcpInletLoadBalancer,
+    index: usize,
+    route: Route,
+}
+
+impl TcpInletRouteHandle {
+    pub(crate) fn route(&self) -> &Route {
+        &self.route
+    }
+
+    pub(crate) fn outlet_timeout(&self) -> Option<Duration> {
+        self.load_balancer.outlet_timeout
+    }
+
+    /// The outlet answered, the route is healthy
+    pub(crate) fn connection_succeeded(&self) {
+        self.load_balancer
+            .update(self.index, |r| r.last_failure = None)
+    }
+
+    /// The outlet could not be reached through this route
+    pub(crate) fn connection_failed(&self) {
+        self.load_balancer.route_failed(self.index)
+    }
+
+    /// The connection is closed
+    pub(crate) fn connection_closed(&self) {
+        self.load_balancer.update(self.index, |r| {
+            r.active_connections = r.active_connections.saturating_sub(1)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_core::route;
+
+    fn routes() -> Vec<Route> {
+        vec![route!["outlet1"], route!["outlet2"], route!["outlet3"]]
+    }
+
+    #[test]
+    fn round_robin_uses_the_routes_one_after_the_other() {
+        let load_balancer = TcpInletLoadBalancer::new(routes(), TcpInletLoadBalancing::RoundRobin);
+        let picked: Vec<Route> = (0..4)
+            .map(|_| load_balancer.next_route().unwrap().route().clone())
+            .collect();
+        assert_eq!(
+            picked,
+            vec![
+                route!["outlet1"],
+                route!["outlet2"],
+                route!["outlet3"],
+                route!["outlet1"]
+            ]
+        );
+
+        let statuses = load_balancer.statuses();
+        assert_eq!(statuses[0].active_connections(), 2);
+        assert_eq!(statuses[1].active_connections(), 1);
+        assert_eq!(statuses[2].total_connections(), 1);
+    }
+
+    #[test]
+    fn least_connections_uses_the_least_loaded_route() {
+        let load_balancer =
+            TcpInletLoadBalancer::new(routes(), TcpInletLoadBalancing::LeastConnections);
+        let first = load_balancer.next_route().unwrap();
+        let _second = load_balancer.next_route().unwrap();
+        let _third = load_balancer.next_route().unwrap();
+
+        first.connection_closed();
+        assert_eq!(
+            load_balancer.next_route().unwrap().route(),
+            &route!["outlet1"]
+        );
+    }
+
+    #[test]
+    fn unhealthy_routes_are_skipped_until_the_cooldown_passes() {
+        let load_balancer = TcpInletLoadBalancer::new(routes(), TcpInletLoadBalancing::RoundRobin)
+            .with_cooldown(Duration::from_millis(100));
+
+        let first = load_balancer.next_route().unwrap();
+        first.connection_failed();
+        first.connection_closed();
+        assert!(!load_balancer.statuses()[0].is_healthy());
+        assert_eq!(load_balancer.statuses()[0].failures(), 1);
+
+        // outlet1 is skipped
+        let picked: Vec<Route> = (0..3)
+            .map(|_| load_balancer.next_route().unwrap().route().clone())
+            .collect();
+        assert_eq!(
+            picked,
+            vec![route!["outlet2"], route!["outlet3"], route!["outlet2"]]
+        );
+
+        // until the cooldown passes
+        std::thread::sleep(Duration::from_millis(150));
+        assert!(load_balancer.statuses()[0].is_healthy());
+        assert_eq!(
+            load_balancer.next_route().unwrap().route(),
+            &route!["outlet3"]
+        );
+        assert_eq!(
+            load_balancer.next_route().unwrap().route(),
+            &route!["outlet1"]
+        );
+    }
+
+    #[test]
+    fn unhealthy_routes_are_used_when_no_route_is_healthy() {
+        let load_balancer =
+            TcpInletLoadBalancer::new(vec![route!["outlet1"]], TcpInletLoadBalancing::RoundRobin);
+        load_balancer.next_route().unwrap().connection_failed();
+        assert_eq!(
+            load_balancer.next_route().unwrap().route(),
+            &route!["outlet1"]
+        );
+    }
+
+    #[test]
+    fn failed_routes_are_used_again_once_replaced() {
+        let load_balancer = TcpInletLoadBalancer::new(routes(), TcpInletLoadBalancing::RoundRobin);
+        load_balancer.route_failed(0);
+        assert!(!load_balancer.statuses()[0].is_healthy());
+        assert_eq!(
+            load_balancer.next_route().unwrap().route(),
+            &route!["outlet2"]
+        );
+
+        load_balancer.replace_route(0, route!["outlet1-reconnected"]);
+        let status = &load_balancer.statuses()[0];
+        assert!(status.is_healthy());
+        assert_eq!(status.route(), &route!["outlet1-reconnected"]);
+        assert_eq!(status.failures(), 1);
+        assert_eq!(
+            load_balancer.next_route().unwrap().route(),
+            &route!["outlet3"]
+        );
+        assert_eq!(
+            load_balancer.next_route().unwrap().route(),
+            &route!["outlet1-reconnected"]
+        );
+    }
+
+    #[test]
+    fn parse_load_balancing() {
+        assert_eq!(
+            TcpInletLoadBalancing::from_str("round-robin").unwrap(),
+            TcpInletLoadBalancing::RoundRobin
+        );
+        assert_eq!(
+            TcpInletLoadBalancing::from_str("least-connections").unwrap(),
+            TcpInletLoadBalancing::LeastConnections
+        );
+        assert!(TcpInletLoadBalancing::from_str("random").is_err());
+    }
+}
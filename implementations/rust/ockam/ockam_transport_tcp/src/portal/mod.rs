@@ -1,13 +1,27 @@
 mod addresses;
+mod backpressure;
+mod batching;
+mod connection_limits;
 mod inlet_listener;
+mod load_balancer;
 pub mod options;
 mod outlet_listener;
+mod payload_buffer;
 mod portal_message;
 mod portal_receiver;
 mod portal_worker;
+mod proxy_protocol;
 
+pub use addresses::PortalType;
+pub(crate) use backpressure::PortalBackpressure;
+pub use backpressure::DEFAULT_PORTAL_BUFFER_SIZE;
+pub use batching::*;
+pub(crate) use connection_limits::ConnectionRejection;
+pub use connection_limits::TcpInletConnectionLimits;
 pub(crate) use inlet_listener::*;
+pub use load_balancer::*;
 pub(crate) use outlet_listener::*;
 pub use portal_message::*;
 pub(crate) use portal_receiver::*;
 pub(crate) use portal_worker::*;
+pub use proxy_protocol::ProxyProtocolHeader;
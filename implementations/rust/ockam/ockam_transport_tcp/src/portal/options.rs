@@ -1,4 +1,8 @@
 use crate::portal::addresses::Addresses;
+use crate::portal::{
+    TcpInletConnectionLimits, TcpPortalBatching, DEFAULT_PORTAL_BUFFER_SIZE, MAX_CHUNK_SIZE,
+    MAX_PAYLOAD_SIZE,
+};
 use ockam_core::compat::sync::Arc;
 use ockam_core::flow_control::{FlowControlId, FlowControls};
 use ockam_core::{Address, AllowAll, IncomingAccessControl};
@@ -7,6 +11,13 @@ use ockam_core::{Address, AllowAll, IncomingAccessControl};
 #[derive(Debug)]
 pub struct TcpInletOptions {
     pub(super) incoming_access_control: Arc<dyn IncomingAccessControl>,
+    pub(super) buffer_size: usize,
+    pub(super) chunk_size: usize,
+    pub(super) batching: Option<TcpPortalBatching>,
+    pub(super) connection_limits: TcpInletConnectionLimits,
+    pub(super) unix_socket_mode: Option<u32>,
+    pub(super) replace_unix_socket: bool,
+    pub(super) expect_proxy_protocol: bool,
 }
 
 impl TcpInletOptions {
@@ -14,9 +25,70 @@ impl TcpInletOptions {
     pub fn new() -> Self {
         Self {
             incoming_access_control: Arc::new(AllowAll),
+            buffer_size: DEFAULT_PORTAL_BUFFER_SIZE,
+            chunk_size: MAX_PAYLOAD_SIZE,
+            batching: None,
+            connection_limits: TcpInletConnectionLimits::default(),
+            unix_socket_mode: None,
+            replace_unix_socket: false,
+            expect_proxy_protocol: false,
         }
     }
 
+    /// Limit the rate of new connections and the number of open connections.
+    /// The connections exceeding a limit are closed as soon as they are accepted
+    pub fn with_connection_limits(mut self, connection_limits: TcpInletConnectionLimits) -> Self {
+        self.connection_limits = connection_limits;
+        self
+    }
+
+    /// Set the maximum number of bytes the outlet can send to a connection before the inlet
+    /// writes them to its TCP stream. The outlet stops reading from its TCP stream when it is reached
+    pub fn with_buffer_size(mut self, buffer_size: usize) -> Self {
+        self.buffer_size = buffer_size;
+        self
+    }
+
+    /// Set the maximum number of bytes read at once from a TCP stream and sent in a single
+    /// message to the other side of the portal. The default is [`MAX_PAYLOAD_SIZE`].
+    ///
+    /// Bigger chunks reduce the per-message overhead of large transfers. The chunk size is
+    /// capped at [`MAX_CHUNK_SIZE`], so that every message fits in a TCP transport message
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.clamp(1, MAX_CHUNK_SIZE);
+        self
+    }
+
+    /// Coalesce the small reads of the TCP streams into fewer messages sent to the other side
+    /// of the portal. By default, the data is sent as soon as it is read
+    pub fn with_batching(mut self, batching: TcpPortalBatching) -> Self {
+        self.batching = Some(batching);
+        self
+    }
+
+    /// Set the file mode of the socket file of an inlet listening on a Unix domain socket,
+    /// for example `0o660`. By default, the mode is derived from the umask of the process
+    pub fn with_unix_socket_mode(mut self, mode: u32) -> Self {
+        self.unix_socket_mode = Some(mode);
+        self
+    }
+
+    /// Read a PROXY protocol v2 header at the start of each connection, sent by a proxy in front
+    /// of the inlet, and strip it from the data sent to the outlet. The client address of the
+    /// header is then the one passed on to the outlet. The connections which don't start with
+    /// a valid header are closed
+    pub fn with_expect_proxy_protocol(mut self, expect_proxy_protocol: bool) -> Self {
+        self.expect_proxy_protocol = expect_proxy_protocol;
+        self
+    }
+
+    /// Replace the file found at the path of the Unix domain socket of an inlet.
+    /// By default, the inlet creation fails if the path already exists
+    pub fn with_replace_unix_socket(mut self, replace_unix_socket: bool) -> Self {
+        self.replace_unix_socket = replace_unix_socket;
+        self
+    }
+
     /// Set Incoming Access Control
     pub fn with_incoming_access_control_impl(
         mut self,
@@ -62,6 +134,10 @@ impl Default for TcpInletOptions {
 pub struct TcpOutletOptions {
     pub(super) consumer: Vec<FlowControlId>,
     pub(super) incoming_access_control: Arc<dyn IncomingAccessControl>,
+    pub(super) buffer_size: usize,
+    pub(super) chunk_size: usize,
+    pub(super) batching: Option<TcpPortalBatching>,
+    pub(super) proxy_protocol: bool,
 }
 
 impl TcpOutletOptions {
@@ -70,9 +146,46 @@ impl TcpOutletOptions {
         Self {
             consumer: vec![],
             incoming_access_control: Arc::new(AllowAll),
+            buffer_size: DEFAULT_PORTAL_BUFFER_SIZE,
+            chunk_size: MAX_PAYLOAD_SIZE,
+            batching: None,
+            proxy_protocol: false,
         }
     }
 
+    /// Send a PROXY protocol v2 header to the target at the start of each connection, with the
+    /// address of the client of the inlet. A `LOCAL` header is sent when the inlet doesn't
+    /// forward that address, for example an older inlet. The target must expect the header,
+    /// since it would otherwise read it as the first bytes of the client data
+    pub fn with_proxy_protocol(mut self, proxy_protocol: bool) -> Self {
+        self.proxy_protocol = proxy_protocol;
+        self
+    }
+
+    /// Set the maximum number of bytes the inlet can send to a connection before the outlet
+    /// writes them to its TCP stream. The inlet stops reading from its TCP stream when it is reached
+    pub fn with_buffer_size(mut self, buffer_size: usize) -> Self {
+        self.buffer_size = buffer_size;
+        self
+    }
+
+    /// Set the maximum number of bytes read at once from a TCP stream and sent in a single
+    /// message to the other side of the portal. The default is [`MAX_PAYLOAD_SIZE`].
+    ///
+    /// Bigger chunks reduce the per-message overhead of large transfers. The chunk size is
+    /// capped at [`MAX_CHUNK_SIZE`], so that every message fits in a TCP transport message
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.clamp(1, MAX_CHUNK_SIZE);
+        self
+    }
+
+    /// Coalesce the small reads of the TCP streams into fewer messages sent to the other side
+    /// of the portal. By default, the data is sent as soon as it is read
+    pub fn with_batching(mut self, batching: TcpPortalBatching) -> Self {
+        self.batching = Some(batching);
+        self
+    }
+
     /// Set Incoming Access Control
     pub fn with_incoming_access_control_impl(
         mut self,
@@ -133,3 +246,18 @@ impl Default for TcpOutletOptions {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_chunk_size_fits_in_a_transport_message() {
+        let options = TcpInletOptions::new().with_chunk_size(usize::MAX);
+        assert_eq!(options.chunk_size, MAX_CHUNK_SIZE);
+        let options = TcpOutletOptions::new().with_chunk_size(0);
+        assert_eq!(options.chunk_size, 1);
+        let options = TcpOutletOptions::new().with_chunk_size(32 * 1024);
+        assert_eq!(options.chunk_size, 32 * 1024);
+    }
+}
@@ -1,9 +1,15 @@
 use crate::portal::addresses::{Addresses, PortalType};
-use crate::{portal::TcpPortalWorker, PortalMessage, TcpOutletOptions, TcpRegistry};
+use crate::portal::ProxyTunnel;
+use crate::{
+    portal::TcpPortalWorker, PortalMessage, ProxyProtocolHeader, TcpOutletOptions, TcpProxy,
+    TcpRegistry, TcpResolver,
+};
+use ockam_core::compat::net::{Ipv4Addr, SocketAddr};
+use ockam_core::compat::sync::Arc;
 use ockam_core::{async_trait, Address, DenyAll, NeutralMessage, Result, Routed, Worker};
 use ockam_node::{Context, WorkerBuilder};
 use ockam_transport_core::TransportError;
-use std::net::SocketAddr;
+use std::path::PathBuf;
 use tracing::{debug, instrument};
 
 /// A TCP Portal Outlet listen worker
@@ -11,35 +17,60 @@ use tracing::{debug, instrument};
 /// TCP Portal Outlet listen workers are created by `TcpTransport`
 /// after a call is made to
 /// [`TcpTransport::create_outlet`](crate::TcpTransport::create_outlet).
+///
+/// The peer is resolved for each new connection, so that the connections follow
+/// the changes of the addresses of a host name.
+///
+/// When a proxy is used for the peer, the connections are tunneled through the proxy,
+/// which resolves the peer.
+///
+/// When a Unix domain socket path is given, the connections are made to that socket instead.
 pub(crate) struct TcpOutletListenWorker {
     registry: TcpRegistry,
-    peer: SocketAddr,
+    resolver: Arc<TcpResolver>,
+    proxy: Option<Arc<TcpProxy>>,
+    peer: String,
+    unix_socket_path: Option<PathBuf>,
     options: TcpOutletOptions,
 }
 
 impl TcpOutletListenWorker {
     /// Create a new `TcpOutletListenWorker`
-    fn new(registry: TcpRegistry, peer: SocketAddr, options: TcpOutletOptions) -> Self {
+    fn new(
+        registry: TcpRegistry,
+        resolver: Arc<TcpResolver>,
+        proxy: Option<Arc<TcpProxy>>,
+        peer: String,
+        unix_socket_path: Option<PathBuf>,
+        options: TcpOutletOptions,
+    ) -> Self {
         Self {
             registry,
+            resolver,
+            proxy,
             peer,
+            unix_socket_path,
             options,
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     #[instrument(skip_all, name = "TcpOutletListenWorker::start")]
     pub(crate) async fn start(
         ctx: &Context,
         registry: TcpRegistry,
+        resolver: Arc<TcpResolver>,
+        proxy: Option<Arc<TcpProxy>>,
         address: Address,
-        peer: SocketAddr,
+        peer: String,
+        unix_socket_path: Option<PathBuf>,
         options: TcpOutletOptions,
     ) -> Result<()> {
         let access_control = options.incoming_access_control.clone();
 
         options.setup_flow_control_for_outlet_listener(ctx.flow_controls(), &address);
 
-        let worker = Self::new(registry, peer, options);
+        let worker = Self::new(registry, resolver, proxy, peer, unix_socket_path, options);
         WorkerBuilder::new(worker)
             .with_address(address)
             .with_incoming_access_control_arc(access_control)
@@ -51,6 +82,31 @@ impl TcpOutletListenWorker {
     }
 }
 
+impl TcpOutletListenWorker {
+    /// Resolve the address to connect to for a new connection, and the proxy tunnel to use, if any
+    async fn resolve_peer(&self) -> Result<(SocketAddr, Option<ProxyTunnel>)> {
+        let proxy_tunnel = self
+            .proxy
+            .as_ref()
+            .filter(|proxy| proxy.is_used_for(&self.peer))
+            .map(|proxy| (proxy.clone(), self.peer.clone()));
+        let connected_to = match &proxy_tunnel {
+            Some((proxy, _)) => proxy.address(),
+            None => self.peer.clone(),
+        };
+        let Some(peer) = self
+            .resolver
+            .resolve(&connected_to, false)
+            .await?
+            .first()
+            .copied()
+        else {
+            return Err(TransportError::InvalidAddress)?;
+        };
+        Ok((peer, proxy_tunnel))
+    }
+}
+
 #[async_trait]
 impl Worker for TcpOutletListenWorker {
     type Context = Context;
@@ -78,13 +134,25 @@ impl Worker for TcpOutletListenWorker {
     ) -> Result<()> {
         let return_route = msg.return_route();
         let src_addr = msg.src_addr();
+        let local_info = msg.local_message().local_info();
         let body = msg.into_body()?.into_vec();
         let msg = PortalMessage::decode(&body)?;
 
-        if !matches!(msg, PortalMessage::Ping) {
-            return Err(TransportError::Protocol)?;
-        }
-
+        // The address of the inlet client is passed on to the target with a PROXY protocol header
+        let proxy_protocol_header = match msg {
+            PortalMessage::Ping => ProxyProtocolHeader::Local,
+            PortalMessage::PingFrom {
+                source,
+                destination,
+            } => ProxyProtocolHeader::proxy(source, destination),
+            _ => return Err(TransportError::Protocol)?,
+        };
+        let proxy_protocol_header = self.options.proxy_protocol.then_some(proxy_protocol_header);
+
+        let (peer, proxy_tunnel) = match &self.unix_socket_path {
+            Some(_) => (SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)), None),
+            None => self.resolve_peer().await?,
+        };
         let addresses = Addresses::generate(PortalType::Outlet);
 
         self.options
@@ -93,10 +161,17 @@ impl Worker for TcpOutletListenWorker {
         TcpPortalWorker::start_new_outlet(
             ctx,
             self.registry.clone(),
-            self.peer,
+            peer,
+            self.unix_socket_path.clone(),
+            proxy_tunnel,
+            proxy_protocol_header,
             return_route.clone(),
             addresses.clone(),
             self.options.incoming_access_control.clone(),
+            local_info,
+            self.options.buffer_size,
+            self.options.chunk_size,
+            self.options.batching,
         )
         .await?;
 
@@ -0,0 +1,137 @@
+use ockam_core::bare::size_of_variable_length;
+use ockam_core::compat::vec::Vec;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Tag of the [`PortalMessage::Payload`](crate::PortalMessage::Payload) variant
+const PAYLOAD_VARIANT: u8 = 3;
+
+/// An encoded [`PortalMessage::Payload`](crate::PortalMessage::Payload) whose payload is read
+/// from a TCP stream right after the space reserved for its header.
+///
+/// The buffer is then sent as is, so that the payload is never copied between the TCP
+/// stream and the routing of the message, and a full chunk needs a single allocation.
+pub(crate) struct PayloadMessageBuffer {
+    message: Vec<u8>,
+    header_size: usize,
+}
+
+impl PayloadMessageBuffer {
+    /// Create a buffer for a payload of at most `chunk_size` bytes
+    pub(crate) fn new(chunk_size: usize) -> Self {
+        let header_size = header_size(chunk_size);
+        let mut message = Vec::with_capacity(header_size + chunk_size);
+        message.resize(header_size, 0);
+        Self {
+            message,
+            header_size,
+        }
+    }
+
+    /// Read at most `chunk_size` bytes from a stream, return the number of bytes read.
+    /// 0 is returned when the stream is closed
+    pub(crate) async fn read_from(
+        &mut self,
+        reader: &mut (impl AsyncRead + Unpin),
+    ) -> std::io::Result<usize> {
+        // the read is limited by the spare capacity of the buffer
+        reader.read_buf(&mut self.message).await
+    }
+
+    /// Payload read so far
+    pub(crate) fn payload(&self) -> &[u8] {
+        &self.message[self.header_size..]
+    }
+
+    /// Return true if less than `chunk_size` bytes were read
+    pub(crate) fn has_room(&self) -> bool {
+        self.message.len() < self.message.capacity()
+    }
+
+    /// Write the header of the message and return it
+    pub(crate) fn into_message(mut self) -> Vec<u8> {
+        let payload_size = self.message.len() - self.header_size;
+        let header_size = header_size(payload_size);
+        // A payload much smaller than the chunk size has a shorter length prefix than the one
+        // reserved. The payload is then moved back, which only happens for small payloads
+        if header_size < self.header_size {
+            self.message.copy_within(self.header_size.., header_size);
+            self.message.truncate(header_size + payload_size);
+        }
+        // Don't keep a mostly empty chunk alive while the message is queued
+        if self.message.len() < self.message.capacity() / 2 {
+            self.message.shrink_to_fit();
+        }
+        self.message[0] = PAYLOAD_VARIANT;
+        write_variable_length_integer(&mut self.message[1..header_size], payload_size as u64);
+        self.message
+    }
+}
+
+/// Size of the variant tag and of the length prefix of a payload
+fn header_size(payload_size: usize) -> usize {
+    1 + size_of_variable_length(payload_size as u64)
+}
+
+/// Write a ULEB128 integer to a slice having exactly the size of its encoding
+fn write_variable_length_integer(destination: &mut [u8], mut value: u64) {
+    for byte in destination.iter_mut() {
+        *byte = (value & 0b0111_1111) as u8;
+        value >>= 7;
+        if value != 0 {
+            *byte |= 0b1000_0000;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PortalMessage;
+    use ockam_core::Encodable;
+
+    #[tokio::test]
+    async fn the_buffer_is_encoded_as_a_payload_message() {
+        for (chunk_size, payload_size) in [
+            (48 * 1024, 48 * 1024),
+            (48 * 1024, 20_000),
+            (48 * 1024, 100),
+            (1024 * 1024, 1024 * 1024),
+            (100, 1),
+        ] {
+            let data: Vec<u8> = (0..payload_size).map(|i| i as u8).collect();
+            let mut buffer = PayloadMessageBuffer::new(chunk_size);
+            let read = buffer.read_from(&mut data.as_slice()).await.unwrap();
+            assert_eq!(read, payload_size);
+            assert_eq!(buffer.payload(), data.as_slice());
+
+            let message = buffer.into_message();
+            assert!(message.capacity() <= 2 * message.len());
+            let expected = PortalMessage::Payload(&data, None).encode().unwrap();
+            assert_eq!(
+                message, expected,
+                "chunk {chunk_size}, payload {payload_size}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn a_full_chunk_is_not_copied() {
+        let chunk_size = 64 * 1024;
+        let data = vec![42u8; 2 * chunk_size];
+
+        let mut reader = data.as_slice();
+        let mut buffer = PayloadMessageBuffer::new(chunk_size);
+        let allocation = buffer.message.as_ptr();
+        let read = buffer.read_from(&mut reader).await.unwrap();
+        // the read doesn't go past the chunk size
+        assert_eq!(read, chunk_size);
+
+        // the payload is read in place, and the message is the buffer itself
+        let message = buffer.into_message();
+        assert_eq!(message.as_ptr(), allocation);
+        assert_eq!(
+            PortalMessage::decode(&message).unwrap(),
+            PortalMessage::Payload(&data[..chunk_size], None)
+        );
+    }
+}
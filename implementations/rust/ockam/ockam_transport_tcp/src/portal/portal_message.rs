@@ -1,4 +1,6 @@
+use crate::ProxyProtocolHeader;
 use ockam_core::bare::{read_slice, write_slice};
+use ockam_core::compat::net::SocketAddr;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{Encodable, Encoded, Message, NeutralMessage};
 use serde::{Deserialize, Serialize};
@@ -8,6 +10,17 @@ use serde::{Deserialize, Serialize};
 pub enum PortalMessage<'de> {
     /// First message that Inlet sends to the Outlet
     Ping,
+    /// First message that Inlet sends to the Outlet, with the addresses of its TCP connection:
+    /// the address of the client and the address the client connected to.
+    ///
+    /// It is encoded as a [`PortalMessage::Ping`] followed by a PROXY protocol v2 header,
+    /// which older outlets decode as a plain [`PortalMessage::Ping`]
+    PingFrom {
+        /// Address of the TCP client of the inlet
+        source: SocketAddr,
+        /// Address the TCP client connected to
+        destination: SocketAddr,
+    },
     /// First message that Outlet sends to the Inlet
     Pong,
     /// Message to indicate that connection from Outlet to the target,
@@ -15,6 +28,11 @@ pub enum PortalMessage<'de> {
     Disconnect,
     /// Message with binary payload and packet counter
     Payload(&'de [u8], Option<u16>),
+    /// Maximum number of payload bytes that can be sent to the side sending this message
+    /// before they are acknowledged. The payloads are then acknowledged with [`PortalMessage::Ack`]
+    BufferSize(u64),
+    /// Number of payload bytes written to the TCP stream
+    Ack(u64),
 }
 
 impl<'de> PortalMessage<'de> {
@@ -30,7 +48,20 @@ impl<'de> PortalMessage<'de> {
         let enum_variant = slice.get(0)?;
         let mut index = 1;
         match enum_variant {
-            0 => Some(PortalMessage::Ping),
+            0 => match ProxyProtocolHeader::decode(&slice[index..]) {
+                Ok((
+                    ProxyProtocolHeader::Proxy {
+                        source,
+                        destination,
+                    },
+                    _,
+                )) => Some(PortalMessage::PingFrom {
+                    source,
+                    destination,
+                }),
+                // the trailing bytes are ignored, as they are by the outlets which don't know them
+                _ => Some(PortalMessage::Ping),
+            },
             1 => Some(PortalMessage::Pong),
             2 => Some(PortalMessage::Disconnect),
             3 => {
@@ -53,6 +84,8 @@ impl<'de> PortalMessage<'de> {
                     None
                 }
             }
+            4 => Some(PortalMessage::BufferSize(read_u64(slice, index)?)),
+            5 => Some(PortalMessage::Ack(read_u64(slice, index)?)),
             _ => None,
         }
     }
@@ -74,6 +107,14 @@ impl PortalMessage<'_> {
     fn internal_encode(self) -> std::io::Result<Encoded> {
         match self {
             PortalMessage::Ping => Ok(vec![0]),
+            PortalMessage::PingFrom {
+                source,
+                destination,
+            } => {
+                let mut vec = vec![0];
+                vec.extend(ProxyProtocolHeader::proxy(source, destination).encode());
+                Ok(vec)
+            }
             PortalMessage::Pong => Ok(vec![1]),
             PortalMessage::Disconnect => Ok(vec![2]),
             PortalMessage::Payload(payload, counter) => {
@@ -93,10 +134,24 @@ impl PortalMessage<'_> {
                 // }
                 Ok(vec)
             }
+            PortalMessage::BufferSize(size) => Ok(encode_u64(4, size)),
+            PortalMessage::Ack(count) => Ok(encode_u64(5, count)),
         }
     }
 }
 
+fn read_u64(slice: &[u8], index: usize) -> Option<u64> {
+    let bytes = slice.get(index..index + 8)?;
+    Some(u64::from_le_bytes(bytes.try_into().ok()?))
+}
+
+fn encode_u64(variant: u8, value: u64) -> Encoded {
+    let mut vec = Vec::with_capacity(9);
+    vec.push(variant);
+    vec.extend_from_slice(&value.to_le_bytes());
+    vec
+}
+
 /// An internal message type for a Portal
 #[derive(Serialize, Deserialize, Message, PartialEq, Eq)]
 pub enum PortalInternalMessage {
@@ -104,12 +159,18 @@ pub enum PortalInternalMessage {
     Disconnect,
 }
 
-/// Maximum allowed size for a payload
+/// Default maximum size of a payload, see [`TcpInletOptions::with_chunk_size`](crate::TcpInletOptions::with_chunk_size)
 pub const MAX_PAYLOAD_SIZE: usize = 48 * 1024;
 
+/// Maximum size of a payload. A portal message must fit in a TCP transport message,
+/// whose length is encoded on 2 bytes, along with its routes and the encryption
+/// overhead of the secure channels of its route
+pub const MAX_CHUNK_SIZE: usize = 60 * 1024;
+
 #[cfg(test)]
 mod test {
     use crate::PortalMessage;
+    use ockam_core::compat::net::SocketAddr;
     use ockam_core::Message;
     use ockam_core::{Decodable, Encodable};
     use serde::{Deserialize, Serialize};
@@ -172,6 +233,43 @@ mod test {
         }
     }
 
+    #[test]
+    fn flow_control_messages_can_be_encoded() {
+        let encoded = PortalMessage::encode(PortalMessage::BufferSize(1024 * 1024)).unwrap();
+        let decoded = PortalMessage::decode(&encoded).unwrap();
+        assert_eq!(decoded, PortalMessage::BufferSize(1024 * 1024));
+
+        let encoded = PortalMessage::encode(PortalMessage::Ack(48 * 1024)).unwrap();
+        let decoded = PortalMessage::decode(&encoded).unwrap();
+        assert_eq!(decoded, PortalMessage::Ack(48 * 1024));
+
+        // A truncated message is invalid
+        assert!(PortalMessage::decode(&encoded[..5]).is_err());
+    }
+
+    #[test]
+    fn ping_with_the_connection_addresses() {
+        let source = SocketAddr::from(([192, 168, 1, 10], 51234));
+        let destination = SocketAddr::from(([127, 0, 0, 1], 5000));
+        let encoded = PortalMessage::encode(PortalMessage::PingFrom {
+            source,
+            destination,
+        })
+        .unwrap();
+        let decoded = PortalMessage::decode(&encoded).unwrap();
+        assert_eq!(
+            decoded,
+            PortalMessage::PingFrom {
+                source,
+                destination
+            }
+        );
+
+        // the outlets which don't know the addresses receive a ping
+        let decoded = PortalMessageV1::decode(&encoded).unwrap();
+        assert!(matches!(decoded, PortalMessageV1::Ping));
+    }
+
     #[ignore]
     #[test]
     fn newer_message_can_be_encoded() {
@@ -1,15 +1,20 @@
-use crate::portal::portal_message::MAX_PAYLOAD_SIZE;
-use crate::{PortalInternalMessage, PortalMessage, TcpRegistry};
-use ockam_core::compat::vec::Vec;
+use crate::portal::payload_buffer::PayloadMessageBuffer;
+use crate::portal::{PortalBackpressure, PortalReadHalf};
+use crate::{
+    PortalInternalMessage, PortalMessage, TcpPortalBatching, TcpPortalConnectionCounters,
+    TcpRegistry,
+};
+use ockam_core::compat::sync::Arc;
 use ockam_core::{
     async_trait, Encodable, LocalMessage, OpenTelemetryContext, Route, OCKAM_TRACER_NAME,
 };
 use ockam_core::{route, Address, Processor, Result};
 use ockam_node::Context;
+use ockam_transport_core::BestEffortDelivery;
 use opentelemetry::global;
 use opentelemetry::trace::Tracer;
-use tokio::{io::AsyncReadExt, net::tcp::OwnedReadHalf};
-use tracing::{error, instrument, warn};
+use tokio::sync::Notify;
+use tracing::{debug, error, instrument, warn};
 
 /// A TCP Portal receiving message processor
 ///
@@ -18,29 +23,82 @@ use tracing::{error, instrument, warn};
 /// [`TcpPortalWorker::start_receiver`](crate::TcpPortalWorker::start_receiver)
 pub(crate) struct TcpPortalRecvProcessor {
     registry: TcpRegistry,
-    buf: Vec<u8>,
-    read_half: OwnedReadHalf,
+    chunk_size: usize,
+    batching: Option<TcpPortalBatching>,
+    read_half: PortalReadHalf,
     sender_address: Address,
     onward_route: Route,
     payload_packet_counter: u16,
+    counters: Arc<TcpPortalConnectionCounters>,
+    close_signal: Arc<Notify>,
+    backpressure: Arc<PortalBackpressure>,
+    stream_end: Option<StreamEnd>,
+}
+
+/// End of the TCP stream, reached while reading a batch of data which must be sent first
+enum StreamEnd {
+    /// The stream was closed, or closing it was requested
+    Closed,
+    /// Reading from the stream failed
+    Failed(std::io::Error),
 }
 
 impl TcpPortalRecvProcessor {
     /// Create a new `TcpPortalRecvProcessor`
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         registry: TcpRegistry,
-        read_half: OwnedReadHalf,
+        chunk_size: usize,
+        batching: Option<TcpPortalBatching>,
+        read_half: PortalReadHalf,
         sender_address: Address,
         onward_route: Route,
+        counters: Arc<TcpPortalConnectionCounters>,
+        close_signal: Arc<Notify>,
+        backpressure: Arc<PortalBackpressure>,
     ) -> Self {
         Self {
             registry,
-            buf: Vec::with_capacity(MAX_PAYLOAD_SIZE),
+            chunk_size,
+            batching,
             read_half,
             sender_address,
             onward_route,
             payload_packet_counter: 0,
+            counters,
+            close_signal,
+            backpressure,
+            stream_end: None,
+        }
+    }
+
+    /// Keep reading from the TCP stream until the batching delay expires, enough data is read,
+    /// or the other side of the portal doesn't accept more data.
+    /// Return the end of the stream if it was reached in the meantime
+    async fn read_batch(
+        &mut self,
+        buffer: &mut PayloadMessageBuffer,
+        batching: TcpPortalBatching,
+    ) -> Option<StreamEnd> {
+        let deadline = tokio::time::Instant::now() + batching.delay();
+        while buffer.has_room() && buffer.payload().len() < batching.size_threshold() {
+            // The data read so far is sent right away, the next read waits for the drain
+            if self.backpressure.is_full() {
+                break;
+            }
+            tokio::select! {
+                res = tokio::time::timeout_at(deadline, buffer.read_from(&mut self.read_half)) => {
+                    match res {
+                        Err(_elapsed) => break,
+                        Ok(Ok(0)) => return Some(StreamEnd::Closed),
+                        Ok(Ok(_)) => {}
+                        Ok(Err(err)) => return Some(StreamEnd::Failed(err)),
+                    }
+                }
+                _ = self.close_signal.notified() => return Some(StreamEnd::Closed),
+            }
         }
+        None
     }
 }
 
@@ -65,22 +123,63 @@ impl Processor for TcpPortalRecvProcessor {
 
     #[instrument(skip_all, name = "TcpPortalRecvProcessor::process")]
     async fn process(&mut self, ctx: &mut Context) -> Result<bool> {
-        self.buf.clear();
+        // The payload is read directly into the message sent to the other side
+        let mut buffer = PayloadMessageBuffer::new(self.chunk_size);
 
-        let _len = match self.read_half.read_buf(&mut self.buf).await {
-            Ok(len) => len,
-            Err(err) => {
+        match self.stream_end.take() {
+            // The stream ended while the previous batch was read, that batch was already sent
+            Some(StreamEnd::Failed(err)) => {
                 error!("Tcp Portal connection read failed with error: {}", err);
                 return Ok(false);
             }
-        };
+            Some(StreamEnd::Closed) => {
+                debug!("Tcp Portal connection {} is closed", ctx.address());
+            }
+            None => {
+                if self.backpressure.is_full() {
+                    debug!(
+                        "Tcp Portal connection {} paused until the other side writes its data",
+                        ctx.address()
+                    );
+                    self.counters.add_backpressure_pause();
+                }
+                let backpressure = self.backpressure.clone();
+                let read_half = &mut self.read_half;
+                let buf = &mut buffer;
+
+                // A connection closed on request is handled as if the TCP stream was closed:
+                // the data read so far has already been forwarded before the other side is notified
+                tokio::select! {
+                    res = async {
+                        // Stop reading while the other side has too much data left to write
+                        backpressure.wait_until_drained().await;
+                        buf.read_from(read_half).await
+                    } => {
+                        if let Err(err) = res {
+                            error!("Tcp Portal connection read failed with error: {}", err);
+                            return Ok(false);
+                        }
+                    }
+                    _ = self.close_signal.notified() => {
+                        debug!("Tcp Portal connection {} is closed on request", ctx.address());
+                    }
+                };
+                if let Some(batching) = self.batching {
+                    if !buffer.payload().is_empty() {
+                        self.stream_end = self.read_batch(&mut buffer, batching).await;
+                    }
+                }
+            }
+        }
+        let received = buffer.payload().len();
+        self.counters.add_received(received);
 
         let tracer = global::tracer(OCKAM_TRACER_NAME);
         let tracing_context = tracer.in_span("TcpPortalRecvProcessor::forward_message", |cx| {
             OpenTelemetryContext::inject(&cx)
         });
 
-        if self.buf.is_empty() {
+        if received == 0 {
             // Notify Sender that connection was closed
             ctx.set_tracing_context(tracing_context.clone());
             if let Err(err) = ctx
@@ -108,19 +207,20 @@ impl Processor for TcpPortalRecvProcessor {
             return Ok(false);
         }
 
-        // Loop just in case buf was extended (should not happen though)
-        for chunk in self.buf.chunks(MAX_PAYLOAD_SIZE) {
-            let msg = LocalMessage::new()
-                .with_tracing_context(tracing_context.clone())
-                .with_onward_route(self.onward_route.clone())
-                .with_return_route(route![self.sender_address.clone()])
-                .with_payload(
-                    PortalMessage::Payload(chunk, Some(self.payload_packet_counter)).encode()?,
-                );
-
-            self.payload_packet_counter += 1;
-            ctx.forward(msg).await?;
-        }
+        // TODO: add the packet counter to the message once orchestrator accepts it
+        // The payloads can be delivered on a best-effort basis by transports like UDP,
+        // contrary to the messages opening and closing the portal
+        let msg = LocalMessage::new()
+            .with_tracing_context(tracing_context)
+            .with_onward_route(self.onward_route.clone())
+            .with_return_route(route![self.sender_address.clone()])
+            .with_payload(buffer.into_message())
+            .with_local_info(vec![BestEffortDelivery::local_info()]);
+
+        self.payload_packet_counter = self.payload_packet_counter.wrapping_add(1);
+        ctx.forward(msg).await?;
+        self.counters.add_payload_message();
+        self.backpressure.add_in_flight(received);
 
         Ok(true)
     }
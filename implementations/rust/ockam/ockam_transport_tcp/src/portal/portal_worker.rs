@@ -1,17 +1,24 @@
 use crate::portal::addresses::{Addresses, PortalType};
-use crate::{portal::TcpPortalRecvProcessor, PortalInternalMessage, PortalMessage, TcpRegistry};
+use crate::{
+    portal::{PortalBackpressure, TcpInletRouteHandle, TcpPortalRecvProcessor},
+    PortalInternalMessage, PortalMessage, ProxyProtocolHeader, TcpPortalBatching,
+    TcpPortalConnectionCounters, TcpPortalConnectionInfo, TcpProxy, TcpRegistry,
+};
+use core::sync::atomic::{AtomicBool, Ordering};
 use core::time::Duration;
-use ockam_core::compat::{boxed::Box, net::SocketAddr, sync::Arc};
+use ockam_core::compat::{boxed::Box, net::SocketAddr, sync::Arc, vec::Vec};
+use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{
-    async_trait, AllowAll, AllowOnwardAddresses, AllowSourceAddress, Decodable, DenyAll,
-    IncomingAccessControl, Mailbox, Mailboxes,
+    async_trait, AllowAll, AllowOnwardAddresses, AllowSourceAddress, AsyncTryClone, Decodable,
+    DenyAll, IncomingAccessControl, LocalInfo, Mailbox, Mailboxes,
 };
 use ockam_core::{Any, Result, Route, Routed, Worker};
 use ockam_node::{Context, ProcessorBuilder, WorkerBuilder};
 use ockam_transport_core::TransportError;
-use tokio::io::AsyncWriteExt;
-use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use std::path::PathBuf;
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
 use tokio::net::TcpStream;
+use tokio::sync::Notify;
 use tracing::{debug, info, instrument, trace, warn};
 
 /// Enumerate all `TcpPortalWorker` states
@@ -28,6 +35,48 @@ enum State {
     Initialized,
 }
 
+/// Time given to the client of an inlet to send its PROXY protocol header
+const PROXY_PROTOCOL_HEADER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Proxy, reachable at the peer address of an outlet, and the target that the proxy must connect to
+pub(super) type ProxyTunnel = (Arc<TcpProxy>, String);
+
+/// Half of the stream read by a portal: a TCP stream, or a Unix domain socket stream for an outlet
+pub(super) type PortalReadHalf = Box<dyn AsyncRead + Send + Unpin>;
+
+/// Half of the stream written by a portal
+type PortalWriteHalf = Box<dyn AsyncWrite + Send + Unpin>;
+
+/// Stream of a portal connection, split in two halves
+pub(crate) struct PortalStream {
+    read_half: PortalReadHalf,
+    write_half: PortalWriteHalf,
+    /// Local address of a TCP stream
+    local_address: Option<SocketAddr>,
+}
+
+impl PortalStream {
+    pub(crate) fn tcp(stream: TcpStream) -> Self {
+        let local_address = stream.local_addr().ok();
+        let (rx, tx) = stream.into_split();
+        Self {
+            read_half: Box::new(rx),
+            write_half: Box::new(tx),
+            local_address,
+        }
+    }
+
+    #[cfg(unix)]
+    pub(crate) fn unix(stream: tokio::net::UnixStream) -> Self {
+        let (rx, tx) = stream.into_split();
+        Self {
+            read_half: Box::new(rx),
+            write_half: Box::new(tx),
+            local_address: None,
+        }
+    }
+}
+
 /// A TCP Portal worker
 ///
 /// A TCP Portal worker is responsible for managing the life-cycle of
@@ -37,60 +86,115 @@ enum State {
 pub(crate) struct TcpPortalWorker {
     registry: TcpRegistry,
     state: State,
-    write_half: Option<OwnedWriteHalf>,
-    read_half: Option<OwnedReadHalf>,
+    write_half: Option<PortalWriteHalf>,
+    read_half: Option<PortalReadHalf>,
     peer: SocketAddr,
+    unix_socket_path: Option<PathBuf>,
+    local_address: Option<SocketAddr>,
+    proxy_tunnel: Option<ProxyTunnel>,
+    /// For an inlet: read a PROXY protocol header from the client before sending the ping
+    expect_proxy_protocol: bool,
+    /// For an inlet: address the client connected to, given by its PROXY protocol header.
+    /// For an outlet: header written to the target before any payload
+    proxy_protocol_header: Option<ProxyProtocolHeader>,
     addresses: Addresses,
     remote_route: Option<Route>,
     is_disconnecting: bool,
     portal_type: PortalType,
     last_received_packet_counter: u16,
+    remote_local_info: Vec<LocalInfo>,
+    counters: Arc<TcpPortalConnectionCounters>,
+    close_signal: Arc<Notify>,
+    inlet_route: Option<TcpInletRouteHandle>,
+    pong_received: Arc<AtomicBool>,
+    buffer_size: usize,
+    chunk_size: usize,
+    batching: Option<TcpPortalBatching>,
+    backpressure: Arc<PortalBackpressure>,
+    acknowledge_payloads: bool,
 }
 
 impl TcpPortalWorker {
     /// Start a new `TcpPortalWorker` of type [`TypeName::Inlet`]
+    #[allow(clippy::too_many_arguments)]
     #[instrument(skip_all)]
     pub(super) async fn start_new_inlet(
         ctx: &Context,
         registry: TcpRegistry,
-        stream: TcpStream,
+        stream: PortalStream,
         peer: SocketAddr,
-        ping_route: Route,
+        unix_socket_path: Option<PathBuf>,
+        inlet_route: TcpInletRouteHandle,
         addresses: Addresses,
         access_control: Arc<dyn IncomingAccessControl>,
+        buffer_size: usize,
+        chunk_size: usize,
+        batching: Option<TcpPortalBatching>,
+        expect_proxy_protocol: bool,
     ) -> Result<()> {
         Self::start(
             ctx,
             registry,
             peer,
-            State::SendPing { ping_route },
+            unix_socket_path,
+            None,
+            expect_proxy_protocol,
+            None,
+            State::SendPing {
+                ping_route: inlet_route.route().clone(),
+            },
             Some(stream),
             addresses,
             PortalType::Inlet,
             access_control,
+            vec![],
+            Some(inlet_route),
+            buffer_size,
+            chunk_size,
+            batching,
         )
         .await
     }
 
-    /// Start a new `TcpPortalWorker` of type [`TypeName::Outlet`]
+    /// Start a new `TcpPortalWorker` of type [`TypeName::Outlet`].
+    /// When a proxy tunnel is given, `peer` is the address of the proxy.
+    /// When a Unix domain socket path is given, the outlet connects to it and `peer` is unspecified.
+    /// When a PROXY protocol header is given, it is written to the target once connected
+    #[allow(clippy::too_many_arguments)]
     #[instrument(skip_all)]
     pub(super) async fn start_new_outlet(
         ctx: &Context,
         registry: TcpRegistry,
         peer: SocketAddr,
+        unix_socket_path: Option<PathBuf>,
+        proxy_tunnel: Option<ProxyTunnel>,
+        proxy_protocol_header: Option<ProxyProtocolHeader>,
         pong_route: Route,
         addresses: Addresses,
         access_control: Arc<dyn IncomingAccessControl>,
+        remote_local_info: Vec<LocalInfo>,
+        buffer_size: usize,
+        chunk_size: usize,
+        batching: Option<TcpPortalBatching>,
     ) -> Result<()> {
         Self::start(
             ctx,
             registry,
             peer,
+            unix_socket_path,
+            proxy_tunnel,
+            false,
+            proxy_protocol_header,
             State::SendPong { pong_route },
             None,
             addresses,
             PortalType::Outlet,
             access_control,
+            remote_local_info,
+            None,
+            buffer_size,
+            chunk_size,
+            batching,
         )
         .await
     }
@@ -102,11 +206,20 @@ impl TcpPortalWorker {
         ctx: &Context,
         registry: TcpRegistry,
         peer: SocketAddr,
+        unix_socket_path: Option<PathBuf>,
+        proxy_tunnel: Option<ProxyTunnel>,
+        expect_proxy_protocol: bool,
+        proxy_protocol_header: Option<ProxyProtocolHeader>,
         state: State,
-        stream: Option<TcpStream>,
+        stream: Option<PortalStream>,
         addresses: Addresses,
         portal_type: PortalType,
         access_control: Arc<dyn IncomingAccessControl>,
+        remote_local_info: Vec<LocalInfo>,
+        inlet_route: Option<TcpInletRouteHandle>,
+        buffer_size: usize,
+        chunk_size: usize,
+        batching: Option<TcpPortalBatching>,
     ) -> Result<()> {
         info!(
             "Creating new {:?} at internal: {}, remote: {}",
@@ -115,12 +228,9 @@ impl TcpPortalWorker {
             addresses.remote
         );
 
-        let (rx, tx) = match stream {
-            Some(s) => {
-                let (rx, tx) = s.into_split();
-                (Some(rx), Some(tx))
-            }
-            None => (None, None),
+        let (rx, tx, local_address) = match stream {
+            Some(s) => (Some(s.read_half), Some(s.write_half), s.local_address),
+            None => (None, None, None),
         };
 
         let worker = Self {
@@ -129,11 +239,26 @@ impl TcpPortalWorker {
             write_half: tx,
             read_half: rx,
             peer,
+            unix_socket_path,
+            local_address,
+            proxy_tunnel,
+            expect_proxy_protocol,
+            proxy_protocol_header,
             addresses: addresses.clone(),
             remote_route: None,
             is_disconnecting: false,
             portal_type,
             last_received_packet_counter: u16::MAX,
+            remote_local_info,
+            counters: Default::default(),
+            close_signal: Arc::new(Notify::new()),
+            inlet_route,
+            pong_received: Arc::new(AtomicBool::new(false)),
+            buffer_size,
+            chunk_size,
+            batching,
+            backpressure: Default::default(),
+            acknowledge_payloads: false,
         };
 
         let internal_mailbox = Mailbox::new(
@@ -176,9 +301,14 @@ impl TcpPortalWorker {
             let next_hop = onward_route.next()?.clone();
             let receiver = TcpPortalRecvProcessor::new(
                 self.registry.clone(),
+                self.chunk_size,
+                self.batching,
                 rx,
                 self.addresses.internal.clone(),
                 onward_route,
+                self.counters.clone(),
+                self.close_signal.clone(),
+                self.backpressure.clone(),
             );
 
             ProcessorBuilder::new(receiver)
@@ -270,6 +400,18 @@ impl TcpPortalWorker {
             }
         }
 
+        // Flush the data written so far and close the TCP stream
+        if let Some(mut tx) = self.write_half.take() {
+            if let Err(err) = tx.shutdown().await {
+                debug!(
+                    "{:?} at: {} could not shutdown the tcp stream: {}",
+                    self.portal_type.str(),
+                    self.addresses.internal,
+                    err
+                );
+            }
+        }
+
         ctx.stop_worker(self.addresses.internal.clone()).await?;
 
         info!(
@@ -281,12 +423,63 @@ impl TcpPortalWorker {
         Ok(())
     }
 
+    /// Let the other side know how many bytes it can send before they are written to the TCP
+    /// stream. The other side then stops reading from its own TCP stream when this limit is
+    /// reached, until the payloads written here are acknowledged.
+    #[instrument(skip_all)]
+    async fn announce_buffer_size(&self, ctx: &Context, remote_route: &Route) -> Result<()> {
+        ctx.send_from_address(
+            remote_route.clone(),
+            PortalMessage::BufferSize(self.buffer_size as u64).to_neutral_message()?,
+            self.addresses.remote.clone(),
+        )
+        .await
+    }
+
+    /// Read the PROXY protocol header sent by the client of an inlet. The client address of
+    /// the header replaces the address of the connection, which is the address of a proxy
+    #[instrument(skip_all)]
+    async fn read_proxy_protocol_header(&mut self) -> Result<()> {
+        let Some(rx) = self.read_half.as_mut() else {
+            return Err(TransportError::PortalInvalidState)?;
+        };
+        let header =
+            tokio::time::timeout(PROXY_PROTOCOL_HEADER_TIMEOUT, ProxyProtocolHeader::read(rx))
+                .await
+                .map_err(|_| {
+                    ockam_core::Error::new(
+                        Origin::Transport,
+                        Kind::Timeout,
+                        "the client didn't send a PROXY protocol v2 header in time",
+                    )
+                })??;
+        if let ProxyProtocolHeader::Proxy { source, .. } = header {
+            self.peer = source;
+            self.proxy_protocol_header = Some(header);
+        }
+        Ok(())
+    }
+
     #[instrument(skip_all)]
     async fn handle_send_ping(&self, ctx: &Context, ping_route: Route) -> Result<State> {
+        // The outlet can pass on the addresses of the client connection to its target,
+        // they are unknown for a Unix domain socket
+        let destination = match self.proxy_protocol_header {
+            Some(ProxyProtocolHeader::Proxy { destination, .. }) => Some(destination),
+            _ => self.local_address,
+        };
+        let ping = match destination {
+            Some(destination) if !self.peer.ip().is_unspecified() => PortalMessage::PingFrom {
+                source: self.peer,
+                destination,
+            },
+            _ => PortalMessage::Ping,
+        };
+
         // Force creation of Outlet on the other side
         ctx.send_from_address(
             ping_route,
-            PortalMessage::Ping.to_neutral_message()?,
+            ping.to_neutral_message()?,
             self.addresses.remote.clone(),
         )
         .await?;
@@ -296,15 +489,37 @@ impl TcpPortalWorker {
         Ok(State::ReceivePong)
     }
 
+    /// Connect the outlet to its TCP server, possibly via a proxy, or to its Unix domain socket
     #[instrument(skip_all)]
-    async fn handle_send_pong(&mut self, ctx: &Context, pong_route: Route) -> Result<State> {
-        if self.write_half.is_none() {
-            let stream = TcpStream::connect(self.peer)
+    async fn connect_outlet(&mut self) -> Result<()> {
+        let stream = match (&self.unix_socket_path, &self.proxy_tunnel) {
+            (Some(path), _) => connect_unix_socket(path).await?,
+            (None, Some((proxy, target))) => {
+                PortalStream::tcp(proxy.connect(self.peer, target).await?)
+            }
+            (None, None) => PortalStream::tcp(
+                TcpStream::connect(self.peer)
+                    .await
+                    .map_err(TransportError::from)?,
+            ),
+        };
+        let mut write_half = stream.write_half;
+        if let Some(header) = &self.proxy_protocol_header {
+            write_half
+                .write_all(&header.encode())
                 .await
                 .map_err(TransportError::from)?;
-            let (rx, tx) = stream.into_split();
-            self.write_half = Some(tx);
-            self.read_half = Some(rx);
+        }
+        self.read_half = Some(stream.read_half);
+        self.write_half = Some(write_half);
+        self.local_address = stream.local_address;
+        Ok(())
+    }
+
+    #[instrument(skip_all)]
+    async fn handle_send_pong(&mut self, ctx: &Context, pong_route: Route) -> Result<State> {
+        if self.write_half.is_none() {
+            self.connect_outlet().await?;
 
             // Respond to Inlet before starting the processor but
             // after the connection has been established
@@ -333,6 +548,7 @@ impl TcpPortalWorker {
 
         debug!("Outlet at: {} sent pong", self.addresses.internal);
 
+        self.announce_buffer_size(ctx, &pong_route).await?;
         self.remote_route = Some(pong_route);
         Ok(State::Initialized)
     }
@@ -349,7 +565,28 @@ impl Worker for TcpPortalWorker {
 
         match state {
             State::SendPing { ping_route } => {
-                self.state = self.handle_send_ping(ctx, ping_route.clone()).await?;
+                if self.expect_proxy_protocol {
+                    if let Err(err) = self.read_proxy_protocol_header().await {
+                        warn!(
+                            "Inlet at: {} closes the connection from {}: {}",
+                            self.addresses.internal, self.peer, err
+                        );
+                        if let Some(inlet_route) = self.inlet_route.take() {
+                            inlet_route.connection_closed();
+                        }
+                        return Err(err);
+                    }
+                }
+                self.state = match self.handle_send_ping(ctx, ping_route.clone()).await {
+                    Ok(state) => state,
+                    Err(err) => {
+                        if let Some(inlet_route) = self.inlet_route.take() {
+                            inlet_route.connection_failed();
+                            inlet_route.connection_closed();
+                        }
+                        return Err(err);
+                    }
+                };
             }
             State::SendPong { pong_route } => {
                 self.state = self.handle_send_pong(ctx, pong_route.clone()).await?;
@@ -359,7 +596,23 @@ impl Worker for TcpPortalWorker {
             }
         }
 
-        self.registry.add_portal_worker(&self.addresses.remote);
+        if let Some(outlet_timeout) = self.inlet_route.as_ref().and_then(|r| r.outlet_timeout()) {
+            self.close_if_no_pong(ctx, outlet_timeout).await?;
+        }
+
+        self.registry.add_portal_worker(
+            TcpPortalConnectionInfo::new(
+                self.addresses.remote.clone(),
+                self.addresses.receiver.clone(),
+                self.portal_type,
+                self.peer,
+                self.local_address,
+                self.remote_local_info.clone(),
+                self.counters.clone(),
+                self.close_signal.clone(),
+            )
+            .with_unix_socket_path(self.unix_socket_path.clone()),
+        );
 
         Ok(())
     }
@@ -368,6 +621,14 @@ impl Worker for TcpPortalWorker {
     async fn shutdown(&mut self, _ctx: &mut Self::Context) -> Result<()> {
         self.registry.remove_portal_worker(&self.addresses.remote);
 
+        if let Some(inlet_route) = self.inlet_route.take() {
+            // The outlet never answered the ping
+            if matches!(self.state, State::ReceivePong) {
+                inlet_route.connection_failed();
+            }
+            inlet_route.connection_closed();
+        }
+
         Ok(())
     }
 
@@ -389,6 +650,12 @@ impl Worker for TcpPortalWorker {
         }
         let return_route = msg.return_route();
         let remote_packet = recipient != self.addresses.internal;
+        // The local info identifies the other side of the portal, only keep it once
+        let local_info = if matches!(state, State::ReceivePong) {
+            msg.local_message().local_info()
+        } else {
+            vec![]
+        };
         let payload = msg.into_payload();
 
         match state {
@@ -399,7 +666,8 @@ impl Worker for TcpPortalWorker {
                 if PortalMessage::decode(&payload)? != PortalMessage::Pong {
                     return Err(TransportError::Protocol)?;
                 };
-                self.handle_receive_pong(ctx, return_route).await
+                self.handle_receive_pong(ctx, return_route, local_info)
+                    .await
             }
             State::Initialized => {
                 trace!(
@@ -420,7 +688,17 @@ impl Worker for TcpPortalWorker {
                             self.start_disconnection(ctx, DisconnectionReason::Remote)
                                 .await
                         }
-                        PortalMessage::Ping | PortalMessage::Pong => {
+                        PortalMessage::BufferSize(buffer_size) => {
+                            self.handle_buffer_size(buffer_size);
+                            Ok(())
+                        }
+                        PortalMessage::Ack(count) => {
+                            self.backpressure.acknowledge(count);
+                            Ok(())
+                        }
+                        PortalMessage::Ping
+                        | PortalMessage::PingFrom { .. }
+                        | PortalMessage::Pong => {
                             return Err(TransportError::Protocol)?;
                         }
                     }
@@ -441,14 +719,55 @@ impl Worker for TcpPortalWorker {
 
 impl TcpPortalWorker {
     #[instrument(skip_all)]
-    async fn handle_receive_pong(&mut self, ctx: &Context, return_route: Route) -> Result<()> {
+    async fn handle_receive_pong(
+        &mut self,
+        ctx: &Context,
+        return_route: Route,
+        local_info: Vec<LocalInfo>,
+    ) -> Result<()> {
+        self.registry
+            .set_portal_worker_remote_local_info(&self.addresses.remote, local_info);
         self.start_receiver(ctx, return_route.clone()).await?;
         debug!("Inlet at: {} received pong", self.addresses.internal);
+        self.pong_received.store(true, Ordering::Relaxed);
+        if let Some(inlet_route) = &self.inlet_route {
+            inlet_route.connection_succeeded();
+        }
+        self.announce_buffer_size(ctx, &return_route).await?;
         self.remote_route = Some(return_route);
         self.state = State::Initialized;
         Ok(())
     }
 
+    /// The other side acknowledges the payloads it writes to its TCP stream: from now on
+    /// acknowledge ours, and stop reading from the TCP stream when its buffer is full
+    fn handle_buffer_size(&mut self, buffer_size: u64) {
+        debug!(
+            "{:?} at: {} has a remote buffer size of {} bytes",
+            self.portal_type.str(),
+            self.addresses.internal,
+            buffer_size
+        );
+        self.acknowledge_payloads = true;
+        self.backpressure.set_buffer_size(buffer_size);
+    }
+
+    /// Stop the inlet if the outlet didn't answer the ping after `outlet_timeout`
+    #[instrument(skip_all)]
+    async fn close_if_no_pong(&self, ctx: &Context, outlet_timeout: Duration) -> Result<()> {
+        let ctx = ctx.async_try_clone().await?;
+        let address = self.addresses.internal.clone();
+        let pong_received = self.pong_received.clone();
+        tokio::spawn(async move {
+            ctx.sleep(outlet_timeout).await;
+            if !pong_received.load(Ordering::Relaxed) {
+                warn!("Inlet at: {} didn't receive a pong, closing it", address);
+                let _ = ctx.stop_worker(address).await;
+            }
+        });
+        Ok(())
+    }
+
     #[instrument(skip_all)]
     async fn handle_disconnect(&mut self, ctx: &Context) -> Result<()> {
         info!(
@@ -456,6 +775,17 @@ impl TcpPortalWorker {
             self.portal_type.str(),
             self.addresses.internal
         );
+        // A target which doesn't expect the PROXY protocol header usually closes the
+        // connection right away, since it reads the header as invalid data
+        if self.portal_type == PortalType::Outlet
+            && self.proxy_protocol_header.is_some()
+            && self.counters.bytes_received() == 0
+        {
+            warn!(
+                "The target {} of the outlet at: {} closed the connection without sending any data. Check that it expects a PROXY protocol v2 header",
+                self.peer, self.addresses.internal
+            );
+        }
         self.start_disconnection(ctx, DisconnectionReason::FailedRx)
             .await
     }
@@ -471,7 +801,10 @@ impl TcpPortalWorker {
         self.check_packet_counter(ctx, packet_counter).await?;
         if let Some(tx) = &mut self.write_half {
             match tx.write_all(payload).await {
-                Ok(()) => {}
+                Ok(()) => {
+                    self.counters.add_sent(payload.len());
+                    self.acknowledge_payload(ctx, payload.len()).await?;
+                }
                 Err(err) => {
                     warn!(
                         "Failed to send message to peer {} with error: {}",
@@ -487,6 +820,23 @@ impl TcpPortalWorker {
         Ok(())
     }
 
+    /// Let the other side know that a payload was written to the TCP stream
+    #[instrument(skip_all)]
+    async fn acknowledge_payload(&self, ctx: &Context, count: usize) -> Result<()> {
+        if !self.acknowledge_payloads {
+            return Ok(());
+        }
+        if let Some(remote_route) = &self.remote_route {
+            ctx.send_from_address(
+                remote_route.clone(),
+                PortalMessage::Ack(count as u64).to_neutral_message()?,
+                self.addresses.remote.clone(),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
     #[instrument(skip_all)]
     async fn check_packet_counter(
         &mut self,
@@ -514,3 +864,17 @@ impl TcpPortalWorker {
         Ok(())
     }
 }
+
+/// Connect to a Unix domain socket
+#[cfg(unix)]
+async fn connect_unix_socket(path: &std::path::Path) -> Result<PortalStream> {
+    let stream = tokio::net::UnixStream::connect(path)
+        .await
+        .map_err(|e| crate::transport::common::unix_socket_error(path, e))?;
+    Ok(PortalStream::unix(stream))
+}
+
+#[cfg(not(unix))]
+async fn connect_unix_socket(_path: &std::path::Path) -> Result<PortalStream> {
+    Err(TransportError::InvalidAddress)?
+}
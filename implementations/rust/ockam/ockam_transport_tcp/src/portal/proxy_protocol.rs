@@ -0,0 +1,314 @@
+use ockam_core::compat::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::Result;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Signature starting a PROXY protocol v2 header
+const SIGNATURE: [u8; 12] = [
+    0x0D, 0x0A, 0x0D, 0x0A, 0x00, 0x0D, 0x0A, 0x51, 0x55, 0x49, 0x54, 0x0A,
+];
+
+/// Length of the fixed part of a header: signature, version and command, family and length
+const FIXED_LENGTH: usize = 16;
+
+const VERSION_2: u8 = 0x20;
+const COMMAND_LOCAL: u8 = 0x00;
+const COMMAND_PROXY: u8 = 0x01;
+const FAMILY_UNSPEC: u8 = 0x00;
+const TCP_OVER_IPV4: u8 = 0x11;
+const TCP_OVER_IPV6: u8 = 0x21;
+const IPV4_ADDRESSES_LENGTH: usize = 12;
+const IPV6_ADDRESSES_LENGTH: usize = 36;
+
+/// Header of the [PROXY protocol v2](https://www.haproxy.org/download/2.9/doc/proxy-protocol.txt),
+/// sent at the start of a TCP connection to carry the addresses of the original client
+/// connection through a proxy.
+///
+/// An outlet sends it to its target, to pass on the address of the client of the inlet.
+/// An inlet can also read it from its own clients, when they are behind another proxy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProxyProtocolHeader {
+    /// The connection was not proxied, for example for a health check, or its addresses
+    /// are unknown. The addresses of the connection itself should be used.
+    Local,
+    /// The connection was proxied for a client at `source`, which connected to `destination`
+    Proxy {
+        /// Address of the client
+        source: SocketAddr,
+        /// Address the client connected to
+        destination: SocketAddr,
+    },
+}
+
+impl ProxyProtocolHeader {
+    /// Create the header of a connection proxied for a client at `source`
+    pub fn proxy(source: SocketAddr, destination: SocketAddr) -> Self {
+        Self::Proxy {
+            source,
+            destination,
+        }
+    }
+
+    /// Encode the header in the binary format of the version 2 of the protocol.
+    ///
+    /// When one of the addresses is an IPv6 address, both addresses are sent as IPv6 addresses,
+    /// an IPv4 address being mapped to an IPv6 address
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(FIXED_LENGTH + IPV6_ADDRESSES_LENGTH);
+        bytes.extend_from_slice(&SIGNATURE);
+        match self {
+            ProxyProtocolHeader::Local => {
+                bytes.extend_from_slice(&[VERSION_2 | COMMAND_LOCAL, FAMILY_UNSPEC, 0, 0]);
+            }
+            ProxyProtocolHeader::Proxy {
+                source,
+                destination,
+            } => {
+                bytes.push(VERSION_2 | COMMAND_PROXY);
+                match (source.ip(), destination.ip()) {
+                    (IpAddr::V4(source_ip), IpAddr::V4(destination_ip)) => {
+                        bytes.push(TCP_OVER_IPV4);
+                        bytes.extend_from_slice(&(IPV4_ADDRESSES_LENGTH as u16).to_be_bytes());
+                        bytes.extend_from_slice(&source_ip.octets());
+                        bytes.extend_from_slice(&destination_ip.octets());
+                    }
+                    (source_ip, destination_ip) => {
+                        bytes.push(TCP_OVER_IPV6);
+                        bytes.extend_from_slice(&(IPV6_ADDRESSES_LENGTH as u16).to_be_bytes());
+                        bytes.extend_from_slice(&to_ipv6(source_ip).octets());
+                        bytes.extend_from_slice(&to_ipv6(destination_ip).octets());
+                    }
+                }
+                bytes.extend_from_slice(&source.port().to_be_bytes());
+                bytes.extend_from_slice(&destination.port().to_be_bytes());
+            }
+        }
+        bytes
+    }
+
+    /// Decode a header at the start of `bytes`, and return it with its length in bytes.
+    ///
+    /// The additional information of the header (TLVs) is skipped. A header with the `PROXY`
+    /// command, but with unspecified or Unix addresses, is decoded as a [`ProxyProtocolHeader::Local`]
+    /// header, since the addresses of the connection itself must then be used.
+    pub fn decode(bytes: &[u8]) -> Result<(Self, usize)> {
+        let fixed: &[u8; FIXED_LENGTH] = bytes
+            .get(..FIXED_LENGTH)
+            .and_then(|fixed| fixed.try_into().ok())
+            .ok_or_else(|| error("the PROXY protocol v2 header is incomplete"))?;
+        let length = FIXED_LENGTH + addresses_length(fixed)?;
+        let addresses = bytes
+            .get(FIXED_LENGTH..length)
+            .ok_or_else(|| error("the PROXY protocol v2 header is incomplete"))?;
+        Ok((decode_addresses(fixed, addresses)?, length))
+    }
+
+    /// Read a header from the start of a stream, without reading any byte after it
+    pub(crate) async fn read(reader: &mut (impl AsyncRead + Unpin)) -> Result<Self> {
+        let mut fixed = [0u8; FIXED_LENGTH];
+        reader.read_exact(&mut fixed).await.map_err(read_error)?;
+        let mut addresses = vec![0u8; addresses_length(&fixed)?];
+        reader
+            .read_exact(&mut addresses)
+            .await
+            .map_err(read_error)?;
+        decode_addresses(&fixed, &addresses)
+    }
+}
+
+/// Check the fixed part of a header and return the length of the rest of the header
+fn addresses_length(fixed: &[u8; FIXED_LENGTH]) -> Result<usize> {
+    if fixed[..SIGNATURE.len()] != SIGNATURE {
+        return Err(error(
+            "the connection doesn't start with a PROXY protocol v2 header",
+        ));
+    }
+    if fixed[12] & 0xF0 != VERSION_2 {
+        return Err(error(format!(
+            "the PROXY protocol version {} is not supported",
+            fixed[12] >> 4
+        )));
+    }
+    Ok(u16::from_be_bytes([fixed[14], fixed[15]]) as usize)
+}
+
+fn decode_addresses(fixed: &[u8; FIXED_LENGTH], addresses: &[u8]) -> Result<ProxyProtocolHeader> {
+    match fixed[12] & 0x0F {
+        COMMAND_LOCAL => Ok(ProxyProtocolHeader::Local),
+        COMMAND_PROXY => match fixed[13] {
+            TCP_OVER_IPV4 if addresses.len() >= IPV4_ADDRESSES_LENGTH => {
+                let ip = |at: usize| {
+                    let octets: [u8; 4] = addresses[at..at + 4].try_into().unwrap();
+                    IpAddr::V4(Ipv4Addr::from(octets))
+                };
+                let port = |at: usize| u16::from_be_bytes([addresses[at], addresses[at + 1]]);
+                Ok(ProxyProtocolHeader::proxy(
+                    SocketAddr::new(ip(0), port(8)),
+                    SocketAddr::new(ip(4), port(10)),
+                ))
+            }
+            TCP_OVER_IPV6 if addresses.len() >= IPV6_ADDRESSES_LENGTH => {
+                let ip = |at: usize| {
+                    let octets: [u8; 16] = addresses[at..at + 16].try_into().unwrap();
+                    IpAddr::V6(Ipv6Addr::from(octets))
+                };
+                let port = |at: usize| u16::from_be_bytes([addresses[at], addresses[at + 1]]);
+                Ok(ProxyProtocolHeader::proxy(
+                    SocketAddr::new(ip(0), port(32)),
+                    SocketAddr::new(ip(16), port(34)),
+                ))
+            }
+            TCP_OVER_IPV4 | TCP_OVER_IPV6 => Err(error(
+                "the addresses of the PROXY protocol v2 header are incomplete",
+            )),
+            _ => Ok(ProxyProtocolHeader::Local),
+        },
+        command => Err(error(format!(
+            "the PROXY protocol v2 command {command} is not supported"
+        ))),
+    }
+}
+
+fn to_ipv6(ip: IpAddr) -> Ipv6Addr {
+    match ip {
+        IpAddr::V4(ip) => ip.to_ipv6_mapped(),
+        IpAddr::V6(ip) => ip,
+    }
+}
+
+fn read_error(err: std::io::Error) -> ockam_core::Error {
+    error(format!(
+        "the PROXY protocol v2 header could not be read: {err}"
+    ))
+}
+
+fn error(message: impl Into<String>) -> ockam_core::Error {
+    ockam_core::Error::new(Origin::Transport, Kind::Protocol, message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::str::FromStr;
+
+    #[test]
+    fn ipv4_header() {
+        let header = ProxyProtocolHeader::proxy(
+            SocketAddr::from_str("192.168.1.10:51234").unwrap(),
+            SocketAddr::from_str("10.0.0.1:443").unwrap(),
+        );
+        let bytes = header.encode();
+        assert_eq!(&bytes[..12], &SIGNATURE);
+        assert_eq!(&bytes[12..16], &[0x21, 0x11, 0x00, 0x0C]);
+        assert_eq!(
+            &bytes[16..],
+            &[192, 168, 1, 10, 10, 0, 0, 1, 0xC8, 0x22, 0x01, 0xBB]
+        );
+        assert_eq!(
+            ProxyProtocolHeader::decode(&bytes).unwrap(),
+            (header, bytes.len())
+        );
+    }
+
+    #[test]
+    fn ipv6_header() {
+        let header = ProxyProtocolHeader::proxy(
+            SocketAddr::from_str("[2001:db8::1]:51234").unwrap(),
+            SocketAddr::from_str("[::1]:443").unwrap(),
+        );
+        let bytes = header.encode();
+        assert_eq!(&bytes[12..16], &[0x21, 0x21, 0x00, 0x24]);
+        assert_eq!(bytes.len(), 16 + 36);
+        assert_eq!(
+            ProxyProtocolHeader::decode(&bytes).unwrap(),
+            (header, bytes.len())
+        );
+
+        // an IPv4 address is mapped to an IPv6 address when the other one is an IPv6 address
+        let mixed = ProxyProtocolHeader::proxy(
+            SocketAddr::from_str("192.168.1.10:51234").unwrap(),
+            SocketAddr::from_str("[::1]:443").unwrap(),
+        );
+        let (decoded, _) = ProxyProtocolHeader::decode(&mixed.encode()).unwrap();
+        assert_eq!(
+            decoded,
+            ProxyProtocolHeader::proxy(
+                SocketAddr::from_str("[::ffff:192.168.1.10]:51234").unwrap(),
+                SocketAddr::from_str("[::1]:443").unwrap(),
+            )
+        );
+    }
+
+    #[test]
+    fn local_header() {
+        let bytes = ProxyProtocolHeader::Local.encode();
+        assert_eq!(&bytes[12..], &[0x20, 0x00, 0x00, 0x00]);
+        assert_eq!(
+            ProxyProtocolHeader::decode(&bytes).unwrap(),
+            (ProxyProtocolHeader::Local, 16)
+        );
+
+        // a PROXY command for Unix addresses is handled like a LOCAL command
+        let mut unix = SIGNATURE.to_vec();
+        unix.extend_from_slice(&[0x21, 0x31, 0x00, 0xD8]);
+        unix.extend_from_slice(&[0u8; 216]);
+        assert_eq!(
+            ProxyProtocolHeader::decode(&unix).unwrap(),
+            (ProxyProtocolHeader::Local, unix.len())
+        );
+    }
+
+    #[test]
+    fn the_additional_information_and_the_data_are_skipped() {
+        let header = ProxyProtocolHeader::proxy(
+            SocketAddr::from_str("192.168.1.10:51234").unwrap(),
+            SocketAddr::from_str("10.0.0.1:443").unwrap(),
+        );
+        let mut bytes = header.encode();
+        // add a NOOP TLV to the header, then some data
+        bytes[15] += 4;
+        bytes.extend_from_slice(&[0x04, 0x00, 0x01, 0x00]);
+        let length = bytes.len();
+        bytes.extend_from_slice(b"GET / HTTP/1.1\r\n");
+        assert_eq!(
+            ProxyProtocolHeader::decode(&bytes).unwrap(),
+            (header, length)
+        );
+    }
+
+    #[test]
+    fn invalid_headers() {
+        assert!(ProxyProtocolHeader::decode(b"GET / HTTP/1.1\r\nHost: localhost\r\n").is_err());
+        // version 1 text header
+        assert!(
+            ProxyProtocolHeader::decode(b"PROXY TCP4 192.168.1.10 10.0.0.1 51234 443\r\n").is_err()
+        );
+
+        let bytes = ProxyProtocolHeader::proxy(
+            SocketAddr::from_str("192.168.1.10:51234").unwrap(),
+            SocketAddr::from_str("10.0.0.1:443").unwrap(),
+        )
+        .encode();
+        assert!(ProxyProtocolHeader::decode(&bytes[..20]).is_err());
+        let mut unknown_command = bytes.clone();
+        unknown_command[12] = 0x22;
+        assert!(ProxyProtocolHeader::decode(&unknown_command).is_err());
+    }
+
+    #[tokio::test]
+    async fn read_header_from_a_stream() {
+        let header = ProxyProtocolHeader::proxy(
+            SocketAddr::from_str("[2001:db8::1]:51234").unwrap(),
+            SocketAddr::from_str("[::1]:443").unwrap(),
+        );
+        let mut bytes = header.encode();
+        bytes.extend_from_slice(b"data");
+        let mut reader = bytes.as_slice();
+        assert_eq!(
+            ProxyProtocolHeader::read(&mut reader).await.unwrap(),
+            header
+        );
+        assert_eq!(reader, b"data");
+    }
+}
@@ -1,8 +1,14 @@
+use crate::PortalType;
 use core::fmt;
 use core::fmt::Formatter;
+use ockam_core::compat::sync::Arc;
 use ockam_core::flow_control::FlowControlId;
-use ockam_core::Address;
+use ockam_core::{Address, LocalInfo};
 use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
+use tokio::sync::Notify;
 
 /// Tcp connection mode
 #[derive(Copy, Debug, Clone)]
@@ -22,6 +28,34 @@ impl fmt::Display for TcpConnectionMode {
     }
 }
 
+/// IP family of a Tcp connection. When a peer has both IPv4 and IPv6 addresses, it is the
+/// family of the address whose connection attempt succeeded first
+#[derive(Copy, Debug, Clone, PartialEq, Eq)]
+pub enum IpFamily {
+    /// The connection uses an IPv4 address
+    Ipv4,
+    /// The connection uses an IPv6 address
+    Ipv6,
+}
+
+impl From<&SocketAddr> for IpFamily {
+    fn from(socket_address: &SocketAddr) -> Self {
+        match socket_address {
+            SocketAddr::V4(_) => IpFamily::Ipv4,
+            SocketAddr::V6(_) => IpFamily::Ipv6,
+        }
+    }
+}
+
+impl fmt::Display for IpFamily {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            IpFamily::Ipv4 => write!(f, "ipv4"),
+            IpFamily::Ipv6 => write!(f, "ipv6"),
+        }
+    }
+}
+
 /// Information about specific Tcp sender (corresponds to one specific Tcp connection)
 #[derive(Debug, Clone)]
 pub struct TcpSenderInfo {
@@ -62,6 +96,10 @@ impl TcpSenderInfo {
     pub fn socket_address(&self) -> SocketAddr {
         self.socket_address
     }
+    /// [`IpFamily`] of the socket address
+    pub fn ip_family(&self) -> IpFamily {
+        IpFamily::from(&self.socket_address)
+    }
     /// Corresponding [`FlowControlId`]
     pub fn flow_control_id(&self) -> &FlowControlId {
         &self.flow_control_id
@@ -128,6 +166,7 @@ pub struct TcpListenerInfo {
     address: Address,
     socket_address: SocketAddr,
     flow_control_id: FlowControlId,
+    max_message_size: usize,
 }
 
 impl TcpListenerInfo {
@@ -136,11 +175,13 @@ impl TcpListenerInfo {
         address: Address,
         socket_address: SocketAddr,
         flow_control_id: FlowControlId,
+        max_message_size: usize,
     ) -> Self {
         Self {
             address,
             socket_address,
             flow_control_id,
+            max_message_size,
         }
     }
 
@@ -156,4 +197,154 @@ impl TcpListenerInfo {
     pub fn flow_control_id(&self) -> &FlowControlId {
         &self.flow_control_id
     }
+    /// Maximum size of a message received on the accepted connections
+    pub fn max_message_size(&self) -> usize {
+        self.max_message_size
+    }
+}
+
+/// Information about a specific portal connection (corresponds to one portal worker)
+#[derive(Debug, Clone)]
+pub struct TcpPortalConnectionInfo {
+    address: Address,
+    receiver_address: Address,
+    portal_type: PortalType,
+    peer_address: SocketAddr,
+    unix_socket_path: Option<PathBuf>,
+    local_address: Option<SocketAddr>,
+    started_at: SystemTime,
+    remote_local_info: Vec<LocalInfo>,
+    counters: Arc<TcpPortalConnectionCounters>,
+    close_signal: Arc<Notify>,
+}
+
+impl TcpPortalConnectionInfo {
+    /// Constructor
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        address: Address,
+        receiver_address: Address,
+        portal_type: PortalType,
+        peer_address: SocketAddr,
+        local_address: Option<SocketAddr>,
+        remote_local_info: Vec<LocalInfo>,
+        counters: Arc<TcpPortalConnectionCounters>,
+        close_signal: Arc<Notify>,
+    ) -> Self {
+        Self {
+            address,
+            receiver_address,
+            portal_type,
+            peer_address,
+            unix_socket_path: None,
+            local_address,
+            started_at: SystemTime::now(),
+            remote_local_info,
+            counters,
+            close_signal,
+        }
+    }
+
+    /// Remote address of the portal worker, which identifies the connection
+    pub fn address(&self) -> &Address {
+        &self.address
+    }
+    /// Address of the processor reading from the TCP stream
+    pub fn receiver_address(&self) -> &Address {
+        &self.receiver_address
+    }
+    /// [`PortalType`] for this connection
+    pub fn portal_type(&self) -> PortalType {
+        self.portal_type
+    }
+    /// Address of the TCP peer: the client connected to an inlet, or the server an outlet connects to.
+    /// It is unspecified when the outlet connects to a Unix domain socket
+    pub fn peer_address(&self) -> SocketAddr {
+        self.peer_address
+    }
+    /// Path of the Unix domain socket an outlet connects to, if it doesn't connect to a TCP server
+    pub fn unix_socket_path(&self) -> Option<&Path> {
+        self.unix_socket_path.as_deref()
+    }
+    /// Local address of the TCP stream
+    pub fn local_address(&self) -> Option<SocketAddr> {
+        self.local_address
+    }
+    /// Time when the connection was established
+    pub fn started_at(&self) -> SystemTime {
+        self.started_at
+    }
+    /// Local information attached to the messages received from the other side of the portal,
+    /// for example the identity of a secure channel
+    pub fn remote_local_info(&self) -> &[LocalInfo] {
+        &self.remote_local_info
+    }
+    /// Number of bytes written to the TCP stream
+    pub fn bytes_sent(&self) -> u64 {
+        self.counters.bytes_sent()
+    }
+    /// Number of bytes read from the TCP stream
+    pub fn bytes_received(&self) -> u64 {
+        self.counters.bytes_received()
+    }
+    /// Number of times reading from the TCP stream was paused, because the other side of the
+    /// portal had too many bytes left to write to its own TCP stream
+    pub fn backpressure_pauses(&self) -> u64 {
+        self.counters.backpressure_pauses()
+    }
+    /// Number of messages sent to the other side of the portal with the bytes read from the TCP stream
+    pub fn payload_messages(&self) -> u64 {
+        self.counters.payload_messages()
+    }
+
+    pub(crate) fn with_unix_socket_path(mut self, unix_socket_path: Option<PathBuf>) -> Self {
+        self.unix_socket_path = unix_socket_path;
+        self
+    }
+
+    pub(crate) fn set_remote_local_info(&mut self, local_info: Vec<LocalInfo>) {
+        self.remote_local_info = local_info;
+    }
+
+    /// Ask the portal to close the connection
+    pub(crate) fn request_close(&self) {
+        self.close_signal.notify_one()
+    }
+}
+
+/// Number of bytes transferred on a portal connection
+#[derive(Debug, Default)]
+pub(crate) struct TcpPortalConnectionCounters {
+    bytes_sent: AtomicU64,
+    bytes_received: AtomicU64,
+    backpressure_pauses: AtomicU64,
+    payload_messages: AtomicU64,
+}
+
+impl TcpPortalConnectionCounters {
+    pub(crate) fn add_sent(&self, count: usize) {
+        self.bytes_sent.fetch_add(count as u64, Ordering::Relaxed);
+    }
+    pub(crate) fn add_received(&self, count: usize) {
+        self.bytes_received
+            .fetch_add(count as u64, Ordering::Relaxed);
+    }
+    pub(crate) fn bytes_sent(&self) -> u64 {
+        self.bytes_sent.load(Ordering::Relaxed)
+    }
+    pub(crate) fn bytes_received(&self) -> u64 {
+        self.bytes_received.load(Ordering::Relaxed)
+    }
+    pub(crate) fn add_backpressure_pause(&self) {
+        self.backpressure_pauses.fetch_add(1, Ordering::Relaxed);
+    }
+    pub(crate) fn backpressure_pauses(&self) -> u64 {
+        self.backpressure_pauses.load(Ordering::Relaxed)
+    }
+    pub(crate) fn add_payload_message(&self) {
+        self.payload_messages.fetch_add(1, Ordering::Relaxed);
+    }
+    pub(crate) fn payload_messages(&self) -> u64 {
+        self.payload_messages.load(Ordering::Relaxed)
+    }
 }
@@ -1,10 +1,12 @@
-use crate::{TcpListenerInfo, TcpReceiverInfo, TcpRegistry, TcpSenderInfo};
-use ockam_core::Address;
+use crate::{
+    TcpListenerInfo, TcpPortalConnectionInfo, TcpReceiverInfo, TcpRegistry, TcpSenderInfo,
+};
+use ockam_core::{Address, LocalInfo};
 
 impl TcpRegistry {
-    pub(crate) fn add_portal_worker(&self, addr: &Address) {
+    pub(crate) fn add_portal_worker(&self, info: TcpPortalConnectionInfo) {
         if let Ok(mut lock) = self.registry.write() {
-            lock.add_portal_worker(addr);
+            lock.add_portal_worker(info);
         }
     }
     pub(crate) fn remove_portal_worker(&self, addr: &Address) {
@@ -12,6 +14,15 @@ impl TcpRegistry {
             lock.remove_portal_worker(addr);
         }
     }
+    pub(crate) fn set_portal_worker_remote_local_info(
+        &self,
+        addr: &Address,
+        local_info: Vec<LocalInfo>,
+    ) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.set_portal_worker_remote_local_info(addr, local_info);
+        }
+    }
     pub(crate) fn add_portal_receiver_processor(&self, addr: &Address) {
         if let Ok(mut lock) = self.registry.write() {
             lock.add_portal_receiver_processor(addr);
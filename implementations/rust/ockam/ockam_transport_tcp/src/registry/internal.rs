@@ -1,9 +1,9 @@
-use crate::{TcpListenerInfo, TcpReceiverInfo, TcpSenderInfo};
-use ockam_core::Address;
+use crate::{TcpListenerInfo, TcpPortalConnectionInfo, TcpReceiverInfo, TcpSenderInfo};
+use ockam_core::{Address, LocalInfo};
 
 #[derive(Default, Debug)]
 pub(super) struct InternalRegistry {
-    pub(super) portal_workers: Vec<Address>,
+    pub(super) portal_workers: Vec<TcpPortalConnectionInfo>,
     pub(super) portal_receiver_processors: Vec<Address>,
     pub(super) inlet_listener_processors: Vec<Address>,
     pub(super) outlet_listener_workers: Vec<Address>,
@@ -13,11 +13,20 @@ pub(super) struct InternalRegistry {
 }
 
 impl InternalRegistry {
-    pub(super) fn add_portal_worker(&mut self, addr: &Address) {
-        self.portal_workers.push(addr.clone())
+    pub(super) fn add_portal_worker(&mut self, info: TcpPortalConnectionInfo) {
+        self.portal_workers.push(info)
     }
     pub(super) fn remove_portal_worker(&mut self, addr: &Address) {
-        self.portal_workers.retain(|x| x != addr);
+        self.portal_workers.retain(|x| x.address() != addr);
+    }
+    pub(super) fn set_portal_worker_remote_local_info(
+        &mut self,
+        addr: &Address,
+        local_info: Vec<LocalInfo>,
+    ) {
+        if let Some(info) = self.portal_workers.iter_mut().find(|x| x.address() == addr) {
+            info.set_remote_local_info(local_info);
+        }
     }
     pub(super) fn add_portal_receiver_processor(&mut self, addr: &Address) {
         self.portal_receiver_processors.push(addr.clone())
@@ -1,6 +1,7 @@
 use crate::registry::internal::InternalRegistry;
-use crate::{TcpListenerInfo, TcpReceiverInfo, TcpSenderInfo};
+use crate::{TcpListenerInfo, TcpPortalConnectionInfo, TcpReceiverInfo, TcpSenderInfo};
 use ockam_core::compat::sync::{Arc, RwLock};
+use ockam_core::Address;
 
 /// Registry of all active workers and processors in TCP Transport to ease their lifecycle management
 #[derive(Default, Clone, Debug)]
@@ -23,4 +24,38 @@ impl TcpRegistry {
     pub fn get_all_listeners(&self) -> Vec<TcpListenerInfo> {
         self.registry.read().unwrap().listener_processors.clone()
     }
+
+    /// Return [`Address`]es of all active portal workers (one per portal connection)
+    pub fn get_all_portal_workers(&self) -> Vec<Address> {
+        self.registry
+            .read()
+            .unwrap()
+            .portal_workers
+            .iter()
+            .map(|x| x.address().clone())
+            .collect()
+    }
+
+    /// Return the information about all active portal connections
+    pub fn get_all_portal_connections(&self) -> Vec<TcpPortalConnectionInfo> {
+        self.registry.read().unwrap().portal_workers.clone()
+    }
+
+    /// Return [`Address`]es of all active inlet listener processors
+    pub fn get_all_inlet_listener_processors(&self) -> Vec<Address> {
+        self.registry
+            .read()
+            .unwrap()
+            .inlet_listener_processors
+            .clone()
+    }
+
+    /// Return [`Address`]es of all active outlet listener workers
+    pub fn get_all_outlet_listener_workers(&self) -> Vec<Address> {
+        self.registry
+            .read()
+            .unwrap()
+            .outlet_listener_workers
+            .clone()
+    }
 }
@@ -1,11 +1,13 @@
-use crate::TcpConnectionMode;
+use crate::{IpFamily, TcpConnectionMode};
 use core::fmt;
-use core::fmt::Formatter;
+use core::fmt::{Display, Formatter};
 use ockam_core::compat::net::{SocketAddr, ToSocketAddrs};
+use ockam_core::errcode::{Kind, Origin};
 use ockam_core::flow_control::FlowControlId;
-use ockam_core::{Address, Result};
+use ockam_core::{Address, Error, Result};
 use ockam_node::Context;
 use ockam_transport_core::TransportError;
+use std::path::PathBuf;
 
 /// Result of [`TcpTransport::connect`] call.
 #[derive(Clone, Debug)]
@@ -69,6 +71,10 @@ impl TcpConnection {
     pub fn socket_address(&self) -> &SocketAddr {
         &self.socket_address
     }
+    /// [`IpFamily`] of the socket address
+    pub fn ip_family(&self) -> IpFamily {
+        IpFamily::from(&self.socket_address)
+    }
     /// Generated fresh random [`FlowControlId`]
     pub fn flow_control_id(&self) -> &FlowControlId {
         &self.flow_control_id
@@ -130,24 +136,102 @@ impl TcpListener {
 
 /// Resolve the given peer to a [`SocketAddr`](std::net::SocketAddr)
 pub fn resolve_peer(peer: String) -> Result<SocketAddr> {
+    // Prefer ip4
+    match resolve_peer_addresses(&peer, false)?.first() {
+        Some(p) => Ok(*p),
+        None => Err(TransportError::InvalidAddress)?,
+    }
+}
+
+/// Resolve the given peer to all its [`SocketAddr`](std::net::SocketAddr)s, both from
+/// its A and AAAA records, in the order in which they should be tried.
+///
+/// IPv4 addresses come first, unless `prefer_ipv6` is set.
+pub fn resolve_peer_addresses(peer: &str, prefer_ipv6: bool) -> Result<Vec<SocketAddr>> {
     // Try to parse as SocketAddr
-    if let Ok(p) = parse_socket_addr(&peer) {
-        return Ok(p);
+    if let Ok(p) = parse_socket_addr(peer) {
+        return Ok(vec![p]);
     }
 
     // Try to resolve hostname
-    if let Ok(mut iter) = peer.to_socket_addrs() {
-        // Prefer ip4
-        if let Some(p) = iter.find(|x| x.is_ipv4()) {
-            return Ok(p);
-        }
-        if let Some(p) = iter.find(|x| x.is_ipv6()) {
-            return Ok(p);
+    let addresses: Vec<SocketAddr> = peer
+        .to_socket_addrs()
+        .map_err(|e| resolution_error(peer, e.to_string()))?
+        .collect();
+    if addresses.is_empty() {
+        return Err(resolution_error(peer, "no address found"));
+    }
+
+    Ok(order_by_family(addresses, prefer_ipv6))
+}
+
+/// Put the IPv4 addresses first, unless `prefer_ipv6` is set
+pub(crate) fn order_by_family(addresses: Vec<SocketAddr>, prefer_ipv6: bool) -> Vec<SocketAddr> {
+    let (ipv6, ipv4): (Vec<SocketAddr>, Vec<SocketAddr>) =
+        addresses.into_iter().partition(|a| a.is_ipv6());
+    if prefer_ipv6 {
+        ipv6.into_iter().chain(ipv4).collect()
+    } else {
+        ipv4.into_iter().chain(ipv6).collect()
+    }
+}
+
+pub(crate) fn resolution_error(peer: &str, reason: impl Display) -> Error {
+    let host = peer.rsplit_once(':').map(|(host, _)| host).unwrap_or(peer);
+    Error::new(
+        Origin::Transport,
+        Kind::NotFound,
+        format!("failed to resolve the host '{host}': {reason}"),
+    )
+}
+
+/// Return the path of a Unix domain socket when the peer of an outlet is given as
+/// `unix:<path>` or as an absolute path, for example `unix:/var/run/postgresql/.s.PGSQL.5432`
+pub fn unix_socket_path(peer: &str) -> Option<PathBuf> {
+    match peer.strip_prefix("unix:") {
+        Some(path) => Some(PathBuf::from(path)),
+        None if peer.starts_with('/') => Some(PathBuf::from(peer)),
+        None => None,
+    }
+}
+
+/// Check that `path` is a Unix domain socket that this process is allowed to connect to.
+/// The server doesn't need to accept connections yet
+#[cfg(unix)]
+pub(crate) async fn check_unix_socket(path: &std::path::Path) -> Result<()> {
+    use std::os::unix::fs::FileTypeExt;
+
+    let metadata = std::fs::metadata(path).map_err(|e| unix_socket_error(path, e))?;
+    if !metadata.file_type().is_socket() {
+        return Err(Error::new(
+            Origin::Transport,
+            Kind::Invalid,
+            format!("'{}' is not a Unix domain socket", path.display()),
+        ));
+    }
+    match tokio::net::UnixStream::connect(path).await {
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+            Err(unix_socket_error(path, e))
         }
+        _ => Ok(()),
     }
+}
 
-    // Nothing worked, return an error
-    Err(TransportError::InvalidAddress)?
+#[cfg(unix)]
+pub(crate) fn unix_socket_error(path: &std::path::Path, e: std::io::Error) -> Error {
+    let kind = match e.kind() {
+        std::io::ErrorKind::NotFound => Kind::NotFound,
+        std::io::ErrorKind::PermissionDenied => Kind::Misuse,
+        _ => Kind::Io,
+    };
+    Error::new(
+        Origin::Transport,
+        kind,
+        format!(
+            "failed to access the Unix domain socket '{}': {e}",
+            path.display()
+        ),
+    )
 }
 
 pub(super) fn parse_socket_addr(s: &str) -> Result<SocketAddr> {
@@ -156,7 +240,7 @@ pub(super) fn parse_socket_addr(s: &str) -> Result<SocketAddr> {
 
 #[cfg(test)]
 mod test {
-    use crate::transport::common::parse_socket_addr;
+    use crate::transport::common::{parse_socket_addr, resolve_peer_addresses, unix_socket_path};
     use core::fmt::Debug;
     use ockam_core::{Error, Result};
     use ockam_transport_core::TransportError;
@@ -208,4 +292,39 @@ mod test {
         let result = parse_socket_addr("127.0.0.1:8080");
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_resolve_peer_addresses() {
+        let addresses = resolve_peer_addresses("127.0.0.1:80", true).unwrap();
+        assert_eq!(addresses, vec!["127.0.0.1:80".parse().unwrap()]);
+
+        let addresses = resolve_peer_addresses("localhost:80", false).unwrap();
+        assert!(!addresses.is_empty());
+        assert!(addresses.iter().all(|a| a.port() == 80));
+        if let Some(first_ipv6) = addresses.iter().position(|a| a.is_ipv6()) {
+            assert!(addresses[first_ipv6..].iter().all(|a| a.is_ipv6()));
+        }
+
+        let addresses = resolve_peer_addresses("localhost:80", true).unwrap();
+        if let Some(first_ipv4) = addresses.iter().position(|a| a.is_ipv4()) {
+            assert!(addresses[first_ipv4..].iter().all(|a| a.is_ipv4()));
+        }
+
+        let error = resolve_peer_addresses("outlet.invalid:5432", false).unwrap_err();
+        assert!(error.to_string().contains("outlet.invalid"));
+    }
+
+    #[test]
+    fn test_unix_socket_path() {
+        assert_eq!(
+            unix_socket_path("unix:/var/run/postgresql/.s.PGSQL.5432"),
+            Some("/var/run/postgresql/.s.PGSQL.5432".into())
+        );
+        assert_eq!(
+            unix_socket_path("/var/run/docker.sock"),
+            Some("/var/run/docker.sock".into())
+        );
+        assert_eq!(unix_socket_path("localhost:5432"), None);
+        assert_eq!(unix_socket_path("127.0.0.1:5432"), None);
+    }
 }
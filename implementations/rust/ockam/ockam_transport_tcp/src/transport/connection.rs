@@ -1,4 +1,5 @@
-use crate::transport::common::{resolve_peer, TcpConnection};
+use crate::transport::common::TcpConnection;
+use crate::transport::happy_eyeballs::{connect_happy_eyeballs, interleave_families};
 use crate::workers::{Addresses, TcpRecvProcessor, TcpSendWorker};
 use crate::{TcpConnectionMode, TcpConnectionOptions, TcpTransport};
 use ockam_core::{Address, Result};
@@ -16,15 +17,62 @@ impl TcpTransport {
     /// let connection = tcp.connect("127.0.0.1:5000", TcpConnectionOptions::new()).await?; // and connect to port 5000
     /// # Ok(()) }
     /// ```
+    ///
+    /// When the peer is a hostname it is resolved with the [`TcpResolver`](crate::TcpResolver) of
+    /// the transport. Its IPv4 and IPv6 addresses are then tried alternately, as described by
+    /// [RFC 8305](https://www.rfc-editor.org/rfc/rfc8305) ("Happy Eyeballs"): a new attempt is
+    /// started every [`TcpConnectionOptions::with_happy_eyeballs_delay`], or as soon as the
+    /// previous attempt fails, and the first established connection is used.
+    ///
+    /// When the transport has a [`TcpProxy`](crate::TcpProxy) which is used for the peer, the
+    /// addresses of the proxy are tried instead and the connection is tunneled through the proxy.
+    /// The peer is then resolved by the proxy.
     pub async fn connect(
         &self,
         peer: impl Into<String>,
         options: TcpConnectionOptions,
     ) -> Result<TcpConnection> {
-        // Resolve peer address
-        let socket = resolve_peer(peer.into())?;
+        let peer = peer.into();
+        let socket_options = options.socket_options.clone();
 
-        let (read_half, write_half) = TcpSendWorker::connect(socket).await?;
+        let (socket, (read_half, write_half)) = match self.proxy_for(&peer) {
+            Some(proxy) => {
+                let proxy_address = proxy.address();
+                let addresses = self
+                    .resolver
+                    .resolve(&proxy_address, options.prefer_ipv6)
+                    .await?;
+                connect_happy_eyeballs(
+                    &proxy_address,
+                    interleave_families(addresses),
+                    options.happy_eyeballs_delay,
+                    |address| {
+                        let (proxy, peer) = (proxy.clone(), peer.clone());
+                        let socket_options = socket_options.clone();
+                        async move {
+                            let stream = proxy.connect(address, &peer).await?;
+                            socket_options.apply(&stream);
+                            Ok(stream.into_split())
+                        }
+                    },
+                )
+                .await?
+            }
+            None => {
+                // Resolve peer addresses
+                let addresses = self.resolver.resolve(&peer, options.prefer_ipv6).await?;
+                connect_happy_eyeballs(
+                    &peer,
+                    interleave_families(addresses),
+                    options.happy_eyeballs_delay,
+                    |address| {
+                        let socket_options = socket_options.clone();
+                        async move { TcpSendWorker::connect(address, &socket_options).await }
+                    },
+                )
+                .await?
+            }
+        };
 
         let mode = TcpConnectionMode::Outgoing;
         let addresses = Addresses::generate(mode);
@@ -54,6 +102,7 @@ impl TcpTransport {
             mode,
             &flow_control_id,
             access_control.receiver_outgoing_access_control,
+            self.max_message_size,
         )
         .await?;
 
@@ -0,0 +1,203 @@
+use core::future::Future;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use ockam_transport_core::TransportError;
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::task::JoinSet;
+use tracing::debug;
+
+/// Order the addresses of a peer so that their families alternate, starting with the family
+/// of the first address, as recommended by [RFC 8305](https://www.rfc-editor.org/rfc/rfc8305#section-4).
+///
+/// The relative order of the addresses of each family is kept.
+pub(crate) fn interleave_families(addresses: Vec<SocketAddr>) -> Vec<SocketAddr> {
+    let first_is_ipv6 = match addresses.first() {
+        Some(address) => address.is_ipv6(),
+        None => return addresses,
+    };
+    let (mut first, mut second): (VecDeque<SocketAddr>, VecDeque<SocketAddr>) = addresses
+        .into_iter()
+        .partition(|a| a.is_ipv6() == first_is_ipv6);
+
+    let mut interleaved = Vec::with_capacity(first.len() + second.len());
+    loop {
+        match (first.pop_front(), second.pop_front()) {
+            (None, None) => break,
+            (a, b) => interleaved.extend(a.into_iter().chain(b)),
+        }
+    }
+    interleaved
+}
+
+/// Connect to one of the addresses of a peer, as described by
+/// [RFC 8305](https://www.rfc-editor.org/rfc/rfc8305#section-5).
+///
+/// The addresses are tried in order. A new attempt is started as soon as the previous one fails,
+/// or when `delay` has elapsed without any attempt succeeding. The first successful attempt
+/// is returned and the other attempts are cancelled. This way an unreachable address family,
+/// for example IPv6 without a working route, only delays the connection by `delay`.
+pub(crate) async fn connect_happy_eyeballs<T, F, Fut>(
+    peer: &str,
+    addresses: Vec<SocketAddr>,
+    delay: Duration,
+    connect: F,
+) -> Result<(SocketAddr, T)>
+where
+    T: Send + 'static,
+    F: Fn(SocketAddr) -> Fut,
+    Fut: Future<Output = Result<T>> + Send + 'static,
+{
+    let mut addresses = VecDeque::from(addresses);
+    let mut attempts = JoinSet::new();
+    let mut last_error: Error = TransportError::PeerNotFound.into();
+
+    loop {
+        if let Some(address) = addresses.pop_front() {
+            let attempt = connect(address);
+            attempts.spawn(async move { (address, attempt.await) });
+        }
+        if attempts.is_empty() {
+            break;
+        }
+
+        tokio::select! {
+            Some(attempt) = attempts.join_next() => match attempt {
+                // Dropping the remaining attempts cancels them
+                Ok((address, Ok(connection))) => return Ok((address, connection)),
+                Ok((address, Err(e))) => {
+                    debug!(%peer, %address, err = %e, "Connection attempt failed");
+                    last_error = e;
+                }
+                Err(e) => last_error = Error::new(Origin::Transport, Kind::Internal, e),
+            },
+            _ = tokio::time::sleep(delay), if !addresses.is_empty() => {
+                debug!(%peer, "Starting a new connection attempt while the previous ones are pending");
+            }
+        }
+    }
+
+    debug!(%peer, err = %last_error, "Failed to connect to any address of the peer");
+    Err(last_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::time::Instant;
+
+    fn addr(s: &str) -> SocketAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_interleave_families() {
+        let addresses = vec![
+            addr("[::1]:80"),
+            addr("[::2]:80"),
+            addr("[::3]:80"),
+            addr("127.0.0.1:80"),
+            addr("127.0.0.2:80"),
+        ];
+        assert_eq!(
+            interleave_families(addresses),
+            vec![
+                addr("[::1]:80"),
+                addr("127.0.0.1:80"),
+                addr("[::2]:80"),
+                addr("127.0.0.2:80"),
+                addr("[::3]:80"),
+            ]
+        );
+
+        let addresses = vec![addr("127.0.0.1:80"), addr("[::1]:80")];
+        assert_eq!(interleave_families(addresses.clone()), addresses);
+        assert!(interleave_families(vec![]).is_empty());
+    }
+
+    /// Set a flag when dropped, to check that a connection attempt was cancelled
+    struct DropFlag(Arc<AtomicBool>);
+
+    impl Drop for DropFlag {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_a_hanging_address_only_delays_the_connection() {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let hanging = addr("[::1]:80");
+        let reachable = addr("127.0.0.1:80");
+
+        let start = Instant::now();
+        let (address, _) = connect_happy_eyeballs(
+            "localhost:80",
+            vec![hanging, reachable],
+            Duration::from_millis(50),
+            |address| {
+                let flag = (address == hanging).then(|| DropFlag(cancelled.clone()));
+                async move {
+                    if flag.is_some() {
+                        tokio::time::sleep(Duration::from_secs(30)).await;
+                    }
+                    Ok(())
+                }
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(address, reachable);
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(50));
+        assert!(elapsed < Duration::from_secs(10));
+
+        // the hanging attempt is dropped once its task is aborted
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(cancelled.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_a_failed_attempt_starts_the_next_one_immediately() {
+        let refused = addr("[::1]:80");
+        let reachable = addr("127.0.0.1:80");
+
+        let start = Instant::now();
+        let (address, _) = connect_happy_eyeballs(
+            "localhost:80",
+            vec![refused, reachable],
+            Duration::from_secs(30),
+            |address| async move {
+                if address == refused {
+                    Err(TransportError::PeerNotFound.into())
+                } else {
+                    Ok(())
+                }
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(address, reachable);
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+
+    #[tokio::test]
+    async fn test_the_last_error_is_returned_when_all_attempts_fail() {
+        let result = connect_happy_eyeballs(
+            "localhost:80",
+            vec![addr("[::1]:80"), addr("127.0.0.1:80")],
+            Duration::from_millis(50),
+            |_| async { Err::<(), Error>(TransportError::ConnectionDrop.into()) },
+        )
+        .await;
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            Error::from(TransportError::ConnectionDrop).to_string()
+        );
+    }
+}
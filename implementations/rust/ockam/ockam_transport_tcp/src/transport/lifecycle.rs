@@ -6,7 +6,10 @@ use std::net::SocketAddr;
 use std::sync::Arc;
 use tracing::instrument;
 
-use crate::{TcpConnectionOptions, TcpListenerInfo, TcpRegistry, TcpSenderInfo, TcpTransport, TCP};
+use crate::{
+    TcpConnectionOptions, TcpListenerInfo, TcpProxy, TcpRegistry, TcpResolver, TcpSenderInfo,
+    TcpTransport, TcpTransportOptions, DEFAULT_MAX_MESSAGE_SIZE, TCP,
+};
 
 impl TcpTransport {
     /// Create a TCP transport
@@ -21,9 +24,49 @@ impl TcpTransport {
     /// ```
     #[instrument(name = "create tcp transport", skip_all)]
     pub async fn create(ctx: &Context) -> Result<Self> {
+        Self::create_with_resolver(ctx, TcpResolver::default()).await
+    }
+
+    /// Create a TCP transport resolving the host names of its peers with a specific resolver
+    ///
+    /// ```rust
+    /// use ockam_transport_tcp::{TcpResolver, TcpTransport};
+    /// # use ockam_node::Context;
+    /// # use ockam_core::Result;
+    /// # use std::time::Duration;
+    /// # async fn test(ctx: Context) -> Result<()> {
+    /// let resolver = TcpResolver::default()
+    ///     .with_cache_ttl(Duration::from_secs(10))
+    ///     .with_static_host("db.internal", "10.0.0.12".parse().unwrap());
+    /// let tcp = TcpTransport::create_with_resolver(&ctx, resolver).await?;
+    /// # Ok(()) }
+    /// ```
+    #[instrument(name = "create tcp transport", skip_all)]
+    pub async fn create_with_resolver(ctx: &Context, resolver: TcpResolver) -> Result<Self> {
+        Self::create_with_options(ctx, TcpTransportOptions::new().with_resolver(resolver)).await
+    }
+
+    /// Create a TCP transport with specific options, for example to reach its peers
+    /// through an HTTP proxy
+    ///
+    /// ```rust
+    /// use ockam_transport_tcp::{TcpProxy, TcpTransport, TcpTransportOptions};
+    /// # use ockam_node::Context;
+    /// # use ockam_core::Result;
+    /// # async fn test(ctx: Context) -> Result<()> {
+    /// let proxy = TcpProxy::parse("http://proxy.internal:3128")?.with_no_proxy("10.0.0.0/8")?;
+    /// let tcp = TcpTransport::create_with_options(&ctx, TcpTransportOptions::new().with_proxy(proxy))
+    ///     .await?;
+    /// # Ok(()) }
+    /// ```
+    #[instrument(name = "create tcp transport", skip_all)]
+    pub async fn create_with_options(ctx: &Context, options: TcpTransportOptions) -> Result<Self> {
         let tcp = Self {
             ctx: Arc::new(ctx.async_try_clone().await?),
             registry: TcpRegistry::default(),
+            resolver: Arc::new(options.resolver),
+            proxy: options.proxy.map(Arc::new),
+            max_message_size: options.max_message_size.unwrap_or(DEFAULT_MAX_MESSAGE_SIZE),
         };
         // make the TCP transport available in the list of supported transports for
         // later address resolution when socket addresses will need to be instantiated as TCP
@@ -43,6 +86,31 @@ impl TcpTransport {
         &self.registry
     }
 
+    /// Resolver of the host names of the peers
+    pub fn resolver(&self) -> &TcpResolver {
+        &self.resolver
+    }
+
+    /// Proxy used to reach the peers, if any
+    pub fn proxy(&self) -> Option<&TcpProxy> {
+        self.proxy.as_deref()
+    }
+
+    /// Maximum size of a message received on a connection, unless a listener overrides it
+    /// for the connections it accepts
+    pub fn max_message_size(&self) -> usize {
+        self.max_message_size
+    }
+
+    /// Return the proxy to use for connecting to a peer, given as `host:port`,
+    /// or `None` if the peer must be reached directly
+    pub(crate) fn proxy_for(&self, peer: &str) -> Option<Arc<TcpProxy>> {
+        self.proxy
+            .as_ref()
+            .filter(|proxy| proxy.is_used_for(peer))
+            .cloned()
+    }
+
     /// Search for a connection with the provided socket address
     pub fn find_connection_by_socketaddr(
         &self,
@@ -26,9 +26,16 @@ impl TcpTransport {
     ) -> Result<TcpListener> {
         let flow_control_id = options.flow_control_id.clone();
         let bind_addr = parse_socket_addr(bind_addr.as_ref())?;
+        let max_message_size = options.max_message_size.unwrap_or(self.max_message_size);
         // Could be different from the bind_addr, e.g., if binding to port 0\
-        let (socket_addr, address) =
-            TcpListenProcessor::start(&self.ctx, self.registry.clone(), bind_addr, options).await?;
+        let (socket_addr, address) = TcpListenProcessor::start(
+            &self.ctx,
+            self.registry.clone(),
+            bind_addr,
+            options,
+            max_message_size,
+        )
+        .await?;
 
         Ok(TcpListener::new(address, socket_addr, flow_control_id))
     }
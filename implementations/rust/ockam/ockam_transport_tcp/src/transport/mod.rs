@@ -1,10 +1,15 @@
 pub(crate) mod common;
 mod connection;
+mod happy_eyeballs;
 mod lifecycle;
 mod listener;
 mod portals;
+mod proxy;
+mod resolver;
 
 pub use common::*;
+pub use proxy::*;
+pub use resolver::*;
 
 pub use crate::portal::options::*;
 
@@ -55,6 +60,9 @@ use ockam_node::{Context, HasContext};
 pub struct TcpTransport {
     ctx: Arc<Context>,
     registry: TcpRegistry,
+    resolver: Arc<TcpResolver>,
+    proxy: Option<Arc<TcpProxy>>,
+    max_message_size: usize,
 }
 
 /// This trait adds a `create_tcp_transport` method to any struct returning a Context.
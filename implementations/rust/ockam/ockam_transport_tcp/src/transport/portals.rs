@@ -1,9 +1,17 @@
 use crate::portal::TcpInletListenProcessor;
-use crate::transport::common::{parse_socket_addr, resolve_peer};
-use crate::{portal::TcpOutletListenWorker, TcpInletOptions, TcpOutletOptions, TcpTransport};
+#[cfg(unix)]
+use crate::transport::common::check_unix_socket;
+use crate::transport::common::parse_socket_addr;
+use crate::{
+    portal::TcpOutletListenWorker, TcpInletLoadBalancer, TcpInletLoadBalancing, TcpInletOptions,
+    TcpOutletOptions, TcpTransport,
+};
 use core::fmt::Debug;
 use ockam_core::compat::net::SocketAddr;
 use ockam_core::{Address, Result, Route};
+use ockam_transport_core::TransportError;
+#[cfg(unix)]
+use std::path::Path;
 use tracing::instrument;
 
 impl TcpTransport {
@@ -32,16 +40,93 @@ impl TcpTransport {
         options: TcpInletOptions,
     ) -> Result<(SocketAddr, Address)> {
         let socket_addr = parse_socket_addr(&bind_addr.into())?;
+        let outlet_routes =
+            TcpInletLoadBalancer::new(vec![outlet_route.into()], TcpInletLoadBalancing::default());
         TcpInletListenProcessor::start(
             &self.ctx,
             self.registry.clone(),
-            outlet_route.into(),
+            outlet_routes,
             socket_addr,
             options,
         )
         .await
     }
 
+    /// Create Tcp Inlet that listens on bind_addr and distributes its Tcp connections across
+    /// several Outlets. The route of each new connection is picked by the given
+    /// [`TcpInletLoadBalancer`], which can be kept to inspect the distribution of the connections.
+    ///
+    /// ```rust
+    /// use ockam_transport_tcp::{TcpInletLoadBalancer, TcpInletLoadBalancing, TcpInletOptions, TcpTransport};
+    /// # use ockam_node::Context;
+    /// # use ockam_core::{AllowAll, Result, route};
+    /// # async fn test(ctx: Context) -> Result<()> {
+    /// let outlet_routes = TcpInletLoadBalancer::new(
+    ///     vec![route!["outlet1"], route!["outlet2"]],
+    ///     TcpInletLoadBalancing::RoundRobin,
+    /// );
+    ///
+    /// let tcp = TcpTransport::create(&ctx).await?;
+    /// tcp.create_load_balanced_inlet("inlet", outlet_routes, TcpInletOptions::new()).await?;
+    /// # tcp.stop_inlet("inlet").await?;
+    /// # Ok(()) }
+    /// ```
+    #[instrument(skip(self, outlet_routes), fields(address = ?bind_addr.clone().into()))]
+    pub async fn create_load_balanced_inlet(
+        &self,
+        bind_addr: impl Into<String> + Clone + Debug,
+        outlet_routes: TcpInletLoadBalancer,
+        options: TcpInletOptions,
+    ) -> Result<(SocketAddr, Address)> {
+        let socket_addr = parse_socket_addr(&bind_addr.into())?;
+        TcpInletListenProcessor::start(
+            &self.ctx,
+            self.registry.clone(),
+            outlet_routes,
+            socket_addr,
+            options,
+        )
+        .await
+    }
+
+    /// Create an Inlet accepting the connections of a Unix domain socket created at `path`,
+    /// instead of a TCP listener. The messages are forwarded to the outlets of `outlet_routes`.
+    ///
+    /// The creation fails if `path` already exists, unless the options allow to replace it.
+    /// The socket file is removed when the inlet is stopped.
+    ///
+    /// ```rust
+    /// use ockam_transport_tcp::{TcpInletLoadBalancer, TcpInletLoadBalancing, TcpInletOptions, TcpTransport};
+    /// # use ockam_node::Context;
+    /// # use ockam_core::{AllowAll, Result, route};
+    /// # async fn test(ctx: Context) -> Result<()> {
+    /// let outlet_routes =
+    ///     TcpInletLoadBalancer::new(vec![route!["outlet"]], TcpInletLoadBalancing::default());
+    ///
+    /// let tcp = TcpTransport::create(&ctx).await?;
+    /// let options = TcpInletOptions::new().with_unix_socket_mode(0o660);
+    /// let address = tcp.create_unix_inlet("/tmp/inlet.sock", outlet_routes, options).await?;
+    /// # tcp.stop_inlet(address).await?;
+    /// # Ok(()) }
+    /// ```
+    #[cfg(unix)]
+    #[instrument(skip(self, outlet_routes), fields(path = ?path.as_ref()))]
+    pub async fn create_unix_inlet(
+        &self,
+        path: impl AsRef<Path>,
+        outlet_routes: TcpInletLoadBalancer,
+        options: TcpInletOptions,
+    ) -> Result<Address> {
+        TcpInletListenProcessor::start_unix(
+            &self.ctx,
+            self.registry.clone(),
+            outlet_routes,
+            path.as_ref().to_path_buf(),
+            options,
+        )
+        .await
+    }
+
     /// Stop inlet at addr
     ///
     /// ```rust
@@ -69,6 +154,10 @@ impl TcpTransport {
     /// to Inlet using return route.
     /// Pair of corresponding Inlet and Outlet is called Portal.
     ///
+    /// When the peer is given by host name, it is resolved with the [`TcpResolver`](crate::TcpResolver) of the
+    /// transport each time a new portal connection is established. The outlet creation
+    /// fails if the host name can't be resolved.
+    ///
     /// ```rust
     /// use ockam_transport_tcp::{TcpOutletOptions, TcpTransport};
     /// # use ockam_node::Context;
@@ -87,13 +176,20 @@ impl TcpTransport {
         peer: impl Into<String> + Clone,
         options: TcpOutletOptions,
     ) -> Result<()> {
-        // Resolve peer address
-        let peer_addr = resolve_peer(peer.into())?;
+        // Check that the peer can be resolved before accepting connections,
+        // unless it is resolved by the proxy
+        let peer = peer.into();
+        if self.proxy_for(&peer).is_none() {
+            self.resolver.resolve(&peer, false).await?;
+        }
         TcpOutletListenWorker::start(
             &self.ctx,
             self.registry.clone(),
+            self.resolver.clone(),
+            self.proxy.clone(),
             address.into(),
-            peer_addr,
+            peer,
+            None,
             options,
         )
         .await?;
@@ -109,8 +205,59 @@ impl TcpTransport {
         peer: SocketAddr,
         options: TcpOutletOptions,
     ) -> Result<()> {
-        TcpOutletListenWorker::start(&self.ctx, self.registry.clone(), address, peer, options)
-            .await?;
+        TcpOutletListenWorker::start(
+            &self.ctx,
+            self.registry.clone(),
+            self.resolver.clone(),
+            self.proxy.clone(),
+            address,
+            peer.to_string(),
+            None,
+            options,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Create an Outlet Listener at address, that connects to the Unix domain socket at `path`
+    /// for each new portal connection, instead of a TCP server.
+    ///
+    /// The outlet creation fails if `path` is not a Unix domain socket, or if this process is not
+    /// allowed to connect to it. The server doesn't need to accept connections yet.
+    ///
+    /// ```rust
+    /// use ockam_transport_tcp::{TcpOutletOptions, TcpTransport};
+    /// # use ockam_node::Context;
+    /// # use ockam_core::{AllowAll, Result};
+    /// # async fn test(ctx: Context) -> Result<()> {
+    ///
+    /// let tcp = TcpTransport::create(&ctx).await?;
+    /// tcp.create_unix_outlet("outlet", "/var/run/postgresql/.s.PGSQL.5432", TcpOutletOptions::new()).await?;
+    /// # tcp.stop_outlet("outlet").await?;
+    /// # Ok(()) }
+    /// ```
+    #[cfg(unix)]
+    #[instrument(skip(self), fields(address = ?address.clone().into(), path = ?path.as_ref()))]
+    pub async fn create_unix_outlet(
+        &self,
+        address: impl Into<Address> + Clone + Debug,
+        path: impl AsRef<Path>,
+        options: TcpOutletOptions,
+    ) -> Result<()> {
+        let path = path.as_ref();
+        check_unix_socket(path).await?;
+        TcpOutletListenWorker::start(
+            &self.ctx,
+            self.registry.clone(),
+            self.resolver.clone(),
+            self.proxy.clone(),
+            address.into(),
+            path.display().to_string(),
+            Some(path.to_path_buf()),
+            options,
+        )
+        .await?;
 
         Ok(())
     }
@@ -133,4 +280,28 @@ impl TcpTransport {
         self.ctx.stop_worker(addr).await?;
         Ok(())
     }
+
+    /// Close the portal connection handled by the portal worker at addr.
+    ///
+    /// The data already read from the TCP stream is forwarded to the other side of the portal
+    /// before it is notified of the disconnection. Then both sides flush and close their TCP stream.
+    #[instrument(skip(self), fields(address = ?addr.clone().into()))]
+    pub async fn close_portal_connection(
+        &self,
+        addr: impl Into<Address> + Clone + Debug,
+    ) -> Result<()> {
+        let addr = addr.into();
+        match self
+            .registry
+            .get_all_portal_connections()
+            .into_iter()
+            .find(|c| c.address() == &addr)
+        {
+            Some(connection) => {
+                connection.request_close();
+                Ok(())
+            }
+            None => Err(TransportError::PeerNotFound)?,
+        }
+    }
 }
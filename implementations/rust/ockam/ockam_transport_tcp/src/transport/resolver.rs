@@ -0,0 +1,341 @@
+use core::fmt::Debug;
+use ockam_core::{async_trait, Result};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use crate::transport::common::{order_by_family, parse_socket_addr, resolution_error};
+
+/// Time during which the addresses of a host name are reused, when the resolver
+/// does not return the TTL of its records
+pub const DEFAULT_DNS_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Addresses of a host name, and the time during which they can be reused
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostnameResolution {
+    addresses: Vec<IpAddr>,
+    ttl: Option<Duration>,
+}
+
+impl HostnameResolution {
+    /// Constructor. The TTL is `None` when the resolver does not know it
+    pub fn new(addresses: Vec<IpAddr>, ttl: Option<Duration>) -> Self {
+        Self { addresses, ttl }
+    }
+
+    /// Addresses of the host name
+    pub fn addresses(&self) -> &[IpAddr] {
+        &self.addresses
+    }
+
+    /// TTL of the records of the host name
+    pub fn ttl(&self) -> Option<Duration> {
+        self.ttl
+    }
+}
+
+/// Resolve host names to IP addresses
+#[async_trait]
+pub trait HostnameResolver: Debug + Send + Sync + 'static {
+    /// Return the addresses of a host name
+    async fn resolve(&self, hostname: &str) -> Result<HostnameResolution>;
+}
+
+/// [`HostnameResolver`] using the resolver of the operating system.
+/// The TTL of the records is not known, so [`DEFAULT_DNS_CACHE_TTL`] is used for caching
+#[derive(Debug, Default)]
+pub struct SystemHostnameResolver;
+
+#[async_trait]
+impl HostnameResolver for SystemHostnameResolver {
+    async fn resolve(&self, hostname: &str) -> Result<HostnameResolution> {
+        let addresses = tokio::net::lookup_host((hostname, 0))
+            .await
+            .map_err(|e| resolution_error(hostname, e))?
+            .map(|address| address.ip())
+            .collect();
+        Ok(HostnameResolution::new(addresses, None))
+    }
+}
+
+/// Number of host name resolutions served from the cache, sent to the resolver, and failed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TcpResolverStats {
+    /// Resolutions served from the cache or from the static hosts
+    pub hits: u64,
+    /// Resolutions sent to the resolver because the host name was not cached, or its entry expired
+    pub misses: u64,
+    /// Resolutions which failed
+    pub failures: u64,
+}
+
+/// Resolver used by the TCP transport to find the addresses of the peers given by host name.
+///
+/// The addresses of a host name are cached until the TTL of its records expires, so that a
+/// host name is resolved again when it starts pointing to other addresses, for example when a
+/// CNAME fails over. The TTL of all the cached entries can be overridden with
+/// [`TcpResolver::with_cache_ttl`]. When a host name can't be resolved again, its expired
+/// addresses are still used.
+///
+/// Static hosts, added with [`TcpResolver::with_static_host`], are never resolved. This is
+/// useful in environments without DNS.
+#[derive(Debug)]
+pub struct TcpResolver {
+    resolver: Arc<dyn HostnameResolver>,
+    cache_ttl: Option<Duration>,
+    static_hosts: HashMap<String, IpAddr>,
+    cache: Mutex<HashMap<String, CachedResolution>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    failures: AtomicU64,
+}
+
+#[derive(Debug, Clone)]
+struct CachedResolution {
+    addresses: Vec<IpAddr>,
+    expires_at: Instant,
+}
+
+impl Default for TcpResolver {
+    fn default() -> Self {
+        Self::new(Arc::new(SystemHostnameResolver))
+    }
+}
+
+impl TcpResolver {
+    /// Create a resolver caching the resolutions of a [`HostnameResolver`]
+    pub fn new(resolver: Arc<dyn HostnameResolver>) -> Self {
+        Self {
+            resolver,
+            cache_ttl: None,
+            static_hosts: HashMap::new(),
+            cache: Mutex::new(HashMap::new()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            failures: AtomicU64::new(0),
+        }
+    }
+
+    /// Cache the addresses of host names for this duration, instead of the TTL of their records
+    pub fn with_cache_ttl(mut self, cache_ttl: Duration) -> Self {
+        self.cache_ttl = Some(cache_ttl);
+        self
+    }
+
+    /// Always resolve a host name to the given address
+    pub fn with_static_host(mut self, hostname: &str, address: IpAddr) -> Self {
+        self.static_hosts.insert(hostname.to_lowercase(), address);
+        self
+    }
+
+    /// Resolve a peer given as `<host name or IP address>:<port>` to its socket addresses,
+    /// in the order in which they should be tried.
+    ///
+    /// IPv4 addresses come first, unless `prefer_ipv6` is set.
+    pub async fn resolve(&self, peer: &str, prefer_ipv6: bool) -> Result<Vec<SocketAddr>> {
+        if let Ok(address) = parse_socket_addr(peer) {
+            return Ok(vec![address]);
+        }
+        let Some((hostname, port)) = peer.rsplit_once(':') else {
+            return Err(resolution_error(peer, "the port is missing"));
+        };
+        let port: u16 = port
+            .parse()
+            .map_err(|_| resolution_error(peer, format!("invalid port '{port}'")))?;
+
+        let addresses = self.resolve_hostname(&hostname.to_lowercase()).await?;
+        Ok(order_by_family(
+            addresses
+                .into_iter()
+                .map(|ip| SocketAddr::new(ip, port))
+                .collect(),
+            prefer_ipv6,
+        ))
+    }
+
+    /// Return the number of resolutions served from the cache, sent to the resolver, and failed
+    pub fn stats(&self) -> TcpResolverStats {
+        TcpResolverStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            failures: self.failures.load(Ordering::Relaxed),
+        }
+    }
+
+    async fn resolve_hostname(&self, hostname: &str) -> Result<Vec<IpAddr>> {
+        if let Some(address) = self.static_hosts.get(hostname) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(vec![*address]);
+        }
+
+        let cached = self.cache.lock().unwrap().get(hostname).cloned();
+        if let Some(cached) = &cached {
+            if cached.expires_at > Instant::now() {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return Ok(cached.addresses.clone());
+            }
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let resolution = match self.resolver.resolve(hostname).await {
+            Ok(resolution) if !resolution.addresses().is_empty() => Ok(resolution),
+            Ok(_) => Err(resolution_error(hostname, "no address found")),
+            Err(e) => Err(e),
+        };
+        match resolution {
+            Ok(resolution) => {
+                let ttl = self
+                    .cache_ttl
+                    .or(resolution.ttl())
+                    .unwrap_or(DEFAULT_DNS_CACHE_TTL);
+                self.cache.lock().unwrap().insert(
+                    hostname.to_string(),
+                    CachedResolution {
+                        addresses: resolution.addresses().to_vec(),
+                        expires_at: Instant::now() + ttl,
+                    },
+                );
+                Ok(resolution.addresses().to_vec())
+            }
+            Err(e) => {
+                self.failures.fetch_add(1, Ordering::Relaxed);
+                match cached {
+                    Some(cached) => {
+                        warn!(%hostname, %e, "cannot resolve the host name again, its previous addresses are used");
+                        Ok(cached.addresses)
+                    }
+                    None => Err(e),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicBool;
+
+    /// Resolver returning the next address of a list at each call, and counting its calls
+    #[derive(Debug)]
+    struct MockResolver {
+        addresses: Vec<IpAddr>,
+        ttl: Option<Duration>,
+        calls: AtomicU64,
+        fail: AtomicBool,
+    }
+
+    impl MockResolver {
+        fn new(addresses: &[&str], ttl: Option<Duration>) -> Arc<Self> {
+            Arc::new(Self {
+                addresses: addresses.iter().map(|a| a.parse().unwrap()).collect(),
+                ttl,
+                calls: AtomicU64::new(0),
+                fail: AtomicBool::new(false),
+            })
+        }
+
+        fn calls(&self) -> u64 {
+            self.calls.load(Ordering::Relaxed)
+        }
+    }
+
+    #[async_trait]
+    impl HostnameResolver for MockResolver {
+        async fn resolve(&self, hostname: &str) -> Result<HostnameResolution> {
+            let call = self.calls.fetch_add(1, Ordering::Relaxed) as usize;
+            if self.fail.load(Ordering::Relaxed) {
+                return Err(resolution_error(hostname, "mock failure"));
+            }
+            Ok(HostnameResolution::new(
+                vec![self.addresses[call % self.addresses.len()]],
+                self.ttl,
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cached_resolution_is_reused_until_it_expires() -> Result<()> {
+        let mock = MockResolver::new(&["10.0.0.1", "10.0.0.2"], Some(Duration::from_secs(3600)));
+        let resolver = TcpResolver::new(mock.clone());
+
+        for _ in 0..3 {
+            let addresses = resolver.resolve("db.internal:5432", false).await?;
+            assert_eq!(addresses, vec!["10.0.0.1:5432".parse().unwrap()]);
+        }
+        assert_eq!(mock.calls(), 1);
+        assert_eq!(
+            resolver.stats(),
+            TcpResolverStats {
+                hits: 2,
+                misses: 1,
+                failures: 0
+            }
+        );
+
+        // an expired entry is resolved again
+        let mock = MockResolver::new(&["10.0.0.1", "10.0.0.2"], Some(Duration::ZERO));
+        let resolver = TcpResolver::new(mock.clone());
+        resolver.resolve("db.internal:5432", false).await?;
+        let addresses = resolver.resolve("db.internal:5432", false).await?;
+        assert_eq!(addresses, vec!["10.0.0.2:5432".parse().unwrap()]);
+        assert_eq!(mock.calls(), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cache_ttl_overrides_the_record_ttl() -> Result<()> {
+        let mock = MockResolver::new(&["10.0.0.1", "10.0.0.2"], Some(Duration::from_secs(3600)));
+        let resolver = TcpResolver::new(mock.clone()).with_cache_ttl(Duration::ZERO);
+        resolver.resolve("db.internal:5432", false).await?;
+        resolver.resolve("db.internal:5432", false).await?;
+        assert_eq!(mock.calls(), 2);
+        assert_eq!(resolver.stats().misses, 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_static_hosts_and_addresses_are_not_resolved() -> Result<()> {
+        let mock = MockResolver::new(&["10.0.0.1"], None);
+        let resolver = TcpResolver::new(mock.clone())
+            .with_static_host("DB.internal", "192.168.1.10".parse().unwrap());
+
+        let addresses = resolver.resolve("db.internal:5432", false).await?;
+        assert_eq!(addresses, vec!["192.168.1.10:5432".parse().unwrap()]);
+        let addresses = resolver.resolve("127.0.0.1:5432", false).await?;
+        assert_eq!(addresses, vec!["127.0.0.1:5432".parse().unwrap()]);
+
+        assert_eq!(mock.calls(), 0);
+        assert_eq!(resolver.stats().hits, 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_failed_resolutions() -> Result<()> {
+        let mock = MockResolver::new(&["10.0.0.1"], Some(Duration::ZERO));
+        mock.fail.store(true, Ordering::Relaxed);
+        let resolver = TcpResolver::new(mock.clone());
+        assert!(resolver.resolve("db.internal:5432", false).await.is_err());
+        assert!(resolver.resolve("db.internal", false).await.is_err());
+
+        // the expired addresses are used when the host name can't be resolved again
+        mock.fail.store(false, Ordering::Relaxed);
+        resolver.resolve("db.internal:5432", false).await?;
+        mock.fail.store(true, Ordering::Relaxed);
+        let addresses = resolver.resolve("db.internal:5432", false).await?;
+        assert_eq!(addresses, vec!["10.0.0.1:5432".parse().unwrap()]);
+
+        assert_eq!(
+            resolver.stats(),
+            TcpResolverStats {
+                hits: 0,
+                misses: 3,
+                failures: 2
+            }
+        );
+        Ok(())
+    }
+}
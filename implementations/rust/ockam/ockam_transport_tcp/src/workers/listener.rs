@@ -17,6 +17,7 @@ pub(crate) struct TcpListenProcessor {
     inner: TcpListener,
     socket_address: SocketAddr,
     options: TcpListenerOptions,
+    max_message_size: usize,
 }
 
 impl TcpListenProcessor {
@@ -26,6 +27,7 @@ impl TcpListenProcessor {
         registry: TcpRegistry,
         addr: SocketAddr,
         options: TcpListenerOptions,
+        max_message_size: usize,
     ) -> Result<(SocketAddr, Address)> {
         debug!("Binding TcpListener to {}", addr);
         let inner = TcpListener::bind(addr)
@@ -41,6 +43,7 @@ impl TcpListenProcessor {
             inner,
             socket_address: saddr,
             options,
+            max_message_size,
         };
 
         ctx.start_processor(address.clone(), processor).await?;
@@ -61,6 +64,7 @@ impl Processor for TcpListenProcessor {
             ctx.address(),
             self.socket_address,
             self.options.flow_control_id.clone(),
+            self.max_message_size,
         ));
 
         Ok(())
@@ -80,6 +84,7 @@ impl Processor for TcpListenProcessor {
         // Wait for an incoming connection
         let (stream, peer) = self.inner.accept().await.map_err(TransportError::from)?;
         debug!("TCP connection accepted");
+        self.options.socket_options.apply(&stream);
 
         let mode = TcpConnectionMode::Incoming;
         let addresses = Addresses::generate(mode);
@@ -116,6 +121,7 @@ impl Processor for TcpListenProcessor {
             mode,
             &receiver_flow_control_id,
             access_control.receiver_outgoing_access_control,
+            self.max_message_size,
         )
         .await?;
 
@@ -10,7 +10,7 @@ use ockam_core::{LocalMessage, Processor, Result, TransportMessage};
 use ockam_node::{Context, ProcessorBuilder};
 use ockam_transport_core::TransportError;
 use tokio::{io::AsyncReadExt, net::tcp::OwnedReadHalf};
-use tracing::{error, info, instrument, trace};
+use tracing::{error, info, instrument, trace, warn};
 
 /// A TCP receiving message processor
 ///
@@ -27,6 +27,7 @@ pub(crate) struct TcpRecvProcessor {
     addresses: Addresses,
     mode: TcpConnectionMode,
     flow_control_id: FlowControlId,
+    max_message_size: usize,
 }
 
 impl TcpRecvProcessor {
@@ -38,6 +39,7 @@ impl TcpRecvProcessor {
         addresses: Addresses,
         mode: TcpConnectionMode,
         flow_control_id: FlowControlId,
+        max_message_size: usize,
     ) -> Self {
         Self {
             registry,
@@ -46,6 +48,7 @@ impl TcpRecvProcessor {
             addresses,
             mode,
             flow_control_id,
+            max_message_size,
         }
     }
 
@@ -60,6 +63,7 @@ impl TcpRecvProcessor {
         mode: TcpConnectionMode,
         flow_control_id: &FlowControlId,
         receiver_outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+        max_message_size: usize,
     ) -> Result<()> {
         let receiver = TcpRecvProcessor::new(
             registry,
@@ -68,6 +72,7 @@ impl TcpRecvProcessor {
             addresses.clone(),
             mode,
             flow_control_id.clone(),
+            max_message_size,
         );
 
         let mailbox = Mailbox::new(
@@ -89,6 +94,16 @@ impl TcpRecvProcessor {
 
         Ok(())
     }
+
+    /// Notify the sender that the connection is closed, so that it stops
+    async fn notify_connection_closed(&self, ctx: &Context) -> Result<()> {
+        ctx.send_from_address(
+            self.addresses.sender_internal_address().clone(),
+            TcpSendWorkerMsg::ConnectionClosed,
+            self.addresses.receiver_internal_address().clone(),
+        )
+        .await
+    }
 }
 
 #[async_trait]
@@ -139,20 +154,25 @@ impl Processor for TcpRecvProcessor {
                     "Connection to peer '{}' was closed; dropping stream",
                     self.socket_address
                 );
-
-                // Notify sender tx is closed
-                ctx.send_from_address(
-                    self.addresses.sender_internal_address().clone(),
-                    TcpSendWorkerMsg::ConnectionClosed,
-                    self.addresses.receiver_internal_address().clone(),
-                )
-                .await?;
+                self.notify_connection_closed(ctx).await?;
                 return Ok(false);
             }
         };
 
         trace!("Received message header for {} bytes", len);
 
+        // Don't allocate a buffer for a message exceeding the limit, close the connection instead
+        if len as usize > self.max_message_size {
+            warn!(
+                peer = %self.socket_address,
+                "Closing the connection: the peer announced a message of {len} bytes, \
+                larger than the maximum of {} bytes",
+                self.max_message_size
+            );
+            self.notify_connection_closed(ctx).await?;
+            return Ok(false);
+        }
+
         // Allocate a buffer of that size
         let mut buf = vec![0; len as usize];
 
@@ -1,7 +1,6 @@
+use crate::options::TcpSocketOptions;
 use crate::workers::Addresses;
 use crate::{TcpConnectionMode, TcpRegistry, TcpSenderInfo};
-use cfg_if::cfg_if;
-use core::time::Duration;
 use ockam_core::flow_control::FlowControlId;
 use ockam_core::{
     async_trait,
@@ -13,7 +12,6 @@ use ockam_node::{Context, WorkerBuilder};
 use ockam_transport_core::{encode_transport_message, TransportError};
 
 use serde::{Deserialize, Serialize};
-use socket2::{SockRef, TcpKeepalive};
 use tokio::io::AsyncWriteExt;
 use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
 use tokio::net::TcpStream;
@@ -123,6 +121,7 @@ impl TcpSendWorker {
     #[instrument(skip_all, name = "TcpSendWorker::connect")]
     pub(crate) async fn connect(
         socket_address: SocketAddr,
+        socket_options: &TcpSocketOptions,
     ) -> Result<(OwnedReadHalf, OwnedWriteHalf)> {
         debug!(addr = %socket_address, "Connecting");
         let connection = match TcpStream::connect(socket_address).await {
@@ -136,18 +135,7 @@ impl TcpSendWorker {
             }
         };
 
-        let mut keepalive = TcpKeepalive::new()
-            .with_time(Duration::from_secs(300))
-            .with_interval(Duration::from_secs(75));
-
-        cfg_if! {
-            if #[cfg(unix)] {
-               keepalive = keepalive.with_retries(2);
-            }
-        }
-
-        let socket = SockRef::from(&connection);
-        socket.set_tcp_keepalive(&keepalive).unwrap();
+        socket_options.apply(&connection);
 
         Ok(connection.into_split())
     }
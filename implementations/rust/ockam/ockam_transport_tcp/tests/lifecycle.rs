@@ -2,7 +2,11 @@ use core::time::Duration;
 use ockam_core::compat::rand::{self, Rng};
 use ockam_core::{route, Result, Routed, Worker};
 use ockam_node::Context;
-use ockam_transport_tcp::{TcpConnectionOptions, TcpListenerOptions, TcpTransport};
+use ockam_transport_tcp::{
+    TcpConnectionOptions, TcpListenerOptions, TcpTransport, TcpTransportOptions,
+};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
 
 pub struct Echoer;
 
@@ -173,3 +177,49 @@ async fn tcp_lifecycle__stop_listener__should_stop_accepting_connections(
     assert_eq!(reply2, msg2, "Should receive the same message");
     Ok(())
 }
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn tcp_lifecycle__oversized_message__should_close_connection(
+    ctx: &mut Context,
+) -> Result<()> {
+    let options = TcpListenerOptions::new().with_max_message_size(1000);
+    ctx.flow_controls()
+        .add_consumer("echoer", &options.spawner_flow_control_id());
+    ctx.start_worker("echoer", Echoer).await?;
+
+    let transport = TcpTransport::create_with_options(
+        ctx,
+        TcpTransportOptions::new().with_max_message_size(100),
+    )
+    .await?;
+    let listener = transport.listen("127.0.0.1:0", options).await?;
+
+    // The listener limit overrides the transport limit
+    let listener_info = transport
+        .find_listener_by_socketaddress(*listener.socket_address())
+        .unwrap();
+    assert_eq!(listener_info.max_message_size(), 1000);
+
+    let tx_address = transport
+        .connect(listener.socket_string(), TcpConnectionOptions::new())
+        .await?;
+    let msg = "a".repeat(256);
+    let reply: String = ctx
+        .send_and_receive(route![tx_address, "echoer"], msg.clone())
+        .await?;
+    assert_eq!(reply, msg, "Should receive the same message");
+
+    // Announce a message larger than the limit, without sending it
+    let mut stream = TcpStream::connect(listener.socket_address()).await.unwrap();
+    stream.write_u16(1001).await.unwrap();
+
+    // The connection is closed without waiting for the message
+    let mut buf = [0u8; 16];
+    let read = tokio::time::timeout(Duration::from_secs(5), stream.read(&mut buf))
+        .await
+        .expect("the connection should be closed");
+    assert!(matches!(read, Ok(0) | Err(_)));
+
+    Ok(())
+}
@@ -7,7 +7,9 @@ use ockam_core::compat::rand::random;
 use ockam_core::{route, Result};
 use ockam_node::Context;
 use ockam_transport_tcp::{
-    TcpConnectionOptions, TcpInletOptions, TcpListenerOptions, TcpOutletOptions, TcpTransport,
+    PortalType, ProxyProtocolHeader, TcpConnectionOptions, TcpInletLoadBalancer,
+    TcpInletLoadBalancing, TcpInletOptions, TcpListenerOptions, TcpOutletOptions,
+    TcpPortalBatching, TcpTransport, MAX_PAYLOAD_SIZE,
 };
 
 const LENGTH: usize = 32;
@@ -228,3 +230,550 @@ async fn portal__tcp_connection_with_invalid_message_flow__should_not_succeed(
 
     Ok(())
 }
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 15000)]
+async fn portal__close_connection__should_close_both_streams(ctx: &mut Context) -> Result<()> {
+    let payload1 = generate_binary();
+    let payload2 = generate_binary();
+
+    let tcp = TcpTransport::create(ctx).await?;
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let bind_address = listener.local_addr().unwrap().to_string();
+    tcp.create_outlet("outlet", bind_address, TcpOutletOptions::new())
+        .await?;
+    let (inlet_saddr, _) = tcp
+        .create_inlet("127.0.0.1:0", route!["outlet"], TcpInletOptions::new())
+        .await?;
+
+    let handle = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        read_assert_binary(&mut stream, payload1).await;
+        write_binary(&mut stream, payload2).await;
+
+        // The outlet stream is closed when the connection is closed on the inlet side
+        let mut buf = [0u8; LENGTH];
+        let length = stream.read(&mut buf).await.unwrap();
+        assert_eq!(length, 0);
+    });
+
+    let mut stream = TcpStream::connect(inlet_saddr).await.unwrap();
+    write_binary(&mut stream, payload1).await;
+    read_assert_binary(&mut stream, payload2).await;
+
+    // Wait for the counters to be updated after the last write
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let connections = tcp.registry().get_all_portal_connections();
+    assert_eq!(connections.len(), 2);
+    let inlet_connection = connections
+        .iter()
+        .find(|c| c.portal_type() == PortalType::Inlet)
+        .unwrap();
+    assert_eq!(
+        inlet_connection.peer_address(),
+        stream.local_addr().unwrap()
+    );
+    assert_eq!(inlet_connection.bytes_received(), LENGTH as u64);
+    assert_eq!(inlet_connection.bytes_sent(), LENGTH as u64);
+
+    tcp.close_portal_connection(inlet_connection.address().clone())
+        .await?;
+
+    let mut buf = [0u8; LENGTH];
+    let length = stream.read(&mut buf).await.unwrap();
+    assert_eq!(length, 0);
+
+    let res = handle.await;
+    assert!(res.is_ok());
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 15000)]
+async fn portal__load_balanced_inlet__should_distribute_connections(
+    ctx: &mut Context,
+) -> Result<()> {
+    let tcp = TcpTransport::create(ctx).await?;
+
+    let mut listeners = vec![];
+    for outlet in ["outlet1", "outlet2"] {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let bind_address = listener.local_addr().unwrap().to_string();
+        tcp.create_outlet(outlet, bind_address, TcpOutletOptions::new())
+            .await?;
+        listeners.push(listener);
+    }
+
+    let outlet_routes = TcpInletLoadBalancer::new(
+        vec![route!["outlet1"], route!["outlet2"]],
+        TcpInletLoadBalancing::RoundRobin,
+    );
+    let (inlet_saddr, _) = tcp
+        .create_load_balanced_inlet("127.0.0.1:0", outlet_routes.clone(), TcpInletOptions::new())
+        .await?;
+
+    let mut handles = vec![];
+    for listener in listeners {
+        let payload = generate_binary();
+        handles.push(tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            write_binary(&mut stream, payload).await;
+            stream
+        }));
+    }
+
+    let mut streams = vec![];
+    for _ in 0..2 {
+        let mut stream = TcpStream::connect(inlet_saddr).await.unwrap();
+        let mut buf = [0u8; LENGTH];
+        stream.read_exact(&mut buf).await.unwrap();
+        streams.push(stream);
+    }
+
+    // each outlet received one connection
+    for handle in handles {
+        assert!(handle.await.is_ok());
+    }
+    let statuses = outlet_routes.statuses();
+    assert_eq!(statuses.len(), 2);
+    for status in statuses {
+        assert_eq!(status.active_connections(), 1);
+        assert_eq!(status.total_connections(), 1);
+        assert!(status.is_healthy());
+    }
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 15000)]
+async fn portal__load_balanced_inlet__should_skip_unhealthy_routes(
+    ctx: &mut Context,
+) -> Result<()> {
+    let tcp = TcpTransport::create(ctx).await?;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let bind_address = listener.local_addr().unwrap().to_string();
+    tcp.create_outlet("outlet", bind_address, TcpOutletOptions::new())
+        .await?;
+
+    // the first route doesn't lead to an outlet
+    let outlet_routes = TcpInletLoadBalancer::new(
+        vec![route!["missing_outlet"], route!["outlet"]],
+        TcpInletLoadBalancing::RoundRobin,
+    )
+    .with_outlet_timeout(Duration::from_millis(500));
+    let (inlet_saddr, _) = tcp
+        .create_load_balanced_inlet("127.0.0.1:0", outlet_routes.clone(), TcpInletOptions::new())
+        .await?;
+
+    let _failed = TcpStream::connect(inlet_saddr).await.unwrap();
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    let statuses = outlet_routes.statuses();
+    assert_eq!(statuses[0].failures(), 1);
+    assert_eq!(statuses[0].active_connections(), 0);
+    assert!(!statuses[0].is_healthy());
+
+    // the next connections use the healthy route
+    let handle = tokio::spawn(async move {
+        let mut streams = vec![];
+        for _ in 0..2 {
+            let (stream, _) = listener.accept().await.unwrap();
+            streams.push(stream);
+        }
+        streams
+    });
+    let _stream1 = TcpStream::connect(inlet_saddr).await.unwrap();
+    let _stream2 = TcpStream::connect(inlet_saddr).await.unwrap();
+    assert!(handle.await.is_ok());
+
+    let statuses = outlet_routes.statuses();
+    assert_eq!(statuses[0].total_connections(), 1);
+    assert_eq!(statuses[1].total_connections(), 2);
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 60000)]
+async fn portal__slow_reader__should_bound_the_buffered_bytes(ctx: &mut Context) -> Result<()> {
+    const TOTAL_LENGTH: usize = 100 * 1024 * 1024;
+    const BUFFER_SIZE: usize = 1024 * 1024;
+
+    let tcp = TcpTransport::create(ctx).await?;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let bind_address = listener.local_addr().unwrap().to_string();
+    tcp.create_outlet(
+        "outlet",
+        bind_address,
+        TcpOutletOptions::new().with_buffer_size(BUFFER_SIZE),
+    )
+    .await?;
+    let (inlet_saddr, _) = tcp
+        .create_inlet(
+            "127.0.0.1:0",
+            route!["outlet"],
+            TcpInletOptions::new().with_buffer_size(BUFFER_SIZE),
+        )
+        .await?;
+
+    // the TCP server reads the data much slower than the TCP client writes it
+    let reader = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut buffer = vec![0u8; 64 * 1024];
+        let mut received = 0;
+        while received < TOTAL_LENGTH {
+            let length = stream.read(&mut buffer).await.unwrap();
+            assert_ne!(length, 0, "the connection was closed too early");
+            received += length;
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        received
+    });
+
+    let mut stream = TcpStream::connect(inlet_saddr).await.unwrap();
+    let writer = tokio::spawn(async move {
+        let chunk = vec![42u8; 1024 * 1024];
+        for _ in 0..TOTAL_LENGTH / chunk.len() {
+            stream.write_all(&chunk).await.unwrap();
+        }
+        stream
+    });
+
+    // the bytes read from the TCP client and not yet written to the TCP server
+    // never exceed the buffer size, plus the last payload read by the inlet
+    while !reader.is_finished() {
+        let connections = tcp.registry().get_all_portal_connections();
+        let inlet_received = connections
+            .iter()
+            .find(|c| c.portal_type() == PortalType::Inlet)
+            .map(|c| c.bytes_received());
+        let outlet_sent = connections
+            .iter()
+            .find(|c| c.portal_type() == PortalType::Outlet)
+            .map(|c| c.bytes_sent());
+        if let (Some(inlet_received), Some(outlet_sent)) = (inlet_received, outlet_sent) {
+            let buffered = inlet_received.saturating_sub(outlet_sent);
+            assert!(
+                buffered <= (BUFFER_SIZE + MAX_PAYLOAD_SIZE) as u64,
+                "{buffered} bytes are buffered by the portal"
+            );
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    assert_eq!(reader.await.unwrap(), TOTAL_LENGTH);
+    let _stream = writer.await.unwrap();
+
+    let connections = tcp.registry().get_all_portal_connections();
+    let inlet_connection = connections
+        .iter()
+        .find(|c| c.portal_type() == PortalType::Inlet)
+        .unwrap();
+    assert_eq!(inlet_connection.bytes_received(), TOTAL_LENGTH as u64);
+    assert!(inlet_connection.backpressure_pauses() > 0);
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 15000)]
+async fn portal__batching__should_coalesce_small_writes(ctx: &mut Context) -> Result<()> {
+    const WRITES: usize = 100;
+
+    let tcp = TcpTransport::create(ctx).await?;
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let bind_address = listener.local_addr().unwrap().to_string();
+    tcp.create_outlet("outlet", bind_address, TcpOutletOptions::new())
+        .await?;
+    let batching = TcpPortalBatching::new().with_delay(Duration::from_millis(200));
+    let (inlet_saddr, _) = tcp
+        .create_inlet(
+            "127.0.0.1:0",
+            route!["outlet"],
+            TcpInletOptions::new().with_batching(batching),
+        )
+        .await?;
+
+    let reader = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut received = vec![];
+        stream.read_to_end(&mut received).await.unwrap();
+        received
+    });
+
+    // many small writes, each one sent right away by the TCP client
+    let mut stream = TcpStream::connect(inlet_saddr).await.unwrap();
+    stream.set_nodelay(true).unwrap();
+    let mut sent = vec![];
+    for i in 0..WRITES {
+        let payload = [i as u8; LENGTH];
+        write_binary(&mut stream, payload).await;
+        sent.extend_from_slice(&payload);
+    }
+
+    // Wait for the data to be sent, then check the counters before closing the connection
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    let connections = tcp.registry().get_all_portal_connections();
+    let inlet_connection = connections
+        .iter()
+        .find(|c| c.portal_type() == PortalType::Inlet)
+        .unwrap();
+    assert_eq!(inlet_connection.bytes_received(), (WRITES * LENGTH) as u64);
+    assert!(
+        inlet_connection.payload_messages() < WRITES as u64 / 2,
+        "{} messages were sent for {WRITES} writes",
+        inlet_connection.payload_messages()
+    );
+
+    // the data is received in order, and the batch is flushed when the connection is closed
+    stream.shutdown().await.unwrap();
+    assert_eq!(reader.await.unwrap(), sent);
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 15000)]
+async fn portal__unix_socket_outlet__should_succeed(ctx: &mut Context) -> Result<()> {
+    use tokio::net::UnixListener;
+
+    let payload1 = generate_binary();
+    let payload2 = generate_binary();
+
+    let path = std::env::temp_dir().join(format!("ockam-outlet-{}.sock", random::<u64>()));
+    let listener = UnixListener::bind(&path).unwrap();
+
+    let tcp = TcpTransport::create(ctx).await?;
+    tcp.create_unix_outlet("outlet", &path, TcpOutletOptions::new())
+        .await?;
+    let (inlet_saddr, _) = tcp
+        .create_inlet("127.0.0.1:0", route!["outlet"], TcpInletOptions::new())
+        .await?;
+
+    let handle = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut payload = [0u8; LENGTH];
+        stream.read_exact(&mut payload).await.unwrap();
+        assert_eq!(payload, payload1);
+        stream.write_all(&payload2).await.unwrap();
+        stream
+    });
+
+    let mut stream = TcpStream::connect(inlet_saddr).await.unwrap();
+    write_binary(&mut stream, payload1).await;
+    read_assert_binary(&mut stream, payload2).await;
+    assert!(handle.await.is_ok());
+
+    // the outlet connection reports the socket path
+    let connections = tcp.registry().get_all_portal_connections();
+    let outlet_connection = connections
+        .iter()
+        .find(|c| c.portal_type() == PortalType::Outlet)
+        .unwrap();
+    assert_eq!(outlet_connection.unix_socket_path(), Some(path.as_path()));
+
+    // a path which is not a Unix domain socket is rejected
+    let error = tcp
+        .create_unix_outlet("outlet2", std::env::temp_dir(), TcpOutletOptions::new())
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("is not a Unix domain socket"));
+
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}
+
+#[cfg(unix)]
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 15000)]
+async fn portal__unix_socket_inlet__should_succeed(ctx: &mut Context) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    use tokio::net::UnixStream;
+
+    let payload1 = generate_binary();
+    let payload2 = generate_binary();
+
+    let tcp = TcpTransport::create(ctx).await?;
+    let listener = {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let bind_address = listener.local_addr().unwrap().to_string();
+        tcp.create_outlet("outlet", bind_address, TcpOutletOptions::new())
+            .await?;
+        listener
+    };
+
+    let path = std::env::temp_dir().join(format!("ockam-inlet-{}.sock", random::<u64>()));
+    let outlet_routes =
+        TcpInletLoadBalancer::new(vec![route!["outlet"]], TcpInletLoadBalancing::default());
+    let options = TcpInletOptions::new().with_unix_socket_mode(0o600);
+    let inlet_address = tcp
+        .create_unix_inlet(&path, outlet_routes.clone(), options)
+        .await?;
+    let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+
+    let handle = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        read_assert_binary(&mut stream, payload1).await;
+        write_binary(&mut stream, payload2).await;
+        stream
+    });
+
+    let mut stream = UnixStream::connect(&path).await.unwrap();
+    stream.write_all(&payload1).await.unwrap();
+    let mut payload = [0u8; LENGTH];
+    stream.read_exact(&mut payload).await.unwrap();
+    assert_eq!(payload, payload2);
+    assert!(handle.await.is_ok());
+
+    // an existing path is only replaced when allowed
+    let error = tcp
+        .create_unix_inlet(&path, outlet_routes.clone(), TcpInletOptions::new())
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("already exists"));
+
+    // the socket file is removed when the inlet is stopped
+    tcp.stop_inlet(inlet_address).await?;
+    ctx.sleep(Duration::from_millis(100)).await;
+    assert!(!path.exists());
+
+    Ok(())
+}
+
+/// Read the PROXY protocol v2 header sent by an outlet, with addresses of a known family
+async fn read_proxy_protocol_header(stream: &mut TcpStream) -> ProxyProtocolHeader {
+    let mut fixed = [0u8; 16];
+    stream.read_exact(&mut fixed).await.unwrap();
+    let mut header = fixed.to_vec();
+    header.resize(16 + u16::from_be_bytes([fixed[14], fixed[15]]) as usize, 0);
+    stream.read_exact(&mut header[16..]).await.unwrap();
+    let (decoded, length) = ProxyProtocolHeader::decode(&header).unwrap();
+    assert_eq!(length, header.len());
+    decoded
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 15000)]
+async fn portal__proxy_protocol_outlet__should_send_the_client_address(
+    ctx: &mut Context,
+) -> Result<()> {
+    let payload1 = generate_binary();
+    let payload2 = generate_binary();
+
+    let tcp = TcpTransport::create(ctx).await?;
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let bind_address = listener.local_addr().unwrap().to_string();
+    tcp.create_outlet(
+        "outlet",
+        bind_address,
+        TcpOutletOptions::new().with_proxy_protocol(true),
+    )
+    .await?;
+    let (inlet_socket_addr, _) = tcp
+        .create_inlet("127.0.0.1:0", route!["outlet"], TcpInletOptions::new())
+        .await?;
+
+    let mut stream = TcpStream::connect(inlet_socket_addr).await.unwrap();
+    let client_address = stream.local_addr().unwrap();
+    let handle = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let header = read_proxy_protocol_header(&mut stream).await;
+        read_assert_binary(&mut stream, payload1).await;
+        write_binary(&mut stream, payload2).await;
+        header
+    });
+
+    write_binary(&mut stream, payload1).await;
+    read_assert_binary(&mut stream, payload2).await;
+
+    let header = handle.await.unwrap();
+    assert_eq!(
+        header,
+        ProxyProtocolHeader::proxy(client_address, inlet_socket_addr)
+    );
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 15000)]
+async fn portal__expect_proxy_protocol_inlet__should_forward_the_original_client_address(
+    ctx: &mut Context,
+) -> Result<()> {
+    let tcp = TcpTransport::create(ctx).await?;
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let bind_address = listener.local_addr().unwrap().to_string();
+    tcp.create_outlet(
+        "outlet",
+        bind_address,
+        TcpOutletOptions::new().with_proxy_protocol(true),
+    )
+    .await?;
+    let (inlet_socket_addr, _) = tcp
+        .create_inlet(
+            "127.0.0.1:0",
+            route!["outlet"],
+            TcpInletOptions::new().with_expect_proxy_protocol(true),
+        )
+        .await?;
+
+    // the addresses of the header sent by the client are passed on, for IPv4 and IPv6 clients
+    let headers = [
+        ProxyProtocolHeader::proxy(
+            "192.0.2.10:41000".parse().unwrap(),
+            "198.51.100.1:443".parse().unwrap(),
+        ),
+        ProxyProtocolHeader::proxy(
+            "[2001:db8::10]:41000".parse().unwrap(),
+            "[2001:db8::1]:443".parse().unwrap(),
+        ),
+    ];
+    for expected in headers {
+        let payload = generate_binary();
+        let mut stream = TcpStream::connect(inlet_socket_addr).await.unwrap();
+        stream.write_all(&expected.encode()).await.unwrap();
+        write_binary(&mut stream, payload).await;
+
+        let (mut target, _) = listener.accept().await.unwrap();
+        let header = read_proxy_protocol_header(&mut target).await;
+        assert_eq!(header, expected);
+        // the header of the client is stripped from the data
+        read_assert_binary(&mut target, payload).await;
+    }
+
+    // a LOCAL header is replaced with the addresses of the connection to the inlet
+    let payload = generate_binary();
+    let mut stream = TcpStream::connect(inlet_socket_addr).await.unwrap();
+    let client_address = stream.local_addr().unwrap();
+    stream
+        .write_all(&ProxyProtocolHeader::Local.encode())
+        .await
+        .unwrap();
+    write_binary(&mut stream, payload).await;
+    let (mut target, _) = listener.accept().await.unwrap();
+    let header = read_proxy_protocol_header(&mut target).await;
+    assert_eq!(
+        header,
+        ProxyProtocolHeader::proxy(client_address, inlet_socket_addr)
+    );
+    read_assert_binary(&mut target, payload).await;
+
+    // a connection without a header is closed before reaching the outlet
+    let mut stream = TcpStream::connect(inlet_socket_addr).await.unwrap();
+    stream.write_all(b"GET / HTTP/1.1\r\n\r\n").await.unwrap();
+    let mut buffer = [0u8; 1];
+    assert_eq!(stream.read(&mut buffer).await.unwrap_or(0), 0);
+    assert!(
+        tokio::time::timeout(Duration::from_millis(250), listener.accept())
+            .await
+            .is_err()
+    );
+
+    Ok(())
+}
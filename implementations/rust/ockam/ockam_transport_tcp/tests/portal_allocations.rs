@@ -0,0 +1,98 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use ockam_core::{route, Result};
+use ockam_node::Context;
+use ockam_transport_tcp::{TcpInletOptions, TcpOutletOptions, TcpTransport, MAX_PAYLOAD_SIZE};
+
+/// Allocator counting the number of allocations made by the whole test binary
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+const TOTAL_LENGTH: usize = 32 * 1024 * 1024;
+
+/// Send `TOTAL_LENGTH` bytes through a portal reading chunks of `chunk_size` bytes,
+/// and return the number of allocations made per MiB transferred
+async fn allocations_per_mib(
+    tcp: &TcpTransport,
+    outlet_address: &str,
+    chunk_size: usize,
+) -> Result<usize> {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let bind_address = listener.local_addr().unwrap().to_string();
+    tcp.create_outlet(
+        outlet_address,
+        bind_address,
+        TcpOutletOptions::new().with_chunk_size(chunk_size),
+    )
+    .await?;
+    let (inlet_saddr, _) = tcp
+        .create_inlet(
+            "127.0.0.1:0",
+            route![outlet_address],
+            TcpInletOptions::new().with_chunk_size(chunk_size),
+        )
+        .await?;
+
+    let reader = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut buffer = vec![0u8; 1024 * 1024];
+        let mut received = 0;
+        while received < TOTAL_LENGTH {
+            let length = stream.read(&mut buffer).await.unwrap();
+            assert_ne!(length, 0, "the connection was closed too early");
+            received += length;
+        }
+    });
+
+    let mut stream = TcpStream::connect(inlet_saddr).await.unwrap();
+    let chunk = vec![42u8; 1024 * 1024];
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    for _ in 0..TOTAL_LENGTH / chunk.len() {
+        stream.write_all(&chunk).await.unwrap();
+    }
+    reader.await.unwrap();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+
+    Ok(allocations / (TOTAL_LENGTH / (1024 * 1024)))
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 60000)]
+async fn portal__bigger_chunks__should_allocate_less_per_mib(ctx: &mut Context) -> Result<()> {
+    let tcp = TcpTransport::create(ctx).await?;
+
+    let default_chunks = allocations_per_mib(&tcp, "outlet1", MAX_PAYLOAD_SIZE).await?;
+    let bigger_chunks = allocations_per_mib(&tcp, "outlet2", 1024 * 1024).await?;
+
+    assert!(
+        bigger_chunks < default_chunks,
+        "{bigger_chunks} allocations per MiB with 1 MiB chunks, \
+         {default_chunks} allocations per MiB with {MAX_PAYLOAD_SIZE} bytes chunks"
+    );
+
+    Ok(())
+}
@@ -10,11 +10,13 @@
 use ockam_core::TransportType;
 
 pub use hole_puncher::{PunchError, UdpHolePuncher};
+pub use options::UdpListenerOptions;
 pub use rendezvous_service::UdpRendezvousService;
-pub use transport::UdpTransport;
 pub use transport::UdpTransportExtension;
+pub use transport::{UdpListener, UdpTransport};
 
 mod hole_puncher;
+mod options;
 mod rendezvous_service;
 mod router;
 mod transport;
@@ -0,0 +1,107 @@
+use ockam_core::compat::sync::Arc;
+use ockam_core::flow_control::{FlowControlId, FlowControlOutgoingAccessControl, FlowControls};
+use ockam_core::{Address, AllowAll, OutgoingAccessControl};
+
+/// Options for a UDP listener
+#[derive(Debug)]
+pub struct UdpListenerOptions {
+    pub(crate) flow_control_id: FlowControlId,
+}
+
+impl UdpListenerOptions {
+    /// Mark this UDP listener as a Spawner with a fresh [`FlowControlId`].
+    /// The listener gets its own [`FlowControlId`], and its messages are delivered
+    /// to the consumers of either [`FlowControlId`]
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            flow_control_id: FlowControls::generate_flow_control_id(),
+        }
+    }
+
+    /// Use the spawner [`FlowControlId`] of another listener, for example a TCP listener,
+    /// so that its consumers receive the messages of this listener as well
+    pub fn with_spawner_flow_control_id(mut self, flow_control_id: FlowControlId) -> Self {
+        self.flow_control_id = flow_control_id;
+        self
+    }
+
+    /// Getter for the spawner [`FlowControlId`]
+    pub fn spawner_flow_control_id(&self) -> FlowControlId {
+        self.flow_control_id.clone()
+    }
+}
+
+/// Flow control of the messages received on a local socket
+pub(crate) enum UdpFlowControl {
+    /// Received messages can be delivered to any worker
+    None,
+    /// Received messages are only delivered to the consumers of the flow control id,
+    /// or of the spawner flow control id
+    Listener {
+        flow_control_id: FlowControlId,
+        spawner_flow_control_id: FlowControlId,
+    },
+}
+
+impl UdpFlowControl {
+    pub(crate) fn new(spawner_flow_control_id: Option<FlowControlId>) -> Self {
+        match spawner_flow_control_id {
+            Some(spawner_flow_control_id) => Self::Listener {
+                flow_control_id: FlowControls::generate_flow_control_id(),
+                spawner_flow_control_id,
+            },
+            None => Self::None,
+        }
+    }
+
+    pub(crate) fn flow_control_id(&self) -> Option<FlowControlId> {
+        match self {
+            Self::None => None,
+            Self::Listener {
+                flow_control_id, ..
+            } => Some(flow_control_id.clone()),
+        }
+    }
+
+    /// Register the listener as a producer. The paired sender is part of the same
+    /// producer, and its internal address receives the acknowledgements to send
+    pub(crate) fn setup_flow_control(
+        &self,
+        flow_controls: &FlowControls,
+        listener_address: &Address,
+        sender_address: &Address,
+        sender_internal_address: &Address,
+    ) {
+        if let Self::Listener {
+            flow_control_id,
+            spawner_flow_control_id,
+        } = self
+        {
+            flow_controls.add_producer(
+                listener_address.clone(),
+                flow_control_id,
+                Some(spawner_flow_control_id),
+                vec![sender_address.clone()],
+            );
+            flow_controls.add_consumer(sender_internal_address.clone(), flow_control_id);
+        }
+    }
+
+    pub(crate) fn create_access_control(
+        &self,
+        flow_controls: &FlowControls,
+    ) -> Arc<dyn OutgoingAccessControl> {
+        match self {
+            Self::None => Arc::new(AllowAll),
+            Self::Listener {
+                flow_control_id,
+                spawner_flow_control_id,
+            } => Arc::new(FlowControlOutgoingAccessControl::new(
+                flow_controls,
+                flow_control_id.clone(),
+                Some(spawner_flow_control_id.clone()),
+            )),
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use crate::router::messages::{UdpRouterRequest, UdpRouterResponse};
+use ockam_core::flow_control::FlowControlId;
 use ockam_core::{Address, AllowAll, Result};
 use ockam_node::Context;
 use std::net::SocketAddr;
@@ -31,8 +32,18 @@ impl UdpRouterHandle {
 
     /// Request router start listening on a local UDP port
     /// so the local node can act as a server to other nodes
-    pub async fn listen(&self, local_addr: SocketAddr) -> Result<()> {
-        let msg = UdpRouterRequest::Listen { local_addr };
+    ///
+    /// Returns the address of the sender for this local port, the bound socket address,
+    /// and the flow control id of the listener if a spawner flow control id is given
+    pub async fn listen(
+        &self,
+        local_addr: SocketAddr,
+        spawner_flow_control_id: Option<FlowControlId>,
+    ) -> Result<(Address, SocketAddr, Option<FlowControlId>)> {
+        let msg = UdpRouterRequest::Listen {
+            local_addr,
+            spawner_flow_control_id,
+        };
         let UdpRouterResponse::Listen(res) = self
             .ctx
             .send_and_receive(self.api_addr.clone(), msg)
@@ -1,4 +1,5 @@
-use ockam_core::{Message, Result};
+use ockam_core::flow_control::FlowControlId;
+use ockam_core::{Address, Message, Result};
 use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
 
@@ -6,10 +7,13 @@ use std::net::SocketAddr;
 pub enum UdpRouterRequest {
     /// Listen on a local UDP port so the local node can
     /// act as a server to other nodes
-    Listen { local_addr: SocketAddr },
+    Listen {
+        local_addr: SocketAddr,
+        spawner_flow_control_id: Option<FlowControlId>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Debug, Message)]
 pub enum UdpRouterResponse {
-    Listen(Result<()>),
+    Listen(Result<(Address, SocketAddr, Option<FlowControlId>)>),
 }
@@ -1,7 +1,9 @@
+use crate::options::UdpFlowControl;
 use crate::router::messages::{UdpRouterRequest, UdpRouterResponse};
 use crate::router::UdpRouterHandle;
-use crate::workers::{TransportMessageCodec, UdpListenProcessor, UdpSendWorker};
+use crate::workers::{SharedUdpSocketState, UdpListenProcessor, UdpPacketCodec, UdpSendWorker};
 use futures_util::StreamExt;
+use ockam_core::flow_control::FlowControlId;
 use ockam_core::{
     async_trait, Address, AllowAll, Any, Decodable, DenyAll, LocalMessage, Mailbox, Mailboxes,
     Result, Routed, Worker,
@@ -59,9 +61,10 @@ impl UdpRouter {
         let handle = UdpRouterHandle::try_new(&child_ctx, &api_addr).await?;
 
         // Create sender, listener pair for 'client' messages
-        let client_sender = Self::create_sender_listener(
+        let (client_sender, _, _) = Self::create_sender_listener(
             &child_ctx,
             SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 0),
+            None,
         )
         .await?;
 
@@ -102,8 +105,16 @@ impl UdpRouter {
 
     /// Create a sender, listener pair for the given socket address.
     ///
-    /// Returns the address of the created sender.
-    async fn create_sender_listener(ctx: &Context, local_addr: SocketAddr) -> Result<Address> {
+    /// If a spawner flow control id is given, the listener is a producer and the received
+    /// messages are only delivered to the consumers of its flow control id or of the spawner.
+    ///
+    /// Returns the address of the created sender, the bound socket address
+    /// and the flow control id of the listener.
+    async fn create_sender_listener(
+        ctx: &Context,
+        local_addr: SocketAddr,
+        spawner_flow_control_id: Option<FlowControlId>,
+    ) -> Result<(Address, SocketAddr, Option<FlowControlId>)> {
         // This transport only supports IPv4
         if !local_addr.is_ipv4() {
             error!(local_addr = %local_addr, "This transport only supprts IPv4");
@@ -114,22 +125,58 @@ impl UdpRouter {
         let socket = UdpSocket::bind(local_addr)
             .await
             .map_err(|_| TransportError::InvalidAddress)?;
+        let bound_addr = socket
+            .local_addr()
+            .map_err(|_| TransportError::InvalidAddress)?;
 
         // Split socket into sink and stream
-        let (sink, stream) = UdpFramed::new(socket, TransportMessageCodec).split();
+        let (sink, stream) = UdpFramed::new(socket, UdpPacketCodec).split();
 
-        debug!("Creating new sender and listener for {}", local_addr);
+        debug!("Creating new sender and listener for {}", bound_addr);
+
+        // State shared by the sender and the listener to acknowledge and retransmit messages
+        let state = SharedUdpSocketState::default();
 
         // Create sender
         let sender_addr = Address::random_tagged("UdpSendWorker");
-        let sender = UdpSendWorker::new(sink);
-        // FIXME: @ac
-        ctx.start_worker(sender_addr.clone(), sender).await?;
+        let sender_internal_addr = Address::random_tagged("UdpSendWorker.internal");
+        let sender = UdpSendWorker::new(sink, sender_internal_addr.clone(), state.clone());
+        let main_mailbox = Mailbox::new(
+            sender_addr.clone(),
+            Arc::new(AllowAll), // FIXME: @ac
+            Arc::new(AllowAll), // FIXME: @ac
+        );
+        let internal_mailbox = Mailbox::new(
+            sender_internal_addr.clone(),
+            Arc::new(AllowAll), // FIXME: @ac
+            Arc::new(DenyAll),
+        );
+        WorkerBuilder::new(sender)
+            .with_mailboxes(Mailboxes::new(main_mailbox, vec![internal_mailbox]))
+            .start(ctx)
+            .await?;
 
         // Create listener
-        UdpListenProcessor::start(ctx, stream, sender_addr.clone()).await?;
+        let listener_addr = Address::random_tagged("UdpListenProcessor");
+        let flow_control = UdpFlowControl::new(spawner_flow_control_id);
+        flow_control.setup_flow_control(
+            ctx.flow_controls(),
+            &listener_addr,
+            &sender_addr,
+            &sender_internal_addr,
+        );
+        UdpListenProcessor::start(
+            ctx,
+            listener_addr,
+            stream,
+            sender_addr.clone(),
+            sender_internal_addr,
+            state,
+            flow_control.create_access_control(ctx.flow_controls()),
+        )
+        .await?;
 
-        Ok(sender_addr)
+        Ok((sender_addr, bound_addr, flow_control.flow_control_id()))
     }
 }
 
@@ -161,9 +208,16 @@ impl Worker for UdpRouter {
             let msg = UdpRouterRequest::decode(msg.payload())?;
             trace!("handle_message() API_ADDR: msg = {:?}", msg);
             match msg {
-                UdpRouterRequest::Listen { local_addr } => {
-                    let res = Self::create_sender_listener(&self.ctx, local_addr).await;
-                    let res = res.map(|_| ());
+                UdpRouterRequest::Listen {
+                    local_addr,
+                    spawner_flow_control_id,
+                } => {
+                    let res = Self::create_sender_listener(
+                        &self.ctx,
+                        local_addr,
+                        spawner_flow_control_id,
+                    )
+                    .await;
                     ctx.send_from_address(return_route, UdpRouterResponse::Listen(res), msg_addr)
                         .await?;
                 }
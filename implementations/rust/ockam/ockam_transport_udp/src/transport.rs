@@ -1,31 +1,152 @@
 use crate::router::{UdpRouter, UdpRouterHandle};
-use ockam_core::{async_trait, Result};
+use crate::workers::UdpConnectionWorker;
+use crate::UdpListenerOptions;
+use core::fmt;
+use ockam_core::flow_control::FlowControlId;
+use ockam_core::{async_trait, Address, AllowAll, AsyncTryClone, Result};
 use ockam_node::{Context, HasContext};
 use ockam_transport_core::TransportError;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::sync::{Arc, RwLock};
+
+/// A local UDP port the transport listens on
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UdpListener {
+    socket_address: SocketAddr,
+    sender_address: Address,
+    flow_control_id: Option<FlowControlId>,
+}
+
+impl UdpListener {
+    /// Bound socket address
+    pub fn socket_address(&self) -> SocketAddr {
+        self.socket_address
+    }
+
+    /// Address of the worker sending the datagrams of this local port
+    pub fn sender_address(&self) -> &Address {
+        &self.sender_address
+    }
+
+    /// Flow control id of the received messages, if the listener was created with options
+    pub fn flow_control_id(&self) -> Option<&FlowControlId> {
+        self.flow_control_id.as_ref()
+    }
+}
 
 /// High level management interface for UDP transport
 ///
 /// A node will have, at most, one UDP transport running.
 ///
 /// This transport only supports IPv4.
+///
+/// Messages are acknowledged and retransmitted, unless they are payloads of secure
+/// channels, and keepalives are sent to idle peers to keep NAT mappings open.
 pub struct UdpTransport {
+    ctx: Context,
     router_handle: UdpRouterHandle,
+    listeners: Arc<RwLock<Vec<UdpListener>>>,
 }
 
 impl UdpTransport {
     /// Create a new UDP transport for the current node
     pub async fn create(ctx: &Context) -> Result<UdpTransport> {
         let router_handle = UdpRouter::register(ctx).await?;
-        Ok(Self { router_handle })
+        Ok(Self {
+            ctx: ctx.async_try_clone().await?,
+            router_handle,
+            listeners: Default::default(),
+        })
     }
 
     /// Start listening to incoming datagrams on a specified local address
-    pub async fn listen<S: AsRef<str>>(&self, bind_addr: S) -> Result<()> {
+    ///
+    /// Returns the bound socket address, which is useful when listening on port 0
+    pub async fn listen<S: AsRef<str>>(&self, bind_addr: S) -> Result<SocketAddr> {
+        let listener = self.create_listener(bind_addr.as_ref(), None).await?;
+        Ok(listener.socket_address())
+    }
+
+    /// Start listening to incoming datagrams on a specified local address.
+    /// The received messages are only delivered to the consumers of the
+    /// flow control ids of the listener
+    pub async fn listen_with_options<S: AsRef<str>>(
+        &self,
+        bind_addr: S,
+        options: UdpListenerOptions,
+    ) -> Result<UdpListener> {
+        self.create_listener(bind_addr.as_ref(), Some(options.flow_control_id))
+            .await
+    }
+
+    async fn create_listener(
+        &self,
+        bind_addr: &str,
+        spawner_flow_control_id: Option<FlowControlId>,
+    ) -> Result<UdpListener> {
         let bind_addr = bind_addr
-            .as_ref()
             .parse()
             .map_err(|_| TransportError::InvalidAddress)?;
-        self.router_handle.listen(bind_addr).await
+        let (sender_address, socket_address, flow_control_id) = self
+            .router_handle
+            .listen(bind_addr, spawner_flow_control_id)
+            .await?;
+        let listener = UdpListener {
+            socket_address,
+            sender_address,
+            flow_control_id,
+        };
+        self.listeners
+            .write()
+            .map_err(|_| TransportError::GenericIo)?
+            .push(listener.clone());
+        Ok(listener)
+    }
+
+    /// Return the local ports the transport listens on
+    pub fn listeners(&self) -> Vec<UdpListener> {
+        self.listeners
+            .read()
+            .map(|listeners| listeners.clone())
+            .unwrap_or_default()
+    }
+
+    /// Create a local address forwarding messages to a remote peer,
+    /// given as `host:port`, so that it can be used in routes
+    pub async fn connect<S: AsRef<str>>(&self, peer: S) -> Result<Address> {
+        let peer = peer.as_ref().to_string();
+        // Fail early if the peer can't be resolved
+        let mut resolved = peer
+            .to_socket_addrs()
+            .map_err(|_| TransportError::InvalidAddress)?;
+        if !resolved.any(|a| a.is_ipv4()) {
+            return Err(TransportError::InvalidAddress)?;
+        }
+
+        let address = Address::random_tagged("UdpConnectionWorker");
+        // FIXME: @ac
+        self.ctx
+            .start_worker_with_access_control(
+                address.clone(),
+                UdpConnectionWorker::new(peer),
+                AllowAll,
+                AllowAll,
+            )
+            .await?;
+        Ok(address)
+    }
+
+    /// Stop forwarding messages to a remote peer
+    pub async fn disconnect(&self, address: &Address) -> Result<()> {
+        self.ctx.stop_worker(address.clone()).await
+    }
+}
+
+impl fmt::Debug for UdpTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UdpTransport")
+            .field("listeners", &self.listeners())
+            .finish()
     }
 }
 
@@ -4,31 +4,152 @@ use ockam_core::{Decodable, Encodable};
 use ockam_transport_core::TransportError;
 use tokio_util::codec::{Decoder, Encoder};
 
-pub(crate) struct TransportMessageCodec;
+const BEST_EFFORT: u8 = 0;
+const RELIABLE: u8 = 1;
+const ACK: u8 = 2;
+const KEEPALIVE: u8 = 3;
 
-impl Encoder<TransportMessage> for TransportMessageCodec {
-    type Error = TransportError;
-    fn encode(&mut self, item: TransportMessage, dst: &mut BytesMut) -> Result<(), Self::Error> {
-        let msg_buf = item.encode().map_err(|_| TransportError::SendBadMessage)?;
-        let len = msg_buf.len();
-        dst.put_u16(len as u16);
+/// A datagram exchanged by the UDP transport
+#[derive(Debug, Clone)]
+pub(crate) enum UdpPacket {
+    /// A message which is sent once and not acknowledged
+    BestEffort(TransportMessage),
+    /// A message which is retransmitted until the peer acknowledges its sequence number
+    Reliable { seq: u64, msg: TransportMessage },
+    /// The acknowledgement of a reliable message
+    Ack { seq: u64 },
+    /// An empty datagram sent on idle paths to keep NAT mappings open
+    Keepalive,
+}
+
+pub(crate) struct UdpPacketCodec;
+
+impl UdpPacketCodec {
+    fn encode_message(msg: TransportMessage, dst: &mut BytesMut) -> Result<(), TransportError> {
+        let msg_buf = msg.encode().map_err(|_| TransportError::SendBadMessage)?;
+        let len = u16::try_from(msg_buf.len()).map_err(|_| TransportError::SendBadMessage)?;
+        dst.put_u16(len);
         dst.put(&msg_buf[..]);
         Ok(())
     }
+
+    fn decode_message(src: &mut BytesMut) -> Result<TransportMessage, TransportError> {
+        if src.remaining() < 2 {
+            return Err(TransportError::RecvBadMessage);
+        }
+        let len = src.get_u16() as usize;
+        if src.remaining() < len {
+            return Err(TransportError::RecvBadMessage);
+        }
+        TransportMessage::decode(&src.split_to(len)[..]).map_err(|_| TransportError::RecvBadMessage)
+    }
+
+    fn decode_seq(src: &mut BytesMut) -> Result<u64, TransportError> {
+        if src.remaining() < 8 {
+            return Err(TransportError::RecvBadMessage);
+        }
+        Ok(src.get_u64())
+    }
+}
+
+impl Encoder<UdpPacket> for UdpPacketCodec {
+    type Error = TransportError;
+    fn encode(&mut self, item: UdpPacket, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        match item {
+            UdpPacket::BestEffort(msg) => {
+                dst.put_u8(BEST_EFFORT);
+                Self::encode_message(msg, dst)
+            }
+            UdpPacket::Reliable { seq, msg } => {
+                dst.put_u8(RELIABLE);
+                dst.put_u64(seq);
+                Self::encode_message(msg, dst)
+            }
+            UdpPacket::Ack { seq } => {
+                dst.put_u8(ACK);
+                dst.put_u64(seq);
+                Ok(())
+            }
+            UdpPacket::Keepalive => {
+                dst.put_u8(KEEPALIVE);
+                Ok(())
+            }
+        }
+    }
 }
 
-impl Decoder for TransportMessageCodec {
-    type Item = TransportMessage;
+impl Decoder for UdpPacketCodec {
+    type Item = UdpPacket;
     type Error = TransportError;
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
         if src.is_empty() {
             return Ok(None);
         }
 
-        let len = src.get_u16() as usize;
-        let msg = TransportMessage::decode(&src.split_to(len)[..])
-            .map_err(|_| TransportError::RecvBadMessage)?;
+        let packet = match src.get_u8() {
+            BEST_EFFORT => Self::decode_message(src).map(UdpPacket::BestEffort),
+            RELIABLE => Self::decode_seq(src).and_then(|seq| {
+                Self::decode_message(src).map(|msg| UdpPacket::Reliable { seq, msg })
+            }),
+            ACK => Self::decode_seq(src).map(|seq| UdpPacket::Ack { seq }),
+            KEEPALIVE => Ok(UdpPacket::Keepalive),
+            _ => Err(TransportError::RecvBadMessage),
+        };
+
+        // Drop the rest of a malformed datagram so that the next one can be read
+        if packet.is_err() {
+            src.clear();
+        }
+
+        packet.map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_core::route;
+
+    fn roundtrip(packet: UdpPacket) -> UdpPacket {
+        let mut buffer = BytesMut::new();
+        UdpPacketCodec.encode(packet, &mut buffer).unwrap();
+        let decoded = UdpPacketCodec.decode(&mut buffer).unwrap().unwrap();
+        assert!(buffer.is_empty());
+        decoded
+    }
+
+    #[test]
+    fn packets_can_be_encoded_and_decoded() {
+        let msg = TransportMessage::latest(route!["a"], route!["b"], vec![1, 2, 3]);
+
+        match roundtrip(UdpPacket::Reliable {
+            seq: 42,
+            msg: msg.clone(),
+        }) {
+            UdpPacket::Reliable { seq, msg: decoded } => {
+                assert_eq!(seq, 42);
+                assert_eq!(decoded, msg);
+            }
+            other => panic!("unexpected packet {other:?}"),
+        }
+        assert!(matches!(
+            roundtrip(UdpPacket::BestEffort(msg.clone())),
+            UdpPacket::BestEffort(decoded) if decoded == msg
+        ));
+        assert!(matches!(
+            roundtrip(UdpPacket::Ack { seq: 7 }),
+            UdpPacket::Ack { seq: 7 }
+        ));
+        assert!(matches!(
+            roundtrip(UdpPacket::Keepalive),
+            UdpPacket::Keepalive
+        ));
+    }
 
-        Ok(Some(msg))
+    #[test]
+    fn truncated_packets_are_rejected() {
+        let mut buffer = BytesMut::from(&[RELIABLE, 0, 0][..]);
+        assert!(UdpPacketCodec.decode(&mut buffer).is_err());
+        assert!(buffer.is_empty());
     }
 }
@@ -0,0 +1,41 @@
+use crate::UDP;
+use ockam_core::{async_trait, Address, Any, Result, Routed, Worker};
+use ockam_node::Context;
+use tracing::trace;
+
+/// A connection to a remote UDP peer
+///
+/// UDP is connectionless, this worker only gives a local address to a remote peer so
+/// that it can be used in routes and multiaddrs. Messages sent to this worker
+/// are forwarded to the peer through the UDP router.
+pub(crate) struct UdpConnectionWorker {
+    /// Address of the remote peer, as `host:port`
+    peer: String,
+}
+
+impl UdpConnectionWorker {
+    pub(crate) fn new(peer: String) -> Self {
+        Self { peer }
+    }
+}
+
+#[async_trait]
+impl Worker for UdpConnectionWorker {
+    type Message = Any;
+    type Context = Context;
+
+    async fn initialize(&mut self, ctx: &mut Context) -> Result<()> {
+        ctx.set_cluster(crate::CLUSTER_NAME).await
+    }
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<Any>) -> Result<()> {
+        // Replace our address with the address of the peer
+        let msg = msg
+            .into_local_message()
+            .pop_front_onward_route()?
+            .push_front_onward_route(&Address::new(UDP, self.peer.clone()));
+        trace!(peer = %self.peer, onward_route = %msg.onward_route_ref(), "Forwarding to UDP peer");
+
+        ctx.forward(msg).await
+    }
+}
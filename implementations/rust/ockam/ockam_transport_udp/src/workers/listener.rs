@@ -1,11 +1,15 @@
-use super::TransportMessageCodec;
+use super::{SharedUdpSocketState, UdpPacket, UdpPacketCodec, UdpSenderEvent};
 use crate::UDP;
 use futures_util::stream::SplitStream;
 use futures_util::StreamExt;
-use ockam_core::{async_trait, route, Address, AllowAll, LocalMessage, Processor, Result};
-use ockam_node::Context;
+use ockam_core::compat::sync::Arc;
+use ockam_core::{
+    async_trait, route, Address, AllowAll, LocalMessage, OutgoingAccessControl, Processor, Result,
+};
+use ockam_node::{Context, ProcessorBuilder};
+use ockam_transport_core::TransportError;
 use tokio_util::udp::UdpFramed;
-use tracing::{debug, warn};
+use tracing::{debug, trace, warn};
 
 /// A listener for the UDP transport
 ///
@@ -15,27 +19,42 @@ use tracing::{debug, warn};
 /// When a message is received, the address of the paired sender
 /// ([`UdpSendWorker`](crate::workers::UdpSendWorker)) is injected into the message's
 /// return route so that replies are sent to the sender.
+///
+/// Reliable messages are acknowledged through the paired sender, and their
+/// retransmissions are dropped.
 pub(crate) struct UdpListenProcessor {
-    /// The read half of the udnerlying UDP socket.
-    stream: SplitStream<UdpFramed<TransportMessageCodec>>,
+    /// The read half of the underlying UDP socket.
+    stream: SplitStream<UdpFramed<UdpPacketCodec>>,
     /// Address of our sender counterpart
     sender_addr: Address,
+    /// Address of our sender counterpart receiving acknowledgements to send
+    sender_internal_addr: Address,
+    state: SharedUdpSocketState,
 }
 
 impl UdpListenProcessor {
     pub(crate) async fn start(
         ctx: &Context,
-        stream: SplitStream<UdpFramed<TransportMessageCodec>>,
+        addr: Address,
+        stream: SplitStream<UdpFramed<UdpPacketCodec>>,
         sender_addr: Address,
+        sender_internal_addr: Address,
+        state: SharedUdpSocketState,
+        outgoing_access_control: Arc<dyn OutgoingAccessControl>,
     ) -> Result<()> {
         let processor = Self {
             stream,
             sender_addr,
+            sender_internal_addr,
+            state,
         };
-        let addr = Address::random_tagged("UdpListenProcessor");
 
         // FIXME: @ac
-        ctx.start_processor_with_access_control(addr.clone(), processor, AllowAll, AllowAll)
+        ProcessorBuilder::new(processor)
+            .with_address(addr)
+            .with_incoming_access_control(AllowAll)
+            .with_outgoing_access_control_arc(outgoing_access_control)
+            .start(ctx)
             .await?;
 
         Ok(())
@@ -52,9 +71,9 @@ impl Processor for UdpListenProcessor {
 
     async fn process(&mut self, ctx: &mut Self::Context) -> Result<bool> {
         debug!("Waiting for incoming UDP datagram...");
-        let (mut msg, addr) = match self.stream.next().await {
+        let (packet, addr) = match self.stream.next().await {
             Some(res) => match res {
-                Ok((msg, addr)) => (LocalMessage::from_transport_message(msg), addr),
+                Ok((packet, addr)) => (packet, addr),
                 Err(e) => {
                     warn!(
                         "Failed to read message, will wait for next message: {:?}",
@@ -69,6 +88,45 @@ impl Processor for UdpListenProcessor {
             }
         };
 
+        let (reliable_seq, msg) = {
+            let mut state = self.state.lock().map_err(|_| TransportError::GenericIo)?;
+            state.heard(addr);
+            match packet {
+                UdpPacket::BestEffort(msg) => (None, Some(msg)),
+                UdpPacket::Reliable { seq, msg } => {
+                    if state.first_reception(addr, seq) {
+                        (Some(seq), Some(msg))
+                    } else {
+                        trace!(peer = %addr, %seq, "Dropping a retransmitted message");
+                        (Some(seq), None)
+                    }
+                }
+                UdpPacket::Ack { seq } => {
+                    state.acked(addr, seq);
+                    return Ok(true);
+                }
+                UdpPacket::Keepalive => {
+                    trace!(peer = %addr, "Received a keepalive");
+                    return Ok(true);
+                }
+            }
+        };
+
+        // Acknowledge reliable messages, including retransmissions since the
+        // previous acknowledgement may have been lost
+        if let Some(seq) = reliable_seq {
+            ctx.send(
+                self.sender_internal_addr.clone(),
+                UdpSenderEvent::Ack { peer: addr, seq },
+            )
+            .await?;
+        }
+
+        let mut msg = match msg {
+            Some(msg) => LocalMessage::from_transport_message(msg),
+            None => return Ok(true),
+        };
+
         // Set return route to go directly to paired sender, skipping the UDP router
         let new_route = route![
             self.sender_addr.clone(),
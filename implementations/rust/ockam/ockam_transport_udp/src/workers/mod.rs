@@ -1,9 +1,13 @@
 // TODO: Would it be logical to move this `workers` directory into the `router` directory?
 
 pub(crate) use codec::*;
+pub(crate) use connection::*;
 pub(crate) use listener::*;
 pub(crate) use sender::*;
+pub(crate) use state::*;
 
 mod codec;
+mod connection;
 mod listener;
 mod sender;
+mod state;
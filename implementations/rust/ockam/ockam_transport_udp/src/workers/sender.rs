@@ -1,44 +1,116 @@
-use super::TransportMessageCodec;
+use super::{
+    SharedUdpSocketState, UdpPacket, UdpPacketCodec, KEEPALIVE_INTERVAL, RETRANSMIT_INTERVAL,
+};
 use crate::UDP;
 use futures_util::{stream::SplitSink, SinkExt};
-use ockam_core::{async_trait, Any, Result, Routed, TransportMessage, Worker};
-use ockam_node::Context;
-use ockam_transport_core::TransportError;
+use ockam_core::{
+    async_trait, Address, Any, Decodable, LocalMessage, Message, Result, Routed, Worker,
+};
+use ockam_node::{Context, DelayedEvent};
+use ockam_transport_core::{BestEffortDelivery, TransportError};
+use serde::{Deserialize, Serialize};
 use std::net::{SocketAddr, ToSocketAddrs};
+use std::time::Instant;
 use tokio_util::udp::UdpFramed;
 use tracing::{error, trace, warn};
 
+/// Events sent to the internal address of a [`UdpSendWorker`]
+#[derive(Serialize, Deserialize, Debug, Clone, Message)]
+pub(crate) enum UdpSenderEvent {
+    /// Time to retransmit unacknowledged messages and to send keepalives
+    Tick,
+    /// Acknowledge a reliable message received by the paired listener
+    Ack { peer: SocketAddr, seq: u64 },
+}
+
 /// A sender for the UDP transport
 ///
 /// This worker handles the sending of messages on a
 /// local socket. See [`UdpRouter`](crate::router::UdpRouter) for more details.
+///
+/// Messages are retransmitted until they are acknowledged, unless they are marked with
+/// [`BestEffortDelivery`] by their sender, like the payloads of TCP portals. Keepalives are sent
+/// to idle peers so that NAT mappings stay open.
 pub(crate) struct UdpSendWorker {
-    /// The read half of the udnerlying UDP socket.
-    sink: SplitSink<UdpFramed<TransportMessageCodec>, (TransportMessage, SocketAddr)>,
+    /// The write half of the underlying UDP socket.
+    sink: SplitSink<UdpFramed<UdpPacketCodec>, (UdpPacket, SocketAddr)>,
+    /// Address receiving [`UdpSenderEvent`]s
+    internal_addr: Address,
+    state: SharedUdpSocketState,
+    timer: Option<DelayedEvent<UdpSenderEvent>>,
+    next_seq: u64,
+    retransmission_scheduled: bool,
 }
 
 impl UdpSendWorker {
     /// Create a new `UdpSendWorker`
     pub(crate) fn new(
-        sink: SplitSink<UdpFramed<TransportMessageCodec>, (TransportMessage, SocketAddr)>,
+        sink: SplitSink<UdpFramed<UdpPacketCodec>, (UdpPacket, SocketAddr)>,
+        internal_addr: Address,
+        state: SharedUdpSocketState,
     ) -> Self {
-        Self { sink }
+        Self {
+            sink,
+            internal_addr,
+            state,
+            timer: None,
+            next_seq: 0,
+            retransmission_scheduled: false,
+        }
     }
-}
 
-#[async_trait]
-impl Worker for UdpSendWorker {
-    type Message = Any;
-    type Context = Context;
+    async fn send_packet(&mut self, peer: SocketAddr, packet: UdpPacket) -> Result<()> {
+        self.state
+            .lock()
+            .map_err(|_| TransportError::GenericIo)?
+            .sent(peer, &packet);
+        match self.sink.send((packet, peer)).await {
+            Ok(()) => {
+                trace!("Successful send to {}", peer);
+                Ok(())
+            }
+            Err(e) => {
+                error!("Failed send to {}: {:?}", peer, e);
+                Err(e)?
+            }
+        }
+    }
 
-    async fn handle_message(
-        &mut self,
-        _ctx: &mut Context,
-        msg: Routed<Self::Message>,
-    ) -> Result<()> {
-        // Parse message and remove our address from its routing
-        let mut msg = msg.into_local_message();
-        msg = msg.pop_front_onward_route()?;
+    /// Schedule the next tick: soon if messages wait for an acknowledgement,
+    /// otherwise when keepalives may be due
+    async fn schedule_tick(&mut self) -> Result<()> {
+        let has_pending = self
+            .state
+            .lock()
+            .map_err(|_| TransportError::GenericIo)?
+            .has_pending();
+        if let Some(timer) = self.timer.as_mut() {
+            if has_pending {
+                timer.schedule(RETRANSMIT_INTERVAL).await?;
+            } else {
+                timer.schedule(KEEPALIVE_INTERVAL).await?;
+            }
+        }
+        self.retransmission_scheduled = has_pending;
+        Ok(())
+    }
+
+    async fn handle_tick(&mut self) -> Result<()> {
+        let due = self
+            .state
+            .lock()
+            .map_err(|_| TransportError::GenericIo)?
+            .due(Instant::now());
+        for (peer, packet) in due {
+            // A failed retransmission is retried on the next tick
+            let _ = self.send_packet(peer, packet).await;
+        }
+        self.schedule_tick().await
+    }
+
+    async fn handle_route(&mut self, msg: LocalMessage) -> Result<()> {
+        // Remove our address from the message routing
+        let mut msg = msg.pop_front_onward_route()?;
         trace!("Sending message to {:?}", msg.onward_route_ref());
 
         // Resolve peer address to IPv4 SocketAddr(s).
@@ -73,15 +145,59 @@ impl Worker for UdpSendWorker {
             return Err(TransportError::InvalidAddress)?;
         }
 
-        // Send
-        match self.sink.send((msg.into_transport_message(), addr)).await {
-            Ok(()) => {
-                trace!("Successful send to {}", addr);
-                Ok(())
+        let packet = if BestEffortDelivery::is_marked(&msg) {
+            UdpPacket::BestEffort(msg.into_transport_message())
+        } else {
+            let seq = self.next_seq;
+            self.next_seq += 1;
+            UdpPacket::Reliable {
+                seq,
+                msg: msg.into_transport_message(),
             }
-            Err(e) => {
-                error!("Failed send to {}: {:?}", addr, e);
-                Err(e)?
+        };
+        let is_reliable = matches!(packet, UdpPacket::Reliable { .. });
+
+        self.send_packet(addr, packet).await?;
+
+        if is_reliable && !self.retransmission_scheduled {
+            self.schedule_tick().await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Worker for UdpSendWorker {
+    type Message = Any;
+    type Context = Context;
+
+    async fn initialize(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        self.timer = Some(
+            DelayedEvent::create(ctx, self.internal_addr.clone(), UdpSenderEvent::Tick).await?,
+        );
+        self.schedule_tick().await
+    }
+
+    async fn shutdown(&mut self, _ctx: &mut Self::Context) -> Result<()> {
+        if let Some(timer) = self.timer.as_mut() {
+            timer.cancel();
+        }
+        Ok(())
+    }
+
+    async fn handle_message(
+        &mut self,
+        _ctx: &mut Context,
+        msg: Routed<Self::Message>,
+    ) -> Result<()> {
+        if msg.msg_addr() != self.internal_addr {
+            return self.handle_route(msg.into_local_message()).await;
+        }
+
+        match UdpSenderEvent::decode(msg.payload())? {
+            UdpSenderEvent::Tick => self.handle_tick().await,
+            UdpSenderEvent::Ack { peer, seq } => {
+                self.send_packet(peer, UdpPacket::Ack { seq }).await
             }
         }
     }
@@ -0,0 +1,140 @@
+use super::UdpPacket;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// Interval between two transmissions of a reliable message which is not acknowledged
+pub const RETRANSMIT_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Number of transmissions of a reliable message before giving up
+pub const MAX_TRANSMISSIONS: u8 = 10;
+
+/// Interval between two keepalives sent to an idle peer, short enough
+/// to hold the mappings of most NAT devices
+pub const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Peers which didn't send anything for this long are forgotten,
+/// and don't receive keepalives anymore
+pub const PEER_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Number of sequence numbers remembered per peer to drop retransmitted duplicates
+const RECEIVED_WINDOW: usize = 1024;
+
+/// A reliable message waiting to be acknowledged
+pub(crate) struct PendingPacket {
+    packet: UdpPacket,
+    transmissions: u8,
+    last_transmission: Instant,
+}
+
+#[derive(Default)]
+struct Peer {
+    last_sent: Option<Instant>,
+    last_received: Option<Instant>,
+    received: BTreeSet<u64>,
+    received_order: VecDeque<u64>,
+}
+
+/// State shared by the sender and the listener of a local socket
+#[derive(Default)]
+pub(crate) struct UdpSocketState {
+    pending: BTreeMap<(SocketAddr, u64), PendingPacket>,
+    peers: BTreeMap<SocketAddr, Peer>,
+}
+
+pub(crate) type SharedUdpSocketState = Arc<Mutex<UdpSocketState>>;
+
+impl UdpSocketState {
+    /// Record a datagram sent to `peer`, and keep it if it must be retransmitted
+    pub(crate) fn sent(&mut self, peer: SocketAddr, packet: &UdpPacket) {
+        let now = Instant::now();
+        if let UdpPacket::Reliable { seq, .. } = packet {
+            let pending = self
+                .pending
+                .entry((peer, *seq))
+                .or_insert_with(|| PendingPacket {
+                    packet: packet.clone(),
+                    transmissions: 0,
+                    last_transmission: now,
+                });
+            pending.transmissions += 1;
+            pending.last_transmission = now;
+        }
+        self.peers.entry(peer).or_default().last_sent = Some(now);
+    }
+
+    /// Record a datagram received from `peer`
+    pub(crate) fn heard(&mut self, peer: SocketAddr) {
+        self.peers.entry(peer).or_default().last_received = Some(Instant::now());
+    }
+
+    /// Stop retransmitting a message acknowledged by `peer`
+    pub(crate) fn acked(&mut self, peer: SocketAddr, seq: u64) {
+        self.pending.remove(&(peer, seq));
+    }
+
+    /// Return true the first time a reliable message is received from `peer`,
+    /// and false for its retransmissions
+    pub(crate) fn first_reception(&mut self, peer: SocketAddr, seq: u64) -> bool {
+        let peer = self.peers.entry(peer).or_default();
+        if !peer.received.insert(seq) {
+            return false;
+        }
+        peer.received_order.push_back(seq);
+        if peer.received_order.len() > RECEIVED_WINDOW {
+            if let Some(oldest) = peer.received_order.pop_front() {
+                peer.received.remove(&oldest);
+            }
+        }
+        true
+    }
+
+    /// Return true if some messages are waiting to be acknowledged
+    pub(crate) fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Return the datagrams to send now: the retransmissions of unacknowledged messages
+    /// and the keepalives of idle peers. Messages sent too many times and silent peers are dropped.
+    pub(crate) fn due(&mut self, now: Instant) -> Vec<(SocketAddr, UdpPacket)> {
+        let mut due = vec![];
+
+        self.pending.retain(|(peer, seq), pending| {
+            if now.saturating_duration_since(pending.last_transmission) < RETRANSMIT_INTERVAL {
+                true
+            } else if pending.transmissions >= MAX_TRANSMISSIONS {
+                warn!(%peer, %seq, "Dropping a message which was never acknowledged");
+                false
+            } else {
+                due.push((*peer, pending.packet.clone()));
+                true
+            }
+        });
+
+        self.peers.retain(|peer, state| {
+            let last_received = match state.last_received {
+                Some(last_received) => last_received,
+                // Only the peers which answered need their NAT mappings to be kept open
+                None => {
+                    return state
+                        .last_sent
+                        .is_some_and(|t| now.saturating_duration_since(t) < PEER_TIMEOUT)
+                }
+            };
+            if now.saturating_duration_since(last_received) >= PEER_TIMEOUT {
+                debug!(%peer, "Forgetting a silent UDP peer");
+                return false;
+            }
+            if state.last_sent.map_or(true, |t| {
+                now.saturating_duration_since(t) >= KEEPALIVE_INTERVAL
+            }) {
+                due.push((*peer, UdpPacket::Keepalive));
+            }
+            true
+        });
+
+        due
+    }
+}
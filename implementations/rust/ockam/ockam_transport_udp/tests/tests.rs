@@ -1,7 +1,7 @@
 use ockam_core::compat::rand::{self, Rng};
 use ockam_core::{route, Address, AllowAll, Result, Routed, Worker};
 use ockam_node::{Context, MessageReceiveOptions, MessageSendReceiveOptions};
-use ockam_transport_udp::{UdpTransport, UDP};
+use ockam_transport_udp::{UdpListenerOptions, UdpTransport, UDP};
 use std::net::SocketAddr;
 use std::time::Duration;
 use tracing::{debug, error, trace};
@@ -187,6 +187,85 @@ async fn send_receive(ctx: &mut Context) -> Result<()> {
     Ok(())
 }
 
+#[ockam_macros::test]
+async fn send_receive_over_a_connection(ctx: &mut Context) -> Result<()> {
+    // Transport
+    let transport = UdpTransport::create(ctx).await?;
+
+    // Listener, on a port chosen by the system
+    ctx.start_worker("echoer", Echoer::new()).await?;
+    let bind_addr = transport.listen("127.0.0.1:0").await?;
+    assert_ne!(bind_addr.port(), 0);
+    assert_eq!(
+        transport
+            .listeners()
+            .iter()
+            .map(|l| l.socket_address())
+            .collect::<Vec<_>>(),
+        vec![bind_addr]
+    );
+
+    // Sender
+    let connection = transport.connect(bind_addr.to_string()).await?;
+    let msg = String::from("Hola");
+    let reply = ctx
+        .send_and_receive_extended::<String>(
+            route![connection.clone(), "echoer"],
+            msg.clone(),
+            MessageSendReceiveOptions::new().with_timeout(TIMEOUT),
+        )
+        .await?
+        .into_body()?;
+    assert_eq!(reply, msg, "Should receive the same message");
+
+    transport.disconnect(&connection).await?;
+    let res = ctx
+        .send(route![connection, "echoer"], String::from("Hola"))
+        .await;
+    assert!(res.is_err(), "Should not send messages after disconnection");
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn listener_with_options_only_delivers_to_consumers(ctx: &mut Context) -> Result<()> {
+    let transport = UdpTransport::create(ctx).await?;
+
+    let options = UdpListenerOptions::new();
+    ctx.flow_controls()
+        .add_consumer("echoer", &options.spawner_flow_control_id());
+    ctx.start_worker("echoer", Echoer::new()).await?;
+    ctx.start_worker("other_echoer", Echoer::new()).await?;
+    let listener = transport
+        .listen_with_options("127.0.0.1:0", options)
+        .await?;
+    assert!(listener.flow_control_id().is_some());
+
+    let connection = transport
+        .connect(listener.socket_address().to_string())
+        .await?;
+    let reply = ctx
+        .send_and_receive_extended::<String>(
+            route![connection.clone(), "echoer"],
+            String::from("Hola"),
+            MessageSendReceiveOptions::new().with_timeout(TIMEOUT),
+        )
+        .await?
+        .into_body()?;
+    assert_eq!(reply, "Hola");
+
+    let res = ctx
+        .send_and_receive_extended::<String>(
+            route![connection, "other_echoer"],
+            String::from("Hola"),
+            MessageSendReceiveOptions::new().with_timeout(Duration::from_secs(1)),
+        )
+        .await;
+    assert!(res.is_err(), "Only consumers should receive messages");
+
+    Ok(())
+}
+
 pub struct Echoer {
     prev_src_addr: Option<String>,
 }
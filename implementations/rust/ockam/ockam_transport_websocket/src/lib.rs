@@ -89,11 +89,15 @@ use std::net::SocketAddr;
 
 use ockam_core::{Result, TransportType};
 use ockam_transport_core::TransportError;
+pub use options::*;
+pub use proxy::HttpProxy;
 pub use transport::*;
 
 use crate::router::{WebSocketRouter, WebSocketRouterHandle};
 
 mod error;
+mod options;
+mod proxy;
 mod router;
 mod transport;
 mod workers;
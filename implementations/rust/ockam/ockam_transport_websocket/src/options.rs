@@ -0,0 +1,123 @@
+use core::time::Duration;
+
+use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
+
+use crate::HttpProxy;
+
+/// Default maximum size, in bytes, of a WebSocket frame sent or received by a connection
+pub const DEFAULT_MAX_FRAME_SIZE: usize = 1024 * 1024;
+
+/// Default interval between two WebSocket pings sent on an idle connection
+pub const DEFAULT_KEEPALIVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Options for an outgoing WebSocket connection
+#[derive(Clone, Debug)]
+pub struct WebSocketConnectionOptions {
+    pub(crate) tls: bool,
+    pub(crate) proxy: Option<HttpProxy>,
+    pub(crate) max_frame_size: usize,
+    pub(crate) keepalive_interval: Option<Duration>,
+}
+
+impl WebSocketConnectionOptions {
+    /// Connection options without TLS, using the HTTP proxy set with `HTTPS_PROXY`, if any
+    pub fn new() -> Self {
+        Self {
+            tls: false,
+            proxy: HttpProxy::from_env(),
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE,
+            keepalive_interval: Some(DEFAULT_KEEPALIVE_INTERVAL),
+        }
+    }
+
+    /// Connect with `wss://` instead of `ws://`
+    pub fn with_tls(mut self, tls: bool) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    /// Tunnel the connection through an HTTP proxy, or connect directly if `None`
+    pub fn with_proxy(mut self, proxy: Option<HttpProxy>) -> Self {
+        self.proxy = proxy;
+        self
+    }
+
+    /// Set the maximum size of the frames sent and received on the connection
+    pub fn with_max_frame_size(mut self, max_frame_size: usize) -> Self {
+        self.max_frame_size = max_frame_size;
+        self
+    }
+
+    /// Set the interval between two pings on an idle connection, or disable them with `None`
+    pub fn with_keepalive_interval(mut self, keepalive_interval: Option<Duration>) -> Self {
+        self.keepalive_interval = keepalive_interval;
+        self
+    }
+
+    /// Return true if the connection uses TLS
+    pub fn tls(&self) -> bool {
+        self.tls
+    }
+
+    /// Return the HTTP proxy used to establish the connection
+    pub fn proxy(&self) -> Option<&HttpProxy> {
+        self.proxy.as_ref()
+    }
+
+    pub(crate) fn websocket_config(&self) -> WebSocketConfig {
+        websocket_config(self.max_frame_size)
+    }
+}
+
+impl Default for WebSocketConnectionOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Options for the connections accepted by a WebSocket listener
+#[derive(Clone, Debug)]
+pub struct WebSocketListenerOptions {
+    pub(crate) max_frame_size: usize,
+    pub(crate) keepalive_interval: Option<Duration>,
+}
+
+impl WebSocketListenerOptions {
+    /// Default listener options
+    pub fn new() -> Self {
+        Self {
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE,
+            keepalive_interval: Some(DEFAULT_KEEPALIVE_INTERVAL),
+        }
+    }
+
+    /// Set the maximum size of the frames sent and received on the accepted connections
+    pub fn with_max_frame_size(mut self, max_frame_size: usize) -> Self {
+        self.max_frame_size = max_frame_size;
+        self
+    }
+
+    /// Set the interval between two pings on an idle connection, or disable them with `None`
+    pub fn with_keepalive_interval(mut self, keepalive_interval: Option<Duration>) -> Self {
+        self.keepalive_interval = keepalive_interval;
+        self
+    }
+
+    pub(crate) fn websocket_config(&self) -> WebSocketConfig {
+        websocket_config(self.max_frame_size)
+    }
+}
+
+impl Default for WebSocketListenerOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Messages are sent as a single frame, so both limits are set to the same value
+fn websocket_config(max_frame_size: usize) -> WebSocketConfig {
+    let mut config = WebSocketConfig::default();
+    config.max_frame_size = Some(max_frame_size);
+    config.max_message_size = Some(max_frame_size);
+    config
+}
@@ -1,5 +1,4 @@
-use core::str::FromStr;
-use std::net::{SocketAddr, ToSocketAddrs};
+use std::net::SocketAddr;
 
 use ockam_core::{async_trait, Address, AsyncTryClone, DenyAll, Result};
 use ockam_node::Context;
@@ -7,7 +6,7 @@ use ockam_transport_core::TransportError;
 
 use crate::router::{WebSocketRouterRequest, WebSocketRouterResponse};
 use crate::workers::{WebSocketListenProcessor, WorkerPair};
-use crate::{parse_socket_addr, WebSocketAddress};
+use crate::{WebSocketConnectionOptions, WebSocketListenerOptions};
 
 /// A handle to connect to a WebSocketRouter.
 ///
@@ -37,16 +36,14 @@ impl WebSocketRouterHandle {
         Self { ctx, api_addr }
     }
 
+    /// Return the address used to send requests to the router.
+    pub(crate) fn api_addr(&self) -> Address {
+        self.api_addr.clone()
+    }
+
     /// Register a new connection worker with this router.
     pub(crate) async fn register(&self, pair: &WorkerPair) -> Result<()> {
-        let mut accepts = vec![pair.peer()];
-
-        accepts.extend(
-            pair.hostnames()
-                .iter()
-                .filter_map(|x| WebSocketAddress::from_str(x).ok())
-                .map(|addr| addr.into()),
-        );
+        let accepts = vec![pair.peer()];
         let self_addr = pair.tx_addr();
         let response = self
             .ctx
@@ -62,49 +59,48 @@ impl WebSocketRouterHandle {
     }
 
     /// Bind an incoming connection listener for this router.
-    pub(crate) async fn bind(&self, addr: impl Into<SocketAddr>) -> Result<SocketAddr> {
+    pub(crate) async fn bind(
+        &self,
+        addr: impl Into<SocketAddr>,
+        options: WebSocketListenerOptions,
+    ) -> Result<SocketAddr> {
         let socket_addr = addr.into();
-        WebSocketListenProcessor::start(&self.ctx, self.async_try_clone().await?, socket_addr).await
+        WebSocketListenProcessor::start(
+            &self.ctx,
+            self.async_try_clone().await?,
+            socket_addr,
+            options,
+        )
+        .await
     }
 
-    /// Return the peer's `SocketAddr` and `hostnames` given a plain `String` address.
-    pub(crate) fn resolve_peer(peer: impl Into<String>) -> Result<(SocketAddr, Vec<String>)> {
-        let peer_str = peer.into();
-        let peer_addr;
-        let hostnames;
-
-        // Try to parse as SocketAddr
-        if let Ok(p) = parse_socket_addr(peer_str.clone()) {
-            peer_addr = p;
-            hostnames = vec![];
-        }
-        // Try to resolve hostname
-        else if let Ok(mut iter) = peer_str.to_socket_addrs() {
-            // FIXME: We only take ipv4 for now
-            if let Some(p) = iter.find(|x| x.is_ipv4()) {
-                peer_addr = p;
-            } else {
-                return Err(TransportError::InvalidAddress)?;
-            }
-
-            hostnames = vec![peer_str];
-        } else {
+    /// Establish an outgoing WS connection on an existing transport.
+    ///
+    /// `peer` is a `host:port` address. Return the address of the worker sending
+    /// messages to the peer.
+    pub(crate) async fn connect<S: AsRef<str>>(
+        &self,
+        peer: S,
+        options: WebSocketConnectionOptions,
+    ) -> Result<Address> {
+        let peer = peer.as_ref();
+        if peer.rsplit_once(':').is_none() {
             return Err(TransportError::InvalidAddress)?;
         }
 
-        Ok((peer_addr, hostnames))
-    }
-
-    /// Establish an outgoing WS connection on an existing transport.
-    pub(crate) async fn connect<S: AsRef<str>>(&self, peer: S) -> Result<()> {
-        // Get peer address and connect to it.
-        let (peer_addr, hostnames) = Self::resolve_peer(peer.as_ref())?;
-
         // Create a new `WorkerPair` for the given peer, initializing a new pair
         // of sender worker and receiver processor.
-        let pair = WorkerPair::from_client(&self.ctx, peer_addr, hostnames).await?;
+        let pair =
+            WorkerPair::from_client(&self.ctx, peer.to_string(), options, self.api_addr()).await?;
 
         // Handle node's register request.
-        self.register(&pair).await
+        self.register(&pair).await?;
+        Ok(pair.tx_addr())
+    }
+
+    /// Stop the worker sending messages on a connection, which also stops the
+    /// receiving half and unregisters the connection.
+    pub(crate) async fn stop_sender(&self, sender_addr: Address) -> Result<()> {
+        self.ctx.stop_worker(sender_addr).await
     }
 }
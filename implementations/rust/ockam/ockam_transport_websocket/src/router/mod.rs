@@ -1,4 +1,3 @@
-use core::str::FromStr;
 use std::collections::BTreeMap;
 use std::ops::Deref;
 use std::sync::Arc;
@@ -12,7 +11,7 @@ use ockam_node::{Context, WorkerBuilder};
 use ockam_transport_core::TransportError;
 
 use crate::workers::WorkerPair;
-use crate::{WebSocketAddress, WS};
+use crate::{WebSocketConnectionOptions, WS};
 use serde::{Deserialize, Serialize};
 
 mod handle;
@@ -26,6 +25,11 @@ pub enum WebSocketRouterRequest {
         /// The clients own worker bus address.
         self_addr: Address,
     },
+    /// Unregister a client whose connection was closed.
+    Unregister {
+        /// The clients own worker bus address.
+        self_addr: Address,
+    },
 }
 
 #[derive(Serialize, Deserialize, Debug, Message)]
@@ -141,6 +145,10 @@ impl Worker for WebSocketRouter {
                     )
                     .await?;
                 }
+                WebSocketRouterRequest::Unregister { self_addr } => {
+                    trace!("handle_message unregister: {:?}", self_addr);
+                    self.handle_unregister(self_addr);
+                }
             };
         } else {
             return Err(TransportError::InvalidAddress)?;
@@ -196,39 +204,38 @@ impl WebSocketRouter {
             return Err(TransportError::InvalidAddress)?;
         }
 
-        // Do not connect twice.
-        for accept in &accepts {
-            if self.map.contains_key(accept) {
-                return Err(TransportError::AlreadyConnected)?;
-            }
-        }
-
-        // Add a new entry for each hostname/address pair.
+        // Add a new entry for each hostname/address pair, keeping the
+        // connections which are already registered.
         for accept in accepts {
-            self.map.insert(accept.clone(), self_addr.clone());
+            if self.map.contains_key(&accept) {
+                debug!("WS connection to {} is already registered", accept);
+                continue;
+            }
+            self.map.insert(accept, self_addr.clone());
         }
 
         Ok(())
     }
 
-    async fn connect(&mut self, peer: String) -> Result<Address> {
-        // Get peer address and connect to it.
-        let (peer_addr, hostnames) = WebSocketRouterHandle::resolve_peer(peer)?;
+    fn handle_unregister(&mut self, self_addr: Address) {
+        self.map.retain(|_, addr| addr != &self_addr);
+    }
 
+    async fn connect(&mut self, peer: String) -> Result<Address> {
         // Create a new `WorkerPair` for the given peer, initializing a new pair
         // of sender worker and receiver processor.
-        let pair = WorkerPair::from_client(&self.ctx, peer_addr, hostnames).await?;
+        let pair = WorkerPair::from_client(
+            &self.ctx,
+            peer,
+            WebSocketConnectionOptions::new(),
+            self.api_addr.clone(),
+        )
+        .await?;
 
         // Handle node's register request.
-        let mut accepts = vec![pair.peer()];
-        accepts.extend(
-            pair.hostnames()
-                .iter()
-                .filter_map(|x| WebSocketAddress::from_str(x).ok())
-                .map(|addr| addr.into()),
-        );
         let self_addr = pair.tx_addr();
-        self.handle_register(accepts, self_addr.clone()).await?;
+        self.handle_register(vec![pair.peer()], self_addr.clone())
+            .await?;
 
         Ok(self_addr)
     }
@@ -5,7 +5,10 @@ use std::str::FromStr;
 use ockam_core::{async_trait, Address, Result};
 use ockam_node::{Context, HasContext};
 
-use crate::{parse_socket_addr, WebSocketRouter, WebSocketRouterHandle, WS};
+use crate::{
+    parse_socket_addr, WebSocketConnectionOptions, WebSocketListenerOptions, WebSocketRouter,
+    WebSocketRouterHandle, WS,
+};
 
 /// High level management interface for WebSocket transports.
 ///
@@ -67,6 +70,8 @@ impl WebSocketTransport {
 
     /// Establish an outgoing WebSocket connection on an existing transport.
     ///
+    /// Return the address of the worker sending messages on the connection.
+    ///
     /// ```rust
     /// use ockam_transport_websocket::WebSocketTransport;
     /// # use ockam_node::Context;
@@ -77,8 +82,37 @@ impl WebSocketTransport {
     /// ws.connect("127.0.0.1:5000").await?; // and connect to port 5000
     /// # Ok(()) }
     /// ```
-    pub async fn connect<S: AsRef<str>>(&self, peer: S) -> Result<()> {
-        self.router_handle.connect(peer).await
+    pub async fn connect<S: AsRef<str>>(&self, peer: S) -> Result<Address> {
+        self.connect_with_options(peer, WebSocketConnectionOptions::new())
+            .await
+    }
+
+    /// Establish an outgoing WebSocket connection to a `host:port` peer, with TLS
+    /// or through an HTTP proxy for example.
+    ///
+    /// ```rust
+    /// use ockam_transport_websocket::{HttpProxy, WebSocketConnectionOptions, WebSocketTransport};
+    /// # use ockam_node::Context;
+    /// # use ockam_core::Result;
+    /// # async fn test(ctx: Context) -> Result<()> {
+    /// let ws = WebSocketTransport::create(&ctx).await?;
+    /// let options = WebSocketConnectionOptions::new()
+    ///     .with_tls(true)
+    ///     .with_proxy(Some(HttpProxy::parse("http://proxy.internal:3128")?));
+    /// ws.connect_with_options("relay.example.com:443", options).await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn connect_with_options<S: AsRef<str>>(
+        &self,
+        peer: S,
+        options: WebSocketConnectionOptions,
+    ) -> Result<Address> {
+        self.router_handle.connect(peer, options).await
+    }
+
+    /// Close a connection created with [`connect`](crate::WebSocketTransport::connect)
+    pub async fn disconnect(&self, address: impl Into<Address>) -> Result<()> {
+        self.router_handle.stop_sender(address.into()).await
     }
 
     /// Start listening to incoming connections on an existing transport.
@@ -97,8 +131,24 @@ impl WebSocketTransport {
     /// ws.listen("127.0.0.1:8000").await?;
     /// # Ok(()) }
     pub async fn listen<S: AsRef<str>>(&self, bind_addr: S) -> Result<SocketAddr> {
+        self.listen_with_options(bind_addr, WebSocketListenerOptions::new())
+            .await
+    }
+
+    /// Start listening to incoming connections, with a custom frame size limit for example.
+    pub async fn listen_with_options<S: AsRef<str>>(
+        &self,
+        bind_addr: S,
+        options: WebSocketListenerOptions,
+    ) -> Result<SocketAddr> {
         let bind_addr = parse_socket_addr(bind_addr)?;
-        self.router_handle.bind(bind_addr).await
+        self.router_handle.bind(bind_addr, options).await
+    }
+}
+
+impl fmt::Debug for WebSocketTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebSocketTransport").finish()
     }
 }
 
@@ -6,7 +6,7 @@ use ockam_core::{async_trait, Address, AllowAll, Processor, Result};
 use ockam_node::Context;
 use ockam_transport_core::TransportError;
 
-use crate::{error::WebSocketError, workers::WorkerPair, WebSocketRouterHandle};
+use crate::{workers::WorkerPair, WebSocketListenerOptions, WebSocketRouterHandle};
 
 /// A worker that runs in the background as a `Processor` waiting for incoming
 /// clients' connections.
@@ -16,6 +16,7 @@ use crate::{error::WebSocketError, workers::WorkerPair, WebSocketRouterHandle};
 pub(crate) struct WebSocketListenProcessor {
     inner: TcpListener,
     router_handle: WebSocketRouterHandle,
+    options: WebSocketListenerOptions,
 }
 
 impl WebSocketListenProcessor {
@@ -24,6 +25,7 @@ impl WebSocketListenProcessor {
         ctx: &Context,
         router_handle: WebSocketRouterHandle,
         addr: SocketAddr,
+        options: WebSocketListenerOptions,
     ) -> Result<SocketAddr> {
         debug!("Binding WebSocketListener to {}", addr);
         let inner = TcpListener::bind(addr)
@@ -33,6 +35,7 @@ impl WebSocketListenProcessor {
         let processor = Self {
             inner,
             router_handle,
+            options,
         };
         let waddr = Address::random_tagged("WebSocketListenProcessor");
         ctx.start_processor_with_access_control(
@@ -57,15 +60,32 @@ impl Processor for WebSocketListenProcessor {
 
         // Wait for an incoming connection
         let (tcp_stream, peer) = self.inner.accept().await.map_err(TransportError::from)?;
-        let ws_stream = tokio_tungstenite::accept_async(tcp_stream)
-            .await
-            .map_err(WebSocketError::from)?;
+        let ws_stream = match tokio_tungstenite::accept_async_with_config(
+            tcp_stream,
+            Some(self.options.websocket_config()),
+        )
+        .await
+        {
+            Ok(ws_stream) => ws_stream,
+            Err(e) => {
+                // A failed handshake must not stop the listener
+                warn!("WebSocket handshake with {} failed: {}", peer, e);
+                return Ok(true);
+            }
+        };
         debug!("TCP connection accepted");
 
         // Spawn a connection worker for it
-        let pair = WorkerPair::from_server(ctx, ws_stream, peer, vec![]).await?;
+        let pair = WorkerPair::from_server(
+            ctx,
+            ws_stream,
+            peer,
+            &self.options,
+            self.router_handle.api_addr(),
+        )
+        .await?;
 
-        // Register the connection with the local TcpRouter
+        // Register the connection with the local WebSocketRouter
         self.router_handle.register(&pair).await?;
         debug!("TCP connection registered");
 
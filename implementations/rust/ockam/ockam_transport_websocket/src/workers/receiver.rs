@@ -1,10 +1,8 @@
-use std::net::SocketAddr;
-
 use futures_util::stream::SplitStream;
 use futures_util::StreamExt;
+use tokio_tungstenite::tungstenite::protocol::Message as WebSocketMessage;
 use tokio_tungstenite::WebSocketStream;
 
-use crate::WebSocketAddress;
 use ockam_core::{
     async_trait, Address, Decodable, LocalMessage, Processor, Result, TransportMessage,
 };
@@ -23,19 +21,33 @@ where
     S: AsyncStream,
 {
     ws_stream: SplitStream<WebSocketStream<S>>,
-    peer_addr: Address,
+    peer: String,
+    sender_addr: Address,
 }
 
 impl<S> WebSocketRecvProcessor<S>
 where
     S: AsyncStream,
 {
-    pub(crate) fn new(ws_stream: SplitStream<WebSocketStream<S>>, peer: SocketAddr) -> Self {
+    /// Create a processor for the messages sent by `peer`.
+    ///
+    /// Replies are routed to `sender_addr`, the address of the sending half of the connection.
+    pub(crate) fn new(
+        ws_stream: SplitStream<WebSocketStream<S>>,
+        peer: String,
+        sender_addr: Address,
+    ) -> Self {
         Self {
             ws_stream,
-            peer_addr: WebSocketAddress::from(peer).into(),
+            peer,
+            sender_addr,
         }
     }
+
+    /// Stop the sending half of the connection, which unregisters it from the router
+    async fn close(&self, ctx: &Context) {
+        let _ = ctx.stop_worker(self.sender_addr.clone()).await;
+    }
 }
 
 #[async_trait]
@@ -60,22 +72,40 @@ where
                 Err(_e) => {
                     info!(
                         "Connection to peer '{}' was closed; dropping stream",
-                        self.peer_addr
+                        self.peer
                     );
+                    self.close(ctx).await;
                     return Ok(false);
                 }
             },
             None => {
                 info!(
                     "Stream connected to peer '{}' is exhausted; dropping stream",
-                    self.peer_addr
+                    self.peer
                 );
+                self.close(ctx).await;
                 return Ok(false);
             }
         };
 
         // Extract message payload
-        let encoded_msg = ws_msg.into_data();
+        let encoded_msg = match ws_msg {
+            WebSocketMessage::Binary(data) => data,
+            // Pongs are sent back automatically when the next frame is written
+            WebSocketMessage::Ping(_) | WebSocketMessage::Pong(_) | WebSocketMessage::Frame(_) => {
+                trace!("Got keepalive message from: {}", self.peer);
+                return Ok(true);
+            }
+            WebSocketMessage::Text(_) => {
+                warn!("Ignoring a text message from peer '{}'", self.peer);
+                return Ok(true);
+            }
+            WebSocketMessage::Close(_) => {
+                info!("Peer '{}' closed the connection", self.peer);
+                self.close(ctx).await;
+                return Ok(false);
+            }
+        };
 
         // Deserialize the message
         let msg =
@@ -84,12 +114,13 @@ where
 
         // Heartbeat message
         if !msg.has_next_on_onward_route() {
-            trace!("Got heartbeat message from: {}", self.peer_addr);
+            trace!("Got heartbeat message from: {}", self.peer);
+            return Ok(true);
         }
 
-        // Insert the peer address into the return route so that
-        // reply routing can be properly resolved
-        msg = msg.push_front_return_route(&self.peer_addr);
+        // Insert the address of the sending half of the connection into the
+        // return route so that replies go back through the same connection
+        msg = msg.push_front_return_route(&self.sender_addr);
 
         // Some verbose logging we may want to remove
         trace!("Message onward route: {}", msg.onward_route_ref());
@@ -4,12 +4,14 @@ use std::time::Duration;
 
 use futures_util::stream::{SplitSink, SplitStream};
 use futures_util::{SinkExt, StreamExt};
+use tokio::net::TcpStream;
 use tokio_tungstenite::tungstenite::protocol::Message as WebSocketMessage;
 
 use crate::error::WebSocketError;
+use crate::router::WebSocketRouterRequest;
 use ockam_core::{
-    async_trait, route, Address, AllowAll, Any, Decodable, Encodable, LocalMessage, Mailbox,
-    Mailboxes, Result, Routed, TransportMessage, Worker,
+    async_trait, Address, AllowAll, Any, Decodable, Encodable, LocalMessage, Mailbox, Mailboxes,
+    Result, Routed, Worker,
 };
 use ockam_node::{Context, DelayedEvent, WorkerBuilder};
 use ockam_transport_core::TransportError;
@@ -17,20 +19,16 @@ use ockam_transport_core::TransportError;
 use crate::workers::{
     AsyncStream, TcpClientStream, TcpServerStream, WebSocketRecvProcessor, WebSocketStream,
 };
-use crate::WebSocketAddress;
+use crate::{WebSocketAddress, WebSocketConnectionOptions, WebSocketListenerOptions, WS};
 
 /// Transmit and receive peers of a WebSocket connection.
 #[derive(Debug)]
 pub(crate) struct WorkerPair {
-    hostnames: Vec<String>,
     peer: Address,
     tx_addr: Address,
 }
 
 impl WorkerPair {
-    pub(crate) fn hostnames(&self) -> &[String] {
-        &self.hostnames
-    }
     pub(crate) fn peer(&self) -> Address {
         self.peer.clone()
     }
@@ -42,18 +40,23 @@ impl WorkerPair {
     /// returns a `WorkerPair` instance that will be registered by the `WebSocketRouter`.
     ///
     /// The WebSocket stream is created when the `WebSocketSendWorker` is initialized.
+    /// `peer` is the `host:port` address of the other node, which is only resolved
+    /// by the HTTP proxy when the connection goes through a proxy.
     pub(crate) async fn from_client(
         ctx: &Context,
-        peer: SocketAddr,
-        hostnames: Vec<String>,
+        peer: String,
+        options: WebSocketConnectionOptions,
+        router_api_addr: Address,
     ) -> Result<WorkerPair> {
         trace!("Creating new WS worker pair");
 
         let internal_addr = Address::random_tagged("WebSocketSender.internal.from_client");
         let sender = WebSocketSendWorker::<TcpClientStream>::new(
-            peer,
+            peer.clone(),
+            options,
             internal_addr.clone(),
             DelayedEvent::create(ctx, internal_addr.clone(), vec![]).await?,
+            router_api_addr,
         );
 
         let tx_addr = Address::random_tagged("WebSocketSender.tx_addr.from_client");
@@ -77,8 +80,7 @@ impl WorkerPair {
 
         // Return a handle to the worker pair
         Ok(WorkerPair {
-            hostnames,
-            peer: WebSocketAddress::from(peer).into(),
+            peer: Address::new(WS, peer),
             tx_addr,
         })
     }
@@ -89,7 +91,8 @@ impl WorkerPair {
         ctx: &Context,
         stream: WebSocketStream<TcpServerStream>,
         peer: SocketAddr,
-        hostnames: Vec<String>,
+        options: &WebSocketListenerOptions,
+        router_api_addr: Address,
     ) -> Result<WorkerPair> {
         trace!("Creating new WS worker pair");
 
@@ -97,8 +100,10 @@ impl WorkerPair {
         let sender = WebSocketSendWorker::<TcpServerStream>::new(
             stream,
             peer,
+            options,
             internal_addr.clone(),
             DelayedEvent::create(ctx, internal_addr.clone(), vec![]).await?,
+            router_api_addr,
         );
 
         let tx_addr = Address::random_tagged("WebSocketSender.tx_addr.from_server");
@@ -121,7 +126,6 @@ impl WorkerPair {
 
         // Return a handle to the worker pair
         Ok(WorkerPair {
-            hostnames,
             peer: WebSocketAddress::from(peer).into(),
             tx_addr,
         })
@@ -133,16 +137,23 @@ impl WorkerPair {
 /// This half of the worker is created when spawning a new connection
 /// worker pair, and listens for messages from the node message system
 /// to dispatch to a remote peer.
+///
+/// When the connection is idle, a ping is sent every keepalive interval so that
+/// proxies and load balancers don't close it.
 pub(crate) struct WebSocketSendWorker<S>
 where
     S: AsyncStream,
 {
     ws_stream: Option<SplitStream<WebSocketStream<S>>>,
     ws_sink: Option<SplitSink<WebSocketStream<S>, WebSocketMessage>>,
-    peer: SocketAddr,
+    peer: String,
     internal_addr: Address,
+    rx_addr: Option<Address>,
+    router_api_addr: Address,
     heartbeat: DelayedEvent<Vec<u8>>,
     heartbeat_interval: Option<Duration>,
+    max_frame_size: usize,
+    connection_options: Option<WebSocketConnectionOptions>,
 }
 
 impl<S> WebSocketSendWorker<S>
@@ -152,7 +163,7 @@ where
     async fn handle_initialize(&mut self, ctx: &mut Context) -> Result<()> {
         if let Some(ws_stream) = self.ws_stream.take() {
             let rx_addr = Address::random_tagged("WebSocketSendWorker.rx_addr");
-            let receiver = WebSocketRecvProcessor::new(ws_stream, self.peer);
+            let receiver = WebSocketRecvProcessor::new(ws_stream, self.peer.clone(), ctx.address());
             ctx.start_processor_with_access_control(
                 rx_addr.clone(),
                 receiver,
@@ -160,6 +171,7 @@ where
                 AllowAll, // FIXME: @ac
             )
             .await?;
+            self.rx_addr = Some(rx_addr);
         } else {
             return Err(TransportError::GenericIo)?;
         }
@@ -169,6 +181,28 @@ where
         Ok(())
     }
 
+    /// Stop the receiving half of the connection and let the router know that the
+    /// connection can't be used anymore
+    async fn handle_shutdown(&mut self, ctx: &mut Context) -> Result<()> {
+        self.heartbeat.cancel();
+        if let Some(rx_addr) = self.rx_addr.take() {
+            let _ = ctx.stop_processor(rx_addr).await;
+        }
+        if let Some(mut ws_sink) = self.ws_sink.take() {
+            let _ = ws_sink.close().await;
+        }
+        // The router may already be stopped when the node shuts down
+        let _ = ctx
+            .send(
+                self.router_api_addr.clone(),
+                WebSocketRouterRequest::Unregister {
+                    self_addr: ctx.address(),
+                },
+            )
+            .await;
+        Ok(())
+    }
+
     async fn schedule_heartbeat(&mut self) -> Result<()> {
         let heartbeat_interval = match &self.heartbeat_interval {
             Some(hi) => *hi,
@@ -181,29 +215,9 @@ where
     /// Receive messages from the `WebSocketRouter` to send
     /// across the `WebSocketStream` to the next remote peer.
     async fn handle_msg(&mut self, ctx: &mut Context, msg: Routed<Any>) -> Result<()> {
-        self.heartbeat.cancel();
-
-        let ws_sink = if let Some(ws_sink) = &mut self.ws_sink {
-            ws_sink
-        } else {
-            return Err(TransportError::PeerNotFound)?;
-        };
-
         let recipient = msg.msg_addr();
-        if recipient == self.internal_addr {
-            let msg = TransportMessage::latest(route![], route![], vec![]);
-            // Sending empty heartbeat
-            if ws_sink
-                .send(WebSocketMessage::from(msg.encode()?))
-                .await
-                .is_err()
-            {
-                warn!("Failed to send heartbeat to peer {}", self.peer);
-                ctx.stop_worker(ctx.address()).await?;
-
-                return Ok(());
-            }
-            debug!("Sent heartbeat to peer {}", self.peer);
+        let ws_msg = if recipient == self.internal_addr {
+            WebSocketMessage::Ping(vec![])
         } else {
             let mut msg = LocalMessage::decode(msg.payload())?;
 
@@ -211,12 +225,36 @@ where
             // knows what to do with the incoming message
             msg = msg.pop_front_onward_route()?;
 
-            let msg = WebSocketMessage::from(msg.into_transport_message().encode()?);
-            if ws_sink.send(msg).await.is_err() {
-                warn!("Failed to send message to peer {}", self.peer);
-                ctx.stop_worker(ctx.address()).await?;
-                return Ok(());
+            let encoded = msg.into_transport_message().encode()?;
+            if encoded.len() > self.max_frame_size {
+                warn!(
+                    "Dropping a message of {} bytes for peer {}: the maximum frame size is {} bytes",
+                    encoded.len(),
+                    self.peer,
+                    self.max_frame_size
+                );
+                return Err(TransportError::Capacity)?;
             }
+            WebSocketMessage::from(encoded)
+        };
+
+        self.heartbeat.cancel();
+
+        let ws_sink = if let Some(ws_sink) = &mut self.ws_sink {
+            ws_sink
+        } else {
+            return Err(TransportError::PeerNotFound)?;
+        };
+
+        let is_ping = ws_msg.is_ping();
+        if ws_sink.send(ws_msg).await.is_err() {
+            warn!("Failed to send message to peer {}", self.peer);
+            ctx.stop_worker(ctx.address()).await?;
+            return Ok(());
+        }
+        if is_ping {
+            debug!("Sent ping to peer {}", self.peer);
+        } else {
             debug!("Sent message to peer {}", self.peer);
         }
 
@@ -230,43 +268,75 @@ impl WebSocketSendWorker<TcpServerStream> {
     fn new(
         stream: WebSocketStream<TcpServerStream>,
         peer: SocketAddr,
+        options: &WebSocketListenerOptions,
         internal_addr: Address,
         heartbeat: DelayedEvent<Vec<u8>>,
+        router_api_addr: Address,
     ) -> Self {
         let (ws_sink, ws_stream) = stream.split();
         Self {
             ws_sink: Some(ws_sink),
             ws_stream: Some(ws_stream),
-            peer,
+            peer: peer.to_string(),
             internal_addr,
+            rx_addr: None,
+            router_api_addr,
             heartbeat,
-            heartbeat_interval: None,
+            heartbeat_interval: options.keepalive_interval,
+            max_frame_size: options.max_frame_size,
+            connection_options: None,
         }
     }
 }
 
 impl WebSocketSendWorker<TcpClientStream> {
-    fn new(peer: SocketAddr, internal_addr: Address, heartbeat: DelayedEvent<Vec<u8>>) -> Self {
+    fn new(
+        peer: String,
+        options: WebSocketConnectionOptions,
+        internal_addr: Address,
+        heartbeat: DelayedEvent<Vec<u8>>,
+        router_api_addr: Address,
+    ) -> Self {
         Self {
             ws_stream: None,
             ws_sink: None,
             peer,
             internal_addr,
+            rx_addr: None,
+            router_api_addr,
             heartbeat,
-            heartbeat_interval: None,
+            heartbeat_interval: options.keepalive_interval,
+            max_frame_size: options.max_frame_size,
+            connection_options: Some(options),
         }
     }
 
     async fn initialize_stream(&mut self) -> Result<()> {
-        if self.ws_stream.is_none() {
-            let peer = WebSocketAddress::from(self.peer).to_string();
-            let (stream, _) = tokio_tungstenite::connect_async(peer)
-                .await
-                .map_err(WebSocketError::from)?;
-            let (ws_sink, ws_stream) = stream.split();
-            self.ws_sink = Some(ws_sink);
-            self.ws_stream = Some(ws_stream);
+        if self.ws_stream.is_some() {
+            return Ok(());
         }
+        let options = self.connection_options.take().unwrap_or_default();
+
+        let tcp_stream = match options.proxy().filter(|p| !p.is_bypassed(&self.peer)) {
+            Some(proxy) => proxy.connect(&self.peer).await?,
+            None => TcpStream::connect(&self.peer)
+                .await
+                .map_err(TransportError::from)?,
+        };
+
+        let scheme = if options.tls() { "wss" } else { "ws" };
+        let url = format!("{scheme}://{}", self.peer);
+        let (stream, _) = tokio_tungstenite::client_async_tls_with_config(
+            url,
+            tcp_stream,
+            Some(options.websocket_config()),
+            None,
+        )
+        .await
+        .map_err(WebSocketError::from)?;
+        let (ws_sink, ws_stream) = stream.split();
+        self.ws_sink = Some(ws_sink);
+        self.ws_stream = Some(ws_stream);
         Ok(())
     }
 }
@@ -281,6 +351,10 @@ impl Worker for WebSocketSendWorker<TcpServerStream> {
         Ok(())
     }
 
+    async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        self.handle_shutdown(ctx).await
+    }
+
     async fn handle_message(
         &mut self,
         ctx: &mut Context,
@@ -301,6 +375,10 @@ impl Worker for WebSocketSendWorker<TcpClientStream> {
         Ok(())
     }
 
+    async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        self.handle_shutdown(ctx).await
+    }
+
     async fn handle_message(
         &mut self,
         ctx: &mut Context,
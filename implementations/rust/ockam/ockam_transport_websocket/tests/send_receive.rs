@@ -1,7 +1,7 @@
 use ockam_core::compat::rand::{self, Rng};
 use ockam_core::{route, Result, Routed, Worker};
 use ockam_node::Context;
-use ockam_transport_websocket::{WebSocketTransport, WS};
+use ockam_transport_websocket::{WebSocketConnectionOptions, WebSocketTransport, WS};
 
 #[ignore]
 #[ockam_macros::test]
@@ -25,6 +25,31 @@ async fn send_receive(ctx: &mut Context) -> Result<()> {
     Ok(())
 }
 
+#[ockam_macros::test]
+async fn send_receive_over_an_explicit_connection(ctx: &mut Context) -> Result<()> {
+    let transport = WebSocketTransport::create(ctx).await?;
+    let listener_address = transport.listen("127.0.0.1:0").await?;
+    ctx.start_worker("echoer", Echoer).await?;
+
+    let options = WebSocketConnectionOptions::new().with_proxy(None);
+    let connection = transport
+        .connect_with_options(listener_address.to_string(), options)
+        .await?;
+
+    let msg = "Hello over WebSocket".to_string();
+    let reply = ctx
+        .send_and_receive::<String>(route![connection.clone(), "echoer"], msg.clone())
+        .await?;
+    assert_eq!(reply, msg, "Should receive the same message");
+
+    transport.disconnect(connection.clone()).await?;
+    let res = ctx
+        .send_and_receive::<String>(route![connection, "echoer"], msg)
+        .await;
+    assert!(res.is_err(), "The connection should be closed");
+    Ok(())
+}
+
 pub struct Echoer;
 
 #[ockam_core::worker]
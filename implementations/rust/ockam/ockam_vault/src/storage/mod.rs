@@ -1,7 +1,11 @@
+#[cfg(feature = "storage")]
+mod secrets_encryption;
 mod secrets_repository;
 #[cfg(feature = "storage")]
 mod secrets_repository_sql;
 
+#[cfg(feature = "storage")]
+pub use secrets_encryption::*;
 pub use secrets_repository::*;
 #[cfg(feature = "storage")]
 pub use secrets_repository_sql::*;
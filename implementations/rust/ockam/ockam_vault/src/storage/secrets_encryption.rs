@@ -0,0 +1,74 @@
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use ockam_core::compat::rand::{thread_rng, RngCore};
+use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::Result;
+
+/// Length of the nonce prepended to each encrypted secret
+const NONCE_LENGTH: usize = 12;
+
+/// Encryption of the secrets persisted by a [`super::SecretsSqlxDatabase`].
+///
+/// Each secret is encrypted with AES-256-GCM and a random nonce. The nonce is stored in front
+/// of the ciphertext.
+#[derive(Clone)]
+pub struct SecretsEncryption {
+    cipher: Aes256Gcm,
+}
+
+impl SecretsEncryption {
+    /// Create an encryption using a 256 bits key
+    pub fn new(key: [u8; 32]) -> Self {
+        Self {
+            cipher: Aes256Gcm::new(&key.into()),
+        }
+    }
+
+    /// Encrypt some data
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let mut nonce = [0u8; NONCE_LENGTH];
+        thread_rng().fill_bytes(&mut nonce);
+        let ciphertext = self
+            .cipher
+            .encrypt(Nonce::from_slice(&nonce), plaintext)
+            .map_err(|_| Self::error("cannot encrypt a secret"))?;
+
+        let mut result = nonce.to_vec();
+        result.extend(ciphertext);
+        Ok(result)
+    }
+
+    /// Decrypt some data encrypted with the same key
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>> {
+        if data.len() < NONCE_LENGTH {
+            return Err(Self::error("an encrypted secret is too short"));
+        }
+        let (nonce, ciphertext) = data.split_at(NONCE_LENGTH);
+        self.cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| Self::error("cannot decrypt a secret, the encryption key is invalid"))
+    }
+
+    fn error(message: &str) -> ockam_core::Error {
+        ockam_core::Error::new(Origin::Vault, Kind::Invalid, message)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_decrypt() -> Result<()> {
+        let encryption = SecretsEncryption::new([1; 32]);
+        let encrypted = encryption.encrypt(&[2; 32])?;
+        assert_ne!(&encrypted[NONCE_LENGTH..NONCE_LENGTH + 32], &[2; 32]);
+        assert_eq!(encryption.decrypt(&encrypted)?, [2; 32]);
+
+        // a different key can not decrypt the secret
+        let other = SecretsEncryption::new([3; 32]);
+        assert!(other.decrypt(&encrypted).is_err());
+        Ok(())
+    }
+}
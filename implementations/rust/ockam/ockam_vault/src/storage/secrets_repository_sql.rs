@@ -8,6 +8,7 @@ use ockam_core::Result;
 use ockam_node::database::{FromSqlxError, SqlxDatabase, SqlxType, ToSqlxType, ToVoid};
 
 use crate::storage::secrets_repository::SecretsRepository;
+use crate::storage::SecretsEncryption;
 
 use crate::{
     ECDSASHA256CurveP256SecretKey, EdDSACurve25519SecretKey, HandleToSecret, SigningSecret,
@@ -15,22 +16,93 @@ use crate::{
 };
 
 /// Implementation of a secrets repository using a SQL database
+/// The secrets can optionally be encrypted before being stored
 #[derive(Clone)]
 pub struct SecretsSqlxDatabase {
     database: SqlxDatabase,
+    encryption: Option<SecretsEncryption>,
 }
 
 impl SecretsSqlxDatabase {
     /// Create a new database for secrets
     pub fn new(database: SqlxDatabase) -> Self {
         debug!("create a repository for secrets");
-        Self { database }
+        Self {
+            database,
+            encryption: None,
+        }
     }
 
     /// Create a new in-memory database for secrets
     pub async fn create() -> Result<Self> {
         Ok(Self::new(SqlxDatabase::in_memory("secrets").await?))
     }
+
+    /// Encrypt the secrets stored in the database with a specific encryption
+    pub fn with_encryption(self, encryption: SecretsEncryption) -> Self {
+        Self {
+            encryption: Some(encryption),
+            ..self
+        }
+    }
+
+    /// Encrypt all the secrets currently stored in plain text in the database.
+    /// The secrets are rewritten in a single transaction.
+    pub async fn encrypt_secrets(&self, encryption: &SecretsEncryption) -> Result<()> {
+        let mut transaction = self.database.begin().await.into_core()?;
+
+        let query1 = query_as("SELECT handle, secret_type, secret FROM signing_secret");
+        let rows: Vec<SigningSecretRow> = query1.fetch_all(&mut *transaction).await.into_core()?;
+        for row in rows {
+            let query = query("UPDATE signing_secret SET secret = ? WHERE handle = ?")
+                .bind(encryption.encrypt(&row.secret)?.to_sql())
+                .bind(row.handle.to_sql());
+            query.execute(&mut *transaction).await.void()?;
+        }
+
+        let query2 = query_as("SELECT handle, secret FROM x25519_secret");
+        let rows: Vec<X25519SecretRow> = query2.fetch_all(&mut *transaction).await.into_core()?;
+        for row in rows {
+            let query = query("UPDATE x25519_secret SET secret = ? WHERE handle = ?")
+                .bind(encryption.encrypt(&row.secret)?.to_sql())
+                .bind(row.handle.to_sql());
+            query.execute(&mut *transaction).await.void()?;
+        }
+
+        transaction.commit().await.void()
+    }
+
+    /// Return the value to store for a secret
+    fn encode_secret(&self, secret: &[u8; 32]) -> Result<SqlxType> {
+        match &self.encryption {
+            Some(encryption) => Ok(encryption.encrypt(secret)?.to_sql()),
+            None => Ok(secret.to_sql()),
+        }
+    }
+
+    /// Return the secret for a stored value
+    fn decode_secret(&self, secret: &[u8]) -> Result<Vec<u8>> {
+        match &self.encryption {
+            Some(encryption) => encryption.decrypt(secret),
+            None => Ok(secret.to_vec()),
+        }
+    }
+
+    fn decode_signing_secret(&self, row: SigningSecretRow) -> Result<SigningSecret> {
+        SigningSecretRow {
+            secret: self.decode_secret(&row.secret)?,
+            ..row
+        }
+        .signing_secret()
+    }
+
+    fn decode_x25519_secret(&self, row: X25519SecretRow) -> Result<X25519SecretKey> {
+        X25519SecretRow {
+            secret: self.decode_secret(&row.secret)?,
+            ..row
+        }
+        .x25519_secret()
+    }
 }
 
 const ED_DSA_CURVE_25519: &str = "EdDSACurve25519";
@@ -51,7 +123,7 @@ impl SecretsRepository for SecretsSqlxDatabase {
         let query = query("INSERT OR REPLACE INTO signing_secret VALUES (?, ?, ?)")
             .bind(handle.to_sql())
             .bind(secret_type.to_sql())
-            .bind(secret.to_sql());
+            .bind(self.encode_secret(signing_secret_key(&secret))?);
         query.execute(&*self.database.pool).await.void()
     }
 
@@ -65,7 +137,7 @@ impl SecretsRepository for SecretsSqlxDatabase {
                 .bind(handle.to_sql());
         let row: Option<SigningSecretRow> =
             query1.fetch_optional(&mut *transaction).await.into_core()?;
-        let secret = row.map(|r| r.signing_secret()).transpose()?;
+        let secret = row.map(|r| self.decode_signing_secret(r)).transpose()?;
 
         let result = if let Some(secret) = secret {
             let query = query("DELETE FROM signing_secret WHERE handle = ?").bind(handle.to_sql());
@@ -89,7 +161,7 @@ impl SecretsRepository for SecretsSqlxDatabase {
             .fetch_optional(&*self.database.pool)
             .await
             .into_core()?;
-        Ok(row.map(|r| r.signing_secret()).transpose()?)
+        Ok(row.map(|r| self.decode_signing_secret(r)).transpose()?)
     }
 
     async fn get_signing_secret_handles(&self) -> Result<Vec<SigningSecretKeyHandle>> {
@@ -109,7 +181,7 @@ impl SecretsRepository for SecretsSqlxDatabase {
     ) -> Result<()> {
         let query = query("INSERT OR REPLACE INTO x25519_secret VALUES (?, ?)")
             .bind(handle.to_sql())
-            .bind(secret.to_sql());
+            .bind(self.encode_secret(secret.key())?);
         query.execute(&*self.database.pool).await.void()
     }
 
@@ -122,7 +194,7 @@ impl SecretsRepository for SecretsSqlxDatabase {
             .bind(handle.to_sql());
         let row: Option<X25519SecretRow> =
             query1.fetch_optional(&mut *transaction).await.into_core()?;
-        let secret = row.map(|r| r.x25519_secret()).transpose()?;
+        let secret = row.map(|r| self.decode_x25519_secret(r)).transpose()?;
 
         let result = if let Some(secret) = secret {
             let query = query("DELETE FROM x25519_secret WHERE handle = ?").bind(handle.to_sql());
@@ -145,7 +217,7 @@ impl SecretsRepository for SecretsSqlxDatabase {
             .fetch_optional(&*self.database.pool)
             .await
             .into_core()?;
-        Ok(row.map(|r| r.x25519_secret()).transpose()?)
+        Ok(row.map(|r| self.decode_x25519_secret(r)).transpose()?)
     }
 
     async fn get_x25519_secret_handles(&self) -> Result<Vec<X25519SecretKeyHandle>> {
@@ -177,6 +249,14 @@ impl ToSqlxType for SigningSecret {
     }
 }
 
+/// Return the bytes of a signing secret
+fn signing_secret_key(secret: &SigningSecret) -> &[u8; 32] {
+    match secret {
+        SigningSecret::EdDSACurve25519(k) => k.key(),
+        SigningSecret::ECDSASHA256CurveP256(k) => k.key(),
+    }
+}
+
 impl ToSqlxType for SigningSecretKeyHandle {
     fn to_sql(&self) -> SqlxType {
         self.handle().to_sql()
@@ -344,6 +424,44 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_encrypted_secrets_repository() -> Result<()> {
+        let database = SqlxDatabase::in_memory("secrets").await?;
+        let plain = SecretsSqlxDatabase::new(database.clone());
+
+        let handle1 =
+            SigningSecretKeyHandle::ECDSASHA256CurveP256(HandleToSecret::new(vec![1, 2, 3]));
+        let secret1 =
+            SigningSecret::ECDSASHA256CurveP256(ECDSASHA256CurveP256SecretKey::new([1; 32]));
+        plain
+            .store_signing_secret(&handle1, secret1.clone())
+            .await?;
+
+        let handle2 = X25519SecretKeyHandle(HandleToSecret::new(vec![4, 5, 6]));
+        let secret2 = X25519SecretKey::new([2; 32]);
+        plain.store_x25519_secret(&handle2, secret2.clone()).await?;
+
+        // encrypt the existing secrets
+        let encryption = SecretsEncryption::new([3; 32]);
+        plain.encrypt_secrets(&encryption).await?;
+
+        // the secrets can only be read with the encryption key
+        assert!(plain.get_signing_secret(&handle1).await.is_err());
+        let encrypted = SecretsSqlxDatabase::new(database).with_encryption(encryption);
+        assert!(encrypted.get_signing_secret(&handle1).await? == Some(secret1));
+        assert!(encrypted.get_x25519_secret(&handle2).await? == Some(secret2));
+
+        // new secrets are stored encrypted
+        let handle3 = X25519SecretKeyHandle(HandleToSecret::new(vec![7, 8, 9]));
+        let secret3 = X25519SecretKey::new([4; 32]);
+        encrypted
+            .store_x25519_secret(&handle3, secret3.clone())
+            .await?;
+        assert!(plain.get_x25519_secret(&handle3).await.is_err());
+        assert!(encrypted.get_x25519_secret(&handle3).await? == Some(secret3));
+        Ok(())
+    }
+
     /// HELPERS
     async fn create_repository() -> Result<Arc<dyn SecretsRepository>> {
         Ok(Arc::new(SecretsSqlxDatabase::create().await?))
@@ -1,18 +1,26 @@
 use crate::error::Error;
+use aws_config::timeout::TimeoutConfig;
 use aws_config::{BehaviorVersion, SdkConfig};
-use aws_sdk_kms::error::SdkError;
+use aws_sdk_kms::config::ProvideCredentials;
+use aws_sdk_kms::error::{DisplayErrorContext, SdkError};
 use aws_sdk_kms::operation::schedule_key_deletion::ScheduleKeyDeletionError;
 use aws_sdk_kms::primitives::Blob;
 use aws_sdk_kms::types::{KeySpec, KeyUsageType, MessageType, SigningAlgorithmSpec};
 use aws_sdk_kms::Client;
+use ockam_core::env::get_env_with_default;
 use ockam_core::{async_trait, Result};
 use ockam_vault::{
     ECDSASHA256CurveP256PublicKey, ECDSASHA256CurveP256Signature, HandleToSecret, Signature,
     SigningSecretKeyHandle, VerifyingPublicKey,
 };
 use sha2::{Digest, Sha256};
+use std::time::Duration;
 use tracing as log;
 
+/// Maximum duration of a call to AWS KMS, including retries.
+/// This value can be overridden with the OCKAM_AWS_KMS_TIMEOUT environment variable
+const DEFAULT_OPERATION_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// AWS KMS client.
 #[derive(Debug, Clone)]
 pub struct AwsKmsClient {
@@ -40,8 +48,13 @@ pub struct AwsKmsConfig {
 
 impl AwsKmsConfig {
     /// Create a new configuration for the AWS KMS
+    /// The calls to AWS KMS time out so that a node does not hang when AWS can not be reached
     pub async fn default() -> Result<AwsKmsConfig> {
-        let sdk_config = aws_config::defaults(BehaviorVersion::latest()).load().await;
+        let timeout = get_env_with_default("OCKAM_AWS_KMS_TIMEOUT", DEFAULT_OPERATION_TIMEOUT)?;
+        let sdk_config = aws_config::defaults(BehaviorVersion::latest())
+            .timeout_config(TimeoutConfig::builder().operation_timeout(timeout).build())
+            .load()
+            .await;
         Ok(Self::new(sdk_config))
     }
 
@@ -70,7 +83,21 @@ impl AwsKmsConfig {
 
 impl AwsKmsClient {
     /// Create a new AWS KMS client.
+    /// An error is returned if no region or no credentials can be found
     pub async fn new(config: AwsKmsConfig) -> Result<AwsKmsClient> {
+        if config.sdk_config.region().is_none() {
+            return Err(Error::MissingRegion)?;
+        }
+        let credentials_provider = config
+            .sdk_config
+            .credentials_provider()
+            .ok_or(Error::MissingCredentials)?;
+        // resolve the credentials now in order to fail early with a clear error
+        if let Err(err) = credentials_provider.provide_credentials().await {
+            log::error!(%err, "failed to load the aws credentials");
+            return Err(Error::MissingCredentials)?;
+        }
+
         let client = Client::new(&config.sdk_config);
         Ok(Self { client, config })
     }
@@ -102,7 +129,7 @@ impl AwsKmsClient {
             Err(err) => {
                 log::error!(%err, "failed to create new key");
                 return Err(Into::<ockam_core::Error>::into(Error::Create(
-                    err.to_string(),
+                    DisplayErrorContext(&err).to_string(),
                 )));
             }
         };
@@ -137,7 +164,7 @@ impl AwsKmsClient {
                 log::error!(%key, %err, "failed to schedule key for deletion");
                 Err(Error::Delete {
                     keyid: key.to_string(),
-                    error: err.to_string(),
+                    error: DisplayErrorContext(&err).to_string(),
                 })?
             }
             Ok(_) => {
@@ -161,7 +188,7 @@ impl AwsKmsClient {
                 log::error!(%key, %err, "failed to get public key");
                 Error::Export {
                     keyid: key.to_string(),
-                    error: err.to_string(),
+                    error: DisplayErrorContext(&err).to_string(),
                 }
             })?;
         if output.key_spec() != Some(&KeySpec::EccNistP256) {
@@ -204,7 +231,7 @@ impl AwsKmsClient {
             log::error!(%key, %err, "failed to sign message");
             Error::Sign {
                 keyid: key.to_string(),
-                error: err.to_string(),
+                error: DisplayErrorContext(&err).to_string(),
             }
         })?;
         if let Some(sig) = output.signature() {
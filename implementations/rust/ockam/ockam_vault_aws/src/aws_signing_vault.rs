@@ -33,7 +33,12 @@ impl AwsSigningVault {
     /// Create a new AWS security module
     pub async fn create_with_config(config: AwsKmsConfig) -> Result<Self> {
         let client = AwsKmsClient::new(config).await?;
+        Self::create_with_client(Arc::new(client)).await
+    }
 
+    /// Create a new security module using a specific KMS client.
+    /// This can be used to support other KMS providers, or to stub the KMS in tests
+    pub async fn create_with_client(client: Arc<dyn KmsClient + Send + Sync>) -> Result<Self> {
         let mut key_pairs: Vec<AwsKeyPair> = vec![];
         // Fetch list of all keys, then fetch the public key for each key
         let keys = client.list_keys().await?;
@@ -49,7 +54,7 @@ impl AwsSigningVault {
         }
 
         Ok(Self {
-            client: Arc::new(client),
+            client,
             keys: Arc::new(RwLock::new(key_pairs)),
         })
     }
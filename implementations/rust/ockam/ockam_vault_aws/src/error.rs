@@ -4,16 +4,20 @@ use thiserror::Error;
 #[allow(missing_docs)]
 #[derive(Error, Debug)]
 pub enum Error {
-    #[error("aws sdk error creating new key")]
+    #[error("aws sdk error creating new key: {0}")]
     Create(String),
-    #[error("aws sdk error signing message with key {keyid}")]
+    #[error("aws sdk error signing message with key {keyid}: {error}")]
     Sign { keyid: String, error: String },
-    #[error("aws sdk error verifying message with key {keyid}")]
+    #[error("aws sdk error verifying message with key {keyid}: {error}")]
     Verify { keyid: String, error: String },
-    #[error("aws sdk error exporting public key {keyid}")]
+    #[error("aws sdk error exporting public key {keyid}: {error}")]
     Export { keyid: String, error: String },
-    #[error("aws sdk error exporting public key {keyid}")]
+    #[error("aws sdk error deleting key {keyid}: {error}")]
     Delete { keyid: String, error: String },
+    #[error("no aws credentials were found. Set the AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY environment variables, or configure a profile in ~/.aws/credentials")]
+    MissingCredentials,
+    #[error("no aws region was found. Set the AWS_REGION environment variable, or configure a region in ~/.aws/config")]
+    MissingRegion,
     #[error("aws did not return a key id")]
     MissingKeyId,
     #[error("aws did not return the list of existing keys")]
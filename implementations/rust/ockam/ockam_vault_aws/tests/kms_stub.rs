@@ -0,0 +1,141 @@
+use aws_config::SdkConfig;
+use ockam_core::{async_trait, Result};
+use ockam_vault::{
+    ECDSASHA256CurveP256PublicKey, ECDSASHA256CurveP256Signature, HandleToSecret, Signature,
+    SigningKeyType, SigningSecretKeyHandle, SoftwareVaultForVerifyingSignatures, VaultForSigning,
+    VaultForVerifyingSignatures, VerifyingPublicKey,
+};
+use ockam_vault_aws::{AwsKmsConfig, AwsSigningVault, KmsClient};
+use p256::ecdsa::signature::Signer;
+use p256::ecdsa::SigningKey;
+use std::sync::{Arc, Mutex};
+
+/// These tests use a stubbed KMS client so that they can be executed without any AWS account
+
+#[tokio::test]
+async fn test_sign_verify_with_stub() -> Result<()> {
+    let signing_vault = AwsSigningVault::create_with_client(Arc::new(StubKmsClient::new())).await?;
+    let handle = signing_vault
+        .generate_signing_secret_key(SigningKeyType::ECDSASHA256CurveP256)
+        .await?;
+    let message = b"hello world";
+    let signature = signing_vault.sign(&handle, message.as_slice()).await?;
+    let public_key = signing_vault.get_verifying_public_key(&handle).await?;
+
+    // the signatures created with the KMS can be verified by the software vault
+    let verifier = SoftwareVaultForVerifyingSignatures::new();
+    assert!(
+        verifier
+            .verify_signature(&public_key, message, &signature)
+            .await?
+    );
+    assert!(
+        !verifier
+            .verify_signature(&public_key, b"another message", &signature)
+            .await?
+    );
+
+    // only ECDSA keys are supported
+    let result = signing_vault
+        .generate_signing_secret_key(SigningKeyType::EdDSACurve25519)
+        .await;
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_existing_keys_are_loaded_with_stub() -> Result<()> {
+    let client = StubKmsClient::new();
+    let handle = client.create_key().await?;
+    let public_key = client.public_key(&handle).await?;
+
+    let signing_vault = AwsSigningVault::create_with_client(Arc::new(client)).await?;
+    assert_eq!(signing_vault.number_of_keys().await?, 1);
+    assert_eq!(
+        signing_vault.get_secret_key_handle(&public_key).await?,
+        handle
+    );
+
+    assert!(signing_vault.delete_signing_secret_key(handle).await?);
+    assert_eq!(signing_vault.number_of_keys().await?, 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_missing_region_is_reported() {
+    let config = AwsKmsConfig::new(SdkConfig::builder().build());
+    let error = AwsSigningVault::create_with_config(config)
+        .await
+        .err()
+        .unwrap();
+    assert!(error.to_string().contains("no aws region was found"));
+}
+
+/// KMS client storing its keys in memory
+struct StubKmsClient {
+    keys: Mutex<Vec<(SigningSecretKeyHandle, SigningKey)>>,
+}
+
+impl StubKmsClient {
+    fn new() -> Self {
+        Self {
+            keys: Mutex::new(vec![]),
+        }
+    }
+
+    fn signing_key(&self, handle: &SigningSecretKeyHandle) -> Result<SigningKey> {
+        self.keys
+            .lock()
+            .unwrap()
+            .iter()
+            .find_map(|(h, k)| if h == handle { Some(k.clone()) } else { None })
+            .ok_or_else(|| ockam_vault_aws::Error::KeyNotFound.into())
+    }
+}
+
+#[async_trait]
+impl KmsClient for StubKmsClient {
+    async fn create_key(&self) -> Result<SigningSecretKeyHandle> {
+        let mut keys = self.keys.lock().unwrap();
+        let index = keys.len() as u8 + 1;
+        let handle = SigningSecretKeyHandle::ECDSASHA256CurveP256(HandleToSecret::new(
+            format!("stub-key-{index}").into_bytes(),
+        ));
+        let signing_key = SigningKey::from_slice(&[index; 32]).unwrap();
+        keys.push((handle.clone(), signing_key));
+        Ok(handle)
+    }
+
+    async fn delete_key(&self, key: &SigningSecretKeyHandle) -> Result<bool> {
+        let mut keys = self.keys.lock().unwrap();
+        let len = keys.len();
+        keys.retain(|(h, _)| h != key);
+        Ok(keys.len() != len)
+    }
+
+    async fn public_key(&self, key: &SigningSecretKeyHandle) -> Result<VerifyingPublicKey> {
+        let signing_key = self.signing_key(key)?;
+        let public_key = signing_key.verifying_key().to_encoded_point(false);
+        Ok(VerifyingPublicKey::ECDSASHA256CurveP256(
+            ECDSASHA256CurveP256PublicKey(public_key.as_bytes().try_into().unwrap()),
+        ))
+    }
+
+    async fn list_keys(&self) -> Result<Vec<SigningSecretKeyHandle>> {
+        Ok(self
+            .keys
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(h, _)| h.clone())
+            .collect())
+    }
+
+    async fn sign(&self, key: &SigningSecretKeyHandle, message: &[u8]) -> Result<Signature> {
+        let signing_key = self.signing_key(key)?;
+        let signature: p256::ecdsa::Signature = signing_key.sign(message);
+        Ok(Signature::ECDSASHA256CurveP256(
+            ECDSASHA256CurveP256Signature(signature.to_bytes().as_slice().try_into().unwrap()),
+        ))
+    }
+}